@@ -34,7 +34,7 @@ use quickwit_metastore::{IndexMetadata, Split, SplitState};
 use quickwit_proto::search::{CountHits, SortField, SortOrder};
 use quickwit_proto::types::IndexId;
 use quickwit_rest_client::models::{IngestSource, SearchResponseRestClient};
-use quickwit_rest_client::rest_client::{CommitType, IngestEvent};
+use quickwit_rest_client::rest_client::{CommitType, IngestEvent, QuickwitClient};
 use quickwit_serve::{ListSplitsQueryParams, SearchRequestQueryString, SortBy};
 use quickwit_storage::{load_file, StorageResolver};
 use tabled::settings::object::{FirstRow, Rows, Segment};
@@ -45,7 +45,7 @@ use tracing::{debug, Level};
 
 use crate::checklist::{GREEN_COLOR, RED_COLOR};
 use crate::stats::{mean, percentile, std_deviation};
-use crate::{client_args, make_table, prompt_confirmation, ClientArgs};
+use crate::{client_args, display_or_watch, make_table, prompt_confirmation, ClientArgs};
 
 pub fn build_index_command() -> Command {
     Command::new("index")
@@ -111,6 +111,8 @@ pub fn build_index_command() -> Command {
                 .args(&[
                     arg!(--index <INDEX> "ID of the target index")
                         .required(true),
+                    arg!(--watch "Refreshes the statistics every 5 seconds until interrupted.")
+                        .required(false),
                 ])
             )
         .subcommand(
@@ -217,6 +219,7 @@ pub struct UpdateIndexArgs {
 pub struct DescribeIndexArgs {
     pub client_args: ClientArgs,
     pub index_id: IndexId,
+    pub watch: bool,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -348,9 +351,11 @@ impl IndexCliCommand {
         let index_id = matches
             .remove_one::<String>("index")
             .expect("`index` should be a required arg.");
+        let watch = matches.get_flag("watch");
         Ok(Self::Describe(DescribeIndexArgs {
             client_args,
             index_id,
+            watch,
         }))
     }
 
@@ -587,15 +592,21 @@ struct IndexRow {
 pub async fn describe_index_cli(args: DescribeIndexArgs) -> anyhow::Result<()> {
     debug!(args=?args, "describe-index");
     let qw_client = args.client_args.client();
-    let index_metadata = qw_client.indexes().get(&args.index_id).await?;
+    display_or_watch(args.watch, || {
+        render_index_stats(&qw_client, &args.index_id)
+    })
+    .await
+}
+
+async fn render_index_stats(qw_client: &QuickwitClient, index_id: &str) -> anyhow::Result<String> {
+    let index_metadata = qw_client.indexes().get(index_id).await?;
     let list_splits_query_params = ListSplitsQueryParams::default();
     let splits = qw_client
-        .splits(&args.index_id)
+        .splits(index_id)
         .list(list_splits_query_params)
         .await?;
     let index_stats = IndexStats::from_metadata(index_metadata, splits)?;
-    println!("{}", index_stats.display_as_table());
-    Ok(())
+    Ok(index_stats.display_as_table())
 }
 
 pub struct IndexStats {
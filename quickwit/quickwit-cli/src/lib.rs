@@ -14,11 +14,14 @@
 
 #![deny(clippy::disallowed_methods)]
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::future::Future;
 use std::str::FromStr;
+use std::time::Duration;
 
 use anyhow::Context;
 use clap::{arg, Arg, ArgMatches};
+use colored::Colorize;
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::Confirm;
 use quickwit_common::runtimes::RuntimesConfig;
@@ -59,6 +62,9 @@ pub mod tool;
 /// Throughput calculation window size.
 const THROUGHPUT_WINDOW_SIZE: usize = 5;
 
+/// Interval at which the output of the commands run with `--watch` is refreshed.
+const WATCH_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
 pub const QW_ENABLE_TOKIO_CONSOLE_ENV_KEY: &str = "QW_ENABLE_TOKIO_CONSOLE";
 
 pub const QW_ENABLE_OPENTELEMETRY_OTLP_EXPORTER_ENV_KEY: &str =
@@ -218,17 +224,20 @@ async fn load_node_config(config_uri: &Uri) -> anyhow::Result<NodeConfig> {
 
 fn get_resolvers(
     storage_configs: &StorageConfigs,
+    storage_credentials: &BTreeMap<String, StorageConfigs>,
     metastore_configs: &MetastoreConfigs,
 ) -> (StorageResolver, MetastoreResolver) {
     // The CLI tests rely on the unconfigured singleton resolvers, so it's better to return them if
     // the storage and metastore configs are not set.
-    if storage_configs.is_empty() && metastore_configs.is_empty() {
+    if storage_configs.is_empty() && storage_credentials.is_empty() && metastore_configs.is_empty()
+    {
         return (
             StorageResolver::unconfigured(),
             MetastoreResolver::unconfigured(),
         );
     }
-    let storage_resolver = StorageResolver::configured(storage_configs);
+    let storage_resolver =
+        StorageResolver::configured_with_credentials(storage_configs, storage_credentials);
     let metastore_resolver =
         MetastoreResolver::configured(storage_resolver.clone(), metastore_configs);
     (storage_resolver, metastore_resolver)
@@ -261,7 +270,15 @@ pub async fn run_index_checklist(
         .index_metadata(IndexMetadataRequest::for_index_id(index_id.to_string()))
         .await?
         .deserialize_index_metadata()?;
-    let index_storage = storage_resolver.resolve(index_metadata.index_uri()).await?;
+    let index_storage = storage_resolver
+        .resolve_with_credentials(
+            index_metadata.index_uri(),
+            index_metadata
+                .index_config
+                .storage_credentials_opt
+                .as_deref(),
+        )
+        .await?;
     checks.push(("index storage", index_storage.check_connectivity().await));
 
     if let Some(source_config) = source_config_opt {
@@ -303,6 +320,38 @@ pub fn make_table<T: Tabled>(
     table
 }
 
+/// Displays the output rendered by `render_fn` once or, if `watch` is set, refreshes it every
+/// `WATCH_REFRESH_INTERVAL` until the command is interrupted.
+///
+/// In watch mode, errors are displayed in place of the output and do not stop the refreshes, so
+/// that watching survives a node restart.
+async fn display_or_watch<F, Fut>(watch: bool, mut render_fn: F) -> anyhow::Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<String>>,
+{
+    if !watch {
+        println!("{}", render_fn().await?);
+        return Ok(());
+    }
+    let mut interval = tokio::time::interval(WATCH_REFRESH_INTERVAL);
+    loop {
+        interval.tick().await;
+        // The output is rendered before clearing the terminal to avoid flickering.
+        let output = match render_fn().await {
+            Ok(output) => output,
+            Err(error) => format!("{} {error:#}", "Error:".red()),
+        };
+        // Clears the terminal and moves the cursor to its top left corner.
+        print!("\x1B[2J\x1B[1;1H");
+        println!(
+            "Every {}s: {}\n\n{output}",
+            WATCH_REFRESH_INTERVAL.as_secs(),
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+        );
+    }
+}
+
 /// Prompts user for confirmation.
 fn prompt_confirmation(prompt: &str, default: bool) -> bool {
     if Confirm::with_theme(&ColorfulTheme::default())
@@ -428,8 +477,13 @@ mod tests {
             ..Default::default()
         };
         let storage_configs = StorageConfigs::new(vec![s3_storage_config.into()]);
+        let storage_credentials = BTreeMap::from([(
+            "account-a".to_string(),
+            StorageConfigs::new(vec![S3StorageConfig::default().into()]),
+        )]);
         let metastore_configs = MetastoreConfigs::default();
-        let (_storage_resolver, _metastore_resolver) =
-            get_resolvers(&storage_configs, &metastore_configs);
+        let (storage_resolver, _metastore_resolver) =
+            get_resolvers(&storage_configs, &storage_credentials, &metastore_configs);
+        assert!(storage_resolver.has_storage_credentials("account-a"));
     }
 }
@@ -40,6 +40,7 @@ fn main() -> anyhow::Result<()> {
     let main_runtime_num_threads: usize = get_main_runtime_num_threads();
     let rt = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
+        .enable_metrics_poll_time_histogram()
         .on_thread_unpark(busy_detector::thread_unpark)
         .on_thread_park(busy_detector::thread_park)
         .thread_name("main_runtime_thread")
@@ -615,9 +616,23 @@ mod tests {
             command,
             CliCommand::Index(IndexCliCommand::Describe(DescribeIndexArgs {
                 index_id,
+                watch: false,
                 ..
             })) if &index_id == "wikipedia"
         ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(["index", "describe", "--index", "wikipedia", "--watch"])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Describe(DescribeIndexArgs {
+                watch: true,
+                ..
+            }))
+        ));
     }
 
     #[test]
@@ -110,8 +110,11 @@ impl RunCliCommand {
         let version_text = BuildInfo::get_version_text();
         info!("quickwit version: {version_text}");
         let mut node_config = load_node_config(&self.config_uri).await?;
-        let (storage_resolver, metastore_resolver) =
-            get_resolvers(&node_config.storage_configs, &node_config.metastore_configs);
+        let (storage_resolver, metastore_resolver) = get_resolvers(
+            &node_config.storage_configs,
+            &node_config.storage_credentials,
+            &node_config.metastore_configs,
+        );
         crate::busy_detector::set_enabled(true);
 
         if let Some(services) = &self.services {
@@ -22,13 +22,14 @@ use quickwit_common::uri::Uri;
 use quickwit_config::{validate_identifier, ConfigFormat, SourceConfig};
 use quickwit_metastore::checkpoint::SourceCheckpoint;
 use quickwit_proto::types::{IndexId, SourceId};
+use quickwit_rest_client::rest_client::QuickwitClient;
 use quickwit_storage::{load_file, StorageResolver};
 use serde_json::Value as JsonValue;
 use tabled::{Table, Tabled};
 use tracing::debug;
 
 use crate::checklist::GREEN_COLOR;
-use crate::{client_args, make_table, prompt_confirmation, ClientArgs};
+use crate::{client_args, display_or_watch, make_table, prompt_confirmation, ClientArgs};
 
 pub fn build_source_command() -> Command {
     Command::new("source")
@@ -121,6 +122,9 @@ pub fn build_source_command() -> Command {
                     arg!(--source <SOURCE_ID> "ID of the source.")
                         .display_order(2)
                         .required(true),
+                    arg!(--watch "Refreshes the source and its checkpoint every 5 seconds until interrupted.")
+                        .display_order(3)
+                        .required(false),
                 ])
             )
         .subcommand(
@@ -185,6 +189,7 @@ pub struct DescribeSourceArgs {
     pub client_args: ClientArgs,
     pub index_id: IndexId,
     pub source_id: SourceId,
+    pub watch: bool,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -329,10 +334,12 @@ impl SourceCliCommand {
         let source_id = matches
             .remove_one::<String>("source")
             .expect("`source` should be a required arg.");
+        let watch = matches.get_flag("watch");
         Ok(DescribeSourceArgs {
             client_args,
             index_id,
             source_id,
+            watch,
         })
     }
 
@@ -443,23 +450,37 @@ async fn delete_source_cli(args: DeleteSourceArgs) -> anyhow::Result<()> {
 async fn describe_source_cli(args: DescribeSourceArgs) -> anyhow::Result<()> {
     debug!(args=?args, "describe-source");
     let qw_client = args.client_args.client();
+    display_or_watch(args.watch, || {
+        render_source(&qw_client, &args.index_id, &args.source_id)
+    })
+    .await
+}
+
+async fn render_source(
+    qw_client: &QuickwitClient,
+    index_id: &str,
+    source_id: &str,
+) -> anyhow::Result<String> {
     let index_metadata = qw_client
         .indexes()
-        .get(&args.index_id)
+        .get(index_id)
         .await
         .context("failed to fetch index metadata")?;
     let source_checkpoint = index_metadata
         .checkpoint
-        .source_checkpoint(&args.source_id)
+        .source_checkpoint(source_id)
         .cloned()
         .unwrap_or_default();
     let (source_table, params_table, checkpoint_table) = make_describe_source_tables(
         source_checkpoint,
         index_metadata.sources.into_values(),
-        &args.source_id,
+        source_id,
     )?;
-    display_tables(&[source_table, params_table, checkpoint_table]);
-    Ok(())
+    Ok(render_tables(&[
+        source_table,
+        params_table,
+        checkpoint_table,
+    ]))
 }
 
 fn make_describe_source_tables<I>(
@@ -551,10 +572,11 @@ struct CheckpointRow {
 }
 
 fn display_tables(tables: &[Table]) {
-    println!(
-        "{}",
-        tables.iter().map(|table| table.to_string()).join("\n\n")
-    );
+    println!("{}", render_tables(tables));
+}
+
+fn render_tables(tables: &[Table]) -> String {
+    tables.iter().map(|table| table.to_string()).join("\n\n")
 }
 
 async fn reset_checkpoint_cli(args: ResetCheckpointArgs) -> anyhow::Result<()> {
@@ -779,8 +801,30 @@ mod tests {
                 client_args: ClientArgs::default(),
                 index_id: "hdfs-logs".to_string(),
                 source_id: "hdfs-logs-source".to_string(),
+                watch: false,
             }));
         assert_eq!(command, expected_command);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(vec![
+                "source",
+                "describe",
+                "--index",
+                "hdfs-logs",
+                "--source",
+                "hdfs-logs-source",
+                "--watch",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        assert!(matches!(
+            command,
+            CliCommand::Source(SourceCliCommand::DescribeSource(DescribeSourceArgs {
+                watch: true,
+                ..
+            }))
+        ));
     }
 
     #[test]
@@ -830,6 +874,8 @@ mod tests {
             source_params: SourceParams::file_from_str("path/to/file").unwrap(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         }];
         let expected_source = vec![SourceRow {
             source_id: "foo-source".to_string(),
@@ -891,6 +937,8 @@ mod tests {
                 source_params: SourceParams::stdin(),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                activation_windows: Vec::new(),
+                backfill_time_range: None,
             },
             SourceConfig {
                 source_id: "bar-source".to_string(),
@@ -899,6 +947,8 @@ mod tests {
                 source_params: SourceParams::stdin(),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                activation_windows: Vec::new(),
+                backfill_time_range: None,
             },
         ];
         let expected_sources = [
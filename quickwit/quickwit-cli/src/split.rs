@@ -12,21 +12,51 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::{bail, Context};
 use clap::{arg, ArgMatches, Command};
 use colored::Colorize;
 use itertools::Itertools;
-use quickwit_metastore::{Split, SplitState};
-use quickwit_proto::types::{IndexId, SplitId};
+use quickwit_common::split_file;
+use quickwit_common::uri::Uri;
+use quickwit_config::DocMapping;
+use quickwit_metastore::{
+    IndexMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt,
+    MetastoreServiceStreamSplitsExt, Split, SplitMetadata, SplitState, StageSplitsRequestExt,
+};
+use quickwit_proto::metastore::{
+    IndexMetadataRequest, ListSplitsRequest, MetastoreService, MetastoreServiceClient,
+    PublishSplitsRequest, StageSplitsRequest,
+};
+use quickwit_proto::types::{IndexId, IndexUid, SplitId};
 use quickwit_serve::ListSplitsQueryParams;
+use quickwit_storage::FilePayload;
+use serde::{Deserialize, Serialize};
 use tabled::{Table, Tabled};
 use time::{format_description, Date, OffsetDateTime, PrimitiveDateTime};
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tracing::debug;
 
 use crate::checklist::GREEN_COLOR;
-use crate::{client_args, make_table, prompt_confirmation, ClientArgs};
+use crate::{
+    client_args, config_cli_arg, get_resolvers, load_node_config, make_table, prompt_confirmation,
+    ClientArgs,
+};
+
+/// Name of the manifest entry of a split archive. It is always the first entry of the archive.
+const SPLIT_ARCHIVE_MANIFEST_PATH: &str = "manifest.json";
+
+/// Version of the split archive format, bumped on breaking changes of the manifest.
+const SPLIT_ARCHIVE_VERSION: &str = "0.2";
+
+/// Size of the buffer used to compute the checksums of split files.
+const CRC32C_BUFFER_SIZE: usize = 1 << 20;
 
 pub fn build_split_command() -> Command {
     Command::new("split")
@@ -100,6 +130,50 @@ pub fn build_split_command() -> Command {
                         .required(false),
                 ])
             )
+        .subcommand(
+            Command::new("export")
+                .about("Exports splits and their metadata to a portable archive. Requires a node config.")
+                .arg(config_cli_arg())
+                .args(&[
+                    arg!(--index <INDEX> "ID of the source index")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--splits <SPLIT_IDS> "Comma-separated list of split IDs to export. Defaults to all the published splits of the index.")
+                        .display_order(2)
+                        .required(false)
+                        .value_delimiter(','),
+                    arg!(--"output-path" <OUTPUT_PATH> "Path of the archive to create.")
+                        .display_order(3)
+                        .required(true),
+                ])
+            )
+        .subcommand(
+            Command::new("import")
+                .about("Imports and publishes the splits of an archive created with `split export`. Requires a node config.")
+                .arg(config_cli_arg())
+                .args(&[
+                    arg!(--index <INDEX> "ID of the target index. Defaults to the ID of the exported index. Its doc mapping must be identical to the doc mapping of the exported index.")
+                        .display_order(1)
+                        .required(false),
+                    arg!(--"input-path" <INPUT_PATH> "Path of the archive to import.")
+                        .display_order(2)
+                        .required(true),
+                ])
+            )
+        .subcommand(
+            Command::new("verify")
+                .about("Verifies the split files in the storage against the CRC32C checksums recorded in their metadata. Requires a node config.")
+                .arg(config_cli_arg())
+                .args(&[
+                    arg!(--index <INDEX> "ID of the target index")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--splits <SPLIT_IDS> "Comma-separated list of split IDs to verify. Defaults to all the published splits of the index.")
+                        .display_order(2)
+                        .required(false)
+                        .value_delimiter(','),
+                ])
+            )
         .arg_required_else_help(true)
 }
 
@@ -156,11 +230,36 @@ pub struct DescribeSplitArgs {
     pub verbose: bool,
 }
 
+#[derive(Debug, Eq, PartialEq)]
+pub struct ExportSplitsArgs {
+    pub config_uri: Uri,
+    pub index_id: IndexId,
+    pub split_ids: Option<Vec<SplitId>>,
+    pub output_path: PathBuf,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ImportSplitsArgs {
+    pub config_uri: Uri,
+    pub index_id: Option<IndexId>,
+    pub input_path: PathBuf,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct VerifySplitsArgs {
+    pub config_uri: Uri,
+    pub index_id: IndexId,
+    pub split_ids: Option<Vec<SplitId>>,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum SplitCliCommand {
     List(ListSplitArgs),
     MarkForDeletion(MarkForDeletionArgs),
     Describe(DescribeSplitArgs),
+    Export(ExportSplitsArgs),
+    Import(ImportSplitsArgs),
+    Verify(VerifySplitsArgs),
 }
 
 impl SplitCliCommand {
@@ -172,6 +271,9 @@ impl SplitCliCommand {
             "describe" => Self::parse_describe_args(submatches),
             "list" => Self::parse_list_args(submatches),
             "mark-for-deletion" => Self::parse_mark_for_deletion_args(submatches),
+            "export" => Self::parse_export_args(submatches),
+            "import" => Self::parse_import_args(submatches),
+            "verify" => Self::parse_verify_args(submatches),
             _ => bail!("unknown split subcommand `{subcommand}`"),
         }
     }
@@ -275,11 +377,72 @@ impl SplitCliCommand {
         }))
     }
 
+    fn parse_export_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let config_uri = matches
+            .remove_one::<String>("config")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`config` should be a required arg.")?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let split_ids = matches
+            .remove_many::<String>("splits")
+            .map(|values| values.collect());
+        let output_path = matches
+            .remove_one::<String>("output-path")
+            .map(PathBuf::from)
+            .expect("`output-path` should be a required arg.");
+        Ok(Self::Export(ExportSplitsArgs {
+            config_uri,
+            index_id,
+            split_ids,
+            output_path,
+        }))
+    }
+
+    fn parse_import_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let config_uri = matches
+            .remove_one::<String>("config")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`config` should be a required arg.")?;
+        let index_id = matches.remove_one::<String>("index");
+        let input_path = matches
+            .remove_one::<String>("input-path")
+            .map(PathBuf::from)
+            .expect("`input-path` should be a required arg.");
+        Ok(Self::Import(ImportSplitsArgs {
+            config_uri,
+            index_id,
+            input_path,
+        }))
+    }
+
+    fn parse_verify_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let config_uri = matches
+            .remove_one::<String>("config")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`config` should be a required arg.")?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let split_ids = matches
+            .remove_many::<String>("splits")
+            .map(|values| values.collect());
+        Ok(Self::Verify(VerifySplitsArgs {
+            config_uri,
+            index_id,
+            split_ids,
+        }))
+    }
+
     pub async fn execute(self) -> anyhow::Result<()> {
         match self {
             Self::List(args) => list_split_cli(args).await,
             Self::MarkForDeletion(args) => mark_splits_for_deletion_cli(args).await,
             Self::Describe(args) => describe_split_cli(args).await,
+            Self::Export(args) => export_splits_cli(args).await,
+            Self::Import(args) => import_splits_cli(args).await,
+            Self::Verify(args) => verify_splits_cli(args).await,
         }
     }
 }
@@ -388,6 +551,403 @@ async fn describe_split_cli(args: DescribeSplitArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Manifest of a split archive. The archive is a tar file starting with the manifest, followed by
+/// one `<split_id>.split` entry per split listed in the manifest. All the splits were indexed with
+/// `doc_mapping`.
+#[derive(Debug, Serialize, Deserialize)]
+struct SplitArchiveManifest {
+    version: String,
+    index_id: IndexId,
+    doc_mapping: DocMapping,
+    splits: Vec<SplitMetadata>,
+}
+
+async fn export_splits_cli(args: ExportSplitsArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "export-splits");
+    println!("❯ Exporting splits...");
+
+    let config = load_node_config(&args.config_uri).await?;
+    let (storage_resolver, metastore_resolver) = get_resolvers(
+        &config.storage_configs,
+        &config.storage_credentials,
+        &config.metastore_configs,
+    );
+    let metastore = metastore_resolver.resolve(&config.metastore_uri).await?;
+    let index_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(args.index_id.clone()))
+        .await?
+        .deserialize_index_metadata()?;
+    let index_storage = storage_resolver
+        .resolve_with_credentials(
+            index_metadata.index_uri(),
+            index_metadata
+                .index_config
+                .storage_credentials_opt
+                .as_deref(),
+        )
+        .await?;
+
+    let splits_metadata = list_published_splits(
+        &metastore,
+        index_metadata.index_uid.clone(),
+        args.split_ids.as_deref(),
+    )
+    .await?;
+
+    // The splits indexed with a previous doc mapping cannot be searched with the current one, so
+    // they would not be importable.
+    let doc_mapping = index_metadata.index_config.doc_mapping.clone();
+    let stale_split_ids = splits_metadata
+        .iter()
+        .filter(|split_metadata| split_metadata.doc_mapping_uid != doc_mapping.doc_mapping_uid)
+        .map(|split_metadata| &split_metadata.split_id)
+        .join(", ");
+    if !stale_split_ids.is_empty() {
+        bail!(
+            "splits `{stale_split_ids}` were indexed with a previous doc mapping and cannot be \
+             exported"
+        );
+    }
+    let archive_file = File::create(&args.output_path)
+        .with_context(|| format!("failed to create archive `{}`", args.output_path.display()))?;
+    let mut archive_builder = tar::Builder::new(archive_file);
+    let num_splits = splits_metadata.len();
+    let manifest = SplitArchiveManifest {
+        version: SPLIT_ARCHIVE_VERSION.to_string(),
+        index_id: args.index_id,
+        doc_mapping,
+        splits: splits_metadata,
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    append_archive_entry(
+        &mut archive_builder,
+        SPLIT_ARCHIVE_MANIFEST_PATH,
+        &manifest_json,
+    )?;
+
+    // The splits are downloaded one at a time to a temporary file and copied from there into the
+    // archive, so that they are never held in memory.
+    let temp_dir = tempfile::tempdir()?;
+    let temp_split_path = temp_dir.path().join("split");
+
+    for split_metadata in &manifest.splits {
+        println!("Exporting split `{}`", split_metadata.split_id);
+        let split_file = split_file(&split_metadata.split_id);
+        index_storage
+            .copy_to_file(Path::new(&split_file), &temp_split_path)
+            .await?;
+        let temp_split_file = tokio::fs::File::open(&temp_split_path).await?;
+        check_split_checksum(split_metadata, temp_split_file).await?;
+
+        let mut temp_split_file = File::open(&temp_split_path)?;
+        archive_builder.append_file(&split_file, &mut temp_split_file)?;
+    }
+    archive_builder.into_inner()?.sync_all()?;
+
+    println!(
+        "{} {num_splits} split(s) successfully exported to `{}`.",
+        "✔".color(GREEN_COLOR),
+        args.output_path.display()
+    );
+    Ok(())
+}
+
+async fn import_splits_cli(args: ImportSplitsArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "import-splits");
+    println!("❯ Importing splits...");
+
+    let archive_file = File::open(&args.input_path)
+        .with_context(|| format!("failed to open archive `{}`", args.input_path.display()))?;
+    let mut archive = tar::Archive::new(archive_file);
+    let mut archive_entries = archive.entries()?;
+    let manifest = read_split_archive_manifest(&mut archive_entries)?;
+
+    // The split files are uploaded straight from their position in the archive.
+    let mut split_file_ranges: Vec<(String, Range<u64>)> = Vec::new();
+
+    for archive_entry_res in archive_entries {
+        let archive_entry = archive_entry_res?;
+        let split_file = archive_entry.path()?.to_string_lossy().to_string();
+        let start = archive_entry.raw_file_position();
+        let end = start + archive_entry.size();
+        split_file_ranges.push((split_file, start..end));
+    }
+
+    let config = load_node_config(&args.config_uri).await?;
+    let (storage_resolver, metastore_resolver) = get_resolvers(
+        &config.storage_configs,
+        &config.storage_credentials,
+        &config.metastore_configs,
+    );
+    let metastore = metastore_resolver.resolve(&config.metastore_uri).await?;
+    let index_id = args.index_id.unwrap_or(manifest.index_id);
+    let index_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(index_id))
+        .await?
+        .deserialize_index_metadata()?;
+    let index_uid = index_metadata.index_uid.clone();
+    let index_storage = storage_resolver
+        .resolve_with_credentials(
+            index_metadata.index_uri(),
+            index_metadata
+                .index_config
+                .storage_credentials_opt
+                .as_deref(),
+        )
+        .await?;
+
+    check_doc_mapping_compatibility(
+        &manifest.doc_mapping,
+        &index_metadata.index_config.doc_mapping,
+    )?;
+    // The splits are re-attached to the target index. Their delete opstamp is reset because the
+    // delete tasks of the source index do not exist in the target index.
+    let mut splits_metadata = manifest.splits;
+
+    for split_metadata in &splits_metadata {
+        if split_metadata.doc_mapping_uid != manifest.doc_mapping.doc_mapping_uid {
+            bail!(
+                "split `{}` was not indexed with the doc mapping of the split archive",
+                split_metadata.split_id
+            );
+        }
+    }
+    for split_metadata in &mut splits_metadata {
+        split_metadata.index_uid = index_uid.clone();
+        split_metadata.doc_mapping_uid = index_metadata.index_config.doc_mapping.doc_mapping_uid;
+        split_metadata.delete_opstamp = 0;
+    }
+    let split_ids: Vec<SplitId> = splits_metadata
+        .iter()
+        .map(|split_metadata| split_metadata.split_id.clone())
+        .collect();
+    let num_splits = split_ids.len();
+
+    // Like the indexing pipelines, we stage the splits before uploading them so that the garbage
+    // collector can clean up the files of an interrupted import.
+    let stage_splits_request =
+        StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), splits_metadata)?;
+    metastore.stage_splits(stage_splits_request).await?;
+
+    let mut pending_split_files: HashSet<String> = split_ids.iter().map(split_file).collect();
+
+    for (split_file, byte_range) in split_file_ranges {
+        if !pending_split_files.remove(&split_file) {
+            bail!("unexpected entry `{split_file}` in split archive");
+        }
+        println!("Importing split `{split_file}`");
+        let split_payload = FilePayload::open_slice(&args.input_path, byte_range)?;
+        index_storage
+            .put(Path::new(&split_file), Box::new(split_payload))
+            .await?;
+    }
+    if !pending_split_files.is_empty() {
+        bail!(
+            "split archive is missing files `{}`",
+            pending_split_files.iter().sorted().join(", ")
+        );
+    }
+    let publish_splits_request = PublishSplitsRequest {
+        index_uid: Some(index_uid),
+        staged_split_ids: split_ids,
+        replaced_split_ids: Vec::new(),
+        index_checkpoint_delta_json_opt: None,
+        publish_token_opt: None,
+    };
+    metastore.publish_splits(publish_splits_request).await?;
+
+    println!(
+        "{} {num_splits} split(s) successfully imported into `{}`.",
+        "✔".color(GREEN_COLOR),
+        index_metadata.index_id()
+    );
+    Ok(())
+}
+
+async fn verify_splits_cli(args: VerifySplitsArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "verify-splits");
+    println!("❯ Verifying splits...");
+
+    let config = load_node_config(&args.config_uri).await?;
+    let (storage_resolver, metastore_resolver) = get_resolvers(
+        &config.storage_configs,
+        &config.storage_credentials,
+        &config.metastore_configs,
+    );
+    let metastore = metastore_resolver.resolve(&config.metastore_uri).await?;
+    let index_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(args.index_id.clone()))
+        .await?
+        .deserialize_index_metadata()?;
+    let index_storage = storage_resolver
+        .resolve_with_credentials(
+            index_metadata.index_uri(),
+            index_metadata
+                .index_config
+                .storage_credentials_opt
+                .as_deref(),
+        )
+        .await?;
+    let splits_metadata = list_published_splits(
+        &metastore,
+        index_metadata.index_uid.clone(),
+        args.split_ids.as_deref(),
+    )
+    .await?;
+
+    let mut num_verified_splits = 0;
+    let mut corrupted_split_ids = Vec::new();
+
+    for split_metadata in &splits_metadata {
+        if split_metadata.crc32c_checksum.is_none() {
+            println!(
+                "Skipping split `{}`: no checksum recorded",
+                split_metadata.split_id
+            );
+            continue;
+        }
+        println!("Verifying split `{}`", split_metadata.split_id);
+        let split_file = split_file(&split_metadata.split_id);
+        let split_num_bytes = index_storage.file_num_bytes(Path::new(&split_file)).await?;
+        let split_stream = index_storage
+            .get_slice_stream(Path::new(&split_file), 0..split_num_bytes as usize)
+            .await?;
+
+        if let Err(error) = check_split_checksum(split_metadata, split_stream).await {
+            println!("{} {error:#}", "✘".red());
+            corrupted_split_ids.push(split_metadata.split_id.clone());
+        }
+        num_verified_splits += 1;
+    }
+    if !corrupted_split_ids.is_empty() {
+        bail!(
+            "splits `{}` do not match their checksum",
+            corrupted_split_ids.join(", ")
+        );
+    }
+    println!(
+        "{} {num_verified_splits} split(s) successfully verified.",
+        "✔".color(GREEN_COLOR),
+    );
+    Ok(())
+}
+
+/// Lists the published splits of an index, restricted to `split_ids` if provided, and fails if
+/// some of them cannot be found.
+async fn list_published_splits(
+    metastore: &MetastoreServiceClient,
+    index_uid: IndexUid,
+    split_ids_opt: Option<&[SplitId]>,
+) -> anyhow::Result<Vec<SplitMetadata>> {
+    let list_splits_query =
+        ListSplitsQuery::for_index(index_uid).with_split_state(SplitState::Published);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&list_splits_query)?;
+    let mut splits_metadata = metastore
+        .list_splits(list_splits_request)
+        .await?
+        .collect_splits_metadata()
+        .await?;
+
+    if let Some(split_ids) = split_ids_opt {
+        splits_metadata.retain(|split_metadata| split_ids.contains(&split_metadata.split_id));
+
+        if splits_metadata.len() != split_ids.len() {
+            let missing_split_ids = split_ids
+                .iter()
+                .filter(|split_id| {
+                    !splits_metadata
+                        .iter()
+                        .any(|split_metadata| &split_metadata.split_id == *split_id)
+                })
+                .join(", ");
+            bail!("could not find published splits `{missing_split_ids}`");
+        }
+    }
+    Ok(splits_metadata)
+}
+
+/// Checks that the content of a split file matches the checksum recorded in its metadata. Splits
+/// uploaded before checksums were recorded are not checked.
+async fn check_split_checksum(
+    split_metadata: &SplitMetadata,
+    split_reader: impl AsyncRead + Unpin,
+) -> anyhow::Result<()> {
+    let Some(expected_crc32c) = split_metadata.crc32c_checksum else {
+        return Ok(());
+    };
+    let crc32c = compute_crc32c(split_reader).await?;
+
+    if crc32c != expected_crc32c {
+        bail!(
+            "checksum mismatch for split `{}`: expected {expected_crc32c:08x}, got {crc32c:08x}",
+            split_metadata.split_id
+        );
+    }
+    Ok(())
+}
+
+async fn compute_crc32c(mut reader: impl AsyncRead + Unpin) -> io::Result<u32> {
+    let mut buffer = vec![0u8; CRC32C_BUFFER_SIZE];
+    let mut crc32c = 0;
+    loop {
+        let num_bytes = reader.read(&mut buffer).await?;
+
+        if num_bytes == 0 {
+            return Ok(crc32c);
+        }
+        crc32c = crc32c::crc32c_append(crc32c, &buffer[..num_bytes]);
+    }
+}
+
+/// Checks that the splits indexed with the doc mapping of a split archive can be searched with the
+/// doc mapping of the target index, that is, that both doc mappings are identical up to their UID.
+fn check_doc_mapping_compatibility(
+    archive_doc_mapping: &DocMapping,
+    index_doc_mapping: &DocMapping,
+) -> anyhow::Result<()> {
+    let mut archive_doc_mapping = archive_doc_mapping.clone();
+    archive_doc_mapping.doc_mapping_uid = index_doc_mapping.doc_mapping_uid;
+
+    if archive_doc_mapping != *index_doc_mapping {
+        bail!(
+            "the doc mapping of the split archive is not compatible with the doc mapping of the \
+             target index"
+        );
+    }
+    Ok(())
+}
+
+fn append_archive_entry<W: Write>(
+    archive_builder: &mut tar::Builder<W>,
+    path: &str,
+    data: &[u8],
+) -> io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    archive_builder.append_data(&mut header, path, data)
+}
+
+fn read_split_archive_manifest<R: Read>(
+    archive_entries: &mut tar::Entries<R>,
+) -> anyhow::Result<SplitArchiveManifest> {
+    let mut manifest_entry = archive_entries.next().context("split archive is empty")??;
+
+    if manifest_entry.path()?.as_ref() != Path::new(SPLIT_ARCHIVE_MANIFEST_PATH) {
+        bail!("split archive should start with a `{SPLIT_ARCHIVE_MANIFEST_PATH}` entry");
+    }
+    let manifest: SplitArchiveManifest = serde_json::from_reader(&mut manifest_entry)
+        .context("failed to deserialize split archive manifest")?;
+
+    if manifest.version != SPLIT_ARCHIVE_VERSION {
+        bail!(
+            "unsupported split archive version `{}`, expected `{SPLIT_ARCHIVE_VERSION}`",
+            manifest.version
+        );
+    }
+    Ok(manifest)
+}
+
 fn make_split_table(splits: &[Split], title: &str) -> Table {
     let rows = splits
         .iter()
@@ -475,6 +1035,8 @@ struct SplitRow {
 
 #[cfg(test)]
 mod tests {
+    use quickwit_config::IndexConfig;
+    use quickwit_proto::types::DocMappingUid;
     use reqwest::Url;
     use time::macros::datetime;
 
@@ -595,6 +1157,162 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_split_export_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from(vec![
+            "split",
+            "export",
+            "--index",
+            "wikipedia",
+            "--splits",
+            "split1,split2",
+            "--output-path",
+            "wikipedia.tar",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::Split(SplitCliCommand::Export(ExportSplitsArgs {
+                index_id,
+                split_ids: Some(split_ids),
+                output_path,
+                ..
+            })) if index_id == "wikipedia"
+                && split_ids == vec!["split1".to_string(), "split2".to_string()]
+                && output_path == PathBuf::from("wikipedia.tar")
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_split_import_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from(vec![
+            "split",
+            "import",
+            "--input-path",
+            "wikipedia.tar",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::Split(SplitCliCommand::Import(ImportSplitsArgs {
+                index_id: None,
+                input_path,
+                ..
+            })) if input_path == PathBuf::from("wikipedia.tar")
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_split_verify_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from(vec![
+            "split",
+            "verify",
+            "--index",
+            "wikipedia",
+            "--splits",
+            "split1,split2",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::Split(SplitCliCommand::Verify(VerifySplitsArgs {
+                index_id,
+                split_ids: Some(split_ids),
+                ..
+            })) if index_id == "wikipedia"
+                && split_ids == vec!["split1".to_string(), "split2".to_string()]
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_check_split_checksum() {
+        let split_content = b"split content".to_vec();
+        let mut split_metadata = SplitMetadata::for_test("split".to_string());
+        check_split_checksum(&split_metadata, &split_content[..])
+            .await
+            .unwrap();
+
+        split_metadata.crc32c_checksum = Some(crc32c::crc32c(&split_content));
+        check_split_checksum(&split_metadata, &split_content[..])
+            .await
+            .unwrap();
+
+        let error = check_split_checksum(&split_metadata, &b"corrupted"[..])
+            .await
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("checksum mismatch for split `split`"));
+    }
+
+    #[test]
+    fn test_split_archive_manifest() {
+        let mut archive_builder = tar::Builder::new(Vec::new());
+        let manifest = SplitArchiveManifest {
+            version: SPLIT_ARCHIVE_VERSION.to_string(),
+            index_id: "wikipedia".to_string(),
+            doc_mapping: IndexConfig::for_test("wikipedia", "ram:///indexes/wikipedia").doc_mapping,
+            splits: vec![SplitMetadata::for_test("split1".to_string())],
+        };
+        let manifest_json = serde_json::to_vec(&manifest).unwrap();
+        append_archive_entry(
+            &mut archive_builder,
+            SPLIT_ARCHIVE_MANIFEST_PATH,
+            &manifest_json,
+        )
+        .unwrap();
+        append_archive_entry(&mut archive_builder, "split1.split", b"split1").unwrap();
+        let archive_data = archive_builder.into_inner().unwrap();
+
+        let mut archive = tar::Archive::new(archive_data.as_slice());
+        let mut archive_entries = archive.entries().unwrap();
+        let manifest = read_split_archive_manifest(&mut archive_entries).unwrap();
+        assert_eq!(manifest.index_id, "wikipedia");
+        assert_eq!(manifest.splits.len(), 1);
+        assert_eq!(manifest.splits[0].split_id, "split1");
+
+        let mut split_entry = archive_entries.next().unwrap().unwrap();
+        assert_eq!(
+            split_entry.path().unwrap().as_ref(),
+            Path::new("split1.split")
+        );
+        let mut split_data = Vec::new();
+        split_entry.read_to_end(&mut split_data).unwrap();
+        assert_eq!(split_data, b"split1");
+
+        let mut archive_builder = tar::Builder::new(Vec::new());
+        append_archive_entry(&mut archive_builder, "split1.split", b"split1").unwrap();
+        let archive_data = archive_builder.into_inner().unwrap();
+        let mut archive = tar::Archive::new(archive_data.as_slice());
+        let mut archive_entries = archive.entries().unwrap();
+        read_split_archive_manifest(&mut archive_entries).unwrap_err();
+    }
+
+    #[test]
+    fn test_check_doc_mapping_compatibility() {
+        let index_config = IndexConfig::for_test("wikipedia", "ram:///indexes/wikipedia");
+        let archive_doc_mapping = index_config.doc_mapping.clone();
+
+        let mut index_doc_mapping = index_config.doc_mapping.clone();
+        index_doc_mapping.doc_mapping_uid = DocMappingUid::random();
+        check_doc_mapping_compatibility(&archive_doc_mapping, &index_doc_mapping).unwrap();
+
+        index_doc_mapping.timestamp_field = None;
+        check_doc_mapping_compatibility(&archive_doc_mapping, &index_doc_mapping).unwrap_err();
+    }
+
     #[test]
     fn test_parse_date() {
         assert_eq!(
@@ -401,8 +401,11 @@ pub async fn local_ingest_docs_cli(args: LocalIngestDocsArgs) -> anyhow::Result<
     println!("❯ Ingesting documents locally...");
 
     let config = load_node_config(&args.config_uri).await?;
-    let (storage_resolver, metastore_resolver) =
-        get_resolvers(&config.storage_configs, &config.metastore_configs);
+    let (storage_resolver, metastore_resolver) = get_resolvers(
+        &config.storage_configs,
+        &config.storage_credentials,
+        &config.metastore_configs,
+    );
     let mut metastore = metastore_resolver.resolve(&config.metastore_uri).await?;
 
     let source_params = if let Some(uri) = args.input_path_opt.as_ref() {
@@ -420,6 +423,8 @@ pub async fn local_ingest_docs_cli(args: LocalIngestDocsArgs) -> anyhow::Result<
         source_params,
         transform_config,
         input_format: args.input_format,
+        activation_windows: Vec::new(),
+        backfill_time_range: None,
     };
     run_index_checklist(
         &mut metastore,
@@ -530,8 +535,11 @@ pub async fn local_search_cli(args: LocalSearchArgs) -> anyhow::Result<()> {
     debug!(args=?args, "local-search");
     println!("❯ Searching directly on the index storage (without calling REST API)...");
     let config = load_node_config(&args.config_uri).await?;
-    let (storage_resolver, metastore_resolver) =
-        get_resolvers(&config.storage_configs, &config.metastore_configs);
+    let (storage_resolver, metastore_resolver) = get_resolvers(
+        &config.storage_configs,
+        &config.storage_credentials,
+        &config.metastore_configs,
+    );
     let metastore: MetastoreServiceClient =
         metastore_resolver.resolve(&config.metastore_uri).await?;
     let aggs = args
@@ -545,6 +553,9 @@ pub async fn local_search_cli(args: LocalSearchArgs) -> anyhow::Result<()> {
         max_hits: args.max_hits as u64,
         search_fields: args.search_fields,
         snippet_fields: args.snippet_fields,
+        docvalue_fields: None,
+        fields: None,
+        exclude_fields: None,
         start_timestamp: args.start_timestamp,
         end_timestamp: args.end_timestamp,
         aggs,
@@ -552,6 +563,7 @@ pub async fn local_search_cli(args: LocalSearchArgs) -> anyhow::Result<()> {
         sort_by,
         count_all: CountHits::CountAll,
         allow_failed_splits: false,
+        profile: false,
     };
     let search_request =
         search_request_from_api_request(vec![args.index_id], search_request_query_string)?;
@@ -568,8 +580,11 @@ pub async fn merge_cli(args: MergeArgs) -> anyhow::Result<()> {
     debug!(args=?args, "run-merge-operations");
     println!("❯ Merging splits locally...");
     let config = load_node_config(&args.config_uri).await?;
-    let (storage_resolver, metastore_resolver) =
-        get_resolvers(&config.storage_configs, &config.metastore_configs);
+    let (storage_resolver, metastore_resolver) = get_resolvers(
+        &config.storage_configs,
+        &config.storage_credentials,
+        &config.metastore_configs,
+    );
     let mut metastore = metastore_resolver.resolve(&config.metastore_uri).await?;
     run_index_checklist(&mut metastore, &storage_resolver, &args.index_id, None).await?;
     // The indexing service needs to update its cluster chitchat state so that the control plane is
@@ -610,6 +625,8 @@ pub async fn merge_cli(args: MergeArgs) -> anyhow::Result<()> {
                 source_params: SourceParams::Vec(VecSourceParams::default()),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                activation_windows: Vec::new(),
+                backfill_time_range: None,
             },
             pipeline_uid: PipelineUid::random(),
         })
@@ -655,8 +672,11 @@ pub async fn garbage_collect_index_cli(args: GarbageCollectIndexArgs) -> anyhow:
     println!("❯ Garbage collecting index...");
 
     let config = load_node_config(&args.config_uri).await?;
-    let (storage_resolver, metastore_resolver) =
-        get_resolvers(&config.storage_configs, &config.metastore_configs);
+    let (storage_resolver, metastore_resolver) = get_resolvers(
+        &config.storage_configs,
+        &config.storage_credentials,
+        &config.metastore_configs,
+    );
     let metastore = metastore_resolver.resolve(&config.metastore_uri).await?;
     let mut index_service = IndexService::new(metastore, storage_resolver);
     let removal_info = index_service
@@ -720,14 +740,25 @@ async fn extract_split_cli(args: ExtractSplitArgs) -> anyhow::Result<()> {
     println!("❯ Extracting split...");
 
     let config = load_node_config(&args.config_uri).await?;
-    let (storage_resolver, metastore_resolver) =
-        get_resolvers(&config.storage_configs, &config.metastore_configs);
+    let (storage_resolver, metastore_resolver) = get_resolvers(
+        &config.storage_configs,
+        &config.storage_credentials,
+        &config.metastore_configs,
+    );
     let metastore = metastore_resolver.resolve(&config.metastore_uri).await?;
     let index_metadata = metastore
         .index_metadata(IndexMetadataRequest::for_index_id(args.index_id))
         .await?
         .deserialize_index_metadata()?;
-    let index_storage = storage_resolver.resolve(index_metadata.index_uri()).await?;
+    let index_storage = storage_resolver
+        .resolve_with_credentials(
+            index_metadata.index_uri(),
+            index_metadata
+                .index_config
+                .storage_credentials_opt
+                .as_deref(),
+        )
+        .await?;
     let split_file = PathBuf::from(format!("{}.split", args.split_id));
     let split_data = index_storage.get_all(split_file.as_path()).await?;
     let (_hotcache_bytes, bundle_storage) = BundleStorage::open_from_split_data_with_owned_bytes(
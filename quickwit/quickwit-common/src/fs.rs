@@ -39,6 +39,16 @@ pub fn get_cache_directory_path(data_dir_path: &Path) -> PathBuf {
 /// Get the total size of the disk containing the given directory, or `None` if
 /// it couldn't be determined.
 pub fn get_disk_size(dir_path: &Path) -> Option<ByteSize> {
+    with_disk(dir_path, |disk| ByteSize::b(disk.total_space()))
+}
+
+/// Get the space available on the disk containing the given directory, or `None` if
+/// it couldn't be determined.
+pub fn get_available_disk_space(dir_path: &Path) -> Option<ByteSize> {
+    with_disk(dir_path, |disk| ByteSize::b(disk.available_space()))
+}
+
+fn with_disk<T>(dir_path: &Path, disk_fn: impl FnOnce(&Disk) -> T) -> Option<T> {
     let disks = sysinfo::Disks::new_with_refreshed_list_specifics(
         DiskRefreshKind::nothing().with_storage(),
     );
@@ -65,7 +75,7 @@ pub fn get_disk_size(dir_path: &Path) -> Option<ByteSize> {
             return None;
         }
     }
-    best_match.map(|(disk, _)| ByteSize::b(disk.total_space()))
+    best_match.map(|(disk, _)| disk_fn(disk))
 }
 
 #[cfg(test)]
@@ -91,6 +91,7 @@ pub fn limiter(throughput: ByteSize) -> Limiter {
 #[derive(Clone)]
 pub struct IoControls {
     throughput_limiter_opt: Option<Limiter>,
+    read_throughput_limiter_opt: Option<Limiter>,
     bytes_counter: IntCounter,
     progress: Progress,
     kill_switch: KillSwitch,
@@ -102,6 +103,7 @@ impl Default for IoControls {
             IntCounter::new("default_write_num_bytes", "Default write counter.").unwrap();
         IoControls {
             throughput_limiter_opt: None,
+            read_throughput_limiter_opt: None,
             progress: Progress::default(),
             kill_switch: KillSwitch::default(),
             bytes_counter: default_bytes_counter,
@@ -151,6 +153,20 @@ impl IoControls {
         self
     }
 
+    /// Sets the limiter applied to the reads performed via `consume_read_blocking`. Unlike the
+    /// throughput limiter, it is not applied to writes.
+    pub fn set_read_throughput_limiter_opt(
+        mut self,
+        read_throughput_limiter_opt: Option<Limiter>,
+    ) -> Self {
+        self.read_throughput_limiter_opt = read_throughput_limiter_opt;
+        self
+    }
+
+    pub fn has_read_throughput_limiter(&self) -> bool {
+        self.read_throughput_limiter_opt.is_some()
+    }
+
     pub fn set_bytes_counter(mut self, bytes_counter: IntCounter) -> Self {
         self.bytes_counter = bytes_counter;
         self
@@ -165,6 +181,16 @@ impl IoControls {
         self.kill_switch = kill_switch;
         self
     }
+
+    /// Blocks until `num_bytes` can be read without exceeding the read throughput limit.
+    pub fn consume_read_blocking(&self, num_bytes: usize) -> io::Result<()> {
+        let _guard = self.check_if_alive()?;
+        if let Some(read_throughput_limiter) = &self.read_throughput_limiter_opt {
+            read_throughput_limiter.blocking_consume(num_bytes);
+        }
+        Ok(())
+    }
+
     fn consume_blocking(&self, num_bytes: usize) -> io::Result<()> {
         let _guard = self.check_if_alive()?;
         if let Some(throughput_limiter) = &self.throughput_limiter_opt {
@@ -401,6 +427,22 @@ mod tests {
         assert_eq!(io_controls.num_bytes(), 200_000u64);
     }
 
+    #[test]
+    fn test_consume_read_blocking_limited() {
+        let io_controls = IoControls::default()
+            .set_read_throughput_limiter_opt(Some(crate::io::limiter(ByteSize::mb(2))));
+        let start = Instant::now();
+        // We read 200 KB
+        for _ in 0..200 {
+            io_controls.consume_read_blocking(1_000).unwrap();
+        }
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(50));
+        assert!(elapsed <= Duration::from_millis(150));
+        // Reads are not accounted as written bytes.
+        assert_eq!(io_controls.num_bytes(), 0u64);
+    }
+
     #[test]
     fn test_controlled_writer_no_limit_sync() {
         let io_controls = IoControls::default();
@@ -17,11 +17,11 @@ use std::sync::OnceLock;
 
 use once_cell::sync::Lazy;
 pub use prometheus::{
-    exponential_buckets, linear_buckets, Histogram, HistogramTimer,
-    HistogramVec as PrometheusHistogramVec, IntCounter, IntCounterVec as PrometheusIntCounterVec,
-    IntGauge, IntGaugeVec as PrometheusIntGaugeVec,
+    exponential_buckets, linear_buckets, Gauge, GaugeVec as PrometheusGaugeVec, Histogram,
+    HistogramTimer, HistogramVec as PrometheusHistogramVec, IntCounter,
+    IntCounterVec as PrometheusIntCounterVec, IntGauge, IntGaugeVec as PrometheusIntGaugeVec,
 };
-use prometheus::{Gauge, HistogramOpts, Opts, TextEncoder};
+use prometheus::{HistogramOpts, Opts, TextEncoder};
 
 #[derive(Clone)]
 pub struct HistogramVec<const N: usize> {
@@ -56,6 +56,17 @@ impl<const N: usize> IntGaugeVec<N> {
     }
 }
 
+#[derive(Clone)]
+pub struct FloatGaugeVec<const N: usize> {
+    underlying: PrometheusGaugeVec,
+}
+
+impl<const N: usize> FloatGaugeVec<N> {
+    pub fn with_label_values(&self, label_values: [&str; N]) -> Gauge {
+        self.underlying.with_label_values(&label_values)
+    }
+}
+
 pub fn register_info(name: &'static str, help: &'static str, kvs: BTreeMap<&'static str, String>) {
     let mut counter_opts = Opts::new(name, help).namespace("quickwit");
     for (k, v) in kvs {
@@ -128,6 +139,30 @@ pub fn new_float_gauge(
     gauge
 }
 
+pub fn new_float_gauge_vec<const N: usize>(
+    name: &str,
+    help: &str,
+    subsystem: &str,
+    const_labels: &[(&str, &str)],
+    label_names: [&str; N],
+) -> FloatGaugeVec<N> {
+    let owned_const_labels: HashMap<String, String> = const_labels
+        .iter()
+        .map(|(label_name, label_value)| (label_name.to_string(), label_value.to_string()))
+        .collect();
+    let gauge_opts = Opts::new(name, help)
+        .namespace("quickwit")
+        .subsystem(subsystem)
+        .const_labels(owned_const_labels);
+    let underlying = PrometheusGaugeVec::new(gauge_opts, &label_names)
+        .expect("failed to create float gauge vec");
+
+    let collector = Box::new(underlying.clone());
+    prometheus::register(collector).expect("failed to register float gauge vec");
+
+    FloatGaugeVec { underlying }
+}
+
 pub fn new_gauge(
     name: &str,
     help: &str,
@@ -13,15 +13,22 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::time::Duration;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
+use pin_project::{pin_project, pinned_drop};
 use prometheus::{Gauge, IntCounter, IntGauge};
 use tokio::runtime::Runtime;
 use tokio_metrics::{RuntimeMetrics, RuntimeMonitor};
 
-use crate::metrics::{new_counter, new_float_gauge, new_gauge};
+use crate::metrics::{
+    exponential_buckets, new_counter, new_counter_vec, new_float_gauge, new_gauge, new_gauge_vec,
+    new_histogram_vec, HistogramVec, IntGaugeVec,
+};
 
 static RUNTIMES: OnceCell<HashMap<RuntimeType, tokio::runtime::Runtime>> = OnceCell::new();
 
@@ -92,6 +99,7 @@ fn start_runtimes(config: RuntimesConfig) -> HashMap<RuntimeType, Runtime> {
         blocking_runtime_builder.disable_lifo_slot();
     }
     let blocking_runtime = blocking_runtime_builder
+        .enable_metrics_poll_time_histogram()
         .worker_threads(config.num_threads_blocking)
         .thread_name_fn(|| {
             static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
@@ -106,6 +114,7 @@ fn start_runtimes(config: RuntimesConfig) -> HashMap<RuntimeType, Runtime> {
     runtimes.insert(RuntimeType::Blocking, blocking_runtime);
 
     let non_blocking_runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_metrics_poll_time_histogram()
         .worker_threads(config.num_threads_non_blocking)
         .thread_name_fn(|| {
             static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
@@ -148,12 +157,17 @@ impl RuntimeType {
     }
 }
 
-/// Spawns a background task
+/// Spawns a background task exporting the metrics of the runtime to Prometheus.
+///
+/// The task poll duration histogram is only exported if the runtime was built with
+/// `enable_metrics_poll_time_histogram`.
 pub fn scrape_tokio_runtime_metrics(handle: &tokio::runtime::Handle, label: &'static str) {
     let runtime_monitor = RuntimeMonitor::new(handle);
+    let poll_duration_buckets = poll_duration_bucket_labels(handle);
     handle.spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_secs(1));
-        let mut prometheus_runtime_metrics = PrometheusRuntimeMetrics::new(label);
+        let mut prometheus_runtime_metrics =
+            PrometheusRuntimeMetrics::new(label, &poll_duration_buckets);
 
         for tokio_runtime_metrics in runtime_monitor.intervals() {
             interval.tick().await;
@@ -162,7 +176,34 @@ pub fn scrape_tokio_runtime_metrics(handle: &tokio::runtime::Handle, label: &'st
     });
 }
 
+/// Returns the upper bounds of the buckets of the task poll duration histogram of the runtime,
+/// formatted as Prometheus `le` label values.
+fn poll_duration_bucket_labels(handle: &tokio::runtime::Handle) -> Vec<String> {
+    let runtime_metrics = handle.metrics();
+
+    if !runtime_metrics.poll_time_histogram_enabled() {
+        return Vec::new();
+    }
+    let num_buckets = runtime_metrics.poll_time_histogram_num_buckets();
+
+    (0..num_buckets)
+        .map(|bucket| {
+            if bucket + 1 == num_buckets {
+                return "+Inf".to_string();
+            }
+            let bucket_range = runtime_metrics.poll_time_histogram_bucket_range(bucket);
+            bucket_range.end.as_secs_f64().to_string()
+        })
+        .collect()
+}
+
 struct PrometheusRuntimeMetrics {
+    global_queue_depth: IntGauge,
+    mean_poll_duration_seconds: Gauge,
+    polls_total: IntCounter,
+    /// Number of polls per bucket of the poll duration histogram, since the last time runtime
+    /// metrics were collected.
+    poll_duration_buckets: Vec<IntCounter>,
     scheduled_tasks: IntGauge,
     worker_busy_duration_milliseconds_total: IntCounter,
     worker_busy_ratio: Gauge,
@@ -170,8 +211,39 @@ struct PrometheusRuntimeMetrics {
 }
 
 impl PrometheusRuntimeMetrics {
-    pub fn new(label: &'static str) -> Self {
+    pub fn new(label: &'static str, poll_duration_buckets: &[String]) -> Self {
+        let poll_duration_bucket_counters = new_counter_vec(
+            "tokio_poll_duration_seconds_bucket_total",
+            "The number of task polls whose duration is lower than or equal to the upper bound \
+             `le` of the bucket and greater than the upper bound of the previous bucket.",
+            "runtime",
+            &[("runtime_type", label)],
+            ["le"],
+        );
         Self {
+            global_queue_depth: new_gauge(
+                "tokio_global_queue_depth",
+                "The number of tasks currently scheduled in the runtime's global queue.",
+                "runtime",
+                &[("runtime_type", label)],
+            ),
+            mean_poll_duration_seconds: new_float_gauge(
+                "tokio_mean_poll_duration_seconds",
+                "The mean duration of the task polls since the last time runtime metrics were \
+                 collected.",
+                "runtime",
+                &[("runtime_type", label)],
+            ),
+            polls_total: new_counter(
+                "tokio_polls_total",
+                "The total number of task polls.",
+                "runtime",
+                &[("runtime_type", label)],
+            ),
+            poll_duration_buckets: poll_duration_buckets
+                .iter()
+                .map(|bucket| poll_duration_bucket_counters.with_label_values([bucket.as_str()]))
+                .collect(),
             scheduled_tasks: new_gauge(
                 "tokio_scheduled_tasks",
                 "The total number of tasks currently scheduled in workers' local queues.",
@@ -201,6 +273,19 @@ impl PrometheusRuntimeMetrics {
     }
 
     pub fn update(&mut self, runtime_metrics: &RuntimeMetrics) {
+        self.global_queue_depth
+            .set(runtime_metrics.injection_queue_depth as i64);
+        self.mean_poll_duration_seconds
+            .set(runtime_metrics.mean_poll_duration.as_secs_f64());
+        self.polls_total.inc_by(runtime_metrics.total_polls_count);
+
+        for (bucket_counter, num_polls) in self
+            .poll_duration_buckets
+            .iter()
+            .zip(&runtime_metrics.poll_count_histogram)
+        {
+            bucket_counter.inc_by(*num_polls);
+        }
         self.scheduled_tasks
             .set(runtime_metrics.total_local_queue_depth as i64);
         self.worker_busy_duration_milliseconds_total
@@ -211,6 +296,95 @@ impl PrometheusRuntimeMetrics {
     }
 }
 
+struct TaskMetrics {
+    in_flight_tasks: IntGaugeVec<1>,
+    poll_duration_seconds: HistogramVec<1>,
+}
+
+static TASK_METRICS: Lazy<TaskMetrics> = Lazy::new(|| TaskMetrics {
+    in_flight_tasks: new_gauge_vec(
+        "tokio_instrumented_tasks_in_flight",
+        "The number of instrumented tasks currently in flight.",
+        "runtime",
+        &[],
+        ["task_group"],
+    ),
+    poll_duration_seconds: new_histogram_vec(
+        "tokio_instrumented_task_poll_duration_seconds",
+        "The duration of the polls of the instrumented tasks. Polls longer than a few hundred \
+         microseconds starve the other tasks of the runtime.",
+        "runtime",
+        &[],
+        ["task_group"],
+        exponential_buckets(0.000_01, 4.0, 8).unwrap(),
+    ),
+});
+
+/// Instruments a future so that the duration of each of its polls is recorded in the
+/// `tokio_instrumented_task_poll_duration_seconds` histogram, labelled with `task_group`, and the
+/// number of such futures in flight in the `tokio_instrumented_tasks_in_flight` gauge.
+///
+/// This is meant to attribute runtime starvation to a subsystem (`search`, `ingest`, ...).
+pub fn instrument_task<F: Future>(future: F, task_group: &'static str) -> InstrumentedTask<F> {
+    InstrumentedTask {
+        future,
+        task_group,
+        in_flight: false,
+    }
+}
+
+#[pin_project(PinnedDrop)]
+pub struct InstrumentedTask<F> {
+    #[pin]
+    future: F,
+    task_group: &'static str,
+    in_flight: bool,
+}
+
+impl<F: Future> Future for InstrumentedTask<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        if !*this.in_flight {
+            *this.in_flight = true;
+            TASK_METRICS
+                .in_flight_tasks
+                .with_label_values([*this.task_group])
+                .inc();
+        }
+        let start = Instant::now();
+        let poll_result = this.future.poll(cx);
+        TASK_METRICS
+            .poll_duration_seconds
+            .with_label_values([*this.task_group])
+            .observe(start.elapsed().as_secs_f64());
+
+        if poll_result.is_ready() {
+            *this.in_flight = false;
+            TASK_METRICS
+                .in_flight_tasks
+                .with_label_values([*this.task_group])
+                .dec();
+        }
+        poll_result
+    }
+}
+
+#[pinned_drop]
+impl<F> PinnedDrop for InstrumentedTask<F> {
+    fn drop(self: Pin<&mut Self>) {
+        // The future was dropped before completion.
+        if self.in_flight {
+            TASK_METRICS
+                .in_flight_tasks
+                .with_label_values([self.task_group])
+                .dec();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,6 +403,38 @@ mod tests {
         assert_eq!(runtime.num_threads_non_blocking, 2);
     }
 
+    #[tokio::test]
+    async fn test_instrument_task() {
+        let in_flight_tasks = TASK_METRICS
+            .in_flight_tasks
+            .with_label_values(["test_instrument_task"]);
+        let poll_duration_seconds = TASK_METRICS
+            .poll_duration_seconds
+            .with_label_values(["test_instrument_task"]);
+
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let join_handle = tokio::spawn(instrument_task(rx, "test_instrument_task"));
+        tokio::task::yield_now().await;
+
+        assert_eq!(in_flight_tasks.get(), 1);
+        assert_eq!(poll_duration_seconds.get_sample_count(), 1);
+
+        tx.send(()).unwrap();
+        join_handle.await.unwrap().unwrap();
+
+        assert_eq!(in_flight_tasks.get(), 0);
+        assert_eq!(poll_duration_seconds.get_sample_count(), 2);
+
+        // Dropping an instrumented task before completion decrements the in-flight gauge.
+        let (_tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let mut instrumented_task = Box::pin(instrument_task(rx, "test_instrument_task"));
+        assert!(futures::poll!(&mut instrumented_task).is_pending());
+        assert_eq!(in_flight_tasks.get(), 1);
+
+        drop(instrumented_task);
+        assert_eq!(in_flight_tasks.get(), 0);
+    }
+
     #[test]
     fn test_runtimes_with_given_num_cpus_3() {
         let runtime = RuntimesConfig::with_num_cpus(3);
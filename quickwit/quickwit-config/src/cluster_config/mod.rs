@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::num::NonZeroU32;
+
 use bytesize::ByteSize;
 use quickwit_common::uri::Uri;
 
@@ -25,6 +27,9 @@ pub struct ClusterConfig {
     pub replication_factor: usize,
     pub shard_throughput_limit: ByteSize,
     pub shard_scale_up_factor: f32,
+    /// Number of most recent published splits of each index reported to the searcher split
+    /// caches by the control plane. Prewarming is disabled if unset.
+    pub split_cache_prewarm_num_splits_per_index: Option<NonZeroU32>,
 }
 
 impl ClusterConfig {
@@ -37,6 +42,7 @@ impl ClusterConfig {
             replication_factor: 1,
             shard_throughput_limit: quickwit_common::shared_consts::DEFAULT_SHARD_THROUGHPUT_LIMIT,
             shard_scale_up_factor: 1.01,
+            split_cache_prewarm_num_splits_per_index: None,
         }
     }
 }
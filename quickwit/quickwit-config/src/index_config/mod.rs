@@ -14,6 +14,7 @@
 
 pub(crate) mod serialize;
 
+use std::collections::{BTreeMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -24,9 +25,14 @@ use bytesize::ByteSize;
 use chrono::Utc;
 use cron::Schedule;
 use humantime::parse_duration;
+use once_cell::sync::Lazy;
+use quickwit_common::is_false;
 use quickwit_common::uri::Uri;
 use quickwit_doc_mapper::{DocMapper, DocMapperBuilder, DocMapping};
 use quickwit_proto::types::IndexId;
+use quickwit_query::query_ast::UserQueryDefaults;
+use quickwit_query::BooleanOperand;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 pub use serialize::{load_index_config_from_user_config, load_index_config_update};
 use siphasher::sip::SipHasher;
@@ -34,6 +40,11 @@ use tracing::warn;
 
 use crate::index_config::serialize::VersionedIndexConfig;
 use crate::merge_policy_config::MergePolicyConfig;
+use crate::validate_identifier;
+
+/// Minimum heap size of the in-memory segment of a split, below which segments would be spilled
+/// to disk too often.
+const MIN_SEGMENT_HEAP_SIZE: ByteSize = ByteSize::mb(15);
 
 #[derive(Clone, Debug, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(deny_unknown_fields)]
@@ -41,6 +52,14 @@ pub struct IndexingResources {
     #[schema(value_type = String, default = "2 GB")]
     #[serde(default = "IndexingResources::default_heap_size")]
     pub heap_size: ByteSize,
+    /// Maximum heap size of the in-memory segment of a split. When reached, the segment is
+    /// written to the scratch directory of the split and a new segment is started. The
+    /// segments are merged into a single one when the split is finalized, reading the columns
+    /// from memory-mapped files.
+    #[schema(value_type = Option<String>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segment_heap_size: Option<ByteSize>,
     // DEPRECATED: See #4439
     #[schema(value_type = String)]
     #[serde(default)]
@@ -50,13 +69,14 @@ pub struct IndexingResources {
 
 impl PartialEq for IndexingResources {
     fn eq(&self, other: &Self) -> bool {
-        self.heap_size == other.heap_size
+        self.heap_size == other.heap_size && self.segment_heap_size == other.segment_heap_size
     }
 }
 
 impl Hash for IndexingResources {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.heap_size.hash(state);
+        self.segment_heap_size.hash(state);
     }
 }
 
@@ -80,6 +100,18 @@ impl IndexingResources {
                  version. See #4439. A global limit now exists in indexer configuration."
             );
         }
+        if let Some(segment_heap_size) = self.segment_heap_size {
+            ensure!(
+                segment_heap_size >= MIN_SEGMENT_HEAP_SIZE,
+                "`segment_heap_size` must be at least {MIN_SEGMENT_HEAP_SIZE}, got \
+                 {segment_heap_size}"
+            );
+            ensure!(
+                segment_heap_size < self.heap_size,
+                "`segment_heap_size` must be lower than `heap_size` ({}), got {segment_heap_size}",
+                self.heap_size
+            );
+        }
         Ok(())
     }
 }
@@ -88,17 +120,96 @@ impl Default for IndexingResources {
     fn default() -> Self {
         Self {
             heap_size: Self::default_heap_size(),
+            segment_heap_size: None,
             max_merge_write_throughput: None,
         }
     }
 }
 
+/// Codec used to compress the docstore of the splits.
+#[derive(
+    Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize, utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DocstoreCompression {
+    /// Best compression ratio, tunable with `docstore_compression_level`.
+    #[default]
+    Zstd,
+    /// Faster compression and decompression, at the cost of a lower compression ratio.
+    Lz4,
+    None,
+}
+
+impl DocstoreCompression {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Archive storage classes, whose objects must be restored before they can be read.
+const ARCHIVE_STORAGE_CLASSES: [&str; 2] = ["GLACIER", "DEEP_ARCHIVE"];
+
+/// Object storage classes in which the splits are uploaded, e.g. `STANDARD_IA` or `GLACIER_IR` on
+/// Amazon S3. When unset, splits are uploaded in the default storage class of the bucket.
+///
+/// The storage class of a split is chosen once, at upload time: a split uploaded as immature
+/// keeps its storage class after it matures.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SplitStorageClasses {
+    /// Storage class of the splits that are still to be merged.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub immature: Option<String>,
+    /// Storage class of the splits that are mature when uploaded, such as the output of the last
+    /// merges.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mature: Option<String>,
+}
+
+impl SplitStorageClasses {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns the storage class in which a split is uploaded given its maturity.
+    pub fn storage_class(&self, is_mature: bool) -> Option<&str> {
+        if is_mature {
+            self.mature.as_deref()
+        } else {
+            self.immature.as_deref()
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        for storage_class in self.immature.iter().chain(&self.mature) {
+            ensure!(
+                !storage_class.is_empty(),
+                "`indexing_settings.split_storage_classes` must not contain empty storage classes"
+            );
+            // Splits must remain readable by the searchers, mergers, and janitor.
+            ensure!(
+                !ARCHIVE_STORAGE_CLASSES.contains(&storage_class.as_str()),
+                "archive storage class `{storage_class}` is not supported for splits, use an \
+                 instant retrieval storage class such as `GLACIER_IR` instead"
+            );
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Hash, utoipa::ToSchema)]
 #[serde(deny_unknown_fields)]
 pub struct IndexingSettings {
     #[schema(default = 60)]
     #[serde(default = "IndexingSettings::default_commit_timeout_secs")]
     pub commit_timeout_secs: usize,
+    /// Codec used to compress the blocks of the docstore.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "DocstoreCompression::is_default")]
+    pub docstore_compression: DocstoreCompression,
+    /// Compression level of the docstore, only used by the `zstd` codec.
     #[schema(default = 8)]
     #[serde(default = "IndexingSettings::default_docstore_compression_level")]
     pub docstore_compression_level: i32,
@@ -112,10 +223,23 @@ pub struct IndexingSettings {
     /// `split_num_docs_target` are considered mature and never merged.
     #[serde(default = "IndexingSettings::default_split_num_docs_target")]
     pub split_num_docs_target: usize,
+    /// Target size of the splits on disk. When set, the indexer also commits once the documents
+    /// in its workbench reach this size, and splits whose size is greater than or equal to
+    /// `split_num_bytes_target` are considered mature and never merged.
+    ///
+    /// This produces splits of similar sizes across indexes whose documents have very different
+    /// sizes, which `split_num_docs_target` alone does not.
+    #[schema(value_type = Option<String>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split_num_bytes_target: Option<ByteSize>,
     #[serde(default)]
     pub merge_policy: MergePolicyConfig,
     #[serde(default)]
     pub resources: IndexingResources,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "SplitStorageClasses::is_default")]
+    pub split_storage_classes: SplitStorageClasses,
 }
 
 impl IndexingSettings {
@@ -153,19 +277,131 @@ impl Default for IndexingSettings {
         Self {
             commit_timeout_secs: Self::default_commit_timeout_secs(),
             docstore_blocksize: Self::default_docstore_blocksize(),
+            docstore_compression: DocstoreCompression::default(),
             docstore_compression_level: Self::default_docstore_compression_level(),
             split_num_docs_target: Self::default_split_num_docs_target(),
+            split_num_bytes_target: None,
             merge_policy: MergePolicyConfig::default(),
             resources: IndexingResources::default(),
+            split_storage_classes: SplitStorageClasses::default(),
         }
     }
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(deny_unknown_fields)]
 pub struct SearchSettings {
     #[serde(default)]
     pub default_search_fields: Vec<String>,
+    /// Operator combining the terms of the query strings that do not specify it. Defaults to
+    /// `AND`.
+    #[schema(value_type = Option<String>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_operator: Option<BooleanOperand>,
+    /// Whether the query strings that do not specify it are parsed leniently, ignoring the
+    /// clauses targeting fields missing from the doc mapping instead of failing.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub lenient: bool,
+    /// Per-role restrictions enforced on the searches performed with an API key. When empty,
+    /// searches are not restricted.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub security_policies: Vec<SecurityPolicy>,
+    /// When set, the time range of the searches is clamped to the retention period of the index,
+    /// so that the searches without a time filter, or with an overly wide one, do not list the
+    /// splits that are about to be deleted.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub clamp_to_retention_period: bool,
+    /// Maximum value accepted for `start_offset` and `max_hits` in a search request. Deeper
+    /// pagination should rely on `search_after`.
+    #[schema(default = 10_000)]
+    #[serde(default = "SearchSettings::default_max_result_window")]
+    #[serde(skip_serializing_if = "SearchSettings::is_default_max_result_window")]
+    pub max_result_window: u64,
+}
+
+impl SearchSettings {
+    /// Upper bound of `max_result_window`, which keeps the cost of a single page bounded.
+    pub const MAX_RESULT_WINDOW_LIMIT: u64 = 1_000_000;
+
+    pub fn default_max_result_window() -> u64 {
+        10_000
+    }
+
+    fn is_default_max_result_window(max_result_window: &u64) -> bool {
+        *max_result_window == Self::default_max_result_window()
+    }
+
+    /// Returns the defaults applied to the user queries that do not specify their search fields,
+    /// default operator, or leniency.
+    pub fn user_query_defaults(&self) -> UserQueryDefaults<'_> {
+        UserQueryDefaults {
+            default_search_fields: &self.default_search_fields,
+            default_operator: self.default_operator.unwrap_or(BooleanOperand::And),
+            lenient: self.lenient,
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.max_result_window > 0 && self.max_result_window <= Self::MAX_RESULT_WINDOW_LIMIT,
+            "search settings parameter `max_result_window` must be within [1, {}], but got {}",
+            Self::MAX_RESULT_WINDOW_LIMIT,
+            self.max_result_window
+        );
+        let mut roles = HashSet::new();
+
+        for security_policy in &self.security_policies {
+            ensure!(
+                !security_policy.role.is_empty(),
+                "security policy role must not be empty"
+            );
+            ensure!(
+                roles.insert(&security_policy.role),
+                "security policy role `{}` is defined more than once",
+                security_policy.role
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        Self {
+            default_search_fields: Vec::new(),
+            default_operator: None,
+            lenient: false,
+            security_policies: Vec::new(),
+            clamp_to_retention_period: false,
+            max_result_window: Self::default_max_result_window(),
+        }
+    }
+}
+
+/// Restricts the fields a role can retrieve from an index and the documents it can search.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SecurityPolicy {
+    /// Role the policy applies to.
+    pub role: String,
+    /// Fields the role is allowed to retrieve. Dots denote nested fields. All the fields can be
+    /// retrieved when unset.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retrievable_fields: Option<Vec<String>>,
+    /// Query added as a mandatory filter to the searches of the role. It can reference the claims
+    /// of the API key with `{claims.<name>}`, for instance `tenant_id:{claims.tenant}`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// Whether the role is allowed to update the tags of the documents it can search.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub allow_updates: bool,
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
@@ -228,9 +464,199 @@ impl RetentionPolicy {
     }
 }
 
+/// Progress of the rollout of a candidate index.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RolloutState {
+    /// The candidate index receives a share of the traffic of the primary index.
+    #[default]
+    Evaluating,
+    /// The doc mapping and settings of the candidate index replaced those of the primary index.
+    Promoted,
+    /// The candidate index was discarded.
+    RolledBack,
+}
+
+/// Blue/green rollout of a new index config.
+///
+/// The rollout is declared on a candidate index created alongside the primary index with the new
+/// config. While the rollout is evaluated, a percentage of the documents ingested into the primary
+/// index and of the searches targeting it are mirrored to the candidate index. At the end of the
+/// evaluation period, the control plane compares the two indexes and either promotes the candidate
+/// config to the primary index or rolls it back.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct IndexRollout {
+    #[schema(value_type = String)]
+    pub primary_index_id: IndexId,
+    /// Percentage of the ingest requests of the primary index mirrored to the candidate index.
+    #[serde(default)]
+    pub ingest_percentage: f64,
+    /// Percentage of the search requests of the primary index mirrored to the candidate index.
+    #[serde(default)]
+    pub search_percentage: f64,
+    /// Duration of the evaluation, expressed in a human-friendly way (`1 hour`, `3 days`, ...).
+    #[serde(default = "IndexRollout::default_evaluation_period")]
+    pub evaluation_period: String,
+    /// Minimum number of documents the candidate index must hold to be promoted.
+    #[serde(default)]
+    pub min_num_docs: u64,
+    /// Maximum ratio between the number of bytes per document of the candidate index and of the
+    /// primary index.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_storage_ratio: Option<f64>,
+    /// Maximum ratio between the number of bytes scanned per query of the candidate index and of
+    /// the primary index.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_scanned_bytes_ratio: Option<f64>,
+    #[serde(default)]
+    pub state: RolloutState,
+}
+
+impl IndexRollout {
+    pub fn default_evaluation_period() -> String {
+        "1 hour".to_string()
+    }
+
+    pub fn evaluation_period(&self) -> anyhow::Result<Duration> {
+        parse_duration(&self.evaluation_period).with_context(|| {
+            format!(
+                "failed to parse rollout evaluation period `{}`",
+                self.evaluation_period
+            )
+        })
+    }
+
+    pub fn is_evaluating(&self) -> bool {
+        self.state == RolloutState::Evaluating
+    }
+
+    pub(super) fn validate(&self, index_id: &str) -> anyhow::Result<()> {
+        validate_identifier("primary index", &self.primary_index_id)?;
+        ensure!(
+            self.primary_index_id != index_id,
+            "rollout primary index must differ from the candidate index `{index_id}`"
+        );
+        for (name, percentage) in [
+            ("ingest_percentage", self.ingest_percentage),
+            ("search_percentage", self.search_percentage),
+        ] {
+            ensure!(
+                (0.0..=100.0).contains(&percentage),
+                "rollout `{name}` must be between 0 and 100, got {percentage}"
+            );
+        }
+        ensure!(
+            self.ingest_percentage > 0.0 || self.search_percentage > 0.0,
+            "rollout must mirror some traffic: `ingest_percentage` or `search_percentage` must be \
+             strictly positive"
+        );
+        for (name, ratio_opt) in [
+            ("max_storage_ratio", self.max_storage_ratio),
+            ("max_scanned_bytes_ratio", self.max_scanned_bytes_ratio),
+        ] {
+            if let Some(ratio) = ratio_opt {
+                ensure!(
+                    ratio > 0.0,
+                    "rollout `{name}` must be strictly positive, got {ratio}"
+                );
+            }
+        }
+        self.evaluation_period()?;
+        Ok(())
+    }
+}
+
+/// Maximum number of labels attached to an index.
+const MAX_NUM_INDEX_LABELS: usize = 64;
+
+/// Maximum length of the value of an index label.
+const MAX_INDEX_LABEL_VALUE_LEN: usize = 255;
+
+/// Validates the labels attached to an index. Label keys are identifiers that may also contain
+/// `/` chars, such as `team` or `company.com/data-class`. Label values must not contain commas, so
+/// that label selectors can be passed as comma-separated lists.
+fn validate_index_labels(labels: &BTreeMap<String, String>) -> anyhow::Result<()> {
+    ensure!(
+        labels.len() <= MAX_NUM_INDEX_LABELS,
+        "an index cannot have more than {MAX_NUM_INDEX_LABELS} labels, got {}",
+        labels.len()
+    );
+    for (key, value) in labels {
+        validate_index_label_key(key)?;
+        validate_index_label_value(key, value)?;
+    }
+    Ok(())
+}
+
+fn validate_index_label_key(key: &str) -> anyhow::Result<()> {
+    static LABEL_KEY_REGEX: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"^[a-zA-Z][a-zA-Z0-9-_\./]{0,62}$").expect("regular expression should compile")
+    });
+    ensure!(
+        LABEL_KEY_REGEX.is_match(key),
+        "index label key `{key}` is invalid: label keys must match the following regular \
+         expression: `^[a-zA-Z][a-zA-Z0-9-_\\./]{{0,62}}$`"
+    );
+    Ok(())
+}
+
+fn validate_index_label_value(key: &str, value: &str) -> anyhow::Result<()> {
+    ensure!(
+        value.len() <= MAX_INDEX_LABEL_VALUE_LEN && !value.contains(','),
+        "value of index label `{key}` is invalid: label values must be at most \
+         {MAX_INDEX_LABEL_VALUE_LEN} bytes long and must not contain commas"
+    );
+    Ok(())
+}
+
+/// Selects the indexes carrying a label, parsed from `key`, or carrying a label with a given
+/// value, parsed from `key:value`. Keys and values are validated like the labels of an index.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IndexLabelSelector {
+    pub key: String,
+    pub value_opt: Option<String>,
+}
+
+impl IndexLabelSelector {
+    pub fn matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        match (labels.get(&self.key), &self.value_opt) {
+            (Some(value), Some(expected_value)) => value == expected_value,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+impl FromStr for IndexLabelSelector {
+    type Err = anyhow::Error;
+
+    fn from_str(selector: &str) -> anyhow::Result<Self> {
+        let (key, value_opt) = match selector.split_once(':') {
+            Some((key, value)) => (key, Some(value.to_string())),
+            None => (selector, None),
+        };
+        ensure!(
+            !key.is_empty(),
+            "index label selector `{selector}` is invalid: the label key must not be empty"
+        );
+        validate_index_label_key(key)?;
+
+        if let Some(value) = &value_opt {
+            validate_index_label_value(key, value)?;
+        }
+        Ok(IndexLabelSelector {
+            key: key.to_string(),
+            value_opt,
+        })
+    }
+}
+
 /// Prepends an `@` char at the start of the cron expression if necessary:
 /// `hourly` -> `@hourly`
-fn prepend_at_char(schedule: &str) -> String {
+pub(crate) fn prepend_at_char(schedule: &str) -> String {
     let trimmed_schedule = schedule.trim();
 
     if !trimmed_schedule.is_empty()
@@ -242,6 +668,18 @@ fn prepend_at_char(schedule: &str) -> String {
     trimmed_schedule.to_string()
 }
 
+/// Freeze of the ingestion into an index, set during incident response to stop indexing new
+/// documents, e.g. when a producer is flooding the index with garbage.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct IngestionFreeze {
+    /// Why ingestion was frozen.
+    #[serde(default)]
+    pub reason: String,
+    /// Unix timestamp in seconds of the freeze.
+    pub frozen_at: i64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 #[serde(into = "VersionedIndexConfig")]
@@ -253,9 +691,26 @@ pub struct IndexConfig {
     pub indexing_settings: IndexingSettings,
     pub search_settings: SearchSettings,
     pub retention_policy_opt: Option<RetentionPolicy>,
+    pub rollout_opt: Option<IndexRollout>,
+    /// When set, ingestion into the index is frozen: its sources are not scheduled and the
+    /// ingest API rejects documents. Search is unaffected.
+    pub ingestion_freeze_opt: Option<IngestionFreeze>,
+    /// Name of the storage credentials defined in the node config used to access the index
+    /// storage. When absent, the default storage configs of the node are used.
+    pub storage_credentials_opt: Option<String>,
+    /// Arbitrary key-value pairs describing the index, such as its owning team or the class of
+    /// the data it holds, used to find indexes in the catalog.
+    pub labels: BTreeMap<String, String>,
 }
 
 impl IndexConfig {
+    /// Returns whether the index carries the labels selected by all the selectors.
+    pub fn matches_label_selectors(&self, label_selectors: &[IndexLabelSelector]) -> bool {
+        label_selectors
+            .iter()
+            .all(|label_selector| label_selector.matches(&self.labels))
+    }
+
     /// Return a fingerprint of parameters relevant for indexers
     ///
     /// This should remain private to this crate to avoid confusion with the
@@ -278,6 +733,25 @@ impl IndexConfig {
         self.indexing_params_fingerprint() == other.indexing_params_fingerprint()
     }
 
+    /// Returns the config of this index once the config of a rollout candidate index is promoted:
+    /// the doc mapping, indexing settings, and search settings of the candidate replace those of
+    /// this index.
+    pub fn promote_rollout_candidate(
+        &self,
+        candidate_index_config: &IndexConfig,
+    ) -> anyhow::Result<IndexConfig> {
+        let mut doc_mapping = candidate_index_config.doc_mapping.clone();
+        serialize::validate_doc_mapping_update(&self.doc_mapping, &mut doc_mapping)?;
+
+        let promoted_index_config = IndexConfig {
+            doc_mapping,
+            indexing_settings: candidate_index_config.indexing_settings.clone(),
+            search_settings: candidate_index_config.search_settings.clone(),
+            ..self.clone()
+        };
+        Ok(promoted_index_config)
+    }
+
     #[cfg(any(test, feature = "testsuite"))]
     pub fn for_test(index_id: &str, index_uri: &str) -> Self {
         let index_uri = Uri::from_str(index_uri).unwrap();
@@ -353,6 +827,11 @@ impl IndexConfig {
                 r#"attributes.server"#.to_string(),
                 r"attributes.server\.status".to_string(),
             ],
+            default_operator: None,
+            lenient: false,
+            security_policies: Vec::new(),
+            clamp_to_retention_period: false,
+            max_result_window: 10_000,
         };
         IndexConfig {
             index_id: index_id.to_string(),
@@ -361,6 +840,10 @@ impl IndexConfig {
             indexing_settings,
             search_settings,
             retention_policy_opt: Default::default(),
+            rollout_opt: None,
+            ingestion_freeze_opt: None,
+            storage_credentials_opt: None,
+            labels: BTreeMap::new(),
         }
     }
 }
@@ -371,7 +854,7 @@ impl crate::TestableForRegression for IndexConfig {
         use std::collections::BTreeSet;
         use std::num::NonZeroU32;
 
-        use quickwit_doc_mapper::Mode;
+        use quickwit_doc_mapper::{Mode, Strictness};
         use quickwit_proto::types::DocMappingUid;
 
         use crate::merge_policy_config::StableLogMergePolicyConfig;
@@ -420,6 +903,7 @@ impl crate::TestableForRegression for IndexConfig {
         let doc_mapping = DocMapping {
             doc_mapping_uid: DocMappingUid::for_test(1),
             mode: Mode::default(),
+            strictness: Strictness::default(),
             field_mappings: vec![
                 tenant_id_mapping,
                 timestamp_mapping,
@@ -432,6 +916,7 @@ impl crate::TestableForRegression for IndexConfig {
             max_num_partitions: NonZeroU32::new(100).unwrap(),
             index_field_presence: true,
             store_document_size: false,
+            store_ingest_timestamp: false,
             store_source: true,
             tokenizers: vec![tokenizer],
         };
@@ -458,6 +943,11 @@ impl crate::TestableForRegression for IndexConfig {
         };
         let search_settings = SearchSettings {
             default_search_fields: vec!["message".to_string()],
+            default_operator: None,
+            lenient: false,
+            security_policies: Vec::new(),
+            clamp_to_retention_period: false,
+            max_result_window: 10_000,
         };
         IndexConfig {
             index_id: "my-index".to_string(),
@@ -465,6 +955,10 @@ impl crate::TestableForRegression for IndexConfig {
             doc_mapping,
             indexing_settings,
             retention_policy_opt: retention_policy,
+            rollout_opt: None,
+            ingestion_freeze_opt: None,
+            storage_credentials_opt: None,
+            labels: BTreeMap::new(),
             search_settings,
         }
     }
@@ -486,6 +980,8 @@ pub fn build_doc_mapper(
     let builder = DocMapperBuilder {
         doc_mapping: doc_mapping.clone(),
         default_search_fields: search_settings.default_search_fields.clone(),
+        default_operator: search_settings.default_operator,
+        lenient: search_settings.lenient,
         legacy_type_tag: None,
     };
     Ok(Arc::new(builder.try_build()?))
@@ -506,6 +1002,23 @@ pub(super) fn validate_index_config(
 
     indexing_settings.merge_policy.validate()?;
     indexing_settings.resources.validate()?;
+    indexing_settings.split_storage_classes.validate()?;
+
+    if indexing_settings.docstore_compression == DocstoreCompression::Zstd {
+        ensure!(
+            indexing_settings.docstore_compression_level <= 22,
+            "`indexing_settings.docstore_compression_level` must be at most 22 with the `zstd` \
+             codec, but got {}",
+            indexing_settings.docstore_compression_level
+        );
+    }
+    if let Some(split_num_bytes_target) = indexing_settings.split_num_bytes_target {
+        ensure!(
+            split_num_bytes_target.as_u64() > 0,
+            "`indexing_settings.split_num_bytes_target` must be strictly positive"
+        );
+    }
+    search_settings.validate()?;
 
     if let Some(retention_policy) = retention_policy_opt {
         retention_policy.validate()?;
@@ -602,6 +1115,11 @@ mod tests {
             index_config.search_settings,
             SearchSettings {
                 default_search_fields: vec!["severity_text".to_string(), "body".to_string()],
+                default_operator: None,
+                lenient: false,
+                security_policies: Vec::new(),
+                clamp_to_retention_period: false,
+                max_result_window: 10_000,
             }
         );
     }
@@ -644,6 +1162,11 @@ mod tests {
                 index_config.search_settings,
                 SearchSettings {
                     default_search_fields: vec!["body".to_string()],
+                    default_operator: None,
+                    lenient: false,
+                    security_policies: Vec::new(),
+                    clamp_to_retention_period: false,
+                    max_result_window: 10_000,
                 }
             );
         }
@@ -678,6 +1201,11 @@ mod tests {
                 index_config.search_settings,
                 SearchSettings {
                     default_search_fields: vec!["body".to_string()],
+                    default_operator: None,
+                    lenient: false,
+                    security_policies: Vec::new(),
+                    clamp_to_retention_period: false,
+                    max_result_window: 10_000,
                 }
             );
         }
@@ -740,6 +1268,606 @@ mod tests {
             .contains("failed to parse human-readable duration `x`"));
     }
 
+    #[test]
+    fn test_index_config_with_security_policies() {
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            search_settings:
+              security_policies:
+                - role: support
+                  retrievable_fields: [timestamp, body]
+                  filter: "tenant_id:{claims.tenant}"
+                - role: admin
+                  allow_updates: true
+        "#;
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap();
+        assert_eq!(
+            index_config.search_settings.security_policies,
+            [
+                SecurityPolicy {
+                    role: "support".to_string(),
+                    retrievable_fields: Some(vec!["timestamp".to_string(), "body".to_string()]),
+                    filter: Some("tenant_id:{claims.tenant}".to_string()),
+                    allow_updates: false,
+                },
+                SecurityPolicy {
+                    role: "admin".to_string(),
+                    retrievable_fields: None,
+                    filter: None,
+                    allow_updates: true,
+                },
+            ]
+        );
+
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            search_settings:
+              security_policies:
+                - role: support
+                - role: support
+        "#;
+        let error = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("security policy role `support` is defined more than once"));
+    }
+
+    #[test]
+    fn test_index_config_with_max_result_window() {
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+        "#;
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap();
+        assert_eq!(index_config.search_settings.max_result_window, 10_000);
+
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            search_settings:
+              max_result_window: 50000
+        "#;
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap();
+        assert_eq!(index_config.search_settings.max_result_window, 50_000);
+
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            search_settings:
+              max_result_window: 0
+        "#;
+        let error = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("`max_result_window` must be within [1, 1000000], but got 0"));
+    }
+
+    #[test]
+    fn test_index_config_with_default_query_settings() {
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping:
+              field_mappings:
+                - name: body
+                  type: text
+            search_settings:
+              default_search_fields: [body]
+              default_operator: OR
+              lenient: true
+        "#;
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap();
+        assert_eq!(
+            index_config.search_settings.default_operator,
+            Some(BooleanOperand::Or)
+        );
+        assert!(index_config.search_settings.lenient);
+
+        let doc_mapper =
+            build_doc_mapper(&index_config.doc_mapping, &index_config.search_settings).unwrap();
+        let user_query_defaults = doc_mapper.user_query_defaults();
+        assert_eq!(user_query_defaults.default_search_fields, ["body"]);
+        assert_eq!(user_query_defaults.default_operator, BooleanOperand::Or);
+        assert!(user_query_defaults.lenient);
+    }
+
+    #[test]
+    fn test_index_config_with_rollout() {
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs-candidate
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            rollout:
+              primary_index_id: hdfs-logs
+              ingest_percentage: 10
+              evaluation_period: 1 day
+              max_storage_ratio: 1.2
+        "#;
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap();
+        let rollout = index_config.rollout_opt.unwrap();
+        assert_eq!(rollout.primary_index_id, "hdfs-logs");
+        assert_eq!(rollout.ingest_percentage, 10.0);
+        assert_eq!(rollout.search_percentage, 0.0);
+        assert_eq!(
+            rollout.evaluation_period().unwrap(),
+            Duration::from_secs(24 * 3600)
+        );
+        assert_eq!(rollout.max_storage_ratio, Some(1.2));
+        assert_eq!(rollout.state, RolloutState::Evaluating);
+
+        for (rollout_yaml, expected_error) in [
+            (
+                "primary_index_id: hdfs-logs-candidate\n  ingest_percentage: 10",
+                "must differ from the candidate index",
+            ),
+            (
+                "primary_index_id: hdfs-logs\n  ingest_percentage: 110",
+                "must be between 0 and 100",
+            ),
+            (
+                "primary_index_id: hdfs-logs",
+                "rollout must mirror some traffic",
+            ),
+            (
+                "primary_index_id: hdfs-logs\n  search_percentage: 5\n  max_storage_ratio: 0",
+                "must be strictly positive",
+            ),
+        ] {
+            let config_yaml = format!(
+                "version: 0.8\nindex_id: hdfs-logs-candidate\ndoc_mapping: {{}}\nrollout:\n  \
+                 {rollout_yaml}\n"
+            );
+            let error = load_index_config_from_user_config(
+                ConfigFormat::Yaml,
+                config_yaml.as_bytes(),
+                &Uri::for_test("s3://my-index"),
+            )
+            .unwrap_err();
+            assert!(
+                error.to_string().contains(expected_error),
+                "`{error}` should contain `{expected_error}`"
+            );
+        }
+    }
+
+    #[test]
+    fn test_index_config_with_ingestion_freeze() {
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            ingestion_freeze:
+              reason: producer bug
+              frozen_at: 1700000000
+        "#;
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap();
+        let expected_ingestion_freeze = IngestionFreeze {
+            reason: "producer bug".to_string(),
+            frozen_at: 1_700_000_000,
+        };
+        assert_eq!(
+            index_config.ingestion_freeze_opt,
+            Some(expected_ingestion_freeze)
+        );
+        let index_config_json = serde_json::to_string(&index_config).unwrap();
+        let deserialized_index_config: IndexConfig =
+            serde_json::from_str(&index_config_json).unwrap();
+        assert_eq!(deserialized_index_config, index_config);
+    }
+
+    #[test]
+    fn test_index_config_with_storage_credentials() {
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://account-a-bucket/hdfs-logs"
+            doc_mapping: {}
+            storage_credentials: account-a
+        "#;
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap();
+        assert_eq!(
+            index_config.storage_credentials_opt.as_deref(),
+            Some("account-a")
+        );
+        let index_config_json = serde_json::to_value(&index_config).unwrap();
+        assert_eq!(index_config_json["storage_credentials"], "account-a");
+
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            doc_mapping: {}
+            storage_credentials: "a?"
+        "#;
+        let error = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("storage credentials ID `a?` is invalid"));
+    }
+
+    #[test]
+    fn test_index_config_with_labels() {
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            labels:
+              team: search
+              company.com/data-class: pii
+        "#;
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap();
+        assert_eq!(index_config.labels.len(), 2);
+        let index_config_json = serde_json::to_value(&index_config).unwrap();
+        assert_eq!(index_config_json["labels"]["team"], "search");
+
+        let label_selectors = |selectors: &[&str]| -> Vec<IndexLabelSelector> {
+            selectors
+                .iter()
+                .map(|selector| selector.parse().unwrap())
+                .collect()
+        };
+        assert!(index_config.matches_label_selectors(&[]));
+        assert!(index_config.matches_label_selectors(&label_selectors(&["team"])));
+        assert!(index_config
+            .matches_label_selectors(&label_selectors(&["team:search", "company.com/data-class"])));
+        assert!(!index_config.matches_label_selectors(&label_selectors(&["team:ingest"])));
+        assert!(!index_config.matches_label_selectors(&label_selectors(&["team", "env"])));
+        "".parse::<IndexLabelSelector>().unwrap_err();
+        "team' OR 1=1 --".parse::<IndexLabelSelector>().unwrap_err();
+        "1team".parse::<IndexLabelSelector>().unwrap_err();
+        "team:a,b".parse::<IndexLabelSelector>().unwrap_err();
+        format!("team:{}", "a".repeat(MAX_INDEX_LABEL_VALUE_LEN + 1))
+            .parse::<IndexLabelSelector>()
+            .unwrap_err();
+        assert_eq!(
+            "team:o'search".parse::<IndexLabelSelector>().unwrap(),
+            IndexLabelSelector {
+                key: "team".to_string(),
+                value_opt: Some("o'search".to_string()),
+            }
+        );
+
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            doc_mapping: {}
+            labels:
+              "team:name": search
+        "#;
+        let error = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("index label key `team:name` is invalid"));
+
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            doc_mapping: {}
+            labels:
+              teams: search,ingest
+        "#;
+        let error = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("value of index label `teams` is invalid"));
+    }
+
+    #[test]
+    fn test_index_config_promote_rollout_candidate() {
+        let primary_index_config = IndexConfig::for_test("hdfs-logs", "s3://hdfs-logs");
+        let mut candidate_index_config =
+            IndexConfig::for_test("hdfs-logs-candidate", "s3://hdfs-logs-candidate");
+        candidate_index_config.indexing_settings.commit_timeout_secs = 5;
+        candidate_index_config.search_settings.max_result_window = 100;
+
+        let promoted_index_config = primary_index_config
+            .promote_rollout_candidate(&candidate_index_config)
+            .unwrap();
+        assert_eq!(promoted_index_config.index_id, "hdfs-logs");
+        assert_eq!(
+            promoted_index_config.index_uri,
+            primary_index_config.index_uri
+        );
+        assert_eq!(
+            promoted_index_config.indexing_settings.commit_timeout_secs,
+            5
+        );
+        assert_eq!(promoted_index_config.search_settings.max_result_window, 100);
+
+        // The doc mapping UID must change along with the doc mapping.
+        candidate_index_config.doc_mapping.timestamp_field = None;
+        let error = primary_index_config
+            .promote_rollout_candidate(&candidate_index_config)
+            .unwrap_err();
+        assert!(error.to_string().contains("must change"));
+    }
+
+    #[test]
+    fn test_index_config_with_docstore_compression() {
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+        "#;
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap();
+        assert_eq!(
+            index_config.indexing_settings.docstore_compression,
+            DocstoreCompression::Zstd
+        );
+
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            indexing_settings:
+              docstore_compression: lz4
+        "#;
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap();
+        assert_eq!(
+            index_config.indexing_settings.docstore_compression,
+            DocstoreCompression::Lz4
+        );
+
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            indexing_settings:
+              docstore_compression: zstd
+              docstore_compression_level: 23
+        "#;
+        let error = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("`indexing_settings.docstore_compression_level` must be at most 22"));
+    }
+
+    #[test]
+    fn test_index_config_with_split_num_bytes_target() {
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            indexing_settings:
+              split_num_bytes_target: 2GB
+        "#;
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap();
+        assert_eq!(
+            index_config.indexing_settings.split_num_bytes_target,
+            Some(ByteSize::gb(2))
+        );
+
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            indexing_settings:
+              split_num_bytes_target: 0
+        "#;
+        let error = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("`indexing_settings.split_num_bytes_target` must be strictly positive"));
+    }
+
+    #[test]
+    fn test_index_config_with_split_storage_classes() {
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            indexing_settings:
+              split_storage_classes:
+                mature: GLACIER_IR
+        "#;
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap();
+        let split_storage_classes = &index_config.indexing_settings.split_storage_classes;
+        assert_eq!(split_storage_classes.storage_class(false), None);
+        assert_eq!(
+            split_storage_classes.storage_class(true),
+            Some("GLACIER_IR")
+        );
+
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            indexing_settings:
+              split_storage_classes:
+                immature: STANDARD
+                mature: DEEP_ARCHIVE
+        "#;
+        let error = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("archive storage class `DEEP_ARCHIVE` is not supported for splits"));
+    }
+
+    #[test]
+    fn test_index_config_with_segment_heap_size() {
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            indexing_settings:
+              resources:
+                heap_size: 1GB
+                segment_heap_size: 256MB
+        "#;
+        let index_config = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap();
+        assert_eq!(
+            index_config.indexing_settings.resources.segment_heap_size,
+            Some(ByteSize::mb(256))
+        );
+
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            indexing_settings:
+              resources:
+                heap_size: 1GB
+                segment_heap_size: 2GB
+        "#;
+        let error = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("`segment_heap_size` must be lower than `heap_size`"));
+
+        let config_yaml = r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping: {}
+            indexing_settings:
+              resources:
+                segment_heap_size: 1MB
+        "#;
+        let error = load_index_config_from_user_config(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Uri::for_test("s3://my-index"),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("`segment_heap_size` must be at least"));
+    }
+
     #[test]
     fn test_retention_policy_serialization() {
         let retention_policy = RetentionPolicy {
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 use anyhow::{ensure, Context};
 use quickwit_common::uri::Uri;
@@ -21,10 +21,10 @@ use quickwit_proto::types::{DocMappingUid, IndexId};
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
-use super::validate_index_config;
+use super::{validate_index_config, validate_index_labels};
 use crate::{
-    validate_identifier, ConfigFormat, DocMapping, IndexConfig, IndexingSettings, RetentionPolicy,
-    SearchSettings,
+    validate_identifier, ConfigFormat, DocMapping, IndexConfig, IndexRollout, IndexingSettings,
+    IngestionFreeze, RetentionPolicy, SearchSettings,
 };
 
 /// Alias for the latest serialization format.
@@ -104,59 +104,65 @@ pub fn load_index_config_update(
             .search_settings
             .default_search_fields
             .clone(),
+        default_operator: new_index_config.search_settings.default_operator,
+        lenient: new_index_config.search_settings.lenient,
         legacy_type_tag: None,
     };
     doc_mapper_builder
         .try_build()
         .context("invalid mapping update")?;
 
-    {
-        let new_mapping_uid = new_index_config.doc_mapping.doc_mapping_uid;
-        // we verify whether they are equal ignoring the mapping uid as it is generated at random:
-        // we don't want to record a mapping change when nothing really happened.
-        new_index_config.doc_mapping.doc_mapping_uid =
-            current_index_config.doc_mapping.doc_mapping_uid;
-        if new_index_config.doc_mapping != current_index_config.doc_mapping {
-            new_index_config.doc_mapping.doc_mapping_uid = new_mapping_uid;
-            ensure!(
-                current_index_config.doc_mapping.doc_mapping_uid
-                    != new_index_config.doc_mapping.doc_mapping_uid,
-                "`doc_mapping_doc_mapping_uid` must change when the doc mapping is updated",
-            );
-            ensure!(
-                current_index_config.doc_mapping.timestamp_field
-                    == new_index_config.doc_mapping.timestamp_field,
-                "`doc_mapping.timestamp_field` cannot be updated, current value {}, new expected \
-                 value {}",
-                current_index_config
-                    .doc_mapping
-                    .timestamp_field
-                    .as_deref()
-                    .unwrap_or("<none>"),
-                new_index_config
-                    .doc_mapping
-                    .timestamp_field
-                    .as_deref()
-                    .unwrap_or("<none>"),
-            );
-            // TODO: i'm not sure this is necessary, we can relax this requirement once we know
-            // for sure
-            let current_tokenizers: HashSet<_> =
-                current_index_config.doc_mapping.tokenizers.iter().collect();
-            let new_tokenizers: HashSet<_> =
-                new_index_config.doc_mapping.tokenizers.iter().collect();
-            ensure!(
-                new_tokenizers.is_superset(&current_tokenizers),
-                "`.doc_mapping.tokenizers` must be a superset of previously available tokenizers"
-            );
-        } else {
-            // the docmapping is unchanged, keep the old uid
-        }
-    }
-
+    validate_doc_mapping_update(
+        &current_index_config.doc_mapping,
+        &mut new_index_config.doc_mapping,
+    )?;
     Ok(new_index_config)
 }
 
+/// Ensures that a doc mapping can replace the current doc mapping of an index. If the two doc
+/// mappings are identical, the UID of the current doc mapping is kept.
+pub(super) fn validate_doc_mapping_update(
+    current_doc_mapping: &DocMapping,
+    new_doc_mapping: &mut DocMapping,
+) -> anyhow::Result<()> {
+    let new_mapping_uid = new_doc_mapping.doc_mapping_uid;
+    // we verify whether they are equal ignoring the mapping uid as it is generated at random:
+    // we don't want to record a mapping change when nothing really happened.
+    new_doc_mapping.doc_mapping_uid = current_doc_mapping.doc_mapping_uid;
+
+    if *new_doc_mapping != *current_doc_mapping {
+        new_doc_mapping.doc_mapping_uid = new_mapping_uid;
+        ensure!(
+            current_doc_mapping.doc_mapping_uid != new_doc_mapping.doc_mapping_uid,
+            "`doc_mapping_doc_mapping_uid` must change when the doc mapping is updated",
+        );
+        ensure!(
+            current_doc_mapping.timestamp_field == new_doc_mapping.timestamp_field,
+            "`doc_mapping.timestamp_field` cannot be updated, current value {}, new expected \
+             value {}",
+            current_doc_mapping
+                .timestamp_field
+                .as_deref()
+                .unwrap_or("<none>"),
+            new_doc_mapping
+                .timestamp_field
+                .as_deref()
+                .unwrap_or("<none>"),
+        );
+        // TODO: i'm not sure this is necessary, we can relax this requirement once we know
+        // for sure
+        let current_tokenizers: HashSet<_> = current_doc_mapping.tokenizers.iter().collect();
+        let new_tokenizers: HashSet<_> = new_doc_mapping.tokenizers.iter().collect();
+        ensure!(
+            new_tokenizers.is_superset(&current_tokenizers),
+            "`.doc_mapping.tokenizers` must be a superset of previously available tokenizers"
+        );
+    } else {
+        // the docmapping is unchanged, keep the old uid
+    }
+    Ok(())
+}
+
 impl IndexConfigForSerialization {
     fn index_uri_or_fallback_to_default(
         &self,
@@ -191,6 +197,10 @@ impl IndexConfigForSerialization {
             indexing_settings: self.indexing_settings,
             search_settings: self.search_settings,
             retention_policy_opt: self.retention_policy_opt,
+            rollout_opt: self.rollout_opt,
+            ingestion_freeze_opt: self.ingestion_freeze_opt,
+            storage_credentials_opt: self.storage_credentials_opt,
+            labels: self.labels,
         };
         validate_index_config(
             &index_config.doc_mapping,
@@ -198,6 +208,13 @@ impl IndexConfigForSerialization {
             &index_config.search_settings,
             &index_config.retention_policy_opt,
         )?;
+        if let Some(rollout) = &index_config.rollout_opt {
+            rollout.validate(&index_config.index_id)?;
+        }
+        if let Some(storage_credentials) = &index_config.storage_credentials_opt {
+            validate_identifier("storage credentials", storage_credentials)?;
+        }
+        validate_index_labels(&index_config.labels)?;
         Ok(index_config)
     }
 }
@@ -239,6 +256,21 @@ pub struct IndexConfigV0_8 {
     #[serde(rename = "retention")]
     #[serde(default)]
     pub retention_policy_opt: Option<RetentionPolicy>,
+    #[serde(rename = "rollout")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rollout_opt: Option<IndexRollout>,
+    #[serde(rename = "ingestion_freeze")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingestion_freeze_opt: Option<IngestionFreeze>,
+    #[serde(rename = "storage_credentials")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_credentials_opt: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
 }
 
 impl From<IndexConfig> for IndexConfigV0_8 {
@@ -250,6 +282,10 @@ impl From<IndexConfig> for IndexConfigV0_8 {
             indexing_settings: index_config.indexing_settings,
             search_settings: index_config.search_settings,
             retention_policy_opt: index_config.retention_policy_opt,
+            rollout_opt: index_config.rollout_opt,
+            ingestion_freeze_opt: index_config.ingestion_freeze_opt,
+            storage_credentials_opt: index_config.storage_credentials_opt,
+            labels: index_config.labels,
         }
     }
 }
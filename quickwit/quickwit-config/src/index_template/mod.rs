@@ -14,7 +14,11 @@
 
 mod serialize;
 
-use anyhow::ensure;
+use std::time::Duration;
+
+use anyhow::{ensure, Context};
+use bytesize::ByteSize;
+use humantime::parse_duration;
 use quickwit_common::uri::Uri;
 use quickwit_proto::types::{DocMappingUid, IndexId};
 use serde::{Deserialize, Serialize};
@@ -49,6 +53,71 @@ pub struct IndexTemplate {
     #[serde(rename = "retention")]
     #[serde(default)]
     pub retention_policy_opt: Option<RetentionPolicy>,
+    #[serde(rename = "rollover")]
+    #[serde(default)]
+    pub rollover_policy_opt: Option<RolloverPolicy>,
+}
+
+/// Conditions under which the janitor rolls over the indexes managed by a template.
+///
+/// Documents ingested into the write alias are routed to the latest generation of the alias,
+/// an index named `<write_alias>-<generation>`, for instance `logs-000001`. When the latest
+/// generation meets one of the conditions, the janitor creates the next one from the template.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RolloverPolicy {
+    #[schema(value_type = String)]
+    pub write_alias: IndexId,
+    /// Maximum age of a generation, for instance `7 days`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<String>,
+    /// Maximum size of the splits of a generation on storage.
+    #[schema(value_type = Option<String>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<ByteSize>,
+    /// Maximum number of documents of a generation.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_num_docs: Option<u64>,
+}
+
+impl RolloverPolicy {
+    pub fn max_age(&self) -> anyhow::Result<Option<Duration>> {
+        let Some(max_age) = &self.max_age else {
+            return Ok(None);
+        };
+        let max_age = parse_duration(max_age)
+            .with_context(|| format!("failed to parse rollover max age `{max_age}`"))?;
+        Ok(Some(max_age))
+    }
+
+    /// Returns the ID of the index of the given generation of the write alias.
+    pub fn generation_index_id(&self, generation: u64) -> IndexId {
+        format!("{}-{generation:06}", self.write_alias)
+    }
+
+    /// Returns the generation of the index if it belongs to the write alias.
+    pub fn parse_generation(&self, index_id: &str) -> Option<u64> {
+        let generation_str = index_id
+            .strip_prefix(&self.write_alias)?
+            .strip_prefix('-')?;
+        if generation_str.len() < 6 || !generation_str.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        generation_str.parse().ok()
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        validate_identifier("write alias", &self.write_alias)?;
+        ensure!(
+            self.max_age.is_some() || self.max_size.is_some() || self.max_num_docs.is_some(),
+            "rollover policy must define at least one of `max_age`, `max_size`, or `max_num_docs`"
+        );
+        self.max_age()?;
+        Ok(())
+    }
 }
 
 impl IndexTemplate {
@@ -74,6 +143,10 @@ impl IndexTemplate {
             indexing_settings: self.indexing_settings.clone(),
             search_settings: self.search_settings.clone(),
             retention_policy_opt: self.retention_policy_opt.clone(),
+            rollout_opt: None,
+            ingestion_freeze_opt: None,
+            storage_credentials_opt: None,
+            labels: Default::default(),
         };
         Ok(index_config)
     }
@@ -94,6 +167,9 @@ impl IndexTemplate {
             &self.search_settings,
             &self.retention_policy_opt,
         )?;
+        if let Some(rollover_policy) = &self.rollover_policy_opt {
+            rollover_policy.validate()?;
+        }
         Ok(())
     }
 
@@ -130,6 +206,7 @@ impl IndexTemplate {
             indexing_settings: IndexingSettings::default(),
             search_settings: SearchSettings::default(),
             retention_policy_opt: None,
+            rollover_policy_opt: None,
         }
     }
 }
@@ -173,6 +250,7 @@ impl crate::TestableForRegression for IndexTemplate {
                 retention_period: "42 days".to_string(),
                 evaluation_schedule: "daily".to_string(),
             }),
+            rollover_policy_opt: None,
         }
     }
 
@@ -227,6 +305,11 @@ mod tests {
         };
         index_template.search_settings = SearchSettings {
             default_search_fields: vec!["message".to_string()],
+            default_operator: None,
+            lenient: false,
+            security_policies: Vec::new(),
+            clamp_to_retention_period: false,
+            max_result_window: 10_000,
         };
         index_template.retention_policy_opt = Some(RetentionPolicy {
             retention_period: "42 days".to_string(),
@@ -292,4 +375,52 @@ mod tests {
             .to_string()
             .contains("failed to parse retention period"));
     }
+
+    #[test]
+    fn test_rollover_policy() {
+        let rollover_policy_yaml = r#"
+            write_alias: logs
+            max_age: 7 days
+            max_size: 50GB
+        "#;
+        let rollover_policy: RolloverPolicy = serde_yaml::from_str(rollover_policy_yaml).unwrap();
+        rollover_policy.validate().unwrap();
+        assert_eq!(
+            rollover_policy.max_age().unwrap(),
+            Some(Duration::from_secs(7 * 24 * 3600))
+        );
+        assert_eq!(rollover_policy.max_size, Some(ByteSize::gb(50)));
+        assert_eq!(rollover_policy.max_num_docs, None);
+
+        assert_eq!(rollover_policy.generation_index_id(42), "logs-000042");
+        assert_eq!(rollover_policy.parse_generation("logs-000042"), Some(42));
+        assert_eq!(
+            rollover_policy.parse_generation("logs-1234567"),
+            Some(1234567)
+        );
+        assert_eq!(rollover_policy.parse_generation("logs-42"), None);
+        assert_eq!(rollover_policy.parse_generation("logs-app-000042"), None);
+        assert_eq!(rollover_policy.parse_generation("logs"), None);
+
+        let rollover_policy = RolloverPolicy {
+            write_alias: "logs".to_string(),
+            max_age: None,
+            max_size: None,
+            max_num_docs: None,
+        };
+        let error = rollover_policy.validate().unwrap_err();
+        assert!(error.to_string().contains("at least one of"));
+
+        let mut index_template = IndexTemplate::for_test("test-template", &["logs-*"], 0);
+        index_template.rollover_policy_opt = Some(RolloverPolicy {
+            write_alias: "logs".to_string(),
+            max_age: Some("forever".to_string()),
+            max_size: None,
+            max_num_docs: None,
+        });
+        let error = index_template.validate().unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("failed to parse rollover max age"));
+    }
 }
@@ -44,8 +44,10 @@ pub use cluster_config::ClusterConfig;
 // See #2048
 use index_config::serialize::{IndexConfigV0_8, VersionedIndexConfig};
 pub use index_config::{
-    build_doc_mapper, load_index_config_from_user_config, load_index_config_update, IndexConfig,
-    IndexingResources, IndexingSettings, RetentionPolicy, SearchSettings,
+    build_doc_mapper, load_index_config_from_user_config, load_index_config_update,
+    DocstoreCompression, IndexConfig, IndexLabelSelector, IndexRollout, IndexingResources,
+    IndexingSettings, IngestionFreeze, RetentionPolicy, RolloutState, SearchSettings,
+    SecurityPolicy, SplitStorageClasses,
 };
 pub use quickwit_doc_mapper::DocMapping;
 use serde::de::DeserializeOwned;
@@ -54,16 +56,19 @@ use serde_json::Value as JsonValue;
 use siphasher::sip::SipHasher;
 use source_config::FileSourceParamsForSerde;
 pub use source_config::{
-    load_source_config_from_user_config, load_source_config_update, FileSourceMessageType,
-    FileSourceNotification, FileSourceParams, FileSourceSqs, KafkaSourceParams,
-    KinesisSourceParams, PubSubSourceParams, PulsarSourceAuth, PulsarSourceParams,
-    RegionOrEndpoint, SourceConfig, SourceInputFormat, SourceParams, TransformConfig,
-    VecSourceParams, VoidSourceParams, CLI_SOURCE_ID, INGEST_API_SOURCE_ID, INGEST_V2_SOURCE_ID,
+    load_source_config_from_user_config, load_source_config_update, BackfillTimeRange,
+    FileSourceMessageType, FileSourceNotification, FileSourceParams, FileSourceSqs,
+    KafkaSourceParams, KinesisSourceParams, PubSubSourceParams, PulsarSourceAuth,
+    PulsarSourceParams, RegionOrEndpoint, SourceActivationWindow, SourceConfig, SourceInputFormat,
+    SourceParams, TransformConfig, VecSourceParams, VoidSourceParams, CLI_SOURCE_ID,
+    INGEST_API_SOURCE_ID, INGEST_V2_SOURCE_ID,
 };
 use tracing::warn;
 
 use crate::index_template::IndexTemplateV0_8;
-pub use crate::index_template::{IndexTemplate, IndexTemplateId, VersionedIndexTemplate};
+pub use crate::index_template::{
+    IndexTemplate, IndexTemplateId, RolloverPolicy, VersionedIndexTemplate,
+};
 use crate::merge_policy_config::{
     ConstWriteAmplificationMergePolicyConfig, MergePolicyConfig, StableLogMergePolicyConfig,
 };
@@ -71,13 +76,18 @@ pub use crate::metastore_config::{
     MetastoreBackend, MetastoreConfig, MetastoreConfigs, PostgresMetastoreConfig,
 };
 pub use crate::node_config::{
-    GrpcConfig, IndexerConfig, IngestApiConfig, JaegerConfig, NodeConfig, RestConfig,
-    SearcherConfig, SplitCacheLimits, StorageTimeoutPolicy, TlsConfig, DEFAULT_QW_CONFIG_PATH,
+    ApiKeyConfig, ContentLengthLimits, GrpcCompressionConfig, GrpcConfig, IndexerConfig,
+    IngestApiConfig, JaegerConfig, LeafSearchHedgingPolicy, NodeConfig, OidcConfig,
+    OrphanShardsCleanupConfig, RemoteClusterConfig, RemoteClusterTlsConfig, ReplicationConfig,
+    RestConfig, SearcherConfig, ShadowingConfig, SplitCacheAdmissionPolicy, SplitCacheIndexQuota,
+    SplitCacheLimits, SplitCachePrewarmingConfig, StorageTimeoutPolicy, TlsConfig, TrashConfig,
+    UsageSamplingConfig, DEFAULT_QW_CONFIG_PATH,
 };
 use crate::source_config::serialize::{SourceConfigV0_7, SourceConfigV0_8, VersionedSourceConfig};
 pub use crate::storage_config::{
     AzureStorageConfig, FileStorageConfig, GoogleCloudStorageConfig, RamStorageConfig,
     S3StorageConfig, StorageBackend, StorageBackendFlavor, StorageConfig, StorageConfigs,
+    StorageFaultInjectionConfig,
 };
 
 /// Returns true if the ingest API v2 is enabled.
@@ -98,8 +108,15 @@ pub fn disable_ingest_v1() -> bool {
 #[openapi(components(schemas(
     IndexingResources,
     IndexingSettings,
+    DocstoreCompression,
+    SplitStorageClasses,
     SearchSettings,
+    SecurityPolicy,
     RetentionPolicy,
+    RolloverPolicy,
+    IndexRollout,
+    RolloutState,
+    IngestionFreeze,
     MergePolicyConfig,
     DocMapping,
     VersionedSourceConfig,
@@ -185,6 +202,20 @@ pub fn validate_index_id_pattern(pattern: &str, allow_negative: bool) -> anyhow:
     Ok(())
 }
 
+/// Checks whether an index ID pattern, optionally prefixed with the ID of the remote cluster it
+/// targets (`<cluster_id>:<index_id_pattern>`), conforms to Quickwit conventions. Patterns
+/// targeting a remote cluster cannot be negative.
+pub fn validate_federated_index_id_pattern(
+    pattern: &str,
+    allow_negative: bool,
+) -> anyhow::Result<()> {
+    let Some((cluster_id, index_id_pattern)) = pattern.split_once(':') else {
+        return validate_index_id_pattern(pattern, allow_negative);
+    };
+    validate_identifier("remote cluster", cluster_id)?;
+    validate_index_id_pattern(index_id_pattern, false)
+}
+
 pub fn validate_node_id(node_id: &NodeIdRef) -> anyhow::Result<()> {
     if !is_valid_hostname(node_id.as_str()) {
         bail!(
@@ -334,4 +365,19 @@ mod tests {
         validate_index_id_pattern("-abc", true).unwrap();
         validate_index_id_pattern("-abc", false).unwrap_err();
     }
+
+    #[test]
+    fn test_validate_federated_index_id_pattern() {
+        validate_federated_index_id_pattern("abc.*", false).unwrap();
+        validate_federated_index_id_pattern("-abc", true).unwrap();
+        validate_federated_index_id_pattern("eu-west:abc.*", false).unwrap();
+        validate_federated_index_id_pattern("eu-west:-abc", true).unwrap_err();
+        validate_federated_index_id_pattern("eu-west:", false).unwrap_err();
+        validate_federated_index_id_pattern("eu-west:abc:def", false).unwrap_err();
+
+        assert!(validate_federated_index_id_pattern(":abc", false)
+            .unwrap_err()
+            .to_string()
+            .contains("remote cluster ID `` is invalid"));
+    }
 }
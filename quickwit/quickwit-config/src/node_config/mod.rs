@@ -14,14 +14,14 @@
 
 mod serialize;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
 use std::net::SocketAddr;
 use std::num::{NonZeroU32, NonZeroU64, NonZeroUsize};
 use std::path::PathBuf;
 use std::time::Duration;
 
-use anyhow::{bail, ensure};
+use anyhow::{bail, ensure, Context};
 use bytesize::ByteSize;
 use http::HeaderMap;
 use quickwit_common::net::HostAddr;
@@ -29,15 +29,16 @@ use quickwit_common::shared_consts::{
     DEFAULT_SHARD_BURST_LIMIT, DEFAULT_SHARD_SCALE_UP_FACTOR, DEFAULT_SHARD_THROUGHPUT_LIMIT,
 };
 use quickwit_common::uri::Uri;
+use quickwit_proto::compression::{PayloadCodec, PayloadCompression};
 use quickwit_proto::indexing::CpuCapacity;
-use quickwit_proto::types::NodeId;
-use serde::{Deserialize, Serialize};
+use quickwit_proto::types::{IndexId, NodeId};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use tracing::{info, warn};
 
 use crate::node_config::serialize::load_node_config_with_env;
 use crate::service::QuickwitService;
 use crate::storage_config::StorageConfigs;
-use crate::{ConfigFormat, MetastoreConfigs};
+use crate::{validate_identifier, validate_index_id_pattern, ConfigFormat, MetastoreConfigs};
 
 pub const DEFAULT_QW_CONFIG_PATH: &str = "config/quickwit.yaml";
 
@@ -46,10 +47,156 @@ pub const DEFAULT_QW_CONFIG_PATH: &str = "config/quickwit.yaml";
 pub struct RestConfig {
     pub listen_addr: SocketAddr,
     pub cors_allow_origins: Vec<String>,
+    /// HTTP methods allowed in cross-origin requests. When empty, `GET`, `POST`, `PUT`,
+    /// `DELETE`, and `OPTIONS` are allowed.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cors_allow_methods: Vec<String>,
+    /// Headers allowed in cross-origin requests.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cors_allow_headers: Vec<String>,
+    /// Whether cross-origin requests may include credentials (cookies, `Authorization` header).
+    #[serde(default)]
+    pub cors_allow_credentials: bool,
     #[serde(with = "http_serde::header_map")]
     pub extra_headers: HeaderMap,
     #[serde(default)]
     pub tls: Option<TlsConfig>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub api_keys: Vec<ApiKeyConfig>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oidc: Option<OidcConfig>,
+    /// Roles allowed to use the index management, ingest, delete task, and index template
+    /// endpoints when authentication is enabled. The other roles can only search.
+    #[serde(default = "RestConfig::default_admin_roles")]
+    pub admin_roles: Vec<String>,
+    #[serde(default)]
+    pub content_length_limits: ContentLengthLimits,
+}
+
+impl RestConfig {
+    pub fn default_admin_roles() -> Vec<String> {
+        vec!["admin".to_string()]
+    }
+
+    pub fn redact(&mut self) {
+        for api_key in &mut self.api_keys {
+            api_key.key = "***redacted***".to_string();
+        }
+        if let Some(client_secret) = self
+            .oidc
+            .as_mut()
+            .and_then(|oidc_config| oidc_config.client_secret.as_mut())
+        {
+            *client_secret = "***redacted***".to_string();
+        }
+    }
+}
+
+/// Maximum size of the request bodies accepted by each group of REST endpoints. The size of the
+/// bodies sent to the ingest endpoints is limited by `ingest_api.content_length_limit`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct ContentLengthLimits {
+    /// Search endpoints, including the Elasticsearch compatible ones.
+    pub search: ByteSize,
+    /// Index, source, and split management endpoints.
+    pub index_management: ByteSize,
+}
+
+impl Default for ContentLengthLimits {
+    fn default() -> Self {
+        ContentLengthLimits {
+            search: ByteSize::mib(1),
+            index_management: ByteSize::mib(1),
+        }
+    }
+}
+
+/// An API key accepted by the REST API, along with the role and the claims used to enforce the
+/// index security policies.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeyConfig {
+    pub key: String,
+    pub role: String,
+    #[serde(default)]
+    pub claims: BTreeMap<String, String>,
+}
+
+/// Validates the JWTs issued by an OpenID Connect provider and maps their claims to the roles
+/// used to enforce the index security policies.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OidcConfig {
+    /// Expected `iss` claim. The JWKS URI is discovered from
+    /// `<issuer>/.well-known/openid-configuration` unless `jwks_uri` is set.
+    pub issuer: String,
+    /// Expected `aud` claim.
+    pub audience: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jwks_uri: Option<String>,
+    /// Signature algorithms accepted for the JWTs signed with a key of the JWKS that does not
+    /// specify its algorithm. The tokens signed with a key that specifies it must use that one.
+    #[serde(default = "OidcConfig::default_signature_algorithms")]
+    pub signature_algorithms: Vec<String>,
+    /// Claim holding the role, or the list of roles, of the user.
+    #[serde(default = "OidcConfig::default_role_claim")]
+    pub role_claim: String,
+    /// Maps the values of the role claim to roles. When empty, the values of the role claim are
+    /// used as is.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub role_mapping: BTreeMap<String, String>,
+    /// Name of the cookie holding the JWT, used by the web UI when the token is not passed in the
+    /// `Authorization` header.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cookie_name: Option<String>,
+    /// Client ID registered with the OIDC provider. When set, the web UI users who are not
+    /// authenticated are redirected to the provider to log in with the authorization code flow.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    /// Public URL of the `/oidc/callback` endpoint of this node, registered with the OIDC
+    /// provider as a redirect URI.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_uri: Option<String>,
+}
+
+/// Asymmetric signature algorithms that can be accepted for the JWTs issued by an OIDC provider.
+const OIDC_SIGNATURE_ALGORITHMS: &[&str] = &[
+    "RS256", "RS384", "RS512", "PS256", "PS384", "PS512", "ES256", "ES384", "EdDSA",
+];
+
+impl OidcConfig {
+    fn default_signature_algorithms() -> Vec<String> {
+        vec!["RS256".to_string()]
+    }
+
+    fn default_role_claim() -> String {
+        "roles".to_string()
+    }
+
+    /// Returns true if the web UI users can log in with the authorization code flow.
+    pub fn is_login_enabled(&self) -> bool {
+        self.client_id.is_some()
+    }
+
+    pub fn discovery_uri(&self) -> String {
+        format!(
+            "{}/.well-known/openid-configuration",
+            self.issuer.trim_end_matches('/')
+        )
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -59,6 +206,8 @@ pub struct GrpcConfig {
     pub max_message_size: ByteSize,
     #[serde(default)]
     pub tls: Option<TlsConfig>,
+    #[serde(default)]
+    pub compression: GrpcCompressionConfig,
 }
 
 impl GrpcConfig {
@@ -81,6 +230,40 @@ impl Default for GrpcConfig {
         Self {
             max_message_size: Self::default_max_message_size(),
             tls: None,
+            compression: GrpcCompressionConfig::default(),
+        }
+    }
+}
+
+/// Compression of the large payloads sent between nodes: documents replicated between ingesters
+/// and intermediate aggregation results returned by leaf searches.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GrpcCompressionConfig {
+    #[serde(default)]
+    pub codec: PayloadCodec,
+    #[serde(default = "GrpcCompressionConfig::default_min_payload_size")]
+    pub min_payload_size: ByteSize,
+}
+
+impl GrpcCompressionConfig {
+    fn default_min_payload_size() -> ByteSize {
+        ByteSize::kib(64)
+    }
+
+    pub fn payload_compression(&self) -> PayloadCompression {
+        PayloadCompression {
+            codec: self.codec,
+            min_payload_num_bytes: self.min_payload_size.as_u64() as usize,
+        }
+    }
+}
+
+impl Default for GrpcCompressionConfig {
+    fn default() -> Self {
+        Self {
+            codec: PayloadCodec::None,
+            min_payload_size: Self::default_min_payload_size(),
         }
     }
 }
@@ -112,6 +295,9 @@ pub struct IndexerConfig {
     /// does not starve indexing itself (as it is a latency sensitive operation).
     #[serde(default)]
     pub max_merge_write_throughput: Option<ByteSize>,
+    /// Limits the throughput at which the `MergeExecutor` reads the splits being merged.
+    #[serde(default)]
+    pub max_merge_read_throughput: Option<ByteSize>,
     /// Maximum number of merge or delete operation that can be executed concurrently.
     /// (defaults to num_cpu / 2).
     #[serde(default = "IndexerConfig::default_merge_concurrency")]
@@ -174,6 +360,7 @@ impl IndexerConfig {
             max_concurrent_split_uploads: 4,
             cpu_capacity: PIPELINE_FULL_CAPACITY * 4u32,
             max_merge_write_throughput: None,
+            max_merge_read_throughput: None,
             merge_concurrency: NonZeroUsize::new(3).unwrap(),
         };
         Ok(indexer_config)
@@ -191,11 +378,12 @@ impl Default for IndexerConfig {
             cpu_capacity: Self::default_cpu_capacity(),
             merge_concurrency: Self::default_merge_concurrency(),
             max_merge_write_throughput: None,
+            max_merge_read_throughput: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct SplitCacheLimits {
     pub max_num_bytes: ByteSize,
@@ -203,8 +391,119 @@ pub struct SplitCacheLimits {
     pub max_num_splits: NonZeroU32,
     #[serde(default = "SplitCacheLimits::default_num_concurrent_downloads")]
     pub num_concurrent_downloads: NonZeroU32,
+    /// Number of open split files kept in the file descriptor cache.
     #[serde(default = "SplitCacheLimits::default_max_file_descriptors")]
+    #[serde(alias = "fd_cache_capacity")]
     pub max_file_descriptors: NonZeroU32,
+    /// Maximum number of split files open at the same time, including the ones kept in the file
+    /// descriptor cache. Must be greater than `max_file_descriptors`. When unset, it is derived
+    /// from `max_file_descriptors`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_open_fds: Option<NonZeroU32>,
+    /// Minimum duration, in seconds, a freshly downloaded split is kept in the cache before it
+    /// can be evicted. Prevents a burst of candidates from evicting each other when the cache is
+    /// full.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_residency_secs: Option<NonZeroU64>,
+    /// When set, the splits that have not been accessed for this duration, expressed in a
+    /// human-friendly way (`12 hours`, `7 days`, ...), are evicted from the cache, regardless of
+    /// its size.
+    #[serde(default)]
+    #[serde(deserialize_with = "parse_optional_human_duration")]
+    #[serde(serialize_with = "serialize_optional_duration")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<Duration>,
+    /// Policy deciding whether a candidate split is worth evicting cached splits.
+    #[serde(default)]
+    pub admission_policy: SplitCacheAdmissionPolicy,
+    /// When set, the splits temporally adjacent to the splits targeted by a time-range query
+    /// are reported to the split cache, so that they get downloaded ahead of the next query.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "quickwit_common::is_false")]
+    pub prefetch_adjacent_splits: bool,
+    /// When set, the cached split files are read through memory mappings instead of being
+    /// copied into heap buffers, leaving the residency of their pages to the OS.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "quickwit_common::is_false")]
+    pub use_mmap: bool,
+    /// When set, the footers of the splits, hotcache included, are cached on disk in a second
+    /// tier bounded by this size, for many more splits than the full split files. The other
+    /// reads of these splits are served from object storage.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_footer_num_bytes: Option<ByteSize>,
+    /// Quotas bounding the share of the cache taken by the splits of an index, so that one
+    /// large index cannot evict the splits of all the other indexes. The first quota whose
+    /// pattern matches the ID of an index applies to it.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub index_quotas: Vec<SplitCacheIndexQuota>,
+}
+
+/// Bounds the number of bytes and splits an index can take in the split cache. Each index
+/// matching the pattern gets its own quota.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SplitCacheIndexQuota {
+    /// Index ID pattern, for instance `logs-*`.
+    pub index_id_pattern: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_num_bytes: Option<ByteSize>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_num_splits: Option<NonZeroU32>,
+}
+
+impl SplitCacheIndexQuota {
+    /// Returns true if the index ID matches the pattern of the quota. `*` matches any sequence
+    /// of characters.
+    pub fn matches(&self, index_id: &str) -> bool {
+        let mut parts = self.index_id_pattern.split('*');
+        let prefix = parts.next().unwrap_or_default();
+        let Some(mut remaining) = index_id.strip_prefix(prefix) else {
+            return false;
+        };
+        let mut inner_parts: Vec<&str> = parts.collect();
+        let Some(suffix) = inner_parts.pop() else {
+            // The pattern has no wildcard.
+            return remaining.is_empty();
+        };
+        for inner_part in inner_parts {
+            let Some(position) = remaining.find(inner_part) else {
+                return false;
+            };
+            remaining = &remaining[position + inner_part.len()..];
+        }
+        remaining.ends_with(suffix)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        crate::validate_index_id_pattern(&self.index_id_pattern, false)?;
+
+        if self.max_num_bytes.is_none() && self.max_num_splits.is_none() {
+            bail!(
+                "split cache quota for index ID pattern `{}` must set `max_num_bytes` or \
+                 `max_num_splits`",
+                self.index_id_pattern
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Policy deciding whether a split candidate for download is admitted in a full split cache.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitCacheAdmissionPolicy {
+    /// The most recently accessed candidate is always admitted.
+    #[default]
+    Recency,
+    /// The candidate is only admitted if it has been accessed more frequently than the split it
+    /// would evict, so that one-off scans do not evict hot splits.
+    TinyLfu,
 }
 
 impl SplitCacheLimits {
@@ -219,6 +518,38 @@ impl SplitCacheLimits {
     fn default_max_file_descriptors() -> NonZeroU32 {
         NonZeroU32::new(100).unwrap()
     }
+
+    pub fn min_residency(&self) -> Duration {
+        self.min_residency_secs
+            .map(|min_residency_secs| Duration::from_secs(min_residency_secs.get()))
+            .unwrap_or_default()
+    }
+
+    /// Returns the quota applying to the given index, if any.
+    pub fn index_quota(&self, index_id: &str) -> Option<&SplitCacheIndexQuota> {
+        self.index_quotas
+            .iter()
+            .find(|index_quota| index_quota.matches(index_id))
+    }
+}
+
+fn parse_optional_human_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where D: Deserializer<'de> {
+    let value: String = Deserialize::deserialize(deserializer)?;
+    let duration = humantime::parse_duration(&value).map_err(|error| {
+        de::Error::custom(format!(
+            "failed to parse human-readable duration `{value}`: {error:?}",
+        ))
+    })?;
+    Ok(Some(duration))
+}
+
+fn serialize_optional_duration<S>(value: &Option<Duration>, s: S) -> Result<S::Ok, S::Error>
+where S: Serializer {
+    match value {
+        Some(duration) => s.serialize_str(&humantime::format_duration(*duration).to_string()),
+        None => s.serialize_none(),
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -230,6 +561,12 @@ pub struct SearcherConfig {
     pub split_footer_cache_capacity: ByteSize,
     pub partial_request_cache_capacity: ByteSize,
     pub max_num_concurrent_split_searches: usize,
+    /// Maximum number of split searches waiting for a permit beyond which the searcher rejects
+    /// new leaf search requests, so that the root searcher re-plans their splits onto the other
+    /// searchers.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_num_pending_split_searches: Option<NonZeroUsize>,
     pub max_num_concurrent_split_streams: usize,
     // Strangely, if None, this will also have the effect of not forwarding
     // to searcher.
@@ -241,8 +578,21 @@ pub struct SearcherConfig {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub storage_timeout_policy: Option<StorageTimeoutPolicy>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leaf_search_hedging_policy: Option<LeafSearchHedgingPolicy>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub remote_clusters: Vec<RemoteClusterConfig>,
     pub warmup_memory_budget: ByteSize,
     pub warmup_single_split_initial_allocation: ByteSize,
+    /// Overall memory budget of the searcher caches and aggregations. When set, the aggregation
+    /// memory limit is carved out of the budget and the rest is dynamically partitioned between
+    /// the fast field, split footer, and partial request caches, using their configured
+    /// capacities as initial proportions.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_budget: Option<ByteSize>,
 }
 
 /// Configuration controlling how fast a searcher should timeout a `get_slice`
@@ -278,6 +628,151 @@ impl StorageTimeoutPolicy {
     }
 }
 
+/// Configuration controlling when a root searcher speculatively re-dispatches a slow leaf search
+/// request to another searcher.
+///
+/// A leaf search request that has not completed after the `latency_percentile`-th percentile of
+/// the latencies of the recent leaf search requests (and at least `min_delay_millis`) is sent to
+/// another searcher, and the first response wins. To avoid overloading the cluster when all the
+/// searchers are slow, the number of hedged requests is bounded by `max_hedged_requests_ratio`
+/// times the number of leaf search requests.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct LeafSearchHedgingPolicy {
+    pub latency_percentile: f64,
+    pub min_delay_millis: u64,
+    pub max_hedged_requests_ratio: f64,
+}
+
+impl Default for LeafSearchHedgingPolicy {
+    fn default() -> Self {
+        Self {
+            latency_percentile: 95.0,
+            min_delay_millis: 100,
+            max_hedged_requests_ratio: 0.05,
+        }
+    }
+}
+
+impl LeafSearchHedgingPolicy {
+    pub fn min_delay(&self) -> Duration {
+        Duration::from_millis(self.min_delay_millis)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if !(self.latency_percentile > 0.0 && self.latency_percentile < 100.0) {
+            anyhow::bail!(
+                "leaf_search_hedging_policy.latency_percentile ({}) must be between 0 and 100",
+                self.latency_percentile
+            );
+        }
+        if !(0.0..=1.0).contains(&self.max_hedged_requests_ratio) {
+            anyhow::bail!(
+                "leaf_search_hedging_policy.max_hedged_requests_ratio ({}) must be between 0 and 1",
+                self.max_hedged_requests_ratio
+            );
+        }
+        Ok(())
+    }
+}
+
+/// gRPC port of the remote clusters whose `grpc_endpoint` does not specify one, i.e. the default
+/// REST port plus one.
+const DEFAULT_GRPC_LISTEN_PORT: u16 = 7281;
+
+/// A remote Quickwit cluster to which root search requests can be federated.
+///
+/// The indexes of the remote cluster are targeted with the `<cluster_id>:<index_id_pattern>`
+/// syntax, and only the indexes matching one of `index_id_patterns` can be searched. If
+/// `skip_unavailable` is set, a search request still succeeds when the remote cluster fails to
+/// respond, and the failure is reported in the errors of the response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteClusterConfig {
+    pub cluster_id: String,
+    /// gRPC address of a searcher of the remote cluster, formatted as `<host>:<port>`. The host
+    /// is resolved each time a connection is established.
+    pub grpc_endpoint: String,
+    #[serde(default = "RemoteClusterConfig::default_index_id_patterns")]
+    pub index_id_patterns: Vec<String>,
+    #[serde(default)]
+    pub skip_unavailable: bool,
+    /// TLS settings of the connections to the remote cluster. When not set, the connections are
+    /// not encrypted.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<RemoteClusterTlsConfig>,
+    /// API key sent as a bearer token in the `authorization` metadata of the requests to the
+    /// remote cluster.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
+
+/// TLS settings of the connections to a remote cluster.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteClusterTlsConfig {
+    /// CA certificate validating the certificate of the remote cluster.
+    pub ca_path: String,
+    /// Certificate and key presented to the remote cluster when it validates its clients.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cert_path: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
+    /// Name expected in the certificate of the remote cluster, when it differs from the host of
+    /// `grpc_endpoint`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_name: Option<String>,
+}
+
+impl RemoteClusterConfig {
+    fn default_index_id_patterns() -> Vec<String> {
+        vec!["*".to_string()]
+    }
+
+    pub fn grpc_endpoint(&self) -> anyhow::Result<HostAddr> {
+        HostAddr::parse_with_default_port(&self.grpc_endpoint, DEFAULT_GRPC_LISTEN_PORT)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        validate_identifier("remote cluster", &self.cluster_id)?;
+        self.grpc_endpoint()?;
+
+        if self.index_id_patterns.is_empty() {
+            bail!(
+                "remote cluster `{}` must define at least one index ID pattern",
+                self.cluster_id
+            );
+        }
+        for index_id_pattern in &self.index_id_patterns {
+            validate_index_id_pattern(index_id_pattern, false)?;
+        }
+        if let Some(tls_config) = &self.tls {
+            if tls_config.cert_path.is_some() != tls_config.key_path.is_some() {
+                bail!(
+                    "TLS config of remote cluster `{}` must define both `cert_path` and \
+                     `key_path` or neither",
+                    self.cluster_id
+                );
+            }
+        }
+        if let Some(api_key) = &self.api_key {
+            if api_key.is_empty() || !api_key.chars().all(|c| c.is_ascii_graphic()) {
+                bail!(
+                    "API key of remote cluster `{}` must be a non-empty string of visible ASCII \
+                     characters",
+                    self.cluster_id
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Default for SearcherConfig {
     fn default() -> Self {
         SearcherConfig {
@@ -286,13 +781,17 @@ impl Default for SearcherConfig {
             partial_request_cache_capacity: ByteSize::mb(64),
             max_num_concurrent_split_streams: 100,
             max_num_concurrent_split_searches: 100,
+            max_num_pending_split_searches: None,
             aggregation_memory_limit: ByteSize::mb(500),
             aggregation_bucket_limit: 65000,
             split_cache: None,
             request_timeout_secs: Self::default_request_timeout_secs(),
             storage_timeout_policy: None,
+            leaf_search_hedging_policy: None,
+            remote_clusters: Vec::new(),
             warmup_memory_budget: ByteSize::gb(100),
             warmup_single_split_initial_allocation: ByteSize::gb(1),
+            memory_budget: None,
         }
     }
 }
@@ -306,7 +805,43 @@ impl SearcherConfig {
         NonZeroU64::new(30).unwrap()
     }
     fn validate(&self) -> anyhow::Result<()> {
-        if let Some(split_cache_limits) = self.split_cache {
+        if let Some(leaf_search_hedging_policy) = &self.leaf_search_hedging_policy {
+            leaf_search_hedging_policy.validate()?;
+        }
+        let mut remote_cluster_ids = HashSet::with_capacity(self.remote_clusters.len());
+
+        for remote_cluster in &self.remote_clusters {
+            remote_cluster.validate()?;
+
+            if !remote_cluster_ids.insert(&remote_cluster.cluster_id) {
+                bail!(
+                    "remote cluster ID `{}` is defined more than once",
+                    remote_cluster.cluster_id
+                );
+            }
+        }
+        if let Some(memory_budget) = self.memory_budget {
+            // Each cache must be able to hold a few megabytes.
+            let min_memory_budget = self.aggregation_memory_limit + ByteSize::mb(30);
+
+            if memory_budget < min_memory_budget {
+                bail!(
+                    "memory_budget ({memory_budget}) must be greater than or equal to \
+                     aggregation_memory_limit + 30MB ({min_memory_budget})"
+                );
+            }
+        }
+        if let Some(split_cache_limits) = &self.split_cache {
+            if let Some(max_open_fds) = split_cache_limits.max_open_fds {
+                if max_open_fds <= split_cache_limits.max_file_descriptors {
+                    anyhow::bail!(
+                        "split_cache.max_open_fds ({}) must be greater than \
+                         split_cache.max_file_descriptors ({})",
+                        max_open_fds,
+                        split_cache_limits.max_file_descriptors
+                    );
+                }
+            }
             if self.max_num_concurrent_split_searches
                 > split_cache_limits.max_file_descriptors.get() as usize
             {
@@ -335,6 +870,9 @@ impl SearcherConfig {
                     self.warmup_memory_budget
                 );
             }
+            for index_quota in &split_cache_limits.index_quotas {
+                index_quota.validate()?;
+            }
         }
         Ok(())
     }
@@ -349,6 +887,14 @@ pub struct IngestApiConfig {
     pub max_queue_disk_usage: ByteSize,
     replication_factor: usize,
     pub content_length_limit: ByteSize,
+    /// When set, the Elasticsearch bulk requests are ingested in batches of at most
+    /// `content_length_limit` as their body is received and decompressed, instead of being fully
+    /// buffered in memory. Their body is then limited to this size.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streaming_content_length_limit: Option<ByteSize>,
+    /// Maximum memory taken by the requests ingested in the background with `ack=none`. The
+    /// requests exceeding it are rejected with a 429.
+    pub max_unacknowledged_memory_usage: ByteSize,
     /// (hidden) Targeted throughput for each shard
     pub shard_throughput_limit: ByteSize,
     /// (hidden) Maximum accumulated throughput capacity for underutilized
@@ -368,6 +914,8 @@ impl Default for IngestApiConfig {
             max_queue_disk_usage: ByteSize::gib(4),
             replication_factor: 1,
             content_length_limit: ByteSize::mib(10),
+            streaming_content_length_limit: None,
+            max_unacknowledged_memory_usage: ByteSize::mib(256),
             shard_throughput_limit: DEFAULT_SHARD_THROUGHPUT_LIMIT,
             shard_burst_limit: DEFAULT_SHARD_BURST_LIMIT,
             shard_scale_up_factor: DEFAULT_SHARD_SCALE_UP_FACTOR,
@@ -431,6 +979,20 @@ impl IngestApiConfig {
             self.shard_burst_limit,
             estimated_persist_size,
         );
+        ensure!(
+            self.max_unacknowledged_memory_usage >= self.content_length_limit,
+            "max_unacknowledged_memory_usage ({}) must be at least content_length_limit ({})",
+            self.max_unacknowledged_memory_usage,
+            self.content_length_limit,
+        );
+        if let Some(streaming_content_length_limit) = self.streaming_content_length_limit {
+            ensure!(
+                streaming_content_length_limit >= self.content_length_limit,
+                "streaming_content_length_limit ({}) must be at least content_length_limit ({})",
+                streaming_content_length_limit,
+                self.content_length_limit,
+            );
+        }
         ensure!(
             self.shard_scale_up_factor > 1.0,
             "shard_scale_up_factor ({}) must be greater than 1",
@@ -509,6 +1071,268 @@ impl Default for JaegerConfig {
     }
 }
 
+/// Configuration of the asynchronous replication of the published splits of the indexes of a
+/// primary cluster into this cluster, performed by the janitor.
+///
+/// The janitor periodically copies the split files of the primary indexes matching
+/// `index_id_patterns` from the primary storage to the default index root URI of this cluster,
+/// then mirrors the index configs and the sets of published splits in the local metastore. The
+/// recovery point objective is roughly bounded by `polling_interval_secs` plus the time needed to
+/// copy the newly published splits.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReplicationConfig {
+    /// gRPC address of a metastore node of the primary cluster, formatted as `<host>:<port>`.
+    pub source_grpc_endpoint: String,
+    pub index_id_patterns: Vec<String>,
+    #[serde(default = "ReplicationConfig::default_polling_interval_secs")]
+    pub polling_interval_secs: NonZeroU64,
+}
+
+impl ReplicationConfig {
+    fn default_polling_interval_secs() -> NonZeroU64 {
+        NonZeroU64::new(60).unwrap() // 1 minute
+    }
+
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_secs(self.polling_interval_secs.get())
+    }
+
+    pub fn source_grpc_endpoint(&self) -> anyhow::Result<HostAddr> {
+        HostAddr::parse_with_default_port(&self.source_grpc_endpoint, DEFAULT_GRPC_LISTEN_PORT)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        self.source_grpc_endpoint()?;
+
+        if self.index_id_patterns.is_empty() {
+            bail!("replication config must define at least one index ID pattern");
+        }
+        for index_id_pattern in &self.index_id_patterns {
+            validate_index_id_pattern(index_id_pattern, true)?;
+        }
+        Ok(())
+    }
+}
+
+/// Configuration of the shadowing of the REST traffic of this node to a secondary cluster.
+///
+/// A percentage of the ingest and search requests received by the REST API is replayed, in a
+/// fire-and-forget fashion, against the REST API of a secondary cluster, for instance a cluster
+/// running an upgraded version or a new configuration. The responses of the secondary cluster are
+/// only compared with the primary responses to report divergences.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShadowingConfig {
+    /// Base URL of the REST API of the secondary cluster, for instance
+    /// `http://quickwit-canary:7280`.
+    pub endpoint: String,
+    /// Percentage of the ingest requests to shadow, between 0 and 100.
+    #[serde(default)]
+    pub ingest_percentage: f64,
+    /// Percentage of the search requests to shadow, between 0 and 100.
+    #[serde(default)]
+    pub search_percentage: f64,
+    #[serde(default = "ShadowingConfig::default_timeout_secs")]
+    pub timeout_secs: NonZeroU64,
+    /// Shadow requests are dropped when that many shadow requests are already in flight.
+    #[serde(default = "ShadowingConfig::default_max_num_in_flight_requests")]
+    pub max_num_in_flight_requests: NonZeroUsize,
+    /// API key sent as a bearer token in the `Authorization` header of the shadow requests, in
+    /// place of the credentials of the original callers.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
+
+impl ShadowingConfig {
+    fn default_timeout_secs() -> NonZeroU64 {
+        NonZeroU64::new(10).unwrap()
+    }
+
+    fn default_max_num_in_flight_requests() -> NonZeroUsize {
+        NonZeroUsize::new(100).unwrap()
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.get())
+    }
+
+    /// Returns the base URL of the secondary cluster, without trailing slash.
+    pub fn endpoint(&self) -> &str {
+        self.endpoint.trim_end_matches('/')
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        let endpoint_uri: http::Uri = self
+            .endpoint()
+            .parse()
+            .map_err(|error| anyhow::anyhow!("invalid shadowing endpoint: {error}"))?;
+
+        if !matches!(endpoint_uri.scheme_str(), Some("http" | "https"))
+            || endpoint_uri.authority().is_none()
+        {
+            bail!(
+                "shadowing endpoint `{}` must be an HTTP(S) URL",
+                self.endpoint
+            );
+        }
+        for (percentage_name, percentage) in [
+            ("ingest_percentage", self.ingest_percentage),
+            ("search_percentage", self.search_percentage),
+        ] {
+            if !(0.0..=100.0).contains(&percentage) {
+                bail!("shadowing.{percentage_name} ({percentage}) must be between 0 and 100");
+            }
+        }
+        if self.ingest_percentage == 0.0 && self.search_percentage == 0.0 {
+            bail!("shadowing config must define a non-zero ingest or search percentage");
+        }
+        if let Some(api_key) = &self.api_key {
+            if api_key.is_empty() || !api_key.chars().all(|c| c.is_ascii_graphic()) {
+                bail!("shadowing API key must be a non-empty string of visible ASCII characters");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Configuration of the sampling of the search requests received by the REST API into an
+/// internal usage index.
+///
+/// For each sampled request, the node records the targeted index ID patterns, the query, the
+/// status code, the latency, and the number of hits of the response into the usage index, so that
+/// the query workloads can be analyzed with Quickwit itself.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UsageSamplingConfig {
+    /// ID of the usage index, created at startup if it does not exist.
+    #[serde(default = "UsageSamplingConfig::default_index_id")]
+    pub index_id: IndexId,
+    /// Percentage of the requests of the Quickwit search API to sample, between 0 and 100.
+    #[serde(default)]
+    pub search_percentage: f64,
+    /// Percentage of the requests of the Elasticsearch-compatible search API to sample, between 0
+    /// and 100.
+    #[serde(default)]
+    pub elastic_search_percentage: f64,
+    /// Roles allowed to search the usage index when authentication is enabled, since the sampled
+    /// queries may reveal the data of other indexes.
+    #[serde(default = "UsageSamplingConfig::default_reader_roles")]
+    pub reader_roles: Vec<String>,
+}
+
+impl UsageSamplingConfig {
+    fn default_index_id() -> IndexId {
+        "quickwit-usage".to_string()
+    }
+
+    fn default_reader_roles() -> Vec<String> {
+        vec!["admin".to_string()]
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        validate_identifier("usage index", &self.index_id)?;
+
+        for (percentage_name, percentage) in [
+            ("search_percentage", self.search_percentage),
+            ("elastic_search_percentage", self.elastic_search_percentage),
+        ] {
+            if !(0.0..=100.0).contains(&percentage) {
+                bail!("usage_sampling.{percentage_name} ({percentage}) must be between 0 and 100");
+            }
+        }
+        if self.search_percentage == 0.0 && self.elastic_search_percentage == 0.0 {
+            bail!("usage sampling config must define a non-zero search percentage");
+        }
+        if self.reader_roles.is_empty() || self.reader_roles.iter().any(String::is_empty) {
+            bail!("usage_sampling.reader_roles must contain at least one non-empty role");
+        }
+        Ok(())
+    }
+}
+
+/// Configuration of the index trash.
+///
+/// When enabled, deleting an index moves it to the trash instead of deleting it right away: its
+/// splits are retained but the index is hidden from the search and index APIs. The index can be
+/// restored with the undelete API until the grace period expires, after which the janitor purges
+/// it for good. This configuration must be identical on all the nodes of the cluster.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrashConfig {
+    /// Duration during which a deleted index can be restored, expressed in a human-friendly way
+    /// (`1 hour`, `7 days`, ...).
+    #[serde(default = "TrashConfig::default_grace_period")]
+    pub grace_period: String,
+}
+
+impl TrashConfig {
+    fn default_grace_period() -> String {
+        "7 days".to_string()
+    }
+
+    pub fn grace_period(&self) -> anyhow::Result<Duration> {
+        humantime::parse_duration(&self.grace_period)
+            .with_context(|| format!("failed to parse trash grace period `{}`", self.grace_period))
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        self.grace_period()?;
+        Ok(())
+    }
+}
+
+/// Configuration of the cleanup of orphan shards, performed by the janitor.
+///
+/// The janitor periodically compares the shards hosted by each ingester with the shards recorded
+/// in the metastore. The WAL queues of the shards unknown to the metastore, for instance the
+/// shards of deleted indexes, are removed from the ingesters to reclaim their disk space.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OrphanShardsCleanupConfig {
+    /// Only reports the orphan shards, without removing them.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Minimum duration since an orphan shard was last written to before it is removed, expressed
+    /// in a human-friendly way (`30 minutes`, `1 hour`, ...). It protects the shards that are
+    /// being opened while the janitor lists the shards.
+    #[serde(default = "OrphanShardsCleanupConfig::default_min_idle_period")]
+    pub min_idle_period: String,
+}
+
+impl OrphanShardsCleanupConfig {
+    fn default_min_idle_period() -> String {
+        "1 hour".to_string()
+    }
+
+    pub fn min_idle_period(&self) -> anyhow::Result<Duration> {
+        humantime::parse_duration(&self.min_idle_period).with_context(|| {
+            format!(
+                "failed to parse orphan shards min idle period `{}`",
+                self.min_idle_period
+            )
+        })
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        self.min_idle_period()?;
+        Ok(())
+    }
+}
+
+/// Configuration of the prewarming of the searcher split caches, performed by the control plane.
+///
+/// The control plane periodically reports the most recent published splits of each index to the
+/// split caches of the searchers that will most likely serve them, so that they are downloaded
+/// ahead of the first query.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SplitCachePrewarmingConfig {
+    /// Number of most recent published splits of each index reported to the split caches.
+    pub num_splits_per_index: NonZeroU32,
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct NodeConfig {
     pub cluster_id: String,
@@ -526,11 +1350,20 @@ pub struct NodeConfig {
     pub rest_config: RestConfig,
     pub grpc_config: GrpcConfig,
     pub storage_configs: StorageConfigs,
+    /// Named sets of storage configs that index configs can reference to access their storage
+    /// with dedicated credentials.
+    pub storage_credentials: BTreeMap<String, StorageConfigs>,
     pub metastore_configs: MetastoreConfigs,
     pub indexer_config: IndexerConfig,
     pub searcher_config: SearcherConfig,
     pub ingest_api_config: IngestApiConfig,
     pub jaeger_config: JaegerConfig,
+    pub replication_config_opt: Option<ReplicationConfig>,
+    pub shadowing_config_opt: Option<ShadowingConfig>,
+    pub usage_sampling_config_opt: Option<UsageSamplingConfig>,
+    pub trash_config_opt: Option<TrashConfig>,
+    pub orphan_shards_cleanup_config_opt: Option<OrphanShardsCleanupConfig>,
+    pub split_cache_prewarming_config_opt: Option<SplitCachePrewarmingConfig>,
 }
 
 impl NodeConfig {
@@ -576,9 +1409,26 @@ impl NodeConfig {
     }
 
     pub fn redact(&mut self) {
+        self.rest_config.redact();
+        for remote_cluster_config in &mut self.searcher_config.remote_clusters {
+            if let Some(api_key) = &mut remote_cluster_config.api_key {
+                *api_key = "***redacted***".to_string();
+            }
+        }
+        if let Some(api_key) = self
+            .shadowing_config_opt
+            .as_mut()
+            .and_then(|shadowing_config| shadowing_config.api_key.as_mut())
+        {
+            *api_key = "***redacted***".to_string();
+        }
         self.metastore_configs.redact();
         self.metastore_uri.redact();
         self.storage_configs.redact();
+
+        for storage_configs in self.storage_credentials.values_mut() {
+            storage_configs.redact();
+        }
     }
 
     /// Creates a config with defaults suitable for testing.
@@ -727,6 +1577,23 @@ mod tests {
         )
         .unwrap();
         assert_eq!(grpc_config.max_message_size, ByteSize::mib(4));
+        assert_eq!(grpc_config.compression, GrpcCompressionConfig::default());
+
+        let grpc_config: GrpcConfig = serde_yaml::from_str(
+            r#"
+                compression:
+                  codec: zstd
+                  min_payload_size: 1MiB
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            grpc_config.compression.payload_compression(),
+            PayloadCompression {
+                codec: PayloadCodec::Zstd,
+                min_payload_num_bytes: 1 << 20,
+            }
+        );
     }
 
     #[test]
@@ -734,13 +1601,348 @@ mod tests {
         let grpc_config = GrpcConfig {
             max_message_size: ByteSize::mb(1),
             tls: None,
+            compression: GrpcCompressionConfig::default(),
         };
         assert!(grpc_config.validate().is_ok());
 
         let grpc_config = GrpcConfig {
             max_message_size: ByteSize::kb(1),
             tls: None,
+            compression: GrpcCompressionConfig::default(),
         };
         assert!(grpc_config.validate().is_err());
     }
+
+    #[test]
+    fn test_searcher_config_validate_remote_clusters() {
+        let remote_cluster_config: RemoteClusterConfig = serde_yaml::from_str(
+            r#"
+                cluster_id: eu-west
+                grpc_endpoint: quickwit-eu-west
+            "#,
+        )
+        .unwrap();
+        assert_eq!(remote_cluster_config.index_id_patterns, ["*"]);
+        assert!(!remote_cluster_config.skip_unavailable);
+        assert_eq!(
+            remote_cluster_config.grpc_endpoint().unwrap().to_string(),
+            "quickwit-eu-west:7281"
+        );
+        let mut searcher_config = SearcherConfig {
+            remote_clusters: vec![remote_cluster_config.clone()],
+            ..Default::default()
+        };
+        searcher_config.validate().unwrap();
+
+        searcher_config.remote_clusters[0].index_id_patterns = Vec::new();
+        searcher_config.validate().unwrap_err();
+
+        searcher_config.remote_clusters[0].index_id_patterns = vec!["-logs".to_string()];
+        searcher_config.validate().unwrap_err();
+
+        searcher_config.remote_clusters[0] = remote_cluster_config.clone();
+        searcher_config.remote_clusters[0].grpc_endpoint = "quickwit-eu-west:port".to_string();
+        searcher_config.validate().unwrap_err();
+
+        searcher_config.remote_clusters[0] = remote_cluster_config.clone();
+        searcher_config.remote_clusters[0].tls = Some(RemoteClusterTlsConfig {
+            ca_path: "ca.pem".to_string(),
+            cert_path: Some("cert.pem".to_string()),
+            key_path: None,
+            expected_name: None,
+        });
+        searcher_config.validate().unwrap_err();
+
+        searcher_config.remote_clusters[0] = remote_cluster_config.clone();
+        searcher_config.remote_clusters[0].api_key = Some("api key".to_string());
+        searcher_config.validate().unwrap_err();
+
+        searcher_config.remote_clusters[0].api_key = Some("api-key".to_string());
+        searcher_config.validate().unwrap();
+
+        searcher_config.remote_clusters =
+            vec![remote_cluster_config.clone(), remote_cluster_config];
+        let error = searcher_config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "remote cluster ID `eu-west` is defined more than once"
+        );
+    }
+
+    #[test]
+    fn test_searcher_config_validate_memory_budget() {
+        let mut searcher_config = SearcherConfig {
+            aggregation_memory_limit: ByteSize::mb(500),
+            memory_budget: Some(ByteSize::gb(4)),
+            ..Default::default()
+        };
+        searcher_config.validate().unwrap();
+
+        searcher_config.memory_budget = Some(ByteSize::mb(520));
+        let error = searcher_config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "memory_budget (520.0 MB) must be greater than or equal to aggregation_memory_limit + \
+             30MB (530.0 MB)"
+        );
+    }
+
+    #[test]
+    fn test_searcher_config_validate_split_cache_fd_limits() {
+        let split_cache_limits: SplitCacheLimits = serde_yaml::from_str(
+            r#"
+                max_num_bytes: 1G
+                fd_cache_capacity: 1000
+                max_open_fds: 1200
+                min_residency_secs: 300
+                max_age: 12 hours
+                admission_policy: tiny_lfu
+            "#,
+        )
+        .unwrap();
+        assert_eq!(split_cache_limits.max_file_descriptors.get(), 1_000);
+        assert_eq!(split_cache_limits.max_open_fds.unwrap().get(), 1_200);
+        assert_eq!(split_cache_limits.min_residency(), Duration::from_secs(300));
+        assert_eq!(
+            split_cache_limits.max_age,
+            Some(Duration::from_secs(12 * 3_600))
+        );
+        assert_eq!(
+            split_cache_limits.admission_policy,
+            SplitCacheAdmissionPolicy::TinyLfu
+        );
+
+        let mut searcher_config = SearcherConfig {
+            split_cache: Some(split_cache_limits),
+            ..Default::default()
+        };
+        searcher_config.validate().unwrap();
+
+        searcher_config.split_cache.as_mut().unwrap().max_open_fds = NonZeroU32::new(1_000);
+        let error = searcher_config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "split_cache.max_open_fds (1000) must be greater than \
+             split_cache.max_file_descriptors (1000)"
+        );
+    }
+
+    #[test]
+    fn test_searcher_config_validate_split_cache_index_quotas() {
+        let split_cache_limits: SplitCacheLimits = serde_yaml::from_str(
+            r#"
+                max_num_bytes: 10G
+                index_quotas:
+                  - index_id_pattern: logs-*
+                    max_num_bytes: 1G
+                  - index_id_pattern: "*-traces"
+                    max_num_splits: 100
+            "#,
+        )
+        .unwrap();
+        let logs_quota = split_cache_limits.index_quota("logs-app").unwrap();
+        assert_eq!(logs_quota.max_num_bytes, Some(ByteSize::gb(1)));
+        assert!(logs_quota.max_num_splits.is_none());
+
+        let traces_quota = split_cache_limits.index_quota("otel-traces").unwrap();
+        assert!(traces_quota.max_num_bytes.is_none());
+        assert_eq!(traces_quota.max_num_splits.unwrap().get(), 100);
+
+        // The first matching quota applies.
+        let logs_quota = split_cache_limits.index_quota("logs-traces").unwrap();
+        assert_eq!(logs_quota.index_id_pattern, "logs-*");
+        assert!(split_cache_limits.index_quota("metrics").is_none());
+
+        let mut searcher_config = SearcherConfig {
+            split_cache: Some(split_cache_limits),
+            ..Default::default()
+        };
+        searcher_config.validate().unwrap();
+
+        searcher_config.split_cache.as_mut().unwrap().index_quotas[0].max_num_bytes = None;
+        let error = searcher_config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "split cache quota for index ID pattern `logs-*` must set `max_num_bytes` or \
+             `max_num_splits`"
+        );
+    }
+
+    #[test]
+    fn test_split_cache_index_quota_matches() {
+        let index_quota = |index_id_pattern: &str| SplitCacheIndexQuota {
+            index_id_pattern: index_id_pattern.to_string(),
+            max_num_bytes: None,
+            max_num_splits: NonZeroU32::new(1),
+        };
+        assert!(index_quota("logs").matches("logs"));
+        assert!(!index_quota("logs").matches("logs-app"));
+        assert!(index_quota("logs-*").matches("logs-"));
+        assert!(index_quota("logs-*").matches("logs-app"));
+        assert!(!index_quota("logs-*").matches("app-logs"));
+        assert!(index_quota("*-logs").matches("app-logs"));
+        assert!(index_quota("*").matches("logs"));
+        assert!(index_quota("logs-*-prod*").matches("logs-app-prod-eu"));
+        assert!(!index_quota("logs-*-prod").matches("logs-app-prod-eu"));
+        assert!(index_quota("a*ba").matches("aba"));
+        assert!(!index_quota("ab*ba").matches("aba"));
+    }
+
+    #[test]
+    fn test_replication_config_validate() {
+        let mut replication_config: ReplicationConfig = serde_yaml::from_str(
+            r#"
+                source_grpc_endpoint: quickwit-us-east
+                index_id_patterns:
+                    - logs-*
+                    - -logs-dev
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            replication_config.polling_interval(),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            replication_config
+                .source_grpc_endpoint()
+                .unwrap()
+                .to_string(),
+            "quickwit-us-east:7281"
+        );
+        replication_config.validate().unwrap();
+
+        replication_config.index_id_patterns = Vec::new();
+        replication_config.validate().unwrap_err();
+
+        replication_config.index_id_patterns = vec!["logs-*".to_string()];
+        replication_config.source_grpc_endpoint = "quickwit-us-east:port".to_string();
+        replication_config.validate().unwrap_err();
+    }
+
+    #[test]
+    fn test_trash_config_validate() {
+        let trash_config: TrashConfig = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(
+            trash_config.grace_period().unwrap(),
+            Duration::from_secs(7 * 24 * 3600)
+        );
+        trash_config.validate().unwrap();
+
+        let trash_config = TrashConfig {
+            grace_period: "one day".to_string(),
+        };
+        let error = trash_config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "failed to parse trash grace period `one day`"
+        );
+    }
+
+    #[test]
+    fn test_orphan_shards_cleanup_config_validate() {
+        let orphan_shards_cleanup_config: OrphanShardsCleanupConfig =
+            serde_yaml::from_str("{}").unwrap();
+        assert!(!orphan_shards_cleanup_config.dry_run);
+        assert_eq!(
+            orphan_shards_cleanup_config.min_idle_period().unwrap(),
+            Duration::from_secs(3600)
+        );
+        orphan_shards_cleanup_config.validate().unwrap();
+
+        let orphan_shards_cleanup_config = OrphanShardsCleanupConfig {
+            dry_run: true,
+            min_idle_period: "an hour".to_string(),
+        };
+        let error = orphan_shards_cleanup_config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "failed to parse orphan shards min idle period `an hour`"
+        );
+    }
+
+    #[test]
+    fn test_shadowing_config_validate() {
+        let mut shadowing_config: ShadowingConfig = serde_yaml::from_str(
+            r#"
+                endpoint: http://quickwit-canary:7280/
+                search_percentage: 10
+            "#,
+        )
+        .unwrap();
+        assert_eq!(shadowing_config.endpoint(), "http://quickwit-canary:7280");
+        assert_eq!(shadowing_config.ingest_percentage, 0.0);
+        assert_eq!(shadowing_config.timeout(), Duration::from_secs(10));
+        assert_eq!(shadowing_config.max_num_in_flight_requests.get(), 100);
+        shadowing_config.validate().unwrap();
+
+        shadowing_config.search_percentage = 0.0;
+        let error = shadowing_config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "shadowing config must define a non-zero ingest or search percentage"
+        );
+
+        shadowing_config.ingest_percentage = 120.0;
+        let error = shadowing_config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "shadowing.ingest_percentage (120) must be between 0 and 100"
+        );
+
+        shadowing_config.ingest_percentage = 100.0;
+        shadowing_config.endpoint = "quickwit-canary:7280".to_string();
+        shadowing_config.validate().unwrap_err();
+
+        shadowing_config.endpoint = "http://quickwit-canary:7280".to_string();
+        shadowing_config.api_key = Some("canary-api-key".to_string());
+        shadowing_config.validate().unwrap();
+
+        shadowing_config.api_key = Some("canary api key".to_string());
+        let error = shadowing_config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "shadowing API key must be a non-empty string of visible ASCII characters"
+        );
+    }
+
+    #[test]
+    fn test_usage_sampling_config_validate() {
+        let mut usage_sampling_config: UsageSamplingConfig = serde_yaml::from_str(
+            r#"
+                elastic_search_percentage: 5
+            "#,
+        )
+        .unwrap();
+        assert_eq!(usage_sampling_config.index_id, "quickwit-usage");
+        assert_eq!(usage_sampling_config.search_percentage, 0.0);
+        assert_eq!(usage_sampling_config.reader_roles, ["admin"]);
+        usage_sampling_config.validate().unwrap();
+
+        usage_sampling_config.elastic_search_percentage = 0.0;
+        let error = usage_sampling_config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "usage sampling config must define a non-zero search percentage"
+        );
+
+        usage_sampling_config.search_percentage = 101.0;
+        let error = usage_sampling_config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "usage_sampling.search_percentage (101) must be between 0 and 100"
+        );
+
+        usage_sampling_config.search_percentage = 100.0;
+        usage_sampling_config.reader_roles.clear();
+        let error = usage_sampling_config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "usage_sampling.reader_roles must contain at least one non-empty role"
+        );
+
+        usage_sampling_config.reader_roles = vec!["admin".to_string()];
+        usage_sampling_config.index_id = "_usage".to_string();
+        usage_sampling_config.validate().unwrap_err();
+    }
 }
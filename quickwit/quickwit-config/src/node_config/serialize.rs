@@ -12,14 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 use std::time::Duration;
 
 use anyhow::{bail, Context};
 use bytesize::ByteSize;
-use http::HeaderMap;
+use http::{HeaderMap, HeaderName, HeaderValue, Method};
 use quickwit_common::fs::get_disk_size;
 use quickwit_common::net::{find_private_ip, get_short_hostname, Host};
 use quickwit_common::new_coolid;
@@ -28,7 +28,10 @@ use quickwit_proto::types::NodeId;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
-use super::{GrpcConfig, RestConfig};
+use super::{
+    ApiKeyConfig, ContentLengthLimits, GrpcConfig, OidcConfig, RestConfig,
+    OIDC_SIGNATURE_ALGORITHMS,
+};
 use crate::config_value::ConfigValue;
 use crate::qw_env_vars::*;
 use crate::service::QuickwitService;
@@ -36,7 +39,9 @@ use crate::storage_config::StorageConfigs;
 use crate::templating::render_config;
 use crate::{
     validate_identifier, validate_node_id, ConfigFormat, IndexerConfig, IngestApiConfig,
-    JaegerConfig, MetastoreConfigs, NodeConfig, SearcherConfig, TlsConfig,
+    JaegerConfig, MetastoreConfigs, NodeConfig, OrphanShardsCleanupConfig, ReplicationConfig,
+    SearcherConfig, ShadowingConfig, SplitCachePrewarmingConfig, TlsConfig, TrashConfig,
+    UsageSamplingConfig,
 };
 
 pub const DEFAULT_CLUSTER_ID: &str = "quickwit-default-cluster";
@@ -195,6 +200,8 @@ struct NodeConfigBuilder {
     #[serde(rename = "storage")]
     #[serde(default)]
     storage_configs: StorageConfigs,
+    #[serde(default)]
+    storage_credentials: BTreeMap<String, StorageConfigs>,
     #[serde(rename = "metastore")]
     #[serde(default)]
     metastore_configs: MetastoreConfigs,
@@ -210,6 +217,24 @@ struct NodeConfigBuilder {
     #[serde(rename = "jaeger")]
     #[serde(default)]
     jaeger_config: JaegerConfig,
+    #[serde(rename = "replication")]
+    #[serde(default)]
+    replication_config_opt: Option<ReplicationConfig>,
+    #[serde(rename = "shadowing")]
+    #[serde(default)]
+    shadowing_config_opt: Option<ShadowingConfig>,
+    #[serde(rename = "usage_sampling")]
+    #[serde(default)]
+    usage_sampling_config_opt: Option<UsageSamplingConfig>,
+    #[serde(rename = "trash")]
+    #[serde(default)]
+    trash_config_opt: Option<TrashConfig>,
+    #[serde(rename = "orphan_shards_cleanup")]
+    #[serde(default)]
+    orphan_shards_cleanup_config_opt: Option<OrphanShardsCleanupConfig>,
+    #[serde(rename = "split_cache_prewarming")]
+    #[serde(default)]
+    split_cache_prewarming_config_opt: Option<SplitCachePrewarmingConfig>,
 }
 
 impl NodeConfigBuilder {
@@ -293,9 +318,34 @@ impl NodeConfigBuilder {
 
         self.storage_configs.validate()?;
         self.storage_configs.apply_flavors();
+
+        for (name, storage_configs) in &mut self.storage_credentials {
+            validate_identifier("storage credentials", name)?;
+            storage_configs
+                .validate()
+                .with_context(|| format!("failed to validate storage credentials `{name}`"))?;
+            storage_configs.apply_flavors();
+        }
+
         self.ingest_api_config.validate()?;
         self.searcher_config.validate()?;
 
+        if let Some(replication_config) = &self.replication_config_opt {
+            replication_config.validate()?;
+        }
+        if let Some(shadowing_config) = &self.shadowing_config_opt {
+            shadowing_config.validate()?;
+        }
+        if let Some(usage_sampling_config) = &self.usage_sampling_config_opt {
+            usage_sampling_config.validate()?;
+        }
+        if let Some(trash_config) = &self.trash_config_opt {
+            trash_config.validate()?;
+        }
+        if let Some(orphan_shards_cleanup_config) = &self.orphan_shards_cleanup_config_opt {
+            orphan_shards_cleanup_config.validate()?;
+        }
+
         let gossip_interval = self
             .gossip_interval_ms
             .resolve_optional(env_vars)?
@@ -319,10 +369,17 @@ impl NodeConfigBuilder {
             grpc_config: self.grpc_config,
             metastore_configs: self.metastore_configs,
             storage_configs: self.storage_configs,
+            storage_credentials: self.storage_credentials,
             indexer_config: self.indexer_config,
             searcher_config: self.searcher_config,
             ingest_api_config: self.ingest_api_config,
             jaeger_config: self.jaeger_config,
+            replication_config_opt: self.replication_config_opt,
+            shadowing_config_opt: self.shadowing_config_opt,
+            usage_sampling_config_opt: self.usage_sampling_config_opt,
+            trash_config_opt: self.trash_config_opt,
+            orphan_shards_cleanup_config_opt: self.orphan_shards_cleanup_config_opt,
+            split_cache_prewarming_config_opt: self.split_cache_prewarming_config_opt,
         };
 
         validate(&node_config)?;
@@ -370,6 +427,7 @@ impl ExpectedDiskUsage {
             expected.split_cache = node_config
                 .searcher_config
                 .split_cache
+                .as_ref()
                 .map(|limits| limits.max_num_bytes);
         }
         expected
@@ -415,17 +473,24 @@ impl Default for NodeConfigBuilder {
             rest_config_builder: RestConfigBuilder::default(),
             grpc_config: GrpcConfig::default(),
             storage_configs: StorageConfigs::default(),
+            storage_credentials: BTreeMap::new(),
             metastore_configs: MetastoreConfigs::default(),
             indexer_config: IndexerConfig::default(),
             searcher_config: SearcherConfig::default(),
             ingest_api_config: IngestApiConfig::default(),
             jaeger_config: JaegerConfig::default(),
+            replication_config_opt: None,
+            shadowing_config_opt: None,
+            usage_sampling_config_opt: None,
+            trash_config_opt: None,
+            orphan_shards_cleanup_config_opt: None,
+            split_cache_prewarming_config_opt: None,
         }
     }
 }
 
 #[serde_with::serde_as]
-#[derive(Debug, Deserialize, PartialEq, Default)]
+#[derive(Debug, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 struct RestConfigBuilder {
     #[serde(default)]
@@ -433,11 +498,45 @@ struct RestConfigBuilder {
     #[serde(default)]
     #[serde_as(deserialize_as = "serde_with::OneOrMany<_>")]
     pub cors_allow_origins: Vec<String>,
+    #[serde(default)]
+    #[serde_as(deserialize_as = "serde_with::OneOrMany<_>")]
+    pub cors_allow_methods: Vec<String>,
+    #[serde(default)]
+    #[serde_as(deserialize_as = "serde_with::OneOrMany<_>")]
+    pub cors_allow_headers: Vec<String>,
+    #[serde(default)]
+    pub cors_allow_credentials: bool,
     #[serde(with = "http_serde::header_map")]
     #[serde(default)]
     pub extra_headers: HeaderMap,
     #[serde(default)]
     pub tls: Option<TlsConfig>,
+    #[serde(default)]
+    pub api_keys: Vec<ApiKeyConfig>,
+    #[serde(default)]
+    pub oidc: Option<OidcConfig>,
+    #[serde(default = "RestConfig::default_admin_roles")]
+    pub admin_roles: Vec<String>,
+    #[serde(default)]
+    pub content_length_limits: ContentLengthLimits,
+}
+
+impl Default for RestConfigBuilder {
+    fn default() -> Self {
+        Self {
+            listen_port: None,
+            cors_allow_origins: Vec::new(),
+            cors_allow_methods: Vec::new(),
+            cors_allow_headers: Vec::new(),
+            cors_allow_credentials: false,
+            extra_headers: HeaderMap::new(),
+            tls: None,
+            api_keys: Vec::new(),
+            oidc: None,
+            admin_roles: RestConfig::default_admin_roles(),
+            content_length_limits: ContentLengthLimits::default(),
+        }
+    }
 }
 
 impl RestConfigBuilder {
@@ -452,14 +551,94 @@ impl RestConfigBuilder {
             listen_port_from_config_or_default,
         )
         .resolve(env_vars)?;
+
+        self.validate_cors()?;
+
+        let mut api_keys = HashSet::with_capacity(self.api_keys.len());
+        for api_key in &self.api_keys {
+            if api_key.key.is_empty() || api_key.role.is_empty() {
+                bail!("API key and role must not be empty");
+            }
+            if !api_keys.insert(&api_key.key) {
+                bail!(
+                    "API key for role `{}` is defined more than once",
+                    api_key.role
+                );
+            }
+        }
+        if let Some(oidc_config) = &self.oidc {
+            if oidc_config.issuer.is_empty() || oidc_config.audience.is_empty() {
+                bail!("OIDC issuer and audience must not be empty");
+            }
+            if oidc_config.signature_algorithms.is_empty() {
+                bail!("OIDC signature algorithms must not be empty");
+            }
+            for signature_algorithm in &oidc_config.signature_algorithms {
+                if !OIDC_SIGNATURE_ALGORITHMS.contains(&signature_algorithm.as_str()) {
+                    bail!(
+                        "OIDC signature algorithm `{signature_algorithm}` is not supported, \
+                         expected one of {OIDC_SIGNATURE_ALGORITHMS:?}"
+                    );
+                }
+            }
+            if oidc_config.is_login_enabled()
+                && (oidc_config.redirect_uri.is_none() || oidc_config.cookie_name.is_none())
+            {
+                bail!("OIDC login requires `redirect_uri` and `cookie_name` to be set");
+            }
+        }
         let rest_config = RestConfig {
             listen_addr: SocketAddr::new(listen_ip, listen_port),
             cors_allow_origins: self.cors_allow_origins,
+            cors_allow_methods: self
+                .cors_allow_methods
+                .iter()
+                .map(|method| method.to_ascii_uppercase())
+                .collect(),
+            cors_allow_headers: self.cors_allow_headers,
+            cors_allow_credentials: self.cors_allow_credentials,
             extra_headers: self.extra_headers,
             tls: self.tls,
+            api_keys: self.api_keys,
+            oidc: self.oidc,
+            admin_roles: self.admin_roles,
+            content_length_limits: self.content_length_limits,
         };
         Ok(rest_config)
     }
+
+    fn validate_cors(&self) -> anyhow::Result<()> {
+        for origin in &self.cors_allow_origins {
+            if origin != "*" && HeaderValue::from_str(origin).is_err() {
+                bail!("invalid CORS origin `{origin}`");
+            }
+        }
+        for method in &self.cors_allow_methods {
+            if method != "*" && Method::from_str(method).is_err() {
+                bail!("invalid CORS method `{method}`");
+            }
+        }
+        for header in &self.cors_allow_headers {
+            if header != "*" && HeaderName::from_str(header).is_err() {
+                bail!("invalid CORS header `{header}`");
+            }
+        }
+        if self.cors_allow_credentials {
+            let has_wildcard = self
+                .cors_allow_origins
+                .iter()
+                .chain(&self.cors_allow_methods)
+                .chain(&self.cors_allow_headers)
+                .any(|value| value == "*");
+            if has_wildcard {
+                bail!(
+                    "CORS credentials cannot be allowed along with wildcard origins, methods, or \
+                     headers"
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(any(test, feature = "testsuite"))]
@@ -493,8 +672,15 @@ pub fn node_config_for_tests_from_ports(
     let rest_config = RestConfig {
         listen_addr: rest_listen_addr,
         cors_allow_origins: Vec::new(),
+        cors_allow_methods: Vec::new(),
+        cors_allow_headers: Vec::new(),
+        cors_allow_credentials: false,
         extra_headers: HeaderMap::new(),
         tls: None,
+        api_keys: Vec::new(),
+        oidc: None,
+        admin_roles: RestConfig::default_admin_roles(),
+        content_length_limits: ContentLengthLimits::default(),
     };
     NodeConfig {
         cluster_id: default_cluster_id().unwrap(),
@@ -512,11 +698,18 @@ pub fn node_config_for_tests_from_ports(
         rest_config,
         grpc_config: GrpcConfig::default(),
         storage_configs: StorageConfigs::default(),
+        storage_credentials: BTreeMap::new(),
         metastore_configs: MetastoreConfigs::default(),
         indexer_config: IndexerConfig::default(),
         searcher_config: SearcherConfig::default(),
         ingest_api_config: IngestApiConfig::default(),
         jaeger_config: JaegerConfig::default(),
+        replication_config_opt: None,
+        shadowing_config_opt: None,
+        usage_sampling_config_opt: None,
+        trash_config_opt: None,
+        orphan_shards_cleanup_config_opt: None,
+        split_cache_prewarming_config_opt: None,
     }
 }
 
@@ -524,7 +717,7 @@ pub fn node_config_for_tests_from_ports(
 mod tests {
     use std::env;
     use std::net::Ipv4Addr;
-    use std::num::{NonZeroU64, NonZeroUsize};
+    use std::num::{NonZeroU32, NonZeroU64, NonZeroUsize};
     use std::path::Path;
 
     use bytesize::ByteSize;
@@ -613,6 +806,21 @@ mod tests {
         assert!(s3_storage_config.disable_multi_object_delete);
         assert!(s3_storage_config.disable_multipart_upload);
 
+        assert_eq!(
+            config.storage_credentials.keys().collect::<Vec<_>>(),
+            ["account-a"]
+        );
+        let account_a_s3_storage_config =
+            config.storage_credentials["account-a"].find_s3().unwrap();
+        assert_eq!(
+            account_a_s3_storage_config.region.as_ref().unwrap(),
+            "eu-west-3"
+        );
+        assert_eq!(
+            account_a_s3_storage_config.access_key_id.as_ref().unwrap(),
+            "account-a-access-key-id"
+        );
+
         let postgres_config = config.metastore_configs.find_postgres().unwrap();
         assert_eq!(postgres_config.min_connections, 1);
         assert_eq!(postgres_config.max_connections.get(), 12);
@@ -644,6 +852,7 @@ mod tests {
                 cpu_capacity: IndexerConfig::default_cpu_capacity(),
                 enable_cooperative_indexing: false,
                 max_merge_write_throughput: Some(ByteSize::mb(100)),
+                max_merge_read_throughput: Some(ByteSize::mb(200)),
             }
         );
         assert_eq!(
@@ -662,6 +871,7 @@ mod tests {
                 split_footer_cache_capacity: ByteSize::gb(1),
                 partial_request_cache_capacity: ByteSize::mb(64),
                 max_num_concurrent_split_searches: 150,
+                max_num_pending_split_searches: NonZeroUsize::new(1_000),
                 max_num_concurrent_split_streams: 120,
                 split_cache: None,
                 request_timeout_secs: NonZeroU64::new(30).unwrap(),
@@ -670,8 +880,27 @@ mod tests {
                     timeout_millis: 2_000,
                     max_num_retries: 2
                 }),
+                leaf_search_hedging_policy: Some(crate::LeafSearchHedgingPolicy {
+                    latency_percentile: 99.0,
+                    min_delay_millis: 200,
+                    max_hedged_requests_ratio: 0.1,
+                }),
+                remote_clusters: vec![crate::RemoteClusterConfig {
+                    cluster_id: "eu-west".to_string(),
+                    grpc_endpoint: "quickwit-eu-west.example.com:7281".to_string(),
+                    index_id_patterns: vec!["logs-*".to_string()],
+                    skip_unavailable: true,
+                    tls: Some(crate::RemoteClusterTlsConfig {
+                        ca_path: "/etc/quickwit/eu-west-ca.pem".to_string(),
+                        cert_path: None,
+                        key_path: None,
+                        expected_name: Some("quickwit.eu-west.example.com".to_string()),
+                    }),
+                    api_key: Some("eu-west-api-key".to_string()),
+                }],
                 warmup_memory_budget: ByteSize::gb(100),
                 warmup_single_split_initial_allocation: ByteSize::gb(1),
+                memory_budget: Some(ByteSize::gb(16)),
             }
         );
         assert_eq!(
@@ -683,6 +912,53 @@ mod tests {
                 max_fetch_spans: NonZeroU64::new(1_000).unwrap(),
             }
         );
+        assert_eq!(
+            config.replication_config_opt.unwrap(),
+            ReplicationConfig {
+                source_grpc_endpoint: "quickwit-us-east.example.com:7281".to_string(),
+                index_id_patterns: vec!["logs-*".to_string()],
+                polling_interval_secs: NonZeroU64::new(30).unwrap(),
+            }
+        );
+        assert_eq!(
+            config.shadowing_config_opt.unwrap(),
+            ShadowingConfig {
+                endpoint: "http://quickwit-canary.example.com:7280".to_string(),
+                ingest_percentage: 5.0,
+                search_percentage: 1.5,
+                timeout_secs: NonZeroU64::new(5).unwrap(),
+                max_num_in_flight_requests: NonZeroUsize::new(50).unwrap(),
+                api_key: None,
+            }
+        );
+        assert_eq!(
+            config.usage_sampling_config_opt.unwrap(),
+            UsageSamplingConfig {
+                index_id: "usage".to_string(),
+                search_percentage: 10.0,
+                elastic_search_percentage: 2.5,
+                reader_roles: vec!["admin".to_string()],
+            }
+        );
+        assert_eq!(
+            config.trash_config_opt.unwrap(),
+            TrashConfig {
+                grace_period: "3 days".to_string(),
+            }
+        );
+        assert_eq!(
+            config.orphan_shards_cleanup_config_opt.unwrap(),
+            OrphanShardsCleanupConfig {
+                dry_run: true,
+                min_idle_period: "2 hours".to_string(),
+            }
+        );
+        assert_eq!(
+            config.split_cache_prewarming_config_opt.unwrap(),
+            SplitCachePrewarmingConfig {
+                num_splits_per_index: NonZeroU32::new(10).unwrap(),
+            }
+        );
         Ok(())
     }
 
@@ -896,6 +1172,12 @@ mod tests {
         assert_eq!(config.searcher_config, SearcherConfig::default());
         assert_eq!(config.ingest_api_config, IngestApiConfig::default());
         assert_eq!(config.jaeger_config, JaegerConfig::default());
+        assert!(config.replication_config_opt.is_none());
+        assert!(config.shadowing_config_opt.is_none());
+        assert!(config.trash_config_opt.is_none());
+        assert!(config.orphan_shards_cleanup_config_opt.is_none());
+        assert!(config.split_cache_prewarming_config_opt.is_none());
+        assert!(config.storage_credentials.is_empty());
     }
 
     #[tokio::test]
@@ -1286,6 +1568,222 @@ mod tests {
         .expect_err("Config should not allow empty origins.");
     }
 
+    #[tokio::test]
+    async fn test_rest_config_cors() {
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              cors_allow_origins: https://www.my-domain.com
+              cors_allow_methods: [GET, POST]
+              cors_allow_headers: [content-type, authorization]
+              cors_allow_credentials: true
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(config.rest_config.cors_allow_methods, ["GET", "POST"]);
+        assert_eq!(
+            config.rest_config.cors_allow_headers,
+            ["content-type", "authorization"]
+        );
+        assert!(config.rest_config.cors_allow_credentials);
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              cors_allow_origins: '*'
+              cors_allow_credentials: true
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("CORS credentials cannot be allowed"));
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              cors_allow_headers: 'not a header'
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("invalid CORS header"));
+    }
+
+    #[tokio::test]
+    async fn test_rest_config_api_keys() {
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              api_keys:
+                - key: secret-key
+                  role: support
+                  claims:
+                    tenant: acme
+        "#;
+        let mut config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            config.rest_config.api_keys,
+            [ApiKeyConfig {
+                key: "secret-key".to_string(),
+                role: "support".to_string(),
+                claims: BTreeMap::from_iter([("tenant".to_string(), "acme".to_string())]),
+            }]
+        );
+        assert_eq!(config.rest_config.admin_roles, ["admin"]);
+        config.redact();
+        assert_eq!(config.rest_config.api_keys[0].key, "***redacted***");
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              api_keys:
+                - key: secret-key
+                  role: support
+                - key: secret-key
+                  role: admin
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("is defined more than once"));
+    }
+
+    #[tokio::test]
+    async fn test_rest_config_oidc() {
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              oidc:
+                issuer: https://accounts.example.com/
+                audience: quickwit
+                role_mapping:
+                  quickwit-support: support
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        let oidc_config = config.rest_config.oidc.unwrap();
+        assert_eq!(oidc_config.audience, "quickwit");
+        assert_eq!(oidc_config.signature_algorithms, ["RS256"]);
+        assert_eq!(oidc_config.role_claim, "roles");
+        assert_eq!(oidc_config.role_mapping["quickwit-support"], "support");
+        assert_eq!(
+            oidc_config.discovery_uri(),
+            "https://accounts.example.com/.well-known/openid-configuration"
+        );
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              oidc:
+                issuer: https://accounts.example.com
+                audience: ""
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("OIDC issuer and audience must not be empty"));
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              oidc:
+                issuer: https://accounts.example.com
+                audience: quickwit
+                signature_algorithms: [RS256, HS256]
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("OIDC signature algorithm `HS256` is not supported"));
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              oidc:
+                issuer: https://accounts.example.com
+                audience: quickwit
+                client_id: quickwit
+                client_secret: secret
+                cookie_name: quickwit_token
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("OIDC login requires `redirect_uri` and `cookie_name` to be set"));
+    }
+
+    #[tokio::test]
+    async fn test_rest_config_content_length_limits() {
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              content_length_limits:
+                search: 4MB
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            config.rest_config.content_length_limits,
+            ContentLengthLimits {
+                search: ByteSize::mb(4),
+                index_management: ByteSize::mib(1),
+            }
+        );
+    }
+
     #[tokio::test]
     async fn test_node_config_validates_ingest_config() {
         let ingest_config = IngestApiConfig {
@@ -1302,6 +1800,20 @@ mod tests {
         let error_message = ingest_config.validate().unwrap_err().to_string();
         assert!(error_message.contains("either 1 or 2, got `3`"));
 
+        let ingest_config = IngestApiConfig {
+            streaming_content_length_limit: Some(ByteSize::mib(1)),
+            ..Default::default()
+        };
+        let error_message = ingest_config.validate().unwrap_err().to_string();
+        assert!(error_message.contains("must be at least content_length_limit"));
+
+        let ingest_config = IngestApiConfig {
+            max_unacknowledged_memory_usage: ByteSize::mib(1),
+            ..Default::default()
+        };
+        let error_message = ingest_config.validate().unwrap_err().to_string();
+        assert!(error_message.starts_with("max_unacknowledged_memory_usage"));
+
         let node_config_yaml = r#"
             version: 0.8
             ingest_api:
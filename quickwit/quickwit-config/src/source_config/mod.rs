@@ -18,9 +18,13 @@ use std::borrow::Cow;
 use std::hash::{Hash, Hasher};
 use std::num::NonZeroUsize;
 use std::str::FromStr;
+use std::time::Duration;
 
-use anyhow::ensure;
+use anyhow::{bail, ensure, Context};
 use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use humantime::parse_duration;
 use quickwit_common::is_false;
 use quickwit_common::uri::Uri;
 use quickwit_proto::metastore::SourceType;
@@ -34,6 +38,7 @@ use serialize::VersionedSourceConfig;
 pub use serialize::{load_source_config_from_user_config, load_source_config_update};
 use siphasher::sip::SipHasher;
 
+use crate::index_config::prepend_at_char;
 use crate::{disable_ingest_v1, enable_ingest_v2};
 
 /// Reserved source ID for the `quickwit index ingest` CLI command.
@@ -68,6 +73,14 @@ pub struct SourceConfig {
     // Denotes the input data format.
     #[serde(default)]
     pub input_format: SourceInputFormat,
+
+    /// Recurring time windows during which the control plane schedules the indexing pipelines of
+    /// the source. The source is always scheduled when empty.
+    pub activation_windows: Vec<SourceActivationWindow>,
+
+    /// Event-time range outside of which the documents of the source are dropped. Used to
+    /// reprocess historical data without polluting the current partitions of an index.
+    pub backfill_time_range: Option<BackfillTimeRange>,
 }
 
 impl SourceConfig {
@@ -102,6 +115,8 @@ impl SourceConfig {
             source_params: SourceParams::IngestCli,
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         }
     }
 
@@ -114,6 +129,8 @@ impl SourceConfig {
             source_params: SourceParams::Ingest,
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         }
     }
 
@@ -126,9 +143,30 @@ impl SourceConfig {
             source_params: SourceParams::IngestApi,
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         }
     }
 
+    /// Returns whether the source should be running at the given time according to its activation
+    /// windows.
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        if self.activation_windows.is_empty() {
+            return true;
+        }
+        self.activation_windows.iter().any(|activation_window| {
+            // The activation windows are validated when the source config is built, so we
+            // conservatively keep the source running if one of them cannot be evaluated.
+            activation_window.is_active_at(now).unwrap_or(true)
+        })
+    }
+
+    /// Returns whether the source should currently be running according to its activation
+    /// windows.
+    pub fn is_active(&self) -> bool {
+        self.is_active_at(Utc::now())
+    }
+
     /// Returns a fingerprint of parameters relevant for indexers.
     ///
     /// This should remain private to this crate to avoid confusion with the
@@ -140,6 +178,10 @@ impl SourceConfig {
         self.num_pipelines.hash(&mut hasher);
         self.source_params.hash(&mut hasher);
         self.transform_config.hash(&mut hasher);
+        // Only hashed when set so that the fingerprints of the existing sources do not change.
+        if let Some(backfill_time_range) = &self.backfill_time_range {
+            backfill_time_range.hash(&mut hasher);
+        }
         hasher.finish()
     }
 
@@ -152,6 +194,8 @@ impl SourceConfig {
             source_params,
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         }
     }
 }
@@ -174,6 +218,8 @@ impl crate::TestableForRegression for SourceConfig {
                 timezone: default_timezone(),
             }),
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         }
     }
 
@@ -182,6 +228,106 @@ impl crate::TestableForRegression for SourceConfig {
     }
 }
 
+/// Event-time range to which the ingestion of a source is limited when backfilling historical
+/// data. The bounds are Unix timestamps in seconds, and at least one of them must be set.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BackfillTimeRange {
+    /// Inclusive lower bound of the range.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_timestamp: Option<i64>,
+    /// Exclusive upper bound of the range.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_timestamp: Option<i64>,
+}
+
+impl BackfillTimeRange {
+    /// Returns whether the range contains the given timestamp, expressed in microseconds.
+    pub fn contains_micros(&self, timestamp_micros: i64) -> bool {
+        if let Some(start_timestamp) = self.start_timestamp {
+            if timestamp_micros < start_timestamp.saturating_mul(1_000_000) {
+                return false;
+            }
+        }
+        if let Some(end_timestamp) = self.end_timestamp {
+            if timestamp_micros >= end_timestamp.saturating_mul(1_000_000) {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        match (self.start_timestamp, self.end_timestamp) {
+            (None, None) => {
+                bail!("backfill time range must define `start_timestamp` or `end_timestamp`")
+            }
+            (Some(start_timestamp), Some(end_timestamp)) => {
+                ensure!(
+                    start_timestamp < end_timestamp,
+                    "backfill time range `start_timestamp` must be less than `end_timestamp`"
+                );
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// A recurring time window during which the indexing pipelines of a source are scheduled. Useful
+/// for batchy sources that should only consume indexing resources at specific times (at night,
+/// for instance).
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SourceActivationWindow {
+    /// Defines when the window opens, expressed in a human-friendly way (`hourly`, `daily`, ...)
+    /// or as a cron expression (`0 0 22 * * *`).
+    pub schedule: String,
+    /// Duration of the window, expressed in a human-friendly way (`30m`, `8 hours`, ...).
+    pub duration: String,
+}
+
+impl SourceActivationWindow {
+    pub fn schedule(&self) -> anyhow::Result<Schedule> {
+        let schedule = prepend_at_char(&self.schedule);
+
+        Schedule::from_str(&schedule).with_context(|| {
+            format!(
+                "failed to parse activation window schedule `{}`",
+                self.schedule
+            )
+        })
+    }
+
+    pub fn duration(&self) -> anyhow::Result<Duration> {
+        let duration = parse_duration(&self.duration).with_context(|| {
+            format!(
+                "failed to parse activation window duration `{}`",
+                self.duration
+            )
+        })?;
+        ensure!(
+            !duration.is_zero(),
+            "activation window duration must be strictly positive"
+        );
+        Ok(duration)
+    }
+
+    /// Returns whether the window is open at the given time, i.e. whether the schedule fired
+    /// less than `duration` ago.
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> anyhow::Result<bool> {
+        let schedule = self.schedule()?;
+        let duration = chrono::Duration::from_std(self.duration()?)
+            .context("activation window duration is too large")?;
+        let is_active = schedule
+            .after(&(now - duration))
+            .next()
+            .map(|window_start| window_start <= now)
+            .unwrap_or(false);
+        Ok(is_active)
+    }
+}
+
 #[derive(
     Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Hash, utoipa::ToSchema,
 )]
@@ -756,6 +902,8 @@ mod tests {
                 timezone: "local".to_string(),
             }),
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         assert_eq!(source_config, expected_source_config);
         assert_eq!(source_config.num_pipelines.get(), 2);
@@ -851,6 +999,8 @@ mod tests {
                 timezone: "local".to_string(),
             }),
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         assert_eq!(source_config, expected_source_config);
         assert_eq!(source_config.num_pipelines.get(), 1);
@@ -1355,6 +1505,8 @@ mod tests {
                 timezone: default_timezone(),
             }),
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         assert_eq!(source_config, expected_source_config);
         assert_eq!(source_config.num_pipelines.get(), 1);
@@ -1477,6 +1629,167 @@ mod tests {
         assert_eq!(source_config.input_format, SourceInputFormat::PlainText);
     }
 
+    #[test]
+    fn test_source_config_activation_windows() {
+        let file_content = r#"{
+            "version": "0.8",
+            "source_id": "kafka-source",
+            "source_type": "kafka",
+            "params": {
+                "topic": "my-topic"
+            },
+            "activation_windows": [
+                {
+                    "schedule": "0 0 22 * * *",
+                    "duration": "8h"
+                }
+            ]
+        }"#;
+        let source_config =
+            load_source_config_from_user_config(ConfigFormat::Json, file_content.as_bytes())
+                .unwrap();
+        assert_eq!(source_config.activation_windows.len(), 1);
+
+        let at = |datetime: &str| {
+            DateTime::parse_from_rfc3339(datetime)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        assert!(source_config.is_active_at(at("2024-01-01T23:00:00Z")));
+        assert!(source_config.is_active_at(at("2024-01-02T05:59:59Z")));
+        assert!(!source_config.is_active_at(at("2024-01-02T06:00:01Z")));
+        assert!(!source_config.is_active_at(at("2024-01-02T12:00:00Z")));
+
+        let source_config_without_windows =
+            SourceConfig::for_test("kafka-source", source_config.source_params.clone());
+        assert!(source_config_without_windows.is_active_at(at("2024-01-02T12:00:00Z")));
+    }
+
+    #[test]
+    fn test_source_config_invalid_activation_windows() {
+        let invalid_schedule = r#"{
+            "version": "0.8",
+            "source_id": "kafka-source",
+            "source_type": "kafka",
+            "params": {
+                "topic": "my-topic"
+            },
+            "activation_windows": [{"schedule": "every night", "duration": "8h"}]
+        }"#;
+        let error =
+            load_source_config_from_user_config(ConfigFormat::Json, invalid_schedule.as_bytes())
+                .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("failed to parse activation window schedule"));
+
+        let zero_duration = r#"{
+            "version": "0.8",
+            "source_id": "kafka-source",
+            "source_type": "kafka",
+            "params": {
+                "topic": "my-topic"
+            },
+            "activation_windows": [{"schedule": "daily", "duration": "0s"}]
+        }"#;
+        load_source_config_from_user_config(ConfigFormat::Json, zero_duration.as_bytes())
+            .unwrap_err();
+
+        let unsupported_source = r#"{
+            "version": "0.8",
+            "source_id": "void-source",
+            "source_type": "void",
+            "params": {},
+            "activation_windows": [{"schedule": "daily", "duration": "1h"}]
+        }"#;
+        load_source_config_from_user_config(ConfigFormat::Json, unsupported_source.as_bytes())
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_source_config_backfill_time_range() {
+        let file_content = r#"{
+            "version": "0.8",
+            "source_id": "kafka-source",
+            "source_type": "kafka",
+            "params": {
+                "topic": "my-topic",
+                "enable_backfill_mode": true
+            },
+            "backfill_time_range": {
+                "start_timestamp": 1704067200,
+                "end_timestamp": 1706745600
+            }
+        }"#;
+        let source_config =
+            load_source_config_from_user_config(ConfigFormat::Json, file_content.as_bytes())
+                .unwrap();
+        let backfill_time_range = source_config.backfill_time_range.unwrap();
+        assert_eq!(
+            backfill_time_range,
+            BackfillTimeRange {
+                start_timestamp: Some(1704067200),
+                end_timestamp: Some(1706745600),
+            }
+        );
+        let start_micros = 1704067200 * 1_000_000;
+        let end_micros = 1706745600 * 1_000_000;
+        assert!(!backfill_time_range.contains_micros(start_micros - 1));
+        assert!(backfill_time_range.contains_micros(start_micros));
+        assert!(backfill_time_range.contains_micros(end_micros - 1));
+        assert!(!backfill_time_range.contains_micros(end_micros));
+
+        let unbounded_end = BackfillTimeRange {
+            start_timestamp: Some(1704067200),
+            end_timestamp: None,
+        };
+        assert!(unbounded_end.contains_micros(i64::MAX));
+    }
+
+    #[test]
+    fn test_source_config_invalid_backfill_time_range() {
+        let empty_range = r#"{
+            "version": "0.8",
+            "source_id": "kafka-source",
+            "source_type": "kafka",
+            "params": {
+                "topic": "my-topic"
+            },
+            "backfill_time_range": {}
+        }"#;
+        let error = load_source_config_from_user_config(ConfigFormat::Json, empty_range.as_bytes())
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("must define `start_timestamp` or `end_timestamp`"));
+
+        let inverted_range = r#"{
+            "version": "0.8",
+            "source_id": "kafka-source",
+            "source_type": "kafka",
+            "params": {
+                "topic": "my-topic"
+            },
+            "backfill_time_range": {"start_timestamp": 1706745600, "end_timestamp": 1704067200}
+        }"#;
+        load_source_config_from_user_config(ConfigFormat::Json, inverted_range.as_bytes())
+            .unwrap_err();
+
+        let unsupported_source = r#"{
+            "version": "0.8",
+            "source_id": "void-source",
+            "source_type": "void",
+            "params": {},
+            "backfill_time_range": {"start_timestamp": 1704067200}
+        }"#;
+        let error =
+            load_source_config_from_user_config(ConfigFormat::Json, unsupported_source.as_bytes())
+                .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("only supported for Kafka and file sources"));
+    }
+
     #[tokio::test]
     async fn test_update_kafka_source_config() {
         let source_config_filepath = get_source_config_filepath("kafka-source.json");
@@ -1506,6 +1819,8 @@ mod tests {
                     timezone: "local".to_string(),
                 }),
                 input_format: SourceInputFormat::Json,
+                activation_windows: Vec::new(),
+                backfill_time_range: None,
             };
             assert_eq!(new_source_config, expected_source_config);
             assert_eq!(new_source_config.num_pipelines.get(), 2);
@@ -18,7 +18,7 @@ use anyhow::{bail, ensure};
 use quickwit_proto::types::SourceId;
 use serde::{Deserialize, Serialize};
 
-use super::{TransformConfig, RESERVED_SOURCE_IDS};
+use super::{BackfillTimeRange, SourceActivationWindow, TransformConfig, RESERVED_SOURCE_IDS};
 use crate::{
     validate_identifier, ConfigFormat, FileSourceParams, SourceConfig, SourceInputFormat,
     SourceParams,
@@ -134,6 +134,36 @@ impl SourceConfigForSerialization {
             }
         }
 
+        if !self.activation_windows.is_empty() {
+            match &self.source_params {
+                SourceParams::Kafka(_)
+                | SourceParams::Kinesis(_)
+                | SourceParams::PubSub(_)
+                | SourceParams::Pulsar(_)
+                | SourceParams::File(FileSourceParams::Notifications(_)) => {}
+                _ => {
+                    bail!(
+                        "activation windows are only supported for Kafka, Kinesis, Pulsar, GCP \
+                         PubSub, and file notification sources"
+                    );
+                }
+            }
+            for activation_window in &self.activation_windows {
+                activation_window.schedule()?;
+                activation_window.duration()?;
+            }
+        }
+
+        if let Some(backfill_time_range) = &self.backfill_time_range {
+            match &self.source_params {
+                SourceParams::Kafka(_) | SourceParams::File(_) => {}
+                _ => {
+                    bail!("backfill time ranges are only supported for Kafka and file sources");
+                }
+            }
+            backfill_time_range.validate()?;
+        }
+
         if let Some(transform_config) = &self.transform {
             if matches!(
                 self.input_format,
@@ -154,6 +184,8 @@ impl SourceConfigForSerialization {
             source_params: self.source_params,
             transform_config: self.transform,
             input_format: self.input_format,
+            activation_windows: self.activation_windows,
+            backfill_time_range: self.backfill_time_range,
         })
     }
 }
@@ -167,6 +199,8 @@ impl From<SourceConfig> for SourceConfigV0_8 {
             source_params: source_config.source_params,
             transform: source_config.transform_config,
             input_format: source_config.input_format,
+            activation_windows: source_config.activation_windows,
+            backfill_time_range: source_config.backfill_time_range,
         }
     }
 }
@@ -250,6 +284,14 @@ pub struct SourceConfigV0_8 {
     // Denotes the input data format.
     #[serde(default)]
     pub input_format: SourceInputFormat,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub activation_windows: Vec<SourceActivationWindow>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backfill_time_range: Option<BackfillTimeRange>,
 }
 
 impl From<SourceConfigV0_7> for SourceConfigV0_8 {
@@ -270,6 +312,8 @@ impl From<SourceConfigV0_7> for SourceConfigV0_8 {
             source_params,
             transform,
             input_format,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         }
     }
 }
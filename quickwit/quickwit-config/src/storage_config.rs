@@ -52,6 +52,12 @@ pub enum StorageBackendFlavor {
     /// MinIO
     #[serde(rename = "minio")]
     MinIO,
+    /// Alibaba Cloud Object Storage Service
+    #[serde(alias = "alibaba", alias = "aliyun")]
+    Oss,
+    /// Tencent Cloud Object Storage
+    #[serde(alias = "tencent")]
+    Cos,
 }
 
 /// Holds the storage configurations defined in the `storage` section of node config files.
@@ -101,6 +107,16 @@ impl StorageConfigs {
                 "{left:?} storage config is defined multiple times",
             );
         }
+        for storage_config in self.0.iter() {
+            let fault_injection_config_opt = match storage_config {
+                StorageConfig::File(file_storage_config) => &file_storage_config.fault_injection,
+                StorageConfig::Ram(ram_storage_config) => &ram_storage_config.fault_injection,
+                _ => &None,
+            };
+            if let Some(fault_injection_config) = fault_injection_config_opt {
+                fault_injection_config.validate()?;
+            }
+        }
         Ok(())
     }
 
@@ -318,6 +334,11 @@ pub struct S3StorageConfig {
     pub access_key_id: Option<String>,
     #[serde(default)]
     pub secret_access_key: Option<String>,
+    /// Session token of temporary credentials, for instance issued by a security token service
+    /// (STS).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
     #[serde(default)]
     pub region: Option<String>,
     #[serde(default)]
@@ -329,6 +350,11 @@ pub struct S3StorageConfig {
     pub disable_multi_object_delete: bool,
     #[serde(default)]
     pub disable_multipart_upload: bool,
+    /// Disables the CRC32C checksums (`x-amz-checksum-crc32c`) sent along the uploaded objects,
+    /// for the S3-compatible providers that do not support flexible checksums. The objects are
+    /// still checked with their MD5 digest.
+    #[serde(default)]
+    pub disable_flexible_checksums: bool,
 }
 
 impl S3StorageConfig {
@@ -337,18 +363,45 @@ impl S3StorageConfig {
             Some(StorageBackendFlavor::DigitalOcean) => {
                 self.force_path_style_access = true;
                 self.disable_multi_object_delete = true;
+                self.disable_flexible_checksums = true;
             }
             Some(StorageBackendFlavor::Garage) => {
                 self.region = Some("garage".to_string());
                 self.force_path_style_access = true;
+                self.disable_flexible_checksums = true;
             }
             Some(StorageBackendFlavor::Gcs) => {
                 self.disable_multi_object_delete = true;
                 self.disable_multipart_upload = true;
+                self.disable_flexible_checksums = true;
             }
             Some(StorageBackendFlavor::MinIO) => {
                 self.force_path_style_access = true;
             }
+            Some(StorageBackendFlavor::Oss) => {
+                // OSS rejects path-style requests.
+                self.force_path_style_access = false;
+                self.disable_flexible_checksums = true;
+
+                if self.endpoint.is_none() {
+                    self.endpoint = self.region.as_ref().map(|region| {
+                        let region = region.trim_start_matches("oss-");
+                        format!("https://oss-{region}.aliyuncs.com")
+                    });
+                }
+            }
+            Some(StorageBackendFlavor::Cos) => {
+                // Path-style requests are deprecated by COS.
+                self.force_path_style_access = false;
+                self.disable_flexible_checksums = true;
+
+                if self.endpoint.is_none() {
+                    self.endpoint = self
+                        .region
+                        .as_ref()
+                        .map(|region| format!("https://cos.{region}.myqcloud.com"));
+                }
+            }
             _ => {}
         }
     }
@@ -357,6 +410,9 @@ impl S3StorageConfig {
         if let Some(secret_access_key) = self.secret_access_key.as_mut() {
             *secret_access_key = "***redacted***".to_string();
         }
+        if let Some(session_token) = self.session_token.as_mut() {
+            *session_token = "***redacted***".to_string();
+        }
     }
 
     pub fn endpoint(&self) -> Option<String> {
@@ -385,6 +441,10 @@ impl fmt::Debug for S3StorageConfig {
                 "secret_access_key",
                 &self.secret_access_key.as_ref().map(|_| "***redacted***"),
             )
+            .field(
+                "session_token",
+                &self.session_token.as_ref().map(|_| "***redacted***"),
+            )
             .field("region", &self.region)
             .field("endpoint", &self.endpoint)
             .field("force_path_style_access", &self.force_path_style_access)
@@ -392,17 +452,56 @@ impl fmt::Debug for S3StorageConfig {
                 "disable_multi_object_delete",
                 &self.disable_multi_object_delete,
             )
+            .field("disable_multipart_upload", &self.disable_multipart_upload)
+            .field(
+                "disable_flexible_checksums",
+                &self.disable_flexible_checksums,
+            )
             .finish()
     }
 }
 
 #[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
-pub struct FileStorageConfig;
+pub struct FileStorageConfig {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fault_injection: Option<StorageFaultInjectionConfig>,
+}
 
 #[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
-pub struct RamStorageConfig;
+pub struct RamStorageConfig {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fault_injection: Option<StorageFaultInjectionConfig>,
+}
+
+/// Artificial latency and errors injected into the requests of the `file` and `ram` storages.
+///
+/// This is meant for testing the retry, hedging, and cache code paths without standing up an
+/// object storage emulator. It should never be enabled in production.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct StorageFaultInjectionConfig {
+    /// Latency added to every request.
+    pub latency_millis: u64,
+    /// Upper bound of the random latency added on top of `latency_millis`.
+    pub latency_jitter_millis: u64,
+    /// Percentage of requests failing with a (retryable) service error.
+    pub error_rate_percent: u8,
+}
+
+impl StorageFaultInjectionConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.error_rate_percent <= 100,
+            "storage fault injection `error_rate_percent` must be lower or equal to 100, got `{}`",
+            self.error_rate_percent
+        );
+        Ok(())
+    }
+}
 
 #[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -481,6 +580,19 @@ mod tests {
                 ..Default::default()
             }
             .into(),
+            S3StorageConfig {
+                flavor: Some(StorageBackendFlavor::Oss),
+                region: Some("oss-cn-hangzhou".to_string()),
+                force_path_style_access: true,
+                ..Default::default()
+            }
+            .into(),
+            S3StorageConfig {
+                flavor: Some(StorageBackendFlavor::Cos),
+                region: Some("ap-guangzhou".to_string()),
+                ..Default::default()
+            }
+            .into(),
         ]);
         storage_configs.apply_flavors();
 
@@ -495,9 +607,54 @@ mod tests {
         let gcs_storage_config = storage_configs[2].as_s3().unwrap();
         assert!(gcs_storage_config.disable_multi_object_delete);
         assert!(gcs_storage_config.disable_multipart_upload);
+        assert!(gcs_storage_config.disable_flexible_checksums);
 
         let minio_storage_config = storage_configs[3].as_s3().unwrap();
         assert!(minio_storage_config.force_path_style_access);
+        assert!(!minio_storage_config.disable_flexible_checksums);
+
+        let oss_storage_config = storage_configs[4].as_s3().unwrap();
+        assert!(!oss_storage_config.force_path_style_access);
+        assert!(!oss_storage_config.disable_multi_object_delete);
+        assert!(!oss_storage_config.disable_multipart_upload);
+        assert!(oss_storage_config.disable_flexible_checksums);
+        assert_eq!(
+            oss_storage_config.endpoint.as_deref(),
+            Some("https://oss-cn-hangzhou.aliyuncs.com")
+        );
+
+        let cos_storage_config = storage_configs[5].as_s3().unwrap();
+        assert!(!cos_storage_config.force_path_style_access);
+        assert_eq!(
+            cos_storage_config.endpoint.as_deref(),
+            Some("https://cos.ap-guangzhou.myqcloud.com")
+        );
+    }
+
+    #[test]
+    fn test_storage_s3_config_flavor_keeps_explicit_endpoint() {
+        let mut s3_storage_config = S3StorageConfig {
+            flavor: Some(StorageBackendFlavor::Oss),
+            region: Some("cn-shanghai".to_string()),
+            endpoint: Some("https://oss-cn-shanghai-internal.aliyuncs.com".to_string()),
+            ..Default::default()
+        };
+        s3_storage_config.apply_flavor();
+        assert_eq!(
+            s3_storage_config.endpoint.as_deref(),
+            Some("https://oss-cn-shanghai-internal.aliyuncs.com")
+        );
+
+        let mut s3_storage_config = S3StorageConfig {
+            flavor: Some(StorageBackendFlavor::Oss),
+            region: Some("cn-shanghai".to_string()),
+            ..Default::default()
+        };
+        s3_storage_config.apply_flavor();
+        assert_eq!(
+            s3_storage_config.endpoint.as_deref(),
+            Some("https://oss-cn-shanghai.aliyuncs.com")
+        );
     }
 
     #[test]
@@ -517,6 +674,44 @@ mod tests {
         storage_configs.validate().unwrap_err();
     }
 
+    #[test]
+    fn test_storage_fault_injection_config_serde() {
+        let storage_configs_yaml = r#"
+                file:
+                    fault_injection:
+                        latency_millis: 50
+                        error_rate_percent: 10
+                ram: {}
+            "#;
+        let storage_configs: StorageConfigs = serde_yaml::from_str(storage_configs_yaml).unwrap();
+        storage_configs.validate().unwrap();
+
+        let expected_fault_injection_config = StorageFaultInjectionConfig {
+            latency_millis: 50,
+            latency_jitter_millis: 0,
+            error_rate_percent: 10,
+        };
+        assert_eq!(
+            storage_configs.find_file().unwrap().fault_injection,
+            Some(expected_fault_injection_config)
+        );
+        assert!(storage_configs
+            .find_ram()
+            .unwrap()
+            .fault_injection
+            .is_none());
+
+        let storage_configs = StorageConfigs(vec![RamStorageConfig {
+            fault_injection: Some(StorageFaultInjectionConfig {
+                error_rate_percent: 101,
+                ..Default::default()
+            }),
+        }
+        .into()]);
+        let error = storage_configs.validate().unwrap_err();
+        assert!(error.to_string().contains("error_rate_percent"));
+    }
+
     #[test]
     fn test_storage_configs_redact() {
         let mut storage_configs = StorageConfigs(vec![
@@ -527,6 +722,7 @@ mod tests {
             .into(),
             S3StorageConfig {
                 secret_access_key: Some("test-s3-secret-access-key".to_string()),
+                session_token: Some("test-s3-session-token".to_string()),
                 ..Default::default()
             }
             .into(),
@@ -551,6 +747,15 @@ mod tests {
                 .unwrap(),
             "***redacted***"
         );
+        assert_eq!(
+            storage_configs
+                .find_s3()
+                .unwrap()
+                .session_token
+                .as_ref()
+                .unwrap(),
+            "***redacted***"
+        );
     }
 
     #[test]
@@ -625,6 +830,7 @@ mod tests {
                 force_path_style_access: true
                 disable_multi_object_delete_requests: true
                 disable_multipart_upload: true
+                disable_flexible_checksums: true
             "#;
             let s3_storage_config: S3StorageConfig =
                 serde_yaml::from_str(s3_storage_config_yaml).unwrap();
@@ -635,6 +841,7 @@ mod tests {
                 force_path_style_access: true,
                 disable_multi_object_delete: true,
                 disable_multipart_upload: true,
+                disable_flexible_checksums: true,
                 ..Default::default()
             };
             assert_eq!(s3_storage_config, expected_s3_config);
@@ -682,5 +889,30 @@ mod tests {
 
             assert_eq!(s3_storage_config.flavor, Some(StorageBackendFlavor::MinIO));
         }
+        {
+            let s3_storage_config_yaml = r#"
+                flavor: oss
+            "#;
+            let s3_storage_config: S3StorageConfig =
+                serde_yaml::from_str(s3_storage_config_yaml).unwrap();
+
+            assert_eq!(s3_storage_config.flavor, Some(StorageBackendFlavor::Oss));
+        }
+        {
+            let s3_storage_config_yaml = r#"
+                flavor: cos
+                access_key_id: test-secret-id
+                secret_access_key: test-secret-key
+                session_token: test-session-token
+            "#;
+            let s3_storage_config: S3StorageConfig =
+                serde_yaml::from_str(s3_storage_config_yaml).unwrap();
+
+            assert_eq!(s3_storage_config.flavor, Some(StorageBackendFlavor::Cos));
+            assert_eq!(
+                s3_storage_config.session_token.as_deref(),
+                Some("test-session-token")
+            );
+        }
     }
 }
@@ -29,13 +29,16 @@ use quickwit_actors::{
 use quickwit_cluster::{
     ClusterChange, ClusterChangeStream, ClusterChangeStreamFactory, ClusterNode,
 };
-use quickwit_common::pubsub::EventSubscriber;
+use quickwit_common::pubsub::{EventBroker, EventSubscriber};
 use quickwit_common::uri::Uri;
 use quickwit_common::{shared_consts, Progress};
 use quickwit_config::service::QuickwitService;
-use quickwit_config::{ClusterConfig, IndexConfig, IndexTemplate, SourceConfig};
+use quickwit_config::{ClusterConfig, IndexConfig, IndexTemplate, RolloutState, SourceConfig};
 use quickwit_ingest::{IngesterPool, LocalShardsUpdate};
-use quickwit_metastore::{CreateIndexRequestExt, CreateIndexResponseExt, IndexMetadataResponseExt};
+use quickwit_metastore::{
+    CreateIndexRequestExt, CreateIndexResponseExt, IndexMetadata, IndexMetadataResponseExt,
+    UpdateIndexRequestExt,
+};
 use quickwit_proto::control_plane::{
     AdviseResetShardsRequest, AdviseResetShardsResponse, ControlPlaneError, ControlPlaneResult,
     GetOrCreateOpenShardsRequest, GetOrCreateOpenShardsResponse, GetOrCreateOpenShardsSubrequest,
@@ -45,14 +48,17 @@ use quickwit_proto::metastore::{
     serde_utils, AddSourceRequest, CreateIndexRequest, CreateIndexResponse, DeleteIndexRequest,
     DeleteShardsRequest, DeleteSourceRequest, EmptyResponse, FindIndexTemplateMatchesRequest,
     IndexMetadataResponse, IndexTemplateMatch, MetastoreError, MetastoreResult, MetastoreService,
-    MetastoreServiceClient, PruneShardsRequest, ToggleSourceRequest, UpdateIndexRequest,
-    UpdateSourceRequest,
+    MetastoreServiceClient, PruneShardsRequest, ToggleSourceRequest, TrashIndexRequest,
+    UndeleteIndexRequest, UpdateIndexRequest, UpdateSourceRequest,
 };
+use quickwit_proto::search::ReportSplitsRequest;
 use quickwit_proto::types::{IndexId, IndexUid, NodeId, ShardId, SourceId, SourceUid};
 use serde::Serialize;
 use serde_json::{json, Value as JsonValue};
+use time::OffsetDateTime;
 use tokio::sync::watch;
-use tracing::{debug, error, info};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
 
 use crate::cooldown_map::{CooldownMap, CooldownStatus};
 use crate::debouncer::Debouncer;
@@ -60,6 +66,12 @@ use crate::indexing_scheduler::{IndexingScheduler, IndexingSchedulerState};
 use crate::ingest::ingest_controller::{IngestControllerStats, RebalanceShardsCallback};
 use crate::ingest::IngestController;
 use crate::model::ControlPlaneModel;
+use crate::rollout::{
+    compare_indexes, evaluate_rollout, EvaluateRollouts, ROLLOUT_EVALUATION_INTERVAL,
+};
+use crate::split_cache_prewarming::{
+    spawn_split_cache_prewarming_task, PrewarmSplitCaches, SPLIT_CACHE_PREWARMING_INTERVAL,
+};
 use crate::IndexerPool;
 
 /// Interval between two controls (or checks) of the desired plan VS running plan.
@@ -84,6 +96,7 @@ struct RebuildPlan;
 pub struct ControlPlane {
     cluster_config: ClusterConfig,
     cluster_change_stream_opt: Option<ClusterChangeStream>,
+    event_broker: EventBroker,
     // The control plane state is split into to independent functions, that we naturally isolated
     // code wise and state wise.
     //
@@ -98,6 +111,9 @@ pub struct ControlPlane {
     prune_shard_cooldown: CooldownMap<(IndexId, SourceId)>,
     rebuild_plan_debouncer: Debouncer,
     readiness_tx: watch::Sender<bool>,
+    // The split listing feeding the searcher split caches runs in a background task so that it
+    // does not block the control plane. A new prewarming is skipped while it is still running.
+    split_cache_prewarming_handle_opt: Option<JoinHandle<()>>,
     // Disables the control loop. This is useful for unit testing.
     disable_control_loop: bool,
 }
@@ -117,6 +133,7 @@ impl ControlPlane {
         indexer_pool: IndexerPool,
         ingester_pool: IngesterPool,
         metastore: MetastoreServiceClient,
+        event_broker: EventBroker,
     ) -> (
         Mailbox<Self>,
         ActorHandle<Supervisor<Self>>,
@@ -131,6 +148,7 @@ impl ControlPlane {
             indexer_pool,
             ingester_pool,
             metastore,
+            event_broker,
             disable_control_loop,
         )
     }
@@ -144,6 +162,7 @@ impl ControlPlane {
         indexer_pool: IndexerPool,
         ingester_pool: IngesterPool,
         metastore: MetastoreServiceClient,
+        event_broker: EventBroker,
         disable_control_loop: bool,
     ) -> (
         Mailbox<Self>,
@@ -176,6 +195,7 @@ impl ControlPlane {
                 ControlPlane {
                     cluster_config: cluster_config.clone(),
                     cluster_change_stream_opt: Some(cluster_change_stream_factory.create()),
+                    event_broker: event_broker.clone(),
                     indexing_scheduler,
                     ingest_controller,
                     metastore: metastore.clone(),
@@ -183,6 +203,7 @@ impl ControlPlane {
                     prune_shard_cooldown: CooldownMap::new(NonZeroUsize::new(1024).unwrap()),
                     rebuild_plan_debouncer: Debouncer::new(REBUILD_PLAN_COOLDOWN_PERIOD),
                     readiness_tx,
+                    split_cache_prewarming_handle_opt: None,
                     disable_control_loop,
                 }
             });
@@ -229,7 +250,15 @@ impl Actor for ControlPlane {
         self.ingest_controller.sync_with_all_ingesters(&self.model);
 
         ctx.schedule_self_msg(CONTROL_PLAN_LOOP_INTERVAL, ControlPlanLoop);
+        ctx.schedule_self_msg(ROLLOUT_EVALUATION_INTERVAL, EvaluateRollouts);
 
+        if self
+            .cluster_config
+            .split_cache_prewarm_num_splits_per_index
+            .is_some()
+        {
+            ctx.schedule_self_msg(SPLIT_CACHE_PREWARMING_INTERVAL, PrewarmSplitCaches);
+        }
         let weak_mailbox = ctx.mailbox().downgrade();
         let cluster_change_stream = self
             .cluster_change_stream_opt
@@ -394,6 +423,22 @@ impl ControlPlane {
             .self_send_with_cooldown::<RebuildPlan>(ctx);
         next_rebuild_waiter
     }
+
+    /// Removes a deleted index from the model, and resyncs the ingesters that were hosting its
+    /// shards, including the shards retained while the index was in the trash.
+    fn remove_index(&mut self, index_uid: &IndexUid, ctx: &ActorContext<Self>) {
+        let ingester_needing_resync: BTreeSet<NodeId> =
+            self.model.list_ingesters_for_index(index_uid);
+
+        self.model.delete_index(index_uid);
+
+        self.ingest_controller
+            .sync_with_ingesters(&ingester_needing_resync, &self.model);
+
+        // TODO: Refine the event. Notify index will have the effect to reload the entire state from
+        // the metastore. We should update the state of the control plane.
+        let _rebuild_plan_waiter = self.rebuild_plan_debounced(ctx);
+    }
 }
 
 #[async_trait]
@@ -479,6 +524,254 @@ impl Handler<ControlPlanLoop> for ControlPlane {
     }
 }
 
+#[async_trait]
+impl Handler<EvaluateRollouts> for ControlPlane {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        _message: EvaluateRollouts,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        if self.disable_control_loop {
+            return Ok(());
+        }
+        let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+
+        let due_candidates: Vec<IndexMetadata> = self
+            .model
+            .rollout_candidates()
+            .filter(|index_metadata| {
+                let Some(rollout) = &index_metadata.index_config.rollout_opt else {
+                    return false;
+                };
+                rollout.evaluation_period().is_ok_and(|evaluation_period| {
+                    index_metadata.create_timestamp + evaluation_period.as_secs() as i64
+                        <= now_timestamp
+                })
+            })
+            .cloned()
+            .collect();
+
+        for candidate_index_metadata in due_candidates {
+            self.complete_rollout(candidate_index_metadata, ctx).await?;
+        }
+        ctx.schedule_self_msg(ROLLOUT_EVALUATION_INTERVAL, EvaluateRollouts);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler<PrewarmSplitCaches> for ControlPlane {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        _message: PrewarmSplitCaches,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        if self.disable_control_loop {
+            return Ok(());
+        }
+        let Some(num_splits_per_index) =
+            self.cluster_config.split_cache_prewarm_num_splits_per_index
+        else {
+            return Ok(());
+        };
+        ctx.schedule_self_msg(SPLIT_CACHE_PREWARMING_INTERVAL, PrewarmSplitCaches);
+
+        if self
+            .split_cache_prewarming_handle_opt
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+        {
+            warn!("previous split cache prewarming is still running, skipping");
+            return Ok(());
+        }
+        let indexes: Vec<(IndexUid, Uri, Option<String>)> = self
+            .model
+            .index_metadatas()
+            .map(|index_metadata| {
+                (
+                    index_metadata.index_uid.clone(),
+                    index_metadata.index_uri().clone(),
+                    index_metadata.index_config.storage_credentials_opt.clone(),
+                )
+            })
+            .collect();
+        let split_cache_prewarming_handle = spawn_split_cache_prewarming_task(
+            self.metastore.clone(),
+            self.event_broker.clone(),
+            indexes,
+            num_splits_per_index.get() as usize,
+        );
+        self.split_cache_prewarming_handle_opt = Some(split_cache_prewarming_handle);
+        Ok(())
+    }
+}
+
+impl ControlPlane {
+    /// Compares the candidate index of a rollout with its primary index and either promotes the
+    /// candidate configuration onto the primary index or rolls the rollout back. The rollout is
+    /// left untouched if the comparison fails, and evaluated again later.
+    async fn complete_rollout(
+        &mut self,
+        candidate_index_metadata: IndexMetadata,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        let candidate_index_uid = candidate_index_metadata.index_uid.clone();
+        let mut rollout = candidate_index_metadata
+            .index_config
+            .rollout_opt
+            .clone()
+            .expect("rollout candidates should have a rollout");
+
+        let primary_index_metadata_opt = self
+            .model
+            .index_uid(&rollout.primary_index_id)
+            .and_then(|primary_index_uid| self.model.index_metadata(primary_index_uid))
+            .cloned();
+
+        let outcome = if let Some(primary_index_metadata) = primary_index_metadata_opt {
+            let metastore = self.metastore.clone();
+            let comparison = match ctx
+                .protect_future(compare_indexes(
+                    &metastore,
+                    &primary_index_metadata,
+                    &candidate_index_metadata,
+                ))
+                .await
+            {
+                Ok(comparison) => comparison,
+                Err(metastore_error) => {
+                    warn!(
+                        index_uid=%candidate_index_uid,
+                        error=%metastore_error,
+                        "failed to compare rollout candidate with its primary index"
+                    );
+                    return Ok(());
+                }
+            };
+            match evaluate_rollout(&rollout, &comparison) {
+                Ok(()) => {
+                    self.promote_rollout_candidate(
+                        &primary_index_metadata,
+                        &candidate_index_metadata,
+                        ctx,
+                    )
+                    .await?
+                }
+                Err(reason) => Err(reason),
+            }
+        } else {
+            Err(format!(
+                "primary index `{}` not found",
+                rollout.primary_index_id
+            ))
+        };
+        let outcome_label = match outcome {
+            Ok(()) => {
+                info!(
+                    index_uid=%candidate_index_uid,
+                    primary_index_id=%rollout.primary_index_id,
+                    "promoted rollout candidate"
+                );
+                rollout.state = RolloutState::Promoted;
+                "promoted"
+            }
+            Err(reason) => {
+                warn!(
+                    index_uid=%candidate_index_uid,
+                    primary_index_id=%rollout.primary_index_id,
+                    reason=%reason,
+                    "rolled back rollout"
+                );
+                rollout.state = RolloutState::RolledBack;
+                "rolled_back"
+            }
+        };
+        crate::metrics::CONTROL_PLANE_METRICS
+            .rollouts_total
+            .with_label_values([outcome_label])
+            .inc();
+
+        let index_config = &candidate_index_metadata.index_config;
+        let update_index_request_result = UpdateIndexRequest::try_from_updates(
+            candidate_index_uid.clone(),
+            &index_config.search_settings,
+            &index_config.retention_policy_opt,
+            &index_config.indexing_settings,
+            &index_config.doc_mapping,
+        )
+        .and_then(|mut update_index_request| {
+            update_index_request.rollout_json = Some(serde_utils::to_json_str(&rollout)?);
+            Ok(update_index_request)
+        });
+        let update_index_request = match update_index_request_result {
+            Ok(update_index_request) => update_index_request,
+            Err(metastore_error) => {
+                error!(
+                    index_uid=%candidate_index_uid,
+                    error=%metastore_error,
+                    "failed to build update index request"
+                );
+                return Ok(());
+            }
+        };
+        if let Err(control_plane_error) =
+            <Self as Handler<UpdateIndexRequest>>::handle(self, update_index_request, ctx).await?
+        {
+            error!(
+                index_uid=%candidate_index_uid,
+                error=%control_plane_error,
+                "failed to complete rollout"
+            );
+        }
+        Ok(())
+    }
+
+    /// Applies the configuration of the candidate index to the primary index. Returns the reason of
+    /// the failure otherwise.
+    async fn promote_rollout_candidate(
+        &mut self,
+        primary_index_metadata: &IndexMetadata,
+        candidate_index_metadata: &IndexMetadata,
+        ctx: &ActorContext<Self>,
+    ) -> Result<Result<(), String>, ActorExitStatus> {
+        let promoted_index_config = match primary_index_metadata
+            .index_config
+            .promote_rollout_candidate(&candidate_index_metadata.index_config)
+        {
+            Ok(promoted_index_config) => promoted_index_config,
+            Err(error) => {
+                return Ok(Err(format!("failed to promote candidate index: {error}")));
+            }
+        };
+        let update_index_request = match UpdateIndexRequest::try_from_updates(
+            primary_index_metadata.index_uid.clone(),
+            &promoted_index_config.search_settings,
+            &promoted_index_config.retention_policy_opt,
+            &promoted_index_config.indexing_settings,
+            &promoted_index_config.doc_mapping,
+        ) {
+            Ok(update_index_request) => update_index_request,
+            Err(metastore_error) => {
+                return Ok(Err(format!(
+                    "failed to build update index request: {metastore_error}"
+                )));
+            }
+        };
+        if let Err(control_plane_error) =
+            <Self as Handler<UpdateIndexRequest>>::handle(self, update_index_request, ctx).await?
+        {
+            return Ok(Err(format!(
+                "failed to update primary index: {control_plane_error}"
+            )));
+        }
+        Ok(Ok(()))
+    }
+}
+
 /// This function converts a metastore error into an actor error.
 ///
 /// If the metastore error is implying the transaction has not been
@@ -580,6 +873,8 @@ impl Handler<UpdateIndexRequest> for ControlPlane {
         let index_uid: IndexUid = request.index_uid().clone();
         debug!(%index_uid, "updating index");
 
+        let was_ingestion_frozen = self.model.is_ingestion_frozen(&index_uid);
+
         let response = match ctx
             .protect_future(self.metastore.update_index(request))
             .await
@@ -596,10 +891,19 @@ impl Handler<UpdateIndexRequest> for ControlPlane {
                 return Err(ActorExitStatus::from(anyhow::anyhow!(serde_error)));
             }
         };
-        if self
+        let fingerprint_changed = self
             .model
-            .update_index_config(&index_uid, index_metadata.index_config)?
-        {
+            .update_index_config(&index_uid, index_metadata.index_config)?;
+
+        let is_ingestion_frozen = self.model.is_ingestion_frozen(&index_uid);
+
+        if is_ingestion_frozen && !was_ingestion_frozen {
+            info!(%index_uid, "freezing ingestion");
+            self.ingest_controller
+                .close_index_shards(&index_uid, &mut self.model, ctx.progress())
+                .await;
+        }
+        if fingerprint_changed || is_ingestion_frozen != was_ingestion_frozen {
             let _rebuild_plan_notifier = self.rebuild_plan_debounced(ctx);
         }
         info!(%index_uid, "updated index");
@@ -629,20 +933,43 @@ impl Handler<DeleteIndexRequest> for ControlPlane {
         };
         info!(%index_uid, "deleted index");
 
-        let ingester_needing_resync: BTreeSet<NodeId> = self
-            .model
-            .list_shards_for_index(&index_uid)
-            .flat_map(|shard_entry| shard_entry.ingesters())
-            .map(|node_id_ref| node_id_ref.to_owned())
-            .collect();
+        self.remove_index(&index_uid, ctx);
 
-        self.model.delete_index(&index_uid);
+        let response = EmptyResponse {};
+        Ok(Ok(response))
+    }
+}
 
+// This handler is a metastore call proxied through the control plane: we must first forward the
+// request to the metastore, and then act on the event.
+#[async_trait]
+impl Handler<TrashIndexRequest> for ControlPlane {
+    type Reply = ControlPlaneResult<EmptyResponse>;
+
+    async fn handle(
+        &mut self,
+        request: TrashIndexRequest,
+        ctx: &ActorContext<Self>,
+    ) -> Result<Self::Reply, ActorExitStatus> {
+        let index_uid: IndexUid = request.index_uid().clone();
+        debug!(%index_uid, "moving index to trash");
+
+        if let Err(metastore_error) = ctx
+            .protect_future(self.metastore.trash_index(request))
+            .await
+        {
+            return convert_metastore_error(metastore_error);
+        };
+        info!(%index_uid, "moved index to trash");
+
+        // The shards are closed so that routers stop persisting documents into them, but the
+        // ingesters keep them until the index is purged, so that it can be restored without losing
+        // the documents that were not indexed yet.
         self.ingest_controller
-            .sync_with_ingesters(&ingester_needing_resync, &self.model);
+            .close_index_shards(&index_uid, &mut self.model, ctx.progress())
+            .await;
+        self.model.trash_index(&index_uid);
 
-        // TODO: Refine the event. Notify index will have the effect to reload the entire state from
-        // the metastore. We should update the state of the control plane.
         let _rebuild_plan_waiter = self.rebuild_plan_debounced(ctx);
 
         let response = EmptyResponse {};
@@ -650,6 +977,48 @@ impl Handler<DeleteIndexRequest> for ControlPlane {
     }
 }
 
+// This handler is a metastore call proxied through the control plane: we must first forward the
+// request to the metastore, and then act on the event.
+#[async_trait]
+impl Handler<UndeleteIndexRequest> for ControlPlane {
+    type Reply = ControlPlaneResult<IndexMetadataResponse>;
+
+    async fn handle(
+        &mut self,
+        request: UndeleteIndexRequest,
+        ctx: &ActorContext<Self>,
+    ) -> Result<Self::Reply, ActorExitStatus> {
+        let index_id: IndexId = request.index_id.clone();
+        debug!(%index_id, "restoring index from trash");
+
+        let response = match ctx
+            .protect_future(self.metastore.undelete_index(request))
+            .await
+        {
+            Ok(response) => response,
+            Err(metastore_error) => {
+                return convert_metastore_error(metastore_error);
+            }
+        };
+        let index_metadata = match response.deserialize_index_metadata() {
+            Ok(index_metadata) => index_metadata,
+            Err(serde_error) => {
+                error!(error=?serde_error, "failed to deserialize index metadata");
+                return Err(ActorExitStatus::from(anyhow::anyhow!(serde_error)));
+            }
+        };
+        info!(index_uid=%index_metadata.index_uid, "restored index from trash");
+
+        let should_rebuild_plan = !index_metadata.sources.is_empty();
+        self.model.undelete_index(index_metadata);
+
+        if should_rebuild_plan {
+            let _rebuild_plan_waiter = self.rebuild_plan_debounced(ctx);
+        }
+        Ok(Ok(response))
+    }
+}
+
 // This handler is a metastore call proxied through the control plane: we must first forward the
 // request to the metastore, and then act on the event.
 #[async_trait]
@@ -1121,12 +1490,15 @@ mod tests {
     use mockall::Sequence;
     use quickwit_actors::{AskError, Observe, SupervisorMetrics};
     use quickwit_cluster::ClusterChangeStreamFactoryForTest;
+    use quickwit_common::ServiceStream;
     use quickwit_config::{
-        IndexConfig, KafkaSourceParams, SourceParams, CLI_SOURCE_ID, INGEST_V2_SOURCE_ID,
+        IndexConfig, IndexRollout, KafkaSourceParams, SourceParams, CLI_SOURCE_ID,
+        INGEST_V2_SOURCE_ID,
     };
     use quickwit_indexing::IndexingService;
     use quickwit_metastore::{
-        CreateIndexRequestExt, IndexMetadata, ListIndexesMetadataResponseExt,
+        CreateIndexRequestExt, IndexMetadata, ListIndexesMetadataResponseExt, ListSplitsRequestExt,
+        ListSplitsResponseExt, SortBy, Split, SplitMetadata, SplitState,
     };
     use quickwit_proto::control_plane::{
         GetOrCreateOpenShardsFailureReason, GetOrCreateOpenShardsSubrequest,
@@ -1143,8 +1515,8 @@ mod tests {
     use quickwit_proto::metastore::{
         DeleteShardsResponse, EntityKind, FindIndexTemplateMatchesResponse,
         ListIndexesMetadataRequest, ListIndexesMetadataResponse, ListShardsRequest,
-        ListShardsResponse, ListShardsSubresponse, MetastoreError, MockMetastoreService,
-        OpenShardSubresponse, OpenShardsResponse, SourceType,
+        ListShardsResponse, ListShardsSubresponse, ListSplitsResponse, MetastoreError,
+        MockMetastoreService, OpenShardSubresponse, OpenShardsResponse, SourceType,
     };
     use quickwit_proto::types::{DocMappingUid, Position};
     use tokio::sync::Mutex;
@@ -1193,6 +1565,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
         let index_config = IndexConfig::for_test("test-index", "ram:///test-index");
         let create_index_request =
@@ -1236,6 +1609,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
         let delete_index_request = DeleteIndexRequest {
             index_uid: Some(index_uid),
@@ -1250,6 +1624,65 @@ mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_control_plane_trash_and_undelete_index() {
+        let universe = Universe::with_accelerated_time();
+        let self_node_id: NodeId = "test-node".into();
+        let indexer_pool = IndexerPool::default();
+        let ingester_pool = IngesterPool::default();
+
+        let index_metadata = IndexMetadata::for_test("test-index", "ram://test");
+        let index_uid: IndexUid = index_metadata.index_uid.clone();
+        let mut mock_metastore = MockMetastoreService::new();
+        let index_uid_clone = index_uid.clone();
+        mock_metastore
+            .expect_trash_index()
+            .withf(move |trash_index_request| trash_index_request.index_uid() == &index_uid_clone)
+            .returning(|_| Ok(EmptyResponse {}));
+        mock_metastore
+            .expect_undelete_index()
+            .withf(|undelete_index_request| undelete_index_request.index_id == "test-index")
+            .return_once(move |_| {
+                Ok(IndexMetadataResponse::try_from_index_metadata(&index_metadata).unwrap())
+            });
+        mock_metastore
+            .expect_list_indexes_metadata()
+            .returning(|_| Ok(ListIndexesMetadataResponse::for_test(Vec::new())));
+
+        let cluster_config = ClusterConfig::for_test();
+        let cluster_change_stream_factory = ClusterChangeStreamFactoryForTest::default();
+        let (control_plane_mailbox, _control_plane_handle, _readiness_rx) = ControlPlane::spawn(
+            &universe,
+            cluster_config,
+            self_node_id,
+            cluster_change_stream_factory,
+            indexer_pool,
+            ingester_pool,
+            MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
+        );
+        let trash_index_request = TrashIndexRequest {
+            index_uid: Some(index_uid.clone()),
+        };
+        control_plane_mailbox
+            .ask_for_res(trash_index_request)
+            .await
+            .unwrap();
+
+        let undelete_index_request = UndeleteIndexRequest {
+            index_id: "test-index".to_string(),
+        };
+        let index_metadata = control_plane_mailbox
+            .ask_for_res(undelete_index_request)
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap();
+        assert_eq!(index_metadata.index_uid, index_uid);
+
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_control_plane_add_source() {
         let universe = Universe::with_accelerated_time();
@@ -1295,6 +1728,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
         let index_uid: IndexUid = IndexUid::for_test("test-index", 0);
         let source_config = SourceConfig::for_test("test-source", SourceParams::void());
@@ -1396,6 +1830,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
         let index_uid: IndexUid = IndexUid::for_test("test-index", 0);
         test_source_config.num_pipelines = NonZero::new(pipelines_after_update).unwrap();
@@ -1465,6 +1900,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
         let enable_source_request = ToggleSourceRequest {
             index_uid: Some(index_uid.clone()),
@@ -1521,6 +1957,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
         let delete_source_request = DeleteSourceRequest {
             index_uid: Some(index_uid),
@@ -1590,6 +2027,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
         let get_open_shards_request = GetOrCreateOpenShardsRequest {
             subrequests: vec![GetOrCreateOpenShardsSubrequest {
@@ -1632,7 +2070,10 @@ mod tests {
             .expect_list_indexes_metadata()
             .times(2) // 1 for the first initialization, 1 after the respawn of the control plane.
             .returning(|list_indexes_request: ListIndexesMetadataRequest| {
-                assert_eq!(list_indexes_request, ListIndexesMetadataRequest::all());
+                assert_eq!(
+                    list_indexes_request,
+                    ListIndexesMetadataRequest::all_including_trashed()
+                );
                 Ok(ListIndexesMetadataResponse::for_test(Vec::new()))
             });
         mock_metastore.expect_list_shards().return_once(
@@ -1682,6 +2123,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
         tokio::time::timeout(
             Duration::from_secs(5),
@@ -1779,7 +2221,10 @@ mod tests {
         let index_0_clone = index_0.clone();
         mock_metastore.expect_list_indexes_metadata().return_once(
             move |list_indexes_request: ListIndexesMetadataRequest| {
-                assert_eq!(list_indexes_request, ListIndexesMetadataRequest::all());
+                assert_eq!(
+                    list_indexes_request,
+                    ListIndexesMetadataRequest::all_including_trashed()
+                );
                 Ok(ListIndexesMetadataResponse::for_test(vec![
                     index_0_clone.clone()
                 ]))
@@ -1837,6 +2282,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
         let source_uid = SourceUid {
             index_uid: index_0.index_uid.clone(),
@@ -1931,7 +2377,10 @@ mod tests {
         let index_metadata_clone = index_metadata.clone();
         mock_metastore.expect_list_indexes_metadata().return_once(
             move |list_indexes_request: ListIndexesMetadataRequest| {
-                assert_eq!(list_indexes_request, ListIndexesMetadataRequest::all());
+                assert_eq!(
+                    list_indexes_request,
+                    ListIndexesMetadataRequest::all_including_trashed()
+                );
                 Ok(ListIndexesMetadataResponse::for_test(vec![
                     index_metadata_clone,
                 ]))
@@ -1972,6 +2421,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
         let control_plane_debug_info = control_plane_mailbox.ask(GetDebugInfo).await.unwrap();
         let shard =
@@ -2009,7 +2459,10 @@ mod tests {
         let index_0_clone = index_0.clone();
         mock_metastore.expect_list_indexes_metadata().return_once(
             move |list_indexes_request: ListIndexesMetadataRequest| {
-                assert_eq!(list_indexes_request, ListIndexesMetadataRequest::all());
+                assert_eq!(
+                    list_indexes_request,
+                    ListIndexesMetadataRequest::all_including_trashed()
+                );
                 Ok(ListIndexesMetadataResponse::for_test(vec![
                     index_0_clone.clone()
                 ]))
@@ -2057,6 +2510,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
         let source_uid = SourceUid {
             index_uid: index_0.index_uid.clone(),
@@ -2100,7 +2554,10 @@ mod tests {
             .times(1)
             .in_sequence(&mut seq)
             .returning(move |list_indexes_request: ListIndexesMetadataRequest| {
-                assert_eq!(list_indexes_request, ListIndexesMetadataRequest::all());
+                assert_eq!(
+                    list_indexes_request,
+                    ListIndexesMetadataRequest::all_including_trashed()
+                );
                 Ok(ListIndexesMetadataResponse::for_test(vec![
                     index_0_clone.clone()
                 ]))
@@ -2141,7 +2598,7 @@ mod tests {
                     request.retain_shards_for_sources[0].shard_ids,
                     [ShardId::from(15)]
                 );
-                Ok(RetainShardsResponse {})
+                Ok(RetainShardsResponse::default())
             });
 
         let index_uid_clone = index_0.index_uid.clone();
@@ -2161,7 +2618,7 @@ mod tests {
                 assert_eq!(request.retain_shards_for_sources.len(), 1);
                 let retain_shards_for_source = request.retain_shards_for_sources.pop().unwrap();
                 assert!(&retain_shards_for_source.shard_ids.is_empty());
-                Ok(RetainShardsResponse {})
+                Ok(RetainShardsResponse::default())
             });
         let ingester = IngesterServiceClient::from_mock(mock_ingester);
         ingester_pool.insert("node1".into(), ingester);
@@ -2176,6 +2633,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
         // This update should not trigger anything in the control plane.
         control_plane_mailbox
@@ -2207,7 +2665,7 @@ mod tests {
                     request.retain_shards_for_sources[0].shard_ids,
                     [ShardId::from(15)]
                 );
-                Ok(RetainShardsResponse {})
+                Ok(RetainShardsResponse::default())
             });
         let ingester = IngesterServiceClient::from_mock(mock_ingester);
         ingester_pool.insert("node1".into(), ingester);
@@ -2231,7 +2689,10 @@ mod tests {
         let index_0_clone = index_0.clone();
         mock_metastore.expect_list_indexes_metadata().return_once(
             move |list_indexes_request: ListIndexesMetadataRequest| {
-                assert_eq!(list_indexes_request, ListIndexesMetadataRequest::all());
+                assert_eq!(
+                    list_indexes_request,
+                    ListIndexesMetadataRequest::all_including_trashed()
+                );
                 Ok(ListIndexesMetadataResponse::for_test(vec![
                     index_0_clone.clone()
                 ]))
@@ -2272,6 +2733,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
         // This update should not trigger anything in the control plane.
         control_plane_mailbox
@@ -2355,6 +2817,7 @@ mod tests {
             indexer_pool,
             ingester_pool,
             MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
         );
 
         let response = control_plane_mailbox
@@ -2443,6 +2906,7 @@ mod tests {
                 indexer_pool.clone(),
                 ingester_pool,
                 metastore,
+                EventBroker::default(),
                 disable_control_loop,
             );
         let cluster_change_stream_tx = cluster_change_stream_factory.change_stream_tx();
@@ -2493,7 +2957,7 @@ mod tests {
         let mut mock_ingester = MockIngesterService::new();
         mock_ingester
             .expect_retain_shards()
-            .return_once(|_| Ok(RetainShardsResponse {}));
+            .return_once(|_| Ok(RetainShardsResponse::default()));
         mock_ingester.expect_init_shards().return_once(|request| {
             let shard = request.subrequests[0].shard().clone();
             let response = InitShardsResponse {
@@ -2559,6 +3023,7 @@ mod tests {
             indexer_pool.clone(),
             ingester_pool,
             metastore,
+            EventBroker::default(),
         );
         let index_config = IndexConfig::for_test("test-index", "ram:///test-index");
         let mut source_config = SourceConfig::ingest_v2();
@@ -2647,7 +3112,7 @@ mod tests {
         let mut mock_ingester = MockIngesterService::new();
         mock_ingester
             .expect_retain_shards()
-            .return_once(|_| Ok(RetainShardsResponse {}));
+            .return_once(|_| Ok(RetainShardsResponse::default()));
         mock_ingester.expect_init_shards().return_once(|request| {
             let shard = request.subrequests[0].shard().clone();
             let response = InitShardsResponse {
@@ -2713,6 +3178,7 @@ mod tests {
             indexer_pool.clone(),
             ingester_pool,
             metastore,
+            EventBroker::default(),
         );
         let index_config = IndexConfig::for_test("test-index", "ram:///test-index");
         let mut source_config = SourceConfig::ingest_v2();
@@ -2763,4 +3229,139 @@ mod tests {
 
         universe.assert_quit().await;
     }
+
+    #[tokio::test]
+    async fn test_control_plane_rolls_back_rollout_without_primary_index() {
+        let universe = Universe::with_accelerated_time();
+        let self_node_id: NodeId = "test-node".into();
+        let indexer_pool = IndexerPool::default();
+        let ingester_pool = IngesterPool::default();
+
+        let mut candidate_index_metadata =
+            IndexMetadata::for_test("test-index-candidate", "ram:///test-index-candidate");
+        candidate_index_metadata.create_timestamp = 0;
+        candidate_index_metadata.index_config.rollout_opt = Some(IndexRollout {
+            primary_index_id: "test-index".to_string(),
+            ingest_percentage: 10.0,
+            search_percentage: 10.0,
+            evaluation_period: IndexRollout::default_evaluation_period(),
+            min_num_docs: 0,
+            max_storage_ratio: None,
+            max_scanned_bytes_ratio: None,
+            state: RolloutState::Evaluating,
+        });
+        let candidate_index_metadata_clone = candidate_index_metadata.clone();
+
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_list_indexes_metadata()
+            .return_once(move |_| {
+                Ok(ListIndexesMetadataResponse::for_test(vec![
+                    candidate_index_metadata_clone,
+                ]))
+            });
+        let (rollout_tx, mut rollout_rx) = tokio::sync::mpsc::unbounded_channel();
+        mock_metastore
+            .expect_update_index()
+            .return_once(move |update_index_request| {
+                let rollout = update_index_request.deserialize_rollout().unwrap().unwrap();
+                rollout_tx.send(rollout.clone()).unwrap();
+
+                candidate_index_metadata.set_rollout(rollout);
+                let response =
+                    IndexMetadataResponse::try_from_index_metadata(&candidate_index_metadata)
+                        .unwrap();
+                Ok(response)
+            });
+        let cluster_config = ClusterConfig::for_test();
+        let cluster_change_stream_factory = ClusterChangeStreamFactoryForTest::default();
+        let (_control_plane_mailbox, _control_plane_handle, _readiness_rx) = ControlPlane::spawn(
+            &universe,
+            cluster_config,
+            self_node_id,
+            cluster_change_stream_factory,
+            indexer_pool,
+            ingester_pool,
+            MetastoreServiceClient::from_mock(mock_metastore),
+            EventBroker::default(),
+        );
+        let rollout = rollout_rx.recv().await.unwrap();
+        assert_eq!(rollout.state, RolloutState::RolledBack);
+
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_control_plane_prewarms_split_caches() {
+        let universe = Universe::with_accelerated_time();
+        let self_node_id: NodeId = "test-node".into();
+        let indexer_pool = IndexerPool::default();
+        let ingester_pool = IngesterPool::default();
+
+        let index_metadata = IndexMetadata::for_test("test-index", "ram:///indexes/test-index");
+        let index_uid = index_metadata.index_uid.clone();
+
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_list_indexes_metadata()
+            .return_once(move |_| Ok(ListIndexesMetadataResponse::for_test(vec![index_metadata])));
+        mock_metastore
+            .expect_list_splits()
+            .returning(move |list_splits_request| {
+                let query = list_splits_request.deserialize_list_splits_query().unwrap();
+                assert_eq!(query.index_uids.unwrap(), vec![index_uid.clone()]);
+                assert_eq!(query.split_states, vec![SplitState::Published]);
+                assert_eq!(query.sort_by, SortBy::Recency);
+                assert_eq!(query.limit, Some(2));
+
+                let splits = [("split-b", 30), ("split-c", 20)]
+                    .into_iter()
+                    .map(|(split_id, time_range_end)| Split {
+                        split_metadata: SplitMetadata {
+                            split_id: split_id.to_string(),
+                            index_uid: index_uid.clone(),
+                            time_range: Some(0..=time_range_end),
+                            ..Default::default()
+                        },
+                        split_state: SplitState::Published,
+                        update_timestamp: 0,
+                        publish_timestamp: None,
+                    })
+                    .collect();
+                let response = ListSplitsResponse::try_from_splits(splits).unwrap();
+                Ok(ServiceStream::from(vec![Ok(response)]))
+            });
+        let event_broker = EventBroker::default();
+        let (report_splits_tx, mut report_splits_rx) = tokio::sync::mpsc::unbounded_channel();
+        let _subscription_handle =
+            event_broker.subscribe(move |report_splits_request: ReportSplitsRequest| {
+                let _ = report_splits_tx.send(report_splits_request);
+            });
+        let mut cluster_config = ClusterConfig::for_test();
+        cluster_config.split_cache_prewarm_num_splits_per_index = NonZero::new(2);
+        let cluster_change_stream_factory = ClusterChangeStreamFactoryForTest::default();
+        let (_control_plane_mailbox, _control_plane_handle, _readiness_rx) = ControlPlane::spawn(
+            &universe,
+            cluster_config,
+            self_node_id,
+            cluster_change_stream_factory,
+            indexer_pool,
+            ingester_pool,
+            MetastoreServiceClient::from_mock(mock_metastore),
+            event_broker,
+        );
+        let report_splits_request = report_splits_rx.recv().await.unwrap();
+        let split_ids: Vec<&str> = report_splits_request
+            .report_splits
+            .iter()
+            .map(|report_split| report_split.split_id.as_str())
+            .collect();
+        assert_eq!(split_ids, ["split-b", "split-c"]);
+        assert_eq!(
+            report_splits_request.report_splits[0].storage_uri,
+            "ram:///indexes/test-index"
+        );
+
+        universe.assert_quit().await;
+    }
 }
@@ -30,8 +30,8 @@ use quickwit_proto::indexing::{
     ApplyIndexingPlanRequest, CpuCapacity, IndexingService, IndexingTask, PIPELINE_FULL_CAPACITY,
     PIPELINE_THROUGHPUT,
 };
-use quickwit_proto::types::NodeId;
-use scheduling::{SourceToSchedule, SourceToScheduleType};
+use quickwit_proto::types::{NodeId, SourceUid};
+use scheduling::{SourcePriority, SourceToSchedule, SourceToScheduleType};
 use serde::Serialize;
 use tracing::{debug, info, warn};
 
@@ -56,6 +56,9 @@ pub struct IndexingSchedulerState {
     pub last_applied_physical_plan: Option<PhysicalIndexingPlan>,
     #[serde(skip)]
     pub last_applied_plan_timestamp: Option<Instant>,
+    /// Sources left out of the last plan because they were outside of their activation windows.
+    #[serde(skip)]
+    pub inactive_sources: FnvHashSet<SourceUid>,
 }
 
 /// The [`IndexingScheduler`] is responsible for listing indexing tasks and assigning them to
@@ -156,11 +159,42 @@ fn compute_load_per_shard(shard_entries: &[&ShardEntry]) -> NonZeroU32 {
     }
 }
 
-fn get_sources_to_schedule(model: &ControlPlaneModel) -> Vec<SourceToSchedule> {
+/// Sources running in backfill mode are scheduled with a lower priority than realtime sources:
+/// they are preempted when the indexers run out of capacity.
+fn source_priority(source_params: &SourceParams) -> SourcePriority {
+    let enable_backfill_mode = match source_params {
+        SourceParams::Kafka(kafka_params) => kafka_params.enable_backfill_mode,
+        SourceParams::Kinesis(kinesis_params) => kinesis_params.enable_backfill_mode,
+        SourceParams::PubSub(pubsub_params) => pubsub_params.enable_backfill_mode,
+        _ => false,
+    };
+    if enable_backfill_mode {
+        SourcePriority::Backfill
+    } else {
+        SourcePriority::Realtime
+    }
+}
+
+/// Returns the enabled sources that are currently outside of their activation windows.
+fn get_inactive_sources(model: &ControlPlaneModel) -> FnvHashSet<SourceUid> {
+    model
+        .source_configs()
+        .filter(|(_, source_config)| source_config.enabled && !source_config.is_active())
+        .map(|(source_uid, _)| source_uid)
+        .collect()
+}
+
+fn get_sources_to_schedule(
+    model: &ControlPlaneModel,
+    inactive_sources: &FnvHashSet<SourceUid>,
+) -> Vec<SourceToSchedule> {
     let mut sources = Vec::new();
 
     for (source_uid, source_config) in model.source_configs() {
-        if !source_config.enabled {
+        if !source_config.enabled
+            || inactive_sources.contains(&source_uid)
+            || model.is_ingestion_frozen(&source_uid.index_uid)
+        {
             continue;
         }
         let params_fingerprint = model
@@ -183,6 +217,7 @@ fn get_sources_to_schedule(model: &ControlPlaneModel) -> Vec<SourceToSchedule> {
                     source_uid,
                     source_type: SourceToScheduleType::IngestV1,
                     params_fingerprint,
+                    priority: SourcePriority::Realtime,
                 });
             }
             SourceParams::Ingest => {
@@ -209,6 +244,7 @@ fn get_sources_to_schedule(model: &ControlPlaneModel) -> Vec<SourceToSchedule> {
                         load_per_shard,
                     },
                     params_fingerprint,
+                    priority: SourcePriority::Realtime,
                 });
             }
             SourceParams::Kafka(_)
@@ -225,6 +261,7 @@ fn get_sources_to_schedule(model: &ControlPlaneModel) -> Vec<SourceToSchedule> {
                             .unwrap(),
                     },
                     params_fingerprint,
+                    priority: source_priority(&source_config.source_params),
                 });
             }
         }
@@ -257,7 +294,9 @@ impl IndexingScheduler {
 
         let notify_on_drop = self.next_rebuild_tracker.start_rebuild();
 
-        let sources = get_sources_to_schedule(model);
+        let inactive_sources = get_inactive_sources(model);
+        let sources = get_sources_to_schedule(model, &inactive_sources);
+        self.state.inactive_sources = inactive_sources;
 
         let indexers: Vec<IndexerNodeInfo> = self.get_indexers_from_indexer_pool();
 
@@ -305,6 +344,7 @@ impl IndexingScheduler {
 
     /// Checks if the last applied plan corresponds to the running indexing tasks present in the
     /// chitchat cluster state. If true, do nothing.
+    /// - If a source entered or left its activation windows, schedule a new indexing plan.
     /// - If node IDs differ, schedule a new indexing plan.
     /// - If indexing tasks differ, apply again the last plan.
     pub(crate) fn control_running_plan(&mut self, model: &ControlPlaneModel) {
@@ -318,6 +358,11 @@ impl IndexingScheduler {
                 self.rebuild_plan(model);
                 return;
             };
+        if get_inactive_sources(model) != self.state.inactive_sources {
+            info!("sources entered or left their activation windows: schedule an indexing plan");
+            self.rebuild_plan(model);
+            return;
+        }
         if let Some(last_applied_plan_timestamp) = self.state.last_applied_plan_timestamp {
             if Instant::now().duration_since(last_applied_plan_timestamp)
                 < MIN_DURATION_BETWEEN_SCHEDULING
@@ -660,7 +705,10 @@ mod tests {
     use std::str::FromStr;
 
     use proptest::{prop_compose, proptest};
-    use quickwit_config::{IndexConfig, KafkaSourceParams, SourceConfig, SourceParams};
+    use quickwit_config::{
+        IndexConfig, IngestionFreeze, KafkaSourceParams, SourceActivationWindow, SourceConfig,
+        SourceParams,
+    };
     use quickwit_metastore::IndexMetadata;
     use quickwit_proto::types::{IndexUid, PipelineUid, ShardId, SourceUid};
 
@@ -850,6 +898,8 @@ mod tests {
                     source_params: SourceParams::Kafka(kafka_source_params.clone()),
                     transform_config: None,
                     input_format: Default::default(),
+                    activation_windows: Vec::new(),
+                    backfill_time_range: None,
                 },
             )
             .unwrap();
@@ -863,6 +913,8 @@ mod tests {
                     source_params: SourceParams::Kafka(kafka_source_params.clone()),
                     transform_config: None,
                     input_format: Default::default(),
+                    activation_windows: Vec::new(),
+                    backfill_time_range: None,
                 },
             )
             .unwrap();
@@ -877,6 +929,8 @@ mod tests {
                     source_params: SourceParams::IngestApi,
                     transform_config: None,
                     input_format: Default::default(),
+                    activation_windows: Vec::new(),
+                    backfill_time_range: None,
                 },
             )
             .unwrap();
@@ -891,6 +945,8 @@ mod tests {
                     source_params: SourceParams::Ingest,
                     transform_config: None,
                     input_format: Default::default(),
+                    activation_windows: Vec::new(),
+                    backfill_time_range: None,
                 },
             )
             .unwrap();
@@ -906,6 +962,8 @@ mod tests {
                     source_params: SourceParams::Ingest,
                     transform_config: None,
                     input_format: Default::default(),
+                    activation_windows: Vec::new(),
+                    backfill_time_range: None,
                 },
             )
             .unwrap();
@@ -920,6 +978,8 @@ mod tests {
                     source_params: SourceParams::IngestCli,
                     transform_config: None,
                     input_format: Default::default(),
+                    activation_windows: Vec::new(),
+                    backfill_time_range: None,
                 },
             )
             .unwrap();
@@ -931,10 +991,102 @@ mod tests {
             ..Default::default()
         };
         model.insert_shards(&index_uid, &"ingest_v2".to_string(), vec![shard]);
-        let shards: Vec<SourceToSchedule> = get_sources_to_schedule(&model);
+        let shards: Vec<SourceToSchedule> = get_sources_to_schedule(&model, &FnvHashSet::default());
         assert_eq!(shards.len(), 3);
     }
 
+    #[test]
+    fn test_get_sources_to_schedule_with_activation_windows() {
+        let mut model = ControlPlaneModel::default();
+        let index_metadata = IndexMetadata::for_test("test-index", "ram:///test-index");
+        let index_uid = index_metadata.index_uid.clone();
+        model.add_index(index_metadata);
+
+        let kafka_source_params = SourceParams::Kafka(KafkaSourceParams {
+            topic: "kafka-topic".to_string(),
+            client_log_level: None,
+            client_params: serde_json::json!({}),
+            enable_backfill_mode: false,
+        });
+        let mut active_source_config =
+            SourceConfig::for_test("active-source", kafka_source_params.clone());
+        active_source_config.activation_windows = vec![SourceActivationWindow {
+            schedule: "* * * * * *".to_string(),
+            duration: "1h".to_string(),
+        }];
+        model.add_source(&index_uid, active_source_config).unwrap();
+
+        let mut inactive_source_config =
+            SourceConfig::for_test("inactive-source", kafka_source_params);
+        inactive_source_config.activation_windows = vec![SourceActivationWindow {
+            schedule: "0 0 0 1 1 * 2000".to_string(),
+            duration: "1h".to_string(),
+        }];
+        model
+            .add_source(&index_uid, inactive_source_config)
+            .unwrap();
+
+        let inactive_sources = get_inactive_sources(&model);
+        let inactive_source_uid = SourceUid {
+            index_uid: index_uid.clone(),
+            source_id: "inactive-source".to_string(),
+        };
+        assert_eq!(
+            inactive_sources,
+            FnvHashSet::from_iter([inactive_source_uid])
+        );
+        let sources = get_sources_to_schedule(&model, &inactive_sources);
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].source_uid.source_id, "active-source");
+    }
+
+    #[test]
+    fn test_get_sources_to_schedule_skips_frozen_indexes() {
+        let mut model = ControlPlaneModel::default();
+        let mut index_metadata = IndexMetadata::for_test("test-index", "ram:///test-index");
+        index_metadata.index_config.ingestion_freeze_opt = Some(IngestionFreeze {
+            reason: "incident-1234".to_string(),
+            frozen_at: 1_700_000_000,
+        });
+        let index_uid = index_metadata.index_uid.clone();
+        model.add_index(index_metadata);
+
+        let kafka_source_params = SourceParams::Kafka(KafkaSourceParams {
+            topic: "kafka-topic".to_string(),
+            client_log_level: None,
+            client_params: serde_json::json!({}),
+            enable_backfill_mode: false,
+        });
+        let source_config = SourceConfig::for_test("kafka-source", kafka_source_params);
+        model.add_source(&index_uid, source_config).unwrap();
+
+        let sources = get_sources_to_schedule(&model, &FnvHashSet::default());
+        assert!(sources.is_empty());
+    }
+
+    #[test]
+    fn test_source_priority() {
+        let mut kafka_source_params = KafkaSourceParams {
+            topic: "kafka-topic".to_string(),
+            client_log_level: None,
+            client_params: serde_json::json!({}),
+            enable_backfill_mode: false,
+        };
+        assert_eq!(
+            source_priority(&SourceParams::Kafka(kafka_source_params.clone())),
+            SourcePriority::Realtime
+        );
+        kafka_source_params.enable_backfill_mode = true;
+        assert_eq!(
+            source_priority(&SourceParams::Kafka(kafka_source_params)),
+            SourcePriority::Backfill
+        );
+        assert_eq!(
+            source_priority(&SourceParams::Ingest),
+            SourcePriority::Realtime
+        );
+    }
+
     #[test]
     fn test_build_physical_indexing_plan_simple() {
         let source_1 = SourceUid {
@@ -953,6 +1105,7 @@ mod tests {
                     load_per_pipeline: NonZeroU32::new(1_000).unwrap(),
                 },
                 params_fingerprint: 0,
+                priority: SourcePriority::Realtime,
             },
             SourceToSchedule {
                 source_uid: source_2.clone(),
@@ -961,6 +1114,7 @@ mod tests {
                     load_per_pipeline: NonZeroU32::new(1_000).unwrap(),
                 },
                 params_fingerprint: 0,
+                priority: SourcePriority::Realtime,
             },
         ];
         let mut indexer_max_loads = FnvHashMap::default();
@@ -1037,7 +1191,8 @@ mod tests {
                 model.add_source(index_uid, source_config.clone()).unwrap();
             }
 
-            let sources: Vec<SourceToSchedule> = get_sources_to_schedule(&model);
+            let sources: Vec<SourceToSchedule> =
+                get_sources_to_schedule(&model, &FnvHashSet::default());
             let mut indexer_max_loads = FnvHashMap::default();
             for i in 0..num_indexers {
                 let indexer_id = format!("indexer-{i}");
@@ -1075,6 +1230,8 @@ mod tests {
               source_params: kafka_source_params_for_test(),
               transform_config: None,
               input_format: SourceInputFormat::Json,
+              activation_windows: Vec::new(),
+              backfill_time_range: None,
           })
       }
     }
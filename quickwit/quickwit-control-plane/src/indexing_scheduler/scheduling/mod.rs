@@ -22,6 +22,7 @@ use fnv::{FnvHashMap, FnvHashSet};
 use quickwit_common::rate_limited_debug;
 use quickwit_proto::indexing::{CpuCapacity, IndexingTask};
 use quickwit_proto::types::{PipelineUid, ShardId, SourceUid};
+pub use scheduling_logic_model::SourcePriority;
 use scheduling_logic_model::{IndexerOrd, SourceOrd};
 use tracing::{error, warn};
 
@@ -68,7 +69,11 @@ fn populate_problem(
             num_pipelines,
             load_per_pipeline,
         } => {
-            let source_ord = problem.add_source(*num_pipelines, *load_per_pipeline);
+            let source_ord = problem.add_source_with_priority(
+                *num_pipelines,
+                *load_per_pipeline,
+                source.priority,
+            );
             Some(source_ord)
         }
     }
@@ -155,6 +160,8 @@ pub struct SourceToSchedule {
     pub source_uid: SourceUid,
     pub source_type: SourceToScheduleType,
     pub params_fingerprint: u64,
+    /// Only non-sharded sources can have the backfill priority.
+    pub priority: SourcePriority,
 }
 
 #[derive(Debug)]
@@ -578,12 +585,16 @@ fn add_shard_to_indexer(
 
 // If the total node capacities is lower than 110% of the problem load, this
 // function scales the load of the indexer to reach this limit.
+//
+// The load of backfill sources is only accounted for up to the actual node capacities:
+// beyond that point, backfill sources are preempted instead.
 fn inflate_node_capacities_if_necessary(problem: &mut SchedulingProblem) {
     // First we scale the problem to the point where any indexer can fit the largest shard.
     let Some(largest_shard_load) = problem.sources().map(|source| source.load_per_shard).max()
     else {
         return;
     };
+    let actual_total_node_capacities: f32 = problem.total_node_capacities().cpu_millis() as f32;
 
     // We first artificially scale down the node capacities.
     //
@@ -613,7 +624,10 @@ fn inflate_node_capacities_if_necessary(problem: &mut SchedulingProblem) {
 
     let total_node_capacities: f32 = problem.total_node_capacities().cpu_millis() as f32;
     let total_load: f32 = problem.total_load() as f32;
-    let inflated_total_load = total_load * 1.2f32;
+    let realtime_load: f32 = problem.total_load_with_priority(SourcePriority::Realtime) as f32;
+    let inflated_total_load = (total_load * 1.2f32)
+        .min(actual_total_node_capacities)
+        .max(realtime_load * 1.2f32);
     if inflated_total_load >= total_node_capacities {
         // We need to inflate our node capacities to match the problem.
         let ratio = inflated_total_load / total_node_capacities;
@@ -683,11 +697,12 @@ pub fn build_physical_indexing_plan(
 }
 
 /// Makes any checks on the sources.
-/// Sharded sources are not allowed to have no shards.
+/// Sharded sources are not allowed to have no shards or to have the backfill priority.
 fn check_sources(sources: &[SourceToSchedule]) {
     for source in sources {
         if let SourceToScheduleType::Sharded { shard_ids, .. } = &source.source_type {
-            assert!(!shard_ids.is_empty())
+            assert!(!shard_ids.is_empty());
+            assert_eq!(source.priority, SourcePriority::Realtime);
         }
     }
 }
@@ -751,8 +766,8 @@ mod tests {
 
     use super::{
         build_physical_indexing_plan,
-        convert_scheduling_solution_to_physical_plan_single_node_single_source, SourceToSchedule,
-        SourceToScheduleType,
+        convert_scheduling_solution_to_physical_plan_single_node_single_source, SourcePriority,
+        SourceToSchedule, SourceToScheduleType,
     };
     use crate::indexing_plan::PhysicalIndexingPlan;
     use crate::indexing_scheduler::get_shard_locality_metrics;
@@ -792,6 +807,7 @@ mod tests {
                 load_per_shard: NonZeroU32::new(1_000).unwrap(),
             },
             params_fingerprint: 0,
+            priority: SourcePriority::Realtime,
         };
         let source_1 = SourceToSchedule {
             source_uid: source_uid1.clone(),
@@ -800,11 +816,13 @@ mod tests {
                 load_per_pipeline: NonZeroU32::new(3_200).unwrap(),
             },
             params_fingerprint: 0,
+            priority: SourcePriority::Realtime,
         };
         let source_2 = SourceToSchedule {
             source_uid: source_uid2.clone(),
             source_type: SourceToScheduleType::IngestV1,
             params_fingerprint: 0,
+            priority: SourcePriority::Realtime,
         };
         let mut indexer_id_to_cpu_capacities = FnvHashMap::default();
         indexer_id_to_cpu_capacities.insert(indexer1.clone(), mcpu(16_000));
@@ -866,6 +884,7 @@ mod tests {
                     load_per_shard: NonZeroU32::new(250).unwrap(),
                 },
                 params_fingerprint: 0,
+                priority: SourcePriority::Realtime,
             })
             .collect();
 
@@ -906,6 +925,7 @@ mod tests {
                 load_per_pipeline: NonZeroU32::new(1000).unwrap(),
             },
             params_fingerprint: 0,
+            priority: SourcePriority::Realtime,
         };
         let sources = vec![source_1];
 
@@ -983,6 +1003,7 @@ mod tests {
                 load_per_shard: NonZeroU32::new(1_000).unwrap(),
             },
             params_fingerprint: 0,
+            priority: SourcePriority::Realtime,
         }];
         let mut indexer_id_to_cpu_capacities = FnvHashMap::default();
         indexer_id_to_cpu_capacities.insert("node1".to_string(), mcpu(10_000));
@@ -1023,6 +1044,7 @@ mod tests {
                 load_per_shard: NonZeroU32::new(load_per_shard.cpu_millis()).unwrap(),
             },
             params_fingerprint: 0,
+            priority: SourcePriority::Realtime,
         }];
         const NODE: &str = "node1";
         let mut indexer_id_to_cpu_capacities = FnvHashMap::default();
@@ -1202,6 +1224,7 @@ mod tests {
                 },
                 source_type: SourceToScheduleType::IngestV1,
                 params_fingerprint: 0,
+                priority: SourcePriority::Realtime,
             },
             SourceToSchedule {
                 source_uid: SourceUid {
@@ -1216,6 +1239,7 @@ mod tests {
                     load_per_shard: NonZeroU32::new(250).unwrap(),
                 },
                 params_fingerprint: 0,
+                priority: SourcePriority::Realtime,
             },
         ];
         let mut capacities = FnvHashMap::default();
@@ -1263,6 +1287,7 @@ mod tests {
                     load_per_shard: NonZeroU32::new(1_000).unwrap(),
                 },
                 params_fingerprint: 0,
+                priority: SourcePriority::Realtime,
             };
             let tasks = convert_scheduling_solution_to_physical_plan_single_node_single_source(
                 4,
@@ -1289,6 +1314,7 @@ mod tests {
                     load_per_shard: NonZeroU32::new(250).unwrap(),
                 },
                 params_fingerprint: 0,
+                priority: SourcePriority::Realtime,
             };
             let tasks = convert_scheduling_solution_to_physical_plan_single_node_single_source(
                 4,
@@ -1331,6 +1357,7 @@ mod tests {
                     load_per_pipeline: NonZeroU32::new(4000).unwrap(),
                 },
                 params_fingerprint: 0,
+                priority: SourcePriority::Realtime,
             };
             let tasks = convert_scheduling_solution_to_physical_plan_single_node_single_source(
                 1,
@@ -1350,6 +1377,7 @@ mod tests {
                     load_per_pipeline: NonZeroU32::new(1_000).unwrap(),
                 },
                 params_fingerprint: 0,
+                priority: SourcePriority::Realtime,
             };
             let tasks = convert_scheduling_solution_to_physical_plan_single_node_single_source(
                 0,
@@ -1366,6 +1394,7 @@ mod tests {
                     load_per_pipeline: NonZeroU32::new(1_000).unwrap(),
                 },
                 params_fingerprint: 0,
+                priority: SourcePriority::Realtime,
             };
             let tasks = convert_scheduling_solution_to_physical_plan_single_node_single_source(
                 2,
@@ -1388,6 +1417,7 @@ mod tests {
                     load_per_pipeline: NonZeroU32::new(1_000).unwrap(),
                 },
                 params_fingerprint: 0,
+                priority: SourcePriority::Realtime,
             };
             let tasks = convert_scheduling_solution_to_physical_plan_single_node_single_source(
                 2,
@@ -58,15 +58,23 @@ pub fn solve(
     // Again, we shave off some shards to make sure they are
     // within their capacity.
     enforce_indexers_cpu_capacity(&problem, &mut solution);
+    // Backfill sources only get the capacity left over by realtime sources.
+    // We set their shards aside while we place the realtime sources.
+    let backfill_solution = take_backfill_shards(&problem, &mut solution);
     // The solution now meets the constraint, but it does not necessarily
     // contains all of the shards that we need to assign.
     //
     // We first assign sources to indexers that have some affinity with them
     // (provided they have the capacity.)
-    place_unassigned_shards_with_affinity(&problem, &mut solution);
+    place_unassigned_shards_with_affinity(&problem, &mut solution, SourcePriority::Realtime);
     // Finally we assign the remaining shards, regardess of whether they have affinity
     // or not.
-    place_unassigned_shards_ignoring_affinity(problem, &solution)
+    let mut solution = place_unassigned_shards_ignoring_affinity(&mut problem, &solution);
+    // Backfill shards are put back where they still fit. The ones that do not fit are
+    // preempted and will be resumed from their checkpoint once some capacity is available.
+    restore_backfill_shards(&problem, &backfill_solution, &mut solution);
+    place_backfill_shards(&problem, &mut solution);
+    solution
 }
 
 // -------------------------------------------------------------------------
@@ -176,16 +184,22 @@ fn enforce_indexer_cpu_capacity(
     }
     let mut load_to_remove: CpuCapacity =
         CpuCapacity::from_cpu_millis(total_load) - indexer_cpu_capacity;
-    let mut source_cpu_capacities: Vec<(CpuCapacity, SourceOrd)> = indexer_assignment
-        .num_shards_per_source
-        .iter()
-        .map(|(&source_ord, num_shards)| {
-            let load_for_source = problem.source_load_per_shard(source_ord).get() * num_shards;
-            (CpuCapacity::from_cpu_millis(load_for_source), source_ord)
-        })
-        .collect();
+    // We remove backfill sources first.
+    let mut source_cpu_capacities: Vec<(SourcePriority, CpuCapacity, SourceOrd)> =
+        indexer_assignment
+            .num_shards_per_source
+            .iter()
+            .map(|(&source_ord, num_shards)| {
+                let load_for_source = problem.source_load_per_shard(source_ord).get() * num_shards;
+                (
+                    problem.source_priority(source_ord),
+                    CpuCapacity::from_cpu_millis(load_for_source),
+                    source_ord,
+                )
+            })
+            .collect();
     source_cpu_capacities.sort();
-    for (source_cpu_capacity, source_ord) in source_cpu_capacities {
+    for (_priority, source_cpu_capacity, source_ord) in source_cpu_capacities {
         indexer_assignment.num_shards_per_source.remove(&source_ord);
         load_to_remove = if load_to_remove <= source_cpu_capacity {
             break;
@@ -232,8 +246,12 @@ fn attempt_place_unassigned_shards(
 fn place_unassigned_shards_with_affinity(
     problem: &SchedulingProblem,
     solution: &mut SchedulingSolution,
+    priority: SourcePriority,
 ) {
-    let mut unassigned_shards: Vec<Source> = compute_unassigned_sources(problem, solution);
+    let mut unassigned_shards: Vec<Source> = compute_unassigned_sources(problem, solution)
+        .into_iter()
+        .filter(|source| source.priority == priority)
+        .collect();
     unassigned_shards.sort_by_key(|source| {
         let load = source.num_shards * source.load_per_shard.get();
         Reverse(load)
@@ -283,12 +301,17 @@ fn place_unassigned_shards_with_affinity(
 //
 // If this algorithm fails to place all remaining shards, we inflate
 // the node capacities by 20% in the scheduling problem and start from the beginning.
+//
+// Only realtime sources are placed in this phase.
 #[must_use]
 fn place_unassigned_shards_ignoring_affinity(
-    mut problem: SchedulingProblem,
+    problem: &mut SchedulingProblem,
     partial_solution: &SchedulingSolution,
 ) -> SchedulingSolution {
-    let mut unassigned_shards: Vec<Source> = compute_unassigned_sources(&problem, partial_solution);
+    let mut unassigned_shards: Vec<Source> = compute_unassigned_sources(problem, partial_solution)
+        .into_iter()
+        .filter(|source| source.priority == SourcePriority::Realtime)
+        .collect();
     unassigned_shards.sort_by_key(|source| {
         let load = source.num_shards * source.load_per_shard.get();
         Reverse(load)
@@ -301,7 +324,7 @@ fn place_unassigned_shards_ignoring_affinity(
     // 1.2^30 is about 240.
     // If we reach 30 attempts we are certain to have a logical bug.
     for attempt_number in 0..30 {
-        match attempt_place_unassigned_shards(&unassigned_shards[..], &problem, partial_solution) {
+        match attempt_place_unassigned_shards(&unassigned_shards[..], problem, partial_solution) {
             Ok(solution) => {
                 if attempt_number != 0 {
                     warn!(
@@ -323,14 +346,18 @@ fn assert_place_unassigned_shards_post_condition(
     problem: &SchedulingProblem,
     solution: &SchedulingSolution,
 ) {
-    // We make sure we all shard are as placed.
+    // We make sure we all shard are as placed. Backfill sources may be preempted.
     for source in problem.sources() {
         let num_assigned_shards: u32 = solution
             .indexer_assignments
             .iter()
             .map(|indexer_assignment| indexer_assignment.num_shards(source.source_ord))
             .sum();
-        assert_eq!(num_assigned_shards, source.num_shards);
+        if source.priority == SourcePriority::Realtime {
+            assert_eq!(num_assigned_shards, source.num_shards);
+        } else {
+            assert!(num_assigned_shards <= source.num_shards);
+        }
     }
     // We make sure that the node capacity is respected.
     for indexer_assignment in &solution.indexer_assignments {
@@ -340,6 +367,92 @@ fn assert_place_unassigned_shards_post_condition(
     }
 }
 
+// ----------------------------------------------------
+// Phase 4
+// Place backfill sources.
+//
+// Backfill sources are placed on the capacity left over by realtime sources.
+// We first put back the backfill shards that were previously assigned, as long as they
+// fit on their indexer. We then place the remaining backfill shards, first on the indexers
+// with which they have some affinity, then on the indexers with the highest available capacity.
+//
+// Contrary to realtime sources, we never inflate the node capacities to place backfill shards:
+// the shards that do not fit are simply left unassigned. Their pipelines are shut down and
+// resumed from their checkpoint in a later scheduling round.
+
+/// Removes the shards of backfill sources from the solution and returns them.
+fn take_backfill_shards(
+    problem: &SchedulingProblem,
+    solution: &mut SchedulingSolution,
+) -> SchedulingSolution {
+    let mut backfill_solution = SchedulingSolution::with_num_indexers(solution.num_indexers());
+    for (indexer_assignment, backfill_indexer_assignment) in solution
+        .indexer_assignments
+        .iter_mut()
+        .zip(&mut backfill_solution.indexer_assignments)
+    {
+        indexer_assignment
+            .num_shards_per_source
+            .retain(|&source_ord, &mut num_shards| {
+                if problem.source_priority(source_ord) == SourcePriority::Realtime {
+                    return true;
+                }
+                backfill_indexer_assignment.add_shards(source_ord, num_shards);
+                false
+            });
+    }
+    backfill_solution
+}
+
+fn restore_backfill_shards(
+    problem: &SchedulingProblem,
+    backfill_solution: &SchedulingSolution,
+    solution: &mut SchedulingSolution,
+) {
+    for (indexer_assignment, backfill_indexer_assignment) in solution
+        .indexer_assignments
+        .iter_mut()
+        .zip(&backfill_solution.indexer_assignments)
+    {
+        for (&source_ord, &num_shards) in &backfill_indexer_assignment.num_shards_per_source {
+            let available_capacity = indexer_assignment.indexer_available_capacity(problem);
+            let num_placable_shards =
+                available_capacity.max(0) as u32 / problem.source_load_per_shard(source_ord);
+            let num_shards_to_place = num_placable_shards.min(num_shards);
+
+            if num_shards_to_place > 0 {
+                indexer_assignment.add_shards(source_ord, num_shards_to_place);
+            }
+        }
+    }
+}
+
+fn place_backfill_shards(problem: &SchedulingProblem, solution: &mut SchedulingSolution) {
+    place_unassigned_shards_with_affinity(problem, solution, SourcePriority::Backfill);
+
+    let mut unassigned_shards: Vec<Source> = compute_unassigned_sources(problem, solution)
+        .into_iter()
+        .filter(|source| source.priority == SourcePriority::Backfill)
+        .collect();
+    unassigned_shards.sort_by_key(|source| {
+        let load = source.num_shards * source.load_per_shard.get();
+        Reverse(load)
+    });
+    for source in &unassigned_shards {
+        let indexers_with_most_available_capacity =
+            compute_indexer_available_capacity(problem, solution)
+                .sorted_by_key(|(indexer_ord, capacity)| Reverse((*capacity, *indexer_ord)));
+        // Failing to place all of the shards of a backfill source is fine: the remaining shards
+        // are preempted.
+        let _ = place_unassigned_shards_single_source(
+            source,
+            indexers_with_most_available_capacity,
+            solution,
+        );
+    }
+    assert_place_unassigned_shards_post_condition(problem, solution);
+}
+
 struct NotEnoughCapacity;
 
 /// Return Err(NotEnoughCapacity) iff the algorithm was unable to pack all of the sources
@@ -542,6 +655,7 @@ mod tests {
                 load_per_shard: NonZeroU32::new(1_000).unwrap(),
                 num_shards: 4,
                 affinities: BTreeMap::default(),
+                priority: SourcePriority::Realtime,
             }
         );
     }
@@ -566,6 +680,7 @@ mod tests {
                 load_per_shard: NonZeroU32::new(1_000).unwrap(),
                 num_shards: 5 - (1 + 2),
                 affinities: Default::default(),
+                priority: SourcePriority::Realtime,
             }
         );
         assert_eq!(
@@ -575,6 +690,7 @@ mod tests {
                 load_per_shard: NonZeroU32::new(2_000).unwrap(),
                 num_shards: 15 - (3 + 3),
                 affinities: Default::default(),
+                priority: SourcePriority::Realtime,
             }
         );
     }
@@ -584,7 +700,7 @@ mod tests {
         let mut problem = SchedulingProblem::with_indexer_cpu_capacities(vec![mcpu(4_000)]);
         problem.add_source(4, NonZeroU32::new(1_000).unwrap());
         let partial_solution = problem.new_solution();
-        let solution = place_unassigned_shards_ignoring_affinity(problem, &partial_solution);
+        let solution = place_unassigned_shards_ignoring_affinity(&mut problem, &partial_solution);
         assert_eq!(solution.indexer_assignments[0].num_shards(0), 4);
     }
 
@@ -597,7 +713,7 @@ mod tests {
         problem.inc_affinity(0, 1);
         problem.inc_affinity(1, 0);
         let mut solution = problem.new_solution();
-        place_unassigned_shards_with_affinity(&problem, &mut solution);
+        place_unassigned_shards_with_affinity(&problem, &mut solution, SourcePriority::Realtime);
         assert_eq!(solution.indexer_assignments[0].num_shards(1), 4);
         assert_eq!(solution.indexer_assignments[1].num_shards(0), 4);
     }
@@ -625,6 +741,7 @@ mod tests {
                 load_per_shard: NonZeroU32::new(1_000).unwrap(),
                 num_shards: 5 - (1 + 2),
                 affinities: Default::default(),
+                priority: SourcePriority::Realtime,
             }
         );
         assert_eq!(
@@ -634,6 +751,7 @@ mod tests {
                 load_per_shard: NonZeroU32::new(2_000).unwrap(),
                 num_shards: 15 - (3 + 3),
                 affinities: Default::default(),
+                priority: SourcePriority::Realtime,
             }
         );
     }
@@ -647,6 +765,55 @@ mod tests {
         solve(problem, previous_solution);
     }
 
+    #[test]
+    fn test_solve_preempts_backfill_sources() {
+        let mut problem = SchedulingProblem::with_indexer_cpu_capacities(vec![mcpu(4_000)]);
+        problem.add_source_with_priority(
+            1,
+            NonZeroU32::new(4_000).unwrap(),
+            SourcePriority::Backfill,
+        );
+        problem.add_source(3, NonZeroU32::new(1_000).unwrap());
+        let mut previous_solution = problem.new_solution();
+        previous_solution.indexer_assignments[0].add_shards(0, 1);
+        let solution = solve(problem, previous_solution);
+        assert_eq!(solution.indexer_assignments[0].num_shards(0), 0);
+        assert_eq!(solution.indexer_assignments[0].num_shards(1), 3);
+    }
+
+    #[test]
+    fn test_solve_resumes_backfill_sources() {
+        let mut problem = SchedulingProblem::with_indexer_cpu_capacities(vec![mcpu(4_000)]);
+        problem.add_source_with_priority(
+            1,
+            NonZeroU32::new(4_000).unwrap(),
+            SourcePriority::Backfill,
+        );
+        problem.add_source(0, NonZeroU32::new(1_000).unwrap());
+        let mut previous_solution = problem.new_solution();
+        previous_solution.indexer_assignments[0].add_shards(1, 3);
+        let solution = solve(problem, previous_solution);
+        assert_eq!(solution.indexer_assignments[0].num_shards(0), 1);
+        assert_eq!(solution.indexer_assignments[0].num_shards(1), 0);
+    }
+
+    #[test]
+    fn test_enforce_nodes_cpu_capacity_removes_backfill_sources_first() {
+        let mut problem = SchedulingProblem::with_indexer_cpu_capacities(vec![mcpu(5_000)]);
+        problem.add_source(1, NonZeroU32::new(1_000).unwrap());
+        problem.add_source_with_priority(
+            1,
+            NonZeroU32::new(4_500).unwrap(),
+            SourcePriority::Backfill,
+        );
+        let mut solution = problem.new_solution();
+        solution.indexer_assignments[0].add_shards(0, 1);
+        solution.indexer_assignments[0].add_shards(1, 1);
+        enforce_indexers_cpu_capacity(&problem, &mut solution);
+        assert_eq!(solution.indexer_assignments[0].num_shards(0), 1);
+        assert_eq!(solution.indexer_assignments[0].num_shards(1), 0);
+    }
+
     fn indexer_cpu_capacity_strat() -> impl Strategy<Value = CpuCapacity> {
         prop_oneof![
             1u32..10_000u32,
@@ -661,7 +828,14 @@ mod tests {
         0u32..3u32
     }
 
-    fn source_strat() -> impl Strategy<Value = (u32, NonZeroU32)> {
+    fn source_priority_strat() -> impl Strategy<Value = SourcePriority> {
+        prop_oneof![
+            3 => Just(SourcePriority::Realtime),
+            1 => Just(SourcePriority::Backfill),
+        ]
+    }
+
+    fn source_strat() -> impl Strategy<Value = (u32, NonZeroU32, SourcePriority)> {
         let load_strat = prop_oneof![
             Just(1u32),
             Just(2u32),
@@ -673,6 +847,7 @@ mod tests {
         (
             num_shards(),
             load_strat.prop_map(|load| NonZeroU32::new(load).unwrap()),
+            source_priority_strat(),
         )
     }
 
@@ -685,8 +860,8 @@ mod tests {
         let sources_strat = proptest::collection::vec(source_strat(), num_sources);
         (indexer_cpu_capacity_strat, sources_strat).prop_map(|(node_cpu_capacities, sources)| {
             let mut problem = SchedulingProblem::with_indexer_cpu_capacities(node_cpu_capacities);
-            for (num_shards, load_per_shard) in sources {
-                problem.add_source(num_shards, load_per_shard);
+            for (num_shards, load_per_shard, priority) in sources {
+                problem.add_source_with_priority(num_shards, load_per_shard, priority);
             }
             problem
         })
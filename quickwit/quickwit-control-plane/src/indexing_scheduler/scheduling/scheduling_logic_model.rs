@@ -21,6 +21,18 @@ use quickwit_proto::indexing::CpuCapacity;
 pub type SourceOrd = u32;
 pub type IndexerOrd = usize;
 
+/// Priority of a source in the scheduling problem.
+///
+/// Realtime sources are always scheduled, if necessary by inflating the indexer capacities.
+/// Backfill sources are only scheduled on the capacity left over by realtime sources and are
+/// preempted when indexers run out of capacity.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum SourcePriority {
+    Backfill,
+    #[default]
+    Realtime,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Source {
     pub source_ord: SourceOrd,
@@ -36,6 +48,7 @@ pub struct Source {
     /// and `affinity(source, indexer) <= num shard of source on indexer`
     pub affinities: BTreeMap<IndexerOrd, u32>,
     pub num_shards: u32,
+    pub priority: SourcePriority,
 }
 
 impl Source {
@@ -131,17 +144,36 @@ impl SchedulingProblem {
             .sum()
     }
 
+    /// Returns the total load of the sources with the given priority.
+    pub fn total_load_with_priority(&self, priority: SourcePriority) -> u32 {
+        self.sources
+            .iter()
+            .filter(|source| source.priority == priority)
+            .map(|source| source.num_shards * source.load_per_shard.get())
+            .sum()
+    }
+
     pub fn sources(&self) -> impl Iterator<Item = Source> + '_ {
         self.sources.iter().cloned()
     }
 
     pub fn add_source(&mut self, num_shards: u32, load_per_shard: NonZeroU32) -> SourceOrd {
+        self.add_source_with_priority(num_shards, load_per_shard, SourcePriority::Realtime)
+    }
+
+    pub fn add_source_with_priority(
+        &mut self,
+        num_shards: u32,
+        load_per_shard: NonZeroU32,
+        priority: SourcePriority,
+    ) -> SourceOrd {
         let source_ord = self.sources.len() as SourceOrd;
         self.sources.push(Source {
             source_ord,
             num_shards,
             load_per_shard,
             affinities: Default::default(),
+            priority,
         });
         source_ord
     }
@@ -160,6 +192,10 @@ impl SchedulingProblem {
         self.sources[source_ord as usize].load_per_shard
     }
 
+    pub fn source_priority(&self, source_ord: SourceOrd) -> SourcePriority {
+        self.sources[source_ord as usize].priority
+    }
+
     pub fn num_sources(&self) -> usize {
         self.sources.len()
     }
@@ -256,6 +292,7 @@ mod tests {
             load_per_shard: NonZeroU32::new(1000u32).unwrap(),
             affinities,
             num_shards: 2 + 3,
+            priority: SourcePriority::Realtime,
         }
     }
 
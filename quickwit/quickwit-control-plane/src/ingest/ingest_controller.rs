@@ -256,6 +256,9 @@ fn get_open_shard_from_model(
     let Some(index_uid) = model.index_uid(&get_open_shards_subrequest.index_id) else {
         return Err(GetOrCreateOpenShardsFailureReason::IndexNotFound);
     };
+    if model.is_ingestion_frozen(index_uid) {
+        return Err(GetOrCreateOpenShardsFailureReason::IndexFrozen);
+    }
     let Some(open_shard_entries) = model.find_open_shards(
         index_uid,
         &get_open_shards_subrequest.source_id,
@@ -1125,6 +1128,57 @@ impl IngestController {
         Ok(Some(tokio::spawn(close_shards_and_send_callback_fut)))
     }
 
+    /// Closes the open shards of the sources of an index, so that routers stop persisting
+    /// documents into them. This is used when the ingestion into the index is frozen.
+    pub(crate) async fn close_index_shards(
+        &self,
+        index_uid: &IndexUid,
+        model: &mut ControlPlaneModel,
+        progress: &Progress,
+    ) {
+        let Some(index_metadata) = model.index_metadata(index_uid) else {
+            return;
+        };
+        let mut shards_to_close = Vec::new();
+
+        for source_id in index_metadata.sources.keys() {
+            let source_uid = SourceUid {
+                index_uid: index_uid.clone(),
+                source_id: source_id.clone(),
+            };
+            let Some(shard_entries) = model.get_shards_for_source(&source_uid) else {
+                continue;
+            };
+            for shard_entry in shard_entries.values() {
+                if !shard_entry.is_open() {
+                    continue;
+                }
+                let leader_id = NodeId::from(shard_entry.leader_id.clone());
+                let shard_pkey = ShardPKey {
+                    index_uid: Some(index_uid.clone()),
+                    source_id: source_id.clone(),
+                    shard_id: shard_entry.shard_id.clone(),
+                };
+                shards_to_close.push((leader_id, shard_pkey));
+            }
+        }
+        if shards_to_close.is_empty() {
+            return;
+        }
+        let closed_shards = progress
+            .protect_future(self.close_shards(shards_to_close))
+            .await;
+
+        for closed_shard in closed_shards {
+            let shard_id = closed_shard.shard_id().clone();
+            let source_uid = SourceUid {
+                index_uid: closed_shard.index_uid().clone(),
+                source_id: closed_shard.source_id,
+            };
+            model.close_shards(&source_uid, &[shard_id]);
+        }
+    }
+
     fn close_shards(
         &self,
         shards_to_close: Vec<(LeaderId, ShardPKey)>,
@@ -1242,7 +1296,7 @@ mod tests {
     use quickwit_common::setup_logging_for_tests;
     use quickwit_common::shared_consts::DEFAULT_SHARD_THROUGHPUT_LIMIT;
     use quickwit_common::tower::DelayLayer;
-    use quickwit_config::{DocMapping, SourceConfig, INGEST_V2_SOURCE_ID};
+    use quickwit_config::{DocMapping, IngestionFreeze, SourceConfig, INGEST_V2_SOURCE_ID};
     use quickwit_ingest::{RateMibPerSec, ShardInfo};
     use quickwit_metastore::IndexMetadata;
     use quickwit_proto::control_plane::GetOrCreateOpenShardsSubrequest;
@@ -1569,6 +1623,71 @@ mod tests {
         assert!(!metastore_error.is_transaction_certainly_aborted());
     }
 
+    #[tokio::test]
+    async fn test_ingest_controller_get_or_create_open_shards_frozen_index() {
+        let metastore = MetastoreServiceClient::mocked();
+        let ingester_pool = IngesterPool::default();
+        let replication_factor = 2;
+
+        let mut controller = IngestController::new(
+            metastore,
+            ingester_pool,
+            replication_factor,
+            TEST_SHARD_THROUGHPUT_LIMIT_MIB,
+            1.001,
+        );
+        let mut index_metadata = IndexMetadata::for_test("test-index", "ram://indexes/test-index");
+        index_metadata.index_config.ingestion_freeze_opt = Some(IngestionFreeze {
+            reason: "incident-1234".to_string(),
+            frozen_at: 1_700_000_000,
+        });
+        let index_uid = index_metadata.index_uid.clone();
+
+        let mut model = ControlPlaneModel::default();
+        model.add_index(index_metadata);
+
+        let mut source_config = SourceConfig::ingest_v2();
+        source_config.source_id = "test-source".to_string();
+        model.add_source(&index_uid, source_config).unwrap();
+
+        let shards = vec![Shard {
+            index_uid: index_uid.clone().into(),
+            source_id: "test-source".to_string(),
+            shard_id: Some(ShardId::from(1)),
+            leader_id: "test-ingester-0".to_string(),
+            shard_state: ShardState::Open as i32,
+            ..Default::default()
+        }];
+        model.insert_shards(&index_uid, &"test-source".to_string(), shards);
+
+        let request = GetOrCreateOpenShardsRequest {
+            subrequests: vec![GetOrCreateOpenShardsSubrequest {
+                subrequest_id: 0,
+                index_id: "test-index".to_string(),
+                source_id: "test-source".to_string(),
+            }],
+            closed_shards: Vec::new(),
+            unavailable_leaders: Vec::new(),
+        };
+        let progress = Progress::default();
+
+        let response = controller
+            .get_or_create_open_shards(request, &mut model, &progress)
+            .await
+            .unwrap();
+
+        assert!(response.successes.is_empty());
+        assert_eq!(response.failures.len(), 1);
+
+        let failure = &response.failures[0];
+        assert_eq!(failure.subrequest_id, 0);
+        assert_eq!(failure.index_id, "test-index");
+        assert_eq!(
+            failure.reason(),
+            GetOrCreateOpenShardsFailureReason::IndexFrozen
+        );
+    }
+
     #[tokio::test]
     async fn test_ingest_controller_get_open_shards_handles_closed_shards() {
         let metastore = MetastoreServiceClient::mocked();
@@ -2891,7 +3010,7 @@ mod tests {
                     [ShardId::from(1), ShardId::from(3)]
                 );
                 count_calls_clone.fetch_add(1, Ordering::Release);
-                Ok(RetainShardsResponse {})
+                Ok(RetainShardsResponse::default())
             });
         ingester_pool.insert(
             "node-1".into(),
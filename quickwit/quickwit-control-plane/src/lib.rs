@@ -18,6 +18,8 @@ pub mod indexing_scheduler;
 pub mod ingest;
 pub(crate) mod metrics;
 pub(crate) mod model;
+pub(crate) mod rollout;
+pub(crate) mod split_cache_prewarming;
 
 use quickwit_common::tower::Pool;
 use quickwit_proto::indexing::{CpuCapacity, IndexingServiceClient, IndexingTask};
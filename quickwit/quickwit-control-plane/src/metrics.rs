@@ -14,7 +14,8 @@
 
 use once_cell::sync::Lazy;
 use quickwit_common::metrics::{
-    new_counter, new_gauge, new_gauge_vec, IntCounter, IntGauge, IntGaugeVec,
+    new_counter, new_counter_vec, new_gauge, new_gauge_vec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec,
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -33,6 +34,8 @@ pub struct ControlPlaneMetrics {
     pub open_shards_total: IntGaugeVec<1>,
     pub local_shards: IntGauge,
     pub remote_shards: IntGauge,
+    pub rollouts_total: IntCounterVec<1>,
+    pub prewarmed_splits_total: IntCounter,
 }
 
 impl ControlPlaneMetrics {
@@ -98,6 +101,19 @@ impl Default for ControlPlaneMetrics {
             ),
             local_shards,
             remote_shards,
+            rollouts_total: new_counter_vec(
+                "rollouts_total",
+                "Number of index rollouts completed by outcome (promoted/rolled_back).",
+                "control_plane",
+                &[],
+                ["outcome"],
+            ),
+            prewarmed_splits_total: new_counter(
+                "prewarmed_splits_total",
+                "Number of splits reported to the searcher split caches to prewarm them.",
+                "control_plane",
+                &[],
+            ),
         }
     }
 }
@@ -53,6 +53,9 @@ pub(crate) struct ControlPlaneModel {
     index_uid_table: FnvHashMap<IndexId, IndexUid>,
     index_table: FnvHashMap<IndexUid, IndexMetadata>,
     shard_table: ShardTable,
+    /// Shards of the indexes in the trash. They are no longer managed by the control plane, but
+    /// they are retained on the ingesters until the index is purged or restored.
+    trashed_shards: FnvHashMap<IndexUid, Vec<Shard>>,
 }
 
 impl ControlPlaneModel {
@@ -89,10 +92,19 @@ impl ControlPlaneModel {
         let now = Instant::now();
         self.clear();
 
-        let indexes_metadata = progress
-            .protect_future(metastore.list_indexes_metadata(ListIndexesMetadataRequest::all()))
-            .await?
-            .deserialize_indexes_metadata()
+        let (trashed_indexes_metadata, indexes_metadata): (Vec<IndexMetadata>, Vec<IndexMetadata>) =
+            progress
+                .protect_future(
+                    metastore
+                        .list_indexes_metadata(ListIndexesMetadataRequest::all_including_trashed()),
+                )
+                .await?
+                .deserialize_indexes_metadata()
+                .await?
+                .into_iter()
+                .partition(|index_metadata| index_metadata.is_trashed());
+
+        self.load_trashed_shards_from_metastore(trashed_indexes_metadata, metastore, progress)
             .await?;
 
         let num_indexes = indexes_metadata.len();
@@ -152,6 +164,47 @@ impl ControlPlaneModel {
         Ok(())
     }
 
+    /// Loads the shards of the indexes in the trash so that they are retained on the ingesters.
+    async fn load_trashed_shards_from_metastore(
+        &mut self,
+        trashed_indexes_metadata: Vec<IndexMetadata>,
+        metastore: &mut MetastoreServiceClient,
+        progress: &Progress,
+    ) -> ControlPlaneResult<()> {
+        let subrequests: Vec<ListShardsSubrequest> = trashed_indexes_metadata
+            .iter()
+            .flat_map(|index_metadata| {
+                index_metadata
+                    .sources
+                    .values()
+                    .filter(|source_config| source_config.source_type() == SourceType::IngestV2)
+                    .map(|source_config| ListShardsSubrequest {
+                        index_uid: index_metadata.index_uid.clone().into(),
+                        source_id: source_config.source_id.clone(),
+                        shard_state: None,
+                    })
+            })
+            .collect();
+        if subrequests.is_empty() {
+            return Ok(());
+        }
+        let list_shards_request = metastore::ListShardsRequest { subrequests };
+        let list_shards_response = progress
+            .protect_future(metastore.list_shards(list_shards_request))
+            .await?;
+
+        for list_shards_subresponse in list_shards_response.subresponses {
+            let index_uid = list_shards_subresponse
+                .index_uid
+                .expect("`index_uid` should be a required field");
+            self.trashed_shards
+                .entry(index_uid)
+                .or_default()
+                .extend(list_shards_subresponse.shards);
+        }
+        Ok(())
+    }
+
     pub fn index_uid(&self, index_id: &str) -> Option<&IndexUid> {
         self.index_uid_table.get(index_id)
     }
@@ -160,6 +213,13 @@ impl ControlPlaneModel {
         self.index_table.get(index_uid)
     }
 
+    /// Returns whether the ingestion into the index is frozen.
+    pub(crate) fn is_ingestion_frozen(&self, index_uid: &IndexUid) -> bool {
+        self.index_metadata(index_uid)
+            .map(|index_metadata| index_metadata.index_config.ingestion_freeze_opt.is_some())
+            .unwrap_or(false)
+    }
+
     pub fn source_metadata(&self, source_uid: &SourceUid) -> Option<&SourceConfig> {
         self.index_metadata(&source_uid.index_uid)?
             .sources
@@ -172,6 +232,21 @@ impl ControlPlaneModel {
             .set(self.index_table.len() as i64);
     }
 
+    pub(crate) fn index_metadatas(&self) -> impl Iterator<Item = &IndexMetadata> + '_ {
+        self.index_table.values()
+    }
+
+    /// Returns the metadata of the indexes which are the candidate of an ongoing rollout.
+    pub(crate) fn rollout_candidates(&self) -> impl Iterator<Item = &IndexMetadata> + '_ {
+        self.index_table.values().filter(|index_metadata| {
+            index_metadata
+                .index_config
+                .rollout_opt
+                .as_ref()
+                .is_some_and(|rollout| rollout.is_evaluating())
+        })
+    }
+
     pub(crate) fn source_configs(&self) -> impl Iterator<Item = (SourceUid, &SourceConfig)> + '_ {
         self.index_table.values().flat_map(|index_metadata| {
             index_metadata
@@ -224,9 +299,59 @@ impl ControlPlaneModel {
         self.index_table.remove(index_uid);
         self.index_uid_table.remove(&index_uid.index_id);
         self.shard_table.delete_index(&index_uid.index_id);
+        self.trashed_shards.remove(index_uid);
         self.update_metrics();
     }
 
+    /// Removes an index moved to the trash from the model. Its shards are set aside so that the
+    /// ingesters keep them until the index is purged or restored.
+    pub(crate) fn trash_index(&mut self, index_uid: &IndexUid) {
+        let shards: Vec<Shard> = self
+            .list_shards_for_index(index_uid)
+            .map(|shard_entry| shard_entry.shard.clone())
+            .collect();
+        self.delete_index(index_uid);
+
+        if !shards.is_empty() {
+            self.trashed_shards.insert(index_uid.clone(), shards);
+        }
+    }
+
+    /// Adds an index restored from the trash back to the model, along with its shards.
+    pub(crate) fn undelete_index(&mut self, index_metadata: IndexMetadata) {
+        let index_uid = index_metadata.index_uid.clone();
+        self.add_index(index_metadata);
+
+        let Some(shards) = self.trashed_shards.remove(&index_uid) else {
+            return;
+        };
+        let mut per_source_shards: FnvHashMap<SourceId, Vec<Shard>> = FnvHashMap::default();
+
+        for shard in shards {
+            per_source_shards
+                .entry(shard.source_id.clone())
+                .or_default()
+                .push(shard);
+        }
+        for (source_id, shards) in per_source_shards {
+            self.shard_table
+                .insert_shards(&index_uid, &source_id, shards);
+        }
+    }
+
+    /// Lists the ingesters hosting the shards of an index, including the shards set aside while
+    /// the index is in the trash.
+    pub(crate) fn list_ingesters_for_index(&self, index_uid: &IndexUid) -> BTreeSet<NodeId> {
+        let trashed_shards = self.trashed_shards.get(index_uid).into_iter().flatten();
+
+        self.list_shards_for_index(index_uid)
+            .map(|shard_entry| &shard_entry.shard)
+            .chain(trashed_shards)
+            .flat_map(|shard| shard.ingesters())
+            .map(|node_id_ref| node_id_ref.to_owned())
+            .collect()
+    }
+
     /// Adds a source to a given index. Returns an error if the source already
     /// exists.
     pub(crate) fn add_source(
@@ -314,15 +439,36 @@ impl ControlPlaneModel {
         self.shard_table.all_shards_with_source()
     }
 
+    /// Lists the shards hosted on a given node, including the shards of the indexes in the trash.
     pub fn list_shards_for_node(
         &self,
         ingester: &NodeId,
     ) -> impl Deref<Target = FnvHashMap<SourceUid, BTreeSet<ShardId>>> + '_ {
-        if let Some(shards_for_node) = self.shard_table.list_shards_for_node(ingester) {
-            Cow::Borrowed(shards_for_node)
-        } else {
-            Cow::Owned(FnvHashMap::default())
+        let shards_for_node_opt = self.shard_table.list_shards_for_node(ingester);
+
+        let mut trashed_shards_for_node = self
+            .trashed_shards
+            .values()
+            .flatten()
+            .filter(|shard| shard.ingesters().any(|node_id| node_id == ingester))
+            .peekable();
+
+        if trashed_shards_for_node.peek().is_none() {
+            return if let Some(shards_for_node) = shards_for_node_opt {
+                Cow::Borrowed(shards_for_node)
+            } else {
+                Cow::Owned(FnvHashMap::default())
+            };
         }
+        let mut shards_for_node = shards_for_node_opt.cloned().unwrap_or_default();
+
+        for shard in trashed_shards_for_node {
+            shards_for_node
+                .entry(shard.source_uid())
+                .or_default()
+                .insert(shard.shard_id().clone());
+        }
+        Cow::Owned(shards_for_node)
     }
 
     pub fn list_shards_for_index<'a>(
@@ -580,7 +726,7 @@ mod tests {
         mock_metastore
             .expect_list_indexes_metadata()
             .returning(|request| {
-                assert_eq!(request, ListIndexesMetadataRequest::all());
+                assert_eq!(request, ListIndexesMetadataRequest::all_including_trashed());
 
                 let mut index_0 = IndexMetadata::for_test("test-index-0", "ram:///test-index-0");
                 let mut source_config = SourceConfig::ingest_v2();
@@ -816,6 +962,65 @@ mod tests {
         assert_eq!(model.shard_table.num_sources(), 0);
     }
 
+    #[test]
+    fn test_control_plane_model_trash_and_undelete_index() {
+        let mut model = ControlPlaneModel::default();
+
+        let mut index_metadata = IndexMetadata::for_test("test-index", "ram:///indexes");
+        let index_uid = index_metadata.index_uid.clone();
+        index_metadata
+            .add_source(SourceConfig::ingest_v2())
+            .unwrap();
+        model.add_index(index_metadata.clone());
+
+        let shard = Shard {
+            index_uid: Some(index_uid.clone()),
+            source_id: INGEST_V2_SOURCE_ID.to_string(),
+            shard_id: Some(ShardId::from(1)),
+            shard_state: ShardState::Open as i32,
+            leader_id: "test-leader".to_string(),
+            follower_id: Some("test-follower".to_string()),
+            ..Default::default()
+        };
+        model.insert_shards(
+            &index_uid,
+            &INGEST_V2_SOURCE_ID.to_string(),
+            vec![shard.clone()],
+        );
+        let source_uid = shard.source_uid();
+        let leader_id: NodeId = "test-leader".into();
+        let follower_id: NodeId = "test-follower".into();
+
+        model.trash_index(&index_uid);
+
+        assert!(model.index_uid("test-index").is_none());
+        assert_eq!(model.shard_table.num_shards(), 0);
+
+        // The shards of the trashed index are still retained on the ingesters.
+        for node_id in [&leader_id, &follower_id] {
+            let shards_for_node = model.list_shards_for_node(node_id);
+            assert_eq!(shards_for_node.len(), 1);
+            assert!(shards_for_node[&source_uid].contains(&ShardId::from(1)));
+        }
+        assert_eq!(
+            model.list_ingesters_for_index(&index_uid),
+            BTreeSet::from([follower_id.clone(), leader_id.clone()])
+        );
+
+        model.undelete_index(index_metadata);
+
+        assert_eq!(model.index_uid("test-index"), Some(&index_uid));
+        assert_eq!(model.shard_table.num_shards(), 1);
+        assert!(model.trashed_shards.is_empty());
+
+        model.trash_index(&index_uid);
+        model.delete_index(&index_uid);
+
+        assert!(model.trashed_shards.is_empty());
+        assert!(model.list_shards_for_node(&leader_id).is_empty());
+        assert!(model.list_ingesters_for_index(&index_uid).is_empty());
+    }
+
     #[test]
     fn test_control_plane_model_toggle_source() {
         let mut model = ControlPlaneModel::default();
@@ -0,0 +1,234 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use quickwit_config::IndexRollout;
+use quickwit_metastore::{
+    IndexMetadata, ListSplitsQuery, ListSplitsRequestExt, MetastoreServiceStreamSplitsExt,
+    SplitState,
+};
+use quickwit_proto::metastore::{
+    GetIndexUsageRequest, IndexUsage, ListSplitsRequest, MetastoreResult, MetastoreService,
+    MetastoreServiceClient,
+};
+
+/// Interval between two evaluations of the index rollouts.
+pub(crate) const ROLLOUT_EVALUATION_INTERVAL: Duration = if cfg!(any(test, feature = "testsuite")) {
+    Duration::from_millis(100)
+} else {
+    Duration::from_secs(60)
+};
+
+#[derive(Debug)]
+pub(crate) struct EvaluateRollouts;
+
+/// Comparison between the candidate index of a rollout and its primary index.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct RolloutComparison {
+    pub candidate_num_docs: u64,
+    /// Ratio between the number of bytes per document of the candidate and primary indexes.
+    pub storage_ratio_opt: Option<f64>,
+    /// Ratio between the number of bytes scanned per query of the candidate and primary indexes.
+    pub scanned_bytes_ratio_opt: Option<f64>,
+}
+
+/// Compares the splits published and the searches served by the candidate index of a rollout
+/// with those of its primary index since the creation of the candidate index.
+pub(crate) async fn compare_indexes(
+    metastore: &MetastoreServiceClient,
+    primary_index_metadata: &IndexMetadata,
+    candidate_index_metadata: &IndexMetadata,
+) -> MetastoreResult<RolloutComparison> {
+    let primary_split_stats = split_stats(
+        metastore,
+        ListSplitsQuery::for_index(primary_index_metadata.index_uid.clone())
+            .with_create_timestamp_gte(candidate_index_metadata.create_timestamp),
+    )
+    .await?;
+    let candidate_split_stats = split_stats(
+        metastore,
+        ListSplitsQuery::for_index(candidate_index_metadata.index_uid.clone()),
+    )
+    .await?;
+    let primary_usage = index_usage(metastore, primary_index_metadata).await?;
+    let candidate_usage = index_usage(metastore, candidate_index_metadata).await?;
+
+    let comparison = RolloutComparison {
+        candidate_num_docs: candidate_split_stats.0,
+        storage_ratio_opt: ratio(candidate_split_stats, primary_split_stats),
+        scanned_bytes_ratio_opt: ratio(
+            (
+                candidate_usage.num_queries,
+                candidate_usage.num_bytes_scanned,
+            ),
+            (primary_usage.num_queries, primary_usage.num_bytes_scanned),
+        ),
+    };
+    Ok(comparison)
+}
+
+/// Returns the number of documents and bytes of the published splits matching the query.
+async fn split_stats(
+    metastore: &MetastoreServiceClient,
+    query: ListSplitsQuery,
+) -> MetastoreResult<(u64, u64)> {
+    let query = query.with_split_state(SplitState::Published);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+    let splits_metadata = metastore
+        .list_splits(list_splits_request)
+        .await?
+        .collect_splits_metadata()
+        .await?;
+    let num_docs: u64 = splits_metadata
+        .iter()
+        .map(|split_metadata| split_metadata.num_docs as u64)
+        .sum();
+    let num_bytes: u64 = splits_metadata
+        .iter()
+        .map(|split_metadata| split_metadata.footer_offsets.end)
+        .sum();
+    Ok((num_docs, num_bytes))
+}
+
+async fn index_usage(
+    metastore: &MetastoreServiceClient,
+    index_metadata: &IndexMetadata,
+) -> MetastoreResult<IndexUsage> {
+    let get_index_usage_request = GetIndexUsageRequest {
+        index_uid: Some(index_metadata.index_uid.clone()),
+        start_timestamp: None,
+        end_timestamp: None,
+    };
+    let index_usage = metastore
+        .get_index_usage(get_index_usage_request)
+        .await?
+        .usage
+        .unwrap_or_default();
+    Ok(index_usage)
+}
+
+/// Returns the ratio between the candidate and primary numbers of bytes per unit, documents or
+/// queries, or `None` if one of them has no unit or no byte.
+fn ratio(candidate: (u64, u64), primary: (u64, u64)) -> Option<f64> {
+    let (candidate_num_units, candidate_num_bytes) = candidate;
+    let (primary_num_units, primary_num_bytes) = primary;
+
+    if candidate_num_units == 0 || primary_num_units == 0 || primary_num_bytes == 0 {
+        return None;
+    }
+    let candidate_bytes_per_unit = candidate_num_bytes as f64 / candidate_num_units as f64;
+    let primary_bytes_per_unit = primary_num_bytes as f64 / primary_num_units as f64;
+    Some(candidate_bytes_per_unit / primary_bytes_per_unit)
+}
+
+/// Decides whether the candidate index of a rollout should be promoted. Returns the reason of the
+/// rollback otherwise.
+pub(crate) fn evaluate_rollout(
+    rollout: &IndexRollout,
+    comparison: &RolloutComparison,
+) -> Result<(), String> {
+    if comparison.candidate_num_docs < rollout.min_num_docs {
+        return Err(format!(
+            "candidate index holds {} documents, fewer than the required {}",
+            comparison.candidate_num_docs, rollout.min_num_docs
+        ));
+    }
+    for (name, max_ratio_opt, ratio_opt) in [
+        (
+            "storage",
+            rollout.max_storage_ratio,
+            comparison.storage_ratio_opt,
+        ),
+        (
+            "scanned bytes",
+            rollout.max_scanned_bytes_ratio,
+            comparison.scanned_bytes_ratio_opt,
+        ),
+    ] {
+        let Some(max_ratio) = max_ratio_opt else {
+            continue;
+        };
+        let Some(ratio) = ratio_opt else {
+            return Err(format!(
+                "not enough data to compare the {name} of the indexes"
+            ));
+        };
+        if ratio > max_ratio {
+            return Err(format!(
+                "{name} ratio {ratio:.2} exceeds the maximum {max_ratio:.2}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_config::RolloutState;
+
+    use super::*;
+
+    #[test]
+    fn test_rollout_ratio() {
+        assert_eq!(ratio((0, 0), (10, 100)), None);
+        assert_eq!(ratio((10, 100), (0, 0)), None);
+        assert_eq!(ratio((10, 100), (10, 0)), None);
+        assert_eq!(ratio((10, 150), (20, 200)), Some(1.5));
+    }
+
+    #[test]
+    fn test_evaluate_rollout() {
+        let rollout = IndexRollout {
+            primary_index_id: "test-index".to_string(),
+            ingest_percentage: 10.0,
+            search_percentage: 10.0,
+            evaluation_period: IndexRollout::default_evaluation_period(),
+            min_num_docs: 100,
+            max_storage_ratio: Some(1.2),
+            max_scanned_bytes_ratio: None,
+            state: RolloutState::Evaluating,
+        };
+        let comparison = RolloutComparison {
+            candidate_num_docs: 1_000,
+            storage_ratio_opt: Some(1.1),
+            scanned_bytes_ratio_opt: None,
+        };
+        evaluate_rollout(&rollout, &comparison).unwrap();
+
+        let comparison = RolloutComparison {
+            candidate_num_docs: 10,
+            storage_ratio_opt: Some(1.1),
+            scanned_bytes_ratio_opt: None,
+        };
+        let reason = evaluate_rollout(&rollout, &comparison).unwrap_err();
+        assert!(reason.contains("fewer than the required 100"));
+
+        let comparison = RolloutComparison {
+            candidate_num_docs: 1_000,
+            storage_ratio_opt: Some(1.5),
+            scanned_bytes_ratio_opt: None,
+        };
+        let reason = evaluate_rollout(&rollout, &comparison).unwrap_err();
+        assert_eq!(reason, "storage ratio 1.50 exceeds the maximum 1.20");
+
+        let comparison = RolloutComparison {
+            candidate_num_docs: 1_000,
+            storage_ratio_opt: None,
+            scanned_bytes_ratio_opt: None,
+        };
+        let reason = evaluate_rollout(&rollout, &comparison).unwrap_err();
+        assert!(reason.contains("not enough data"));
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use quickwit_common::pubsub::EventBroker;
+use quickwit_common::uri::Uri;
+use quickwit_metastore::{
+    ListSplitsQuery, ListSplitsRequestExt, MetastoreServiceStreamSplitsExt, SplitMetadata,
+    SplitState,
+};
+use quickwit_proto::metastore::{
+    ListSplitsRequest, MetastoreResult, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::search::{ReportSplit, ReportSplitsRequest};
+use quickwit_proto::types::IndexUid;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Interval between two prewarmings of the searcher split caches.
+pub(crate) const SPLIT_CACHE_PREWARMING_INTERVAL: Duration =
+    if cfg!(any(test, feature = "testsuite")) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(5 * 60)
+    };
+
+#[derive(Debug)]
+pub(crate) struct PrewarmSplitCaches;
+
+/// Spawns a task listing the splits to prewarm and reporting them to the searcher split caches.
+pub(crate) fn spawn_split_cache_prewarming_task(
+    metastore: MetastoreServiceClient,
+    event_broker: EventBroker,
+    indexes: Vec<(IndexUid, Uri, Option<String>)>,
+    num_splits_per_index: usize,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let report_splits = splits_to_prewarm(&metastore, indexes, num_splits_per_index).await;
+
+        if !report_splits.is_empty() {
+            crate::metrics::CONTROL_PLANE_METRICS
+                .prewarmed_splits_total
+                .inc_by(report_splits.len() as u64);
+            event_broker.publish(ReportSplitsRequest { report_splits });
+        }
+    })
+}
+
+/// Returns the `num_splits_per_index` most recent published splits of each index, which are the
+/// splits the searchers are the most likely to serve. The recency of a split is given by the end
+/// of its time range, or by its creation date for the indexes without timestamp field.
+///
+/// The indexes whose splits cannot be listed are skipped.
+pub(crate) async fn splits_to_prewarm(
+    metastore: &MetastoreServiceClient,
+    indexes: Vec<(IndexUid, Uri, Option<String>)>,
+    num_splits_per_index: usize,
+) -> Vec<ReportSplit> {
+    let mut report_splits = Vec::new();
+
+    for (index_uid, index_uri, storage_credentials_opt) in indexes {
+        let splits_metadata =
+            match most_recent_published_splits(metastore, index_uid.clone(), num_splits_per_index)
+                .await
+            {
+                Ok(splits_metadata) => splits_metadata,
+                Err(metastore_error) => {
+                    warn!(
+                        index_uid=%index_uid,
+                        error=%metastore_error,
+                        "failed to list splits to prewarm in the searcher split caches"
+                    );
+                    continue;
+                }
+            };
+        let storage_uri = index_uri.to_string();
+        report_splits.extend(
+            splits_metadata
+                .into_iter()
+                .map(|split_metadata| ReportSplit {
+                    split_id: split_metadata.split_id,
+                    storage_uri: storage_uri.clone(),
+                    storage_credentials: storage_credentials_opt.clone(),
+                    index_id: Some(split_metadata.index_uid.index_id),
+                }),
+        );
+    }
+    report_splits
+}
+
+async fn most_recent_published_splits(
+    metastore: &MetastoreServiceClient,
+    index_uid: IndexUid,
+    num_splits: usize,
+) -> MetastoreResult<Vec<SplitMetadata>> {
+    let query = ListSplitsQuery::for_index(index_uid)
+        .with_split_state(SplitState::Published)
+        .sort_by_recency()
+        .with_limit(num_splits);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+    metastore
+        .list_splits(list_splits_request)
+        .await?
+        .collect_splits_metadata()
+        .await
+}
@@ -19,6 +19,7 @@ use fnv::FnvHashMap;
 use futures::{Stream, StreamExt};
 use quickwit_actors::{Inbox, Mailbox, Observe, Universe};
 use quickwit_cluster::{create_cluster_for_test, ChannelTransport, Cluster, ClusterChange};
+use quickwit_common::pubsub::EventBroker;
 use quickwit_common::test_utils::wait_until_predicate;
 use quickwit_common::tower::{Change, Pool};
 use quickwit_config::service::QuickwitService;
@@ -57,6 +58,8 @@ fn index_metadata_for_test(index_id: &str, source_id: &str, num_pipelines: usize
         }),
         transform_config: None,
         input_format: SourceInputFormat::Json,
+        activation_windows: Vec::new(),
+        backfill_time_range: None,
     };
     index_metadata.add_source(kafka_source_config).unwrap();
     index_metadata
@@ -148,6 +151,7 @@ async fn start_control_plane(
         indexer_pool,
         ingester_pool,
         MetastoreServiceClient::from_mock(mock_metastore),
+        EventBroker::default(),
     );
 
     (indexer_inboxes, control_plane_mailbox)
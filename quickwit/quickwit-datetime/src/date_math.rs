@@ -0,0 +1,270 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use time::{Date, Duration, OffsetDateTime, Time};
+
+use crate::{parse_date_time_str, DateTimeInputFormat, TantivyDateTime};
+
+/// Direction in which the `/<unit>` operations of a date math expression round. Following
+/// Elasticsearch, the lower bounds of a range are rounded down when inclusive and up when
+/// exclusive, and conversely for the upper bounds.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DateMathRounding {
+    /// Rounds to the first millisecond of the unit.
+    Down,
+    /// Rounds to the last millisecond of the unit.
+    Up,
+}
+
+/// Returns whether the string is a date math expression, i.e. starts with `now` or with a date
+/// followed by `||`.
+pub fn is_date_math_expression(text: &str) -> bool {
+    if let Some(operations) = text.strip_prefix("now") {
+        return operations.is_empty() || operations.starts_with(['+', '-', '/']);
+    }
+    text.contains("||")
+}
+
+/// Evaluates a date math expression such as `now-15m`, `now/d`, or `2024-01-01||+1M/M`.
+///
+/// The anchor, `now` or a date parsed with one of `anchor_formats`, is followed by any number
+/// of operations: `+<n><unit>` and `-<n><unit>` add and subtract time, and `/<unit>` rounds
+/// according to `rounding`. The supported units are `y` (years), `M` (months), `w` (weeks), `d`
+/// (days), `h` or `H` (hours), `m` (minutes), and `s` (seconds). Dates are evaluated in UTC.
+pub fn evaluate_date_math(
+    expression: &str,
+    now: OffsetDateTime,
+    rounding: DateMathRounding,
+    anchor_formats: &[DateTimeInputFormat],
+) -> Result<TantivyDateTime, String> {
+    let (mut date_time, mut operations) = if let Some(operations) = expression.strip_prefix("now") {
+        (now, operations)
+    } else if let Some((anchor, operations)) = expression.split_once("||") {
+        let anchor_date_time = parse_date_time_str(anchor, anchor_formats)?.into_utc();
+        (anchor_date_time, operations)
+    } else {
+        return Err(format!("`{expression}` is not a date math expression"));
+    };
+    while let Some(operator) = operations.chars().next() {
+        operations = &operations[operator.len_utf8()..];
+
+        match operator {
+            '+' | '-' => {
+                let num_digits = operations
+                    .find(|ch: char| !ch.is_ascii_digit())
+                    .unwrap_or(operations.len());
+                let amount: i64 = operations[..num_digits].parse().map_err(|_| {
+                    format!("expected a number after `{operator}` in date math `{expression}`")
+                })?;
+                let unit = parse_unit(&operations[num_digits..], expression)?;
+                let amount = if operator == '-' { -amount } else { amount };
+                date_time = add_units(date_time, amount, unit)
+                    .ok_or_else(|| format!("date math `{expression}` is out of range"))?;
+                operations = &operations[num_digits + 1..];
+            }
+            '/' => {
+                let unit = parse_unit(operations, expression)?;
+                date_time = round(date_time, unit, rounding)
+                    .ok_or_else(|| format!("date math `{expression}` is out of range"))?;
+                operations = &operations[1..];
+            }
+            _ => {
+                return Err(format!(
+                    "unexpected character `{operator}` in date math `{expression}`"
+                ));
+            }
+        }
+    }
+    Ok(TantivyDateTime::from_utc(date_time))
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum DateMathUnit {
+    Year,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+fn parse_unit(operations: &str, expression: &str) -> Result<DateMathUnit, String> {
+    let unit = match operations.chars().next() {
+        Some('y') => DateMathUnit::Year,
+        Some('M') => DateMathUnit::Month,
+        Some('w') => DateMathUnit::Week,
+        Some('d') => DateMathUnit::Day,
+        Some('h') | Some('H') => DateMathUnit::Hour,
+        Some('m') => DateMathUnit::Minute,
+        Some('s') => DateMathUnit::Second,
+        _ => {
+            return Err(format!(
+                "expected a unit among `y`, `M`, `w`, `d`, `h`, `H`, `m`, and `s` in date math \
+                 `{expression}`"
+            ));
+        }
+    };
+    Ok(unit)
+}
+
+fn add_units(date_time: OffsetDateTime, amount: i64, unit: DateMathUnit) -> Option<OffsetDateTime> {
+    let unit_secs: i64 = match unit {
+        DateMathUnit::Year => return add_months(date_time, amount.checked_mul(12)?),
+        DateMathUnit::Month => return add_months(date_time, amount),
+        DateMathUnit::Week => 7 * 24 * 3600,
+        DateMathUnit::Day => 24 * 3600,
+        DateMathUnit::Hour => 3600,
+        DateMathUnit::Minute => 60,
+        DateMathUnit::Second => 1,
+    };
+    let num_secs = amount.checked_mul(unit_secs)?;
+    date_time.checked_add(Duration::seconds(num_secs))
+}
+
+/// Adds calendar months, clamping the day to the length of the resulting month.
+fn add_months(date_time: OffsetDateTime, num_months: i64) -> Option<OffsetDateTime> {
+    let month_index = date_time.year() as i64 * 12 + date_time.month() as i64 - 1;
+    let new_month_index = month_index.checked_add(num_months)?;
+    let year = i32::try_from(new_month_index.div_euclid(12)).ok()?;
+    let month = time::Month::try_from(new_month_index.rem_euclid(12) as u8 + 1).ok()?;
+    let day = date_time
+        .day()
+        .min(time::util::days_in_year_month(year, month));
+    let date = Date::from_calendar_date(year, month, day).ok()?;
+    Some(date_time.replace_date(date))
+}
+
+fn round(
+    date_time: OffsetDateTime,
+    unit: DateMathUnit,
+    rounding: DateMathRounding,
+) -> Option<OffsetDateTime> {
+    let date = date_time.date();
+    let rounded_down = match unit {
+        DateMathUnit::Year => date
+            .replace_month(time::Month::January)
+            .ok()?
+            .replace_day(1)
+            .ok()?
+            .midnight()
+            .assume_utc(),
+        DateMathUnit::Month => date.replace_day(1).ok()?.midnight().assume_utc(),
+        DateMathUnit::Week => {
+            let days_from_monday = date.weekday().number_days_from_monday() as i64;
+            date.checked_sub(Duration::days(days_from_monday))?
+                .midnight()
+                .assume_utc()
+        }
+        DateMathUnit::Day => date.midnight().assume_utc(),
+        DateMathUnit::Hour => date_time.replace_time(Time::from_hms(date_time.hour(), 0, 0).ok()?),
+        DateMathUnit::Minute => {
+            date_time.replace_time(Time::from_hms(date_time.hour(), date_time.minute(), 0).ok()?)
+        }
+        DateMathUnit::Second => date_time.replace_nanosecond(0).ok()?,
+    };
+    match rounding {
+        DateMathRounding::Down => Some(rounded_down),
+        DateMathRounding::Up => {
+            add_units(rounded_down, 1, unit)?.checked_sub(Duration::milliseconds(1))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn evaluate(expression: &str, rounding: DateMathRounding) -> OffsetDateTime {
+        let now = datetime!(2024-03-31 13:37:42.123 UTC);
+        evaluate_date_math(expression, now, rounding, &[DateTimeInputFormat::Rfc3339])
+            .unwrap()
+            .into_utc()
+    }
+
+    #[test]
+    fn test_is_date_math_expression() {
+        assert!(is_date_math_expression("now"));
+        assert!(is_date_math_expression("now-15m"));
+        assert!(is_date_math_expression("now/d"));
+        assert!(is_date_math_expression("2024-01-01T00:00:00Z||+1d"));
+        assert!(!is_date_math_expression("nowhere"));
+        assert!(!is_date_math_expression("2024-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_evaluate_date_math() {
+        let down = DateMathRounding::Down;
+        let up = DateMathRounding::Up;
+
+        assert_eq!(
+            evaluate("now", down),
+            datetime!(2024-03-31 13:37:42.123 UTC)
+        );
+        assert_eq!(
+            evaluate("now-15m", down),
+            datetime!(2024-03-31 13:22:42.123 UTC)
+        );
+        assert_eq!(
+            evaluate("now+2h", down),
+            datetime!(2024-03-31 15:37:42.123 UTC)
+        );
+        assert_eq!(evaluate("now/d", down), datetime!(2024-03-31 00:00 UTC));
+        assert_eq!(
+            evaluate("now/d", up),
+            datetime!(2024-03-31 23:59:59.999 UTC)
+        );
+        assert_eq!(evaluate("now-1d/d", down), datetime!(2024-03-30 00:00 UTC));
+        assert_eq!(evaluate("now/w", down), datetime!(2024-03-25 00:00 UTC));
+        assert_eq!(
+            evaluate("now/M", up),
+            datetime!(2024-03-31 23:59:59.999 UTC)
+        );
+        assert_eq!(evaluate("now/y", down), datetime!(2024-01-01 00:00 UTC));
+        assert_eq!(evaluate("now/H", down), datetime!(2024-03-31 13:00 UTC));
+        assert_eq!(evaluate("now/s", down), datetime!(2024-03-31 13:37:42 UTC));
+        // The day is clamped to the length of the month.
+        assert_eq!(
+            evaluate("now-1M", down),
+            datetime!(2024-02-29 13:37:42.123 UTC)
+        );
+        assert_eq!(
+            evaluate("now+1y-1M", down),
+            datetime!(2025-02-28 13:37:42.123 UTC)
+        );
+        assert_eq!(
+            evaluate("2024-01-01T12:00:00Z||+1d/d", down),
+            datetime!(2024-01-02 00:00 UTC)
+        );
+    }
+
+    #[test]
+    fn test_evaluate_date_math_errors() {
+        let now = OffsetDateTime::now_utc();
+        let formats = [DateTimeInputFormat::Rfc3339];
+
+        for expression in [
+            "now-",
+            "now-15",
+            "now-15q",
+            "now/",
+            "now*2d",
+            "yesterday||+1d",
+        ] {
+            evaluate_date_math(expression, now, DateMathRounding::Down, &formats).unwrap_err();
+        }
+    }
+}
@@ -12,10 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod date_math;
 mod date_time_format;
 mod date_time_parsing;
 pub mod java_date_time_format;
 
+pub use date_math::{evaluate_date_math, is_date_math_expression, DateMathRounding};
 pub use date_time_format::{DateTimeInputFormat, DateTimeOutputFormat};
 pub use date_time_parsing::{
     parse_date_time_str, parse_timestamp, parse_timestamp_float, parse_timestamp_int,
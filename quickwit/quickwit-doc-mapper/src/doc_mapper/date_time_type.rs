@@ -18,7 +18,7 @@ use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value as JsonValue;
 use tantivy::schema::{DateTimePrecision, OwnedValue as TantivyValue};
 
-use super::default_as_true;
+use super::{default_as_true, ValueLimits};
 
 /// A struct holding DateTime field options.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -49,6 +49,11 @@ pub struct QuickwitDateTimeOptions {
 
     #[serde(default)]
     pub fast: bool,
+
+    /// Limit on the number of values of the field.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ValueLimits>,
 }
 
 impl Default for QuickwitDateTimeOptions {
@@ -61,6 +66,7 @@ impl Default for QuickwitDateTimeOptions {
             indexed: true,
             stored: true,
             fast: false,
+            limits: None,
         }
     }
 }
@@ -220,6 +226,7 @@ mod tests {
             indexed: true,
             fast: true,
             stored: false,
+            limits: None,
         };
         assert_eq!(date_time_options, expected_date_time_options);
     }
@@ -290,6 +297,7 @@ mod tests {
             indexed: true,
             fast: true,
             stored: false,
+            limits: None,
         };
         assert_eq!(date_time_options, expected_date_time_options);
     }
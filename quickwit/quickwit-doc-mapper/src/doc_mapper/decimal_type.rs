@@ -0,0 +1,565 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Bound;
+
+use quickwit_query::query_ast::{
+    FullTextQuery, QueryAst, QueryAstTransformer, RangeQuery, TermQuery, TermSetQuery,
+};
+use quickwit_query::{InvalidQuery, JsonLiteral};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tantivy::schema::OwnedValue as TantivyValue;
+
+use super::field_mapping_entry::NumericOutputFormat;
+use super::mapping_tree::MappingNode;
+use super::{default_as_true, FieldMappingType, ValueLimits};
+
+/// Maximum scale of a decimal field: `10^18` is the largest power of ten that fits in an `i64`.
+const MAX_SCALE: u32 = 18;
+
+/// Options associated to a decimal field.
+///
+/// Decimal values are stored as integers scaled by `10^scale`: with a scale of 2, `12.34` is
+/// indexed and stored as `1234`. Range queries and aggregations on the field are therefore exact.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QuickwitDecimalOptions {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Number of digits after the decimal point.
+    pub scale: u32,
+    #[serde(default = "default_as_true")]
+    pub stored: bool,
+    #[serde(default = "default_as_true")]
+    pub indexed: bool,
+    #[serde(default)]
+    pub fast: bool,
+    #[serde(default)]
+    pub output_format: NumericOutputFormat,
+    /// Limit on the number of values of the field.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ValueLimits>,
+}
+
+impl QuickwitDecimalOptions {
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        if self.scale > MAX_SCALE {
+            anyhow::bail!(
+                "decimal scale must be less than or equal to {MAX_SCALE}, got {}",
+                self.scale
+            );
+        }
+        Ok(())
+    }
+
+    pub(crate) fn validate_json(
+        &self,
+        json_value: &serde_json_borrow::Value,
+    ) -> Result<(), String> {
+        match json_value {
+            serde_json_borrow::Value::Number(number) => {
+                let number = serde_json::Number::from(*number);
+                self.parse_str(&number.to_string())?;
+                Ok(())
+            }
+            serde_json_borrow::Value::Str(decimal_str) => {
+                self.parse_str(decimal_str)?;
+                Ok(())
+            }
+            _ => Err(format!(
+                "expected JSON number or string, got `{json_value}`"
+            )),
+        }
+    }
+
+    pub(crate) fn parse_json(&self, json_value: &JsonValue) -> Result<TantivyValue, String> {
+        let scaled_value = match json_value {
+            JsonValue::Number(number) => self.parse_str(&number.to_string())?,
+            JsonValue::String(decimal_str) => self.parse_str(decimal_str)?,
+            _ => {
+                return Err(format!(
+                    "expected JSON number or string, got `{json_value}`"
+                ))
+            }
+        };
+        Ok(TantivyValue::I64(scaled_value))
+    }
+
+    /// Parses a decimal number into its scaled integer representation, rejecting numbers with
+    /// more decimal digits than the scale of the field.
+    fn parse_str(&self, decimal_str: &str) -> Result<i64, String> {
+        let scaled_decimal = ScaledDecimal::parse(decimal_str, self.scale)?;
+
+        if scaled_decimal.is_truncated {
+            return Err(format!(
+                "decimal `{decimal_str}` has more than {} digits after the decimal point",
+                self.scale
+            ));
+        }
+        Ok(scaled_decimal.value)
+    }
+
+    pub(crate) fn format_to_json(&self, scaled_value: i64) -> Option<JsonValue> {
+        let decimal_str = format_scaled_decimal(scaled_value, self.scale);
+
+        match self.output_format {
+            NumericOutputFormat::String => Some(JsonValue::String(decimal_str)),
+            NumericOutputFormat::Number => decimal_str.parse().ok().map(JsonValue::Number),
+        }
+    }
+}
+
+/// A decimal number scaled by `10^scale` and rounded toward zero.
+#[derive(Debug, Eq, PartialEq)]
+struct ScaledDecimal {
+    value: i64,
+    /// Whether the number is negative. Unlike the sign of `value`, it is preserved when the number
+    /// is truncated to zero.
+    is_negative: bool,
+    /// Whether non-zero digits were dropped while scaling the number.
+    is_truncated: bool,
+}
+
+impl ScaledDecimal {
+    fn parse(decimal_str: &str, scale: u32) -> Result<Self, String> {
+        let invalid_decimal = || format!("failed to parse decimal `{decimal_str}`");
+        let trimmed_str = decimal_str.trim();
+
+        let (is_negative, unsigned_str) = if let Some(unsigned_str) = trimmed_str.strip_prefix('-')
+        {
+            (true, unsigned_str)
+        } else {
+            (false, trimmed_str.strip_prefix('+').unwrap_or(trimmed_str))
+        };
+        let (mantissa_str, exponent) = match unsigned_str.split_once(['e', 'E']) {
+            Some((mantissa_str, exponent_str)) => {
+                let exponent: i32 = exponent_str.parse().map_err(|_| invalid_decimal())?;
+                (mantissa_str, exponent)
+            }
+            None => (unsigned_str, 0),
+        };
+        let (integer_str, fraction_str) =
+            mantissa_str.split_once('.').unwrap_or((mantissa_str, ""));
+
+        if (integer_str.is_empty() && fraction_str.is_empty())
+            || !integer_str.bytes().all(|byte| byte.is_ascii_digit())
+            || !fraction_str.bytes().all(|byte| byte.is_ascii_digit())
+        {
+            return Err(invalid_decimal());
+        }
+        let digits: String = format!("{integer_str}{fraction_str}");
+        let digits = digits.trim_start_matches('0');
+        let shift = i64::from(exponent) - fraction_str.len() as i64 + i64::from(scale);
+
+        let out_of_range =
+            || format!("decimal `{decimal_str}` is out of range for a scale of {scale}");
+        let (kept_digits, dropped_digits) = if shift >= 0 {
+            (digits, "")
+        } else {
+            let num_dropped_digits = ((-shift) as usize).min(digits.len());
+            digits.split_at(digits.len() - num_dropped_digits)
+        };
+        let mut unsigned_value: i128 = if kept_digits.is_empty() {
+            0
+        } else if kept_digits.len() > 19 {
+            return Err(out_of_range());
+        } else {
+            kept_digits.parse().map_err(|_| invalid_decimal())?
+        };
+        if shift > 0 && unsigned_value != 0 {
+            let multiplier = u32::try_from(shift)
+                .ok()
+                .and_then(|shift| 10i128.checked_pow(shift))
+                .ok_or_else(out_of_range)?;
+            unsigned_value = unsigned_value
+                .checked_mul(multiplier)
+                .ok_or_else(out_of_range)?;
+        }
+        let signed_value = if is_negative {
+            -unsigned_value
+        } else {
+            unsigned_value
+        };
+        let value = i64::try_from(signed_value).map_err(|_| out_of_range())?;
+        let is_truncated = dropped_digits.bytes().any(|byte| byte != b'0');
+        Ok(Self {
+            value,
+            is_negative,
+            is_truncated,
+        })
+    }
+
+    /// Returns the largest scaled integer lower than or equal to the decimal number.
+    fn floor(&self) -> Option<i64> {
+        if self.is_truncated && self.is_negative {
+            self.value.checked_sub(1)
+        } else {
+            Some(self.value)
+        }
+    }
+
+    /// Returns the smallest scaled integer greater than or equal to the decimal number.
+    fn ceil(&self) -> Option<i64> {
+        if self.is_truncated && !self.is_negative {
+            self.value.checked_add(1)
+        } else {
+            Some(self.value)
+        }
+    }
+}
+
+fn format_scaled_decimal(scaled_value: i64, scale: u32) -> String {
+    let sign = if scaled_value < 0 { "-" } else { "" };
+    let digits = scaled_value.unsigned_abs().to_string();
+
+    if scale == 0 {
+        return format!("{sign}{digits}");
+    }
+    let scale = scale as usize;
+    let padded_digits = format!("{digits:0>width$}", width = scale + 1);
+    let (integer_digits, fraction_digits) = padded_digits.split_at(padded_digits.len() - scale);
+    format!("{sign}{integer_digits}.{fraction_digits}")
+}
+
+/// Rewrites the term, term set, full-text, and range queries targeting decimal fields so that
+/// their values are expressed as scaled integers, like the indexed values.
+pub(crate) struct DecimalQueryRewriter<'a> {
+    pub field_mappings: &'a MappingNode,
+}
+
+impl DecimalQueryRewriter<'_> {
+    fn decimal_options(&self, field: &str) -> Option<QuickwitDecimalOptions> {
+        match self.field_mappings.find_field_mapping_type(field)? {
+            FieldMappingType::Decimal(decimal_options, _) => Some(decimal_options),
+            _ => None,
+        }
+    }
+}
+
+fn invalid_search_term(field: &str, value: &str) -> InvalidQuery {
+    InvalidQuery::InvalidSearchTerm {
+        expected_value_type: "decimal",
+        field_name: field.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn rewrite_term(
+    field: String,
+    value: &str,
+    decimal_options: &QuickwitDecimalOptions,
+) -> Result<QueryAst, InvalidQuery> {
+    let scaled_decimal = ScaledDecimal::parse(value, decimal_options.scale)
+        .map_err(|_| invalid_search_term(&field, value))?;
+    if scaled_decimal.is_truncated {
+        // No value of the field can be equal to a number more precise than the field.
+        return Ok(QueryAst::MatchNone);
+    }
+    let term_query = TermQuery {
+        field,
+        value: scaled_decimal.value.to_string(),
+    };
+    Ok(term_query.into())
+}
+
+fn rewrite_bound(
+    field: &str,
+    bound: Bound<JsonLiteral>,
+    decimal_options: &QuickwitDecimalOptions,
+    is_lower_bound: bool,
+) -> Result<Bound<JsonLiteral>, InvalidQuery> {
+    let invalid_boundary = || InvalidQuery::InvalidBoundary {
+        expected_value_type: "decimal",
+        field_name: field.to_string(),
+    };
+    let (literal, is_inclusive) = match bound {
+        Bound::Included(literal) => (literal, true),
+        Bound::Excluded(literal) => (literal, false),
+        Bound::Unbounded => return Ok(Bound::Unbounded),
+    };
+    let decimal_str = match literal {
+        JsonLiteral::Number(number) => number.to_string(),
+        JsonLiteral::String(decimal_str) => decimal_str,
+        JsonLiteral::Bool(_) => return Err(invalid_boundary()),
+    };
+    let scaled_decimal = ScaledDecimal::parse(&decimal_str, decimal_options.scale)
+        .map_err(|_| invalid_boundary())?;
+
+    if !scaled_decimal.is_truncated {
+        let literal = JsonLiteral::Number(scaled_decimal.value.into());
+        return Ok(if is_inclusive {
+            Bound::Included(literal)
+        } else {
+            Bound::Excluded(literal)
+        });
+    }
+    // The bound falls between two scaled integers, so we round it inward and make it inclusive.
+    let rounded_value_opt = if is_lower_bound {
+        scaled_decimal.ceil()
+    } else {
+        scaled_decimal.floor()
+    };
+    let rounded_value = rounded_value_opt.ok_or_else(invalid_boundary)?;
+    Ok(Bound::Included(JsonLiteral::Number(rounded_value.into())))
+}
+
+impl QueryAstTransformer for DecimalQueryRewriter<'_> {
+    type Err = InvalidQuery;
+
+    fn transform_term(&mut self, term_query: TermQuery) -> Result<Option<QueryAst>, InvalidQuery> {
+        let Some(decimal_options) = self.decimal_options(&term_query.field) else {
+            return Ok(Some(term_query.into()));
+        };
+        rewrite_term(term_query.field, &term_query.value, &decimal_options).map(Some)
+    }
+
+    fn transform_term_set(
+        &mut self,
+        mut term_set_query: TermSetQuery,
+    ) -> Result<Option<QueryAst>, InvalidQuery> {
+        for (field, terms) in term_set_query.terms_per_field.iter_mut() {
+            let Some(decimal_options) = self.decimal_options(field) else {
+                continue;
+            };
+            let mut scaled_terms = std::collections::BTreeSet::new();
+
+            for term in terms.iter() {
+                let scaled_decimal = ScaledDecimal::parse(term, decimal_options.scale)
+                    .map_err(|_| invalid_search_term(field, term))?;
+                if !scaled_decimal.is_truncated {
+                    scaled_terms.insert(scaled_decimal.value.to_string());
+                }
+            }
+            *terms = scaled_terms;
+        }
+        Ok(Some(term_set_query.into()))
+    }
+
+    fn transform_full_text(
+        &mut self,
+        full_text_query: FullTextQuery,
+    ) -> Result<Option<QueryAst>, InvalidQuery> {
+        let Some(decimal_options) = self.decimal_options(&full_text_query.field) else {
+            return Ok(Some(full_text_query.into()));
+        };
+        rewrite_term(
+            full_text_query.field,
+            &full_text_query.text,
+            &decimal_options,
+        )
+        .map(Some)
+    }
+
+    fn transform_range(
+        &mut self,
+        range_query: RangeQuery,
+    ) -> Result<Option<QueryAst>, InvalidQuery> {
+        let Some(decimal_options) = self.decimal_options(&range_query.field) else {
+            return Ok(Some(range_query.into()));
+        };
+        let lower_bound = rewrite_bound(
+            &range_query.field,
+            range_query.lower_bound,
+            &decimal_options,
+            true,
+        )?;
+        let upper_bound = rewrite_bound(
+            &range_query.field,
+            range_query.upper_bound,
+            &decimal_options,
+            false,
+        )?;
+        let range_query = RangeQuery {
+            field: range_query.field,
+            lower_bound,
+            upper_bound,
+        };
+        Ok(Some(range_query.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal_options(scale: u32) -> QuickwitDecimalOptions {
+        QuickwitDecimalOptions {
+            description: None,
+            scale,
+            stored: true,
+            indexed: true,
+            fast: true,
+            output_format: NumericOutputFormat::Number,
+            limits: None,
+        }
+    }
+
+    #[test]
+    fn test_scaled_decimal_parse() {
+        let parse = |decimal_str: &str, scale: u32| {
+            ScaledDecimal::parse(decimal_str, scale)
+                .map(|scaled| (scaled.value, scaled.is_truncated))
+        };
+        assert_eq!(parse("12.34", 2), Ok((1234, false)));
+        assert_eq!(parse("12.3", 2), Ok((1230, false)));
+        assert_eq!(parse("-0.05", 2), Ok((-5, false)));
+        assert_eq!(parse("+7", 2), Ok((700, false)));
+        assert_eq!(parse(".5", 1), Ok((5, false)));
+        assert_eq!(parse("1.2300", 2), Ok((123, false)));
+        assert_eq!(parse("1.234", 2), Ok((123, true)));
+        assert_eq!(parse("-1.234", 2), Ok((-123, true)));
+        assert_eq!(parse("1e-7", 2), Ok((0, true)));
+        assert_eq!(parse("1.5e2", 0), Ok((150, false)));
+        assert_eq!(parse("92233720368547758.07", 2), Ok((i64::MAX, false)));
+
+        parse("", 2).unwrap_err();
+        parse(".", 2).unwrap_err();
+        parse("1.2.3", 2).unwrap_err();
+        parse("abc", 2).unwrap_err();
+        parse("92233720368547758.08", 2).unwrap_err();
+    }
+
+    #[test]
+    fn test_scaled_decimal_floor_ceil() {
+        let scaled_decimal = ScaledDecimal::parse("1.234", 2).unwrap();
+        assert_eq!(scaled_decimal.floor(), Some(123));
+        assert_eq!(scaled_decimal.ceil(), Some(124));
+
+        let scaled_decimal = ScaledDecimal::parse("-1.234", 2).unwrap();
+        assert_eq!(scaled_decimal.floor(), Some(-124));
+        assert_eq!(scaled_decimal.ceil(), Some(-123));
+
+        let scaled_decimal = ScaledDecimal::parse("1.23", 2).unwrap();
+        assert_eq!(scaled_decimal.floor(), Some(123));
+        assert_eq!(scaled_decimal.ceil(), Some(123));
+
+        let scaled_decimal = ScaledDecimal::parse("0.001", 2).unwrap();
+        assert_eq!(scaled_decimal.floor(), Some(0));
+        assert_eq!(scaled_decimal.ceil(), Some(1));
+
+        let scaled_decimal = ScaledDecimal::parse("-0.001", 2).unwrap();
+        assert_eq!(scaled_decimal.floor(), Some(-1));
+        assert_eq!(scaled_decimal.ceil(), Some(0));
+
+        let scaled_decimal = ScaledDecimal::parse("-0", 2).unwrap();
+        assert_eq!(scaled_decimal.floor(), Some(0));
+        assert_eq!(scaled_decimal.ceil(), Some(0));
+    }
+
+    #[test]
+    fn test_format_scaled_decimal() {
+        assert_eq!(format_scaled_decimal(1234, 2), "12.34");
+        assert_eq!(format_scaled_decimal(-5, 2), "-0.05");
+        assert_eq!(format_scaled_decimal(0, 3), "0.000");
+        assert_eq!(format_scaled_decimal(1234, 0), "1234");
+        assert_eq!(format_scaled_decimal(i64::MIN, 2), "-92233720368547758.08");
+    }
+
+    #[test]
+    fn test_decimal_options_parse_json() {
+        let decimal_options = decimal_options(2);
+        assert_eq!(
+            decimal_options
+                .parse_json(&serde_json::json!(12.34))
+                .unwrap(),
+            TantivyValue::I64(1234)
+        );
+        assert_eq!(
+            decimal_options
+                .parse_json(&serde_json::json!("0.1"))
+                .unwrap(),
+            TantivyValue::I64(10)
+        );
+        let error = decimal_options
+            .parse_json(&serde_json::json!("0.125"))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "decimal `0.125` has more than 2 digits after the decimal point"
+        );
+        decimal_options
+            .parse_json(&serde_json::json!(true))
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_decimal_options_format_to_json() {
+        let mut decimal_options = decimal_options(2);
+        assert_eq!(
+            decimal_options.format_to_json(1234),
+            Some(serde_json::json!(12.34))
+        );
+        decimal_options.output_format = NumericOutputFormat::String;
+        assert_eq!(
+            decimal_options.format_to_json(1230),
+            Some(serde_json::json!("12.30"))
+        );
+    }
+
+    #[test]
+    fn test_rewrite_bound() {
+        let decimal_options = decimal_options(2);
+        let rewrite = |bound: Bound<JsonLiteral>, is_lower_bound: bool| {
+            rewrite_bound("price", bound, &decimal_options, is_lower_bound).unwrap()
+        };
+        assert_eq!(
+            rewrite(
+                Bound::Included(JsonLiteral::String("10.5".to_string())),
+                true
+            ),
+            Bound::Included(JsonLiteral::Number(1050.into()))
+        );
+        assert_eq!(
+            rewrite(
+                Bound::Excluded(JsonLiteral::String("10.505".to_string())),
+                true
+            ),
+            Bound::Included(JsonLiteral::Number(1051.into()))
+        );
+        assert_eq!(
+            rewrite(
+                Bound::Excluded(JsonLiteral::String("10.505".to_string())),
+                false
+            ),
+            Bound::Included(JsonLiteral::Number(1050.into()))
+        );
+        // Bounds truncated to zero must keep 0.00 in the range.
+        assert_eq!(
+            rewrite(
+                Bound::Excluded(JsonLiteral::String("-0.001".to_string())),
+                true
+            ),
+            Bound::Included(JsonLiteral::Number(0.into()))
+        );
+        assert_eq!(
+            rewrite(
+                Bound::Excluded(JsonLiteral::String("0.001".to_string())),
+                false
+            ),
+            Bound::Included(JsonLiteral::Number(0.into()))
+        );
+        assert_eq!(rewrite(Bound::Unbounded, false), Bound::Unbounded);
+
+        rewrite_bound(
+            "price",
+            Bound::Included(JsonLiteral::Bool(true)),
+            &decimal_options,
+            true,
+        )
+        .unwrap_err();
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use quickwit_query::BooleanOperand;
 use serde::de::IgnoredAny;
 use serde::{Deserialize, Serialize};
 
@@ -32,6 +33,14 @@ pub struct DocMapperBuilder {
     /// Default search field names.
     #[serde(default)]
     pub default_search_fields: Vec<String>,
+    /// Default operator of the user queries that do not specify it.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_operator: Option<BooleanOperand>,
+    /// Default leniency of the user queries that do not specify it.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "quickwit_common::is_false")]
+    pub lenient: bool,
 
     /// Allow the "type" field separately.
     /// This is a residue from when the DocMapper was a trait.
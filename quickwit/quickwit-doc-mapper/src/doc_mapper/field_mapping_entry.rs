@@ -27,7 +27,8 @@ use tantivy::schema::{
 };
 
 use super::date_time_type::QuickwitDateTimeOptions;
-use super::{default_as_true, FieldMappingType};
+use super::decimal_type::QuickwitDecimalOptions;
+use super::{default_as_true, FieldMappingType, RedactionRule, Redactor, ValueLimits};
 use crate::doc_mapper::field_mapping_type::QuickwitFieldType;
 use crate::{Cardinality, QW_RESERVED_FIELD_NAMES};
 
@@ -95,6 +96,9 @@ pub struct QuickwitNumericOptions {
     pub coerce: bool,
     #[serde(default)]
     pub output_format: NumericOutputFormat,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ValueLimits>,
 }
 
 impl Default for QuickwitNumericOptions {
@@ -106,6 +110,7 @@ impl Default for QuickwitNumericOptions {
             fast: false,
             coerce: true,
             output_format: NumericOutputFormat::default(),
+            limits: None,
         }
     }
 }
@@ -122,6 +127,9 @@ pub struct QuickwitBoolOptions {
     pub indexed: bool,
     #[serde(default)]
     pub fast: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ValueLimits>,
 }
 
 impl Default for QuickwitBoolOptions {
@@ -131,6 +139,7 @@ impl Default for QuickwitBoolOptions {
             indexed: true,
             stored: true,
             fast: false,
+            limits: None,
         }
     }
 }
@@ -158,6 +167,14 @@ pub struct QuickwitBytesOptions {
     /// Output format of the bytes field.
     #[serde(default)]
     pub output_format: BinaryFormat,
+    /// Maximum size in bytes of a decoded value. Documents with larger values are rejected.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_num_bytes: Option<usize>,
+    /// Limit on the number of values of the field.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ValueLimits>,
 }
 
 impl Default for QuickwitBytesOptions {
@@ -169,10 +186,45 @@ impl Default for QuickwitBytesOptions {
             fast: false,
             input_format: BinaryFormat::default(),
             output_format: BinaryFormat::default(),
+            max_num_bytes: None,
+            limits: None,
         }
     }
 }
 
+impl QuickwitBytesOptions {
+    /// Parses a string encoded with the input format, checking that the decoded value does not
+    /// exceed `max_num_bytes`.
+    pub fn parse_str(&self, byte_str: &str) -> Result<Vec<u8>, String> {
+        let payload = self.input_format.parse_str(byte_str)?;
+        self.check_num_bytes(&payload)?;
+        Ok(payload)
+    }
+
+    /// Parses the `serde_json::Value` into `tantivy::schema::Value`, checking that the decoded
+    /// value does not exceed `max_num_bytes`.
+    pub fn parse_json(&self, json_val: &JsonValue) -> Result<TantivyValue, String> {
+        let tantivy_value = self.input_format.parse_json(json_val)?;
+
+        if let TantivyValue::Bytes(payload) = &tantivy_value {
+            self.check_num_bytes(payload)?;
+        }
+        Ok(tantivy_value)
+    }
+
+    fn check_num_bytes(&self, payload: &[u8]) -> Result<(), String> {
+        if let Some(max_num_bytes) = self.max_num_bytes {
+            if payload.len() > max_num_bytes {
+                return Err(format!(
+                    "bytes value of {} bytes exceeds the maximum size of {max_num_bytes} bytes",
+                    payload.len()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Available binary formats.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -251,6 +303,9 @@ pub struct QuickwitIpAddrOptions {
     pub indexed: bool,
     #[serde(default)]
     pub fast: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ValueLimits>,
 }
 
 impl Default for QuickwitIpAddrOptions {
@@ -260,6 +315,7 @@ impl Default for QuickwitIpAddrOptions {
             indexed: true,
             stored: true,
             fast: false,
+            limits: None,
         }
     }
 }
@@ -451,6 +507,15 @@ pub struct QuickwitTextOptions {
     pub stored: bool,
     #[serde(default)]
     pub fast: FastFieldOptions,
+    /// Redaction rules applied to the field values before they are indexed and stored.
+    #[schema(value_type = Vec<RedactionRule>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Redactor::is_empty")]
+    pub redact: Redactor,
+    /// Limits on the number and the length of the field values.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ValueLimits>,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
@@ -516,6 +581,8 @@ impl Default for QuickwitTextOptions {
             indexing_options: Some(TextIndexingOptions::default()),
             stored: true,
             fast: FastFieldOptions::default(),
+            redact: Redactor::default(),
+            limits: None,
         }
     }
 }
@@ -605,6 +672,10 @@ pub struct QuickwitJsonOptions {
     /// If true, the json object will be stored in columnar format.
     #[serde(default)]
     pub fast: FastFieldOptions,
+    /// Limits on the number of values of the nested arrays and the length of the text values.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ValueLimits>,
 }
 
 impl QuickwitJsonOptions {
@@ -625,6 +696,7 @@ impl Default for QuickwitJsonOptions {
             stored: true,
             expand_dots: true,
             fast: FastFieldOptions::default(),
+            limits: None,
         }
     }
 }
@@ -740,6 +812,11 @@ fn deserialize_mapping_type(
             }
             return Ok(FieldMappingType::Concatenate(concatenate_options));
         }
+        QuickwitFieldType::Decimal(cardinality) => {
+            let decimal_options: QuickwitDecimalOptions = serde_json::from_value(json)?;
+            decimal_options.validate()?;
+            return Ok(FieldMappingType::Decimal(decimal_options, cardinality));
+        }
     };
     match typ {
         Type::Str => {
@@ -801,6 +878,10 @@ impl TryFrom<FieldMappingEntryForSerialization> for FieldMappingEntry {
             quickwit_field_type,
             JsonValue::Object(value.field_mapping_json),
         )
+        .and_then(|mapping_type| {
+            validate_value_limits(&mapping_type)?;
+            Ok(mapping_type)
+        })
         .map_err(|err| format!("error while parsing field `{}`: {}", value.name, err))?;
         Ok(FieldMappingEntry {
             name: value.name,
@@ -809,6 +890,27 @@ impl TryFrom<FieldMappingEntryForSerialization> for FieldMappingEntry {
     }
 }
 
+/// Checks that the value limits of a field are applicable to its type.
+fn validate_value_limits(mapping_type: &FieldMappingType) -> anyhow::Result<()> {
+    let Some(value_limits) = mapping_type.value_limits() else {
+        return Ok(());
+    };
+    let is_json = matches!(mapping_type, FieldMappingType::Json(..));
+    let is_multivalued = matches!(
+        mapping_type.quickwit_field_type(),
+        QuickwitFieldType::Array(_) | QuickwitFieldType::Decimal(Cardinality::MultiValued)
+    );
+    if value_limits.max_num_values.is_some() && !is_json && !is_multivalued {
+        bail!("`max_num_values` is only supported by array and json fields");
+    }
+    let is_text = matches!(mapping_type, FieldMappingType::Text(..));
+
+    if value_limits.max_value_length.is_some() && !is_json && !is_text {
+        bail!("`max_value_length` is only supported by text and json fields");
+    }
+    Ok(())
+}
+
 /// Serialize object into a `Map` of json values.
 fn serialize_to_map<S: Serialize>(val: &S) -> Option<serde_json::Map<String, JsonValue>> {
     let json_val = serde_json::to_value(val).ok()?;
@@ -827,6 +929,7 @@ fn typed_mapping_to_json_params(
         FieldMappingType::U64(options, _)
         | FieldMappingType::I64(options, _)
         | FieldMappingType::F64(options, _) => serialize_to_map(&options),
+        FieldMappingType::Decimal(options, _) => serialize_to_map(&options),
         FieldMappingType::Bool(options, _) => serialize_to_map(&options),
         FieldMappingType::Bytes(options, _) => serialize_to_map(&options),
         FieldMappingType::IpAddr(options, _) => serialize_to_map(&options),
@@ -1720,6 +1823,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_bytes_mapping_with_max_num_bytes() {
+        let entry = serde_json::from_str::<FieldMappingEntry>(
+            r#"
+            {
+                "name": "my_field_name",
+                "type": "bytes",
+                "max_num_bytes": 32
+            }
+            "#,
+        )
+        .unwrap();
+        let FieldMappingType::Bytes(bytes_options, _) = &entry.mapping_type else {
+            panic!("expected bytes field mapping");
+        };
+        assert_eq!(bytes_options.max_num_bytes, Some(32));
+
+        let entry_deserser = serde_json::to_value(&entry).unwrap();
+        assert_eq!(entry_deserser["max_num_bytes"], json!(32));
+    }
+
     #[test]
     fn test_parse_bytes_mapping_arr() {
         let entry = serde_json::from_str::<FieldMappingEntry>(
@@ -1746,6 +1870,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_decimal_mapping() {
+        let entry = serde_json::from_str::<FieldMappingEntry>(
+            r#"
+            {
+                "name": "price",
+                "type": "array<decimal>",
+                "scale": 2,
+                "fast": true
+            }
+            "#,
+        )
+        .unwrap();
+        let FieldMappingType::Decimal(decimal_options, cardinality) = &entry.mapping_type else {
+            panic!("expected decimal field mapping");
+        };
+        assert_eq!(decimal_options.scale, 2);
+        assert_eq!(*cardinality, Cardinality::MultiValued);
+
+        let entry_deserser = serde_json::to_value(&entry).unwrap();
+        assert_eq!(
+            entry_deserser,
+            json!({
+                "name": "price",
+                "type": "array<decimal>",
+                "scale": 2,
+                "stored": true,
+                "indexed": true,
+                "fast": true,
+                "output_format": "number"
+            })
+        );
+        let error = serde_json::from_str::<FieldMappingEntry>(
+            r#"
+            {
+                "name": "price",
+                "type": "decimal",
+                "scale": 19
+            }
+            "#,
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("decimal scale must be less than or equal to 18"));
+
+        serde_json::from_str::<FieldMappingEntry>(
+            r#"
+            {
+                "name": "price",
+                "type": "decimal"
+            }
+            "#,
+        )
+        .unwrap_err();
+    }
+
     #[test]
     fn test_parse_bytes_mapping_arr_and_fast_forbidden() {
         let err = serde_json::from_str::<FieldMappingEntry>(
@@ -1783,6 +1964,7 @@ mod tests {
             stored: true,
             fast: FastFieldOptions::Disabled,
             expand_dots: true,
+            limits: None,
         };
         assert_eq!(&field_mapping_entry.name, "my_json_field");
         assert!(
@@ -1836,6 +2018,7 @@ mod tests {
             stored: false,
             expand_dots: true,
             fast: FastFieldOptions::Disabled,
+            limits: None,
         };
         assert_eq!(&field_mapping_entry.name, "my_json_field_multi");
         assert!(
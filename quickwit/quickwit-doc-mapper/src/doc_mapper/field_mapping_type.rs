@@ -15,7 +15,9 @@
 use tantivy::schema::Type;
 
 use super::date_time_type::QuickwitDateTimeOptions;
+use super::decimal_type::QuickwitDecimalOptions;
 use super::field_mapping_entry::QuickwitBoolOptions;
+use super::ValueLimits;
 use crate::doc_mapper::field_mapping_entry::{
     QuickwitBytesOptions, QuickwitConcatenateOptions, QuickwitIpAddrOptions, QuickwitJsonOptions,
     QuickwitNumericOptions, QuickwitObjectOptions, QuickwitTextOptions,
@@ -36,6 +38,8 @@ pub enum FieldMappingType {
     DateTime(QuickwitDateTimeOptions, Cardinality),
     /// 64-bit float mapping type configuration.
     F64(QuickwitNumericOptions, Cardinality),
+    /// Fixed-precision decimal mapping type configuration.
+    Decimal(QuickwitDecimalOptions, Cardinality),
     /// Bool mapping type configuration.
     Bool(QuickwitBoolOptions, Cardinality),
     /// IP Address mapping type configuration.
@@ -58,6 +62,9 @@ impl FieldMappingType {
             FieldMappingType::I64(_, cardinality) => (Type::I64, *cardinality),
             FieldMappingType::U64(_, cardinality) => (Type::U64, *cardinality),
             FieldMappingType::F64(_, cardinality) => (Type::F64, *cardinality),
+            FieldMappingType::Decimal(_, cardinality) => {
+                return QuickwitFieldType::Decimal(*cardinality);
+            }
             FieldMappingType::Bool(_, cardinality) => (Type::Bool, *cardinality),
             FieldMappingType::IpAddr(_, cardinality) => (Type::IpAddr, *cardinality),
             FieldMappingType::DateTime(_, cardinality) => (Type::Date, *cardinality),
@@ -73,6 +80,23 @@ impl FieldMappingType {
             Cardinality::MultiValued => QuickwitFieldType::Array(primitive_type),
         }
     }
+
+    /// Returns the limits on the number and the length of the field values, if any.
+    pub fn value_limits(&self) -> Option<&ValueLimits> {
+        match self {
+            FieldMappingType::Text(options, _) => options.limits.as_ref(),
+            FieldMappingType::I64(options, _)
+            | FieldMappingType::U64(options, _)
+            | FieldMappingType::F64(options, _) => options.limits.as_ref(),
+            FieldMappingType::DateTime(options, _) => options.limits.as_ref(),
+            FieldMappingType::Decimal(options, _) => options.limits.as_ref(),
+            FieldMappingType::Bool(options, _) => options.limits.as_ref(),
+            FieldMappingType::IpAddr(options, _) => options.limits.as_ref(),
+            FieldMappingType::Bytes(options, _) => options.limits.as_ref(),
+            FieldMappingType::Json(options, _) => options.limits.as_ref(),
+            FieldMappingType::Object(_) | FieldMappingType::Concatenate(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -81,6 +105,7 @@ pub enum QuickwitFieldType {
     Object,
     Concatenate,
     Array(Type),
+    Decimal(Cardinality),
 }
 
 impl QuickwitFieldType {
@@ -90,6 +115,8 @@ impl QuickwitFieldType {
             QuickwitFieldType::Object => "object".to_string(),
             QuickwitFieldType::Array(typ) => format!("array<{}>", primitive_type_to_str(typ)),
             QuickwitFieldType::Concatenate => "concatenate".to_string(),
+            QuickwitFieldType::Decimal(Cardinality::SingleValued) => "decimal".to_string(),
+            QuickwitFieldType::Decimal(Cardinality::MultiValued) => "array<decimal>".to_string(),
         }
     }
 
@@ -100,6 +127,12 @@ impl QuickwitFieldType {
         if type_str == "concatenate" {
             return Some(QuickwitFieldType::Concatenate);
         }
+        if type_str == "decimal" {
+            return Some(QuickwitFieldType::Decimal(Cardinality::SingleValued));
+        }
+        if type_str == "array<decimal>" {
+            return Some(QuickwitFieldType::Decimal(Cardinality::MultiValued));
+        }
         if type_str.starts_with("array<") && type_str.ends_with('>') {
             let parsed_type_str = parse_primitive_type(&type_str[6..type_str.len() - 1])?;
             return Some(QuickwitFieldType::Array(parsed_type_str));
@@ -146,6 +179,7 @@ mod tests {
     use tantivy::schema::Type;
 
     use super::QuickwitFieldType;
+    use crate::Cardinality;
 
     #[track_caller]
     fn test_parse_type_aux(type_str: &str, expected: Option<QuickwitFieldType>) {
@@ -163,5 +197,9 @@ mod tests {
         test_parse_type_aux("object2", None);
         test_parse_type_aux("bool", Some(QuickwitFieldType::Simple(Type::Bool)));
         test_parse_type_aux("ip", Some(QuickwitFieldType::Simple(Type::IpAddr)));
+        test_parse_type_aux(
+            "array<decimal>",
+            Some(QuickwitFieldType::Decimal(Cardinality::MultiValued)),
+        );
     }
 }
@@ -0,0 +1,90 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::Infallible;
+
+use quickwit_query::query_ast::{
+    FieldPresenceQuery, FullTextQuery, QueryAst, QueryAstTransformer, RangeQuery, TermQuery,
+    TermSetQuery,
+};
+
+use crate::LOGICAL_TIMESTAMP_FIELD_NAME;
+
+/// Rewrites the queries targeting the logical `@timestamp` field so that they target the
+/// timestamp field of the index instead. This lets a single time filter apply to indexes with
+/// differently named timestamp fields.
+pub(crate) struct LogicalTimestampFieldRewriter<'a> {
+    pub timestamp_field: &'a str,
+}
+
+impl LogicalTimestampFieldRewriter<'_> {
+    fn rewrite_field(&self, field: &mut String) {
+        if field == LOGICAL_TIMESTAMP_FIELD_NAME {
+            *field = self.timestamp_field.to_string();
+        }
+    }
+}
+
+impl QueryAstTransformer for LogicalTimestampFieldRewriter<'_> {
+    type Err = Infallible;
+
+    fn transform_term(
+        &mut self,
+        mut term_query: TermQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        self.rewrite_field(&mut term_query.field);
+        Ok(Some(term_query.into()))
+    }
+
+    fn transform_term_set(
+        &mut self,
+        mut term_set_query: TermSetQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        if let Some(terms) = term_set_query
+            .terms_per_field
+            .remove(LOGICAL_TIMESTAMP_FIELD_NAME)
+        {
+            term_set_query
+                .terms_per_field
+                .entry(self.timestamp_field.to_string())
+                .or_default()
+                .extend(terms);
+        }
+        Ok(Some(term_set_query.into()))
+    }
+
+    fn transform_full_text(
+        &mut self,
+        mut full_text_query: FullTextQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        self.rewrite_field(&mut full_text_query.field);
+        Ok(Some(full_text_query.into()))
+    }
+
+    fn transform_range(
+        &mut self,
+        mut range_query: RangeQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        self.rewrite_field(&mut range_query.field);
+        Ok(Some(range_query.into()))
+    }
+
+    fn transform_exists(
+        &mut self,
+        mut exists_query: FieldPresenceQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        self.rewrite_field(&mut exists_query.field);
+        Ok(Some(exists_query.into()))
+    }
+}
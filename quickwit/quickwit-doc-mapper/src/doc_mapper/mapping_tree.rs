@@ -19,6 +19,7 @@ use std::str::FromStr;
 
 use anyhow::bail;
 use itertools::Itertools;
+use quickwit_common::rate_limited_warn;
 use serde_json::Value as JsonValue;
 use serde_json_borrow::{Map as BorrowedJsonMap, Value as BorrowedJsonValue};
 use tantivy::schema::{
@@ -28,20 +29,23 @@ use tantivy::schema::{
 use tantivy::TantivyDocument as Document;
 
 use super::date_time_type::QuickwitDateTimeOptions;
+use super::decimal_type::QuickwitDecimalOptions;
 use super::field_mapping_entry::QuickwitBoolOptions;
 use super::tantivy_val_to_json::formatted_tantivy_value_to_json;
 use crate::doc_mapper::field_mapping_entry::{
     QuickwitBytesOptions, QuickwitIpAddrOptions, QuickwitNumericOptions, QuickwitObjectOptions,
     QuickwitTextOptions,
 };
-use crate::doc_mapper::{FieldMappingType, QuickwitJsonOptions};
-use crate::{Cardinality, DocParsingError, FieldMappingEntry, ModeType};
+use crate::doc_mapper::{FieldMappingType, QuickwitJsonOptions, Redactor, ValueLimits};
+use crate::metrics::{record_type_mismatch, TypeMismatchOutcome};
+use crate::{Cardinality, DocParsingError, FieldMappingEntry, ModeType, Strictness};
 
 #[derive(Clone, Debug)]
 pub enum LeafType {
     Bool(QuickwitBoolOptions),
     Bytes(QuickwitBytesOptions),
     DateTime(QuickwitDateTimeOptions),
+    Decimal(QuickwitDecimalOptions),
     F64(QuickwitNumericOptions),
     I64(QuickwitNumericOptions),
     U64(QuickwitNumericOptions),
@@ -155,6 +159,21 @@ pub(crate) fn map_primitive_json_to_tantivy(value: JsonValue) -> Option<TantivyV
 }
 
 impl LeafType {
+    fn value_limits(&self) -> Option<&ValueLimits> {
+        match self {
+            LeafType::Bool(options) => options.limits.as_ref(),
+            LeafType::Bytes(options) => options.limits.as_ref(),
+            LeafType::DateTime(options) => options.limits.as_ref(),
+            LeafType::Decimal(options) => options.limits.as_ref(),
+            LeafType::F64(options) | LeafType::I64(options) | LeafType::U64(options) => {
+                options.limits.as_ref()
+            }
+            LeafType::IpAddr(options) => options.limits.as_ref(),
+            LeafType::Json(options) => options.limits.as_ref(),
+            LeafType::Text(options) => options.limits.as_ref(),
+        }
+    }
+
     fn validate_from_json(&self, json_val: &BorrowedJsonValue) -> Result<(), String> {
         match self {
             LeafType::Text(_) => {
@@ -191,9 +210,10 @@ impl LeafType {
             LeafType::DateTime(date_time_options) => {
                 date_time_options.validate_json(json_val).map(|_| ())
             }
+            LeafType::Decimal(decimal_options) => decimal_options.validate_json(json_val),
             LeafType::Bytes(binary_options) => {
                 if let Some(byte_str) = json_val.as_str() {
-                    binary_options.input_format.parse_str(byte_str)?;
+                    binary_options.parse_str(byte_str)?;
                     Ok(())
                 } else {
                     Err(format!(
@@ -242,7 +262,8 @@ impl LeafType {
                 }
             }
             LeafType::DateTime(date_time_options) => date_time_options.parse_json(&json_val),
-            LeafType::Bytes(binary_options) => binary_options.input_format.parse_json(&json_val),
+            LeafType::Decimal(decimal_options) => decimal_options.parse_json(&json_val),
+            LeafType::Bytes(binary_options) => binary_options.parse_json(&json_val),
             LeafType::Json(_) => {
                 if let JsonValue::Object(json_obj) = json_val {
                     Ok(TantivyValue::Object(
@@ -293,6 +314,9 @@ impl LeafType {
             LeafType::DateTime(_date_time_options) => {
                 Err("unsupported concat type: DateTime".to_string())
             }
+            LeafType::Decimal(_decimal_options) => {
+                Err("unsupported concat type: Decimal".to_string())
+            }
             LeafType::Bytes(_binary_options) => Err("unsupported concat type: Bytes".to_string()),
             LeafType::Json(_) => {
                 if let JsonValue::Object(json_obj) = json_val {
@@ -309,6 +333,45 @@ impl LeafType {
         }
     }
 
+    /// Converts a JSON value whose type does not match the leaf type into a JSON value of the
+    /// expected type, if possible.
+    fn coerce_json_val(&self, json_val: &JsonValue) -> Option<JsonValue> {
+        match (self, json_val) {
+            (LeafType::Text(_), JsonValue::Number(_) | JsonValue::Bool(_)) => {
+                Some(JsonValue::String(json_val.to_string()))
+            }
+            (LeafType::I64(_), JsonValue::String(str_val)) => {
+                str_val.parse::<i64>().ok().map(JsonValue::from)
+            }
+            (LeafType::U64(_), JsonValue::String(str_val)) => {
+                str_val.parse::<u64>().ok().map(JsonValue::from)
+            }
+            (LeafType::F64(_), JsonValue::String(str_val)) => str_val
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(JsonValue::Number),
+            (LeafType::Bool(_), JsonValue::String(str_val)) => {
+                str_val.parse::<bool>().ok().map(JsonValue::Bool)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether [`LeafType::coerce_json_val`] can convert the JSON value.
+    fn is_coercible(&self, json_val: &BorrowedJsonValue) -> bool {
+        match (self, json_val) {
+            (LeafType::Text(_), BorrowedJsonValue::Number(_) | BorrowedJsonValue::Bool(_)) => true,
+            (LeafType::I64(_), BorrowedJsonValue::Str(str_val)) => str_val.parse::<i64>().is_ok(),
+            (LeafType::U64(_), BorrowedJsonValue::Str(str_val)) => str_val.parse::<u64>().is_ok(),
+            (LeafType::F64(_), BorrowedJsonValue::Str(str_val)) => str_val
+                .parse::<f64>()
+                .map_or(false, |float_val| float_val.is_finite()),
+            (LeafType::Bool(_), BorrowedJsonValue::Str(str_val)) => str_val.parse::<bool>().is_ok(),
+            _ => false,
+        }
+    }
+
     fn supported_for_concat(&self) -> bool {
         use LeafType::*;
         matches!(self, Text(_) | U64(_) | I64(_) | F64(_) | Bool(_) | Json(_))
@@ -316,6 +379,7 @@ impl LeafType {
             // Since concat is a JSON field, anything that JSON supports can be supported
             DateTime(_), // Could be supported if the date is converted to Rfc3339
             IpAddr(_),
+            Decimal(_),
             // won't be supported
             Bytes(_),
         */
@@ -336,6 +400,7 @@ impl MappingLeaf {
         &self,
         json_value: &BorrowedJsonValue,
         path: &[&str],
+        strictness: Strictness,
     ) -> Result<(), DocParsingError> {
         if json_value.is_null() {
             // We just ignore `null`.
@@ -343,6 +408,9 @@ impl MappingLeaf {
         }
         if let BorrowedJsonValue::Array(els) = json_value {
             if self.cardinality == Cardinality::SingleValued {
+                if strictness == Strictness::Lenient {
+                    return Ok(());
+                }
                 return Err(DocParsingError::MultiValuesNotSupported(path.join(".")));
             }
             for el_json_val in els {
@@ -350,25 +418,38 @@ impl MappingLeaf {
                     // We just ignore `null`.
                     continue;
                 }
-                self.typ
-                    .validate_from_json(el_json_val)
-                    .map_err(|err_msg| DocParsingError::ValueError(path.join("."), err_msg))?;
+                self.validate_json_val(el_json_val, path, strictness)?;
             }
             return Ok(());
         }
+        self.validate_json_val(json_value, path, strictness)
+    }
 
-        self.typ
-            .validate_from_json(json_value)
-            .map_err(|err_msg| DocParsingError::ValueError(path.join("."), err_msg))?;
-
-        Ok(())
+    fn validate_json_val(
+        &self,
+        json_val: &BorrowedJsonValue,
+        path: &[&str],
+        strictness: Strictness,
+    ) -> Result<(), DocParsingError> {
+        let Err(err_msg) = self.typ.validate_from_json(json_val) else {
+            return Ok(());
+        };
+        match strictness {
+            Strictness::Coerce if self.typ.is_coercible(json_val) => Ok(()),
+            Strictness::Strict | Strictness::Coerce => {
+                Err(DocParsingError::ValueError(path.join("."), err_msg))
+            }
+            Strictness::Lenient => Ok(()),
+        }
     }
 
     pub fn doc_from_json(
         &self,
         json_val: JsonValue,
+        strictness: Strictness,
         document: &mut Document,
         path: &mut [String],
+        dynamic_json_obj: &mut serde_json::Map<String, JsonValue>,
     ) -> Result<(), DocParsingError> {
         if json_val.is_null() {
             // We just ignore `null`.
@@ -376,48 +457,101 @@ impl MappingLeaf {
         }
         if let JsonValue::Array(els) = json_val {
             if self.cardinality == Cardinality::SingleValued {
+                if strictness == Strictness::Lenient {
+                    record_type_mismatch(TypeMismatchOutcome::Dumped);
+                    insert_dynamic_json_val(path, JsonValue::Array(els), dynamic_json_obj);
+                    return Ok(());
+                }
+                record_type_mismatch(TypeMismatchOutcome::Rejected);
                 return Err(DocParsingError::MultiValuesNotSupported(path.join(".")));
             }
+            let mut mismatched_json_vals = Vec::new();
+
             for el_json_val in els {
                 if el_json_val.is_null() {
                     // We just ignore `null`.
                     continue;
                 }
-                if !self.concatenate.is_empty() {
-                    let concat_values = self
-                        .typ
-                        .tantivy_value_from_json(el_json_val.clone())
-                        .map_err(|err_msg| DocParsingError::ValueError(path.join("."), err_msg))?;
-                    for concat_value in concat_values {
-                        for field in &self.concatenate {
-                            document.add_field_value(*field, &concat_value);
-                        }
-                    }
+                if let Some(mismatched_json_val) =
+                    self.add_json_val(el_json_val, strictness, document, path)?
+                {
+                    mismatched_json_vals.push(mismatched_json_val);
                 }
-                let value = self
-                    .typ
-                    .value_from_json(el_json_val)
-                    .map_err(|err_msg| DocParsingError::ValueError(path.join("."), err_msg))?;
-                document.add_field_value(self.field, &value);
+            }
+            if !mismatched_json_vals.is_empty() {
+                insert_dynamic_json_val(
+                    path,
+                    JsonValue::Array(mismatched_json_vals),
+                    dynamic_json_obj,
+                );
             }
             return Ok(());
         }
+        if let Some(mismatched_json_val) =
+            self.add_json_val(json_val, strictness, document, path)?
+        {
+            insert_dynamic_json_val(path, mismatched_json_val, dynamic_json_obj);
+        }
+        Ok(())
+    }
 
-        if !self.concatenate.is_empty() {
-            let concat_values = self
-                .typ
-                .tantivy_value_from_json(json_val.clone())
-                .map_err(|err_msg| DocParsingError::ValueError(path.join("."), err_msg))?;
-            for concat_value in concat_values {
-                for field in &self.concatenate {
-                    document.add_field_value(*field, &concat_value);
-                }
+    /// Adds a single JSON value to the document according to the strictness level. In lenient
+    /// mode, the value is returned when it cannot be converted to the type of the leaf so that it
+    /// can be captured in the dynamic field.
+    fn add_json_val(
+        &self,
+        json_val: JsonValue,
+        strictness: Strictness,
+        document: &mut Document,
+        path: &[String],
+    ) -> Result<Option<JsonValue>, DocParsingError> {
+        if strictness == Strictness::Strict {
+            self.try_add_json_val(json_val, document)
+                .map_err(|err_msg| {
+                    record_type_mismatch(TypeMismatchOutcome::Rejected);
+                    DocParsingError::ValueError(path.join("."), err_msg)
+                })?;
+            return Ok(None);
+        }
+        let Err(err_msg) = self.try_add_json_val(json_val.clone(), document) else {
+            return Ok(None);
+        };
+        if let Some(coerced_json_val) = self.typ.coerce_json_val(&json_val) {
+            if self.try_add_json_val(coerced_json_val, document).is_ok() {
+                record_type_mismatch(TypeMismatchOutcome::Coerced);
+                rate_limited_warn!(
+                    limit_per_min = 10,
+                    "coerced value `{json_val}` of field `{}`: {err_msg}",
+                    path.join(".")
+                );
+                return Ok(None);
+            }
+        }
+        if strictness == Strictness::Lenient {
+            record_type_mismatch(TypeMismatchOutcome::Dumped);
+            return Ok(Some(json_val));
+        }
+        record_type_mismatch(TypeMismatchOutcome::Rejected);
+        Err(DocParsingError::ValueError(path.join("."), err_msg))
+    }
+
+    /// Converts a single JSON value and adds it to the document. Nothing is added if the
+    /// conversion fails.
+    fn try_add_json_val(&self, json_val: JsonValue, document: &mut Document) -> Result<(), String> {
+        let concat_values: Vec<TantivyValue> = if self.concatenate.is_empty() {
+            Vec::new()
+        } else {
+            self.typ
+                .tantivy_value_from_json(json_val.clone())?
+                .collect()
+        };
+        let value = self.typ.value_from_json(json_val)?;
+
+        for concat_value in &concat_values {
+            for field in &self.concatenate {
+                document.add_field_value(*field, concat_value);
             }
         }
-        let value = self
-            .typ
-            .value_from_json(json_val)
-            .map_err(|err_msg| DocParsingError::ValueError(path.join("."), err_msg))?;
         document.add_field_value(self.field, &value);
         Ok(())
     }
@@ -731,6 +865,19 @@ pub(crate) struct MappingNode {
     branches_order: Vec<String>,
 }
 
+/// Captures a JSON value whose type does not match its field mapping in the dynamic field.
+fn insert_dynamic_json_val(
+    path: &[String],
+    json_val: JsonValue,
+    dynamic_json_obj: &mut serde_json::Map<String, JsonValue>,
+) {
+    let Some((field_name, parent_path)) = path.split_last() else {
+        return;
+    };
+    let dynamic_json_obj_after_path = get_or_insert_path(parent_path, dynamic_json_obj);
+    dynamic_json_obj_after_path.insert(field_name.clone(), json_val);
+}
+
 fn get_or_insert_path<'a>(
     path: &[String],
     mut dynamic_json_obj: &'a mut serde_json::Map<String, JsonValue>,
@@ -796,6 +943,126 @@ impl MappingNode {
         }
     }
 
+    /// Returns true if at least one text field of the mapping tree defines redaction rules.
+    pub fn has_redaction_rules(&self) -> bool {
+        self.branches.values().any(|child_tree| match child_tree {
+            MappingTree::Leaf(mapping_leaf) => {
+                if let LeafType::Text(text_options) = &mapping_leaf.typ {
+                    !text_options.redact.is_empty()
+                } else {
+                    false
+                }
+            }
+            MappingTree::Node(mapping_node) => mapping_node.has_redaction_rules(),
+        })
+    }
+
+    /// Returns true if the mapping tree contains at least one decimal field.
+    pub fn has_decimal_fields(&self) -> bool {
+        self.branches.values().any(|child_tree| match child_tree {
+            MappingTree::Leaf(mapping_leaf) => matches!(mapping_leaf.typ, LeafType::Decimal(_)),
+            MappingTree::Node(mapping_node) => mapping_node.has_decimal_fields(),
+        })
+    }
+
+    /// Applies the redaction rules of the text fields to the matching values of `json_obj`.
+    pub fn redact_json_obj(&self, json_obj: &mut serde_json::Map<String, JsonValue>) {
+        for (field_name, json_val) in json_obj.iter_mut() {
+            let Some(child_tree) = self.branches.get(field_name) else {
+                continue;
+            };
+            match (child_tree, json_val) {
+                (MappingTree::Leaf(mapping_leaf), json_val) => {
+                    if let LeafType::Text(text_options) = &mapping_leaf.typ {
+                        redact_json_val(&text_options.redact, json_val);
+                    }
+                }
+                (MappingTree::Node(mapping_node), JsonValue::Object(child_json_obj)) => {
+                    mapping_node.redact_json_obj(child_json_obj);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns true if at least one field of the mapping tree defines value limits.
+    pub fn has_value_limits(&self) -> bool {
+        self.branches.values().any(|child_tree| match child_tree {
+            MappingTree::Leaf(mapping_leaf) => mapping_leaf.typ.value_limits().is_some(),
+            MappingTree::Node(mapping_node) => mapping_node.has_value_limits(),
+        })
+    }
+
+    /// Enforces the value limits of the fields on the matching values of `json_obj`. The values
+    /// of the unmapped fields are subject to `dynamic_value_limits_opt`.
+    pub fn enforce_value_limits(
+        &self,
+        json_obj: &mut serde_json::Map<String, JsonValue>,
+        dynamic_value_limits_opt: Option<&ValueLimits>,
+        path: &mut Vec<String>,
+    ) -> Result<(), DocParsingError> {
+        for (field_name, json_val) in json_obj.iter_mut() {
+            let value_limits_opt = match self.branches.get(field_name) {
+                Some(MappingTree::Leaf(mapping_leaf)) => mapping_leaf.typ.value_limits(),
+                Some(MappingTree::Node(mapping_node)) => {
+                    if let JsonValue::Object(child_json_obj) = json_val {
+                        path.push(field_name.clone());
+                        mapping_node.enforce_value_limits(
+                            child_json_obj,
+                            dynamic_value_limits_opt,
+                            path,
+                        )?;
+                        path.pop();
+                    }
+                    continue;
+                }
+                None => dynamic_value_limits_opt,
+            };
+            if let Some(value_limits) = value_limits_opt {
+                value_limits.enforce(json_val).map_err(|err_msg| {
+                    path.push(field_name.clone());
+                    DocParsingError::ValueError(path.join("."), err_msg)
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the values of `json_obj` against the value limits rejecting the documents, so that
+    /// the documents that would be rejected at indexing are rejected when they are validated.
+    pub fn check_value_limits<'a>(
+        &self,
+        json_obj: &'a BorrowedJsonMap,
+        dynamic_value_limits_opt: Option<&ValueLimits>,
+        path: &mut Vec<&'a str>,
+    ) -> Result<(), DocParsingError> {
+        for (field_name, json_val) in json_obj.iter() {
+            let value_limits_opt = match self.branches.get(field_name) {
+                Some(MappingTree::Leaf(mapping_leaf)) => mapping_leaf.typ.value_limits(),
+                Some(MappingTree::Node(mapping_node)) => {
+                    if let BorrowedJsonValue::Object(child_json_obj) = json_val {
+                        path.push(field_name);
+                        mapping_node.check_value_limits(
+                            child_json_obj,
+                            dynamic_value_limits_opt,
+                            path,
+                        )?;
+                        path.pop();
+                    }
+                    continue;
+                }
+                None => dynamic_value_limits_opt,
+            };
+            if let Some(value_limits) = value_limits_opt {
+                value_limits.check(json_val).map_err(|err_msg| {
+                    path.push(field_name);
+                    DocParsingError::ValueError(path.join("."), err_msg)
+                })?;
+            }
+        }
+        Ok(())
+    }
+
     #[cfg(test)]
     pub fn num_fields(&self) -> usize {
         self.branches.len()
@@ -824,12 +1091,13 @@ impl MappingNode {
         &self,
         json_obj: &'a BorrowedJsonMap,
         strict_mode: bool,
+        strictness: Strictness,
         path: &mut Vec<&'a str>,
     ) -> Result<(), DocParsingError> {
         for (field_name, json_val) in json_obj.iter() {
             if let Some(child_tree) = self.branches.get(field_name) {
                 path.push(field_name);
-                child_tree.validate_from_json(json_val, path, strict_mode)?;
+                child_tree.validate_from_json(json_val, path, strict_mode, strictness)?;
                 path.pop();
             } else if strict_mode {
                 path.push(field_name);
@@ -844,6 +1112,7 @@ impl MappingNode {
         &self,
         json_obj: serde_json::Map<String, JsonValue>,
         mode: ModeType,
+        strictness: Strictness,
         document: &mut Document,
         path: &mut Vec<String>,
         dynamic_json_obj: &mut serde_json::Map<String, JsonValue>,
@@ -851,7 +1120,14 @@ impl MappingNode {
         for (field_name, val) in json_obj {
             if let Some(child_tree) = self.branches.get(&field_name) {
                 path.push(field_name);
-                child_tree.doc_from_json(val, mode, document, path, dynamic_json_obj)?;
+                child_tree.doc_from_json(
+                    val,
+                    mode,
+                    strictness,
+                    document,
+                    path,
+                    dynamic_json_obj,
+                )?;
                 path.pop();
             } else {
                 match mode {
@@ -888,6 +1164,25 @@ impl MappingNode {
     }
 }
 
+fn redact_json_val(redactor: &Redactor, json_val: &mut JsonValue) {
+    if redactor.is_empty() {
+        return;
+    }
+    match json_val {
+        JsonValue::String(text) => {
+            redactor.redact(text);
+        }
+        JsonValue::Array(json_vals) => {
+            for json_val in json_vals {
+                if let JsonValue::String(text) = json_val {
+                    redactor.redact(text);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
 impl From<MappingTree> for FieldMappingType {
     fn from(mapping_tree: MappingTree) -> Self {
         match mapping_tree {
@@ -906,6 +1201,7 @@ impl From<MappingLeaf> for FieldMappingType {
             LeafType::I64(opt) => FieldMappingType::I64(opt, leaf.cardinality),
             LeafType::U64(opt) => FieldMappingType::U64(opt, leaf.cardinality),
             LeafType::F64(opt) => FieldMappingType::F64(opt, leaf.cardinality),
+            LeafType::Decimal(opt) => FieldMappingType::Decimal(opt, leaf.cardinality),
             LeafType::Bool(opt) => FieldMappingType::Bool(opt, leaf.cardinality),
             LeafType::IpAddr(opt) => FieldMappingType::IpAddr(opt, leaf.cardinality),
             LeafType::DateTime(opt) => FieldMappingType::DateTime(opt, leaf.cardinality),
@@ -933,14 +1229,17 @@ impl MappingTree {
         json_value: &'a BorrowedJsonValue<'a>,
         field_path: &mut Vec<&'a str>,
         strict_mode: bool,
+        strictness: Strictness,
     ) -> Result<(), DocParsingError> {
         match self {
             MappingTree::Leaf(mapping_leaf) => {
-                mapping_leaf.validate_from_json(json_value, field_path)
+                mapping_leaf.validate_from_json(json_value, field_path, strictness)
             }
             MappingTree::Node(mapping_node) => {
                 if let Some(json_obj) = json_value.as_object() {
-                    mapping_node.validate_from_json(json_obj, strict_mode, field_path)
+                    mapping_node.validate_from_json(json_obj, strict_mode, strictness, field_path)
+                } else if strictness == Strictness::Lenient {
+                    Ok(())
                 } else {
                     Err(DocParsingError::ValueError(
                         field_path.join("."),
@@ -955,17 +1254,29 @@ impl MappingTree {
         &self,
         json_value: JsonValue,
         mode: ModeType,
+        strictness: Strictness,
         document: &mut Document,
         path: &mut Vec<String>,
         dynamic_json_obj: &mut serde_json::Map<String, JsonValue>,
     ) -> Result<(), DocParsingError> {
         match self {
             MappingTree::Leaf(mapping_leaf) => {
-                mapping_leaf.doc_from_json(json_value, document, path)
+                mapping_leaf.doc_from_json(json_value, strictness, document, path, dynamic_json_obj)
             }
             MappingTree::Node(mapping_node) => {
                 if let JsonValue::Object(json_obj) = json_value {
-                    mapping_node.doc_from_json(json_obj, mode, document, path, dynamic_json_obj)
+                    mapping_node.doc_from_json(
+                        json_obj,
+                        mode,
+                        strictness,
+                        document,
+                        path,
+                        dynamic_json_obj,
+                    )
+                } else if strictness == Strictness::Lenient {
+                    record_type_mismatch(TypeMismatchOutcome::Dumped);
+                    insert_dynamic_json_val(path, json_value, dynamic_json_obj);
+                    Ok(())
                 } else {
                     Err(DocParsingError::ValueError(
                         path.join("."),
@@ -1101,6 +1412,22 @@ fn get_numeric_options_for_numeric_field(
     numeric_options
 }
 
+fn get_numeric_options_for_decimal_field(
+    quickwit_decimal_options: &QuickwitDecimalOptions,
+) -> NumericOptions {
+    let mut numeric_options = NumericOptions::default();
+    if quickwit_decimal_options.stored {
+        numeric_options = numeric_options.set_stored();
+    }
+    if quickwit_decimal_options.indexed {
+        numeric_options = numeric_options.set_indexed();
+    }
+    if quickwit_decimal_options.fast {
+        numeric_options = numeric_options.set_fast();
+    }
+    numeric_options
+}
+
 fn get_date_time_options(quickwit_date_time_options: &QuickwitDateTimeOptions) -> DateOptions {
     let mut date_time_options = DateOptions::default();
     if quickwit_date_time_options.stored {
@@ -1248,6 +1575,17 @@ fn build_mapping_from_field_type<'a>(
             };
             Ok((MappingTree::Leaf(mapping_leaf), Vec::new()))
         }
+        FieldMappingType::Decimal(options, cardinality) => {
+            let numeric_options = get_numeric_options_for_decimal_field(options);
+            let field = schema_builder.add_i64_field(&field_name, numeric_options);
+            let mapping_leaf = MappingLeaf {
+                field,
+                typ: LeafType::Decimal(options.clone()),
+                cardinality: *cardinality,
+                concatenate: Vec::new(),
+            };
+            Ok((MappingTree::Leaf(mapping_leaf), Vec::new()))
+        }
         FieldMappingType::Bool(options, cardinality) => {
             let numeric_options = get_numeric_options_for_bool_field(options);
             let field = schema_builder.add_bool_field(&field_name, numeric_options);
@@ -1338,6 +1676,7 @@ mod tests {
         MapOrArrayIter, MappingLeaf,
     };
     use crate::doc_mapper::date_time_type::QuickwitDateTimeOptions;
+    use crate::doc_mapper::decimal_type::QuickwitDecimalOptions;
     use crate::doc_mapper::field_mapping_entry::{
         BinaryFormat, QuickwitBoolOptions, QuickwitBytesOptions, QuickwitIpAddrOptions,
         QuickwitNumericOptions, QuickwitTextOptions,
@@ -1523,7 +1862,13 @@ mod tests {
         let mut document = Document::default();
         let mut path = Vec::new();
         leaf_entry
-            .doc_from_json(json!([true, false, true]), &mut document, &mut path)
+            .doc_from_json(
+                json!([true, false, true]),
+                Strictness::Strict,
+                &mut document,
+                &mut path,
+                &mut Default::default(),
+            )
             .unwrap();
         assert_eq!(document.len(), 3);
         let values: Vec<bool> = document
@@ -1575,7 +1920,13 @@ mod tests {
         let mut document = Document::default();
         let mut path = Vec::new();
         leaf_entry
-            .doc_from_json(serde_json::json!([10u64, 20u64]), &mut document, &mut path)
+            .doc_from_json(
+                serde_json::json!([10u64, 20u64]),
+                Strictness::Strict,
+                &mut document,
+                &mut path,
+                &mut Default::default(),
+            )
             .unwrap();
         assert_eq!(document.len(), 2);
         let values: Vec<i64> = document
@@ -1598,7 +1949,13 @@ mod tests {
         let mut document = Document::default();
         let mut path = Vec::new();
         leaf_entry
-            .doc_from_json(serde_json::json!(null), &mut document, &mut path)
+            .doc_from_json(
+                serde_json::json!(null),
+                Strictness::Strict,
+                &mut document,
+                &mut path,
+                &mut Default::default(),
+            )
             .unwrap();
         assert_eq!(document.len(), 0);
     }
@@ -1616,7 +1973,13 @@ mod tests {
         let mut document = Document::default();
         let mut path = Vec::new();
         leaf_entry
-            .doc_from_json(serde_json::json!(10u64), &mut document, &mut path)
+            .doc_from_json(
+                serde_json::json!(10u64),
+                Strictness::Strict,
+                &mut document,
+                &mut path,
+                &mut Default::default(),
+            )
             .unwrap();
         assert_eq!(document.len(), 1);
         assert_eq!(document.get_first(field).unwrap().as_i64().unwrap(), 10i64);
@@ -1637,8 +2000,10 @@ mod tests {
         let parse_err = leaf_entry
             .doc_from_json(
                 serde_json::json!([10u64, [1u64, 2u64]]),
+                Strictness::Strict,
                 &mut document,
                 &mut path,
+                &mut Default::default(),
             )
             .unwrap_err();
         assert_eq!(
@@ -1751,6 +2116,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_bytes_max_num_bytes() {
+        let typ = LeafType::Bytes(QuickwitBytesOptions {
+            input_format: BinaryFormat::Hex,
+            max_num_bytes: Some(4),
+            ..QuickwitBytesOptions::default()
+        });
+        let value = typ.value_from_json(json!("deadbeef")).unwrap();
+        assert_eq!((&value).as_bytes().unwrap(), [0xde, 0xad, 0xbe, 0xef]);
+
+        let error = typ.value_from_json(json!("deadbeef00")).unwrap_err();
+        assert_eq!(
+            error,
+            "bytes value of 5 bytes exceeds the maximum size of 4 bytes"
+        );
+        let json_val: serde_json_borrow::Value = serde_json::from_str(r#""deadbeef00""#).unwrap();
+        let error = typ.validate_from_json(&json_val).unwrap_err();
+        assert_eq!(
+            error,
+            "bytes value of 5 bytes exceeds the maximum size of 4 bytes"
+        );
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        let decimal_options: QuickwitDecimalOptions =
+            serde_json::from_value(json!({"scale": 3})).unwrap();
+        let typ = LeafType::Decimal(decimal_options);
+
+        let value = typ.value_from_json(json!("-1.5")).unwrap();
+        assert_eq!(value, TantivyValue::I64(-1500));
+
+        let value = typ.value_from_json(json!(0.125)).unwrap();
+        assert_eq!(value, TantivyValue::I64(125));
+
+        let error = typ.value_from_json(json!("0.1234")).unwrap_err();
+        assert_eq!(
+            error,
+            "decimal `0.1234` has more than 3 digits after the decimal point"
+        );
+        let json_val: serde_json_borrow::Value = serde_json::from_str("0.1234").unwrap();
+        typ.validate_from_json(&json_val).unwrap_err();
+
+        let json_val: serde_json_borrow::Value = serde_json::from_str(r#""1.25""#).unwrap();
+        typ.validate_from_json(&json_val).unwrap();
+    }
+
     #[test]
     fn test_parse_bytes_number_should_err() {
         let typ = LeafType::Bytes(QuickwitBytesOptions::default());
@@ -1786,8 +2198,10 @@ mod tests {
                     "dGhpcyBpcyBhIGJhc2U2NCBlbmNvZGVkIHN0cmluZw==",
                     "dGhpcyBpcyBhIGJhc2U2NCBlbmNvZGVkIHN0cmluZw=="
                 ]),
+                Strictness::Strict,
                 &mut document,
                 &mut path,
+                &mut Default::default(),
             )
             .unwrap();
         assert_eq!(document.len(), 2);
@@ -13,14 +13,18 @@
 // limitations under the License.
 
 mod date_time_type;
+mod decimal_type;
 mod doc_mapper_builder;
 mod doc_mapper_impl;
 mod field_mapping_entry;
 mod field_mapping_type;
 mod field_presence;
+mod logical_timestamp;
 mod mapping_tree;
+mod redaction;
 mod tantivy_val_to_json;
 mod tokenizer_entry;
+mod value_limits;
 
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
@@ -40,13 +44,18 @@ pub(crate) use field_mapping_entry::{
 #[cfg(test)]
 pub(crate) use field_mapping_entry::{QuickwitNumericOptions, QuickwitTextOptions};
 pub use field_mapping_type::FieldMappingType;
+pub use redaction::RedactionRule;
+pub(crate) use redaction::Redactor;
 use serde_json::Value as JsonValue;
 use tantivy::schema::{Field, FieldType};
 use tantivy::Term;
+pub use tantivy_val_to_json::tantivy_value_to_json;
 pub use tokenizer_entry::{analyze_text, TokenizerConfig, TokenizerEntry};
 pub(crate) use tokenizer_entry::{
-    NgramTokenizerOption, RegexTokenizerOption, TokenFilterType, TokenizerType,
+    NgramTokenizerOption, RegexTokenizerOption, StopWordsFilterOption, StopWordsLanguage,
+    TokenFilterType, TokenizerType,
 };
+pub use value_limits::{ValueLimitPolicy, ValueLimits};
 
 /// Function used with serde to initialize boolean value at true if there is no value in json.
 fn default_as_true() -> bool {
@@ -290,8 +299,8 @@ mod tests {
         let query_ast = UserInputQuery {
             user_text: "json_field.toto.titi:hello".to_string(),
             default_fields: None,
-            default_operator: BooleanOperand::And,
-            lenient: false,
+            default_operator: Some(BooleanOperand::And),
+            lenient: Some(false),
         }
         .parse_user_query(&[])
         .unwrap();
@@ -446,6 +455,61 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_doc_strictness() {
+        let doc_mapper_json = |strictness: &str| {
+            format!(
+                r#"{{
+                    "strictness": "{strictness}",
+                    "field_mappings": [
+                        {{
+                            "name": "count",
+                            "type": "u64",
+                            "coerce": false
+                        }},
+                        {{
+                            "name": "attributes",
+                            "type": "object",
+                            "field_mappings": [
+                                {{
+                                    "name": "is_error",
+                                    "type": "bool"
+                                }}
+                            ]
+                        }}
+                    ]
+                }}"#
+            )
+        };
+        let doc_mapper = serde_json::from_str::<DocMapper>(&doc_mapper_json("strict")).unwrap();
+        assert!(matches!(
+            test_validate_doc_aux(&doc_mapper, r#"{ "count": "42" }"#).unwrap_err(),
+            DocParsingError::ValueError(_, _)
+        ));
+
+        let doc_mapper = serde_json::from_str::<DocMapper>(&doc_mapper_json("coerce")).unwrap();
+        test_validate_doc_aux(
+            &doc_mapper,
+            r#"{ "count": "42", "attributes": { "is_error": "false" } }"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            test_validate_doc_aux(&doc_mapper, r#"{ "count": "forty-two" }"#).unwrap_err(),
+            DocParsingError::ValueError(_, _)
+        ));
+        assert!(matches!(
+            test_validate_doc_aux(&doc_mapper, r#"{ "attributes": "error" }"#).unwrap_err(),
+            DocParsingError::ValueError(_, _)
+        ));
+
+        let doc_mapper = serde_json::from_str::<DocMapper>(&doc_mapper_json("lenient")).unwrap();
+        test_validate_doc_aux(
+            &doc_mapper,
+            r#"{ "count": ["forty-two"], "attributes": "error" }"#,
+        )
+        .unwrap();
+    }
+
     #[test]
     fn test_validate_doc_timestamp() {
         const JSON_CONFIG_TS_AT_ROOT: &str = r#"{
@@ -238,6 +238,13 @@ pub fn formatted_tantivy_value_to_json(
         LeafType::F64(numeric_options) => value_to_float(value, numeric_options),
         LeafType::U64(numeric_options) => value_to_u64(value, numeric_options),
         LeafType::I64(numeric_options) => value_to_i64(value, numeric_options),
+        LeafType::Decimal(decimal_options) => {
+            if let TantivyValue::I64(scaled_value) = value {
+                return decimal_options.format_to_json(scaled_value);
+            } else {
+                Err(value)
+            }
+        }
         LeafType::Json(_) => {
             if let TantivyValue::Object(obj) = value {
                 // TODO do we want to allow almost everything here?
@@ -12,12 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use anyhow::Context;
-use quickwit_query::{CodeTokenizer, DEFAULT_REMOVE_TOKEN_LENGTH};
+use anyhow::{ensure, Context};
+use quickwit_query::{CodeTokenizer, TrimFilter, DEFAULT_REMOVE_TOKEN_LENGTH};
 use serde::{Deserialize, Serialize};
 use tantivy::tokenizer::{
-    AsciiFoldingFilter, LowerCaser, NgramTokenizer, RegexTokenizer, RemoveLongFilter,
-    SimpleTokenizer, TextAnalyzer, Token,
+    AsciiFoldingFilter, Language, LowerCaser, NgramTokenizer, RawTokenizer, RegexTokenizer,
+    RemoveLongFilter, SimpleTokenizer, StopWordFilter, TextAnalyzer, Token,
 };
 
 /// A `TokenizerEntry` defines a custom tokenizer with its name and configuration.
@@ -43,6 +43,7 @@ impl TokenizerConfig {
     /// Build a `TextAnalyzer` from a `TokenizerConfig`.
     pub fn text_analyzer(&self) -> anyhow::Result<TextAnalyzer> {
         let mut text_analyzer_builder = match &self.tokenizer_type {
+            TokenizerType::Raw => TextAnalyzer::builder(RawTokenizer::default()).dynamic(),
             TokenizerType::Simple => TextAnalyzer::builder(SimpleTokenizer::default()).dynamic(),
             #[cfg(any(test, feature = "multilang"))]
             TokenizerType::Multilang => {
@@ -62,7 +63,7 @@ impl TokenizerConfig {
             }
         };
         for filter in &self.filters {
-            match filter.tantivy_token_filter_enum() {
+            match filter.tantivy_token_filter_enum()? {
                 TantivyTokenFilterEnum::RemoveLong(token_filter) => {
                     text_analyzer_builder = text_analyzer_builder.filter_dynamic(token_filter);
                 }
@@ -72,6 +73,14 @@ impl TokenizerConfig {
                 TantivyTokenFilterEnum::AsciiFolding(token_filter) => {
                     text_analyzer_builder = text_analyzer_builder.filter_dynamic(token_filter);
                 }
+                TantivyTokenFilterEnum::Trim(token_filter) => {
+                    text_analyzer_builder = text_analyzer_builder.filter_dynamic(token_filter);
+                }
+                TantivyTokenFilterEnum::StopWords(token_filters) => {
+                    for token_filter in token_filters {
+                        text_analyzer_builder = text_analyzer_builder.filter_dynamic(token_filter);
+                    }
+                }
             }
         }
         Ok(text_analyzer_builder.build())
@@ -95,6 +104,10 @@ pub enum TokenFilterType {
     RemoveLong,
     LowerCaser,
     AsciiFolding,
+    /// Removes the leading and trailing whitespaces of the tokens. Combined with the `raw`
+    /// tokenizer, and the `lower_caser` and `ascii_folding` filters, it normalizes keywords.
+    Trim,
+    StopWords(StopWordsFilterOption),
 }
 
 /// Tantivy token filter enum to build
@@ -103,16 +116,93 @@ enum TantivyTokenFilterEnum {
     RemoveLong(RemoveLongFilter),
     LowerCaser(LowerCaser),
     AsciiFolding(AsciiFoldingFilter),
+    Trim(TrimFilter),
+    StopWords(Vec<StopWordFilter>),
 }
 
 impl TokenFilterType {
-    fn tantivy_token_filter_enum(&self) -> TantivyTokenFilterEnum {
-        match &self {
+    fn tantivy_token_filter_enum(&self) -> anyhow::Result<TantivyTokenFilterEnum> {
+        let token_filter = match &self {
             Self::RemoveLong => TantivyTokenFilterEnum::RemoveLong(RemoveLongFilter::limit(
                 DEFAULT_REMOVE_TOKEN_LENGTH,
             )),
             Self::LowerCaser => TantivyTokenFilterEnum::LowerCaser(LowerCaser),
             Self::AsciiFolding => TantivyTokenFilterEnum::AsciiFolding(AsciiFoldingFilter),
+            Self::Trim => TantivyTokenFilterEnum::Trim(TrimFilter),
+            Self::StopWords(options) => TantivyTokenFilterEnum::StopWords(options.token_filters()?),
+        };
+        Ok(token_filter)
+    }
+}
+
+/// Removes the stop words of the given languages, as well as user-supplied words. The built-in
+/// lists are lowercase, so this filter is usually placed after the `lower_caser` filter.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StopWordsFilterOption {
+    #[serde(default)]
+    pub languages: Vec<StopWordsLanguage>,
+    #[serde(default)]
+    pub words: Vec<String>,
+}
+
+impl StopWordsFilterOption {
+    fn token_filters(&self) -> anyhow::Result<Vec<StopWordFilter>> {
+        ensure!(
+            !self.languages.is_empty() || !self.words.is_empty(),
+            "stop words filter must define at least one language or word"
+        );
+        let mut token_filters: Vec<StopWordFilter> = self
+            .languages
+            .iter()
+            .map(|language| {
+                StopWordFilter::new(language.tantivy_language())
+                    .expect("built-in stop word list should exist for every supported language")
+            })
+            .collect();
+
+        if !self.words.is_empty() {
+            token_filters.push(StopWordFilter::remove(self.words.iter().cloned()));
+        }
+        Ok(token_filters)
+    }
+}
+
+/// Languages with a built-in stop word list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StopWordsLanguage {
+    Danish,
+    Dutch,
+    English,
+    Finnish,
+    French,
+    German,
+    Hungarian,
+    Italian,
+    Norwegian,
+    Portuguese,
+    Russian,
+    Spanish,
+    Swedish,
+}
+
+impl StopWordsLanguage {
+    fn tantivy_language(&self) -> Language {
+        match self {
+            Self::Danish => Language::Danish,
+            Self::Dutch => Language::Dutch,
+            Self::English => Language::English,
+            Self::Finnish => Language::Finnish,
+            Self::French => Language::French,
+            Self::German => Language::German,
+            Self::Hungarian => Language::Hungarian,
+            Self::Italian => Language::Italian,
+            Self::Norwegian => Language::Norwegian,
+            Self::Portuguese => Language::Portuguese,
+            Self::Russian => Language::Russian,
+            Self::Spanish => Language::Spanish,
+            Self::Swedish => Language::Swedish,
         }
     }
 }
@@ -123,6 +213,8 @@ pub enum TokenizerType {
     #[cfg(any(test, feature = "multilang"))]
     Multilang,
     Ngram(NgramTokenizerOption),
+    /// Does not tokenize the text. Used with filters to normalize keywords.
+    Raw,
     Regex(RegexTokenizerOption),
     Simple,
     SourceCode,
@@ -145,7 +237,7 @@ pub struct RegexTokenizerOption {
 
 #[cfg(test)]
 mod tests {
-    use super::{NgramTokenizerOption, TokenizerType};
+    use super::{analyze_text, NgramTokenizerOption, TokenizerType};
     use crate::doc_mapper::RegexTokenizerOption;
     use crate::TokenizerEntry;
 
@@ -238,4 +330,63 @@ mod tests {
             _ => panic!("Unexpected tokenizer type"),
         }
     }
+
+    #[test]
+    fn test_tokenizer_entry_raw_normalizer() {
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "keyword_normalizer",
+                "type": "raw",
+                "filters": ["trim", "lower_caser", "ascii_folding"]
+            }
+            "#,
+        )
+        .unwrap();
+        let tokens = analyze_text(" Café Crème\n", &tokenizer_config_entry.config).unwrap();
+        let token_texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(token_texts, ["cafe creme"]);
+    }
+
+    #[test]
+    fn test_tokenizer_entry_stop_words() {
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "simple",
+                "filters": [
+                    "lower_caser",
+                    {
+                        "stop_words": {
+                            "languages": ["english", "french"],
+                            "words": ["quickwit"]
+                        }
+                    }
+                ]
+            }
+            "#,
+        )
+        .unwrap();
+        let tokens = analyze_text(
+            "The Quickwit engine et le moteur",
+            &tokenizer_config_entry.config,
+        )
+        .unwrap();
+        let token_texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(token_texts, ["engine", "moteur"]);
+
+        let empty_stop_words_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "simple",
+                "filters": [{"stop_words": {}}]
+            }
+            "#,
+        )
+        .unwrap();
+        let error = empty_stop_words_entry.config.text_analyzer().unwrap_err();
+        assert!(error.to_string().contains("at least one language or word"));
+    }
 }
@@ -0,0 +1,263 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::num::NonZeroUsize;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use serde_json_borrow::Value as BorrowedJsonValue;
+
+use crate::metrics::DOC_MAPPER_METRICS;
+
+/// Defines how the values exceeding the limits of a field are handled.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueLimitPolicy {
+    /// Drops the values in excess and truncates the values that are too long.
+    #[default]
+    Truncate,
+    /// Rejects the document.
+    Reject,
+}
+
+impl ValueLimitPolicy {
+    fn outcome(&self) -> &'static str {
+        match self {
+            Self::Truncate => "truncated",
+            Self::Reject => "rejected",
+        }
+    }
+}
+
+/// Limits on the number and the length of the values of a field. For JSON fields and the dynamic
+/// mapping, the limits apply to each nested array and text value.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ValueLimits {
+    /// Maximum number of values of an array.
+    #[schema(value_type = Option<usize>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_num_values: Option<NonZeroUsize>,
+    /// Maximum length of a text value in bytes.
+    #[schema(value_type = Option<usize>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_value_length: Option<NonZeroUsize>,
+    /// Whether the values exceeding the limits are truncated or the document is rejected.
+    #[serde(default)]
+    pub on_limit_exceeded: ValueLimitPolicy,
+}
+
+impl ValueLimits {
+    /// Enforces the limits on `json_val`, truncating the values in place or returning an error
+    /// message depending on the policy.
+    pub(crate) fn enforce(&self, json_val: &mut JsonValue) -> Result<(), String> {
+        match json_val {
+            JsonValue::String(text) => {
+                let Some(max_value_length) = self.max_value_length else {
+                    return Ok(());
+                };
+                if text.len() <= max_value_length.get() {
+                    return Ok(());
+                }
+                self.record_limit_exceeded("value_length");
+
+                if self.on_limit_exceeded == ValueLimitPolicy::Reject {
+                    return Err(value_length_exceeded_message(text.len(), max_value_length));
+                }
+                let mut new_len = max_value_length.get();
+
+                while !text.is_char_boundary(new_len) {
+                    new_len -= 1;
+                }
+                text.truncate(new_len);
+            }
+            JsonValue::Array(json_vals) => {
+                if let Some(max_num_values) = self.max_num_values {
+                    if json_vals.len() > max_num_values.get() {
+                        self.record_limit_exceeded("num_values");
+
+                        if self.on_limit_exceeded == ValueLimitPolicy::Reject {
+                            return Err(num_values_exceeded_message(
+                                json_vals.len(),
+                                max_num_values,
+                            ));
+                        }
+                        json_vals.truncate(max_num_values.get());
+                    }
+                }
+                for json_val in json_vals {
+                    self.enforce(json_val)?;
+                }
+            }
+            JsonValue::Object(json_obj) => {
+                for json_val in json_obj.values_mut() {
+                    self.enforce(json_val)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Returns an error message if `json_val` exceeds the limits and the policy is to reject the
+    /// document. Under the truncate policy, the values are accepted as they are truncated when
+    /// the document is indexed.
+    pub(crate) fn check(&self, json_val: &BorrowedJsonValue) -> Result<(), String> {
+        if self.on_limit_exceeded != ValueLimitPolicy::Reject {
+            return Ok(());
+        }
+        match json_val {
+            BorrowedJsonValue::Str(text) => {
+                if let Some(max_value_length) = self.max_value_length {
+                    if text.len() > max_value_length.get() {
+                        self.record_limit_exceeded("value_length");
+                        return Err(value_length_exceeded_message(text.len(), max_value_length));
+                    }
+                }
+            }
+            BorrowedJsonValue::Array(json_vals) => {
+                if let Some(max_num_values) = self.max_num_values {
+                    if json_vals.len() > max_num_values.get() {
+                        self.record_limit_exceeded("num_values");
+                        return Err(num_values_exceeded_message(json_vals.len(), max_num_values));
+                    }
+                }
+                for json_val in json_vals {
+                    self.check(json_val)?;
+                }
+            }
+            BorrowedJsonValue::Object(json_obj) => {
+                for (_, json_val) in json_obj.iter() {
+                    self.check(json_val)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn record_limit_exceeded(&self, limit: &'static str) {
+        DOC_MAPPER_METRICS
+            .value_limits_exceeded_total
+            .with_label_values([limit, self.on_limit_exceeded.outcome()])
+            .inc();
+    }
+}
+
+fn value_length_exceeded_message(value_length: usize, max_value_length: NonZeroUsize) -> String {
+    format!(
+        "text value of {value_length} bytes exceeds the maximum value length of {max_value_length}"
+    )
+}
+
+fn num_values_exceeded_message(num_values: usize, max_num_values: NonZeroUsize) -> String {
+    format!("array of {num_values} values exceeds the maximum number of values of {max_num_values}")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn value_limits(
+        max_num_values: usize,
+        max_value_length: usize,
+        on_limit_exceeded: ValueLimitPolicy,
+    ) -> ValueLimits {
+        ValueLimits {
+            max_num_values: NonZeroUsize::new(max_num_values),
+            max_value_length: NonZeroUsize::new(max_value_length),
+            on_limit_exceeded,
+        }
+    }
+
+    #[test]
+    fn test_value_limits_truncate() {
+        let value_limits = value_limits(2, 4, ValueLimitPolicy::Truncate);
+
+        let mut json_val = json!(["abcdef", "ab", "abc"]);
+        value_limits.enforce(&mut json_val).unwrap();
+        assert_eq!(json_val, json!(["abcd", "ab"]));
+
+        let mut json_val = json!({"tags": [1, 2, 3], "nested": {"msg": "héhé"}});
+        value_limits.enforce(&mut json_val).unwrap();
+        assert_eq!(json_val, json!({"tags": [1, 2], "nested": {"msg": "hé"}}));
+
+        let mut json_val = json!(12345678);
+        value_limits.enforce(&mut json_val).unwrap();
+        assert_eq!(json_val, json!(12345678));
+    }
+
+    #[test]
+    fn test_value_limits_reject() {
+        let value_limits = value_limits(2, 4, ValueLimitPolicy::Reject);
+
+        let mut json_val = json!(["abcd", "ab"]);
+        value_limits.enforce(&mut json_val).unwrap();
+
+        let error = value_limits.enforce(&mut json!([1, 2, 3])).unwrap_err();
+        assert_eq!(
+            error,
+            "array of 3 values exceeds the maximum number of values of 2"
+        );
+        let error = value_limits
+            .enforce(&mut json!({"msg": "abcdef"}))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "text value of 6 bytes exceeds the maximum value length of 4"
+        );
+    }
+
+    #[test]
+    fn test_value_limits_check() {
+        fn check(value_limits: &ValueLimits, json: &str) -> Result<(), String> {
+            let json_val: BorrowedJsonValue = serde_json::from_str(json).unwrap();
+            value_limits.check(&json_val)
+        }
+        let value_limits = value_limits(2, 4, ValueLimitPolicy::Reject);
+        check(&value_limits, r#"["abcd", "ab"]"#).unwrap();
+
+        let error = check(&value_limits, "[1, 2, 3]").unwrap_err();
+        assert_eq!(
+            error,
+            "array of 3 values exceeds the maximum number of values of 2"
+        );
+        let error = check(&value_limits, r#"{"msg": ["abcdef"]}"#).unwrap_err();
+        assert_eq!(
+            error,
+            "text value of 6 bytes exceeds the maximum value length of 4"
+        );
+        // Values exceeding the limits are accepted when they are truncated.
+        let value_limits = self::value_limits(2, 4, ValueLimitPolicy::Truncate);
+        check(&value_limits, r#"["abcdef", "ab", "abc"]"#).unwrap();
+    }
+
+    #[test]
+    fn test_value_limits_serde() {
+        let value_limits: ValueLimits =
+            serde_json::from_str(r#"{"max_num_values": 100, "on_limit_exceeded": "reject"}"#)
+                .unwrap();
+        assert_eq!(
+            value_limits,
+            self::value_limits(100, 0, ValueLimitPolicy::Reject)
+        );
+
+        serde_json::from_str::<ValueLimits>(r#"{"max_num_values": 0}"#).unwrap_err();
+        serde_json::from_str::<ValueLimits>(r#"{"max_values": 10}"#).unwrap_err();
+    }
+}
@@ -34,6 +34,29 @@ pub enum ModeType {
     Dynamic,
 }
 
+/// Defines how the values whose type does not match their field mapping should be handled.
+#[derive(Clone, Copy, Default, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Strictness {
+    /// Strict: rejects the document. Values are only coerced for the numeric fields with the
+    /// `coerce` parameter enabled.
+    #[default]
+    Strict,
+    /// Coerce: converts the value to the type of the field when possible and rejects the document
+    /// otherwise.
+    Coerce,
+    /// Lenient: converts the value to the type of the field when possible and captures it in the
+    /// dynamic field otherwise.
+    Lenient,
+}
+
+impl Strictness {
+    /// Returns whether this is the default strictness level.
+    pub fn is_strict(&self) -> bool {
+        *self == Strictness::Strict
+    }
+}
+
 /// Defines how unmapped fields should be handled.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum Mode {
@@ -115,6 +138,11 @@ pub struct DocMapping {
     )]
     pub mode: Mode,
 
+    /// Defines how the values whose type does not match their field mapping should be handled.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Strictness::is_strict")]
+    pub strictness: Strictness,
+
     /// Defines the schema of ingested documents and describes how each field value should be
     /// parsed, tokenized, indexed, and stored.
     #[serde(default)]
@@ -152,6 +180,12 @@ pub struct DocMapping {
     #[serde(default)]
     pub store_document_size: bool,
 
+    /// Whether to record the time at which each document is ingested in the `_ingested_at` fast
+    /// field.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "quickwit_common::is_false")]
+    pub store_ingest_timestamp: bool,
+
     /// Whether to store the original source documents in the doc store.
     #[serde(default)]
     pub store_source: bool,
@@ -196,6 +230,7 @@ mod tests {
         let doc_mapping = DocMapping {
             doc_mapping_uid: DocMappingUid::random(),
             mode: Mode::Strict,
+            strictness: Strictness::Coerce,
             field_mappings: vec![
                 FieldMappingEntry {
                     name: "timestamp".to_string(),
@@ -218,6 +253,7 @@ mod tests {
             max_num_partitions: NonZeroU32::new(100).unwrap(),
             index_field_presence: true,
             store_document_size: true,
+            store_ingest_timestamp: true,
             store_source: true,
             tokenizers: vec![TokenizerEntry {
                 name: "whitespace".to_string(),
@@ -251,6 +287,7 @@ mod tests {
         );
         assert_eq!(doc_mapping.index_field_presence, false);
         assert_eq!(doc_mapping.store_document_size, false);
+        assert_eq!(doc_mapping.store_ingest_timestamp, false);
         assert_eq!(doc_mapping.store_source, false);
     }
 }
@@ -23,6 +23,7 @@
 mod doc_mapper;
 mod doc_mapping;
 mod error;
+mod metrics;
 mod query_builder;
 mod routing_expression;
 
@@ -30,16 +31,17 @@ mod routing_expression;
 pub mod tag_pruning;
 
 pub use doc_mapper::{
-    analyze_text, Automaton, BinaryFormat, DocMapper, DocMapperBuilder, FastFieldWarmupInfo,
-    FieldMappingEntry, FieldMappingType, JsonObject, NamedField, QuickwitBytesOptions,
-    QuickwitJsonOptions, TermRange, TokenizerConfig, TokenizerEntry, WarmupInfo,
+    analyze_text, tantivy_value_to_json, Automaton, BinaryFormat, DocMapper, DocMapperBuilder,
+    FastFieldWarmupInfo, FieldMappingEntry, FieldMappingType, JsonObject, NamedField,
+    QuickwitBytesOptions, QuickwitJsonOptions, RedactionRule, TermRange, TokenizerConfig,
+    TokenizerEntry, ValueLimitPolicy, ValueLimits, WarmupInfo,
 };
 use doc_mapper::{
     FastFieldOptions, FieldMappingEntryForSerialization, IndexRecordOptionSchema,
     NgramTokenizerOption, QuickwitTextNormalizer, QuickwitTextTokenizer, RegexTokenizerOption,
-    TokenFilterType, TokenizerType,
+    StopWordsFilterOption, StopWordsLanguage, TokenFilterType, TokenizerType,
 };
-pub use doc_mapping::{DocMapping, Mode, ModeType};
+pub use doc_mapping::{DocMapping, Mode, ModeType, Strictness};
 pub use error::{DocParsingError, QueryParserError};
 use quickwit_common::shared_consts::FIELD_PRESENCE_FIELD_NAME;
 use quickwit_proto::types::DocMappingUid;
@@ -54,11 +56,19 @@ pub const DYNAMIC_FIELD_NAME: &str = "_dynamic";
 /// Field name reserved for storing the length of source document.
 pub const DOCUMENT_SIZE_FIELD_NAME: &str = "_doc_length";
 
+/// Field name reserved for storing the time at which a document was ingested.
+pub const INGEST_TIMESTAMP_FIELD_NAME: &str = "_ingested_at";
+
+/// Logical field name that queries can use to target the timestamp field of an index, whatever
+/// its name, unless the doc mapping defines a field with this name.
+pub const LOGICAL_TIMESTAMP_FIELD_NAME: &str = "@timestamp";
+
 /// Quickwit reserved field names.
 const QW_RESERVED_FIELD_NAMES: &[&str] = &[
     DOCUMENT_SIZE_FIELD_NAME,
     DYNAMIC_FIELD_NAME,
     FIELD_PRESENCE_FIELD_NAME,
+    INGEST_TIMESTAMP_FIELD_NAME,
     SOURCE_FIELD_NAME,
 ];
 
@@ -82,11 +92,17 @@ pub enum Cardinality {
     QuickwitJsonOptions,
     QuickwitTextNormalizer,
     QuickwitTextTokenizer,
+    RedactionRule,
     RegexTokenizerOption,
+    StopWordsFilterOption,
+    StopWordsLanguage,
+    Strictness,
     TokenFilterType,
     TokenizerConfig,
     TokenizerEntry,
     TokenizerType,
+    ValueLimitPolicy,
+    ValueLimits,
 )))]
 /// Schema used for the OpenAPI generation which are apart of this crate.
 pub struct DocMapperApiSchemas;
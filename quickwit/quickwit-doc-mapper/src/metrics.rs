@@ -0,0 +1,84 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use once_cell::sync::Lazy;
+use quickwit_common::metrics::{new_counter_vec, IntCounterVec};
+
+pub(crate) struct DocMapperMetrics {
+    pub redactions_total: IntCounterVec<1>,
+    pub type_mismatches_total: IntCounterVec<1>,
+    pub value_limits_exceeded_total: IntCounterVec<2>,
+}
+
+impl Default for DocMapperMetrics {
+    fn default() -> Self {
+        DocMapperMetrics {
+            redactions_total: new_counter_vec(
+                "redactions_total",
+                "Number of text value parts redacted at indexing time by redaction rule in \
+                 [email, credit_card, token, regex]",
+                "doc_mapper",
+                &[],
+                ["rule"],
+            ),
+            type_mismatches_total: new_counter_vec(
+                "type_mismatches_total",
+                "Number of values whose type does not match their field mapping, by outcome in \
+                 [coerced, dumped, rejected]",
+                "doc_mapper",
+                &[],
+                ["outcome"],
+            ),
+            value_limits_exceeded_total: new_counter_vec(
+                "value_limits_exceeded_total",
+                "Number of values exceeding the limits of their field, by limit in [num_values, \
+                 value_length] and outcome in [truncated, rejected]",
+                "doc_mapper",
+                &[],
+                ["limit", "outcome"],
+            ),
+        }
+    }
+}
+
+/// `DOC_MAPPER_METRICS` exposes doc mapping related metrics through a prometheus endpoint.
+pub(crate) static DOC_MAPPER_METRICS: Lazy<DocMapperMetrics> = Lazy::new(DocMapperMetrics::default);
+
+/// What happened to a value whose type does not match its field mapping.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum TypeMismatchOutcome {
+    /// The value was converted to the type of the field.
+    Coerced,
+    /// The value was captured in the dynamic field.
+    Dumped,
+    /// The document was rejected.
+    Rejected,
+}
+
+impl TypeMismatchOutcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Coerced => "coerced",
+            Self::Dumped => "dumped",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+pub(crate) fn record_type_mismatch(outcome: TypeMismatchOutcome) {
+    DOC_MAPPER_METRICS
+        .type_mismatches_total
+        .with_label_values([outcome.as_str()])
+        .inc();
+}
@@ -408,8 +408,8 @@ mod test {
         let user_input_query = UserInputQuery {
             user_text: user_query.to_string(),
             default_fields: Some(search_fields),
-            default_operator: BooleanOperand::And,
-            lenient,
+            default_operator: Some(BooleanOperand::And),
+            lenient: Some(lenient),
         };
         let query_ast = user_input_query
             .parse_user_query(&[])
@@ -108,7 +108,9 @@ fn extract_unsimplified_tags_filter_ast(query_ast: QueryAst) -> UnsimplifiedTagF
                 value: wildcard_query.value,
             }
         }
-        QueryAst::Boost { underlying, .. } => extract_unsimplified_tags_filter_ast(*underlying),
+        QueryAst::Boost { underlying, .. } | QueryAst::Named { underlying, .. } => {
+            extract_unsimplified_tags_filter_ast(*underlying)
+        }
         QueryAst::UserInput(_user_text_query) => {
             panic!("Extract unsimplified should only be called on AST without UserInputQuery.");
         }
@@ -393,8 +395,8 @@ mod test {
         let query_ast: QueryAst = UserInputQuery {
             user_text: user_query.to_string(),
             default_fields: None,
-            default_operator: BooleanOperand::Or,
-            lenient: false,
+            default_operator: Some(BooleanOperand::Or),
+            lenient: Some(false),
         }
         .into();
         let parsed_query_ast = query_ast.parse_user_query(&[]).unwrap();
@@ -16,6 +16,7 @@ use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::time::Duration;
 
+use anyhow::bail;
 use futures_util::StreamExt;
 use itertools::Itertools;
 use quickwit_common::fs::{empty_dir, get_cache_directory_path};
@@ -32,7 +33,7 @@ use quickwit_proto::metastore::{
     serde_utils, AddSourceRequest, CreateIndexRequest, DeleteIndexRequest, EntityKind,
     IndexMetadataRequest, ListIndexesMetadataRequest, ListSplitsRequest,
     MarkSplitsForDeletionRequest, MetastoreError, MetastoreService, MetastoreServiceClient,
-    ResetSourceCheckpointRequest, UpdateSourceRequest,
+    ResetSourceCheckpointRequest, TrashIndexRequest, UndeleteIndexRequest, UpdateSourceRequest,
 };
 use quickwit_proto::types::{IndexUid, SplitId};
 use quickwit_proto::{ServiceError, ServiceErrorCode};
@@ -97,6 +98,7 @@ impl ServiceError for IndexServiceError {
 pub struct IndexService {
     metastore: MetastoreServiceClient,
     storage_resolver: StorageResolver,
+    trash_enabled: bool,
 }
 
 impl IndexService {
@@ -105,9 +107,17 @@ impl IndexService {
         Self {
             metastore,
             storage_resolver,
+            trash_enabled: false,
         }
     }
 
+    /// Makes [`IndexService::delete_index`] move indexes to the trash instead of deleting them
+    /// right away.
+    pub fn with_trash_enabled(mut self, trash_enabled: bool) -> Self {
+        self.trash_enabled = trash_enabled;
+        self
+    }
+
     pub fn metastore(&self) -> MetastoreServiceClient {
         self.metastore.clone()
     }
@@ -159,6 +169,10 @@ impl IndexService {
     /// This is equivalent to running `rm -rf <index path>` for a local index or
     /// `aws s3 rm --recursive <index path>` for a remote Amazon S3 index.
     ///
+    /// If the trash is enabled, the index is moved to the trash instead and no split is deleted
+    /// until the janitor purges it. Deleting an index that is already in the trash purges it right
+    /// away.
+    ///
     /// * `index_id` - The target index Id.
     /// * `dry_run` - Should this only return a list of affected files without performing deletion.
     pub async fn delete_index(
@@ -167,17 +181,19 @@ impl IndexService {
         dry_run: bool,
     ) -> Result<Vec<SplitInfo>, IndexServiceError> {
         let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.to_string());
-        let index_metadata = self
-            .metastore
-            .index_metadata(index_metadata_request)
-            .await?
-            .deserialize_index_metadata()?;
-        let index_uid = index_metadata.index_uid.clone();
-        let index_uri = index_metadata.into_index_config().index_uri.clone();
-        let storage = self.storage_resolver.resolve(&index_uri).await?;
-
+        let index_metadata = match self.metastore.index_metadata(index_metadata_request).await {
+            Ok(index_metadata_response) => index_metadata_response.deserialize_index_metadata()?,
+            Err(MetastoreError::NotFound(entity)) => {
+                let Some(index_metadata) = self.trashed_index_metadata(index_id).await? else {
+                    return Err(MetastoreError::NotFound(entity).into());
+                };
+                index_metadata
+            }
+            Err(metastore_error) => return Err(metastore_error.into()),
+        };
         if dry_run {
-            let list_splits_request = ListSplitsRequest::try_from_index_uid(index_uid)?;
+            let list_splits_request =
+                ListSplitsRequest::try_from_index_uid(index_metadata.index_uid)?;
             let splits_to_delete: Vec<SplitInfo> = self
                 .metastore
                 .list_splits(list_splits_request)
@@ -189,6 +205,52 @@ impl IndexService {
                 .collect();
             return Ok(splits_to_delete);
         }
+        if self.trash_enabled && !index_metadata.is_trashed() {
+            let trash_index_request = TrashIndexRequest {
+                index_uid: Some(index_metadata.index_uid),
+            };
+            self.metastore.trash_index(trash_index_request).await?;
+            return Ok(Vec::new());
+        }
+        self.purge_index(index_metadata).await
+    }
+
+    /// Returns the metadata of the index specified with `index_id` if it is in the trash.
+    async fn trashed_index_metadata(
+        &self,
+        index_id: &str,
+    ) -> Result<Option<IndexMetadata>, IndexServiceError> {
+        let list_indexes_metadata_request = ListIndexesMetadataRequest {
+            index_id_patterns: vec![index_id.to_string()],
+            include_trashed: true,
+            label_selectors: Vec::new(),
+        };
+        let index_metadata_opt = self
+            .metastore
+            .list_indexes_metadata(list_indexes_metadata_request)
+            .await?
+            .deserialize_indexes_metadata()
+            .await?
+            .into_iter()
+            .find(|index_metadata| index_metadata.is_trashed());
+        Ok(index_metadata_opt)
+    }
+
+    /// Deletes the splits and the metadata of an index, whether it is in the trash or not.
+    pub async fn purge_index(
+        &self,
+        index_metadata: IndexMetadata,
+    ) -> Result<Vec<SplitInfo>, IndexServiceError> {
+        let index_uid = index_metadata.index_uid.clone();
+        let index_config = index_metadata.into_index_config();
+        let storage = self
+            .storage_resolver
+            .resolve_with_credentials(
+                &index_config.index_uri,
+                index_config.storage_credentials_opt.as_deref(),
+            )
+            .await?;
+
         // Schedule staged and published splits for deletion.
         let query = ListSplitsQuery::for_index(index_uid.clone())
             .with_split_states([SplitState::Staged, SplitState::Published]);
@@ -232,6 +294,19 @@ impl IndexService {
         Ok(deleted_splits)
     }
 
+    /// Restores the index specified with `index_id` from the trash.
+    pub async fn undelete_index(&self, index_id: &str) -> Result<IndexMetadata, IndexServiceError> {
+        let undelete_index_request = UndeleteIndexRequest {
+            index_id: index_id.to_string(),
+        };
+        let index_metadata = self
+            .metastore
+            .undelete_index(undelete_index_request)
+            .await?
+            .deserialize_index_metadata()?;
+        Ok(index_metadata)
+    }
+
     /// Deletes the indexes specified with `index_id_patterns`.
     /// This is a wrapper of delete_index, and support index delete with index pattern
     ///
@@ -245,6 +320,8 @@ impl IndexService {
     ) -> Result<Vec<SplitInfo>, IndexServiceError> {
         let list_indexes_metadatas_request = ListIndexesMetadataRequest {
             index_id_patterns: index_id_patterns.to_owned(),
+            include_trashed: false,
+            label_selectors: Vec::new(),
         };
         // disallow index_id patterns
         for index_id_pattern in &index_id_patterns {
@@ -356,7 +433,10 @@ impl IndexService {
         let index_config = index_metadata.into_index_config();
         let storage = self
             .storage_resolver
-            .resolve(&index_config.index_uri)
+            .resolve_with_credentials(
+                &index_config.index_uri,
+                index_config.storage_credentials_opt.as_deref(),
+            )
             .await?;
 
         let deleted_entries = run_garbage_collect(
@@ -394,7 +474,13 @@ impl IndexService {
         let index_uid = index_metadata.index_uid.clone();
         let storage = self
             .storage_resolver
-            .resolve(index_metadata.index_uri())
+            .resolve_with_credentials(
+                index_metadata.index_uri(),
+                index_metadata
+                    .index_config
+                    .storage_credentials_opt
+                    .as_deref(),
+            )
             .await?;
         let list_splits_request = ListSplitsRequest::try_from_index_uid(index_uid.clone())?;
         let splits_metadata: Vec<SplitMetadata> = self
@@ -546,20 +632,34 @@ pub async fn clear_cache_directory(data_dir_path: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
-/// Validates the storage URI by effectively resolving it.
+/// Validates the storage URI and the storage credentials referenced by the index config by
+/// effectively resolving the URI.
 pub async fn validate_storage_uri(
     storage_resolver: &StorageResolver,
     index_config: &IndexConfig,
 ) -> anyhow::Result<()> {
-    storage_resolver.resolve(&index_config.index_uri).await?;
+    if let Some(storage_credentials) = &index_config.storage_credentials_opt {
+        if !storage_resolver.has_storage_credentials(storage_credentials) {
+            bail!("storage credentials `{storage_credentials}` are not defined in the node config");
+        }
+    }
+    storage_resolver
+        .resolve_with_credentials(
+            &index_config.index_uri,
+            index_config.storage_credentials_opt.as_deref(),
+        )
+        .await?;
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
 
     use quickwit_common::uri::Uri;
-    use quickwit_config::{IndexConfig, CLI_SOURCE_ID, INGEST_API_SOURCE_ID, INGEST_V2_SOURCE_ID};
+    use quickwit_config::{
+        IndexConfig, StorageConfigs, CLI_SOURCE_ID, INGEST_API_SOURCE_ID, INGEST_V2_SOURCE_ID,
+    };
     use quickwit_metastore::{
         metastore_for_test, MetastoreServiceExt, SplitMetadata, StageSplitsRequestExt,
     };
@@ -613,6 +713,45 @@ mod tests {
         assert!(index_metadata_0.index_uid != index_metadata_1.index_uid);
     }
 
+    #[tokio::test]
+    async fn test_create_index_with_storage_credentials() {
+        let metastore = metastore_for_test();
+        let storage_credentials =
+            BTreeMap::from([("account-a".to_string(), StorageConfigs::default())]);
+        let storage_resolver = StorageResolver::configured_with_credentials(
+            &StorageConfigs::default(),
+            &storage_credentials,
+        );
+        let mut index_service = IndexService::new(metastore, storage_resolver);
+
+        let mut index_config = IndexConfig::for_test("test-index", "ram://indexes/test-index");
+        index_config.storage_credentials_opt = Some("account-b".to_string());
+        let error = index_service
+            .create_index(index_config.clone(), false)
+            .await
+            .unwrap_err();
+        let IndexServiceError::InvalidConfig(inner_error) = error else {
+            panic!("expected `InvalidConfig` variant, got {:?}", error)
+        };
+        assert_eq!(
+            inner_error.to_string(),
+            "storage credentials `account-b` are not defined in the node config"
+        );
+
+        index_config.storage_credentials_opt = Some("account-a".to_string());
+        let index_metadata = index_service
+            .create_index(index_config, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            index_metadata
+                .index_config
+                .storage_credentials_opt
+                .as_deref(),
+            Some("account-a")
+        );
+    }
+
     #[tokio::test]
     async fn test_delete_index() {
         let mut metastore = metastore_for_test();
@@ -673,4 +812,65 @@ mod tests {
         assert!(splits.is_empty());
         assert!(!storage.exists(split_path).await.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_delete_index_with_trash() {
+        let mut metastore = metastore_for_test();
+        let storage_resolver = StorageResolver::for_test();
+        let storage = storage_resolver
+            .resolve(&Uri::for_test("ram://indexes/test-index"))
+            .await
+            .unwrap();
+        let mut index_service =
+            IndexService::new(metastore.clone(), storage_resolver).with_trash_enabled(true);
+        let index_id = "test-index";
+        let index_uri = "ram://indexes/test-index";
+        let index_config = IndexConfig::for_test(index_id, index_uri);
+        let index_uid = index_service
+            .create_index(index_config.clone(), false)
+            .await
+            .unwrap()
+            .index_uid;
+
+        let split_id = "test-split";
+        let split_metadata = SplitMetadata {
+            split_id: split_id.to_string(),
+            index_uid: index_uid.clone(),
+            ..Default::default()
+        };
+        let stage_splits_request = StageSplitsRequest::try_from_splits_metadata(
+            index_uid.clone(),
+            vec![split_metadata.clone()],
+        )
+        .unwrap();
+        metastore.stage_splits(stage_splits_request).await.unwrap();
+
+        let split_path_str = format!("{}.split", split_id);
+        let split_path = Path::new(&split_path_str);
+        let payload: Box<dyn PutPayload> = Box::new(vec![0]);
+        storage.put(split_path, payload).await.unwrap();
+
+        let split_infos = index_service.delete_index(index_id, false).await.unwrap();
+        assert!(split_infos.is_empty());
+
+        assert!(!metastore.index_exists(index_id).await.unwrap());
+        assert!(storage.exists(split_path).await.unwrap());
+
+        let index_metadata = index_service.undelete_index(index_id).await.unwrap();
+        assert_eq!(index_metadata.index_uid, index_uid);
+        assert!(metastore.index_exists(index_id).await.unwrap());
+
+        index_service.delete_index(index_id, false).await.unwrap();
+
+        // Deleting an index in the trash purges it.
+        let split_infos = index_service.delete_index(index_id, false).await.unwrap();
+        assert_eq!(split_infos.len(), 1);
+        assert!(!storage.exists(split_path).await.unwrap());
+
+        let error = index_service.undelete_index(index_id).await.unwrap_err();
+        assert!(matches!(
+            error,
+            IndexServiceError::Metastore(MetastoreError::NotFound(_))
+        ));
+    }
 }
@@ -15,15 +15,16 @@
 use std::string::FromUtf8Error;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::{bail, Context};
 use async_trait::async_trait;
 use bytes::Bytes;
 use quickwit_actors::{Actor, ActorContext, ActorExitStatus, Handler, Mailbox, QueueCapacity};
-use quickwit_common::metrics::IntCounter;
+use quickwit_common::metrics::{Histogram, IntCounter};
 use quickwit_common::rate_limited_tracing::rate_limited_warn;
 use quickwit_common::runtimes::RuntimeType;
-use quickwit_config::{SourceInputFormat, TransformConfig};
+use quickwit_config::{BackfillTimeRange, SourceInputFormat, TransformConfig};
 use quickwit_doc_mapper::{DocMapper, DocParsingError, JsonObject};
 use quickwit_opentelemetry::otlp::{
     parse_otlp_logs_json, parse_otlp_logs_protobuf, parse_otlp_spans_json,
@@ -40,12 +41,22 @@ use tokio::runtime::Handle;
 #[cfg(feature = "vrl")]
 use super::vrl_processing::*;
 use crate::actors::Indexer;
+use crate::metrics::PipelineStage;
 use crate::models::{
     NewPublishLock, NewPublishToken, ProcessedDoc, ProcessedDocBatch, PublishLock, RawDocBatch,
 };
 
 const PLAIN_TEXT: &str = "plain_text";
 
+/// Maximum amount of time the doc processor spends processing documents before it yields to let
+/// the other tasks of the indexing runtime, such as the doc processors of other pipelines, run.
+/// Yielding happens between two documents, so a single document can exceed this budget.
+const DOC_PROCESSING_YIELD_BUDGET: Duration = Duration::from_millis(10);
+
+/// Documents taking longer to process are logged, to help find the culprits of a CPU-bound
+/// pipeline.
+const SLOW_DOC_PROCESSING_THRESHOLD: Duration = Duration::from_millis(100);
+
 pub(super) struct JsonDoc {
     json_obj: JsonObject,
     num_bytes: usize,
@@ -92,6 +103,8 @@ pub enum DocProcessorError {
     #[cfg(feature = "vrl")]
     #[error("VRL transform error: {0}")]
     Transform(VrlTerminate),
+    #[error("document timestamp is outside of the backfill time range")]
+    OutOfBackfillTimeRange,
 }
 
 impl From<OtlpLogsError> for DocProcessorError {
@@ -216,6 +229,44 @@ fn parse_raw_doc(
     try_into_json_docs(input_format, raw_doc, num_bytes)
 }
 
+/// Runs a VRL transform against sample raw documents without indexing them, and returns for each
+/// document either the transformed JSON object or the error that would make the doc processor
+/// drop it.
+#[cfg(feature = "vrl")]
+pub fn dry_run_transform(
+    input_format: SourceInputFormat,
+    transform_config: TransformConfig,
+    raw_docs: Vec<Bytes>,
+) -> anyhow::Result<Vec<Result<JsonObject, DocProcessorError>>> {
+    if !matches!(
+        input_format,
+        SourceInputFormat::Json | SourceInputFormat::PlainText
+    ) {
+        bail!("VRL transforms are not supported for OTLP input formats");
+    }
+    let mut vrl_program = VrlProgram::try_from_transform_config(transform_config)?;
+    let results = raw_docs
+        .into_iter()
+        .map(|raw_doc| {
+            let num_bytes = raw_doc.len();
+            try_into_vrl_doc(input_format, raw_doc, num_bytes)
+                .and_then(|vrl_doc| vrl_program.transform_doc(vrl_doc))
+                .and_then(JsonDoc::try_from_vrl_doc)
+                .map(|json_doc| json_doc.json_obj)
+        })
+        .collect();
+    Ok(results)
+}
+
+#[cfg(not(feature = "vrl"))]
+pub fn dry_run_transform(
+    _input_format: SourceInputFormat,
+    _transform_config: TransformConfig,
+    _raw_docs: Vec<Bytes>,
+) -> anyhow::Result<Vec<Result<JsonObject, DocProcessorError>>> {
+    bail!("VRL is not enabled: please recompile with the `vrl` feature")
+}
+
 enum JsonDocIterator {
     One(Option<Result<JsonDoc, DocProcessorError>>),
     Logs(JsonLogIterator),
@@ -314,24 +365,38 @@ pub struct DocProcessorCounters {
     source_id: SourceId,
 
     /// Overall number of documents received, partitioned
-    /// into 5 categories:
+    /// into 6 categories:
     /// - valid documents
     /// - number of docs that could not be parsed.
     /// - number of docs that were not valid json.
     /// - number of docs that could not be transformed.
     /// - number of docs for which the doc mapper returned an error.
+    /// - number of docs dropped because their timestamp is outside of the backfill time range.
     /// - number of valid docs.
     pub valid: DocProcessorCounter,
     pub doc_mapper_errors: DocProcessorCounter,
     pub transform_errors: DocProcessorCounter,
     pub json_parse_errors: DocProcessorCounter,
     pub otlp_parse_errors: DocProcessorCounter,
+    pub out_of_range: DocProcessorCounter,
 
     /// Number of bytes that went through the indexer
     /// during its entire lifetime.
     ///
     /// Includes both valid and invalid documents.
     pub num_bytes_total: AtomicU64,
+
+    /// Amount of time spent processing documents during the entire lifetime of the doc
+    /// processor (in micros).
+    pub processing_micros: AtomicU64,
+    #[serde(skip)]
+    processing_micros_metric: IntCounter,
+    #[serde(skip)]
+    event_time_lag_seconds_metric: Histogram,
+    #[serde(skip)]
+    transform_duration_seconds_metric: Histogram,
+    #[serde(skip)]
+    doc_map_duration_seconds_metric: Histogram,
 }
 
 impl DocProcessorCounters {
@@ -346,6 +411,26 @@ impl DocProcessorCounters {
             DocProcessorCounter::for_index_and_doc_processor_outcome(&index_id, "json_parse_error");
         let otlp_parse_errors =
             DocProcessorCounter::for_index_and_doc_processor_outcome(&index_id, "otlp_parse_error");
+        let out_of_range =
+            DocProcessorCounter::for_index_and_doc_processor_outcome(&index_id, "out_of_range");
+        let processing_micros_metric = crate::metrics::INDEXER_METRICS
+            .doc_processing_micros
+            .with_label_values([quickwit_common::metrics::index_label(&index_id), &source_id]);
+        let event_time_lag_seconds_metric = crate::metrics::INDEXER_METRICS
+            .event_time_lag_seconds
+            .with_label_values([quickwit_common::metrics::index_label(&index_id)]);
+        let pipeline_stage_duration_metric = |stage: PipelineStage| {
+            crate::metrics::INDEXER_METRICS
+                .pipeline_stage_duration_seconds
+                .with_label_values([
+                    quickwit_common::metrics::index_label(&index_id),
+                    &source_id,
+                    stage.as_str(),
+                ])
+        };
+        let transform_duration_seconds_metric =
+            pipeline_stage_duration_metric(PipelineStage::Transform);
+        let doc_map_duration_seconds_metric = pipeline_stage_duration_metric(PipelineStage::DocMap);
         DocProcessorCounters {
             index_id,
             source_id,
@@ -355,7 +440,13 @@ impl DocProcessorCounters {
             transform_errors,
             json_parse_errors,
             otlp_parse_errors,
+            out_of_range,
             num_bytes_total: Default::default(),
+            processing_micros: Default::default(),
+            processing_micros_metric,
+            event_time_lag_seconds_metric,
+            transform_duration_seconds_metric,
+            doc_map_duration_seconds_metric,
         }
     }
 
@@ -366,6 +457,7 @@ impl DocProcessorCounters {
             + self.json_parse_errors.get_num_docs()
             + self.otlp_parse_errors.get_num_docs()
             + self.transform_errors.get_num_docs()
+            + self.out_of_range.get_num_docs()
     }
 
     /// Returns the overall number of docs that were sent to the indexer but were invalid.
@@ -378,6 +470,30 @@ impl DocProcessorCounters {
             + self.transform_errors.get_num_docs()
     }
 
+    pub fn record_processing_time(&self, elapsed: Duration) {
+        let elapsed_micros = elapsed.as_micros() as u64;
+        self.processing_micros
+            .fetch_add(elapsed_micros, Ordering::Relaxed);
+        self.processing_micros_metric.inc_by(elapsed_micros);
+    }
+
+    /// Records the time spent parsing and transforming, and then mapping the documents of a batch.
+    pub fn record_stage_durations(&self, transform_elapsed: Duration, doc_map_elapsed: Duration) {
+        self.transform_duration_seconds_metric
+            .observe(transform_elapsed.as_secs_f64());
+        self.doc_map_duration_seconds_metric
+            .observe(doc_map_elapsed.as_secs_f64());
+    }
+
+    /// Records the delay between the event time of a document and its ingestion.
+    pub fn record_event_time_lag(&self, ingest_timestamp: DateTime, event_timestamp: DateTime) {
+        let lag_micros =
+            ingest_timestamp.into_timestamp_micros() - event_timestamp.into_timestamp_micros();
+        // Documents from the future are not lagging.
+        let lag_secs = lag_micros.max(0) as f64 / 1_000_000.0;
+        self.event_time_lag_seconds_metric.observe(lag_secs);
+    }
+
     pub fn record_valid(&self, num_bytes: u64) {
         self.num_bytes_total.fetch_add(num_bytes, Ordering::Relaxed);
         self.valid.record_doc(num_bytes);
@@ -399,6 +515,9 @@ impl DocProcessorCounters {
             DocProcessorError::Transform(_) => {
                 self.transform_errors.record_doc(num_bytes);
             }
+            DocProcessorError::OutOfBackfillTimeRange => {
+                self.out_of_range.record_doc(num_bytes);
+            }
         };
     }
 }
@@ -407,11 +526,14 @@ pub struct DocProcessor {
     doc_mapper: Arc<DocMapper>,
     indexer_mailbox: Mailbox<Indexer>,
     timestamp_field_opt: Option<Field>,
+    ingest_timestamp_field_opt: Option<Field>,
     counters: Arc<DocProcessorCounters>,
     publish_lock: PublishLock,
     #[cfg(feature = "vrl")]
     transform_opt: Option<VrlProgram>,
     input_format: SourceInputFormat,
+    backfill_time_range_opt: Option<BackfillTimeRange>,
+    yield_budget: Duration,
 }
 
 impl DocProcessor {
@@ -422,15 +544,21 @@ impl DocProcessor {
         indexer_mailbox: Mailbox<Indexer>,
         transform_config_opt: Option<TransformConfig>,
         input_format: SourceInputFormat,
+        backfill_time_range_opt: Option<BackfillTimeRange>,
     ) -> anyhow::Result<Self> {
         let timestamp_field_opt = extract_timestamp_field(&doc_mapper)?;
+        let ingest_timestamp_field_opt = doc_mapper.ingest_timestamp_field();
         if cfg!(not(feature = "vrl")) && transform_config_opt.is_some() {
             bail!("VRL is not enabled: please recompile with the `vrl` feature")
         }
+        if backfill_time_range_opt.is_some() && timestamp_field_opt.is_none() {
+            bail!("backfill time ranges require the index to define a timestamp field")
+        }
         Ok(DocProcessor {
             doc_mapper,
             indexer_mailbox,
             timestamp_field_opt,
+            ingest_timestamp_field_opt,
             counters: Arc::new(DocProcessorCounters::new(index_id, source_id)),
             publish_lock: PublishLock::default(),
             #[cfg(feature = "vrl")]
@@ -438,6 +566,8 @@ impl DocProcessor {
                 .map(VrlProgram::try_from_transform_config)
                 .transpose()?,
             input_format,
+            backfill_time_range_opt,
+            yield_budget: DOC_PROCESSING_YIELD_BUDGET,
         })
     }
 
@@ -461,17 +591,33 @@ impl DocProcessor {
         Ok(Some(timestamp))
     }
 
-    fn process_raw_doc(&mut self, raw_doc: Bytes, processed_docs: &mut Vec<ProcessedDoc>) {
+    /// Processes a raw document and returns the time spent parsing and transforming it, and the
+    /// time spent mapping the resulting documents.
+    fn process_raw_doc(
+        &mut self,
+        raw_doc: Bytes,
+        processed_docs: &mut Vec<ProcessedDoc>,
+    ) -> (Duration, Duration) {
         let num_bytes = raw_doc.len();
+        let start = Instant::now();
+        let mut doc_map_elapsed = Duration::ZERO;
 
         #[cfg(feature = "vrl")]
         let transform_opt = self.transform_opt.as_mut();
         #[cfg(not(feature = "vrl"))]
         let transform_opt: Option<&mut VrlProgram> = None;
 
+        let ingest_timestamp_opt = self
+            .ingest_timestamp_field_opt
+            .map(|_| DateTime::from_utc(tantivy::time::OffsetDateTime::now_utc()));
+
         for json_doc_result in parse_raw_doc(self.input_format, raw_doc, num_bytes, transform_opt) {
-            let processed_doc_result =
-                json_doc_result.and_then(|json_doc| self.process_json_doc(json_doc));
+            let processed_doc_result = json_doc_result.and_then(|json_doc| {
+                let doc_map_start = Instant::now();
+                let processed_doc_result = self.process_json_doc(json_doc, ingest_timestamp_opt);
+                doc_map_elapsed += doc_map_start.elapsed();
+                processed_doc_result
+            });
 
             match processed_doc_result {
                 Ok(processed_doc) => {
@@ -479,25 +625,65 @@ impl DocProcessor {
                     processed_docs.push(processed_doc);
                 }
                 Err(error) => {
-                    rate_limited_warn!(
-                        limit_per_min = 10,
-                        index_id = self.counters.index_id,
-                        source_id = self.counters.source_id,
-                        "{error}",
-                    );
+                    // Dropping out-of-range documents is the expected behavior when backfilling.
+                    if !matches!(error, DocProcessorError::OutOfBackfillTimeRange) {
+                        rate_limited_warn!(
+                            limit_per_min = 10,
+                            index_id = self.counters.index_id,
+                            source_id = self.counters.source_id,
+                            "{error}",
+                        );
+                    }
                     self.counters.record_error(error, num_bytes as u64);
                 }
             }
         }
+        let elapsed = start.elapsed();
+        self.counters.record_processing_time(elapsed);
+
+        if elapsed >= SLOW_DOC_PROCESSING_THRESHOLD {
+            rate_limited_warn!(
+                limit_per_min = 10,
+                index_id = self.counters.index_id,
+                source_id = self.counters.source_id,
+                num_bytes,
+                elapsed_ms = elapsed.as_millis() as u64,
+                "slow document processing"
+            );
+        }
+        (elapsed.saturating_sub(doc_map_elapsed), doc_map_elapsed)
     }
 
-    fn process_json_doc(&self, json_doc: JsonDoc) -> Result<ProcessedDoc, DocProcessorError> {
+    fn process_json_doc(
+        &self,
+        json_doc: JsonDoc,
+        ingest_timestamp_opt: Option<DateTime>,
+    ) -> Result<ProcessedDoc, DocProcessorError> {
         let num_bytes = json_doc.num_bytes;
 
-        let (partition, doc) = self
+        let (partition, mut doc) = self
             .doc_mapper
             .doc_from_json_obj(json_doc.json_obj, json_doc.num_bytes as u64)?;
         let timestamp_opt = self.extract_timestamp(&doc)?;
+
+        if let (Some(backfill_time_range), Some(timestamp)) =
+            (&self.backfill_time_range_opt, timestamp_opt)
+        {
+            if !backfill_time_range.contains_micros(timestamp.into_timestamp_micros()) {
+                return Err(DocProcessorError::OutOfBackfillTimeRange);
+            }
+        }
+
+        if let (Some(ingest_timestamp_field), Some(ingest_timestamp)) =
+            (self.ingest_timestamp_field_opt, ingest_timestamp_opt)
+        {
+            doc.add_date(ingest_timestamp_field, ingest_timestamp);
+
+            if let Some(timestamp) = timestamp_opt {
+                self.counters
+                    .record_event_time_lag(ingest_timestamp, timestamp);
+            }
+        }
         Ok(ProcessedDoc {
             doc,
             timestamp_opt,
@@ -573,12 +759,29 @@ impl Handler<RawDocBatch> for DocProcessor {
             return Ok(());
         }
         let mut processed_docs: Vec<ProcessedDoc> = Vec::with_capacity(raw_doc_batch.docs.len());
+        let mut budget_start = Instant::now();
+        let mut transform_elapsed = Duration::ZERO;
+        let mut doc_map_elapsed = Duration::ZERO;
 
         for raw_doc in raw_doc_batch.docs {
-            let _protected_zone_guard = ctx.protect_zone();
-            self.process_raw_doc(raw_doc, &mut processed_docs);
+            {
+                let _protected_zone_guard = ctx.protect_zone();
+                let (raw_doc_transform_elapsed, raw_doc_doc_map_elapsed) =
+                    self.process_raw_doc(raw_doc, &mut processed_docs);
+                transform_elapsed += raw_doc_transform_elapsed;
+                doc_map_elapsed += raw_doc_doc_map_elapsed;
+            }
             ctx.record_progress();
+
+            // Cooperatively yield once the budget is exhausted so that CPU-heavy documents do
+            // not monopolize the indexing worker threads.
+            if budget_start.elapsed() >= self.yield_budget {
+                ctx.yield_now().await;
+                budget_start = Instant::now();
+            }
         }
+        self.counters
+            .record_stage_durations(transform_elapsed, doc_map_elapsed);
         let processed_doc_batch = ProcessedDocBatch::new(
             processed_docs,
             raw_doc_batch.checkpoint_delta,
@@ -658,6 +861,7 @@ mod tests {
             indexer_mailbox,
             None,
             SourceInputFormat::Json,
+            None,
         )
         .unwrap();
         let (doc_processor_mailbox, doc_processor_handle) =
@@ -722,6 +926,60 @@ mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_doc_processor_backfill_time_range() {
+        let universe = Universe::with_accelerated_time();
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let (indexer_mailbox, indexer_inbox) = universe.create_test_mailbox();
+        let backfill_time_range = BackfillTimeRange {
+            start_timestamp: Some(1628837062),
+            end_timestamp: Some(1628837063),
+        };
+        let doc_processor = DocProcessor::try_new(
+            "my-index".to_string(),
+            "my-source".to_string(),
+            doc_mapper,
+            indexer_mailbox,
+            None,
+            SourceInputFormat::Json,
+            Some(backfill_time_range),
+        )
+        .unwrap();
+        let (doc_processor_mailbox, doc_processor_handle) =
+            universe.spawn_builder().spawn(doc_processor);
+        doc_processor_mailbox
+            .send_message(RawDocBatch::for_test(
+                &[
+                    br#"{"body": "before", "timestamp": 1628837061}"#,
+                    br#"{"body": "within", "timestamp": 1628837062}"#,
+                    br#"{"body": "after", "timestamp": 1628837063}"#,
+                ],
+                0..3,
+            ))
+            .await
+            .unwrap();
+
+        let counters = doc_processor_handle
+            .process_pending_and_observe()
+            .await
+            .state;
+        assert_eq!(counters.valid.get_num_docs(), 1);
+        assert_eq!(counters.out_of_range.get_num_docs(), 2);
+        assert_eq!(counters.num_processed_docs(), 3);
+        assert_eq!(counters.num_invalid_docs(), 0);
+
+        let output_messages = indexer_inbox.drain_for_test();
+        assert_eq!(output_messages.len(), 1);
+        let batch = *(output_messages
+            .into_iter()
+            .next()
+            .unwrap()
+            .downcast::<ProcessedDocBatch>()
+            .unwrap());
+        assert_eq!(batch.docs.len(), 1);
+        universe.assert_quit().await;
+    }
+
     const DOCMAPPER_WITH_PARTITION_JSON: &str = r#"
         {
             "tag_fields": ["tenant"],
@@ -732,6 +990,52 @@ mod tests {
             ]
         }"#;
 
+    #[tokio::test]
+    async fn test_doc_processor_yields_when_budget_is_exhausted() {
+        let universe = Universe::with_accelerated_time();
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let (indexer_mailbox, indexer_inbox) = universe.create_test_mailbox();
+        let mut doc_processor = DocProcessor::try_new(
+            "my-index".to_string(),
+            "my-source".to_string(),
+            doc_mapper,
+            indexer_mailbox,
+            None,
+            SourceInputFormat::Json,
+            None,
+        )
+        .unwrap();
+        // The doc processor yields after each document.
+        doc_processor.yield_budget = Duration::ZERO;
+
+        let (doc_processor_mailbox, doc_processor_handle) =
+            universe.spawn_builder().spawn(doc_processor);
+        let docs: Vec<&[u8]> = (0..100)
+            .map(|_| br#"{"body": "happy", "timestamp": 1628837062}"#.as_slice())
+            .collect();
+        doc_processor_mailbox
+            .send_message(RawDocBatch::for_test(&docs, 0..100))
+            .await
+            .unwrap();
+
+        let counters = doc_processor_handle
+            .process_pending_and_observe()
+            .await
+            .state;
+        assert_eq!(counters.valid.get_num_docs(), 100);
+
+        let output_messages = indexer_inbox.drain_for_test();
+        assert_eq!(output_messages.len(), 1);
+        let batch = *(output_messages
+            .into_iter()
+            .next()
+            .unwrap()
+            .downcast::<ProcessedDocBatch>()
+            .unwrap());
+        assert_eq!(batch.docs.len(), 100);
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_doc_processor_partitioning() {
         let doc_mapper: Arc<DocMapper> =
@@ -745,6 +1049,7 @@ mod tests {
             indexer_mailbox,
             None,
             SourceInputFormat::Json,
+            None,
         )
         .unwrap();
         let (doc_processor_mailbox, doc_processor_handle) =
@@ -781,6 +1086,62 @@ mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_doc_processor_ingest_timestamp() {
+        let doc_mapper_json = r#"
+            {
+                "store_ingest_timestamp": true,
+                "timestamp_field": "timestamp",
+                "field_mappings": [
+                    { "name": "timestamp", "type": "datetime", "fast": true },
+                    { "name": "body", "type": "text" }
+                ]
+            }"#;
+        let doc_mapper: Arc<DocMapper> =
+            Arc::new(serde_json::from_str::<DocMapper>(doc_mapper_json).unwrap());
+        let universe = Universe::with_accelerated_time();
+        let (indexer_mailbox, indexer_inbox) = universe.create_test_mailbox();
+        let doc_processor = DocProcessor::try_new(
+            "my-index".to_string(),
+            "my-source".to_string(),
+            doc_mapper.clone(),
+            indexer_mailbox,
+            None,
+            SourceInputFormat::Json,
+            None,
+        )
+        .unwrap();
+        let (doc_processor_mailbox, doc_processor_handle) =
+            universe.spawn_builder().spawn(doc_processor);
+        let start_timestamp = DateTime::from_utc(tantivy::time::OffsetDateTime::now_utc());
+        doc_processor_mailbox
+            .send_message(RawDocBatch::for_test(
+                &[br#"{"timestamp": 1628837062, "body": "happy"}"#],
+                0..1,
+            ))
+            .await
+            .unwrap();
+        doc_processor_handle.process_pending_and_observe().await;
+        let end_timestamp = DateTime::from_utc(tantivy::time::OffsetDateTime::now_utc());
+
+        let processed_doc_batches: Vec<ProcessedDocBatch> = indexer_inbox.drain_for_test_typed();
+        assert_eq!(processed_doc_batches.len(), 1);
+        let processed_doc = &processed_doc_batches[0].docs[0];
+        let ingest_timestamp_field = doc_mapper.ingest_timestamp_field().unwrap();
+        let ingest_timestamp = processed_doc
+            .doc
+            .get_first(ingest_timestamp_field)
+            .and_then(|value| value.as_datetime())
+            .unwrap();
+        assert!(start_timestamp <= ingest_timestamp);
+        assert!(ingest_timestamp <= end_timestamp);
+        assert_eq!(
+            processed_doc.timestamp_opt,
+            Some(DateTime::from_timestamp_secs(1628837062))
+        );
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_doc_processor_forward_publish_lock() {
         let doc_mapper = Arc::new(default_doc_mapper_for_test());
@@ -793,6 +1154,7 @@ mod tests {
             indexer_mailbox,
             None,
             SourceInputFormat::Json,
+            None,
         )
         .unwrap();
         let (doc_processor_mailbox, doc_processor_handle) =
@@ -825,6 +1187,7 @@ mod tests {
             indexer_mailbox,
             None,
             SourceInputFormat::Json,
+            None,
         )
         .unwrap();
         let (doc_processor_mailbox, doc_processor_handle) =
@@ -871,6 +1234,7 @@ mod tests {
             indexer_mailbox,
             None,
             SourceInputFormat::OtlpLogsJson,
+            None,
         )
         .unwrap();
 
@@ -948,6 +1312,7 @@ mod tests {
             indexer_mailbox,
             None,
             SourceInputFormat::OtlpLogsProtobuf,
+            None,
         )
         .unwrap();
 
@@ -1027,6 +1392,7 @@ mod tests {
             indexer_mailbox,
             None,
             SourceInputFormat::OtlpTracesJson,
+            None,
         )
         .unwrap();
 
@@ -1100,6 +1466,7 @@ mod tests {
             indexer_mailbox,
             None,
             SourceInputFormat::OtlpTracesProtobuf,
+            None,
         )
         .unwrap();
 
@@ -1186,6 +1553,7 @@ mod tests_vrl {
             indexer_mailbox,
             Some(transform_config),
             SourceInputFormat::Json,
+            None,
         )
         .unwrap();
         let (doc_processor_mailbox, doc_processor_handle) =
@@ -1252,6 +1620,39 @@ mod tests_vrl {
         Ok(())
     }
 
+    #[test]
+    fn test_dry_run_transform() {
+        let transform_config = TransformConfig::for_test(".body = upcase(string!(.body))");
+        let raw_docs = vec![
+            Bytes::from_static(br#"{"body": "happy"}"#),
+            Bytes::from_static(br#"{"body": 42}"#),
+            Bytes::from_static(b"{"),
+        ];
+        let results =
+            dry_run_transform(SourceInputFormat::Json, transform_config, raw_docs).unwrap();
+        assert_eq!(results.len(), 3);
+
+        let transformed_doc = results[0].as_ref().unwrap();
+        assert_eq!(
+            JsonValue::Object(transformed_doc.clone()),
+            serde_json::json!({"body": "HAPPY"})
+        );
+        assert!(matches!(results[1], Err(DocProcessorError::Transform(_))));
+        assert!(matches!(results[2], Err(DocProcessorError::JsonParsing(_))));
+
+        let transform_config = TransformConfig::for_test(".body = upcase(string!(.plain_text))");
+        let raw_docs = vec![Bytes::from_static(b"happy")];
+        let results =
+            dry_run_transform(SourceInputFormat::PlainText, transform_config, raw_docs).unwrap();
+        assert_eq!(
+            JsonValue::Object(results[0].as_ref().unwrap().clone()),
+            serde_json::json!({"body": "HAPPY", "plain_text": "happy"})
+        );
+
+        let transform_config = TransformConfig::for_test(".body = upcase(");
+        dry_run_transform(SourceInputFormat::Json, transform_config, Vec::new()).unwrap_err();
+    }
+
     #[tokio::test]
     async fn test_doc_processor_with_plain_text_input() {
         let index_id = "my-index";
@@ -1277,6 +1678,7 @@ mod tests_vrl {
             indexer_mailbox,
             Some(transform_config),
             SourceInputFormat::PlainText,
+            None,
         )
         .unwrap();
         let (doc_processor_mailbox, doc_processor_handle) =
@@ -16,6 +16,7 @@ use std::collections::hash_map::Entry;
 use std::num::NonZeroU32;
 use std::ops::RangeInclusive;
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::Context;
 use async_trait::async_trait;
@@ -30,7 +31,7 @@ use quickwit_common::io::IoControls;
 use quickwit_common::metrics::GaugeGuard;
 use quickwit_common::runtimes::RuntimeType;
 use quickwit_common::temp_dir::TempDirectory;
-use quickwit_config::IndexingSettings;
+use quickwit_config::{DocstoreCompression, IndexingSettings};
 use quickwit_doc_mapper::DocMapper;
 use quickwit_metastore::checkpoint::{IndexCheckpointDelta, SourceCheckpointDelta};
 use quickwit_proto::indexing::{IndexingPipelineId, PipelineMetrics};
@@ -51,6 +52,7 @@ use ulid::Ulid;
 
 use crate::actors::cooperative_indexing::{CooperativeIndexingCycle, CooperativeIndexingPeriod};
 use crate::actors::IndexSerializer;
+use crate::metrics::{observe_pipeline_stage_duration, PipelineStage};
 use crate::models::{
     CommitTrigger, EmptySplit, IndexedSplitBatchBuilder, IndexedSplitBuilder, NewPublishLock,
     NewPublishToken, ProcessedDoc, ProcessedDocBatch, PublishLock,
@@ -101,13 +103,8 @@ struct IndexerState {
 }
 
 impl IndexerState {
-    fn create_indexed_split_builder(
-        &self,
-        partition_id: u64,
-        last_delete_opstamp: u64,
-        ctx: &ActorContext<Indexer>,
-    ) -> anyhow::Result<IndexedSplitBuilder> {
-        let index_builder = IndexBuilder::new()
+    fn index_builder(&self) -> IndexBuilder {
+        IndexBuilder::new()
             .settings(self.index_settings.clone())
             .schema(self.schema.clone())
             .tokenizers(self.tokenizer_manager.clone())
@@ -115,8 +112,17 @@ impl IndexerState {
                 get_quickwit_fastfield_normalizer_manager()
                     .tantivy_manager()
                     .clone(),
-            );
+            )
+    }
 
+    fn create_indexed_split_builder(
+        &self,
+        partition_id: u64,
+        last_delete_opstamp: u64,
+        ctx: &ActorContext<Indexer>,
+    ) -> anyhow::Result<IndexedSplitBuilder> {
+        let index_builder = self.index_builder();
+        let spill_segments = self.indexing_settings.resources.segment_heap_size.is_some();
         let io_controls = IoControls::default()
             .set_progress(ctx.progress().clone())
             .set_kill_switch(ctx.kill_switch().clone())
@@ -130,6 +136,7 @@ impl IndexerState {
             self.indexing_directory.clone(),
             index_builder,
             io_controls,
+            spill_segments,
         )?;
         info!(
             split_id=%indexed_split.split_id(),
@@ -299,6 +306,7 @@ impl IndexerState {
             .context("batch delta does not follow indexer checkpoint")?;
         let mut memory_usage_delta: i64 = 0;
         counters.num_doc_batches_in_workbench += 1;
+        let index_start = Instant::now();
         for doc in batch.docs {
             let ProcessedDoc {
                 doc,
@@ -331,11 +339,26 @@ impl IndexerState {
                 .index_writer
                 .add_document(doc)
                 .context("failed to add document")?;
-            let mem_usage_after = indexed_split.index_writer.mem_usage() as u64;
+            let mut mem_usage_after = indexed_split.index_writer.mem_usage() as u64;
+
+            if let Some(segment_heap_size) = self.indexing_settings.resources.segment_heap_size {
+                if mem_usage_after >= segment_heap_size.as_u64() {
+                    indexed_split
+                        .spill_segment(self.index_builder())
+                        .context("failed to spill segment")?;
+                    mem_usage_after = indexed_split.index_writer.mem_usage() as u64;
+                }
+            }
             memory_usage_delta += mem_usage_after as i64 - mem_usage_before as i64;
             ctx.record_progress();
         }
         memory_usage.add(memory_usage_delta);
+        observe_pipeline_stage_duration(
+            &self.pipeline_id.index_uid.index_id,
+            &self.pipeline_id.source_id,
+            PipelineStage::Index,
+            index_start.elapsed(),
+        );
         Ok(())
     }
 }
@@ -544,9 +567,13 @@ impl Indexer {
     ) -> Self {
         let schema = doc_mapper.schema();
         let tokenizer_manager = doc_mapper.tokenizer_manager().clone();
-        let docstore_compression = Compressor::Zstd(ZstdCompressor {
-            compression_level: Some(indexing_settings.docstore_compression_level),
-        });
+        let docstore_compression = match indexing_settings.docstore_compression {
+            DocstoreCompression::Zstd => Compressor::Zstd(ZstdCompressor {
+                compression_level: Some(indexing_settings.docstore_compression_level),
+            }),
+            DocstoreCompression::Lz4 => Compressor::Lz4,
+            DocstoreCompression::None => Compressor::None,
+        };
         let index_settings = IndexSettings {
             docstore_blocksize: indexing_settings.docstore_blocksize,
             docstore_compression,
@@ -589,6 +616,20 @@ impl Indexer {
         }
     }
 
+    /// Returns the uncompressed size of the documents in the workbench.
+    fn num_bytes_in_workbench(&self) -> ByteSize {
+        let Some(workbench) = &self.indexing_workbench_opt else {
+            return ByteSize(0u64);
+        };
+        let num_bytes = workbench
+            .indexed_splits
+            .values()
+            .chain(workbench.other_indexed_split_opt.iter())
+            .map(|split| split.split_attrs.uncompressed_docs_size_in_bytes)
+            .sum::<u64>();
+        ByteSize(num_bytes)
+    }
+
     async fn index_batch(
         &mut self,
         batch: ProcessedDocBatch,
@@ -615,6 +656,14 @@ impl Indexer {
             self.send_to_serializer(CommitTrigger::NumDocsLimit, ctx)
                 .await?;
         }
+        if let Some(split_num_bytes_target) =
+            self.indexer_state.indexing_settings.split_num_bytes_target
+        {
+            if self.num_bytes_in_workbench() >= split_num_bytes_target {
+                self.send_to_serializer(CommitTrigger::NumBytesLimit, ctx)
+                    .await?;
+            }
+        }
         if force_commit {
             self.send_to_serializer(CommitTrigger::ForceCommit, ctx)
                 .await?;
@@ -747,6 +796,82 @@ mod tests {
         )
     }
 
+    #[tokio::test]
+    async fn test_indexer_triggers_commit_on_target_num_bytes() -> anyhow::Result<()> {
+        let index_uid = IndexUid::new_with_random_ulid("test-index");
+        let pipeline_id = IndexingPipelineId {
+            index_uid: index_uid.clone(),
+            source_id: "test-source".to_string(),
+            node_id: NodeId::from("test-node"),
+            pipeline_uid: PipelineUid::default(),
+        };
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let schema = doc_mapper.schema();
+        let body_field = schema.get_field("body").unwrap();
+        let timestamp_field = schema.get_field("timestamp").unwrap();
+        let indexing_directory = TempDirectory::for_test();
+        let mut indexing_settings = IndexingSettings::for_test();
+        indexing_settings.split_num_bytes_target = Some(ByteSize(100));
+        let universe = Universe::with_accelerated_time();
+        let (index_serializer_mailbox, index_serializer_inbox) = universe.create_test_mailbox();
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_last_delete_opstamp()
+            .times(1)
+            .returning(move |delete_opstamp_request| {
+                assert_eq!(delete_opstamp_request.index_uid(), &index_uid);
+                Ok(LastDeleteOpstampResponse::new(10))
+            });
+        mock_metastore.expect_publish_splits().never();
+        let indexer = Indexer::new(
+            pipeline_id,
+            doc_mapper,
+            MetastoreServiceClient::from_mock(mock_metastore),
+            indexing_directory,
+            indexing_settings,
+            None,
+            index_serializer_mailbox,
+        );
+        let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
+
+        for checkpoint_start in [0, 2] {
+            let processed_docs = (0..2)
+                .map(|_| ProcessedDoc {
+                    doc: doc!(
+                        body_field=>"this is a test document",
+                        timestamp_field=>DateTime::from_timestamp_secs(1_662_529_435)
+                    ),
+                    timestamp_opt: Some(DateTime::from_timestamp_secs(1_662_529_435)),
+                    partition: 1,
+                    num_bytes: 30,
+                })
+                .collect();
+            indexer_mailbox
+                .send_message(ProcessedDocBatch::new(
+                    processed_docs,
+                    SourceCheckpointDelta::from_range(checkpoint_start..checkpoint_start + 2),
+                    false,
+                ))
+                .await?;
+        }
+        let indexer_counters = indexer_handle.process_pending_and_observe().await.state;
+        assert_eq!(indexer_counters.num_splits_emitted, 1);
+        assert_eq!(indexer_counters.num_docs_in_workbench, 0);
+
+        let messages: Vec<IndexedSplitBatchBuilder> = index_serializer_inbox.drain_for_test_typed();
+        assert_eq!(messages.len(), 1);
+        let batch = messages.into_iter().next().unwrap();
+        assert_eq!(batch.commit_trigger, CommitTrigger::NumBytesLimit);
+        assert_eq!(batch.splits[0].split_attrs.num_docs, 4);
+        assert_eq!(
+            batch.splits[0].split_attrs.uncompressed_docs_size_in_bytes,
+            120
+        );
+        batch.splits.into_iter().next().unwrap().finalize()?;
+        universe.assert_quit().await;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_indexer_triggers_commit_on_target_num_docs() -> anyhow::Result<()> {
         let index_uid = IndexUid::new_with_random_ulid("test-index");
@@ -961,6 +1086,88 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_indexer_spills_segments_on_segment_heap_size() -> anyhow::Result<()> {
+        let universe = Universe::new();
+        let pipeline_id = IndexingPipelineId {
+            index_uid: IndexUid::new_with_random_ulid("test-index"),
+            source_id: "test-source".to_string(),
+            node_id: NodeId::from("test-node"),
+            pipeline_uid: PipelineUid::default(),
+        };
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let schema = doc_mapper.schema();
+        let body_field = schema.get_field("body").unwrap();
+        let indexing_directory = TempDirectory::for_test();
+        let mut indexing_settings = IndexingSettings::for_test();
+        indexing_settings.split_num_docs_target = 3_000;
+        indexing_settings.resources.heap_size = ByteSize::mb(16);
+        indexing_settings.resources.segment_heap_size = Some(ByteSize::mb(8));
+        let (index_serializer_mailbox, index_serializer_inbox) = universe.create_test_mailbox();
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_last_delete_opstamp()
+            .returning(move |_last_delete_opstamp_request| Ok(LastDeleteOpstampResponse::new(10)));
+        mock_metastore.expect_publish_splits().never();
+        let indexer = Indexer::new(
+            pipeline_id,
+            doc_mapper,
+            MetastoreServiceClient::from_mock(mock_metastore),
+            indexing_directory,
+            indexing_settings,
+            None,
+            index_serializer_mailbox,
+        );
+        let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
+
+        let make_doc = |i: u64| {
+            let mut body = String::new();
+            for val in 100 * i..100 * (i + 1) {
+                write!(&mut body, "{val} ").unwrap();
+            }
+            let num_bytes = body.len() * 2;
+            ProcessedDoc {
+                doc: doc!(body_field=>body),
+                timestamp_opt: None,
+                partition: 0,
+                num_bytes,
+            }
+        };
+        for batch_idx in 0..30 {
+            let docs = (batch_idx * 100..(batch_idx + 1) * 100)
+                .map(make_doc)
+                .collect();
+            indexer_mailbox
+                .send_message(ProcessedDocBatch::new(
+                    docs,
+                    SourceCheckpointDelta::from_range(batch_idx * 100..(batch_idx + 1) * 100),
+                    false,
+                ))
+                .await?;
+        }
+        indexer_handle.process_pending_and_observe().await;
+
+        let messages: Vec<IndexedSplitBatchBuilder> = index_serializer_inbox.drain_for_test_typed();
+        assert_eq!(messages.len(), 1);
+        let batch = messages.into_iter().next().unwrap();
+        // The segments were spilled before the memory limit was reached.
+        assert_eq!(batch.commit_trigger, CommitTrigger::NumDocsLimit);
+        assert_eq!(batch.splits.len(), 1);
+
+        let split_builder = batch.splits.into_iter().next().unwrap();
+        assert!(split_builder.num_spilled_segments() > 0);
+
+        let split = split_builder.finalize()?;
+        assert_eq!(split.split_attrs.num_docs, 3_000);
+
+        let segment_metas = split.index.searchable_segment_metas()?;
+        assert_eq!(segment_metas.len(), 1);
+        assert_eq!(segment_metas[0].num_docs(), 3_000);
+
+        universe.assert_quit().await;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_indexer_triggers_commit_on_timeout() -> anyhow::Result<()> {
         let universe = Universe::new();
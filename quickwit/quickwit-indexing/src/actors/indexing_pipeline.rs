@@ -45,7 +45,8 @@ use crate::actors::{Indexer, Packager, Publisher, Uploader};
 use crate::merge_policy::MergePolicy;
 use crate::models::IndexingStatistics;
 use crate::source::{
-    quickwit_supported_sources, AssignShards, Assignment, SourceActor, SourceRuntime,
+    partition_lags_from_observable_state, quickwit_supported_sources, AssignShards, Assignment,
+    SourceActor, SourceRuntime, TimedSource,
 };
 use crate::split_store::IndexingSplitStore;
 use crate::SplitsUpdateMailbox;
@@ -54,6 +55,9 @@ const SUPERVISE_INTERVAL: Duration = Duration::from_secs(1);
 
 const MAX_RETRY_DELAY: Duration = Duration::from_secs(600); // 10 min.
 
+/// Minimum duration over which the throughput of the pipeline is averaged.
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
+
 #[derive(Debug)]
 struct SuperviseLoop;
 
@@ -120,6 +124,10 @@ pub struct IndexingPipeline {
     // requiring a respawn of the pipeline.
     // We keep the list of shards here however, to reassign them after a respawn.
     shard_ids: BTreeSet<ShardId>,
+
+    // Start of the current throughput window and number of bytes processed at that time.
+    throughput_window_start: Instant,
+    throughput_window_start_num_bytes: u64,
 }
 
 #[async_trait]
@@ -165,6 +173,8 @@ impl IndexingPipeline {
                 ..Default::default()
             },
             shard_ids: Default::default(),
+            throughput_window_start: Instant::now(),
+            throughput_window_start_num_bytes: 0,
         }
     }
 
@@ -247,10 +257,12 @@ impl IndexingPipeline {
         let Some(handles) = &self.handles_opt else {
             return;
         };
+        handles.source_handle.refresh_observe();
         handles.doc_processor.refresh_observe();
         handles.indexer.refresh_observe();
         handles.uploader.refresh_observe();
         handles.publisher.refresh_observe();
+        let throughput_bytes_per_sec = self.statistics.throughput_bytes_per_sec;
         self.statistics = self
             .previous_generations_statistics
             .clone()
@@ -266,9 +278,52 @@ impl IndexingPipeline {
         self.statistics.pipeline_metrics_opt = pipeline_metrics_opt;
         self.statistics.params_fingerprint = self.params.params_fingerprint;
         self.statistics.shard_ids.clone_from(&self.shard_ids);
+        self.statistics.throughput_bytes_per_sec = throughput_bytes_per_sec;
+        self.statistics.partition_lags =
+            partition_lags_from_observable_state(&handles.source_handle.last_observation());
+        self.update_throughput();
+        self.update_source_metrics();
         ctx.observe(self);
     }
 
+    fn update_throughput(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.throughput_window_start);
+
+        if elapsed < THROUGHPUT_WINDOW {
+            return;
+        }
+        let total_bytes_processed = self.statistics.total_bytes_processed;
+        let num_bytes =
+            total_bytes_processed.saturating_sub(self.throughput_window_start_num_bytes);
+        self.statistics.throughput_bytes_per_sec =
+            (num_bytes as f64 / elapsed.as_secs_f64()) as u64;
+        self.throughput_window_start = now;
+        self.throughput_window_start_num_bytes = total_bytes_processed;
+
+        let index_id = self.params.pipeline_id.index_uid.index_id.as_str();
+        let source_id = self.params.pipeline_id.source_id.as_str();
+        crate::metrics::INDEXER_METRICS
+            .source_processed_bytes_total
+            .with_label_values([index_id, source_id])
+            .inc_by(num_bytes);
+    }
+
+    fn update_source_metrics(&self) {
+        let index_id = self.params.pipeline_id.index_uid.index_id.as_str();
+        let source_id = self.params.pipeline_id.source_id.as_str();
+
+        for partition_lag in &self.statistics.partition_lags {
+            let Some(lag) = partition_lag.lag_opt else {
+                continue;
+            };
+            crate::metrics::INDEXER_METRICS
+                .source_partition_lag
+                .with_label_values([index_id, source_id, partition_lag.partition_id.as_str()])
+                .set(lag as i64);
+        }
+    }
+
     /// Checks if some actors have terminated.
     async fn perform_health_check(
         &mut self,
@@ -420,6 +475,7 @@ impl IndexingPipeline {
             indexer_mailbox,
             self.params.source_config.transform_config.clone(),
             self.params.source_config.input_format,
+            self.params.source_config.backfill_time_range,
         )?;
         let (doc_processor_mailbox, doc_processor_handle) = ctx
             .spawn_actor()
@@ -443,6 +499,7 @@ impl IndexingPipeline {
         let source = ctx
             .protect_future(quickwit_supported_sources().load_source(source_runtime))
             .await?;
+        let source = Box::new(TimedSource::new(source, &self.params.pipeline_id));
         let actor_source = SourceActor {
             source,
             doc_processor_mailbox,
@@ -647,6 +704,8 @@ mod tests {
             source_params: SourceParams::file_from_str(test_file).unwrap(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let source_config_clone = source_config.clone();
 
@@ -768,6 +827,8 @@ mod tests {
             source_params: SourceParams::file_from_str(test_file).unwrap(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let source_config_clone = source_config.clone();
 
@@ -874,6 +935,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let source_config_clone = source_config.clone();
 
@@ -910,6 +973,7 @@ mod tests {
             retention_policy: None,
             max_concurrent_split_uploads: 2,
             merge_io_throughput_limiter_opt: None,
+            merge_read_throughput_limiter_opt: None,
             merge_scheduler_service: universe.get_or_spawn_one(),
             event_broker: Default::default(),
         };
@@ -980,6 +1044,8 @@ mod tests {
             source_params: SourceParams::file_from_str(test_file).unwrap(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let source_config_clone = source_config.clone();
 
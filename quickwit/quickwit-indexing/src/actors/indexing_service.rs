@@ -62,7 +62,10 @@ use tracing::{debug, error, info, warn};
 use super::merge_pipeline::{MergePipeline, MergePipelineParams};
 use super::{MergePlanner, MergeSchedulerService};
 use crate::actors::merge_pipeline::FinishPendingMergesAndShutdownPipeline;
-use crate::models::{DetachIndexingPipeline, DetachMergePipeline, ObservePipeline, SpawnPipeline};
+use crate::models::{
+    DetachIndexingPipeline, DetachMergePipeline, ObservePipeline, ObserveSourceLags,
+    SourceLagReport, SpawnPipeline,
+};
 use crate::source::{AssignShards, Assignment};
 use crate::split_store::{IndexingSplitCache, SplitStoreQuota};
 use crate::{IndexingPipeline, IndexingPipelineParams, IndexingSplitStore, IndexingStatistics};
@@ -114,6 +117,7 @@ pub struct IndexingService {
     merge_pipeline_handles: HashMap<MergePipelineId, MergePipelineHandle>,
     cooperative_indexing_permits: Option<Arc<Semaphore>>,
     merge_io_throughput_limiter_opt: Option<Limiter>,
+    merge_read_throughput_limiter_opt: Option<Limiter>,
     event_broker: EventBroker,
 }
 
@@ -149,6 +153,8 @@ impl IndexingService {
         )?;
         let merge_io_throughput_limiter_opt =
             indexer_config.max_merge_write_throughput.map(io::limiter);
+        let merge_read_throughput_limiter_opt =
+            indexer_config.max_merge_read_throughput.map(io::limiter);
         let split_cache_dir_path = get_cache_directory_path(&data_dir_path);
         let local_split_store =
             IndexingSplitCache::open(split_cache_dir_path, split_store_space_quota).await?;
@@ -176,6 +182,7 @@ impl IndexingService {
             max_concurrent_split_uploads: indexer_config.max_concurrent_split_uploads,
             merge_pipeline_handles: HashMap::new(),
             merge_io_throughput_limiter_opt,
+            merge_read_throughput_limiter_opt,
             cooperative_indexing_permits,
             event_broker,
         })
@@ -283,7 +290,10 @@ impl IndexingService {
             })?;
         let storage = self
             .storage_resolver
-            .resolve(&index_config.index_uri)
+            .resolve_with_credentials(
+                &index_config.index_uri,
+                index_config.storage_credentials_opt.as_deref(),
+            )
             .await
             .map_err(|error| {
                 let message = format!("failed to spawn indexing pipeline: {error}");
@@ -292,7 +302,12 @@ impl IndexingService {
         let merge_policy =
             crate::merge_policy::merge_policy_from_settings(&index_config.indexing_settings);
         let retention_policy = index_config.retention_policy_opt.clone();
-        let split_store = IndexingSplitStore::new(storage.clone(), self.local_split_store.clone());
+        let split_store = IndexingSplitStore::new(
+            storage.clone(),
+            self.local_split_store.clone(),
+            index_config.indexing_settings.split_storage_classes.clone(),
+            index_config.storage_credentials_opt.clone(),
+        );
 
         let doc_mapper = build_doc_mapper(&index_config.doc_mapping, &index_config.search_settings)
             .map_err(|error| IndexingError::Internal(error.to_string()))?;
@@ -308,6 +323,7 @@ impl IndexingService {
             merge_policy: merge_policy.clone(),
             retention_policy: retention_policy.clone(),
             merge_io_throughput_limiter_opt: self.merge_io_throughput_limiter_opt.clone(),
+            merge_read_throughput_limiter_opt: self.merge_read_throughput_limiter_opt.clone(),
             max_concurrent_split_uploads: self.max_concurrent_split_uploads,
             event_broker: self.event_broker.clone(),
         };
@@ -890,6 +906,36 @@ impl Handler<ObservePipeline> for IndexingService {
     }
 }
 
+#[async_trait]
+impl Handler<ObserveSourceLags> for IndexingService {
+    type Reply = Vec<SourceLagReport>;
+
+    async fn handle(
+        &mut self,
+        _message: ObserveSourceLags,
+        _ctx: &ActorContext<Self>,
+    ) -> Result<Self::Reply, ActorExitStatus> {
+        let mut source_lag_reports = Vec::with_capacity(self.indexing_pipelines.len());
+
+        for pipeline_handle in self.indexing_pipelines.values() {
+            let pipeline_id = &pipeline_handle.indexing_pipeline_id;
+            let statistics = pipeline_handle.handle.last_observation();
+            let source_lag_report = SourceLagReport {
+                index_id: pipeline_id.index_uid.index_id.clone(),
+                source_id: pipeline_id.source_id.clone(),
+                pipeline_uid: pipeline_id.pipeline_uid,
+                throughput_bytes_per_sec: statistics.throughput_bytes_per_sec,
+                partition_lags: statistics.partition_lags.clone(),
+            };
+            source_lag_reports.push(source_lag_report);
+        }
+        source_lag_reports.sort_by(|left, right| {
+            (&left.index_id, &left.source_id).cmp(&(&right.index_id, &right.source_id))
+        });
+        Ok(source_lag_reports)
+    }
+}
+
 #[async_trait]
 impl Handler<DetachIndexingPipeline> for IndexingService {
     type Reply = Result<ActorHandle<IndexingPipeline>, IndexingError>;
@@ -1111,6 +1157,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let spawn_pipeline_msg = SpawnPipeline {
             index_id: index_id.clone(),
@@ -1192,6 +1240,8 @@ mod tests {
             }),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let create_index_request = CreateIndexRequest::try_from_index_and_source_configs(
             &index_config,
@@ -1279,6 +1329,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         {
             // Assign 2 indexing tasks
@@ -1328,6 +1380,8 @@ mod tests {
             source_params: SourceParams::Kafka(kafka_params),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         {
             // Assign 2 more indexing tasks (1 new source + activate ingest API source)
@@ -1535,6 +1589,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let create_index_request =
             CreateIndexRequest::try_from_index_config(&index_config).unwrap();
@@ -1664,6 +1720,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         index_metadata
             .sources
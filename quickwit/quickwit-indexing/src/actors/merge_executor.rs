@@ -489,22 +489,32 @@ impl MergeExecutor {
     ) -> anyhow::Result<ControlledDirectory> {
         let shadowing_meta_json_directory = create_shadowing_meta_json_directory(union_index_meta)?;
 
+        let io_controls = self
+            .io_controls
+            .clone()
+            .set_kill_switch(ctx.kill_switch().clone())
+            .set_progress(ctx.progress().clone());
+
         // This directory is here to receive the merged split, as well as the final meta.json file.
         let output_directory = ControlledDirectory::new(
             Box::new(MmapDirectory::open_with_madvice(
                 output_path,
                 Advice::Sequential,
             )?),
-            self.io_controls
-                .clone()
-                .set_kill_switch(ctx.kill_switch().clone())
-                .set_progress(ctx.progress().clone()),
+            io_controls.clone(),
         );
         let mut directory_stack: Vec<Box<dyn Directory>> = vec![
             output_directory.box_clone(),
             Box::new(shadowing_meta_json_directory),
         ];
-        directory_stack.extend(split_directories.into_iter());
+        // The split directories are wrapped so that reads are subject to the merge read
+        // throughput limit.
+        directory_stack.extend(split_directories.into_iter().map(|split_directory| {
+            Box::new(ControlledDirectory::new(
+                split_directory,
+                io_controls.clone(),
+            )) as Box<dyn Directory>
+        }));
         let union_directory = UnionDirectory::union_of(directory_stack);
         let union_index = open_index(
             union_directory,
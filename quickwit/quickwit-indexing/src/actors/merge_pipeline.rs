@@ -307,8 +307,10 @@ impl MergePipeline {
 
         // The merge and split download share the same throughput limiter.
         // This is how cloning the `IoControls` works.
-        let merge_executor_io_controls =
-            split_downloader_io_controls.clone().set_component("merger");
+        let merge_executor_io_controls = split_downloader_io_controls
+            .clone()
+            .set_read_throughput_limiter_opt(self.params.merge_read_throughput_limiter_opt.clone())
+            .set_component("merger");
 
         let merge_executor = MergeExecutor::new(
             self.params.pipeline_id.clone(),
@@ -572,6 +574,7 @@ pub struct MergePipelineParams {
     pub retention_policy: Option<RetentionPolicy>,
     pub max_concurrent_split_uploads: usize, //< TODO share with the indexing pipeline.
     pub merge_io_throughput_limiter_opt: Option<Limiter>,
+    pub merge_read_throughput_limiter_opt: Option<Limiter>,
     pub event_broker: EventBroker,
 }
 
@@ -636,6 +639,7 @@ mod tests {
             retention_policy: None,
             max_concurrent_split_uploads: 2,
             merge_io_throughput_limiter_opt: None,
+            merge_read_throughput_limiter_opt: None,
             event_broker: Default::default(),
         };
         let pipeline = MergePipeline::new(pipeline_params, None, universe.spawn_ctx());
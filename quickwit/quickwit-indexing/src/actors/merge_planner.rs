@@ -424,6 +424,7 @@ mod tests {
                 maturation_period: Duration::from_secs(3600),
             },
             50_000,
+            None,
         ));
         let universe = Universe::with_accelerated_time();
         let (merge_split_downloader_mailbox, merge_split_downloader_inbox) =
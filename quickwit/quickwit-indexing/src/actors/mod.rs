@@ -30,7 +30,7 @@ mod uploader;
 #[cfg(feature = "vrl")]
 mod vrl_processing;
 
-pub use doc_processor::{DocProcessor, DocProcessorCounters};
+pub use doc_processor::{dry_run_transform, DocProcessor, DocProcessorCounters, DocProcessorError};
 pub use index_serializer::IndexSerializer;
 pub use indexer::{Indexer, IndexerCounters};
 pub use indexing_pipeline::{IndexingPipeline, IndexingPipelineParams};
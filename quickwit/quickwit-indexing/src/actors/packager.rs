@@ -16,6 +16,7 @@ use std::collections::BTreeSet;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::{bail, Context};
 use async_trait::async_trait;
@@ -44,6 +45,7 @@ const MAX_VALUES_PER_TAG_FIELD: usize = if cfg!(any(test, feature = "testsuite")
 };
 
 use crate::actors::Uploader;
+use crate::metrics::{observe_pipeline_stage_duration, PipelineStage};
 use crate::models::{
     EmptySplit, IndexedSplit, IndexedSplitBatch, PackagedSplit, PackagedSplitBatch,
 };
@@ -135,6 +137,7 @@ impl Handler<IndexedSplitBatch> for Packager {
             "start-packaging-splits"
         );
         fail_point!("packager:before");
+        let pack_start = Instant::now();
         let mut packaged_splits = Vec::with_capacity(batch.splits.len());
         for split in batch.splits {
             if batch.publish_lock.is_dead() {
@@ -148,6 +151,15 @@ impl Handler<IndexedSplitBatch> for Packager {
             let packaged_split = self.process_indexed_split(split, ctx).await?;
             packaged_splits.push(packaged_split);
         }
+        // Merge pipelines also go through the packager: only indexing batches are reported.
+        if let (None, Some(packaged_split)) = (&batch.merge_task_opt, packaged_splits.first()) {
+            observe_pipeline_stage_duration(
+                &packaged_split.split_attrs.index_uid.index_id,
+                &packaged_split.split_attrs.source_id,
+                PipelineStage::Pack,
+                pack_start.elapsed(),
+            );
+        }
         ctx.send_message(
             &self.uploader_mailbox,
             PackagedSplitBatch::new(
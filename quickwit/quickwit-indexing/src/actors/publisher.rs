@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Instant;
+
 use anyhow::Context;
 use async_trait::async_trait;
 use fail::fail_point;
@@ -21,6 +23,7 @@ use serde::Serialize;
 use tracing::{info, instrument, warn};
 
 use crate::actors::MergePlanner;
+use crate::metrics::{observe_pipeline_stage_duration, PipelineStage};
 use crate::models::{NewSplits, SplitsUpdate};
 use crate::source::{SourceActor, SuggestTruncate};
 
@@ -144,6 +147,8 @@ impl Handler<SplitsUpdate> for Publisher {
             .map(|split| split.split_id.clone())
             .collect();
         if let Some(_guard) = publish_lock.acquire().await {
+            let publish_start = Instant::now();
+            let index_id = index_uid.index_id.clone();
             let publish_splits_request = PublishSplitsRequest {
                 index_uid: Some(index_uid),
                 staged_split_ids: split_ids.clone(),
@@ -154,6 +159,15 @@ impl Handler<SplitsUpdate> for Publisher {
             ctx.protect_future(self.metastore.publish_splits(publish_splits_request))
                 .await
                 .context("failed to publish splits")?;
+            // Only the indexing pipelines carry a checkpoint delta.
+            if let Some(checkpoint_delta) = &checkpoint_delta_opt {
+                observe_pipeline_stage_duration(
+                    &index_id,
+                    &checkpoint_delta.source_id,
+                    PipelineStage::Publish,
+                    publish_start.elapsed(),
+                );
+            }
         } else {
             // TODO: Remove the junk right away?
             info!(
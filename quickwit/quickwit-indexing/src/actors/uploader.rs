@@ -17,6 +17,7 @@ use std::iter::FromIterator;
 use std::mem;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::{bail, Context};
 use async_trait::async_trait;
@@ -32,7 +33,7 @@ use quickwit_metastore::{SplitMetadata, StageSplitsRequestExt};
 use quickwit_proto::metastore::{MetastoreService, MetastoreServiceClient, StageSplitsRequest};
 use quickwit_proto::search::{ReportSplit, ReportSplitsRequest};
 use quickwit_proto::types::{IndexUid, PublishToken};
-use quickwit_storage::SplitPayloadBuilder;
+use quickwit_storage::{PutPayload, SplitPayloadBuilder};
 use serde::Serialize;
 use tokio::sync::oneshot::Sender;
 use tokio::sync::{oneshot, Semaphore, SemaphorePermit};
@@ -41,7 +42,7 @@ use tracing::{debug, info, instrument, warn, Instrument, Span};
 use crate::actors::sequencer::{Sequencer, SequencerCommand};
 use crate::actors::Publisher;
 use crate::merge_policy::{MergePolicy, MergeTask};
-use crate::metrics::INDEXER_METRICS;
+use crate::metrics::{observe_pipeline_stage_duration, PipelineStage, INDEXER_METRICS};
 use crate::models::{
     create_split_metadata, EmptySplit, PackagedSplit, PackagedSplitBatch, PublishLock, SplitsUpdate,
 };
@@ -303,9 +304,22 @@ impl Handler<PackagedSplitBatch> for Uploader {
         let retention_policy = self.retention_policy.clone();
         debug!(split_ids=?split_ids, "start-stage-and-store-splits");
         let event_broker = self.event_broker.clone();
+        // Only the indexing pipelines report the time spent in the upload stage.
+        let stage_labels_opt: Option<(String, String)> =
+            if matches!(self.uploader_type, UploaderType::IndexUploader) {
+                batch.splits.first().map(|split| {
+                    (
+                        split.split_attrs.index_uid.index_id.clone(),
+                        split.split_attrs.source_id.clone(),
+                    )
+                })
+            } else {
+                None
+            };
         spawn_named_task(
             async move {
                 fail_point!("uploader:intask:before");
+                let upload_start = Instant::now();
 
                 let mut split_metadata_list = Vec::with_capacity(batch.splits.len());
                 let mut report_splits: Vec<ReportSplit> = Vec::with_capacity(batch.splits.len());
@@ -331,17 +345,27 @@ impl Handler<PackagedSplitBatch> for Uploader {
                             return;
                         }
                     };
-                    let split_metadata = create_split_metadata(
+                    let crc32c_checksum = match split_streamer.crc32c().await {
+                        Ok(crc32c_checksum) => crc32c_checksum,
+                        Err(e) => {
+                            warn!(cause=?e, split_id=packaged_split.split_id(), "could not compute split checksum");
+                            return;
+                        }
+                    };
+                    let mut split_metadata = create_split_metadata(
                         &merge_policy,
                         retention_policy.as_ref(),
                         &packaged_split.split_attrs,
                         packaged_split.tags.clone(),
                         split_streamer.footer_range.start..split_streamer.footer_range.end,
                     );
+                    split_metadata.crc32c_checksum = Some(crc32c_checksum);
 
                     report_splits.push(ReportSplit {
                         storage_uri: split_store.remote_uri().to_string(),
                         split_id: packaged_split.split_id().to_string(),
+                        storage_credentials: split_store.storage_credentials().map(ToString::to_string),
+                        index_id: Some(index_uid.index_id.clone()),
                     });
 
                     split_metadata_list.push(split_metadata);
@@ -387,6 +411,14 @@ impl Handler<PackagedSplitBatch> for Uploader {
 
                     packaged_splits_and_metadata.push((packaged_split, metadata));
                 }
+                if let Some((index_id, source_id)) = &stage_labels_opt {
+                    observe_pipeline_stage_duration(
+                        index_id,
+                        source_id,
+                        PipelineStage::Upload,
+                        upload_start.elapsed(),
+                    );
+                }
 
                 let splits_update = make_publish_operation(
                     index_uid,
@@ -512,7 +544,7 @@ async fn upload_split(
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use std::time::Duration;
 
     use quickwit_actors::{ObservationType, Universe};
@@ -521,7 +553,7 @@ mod tests {
     use quickwit_metastore::checkpoint::{IndexCheckpointDelta, SourceCheckpointDelta};
     use quickwit_proto::metastore::{EmptyResponse, MockMetastoreService};
     use quickwit_proto::types::{DocMappingUid, NodeId};
-    use quickwit_storage::RamStorage;
+    use quickwit_storage::{RamStorage, Storage};
     use tantivy::DateTime;
     use tokio::sync::oneshot;
 
@@ -551,6 +583,7 @@ mod tests {
                 index_uid.index_id == "test-index"
                     && split_metadata.split_id() == "test-split"
                     && split_metadata.time_range == Some(1628203589..=1628203640)
+                    && split_metadata.crc32c_checksum.is_some()
             })
             .times(1)
             .returning(|_| Ok(EmptyResponse {}));
@@ -643,6 +676,15 @@ mod tests {
         let mut files = ram_storage.list_files().await;
         files.sort();
         assert_eq!(&files, &[PathBuf::from("test-split.split")]);
+
+        let split_file = ram_storage
+            .get_all(Path::new("test-split.split"))
+            .await
+            .unwrap();
+        assert_eq!(
+            new_splits[0].crc32c_checksum,
+            Some(crc32c::crc32c(&split_file))
+        );
         universe.assert_quit().await;
         Ok(())
     }
@@ -13,19 +13,20 @@
 // limitations under the License.
 
 use std::io::{BufWriter, IntoInnerError};
-use std::ops::Deref;
+use std::ops::{Deref, Range};
 use std::path::Path;
 use std::sync::Arc;
 use std::{fmt, io};
 
 use arc_swap::ArcSwap;
+use async_trait::async_trait;
 use quickwit_common::io::{ControlledWrite, IoControls, IoControlsAccess};
 use quickwit_common::ProtectedZoneGuard;
 use tantivy::directory::error::{DeleteError, OpenReadError, OpenWriteError};
 use tantivy::directory::{
-    AntiCallToken, FileHandle, TerminatingWrite, WatchCallback, WatchHandle, WritePtr,
+    AntiCallToken, FileHandle, OwnedBytes, TerminatingWrite, WatchCallback, WatchHandle, WritePtr,
 };
-use tantivy::Directory;
+use tantivy::{Directory, HasLen};
 
 /// Buffer capacity.
 ///
@@ -37,7 +38,7 @@ const BUFFER_NUM_BYTES: usize = 8_192;
 /// with functionalities such as
 /// - records progress everytime a write (Note there is however a buffer writer above it)
 /// - if the killswitch is activated, returns an error on the first write happening after it
-/// - in the future, record a writing speed, possibly introduce some throttling, etc.
+/// - throttles writes and, if a read throughput limiter is set, reads.
 #[derive(Clone)]
 pub struct ControlledDirectory {
     underlying: Arc<dyn Directory>,
@@ -52,6 +53,15 @@ impl ControlledDirectory {
         }
     }
 
+    /// Wraps another directory, sharing the I/O controls of this directory: setting the I/O
+    /// controls of one of them applies to both.
+    pub fn with_shared_io_controls(&self, directory: Box<dyn Directory>) -> ControlledDirectory {
+        ControlledDirectory {
+            underlying: directory.into(),
+            io_controls: self.io_controls.clone(),
+        }
+    }
+
     pub fn check_if_alive(&self) -> io::Result<ProtectedZoneGuard> {
         self.io_controls.load().check_if_alive()
     }
@@ -71,7 +81,16 @@ impl Directory for ControlledDirectory {
     fn get_file_handle(&self, path: &Path) -> Result<Arc<dyn FileHandle>, OpenReadError> {
         self.check_if_alive()
             .map_err(|io_err| OpenReadError::wrap_io_error(io_err, path.to_path_buf()))?;
-        self.underlying.get_file_handle(path)
+        let underlying_file_handle = self.underlying.get_file_handle(path)?;
+
+        if !self.io_controls.load().has_read_throughput_limiter() {
+            return Ok(underlying_file_handle);
+        }
+        let controlled_file_handle = ControlledFileHandle {
+            underlying: underlying_file_handle,
+            io_controls: self.io_controls.clone(),
+        };
+        Ok(Arc::new(controlled_file_handle))
     }
 
     fn delete(&self, path: &Path) -> Result<(), DeleteError> {
@@ -153,6 +172,33 @@ impl IoControlsAccess for HotswappableIoControls {
     }
 }
 
+/// File handle throttling the reads of the underlying file handle.
+struct ControlledFileHandle {
+    underlying: Arc<dyn FileHandle>,
+    io_controls: HotswappableIoControls,
+}
+
+impl fmt::Debug for ControlledFileHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ControlledFileHandle({:?})", self.underlying.as_ref())
+    }
+}
+
+#[async_trait]
+impl FileHandle for ControlledFileHandle {
+    fn read_bytes(&self, byte_range: Range<usize>) -> io::Result<OwnedBytes> {
+        self.io_controls
+            .apply(|io_controls| io_controls.consume_read_blocking(byte_range.len()))?;
+        self.underlying.read_bytes(byte_range)
+    }
+}
+
+impl HasLen for ControlledFileHandle {
+    fn len(&self) -> usize {
+        self.underlying.len()
+    }
+}
+
 // Wrapper to work around the orphan rule. (hence the word "Adopted").
 struct AdoptedControlledWrite(ControlledWrite<HotswappableIoControls, Box<dyn TerminatingWrite>>);
 
@@ -179,6 +225,7 @@ impl TerminatingWrite for AdoptedControlledWrite {
 mod tests {
     use std::io::Write;
 
+    use bytesize::ByteSize;
     use tantivy::directory::RamDirectory;
 
     use super::*;
@@ -236,4 +283,22 @@ mod tests {
         wrt.terminate()?;
         Ok(())
     }
+
+    #[test]
+    fn test_controlled_file_handle_read() -> anyhow::Result<()> {
+        let directory = RamDirectory::default();
+        directory.atomic_write(Path::new("test"), b"hello")?;
+        let io_controls = IoControls::default()
+            .set_read_throughput_limiter_opt(Some(quickwit_common::io::limiter(ByteSize::mb(1))));
+        let controlled_directory =
+            ControlledDirectory::new(Box::new(directory), io_controls.clone());
+        let file_handle = controlled_directory.get_file_handle(Path::new("test"))?;
+        assert_eq!(file_handle.len(), 5);
+        assert_eq!(file_handle.read_bytes(0..5)?.as_slice(), b"hello");
+
+        io_controls.kill();
+        let err = file_handle.read_bytes(0..5).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        Ok(())
+    }
 }
@@ -30,7 +30,7 @@ pub use crate::actors::{
     IndexingPipelineParams, IndexingService, PublisherType, Sequencer, SplitsUpdateMailbox,
 };
 pub use crate::controlled_directory::ControlledDirectory;
-use crate::models::IndexingStatistics;
+use crate::models::{IndexingStatistics, PartitionLag, SourceLagReport};
 pub use crate::split_store::{get_tantivy_directory_from_split_bundle, IndexingSplitStore};
 
 pub mod actors;
@@ -51,7 +51,13 @@ use self::merge_policy::MergePolicy;
 pub use self::source::check_source_connectivity;
 
 #[derive(utoipa::OpenApi)]
-#[openapi(components(schemas(IndexingStatistics, PipelineMetrics, CpuCapacity)))]
+#[openapi(components(schemas(
+    IndexingStatistics,
+    PartitionLag,
+    PipelineMetrics,
+    CpuCapacity,
+    SourceLagReport
+)))]
 /// Schema used for the OpenAPI generation which are apart of this crate.
 pub struct IndexingApiSchemas;
 
@@ -46,11 +46,12 @@ const FINALIZE_MIN_MERGE_FACTOR: usize = 3;
 /// and for a given merge operation, we build split in a greedy way.
 /// After sorting the splits per creation date, we append splits one after the
 /// other until we either reach `max_merge_factor` or we exceed the
-/// targeted` split_num_docs`.
+/// targeted` split_num_docs` or, if set, the targeted `split_num_bytes`.
 #[derive(Debug, Clone)]
 pub struct ConstWriteAmplificationMergePolicy {
     config: ConstWriteAmplificationMergePolicyConfig,
     split_num_docs_target: usize,
+    split_num_bytes_target_opt: Option<u64>,
 }
 
 impl Default for ConstWriteAmplificationMergePolicy {
@@ -58,6 +59,7 @@ impl Default for ConstWriteAmplificationMergePolicy {
         ConstWriteAmplificationMergePolicy {
             config: Default::default(),
             split_num_docs_target: IndexingSettings::default_split_num_docs_target(),
+            split_num_bytes_target_opt: None,
         }
     }
 }
@@ -66,13 +68,24 @@ impl ConstWriteAmplificationMergePolicy {
     pub fn new(
         config: ConstWriteAmplificationMergePolicyConfig,
         split_num_docs_target: usize,
+        split_num_bytes_target_opt: Option<u64>,
     ) -> Self {
         ConstWriteAmplificationMergePolicy {
             config,
             split_num_docs_target,
+            split_num_bytes_target_opt,
         }
     }
 
+    /// Returns true if a split with the given number of docs and size has reached the split size
+    /// target.
+    fn is_split_size_target_reached(&self, num_docs: usize, num_bytes: u64) -> bool {
+        num_docs >= self.split_num_docs_target
+            || self
+                .split_num_bytes_target_opt
+                .is_some_and(|split_num_bytes_target| num_bytes >= split_num_bytes_target)
+    }
+
     #[cfg(test)]
     fn for_test() -> ConstWriteAmplificationMergePolicy {
         use std::time::Duration;
@@ -85,7 +98,7 @@ impl ConstWriteAmplificationMergePolicy {
             max_finalize_merge_operations: 0,
             max_finalize_split_num_docs: None,
         };
-        Self::new(config, 10_000_000)
+        Self::new(config, 10_000_000, None)
     }
 
     /// Returns a merge operation within one `num_merge_ops` level if one can be built from the
@@ -98,14 +111,16 @@ impl ConstWriteAmplificationMergePolicy {
     ) -> Option<MergeOperation> {
         let mut num_splits_in_merge = 0;
         let mut num_docs_in_merge = 0;
+        let mut num_bytes_in_merge = 0;
         for split in splits.iter().take(*merge_factor_range.end()) {
             num_docs_in_merge += split.num_docs;
+            num_bytes_in_merge += split.footer_offsets.end;
             num_splits_in_merge += 1;
-            if num_docs_in_merge >= self.split_num_docs_target {
+            if self.is_split_size_target_reached(num_docs_in_merge, num_bytes_in_merge) {
                 break;
             }
         }
-        if (num_docs_in_merge < self.split_num_docs_target)
+        if !self.is_split_size_target_reached(num_docs_in_merge, num_bytes_in_merge)
             && (num_splits_in_merge < *merge_factor_range.start())
         {
             return None;
@@ -228,11 +243,16 @@ impl MergePolicy for ConstWriteAmplificationMergePolicy {
         merge_operations
     }
 
-    fn split_maturity(&self, split_num_docs: usize, split_num_merge_ops: usize) -> SplitMaturity {
+    fn split_maturity(
+        &self,
+        split_num_docs: usize,
+        split_num_bytes: u64,
+        split_num_merge_ops: usize,
+    ) -> SplitMaturity {
         if split_num_merge_ops >= self.config.max_merge_ops {
             return SplitMaturity::Mature;
         }
-        if split_num_docs >= self.split_num_docs_target {
+        if self.is_split_size_target_reached(split_num_docs, split_num_bytes) {
             return SplitMaturity::Mature;
         }
         SplitMaturity::Immature {
@@ -281,7 +301,7 @@ mod tests {
 
     #[test]
     fn test_split_is_mature() {
-        let merge_policy = ConstWriteAmplificationMergePolicy::for_test();
+        let mut merge_policy = ConstWriteAmplificationMergePolicy::for_test();
         let split = create_splits(&merge_policy, vec![9_000_000])
             .into_iter()
             .next()
@@ -289,21 +309,39 @@ mod tests {
         // Split under max_merge_docs, num_merge_ops < max_merge_ops and created before now() -
         // maturation_period is not mature.
         assert_eq!(
-            merge_policy.split_maturity(split.num_docs, split.num_merge_ops),
+            merge_policy.split_maturity(
+                split.num_docs,
+                split.footer_offsets.end,
+                split.num_merge_ops
+            ),
             SplitMaturity::Immature {
                 maturation_period: Duration::from_secs(3600)
             }
         );
         // Split with docs > max_merge_docs is mature.
         assert_eq!(
-            merge_policy
-                .split_maturity(merge_policy.split_num_docs_target + 1, split.num_merge_ops),
+            merge_policy.split_maturity(
+                merge_policy.split_num_docs_target + 1,
+                0,
+                split.num_merge_ops
+            ),
             SplitMaturity::Mature
         );
 
         // Split with num_merge_ops >= max_merge_ops is mature
         assert_eq!(
-            merge_policy.split_maturity(split.num_docs, merge_policy.config.max_merge_ops),
+            merge_policy.split_maturity(
+                split.num_docs,
+                split.footer_offsets.end,
+                merge_policy.config.max_merge_ops
+            ),
+            SplitMaturity::Mature
+        );
+
+        // Split with num_bytes >= split_num_bytes_target is mature.
+        merge_policy.split_num_bytes_target_opt = Some(1_000);
+        assert_eq!(
+            merge_policy.split_maturity(split.num_docs, 1_000, split.num_merge_ops),
             SplitMaturity::Mature
         );
     }
@@ -322,7 +360,7 @@ mod tests {
             split_id: "01GE1R0KBFQHJ76030RYRAS8QA".to_string(),
             num_docs: 1,
             create_timestamp: 1665000000,
-            maturity: merge_policy.split_maturity(1, 0),
+            maturity: merge_policy.split_maturity(1, 0, 0),
             num_merge_ops: 4,
             ..Default::default()
         }];
@@ -341,7 +379,7 @@ mod tests {
                 num_docs: 1_000,
                 num_merge_ops: 1,
                 create_timestamp,
-                maturity: merge_policy.split_maturity(1_000, 1),
+                maturity: merge_policy.split_maturity(1_000, 0, 1),
                 ..Default::default()
             })
             .collect();
@@ -356,7 +394,7 @@ mod tests {
     #[test]
     fn test_const_write_merge_policy_merge_factor_max() {
         let merge_policy = ConstWriteAmplificationMergePolicy::for_test();
-        let time_to_maturity = merge_policy.split_maturity(1_000, 1);
+        let time_to_maturity = merge_policy.split_maturity(1_000, 0, 1);
         let create_timestamp = OffsetDateTime::now_utc().unix_timestamp();
         let mut splits =
             (0..merge_policy.config.max_merge_factor + merge_policy.config.merge_factor - 1)
@@ -380,7 +418,7 @@ mod tests {
     #[test]
     fn test_const_write_merge_policy_older_first() {
         let merge_policy = ConstWriteAmplificationMergePolicy::for_test();
-        let time_to_maturity = merge_policy.split_maturity(1_000, 1);
+        let time_to_maturity = merge_policy.split_maturity(1_000, 0, 1);
         let now_timestamp: i64 = OffsetDateTime::now_utc().unix_timestamp();
         let mut splits: Vec<SplitMetadata> = (0..merge_policy.config.max_merge_factor)
             .map(|i| SplitMetadata {
@@ -417,7 +455,7 @@ mod tests {
         let mut splits = (0..4)
             .map(|i| {
                 let num_docs = (merge_policy.split_num_docs_target + 2) / 3;
-                let time_to_maturity = merge_policy.split_maturity(num_docs, 1);
+                let time_to_maturity = merge_policy.split_maturity(num_docs, 0, 1);
                 SplitMetadata {
                     split_id: format!("split-{i}"),
                     num_docs,
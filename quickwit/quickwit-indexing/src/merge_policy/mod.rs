@@ -161,7 +161,12 @@ pub trait MergePolicy: Send + Sync + fmt::Debug {
     /// A split is either:
     /// - `Mature` if it does not undergo new merge operations.
     /// - or `Immature` with a `maturation_period` after which it becomes mature.
-    fn split_maturity(&self, split_num_docs: usize, split_num_merge_ops: usize) -> SplitMaturity;
+    fn split_maturity(
+        &self,
+        split_num_docs: usize,
+        split_num_bytes: u64,
+        split_num_merge_ops: usize,
+    ) -> SplitMaturity;
 
     /// Checks a bunch of properties specific to the given merge policy.
     /// This method is used in proptesting.
@@ -173,15 +178,25 @@ pub trait MergePolicy: Send + Sync + fmt::Debug {
 }
 
 pub fn merge_policy_from_settings(settings: &IndexingSettings) -> Arc<dyn MergePolicy> {
+    let split_num_bytes_target_opt = settings
+        .split_num_bytes_target
+        .map(|split_num_bytes_target| split_num_bytes_target.as_u64());
     match settings.merge_policy.clone() {
         MergePolicyConfig::Nop => Arc::new(NopMergePolicy),
         MergePolicyConfig::ConstWriteAmplification(config) => {
-            let merge_policy =
-                ConstWriteAmplificationMergePolicy::new(config, settings.split_num_docs_target);
+            let merge_policy = ConstWriteAmplificationMergePolicy::new(
+                config,
+                settings.split_num_docs_target,
+                split_num_bytes_target_opt,
+            );
             Arc::new(merge_policy)
         }
         MergePolicyConfig::StableLog(config) => {
-            let merge_policy = StableLogMergePolicy::new(config, settings.split_num_docs_target);
+            let merge_policy = StableLogMergePolicy::new(
+                config,
+                settings.split_num_docs_target,
+                split_num_bytes_target_opt,
+            );
             Arc::new(merge_policy)
         }
     }
@@ -289,7 +304,7 @@ pub mod tests {
             .enumerate()
             .map(|(split_ord, (num_docs, time_range))| {
                 let create_timestamp = OffsetDateTime::now_utc().unix_timestamp();
-                let time_to_maturity = merge_policy.split_maturity(num_docs, 0);
+                let time_to_maturity = merge_policy.split_maturity(num_docs, 0, 0);
                 SplitMetadata {
                     split_id: format!("split_{split_ord:02}"),
                     num_docs,
@@ -513,7 +528,7 @@ pub mod tests {
                 let time_first = split_ord as i64 * 1_000;
                 let time_last = time_first + 999;
                 let time_range = time_first..=time_last;
-                let time_to_maturity = merge_policy.split_maturity(num_docs, 0);
+                let time_to_maturity = merge_policy.split_maturity(num_docs, 0, 0);
                 mock_split_meta_from_num_docs(time_range, num_docs as u64, time_to_maturity)
             })
             .collect();
@@ -37,7 +37,12 @@ impl MergePolicy for NopMergePolicy {
         Vec::new()
     }
 
-    fn split_maturity(&self, _split_num_docs: usize, _split_num_merge_ops: usize) -> SplitMaturity {
+    fn split_maturity(
+        &self,
+        _split_num_docs: usize,
+        _split_num_bytes: u64,
+        _split_num_merge_ops: usize,
+    ) -> SplitMaturity {
         // With the no merge policy, all splits are mature immediately as they will never undergo
         // any merge.
         SplitMaturity::Mature
@@ -54,7 +59,10 @@ mod tests {
     #[test]
     pub fn test_no_merge_policy_maturity_timestamp() {
         // All splits are always mature for `NopMergePolicy`.
-        assert_eq!(NopMergePolicy.split_maturity(10, 0), SplitMaturity::Mature);
+        assert_eq!(
+            NopMergePolicy.split_maturity(10, 0, 0),
+            SplitMaturity::Mature
+        );
     }
 
     #[test]
@@ -55,10 +55,14 @@ use crate::merge_policy::{splits_short_debug, MergeOperation, MergePolicy};
 ///
 /// Because we stop merging splits reaching a size larger than if it would result in a size larger
 /// than `target_num_docs`.
+///
+/// If `split_num_bytes_target_opt` is set, splits reaching this size on disk are also considered
+/// mature, and merges stop growing once they reach it.
 #[derive(Debug, Clone)]
 pub struct StableLogMergePolicy {
     config: StableLogMergePolicyConfig,
     split_num_docs_target: usize,
+    split_num_bytes_target_opt: Option<u64>,
 }
 
 impl Default for StableLogMergePolicy {
@@ -66,6 +70,7 @@ impl Default for StableLogMergePolicy {
         StableLogMergePolicy {
             config: Default::default(),
             split_num_docs_target: IndexingSettings::default_split_num_docs_target(),
+            split_num_bytes_target_opt: None,
         }
     }
 }
@@ -88,12 +93,23 @@ impl StableLogMergePolicy {
     pub fn new(
         config: StableLogMergePolicyConfig,
         split_num_docs_target: usize,
+        split_num_bytes_target_opt: Option<u64>,
     ) -> StableLogMergePolicy {
         StableLogMergePolicy {
             config,
             split_num_docs_target,
+            split_num_bytes_target_opt,
         }
     }
+
+    /// Returns true if a split with the given number of docs and size has reached the split size
+    /// target.
+    fn is_split_size_target_reached(&self, num_docs: usize, num_bytes: u64) -> bool {
+        num_docs >= self.split_num_docs_target
+            || self
+                .split_num_bytes_target_opt
+                .is_some_and(|split_num_bytes_target| num_bytes >= split_num_bytes_target)
+    }
 }
 
 impl MergePolicy for StableLogMergePolicy {
@@ -113,8 +129,13 @@ impl MergePolicy for StableLogMergePolicy {
     }
 
     /// A mature split for merge is a split that won't undergo any merge operation in the future.
-    fn split_maturity(&self, split_num_docs: usize, _split_num_merge_ops: usize) -> SplitMaturity {
-        if split_num_docs >= self.split_num_docs_target {
+    fn split_maturity(
+        &self,
+        split_num_docs: usize,
+        split_num_bytes: u64,
+        _split_num_merge_ops: usize,
+    ) -> SplitMaturity {
+        if self.is_split_size_target_reached(split_num_docs, split_num_bytes) {
             return SplitMaturity::Mature;
         }
         SplitMaturity::Immature {
@@ -283,9 +304,10 @@ impl StableLogMergePolicy {
             return MergeCandidateSize::OneMoreSplitWouldBeTooBig;
         }
         let num_docs_in_merge: usize = splits.iter().map(|split| split.num_docs).sum();
+        let num_bytes_in_merge: u64 = splits.iter().map(|split| split.footer_offsets.end).sum();
 
-        // The resulting split will exceed `split_num_docs_target`.
-        if num_docs_in_merge >= self.split_num_docs_target {
+        // The resulting split will exceed `split_num_docs_target` or `split_num_bytes_target_opt`.
+        if self.is_split_size_target_reached(num_docs_in_merge, num_bytes_in_merge) {
             return MergeCandidateSize::OneMoreSplitWouldBeTooBig;
         }
 
@@ -372,23 +394,59 @@ mod tests {
         let merge_policy = StableLogMergePolicy::default();
         // Split under max_merge_docs and created before now() - maturation_period is not mature.
         assert_eq!(
-            merge_policy.split_maturity(9_000_000, 0),
+            merge_policy.split_maturity(9_000_000, 0, 0),
             SplitMaturity::Immature {
                 maturation_period: Duration::from_secs(3600 * 48)
             }
         );
         assert_eq!(
-            merge_policy.split_maturity(&merge_policy.split_num_docs_target + 1, 0),
+            merge_policy.split_maturity(&merge_policy.split_num_docs_target + 1, 0, 0),
             SplitMaturity::Mature
         );
         // Split under max_merge_docs but with create_timestamp >= now + maturity duration is
         // mature.
         assert_eq!(
-            merge_policy.split_maturity(9_000_000, 0),
+            merge_policy.split_maturity(9_000_000, 0, 0),
+            SplitMaturity::Immature {
+                maturation_period: merge_policy.config.maturation_period
+            }
+        );
+    }
+
+    #[test]
+    fn test_split_is_mature_with_split_num_bytes_target() {
+        let merge_policy = StableLogMergePolicy::new(
+            StableLogMergePolicyConfig::default(),
+            10_000_000,
+            Some(1_000),
+        );
+        assert_eq!(
+            merge_policy.split_maturity(1_000, 999, 0),
             SplitMaturity::Immature {
                 maturation_period: merge_policy.config.maturation_period
             }
         );
+        assert_eq!(
+            merge_policy.split_maturity(1_000, 1_000, 0),
+            SplitMaturity::Mature
+        );
+    }
+
+    #[test]
+    fn test_stable_log_merge_policy_stops_at_split_num_bytes_target() {
+        let merge_policy = StableLogMergePolicy::new(
+            StableLogMergePolicyConfig::default(),
+            10_000_000,
+            Some(3_000),
+        );
+        let mut splits = create_splits(&merge_policy, vec![100_000; 10]);
+        for split in splits.iter_mut() {
+            split.footer_offsets = 0..1_000;
+        }
+        let merge_ops = merge_policy.operations(&mut splits);
+        assert_eq!(splits.len(), 7);
+        assert_eq!(merge_ops.len(), 1);
+        assert_eq!(merge_ops[0].splits_as_slice().len(), 3);
     }
 
     #[test]
@@ -566,7 +624,11 @@ mod tests {
         let merge_policy = StableLogMergePolicy::default();
         let mut splits = create_splits(&merge_policy, vec![9_999_999, 10_000_000]);
         for split in splits.iter_mut() {
-            let time_to_maturity = merge_policy.split_maturity(split.num_docs, split.num_merge_ops);
+            let time_to_maturity = merge_policy.split_maturity(
+                split.num_docs,
+                split.footer_offsets.end,
+                split.num_merge_ops,
+            );
             split.maturity = time_to_maturity;
         }
         let merge_ops = merge_policy.operations(&mut splits);
@@ -646,7 +708,7 @@ mod tests {
             max_merge_factor: 6,
             maturation_period: Duration::from_secs(3600),
         };
-        let merge_policy = StableLogMergePolicy::new(config, 10_000_000);
+        let merge_policy = StableLogMergePolicy::new(config, 10_000_000, None);
         crate::merge_policy::tests::proptest_merge_policy(&merge_policy);
     }
 
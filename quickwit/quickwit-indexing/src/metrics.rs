@@ -12,21 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
 use once_cell::sync::Lazy;
 use quickwit_common::metrics::{
-    new_counter, new_counter_vec, new_gauge, new_gauge_vec, IntCounter, IntCounterVec, IntGauge,
-    IntGaugeVec,
+    exponential_buckets, index_label, new_counter, new_counter_vec, new_gauge, new_gauge_vec,
+    new_histogram_vec, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 
 pub struct IndexerMetrics {
     pub processed_docs_total: IntCounterVec<2>,
     pub processed_bytes: IntCounterVec<2>,
+    pub doc_processing_micros: IntCounterVec<2>,
+    pub event_time_lag_seconds: HistogramVec<1>,
+    pub pipeline_stage_duration_seconds: HistogramVec<3>,
     pub backpressure_micros: IntCounterVec<1>,
     pub available_concurrent_upload_permits: IntGaugeVec<1>,
     pub split_builders: IntGauge,
     pub ongoing_merge_operations: IntGauge,
     pub pending_merge_operations: IntGauge,
     pub pending_merge_bytes: IntGauge,
+    pub source_partition_lag: IntGaugeVec<3>,
+    pub source_processed_bytes_total: IntCounterVec<2>,
+    pub source_compressed_bytes_total: IntCounterVec<1>,
+    pub source_decompressed_bytes_total: IntCounterVec<1>,
+    pub source_decompression_micros: IntCounterVec<1>,
+    pub source_decompression_errors_total: IntCounterVec<1>,
     // We use a lazy counter, as most users do not use Kafka.
     #[cfg_attr(not(feature = "kafka"), allow(dead_code))]
     pub kafka_rebalance_total: Lazy<IntCounter>,
@@ -51,6 +62,34 @@ impl Default for IndexerMetrics {
                 &[],
                 ["index", "docs_processed_status"],
             ),
+            doc_processing_micros: new_counter_vec(
+                "doc_processing_micros",
+                "Amount of time spent by the doc processors parsing, transforming and mapping \
+                 documents (in micros), by index and source.",
+                "indexing",
+                &[],
+                ["index", "source"],
+            ),
+            event_time_lag_seconds: new_histogram_vec(
+                "event_time_lag_seconds",
+                "Delay between the event time (timestamp field) of the documents and their \
+                 ingestion by the indexer (in seconds), by index. Only recorded for the indexes \
+                 storing the ingest timestamp of their documents.",
+                "indexing",
+                &[],
+                ["index"],
+                exponential_buckets(1.0, 2.0, 18).unwrap(),
+            ),
+            pipeline_stage_duration_seconds: new_histogram_vec(
+                "pipeline_stage_duration_seconds",
+                "Time spent by the indexing pipelines processing a batch in each stage in \
+                 [source_read, transform, doc_map, index, pack, upload, publish] (in seconds), by \
+                 index and source.",
+                "indexing",
+                &[],
+                ["index", "source", "stage"],
+                exponential_buckets(0.001, 2.0, 18).unwrap(),
+            ),
             backpressure_micros: new_counter_vec(
                 "backpressure_micros",
                 "Amount of time spent in backpressure (in micros). This time only includes the \
@@ -90,6 +129,51 @@ impl Default for IndexerMetrics {
                 "indexing",
                 &[],
             ),
+            source_partition_lag: new_gauge_vec(
+                "source_partition_lag",
+                "Number of records available in a source partition that have not been read yet. \
+                 Only reported by sources able to obtain the end of their partitions.",
+                "indexing",
+                &[],
+                ["index", "source", "partition"],
+            ),
+            source_processed_bytes_total: new_counter_vec(
+                "source_processed_bytes_total",
+                "Number of bytes read by the source of an indexing pipeline.",
+                "indexing",
+                &[],
+                ["index", "source"],
+            ),
+            source_compressed_bytes_total: new_counter_vec(
+                "source_compressed_bytes_total",
+                "Number of compressed bytes of the payloads decompressed by the sources, by codec.",
+                "indexing",
+                &[],
+                ["codec"],
+            ),
+            source_decompressed_bytes_total: new_counter_vec(
+                "source_decompressed_bytes_total",
+                "Number of bytes produced by the decompression of the payloads read by the \
+                 sources, by codec.",
+                "indexing",
+                &[],
+                ["codec"],
+            ),
+            source_decompression_micros: new_counter_vec(
+                "source_decompression_micros",
+                "Amount of time spent decompressing the payloads read by the sources (in micros), \
+                 by codec.",
+                "indexing",
+                &[],
+                ["codec"],
+            ),
+            source_decompression_errors_total: new_counter_vec(
+                "source_decompression_errors_total",
+                "Number of payloads read by the sources that could not be decompressed, by codec.",
+                "indexing",
+                &[],
+                ["codec"],
+            ),
             kafka_rebalance_total: Lazy::new(|| {
                 new_counter(
                     "kafka_rebalance_total",
@@ -105,3 +189,50 @@ impl Default for IndexerMetrics {
 /// `INDEXER_METRICS` exposes indexing related metrics through a prometheus
 /// endpoint.
 pub static INDEXER_METRICS: Lazy<IndexerMetrics> = Lazy::new(IndexerMetrics::default);
+
+/// Stages of the indexing pipelines reported by the `pipeline_stage_duration_seconds` metric.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum PipelineStage {
+    /// Reading a batch of raw documents from the source.
+    SourceRead,
+    /// Parsing the raw documents and applying the VRL transform.
+    Transform,
+    /// Converting the documents into tantivy documents with the doc mapper.
+    DocMap,
+    /// Adding the documents to the in-memory index.
+    Index,
+    /// Building the split files and the hotcache.
+    Pack,
+    /// Staging and uploading the splits to the storage.
+    Upload,
+    /// Publishing the splits in the metastore.
+    Publish,
+}
+
+impl PipelineStage {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            PipelineStage::SourceRead => "source_read",
+            PipelineStage::Transform => "transform",
+            PipelineStage::DocMap => "doc_map",
+            PipelineStage::Index => "index",
+            PipelineStage::Pack => "pack",
+            PipelineStage::Upload => "upload",
+            PipelineStage::Publish => "publish",
+        }
+    }
+}
+
+/// Records the time spent processing a batch in a stage of the indexing pipeline of the given
+/// index and source.
+pub(crate) fn observe_pipeline_stage_duration(
+    index_id: &str,
+    source_id: &str,
+    stage: PipelineStage,
+    elapsed: Duration,
+) {
+    INDEXER_METRICS
+        .pipeline_stage_duration_seconds
+        .with_label_values([index_label(index_id), source_id, stage.as_str()])
+        .observe(elapsed.as_secs_f64());
+}
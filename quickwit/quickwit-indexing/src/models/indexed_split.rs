@@ -12,9 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::fmt;
 use std::path::Path;
+use std::{fmt, iter, mem};
 
+use anyhow::bail;
 use quickwit_common::io::IoControls;
 use quickwit_common::metrics::GaugeGuard;
 use quickwit_common::temp_dir::TempDirectory;
@@ -22,7 +23,7 @@ use quickwit_metastore::checkpoint::IndexCheckpointDelta;
 use quickwit_proto::indexing::IndexingPipelineId;
 use quickwit_proto::types::{DocMappingUid, IndexUid, PublishToken};
 use tantivy::directory::MmapDirectory;
-use tantivy::IndexBuilder;
+use tantivy::{Directory, IndexBuilder};
 use tracing::{instrument, Span};
 
 use crate::controlled_directory::ControlledDirectory;
@@ -35,6 +36,17 @@ pub struct IndexedSplitBuilder {
     pub index_writer: tantivy::SingleSegmentIndexWriter,
     pub split_scratch_directory: TempDirectory,
     pub controlled_directory_opt: Option<ControlledDirectory>,
+    /// Scratch directory of the segment being built when the segments of the split can be
+    /// spilled to disk. See [`IndexedSplitBuilder::spill_segment`].
+    segment_scratch_directory_opt: Option<TempDirectory>,
+    spilled_segments: Vec<SpilledSegment>,
+}
+
+/// A segment of a split under construction that was written to disk to bound the heap usage of
+/// the indexer.
+struct SpilledSegment {
+    index: tantivy::Index,
+    _segment_scratch_directory: TempDirectory,
 }
 
 pub struct IndexedSplit {
@@ -81,6 +93,7 @@ impl IndexedSplitBuilder {
         scratch_directory: TempDirectory,
         index_builder: IndexBuilder,
         io_controls: IoControls,
+        spill_segments: bool,
     ) -> anyhow::Result<Self> {
         // We avoid intermediary merge, and instead merge all segments in the packager.
         // The benefit is that we don't have to wait for potentially existing merges,
@@ -94,8 +107,20 @@ impl IndexedSplitBuilder {
 
         let controlled_directory = ControlledDirectory::new(box_mmap_directory, io_controls);
 
-        let index_writer =
-            index_builder.single_segment_index_writer(controlled_directory.clone(), 15_000_000)?;
+        // When the segments can be spilled, each segment is built in its own scratch directory
+        // and the root of the split scratch directory receives the merged segment.
+        let (index_writer, segment_scratch_directory_opt) = if spill_segments {
+            let (index_writer, segment_scratch_directory) = create_segment_index_writer(
+                &split_scratch_directory,
+                &controlled_directory,
+                index_builder,
+            )?;
+            (index_writer, Some(segment_scratch_directory))
+        } else {
+            let index_writer = index_builder
+                .single_segment_index_writer(controlled_directory.clone(), 15_000_000)?;
+            (index_writer, None)
+        };
         Ok(Self {
             split_attrs: SplitAttrs {
                 node_id: pipeline_id.node_id,
@@ -114,9 +139,44 @@ impl IndexedSplitBuilder {
             index_writer,
             split_scratch_directory,
             controlled_directory_opt: Some(controlled_directory),
+            segment_scratch_directory_opt,
+            spilled_segments: Vec::new(),
         })
     }
 
+    /// Writes the segment being built to disk and starts a new one, releasing the memory held
+    /// by the index writer. Only builders created with `spill_segments` support this operation.
+    pub fn spill_segment(&mut self, index_builder: IndexBuilder) -> anyhow::Result<()> {
+        let (Some(segment_scratch_directory), Some(controlled_directory)) = (
+            self.segment_scratch_directory_opt.take(),
+            &self.controlled_directory_opt,
+        ) else {
+            bail!(
+                "segments of split `{}` cannot be spilled",
+                self.split_attrs.split_id
+            );
+        };
+        let (new_index_writer, new_segment_scratch_directory) = create_segment_index_writer(
+            &self.split_scratch_directory,
+            controlled_directory,
+            index_builder,
+        )?;
+        let index_writer = mem::replace(&mut self.index_writer, new_index_writer);
+        let index = index_writer.finalize()?;
+
+        self.spilled_segments.push(SpilledSegment {
+            index,
+            _segment_scratch_directory: segment_scratch_directory,
+        });
+        self.segment_scratch_directory_opt = Some(new_segment_scratch_directory);
+        Ok(())
+    }
+
+    /// Returns the number of segments of the split written to disk so far.
+    pub fn num_spilled_segments(&self) -> usize {
+        self.spilled_segments.len()
+    }
+
     #[instrument(name="serialize_split",
         skip_all,
         fields(
@@ -133,9 +193,41 @@ impl IndexedSplitBuilder {
     )]
     pub fn finalize(self) -> anyhow::Result<IndexedSplit> {
         let index = self.index_writer.finalize()?;
+
+        let Some(segment_scratch_directory) = self.segment_scratch_directory_opt else {
+            return Ok(IndexedSplit {
+                split_attrs: self.split_attrs,
+                index,
+                split_scratch_directory: self.split_scratch_directory,
+                controlled_directory_opt: self.controlled_directory_opt,
+            });
+        };
+        if self.spilled_segments.is_empty() {
+            // The segment scratch directory keeps the split scratch directory alive.
+            return Ok(IndexedSplit {
+                split_attrs: self.split_attrs,
+                index,
+                split_scratch_directory: segment_scratch_directory,
+                controlled_directory_opt: self.controlled_directory_opt,
+            });
+        }
+        let Some(controlled_directory) = &self.controlled_directory_opt else {
+            bail!(
+                "segments of split `{}` cannot be merged",
+                self.split_attrs.split_id
+            );
+        };
+        let indexes: Vec<tantivy::Index> = self
+            .spilled_segments
+            .iter()
+            .map(|spilled_segment| spilled_segment.index.clone())
+            .chain(iter::once(index))
+            .collect();
+        // The columns of the segments are read from memory-mapped files.
+        let merged_index = tantivy::merge_indices(&indexes, controlled_directory.box_clone())?;
         Ok(IndexedSplit {
             split_attrs: self.split_attrs,
-            index,
+            index: merged_index,
             split_scratch_directory: self.split_scratch_directory,
             controlled_directory_opt: self.controlled_directory_opt,
         })
@@ -150,6 +242,20 @@ impl IndexedSplitBuilder {
     }
 }
 
+/// Creates an index writer building a segment in a new child directory of the split scratch
+/// directory.
+fn create_segment_index_writer(
+    split_scratch_directory: &TempDirectory,
+    controlled_directory: &ControlledDirectory,
+    index_builder: IndexBuilder,
+) -> anyhow::Result<(tantivy::SingleSegmentIndexWriter, TempDirectory)> {
+    let segment_scratch_directory = split_scratch_directory.named_temp_child("segment-")?;
+    let mmap_directory = MmapDirectory::open(segment_scratch_directory.path())?;
+    let segment_directory = controlled_directory.with_shared_io_controls(Box::new(mmap_directory));
+    let index_writer = index_builder.single_segment_index_writer(segment_directory, 15_000_000)?;
+    Ok((index_writer, segment_scratch_directory))
+}
+
 #[derive(Debug)]
 pub struct IndexedSplitBatch {
     pub splits: Vec<IndexedSplit>,
@@ -170,6 +276,7 @@ pub enum CommitTrigger {
     ForceCommit,
     MemoryLimit,
     NoMoreDocs,
+    NumBytesLimit,
     NumDocsLimit,
     Timeout,
 }
@@ -20,6 +20,7 @@ use quickwit_proto::types::ShardId;
 use serde::Serialize;
 
 use crate::actors::{DocProcessorCounters, IndexerCounters, PublisherCounters, UploaderCounters};
+use crate::models::PartitionLag;
 
 /// A Struct that holds all statistical data about indexing
 #[derive(Clone, Debug, Default, Serialize, utoipa::ToSchema)]
@@ -52,6 +53,10 @@ pub struct IndexingStatistics {
     #[schema(value_type = Vec<u64>)]
     pub shard_ids: BTreeSet<ShardId>,
     pub params_fingerprint: u64,
+    /// Number of bytes processed per second, averaged over the last observation window.
+    pub throughput_bytes_per_sec: u64,
+    /// Lag of the partitions or shards read by the source, when the source reports it.
+    pub partition_lags: Vec<PartitionLag>,
 }
 
 impl IndexingStatistics {
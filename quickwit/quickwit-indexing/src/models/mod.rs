@@ -26,6 +26,7 @@ mod publish_lock;
 mod publisher_message;
 mod raw_doc_batch;
 mod shard_positions;
+mod source_lag;
 mod split_attrs;
 
 pub use indexed_split::{
@@ -47,6 +48,7 @@ use quickwit_proto::types::PublishToken;
 pub use raw_doc_batch::RawDocBatch;
 pub(crate) use shard_positions::LocalShardPositionsUpdate;
 pub use shard_positions::ShardPositionsService;
+pub use source_lag::{ObserveSourceLags, PartitionLag, SourceLagReport};
 pub use split_attrs::{create_split_metadata, SplitAttrs};
 
 #[derive(Debug)]
@@ -0,0 +1,53 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use quickwit_proto::types::{IndexId, PipelineUid, Position, SourceId};
+use serde::{Deserialize, Serialize};
+
+/// Reading progress of a source for one of its partitions (or shards).
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PartitionLag {
+    pub partition_id: String,
+    /// Position of the last record read by the source.
+    #[schema(value_type = String)]
+    pub current_position: Position,
+    /// Position of the last record available in the partition, when the source is able to
+    /// obtain it (e.g. the high watermark minus one for Kafka, or the EOF position of a shard
+    /// once it has been fully read for the ingest API).
+    #[schema(value_type = Option<String>)]
+    pub end_position_opt: Option<Position>,
+    /// Number of records available in the partition that have not been read yet.
+    pub lag_opt: Option<u64>,
+    /// Time lag of the source in milliseconds, for sources that report it instead of a number of
+    /// records (e.g. `MillisBehindLatest` for Kinesis).
+    pub lag_millis_opt: Option<u64>,
+}
+
+/// Observation returned by `GET /api/v1/indexing/sources`: the lag and throughput of the source
+/// of an indexing pipeline.
+#[derive(Clone, Debug, Serialize, utoipa::ToSchema)]
+pub struct SourceLagReport {
+    pub index_id: IndexId,
+    pub source_id: SourceId,
+    #[schema(value_type = String)]
+    pub pipeline_uid: PipelineUid,
+    /// Number of bytes read by the source per second, averaged over the last observation
+    /// window.
+    pub throughput_bytes_per_sec: u64,
+    pub partition_lags: Vec<PartitionLag>,
+}
+
+/// Asks the indexing service for the lag and throughput of all its running pipelines.
+#[derive(Debug)]
+pub struct ObserveSourceLags;
@@ -100,8 +100,11 @@ pub fn create_split_metadata(
         .as_ref()
         .map(|range| range.start().into_timestamp_secs()..=range.end().into_timestamp_secs());
 
-    let mut maturity =
-        merge_policy.split_maturity(split_attrs.num_docs as usize, split_attrs.num_merge_ops);
+    let mut maturity = merge_policy.split_maturity(
+        split_attrs.num_docs as usize,
+        footer_offsets.end,
+        split_attrs.num_merge_ops,
+    );
     if let Some(max_maturity) = max_maturity_before_end_of_retention(
         retention_policy,
         create_timestamp,
@@ -125,6 +128,7 @@ pub fn create_split_metadata(
         footer_offsets,
         delete_opstamp: split_attrs.delete_opstamp,
         num_merge_ops: split_attrs.num_merge_ops,
+        crc32c_checksum: None,
     }
 }
 
@@ -15,8 +15,8 @@
 use std::io;
 use std::path::Path;
 
-use anyhow::Context;
-use async_compression::tokio::bufread::GzipDecoder;
+use anyhow::{bail, Context};
+use async_compression::tokio::bufread::{GzipDecoder, ZstdDecoder};
 use bytes::Bytes;
 use quickwit_common::uri::Uri;
 use quickwit_common::Progress;
@@ -26,6 +26,7 @@ use quickwit_proto::types::Position;
 use quickwit_storage::StorageResolver;
 use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
 
+use super::payload_decompression::{PayloadCompression, COMPRESSION_MAGIC_NUM_BYTES};
 use super::{BatchBuilder, BATCH_NUM_BYTES_LIMIT};
 
 pub struct FileRecord {
@@ -102,12 +103,27 @@ impl DocFileReader {
         if file_size == 0 {
             return Ok(DocFileReader::empty());
         }
-        // If it's a gzip file, we can't seek to a specific offset. `SkipReader`
+        let compression_opt = match uri.extension() {
+            Some("gz") => Some(PayloadCompression::Gzip),
+            Some("zst") => Some(PayloadCompression::Zstd),
+            _ => {
+                let magic_bytes_range = 0..file_size.min(COMPRESSION_MAGIC_NUM_BYTES);
+                let magic_bytes = storage.get_slice(file_name, magic_bytes_range).await?;
+                PayloadCompression::detect(&magic_bytes)
+            }
+        };
+        // If it's a compressed file, we can't seek to a specific offset. `SkipReader`
         // starts from the beginning of the file, decompresses and skips the
         // first `offset` bytes.
-        let reader = if uri.extension() == Some("gz") {
-            let stream = storage.get_slice_stream(file_name, 0..file_size).await?;
-            let decompressed_stream = Box::new(GzipDecoder::new(BufReader::new(stream)));
+        let reader = if let Some(compression) = compression_opt {
+            let stream = BufReader::new(storage.get_slice_stream(file_name, 0..file_size).await?);
+            let decompressed_stream: Box<dyn AsyncRead + Send + Unpin> = match compression {
+                PayloadCompression::Gzip => Box::new(GzipDecoder::new(stream)),
+                PayloadCompression::Zstd => Box::new(ZstdDecoder::new(stream)),
+                PayloadCompression::Snappy => {
+                    bail!("snappy-compressed file `{uri}` is not supported")
+                }
+            };
             DocFileReader {
                 reader: SkipReader::new(decompressed_stream, offset),
                 next_offset: offset as u64,
@@ -451,6 +467,27 @@ mod tests {
         aux_test_resumed_read_record(dummy_doc_file_uri, 1000, 1000).await;
     }
 
+    #[tokio::test]
+    async fn test_resumed_read_record_compression_detected_from_magic_bytes() {
+        let mut documents_bytes = Vec::new();
+        for i in 0..1000 {
+            documents_bytes.extend_from_slice(format!("{:0>7}\n", i).as_bytes());
+        }
+        let zstd_documents = zstd::encode_all(&documents_bytes[..], 3).unwrap();
+        let mut gzip_encoder =
+            flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut gzip_encoder, &documents_bytes).unwrap();
+        let gzip_documents = gzip_encoder.finish().unwrap();
+
+        for compressed_documents in [zstd_documents, gzip_documents] {
+            let mut doc_file = tempfile::NamedTempFile::new().unwrap();
+            std::io::Write::write_all(&mut doc_file, &compressed_documents).unwrap();
+            let doc_file_uri = doc_file.path().to_str().unwrap();
+            aux_test_resumed_read_record(doc_file_uri, 1000, 1).await;
+            aux_test_resumed_read_record(doc_file_uri, 1000, 999).await;
+        }
+    }
+
     async fn aux_test_full_read_batch(
         file: impl AsRef<str>,
         expected_lines: usize,
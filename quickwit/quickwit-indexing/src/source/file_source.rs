@@ -237,6 +237,8 @@ mod tests {
             source_params: SourceParams::File(params.clone()),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let index_uid = IndexUid::new_with_random_ulid("test-index");
         let source_runtime = SourceRuntimeBuilder::new(index_uid, source_config).build();
@@ -289,6 +291,8 @@ mod tests {
             source_params: SourceParams::File(params.clone()),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let index_uid = IndexUid::new_with_random_ulid("test-index");
         let source_runtime = SourceRuntimeBuilder::new(index_uid, source_config).build();
@@ -353,6 +357,8 @@ mod tests {
             source_params: SourceParams::File(params.clone()),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let partition_id = PartitionId::from(uri.as_str());
         let source_checkpoint_delta = SourceCheckpointDelta::from_partition_delta(
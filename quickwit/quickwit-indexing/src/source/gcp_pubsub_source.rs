@@ -317,6 +317,8 @@ mod gcp_pubsub_emulator_tests {
             }),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         }
     }
 
@@ -50,7 +50,9 @@ use super::{
     EMIT_BATCHES_TIMEOUT,
 };
 use crate::actors::DocProcessor;
-use crate::models::{LocalShardPositionsUpdate, NewPublishLock, NewPublishToken, PublishLock};
+use crate::models::{
+    LocalShardPositionsUpdate, NewPublishLock, NewPublishToken, PartitionLag, PublishLock,
+};
 
 pub struct IngestSourceFactory;
 
@@ -637,6 +639,29 @@ impl Source for IngestSource {
         "IngestSource".to_string()
     }
 
+    fn partition_lags(&self) -> Vec<PartitionLag> {
+        self.assigned_shards
+            .iter()
+            .sorted_by(|(left_shard_id, _), (right_shard_id, _)| left_shard_id.cmp(right_shard_id))
+            .map(|(_, assigned_shard)| {
+                // The fetch streams do not report the replication position of the shards, so the
+                // end position is only known once the source has reached the EOF of a shard.
+                let reached_eof = matches!(
+                    assigned_shard.status,
+                    IndexingStatus::ReachedEof | IndexingStatus::Complete
+                );
+                PartitionLag {
+                    partition_id: assigned_shard.partition_id.to_string(),
+                    current_position: assigned_shard.current_position_inclusive.clone(),
+                    end_position_opt: reached_eof
+                        .then(|| assigned_shard.current_position_inclusive.clone()),
+                    lag_opt: reached_eof.then_some(0),
+                    lag_millis_opt: None,
+                }
+            })
+            .collect()
+    }
+
     fn observable_state(&self) -> serde_json::Value {
         let assigned_shards: Vec<serde_json::Value> = self
             .assigned_shards
@@ -1354,6 +1379,21 @@ mod tests {
             local_shard_positions_update,
             expected_local_shard_positions_update,
         );
+
+        let partition_lags = source.partition_lags();
+        assert_eq!(partition_lags.len(), 2);
+
+        assert_eq!(partition_lags[0].partition_id, "00000000000000000001");
+        assert_eq!(partition_lags[0].current_position, Position::offset(11u64));
+        assert!(partition_lags[0].end_position_opt.is_none());
+        assert!(partition_lags[0].lag_opt.is_none());
+
+        assert_eq!(partition_lags[1].partition_id, "00000000000000000002");
+        assert_eq!(
+            partition_lags[1].end_position_opt,
+            Some(Position::eof(22u64))
+        );
+        assert_eq!(partition_lags[1].lag_opt, Some(0));
     }
 
     #[tokio::test]
@@ -41,7 +41,8 @@ use tokio::time;
 use tracing::{debug, info, warn};
 
 use crate::actors::DocProcessor;
-use crate::models::{NewPublishLock, PublishLock};
+use crate::models::{NewPublishLock, PartitionLag, PublishLock};
+use crate::source::payload_decompression::decompress_payload;
 use crate::source::{
     BatchBuilder, Source, SourceContext, SourceRuntime, TypedSourceFactory, BATCH_NUM_BYTES_LIMIT,
     EMIT_BATCHES_TIMEOUT,
@@ -49,6 +50,13 @@ use crate::source::{
 
 type GroupId = String;
 
+/// Interval at which the consumer poll loop fetches the low and high watermarks of the assigned
+/// partitions to compute the lag of the source.
+const WATERMARKS_FETCH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Timeout of a single watermarks fetch request.
+const WATERMARKS_FETCH_TIMEOUT: Duration = Duration::from_secs(1);
+
 /// Factory for instantiating a `KafkaSource`.
 pub struct KafkaSourceFactory;
 
@@ -76,6 +84,8 @@ enum KafkaEvent {
         ack_tx: oneshot::Sender<()>,
     },
     PartitionEOF(i32),
+    /// Low and high watermarks of the partitions assigned to the consumer.
+    Watermarks(Vec<(i32, i64, i64)>),
     Error(anyhow::Error),
 }
 
@@ -202,6 +212,8 @@ pub struct KafkaSourceState {
     pub num_invalid_messages: u64,
     /// Number of rebalances the consumer went through.
     pub num_rebalances: usize,
+    /// Low and high watermarks of each partition, as last fetched from the brokers.
+    pub watermarks: HashMap<i32, (i64, i64)>,
 }
 
 /// A `KafkaSource` consumes a topic and forwards its messages to an `Indexer`.
@@ -345,6 +357,7 @@ impl KafkaSource {
 
         self.state.assigned_partitions.clear();
         self.state.current_positions.clear();
+        self.state.watermarks.clear();
         self.state.num_inactive_partitions = 0;
 
         let mut next_offsets: Vec<(i32, Offset)> = Vec::with_capacity(partitions.len());
@@ -425,6 +438,16 @@ impl KafkaSource {
         );
     }
 
+    fn process_watermarks(&mut self, watermarks: Vec<(i32, i64, i64)>) {
+        for (partition, low_watermark, high_watermark) in watermarks {
+            if self.state.assigned_partitions.contains_key(&partition) {
+                self.state
+                    .watermarks
+                    .insert(partition, (low_watermark, high_watermark));
+            }
+        }
+    }
+
     fn should_exit(&self) -> bool {
         self.backfill_mode_enabled
             // This check ensures that we don't shutdown the source before the first partition assignment.
@@ -472,6 +495,7 @@ impl Source for KafkaSource {
                         KafkaEvent::AssignPartitions { partitions, assignment_tx} => self.process_assign_partitions(ctx, &partitions, assignment_tx).await?,
                         KafkaEvent::RevokePartitions { ack_tx } => self.process_revoke_partitions(ctx, doc_processor_mailbox, &mut batch_builder, ack_tx).await?,
                         KafkaEvent::PartitionEOF(partition) => self.process_partition_eof(partition),
+                        KafkaEvent::Watermarks(watermarks) => self.process_watermarks(watermarks),
                         KafkaEvent::Error(error) => Err(ActorExitStatus::from(error))?,
                     }
                     if batch_builder.num_bytes >= BATCH_NUM_BYTES_LIMIT {
@@ -524,6 +548,38 @@ impl Source for KafkaSource {
         format!("{:?}", self)
     }
 
+    fn partition_lags(&self) -> Vec<PartitionLag> {
+        let mut partition_lags = Vec::with_capacity(self.state.watermarks.len());
+
+        for (partition, &(low_watermark, high_watermark)) in self.state.watermarks.iter().sorted() {
+            let Some(partition_id) = self.state.assigned_partitions.get(partition) else {
+                continue;
+            };
+            let current_position = self
+                .state
+                .current_positions
+                .get(partition)
+                .cloned()
+                .unwrap_or_default();
+            // Kafka watermarks are exclusive whereas Quickwit positions are inclusive.
+            let next_offset = current_position
+                .as_i64()
+                .map(|offset| offset + 1)
+                .unwrap_or(low_watermark);
+            let lag = (high_watermark - next_offset).max(0) as u64;
+            let end_position = previous_position_for_offset(high_watermark);
+
+            partition_lags.push(PartitionLag {
+                partition_id: partition_id.to_string(),
+                current_position,
+                end_position_opt: Some(end_position),
+                lag_opt: Some(lag),
+                lag_millis_opt: None,
+            });
+        }
+        partition_lags
+    }
+
     fn observable_state(&self) -> JsonValue {
         let assigned_partitions: Vec<&i32> =
             self.state.assigned_partitions.keys().sorted().collect();
@@ -567,6 +623,8 @@ fn spawn_consumer_poll_loop(
             let _ = events_tx.blocking_send(KafkaEvent::Error(anyhow!(error)));
             return;
         }
+        let mut next_watermarks_fetch = Instant::now() + WATERMARKS_FETCH_INTERVAL;
+
         while !events_tx.is_closed() {
             if let Some(message_res) = consumer.poll(Some(Duration::from_secs(1))) {
                 let event = match message_res {
@@ -607,12 +665,49 @@ fn spawn_consumer_poll_loop(
                     warn!(error=?error, "failed to commit offsets");
                 }
             }
+            if Instant::now() >= next_watermarks_fetch {
+                next_watermarks_fetch = Instant::now() + WATERMARKS_FETCH_INTERVAL;
+
+                let watermarks = fetch_watermarks(&consumer, &topic);
+                if !watermarks.is_empty()
+                    && events_tx
+                        .blocking_send(KafkaEvent::Watermarks(watermarks))
+                        .is_err()
+                {
+                    break;
+                }
+            }
         }
         debug!("exiting consumer poll loop");
         consumer.unsubscribe();
     })
 }
 
+/// Fetches the low and high watermarks of the partitions currently assigned to the consumer.
+/// Partitions for which the request fails are omitted.
+fn fetch_watermarks(consumer: &RdKafkaConsumer, topic: &str) -> Vec<(i32, i64, i64)> {
+    let assignment = match consumer.assignment() {
+        Ok(assignment) => assignment,
+        Err(error) => {
+            warn!(error=?error, "failed to get partition assignment");
+            return Vec::new();
+        }
+    };
+    let mut watermarks = Vec::with_capacity(assignment.count());
+
+    for partition in collect_partitions(&assignment, topic) {
+        match consumer.fetch_watermarks(topic, partition, WATERMARKS_FETCH_TIMEOUT) {
+            Ok((low_watermark, high_watermark)) => {
+                watermarks.push((partition, low_watermark, high_watermark))
+            }
+            Err(error) => {
+                warn!(error=?error, partition, "failed to fetch partition watermarks");
+            }
+        }
+    }
+    watermarks
+}
+
 /// Returns the preceding `Position` for the offset.
 fn previous_position_for_offset(offset: i64) -> Position {
     if offset == 0 {
@@ -730,11 +825,12 @@ fn parse_client_params(client_params: JsonValue) -> anyhow::Result<ClientConfig>
     Ok(client_config)
 }
 
-/// Returns the message payload as a `Bytes` object if it exists and is not empty.
+/// Returns the message payload as a `Bytes` object if it exists and is not empty. Payloads
+/// compressed with gzip, zstd, or framed snappy are decompressed.
 fn message_payload_to_doc(message: &BorrowedMessage) -> Option<Bytes> {
     match message.payload() {
         Some(payload) if !payload.is_empty() => {
-            let doc = Bytes::from(payload.to_vec());
+            let doc = decompress_payload(payload);
             return Some(doc);
         }
         Some(_) => debug!(
@@ -888,6 +984,8 @@ mod kafka_broker_tests {
             }),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         (source_id, source_config)
     }
@@ -1167,6 +1265,53 @@ mod kafka_broker_tests {
         assert!(!kafka_source.should_exit());
     }
 
+    #[tokio::test]
+    async fn test_kafka_source_partition_lags() {
+        let admin_client = create_admin_client();
+        let topic = append_random_suffix("test-kafka-source--partition-lags--topic");
+        create_topic(&admin_client, &topic, 2).await.unwrap();
+
+        let index_id = append_random_suffix("test-kafka-source--partition-lags--index");
+        let index_uid = IndexUid::new_with_random_ulid(&index_id);
+        let (_source_id, source_config) = get_source_config(&topic, "earliest");
+        let SourceParams::Kafka(params) = source_config.clone().source_params else {
+            panic!(
+                "Expected Kafka source params, got {:?}.",
+                source_config.source_params
+            );
+        };
+        let source_runtime = SourceRuntimeBuilder::new(index_uid, source_config).build();
+        let mut kafka_source = KafkaSource::try_new(source_runtime, params).await.unwrap();
+        let partition_id_1 = PartitionId::from(1u64);
+        let partition_id_2 = PartitionId::from(2u64);
+        kafka_source.state.assigned_partitions =
+            HashMap::from_iter([(1, partition_id_1.clone()), (2, partition_id_2.clone())]);
+        kafka_source
+            .state
+            .current_positions
+            .insert(1, Position::offset(41i64));
+
+        assert!(kafka_source.partition_lags().is_empty());
+
+        // Watermarks of unassigned partitions are ignored.
+        kafka_source.process_watermarks(vec![(1, 0, 50), (2, 10, 20), (3, 0, 100)]);
+
+        let partition_lags = kafka_source.partition_lags();
+        assert_eq!(partition_lags.len(), 2);
+
+        assert_eq!(partition_lags[0].partition_id, partition_id_1.to_string());
+        assert_eq!(partition_lags[0].current_position, Position::offset(41i64));
+        assert_eq!(
+            partition_lags[0].end_position_opt,
+            Some(Position::offset(49i64))
+        );
+        assert_eq!(partition_lags[0].lag_opt, Some(8));
+
+        assert_eq!(partition_lags[1].partition_id, partition_id_2.to_string());
+        assert_eq!(partition_lags[1].current_position, Position::Beginning);
+        assert_eq!(partition_lags[1].lag_opt, Some(10));
+    }
+
     #[tokio::test]
     async fn test_kafka_source_suggest_truncate() {
         let admin_client = create_admin_client();
@@ -36,6 +36,7 @@ use tracing::{info, warn};
 use super::api::list_shards;
 use super::shard_consumer::{ShardConsumer, ShardConsumerHandle, ShardConsumerMessage};
 use crate::actors::DocProcessor;
+use crate::models::PartitionLag;
 use crate::source::kinesis::helpers::get_kinesis_client;
 use crate::source::{
     BatchBuilder, Source, SourceContext, SourceRuntime, TypedSourceFactory, BATCH_NUM_BYTES_LIMIT,
@@ -315,6 +316,21 @@ impl Source for KinesisSource {
         format!("{:?}", self)
     }
 
+    fn partition_lags(&self) -> Vec<PartitionLag> {
+        self.state
+            .shard_consumers
+            .values()
+            .sorted_by(|left, right| left.partition_id.cmp(&right.partition_id))
+            .map(|shard_consumer_state| {
+                shard_partition_lag(
+                    &shard_consumer_state.partition_id,
+                    &shard_consumer_state.current_position,
+                    shard_consumer_state.lag_millis,
+                )
+            })
+            .collect()
+    }
+
     fn observable_state(&self) -> JsonValue {
         let shard_consumer_positions: Vec<(&ShardId, &Position)> = self
             .state
@@ -335,6 +351,25 @@ impl Source for KinesisSource {
     }
 }
 
+/// Computes the lag of a shard from the `MillisBehindLatest` value last reported by its consumer.
+fn shard_partition_lag(
+    partition_id: &PartitionId,
+    current_position: &Position,
+    lag_millis_opt: Option<i64>,
+) -> PartitionLag {
+    let lag_millis_opt = lag_millis_opt.map(|lag_millis| lag_millis.max(0) as u64);
+    // Kinesis does not expose the sequence number of the last record of a shard, so the end
+    // position is only known once the consumer has caught up with the shard.
+    let caught_up = lag_millis_opt == Some(0);
+    PartitionLag {
+        partition_id: partition_id.to_string(),
+        current_position: current_position.clone(),
+        end_position_opt: caught_up.then(|| current_position.clone()),
+        lag_opt: caught_up.then_some(0),
+        lag_millis_opt,
+    }
+}
+
 pub(super) async fn get_region(
     region_or_endpoint_opt: Option<RegionOrEndpoint>,
 ) -> anyhow::Result<RegionOrEndpoint> {
@@ -353,6 +388,44 @@ pub(super) async fn get_region(
     bail!("unable to sniff region from environment")
 }
 
+#[cfg(test)]
+mod partition_lag_tests {
+    use super::*;
+
+    #[test]
+    fn test_shard_partition_lag() {
+        let partition_id = PartitionId::from("shardId-000000000000");
+
+        let partition_lag = shard_partition_lag(&partition_id, &Position::Beginning, None);
+        assert_eq!(partition_lag.partition_id, "shardId-000000000000");
+        assert_eq!(partition_lag.current_position, Position::Beginning);
+        assert!(partition_lag.end_position_opt.is_none());
+        assert!(partition_lag.lag_opt.is_none());
+        assert!(partition_lag.lag_millis_opt.is_none());
+
+        let current_position = Position::offset(42u64);
+        let partition_lag = shard_partition_lag(&partition_id, &current_position, Some(1_500));
+        assert_eq!(partition_lag.current_position, current_position);
+        assert!(partition_lag.end_position_opt.is_none());
+        assert!(partition_lag.lag_opt.is_none());
+        assert_eq!(partition_lag.lag_millis_opt, Some(1_500));
+
+        // The consumer has caught up with the shard.
+        let partition_lag = shard_partition_lag(&partition_id, &current_position, Some(0));
+        assert_eq!(
+            partition_lag.end_position_opt,
+            Some(current_position.clone())
+        );
+        assert_eq!(partition_lag.lag_opt, Some(0));
+        assert_eq!(partition_lag.lag_millis_opt, Some(0));
+
+        // Negative lags reported because of clock skew are clamped.
+        let partition_lag = shard_partition_lag(&partition_id, &current_position, Some(-10));
+        assert_eq!(partition_lag.lag_opt, Some(0));
+        assert_eq!(partition_lag.lag_millis_opt, Some(0));
+    }
+}
+
 #[cfg(all(test, feature = "kinesis-localstack-tests"))]
 mod tests {
 
@@ -62,12 +62,14 @@ mod ingest_api_source;
 mod kafka_source;
 #[cfg(feature = "kinesis")]
 mod kinesis;
+mod payload_decompression;
 #[cfg(feature = "pulsar")]
 mod pulsar_source;
 #[cfg(feature = "queue-sources")]
 mod queue_sources;
 mod source_factory;
 mod stdin_source;
+mod timed_source;
 mod vec_source;
 mod void_source;
 
@@ -109,6 +111,7 @@ use quickwit_proto::types::{IndexUid, NodeIdRef, PipelineUid, ShardId};
 use quickwit_storage::StorageResolver;
 use serde_json::Value as JsonValue;
 pub use source_factory::{SourceFactory, SourceLoader, TypedSourceFactory};
+pub(crate) use timed_source::TimedSource;
 use tokio::runtime::Handle;
 use tracing::error;
 pub use vec_source::{VecSource, VecSourceFactory};
@@ -116,7 +119,7 @@ pub use void_source::{VoidSource, VoidSourceFactory};
 
 use self::doc_file_reader::dir_and_filename;
 use crate::actors::DocProcessor;
-use crate::models::RawDocBatch;
+use crate::models::{PartitionLag, RawDocBatch};
 use crate::source::ingest::IngestSourceFactory;
 use crate::source::ingest_api_source::IngestApiSourceFactory;
 
@@ -208,6 +211,20 @@ impl SourceRuntime {
 
 pub type SourceContext = ActorContext<SourceActor>;
 
+/// Key under which the [`SourceActor`] exposes the partition lags reported by the source in its
+/// observable state.
+const PARTITION_LAGS_KEY: &str = "partition_lags";
+
+/// Extracts the partition lags from the observable state of a [`SourceActor`].
+pub(crate) fn partition_lags_from_observable_state(
+    observable_state: &JsonValue,
+) -> Vec<PartitionLag> {
+    observable_state
+        .get(PARTITION_LAGS_KEY)
+        .and_then(|partition_lags_json| serde_json::from_value(partition_lags_json.clone()).ok())
+        .unwrap_or_default()
+}
+
 /// A Source is a trait that is mounted in a light wrapping Actor called `SourceActor`.
 ///
 /// For this reason, its methods mimics those of Actor.
@@ -300,6 +317,13 @@ pub trait Source: Send + 'static {
     /// A name identifying the type of source.
     fn name(&self) -> String;
 
+    /// Returns the lag of the partitions or shards read by the source.
+    ///
+    /// Sources that cannot obtain the end position of their partitions return an empty list.
+    fn partition_lags(&self) -> Vec<PartitionLag> {
+        Vec::new()
+    }
+
     /// Returns an observable_state for the actor.
     ///
     /// This object is simply a json object, and its content may vary depending on the
@@ -335,7 +359,18 @@ impl Actor for SourceActor {
     }
 
     fn observable_state(&self) -> Self::ObservableState {
-        self.source.observable_state()
+        let mut observable_state = self.source.observable_state();
+        let partition_lags = self.source.partition_lags();
+
+        if !partition_lags.is_empty() {
+            if let Some(observable_state_obj) = observable_state.as_object_mut() {
+                observable_state_obj.insert(
+                    PARTITION_LAGS_KEY.to_string(),
+                    serde_json::to_value(partition_lags).expect("partition lags should serialize"),
+                );
+            }
+        }
+        observable_state
     }
 
     fn runtime_handle(&self) -> Handle {
@@ -688,6 +723,8 @@ mod tests {
                 source_params: SourceParams::void(),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                activation_windows: Vec::new(),
+                backfill_time_range: None,
             };
             check_source_connectivity(&StorageResolver::for_test(), &source_config).await?;
         }
@@ -699,6 +736,8 @@ mod tests {
                 source_params: SourceParams::Vec(VecSourceParams::default()),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                activation_windows: Vec::new(),
+                backfill_time_range: None,
             };
             check_source_connectivity(&StorageResolver::for_test(), &source_config).await?;
         }
@@ -710,6 +749,8 @@ mod tests {
                 source_params: SourceParams::file_from_str("file-does-not-exist.json").unwrap(),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                activation_windows: Vec::new(),
+                backfill_time_range: None,
             };
             assert!(
                 check_source_connectivity(&StorageResolver::for_test(), &source_config)
@@ -725,6 +766,8 @@ mod tests {
                 source_params: SourceParams::file_from_str("data/test_corpus.json").unwrap(),
                 transform_config: None,
                 input_format: SourceInputFormat::Json,
+                activation_windows: Vec::new(),
+                backfill_time_range: None,
             };
             assert!(
                 check_source_connectivity(&StorageResolver::for_test(), &source_config)
@@ -0,0 +1,204 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::{self, Read};
+use std::time::Instant;
+
+use bytes::Bytes;
+use quickwit_common::rate_limited_tracing::rate_limited_warn;
+
+use crate::metrics::INDEXER_METRICS;
+
+const GZIP_MAGIC_BYTES: &[u8] = &[0x1f, 0x8b];
+
+const ZSTD_MAGIC_BYTES: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Stream identifier chunk starting the snappy framing format. Raw snappy blocks have no magic
+/// bytes and cannot be detected.
+const SNAPPY_FRAMED_MAGIC_BYTES: &[u8] = b"\xff\x06\x00\x00sNaPpY";
+
+/// Number of bytes required to detect the compression of a payload.
+pub(crate) const COMPRESSION_MAGIC_NUM_BYTES: usize = SNAPPY_FRAMED_MAGIC_BYTES.len();
+
+/// Decompressed payloads larger than this are rejected to protect the indexer from compression
+/// bombs.
+const MAX_DECOMPRESSED_PAYLOAD_NUM_BYTES: u64 = 64 * 1024 * 1024; // 64 MiB
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PayloadCompression {
+    Gzip,
+    Zstd,
+    Snappy,
+}
+
+impl PayloadCompression {
+    /// Detects the compression of a payload from its first bytes.
+    pub fn detect(payload: &[u8]) -> Option<Self> {
+        if payload.starts_with(GZIP_MAGIC_BYTES) {
+            Some(Self::Gzip)
+        } else if payload.starts_with(ZSTD_MAGIC_BYTES) {
+            Some(Self::Zstd)
+        } else if payload.starts_with(SNAPPY_FRAMED_MAGIC_BYTES) {
+            Some(Self::Snappy)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+            Self::Snappy => "snappy",
+        }
+    }
+
+    fn decompress(&self, payload: &[u8]) -> io::Result<Vec<u8>> {
+        let decoder: Box<dyn Read + '_> = match self {
+            Self::Gzip => Box::new(flate2::read::MultiGzDecoder::new(payload)),
+            Self::Zstd => Box::new(zstd::stream::read::Decoder::new(payload)?),
+            Self::Snappy => Box::new(snap::read::FrameDecoder::new(payload)),
+        };
+        let mut decompressed_payload = Vec::new();
+        decoder
+            .take(MAX_DECOMPRESSED_PAYLOAD_NUM_BYTES + 1)
+            .read_to_end(&mut decompressed_payload)?;
+
+        if decompressed_payload.len() as u64 > MAX_DECOMPRESSED_PAYLOAD_NUM_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("decompressed payload exceeds {MAX_DECOMPRESSED_PAYLOAD_NUM_BYTES} bytes"),
+            ));
+        }
+        Ok(decompressed_payload)
+    }
+}
+
+/// Decompresses a payload compressed with gzip, zstd, or framed snappy, detected from its magic
+/// bytes. Uncompressed payloads, and payloads that fail to decompress, are returned as is: the
+/// latter are then reported as parse failures by the doc processor.
+pub(crate) fn decompress_payload(payload: &[u8]) -> Bytes {
+    let Some(compression) = PayloadCompression::detect(payload) else {
+        return Bytes::copy_from_slice(payload);
+    };
+    let codec = compression.as_str();
+    let now = Instant::now();
+
+    match compression.decompress(payload) {
+        Ok(decompressed_payload) => {
+            INDEXER_METRICS
+                .source_decompression_micros
+                .with_label_values([codec])
+                .inc_by(now.elapsed().as_micros() as u64);
+            INDEXER_METRICS
+                .source_compressed_bytes_total
+                .with_label_values([codec])
+                .inc_by(payload.len() as u64);
+            INDEXER_METRICS
+                .source_decompressed_bytes_total
+                .with_label_values([codec])
+                .inc_by(decompressed_payload.len() as u64);
+            Bytes::from(decompressed_payload)
+        }
+        Err(error) => {
+            rate_limited_warn!(
+                limit_per_min = 10,
+                "failed to decompress {codec} payload: {error}"
+            );
+            INDEXER_METRICS
+                .source_decompression_errors_total
+                .with_label_values([codec])
+                .inc();
+            Bytes::copy_from_slice(payload)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    const DOC: &[u8] = br#"{"body": "hello world"}"#;
+
+    #[test]
+    fn test_detect_payload_compression() {
+        assert_eq!(PayloadCompression::detect(DOC), None);
+        assert_eq!(PayloadCompression::detect(b""), None);
+        assert_eq!(PayloadCompression::detect(b"\x1f"), None);
+
+        let mut gzip_encoder =
+            flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip_encoder.write_all(DOC).unwrap();
+        let gzip_payload = gzip_encoder.finish().unwrap();
+        assert_eq!(
+            PayloadCompression::detect(&gzip_payload),
+            Some(PayloadCompression::Gzip)
+        );
+        let zstd_payload = zstd::encode_all(DOC, 3).unwrap();
+        assert_eq!(
+            PayloadCompression::detect(&zstd_payload),
+            Some(PayloadCompression::Zstd)
+        );
+        let mut snappy_encoder = snap::write::FrameEncoder::new(Vec::new());
+        snappy_encoder.write_all(DOC).unwrap();
+        let snappy_payload = snappy_encoder.into_inner().unwrap();
+        assert_eq!(
+            PayloadCompression::detect(&snappy_payload),
+            Some(PayloadCompression::Snappy)
+        );
+    }
+
+    #[test]
+    fn test_decompress_payload() {
+        assert_eq!(decompress_payload(DOC), DOC);
+
+        let mut gzip_encoder =
+            flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip_encoder.write_all(DOC).unwrap();
+        let gzip_payload = gzip_encoder.finish().unwrap();
+        assert_eq!(decompress_payload(&gzip_payload), DOC);
+
+        let zstd_payload = zstd::encode_all(DOC, 3).unwrap();
+        let decompressed_bytes_before = INDEXER_METRICS
+            .source_decompressed_bytes_total
+            .with_label_values(["zstd"])
+            .get();
+        assert_eq!(decompress_payload(&zstd_payload), DOC);
+        assert!(
+            INDEXER_METRICS
+                .source_decompressed_bytes_total
+                .with_label_values(["zstd"])
+                .get()
+                >= decompressed_bytes_before + DOC.len() as u64
+        );
+
+        let mut snappy_encoder = snap::write::FrameEncoder::new(Vec::new());
+        snappy_encoder.write_all(DOC).unwrap();
+        let snappy_payload = snappy_encoder.into_inner().unwrap();
+        assert_eq!(decompress_payload(&snappy_payload), DOC);
+
+        // Corrupted payloads are returned as is.
+        let corrupted_payload = [&zstd_payload[..4], b"corrupted"].concat();
+        assert_eq!(decompress_payload(&corrupted_payload), corrupted_payload);
+    }
+
+    #[test]
+    fn test_decompress_payload_too_large() {
+        let large_doc = vec![b'a'; MAX_DECOMPRESSED_PAYLOAD_NUM_BYTES as usize + 1];
+        let zstd_payload = zstd::encode_all(&large_doc[..], 3).unwrap();
+        assert_eq!(decompress_payload(&zstd_payload), zstd_payload);
+    }
+}
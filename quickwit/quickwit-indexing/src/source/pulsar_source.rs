@@ -503,6 +503,8 @@ mod pulsar_broker_tests {
             }),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         (source_id, source_config)
     }
@@ -128,6 +128,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let source_runtime = SourceRuntimeBuilder::new(index_uid, source_config).build();
         source_loader.load_source(source_runtime).await?;
@@ -0,0 +1,112 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use quickwit_actors::{ActorExitStatus, Mailbox};
+use quickwit_metastore::checkpoint::SourceCheckpoint;
+use quickwit_proto::indexing::IndexingPipelineId;
+use quickwit_proto::types::ShardId;
+use serde_json::Value as JsonValue;
+
+use super::{Source, SourceContext};
+use crate::actors::DocProcessor;
+use crate::metrics::{observe_pipeline_stage_duration, PipelineStage};
+use crate::models::PartitionLag;
+
+/// Wraps a source and records the time it spends emitting batches in the `source_read` stage of
+/// the `pipeline_stage_duration_seconds` metric.
+pub(crate) struct TimedSource {
+    source: Box<dyn Source>,
+    index_id: String,
+    source_id: String,
+}
+
+impl TimedSource {
+    pub fn new(source: Box<dyn Source>, pipeline_id: &IndexingPipelineId) -> Self {
+        Self {
+            source,
+            index_id: pipeline_id.index_uid.index_id.clone(),
+            source_id: pipeline_id.source_id.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl Source for TimedSource {
+    async fn initialize(
+        &mut self,
+        doc_processor_mailbox: &Mailbox<DocProcessor>,
+        ctx: &SourceContext,
+    ) -> Result<(), ActorExitStatus> {
+        self.source.initialize(doc_processor_mailbox, ctx).await
+    }
+
+    async fn emit_batches(
+        &mut self,
+        doc_processor_mailbox: &Mailbox<DocProcessor>,
+        ctx: &SourceContext,
+    ) -> Result<Duration, ActorExitStatus> {
+        let start = Instant::now();
+        let emit_batches_res = self.source.emit_batches(doc_processor_mailbox, ctx).await;
+        observe_pipeline_stage_duration(
+            &self.index_id,
+            &self.source_id,
+            PipelineStage::SourceRead,
+            start.elapsed(),
+        );
+        emit_batches_res
+    }
+
+    async fn assign_shards(
+        &mut self,
+        shard_ids: BTreeSet<ShardId>,
+        doc_processor_mailbox: &Mailbox<DocProcessor>,
+        ctx: &SourceContext,
+    ) -> anyhow::Result<()> {
+        self.source
+            .assign_shards(shard_ids, doc_processor_mailbox, ctx)
+            .await
+    }
+
+    async fn suggest_truncate(
+        &mut self,
+        checkpoint: SourceCheckpoint,
+        ctx: &SourceContext,
+    ) -> anyhow::Result<()> {
+        self.source.suggest_truncate(checkpoint, ctx).await
+    }
+
+    async fn finalize(
+        &mut self,
+        exit_status: &ActorExitStatus,
+        ctx: &SourceContext,
+    ) -> anyhow::Result<()> {
+        self.source.finalize(exit_status, ctx).await
+    }
+
+    fn name(&self) -> String {
+        self.source.name()
+    }
+
+    fn partition_lags(&self) -> Vec<PartitionLag> {
+        self.source.partition_lags()
+    }
+
+    fn observable_state(&self) -> JsonValue {
+        self.source.observable_state()
+    }
+}
@@ -162,6 +162,8 @@ mod tests {
             source_params: SourceParams::Vec(params.clone()),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let source_runtime = SourceRuntimeBuilder::new(index_uid, source_config).build();
         let vec_source = VecSourceFactory::typed_create_source(source_runtime, params).await?;
@@ -210,6 +212,8 @@ mod tests {
             source_params: SourceParams::Vec(params.clone()),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let source_delta = SourceCheckpointDelta::from_range(0u64..2u64);
         let source_runtime = SourceRuntimeBuilder::new(index_uid, source_config)
@@ -84,6 +84,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let source_runtime = SourceRuntimeBuilder::new(index_uid, source_config).build();
         let source = quickwit_supported_sources()
@@ -104,6 +106,8 @@ mod tests {
             source_params: SourceParams::void(),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let source_runtime = SourceRuntimeBuilder::new(index_uid, source_config).build();
         let void_source =
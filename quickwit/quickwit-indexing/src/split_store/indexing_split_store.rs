@@ -23,6 +23,7 @@ use anyhow::Context;
 use bytesize::ByteSize;
 use quickwit_common::io::{IoControls, IoControlsAccess};
 use quickwit_common::uri::Uri;
+use quickwit_config::SplitStorageClasses;
 use quickwit_metastore::SplitMetadata;
 use quickwit_storage::{PutPayload, Storage, StorageResult};
 use tantivy::directory::{Advice, MmapDirectory};
@@ -61,16 +62,28 @@ struct InnerIndexingSplitStore {
     /// The remote storage.
     remote_storage: Arc<dyn Storage>,
     split_cache: Arc<IndexingSplitCache>,
+    /// Storage classes in which the splits are uploaded, depending on their maturity.
+    split_storage_classes: SplitStorageClasses,
+    /// Name of the storage credentials used to access the remote storage, reported to the
+    /// searchers along with the uploaded splits.
+    storage_credentials_opt: Option<String>,
 }
 
 impl IndexingSplitStore {
     /// Creates an instance of [`IndexingSplitStore`]
     ///
     /// It needs the remote storage to work with.
-    pub fn new(remote_storage: Arc<dyn Storage>, split_cache: Arc<IndexingSplitCache>) -> Self {
+    pub fn new(
+        remote_storage: Arc<dyn Storage>,
+        split_cache: Arc<IndexingSplitCache>,
+        split_storage_classes: SplitStorageClasses,
+        storage_credentials_opt: Option<String>,
+    ) -> Self {
         let inner = InnerIndexingSplitStore {
             remote_storage,
             split_cache,
+            split_storage_classes,
+            storage_credentials_opt,
         };
         Self {
             inner: Arc::new(inner),
@@ -83,6 +96,8 @@ impl IndexingSplitStore {
         let inner = InnerIndexingSplitStore {
             remote_storage,
             split_cache: Arc::new(IndexingSplitCache::no_caching()),
+            split_storage_classes: SplitStorageClasses::default(),
+            storage_credentials_opt: None,
         };
         IndexingSplitStore {
             inner: Arc::new(inner),
@@ -93,6 +108,10 @@ impl IndexingSplitStore {
         self.inner.remote_storage.uri()
     }
 
+    pub fn storage_credentials(&self) -> Option<&str> {
+        self.inner.storage_credentials_opt.as_deref()
+    }
+
     fn split_path(&self, split_id: &str) -> PathBuf {
         PathBuf::from(quickwit_common::split_file(split_id))
     }
@@ -102,6 +121,8 @@ impl IndexingSplitStore {
     /// If a split is identified as mature by the merge policy,
     /// it will not be cached into the local storage.
     ///
+    /// The split is uploaded in the storage class configured for its maturity, if any.
+    ///
     /// In order to limit the write IO, the file might be moved (and not copied into
     /// the store).
     /// In other words, after calling this function the file will not be available
@@ -118,9 +139,14 @@ impl IndexingSplitStore {
 
         let key = self.split_path(split.split_id());
         let is_mature = split.is_mature(OffsetDateTime::now_utc());
-        self.inner
-            .remote_storage
-            .put(&key, put_payload)
+        let remote_storage = &self.inner.remote_storage;
+        let put_fut = match self.inner.split_storage_classes.storage_class(is_mature) {
+            Some(storage_class) => {
+                remote_storage.put_with_storage_class(&key, put_payload, storage_class)
+            }
+            None => remote_storage.put(&key, put_payload),
+        };
+        put_fut
             .instrument(info_span!("store_split_in_remote_storage", split=?split.split_id(), is_mature=is_mature, num_bytes=split_num_bytes))
             .await
             .with_context(|| {
@@ -223,8 +249,9 @@ mod tests {
 
     use bytesize::ByteSize;
     use quickwit_common::io::IoControls;
+    use quickwit_config::SplitStorageClasses;
     use quickwit_metastore::{SplitMaturity, SplitMetadata};
-    use quickwit_storage::{PutPayload, RamStorage, SplitPayloadBuilder};
+    use quickwit_storage::{MockStorage, PutPayload, RamStorage, SplitPayloadBuilder};
     use tempfile::tempdir;
     use time::OffsetDateTime;
     use tokio::fs;
@@ -255,7 +282,12 @@ mod tests {
         )
         .await?;
         let remote_storage = Arc::new(RamStorage::default());
-        let split_store = IndexingSplitStore::new(remote_storage, Arc::new(split_cache));
+        let split_store = IndexingSplitStore::new(
+            remote_storage,
+            Arc::new(split_cache),
+            SplitStorageClasses::default(),
+            None,
+        );
 
         let split_id1 = Ulid::new().to_string();
         let split_id2 = Ulid::new().to_string();
@@ -329,6 +361,43 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_store_split_with_storage_class() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let mut mock_storage = MockStorage::default();
+        mock_storage
+            .expect_put_with_storage_class()
+            .withf(|path, _payload, storage_class| {
+                path.to_string_lossy().ends_with(".split") && storage_class == "GLACIER_IR"
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        let split_storage_classes = SplitStorageClasses {
+            immature: None,
+            mature: Some("GLACIER_IR".to_string()),
+        };
+        let split_store = IndexingSplitStore::new(
+            Arc::new(mock_storage),
+            Arc::new(IndexingSplitCache::no_caching()),
+            split_storage_classes,
+            None,
+        );
+        let split_id = Ulid::new().to_string();
+        let split_metadata = SplitMetadata {
+            split_id: split_id.clone(),
+            maturity: SplitMaturity::Mature,
+            ..Default::default()
+        };
+        let split_path = temp_dir.path().join(&split_id);
+        fs::create_dir_all(&split_path).await?;
+        let split_payload = SplitPayloadBuilder::get_split_payload(&[], &[], &[1, 2, 3])?;
+        split_store
+            .store_split(&split_metadata, &split_path, Box::new(split_payload))
+            .await?;
+        assert!(!split_path.try_exists()?);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_eviction_and_fallback_to_remote() -> anyhow::Result<()> {
         let temp_dir = tempfile::tempdir()?;
@@ -341,7 +410,12 @@ mod tests {
         .await?;
 
         let remote_storage = Arc::new(RamStorage::default());
-        let split_store = IndexingSplitStore::new(remote_storage, Arc::new(split_cache));
+        let split_store = IndexingSplitStore::new(
+            remote_storage,
+            Arc::new(split_cache),
+            SplitStorageClasses::default(),
+            None,
+        );
 
         let split_id1 = Ulid::new().to_string();
         let split_payload1 = SplitPayloadBuilder::get_split_payload(&[], &[], &[5, 5, 5])?;
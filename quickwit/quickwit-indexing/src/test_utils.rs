@@ -170,6 +170,8 @@ impl TestSandbox {
             }),
             transform_config: None,
             input_format: SourceInputFormat::Json,
+            activation_windows: Vec::new(),
+            backfill_time_range: None,
         };
         let pipeline_id = self
             .indexing_service
@@ -33,6 +33,8 @@ pub enum IngestServiceError {
     IndexAlreadyExists { index_id: IndexId },
     #[error("index `{index_id}` not found")]
     IndexNotFound { index_id: IndexId },
+    #[error("ingestion into index `{index_id}` is frozen")]
+    IngestionFrozen { index_id: IndexId },
     #[error("an internal error occurred: {0}")]
     Internal(String),
     #[error("invalid position: {0}")]
@@ -103,6 +105,9 @@ impl From<IngestFailure> for IngestServiceError {
             IngestFailureReason::IndexNotFound => IngestServiceError::IndexNotFound {
                 index_id: ingest_failure.index_id,
             },
+            IngestFailureReason::IndexFrozen => IngestServiceError::IngestionFrozen {
+                index_id: ingest_failure.index_id,
+            },
             IngestFailureReason::SourceNotFound => IngestServiceError::Internal(format!(
                 "Ingest v2 source not found for index {}",
                 ingest_failure.index_id
@@ -147,6 +152,7 @@ impl ServiceError for IngestServiceError {
             }
             Self::IndexAlreadyExists { .. } => ServiceErrorCode::AlreadyExists,
             Self::IndexNotFound { .. } => ServiceErrorCode::NotFound,
+            Self::IngestionFrozen { .. } => ServiceErrorCode::Conflict,
             Self::Internal(err_msg) => {
                 rate_limited_error!(limit_per_min = 6, "ingest internal error: {err_msg}");
                 ServiceErrorCode::Internal
@@ -197,6 +203,7 @@ impl From<IngestServiceError> for tonic::Status {
             IngestServiceError::Corruption { .. } => tonic::Code::DataLoss,
             IngestServiceError::IndexAlreadyExists { .. } => tonic::Code::AlreadyExists,
             IngestServiceError::IndexNotFound { .. } => tonic::Code::NotFound,
+            IngestServiceError::IngestionFrozen { .. } => tonic::Code::FailedPrecondition,
             IngestServiceError::Internal(_) => tonic::Code::Internal,
             IngestServiceError::InvalidPosition(_) => tonic::Code::InvalidArgument,
             IngestServiceError::IoError { .. } => tonic::Code::Internal,
@@ -31,8 +31,10 @@ use quickwit_common::metrics::{GaugeGuard, MEMORY_METRICS};
 use quickwit_common::pretty::PrettyDisplay;
 use quickwit_common::pubsub::{EventBroker, EventSubscriber};
 use quickwit_common::rate_limiter::{RateLimiter, RateLimiterSettings};
+use quickwit_common::runtimes::instrument_task;
 use quickwit_common::tower::Pool;
 use quickwit_common::{rate_limited_error, rate_limited_warn, ServiceStream};
+use quickwit_proto::compression::{PayloadCodec, PayloadCompression};
 use quickwit_proto::control_plane::{
     AdviseResetShardsRequest, ControlPlaneService, ControlPlaneServiceClient,
 };
@@ -115,6 +117,8 @@ pub struct Ingester {
     memory_capacity: ByteSize,
     rate_limiter_settings: RateLimiterSettings,
     replication_factor: usize,
+    // Compression applied to the documents replicated to followers.
+    replication_compression: PayloadCompression,
     // This semaphore ensures that the ingester that not run two reset shards operations
     // concurrently.
     reset_shards_permits: Arc<Semaphore>,
@@ -139,6 +143,7 @@ impl Ingester {
         memory_capacity: ByteSize,
         rate_limiter_settings: RateLimiterSettings,
         replication_factor: usize,
+        replication_compression: PayloadCompression,
         idle_shard_timeout: Duration,
     ) -> IngestV2Result<Self> {
         let self_node_id: NodeId = cluster.self_node_id().into();
@@ -157,6 +162,7 @@ impl Ingester {
             memory_capacity,
             rate_limiter_settings,
             replication_factor,
+            replication_compression,
             reset_shards_permits: Arc::new(Semaphore::new(1)),
         };
         ingester.background_reset_shards();
@@ -400,7 +406,7 @@ impl Ingester {
         let mut ack_replication_stream = ingester
             .open_replication_stream(syn_replication_stream)
             .await?;
-        ack_replication_stream
+        let open_response = ack_replication_stream
             .next()
             .await
             .expect("TODO")
@@ -408,11 +414,18 @@ impl Ingester {
             .into_open_response()
             .expect("first message should be an open response");
 
+        // Followers running a version that cannot decode compressed documents do not set the flag.
+        let doc_batch_compression = if open_response.accepts_compressed_doc_batches {
+            self.replication_compression
+        } else {
+            PayloadCompression::default()
+        };
         let replication_stream_task_handle = ReplicationStreamTask::spawn(
             leader_id.clone(),
             follower_id.clone(),
             syn_replication_stream_tx,
             ack_replication_stream,
+            doc_batch_compression,
         );
         let replication_client = replication_stream_task_handle.replication_client();
         entry.insert(replication_stream_task_handle);
@@ -626,6 +639,7 @@ impl Ingester {
                         shard_id: subrequest.shard_id.clone(),
                         from_position_exclusive: Some(from_position_exclusive),
                         doc_batch: Some(valid_doc_batch.clone()),
+                        doc_buffer_codec: PayloadCodec::None as i32,
                     };
                     per_follower_replicate_subrequests
                         .entry(follower_id)
@@ -863,6 +877,7 @@ impl Ingester {
         let (ack_replication_stream_tx, ack_replication_stream) = ServiceStream::new_unbounded();
         let open_response = OpenReplicationStreamResponse {
             replication_seqno: 0,
+            accepts_compressed_doc_batches: true,
         };
         let ack_replication_message = AckReplicationMessage::new_open_response(open_response);
         ack_replication_stream_tx
@@ -1118,7 +1133,7 @@ impl IngesterService for Ingester {
         let mut gauge_guard = GaugeGuard::from_gauge(&MEMORY_METRICS.in_flight.ingester_persist);
         gauge_guard.add(request_size_bytes as i64);
 
-        self.persist_inner(persist_request).await
+        instrument_task(self.persist_inner(persist_request), "ingest").await
     }
 
     async fn open_replication_stream(
@@ -1169,20 +1184,37 @@ impl IngesterService for Ingester {
                     })
             })
             .collect();
+        let min_idle = Duration::from_secs(request.min_idle_secs);
+        let now = Instant::now();
+
         let mut state_guard =
             with_lock_metrics!(self.state.lock_fully(), "retain_shards", "write").await?;
-        let remove_queue_ids: HashSet<QueueId> = state_guard
+        let remove_queue_ids: Vec<QueueId> = state_guard
             .shards
-            .keys()
-            .filter(move |shard_id| !retain_queue_ids.contains(*shard_id))
-            .map(ToString::to_string)
+            .iter()
+            .filter(|(queue_id, shard)| {
+                !retain_queue_ids.contains(*queue_id)
+                    && now.duration_since(shard.last_write_instant) >= min_idle
+            })
+            .map(|(queue_id, _)| queue_id.clone())
             .collect();
+
+        if request.dry_run {
+            if !remove_queue_ids.is_empty() {
+                info!(queues=?remove_queue_ids, "found queues to remove (dry run)");
+            }
+            return Ok(RetainShardsResponse {
+                removed_queue_ids: remove_queue_ids,
+            });
+        }
         info!(queues=?remove_queue_ids, "removing queues");
-        for queue_id in remove_queue_ids {
-            state_guard.delete_shard(&queue_id).await;
+        for queue_id in &remove_queue_ids {
+            state_guard.delete_shard(queue_id).await;
         }
         self.check_decommissioning_status(&mut state_guard);
-        Ok(RetainShardsResponse {})
+        Ok(RetainShardsResponse {
+            removed_queue_ids: remove_queue_ids,
+        })
     }
 
     async fn truncate_shards(
@@ -1326,6 +1358,7 @@ mod tests {
         memory_capacity: ByteSize,
         rate_limiter_settings: RateLimiterSettings,
         replication_factor: usize,
+        replication_compression: PayloadCompression,
         idle_shard_timeout: Duration,
     }
 
@@ -1345,6 +1378,7 @@ mod tests {
                 memory_capacity: ByteSize::mb(1),
                 rate_limiter_settings: RateLimiterSettings::default(),
                 replication_factor: 1,
+                replication_compression: PayloadCompression::default(),
                 idle_shard_timeout: DEFAULT_IDLE_SHARD_TIMEOUT,
             }
         }
@@ -1389,6 +1423,14 @@ mod tests {
             self
         }
 
+        pub fn with_replication_compression(
+            mut self,
+            replication_compression: PayloadCompression,
+        ) -> Self {
+            self.replication_compression = replication_compression;
+            self
+        }
+
         pub fn with_idle_shard_timeout(mut self, idle_shard_timeout: Duration) -> Self {
             self.idle_shard_timeout = idle_shard_timeout;
             self
@@ -1425,6 +1467,7 @@ mod tests {
                 self.memory_capacity,
                 self.rate_limiter_settings,
                 self.replication_factor,
+                self.replication_compression,
                 self.idle_shard_timeout,
             )
             .await
@@ -2924,13 +2967,14 @@ mod tests {
             .open_replication_stream(syn_replication_stream)
             .await
             .unwrap();
-        ack_replication_stream
+        let open_response = ack_replication_stream
             .next()
             .await
             .unwrap()
             .unwrap()
             .into_open_response()
             .unwrap();
+        assert!(open_response.accepts_compressed_doc_batches);
 
         let state_guard = ingester.state.lock_fully().await.unwrap();
         assert!(state_guard.replication_tasks.contains_key("test-leader"));
@@ -3433,8 +3477,10 @@ mod tests {
                 source_id: "test-source".to_string(),
                 shard_ids: vec![ShardId::from(17u64)],
             }],
+            ..Default::default()
         };
-        ingester.retain_shards(retain_shards_request).await.unwrap();
+        let retain_shards_response = ingester.retain_shards(retain_shards_request).await.unwrap();
+        assert_eq!(retain_shards_response.removed_queue_ids.len(), 1);
 
         {
             let state_guard = ingester.state.lock_fully().await.unwrap();
@@ -3443,6 +3489,68 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_ingester_retain_shards_dry_run_and_min_idle() {
+        let (_ingester_ctx, ingester) = IngesterForTest::default().build().await;
+
+        let index_uid: IndexUid = IndexUid::for_test("test-index", 0);
+        let queue_id_01 = queue_id(&index_uid, "test-source", &ShardId::from(1));
+        let queue_id_02 = queue_id(&index_uid, "test-source", &ShardId::from(2));
+
+        let mut state_guard = ingester.state.lock_fully().await.unwrap();
+        let now = Instant::now();
+
+        for (queue_id, last_write_instant) in [
+            (&queue_id_01, now - Duration::from_secs(120)),
+            (&queue_id_02, now),
+        ] {
+            let mut shard = IngesterShard::new_solo(
+                ShardState::Closed,
+                Position::Beginning,
+                Position::Beginning,
+                None,
+                now,
+                false,
+            );
+            shard.last_write_instant = last_write_instant;
+            state_guard.shards.insert(queue_id.clone(), shard);
+            state_guard.mrecordlog.create_queue(queue_id).await.unwrap();
+        }
+        drop(state_guard);
+
+        let retain_shards_request = RetainShardsRequest {
+            retain_shards_for_sources: Vec::new(),
+            min_idle_secs: 60,
+            dry_run: true,
+        };
+        let retain_shards_response = ingester.retain_shards(retain_shards_request).await.unwrap();
+        assert_eq!(
+            retain_shards_response.removed_queue_ids,
+            [queue_id_01.clone()]
+        );
+
+        {
+            let state_guard = ingester.state.lock_fully().await.unwrap();
+            assert_eq!(state_guard.shards.len(), 2);
+        }
+
+        let retain_shards_request = RetainShardsRequest {
+            retain_shards_for_sources: Vec::new(),
+            min_idle_secs: 60,
+            dry_run: false,
+        };
+        let retain_shards_response = ingester.retain_shards(retain_shards_request).await.unwrap();
+        assert_eq!(
+            retain_shards_response.removed_queue_ids,
+            [queue_id_01.clone()]
+        );
+
+        let state_guard = ingester.state.lock_fully().await.unwrap();
+        assert_eq!(state_guard.shards.len(), 1);
+        assert!(state_guard.shards.contains_key(&queue_id_02));
+        assert!(!state_guard.mrecordlog.queue_exists(&queue_id_01));
+    }
+
     #[tokio::test]
     async fn test_ingester_close_shards() {
         let (_ingester_ctx, ingester) = IngesterForTest::default().build().await;
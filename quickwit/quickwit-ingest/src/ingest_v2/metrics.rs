@@ -28,6 +28,7 @@ pub(crate) struct IngestResultMetrics {
     pub circuit_breaker: IntCounter,
     pub unspecified: IntCounter,
     pub index_not_found: IntCounter,
+    pub index_frozen: IntCounter,
     pub source_not_found: IntCounter,
     pub internal: IntCounter,
     pub no_shards_available: IntCounter,
@@ -55,6 +56,7 @@ impl Default for IngestResultMetrics {
             circuit_breaker: ingest_result_total_vec.with_label_values(["circuit_breaker"]),
             unspecified: ingest_result_total_vec.with_label_values(["unspecified"]),
             index_not_found: ingest_result_total_vec.with_label_values(["index_not_found"]),
+            index_frozen: ingest_result_total_vec.with_label_values(["index_frozen"]),
             source_not_found: ingest_result_total_vec.with_label_values(["source_not_found"]),
             internal: ingest_result_total_vec.with_label_values(["internal"]),
             no_shards_available: ingest_result_total_vec.with_label_values(["no_shards_available"]),
@@ -20,6 +20,7 @@ use futures::{Future, StreamExt};
 use mrecordlog::error::CreateQueueError;
 use quickwit_common::metrics::{GaugeGuard, MEMORY_METRICS};
 use quickwit_common::{rate_limited_warn, ServiceStream};
+use quickwit_proto::compression::{PayloadCodec, PayloadCompression};
 use quickwit_proto::ingest::ingester::{
     ack_replication_message, syn_replication_message, AckReplicationMessage, IngesterStatus,
     InitReplicaRequest, InitReplicaResponse, ReplicateFailure, ReplicateFailureReason,
@@ -125,12 +126,14 @@ pub(super) struct ReplicationStreamTask {
 }
 
 impl ReplicationStreamTask {
-    /// Spawns a [`ReplicationStreamTask`].
+    /// Spawns a [`ReplicationStreamTask`]. The documents replicated through the stream are
+    /// compressed with `doc_batch_compression`, which must be supported by the follower.
     pub fn spawn(
         leader_id: NodeId,
         follower_id: NodeId,
         syn_replication_stream_tx: mpsc::Sender<SynReplicationMessage>,
         ack_replication_stream: ServiceStream<IngestV2Result<AckReplicationMessage>>,
+        doc_batch_compression: PayloadCompression,
     ) -> ReplicationStreamTaskHandle {
         let (replication_request_tx, replication_request_rx) =
             mpsc::channel::<OneShotReplicationRequest>(3);
@@ -147,6 +150,7 @@ impl ReplicationStreamTask {
 
         ReplicationStreamTaskHandle {
             replication_request_tx,
+            doc_batch_compression,
             enqueue_syn_requests_join_handle,
             dequeue_ack_responses_join_handle,
         }
@@ -265,6 +269,7 @@ impl ReplicationStreamTask {
 
 pub(super) struct ReplicationStreamTaskHandle {
     replication_request_tx: mpsc::Sender<OneShotReplicationRequest>,
+    doc_batch_compression: PayloadCompression,
     enqueue_syn_requests_join_handle: JoinHandle<()>,
     dequeue_ack_responses_join_handle: JoinHandle<()>,
 }
@@ -275,6 +280,7 @@ impl ReplicationStreamTaskHandle {
     pub fn replication_client(&self) -> ReplicationClient {
         ReplicationClient {
             replication_request_tx: self.replication_request_tx.clone(),
+            doc_batch_compression: self.doc_batch_compression,
         }
     }
 }
@@ -302,6 +308,7 @@ pub(super) enum ReplicationError {
 #[derive(Debug)]
 pub(super) struct ReplicationClient {
     replication_request_tx: mpsc::Sender<OneShotReplicationRequest>,
+    doc_batch_compression: PayloadCompression,
 }
 
 /// Single-use client that enqueues replication requests into the replication stream.
@@ -336,6 +343,9 @@ impl ReplicationClient {
 
     /// Enqueues a replicate request into the replication stream and waits for the response. Times
     /// out after [`REPLICATION_REQUEST_TIMEOUT`] seconds.
+    ///
+    /// The documents are compressed on the blocking thread pool beforehand if the follower
+    /// supports it.
     pub fn replicate(
         self,
         leader_id: NodeId,
@@ -343,16 +353,17 @@ impl ReplicationClient {
         subrequests: Vec<ReplicateSubrequest>,
         commit_type: CommitTypeV2,
     ) -> impl Future<Output = Result<ReplicateResponse, ReplicationError>> + Send + 'static {
-        let replicate_request = ReplicateRequest {
-            leader_id: leader_id.into(),
-            follower_id: follower_id.into(),
-            subrequests,
-            commit_type: commit_type as i32,
-            replication_seqno: 0, // replication number are generated further down
-        };
-        let replication_request = ReplicationRequest::Replicate(replicate_request);
+        async move {
+            let subrequests = compress_doc_batches(subrequests, self.doc_batch_compression).await;
+            let replicate_request = ReplicateRequest {
+                leader_id: leader_id.into(),
+                follower_id: follower_id.into(),
+                subrequests,
+                commit_type: commit_type as i32,
+                replication_seqno: 0, // replication number are generated further down
+            };
+            let replication_request = ReplicationRequest::Replicate(replicate_request);
 
-        async {
             self.submit(replication_request)
                 .await
                 .map(|replication_response| {
@@ -391,6 +402,25 @@ impl ReplicationClient {
     }
 }
 
+/// Compresses the documents of the subrequests on the blocking thread pool so that compressing
+/// large batches does not stall the runtime.
+async fn compress_doc_batches(
+    mut subrequests: Vec<ReplicateSubrequest>,
+    doc_batch_compression: PayloadCompression,
+) -> Vec<ReplicateSubrequest> {
+    if doc_batch_compression.codec == PayloadCodec::None {
+        return subrequests;
+    }
+    tokio::task::spawn_blocking(move || {
+        for subrequest in &mut subrequests {
+            subrequest.compress_doc_batch(&doc_batch_compression);
+        }
+        subrequests
+    })
+    .await
+    .expect("compression task should not panic")
+}
+
 /// Replication task executed for each replication stream.
 pub(super) struct ReplicationTask {
     leader_id: NodeId,
@@ -482,7 +512,7 @@ impl ReplicationTask {
 
     async fn replicate(
         &mut self,
-        replicate_request: ReplicateRequest,
+        mut replicate_request: ReplicateRequest,
     ) -> IngestV2Result<ReplicateResponse> {
         if replicate_request.leader_id != self.leader_id {
             return Err(IngestV2Error::Internal(format!(
@@ -503,6 +533,14 @@ impl ReplicationTask {
                 self.current_replication_seqno, replicate_request.replication_seqno
             )));
         }
+        // The leader may compress the documents before sending them.
+        for subrequest in &mut replicate_request.subrequests {
+            subrequest.decompress_doc_batch().map_err(|error| {
+                IngestV2Error::Internal(format!(
+                    "failed to decompress replicated documents: {error}"
+                ))
+            })?;
+        }
         let request_size_bytes = replicate_request.num_bytes();
         let mut gauge_guard = GaugeGuard::from_gauge(&MEMORY_METRICS.in_flight.ingester_replicate);
         gauge_guard.add(request_size_bytes as i64);
@@ -762,6 +800,7 @@ impl Drop for ReplicationTaskHandle {
 #[cfg(test)]
 mod tests {
 
+    use quickwit_proto::compression::{PayloadCodec, PayloadCompression};
     use quickwit_proto::ingest::ingester::{ReplicateSubrequest, ReplicateSuccess};
     use quickwit_proto::ingest::{DocBatchV2, Shard};
     use quickwit_proto::types::{queue_id, IndexUid, ShardId};
@@ -834,6 +873,7 @@ mod tests {
             follower_id,
             syn_replication_stream_tx,
             ack_replication_stream,
+            PayloadCompression::default(),
         );
         let dummy_replication_task_future = async move {
             while let Some(syn_replication_message) = syn_replication_stream_rx.recv().await {
@@ -881,6 +921,7 @@ mod tests {
             follower_id.clone(),
             syn_replication_stream_tx,
             ack_replication_stream,
+            PayloadCompression::default(),
         );
         let dummy_replication_task_future = async move {
             while let Some(syn_replication_message) = syn_replication_stream_rx.recv().await {
@@ -933,6 +974,7 @@ mod tests {
                 shard_id: Some(ShardId::from(1)),
                 doc_batch: Some(DocBatchV2::for_test(["test-doc-foo"])),
                 from_position_exclusive: Some(Position::Beginning),
+                doc_buffer_codec: PayloadCodec::None as i32,
             },
             ReplicateSubrequest {
                 subrequest_id: 1,
@@ -941,6 +983,7 @@ mod tests {
                 shard_id: Some(ShardId::from(2)),
                 doc_batch: Some(DocBatchV2::for_test(["test-doc-bar", "test-doc-baz"])),
                 from_position_exclusive: Some(Position::Beginning),
+                doc_buffer_codec: PayloadCodec::None as i32,
             },
             ReplicateSubrequest {
                 subrequest_id: 2,
@@ -949,6 +992,7 @@ mod tests {
                 shard_id: Some(ShardId::from(1)),
                 doc_batch: Some(DocBatchV2::for_test(["test-qux", "test-doc-tux"])),
                 from_position_exclusive: Some(Position::offset(0u64)),
+                doc_buffer_codec: PayloadCodec::None as i32,
             },
         ];
         let replicate_response = replication_stream_task_handle
@@ -1006,6 +1050,7 @@ mod tests {
             follower_id.clone(),
             syn_replication_stream_tx,
             ack_replication_stream,
+            PayloadCompression::default(),
         );
         let timeout_error = replication_stream_task_handle
             .replication_client()
@@ -1032,6 +1077,35 @@ mod tests {
         assert!(matches!(closed_error, ReplicationError::Closed));
     }
 
+    #[tokio::test]
+    async fn test_compress_doc_batches() {
+        let subrequests = vec![ReplicateSubrequest {
+            subrequest_id: 0,
+            index_uid: Some(IndexUid::for_test("test-index", 0)),
+            source_id: "test-source".to_string(),
+            shard_id: Some(ShardId::from(1)),
+            doc_batch: Some(DocBatchV2::for_test(["test-doc-foo", "test-doc-bar"])),
+            from_position_exclusive: Some(Position::Beginning),
+            doc_buffer_codec: PayloadCodec::None as i32,
+        }];
+        let uncompressed_subrequests =
+            compress_doc_batches(subrequests.clone(), PayloadCompression::default()).await;
+        assert_eq!(uncompressed_subrequests, subrequests);
+
+        let zstd_compression = PayloadCompression {
+            codec: PayloadCodec::Zstd,
+            min_payload_num_bytes: 0,
+        };
+        let mut compressed_subrequests =
+            compress_doc_batches(subrequests.clone(), zstd_compression).await;
+        assert_eq!(
+            compressed_subrequests[0].doc_buffer_codec,
+            PayloadCodec::Zstd as i32
+        );
+        compressed_subrequests[0].decompress_doc_batch().unwrap();
+        assert_eq!(compressed_subrequests, subrequests);
+    }
+
     #[tokio::test]
     async fn test_replication_task_happy_path() {
         let leader_id: NodeId = "test-leader".into();
@@ -1157,7 +1231,7 @@ mod tests {
 
         drop(state_guard);
 
-        let replicate_request = ReplicateRequest {
+        let mut replicate_request = ReplicateRequest {
             leader_id: "test-leader".to_string(),
             follower_id: "test-follower".to_string(),
             commit_type: CommitTypeV2::Auto as i32,
@@ -1169,6 +1243,7 @@ mod tests {
                     shard_id: Some(ShardId::from(1)),
                     doc_batch: Some(DocBatchV2::for_test(["test-doc-foo"])),
                     from_position_exclusive: Some(Position::Beginning),
+                    doc_buffer_codec: PayloadCodec::None as i32,
                 },
                 ReplicateSubrequest {
                     subrequest_id: 1,
@@ -1177,6 +1252,7 @@ mod tests {
                     shard_id: Some(ShardId::from(2)),
                     doc_batch: Some(DocBatchV2::for_test(["test-doc-bar", "test-doc-baz"])),
                     from_position_exclusive: Some(Position::Beginning),
+                    doc_buffer_codec: PayloadCodec::None as i32,
                 },
                 ReplicateSubrequest {
                     subrequest_id: 2,
@@ -1185,10 +1261,21 @@ mod tests {
                     shard_id: Some(ShardId::from(1)),
                     doc_batch: Some(DocBatchV2::for_test(["test-doc-qux", "test-doc-tux"])),
                     from_position_exclusive: Some(Position::Beginning),
+                    doc_buffer_codec: PayloadCodec::None as i32,
                 },
             ],
             replication_seqno: 3,
         };
+        // The follower decompresses the documents compressed by the leader.
+        let zstd_compression = PayloadCompression {
+            codec: PayloadCodec::Zstd,
+            min_payload_num_bytes: 0,
+        };
+        replicate_request.subrequests[1].compress_doc_batch(&zstd_compression);
+        assert_eq!(
+            replicate_request.subrequests[1].doc_buffer_codec,
+            PayloadCodec::Zstd as i32
+        );
         let syn_replication_message =
             SynReplicationMessage::new_replicate_request(replicate_request);
         syn_replication_stream_tx
@@ -1260,6 +1347,7 @@ mod tests {
                 shard_id: Some(ShardId::from(1)),
                 doc_batch: Some(DocBatchV2::for_test(["test-doc-moo"])),
                 from_position_exclusive: Some(Position::offset(0u64)),
+                doc_buffer_codec: PayloadCodec::None as i32,
             }],
             replication_seqno: 4,
         };
@@ -1345,6 +1433,7 @@ mod tests {
                 shard_id: Some(ShardId::from(1)),
                 doc_batch: Some(DocBatchV2::for_test(["test-doc-foo"])),
                 from_position_exclusive: Position::offset(0u64).into(),
+                doc_buffer_codec: PayloadCodec::None as i32,
             }],
             replication_seqno: 0,
         };
@@ -1422,6 +1511,7 @@ mod tests {
                 shard_id: Some(ShardId::from(1)),
                 doc_batch: Some(DocBatchV2::for_test(["test-doc-foo"])),
                 from_position_exclusive: Position::offset(0u64).into(),
+                doc_buffer_codec: PayloadCodec::None as i32,
             }],
             replication_seqno: 0,
         };
@@ -1517,6 +1607,7 @@ mod tests {
                 shard_id: Some(ShardId::from(1)),
                 doc_batch: Some(DocBatchV2::for_test(["test-doc-foo"])),
                 from_position_exclusive: Position::offset(0u64).into(),
+                doc_buffer_codec: PayloadCodec::None as i32,
             }],
             replication_seqno: 0,
         };
@@ -1599,6 +1690,7 @@ mod tests {
                 shard_id: Some(ShardId::from(1)),
                 doc_batch: Some(DocBatchV2::for_test(["test-doc-foo"])),
                 from_position_exclusive: Some(Position::Beginning),
+                doc_buffer_codec: PayloadCodec::None as i32,
             }],
             replication_seqno: 0,
         };
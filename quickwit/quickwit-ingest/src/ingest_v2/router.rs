@@ -531,6 +531,7 @@ fn update_ingest_metrics(ingest_result: &IngestV2Result<IngestResponseV2>, num_s
                     IngestFailureReason::IndexNotFound => {
                         ingest_results_metrics.index_not_found.inc()
                     }
+                    IngestFailureReason::IndexFrozen => ingest_results_metrics.index_frozen.inc(),
                     IngestFailureReason::SourceNotFound => {
                         ingest_results_metrics.source_not_found.inc()
                     }
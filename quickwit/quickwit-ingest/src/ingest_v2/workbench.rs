@@ -144,6 +144,7 @@ impl IngestWorkbench {
     ) {
         let last_failure = match open_shards_failure.reason() {
             GetOrCreateOpenShardsFailureReason::IndexNotFound => SubworkbenchFailure::IndexNotFound,
+            GetOrCreateOpenShardsFailureReason::IndexFrozen => SubworkbenchFailure::IndexFrozen,
             GetOrCreateOpenShardsFailureReason::SourceNotFound => {
                 SubworkbenchFailure::SourceNotFound
             }
@@ -328,6 +329,8 @@ impl IngestWorkbench {
 pub(super) enum SubworkbenchFailure {
     // There is no entry in the routing table for this index.
     IndexNotFound,
+    // The ingestion into this index is frozen.
+    IndexFrozen,
     // There is no entry in the routing table for this source.
     SourceNotFound,
     // The routing table entry for this source is empty, shards are all closed, or their leaders
@@ -348,6 +351,7 @@ impl SubworkbenchFailure {
     fn reason(&self) -> IngestFailureReason {
         match self {
             Self::IndexNotFound => IngestFailureReason::IndexNotFound,
+            Self::IndexFrozen => IngestFailureReason::IndexFrozen,
             Self::SourceNotFound => IngestFailureReason::SourceNotFound,
             Self::Internal => IngestFailureReason::Internal,
             Self::NoShardsAvailable => IngestFailureReason::NoShardsAvailable,
@@ -391,10 +395,12 @@ impl IngestSubworkbench {
     /// Returns `false` if and only if the last attempt suggests retrying (on any node) will fail.
     /// e.g.:
     /// - the index does not exist
+    /// - the ingestion into the index is frozen
     /// - the source does not exist.
     fn last_failure_is_transient(&self) -> bool {
         match self.last_failure_opt {
             Some(SubworkbenchFailure::IndexNotFound) => false,
+            Some(SubworkbenchFailure::IndexFrozen) => false,
             Some(SubworkbenchFailure::SourceNotFound) => false,
             Some(SubworkbenchFailure::Internal) => true,
             Some(SubworkbenchFailure::NoShardsAvailable) => true,
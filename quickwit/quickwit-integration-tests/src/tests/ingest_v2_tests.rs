@@ -25,7 +25,7 @@ use quickwit_proto::ingest::ParseFailureReason;
 use quickwit_rest_client::error::{ApiError, Error};
 use quickwit_rest_client::models::IngestSource;
 use quickwit_rest_client::rest_client::CommitType;
-use quickwit_serve::{ListSplitsQueryParams, RestIngestResponse, RestParseFailure};
+use quickwit_serve::{IngestAckLevel, ListSplitsQueryParams, RestIngestResponse, RestParseFailure};
 use serde_json::json;
 
 use crate::ingest_json;
@@ -254,10 +254,18 @@ async fn test_ingest_v2_index_not_found() {
         )
         .await
         .unwrap_err();
-    let Error::Api(ApiError { message, code }) = missing_index_err else {
+    let Error::Api(ApiError {
+        message,
+        code,
+        error_code,
+        retryable,
+    }) = missing_index_err
+    else {
         panic!("Expected an API error.");
     };
     assert_eq!(code, 404u16);
+    assert_eq!(error_code.as_deref(), Some("not_found"));
+    assert!(!retryable);
     let error_message = message.unwrap();
     assert_eq!(error_message, "index `missing_index` not found");
     sandbox.shutdown().await.unwrap();
@@ -311,6 +319,7 @@ async fn test_ingest_v2_happy_path() {
             num_ingested_docs: Some(1),
             num_rejected_docs: Some(0),
             parse_failures: None,
+            ack_level: Some(IngestAckLevel::Leader),
         },
     );
 
@@ -377,6 +386,7 @@ async fn test_commit_force() {
             num_ingested_docs: Some(1),
             num_rejected_docs: Some(0),
             parse_failures: None,
+            ack_level: Some(IngestAckLevel::Searchable),
         },
     );
 
@@ -457,6 +467,7 @@ async fn test_commit_wait_for() {
             num_ingested_docs: Some(1),
             num_rejected_docs: Some(0),
             parse_failures: None,
+            ack_level: Some(IngestAckLevel::Searchable),
         },
     );
     assert_eq!(
@@ -466,6 +477,7 @@ async fn test_commit_wait_for() {
             num_ingested_docs: Some(1),
             num_rejected_docs: Some(0),
             parse_failures: None,
+            ack_level: Some(IngestAckLevel::Searchable),
         },
     );
 
@@ -528,6 +540,7 @@ async fn test_commit_auto() {
             num_ingested_docs: Some(1),
             num_rejected_docs: Some(0),
             parse_failures: None,
+            ack_level: Some(IngestAckLevel::Leader),
         },
     );
 
@@ -586,6 +599,7 @@ async fn test_detailed_ingest_response() {
                 message: "failed to parse JSON document".to_string(),
                 reason: ParseFailureReason::InvalidJson,
             }]),
+            ack_level: Some(IngestAckLevel::Leader),
         },
     );
     sandbox.shutdown().await.unwrap();
@@ -91,7 +91,7 @@ async fn test_searcher_and_metastore_without_control_plane() {
         .await
         .unwrap_err();
 
-    if let RestClientError::Api(ApiError { message, code }) = search_error {
+    if let RestClientError::Api(ApiError { message, code, .. }) = search_error {
         assert_eq!(
             message.unwrap(),
             "could not find indexes matching the IDs `[\"does-not-exist\"]`"
@@ -39,7 +39,7 @@ use quickwit_proto::jaeger::storage::v1::{
     SpansResponseChunk, TraceQueryParameters,
 };
 use quickwit_proto::opentelemetry::proto::trace::v1::status::StatusCode as OtlpStatusCode;
-use quickwit_proto::search::{CountHits, ListTermsRequest, SearchRequest};
+use quickwit_proto::search::{CountHits, ListTermsRequest, SearchRequest, SecurityContext};
 use quickwit_query::query_ast::{BoolQuery, QueryAst, RangeQuery, TermQuery, UserInputQuery};
 use quickwit_query::BooleanOperand;
 use quickwit_search::{FindTraceIdsCollector, SearchService};
@@ -90,6 +90,7 @@ impl JaegerService {
         &self,
         request: GetServicesRequest,
         index_id_patterns: Vec<String>,
+        security_context_opt: Option<SecurityContext>,
     ) -> JaegerResult<GetServicesResponse> {
         debug!(request=?request, index_ids=?index_id_patterns, "`get_services` request");
 
@@ -105,6 +106,7 @@ impl JaegerService {
             end_timestamp: None,
             start_key: None,
             end_key: None,
+            security_context: security_context_opt,
         };
         let search_response = self.search_service.root_list_terms(search_request).await?;
         let services: Vec<String> = search_response
@@ -123,6 +125,7 @@ impl JaegerService {
         &self,
         request: GetOperationsRequest,
         index_id_patterns: Vec<String>,
+        security_context_opt: Option<SecurityContext>,
     ) -> JaegerResult<GetOperationsResponse> {
         debug!(request=?request, request=?request, index_ids=?index_id_patterns, "`get_operations` request");
 
@@ -142,6 +145,7 @@ impl JaegerService {
             end_timestamp: None,
             start_key,
             end_key,
+            security_context: security_context_opt,
         };
         let search_response = self.search_service.root_list_terms(search_request).await?;
         let operations: Vec<Operation> = search_response
@@ -163,6 +167,7 @@ impl JaegerService {
         &self,
         request: FindTraceIDsRequest,
         index_id_patterns: Vec<String>,
+        security_context_opt: Option<SecurityContext>,
     ) -> JaegerResult<FindTraceIDsResponse> {
         debug!(request=?request, index_ids=?index_id_patterns, "`find_trace_ids` request");
 
@@ -170,7 +175,9 @@ impl JaegerService {
             .query
             .ok_or_else(|| Status::invalid_argument("Query is empty."))?;
 
-        let (trace_ids, _) = self.find_trace_ids(trace_query, index_id_patterns).await?;
+        let (trace_ids, _) = self
+            .find_trace_ids(trace_query, index_id_patterns, security_context_opt)
+            .await?;
         let trace_ids = trace_ids
             .into_iter()
             .map(|trace_id| trace_id.to_vec())
@@ -188,6 +195,7 @@ impl JaegerService {
         request_start: Instant,
         index_id_patterns: Vec<String>,
         root_only: bool,
+        security_context_opt: Option<SecurityContext>,
     ) -> JaegerResult<SpanStream> {
         debug!(request=?request, "`find_traces` request");
 
@@ -195,7 +203,11 @@ impl JaegerService {
             .query
             .ok_or_else(|| Status::invalid_argument("Trace query is empty."))?;
         let (trace_ids, span_timestamps_range) = self
-            .find_trace_ids(trace_query, index_id_patterns.clone())
+            .find_trace_ids(
+                trace_query,
+                index_id_patterns.clone(),
+                security_context_opt.clone(),
+            )
             .await?;
         let start = span_timestamps_range.start() - self.max_trace_duration_secs;
         let end = span_timestamps_range.end() + self.max_trace_duration_secs;
@@ -208,6 +220,7 @@ impl JaegerService {
                 request_start,
                 index_id_patterns,
                 root_only,
+                security_context_opt,
             )
             .await?;
         Ok(response)
@@ -220,6 +233,7 @@ impl JaegerService {
         operation_name: &'static str,
         request_start: Instant,
         index_id_patterns: Vec<String>,
+        security_context_opt: Option<SecurityContext>,
     ) -> JaegerResult<SpanStream> {
         debug!(request=?request, "`get_trace` request");
         debug_assert_eq!(request.trace_id.len(), 16);
@@ -236,6 +250,7 @@ impl JaegerService {
                 request_start,
                 index_id_patterns,
                 false,
+                security_context_opt,
             )
             .await?;
         Ok(response)
@@ -246,6 +261,7 @@ impl JaegerService {
         &self,
         trace_query: TraceQueryParameters,
         index_id_patterns: Vec<String>,
+        security_context_opt: Option<SecurityContext>,
     ) -> Result<(Vec<TraceId>, TimeIntervalSecs), Status> {
         let span_kind_opt = None;
         let min_span_start_timestamp_secs_opt = trace_query.start_time_min.map(|ts| ts.seconds);
@@ -278,6 +294,7 @@ impl JaegerService {
             start_timestamp: min_span_start_timestamp_secs_opt,
             end_timestamp: max_span_start_timestamp_secs_opt,
             count_hits: CountHits::Underestimate.into(),
+            security_context: security_context_opt,
             ..Default::default()
         };
         let search_response = self.search_service.root_search(search_request).await?;
@@ -300,6 +317,7 @@ impl JaegerService {
         request_start: Instant,
         index_id_patterns: Vec<String>,
         root_only: bool,
+        security_context_opt: Option<SecurityContext>,
     ) -> Result<SpanStream, Status> {
         if trace_ids.is_empty() {
             let (_tx, rx) = mpsc::channel(1);
@@ -322,8 +340,8 @@ impl JaegerService {
             let is_root = UserInputQuery {
                 user_text: "NOT is_root:false".to_string(),
                 default_fields: None,
-                default_operator: BooleanOperand::And,
-                lenient: true,
+                default_operator: Some(BooleanOperand::And),
+                lenient: Some(true),
             };
             let mut new_query = BoolQuery::default();
             new_query.must.push(query.into());
@@ -342,6 +360,7 @@ impl JaegerService {
             end_timestamp: Some(*search_window.end()),
             max_hits: self.max_fetch_spans,
             count_hits: CountHits::Underestimate.into(),
+            security_context: security_context_opt,
             ..Default::default()
         };
         let search_response = match self.search_service.root_search(search_request).await {
@@ -494,7 +513,7 @@ impl SpanReaderPlugin for JaegerService {
         let index_id_patterns =
             extract_otel_traces_index_id_patterns_from_metadata(request.metadata())?;
         metrics!(
-            self.get_services_for_indexes(request.into_inner(), index_id_patterns)
+            self.get_services_for_indexes(request.into_inner(), index_id_patterns, None)
                 .await,
             [get_services, OTEL_TRACES_INDEX_ID]
         );
@@ -507,7 +526,7 @@ impl SpanReaderPlugin for JaegerService {
         let index_id_patterns =
             extract_otel_traces_index_id_patterns_from_metadata(request.metadata())?;
         metrics!(
-            self.get_operations_for_indexes(request.into_inner(), index_id_patterns)
+            self.get_operations_for_indexes(request.into_inner(), index_id_patterns, None)
                 .await,
             [get_operations, OTEL_TRACES_INDEX_ID]
         );
@@ -520,7 +539,7 @@ impl SpanReaderPlugin for JaegerService {
         let index_id_patterns =
             extract_otel_traces_index_id_patterns_from_metadata(request.metadata())?;
         metrics!(
-            self.find_trace_ids_for_indexes(request.into_inner(), index_id_patterns)
+            self.find_trace_ids_for_indexes(request.into_inner(), index_id_patterns, None)
                 .await,
             [find_trace_ids, OTEL_TRACES_INDEX_ID]
         );
@@ -540,6 +559,7 @@ impl SpanReaderPlugin for JaegerService {
             false, /* if we use true, Jaeger will display "1 Span", and display an empty trace
                     * when clicking on the ui (but display the full trace after reloading the
                     * page) */
+            None,
         )
         .await
         .map(Response::new)
@@ -556,6 +576,7 @@ impl SpanReaderPlugin for JaegerService {
             "get_trace",
             Instant::now(),
             index_id_patterns,
+            None,
         )
         .await
         .map(Response::new)
@@ -1218,8 +1239,8 @@ mod tests {
                 quickwit_query::query_ast::UserInputQuery {
                     user_text: "query".to_string(),
                     default_fields: None,
-                    default_operator: quickwit_query::BooleanOperand::And,
-                    lenient: false,
+                    default_operator: Some(quickwit_query::BooleanOperand::And),
+                    lenient: Some(false),
                 }
                 .into()
             );
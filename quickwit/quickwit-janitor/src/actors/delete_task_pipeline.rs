@@ -226,6 +226,7 @@ impl DeleteTaskPipeline {
         let task_planner = DeleteTaskPlanner::new(
             self.index_uid.clone(),
             index_uri.clone(),
+            index_config.storage_credentials_opt.clone(),
             doc_mapper_str,
             self.metastore.clone(),
             self.search_job_placer.clone(),
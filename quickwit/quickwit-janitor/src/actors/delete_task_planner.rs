@@ -74,6 +74,7 @@ const NUM_STALE_SPLITS_TO_FETCH: usize = 1000;
 pub struct DeleteTaskPlanner {
     index_uid: IndexUid,
     index_uri: Uri,
+    storage_credentials_opt: Option<String>,
     doc_mapper_str: String,
     metastore: MetastoreServiceClient,
     search_job_placer: SearchJobPlacer,
@@ -116,9 +117,11 @@ impl Actor for DeleteTaskPlanner {
 }
 
 impl DeleteTaskPlanner {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         index_uid: IndexUid,
         index_uri: Uri,
+        storage_credentials_opt: Option<String>,
         doc_mapper_str: String,
         metastore: MetastoreServiceClient,
         search_job_placer: SearchJobPlacer,
@@ -128,6 +131,7 @@ impl DeleteTaskPlanner {
         Self {
             index_uid,
             index_uri,
+            storage_credentials_opt,
             doc_mapper_str,
             metastore,
             search_job_placer,
@@ -320,6 +324,8 @@ impl DeleteTaskPlanner {
                 IndexMetasForLeafSearch {
                     doc_mapper_str: doc_mapper_str.to_string(),
                     index_uri,
+                    storage_credentials_opt: self.storage_credentials_opt.clone(),
+                    tag_overlays: Vec::new(),
                 },
             );
             let leaf_search_request = jobs_to_leaf_request(
@@ -540,6 +546,7 @@ mod tests {
         let delete_planner = DeleteTaskPlanner::new(
             index_uid.clone(),
             index_config.index_uri.clone(),
+            None,
             doc_mapper_str,
             metastore.clone(),
             search_job_placer,
@@ -111,7 +111,11 @@ impl GarbageCollector {
             async move {
                 let index_uid = index.index_uid.clone();
                 let index_uri = index.index_uri();
-                let storage = match storage_resolver.resolve(index_uri).await {
+                let storage_credentials_opt = index.index_config.storage_credentials_opt.as_deref();
+                let storage = match storage_resolver
+                    .resolve_with_credentials(index_uri, storage_credentials_opt)
+                    .await
+                {
                     Ok(storage) => storage,
                     Err(error) => {
                         error!(index=%index.index_id(), error=?error, "failed to resolve the index storage Uri");
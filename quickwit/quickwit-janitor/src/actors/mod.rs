@@ -16,8 +16,16 @@ mod delete_task_pipeline;
 mod delete_task_planner;
 mod delete_task_service;
 mod garbage_collector;
+mod orphan_shards_cleaner;
 mod retention_policy_executor;
+mod rollover_executor;
+mod split_replicator;
+mod trash_purger;
 
 pub use delete_task_service::{DeleteTaskService, DELETE_SERVICE_TASK_DIR_NAME};
 pub use garbage_collector::GarbageCollector;
+pub use orphan_shards_cleaner::OrphanShardsCleaner;
 pub use retention_policy_executor::RetentionPolicyExecutor;
+pub use rollover_executor::RolloverExecutor;
+pub use split_replicator::{SplitReplicator, SPLIT_REPLICATOR_DIR_NAME};
+pub use trash_purger::TrashPurger;
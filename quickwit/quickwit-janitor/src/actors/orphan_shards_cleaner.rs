@@ -0,0 +1,363 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use quickwit_actors::{Actor, ActorContext, Handler};
+use quickwit_ingest::IngesterPool;
+use quickwit_metastore::ListIndexesMetadataResponseExt;
+use quickwit_proto::ingest::ingester::{
+    IngesterService, RetainShardsForSource, RetainShardsRequest,
+};
+use quickwit_proto::metastore::{
+    ListIndexesMetadataRequest, ListShardsRequest, ListShardsSubrequest, MetastoreService,
+    MetastoreServiceClient, SourceType,
+};
+use quickwit_proto::types::{IndexUid, NodeId, ShardId, SourceId};
+use serde::Serialize;
+use tracing::{debug, error, info};
+
+use crate::metrics::JANITOR_METRICS;
+
+const RUN_INTERVAL: Duration = Duration::from_secs(30 * 60); // 30 minutes
+
+/// Shards recorded in the metastore, grouped by ingester (leader or follower) and by source.
+type ShardsPerIngester = HashMap<NodeId, HashMap<(IndexUid, SourceId), Vec<ShardId>>>;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct OrphanShardsCleanerCounters {
+    /// The number of cleanup passes.
+    pub num_passes: usize,
+
+    /// The number of orphan shards found on the ingesters, removed or only reported in dry-run
+    /// mode.
+    pub num_orphan_shards: usize,
+}
+
+#[derive(Debug)]
+struct Loop;
+
+/// An actor that periodically removes the orphan shards from the ingesters, i.e. the WAL queues of
+/// the shards that are no longer recorded in the metastore, such as the shards of deleted indexes
+/// or of deleted sources. Otherwise, these queues keep using disk space until they are removed
+/// manually.
+pub struct OrphanShardsCleaner {
+    metastore: MetastoreServiceClient,
+    ingester_pool: IngesterPool,
+    min_idle_period: Duration,
+    dry_run: bool,
+    counters: OrphanShardsCleanerCounters,
+}
+
+impl OrphanShardsCleaner {
+    pub fn new(
+        metastore: MetastoreServiceClient,
+        ingester_pool: IngesterPool,
+        min_idle_period: Duration,
+        dry_run: bool,
+    ) -> Self {
+        Self {
+            metastore,
+            ingester_pool,
+            min_idle_period,
+            dry_run,
+            counters: OrphanShardsCleanerCounters::default(),
+        }
+    }
+
+    /// Removes the orphan shards from all the ingesters of the cluster.
+    /// Should not return an error to prevent the actor from crashing.
+    async fn clean_orphan_shards(&mut self, ctx: &ActorContext<Self>) {
+        if self.ingester_pool.is_empty() {
+            return;
+        }
+        debug!("cleaning orphan shards");
+        self.counters.num_passes += 1;
+
+        // The shards must be listed before the ingesters are contacted: a shard opened in the
+        // meantime is protected by the min idle period.
+        let mut shards_per_ingester = match self.list_shards_per_ingester(ctx).await {
+            Ok(shards_per_ingester) => shards_per_ingester,
+            Err(error) => {
+                error!(%error, "failed to list shards from the metastore");
+                return;
+            }
+        };
+        let (action, message) = if self.dry_run {
+            ("reported", "found orphan shards (dry run)")
+        } else {
+            ("removed", "removed orphan shards")
+        };
+        for (ingester_id, ingester) in self.ingester_pool.pairs() {
+            let retain_shards_for_sources = shards_per_ingester
+                .remove(&ingester_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(
+                    |((index_uid, source_id), shard_ids)| RetainShardsForSource {
+                        index_uid: Some(index_uid),
+                        source_id,
+                        shard_ids,
+                    },
+                )
+                .collect();
+            let retain_shards_request = RetainShardsRequest {
+                retain_shards_for_sources,
+                min_idle_secs: self.min_idle_period.as_secs(),
+                dry_run: self.dry_run,
+            };
+            let retain_shards_response = match ctx
+                .protect_future(ingester.retain_shards(retain_shards_request))
+                .await
+            {
+                Ok(retain_shards_response) => retain_shards_response,
+                Err(error) => {
+                    error!(%ingester_id, %error, "failed to clean orphan shards");
+                    continue;
+                }
+            };
+            let num_orphan_shards = retain_shards_response.removed_queue_ids.len();
+
+            if num_orphan_shards == 0 {
+                continue;
+            }
+            info!(
+                %ingester_id,
+                queues=?retain_shards_response.removed_queue_ids,
+                "{message}"
+            );
+            self.counters.num_orphan_shards += num_orphan_shards;
+            JANITOR_METRICS
+                .orphan_shards
+                .with_label_values([action])
+                .inc_by(num_orphan_shards as u64);
+        }
+    }
+
+    async fn list_shards_per_ingester(
+        &self,
+        ctx: &ActorContext<Self>,
+    ) -> anyhow::Result<ShardsPerIngester> {
+        // The shards of the indexes in the trash are kept until the indexes are purged.
+        let indexes_metadata = ctx
+            .protect_future(
+                self.metastore
+                    .list_indexes_metadata(ListIndexesMetadataRequest::all_including_trashed()),
+            )
+            .await?
+            .deserialize_indexes_metadata()
+            .await?;
+        let subrequests: Vec<ListShardsSubrequest> = indexes_metadata
+            .iter()
+            .flat_map(|index_metadata| {
+                index_metadata
+                    .sources
+                    .values()
+                    .filter(|source_config| source_config.source_type() == SourceType::IngestV2)
+                    .map(|source_config| ListShardsSubrequest {
+                        index_uid: Some(index_metadata.index_uid.clone()),
+                        source_id: source_config.source_id.clone(),
+                        shard_state: None,
+                    })
+            })
+            .collect();
+        let mut shards_per_ingester = ShardsPerIngester::new();
+
+        if subrequests.is_empty() {
+            return Ok(shards_per_ingester);
+        }
+        let list_shards_request = ListShardsRequest { subrequests };
+        let list_shards_response = ctx
+            .protect_future(self.metastore.list_shards(list_shards_request))
+            .await?;
+
+        for subresponse in list_shards_response.subresponses {
+            for shard in subresponse.shards {
+                let source_key = (shard.index_uid().clone(), shard.source_id.clone());
+                let ingester_ids =
+                    std::iter::once(shard.leader_id.clone()).chain(shard.follower_id.clone());
+
+                for ingester_id in ingester_ids {
+                    shards_per_ingester
+                        .entry(NodeId::from(ingester_id))
+                        .or_default()
+                        .entry(source_key.clone())
+                        .or_default()
+                        .push(shard.shard_id().clone());
+                }
+            }
+        }
+        Ok(shards_per_ingester)
+    }
+}
+
+#[async_trait]
+impl Actor for OrphanShardsCleaner {
+    type ObservableState = OrphanShardsCleanerCounters;
+
+    fn observable_state(&self) -> Self::ObservableState {
+        self.counters.clone()
+    }
+
+    fn name(&self) -> String {
+        "OrphanShardsCleaner".to_string()
+    }
+
+    async fn initialize(
+        &mut self,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), quickwit_actors::ActorExitStatus> {
+        self.handle(Loop, ctx).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler<Loop> for OrphanShardsCleaner {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        _: Loop,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), quickwit_actors::ActorExitStatus> {
+        self.clean_orphan_shards(ctx).await;
+        ctx.schedule_self_msg(RUN_INTERVAL, Loop);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_actors::Universe;
+    use quickwit_config::SourceConfig;
+    use quickwit_metastore::IndexMetadata;
+    use quickwit_proto::ingest::ingester::{
+        IngesterServiceClient, MockIngesterService, RetainShardsResponse,
+    };
+    use quickwit_proto::ingest::Shard;
+    use quickwit_proto::metastore::{
+        ListIndexesMetadataResponse, ListShardsResponse, ListShardsSubresponse,
+        MockMetastoreService,
+    };
+
+    use super::*;
+
+    fn mock_metastore() -> MockMetastoreService {
+        let mut index_metadata = IndexMetadata::for_test("test-index", "ram:///indexes/test-index");
+        index_metadata
+            .add_source(SourceConfig::ingest_v2())
+            .unwrap();
+        let index_uid = index_metadata.index_uid.clone();
+
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_list_indexes_metadata().return_once(
+            move |list_indexes_metadata_request| {
+                assert!(list_indexes_metadata_request.include_trashed);
+                Ok(ListIndexesMetadataResponse::for_test(vec![index_metadata]))
+            },
+        );
+        mock_metastore
+            .expect_list_shards()
+            .return_once(move |list_shards_request| {
+                assert_eq!(list_shards_request.subrequests.len(), 1);
+                let shards = vec![Shard {
+                    index_uid: Some(index_uid.clone()),
+                    source_id: "_ingest-source".to_string(),
+                    shard_id: Some(ShardId::from(1)),
+                    leader_id: "test-ingester-0".to_string(),
+                    follower_id: Some("test-ingester-1".to_string()),
+                    ..Default::default()
+                }];
+                Ok(ListShardsResponse {
+                    subresponses: vec![ListShardsSubresponse {
+                        index_uid: Some(index_uid),
+                        source_id: "_ingest-source".to_string(),
+                        shards,
+                    }],
+                })
+            });
+        mock_metastore
+    }
+
+    fn mock_ingester(
+        expected_num_retained_shards: usize,
+        removed_queue_ids: Vec<String>,
+    ) -> IngesterServiceClient {
+        let mut mock_ingester = MockIngesterService::new();
+        mock_ingester
+            .expect_retain_shards()
+            .once()
+            .return_once(move |retain_shards_request| {
+                assert_eq!(retain_shards_request.min_idle_secs, 3600);
+                assert!(retain_shards_request.dry_run);
+
+                let num_retained_shards: usize = retain_shards_request
+                    .retain_shards_for_sources
+                    .iter()
+                    .map(|retain_shards_for_source| retain_shards_for_source.shard_ids.len())
+                    .sum();
+                assert_eq!(num_retained_shards, expected_num_retained_shards);
+
+                Ok(RetainShardsResponse { removed_queue_ids })
+            });
+        IngesterServiceClient::from_mock(mock_ingester)
+    }
+
+    #[tokio::test]
+    async fn test_orphan_shards_cleaner() {
+        let ingester_pool = IngesterPool::default();
+        ingester_pool.insert("test-ingester-0".into(), mock_ingester(1, Vec::new()));
+        ingester_pool.insert("test-ingester-1".into(), mock_ingester(1, Vec::new()));
+        ingester_pool.insert(
+            "test-ingester-2".into(),
+            mock_ingester(
+                0,
+                vec![
+                    "test-index:0/_ingest-source/2".to_string(),
+                    "test-index:0/_ingest-source/3".to_string(),
+                ],
+            ),
+        );
+        let orphan_shards_cleaner = OrphanShardsCleaner::new(
+            MetastoreServiceClient::from_mock(mock_metastore()),
+            ingester_pool,
+            Duration::from_secs(3600),
+            true,
+        );
+        let universe = Universe::with_accelerated_time();
+        let (_mailbox, handle) = universe.spawn_builder().spawn(orphan_shards_cleaner);
+        let counters = handle.process_pending_and_observe().await.state;
+        assert_eq!(counters.num_passes, 1);
+        assert_eq!(counters.num_orphan_shards, 2);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_orphan_shards_cleaner_without_ingesters() {
+        let orphan_shards_cleaner = OrphanShardsCleaner::new(
+            MetastoreServiceClient::from_mock(MockMetastoreService::new()),
+            IngesterPool::default(),
+            Duration::from_secs(3600),
+            false,
+        );
+        let universe = Universe::with_accelerated_time();
+        let (_mailbox, handle) = universe.spawn_builder().spawn(orphan_shards_cleaner);
+        let counters = handle.process_pending_and_observe().await.state;
+        assert_eq!(counters.num_passes, 0);
+        universe.assert_quit().await;
+    }
+}
@@ -0,0 +1,418 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use quickwit_actors::{Actor, ActorContext, Handler};
+use quickwit_common::uri::Uri;
+use quickwit_config::{IndexTemplate, RolloverPolicy, SourceConfig};
+use quickwit_metastore::{
+    CreateIndexRequestExt, IndexMetadata, ListIndexesMetadataResponseExt, ListSplitsQuery,
+    ListSplitsRequestExt, MetastoreServiceStreamSplitsExt, SplitState,
+};
+use quickwit_proto::metastore::{
+    serde_utils, CreateIndexRequest, ListIndexTemplatesRequest, ListIndexesMetadataRequest,
+    ListSplitsRequest, MetastoreService, MetastoreServiceClient,
+};
+use serde::Serialize;
+use time::OffsetDateTime;
+use tracing::{debug, error, info};
+
+const RUN_INTERVAL: Duration = Duration::from_secs(5 * 60); // 5 minutes
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct RolloverExecutorCounters {
+    /// The number of evaluation passes.
+    pub num_evaluation_passes: usize,
+
+    /// The number of generations created.
+    pub num_rollovers: usize,
+}
+
+#[derive(Debug)]
+struct Loop;
+
+/// An actor that periodically evaluates the rollover policies of the index templates and creates
+/// the next generation of a write alias when its latest generation meets one of the rollover
+/// conditions.
+pub struct RolloverExecutor {
+    metastore: MetastoreServiceClient,
+    default_index_root_uri: Uri,
+    counters: RolloverExecutorCounters,
+}
+
+impl RolloverExecutor {
+    pub fn new(metastore: MetastoreServiceClient, default_index_root_uri: Uri) -> Self {
+        Self {
+            metastore,
+            default_index_root_uri,
+            counters: RolloverExecutorCounters::default(),
+        }
+    }
+
+    /// Evaluates the rollover policies of all the index templates.
+    /// Should not return an error to prevent the actor from crashing.
+    async fn evaluate_rollover_policies(&mut self, ctx: &ActorContext<Self>) {
+        debug!("evaluating rollover policies");
+        self.counters.num_evaluation_passes += 1;
+
+        let index_templates = match self.list_index_templates(ctx).await {
+            Ok(index_templates) => index_templates,
+            Err(error) => {
+                error!(%error, "failed to list index templates from the metastore");
+                return;
+            }
+        };
+        for index_template in index_templates {
+            let Some(rollover_policy) = &index_template.rollover_policy_opt else {
+                continue;
+            };
+            if let Err(error) = self
+                .evaluate_rollover_policy(&index_template, rollover_policy, ctx)
+                .await
+            {
+                error!(
+                    template_id=%index_template.template_id,
+                    write_alias=%rollover_policy.write_alias,
+                    %error,
+                    "failed to evaluate rollover policy"
+                );
+            }
+        }
+    }
+
+    async fn list_index_templates(
+        &self,
+        ctx: &ActorContext<Self>,
+    ) -> anyhow::Result<Vec<IndexTemplate>> {
+        let list_index_templates_response = ctx
+            .protect_future(
+                self.metastore
+                    .list_index_templates(ListIndexTemplatesRequest {}),
+            )
+            .await?;
+        let index_templates = list_index_templates_response
+            .index_templates_json
+            .iter()
+            .map(|index_template_json| serde_utils::from_json_str(index_template_json))
+            .collect::<Result<Vec<IndexTemplate>, _>>()?;
+        Ok(index_templates)
+    }
+
+    async fn evaluate_rollover_policy(
+        &mut self,
+        index_template: &IndexTemplate,
+        rollover_policy: &RolloverPolicy,
+        ctx: &ActorContext<Self>,
+    ) -> anyhow::Result<()> {
+        let list_indexes_metadata_request = ListIndexesMetadataRequest {
+            index_id_patterns: vec![format!("{}-*", rollover_policy.write_alias)],
+            include_trashed: false,
+            label_selectors: Vec::new(),
+        };
+        let indexes_metadata = ctx
+            .protect_future(
+                self.metastore
+                    .list_indexes_metadata(list_indexes_metadata_request),
+            )
+            .await?
+            .deserialize_indexes_metadata()
+            .await?;
+        let latest_generation_opt = indexes_metadata
+            .into_iter()
+            .filter_map(|index_metadata| {
+                let generation = rollover_policy.parse_generation(index_metadata.index_id())?;
+                Some((generation, index_metadata))
+            })
+            .max_by_key(|(generation, _)| *generation);
+
+        let next_generation = match latest_generation_opt {
+            Some((generation, index_metadata)) => {
+                if !self
+                    .should_rollover(rollover_policy, &index_metadata, ctx)
+                    .await?
+                {
+                    return Ok(());
+                }
+                generation + 1
+            }
+            None => 1,
+        };
+        let index_id = rollover_policy.generation_index_id(next_generation);
+        let index_config =
+            index_template.apply_template(index_id.clone(), &self.default_index_root_uri)?;
+        // Like for the indexes auto-created from templates, ingest V1 is disabled.
+        let source_configs = [SourceConfig::ingest_v2(), SourceConfig::cli()];
+        let create_index_request =
+            CreateIndexRequest::try_from_index_and_source_configs(&index_config, &source_configs)?;
+        ctx.protect_future(self.metastore.create_index(create_index_request))
+            .await?;
+
+        info!(
+            write_alias=%rollover_policy.write_alias,
+            index_id=%index_id,
+            "rolled over write alias"
+        );
+        self.counters.num_rollovers += 1;
+        Ok(())
+    }
+
+    async fn should_rollover(
+        &self,
+        rollover_policy: &RolloverPolicy,
+        index_metadata: &IndexMetadata,
+        ctx: &ActorContext<Self>,
+    ) -> anyhow::Result<bool> {
+        let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+
+        if let Some(max_age) = rollover_policy.max_age()? {
+            let age_secs = now_timestamp - index_metadata.create_timestamp;
+
+            if age_secs >= max_age.as_secs() as i64 {
+                return Ok(true);
+            }
+        }
+        if rollover_policy.max_size.is_none() && rollover_policy.max_num_docs.is_none() {
+            return Ok(false);
+        }
+        let query = ListSplitsQuery::for_index(index_metadata.index_uid.clone())
+            .with_split_state(SplitState::Published);
+        let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+        let splits_metadata = ctx
+            .protect_future(self.metastore.list_splits(list_splits_request))
+            .await?
+            .collect_splits_metadata()
+            .await?;
+
+        let num_docs: u64 = splits_metadata
+            .iter()
+            .map(|split_metadata| split_metadata.num_docs as u64)
+            .sum();
+        // The size of a generation is the size of its splits on storage.
+        let num_bytes: u64 = splits_metadata
+            .iter()
+            .map(|split_metadata| split_metadata.footer_offsets.end)
+            .sum();
+
+        let exceeds_max_num_docs = rollover_policy
+            .max_num_docs
+            .map_or(false, |max_num_docs| num_docs >= max_num_docs);
+        let exceeds_max_size = rollover_policy
+            .max_size
+            .map_or(false, |max_size| num_bytes >= max_size.as_u64());
+        Ok(exceeds_max_num_docs || exceeds_max_size)
+    }
+}
+
+#[async_trait]
+impl Actor for RolloverExecutor {
+    type ObservableState = RolloverExecutorCounters;
+
+    fn observable_state(&self) -> Self::ObservableState {
+        self.counters.clone()
+    }
+
+    fn name(&self) -> String {
+        "RolloverExecutor".to_string()
+    }
+
+    async fn initialize(
+        &mut self,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), quickwit_actors::ActorExitStatus> {
+        self.handle(Loop, ctx).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler<Loop> for RolloverExecutor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        _: Loop,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), quickwit_actors::ActorExitStatus> {
+        self.evaluate_rollover_policies(ctx).await;
+        ctx.schedule_self_msg(RUN_INTERVAL, Loop);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytesize::ByteSize;
+    use quickwit_actors::Universe;
+    use quickwit_common::ServiceStream;
+    use quickwit_metastore::{ListSplitsResponseExt, Split, SplitMetadata};
+    use quickwit_proto::metastore::{
+        CreateIndexResponse, ListIndexTemplatesResponse, ListIndexesMetadataResponse,
+        ListSplitsResponse, MockMetastoreService,
+    };
+    use quickwit_proto::types::IndexUid;
+
+    use super::*;
+
+    fn index_template_with_rollover_policy() -> IndexTemplate {
+        let mut index_template = IndexTemplate::for_test("test-template", &["logs-*"], 0);
+        index_template.rollover_policy_opt = Some(RolloverPolicy {
+            write_alias: "logs".to_string(),
+            max_age: Some("7 days".to_string()),
+            max_size: Some(ByteSize::gb(1)),
+            max_num_docs: Some(1_000),
+        });
+        index_template
+    }
+
+    fn make_index_metadata(index_id: &str, create_timestamp: i64) -> IndexMetadata {
+        let mut index_metadata =
+            IndexMetadata::for_test(index_id, &format!("ram:///indexes/{index_id}"));
+        index_metadata.create_timestamp = create_timestamp;
+        index_metadata
+    }
+
+    fn make_split(split_id: &str, num_docs: usize) -> Split {
+        Split {
+            split_metadata: SplitMetadata {
+                split_id: split_id.to_string(),
+                num_docs,
+                ..Default::default()
+            },
+            split_state: SplitState::Published,
+            update_timestamp: 0,
+            publish_timestamp: Some(0),
+        }
+    }
+
+    fn mock_metastore(
+        indexes_metadata: Vec<IndexMetadata>,
+        splits: Vec<Split>,
+        expected_index_id_opt: Option<&'static str>,
+    ) -> MockMetastoreService {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_list_index_templates().returning(|_| {
+            let index_template_json =
+                serde_json::to_string(&index_template_with_rollover_policy()).unwrap();
+            Ok(ListIndexTemplatesResponse {
+                index_templates_json: vec![index_template_json],
+            })
+        });
+        mock_metastore.expect_list_indexes_metadata().returning(
+            move |list_indexes_metadata_request| {
+                assert_eq!(list_indexes_metadata_request.index_id_patterns, ["logs-*"]);
+                Ok(ListIndexesMetadataResponse::for_test(
+                    indexes_metadata.clone(),
+                ))
+            },
+        );
+        mock_metastore.expect_list_splits().returning(move |_| {
+            let response = ListSplitsResponse::try_from_splits(splits.clone()).unwrap();
+            Ok(ServiceStream::from(vec![Ok(response)]))
+        });
+        if let Some(expected_index_id) = expected_index_id_opt {
+            mock_metastore
+                .expect_create_index()
+                .once()
+                .returning(move |create_index_request| {
+                    let index_config = create_index_request.deserialize_index_config().unwrap();
+                    assert_eq!(index_config.index_id, expected_index_id);
+                    Ok(CreateIndexResponse {
+                        index_uid: Some(IndexUid::for_test(expected_index_id, 0)),
+                        index_metadata_json: String::new(),
+                    })
+                });
+        }
+        mock_metastore
+    }
+
+    async fn run_rollover_executor(mock_metastore: MockMetastoreService) -> usize {
+        let rollover_executor = RolloverExecutor::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            Uri::for_test("ram:///indexes"),
+        );
+        let universe = Universe::with_accelerated_time();
+        let (_mailbox, handle) = universe.spawn_builder().spawn(rollover_executor);
+        let counters = handle.process_pending_and_observe().await.state;
+        universe.assert_quit().await;
+        counters.num_rollovers
+    }
+
+    #[tokio::test]
+    async fn test_rollover_executor_creates_first_generation() {
+        let mock_metastore = mock_metastore(Vec::new(), Vec::new(), Some("logs-000001"));
+        assert_eq!(run_rollover_executor(mock_metastore).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rollover_executor_no_rollover() {
+        let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        let indexes_metadata = vec![
+            make_index_metadata("logs-000001", now_timestamp - 10 * 24 * 3600),
+            make_index_metadata("logs-000002", now_timestamp),
+            make_index_metadata("logs-app-000003", now_timestamp),
+        ];
+        let splits = vec![make_split("split-1", 10)];
+        let mock_metastore = mock_metastore(indexes_metadata, splits, None);
+        assert_eq!(run_rollover_executor(mock_metastore).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_rollover_executor_rolls_over_on_max_num_docs() {
+        let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        let indexes_metadata = vec![make_index_metadata("logs-000001", now_timestamp)];
+        let splits = vec![make_split("split-1", 600), make_split("split-2", 400)];
+        let mock_metastore = mock_metastore(indexes_metadata, splits, Some("logs-000002"));
+        assert_eq!(run_rollover_executor(mock_metastore).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rollover_executor_rolls_over_on_max_size() {
+        let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        let indexes_metadata = vec![make_index_metadata("logs-000001", now_timestamp)];
+        let splits = ["split-1", "split-2"]
+            .into_iter()
+            .map(|split_id| {
+                let mut split = make_split(split_id, 10);
+                split.split_metadata.footer_offsets = 0..ByteSize::mb(500).as_u64();
+                split
+            })
+            .collect();
+        let mock_metastore = mock_metastore(indexes_metadata, splits, Some("logs-000002"));
+        assert_eq!(run_rollover_executor(mock_metastore).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rollover_executor_does_not_roll_over_on_uncompressed_size() {
+        let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        let indexes_metadata = vec![make_index_metadata("logs-000001", now_timestamp)];
+        let mut split = make_split("split-1", 10);
+        split.split_metadata.uncompressed_docs_size_in_bytes = ByteSize::gb(2).as_u64();
+        split.split_metadata.footer_offsets = 0..ByteSize::mb(200).as_u64();
+        let mock_metastore = mock_metastore(indexes_metadata, vec![split], None);
+        assert_eq!(run_rollover_executor(mock_metastore).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_rollover_executor_rolls_over_on_max_age() {
+        let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        let indexes_metadata = vec![make_index_metadata(
+            "logs-000041",
+            now_timestamp - 8 * 24 * 3600,
+        )];
+        let mock_metastore = mock_metastore(indexes_metadata, Vec::new(), Some("logs-000042"));
+        assert_eq!(run_rollover_executor(mock_metastore).await, 1);
+    }
+}
@@ -0,0 +1,684 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use quickwit_actors::{Actor, ActorContext, ActorExitStatus, Handler};
+use quickwit_common::split_file;
+use quickwit_common::uri::Uri;
+use quickwit_config::{IndexConfig, ReplicationConfig};
+use quickwit_metastore::{
+    CreateIndexRequestExt, CreateIndexResponseExt, IndexMetadata, ListIndexesMetadataResponseExt,
+    ListSplitsQuery, ListSplitsRequestExt, MetastoreServiceStreamSplitsExt, Split, SplitMetadata,
+    SplitState, StageSplitsRequestExt, UpdateIndexRequestExt,
+};
+use quickwit_proto::metastore::{
+    CreateIndexRequest, ListIndexesMetadataRequest, ListSplitsRequest,
+    MarkSplitsForDeletionRequest, MetastoreService, MetastoreServiceClient, PublishSplitsRequest,
+    StageSplitsRequest, UpdateIndexRequest,
+};
+use quickwit_proto::types::IndexUid;
+use quickwit_storage::{FilePayload, Storage, StorageResolver};
+use serde::Serialize;
+use time::OffsetDateTime;
+use tracing::{debug, error, info};
+
+use crate::metrics::JANITOR_METRICS;
+
+/// Name of the directory, relative to the data directory, in which the split files are
+/// downloaded before being uploaded to the local storage.
+pub const SPLIT_REPLICATOR_DIR_NAME: &str = "replication";
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SplitReplicatorCounters {
+    /// The number of replication passes.
+    pub num_replication_passes: usize,
+
+    /// The number of splits copied from the primary cluster.
+    pub num_replicated_splits: usize,
+
+    /// The number of bytes copied from the primary cluster.
+    pub num_replicated_bytes: u64,
+
+    /// The number of index replications that failed.
+    pub num_failed_replications: usize,
+}
+
+#[derive(Debug)]
+struct Loop;
+
+/// An actor that periodically replicates the published splits of the indexes of a primary
+/// cluster into this cluster.
+///
+/// For each primary index matching the replication index ID patterns, the replicator creates or
+/// updates the local index so that its config mirrors the config of the primary index, copies the
+/// split files of the newly published primary splits to the local index storage, then publishes
+/// the copied splits while replacing the local splits that are no longer published on the primary
+/// cluster (merged or deleted splits). The local index has no sources, so the replicated splits
+/// are never modified locally.
+pub struct SplitReplicator {
+    source_metastore: MetastoreServiceClient,
+    metastore: MetastoreServiceClient,
+    storage_resolver: StorageResolver,
+    default_index_root_uri: Uri,
+    index_id_patterns: Vec<String>,
+    polling_interval: Duration,
+    scratch_directory_path: PathBuf,
+    counters: SplitReplicatorCounters,
+}
+
+impl SplitReplicator {
+    pub fn new(
+        replication_config: &ReplicationConfig,
+        source_metastore: MetastoreServiceClient,
+        metastore: MetastoreServiceClient,
+        storage_resolver: StorageResolver,
+        default_index_root_uri: Uri,
+        data_dir_path: PathBuf,
+    ) -> Self {
+        Self {
+            source_metastore,
+            metastore,
+            storage_resolver,
+            default_index_root_uri,
+            index_id_patterns: replication_config.index_id_patterns.clone(),
+            polling_interval: replication_config.polling_interval(),
+            scratch_directory_path: data_dir_path.join(SPLIT_REPLICATOR_DIR_NAME),
+            counters: SplitReplicatorCounters::default(),
+        }
+    }
+
+    /// Replicates all the primary indexes matching the index ID patterns.
+    /// Should not return an error to prevent the actor from crashing.
+    async fn replicate_indexes(&mut self, ctx: &ActorContext<Self>) {
+        debug!("replicating indexes from primary cluster");
+        self.counters.num_replication_passes += 1;
+
+        let source_indexes_metadata =
+            match list_indexes_metadata(&self.source_metastore, &self.index_id_patterns, ctx).await
+            {
+                Ok(source_indexes_metadata) => source_indexes_metadata,
+                Err(error) => {
+                    error!(%error, "failed to list indexes from the primary metastore");
+                    return;
+                }
+            };
+        let mut local_indexes_metadata: HashMap<String, IndexMetadata> =
+            match list_indexes_metadata(&self.metastore, &self.index_id_patterns, ctx).await {
+                Ok(local_indexes_metadata) => local_indexes_metadata
+                    .into_iter()
+                    .map(|index_metadata| (index_metadata.index_id().to_string(), index_metadata))
+                    .collect(),
+                Err(error) => {
+                    error!(%error, "failed to list indexes from the metastore");
+                    return;
+                }
+            };
+        for source_index_metadata in source_indexes_metadata {
+            let index_id = source_index_metadata.index_id().to_string();
+            let local_index_metadata_opt = local_indexes_metadata.remove(&index_id);
+
+            if let Err(error) = self
+                .replicate_index(source_index_metadata, local_index_metadata_opt, ctx)
+                .await
+            {
+                error!(index_id=%index_id, %error, "failed to replicate index");
+                self.counters.num_failed_replications += 1;
+            }
+        }
+    }
+
+    async fn replicate_index(
+        &mut self,
+        source_index_metadata: IndexMetadata,
+        local_index_metadata_opt: Option<IndexMetadata>,
+        ctx: &ActorContext<Self>,
+    ) -> anyhow::Result<()> {
+        let index_id = source_index_metadata.index_id().to_string();
+
+        let local_index_metadata = match local_index_metadata_opt {
+            Some(local_index_metadata) => {
+                self.update_index_config_if_changed(
+                    &source_index_metadata,
+                    &local_index_metadata,
+                    ctx,
+                )
+                .await?;
+                local_index_metadata
+            }
+            None => self.create_index(&source_index_metadata, ctx).await?,
+        };
+        let local_index_uid = local_index_metadata.index_uid.clone();
+
+        let source_splits = list_published_splits(
+            &self.source_metastore,
+            source_index_metadata.index_uid.clone(),
+            ctx,
+        )
+        .await?;
+        let local_splits =
+            list_published_splits(&self.metastore, local_index_uid.clone(), ctx).await?;
+
+        let source_split_ids: HashSet<&str> =
+            source_splits.iter().map(|split| split.split_id()).collect();
+        let local_split_ids: HashSet<&str> =
+            local_splits.iter().map(|split| split.split_id()).collect();
+
+        let splits_to_replicate: Vec<&Split> = source_splits
+            .iter()
+            .filter(|split| !local_split_ids.contains(split.split_id()))
+            .collect();
+        let split_ids_to_replace: Vec<String> = local_split_ids
+            .iter()
+            .filter(|split_id| !source_split_ids.contains(*split_id))
+            .map(|split_id| split_id.to_string())
+            .collect();
+
+        let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        let lag_secs = splits_to_replicate
+            .iter()
+            .filter_map(|split| split.publish_timestamp)
+            .min()
+            .map_or(0, |publish_timestamp| {
+                (now_timestamp - publish_timestamp).max(0)
+            });
+        let replication_lag_gauge = JANITOR_METRICS
+            .replication_lag_seconds
+            .with_label_values([index_id.as_str()]);
+        replication_lag_gauge.set(lag_secs);
+
+        if splits_to_replicate.is_empty() && split_ids_to_replace.is_empty() {
+            return Ok(());
+        }
+        if splits_to_replicate.is_empty() {
+            // The splits were deleted from the primary cluster, e.g. by the retention policy.
+            let mark_splits_for_deletion_request =
+                MarkSplitsForDeletionRequest::new(local_index_uid, split_ids_to_replace.clone());
+            ctx.protect_future(
+                self.metastore
+                    .mark_splits_for_deletion(mark_splits_for_deletion_request),
+            )
+            .await?;
+        } else {
+            let splits_metadata: Vec<SplitMetadata> = splits_to_replicate
+                .iter()
+                .map(|split| SplitMetadata {
+                    index_uid: local_index_uid.clone(),
+                    ..split.split_metadata.clone()
+                })
+                .collect();
+            let split_ids: Vec<String> = splits_metadata
+                .iter()
+                .map(|split_metadata| split_metadata.split_id.clone())
+                .collect();
+            // Staging the splits before copying their files lets the garbage collector clean up
+            // the files of the splits whose replication fails midway.
+            let stage_splits_request = StageSplitsRequest::try_from_splits_metadata(
+                local_index_uid.clone(),
+                splits_metadata,
+            )?;
+            ctx.protect_future(self.metastore.stage_splits(stage_splits_request))
+                .await?;
+
+            let source_storage = self
+                .storage_resolver
+                .resolve_with_credentials(
+                    source_index_metadata.index_uri(),
+                    source_index_metadata
+                        .index_config
+                        .storage_credentials_opt
+                        .as_deref(),
+                )
+                .await?;
+            let local_storage = self
+                .storage_resolver
+                .resolve_with_credentials(
+                    local_index_metadata.index_uri(),
+                    local_index_metadata
+                        .index_config
+                        .storage_credentials_opt
+                        .as_deref(),
+                )
+                .await?;
+
+            for split_id in &split_ids {
+                let num_bytes = self
+                    .copy_split_file(split_id, &*source_storage, &*local_storage, ctx)
+                    .await
+                    .with_context(|| format!("failed to copy file of split `{split_id}`"))?;
+                self.counters.num_replicated_splits += 1;
+                self.counters.num_replicated_bytes += num_bytes;
+                JANITOR_METRICS
+                    .replicated_splits
+                    .with_label_values([index_id.as_str()])
+                    .inc();
+                JANITOR_METRICS.replicated_bytes.inc_by(num_bytes);
+            }
+            // The copied splits are published and the obsolete splits are replaced in a single
+            // transaction so that searches never see the same documents twice.
+            let publish_splits_request = PublishSplitsRequest {
+                index_uid: Some(local_index_uid),
+                staged_split_ids: split_ids,
+                replaced_split_ids: split_ids_to_replace.clone(),
+                index_checkpoint_delta_json_opt: None,
+                publish_token_opt: None,
+            };
+            ctx.protect_future(self.metastore.publish_splits(publish_splits_request))
+                .await?;
+        }
+        info!(
+            index_id=%index_id,
+            num_replicated_splits=splits_to_replicate.len(),
+            num_replaced_splits=split_ids_to_replace.len(),
+            "replicated index"
+        );
+        replication_lag_gauge.set(0);
+        Ok(())
+    }
+
+    /// Creates the local replica of a primary index. The replica has no sources.
+    ///
+    /// The settings tied to the source cluster are not replicated: the replica is stored with
+    /// the default storage credentials of the local cluster, is not a rollout candidate of a local
+    /// index, and does not ingest documents, so it cannot be frozen.
+    async fn create_index(
+        &self,
+        source_index_metadata: &IndexMetadata,
+        ctx: &ActorContext<Self>,
+    ) -> anyhow::Result<IndexMetadata> {
+        let index_id = source_index_metadata.index_id();
+        let index_uri = self.default_index_root_uri.join(index_id)?;
+        let source_index_config = &source_index_metadata.index_config;
+        let index_config = IndexConfig {
+            index_id: source_index_config.index_id.clone(),
+            index_uri,
+            doc_mapping: source_index_config.doc_mapping.clone(),
+            indexing_settings: source_index_config.indexing_settings.clone(),
+            search_settings: source_index_config.search_settings.clone(),
+            retention_policy_opt: source_index_config.retention_policy_opt.clone(),
+            rollout_opt: None,
+            ingestion_freeze_opt: None,
+            storage_credentials_opt: None,
+            labels: source_index_config.labels.clone(),
+        };
+        let create_index_request = CreateIndexRequest::try_from_index_config(&index_config)?;
+        let create_index_response = ctx
+            .protect_future(self.metastore.create_index(create_index_request))
+            .await?;
+        let index_metadata = create_index_response.deserialize_index_metadata()?;
+
+        info!(index_id=%index_id, "created replica index");
+        Ok(index_metadata)
+    }
+
+    async fn update_index_config_if_changed(
+        &self,
+        source_index_metadata: &IndexMetadata,
+        local_index_metadata: &IndexMetadata,
+        ctx: &ActorContext<Self>,
+    ) -> anyhow::Result<()> {
+        let source_index_config = &source_index_metadata.index_config;
+        let local_index_config = &local_index_metadata.index_config;
+
+        if source_index_config.doc_mapping == local_index_config.doc_mapping
+            && source_index_config.indexing_settings == local_index_config.indexing_settings
+            && source_index_config.search_settings == local_index_config.search_settings
+            && source_index_config.retention_policy_opt == local_index_config.retention_policy_opt
+        {
+            return Ok(());
+        }
+        let update_index_request = UpdateIndexRequest::try_from_updates(
+            local_index_metadata.index_uid.clone(),
+            &source_index_config.search_settings,
+            &source_index_config.retention_policy_opt,
+            &source_index_config.indexing_settings,
+            &source_index_config.doc_mapping,
+        )?;
+        ctx.protect_future(self.metastore.update_index(update_index_request))
+            .await?;
+
+        info!(index_id=%source_index_metadata.index_id(), "updated replica index config");
+        Ok(())
+    }
+
+    /// Copies a split file from the primary storage to the local storage through the scratch
+    /// directory, and returns the size of the file.
+    async fn copy_split_file(
+        &self,
+        split_id: &str,
+        source_storage: &dyn Storage,
+        local_storage: &dyn Storage,
+        ctx: &ActorContext<Self>,
+    ) -> anyhow::Result<u64> {
+        let split_file = split_file(split_id);
+        let split_path = PathBuf::from(&split_file);
+        let scratch_file_path = self.scratch_directory_path.join(&split_file);
+
+        let copy_result = async {
+            let num_bytes = ctx
+                .protect_future(source_storage.copy_to_file(&split_path, &scratch_file_path))
+                .await?;
+            let file_payload = FilePayload::open(&scratch_file_path)?;
+            ctx.protect_future(local_storage.put(&split_path, Box::new(file_payload)))
+                .await?;
+            anyhow::Ok(num_bytes)
+        }
+        .await;
+
+        if let Err(error) = tokio::fs::remove_file(&scratch_file_path).await {
+            debug!(split_id=%split_id, %error, "failed to remove scratch split file");
+        }
+        copy_result
+    }
+}
+
+async fn list_indexes_metadata(
+    metastore: &MetastoreServiceClient,
+    index_id_patterns: &[String],
+    ctx: &ActorContext<SplitReplicator>,
+) -> anyhow::Result<Vec<IndexMetadata>> {
+    let list_indexes_metadata_request = ListIndexesMetadataRequest {
+        index_id_patterns: index_id_patterns.to_vec(),
+        include_trashed: false,
+        label_selectors: Vec::new(),
+    };
+    let indexes_metadata = ctx
+        .protect_future(metastore.list_indexes_metadata(list_indexes_metadata_request))
+        .await?
+        .deserialize_indexes_metadata()
+        .await?;
+    Ok(indexes_metadata)
+}
+
+async fn list_published_splits(
+    metastore: &MetastoreServiceClient,
+    index_uid: IndexUid,
+    ctx: &ActorContext<SplitReplicator>,
+) -> anyhow::Result<Vec<Split>> {
+    let query = ListSplitsQuery::for_index(index_uid).with_split_state(SplitState::Published);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+    let splits = ctx
+        .protect_future(metastore.list_splits(list_splits_request))
+        .await?
+        .collect_splits()
+        .await?;
+    Ok(splits)
+}
+
+#[async_trait]
+impl Actor for SplitReplicator {
+    type ObservableState = SplitReplicatorCounters;
+
+    fn observable_state(&self) -> Self::ObservableState {
+        self.counters.clone()
+    }
+
+    fn name(&self) -> String {
+        "SplitReplicator".to_string()
+    }
+
+    async fn initialize(&mut self, ctx: &ActorContext<Self>) -> Result<(), ActorExitStatus> {
+        tokio::fs::create_dir_all(&self.scratch_directory_path)
+            .await
+            .map_err(anyhow::Error::from)?;
+        self.handle(Loop, ctx).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler<Loop> for SplitReplicator {
+    type Reply = ();
+
+    async fn handle(&mut self, _: Loop, ctx: &ActorContext<Self>) -> Result<(), ActorExitStatus> {
+        self.replicate_indexes(ctx).await;
+        ctx.schedule_self_msg(self.polling_interval, Loop);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+    use std::path::Path;
+
+    use quickwit_actors::Universe;
+    use quickwit_common::ServiceStream;
+    use quickwit_config::IngestionFreeze;
+    use quickwit_metastore::ListSplitsResponseExt;
+    use quickwit_proto::metastore::{
+        CreateIndexResponse, EmptyResponse, ListIndexesMetadataResponse, ListSplitsResponse,
+        MockMetastoreService,
+    };
+
+    use super::*;
+
+    fn replication_config() -> ReplicationConfig {
+        ReplicationConfig {
+            source_grpc_endpoint: "quickwit-us-east:7281".to_string(),
+            index_id_patterns: vec!["logs".to_string()],
+            polling_interval_secs: NonZeroU64::new(60).unwrap(),
+        }
+    }
+
+    fn make_index_metadata(index_uri: &str, incarnation_id: u128) -> IndexMetadata {
+        let mut index_metadata = IndexMetadata::for_test("logs", index_uri);
+        index_metadata.index_uid = IndexUid::for_test("logs", incarnation_id);
+        index_metadata
+    }
+
+    fn make_split(index_uid: &IndexUid, split_id: &str) -> Split {
+        Split {
+            split_metadata: SplitMetadata {
+                split_id: split_id.to_string(),
+                index_uid: index_uid.clone(),
+                ..Default::default()
+            },
+            split_state: SplitState::Published,
+            update_timestamp: 0,
+            publish_timestamp: Some(0),
+        }
+    }
+
+    fn mock_metastore(
+        indexes_metadata: Vec<IndexMetadata>,
+        splits: Vec<Split>,
+    ) -> MockMetastoreService {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_list_indexes_metadata().returning(
+            move |list_indexes_metadata_request| {
+                assert_eq!(list_indexes_metadata_request.index_id_patterns, ["logs"]);
+                Ok(ListIndexesMetadataResponse::for_test(
+                    indexes_metadata.clone(),
+                ))
+            },
+        );
+        mock_metastore.expect_list_splits().returning(move |_| {
+            let response = ListSplitsResponse::try_from_splits(splits.clone()).unwrap();
+            Ok(ServiceStream::from(vec![Ok(response)]))
+        });
+        mock_metastore
+    }
+
+    async fn run_split_replicator(
+        mock_source_metastore: MockMetastoreService,
+        mock_metastore: MockMetastoreService,
+        storage_resolver: StorageResolver,
+    ) -> SplitReplicatorCounters {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let split_replicator = SplitReplicator::new(
+            &replication_config(),
+            MetastoreServiceClient::from_mock(mock_source_metastore),
+            MetastoreServiceClient::from_mock(mock_metastore),
+            storage_resolver,
+            Uri::for_test("ram:///replica"),
+            temp_dir.path().to_path_buf(),
+        );
+        let universe = Universe::with_accelerated_time();
+        let (_mailbox, handle) = universe.spawn_builder().spawn(split_replicator);
+        let counters = handle.process_pending_and_observe().await.state;
+        universe.assert_quit().await;
+        counters
+    }
+
+    #[tokio::test]
+    async fn test_split_replicator_creates_replica_index() {
+        let mut source_index_metadata = make_index_metadata("ram:///primary/logs", 0);
+        let source_index_config = &mut source_index_metadata.index_config;
+        source_index_config.ingestion_freeze_opt = Some(IngestionFreeze {
+            reason: "flood".to_string(),
+            frozen_at: 0,
+        });
+        source_index_config.storage_credentials_opt = Some("us-east".to_string());
+        source_index_config
+            .labels
+            .insert("team".to_string(), "observability".to_string());
+        let mock_source_metastore = mock_metastore(vec![source_index_metadata], Vec::new());
+
+        let mut mock_metastore = mock_metastore(Vec::new(), Vec::new());
+        mock_metastore
+            .expect_create_index()
+            .once()
+            .returning(|create_index_request| {
+                let index_config = create_index_request.deserialize_index_config().unwrap();
+                assert_eq!(index_config.index_id, "logs");
+                assert_eq!(index_config.index_uri, "ram:///replica/logs");
+                assert!(index_config.rollout_opt.is_none());
+                assert!(index_config.ingestion_freeze_opt.is_none());
+                assert!(index_config.storage_credentials_opt.is_none());
+                assert_eq!(index_config.labels["team"], "observability");
+                assert!(create_index_request.source_configs_json.is_empty());
+
+                let index_metadata = IndexMetadata {
+                    index_uid: IndexUid::for_test("logs", 1),
+                    ..IndexMetadata::new(index_config)
+                };
+                Ok(CreateIndexResponse {
+                    index_uid: Some(index_metadata.index_uid.clone()),
+                    index_metadata_json: serde_json::to_string(&index_metadata).unwrap(),
+                })
+            });
+        let counters = run_split_replicator(
+            mock_source_metastore,
+            mock_metastore,
+            StorageResolver::for_test(),
+        )
+        .await;
+        assert_eq!(counters.num_replicated_splits, 0);
+        assert_eq!(counters.num_failed_replications, 0);
+    }
+
+    #[tokio::test]
+    async fn test_split_replicator_replicates_splits() {
+        let storage_resolver = StorageResolver::for_test();
+        let source_storage = storage_resolver
+            .resolve(&Uri::for_test("ram:///primary/logs"))
+            .await
+            .unwrap();
+        source_storage
+            .put(Path::new("split-2.split"), Box::new(b"split-2".to_vec()))
+            .await
+            .unwrap();
+
+        let source_index_metadata = make_index_metadata("ram:///primary/logs", 0);
+        let source_index_uid = source_index_metadata.index_uid.clone();
+        let source_splits = vec![
+            make_split(&source_index_uid, "split-1"),
+            make_split(&source_index_uid, "split-2"),
+        ];
+        let mock_source_metastore = mock_metastore(vec![source_index_metadata], source_splits);
+
+        let local_index_metadata = make_index_metadata("ram:///replica/logs", 1);
+        let local_index_uid = local_index_metadata.index_uid.clone();
+        let local_splits = vec![
+            make_split(&local_index_uid, "split-0"),
+            make_split(&local_index_uid, "split-1"),
+        ];
+        let mut mock_metastore = mock_metastore(vec![local_index_metadata], local_splits);
+        let expected_index_uid = local_index_uid.clone();
+        mock_metastore
+            .expect_stage_splits()
+            .once()
+            .returning(move |stage_splits_request| {
+                assert_eq!(stage_splits_request.index_uid(), &expected_index_uid);
+                let splits_metadata = stage_splits_request.deserialize_splits_metadata().unwrap();
+                assert_eq!(splits_metadata.len(), 1);
+                assert_eq!(splits_metadata[0].split_id, "split-2");
+                assert_eq!(splits_metadata[0].index_uid, expected_index_uid);
+                Ok(EmptyResponse {})
+            });
+        mock_metastore
+            .expect_publish_splits()
+            .once()
+            .returning(move |publish_splits_request| {
+                assert_eq!(publish_splits_request.index_uid(), &local_index_uid);
+                assert_eq!(publish_splits_request.staged_split_ids, ["split-2"]);
+                assert_eq!(publish_splits_request.replaced_split_ids, ["split-0"]);
+                Ok(EmptyResponse {})
+            });
+        let counters = run_split_replicator(
+            mock_source_metastore,
+            mock_metastore,
+            storage_resolver.clone(),
+        )
+        .await;
+        assert_eq!(counters.num_replicated_splits, 1);
+        assert_eq!(counters.num_replicated_bytes, 7);
+        assert_eq!(counters.num_failed_replications, 0);
+
+        let local_storage = storage_resolver
+            .resolve(&Uri::for_test("ram:///replica/logs"))
+            .await
+            .unwrap();
+        let split_bytes = local_storage
+            .get_all(Path::new("split-2.split"))
+            .await
+            .unwrap();
+        assert_eq!(split_bytes.as_slice(), b"split-2");
+    }
+
+    #[tokio::test]
+    async fn test_split_replicator_deletes_obsolete_splits() {
+        let source_index_metadata = make_index_metadata("ram:///primary/logs", 0);
+        let mock_source_metastore = mock_metastore(vec![source_index_metadata], Vec::new());
+
+        let local_index_metadata = make_index_metadata("ram:///replica/logs", 1);
+        let local_index_uid = local_index_metadata.index_uid.clone();
+        let local_splits = vec![make_split(&local_index_uid, "split-0")];
+        let mut mock_metastore = mock_metastore(vec![local_index_metadata], local_splits);
+        mock_metastore
+            .expect_mark_splits_for_deletion()
+            .once()
+            .returning(move |mark_splits_for_deletion_request| {
+                assert_eq!(
+                    mark_splits_for_deletion_request.index_uid(),
+                    &local_index_uid
+                );
+                assert_eq!(mark_splits_for_deletion_request.split_ids, ["split-0"]);
+                Ok(EmptyResponse {})
+            });
+        let counters = run_split_replicator(
+            mock_source_metastore,
+            mock_metastore,
+            StorageResolver::for_test(),
+        )
+        .await;
+        assert_eq!(counters.num_replicated_splits, 0);
+        assert_eq!(counters.num_failed_replications, 0);
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use quickwit_actors::{Actor, ActorContext, Handler};
+use quickwit_index_management::IndexService;
+use quickwit_metastore::{IndexMetadata, ListIndexesMetadataResponseExt};
+use quickwit_proto::metastore::{
+    ListIndexesMetadataRequest, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_storage::StorageResolver;
+use serde::Serialize;
+use time::OffsetDateTime;
+use tracing::{debug, error, info};
+
+const RUN_INTERVAL: Duration = Duration::from_secs(10 * 60); // 10 minutes
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TrashPurgerCounters {
+    /// The number of evaluation passes.
+    pub num_evaluation_passes: usize,
+
+    /// The number of indexes purged from the trash.
+    pub num_purged_indexes: usize,
+}
+
+#[derive(Debug)]
+struct Loop;
+
+/// An actor that periodically purges the indexes that have been sitting in the trash for longer
+/// than the grace period: their splits are removed from storage and the indexes are deleted from
+/// the metastore for good.
+pub struct TrashPurger {
+    metastore: MetastoreServiceClient,
+    storage_resolver: StorageResolver,
+    grace_period: Duration,
+    counters: TrashPurgerCounters,
+}
+
+impl TrashPurger {
+    pub fn new(
+        metastore: MetastoreServiceClient,
+        storage_resolver: StorageResolver,
+        grace_period: Duration,
+    ) -> Self {
+        Self {
+            metastore,
+            storage_resolver,
+            grace_period,
+            counters: TrashPurgerCounters::default(),
+        }
+    }
+
+    /// Purges the indexes whose grace period has elapsed.
+    /// Should not return an error to prevent the actor from crashing.
+    async fn purge_expired_indexes(&mut self, ctx: &ActorContext<Self>) {
+        debug!("purging expired indexes from the trash");
+        self.counters.num_evaluation_passes += 1;
+
+        let expired_indexes_metadata = match self.list_expired_indexes(ctx).await {
+            Ok(expired_indexes_metadata) => expired_indexes_metadata,
+            Err(error) => {
+                error!(%error, "failed to list trashed indexes from the metastore");
+                return;
+            }
+        };
+        let index_service =
+            IndexService::new(self.metastore.clone(), self.storage_resolver.clone());
+
+        for index_metadata in expired_indexes_metadata {
+            let index_uid = index_metadata.index_uid.clone();
+
+            match ctx
+                .protect_future(index_service.purge_index(index_metadata))
+                .await
+            {
+                Ok(deleted_splits) => {
+                    info!(
+                        index_uid=%index_uid,
+                        num_deleted_splits=deleted_splits.len(),
+                        "purged index from the trash"
+                    );
+                    self.counters.num_purged_indexes += 1;
+                }
+                Err(error) => {
+                    error!(index_uid=%index_uid, %error, "failed to purge index from the trash");
+                }
+            }
+        }
+    }
+
+    async fn list_expired_indexes(
+        &self,
+        ctx: &ActorContext<Self>,
+    ) -> anyhow::Result<Vec<IndexMetadata>> {
+        let list_indexes_metadata_request = ListIndexesMetadataRequest {
+            index_id_patterns: vec!["*".to_string()],
+            include_trashed: true,
+            label_selectors: Vec::new(),
+        };
+        let indexes_metadata = ctx
+            .protect_future(
+                self.metastore
+                    .list_indexes_metadata(list_indexes_metadata_request),
+            )
+            .await?
+            .deserialize_indexes_metadata()
+            .await?;
+        let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        let grace_period_secs = self.grace_period.as_secs() as i64;
+
+        let expired_indexes_metadata = indexes_metadata
+            .into_iter()
+            .filter(|index_metadata| {
+                index_metadata
+                    .trash_timestamp_opt
+                    .map_or(false, |trash_timestamp| {
+                        trash_timestamp + grace_period_secs <= now_timestamp
+                    })
+            })
+            .collect();
+        Ok(expired_indexes_metadata)
+    }
+}
+
+#[async_trait]
+impl Actor for TrashPurger {
+    type ObservableState = TrashPurgerCounters;
+
+    fn observable_state(&self) -> Self::ObservableState {
+        self.counters.clone()
+    }
+
+    fn name(&self) -> String {
+        "TrashPurger".to_string()
+    }
+
+    async fn initialize(
+        &mut self,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), quickwit_actors::ActorExitStatus> {
+        self.handle(Loop, ctx).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler<Loop> for TrashPurger {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        _: Loop,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), quickwit_actors::ActorExitStatus> {
+        self.purge_expired_indexes(ctx).await;
+        ctx.schedule_self_msg(RUN_INTERVAL, Loop);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_actors::Universe;
+    use quickwit_config::IndexConfig;
+    use quickwit_metastore::{metastore_for_test, CreateIndexRequestExt, MetastoreServiceExt};
+    use quickwit_proto::metastore::{CreateIndexRequest, TrashIndexRequest};
+
+    use super::*;
+
+    async fn run_trash_purger(grace_period: Duration) -> (MetastoreServiceClient, usize) {
+        let metastore = metastore_for_test();
+
+        for index_id in ["test-index-1", "test-index-2"] {
+            let index_uri = format!("ram:///indexes/{index_id}");
+            let index_config = IndexConfig::for_test(index_id, &index_uri);
+            let create_index_request =
+                CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+            let index_uid = metastore
+                .create_index(create_index_request)
+                .await
+                .unwrap()
+                .index_uid()
+                .clone();
+
+            if index_id == "test-index-1" {
+                let trash_index_request = TrashIndexRequest {
+                    index_uid: Some(index_uid),
+                };
+                metastore.trash_index(trash_index_request).await.unwrap();
+            }
+        }
+        let trash_purger =
+            TrashPurger::new(metastore.clone(), StorageResolver::for_test(), grace_period);
+        let universe = Universe::with_accelerated_time();
+        let (_mailbox, handle) = universe.spawn_builder().spawn(trash_purger);
+        let counters = handle.process_pending_and_observe().await.state;
+        universe.assert_quit().await;
+        assert_eq!(counters.num_evaluation_passes, 1);
+        (metastore, counters.num_purged_indexes)
+    }
+
+    #[tokio::test]
+    async fn test_trash_purger_purges_expired_indexes() {
+        let (mut metastore, num_purged_indexes) = run_trash_purger(Duration::ZERO).await;
+        assert_eq!(num_purged_indexes, 1);
+
+        let list_indexes_metadata_request = ListIndexesMetadataRequest {
+            index_id_patterns: vec!["*".to_string()],
+            include_trashed: true,
+            label_selectors: Vec::new(),
+        };
+        let indexes_metadata = metastore
+            .list_indexes_metadata(list_indexes_metadata_request)
+            .await
+            .unwrap()
+            .deserialize_indexes_metadata()
+            .await
+            .unwrap();
+        assert_eq!(indexes_metadata.len(), 1);
+        assert_eq!(indexes_metadata[0].index_id(), "test-index-2");
+        assert!(metastore.index_exists("test-index-2").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_trash_purger_keeps_indexes_within_grace_period() {
+        let (metastore, num_purged_indexes) =
+            run_trash_purger(Duration::from_secs(7 * 24 * 3600)).await;
+        assert_eq!(num_purged_indexes, 0);
+
+        let list_indexes_metadata_request = ListIndexesMetadataRequest {
+            index_id_patterns: vec!["*".to_string()],
+            include_trashed: true,
+            label_selectors: Vec::new(),
+        };
+        let indexes_metadata = metastore
+            .list_indexes_metadata(list_indexes_metadata_request)
+            .await
+            .unwrap()
+            .deserialize_indexes_metadata()
+            .await
+            .unwrap();
+        assert_eq!(indexes_metadata.len(), 2);
+    }
+}
@@ -18,12 +18,19 @@ use quickwit_actors::{
 };
 use serde_json::{json, Value as JsonValue};
 
-use crate::actors::{DeleteTaskService, GarbageCollector, RetentionPolicyExecutor};
+use crate::actors::{
+    DeleteTaskService, GarbageCollector, OrphanShardsCleaner, RetentionPolicyExecutor,
+    RolloverExecutor, SplitReplicator, TrashPurger,
+};
 
 pub struct JanitorService {
     delete_task_service_handle: Option<ActorHandle<DeleteTaskService>>,
     garbage_collector_handle: ActorHandle<GarbageCollector>,
     retention_policy_executor_handle: ActorHandle<RetentionPolicyExecutor>,
+    rollover_executor_handle: ActorHandle<RolloverExecutor>,
+    split_replicator_handle_opt: Option<ActorHandle<SplitReplicator>>,
+    trash_purger_handle_opt: Option<ActorHandle<TrashPurger>>,
+    orphan_shards_cleaner_handle_opt: Option<ActorHandle<OrphanShardsCleaner>>,
 }
 
 impl JanitorService {
@@ -31,11 +38,19 @@ impl JanitorService {
         delete_task_service_handle: Option<ActorHandle<DeleteTaskService>>,
         garbage_collector_handle: ActorHandle<GarbageCollector>,
         retention_policy_executor_handle: ActorHandle<RetentionPolicyExecutor>,
+        rollover_executor_handle: ActorHandle<RolloverExecutor>,
+        split_replicator_handle_opt: Option<ActorHandle<SplitReplicator>>,
+        trash_purger_handle_opt: Option<ActorHandle<TrashPurger>>,
+        orphan_shards_cleaner_handle_opt: Option<ActorHandle<OrphanShardsCleaner>>,
     ) -> Self {
         Self {
             delete_task_service_handle,
             garbage_collector_handle,
             retention_policy_executor_handle,
+            rollover_executor_handle,
+            split_replicator_handle_opt,
+            trash_purger_handle_opt,
+            orphan_shards_cleaner_handle_opt,
         }
     }
 
@@ -47,6 +62,25 @@ impl JanitorService {
             })
             && self.garbage_collector_handle.state() != ActorState::Failure
             && self.retention_policy_executor_handle.state() != ActorState::Failure
+            && self.rollover_executor_handle.state() != ActorState::Failure
+            && self
+                .split_replicator_handle_opt
+                .as_ref()
+                .map_or(true, |split_replicator_handle| {
+                    split_replicator_handle.state() != ActorState::Failure
+                })
+            && self
+                .trash_purger_handle_opt
+                .as_ref()
+                .map_or(true, |trash_purger_handle| {
+                    trash_purger_handle.state() != ActorState::Failure
+                })
+            && self.orphan_shards_cleaner_handle_opt.as_ref().map_or(
+                true,
+                |orphan_shards_cleaner_handle| {
+                    orphan_shards_cleaner_handle.state() != ActorState::Failure
+                },
+            )
     }
 }
 
@@ -14,12 +14,15 @@
 
 #![deny(clippy::disallowed_methods)]
 
+use anyhow::Context;
 use quickwit_actors::{Mailbox, Universe};
 use quickwit_common::pubsub::EventBroker;
-use quickwit_config::NodeConfig;
+use quickwit_config::{NodeConfig, ReplicationConfig};
 use quickwit_indexing::actors::MergeSchedulerService;
+use quickwit_ingest::IngesterPool;
 use quickwit_metastore::SplitInfo;
 use quickwit_proto::metastore::MetastoreServiceClient;
+use quickwit_proto::tonic::transport::Endpoint;
 use quickwit_search::SearchJobPlacer;
 use quickwit_storage::StorageResolver;
 use tracing::info;
@@ -32,7 +35,10 @@ mod retention_policy_execution;
 
 pub use janitor_service::JanitorService;
 
-use crate::actors::{DeleteTaskService, GarbageCollector, RetentionPolicyExecutor};
+use crate::actors::{
+    DeleteTaskService, GarbageCollector, OrphanShardsCleaner, RetentionPolicyExecutor,
+    RolloverExecutor, SplitReplicator, TrashPurger,
+};
 
 #[derive(utoipa::OpenApi)]
 #[openapi(components(schemas(SplitInfo)))]
@@ -45,6 +51,7 @@ pub async fn start_janitor_service(
     metastore: MetastoreServiceClient,
     search_job_placer: SearchJobPlacer,
     storage_resolver: StorageResolver,
+    ingester_pool: IngesterPool,
     event_broker: EventBroker,
     run_delete_task_service: bool,
 ) -> anyhow::Result<Mailbox<JanitorService>> {
@@ -55,6 +62,55 @@ pub async fn start_janitor_service(
     let retention_policy_executor = RetentionPolicyExecutor::new(metastore.clone());
     let (_, retention_policy_executor_handle) =
         universe.spawn_builder().spawn(retention_policy_executor);
+
+    let rollover_executor =
+        RolloverExecutor::new(metastore.clone(), config.default_index_root_uri.clone());
+    let (_, rollover_executor_handle) = universe.spawn_builder().spawn(rollover_executor);
+
+    let split_replicator_handle_opt =
+        if let Some(replication_config) = &config.replication_config_opt {
+            let source_metastore = connect_source_metastore(replication_config, config).await?;
+            let split_replicator = SplitReplicator::new(
+                replication_config,
+                source_metastore,
+                metastore.clone(),
+                storage_resolver.clone(),
+                config.default_index_root_uri.clone(),
+                config.data_dir_path.clone(),
+            );
+            let (_, split_replicator_handle) = universe.spawn_builder().spawn(split_replicator);
+            Some(split_replicator_handle)
+        } else {
+            None
+        };
+
+    let trash_purger_handle_opt = if let Some(trash_config) = &config.trash_config_opt {
+        let trash_purger = TrashPurger::new(
+            metastore.clone(),
+            storage_resolver.clone(),
+            trash_config.grace_period()?,
+        );
+        let (_, trash_purger_handle) = universe.spawn_builder().spawn(trash_purger);
+        Some(trash_purger_handle)
+    } else {
+        None
+    };
+
+    let orphan_shards_cleaner_handle_opt =
+        if let Some(orphan_shards_cleanup_config) = &config.orphan_shards_cleanup_config_opt {
+            let orphan_shards_cleaner = OrphanShardsCleaner::new(
+                metastore.clone(),
+                ingester_pool,
+                orphan_shards_cleanup_config.min_idle_period()?,
+                orphan_shards_cleanup_config.dry_run,
+            );
+            let (_, orphan_shards_cleaner_handle) =
+                universe.spawn_builder().spawn(orphan_shards_cleaner);
+            Some(orphan_shards_cleaner_handle)
+        } else {
+            None
+        };
+
     let delete_task_service_handle = if run_delete_task_service {
         let delete_task_service = DeleteTaskService::new(
             metastore,
@@ -77,8 +133,31 @@ pub async fn start_janitor_service(
         delete_task_service_handle,
         garbage_collector_handle,
         retention_policy_executor_handle,
+        rollover_executor_handle,
+        split_replicator_handle_opt,
+        trash_purger_handle_opt,
+        orphan_shards_cleaner_handle_opt,
     );
     let (janitor_service_mailbox, _janitor_service_handle) =
         universe.spawn_builder().spawn(janitor_service);
     Ok(janitor_service_mailbox)
 }
+
+/// Creates a metastore client for the primary cluster from which the splits are replicated.
+async fn connect_source_metastore(
+    replication_config: &ReplicationConfig,
+    config: &NodeConfig,
+) -> anyhow::Result<MetastoreServiceClient> {
+    let grpc_addr = replication_config
+        .source_grpc_endpoint()?
+        .resolve()
+        .await
+        .context("failed to resolve gRPC endpoint of primary cluster")?;
+    let channel = Endpoint::from_shared(format!("http://{grpc_addr}"))?.connect_lazy();
+    let metastore = MetastoreServiceClient::from_channel(
+        grpc_addr,
+        channel,
+        config.grpc_config.max_message_size,
+    );
+    Ok(metastore)
+}
@@ -23,6 +23,10 @@ pub struct JanitorMetrics {
     pub gc_deleted_bytes: IntCounter,
     pub gc_runs: IntCounterVec<1>,
     pub gc_seconds_total: IntCounter,
+    pub replication_lag_seconds: IntGaugeVec<1>,
+    pub replicated_splits: IntCounterVec<1>,
+    pub replicated_bytes: IntCounter,
+    pub orphan_shards: IntCounterVec<1>,
     // TODO having a current run duration which is 0|undefined out of run, and returns `now -
     // start_time` during a run would be nice
 }
@@ -63,6 +67,35 @@ impl Default for JanitorMetrics {
                 "quickwit_janitor",
                 &[],
             ),
+            replication_lag_seconds: new_gauge_vec(
+                "replication_lag_seconds",
+                "Age of the oldest split published on the primary cluster and not yet replicated \
+                 (per index).",
+                "quickwit_janitor",
+                &[],
+                ["index"],
+            ),
+            replicated_splits: new_counter_vec(
+                "replicated_splits_total",
+                "Total number of splits replicated from the primary cluster (per index).",
+                "quickwit_janitor",
+                &[],
+                ["index"],
+            ),
+            replicated_bytes: new_counter(
+                "replicated_bytes_total",
+                "Total number of bytes replicated from the primary cluster.",
+                "quickwit_janitor",
+                &[],
+            ),
+            orphan_shards: new_counter_vec(
+                "orphan_shards_total",
+                "Total number of orphan shards found on the ingesters, removed or only reported \
+                 in dry-run mode.",
+                "quickwit_janitor",
+                &[],
+                ["action"],
+            ),
         }
     }
 }
@@ -146,6 +146,8 @@ pub(super) async fn configure_source(
         source_params,
         transform_config,
         input_format,
+        activation_windows: Vec::new(),
+        backfill_time_range: None,
     })
 }
 
@@ -16,13 +16,14 @@ use std::collections::HashSet;
 use std::net::{Ipv4Addr, SocketAddr};
 use std::sync::Arc;
 
+use bytesize::ByteSize;
 use http::Method;
 use quickwit_config::service::QuickwitService;
 use quickwit_config::SearcherConfig;
 use quickwit_proto::metastore::MetastoreServiceClient;
 use quickwit_search::{
-    ClusterClient, SearchJobPlacer, SearchService, SearchServiceClient, SearchServiceImpl,
-    SearcherContext, SearcherPool,
+    ClusterClient, RunningSearches, SearchJobPlacer, SearchService, SearchServiceClient,
+    SearchServiceImpl, SearcherContext, SearcherPool,
 };
 use quickwit_serve::lambda_search_api::*;
 use quickwit_storage::StorageResolver;
@@ -61,21 +62,55 @@ async fn create_local_search_service(
 
 fn native_api(
     search_service: Arc<dyn SearchService>,
+    running_searches: Arc<RunningSearches>,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    search_get_handler(search_service.clone()).or(search_post_handler(search_service))
+    search_get_handler(
+        search_service.clone(),
+        running_searches.clone(),
+        Arc::default(),
+        Default::default(),
+    )
+    .or(search_post_handler(
+        search_service,
+        running_searches,
+        Arc::default(),
+        Default::default(),
+        content_length_limit,
+    ))
 }
 
 fn es_compat_api(
     search_service: Arc<dyn SearchService>,
+    running_searches: Arc<RunningSearches>,
     metastore: MetastoreServiceClient,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     es_compat_search_handler(search_service.clone())
-        .or(es_compat_index_search_handler(search_service.clone()))
-        .or(es_compat_index_count_handler(search_service.clone()))
-        .or(es_compat_scroll_handler(search_service.clone()))
-        .or(es_compat_index_multi_search_handler(search_service.clone()))
+        .or(es_compat_index_search_handler(
+            search_service.clone(),
+            running_searches,
+            Arc::default(),
+            content_length_limit,
+        ))
+        .or(es_compat_index_count_handler(
+            search_service.clone(),
+            Arc::default(),
+            content_length_limit,
+        ))
+        .or(es_compat_scroll_handler(
+            search_service.clone(),
+            content_length_limit,
+        ))
+        .or(es_compat_index_multi_search_handler(
+            search_service.clone(),
+            Arc::default(),
+            content_length_limit,
+        ))
         .or(es_compat_index_field_capabilities_handler(
             search_service.clone(),
+            Arc::default(),
+            content_length_limit,
         ))
         .or(es_compat_index_stats_handler(metastore.clone()))
         .or(es_compat_stats_handler(metastore.clone()))
@@ -93,12 +128,23 @@ fn index_api(
 fn v1_searcher_api(
     search_service: Arc<dyn SearchService>,
     metastore: MetastoreServiceClient,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    let running_searches: Arc<RunningSearches> = Arc::default();
     warp::path!("api" / "v1" / ..)
         .and(
-            native_api(search_service.clone())
-                .or(es_compat_api(search_service, metastore.clone()))
-                .or(index_api(metastore)),
+            native_api(
+                search_service.clone(),
+                running_searches.clone(),
+                content_length_limit,
+            )
+            .or(es_compat_api(
+                search_service,
+                running_searches,
+                metastore.clone(),
+                content_length_limit,
+            ))
+            .or(index_api(metastore)),
         )
         .with(warp::filters::compression::gzip())
         .recover(|rejection| {
@@ -118,6 +164,7 @@ pub async fn setup_searcher_api(
     );
     let _telemetry_handle_opt = quickwit_telemetry::start_telemetry_loop(telemetry_info);
 
+    let search_content_length_limit = node_config.rest_config.content_length_limits.search;
     let search_service = create_local_search_service(
         node_config.searcher_config,
         metastore.clone(),
@@ -144,7 +191,11 @@ pub async fn setup_searcher_api(
 
     let api = warp::any()
         .and(before_hook)
-        .and(v1_searcher_api(search_service, metastore))
+        .and(v1_searcher_api(
+            search_service,
+            metastore,
+            search_content_length_limit,
+        ))
         .with(after_hook);
 
     Ok(api)
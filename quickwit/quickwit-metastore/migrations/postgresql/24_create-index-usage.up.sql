@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS index_usage (
+    index_uid VARCHAR(282) NOT NULL,
+    period_start_timestamp BIGINT NOT NULL,
+    num_docs_ingested BIGINT NOT NULL DEFAULT 0,
+    num_bytes_ingested BIGINT NOT NULL DEFAULT 0,
+    num_queries BIGINT NOT NULL DEFAULT 0,
+    num_bytes_scanned BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (index_uid, period_start_timestamp),
+    FOREIGN KEY (index_uid) REFERENCES indexes (index_uid) ON DELETE CASCADE
+);
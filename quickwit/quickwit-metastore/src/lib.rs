@@ -43,11 +43,12 @@ pub(crate) use metastore::index_metadata::serialize::{IndexMetadataV0_8, Version
 #[cfg(feature = "postgres")]
 pub use metastore::postgres::PostgresqlMetastore;
 pub use metastore::{
-    file_backed, AddSourceRequestExt, CreateIndexRequestExt, CreateIndexResponseExt, IndexMetadata,
-    IndexMetadataResponseExt, IndexesMetadataResponseExt, ListIndexesMetadataResponseExt,
-    ListSplitsQuery, ListSplitsRequestExt, ListSplitsResponseExt, MetastoreServiceExt,
-    MetastoreServiceStreamSplitsExt, PublishSplitsRequestExt, StageSplitsRequestExt,
-    UpdateIndexRequestExt, UpdateSourceRequestExt,
+    file_backed, AddSourceRequestExt, AddTagOverlayRequestExt, CreateIndexRequestExt,
+    CreateIndexResponseExt, IndexMetadata, IndexMetadataResponseExt, IndexesMetadataResponseExt,
+    ListIndexesMetadataRequestExt, ListIndexesMetadataResponseExt, ListSplitsQuery,
+    ListSplitsRequestExt, ListSplitsResponseExt, MetastoreServiceExt,
+    MetastoreServiceStreamSplitsExt, PublishSplitsRequestExt, SortBy, StageSplitsRequestExt,
+    TagOverlay, UpdateIndexRequestExt, UpdateSourceRequestExt, MAX_NUM_TAG_OVERLAYS_PER_INDEX,
 };
 pub use metastore_factory::{MetastoreFactory, UnsupportedMetastore};
 pub use metastore_resolver::MetastoreResolver;
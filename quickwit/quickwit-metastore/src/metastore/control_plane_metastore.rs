@@ -18,21 +18,22 @@ use async_trait::async_trait;
 use quickwit_common::uri::Uri;
 use quickwit_proto::control_plane::{ControlPlaneService, ControlPlaneServiceClient};
 use quickwit_proto::metastore::{
-    AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest, CreateIndexRequest,
-    CreateIndexResponse, CreateIndexTemplateRequest, DeleteIndexRequest,
-    DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest, DeleteShardsResponse,
-    DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse,
+    AccumulateIndexUsageRequest, AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest,
+    AddTagOverlayRequest, CreateIndexRequest, CreateIndexResponse, CreateIndexTemplateRequest,
+    DeleteIndexRequest, DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest,
+    DeleteShardsResponse, DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse,
     FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse, GetIndexTemplateRequest,
-    GetIndexTemplateResponse, IndexMetadataRequest, IndexMetadataResponse, IndexesMetadataRequest,
-    IndexesMetadataResponse, LastDeleteOpstampRequest, LastDeleteOpstampResponse,
-    ListDeleteTasksRequest, ListDeleteTasksResponse, ListIndexTemplatesRequest,
-    ListIndexTemplatesResponse, ListIndexesMetadataRequest, ListIndexesMetadataResponse,
-    ListShardsRequest, ListShardsResponse, ListSplitsRequest, ListSplitsResponse,
-    ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreResult, MetastoreService,
-    MetastoreServiceClient, MetastoreServiceStream, OpenShardsRequest, OpenShardsResponse,
-    PruneShardsRequest, PublishSplitsRequest, ResetSourceCheckpointRequest, StageSplitsRequest,
-    ToggleSourceRequest, UpdateIndexRequest, UpdateSourceRequest, UpdateSplitsDeleteOpstampRequest,
-    UpdateSplitsDeleteOpstampResponse,
+    GetIndexTemplateResponse, GetIndexUsageRequest, GetIndexUsageResponse, IndexMetadataRequest,
+    IndexMetadataResponse, IndexesMetadataRequest, IndexesMetadataResponse,
+    LastDeleteOpstampRequest, LastDeleteOpstampResponse, ListDeleteTasksRequest,
+    ListDeleteTasksResponse, ListIndexTemplatesRequest, ListIndexTemplatesResponse,
+    ListIndexesMetadataRequest, ListIndexesMetadataResponse, ListShardsRequest, ListShardsResponse,
+    ListSplitsRequest, ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest,
+    MetastoreResult, MetastoreService, MetastoreServiceClient, MetastoreServiceStream,
+    OpenShardsRequest, OpenShardsResponse, PruneShardsRequest, PublishSplitsRequest,
+    ResetSourceCheckpointRequest, StageSplitsRequest, ToggleSourceRequest, TrashIndexRequest,
+    UndeleteIndexRequest, UpdateIndexRequest, UpdateSourceRequest,
+    UpdateSplitsDeleteOpstampRequest, UpdateSplitsDeleteOpstampResponse,
 };
 
 /// A [`MetastoreService`] implementation that proxies some requests to the control plane so it can
@@ -95,6 +96,19 @@ impl MetastoreService for ControlPlaneMetastore {
         Ok(response)
     }
 
+    async fn trash_index(&self, request: TrashIndexRequest) -> MetastoreResult<EmptyResponse> {
+        let response = self.control_plane.trash_index(request).await?;
+        Ok(response)
+    }
+
+    async fn undelete_index(
+        &self,
+        request: UndeleteIndexRequest,
+    ) -> MetastoreResult<IndexMetadataResponse> {
+        let response = self.control_plane.undelete_index(request).await?;
+        Ok(response)
+    }
+
     async fn add_source(&self, request: AddSourceRequest) -> MetastoreResult<EmptyResponse> {
         let response = self.control_plane.add_source(request).await?;
         Ok(response)
@@ -274,4 +288,29 @@ impl MetastoreService for ControlPlaneMetastore {
     ) -> MetastoreResult<EmptyResponse> {
         self.metastore.delete_index_templates(request).await
     }
+
+    // Index Usage API
+
+    async fn accumulate_index_usage(
+        &self,
+        request: AccumulateIndexUsageRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.accumulate_index_usage(request).await
+    }
+
+    async fn get_index_usage(
+        &self,
+        request: GetIndexUsageRequest,
+    ) -> MetastoreResult<GetIndexUsageResponse> {
+        self.metastore.get_index_usage(request).await
+    }
+
+    // Tag overlay API
+
+    async fn add_tag_overlay(
+        &self,
+        request: AddTagOverlayRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.add_tag_overlay(request).await
+    }
 }
@@ -19,19 +19,21 @@
 mod serialize;
 mod shards;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 use std::ops::Bound;
 
 use itertools::Itertools;
 use quickwit_common::pretty::PrettySample;
 use quickwit_config::{
-    DocMapping, IndexingSettings, RetentionPolicy, SearchSettings, SourceConfig,
+    DocMapping, IndexRollout, IndexingSettings, IngestionFreeze, RetentionPolicy, SearchSettings,
+    SourceConfig,
 };
 use quickwit_proto::metastore::{
     AcquireShardsRequest, AcquireShardsResponse, DeleteQuery, DeleteShardsRequest,
-    DeleteShardsResponse, DeleteTask, EntityKind, ListShardsSubrequest, ListShardsSubresponse,
-    MetastoreError, MetastoreResult, OpenShardSubrequest, OpenShardSubresponse, PruneShardsRequest,
+    DeleteShardsResponse, DeleteTask, EntityKind, IndexUsage, ListShardsSubrequest,
+    ListShardsSubresponse, MetastoreError, MetastoreResult, OpenShardSubrequest,
+    OpenShardSubresponse, PruneShardsRequest,
 };
 use quickwit_proto::types::{IndexUid, PublishToken, SourceId, SplitId};
 use serde::{Deserialize, Serialize};
@@ -43,7 +45,9 @@ use tracing::{info, warn};
 use super::MutationOccurred;
 use crate::checkpoint::IndexCheckpointDelta;
 use crate::metastore::{use_shard_api, SortBy};
-use crate::{split_tag_filter, IndexMetadata, ListSplitsQuery, Split, SplitMetadata, SplitState};
+use crate::{
+    split_tag_filter, IndexMetadata, ListSplitsQuery, Split, SplitMetadata, SplitState, TagOverlay,
+};
 
 /// A `FileBackedIndex` object carries an index metadata and its split metadata.
 // This struct is meant to be used only within the [`FileBackedMetastore`]. The public visibility is
@@ -234,6 +238,31 @@ impl FileBackedIndex {
         self.metadata.set_doc_mapping(doc_mapping)
     }
 
+    /// Replaces the rollout in the index config, returning whether a mutation occurred.
+    pub fn set_rollout(&mut self, rollout: IndexRollout) -> bool {
+        self.metadata.set_rollout(rollout)
+    }
+
+    /// Replaces the ingestion freeze in the index config, returning whether a mutation occurred.
+    pub fn set_ingestion_freeze(&mut self, ingestion_freeze_opt: Option<IngestionFreeze>) -> bool {
+        self.metadata.set_ingestion_freeze(ingestion_freeze_opt)
+    }
+
+    /// Replaces the labels in the index config, returning whether a mutation occurred.
+    pub fn set_labels(&mut self, labels: BTreeMap<String, String>) -> bool {
+        self.metadata.set_labels(labels)
+    }
+
+    /// Moves the index to the trash, returning whether a mutation occurred.
+    pub(crate) fn trash(&mut self) -> bool {
+        self.metadata.trash()
+    }
+
+    /// Restores the index from the trash.
+    pub(crate) fn undelete(&mut self) -> MetastoreResult<()> {
+        self.metadata.undelete()
+    }
+
     /// Stages a single split.
     ///
     /// If a split already exists and is in the [SplitState::Staged] state,
@@ -368,14 +397,14 @@ impl FileBackedIndex {
         Ok(())
     }
 
-    /// Publishes splits.
+    /// Publishes splits and returns the usage ingested into the index by the published splits.
     pub(crate) fn publish_splits(
         &mut self,
         staged_split_ids: impl IntoIterator<Item = impl AsRef<str>>,
         replaced_split_ids: impl IntoIterator<Item = impl AsRef<str>>,
         checkpoint_delta_opt: Option<IndexCheckpointDelta>,
         publish_token_opt: Option<PublishToken>,
-    ) -> MetastoreResult<()> {
+    ) -> MetastoreResult<IndexUsage> {
         if let Some(checkpoint_delta) = checkpoint_delta_opt {
             let source_id = checkpoint_delta.source_id.clone();
             let source = self.metadata.sources.get(&source_id).ok_or_else(|| {
@@ -412,9 +441,32 @@ impl FileBackedIndex {
                     })?;
             }
         }
-        self.mark_splits_as_published_helper(staged_split_ids)?;
-        self.mark_splits_for_deletion(replaced_split_ids, &[SplitState::Published], true)?;
-        Ok(())
+        let staged_split_ids: Vec<_> = staged_split_ids.into_iter().collect();
+        let replaced_split_ids: Vec<_> = replaced_split_ids.into_iter().collect();
+
+        self.mark_splits_as_published_helper(&staged_split_ids)?;
+        self.mark_splits_for_deletion(&replaced_split_ids, &[SplitState::Published], true)?;
+
+        // Merged splits replace existing splits: only the splits that do not replace any other
+        // split carry newly ingested documents.
+        if !replaced_split_ids.is_empty() {
+            return Ok(IndexUsage::default());
+        }
+        Ok(self.ingested_splits_usage(&staged_split_ids))
+    }
+
+    /// Returns the documents and bytes ingested into the index by publishing the given splits.
+    fn ingested_splits_usage(&self, split_ids: &[impl AsRef<str>]) -> IndexUsage {
+        let mut usage_delta = IndexUsage::default();
+
+        for split_id in split_ids {
+            if let Some(split) = self.splits.get(split_id.as_ref()) {
+                usage_delta.num_docs_ingested += split.split_metadata.num_docs as u64;
+                usage_delta.num_bytes_ingested +=
+                    split.split_metadata.uncompressed_docs_size_in_bytes;
+            }
+        }
+        usage_delta
     }
 
     /// Lists splits.
@@ -522,6 +574,14 @@ impl FileBackedIndex {
         self.metadata.toggle_source(source_id, enable)
     }
 
+    /// Adds tag overlays, all of them or none.
+    pub(crate) fn add_tag_overlays(
+        &mut self,
+        tag_overlays: Vec<TagOverlay>,
+    ) -> MetastoreResult<()> {
+        self.metadata.add_tag_overlays(tag_overlays)
+    }
+
     /// Deletes the source. Returns whether a mutation occurred.
     pub(crate) fn delete_source(&mut self, source_id: &str) -> MetastoreResult<()> {
         self.metadata.delete_source(source_id)
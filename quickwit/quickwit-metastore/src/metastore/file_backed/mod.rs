@@ -40,27 +40,30 @@ use itertools::Itertools;
 use quickwit_common::ServiceStream;
 use quickwit_config::IndexTemplate;
 use quickwit_proto::metastore::{
-    serde_utils, AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest, CreateIndexRequest,
+    index_usage_period_start, serde_utils, AccumulateIndexUsageRequest, AcquireShardsRequest,
+    AcquireShardsResponse, AddSourceRequest, AddTagOverlayRequest, CreateIndexRequest,
     CreateIndexResponse, CreateIndexTemplateRequest, DeleteIndexRequest,
     DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest, DeleteShardsResponse,
     DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse, EntityKind,
     FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse, GetIndexTemplateRequest,
-    GetIndexTemplateResponse, IndexMetadataFailure, IndexMetadataFailureReason,
-    IndexMetadataRequest, IndexMetadataResponse, IndexTemplateMatch, IndexesMetadataRequest,
-    IndexesMetadataResponse, LastDeleteOpstampRequest, LastDeleteOpstampResponse,
-    ListDeleteTasksRequest, ListDeleteTasksResponse, ListIndexTemplatesRequest,
-    ListIndexTemplatesResponse, ListIndexesMetadataRequest, ListIndexesMetadataResponse,
-    ListShardsRequest, ListShardsResponse, ListSplitsRequest, ListSplitsResponse,
-    ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError, MetastoreResult,
-    MetastoreService, MetastoreServiceStream, OpenShardSubrequest, OpenShardsRequest,
-    OpenShardsResponse, PruneShardsRequest, PublishSplitsRequest, ResetSourceCheckpointRequest,
-    StageSplitsRequest, ToggleSourceRequest, UpdateIndexRequest, UpdateSourceRequest,
+    GetIndexTemplateResponse, GetIndexUsageRequest, GetIndexUsageResponse, IndexMetadataFailure,
+    IndexMetadataFailureReason, IndexMetadataRequest, IndexMetadataResponse, IndexTemplateMatch,
+    IndexUsage, IndexesMetadataRequest, IndexesMetadataResponse, LastDeleteOpstampRequest,
+    LastDeleteOpstampResponse, ListDeleteTasksRequest, ListDeleteTasksResponse,
+    ListIndexTemplatesRequest, ListIndexTemplatesResponse, ListIndexesMetadataRequest,
+    ListIndexesMetadataResponse, ListShardsRequest, ListShardsResponse, ListSplitsRequest,
+    ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError,
+    MetastoreResult, MetastoreService, MetastoreServiceStream, OpenShardSubrequest,
+    OpenShardsRequest, OpenShardsResponse, PruneShardsRequest, PublishSplitsRequest,
+    ResetSourceCheckpointRequest, StageSplitsRequest, ToggleSourceRequest, TrashIndexRequest,
+    UndeleteIndexRequest, UpdateIndexRequest, UpdateSourceRequest,
     UpdateSplitsDeleteOpstampRequest, UpdateSplitsDeleteOpstampResponse,
 };
 use quickwit_proto::types::{IndexId, IndexUid};
 use quickwit_storage::Storage;
 use time::OffsetDateTime;
 use tokio::sync::{Mutex, OwnedMutexGuard, RwLock};
+use tracing::warn;
 use ulid::Ulid;
 
 use self::file_backed_index::FileBackedIndex;
@@ -69,12 +72,15 @@ use self::index_id_matcher::IndexIdMatcher;
 use self::lazy_file_backed_index::LazyFileBackedIndex;
 use self::manifest::{load_or_create_manifest, save_manifest, MANIFEST_FILE_NAME};
 use self::state::MetastoreState;
-use self::store_operations::{delete_index, index_exists, load_index, put_index};
+use self::store_operations::{
+    delete_index, delete_index_usage, index_exists, load_index, load_index_usage, put_index,
+    put_index_usage,
+};
 use super::{
-    AddSourceRequestExt, CreateIndexRequestExt, IndexMetadataResponseExt,
-    IndexesMetadataResponseExt, ListIndexesMetadataResponseExt, ListSplitsRequestExt,
-    ListSplitsResponseExt, PublishSplitsRequestExt, StageSplitsRequestExt, UpdateIndexRequestExt,
-    UpdateSourceRequestExt, STREAM_SPLITS_CHUNK_SIZE,
+    AddSourceRequestExt, AddTagOverlayRequestExt, CreateIndexRequestExt, IndexMetadataResponseExt,
+    IndexesMetadataResponseExt, ListIndexesMetadataRequestExt, ListIndexesMetadataResponseExt,
+    ListSplitsRequestExt, ListSplitsResponseExt, PublishSplitsRequestExt, StageSplitsRequestExt,
+    UpdateIndexRequestExt, UpdateSourceRequestExt, STREAM_SPLITS_CHUNK_SIZE,
 };
 use crate::checkpoint::IndexCheckpointDelta;
 use crate::{IndexMetadata, ListSplitsQuery, MetastoreServiceExt, Split, SplitState};
@@ -145,6 +151,8 @@ pub struct FileBackedMetastore {
     state: Arc<RwLock<MetastoreState>>,
     storage: Arc<dyn Storage>,
     polling_interval_opt: Option<Duration>,
+    /// Serializes the updates of the index usage files.
+    usage_lock: Arc<Mutex<()>>,
 }
 
 impl fmt::Debug for FileBackedMetastore {
@@ -164,6 +172,7 @@ impl FileBackedMetastore {
             state: Default::default(),
             storage,
             polling_interval_opt: None,
+            usage_lock: Default::default(),
         }
     }
 
@@ -192,6 +201,7 @@ impl FileBackedMetastore {
             state: Arc::new(RwLock::new(state)),
             storage,
             polling_interval_opt,
+            usage_lock: Default::default(),
         };
         Ok(metastore)
     }
@@ -276,6 +286,24 @@ impl FileBackedMetastore {
         view(&locked_index)
     }
 
+    /// Adds usage deltas, by usage period, to the usage counters of an index.
+    async fn accumulate_index_usage_deltas(
+        &self,
+        index_uid: &IndexUid,
+        usage_deltas: impl IntoIterator<Item = (i64, IndexUsage)>,
+    ) -> MetastoreResult<()> {
+        let _usage_guard = self.usage_lock.lock().await;
+        let mut periods = load_index_usage(&*self.storage, index_uid).await?;
+
+        for (period_start_timestamp, usage_delta) in usage_deltas {
+            periods
+                .entry(period_start_timestamp)
+                .or_default()
+                .accumulate(&usage_delta);
+        }
+        put_index_usage(&*self.storage, index_uid, periods).await
+    }
+
     /// Returns a valid locked index.
     ///
     /// This function guarantees that it has not been
@@ -497,9 +525,19 @@ impl MetastoreService for FileBackedMetastore {
         //   don't want to override an existing metadata file.
         if let Some(index_status) = state_wlock_guard.indexes.get(index_id) {
             if let LazyIndexStatus::Active(_) = index_status {
-                return Err(MetastoreError::AlreadyExists(EntityKind::Index {
-                    index_id: index_id.to_string(),
-                }));
+                drop(state_wlock_guard);
+
+                let metastore_error = self
+                    .read_any(index_id, None, |index| {
+                        Ok(index.metadata().already_exists_error())
+                    })
+                    .await
+                    .unwrap_or_else(|_| {
+                        MetastoreError::AlreadyExists(EntityKind::Index {
+                            index_id: index_id.to_string(),
+                        })
+                    });
+                return Err(metastore_error);
             }
         } else if index_exists(&*self.storage, index_id).await? {
             return Err(MetastoreError::Internal {
@@ -557,6 +595,9 @@ impl MetastoreService for FileBackedMetastore {
         let search_settings = request.deserialize_search_settings()?;
         let indexing_settings = request.deserialize_indexing_settings()?;
         let doc_mapping = request.deserialize_doc_mapping()?;
+        let rollout_opt = request.deserialize_rollout()?;
+        let ingestion_freeze_opt = request.deserialize_ingestion_freeze()?;
+        let labels_opt = request.deserialize_labels()?;
         let index_uid = request.index_uid();
 
         let index_metadata = self
@@ -566,6 +607,16 @@ impl MetastoreService for FileBackedMetastore {
                 mutation_occurred |= index.set_indexing_settings(indexing_settings);
                 mutation_occurred |= index.set_doc_mapping(doc_mapping);
 
+                if let Some(rollout) = rollout_opt {
+                    mutation_occurred |= index.set_rollout(rollout);
+                }
+                if let Some(ingestion_freeze) = ingestion_freeze_opt {
+                    mutation_occurred |= index.set_ingestion_freeze(ingestion_freeze);
+                }
+                if let Some(labels) = labels_opt {
+                    mutation_occurred |= index.set_labels(labels);
+                }
+
                 let index_metadata = index.metadata().clone();
 
                 if mutation_occurred {
@@ -616,6 +667,11 @@ impl MetastoreService for FileBackedMetastore {
             &delete_result,
             Ok(()) | Err(MetastoreError::NotFound(EntityKind::Index { .. }))
         ) {
+            // The usage file of a deleted index is ignored anyway, so failing to delete it is not
+            // an error.
+            if let Err(error) = delete_index_usage(&*self.storage, index_id).await {
+                warn!(%error, index_id, "failed to delete index usage file");
+            }
             state_wlock_guard.indexes.remove(index_id);
             let manifest = state_wlock_guard.as_manifest();
 
@@ -629,6 +685,32 @@ impl MetastoreService for FileBackedMetastore {
         delete_result.map(|_| EmptyResponse {})
     }
 
+    async fn trash_index(&self, request: TrashIndexRequest) -> MetastoreResult<EmptyResponse> {
+        let index_uid = request.index_uid();
+
+        self.mutate(index_uid, |index| Ok(MutationOccurred::from(index.trash())))
+            .await?;
+        Ok(EmptyResponse {})
+    }
+
+    async fn undelete_index(
+        &self,
+        request: UndeleteIndexRequest,
+    ) -> MetastoreResult<IndexMetadataResponse> {
+        let index_uid = self
+            .read_any(&request.index_id, None, |index| {
+                Ok(index.index_uid().clone())
+            })
+            .await?;
+        let index_metadata = self
+            .mutate(&index_uid, |index| {
+                index.undelete()?;
+                Ok(MutationOccurred::Yes(index.metadata().clone()))
+            })
+            .await?;
+        IndexMetadataResponse::try_from_index_metadata(&index_metadata)
+    }
+
     /// -------------------------------------------------------------------------------
     /// Mutations over a single index
 
@@ -672,16 +754,32 @@ impl MetastoreService for FileBackedMetastore {
         let index_checkpoint_delta: Option<IndexCheckpointDelta> =
             request.deserialize_index_checkpoint()?;
         let index_uid = request.index_uid().clone();
-        self.mutate(&index_uid, |index| {
-            index.publish_splits(
-                request.staged_split_ids,
-                request.replaced_split_ids,
-                index_checkpoint_delta,
-                request.publish_token_opt,
-            )?;
-            Ok(MutationOccurred::Yes(()))
-        })
-        .await?;
+        let ingested_usage = self
+            .mutate(&index_uid, |index| {
+                let ingested_usage = index.publish_splits(
+                    request.staged_split_ids,
+                    request.replaced_split_ids,
+                    index_checkpoint_delta,
+                    request.publish_token_opt,
+                )?;
+                Ok(MutationOccurred::Yes(ingested_usage))
+            })
+            .await?;
+        if !ingested_usage.is_empty() {
+            let period_start_timestamp =
+                index_usage_period_start(OffsetDateTime::now_utc().unix_timestamp());
+            // The splits are published at this point: failing to account for their usage must
+            // not fail the request.
+            if let Err(error) = self
+                .accumulate_index_usage_deltas(
+                    &index_uid,
+                    [(period_start_timestamp, ingested_usage)],
+                )
+                .await
+            {
+                warn!(%error, %index_uid, "failed to accumulate ingested usage");
+            }
+        }
         Ok(EmptyResponse {})
     }
 
@@ -823,6 +921,12 @@ impl MetastoreService for FileBackedMetastore {
             .index_metadata_inner(request.index_id, request.index_uid)
             .await
             .map_err(|(metastore_error, _index_id_opt, _index_uid_opt)| metastore_error)?;
+
+        if index_metadata.is_trashed() {
+            return Err(MetastoreError::NotFound(EntityKind::Index {
+                index_id: index_metadata.index_id().to_string(),
+            }));
+        }
         let response = IndexMetadataResponse::try_from_index_metadata(&index_metadata)?;
         Ok(response)
     }
@@ -848,6 +952,14 @@ impl MetastoreService for FileBackedMetastore {
         }
         while let Some(index_metadata_result) = index_metadata_futures.next().await {
             match index_metadata_result {
+                Ok(index_metadata) if index_metadata.is_trashed() => {
+                    let failure = IndexMetadataFailure {
+                        index_id: Some(index_metadata.index_id().to_string()),
+                        index_uid: Some(index_metadata.index_uid),
+                        reason: IndexMetadataFailureReason::NotFound as i32,
+                    };
+                    failures.push(failure)
+                }
                 Ok(index_metadata) => indexes_metadata.push(index_metadata),
                 Err((MetastoreError::NotFound(_), index_id, index_uid)) => {
                     let failure = IndexMetadataFailure {
@@ -884,6 +996,7 @@ impl MetastoreService for FileBackedMetastore {
         // take a write lock on `per_index_metastores`.
         let index_id_matcher =
             IndexIdMatcher::try_from_index_id_patterns(&request.index_id_patterns)?;
+        let label_selectors = request.parse_label_selectors()?;
         let inner_rlock_guard = self.state.read().await;
         let index_ids: Vec<IndexId> = inner_rlock_guard
             .indexes
@@ -905,6 +1018,12 @@ impl MetastoreService for FileBackedMetastore {
         .await?
         .into_iter()
         .flatten()
+        .filter(|index_metadata| request.include_trashed || !index_metadata.is_trashed())
+        .filter(|index_metadata| {
+            index_metadata
+                .index_config
+                .matches_label_selectors(&label_selectors)
+        })
         .collect();
         let response =
             ListIndexesMetadataResponse::try_from_indexes_metadata(indexes_metadata).await?;
@@ -1194,6 +1313,88 @@ impl MetastoreService for FileBackedMetastore {
         }
         Ok(EmptyResponse {})
     }
+
+    async fn accumulate_index_usage(
+        &self,
+        request: AccumulateIndexUsageRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let mut usage_deltas_per_index: HashMap<IndexUid, Vec<(i64, IndexUsage)>> = HashMap::new();
+
+        for subrequest in request.subrequests {
+            let Some(usage_delta) = subrequest.usage_delta else {
+                continue;
+            };
+            if usage_delta.is_empty() {
+                continue;
+            }
+            let Some(index_uid) = subrequest.index_uid else {
+                continue;
+            };
+            usage_deltas_per_index
+                .entry(index_uid)
+                .or_default()
+                .push((subrequest.period_start_timestamp, usage_delta));
+        }
+        for (index_uid, usage_deltas) in usage_deltas_per_index {
+            // The index may have been deleted in the meantime.
+            match self.read(&index_uid, |_index| Ok(())).await {
+                Ok(()) => {}
+                Err(MetastoreError::NotFound(_)) => continue,
+                Err(error) => return Err(error),
+            }
+            self.accumulate_index_usage_deltas(&index_uid, usage_deltas)
+                .await?;
+        }
+        Ok(EmptyResponse {})
+    }
+
+    async fn get_index_usage(
+        &self,
+        request: GetIndexUsageRequest,
+    ) -> MetastoreResult<GetIndexUsageResponse> {
+        let index_uid = request.index_uid();
+        self.read(index_uid, |_index| Ok(())).await?;
+
+        let start_period = request
+            .start_timestamp
+            .map(index_usage_period_start)
+            .unwrap_or(i64::MIN);
+        let end_period = request
+            .end_timestamp
+            .map(index_usage_period_start)
+            .unwrap_or(i64::MAX);
+        let mut usage = IndexUsage::default();
+
+        if start_period < end_period {
+            let periods = load_index_usage(&*self.storage, index_uid).await?;
+
+            for period_usage in periods
+                .range(start_period..end_period)
+                .map(|(_, usage)| usage)
+            {
+                usage.accumulate(period_usage);
+            }
+        }
+        let response = GetIndexUsageResponse { usage: Some(usage) };
+        Ok(response)
+    }
+
+    // Tag overlay API
+
+    async fn add_tag_overlay(
+        &self,
+        request: AddTagOverlayRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let tag_overlays = request.deserialize_tag_overlays()?;
+        let index_uid = request.index_uid();
+
+        self.mutate(index_uid, |index| {
+            index.add_tag_overlays(tag_overlays)?;
+            Ok(MutationOccurred::Yes(()))
+        })
+        .await?;
+        Ok(EmptyResponse {})
+    }
 }
 
 impl MetastoreServiceExt for FileBackedMetastore {}
@@ -1223,11 +1424,10 @@ async fn get_index_metadata(
     metastore: FileBackedMetastore,
     index_id: IndexId,
 ) -> MetastoreResult<Option<IndexMetadata>> {
-    let request = IndexMetadataRequest::for_index_id(index_id);
     let index_metadata_result = metastore
-        .index_metadata(request)
+        .index_metadata_inner(Some(index_id), None)
         .await
-        .and_then(|response| response.deserialize_index_metadata());
+        .map_err(|(metastore_error, _index_id_opt, _index_uid_opt)| metastore_error);
     match index_metadata_result {
         Ok(index_metadata) => Ok(Some(index_metadata)),
         Err(MetastoreError::NotFound { .. }) => Ok(None),
@@ -12,21 +12,44 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
-use quickwit_proto::metastore::{serde_utils, EntityKind, MetastoreError, MetastoreResult};
+use quickwit_proto::metastore::{
+    serde_utils, EntityKind, IndexUsage, MetastoreError, MetastoreResult,
+};
+use quickwit_proto::types::IndexUid;
 use quickwit_storage::{Storage, StorageError, StorageErrorKind};
+use serde::{Deserialize, Serialize};
 
 use crate::metastore::file_backed::file_backed_index::FileBackedIndex;
 
 /// Index metastore file managed by [`FileBackedMetastore`](crate::FileBackedMetastore).
 pub(super) const METASTORE_FILE_NAME: &str = "metastore.json";
 
+/// Index usage file managed by [`FileBackedMetastore`](crate::FileBackedMetastore). The usage
+/// counters are updated much more often than the rest of the index, so they are stored apart
+/// to avoid rewriting the whole index metastore file on every update.
+const USAGE_FILE_NAME: &str = "usage.json";
+
 /// Path to the metadata file from the given index ID.
 pub(super) fn metastore_filepath(index_id: &str) -> PathBuf {
     Path::new(index_id).join(METASTORE_FILE_NAME)
 }
 
+fn usage_filepath(index_id: &str) -> PathBuf {
+    Path::new(index_id).join(USAGE_FILE_NAME)
+}
+
+/// Usage counters of an index, by usage period.
+#[derive(Serialize, Deserialize)]
+struct IndexUsageFile {
+    /// An index can be deleted and recreated with the same ID: the usage file of the previous
+    /// incarnation of the index must be ignored.
+    index_uid: IndexUid,
+    periods: BTreeMap<i64, IndexUsage>,
+}
+
 fn convert_error(index_id: &str, storage_error: StorageError) -> MetastoreError {
     match storage_error.kind() {
         StorageErrorKind::NotFound => MetastoreError::NotFound(EntityKind::Index {
@@ -138,3 +161,57 @@ pub(super) async fn delete_index(storage: &dyn Storage, index_id: &str) -> Metas
         })?;
     Ok(())
 }
+
+/// Loads the usage counters of an index by usage period.
+pub(super) async fn load_index_usage(
+    storage: &dyn Storage,
+    index_uid: &IndexUid,
+) -> MetastoreResult<BTreeMap<i64, IndexUsage>> {
+    let index_id = &index_uid.index_id;
+    let usage_filepath = usage_filepath(index_id);
+
+    let content = match storage.get_all(&usage_filepath).await {
+        Ok(content) => content,
+        Err(storage_error) if storage_error.kind() == StorageErrorKind::NotFound => {
+            return Ok(BTreeMap::new());
+        }
+        Err(storage_error) => return Err(convert_error(index_id, storage_error)),
+    };
+    let usage_file: IndexUsageFile = serde_utils::from_json_bytes(&content)?;
+
+    if usage_file.index_uid != *index_uid {
+        return Ok(BTreeMap::new());
+    }
+    Ok(usage_file.periods)
+}
+
+/// Stores the usage counters of an index by usage period.
+pub(super) async fn put_index_usage(
+    storage: &dyn Storage,
+    index_uid: &IndexUid,
+    periods: BTreeMap<i64, IndexUsage>,
+) -> MetastoreResult<()> {
+    let index_id = &index_uid.index_id;
+    let usage_file = IndexUsageFile {
+        index_uid: index_uid.clone(),
+        periods,
+    };
+    let content: Vec<u8> = serde_utils::to_json_bytes(&usage_file)?;
+    storage
+        .put(&usage_filepath(index_id), Box::new(content))
+        .await
+        .map_err(|storage_error| convert_error(index_id, storage_error))?;
+    Ok(())
+}
+
+/// Deletes the usage counters of an index, if any.
+pub(super) async fn delete_index_usage(
+    storage: &dyn Storage,
+    index_id: &str,
+) -> MetastoreResult<()> {
+    match storage.delete(&usage_filepath(index_id)).await {
+        Ok(()) => Ok(()),
+        Err(storage_error) if storage_error.kind() == StorageErrorKind::NotFound => Ok(()),
+        Err(storage_error) => Err(convert_error(index_id, storage_error)),
+    }
+}
@@ -13,13 +13,15 @@
 // limitations under the License.
 
 pub(crate) mod serialize;
+mod tag_overlay;
 
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use quickwit_common::uri::Uri;
 use quickwit_config::{
-    DocMapping, IndexConfig, IndexingSettings, RetentionPolicy, SearchSettings, SourceConfig,
+    DocMapping, IndexConfig, IndexRollout, IndexingSettings, IngestionFreeze, RetentionPolicy,
+    SearchSettings, SourceConfig,
 };
 use quickwit_proto::metastore::{EntityKind, MetastoreError, MetastoreResult};
 use quickwit_proto::types::{IndexUid, SourceId};
@@ -27,6 +29,7 @@ use serde::{Deserialize, Serialize};
 use serialize::VersionedIndexMetadata;
 use time::OffsetDateTime;
 
+pub use self::tag_overlay::{TagOverlay, MAX_NUM_TAG_OVERLAYS_PER_INDEX};
 use crate::checkpoint::IndexCheckpoint;
 
 /// An index metadata carries all meta data about an index.
@@ -44,6 +47,10 @@ pub struct IndexMetadata {
     pub create_timestamp: i64,
     /// Sources
     pub sources: HashMap<SourceId, SourceConfig>,
+    /// Tag overlays, in their order of creation.
+    pub tag_overlays: Vec<TagOverlay>,
+    /// Time at which the index was moved to the trash, if it was.
+    pub trash_timestamp_opt: Option<i64>,
 }
 
 impl IndexMetadata {
@@ -61,6 +68,8 @@ impl IndexMetadata {
             checkpoint: Default::default(),
             create_timestamp: OffsetDateTime::now_utc().unix_timestamp(),
             sources: HashMap::default(),
+            tag_overlays: Vec::new(),
+            trash_timestamp_opt: None,
         }
     }
 
@@ -95,6 +104,52 @@ impl IndexMetadata {
         &self.index_config().index_uri
     }
 
+    /// Returns whether the index is in the trash.
+    pub fn is_trashed(&self) -> bool {
+        self.trash_timestamp_opt.is_some()
+    }
+
+    /// Moves the index to the trash, returning whether a mutation occurred. Moving an index that
+    /// is already in the trash does not reset its trash timestamp.
+    pub(crate) fn trash(&mut self) -> bool {
+        if self.trash_timestamp_opt.is_some() {
+            return false;
+        }
+        self.trash_timestamp_opt = Some(OffsetDateTime::now_utc().unix_timestamp());
+        true
+    }
+
+    /// Returns the error for a request creating an index with the same ID as this one. Indexes in
+    /// the trash hold on to their ID until they are purged, which the error spells out.
+    pub(crate) fn already_exists_error(&self) -> MetastoreError {
+        let entity = EntityKind::Index {
+            index_id: self.index_id().to_string(),
+        };
+        if self.is_trashed() {
+            return MetastoreError::FailedPrecondition {
+                entity,
+                message: format!(
+                    "index `{}` is in the trash: restore it or delete it again to purge it",
+                    self.index_id()
+                ),
+            };
+        }
+        MetastoreError::AlreadyExists(entity)
+    }
+
+    /// Restores the index from the trash. Returns an error if the index is not in the trash.
+    pub(crate) fn undelete(&mut self) -> MetastoreResult<()> {
+        if self.trash_timestamp_opt.take().is_none() {
+            return Err(MetastoreError::FailedPrecondition {
+                entity: EntityKind::Index {
+                    index_id: self.index_id().to_string(),
+                },
+                message: format!("index `{}` is not in the trash", self.index_id()),
+            });
+        }
+        Ok(())
+    }
+
     /// Replaces or removes the current retention policy, returning whether a mutation occurred.
     pub fn set_retention_policy(&mut self, retention_policy_opt: Option<RetentionPolicy>) -> bool {
         if self.index_config.retention_policy_opt != retention_policy_opt {
@@ -135,6 +190,36 @@ impl IndexMetadata {
         }
     }
 
+    /// Replaces the current rollout, returning whether a mutation occurred.
+    pub fn set_rollout(&mut self, rollout: IndexRollout) -> bool {
+        if self.index_config.rollout_opt.as_ref() != Some(&rollout) {
+            self.index_config.rollout_opt = Some(rollout);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Freezes or unfreezes the ingestion into the index, returning whether a mutation occurred.
+    pub fn set_ingestion_freeze(&mut self, ingestion_freeze_opt: Option<IngestionFreeze>) -> bool {
+        if self.index_config.ingestion_freeze_opt != ingestion_freeze_opt {
+            self.index_config.ingestion_freeze_opt = ingestion_freeze_opt;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Replaces the labels of the index, returning whether a mutation occurred.
+    pub fn set_labels(&mut self, labels: BTreeMap<String, String>) -> bool {
+        if self.index_config.labels != labels {
+            self.index_config.labels = labels;
+            true
+        } else {
+            false
+        }
+    }
+
     /// Adds a source to the index. Returns an error if the source already exists.
     pub fn add_source(&mut self, source_config: SourceConfig) -> MetastoreResult<()> {
         match self.sources.entry(source_config.source_id.clone()) {
@@ -191,6 +276,56 @@ impl IndexMetadata {
         self.checkpoint.remove_source(source_id);
         Ok(())
     }
+
+    /// Adds tag overlays to the index, all of them or none. Returns an error if the index would
+    /// end up with more than [`MAX_NUM_TAG_OVERLAYS_PER_INDEX`] tag overlays.
+    ///
+    /// Before adding the new tag overlays, the tag overlays created before the retention period
+    /// of the index are dropped: the documents they tag are past the retention period too. A new
+    /// tag overlay assigning the same value as the last tag overlay on its field is merged into
+    /// it, so that repeated updates of a tag do not use up the quota.
+    pub(crate) fn add_tag_overlays(
+        &mut self,
+        tag_overlays: Vec<TagOverlay>,
+    ) -> MetastoreResult<()> {
+        let mut new_tag_overlays = self.tag_overlays.clone();
+
+        if let Some(retention_policy) = &self.index_config.retention_policy_opt {
+            if let Ok(retention_period) = retention_policy.retention_period() {
+                let min_create_timestamp =
+                    OffsetDateTime::now_utc().unix_timestamp() - retention_period.as_secs() as i64;
+                new_tag_overlays
+                    .retain(|tag_overlay| tag_overlay.create_timestamp >= min_create_timestamp);
+            }
+        }
+        for tag_overlay in tag_overlays {
+            let last_tag_overlay_on_field_opt = new_tag_overlays
+                .iter_mut()
+                .rev()
+                .find(|other_tag_overlay| other_tag_overlay.field_name == tag_overlay.field_name);
+
+            match last_tag_overlay_on_field_opt {
+                Some(last_tag_overlay) if last_tag_overlay.value_opt == tag_overlay.value_opt => {
+                    last_tag_overlay.merge(tag_overlay);
+                }
+                _ => new_tag_overlays.push(tag_overlay),
+            }
+        }
+        if new_tag_overlays.len() > MAX_NUM_TAG_OVERLAYS_PER_INDEX {
+            return Err(MetastoreError::FailedPrecondition {
+                entity: EntityKind::Index {
+                    index_id: self.index_id().to_string(),
+                },
+                message: format!(
+                    "index `{}` cannot have more than {MAX_NUM_TAG_OVERLAYS_PER_INDEX} tag \
+                     overlays",
+                    self.index_id()
+                ),
+            });
+        }
+        self.tag_overlays = new_tag_overlays;
+        Ok(())
+    }
 }
 
 #[cfg(any(test, feature = "testsuite"))]
@@ -223,6 +358,7 @@ impl quickwit_config::TestableForRegression for IndexMetadata {
             checkpoint,
             create_timestamp: 1789,
             sources: Default::default(),
+            tag_overlays: Vec::new(),
         };
         index_metadata
             .add_source(SourceConfig::sample_for_regression())
@@ -235,5 +371,6 @@ impl quickwit_config::TestableForRegression for IndexMetadata {
         assert_eq!(self.checkpoint, other.checkpoint);
         assert_eq!(self.create_timestamp, other.create_timestamp);
         assert_eq!(self.sources, other.sources);
+        assert_eq!(self.tag_overlays, other.tag_overlays);
     }
 }
@@ -18,6 +18,7 @@ use quickwit_config::{IndexConfig, SourceConfig};
 use quickwit_proto::types::IndexUid;
 use serde::{self, Deserialize, Serialize};
 
+use super::TagOverlay;
 use crate::checkpoint::IndexCheckpoint;
 use crate::split_metadata::utc_now_timestamp;
 use crate::IndexMetadata;
@@ -59,6 +60,8 @@ impl From<IndexMetadata> for IndexMetadataV0_8 {
             checkpoint: index_metadata.checkpoint,
             create_timestamp: index_metadata.create_timestamp,
             sources,
+            tag_overlays: index_metadata.tag_overlays,
+            trash_timestamp: index_metadata.trash_timestamp_opt,
         }
     }
 }
@@ -75,6 +78,13 @@ pub(crate) struct IndexMetadataV0_8 {
     pub create_timestamp: i64,
     #[schema(value_type = Vec<VersionedSourceConfig>)]
     pub sources: Vec<SourceConfig>,
+    #[schema(value_type = Vec<Object>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tag_overlays: Vec<TagOverlay>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trash_timestamp: Option<i64>,
 }
 
 impl TryFrom<IndexMetadataV0_8> for IndexMetadata {
@@ -94,6 +104,8 @@ impl TryFrom<IndexMetadataV0_8> for IndexMetadata {
             checkpoint: v0_8.checkpoint,
             create_timestamp: v0_8.create_timestamp,
             sources,
+            tag_overlays: v0_8.tag_overlays,
+            trash_timestamp_opt: v0_8.trash_timestamp,
         })
     }
 }
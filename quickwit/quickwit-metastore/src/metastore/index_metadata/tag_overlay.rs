@@ -0,0 +1,77 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use quickwit_query::query_ast::{BoolQuery, QueryAst};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// Maximum number of tag overlays per index.
+///
+/// Tag overlays are applied by rewriting the queries targeting their tag field, so the size of the
+/// rewritten queries grows with the number of overlays.
+pub const MAX_NUM_TAG_OVERLAYS_PER_INDEX: usize = 100;
+
+/// A tag overlay assigns a value to a tag field of the documents matching a query, without
+/// rewriting the splits.
+///
+/// Tag overlays are applied at search time, in their order of creation, to the queries targeting
+/// their tag field and to the source of the returned documents. For indexes with a timestamp
+/// field, the query of a tag overlay only matches documents whose timestamp is older than the
+/// creation of the overlay, so that the overlay does not apply to documents ingested later and can
+/// be dropped once its documents are past the retention period.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TagOverlay {
+    /// Query matching the documents to tag.
+    pub query_ast: QueryAst,
+    /// Name of the tag field.
+    pub field_name: String,
+    /// Value assigned to the tag field. `None` unsets the field.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_opt: Option<JsonValue>,
+    /// Time at which the tag overlay was created.
+    pub create_timestamp: i64,
+}
+
+impl TagOverlay {
+    /// Merges a tag overlay assigning the same value to the same field into this one. The merged
+    /// tag overlay matches the documents matched by either of them.
+    pub(crate) fn merge(&mut self, other: TagOverlay) {
+        debug_assert_eq!(self.field_name, other.field_name);
+        debug_assert_eq!(self.value_opt, other.value_opt);
+
+        match &mut self.query_ast {
+            QueryAst::Bool(bool_query) if is_disjunction(bool_query) => {
+                bool_query.should.push(other.query_ast);
+            }
+            _ => {
+                let query_ast = std::mem::replace(&mut self.query_ast, QueryAst::MatchNone);
+                self.query_ast = BoolQuery {
+                    should: vec![query_ast, other.query_ast],
+                    ..Default::default()
+                }
+                .into();
+            }
+        }
+        self.create_timestamp = self.create_timestamp.max(other.create_timestamp);
+    }
+}
+
+fn is_disjunction(bool_query: &BoolQuery) -> bool {
+    bool_query.must.is_empty()
+        && bool_query.must_not.is_empty()
+        && bool_query.filter.is_empty()
+        && !bool_query.should.is_empty()
+        && bool_query.minimum_should_match.is_none()
+}
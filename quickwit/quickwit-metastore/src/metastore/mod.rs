@@ -20,25 +20,27 @@ pub mod postgres;
 pub mod control_plane_metastore;
 
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::ops::{Bound, RangeInclusive};
 
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::TryStreamExt;
-pub use index_metadata::IndexMetadata;
+pub use index_metadata::{IndexMetadata, TagOverlay, MAX_NUM_TAG_OVERLAYS_PER_INDEX};
 use itertools::Itertools;
 use quickwit_common::thread_pool::run_cpu_intensive;
 use quickwit_config::{
-    DocMapping, FileSourceParams, IndexConfig, IndexingSettings, RetentionPolicy, SearchSettings,
-    SourceConfig, SourceParams,
+    DocMapping, FileSourceParams, IndexConfig, IndexLabelSelector, IndexRollout, IndexingSettings,
+    IngestionFreeze, RetentionPolicy, SearchSettings, SourceConfig, SourceParams,
 };
 use quickwit_doc_mapper::tag_pruning::TagFilterAst;
 use quickwit_proto::metastore::{
-    serde_utils, AddSourceRequest, CreateIndexRequest, CreateIndexResponse, DeleteTask,
-    IndexMetadataFailure, IndexMetadataRequest, IndexMetadataResponse, IndexesMetadataResponse,
-    ListIndexesMetadataResponse, ListSplitsRequest, ListSplitsResponse, MetastoreError,
-    MetastoreResult, MetastoreService, MetastoreServiceClient, MetastoreServiceStream,
-    PublishSplitsRequest, StageSplitsRequest, UpdateIndexRequest, UpdateSourceRequest,
+    serde_utils, AddSourceRequest, AddTagOverlayRequest, CreateIndexRequest, CreateIndexResponse,
+    DeleteTask, IndexMetadataFailure, IndexMetadataRequest, IndexMetadataResponse,
+    IndexesMetadataResponse, ListIndexesMetadataRequest, ListIndexesMetadataResponse,
+    ListSplitsRequest, ListSplitsResponse, MetastoreError, MetastoreResult, MetastoreService,
+    MetastoreServiceClient, MetastoreServiceStream, PublishSplitsRequest, StageSplitsRequest,
+    UpdateIndexRequest, UpdateSourceRequest,
 };
 use quickwit_proto::types::{IndexUid, NodeId, SplitId};
 use time::OffsetDateTime;
@@ -206,6 +208,18 @@ pub trait UpdateIndexRequestExt {
     /// Deserilalize the `doc_mapping_json` field of an `[UpdateIndexRequest]` into a
     /// [`DocMapping`] object.
     fn deserialize_doc_mapping(&self) -> MetastoreResult<DocMapping>;
+
+    /// Deserializes the `rollout_json` field of an [`UpdateIndexRequest`] into an
+    /// [`IndexRollout`] object, `None` if the rollout is left unchanged.
+    fn deserialize_rollout(&self) -> MetastoreResult<Option<IndexRollout>>;
+
+    /// Deserializes the `ingestion_freeze_json` field of an [`UpdateIndexRequest`] into an
+    /// optional [`IngestionFreeze`] object, `None` if the ingestion freeze is left unchanged.
+    fn deserialize_ingestion_freeze(&self) -> MetastoreResult<Option<Option<IngestionFreeze>>>;
+
+    /// Deserializes the `labels_json` field of an [`UpdateIndexRequest`] into the labels of the
+    /// index, `None` if the labels are left unchanged.
+    fn deserialize_labels(&self) -> MetastoreResult<Option<BTreeMap<String, String>>>;
 }
 
 impl UpdateIndexRequestExt for UpdateIndexRequest {
@@ -230,6 +244,9 @@ impl UpdateIndexRequestExt for UpdateIndexRequest {
             retention_policy_json,
             indexing_settings_json,
             doc_mapping_json,
+            rollout_json: None,
+            ingestion_freeze_json: None,
+            labels_json: None,
         };
         Ok(update_request)
     }
@@ -252,6 +269,48 @@ impl UpdateIndexRequestExt for UpdateIndexRequest {
     fn deserialize_doc_mapping(&self) -> MetastoreResult<DocMapping> {
         serde_utils::from_json_str(&self.doc_mapping_json)
     }
+
+    fn deserialize_rollout(&self) -> MetastoreResult<Option<IndexRollout>> {
+        self.rollout_json
+            .as_ref()
+            .map(|rollout| serde_utils::from_json_str(rollout))
+            .transpose()
+    }
+
+    fn deserialize_ingestion_freeze(&self) -> MetastoreResult<Option<Option<IngestionFreeze>>> {
+        self.ingestion_freeze_json
+            .as_ref()
+            .map(|ingestion_freeze| serde_utils::from_json_str(ingestion_freeze))
+            .transpose()
+    }
+
+    fn deserialize_labels(&self) -> MetastoreResult<Option<BTreeMap<String, String>>> {
+        self.labels_json
+            .as_ref()
+            .map(|labels| serde_utils::from_json_str(labels))
+            .transpose()
+    }
+}
+
+/// Helper trait to parse the payload of a [`ListIndexesMetadataRequest`].
+pub trait ListIndexesMetadataRequestExt {
+    /// Parses the `label_selectors` field of a [`ListIndexesMetadataRequest`].
+    fn parse_label_selectors(&self) -> MetastoreResult<Vec<IndexLabelSelector>>;
+}
+
+impl ListIndexesMetadataRequestExt for ListIndexesMetadataRequest {
+    fn parse_label_selectors(&self) -> MetastoreResult<Vec<IndexLabelSelector>> {
+        self.label_selectors
+            .iter()
+            .map(|label_selector| {
+                label_selector.parse().map_err(|error: anyhow::Error| {
+                    MetastoreError::InvalidArgument {
+                        message: error.to_string(),
+                    }
+                })
+            })
+            .collect()
+    }
 }
 
 /// Helper trait to build a [`IndexMetadataResponse`] and deserialize its payload.
@@ -421,6 +480,43 @@ impl AddSourceRequestExt for AddSourceRequest {
     }
 }
 
+/// Helper trait to build a [`AddTagOverlayRequest`] and deserialize its payload.
+pub trait AddTagOverlayRequestExt {
+    /// Creates a new [`AddTagOverlayRequest`] from a list of [`TagOverlay`].
+    fn try_from_tag_overlays(
+        index_uid: impl Into<IndexUid>,
+        tag_overlays: &[TagOverlay],
+    ) -> MetastoreResult<AddTagOverlayRequest>;
+
+    /// Deserializes the `tag_overlays_json` field of a [`AddTagOverlayRequest`] into a list of
+    /// [`TagOverlay`].
+    fn deserialize_tag_overlays(&self) -> MetastoreResult<Vec<TagOverlay>>;
+}
+
+impl AddTagOverlayRequestExt for AddTagOverlayRequest {
+    fn try_from_tag_overlays(
+        index_uid: impl Into<IndexUid>,
+        tag_overlays: &[TagOverlay],
+    ) -> MetastoreResult<AddTagOverlayRequest> {
+        let tag_overlays_json = tag_overlays
+            .iter()
+            .map(serde_utils::to_json_str)
+            .collect::<MetastoreResult<Vec<String>>>()?;
+        let request = Self {
+            index_uid: Some(index_uid.into()),
+            tag_overlays_json,
+        };
+        Ok(request)
+    }
+
+    fn deserialize_tag_overlays(&self) -> MetastoreResult<Vec<TagOverlay>> {
+        self.tag_overlays_json
+            .iter()
+            .map(|tag_overlay_json| serde_utils::from_json_str(tag_overlay_json))
+            .collect()
+    }
+}
+
 /// Helper trait to build a [`UpdateSourceRequest`] and deserialize its payload.
 pub trait UpdateSourceRequestExt {
     /// Creates a new [`UpdateSourceRequest`] from a [`SourceConfig`].
@@ -669,6 +765,7 @@ pub enum SortBy {
     None,
     Staleness,
     IndexUid,
+    Recency,
 }
 
 impl SortBy {
@@ -694,6 +791,19 @@ impl SortBy {
                         .split_id
                         .cmp(&right_split.split_metadata.split_id)
                 }),
+            SortBy::Recency => {
+                let recency_key = |split: &Split| {
+                    (
+                        split
+                            .split_metadata
+                            .time_range
+                            .as_ref()
+                            .map(|time_range| *time_range.end()),
+                        split.split_metadata.create_timestamp,
+                    )
+                };
+                recency_key(right_split).cmp(&recency_key(left_split))
+            }
         }
     }
 }
@@ -934,6 +1044,13 @@ impl ListSplitsQuery {
         self
     }
 
+    /// Sorts the splits from the most recent to the least recent, i.e. by end of time range and
+    /// creation timestamp in descending order. The splits without time range come last.
+    pub fn sort_by_recency(mut self) -> Self {
+        self.sort_by = SortBy::Recency;
+        self
+    }
+
     /// Only return splits whose (index_uid, split_id) are lexicographically after this split.
     /// This is only useful if results are sorted by index_uid and split_id.
     pub fn after_split(mut self, split_meta: &SplitMetadata) -> Self {
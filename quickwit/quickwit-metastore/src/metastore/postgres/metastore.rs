@@ -14,6 +14,7 @@
 
 use std::collections::HashMap;
 use std::fmt::{self, Write};
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
@@ -23,27 +24,29 @@ use quickwit_common::pretty::PrettySample;
 use quickwit_common::uri::Uri;
 use quickwit_common::{get_bool_from_env, rate_limited_error, ServiceStream};
 use quickwit_config::{
-    validate_index_id_pattern, IndexTemplate, IndexTemplateId, PostgresMetastoreConfig,
+    validate_index_id_pattern, IndexLabelSelector, IndexTemplate, IndexTemplateId,
+    PostgresMetastoreConfig,
 };
 use quickwit_proto::ingest::{Shard, ShardState};
 use quickwit_proto::metastore::{
-    serde_utils, AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest, CreateIndexRequest,
+    index_usage_period_start, serde_utils, AccumulateIndexUsageRequest, AcquireShardsRequest,
+    AcquireShardsResponse, AddSourceRequest, AddTagOverlayRequest, CreateIndexRequest,
     CreateIndexResponse, CreateIndexTemplateRequest, DeleteIndexRequest,
     DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest, DeleteShardsResponse,
     DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse, EntityKind,
     FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse, GetIndexTemplateRequest,
-    GetIndexTemplateResponse, IndexMetadataFailure, IndexMetadataFailureReason,
-    IndexMetadataRequest, IndexMetadataResponse, IndexTemplateMatch, IndexesMetadataRequest,
-    IndexesMetadataResponse, LastDeleteOpstampRequest, LastDeleteOpstampResponse,
-    ListDeleteTasksRequest, ListDeleteTasksResponse, ListIndexTemplatesRequest,
-    ListIndexTemplatesResponse, ListIndexesMetadataRequest, ListIndexesMetadataResponse,
-    ListShardsRequest, ListShardsResponse, ListShardsSubresponse, ListSplitsRequest,
-    ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError,
-    MetastoreResult, MetastoreService, MetastoreServiceStream, OpenShardSubrequest,
+    GetIndexTemplateResponse, GetIndexUsageRequest, GetIndexUsageResponse, IndexMetadataFailure,
+    IndexMetadataFailureReason, IndexMetadataRequest, IndexMetadataResponse, IndexTemplateMatch,
+    IndexUsage, IndexesMetadataRequest, IndexesMetadataResponse, LastDeleteOpstampRequest,
+    LastDeleteOpstampResponse, ListDeleteTasksRequest, ListDeleteTasksResponse,
+    ListIndexTemplatesRequest, ListIndexTemplatesResponse, ListIndexesMetadataRequest,
+    ListIndexesMetadataResponse, ListShardsRequest, ListShardsResponse, ListShardsSubresponse,
+    ListSplitsRequest, ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest,
+    MetastoreError, MetastoreResult, MetastoreService, MetastoreServiceStream, OpenShardSubrequest,
     OpenShardSubresponse, OpenShardsRequest, OpenShardsResponse, PruneShardsRequest,
     PublishSplitsRequest, ResetSourceCheckpointRequest, StageSplitsRequest, ToggleSourceRequest,
-    UpdateIndexRequest, UpdateSourceRequest, UpdateSplitsDeleteOpstampRequest,
-    UpdateSplitsDeleteOpstampResponse,
+    TrashIndexRequest, UndeleteIndexRequest, UpdateIndexRequest, UpdateSourceRequest,
+    UpdateSplitsDeleteOpstampRequest, UpdateSplitsDeleteOpstampResponse,
 };
 use quickwit_proto::types::{IndexId, IndexUid, Position, PublishToken, ShardId, SourceId};
 use sea_query::{Alias, Asterisk, Expr, Func, PostgresQueryBuilder, Query, UnionType};
@@ -55,7 +58,7 @@ use tracing::{debug, info, instrument, warn};
 use super::error::convert_sqlx_err;
 use super::migrator::run_migrations;
 use super::model::{PgDeleteTask, PgIndex, PgIndexTemplate, PgShard, PgSplit, Splits};
-use super::pool::TrackedPool;
+use super::pool::{spawn_health_check_task, PoolRole, PoolsHealth, TrackedPool};
 use super::split_stream::SplitStream;
 use super::utils::{append_query_filters_and_order_by, establish_connection};
 use super::{
@@ -69,13 +72,14 @@ use crate::file_backed::MutationOccurred;
 use crate::metastore::postgres::model::Shards;
 use crate::metastore::postgres::utils::split_maturity_timestamp;
 use crate::metastore::{
-    use_shard_api, IndexesMetadataResponseExt, PublishSplitsRequestExt, UpdateSourceRequestExt,
-    STREAM_SPLITS_CHUNK_SIZE,
+    use_shard_api, IndexesMetadataResponseExt, ListIndexesMetadataRequestExt,
+    PublishSplitsRequestExt, UpdateSourceRequestExt, STREAM_SPLITS_CHUNK_SIZE,
 };
 use crate::{
-    AddSourceRequestExt, CreateIndexRequestExt, IndexMetadata, IndexMetadataResponseExt,
-    ListIndexesMetadataResponseExt, ListSplitsRequestExt, ListSplitsResponseExt,
-    MetastoreServiceExt, Split, SplitState, StageSplitsRequestExt, UpdateIndexRequestExt,
+    AddSourceRequestExt, AddTagOverlayRequestExt, CreateIndexRequestExt, IndexMetadata,
+    IndexMetadataResponseExt, ListIndexesMetadataResponseExt, ListSplitsRequestExt,
+    ListSplitsResponseExt, MetastoreServiceExt, Split, SplitState, StageSplitsRequestExt,
+    UpdateIndexRequestExt,
 };
 
 /// PostgreSQL metastore implementation.
@@ -83,6 +87,8 @@ use crate::{
 pub struct PostgresqlMetastore {
     uri: Uri,
     connection_pool: TrackedPool<Postgres>,
+    read_replica_pool_opt: Option<TrackedPool<Postgres>>,
+    pools_health: Arc<PoolsHealth>,
 }
 
 impl fmt::Debug for PostgresqlMetastore {
@@ -110,6 +116,9 @@ impl PostgresqlMetastore {
         let max_lifetime_opt = postgres_metastore_config
             .max_connection_lifetime_opt()
             .expect("PostgreSQL metastore config should have been validated");
+        let health_check_interval = postgres_metastore_config
+            .health_check_interval()
+            .expect("PostgreSQL metastore config should have been validated");
 
         let read_only = get_bool_from_env(QW_POSTGRES_READ_ONLY_ENV_KEY, false);
         let skip_migrations = get_bool_from_env(QW_POSTGRES_SKIP_MIGRATIONS_ENV_KEY, false);
@@ -123,19 +132,60 @@ impl PostgresqlMetastore {
             idle_timeout_opt,
             max_lifetime_opt,
             read_only,
+            PoolRole::Primary,
         )
         .await?;
 
         run_migrations(&connection_pool, skip_migrations, skip_locking).await?;
 
+        let read_replica_pool_opt =
+            if let Some(read_replica_uri) = &postgres_metastore_config.read_replica_uri {
+                let read_replica_pool = establish_connection(
+                    read_replica_uri,
+                    min_connections,
+                    max_connections,
+                    acquire_timeout,
+                    idle_timeout_opt,
+                    max_lifetime_opt,
+                    true,
+                    PoolRole::ReadReplica,
+                )
+                .await?;
+                Some(read_replica_pool)
+            } else {
+                None
+            };
+        let pools_health = Arc::new(PoolsHealth::default());
+
+        spawn_health_check_task(
+            connection_pool.clone(),
+            read_replica_pool_opt.clone(),
+            &pools_health,
+            health_check_interval,
+        );
         let metastore = PostgresqlMetastore {
             uri: connection_uri.clone(),
             connection_pool,
+            read_replica_pool_opt,
+            pools_health,
         };
         Ok(metastore)
     }
 }
 
+impl PostgresqlMetastore {
+    /// Returns the connection pool for the read queries that tolerate replication lag: the read
+    /// replica's pool if it is configured and healthy, the primary's pool otherwise.
+    fn read_connection_pool(&self) -> &TrackedPool<Postgres> {
+        match &self.read_replica_pool_opt {
+            Some(read_replica_pool) if self.pools_health.read_replica_is_healthy() => {
+                read_replica_pool
+            }
+            _ => &self.connection_pool,
+        }
+    }
+}
+
 /// Returns an Index object given an index_id or None if it does not exist.
 async fn index_opt<'a, E>(
     executor: E,
@@ -394,7 +444,7 @@ impl MetastoreService for PostgresqlMetastore {
         }
         let index_metadata_json = serde_utils::to_json_str(&index_metadata)?;
 
-        sqlx::query(
+        let insert_result = sqlx::query(
             "INSERT INTO indexes (index_uid, index_id, index_metadata_json) VALUES ($1, $2, $3)",
         )
         .bind(index_metadata.index_uid.to_string())
@@ -402,7 +452,18 @@ impl MetastoreService for PostgresqlMetastore {
         .bind(&index_metadata_json)
         .execute(&self.connection_pool)
         .await
-        .map_err(|sqlx_error| convert_sqlx_err(index_metadata.index_id(), sqlx_error))?;
+        .map_err(|sqlx_error| convert_sqlx_err(index_metadata.index_id(), sqlx_error));
+
+        if let Err(MetastoreError::AlreadyExists(entity)) = insert_result {
+            // The index may be in the trash, in which case its ID is not available yet.
+            let metastore_error =
+                match index_opt(&self.connection_pool, index_metadata.index_id(), false).await {
+                    Ok(Some(pg_index)) => pg_index.index_metadata()?.already_exists_error(),
+                    _ => MetastoreError::AlreadyExists(entity),
+                };
+            return Err(metastore_error);
+        }
+        insert_result?;
 
         let response = CreateIndexResponse {
             index_uid: index_metadata.index_uid.into(),
@@ -419,6 +480,9 @@ impl MetastoreService for PostgresqlMetastore {
         let search_settings = request.deserialize_search_settings()?;
         let indexing_settings = request.deserialize_indexing_settings()?;
         let doc_mapping = request.deserialize_doc_mapping()?;
+        let rollout_opt = request.deserialize_rollout()?;
+        let ingestion_freeze_opt = request.deserialize_ingestion_freeze()?;
+        let labels_opt = request.deserialize_labels()?;
 
         let index_uid: IndexUid = request.index_uid().clone();
         let updated_index_metadata = run_with_tx!(self.connection_pool, tx, "update index", {
@@ -428,6 +492,16 @@ impl MetastoreService for PostgresqlMetastore {
                 mutation_occurred |= index_metadata.set_search_settings(search_settings);
                 mutation_occurred |= index_metadata.set_indexing_settings(indexing_settings);
                 mutation_occurred |= index_metadata.set_doc_mapping(doc_mapping);
+
+                if let Some(rollout) = rollout_opt {
+                    mutation_occurred |= index_metadata.set_rollout(rollout);
+                }
+                if let Some(ingestion_freeze) = ingestion_freeze_opt {
+                    mutation_occurred |= index_metadata.set_ingestion_freeze(ingestion_freeze);
+                }
+                if let Some(labels) = labels_opt {
+                    mutation_occurred |= index_metadata.set_labels(labels);
+                }
                 Ok(MutationOccurred::from(mutation_occurred))
             })
             .await
@@ -458,6 +532,12 @@ impl MetastoreService for PostgresqlMetastore {
                     .expect("`index_id` or `index_uid` should be set"),
             }))?
             .index_metadata()?;
+
+        if index_metadata.is_trashed() {
+            return Err(MetastoreError::NotFound(EntityKind::Index {
+                index_id: index_metadata.index_id().to_string(),
+            }));
+        }
         let response = IndexMetadataResponse::try_from_index_metadata(&index_metadata)?;
         Ok(response)
     }
@@ -531,6 +611,19 @@ impl MetastoreService for PostgresqlMetastore {
                 }
             }
         }
+        let (trashed_indexes_metadata, indexes_metadata): (Vec<IndexMetadata>, Vec<IndexMetadata>) =
+            indexes_metadata
+                .into_iter()
+                .partition(|index_metadata| index_metadata.is_trashed());
+
+        for index_metadata in trashed_indexes_metadata {
+            let failure = IndexMetadataFailure {
+                index_id: Some(index_metadata.index_id().to_string()),
+                index_uid: Some(index_metadata.index_uid),
+                reason: IndexMetadataFailureReason::NotFound as i32,
+            };
+            failures.push(failure);
+        }
         let response =
             IndexesMetadataResponse::try_from_indexes_metadata(indexes_metadata, failures).await?;
         Ok(response)
@@ -541,20 +634,34 @@ impl MetastoreService for PostgresqlMetastore {
         &self,
         request: ListIndexesMetadataRequest,
     ) -> MetastoreResult<ListIndexesMetadataResponse> {
-        let sql =
+        let label_selectors = request.parse_label_selectors()?;
+        let mut sql =
             build_index_id_patterns_sql_query(&request.index_id_patterns).map_err(|error| {
                 MetastoreError::Internal {
                     message: "failed to build `list_indexes_metadata` SQL query".to_string(),
                     cause: error.to_string(),
                 }
             })?;
-        let pg_indexes = sqlx::query_as::<_, PgIndex>(&sql)
-            .fetch_all(&self.connection_pool)
-            .await?;
-        let indexes_metadata: Vec<IndexMetadata> = pg_indexes
+        let (label_selectors_filter, label_selectors_params) =
+            build_label_selectors_sql_filter(&label_selectors);
+
+        if !label_selectors.is_empty() {
+            sql = format!("SELECT * FROM ({sql}) AS indexes WHERE {label_selectors_filter}");
+        }
+        let mut query = sqlx::query_as::<_, PgIndex>(&sql);
+
+        for label_selectors_param in label_selectors_params {
+            query = query.bind(label_selectors_param);
+        }
+        let pg_indexes = query.fetch_all(&self.connection_pool).await?;
+        let mut indexes_metadata: Vec<IndexMetadata> = pg_indexes
             .into_iter()
             .map(|pg_index| pg_index.index_metadata())
             .collect::<MetastoreResult<_>>()?;
+
+        if !request.include_trashed {
+            indexes_metadata.retain(|index_metadata| !index_metadata.is_trashed());
+        }
         let response =
             ListIndexesMetadataResponse::try_from_indexes_metadata(indexes_metadata).await?;
         Ok(response)
@@ -577,6 +684,35 @@ impl MetastoreService for PostgresqlMetastore {
         Ok(EmptyResponse {})
     }
 
+    #[instrument(skip_all, fields(index_id=%request.index_uid()))]
+    async fn trash_index(&self, request: TrashIndexRequest) -> MetastoreResult<EmptyResponse> {
+        let index_uid: IndexUid = request.index_uid().clone();
+        run_with_tx!(self.connection_pool, tx, "trash index", {
+            mutate_index_metadata::<MetastoreError, _>(tx, index_uid, |index_metadata| {
+                Ok(MutationOccurred::from(index_metadata.trash()))
+            })
+            .await?;
+            Ok(())
+        })?;
+        Ok(EmptyResponse {})
+    }
+
+    #[instrument(skip_all, fields(index_id=%request.index_id))]
+    async fn undelete_index(
+        &self,
+        request: UndeleteIndexRequest,
+    ) -> MetastoreResult<IndexMetadataResponse> {
+        let index_metadata = run_with_tx!(self.connection_pool, tx, "undelete index", {
+            let index_uid = index_metadata(tx, &request.index_id, true).await?.index_uid;
+            mutate_index_metadata::<MetastoreError, _>(tx, index_uid, |index_metadata| {
+                index_metadata.undelete()?;
+                Ok(MutationOccurred::Yes(()))
+            })
+            .await
+        })?;
+        IndexMetadataResponse::try_from_index_metadata(&index_metadata)
+    }
+
     #[instrument(skip_all, fields(split_ids))]
     async fn stage_splits(&self, request: StageSplitsRequest) -> MetastoreResult<EmptyResponse> {
         let index_uid: IndexUid = request.index_uid().clone();
@@ -817,8 +953,8 @@ impl MetastoreService for PostgresqlMetastore {
                 sqlx::query_as(PUBLISH_SPLITS_QUERY)
                     .bind(&index_uid)
                     .bind(index_metadata_json)
-                    .bind(staged_split_ids)
-                    .bind(replaced_split_ids)
+                    .bind(&staged_split_ids)
+                    .bind(&replaced_split_ids)
                     .fetch_one(tx.as_mut())
                     .await
                     .map_err(|sqlx_error| convert_sqlx_err(&index_uid.index_id, sqlx_error))?;
@@ -842,6 +978,22 @@ impl MetastoreService for PostgresqlMetastore {
                 let message = "splits are not marked for deletion".to_string();
                 return Err(MetastoreError::FailedPrecondition { entity, message });
             }
+            // Merged splits replace existing splits: only the splits that do not replace any other
+            // split carry newly ingested documents.
+            if replaced_split_ids.is_empty() && !staged_split_ids.is_empty() {
+                const ACCUMULATE_PUBLISHED_SPLITS_USAGE_QUERY: &str =
+                    include_str!("queries/index_usage/accumulate_published_splits.sql");
+
+                let period_start_timestamp =
+                    index_usage_period_start(OffsetDateTime::now_utc().unix_timestamp());
+
+                sqlx::query(ACCUMULATE_PUBLISHED_SPLITS_USAGE_QUERY)
+                    .bind(&index_uid)
+                    .bind(&staged_split_ids)
+                    .bind(period_start_timestamp)
+                    .execute(tx.as_mut())
+                    .await?;
+            }
             info!(
                 %index_uid,
                 "published {num_published_splits} splits and marked {num_marked_splits} for deletion successfully"
@@ -862,7 +1014,7 @@ impl MetastoreService for PostgresqlMetastore {
 
         let (sql_query, values) = sql_query_builder.build_sqlx(PostgresQueryBuilder);
         let pg_split_stream = SplitStream::new(
-            self.connection_pool.clone(),
+            self.read_connection_pool().clone(),
             sql_query,
             |connection_pool: &TrackedPool<Postgres>, sql_query: &String| {
                 sqlx::query_as_with::<_, PgSplit, _>(sql_query, values).fetch(connection_pool)
@@ -1661,6 +1813,118 @@ impl MetastoreService for PostgresqlMetastore {
             .await?;
         Ok(EmptyResponse {})
     }
+
+    async fn accumulate_index_usage(
+        &self,
+        request: AccumulateIndexUsageRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        const ACCUMULATE_INDEX_USAGE_QUERY: &str =
+            include_str!("queries/index_usage/accumulate.sql");
+
+        let num_subrequests = request.subrequests.len();
+        let mut index_uids: Vec<IndexUid> = Vec::with_capacity(num_subrequests);
+        let mut period_start_timestamps: Vec<i64> = Vec::with_capacity(num_subrequests);
+        let mut num_docs_ingested: Vec<i64> = Vec::with_capacity(num_subrequests);
+        let mut num_bytes_ingested: Vec<i64> = Vec::with_capacity(num_subrequests);
+        let mut num_queries: Vec<i64> = Vec::with_capacity(num_subrequests);
+        let mut num_bytes_scanned: Vec<i64> = Vec::with_capacity(num_subrequests);
+
+        for subrequest in request.subrequests {
+            let Some(usage_delta) = subrequest.usage_delta else {
+                continue;
+            };
+            if usage_delta.is_empty() {
+                continue;
+            }
+            let Some(index_uid) = subrequest.index_uid else {
+                continue;
+            };
+            index_uids.push(index_uid);
+            period_start_timestamps.push(subrequest.period_start_timestamp);
+            num_docs_ingested.push(usage_delta.num_docs_ingested as i64);
+            num_bytes_ingested.push(usage_delta.num_bytes_ingested as i64);
+            num_queries.push(usage_delta.num_queries as i64);
+            num_bytes_scanned.push(usage_delta.num_bytes_scanned as i64);
+        }
+        if index_uids.is_empty() {
+            return Ok(EmptyResponse {});
+        }
+        sqlx::query(ACCUMULATE_INDEX_USAGE_QUERY)
+            .bind(&index_uids)
+            .bind(&period_start_timestamps)
+            .bind(&num_docs_ingested)
+            .bind(&num_bytes_ingested)
+            .bind(&num_queries)
+            .bind(&num_bytes_scanned)
+            .execute(&self.connection_pool)
+            .await?;
+        Ok(EmptyResponse {})
+    }
+
+    async fn get_index_usage(
+        &self,
+        request: GetIndexUsageRequest,
+    ) -> MetastoreResult<GetIndexUsageResponse> {
+        const GET_INDEX_USAGE_QUERY: &str = r#"
+            SELECT
+                COALESCE(SUM(num_docs_ingested), 0)::BIGINT,
+                COALESCE(SUM(num_bytes_ingested), 0)::BIGINT,
+                COALESCE(SUM(num_queries), 0)::BIGINT,
+                COALESCE(SUM(num_bytes_scanned), 0)::BIGINT
+            FROM indexes
+            LEFT JOIN index_usage
+                ON index_usage.index_uid = indexes.index_uid
+                AND ($2::BIGINT IS NULL OR period_start_timestamp >= $2)
+                AND ($3::BIGINT IS NULL OR period_start_timestamp < $3)
+            WHERE indexes.index_uid = $1
+            GROUP BY indexes.index_uid
+        "#;
+        let index_uid = request.index_uid();
+        let start_period_opt = request.start_timestamp.map(index_usage_period_start);
+        let end_period_opt = request.end_timestamp.map(index_usage_period_start);
+
+        let pg_usage_opt: Option<(i64, i64, i64, i64)> = sqlx::query_as(GET_INDEX_USAGE_QUERY)
+            .bind(index_uid)
+            .bind(start_period_opt)
+            .bind(end_period_opt)
+            .fetch_optional(&self.connection_pool)
+            .await?;
+        let Some((num_docs_ingested, num_bytes_ingested, num_queries, num_bytes_scanned)) =
+            pg_usage_opt
+        else {
+            return Err(MetastoreError::NotFound(EntityKind::Index {
+                index_id: index_uid.index_id.clone(),
+            }));
+        };
+        let usage = IndexUsage {
+            num_docs_ingested: num_docs_ingested as u64,
+            num_bytes_ingested: num_bytes_ingested as u64,
+            num_queries: num_queries as u64,
+            num_bytes_scanned: num_bytes_scanned as u64,
+        };
+        let response = GetIndexUsageResponse { usage: Some(usage) };
+        Ok(response)
+    }
+
+    // Tag overlay API
+
+    #[instrument(skip(self))]
+    async fn add_tag_overlay(
+        &self,
+        request: AddTagOverlayRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let tag_overlays = request.deserialize_tag_overlays()?;
+        let index_uid: IndexUid = request.index_uid().clone();
+        run_with_tx!(self.connection_pool, tx, "add tag overlays", {
+            mutate_index_metadata::<MetastoreError, _>(tx, index_uid, |index_metadata| {
+                index_metadata.add_tag_overlays(tag_overlays)?;
+                Ok(MutationOccurred::Yes(()))
+            })
+            .await?;
+            Ok(())
+        })?;
+        Ok(EmptyResponse {})
+    }
 }
 
 async fn open_or_fetch_shard<'e>(
@@ -1778,6 +2042,32 @@ fn build_index_id_patterns_sql_query(index_id_patterns: &[String]) -> anyhow::Re
     ))
 }
 
+/// Builds the SQL filter selecting the indexes whose labels match all the label selectors. The
+/// labels are read from the index metadata JSON. The label keys and values are returned as
+/// parameters to bind in order, starting at `$1`.
+fn build_label_selectors_sql_filter(label_selectors: &[IndexLabelSelector]) -> (String, Vec<&str>) {
+    let mut params: Vec<&str> = Vec::new();
+
+    let filter = label_selectors
+        .iter()
+        .map(|label_selector| {
+            params.push(&label_selector.key);
+            let label_value_sql = format!(
+                "index_metadata_json::jsonb -> 'index_config' -> 'labels' ->> ${}",
+                params.len()
+            );
+            match &label_selector.value_opt {
+                Some(value) => {
+                    params.push(value);
+                    format!("{label_value_sql} = ${}", params.len())
+                }
+                None => format!("{label_value_sql} IS NOT NULL"),
+            }
+        })
+        .join(" AND ");
+    (filter, params)
+}
+
 /// A postgres metastore factory
 #[cfg(test)]
 #[async_trait]
@@ -1818,7 +2108,9 @@ mod tests {
     use time::OffsetDateTime;
 
     use super::*;
-    use crate::metastore::postgres::metastore::build_index_id_patterns_sql_query;
+    use crate::metastore::postgres::metastore::{
+        build_index_id_patterns_sql_query, build_label_selectors_sql_filter,
+    };
     use crate::metastore::postgres::model::{PgShard, Splits};
     use crate::tests::shard::ReadWriteShardsForTest;
     use crate::tests::DefaultForTest;
@@ -2239,4 +2531,16 @@ mod tests {
              'index-2-last'"
         );
     }
+
+    #[test]
+    fn test_label_selectors_sql_filter() {
+        let label_selectors = ["team".parse().unwrap(), "env:o'prod".parse().unwrap()];
+        let (filter, params) = build_label_selectors_sql_filter(&label_selectors);
+        assert_eq!(
+            filter,
+            "index_metadata_json::jsonb -> 'index_config' -> 'labels' ->> $1 IS NOT NULL AND \
+             index_metadata_json::jsonb -> 'index_config' -> 'labels' ->> $2 = $3"
+        );
+        assert_eq!(params, ["team", "env", "o'prod"]);
+    }
 }
@@ -13,35 +13,54 @@
 // limitations under the License.
 
 use once_cell::sync::Lazy;
-use quickwit_common::metrics::{new_gauge, IntGauge};
+use quickwit_common::metrics::{new_counter, new_gauge_vec, IntCounter, IntGaugeVec};
 
 #[derive(Clone)]
 pub(super) struct PostgresMetrics {
-    pub acquire_connections: IntGauge,
-    pub active_connections: IntGauge,
-    pub idle_connections: IntGauge,
+    pub acquire_connections: IntGaugeVec<1>,
+    pub active_connections: IntGaugeVec<1>,
+    pub idle_connections: IntGaugeVec<1>,
+    pub healthy_pools: IntGaugeVec<1>,
+    pub read_replica_failovers_total: IntCounter,
 }
 
 impl Default for PostgresMetrics {
     fn default() -> Self {
         Self {
-            acquire_connections: new_gauge(
+            acquire_connections: new_gauge_vec(
                 "acquire_connections",
                 "Number of connections being acquired.",
                 "metastore",
                 &[],
+                ["pool"],
             ),
-            active_connections: new_gauge(
+            active_connections: new_gauge_vec(
                 "active_connections",
                 "Number of active (used + idle) connections.",
                 "metastore",
                 &[],
+                ["pool"],
             ),
-            idle_connections: new_gauge(
+            idle_connections: new_gauge_vec(
                 "idle_connections",
                 "Number of idle connections.",
                 "metastore",
                 &[],
+                ["pool"],
+            ),
+            healthy_pools: new_gauge_vec(
+                "healthy_pools",
+                "Whether the connection pool passed its last health check (1) or not (0).",
+                "metastore",
+                &[],
+                ["pool"],
+            ),
+            read_replica_failovers_total: new_counter(
+                "read_replica_failovers_total",
+                "Number of times the read queries failed over from the read replica to the \
+                 primary.",
+                "metastore",
+                &[],
             ),
         }
     }
@@ -157,6 +157,7 @@ mod tests {
     use sqlx::Acquire;
 
     use super::{get_migrations, run_migrations};
+    use crate::metastore::postgres::pool::PoolRole;
     use crate::metastore::postgres::utils::establish_connection;
 
     #[tokio::test]
@@ -171,10 +172,18 @@ mod tests {
             .expect("environment variable `QW_TEST_DATABASE_URL` should be a valid URI");
 
         {
-            let connection_pool =
-                establish_connection(&uri, 1, 5, Duration::from_secs(2), None, None, false)
-                    .await
-                    .unwrap();
+            let connection_pool = establish_connection(
+                &uri,
+                1,
+                5,
+                Duration::from_secs(2),
+                None,
+                None,
+                false,
+                PoolRole::Primary,
+            )
+            .await
+            .unwrap();
             // make sure migrations are run
             run_migrations(&connection_pool, false, false)
                 .await
@@ -215,10 +224,18 @@ mod tests {
         }
 
         {
-            let connection_pool =
-                establish_connection(&uri, 1, 5, Duration::from_secs(2), None, None, true)
-                    .await
-                    .unwrap();
+            let connection_pool = establish_connection(
+                &uri,
+                1,
+                5,
+                Duration::from_secs(2),
+                None,
+                None,
+                true,
+                PoolRole::Primary,
+            )
+            .await
+            .unwrap();
             // error because we are in read only mode, and we try to run migrations
             run_migrations(&connection_pool, false, false)
                 .await
@@ -12,26 +12,72 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
 use futures::future::BoxFuture;
 use futures::stream::BoxStream;
-use quickwit_common::metrics::GaugeGuard;
+use quickwit_common::metrics::OwnedGaugeGuard;
 use sqlx::database::HasStatement;
 use sqlx::pool::maybe::MaybePoolConnection;
 use sqlx::pool::PoolConnection;
 use sqlx::{
     Acquire, Database, Describe, Either, Error, Execute, Executor, Pool, Postgres, Transaction,
 };
+use tracing::{info, warn};
 
 use super::metrics::POSTGRES_METRICS;
 
+/// Role of the database behind a connection pool. Used to label the pool metrics.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum PoolRole {
+    Primary,
+    ReadReplica,
+}
+
+impl PoolRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Primary => "primary",
+            Self::ReadReplica => "read_replica",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(super) struct TrackedPool<DB: Database> {
     inner_pool: Pool<DB>,
+    pool_role: PoolRole,
 }
 
 impl TrackedPool<Postgres> {
-    pub fn new(inner_pool: Pool<Postgres>) -> Self {
-        Self { inner_pool }
+    pub fn new(inner_pool: Pool<Postgres>, pool_role: PoolRole) -> Self {
+        Self {
+            inner_pool,
+            pool_role,
+        }
+    }
+
+    /// Runs a trivial query against the database and records the outcome in the pool health
+    /// metric.
+    pub async fn check_health(&self) -> bool {
+        let is_healthy = match sqlx::query("SELECT 1").execute(self).await {
+            Ok(_) => true,
+            Err(error) => {
+                warn!(
+                    pool = self.pool_role.as_str(),
+                    %error,
+                    "PostgreSQL connection pool failed its health check"
+                );
+                false
+            }
+        };
+        POSTGRES_METRICS
+            .healthy_pools
+            .with_label_values([self.pool_role.as_str()])
+            .set(is_healthy as i64);
+        is_healthy
     }
 }
 
@@ -39,10 +85,66 @@ impl<DB: Database> Clone for TrackedPool<DB> {
     fn clone(&self) -> Self {
         Self {
             inner_pool: self.inner_pool.clone(),
+            pool_role: self.pool_role,
         }
     }
 }
 
+/// Health of the connection pools, updated by the health check task.
+#[derive(Debug, Default)]
+pub(super) struct PoolsHealth {
+    read_replica_is_healthy: AtomicBool,
+}
+
+impl PoolsHealth {
+    pub fn read_replica_is_healthy(&self) -> bool {
+        self.read_replica_is_healthy.load(Ordering::Relaxed)
+    }
+
+    fn set_read_replica_health(&self, is_healthy: bool) {
+        let was_healthy = self
+            .read_replica_is_healthy
+            .swap(is_healthy, Ordering::Relaxed);
+
+        if was_healthy && !is_healthy {
+            warn!("read replica is unhealthy, failing over read queries to the primary");
+            POSTGRES_METRICS.read_replica_failovers_total.inc();
+        } else if !was_healthy && is_healthy {
+            info!("read replica is healthy, sending read queries to the read replica");
+        }
+    }
+}
+
+/// Spawns a task that health checks the connection pools every `health_check_interval`. The task
+/// stops once the metastore owning `pools_health` is dropped.
+pub(super) fn spawn_health_check_task(
+    primary_pool: TrackedPool<Postgres>,
+    read_replica_pool_opt: Option<TrackedPool<Postgres>>,
+    pools_health: &Arc<PoolsHealth>,
+    health_check_interval: Duration,
+) {
+    let pools_health_weak: Weak<PoolsHealth> = Arc::downgrade(pools_health);
+
+    let future = async move {
+        let mut interval = tokio::time::interval(health_check_interval);
+
+        loop {
+            interval.tick().await;
+
+            let Some(pools_health) = pools_health_weak.upgrade() else {
+                return;
+            };
+            primary_pool.check_health().await;
+
+            if let Some(read_replica_pool) = &read_replica_pool_opt {
+                let is_healthy = read_replica_pool.check_health().await;
+                pools_health.set_read_replica_health(is_healthy);
+            }
+        }
+    };
+    tokio::spawn(future);
+}
+
 impl<'a, DB: Database> Acquire<'a> for &TrackedPool<DB> {
     type Database = DB;
 
@@ -50,16 +152,23 @@ impl<'a, DB: Database> Acquire<'a> for &TrackedPool<DB> {
 
     fn acquire(self) -> BoxFuture<'static, Result<Self::Connection, Error>> {
         let acquire_conn_fut = self.inner_pool.acquire();
+        let pool_role = self.pool_role.as_str();
 
         POSTGRES_METRICS
             .active_connections
+            .with_label_values([pool_role])
             .set(self.inner_pool.size() as i64);
         POSTGRES_METRICS
             .idle_connections
+            .with_label_values([pool_role])
             .set(self.inner_pool.num_idle() as i64);
 
+        let acquire_connections_gauge = POSTGRES_METRICS
+            .acquire_connections
+            .with_label_values([pool_role]);
+
         Box::pin(async move {
-            let mut gauge_guard = GaugeGuard::from_gauge(&POSTGRES_METRICS.acquire_connections);
+            let mut gauge_guard = OwnedGaugeGuard::from_gauge(acquire_connections_gauge);
             gauge_guard.add(1);
 
             let conn = acquire_conn_fut.await?;
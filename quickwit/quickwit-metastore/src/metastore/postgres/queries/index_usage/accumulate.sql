@@ -0,0 +1,22 @@
+INSERT INTO index_usage (index_uid, period_start_timestamp, num_docs_ingested, num_bytes_ingested, num_queries, num_bytes_scanned)
+SELECT
+    index_uid,
+    period_start_timestamp,
+    SUM(num_docs_ingested)::BIGINT,
+    SUM(num_bytes_ingested)::BIGINT,
+    SUM(num_queries)::BIGINT,
+    SUM(num_bytes_scanned)::BIGINT
+FROM
+    UNNEST($1, $2, $3, $4, $5, $6)
+        AS deltas(index_uid, period_start_timestamp, num_docs_ingested, num_bytes_ingested, num_queries, num_bytes_scanned)
+    -- Skip the indexes that have been deleted in the meantime.
+    JOIN indexes USING (index_uid)
+GROUP BY
+    index_uid,
+    period_start_timestamp
+ON CONFLICT (index_uid, period_start_timestamp) DO UPDATE
+SET
+    num_docs_ingested = index_usage.num_docs_ingested + excluded.num_docs_ingested,
+    num_bytes_ingested = index_usage.num_bytes_ingested + excluded.num_bytes_ingested,
+    num_queries = index_usage.num_queries + excluded.num_queries,
+    num_bytes_scanned = index_usage.num_bytes_scanned + excluded.num_bytes_scanned
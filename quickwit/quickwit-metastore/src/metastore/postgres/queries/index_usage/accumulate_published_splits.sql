@@ -0,0 +1,15 @@
+INSERT INTO index_usage (index_uid, period_start_timestamp, num_docs_ingested, num_bytes_ingested)
+SELECT
+    $1,
+    $3,
+    COALESCE(SUM((split_metadata_json::jsonb->>'num_docs')::BIGINT), 0)::BIGINT,
+    COALESCE(SUM((split_metadata_json::jsonb->>'uncompressed_docs_size_in_bytes')::BIGINT), 0)::BIGINT
+FROM
+    splits
+WHERE
+    index_uid = $1
+    AND split_id = ANY($2)
+ON CONFLICT (index_uid, period_start_timestamp) DO UPDATE
+SET
+    num_docs_ingested = index_usage.num_docs_ingested + excluded.num_docs_ingested,
+    num_bytes_ingested = index_usage.num_bytes_ingested + excluded.num_bytes_ingested
@@ -19,19 +19,23 @@ use std::time::Duration;
 
 use quickwit_common::uri::Uri;
 use quickwit_proto::metastore::{MetastoreError, MetastoreResult};
-use sea_query::{any, Expr, Func, Order, SelectStatement};
+use sea_query::{any, Expr, Func, NullOrdering, Order, SelectStatement};
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use sqlx::{ConnectOptions, Postgres};
 use tracing::error;
 use tracing::log::LevelFilter;
 
 use super::model::{Splits, ToTimestampFunc};
-use super::pool::TrackedPool;
+use super::pool::{PoolRole, TrackedPool};
 use super::tags::generate_sql_condition;
 use crate::metastore::{FilterRange, SortBy};
 use crate::{ListSplitsQuery, SplitMaturity, SplitMetadata};
 
 /// Establishes a connection to the given database URI.
+///
+/// The connections to a read replica are established lazily, so that an unavailable replica does
+/// not prevent the metastore from starting.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn establish_connection(
     connection_uri: &Uri,
     min_connections: usize,
@@ -40,6 +44,7 @@ pub(super) async fn establish_connection(
     idle_timeout_opt: Option<Duration>,
     max_lifetime_opt: Option<Duration>,
     read_only: bool,
+    pool_role: PoolRole,
 ) -> MetastoreResult<TrackedPool<Postgres>> {
     let pool_options = PgPoolOptions::new()
         .min_connections(min_connections as u32)
@@ -57,6 +62,10 @@ pub(super) async fn establish_connection(
         // this isn't a security mechanism, only a safeguard against involontary missuse
         connect_options = connect_options.options([("default_transaction_read_only", "on")]);
     }
+    if pool_role == PoolRole::ReadReplica {
+        let sqlx_pool = pool_options.connect_lazy_with(connect_options);
+        return Ok(TrackedPool::new(sqlx_pool, pool_role));
+    }
     let sqlx_pool = pool_options
         .connect_with(connect_options)
         .await
@@ -66,7 +75,7 @@ pub(super) async fn establish_connection(
                 message: error.to_string(),
             }
         })?;
-    let tracked_pool = TrackedPool::new(sqlx_pool);
+    let tracked_pool = TrackedPool::new(sqlx_pool, pool_role);
     Ok(tracked_pool)
 }
 
@@ -207,6 +216,10 @@ pub(super) fn append_query_filters_and_order_by(
             sql.order_by(Splits::IndexUid, Order::Asc)
                 .order_by(Splits::SplitId, Order::Asc);
         }
+        SortBy::Recency => {
+            sql.order_by_with_nulls(Splits::TimeRangeEnd, Order::Desc, NullOrdering::Last)
+                .order_by(Splits::CreateTimestamp, Order::Desc);
+        }
         SortBy::None => (),
     }
 
@@ -132,6 +132,10 @@ pub struct SplitMetadata {
     /// Doc mapping UID used when creating this split. This split may only be merged with other
     /// splits using the same doc mapping UID.
     pub doc_mapping_uid: DocMappingUid,
+
+    /// CRC32C checksum of the split file, computed before it is uploaded. It allows auditing the
+    /// integrity of the split file in the storage. `None` for splits created by older versions.
+    pub crc32c_checksum: Option<u32>,
 }
 
 impl fmt::Debug for SplitMetadata {
@@ -176,6 +180,9 @@ impl fmt::Debug for SplitMetadata {
         debug_struct.field("footer_offsets", &self.footer_offsets);
         debug_struct.field("delete_opstamp", &self.delete_opstamp);
         debug_struct.field("num_merge_ops", &self.num_merge_ops);
+        if let Some(crc32c_checksum) = self.crc32c_checksum {
+            debug_struct.field("crc32c_checksum", &crc32c_checksum);
+        }
         debug_struct.finish()
     }
 }
@@ -281,6 +288,7 @@ impl quickwit_config::TestableForRegression for SplitMetadata {
             footer_offsets: 1000..2000,
             num_merge_ops: 3,
             doc_mapping_uid: DocMappingUid::default(),
+            crc32c_checksum: None,
         }
     }
 
@@ -421,6 +429,7 @@ mod tests {
             delete_opstamp: 0,
             num_merge_ops: 0,
             doc_mapping_uid: DocMappingUid::default(),
+            crc32c_checksum: None,
         };
 
         let expected_output = "SplitMetadata { split_id: \"split-1\", index_uid: IndexUid { \
@@ -19,28 +19,36 @@
 //  - index_metadata
 //  - list_indexes
 //  - delete_index
+//  - trash_index
+//  - undelete_index
+//  - add_tag_overlay
+
+use std::collections::BTreeMap;
 
 use quickwit_common::rand::append_random_suffix;
 use quickwit_config::merge_policy_config::{MergePolicyConfig, StableLogMergePolicyConfig};
 use quickwit_config::{
-    IndexConfig, IndexingSettings, RetentionPolicy, SearchSettings, SourceConfig, CLI_SOURCE_ID,
-    INGEST_V2_SOURCE_ID,
+    IndexConfig, IndexRollout, IndexingSettings, IngestionFreeze, RetentionPolicy, RolloutState,
+    SearchSettings, SourceConfig, CLI_SOURCE_ID, INGEST_V2_SOURCE_ID,
 };
 use quickwit_doc_mapper::{Cardinality, FieldMappingEntry, FieldMappingType, QuickwitJsonOptions};
 use quickwit_proto::metastore::{
-    CreateIndexRequest, DeleteIndexRequest, EntityKind, IndexMetadataFailure,
+    AddTagOverlayRequest, CreateIndexRequest, DeleteIndexRequest, EntityKind, IndexMetadataFailure,
     IndexMetadataFailureReason, IndexMetadataRequest, IndexMetadataSubrequest,
     IndexesMetadataRequest, ListIndexesMetadataRequest, MetastoreError, MetastoreService,
-    StageSplitsRequest, UpdateIndexRequest,
+    StageSplitsRequest, TrashIndexRequest, UndeleteIndexRequest, UpdateIndexRequest,
 };
 use quickwit_proto::types::{DocMappingUid, IndexUid};
+use quickwit_query::query_ast::{BoolQuery, TermQuery};
+use serde_json::Value as JsonValue;
+use time::OffsetDateTime;
 
 use super::DefaultForTest;
 use crate::tests::cleanup_index;
 use crate::{
-    CreateIndexRequestExt, IndexMetadataResponseExt, IndexesMetadataResponseExt,
-    ListIndexesMetadataResponseExt, MetastoreServiceExt, SplitMetadata, StageSplitsRequestExt,
-    UpdateIndexRequestExt,
+    AddTagOverlayRequestExt, CreateIndexRequestExt, IndexMetadataResponseExt,
+    IndexesMetadataResponseExt, ListIndexesMetadataResponseExt, MetastoreServiceExt, SplitMetadata,
+    StageSplitsRequestExt, TagOverlay, UpdateIndexRequestExt, MAX_NUM_TAG_OVERLAYS_PER_INDEX,
 };
 
 pub async fn test_metastore_create_index<
@@ -149,6 +157,210 @@ pub async fn test_metastore_update_retention_policy<
     cleanup_index(&mut metastore, index_uid).await;
 }
 
+pub async fn test_metastore_update_rollout<
+    MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let (mut metastore, index_uid, index_config) =
+        setup_metastore_for_update::<MetastoreToTest>().await;
+    let mut rollout = IndexRollout {
+        primary_index_id: "test-primary-index".to_string(),
+        ingest_percentage: 10.0,
+        search_percentage: 0.0,
+        evaluation_period: "1 hour".to_string(),
+        min_num_docs: 0,
+        max_storage_ratio: None,
+        max_scanned_bytes_ratio: None,
+        state: RolloutState::Evaluating,
+    };
+    let mut index_update = UpdateIndexRequest::try_from_updates(
+        index_uid.clone(),
+        &index_config.search_settings,
+        &index_config.retention_policy_opt,
+        &index_config.indexing_settings,
+        &index_config.doc_mapping,
+    )
+    .unwrap();
+    index_update.rollout_json = Some(serde_json::to_string(&rollout).unwrap());
+
+    let response_metadata = metastore
+        .update_index(index_update.clone())
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(
+        response_metadata.index_config.rollout_opt,
+        Some(rollout.clone())
+    );
+
+    // The rollout is left unchanged when the update does not carry one.
+    index_update.rollout_json = None;
+    let response_metadata = metastore
+        .update_index(index_update.clone())
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(
+        response_metadata.index_config.rollout_opt,
+        Some(rollout.clone())
+    );
+
+    rollout.state = RolloutState::Promoted;
+    index_update.rollout_json = Some(serde_json::to_string(&rollout).unwrap());
+    metastore.update_index(index_update).await.unwrap();
+
+    let updated_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(
+            index_uid.index_id.to_string(),
+        ))
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(updated_metadata.index_config.rollout_opt, Some(rollout));
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
+pub async fn test_metastore_update_ingestion_freeze<
+    MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let (mut metastore, index_uid, index_config) =
+        setup_metastore_for_update::<MetastoreToTest>().await;
+    let ingestion_freeze = IngestionFreeze {
+        reason: "producer bug".to_string(),
+        frozen_at: 1_700_000_000,
+    };
+    let mut index_update = UpdateIndexRequest::try_from_updates(
+        index_uid.clone(),
+        &index_config.search_settings,
+        &index_config.retention_policy_opt,
+        &index_config.indexing_settings,
+        &index_config.doc_mapping,
+    )
+    .unwrap();
+    index_update.ingestion_freeze_json =
+        Some(serde_json::to_string(&Some(&ingestion_freeze)).unwrap());
+
+    let response_metadata = metastore
+        .update_index(index_update.clone())
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(
+        response_metadata.index_config.ingestion_freeze_opt,
+        Some(ingestion_freeze.clone())
+    );
+
+    // The ingestion freeze is left unchanged when the update does not carry one.
+    index_update.ingestion_freeze_json = None;
+    let response_metadata = metastore
+        .update_index(index_update.clone())
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(
+        response_metadata.index_config.ingestion_freeze_opt,
+        Some(ingestion_freeze)
+    );
+
+    index_update.ingestion_freeze_json = Some("null".to_string());
+    metastore.update_index(index_update).await.unwrap();
+
+    let updated_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(
+            index_uid.index_id.to_string(),
+        ))
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert!(updated_metadata.index_config.ingestion_freeze_opt.is_none());
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
+pub async fn test_metastore_update_labels<
+    MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let (mut metastore, index_uid, index_config) =
+        setup_metastore_for_update::<MetastoreToTest>().await;
+    let labels = BTreeMap::from([
+        ("env".to_string(), "prod".to_string()),
+        ("team".to_string(), "o'brien".to_string()),
+    ]);
+    let mut index_update = UpdateIndexRequest::try_from_updates(
+        index_uid.clone(),
+        &index_config.search_settings,
+        &index_config.retention_policy_opt,
+        &index_config.indexing_settings,
+        &index_config.doc_mapping,
+    )
+    .unwrap();
+    index_update.labels_json = Some(serde_json::to_string(&labels).unwrap());
+
+    let response_metadata = metastore
+        .update_index(index_update.clone())
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(response_metadata.index_config.labels, labels);
+
+    // The labels are left unchanged when the update does not carry them.
+    index_update.labels_json = None;
+    let response_metadata = metastore
+        .update_index(index_update)
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(response_metadata.index_config.labels, labels);
+
+    let list_index_ids = |label_selectors: &[&str]| {
+        let list_indexes_metadata_request = ListIndexesMetadataRequest {
+            index_id_patterns: vec![index_uid.index_id.clone()],
+            include_trashed: false,
+            label_selectors: label_selectors
+                .iter()
+                .map(|label_selector| label_selector.to_string())
+                .collect(),
+        };
+        let metastore = metastore.clone();
+        async move {
+            metastore
+                .list_indexes_metadata(list_indexes_metadata_request)
+                .await
+                .unwrap()
+                .deserialize_indexes_metadata()
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|index_metadata| index_metadata.index_id().to_string())
+                .collect::<Vec<String>>()
+        }
+    };
+    assert_eq!(list_index_ids(&["env"]).await.len(), 1);
+    assert_eq!(list_index_ids(&["env:prod", "team:o'brien"]).await.len(), 1);
+    assert!(list_index_ids(&["env:dev"]).await.is_empty());
+    assert!(list_index_ids(&["env", "pii"]).await.is_empty());
+
+    let error = metastore
+        .list_indexes_metadata(ListIndexesMetadataRequest {
+            index_id_patterns: vec![index_uid.index_id.clone()],
+            include_trashed: false,
+            label_selectors: vec![":prod".to_string()],
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
 pub async fn test_metastore_update_search_settings<
     MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
 >() {
@@ -166,6 +378,11 @@ pub async fn test_metastore_update_search_settings<
             index_uid.clone(),
             &SearchSettings {
                 default_search_fields: loop_search_settings.clone(),
+                default_operator: None,
+                lenient: false,
+                security_policies: Vec::new(),
+                clamp_to_retention_period: false,
+                max_result_window: 10_000,
             },
             &index_config.retention_policy_opt,
             &index_config.indexing_settings,
@@ -267,6 +484,7 @@ pub async fn test_metastore_update_doc_mapping<
         indexing_options: None,
         expand_dots: false,
         fast: Default::default(),
+        limits: None,
     };
 
     let initial = index_config.doc_mapping.clone();
@@ -626,7 +844,11 @@ pub async fn test_metastore_list_indexes<MetastoreToTest: MetastoreServiceExt +
         format!("prefix*{index_id_fragment}*suffix-*"),
     ];
     let indexes_count = metastore
-        .list_indexes_metadata(ListIndexesMetadataRequest { index_id_patterns })
+        .list_indexes_metadata(ListIndexesMetadataRequest {
+            index_id_patterns,
+            include_trashed: false,
+            label_selectors: Vec::new(),
+        })
         .await
         .unwrap()
         .deserialize_indexes_metadata()
@@ -662,7 +884,11 @@ pub async fn test_metastore_list_indexes<MetastoreToTest: MetastoreServiceExt +
 
     let index_id_patterns = vec![format!("prefix-*-{index_id_fragment}-suffix-*")];
     let indexes_count = metastore
-        .list_indexes_metadata(ListIndexesMetadataRequest { index_id_patterns })
+        .list_indexes_metadata(ListIndexesMetadataRequest {
+            index_id_patterns,
+            include_trashed: false,
+            label_selectors: Vec::new(),
+        })
         .await
         .unwrap()
         .deserialize_indexes_metadata()
@@ -754,3 +980,239 @@ pub async fn test_metastore_delete_index<
 
     cleanup_index(&mut metastore, index_uid).await;
 }
+
+pub async fn test_metastore_trash_index<
+    MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-trash-index");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid()
+        .clone();
+
+    let error = metastore
+        .undelete_index(UndeleteIndexRequest {
+            index_id: index_id.clone(),
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::FailedPrecondition { .. }));
+
+    let trash_index_request = TrashIndexRequest {
+        index_uid: Some(index_uid.clone()),
+    };
+    metastore.trash_index(trash_index_request).await.unwrap();
+
+    // Trashing an index is idempotent.
+    let trash_index_request = TrashIndexRequest {
+        index_uid: Some(index_uid.clone()),
+    };
+    metastore.trash_index(trash_index_request).await.unwrap();
+
+    let error = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(index_id.clone()))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::NotFound { .. }));
+
+    let indexes_metadata_response = metastore
+        .indexes_metadata(IndexesMetadataRequest {
+            subrequests: vec![IndexMetadataSubrequest {
+                index_id: None,
+                index_uid: Some(index_uid.clone()),
+            }],
+        })
+        .await
+        .unwrap();
+    assert_eq!(indexes_metadata_response.failures.len(), 1);
+    assert_eq!(
+        indexes_metadata_response.failures[0].reason(),
+        IndexMetadataFailureReason::NotFound
+    );
+
+    let indexes_metadata = metastore
+        .list_indexes_metadata(ListIndexesMetadataRequest {
+            index_id_patterns: vec![index_id.clone()],
+            include_trashed: false,
+            label_selectors: Vec::new(),
+        })
+        .await
+        .unwrap()
+        .deserialize_indexes_metadata()
+        .await
+        .unwrap();
+    assert!(indexes_metadata.is_empty());
+
+    let indexes_metadata = metastore
+        .list_indexes_metadata(ListIndexesMetadataRequest {
+            index_id_patterns: vec![index_id.clone()],
+            include_trashed: true,
+            label_selectors: Vec::new(),
+        })
+        .await
+        .unwrap()
+        .deserialize_indexes_metadata()
+        .await
+        .unwrap();
+    assert_eq!(indexes_metadata.len(), 1);
+    assert!(indexes_metadata[0].is_trashed());
+
+    // The ID of an index in the trash is not available until the index is purged.
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let error = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&error, MetastoreError::FailedPrecondition { message, .. } if message.contains("is in the trash"))
+    );
+
+    let index_metadata = metastore
+        .undelete_index(UndeleteIndexRequest {
+            index_id: index_id.clone(),
+        })
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(index_metadata.index_uid, index_uid);
+    assert!(!index_metadata.is_trashed());
+
+    metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(index_id.clone()))
+        .await
+        .unwrap();
+
+    let error = metastore
+        .undelete_index(UndeleteIndexRequest {
+            index_id: "index-not-found".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::NotFound { .. }));
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
+async fn fetch_tag_overlays(
+    metastore: &impl MetastoreService,
+    index_uid: &IndexUid,
+) -> Vec<TagOverlay> {
+    metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(
+            index_uid.index_id.to_string(),
+        ))
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap()
+        .tag_overlays
+}
+
+pub async fn test_metastore_add_tag_overlay<
+    MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let (mut metastore, index_uid, index_config) =
+        setup_metastore_for_update::<MetastoreToTest>().await;
+
+    let tag_overlay = |event_id: &str, acknowledged: bool, create_timestamp: i64| TagOverlay {
+        query_ast: TermQuery::from_field_value("event_id", event_id).into(),
+        field_name: "acknowledged".to_string(),
+        value_opt: Some(JsonValue::Bool(acknowledged)),
+        create_timestamp,
+    };
+    let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+
+    // The tag overlays assigning the same value to a field are merged.
+    let add_tag_overlay_request = AddTagOverlayRequest::try_from_tag_overlays(
+        index_uid.clone(),
+        &[
+            tag_overlay("42", true, 1_000),
+            tag_overlay("43", true, 2_000),
+            tag_overlay("44", false, 3_000),
+        ],
+    )
+    .unwrap();
+    metastore
+        .add_tag_overlay(add_tag_overlay_request)
+        .await
+        .unwrap();
+
+    let tag_overlays = fetch_tag_overlays(&metastore, &index_uid).await;
+    assert_eq!(tag_overlays.len(), 2);
+    assert_eq!(
+        tag_overlays[0].query_ast,
+        BoolQuery {
+            should: vec![
+                TermQuery::from_field_value("event_id", "42").into(),
+                TermQuery::from_field_value("event_id", "43").into(),
+            ],
+            ..Default::default()
+        }
+        .into()
+    );
+    assert_eq!(tag_overlays[0].create_timestamp, 2_000);
+    assert_eq!(tag_overlays[1], tag_overlay("44", false, 3_000));
+
+    // Either all the tag overlays are added or none is.
+    let too_many_tag_overlays: Vec<TagOverlay> = (0..MAX_NUM_TAG_OVERLAYS_PER_INDEX)
+        .map(|ord| tag_overlay(&ord.to_string(), ord % 2 == 0, now_timestamp))
+        .collect();
+    let add_tag_overlay_request =
+        AddTagOverlayRequest::try_from_tag_overlays(index_uid.clone(), &too_many_tag_overlays)
+            .unwrap();
+    let error = metastore
+        .add_tag_overlay(add_tag_overlay_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::FailedPrecondition { .. }));
+    assert_eq!(fetch_tag_overlays(&metastore, &index_uid).await.len(), 2);
+
+    // The tag overlays created before the retention period are dropped.
+    let retention_policy_opt = Some(RetentionPolicy {
+        retention_period: "1 day".to_string(),
+        evaluation_schedule: "daily".to_string(),
+    });
+    let update_index_request = UpdateIndexRequest::try_from_updates(
+        index_uid.clone(),
+        &index_config.search_settings,
+        &retention_policy_opt,
+        &index_config.indexing_settings,
+        &index_config.doc_mapping,
+    )
+    .unwrap();
+    metastore.update_index(update_index_request).await.unwrap();
+
+    let add_tag_overlay_request =
+        AddTagOverlayRequest::try_from_tag_overlays(index_uid.clone(), &too_many_tag_overlays)
+            .unwrap();
+    metastore
+        .add_tag_overlay(add_tag_overlay_request)
+        .await
+        .unwrap();
+    assert_eq!(
+        fetch_tag_overlays(&metastore, &index_uid).await,
+        too_many_tag_overlays
+    );
+
+    let add_tag_overlay_request = AddTagOverlayRequest::try_from_tag_overlays(
+        IndexUid::new_with_random_ulid("index-not-found"),
+        &[tag_overlay("42", true, now_timestamp)],
+    )
+    .unwrap();
+    let error = metastore
+        .add_tag_overlay(add_tag_overlay_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::NotFound { .. }));
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
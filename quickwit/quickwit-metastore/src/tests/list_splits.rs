@@ -1172,6 +1172,7 @@ pub async fn test_metastore_list_sorted_splits<
         split_id: split_id_1.clone(),
         index_uid: index_uid_1.clone(),
         delete_opstamp: 5,
+        time_range: Some(0..=10),
         ..Default::default()
     };
     let split_id_2 = format!("{split_id}--split-2");
@@ -1179,6 +1180,7 @@ pub async fn test_metastore_list_sorted_splits<
         split_id: split_id_2.clone(),
         index_uid: index_uid_2.clone(),
         delete_opstamp: 3,
+        time_range: Some(0..=60),
         ..Default::default()
     };
     let split_id_3 = format!("{split_id}--split-3");
@@ -1186,6 +1188,7 @@ pub async fn test_metastore_list_sorted_splits<
         split_id: split_id_3.clone(),
         index_uid: index_uid_1.clone(),
         delete_opstamp: 1,
+        time_range: Some(0..=30),
         ..Default::default()
     };
     let split_id_4 = format!("{split_id}--split-4");
@@ -1193,6 +1196,7 @@ pub async fn test_metastore_list_sorted_splits<
         split_id: split_id_4.clone(),
         index_uid: index_uid_2.clone(),
         delete_opstamp: 0,
+        time_range: Some(0..=20),
         ..Default::default()
     };
     let split_id_5 = format!("{split_id}--split-5");
@@ -1200,6 +1204,7 @@ pub async fn test_metastore_list_sorted_splits<
         split_id: split_id_5.clone(),
         index_uid: index_uid_1.clone(),
         delete_opstamp: 2,
+        time_range: Some(0..=50),
         ..Default::default()
     };
     let split_id_6 = format!("{split_id}--split-6");
@@ -1207,6 +1212,7 @@ pub async fn test_metastore_list_sorted_splits<
         split_id: split_id_6.clone(),
         index_uid: index_uid_2.clone(),
         delete_opstamp: 4,
+        time_range: None,
         ..Default::default()
     };
 
@@ -1331,6 +1337,49 @@ pub async fn test_metastore_list_sorted_splits<
         ]
     );
 
+    let query =
+        ListSplitsQuery::try_from_index_uids(vec![index_uid_1.clone(), index_uid_2.clone()])
+            .unwrap()
+            .sort_by_recency();
+    let splits = metastore
+        .list_splits(ListSplitsRequest::try_from_list_splits_query(&query).unwrap())
+        .await
+        .unwrap()
+        .collect_splits()
+        .await
+        .unwrap();
+    let split_ids = splits
+        .iter()
+        .map(|split| split.split_id())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        split_ids,
+        &[
+            &split_id_2,
+            &split_id_5,
+            &split_id_3,
+            &split_id_4,
+            &split_id_1,
+            &split_id_6,
+        ]
+    );
+
+    let query = ListSplitsQuery::for_index(index_uid_1.clone())
+        .sort_by_recency()
+        .with_limit(2);
+    let splits = metastore
+        .list_splits(ListSplitsRequest::try_from_list_splits_query(&query).unwrap())
+        .await
+        .unwrap()
+        .collect_splits()
+        .await
+        .unwrap();
+    let split_ids = splits
+        .iter()
+        .map(|split| split.split_id())
+        .collect::<Vec<_>>();
+    assert_eq!(split_ids, &[&split_id_5, &split_id_3]);
+
     cleanup_index(&mut metastore, index_uid_1.clone()).await;
     cleanup_index(&mut metastore, index_uid_2.clone()).await;
 }
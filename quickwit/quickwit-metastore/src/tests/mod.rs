@@ -32,6 +32,7 @@ pub(crate) mod shard;
 pub(crate) mod source;
 pub(crate) mod split;
 pub(crate) mod template;
+pub(crate) mod usage;
 
 use crate::metastore::MetastoreServiceStreamSplitsExt;
 use crate::{ListSplitsRequestExt, MetastoreServiceExt, Split};
@@ -198,6 +199,27 @@ macro_rules! metastore_test_suite {
                 $crate::tests::index::test_metastore_update_retention_policy::<$metastore_type>().await;
             }
 
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_update_rollout() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::index::test_metastore_update_rollout::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_update_ingestion_freeze() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::index::test_metastore_update_ingestion_freeze::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_update_labels() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::index::test_metastore_update_labels::<$metastore_type>().await;
+            }
+
             #[tokio::test]
             #[serial_test::file_serial]
             async fn test_metastore_update_search_settings() {
@@ -271,6 +293,20 @@ macro_rules! metastore_test_suite {
                 $crate::tests::index::test_metastore_delete_index::<$metastore_type>().await;
             }
 
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_trash_index() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::index::test_metastore_trash_index::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_add_tag_overlay() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::index::test_metastore_add_tag_overlay::<$metastore_type>().await;
+            }
+
             // Split API tests
             //
             //  - stage_splits
@@ -544,6 +580,20 @@ macro_rules! metastore_test_suite {
             async fn test_metastore_delete_index_templates() {
                 $crate::tests::template::test_metastore_delete_index_templates::<$metastore_type>().await;
             }
+
+            /// Index Usage API tests
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_accumulate_index_usage() {
+                $crate::tests::usage::test_metastore_accumulate_index_usage::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_index_usage_publish_splits() {
+                $crate::tests::usage::test_metastore_index_usage_publish_splits::<$metastore_type>().await;
+            }
         }
     };
 }
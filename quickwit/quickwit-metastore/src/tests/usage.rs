@@ -0,0 +1,220 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use quickwit_common::rand::append_random_suffix;
+use quickwit_config::IndexConfig;
+use quickwit_proto::metastore::{
+    AccumulateIndexUsageRequest, AccumulateIndexUsageSubrequest, CreateIndexRequest, EntityKind,
+    GetIndexUsageRequest, IndexUsage, MetastoreError, PublishSplitsRequest, StageSplitsRequest,
+    INDEX_USAGE_PERIOD_SECS,
+};
+use quickwit_proto::types::IndexUid;
+
+use super::DefaultForTest;
+use crate::tests::cleanup_index;
+use crate::{CreateIndexRequestExt, MetastoreServiceExt, SplitMetadata, StageSplitsRequestExt};
+
+async fn get_index_usage(metastore: &dyn MetastoreServiceExt, index_uid: &IndexUid) -> IndexUsage {
+    let get_index_usage_request = GetIndexUsageRequest {
+        index_uid: Some(index_uid.clone()),
+        start_timestamp: None,
+        end_timestamp: None,
+    };
+    metastore
+        .get_index_usage(get_index_usage_request)
+        .await
+        .unwrap()
+        .usage
+        .unwrap()
+}
+
+pub async fn test_metastore_accumulate_index_usage<
+    MetastoreToTest: MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-accumulate-index-usage");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid()
+        .clone();
+
+    let usage = get_index_usage(&metastore, &index_uid).await;
+    assert_eq!(usage, IndexUsage::default());
+
+    let usage_delta = IndexUsage {
+        num_queries: 3,
+        num_bytes_scanned: 1_000,
+        ..Default::default()
+    };
+    let accumulate_index_usage_request = AccumulateIndexUsageRequest {
+        subrequests: vec![
+            AccumulateIndexUsageSubrequest {
+                index_uid: Some(index_uid.clone()),
+                usage_delta: Some(usage_delta.clone()),
+                period_start_timestamp: 0,
+            },
+            AccumulateIndexUsageSubrequest {
+                index_uid: Some(index_uid.clone()),
+                usage_delta: Some(usage_delta.clone()),
+                period_start_timestamp: 0,
+            },
+            AccumulateIndexUsageSubrequest {
+                index_uid: Some(index_uid.clone()),
+                usage_delta: Some(usage_delta),
+                period_start_timestamp: INDEX_USAGE_PERIOD_SECS,
+            },
+            // Deltas for indexes that do not exist are ignored.
+            AccumulateIndexUsageSubrequest {
+                index_uid: Some(IndexUid::new_with_random_ulid("index-not-found")),
+                usage_delta: Some(IndexUsage {
+                    num_queries: 1,
+                    ..Default::default()
+                }),
+                period_start_timestamp: 0,
+            },
+        ],
+    };
+    metastore
+        .accumulate_index_usage(accumulate_index_usage_request)
+        .await
+        .unwrap();
+
+    let usage = get_index_usage(&metastore, &index_uid).await;
+    assert_eq!(usage.num_docs_ingested, 0);
+    assert_eq!(usage.num_bytes_ingested, 0);
+    assert_eq!(usage.num_queries, 9);
+    assert_eq!(usage.num_bytes_scanned, 3_000);
+
+    // The usage can be restricted to a time range, by whole usage periods.
+    let get_index_usage_request = GetIndexUsageRequest {
+        index_uid: Some(index_uid.clone()),
+        start_timestamp: Some(INDEX_USAGE_PERIOD_SECS + 60),
+        end_timestamp: None,
+    };
+    let usage = metastore
+        .get_index_usage(get_index_usage_request)
+        .await
+        .unwrap()
+        .usage
+        .unwrap();
+    assert_eq!(usage.num_queries, 3);
+    assert_eq!(usage.num_bytes_scanned, 1_000);
+
+    let get_index_usage_request = GetIndexUsageRequest {
+        index_uid: Some(index_uid.clone()),
+        start_timestamp: None,
+        end_timestamp: Some(INDEX_USAGE_PERIOD_SECS),
+    };
+    let usage = metastore
+        .get_index_usage(get_index_usage_request)
+        .await
+        .unwrap()
+        .usage
+        .unwrap();
+    assert_eq!(usage.num_queries, 6);
+    assert_eq!(usage.num_bytes_scanned, 2_000);
+
+    let get_index_usage_request = GetIndexUsageRequest {
+        index_uid: Some(IndexUid::new_with_random_ulid("index-not-found")),
+        start_timestamp: None,
+        end_timestamp: None,
+    };
+    let error = metastore
+        .get_index_usage(get_index_usage_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MetastoreError::NotFound(EntityKind::Index { .. })
+    ));
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
+pub async fn test_metastore_index_usage_publish_splits<
+    MetastoreToTest: MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-index-usage-publish-splits");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid()
+        .clone();
+
+    let split_metadatas: Vec<SplitMetadata> = (1..=3)
+        .map(|split_ord| SplitMetadata {
+            split_id: format!("{index_id}--split-{split_ord}"),
+            index_uid: index_uid.clone(),
+            num_docs: 10 * split_ord,
+            uncompressed_docs_size_in_bytes: 1_000 * split_ord as u64,
+            ..Default::default()
+        })
+        .collect();
+    let stage_splits_request =
+        StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), split_metadatas.clone())
+            .unwrap();
+    metastore.stage_splits(stage_splits_request).await.unwrap();
+
+    let publish_splits_request = PublishSplitsRequest {
+        index_uid: Some(index_uid.clone()),
+        staged_split_ids: vec![
+            split_metadatas[0].split_id.clone(),
+            split_metadatas[1].split_id.clone(),
+        ],
+        ..Default::default()
+    };
+    metastore
+        .publish_splits(publish_splits_request)
+        .await
+        .unwrap();
+
+    let usage = get_index_usage(&metastore, &index_uid).await;
+    assert_eq!(usage.num_docs_ingested, 30);
+    assert_eq!(usage.num_bytes_ingested, 3_000);
+
+    // Publishing a merged split does not count as ingesting new documents.
+    let publish_splits_request = PublishSplitsRequest {
+        index_uid: Some(index_uid.clone()),
+        staged_split_ids: vec![split_metadatas[2].split_id.clone()],
+        replaced_split_ids: vec![
+            split_metadatas[0].split_id.clone(),
+            split_metadatas[1].split_id.clone(),
+        ],
+        ..Default::default()
+    };
+    metastore
+        .publish_splits(publish_splits_request)
+        .await
+        .unwrap();
+
+    let usage = get_index_usage(&metastore, &index_uid).await;
+    assert_eq!(usage.num_docs_ingested, 30);
+    assert_eq!(usage.num_bytes_ingested, 3_000);
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
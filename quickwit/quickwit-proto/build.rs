@@ -187,6 +187,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Search service.
     let mut prost_config = prost_build::Config::default();
     prost_config
+        .btree_map([
+            "SecurityContext.claims",
+            "SplitSearchProfile.warmup_num_bytes_by_component",
+        ])
         .file_descriptor_set_path("src/codegen/quickwit/search_descriptor.bin")
         .protoc_arg("--experimental_allow_proto3_optional");
 
@@ -199,6 +203,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .type_attribute("PartialHit", "#[derive(Eq, Hash)]")
         .type_attribute("PartialHit.sort_value", "#[derive(Copy)]")
         .type_attribute("SearchRequest", "#[derive(Eq, Hash)]")
+        .type_attribute("SecurityContext", "#[derive(Eq, Hash)]")
         .type_attribute("ListFieldSerialized", "#[derive(Eq)]")
         .type_attribute("SortByValue", "#[derive(Ord, PartialOrd)]")
         .type_attribute("SortField", "#[derive(Eq, Hash)]")
@@ -84,6 +84,7 @@ pub enum GetOrCreateOpenShardsFailureReason {
     IndexNotFound = 1,
     SourceNotFound = 2,
     NoIngestersAvailable = 3,
+    IndexFrozen = 4,
 }
 impl GetOrCreateOpenShardsFailureReason {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -104,6 +105,9 @@ impl GetOrCreateOpenShardsFailureReason {
             GetOrCreateOpenShardsFailureReason::NoIngestersAvailable => {
                 "GET_OR_CREATE_OPEN_SHARDS_FAILURE_REASON_NO_INGESTERS_AVAILABLE"
             }
+            GetOrCreateOpenShardsFailureReason::IndexFrozen => {
+                "GET_OR_CREATE_OPEN_SHARDS_FAILURE_REASON_INDEX_FROZEN"
+            }
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -121,6 +125,9 @@ impl GetOrCreateOpenShardsFailureReason {
             "GET_OR_CREATE_OPEN_SHARDS_FAILURE_REASON_NO_INGESTERS_AVAILABLE" => {
                 Some(Self::NoIngestersAvailable)
             }
+            "GET_OR_CREATE_OPEN_SHARDS_FAILURE_REASON_INDEX_FROZEN" => {
+                Some(Self::IndexFrozen)
+            }
             _ => None,
         }
     }
@@ -185,6 +192,16 @@ pub trait ControlPlaneService: std::fmt::Debug + Send + Sync + 'static {
         &self,
         request: super::metastore::PruneShardsRequest,
     ) -> crate::control_plane::ControlPlaneResult<super::metastore::EmptyResponse>;
+    /// Moves an index to the trash.
+    async fn trash_index(
+        &self,
+        request: super::metastore::TrashIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::EmptyResponse>;
+    /// Restores an index from the trash.
+    async fn undelete_index(
+        &self,
+        request: super::metastore::UndeleteIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::IndexMetadataResponse>;
 }
 #[derive(Debug, Clone)]
 pub struct ControlPlaneServiceClient {
@@ -341,6 +358,18 @@ impl ControlPlaneService for ControlPlaneServiceClient {
     ) -> crate::control_plane::ControlPlaneResult<super::metastore::EmptyResponse> {
         self.inner.0.prune_shards(request).await
     }
+    async fn trash_index(
+        &self,
+        request: super::metastore::TrashIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::EmptyResponse> {
+        self.inner.0.trash_index(request).await
+    }
+    async fn undelete_index(
+        &self,
+        request: super::metastore::UndeleteIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::IndexMetadataResponse> {
+        self.inner.0.undelete_index(request).await
+    }
 }
 #[cfg(any(test, feature = "testsuite"))]
 pub mod mock_control_plane_service {
@@ -429,6 +458,22 @@ pub mod mock_control_plane_service {
         > {
             self.inner.lock().await.prune_shards(request).await
         }
+        async fn trash_index(
+            &self,
+            request: super::super::metastore::TrashIndexRequest,
+        ) -> crate::control_plane::ControlPlaneResult<
+            super::super::metastore::EmptyResponse,
+        > {
+            self.inner.lock().await.trash_index(request).await
+        }
+        async fn undelete_index(
+            &self,
+            request: super::super::metastore::UndeleteIndexRequest,
+        ) -> crate::control_plane::ControlPlaneResult<
+            super::super::metastore::IndexMetadataResponse,
+        > {
+            self.inner.lock().await.undelete_index(request).await
+        }
     }
 }
 pub type BoxFuture<T, E> = std::pin::Pin<
@@ -602,6 +647,40 @@ for InnerControlPlaneServiceClient {
         Box::pin(fut)
     }
 }
+impl tower::Service<super::metastore::TrashIndexRequest>
+for InnerControlPlaneServiceClient {
+    type Response = super::metastore::EmptyResponse;
+    type Error = crate::control_plane::ControlPlaneError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: super::metastore::TrashIndexRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.trash_index(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<super::metastore::UndeleteIndexRequest>
+for InnerControlPlaneServiceClient {
+    type Response = super::metastore::IndexMetadataResponse;
+    type Error = crate::control_plane::ControlPlaneError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: super::metastore::UndeleteIndexRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.undelete_index(request).await };
+        Box::pin(fut)
+    }
+}
 /// A tower service stack is a set of tower services.
 #[derive(Debug)]
 struct ControlPlaneServiceTowerServiceStack {
@@ -657,6 +736,16 @@ struct ControlPlaneServiceTowerServiceStack {
         super::metastore::EmptyResponse,
         crate::control_plane::ControlPlaneError,
     >,
+    trash_index_svc: quickwit_common::tower::BoxService<
+        super::metastore::TrashIndexRequest,
+        super::metastore::EmptyResponse,
+        crate::control_plane::ControlPlaneError,
+    >,
+    undelete_index_svc: quickwit_common::tower::BoxService<
+        super::metastore::UndeleteIndexRequest,
+        super::metastore::IndexMetadataResponse,
+        crate::control_plane::ControlPlaneError,
+    >,
 }
 #[async_trait::async_trait]
 impl ControlPlaneService for ControlPlaneServiceTowerServiceStack {
@@ -724,6 +813,18 @@ impl ControlPlaneService for ControlPlaneServiceTowerServiceStack {
     ) -> crate::control_plane::ControlPlaneResult<super::metastore::EmptyResponse> {
         self.prune_shards_svc.clone().ready().await?.call(request).await
     }
+    async fn trash_index(
+        &self,
+        request: super::metastore::TrashIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::EmptyResponse> {
+        self.trash_index_svc.clone().ready().await?.call(request).await
+    }
+    async fn undelete_index(
+        &self,
+        request: super::metastore::UndeleteIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::IndexMetadataResponse> {
+        self.undelete_index_svc.clone().ready().await?.call(request).await
+    }
 }
 type CreateIndexLayer = quickwit_common::tower::BoxLayer<
     quickwit_common::tower::BoxService<
@@ -825,6 +926,26 @@ type PruneShardsLayer = quickwit_common::tower::BoxLayer<
     super::metastore::EmptyResponse,
     crate::control_plane::ControlPlaneError,
 >;
+type TrashIndexLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        super::metastore::TrashIndexRequest,
+        super::metastore::EmptyResponse,
+        crate::control_plane::ControlPlaneError,
+    >,
+    super::metastore::TrashIndexRequest,
+    super::metastore::EmptyResponse,
+    crate::control_plane::ControlPlaneError,
+>;
+type UndeleteIndexLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        super::metastore::UndeleteIndexRequest,
+        super::metastore::IndexMetadataResponse,
+        crate::control_plane::ControlPlaneError,
+    >,
+    super::metastore::UndeleteIndexRequest,
+    super::metastore::IndexMetadataResponse,
+    crate::control_plane::ControlPlaneError,
+>;
 #[derive(Debug, Default)]
 pub struct ControlPlaneServiceTowerLayerStack {
     create_index_layers: Vec<CreateIndexLayer>,
@@ -837,6 +958,8 @@ pub struct ControlPlaneServiceTowerLayerStack {
     get_or_create_open_shards_layers: Vec<GetOrCreateOpenShardsLayer>,
     advise_reset_shards_layers: Vec<AdviseResetShardsLayer>,
     prune_shards_layers: Vec<PruneShardsLayer>,
+    trash_index_layers: Vec<TrashIndexLayer>,
+    undelete_index_layers: Vec<UndeleteIndexLayer>,
 }
 impl ControlPlaneServiceTowerLayerStack {
     pub fn stack_layer<L>(mut self, layer: L) -> Self
@@ -1109,6 +1232,60 @@ impl ControlPlaneServiceTowerLayerStack {
         >>::Service as tower::Service<
             super::metastore::PruneShardsRequest,
         >>::Future: Send + 'static,
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    super::metastore::TrashIndexRequest,
+                    super::metastore::EmptyResponse,
+                    crate::control_plane::ControlPlaneError,
+                >,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                super::metastore::TrashIndexRequest,
+                super::metastore::EmptyResponse,
+                crate::control_plane::ControlPlaneError,
+            >,
+        >>::Service: tower::Service<
+                super::metastore::TrashIndexRequest,
+                Response = super::metastore::EmptyResponse,
+                Error = crate::control_plane::ControlPlaneError,
+            > + Clone + Send + Sync + 'static,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                super::metastore::TrashIndexRequest,
+                super::metastore::EmptyResponse,
+                crate::control_plane::ControlPlaneError,
+            >,
+        >>::Service as tower::Service<
+            super::metastore::TrashIndexRequest,
+        >>::Future: Send + 'static,
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    super::metastore::UndeleteIndexRequest,
+                    super::metastore::IndexMetadataResponse,
+                    crate::control_plane::ControlPlaneError,
+                >,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                super::metastore::UndeleteIndexRequest,
+                super::metastore::IndexMetadataResponse,
+                crate::control_plane::ControlPlaneError,
+            >,
+        >>::Service: tower::Service<
+                super::metastore::UndeleteIndexRequest,
+                Response = super::metastore::IndexMetadataResponse,
+                Error = crate::control_plane::ControlPlaneError,
+            > + Clone + Send + Sync + 'static,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                super::metastore::UndeleteIndexRequest,
+                super::metastore::IndexMetadataResponse,
+                crate::control_plane::ControlPlaneError,
+            >,
+        >>::Service as tower::Service<
+            super::metastore::UndeleteIndexRequest,
+        >>::Future: Send + 'static,
     {
         self.create_index_layers
             .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
@@ -1130,6 +1307,10 @@ impl ControlPlaneServiceTowerLayerStack {
             .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self.prune_shards_layers
             .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.trash_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.undelete_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self
     }
     pub fn stack_create_index_layer<L>(mut self, layer: L) -> Self
@@ -1342,6 +1523,48 @@ impl ControlPlaneServiceTowerLayerStack {
         self.prune_shards_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
+    pub fn stack_trash_index_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    super::metastore::TrashIndexRequest,
+                    super::metastore::EmptyResponse,
+                    crate::control_plane::ControlPlaneError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                super::metastore::TrashIndexRequest,
+                Response = super::metastore::EmptyResponse,
+                Error = crate::control_plane::ControlPlaneError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            super::metastore::TrashIndexRequest,
+        >>::Future: Send + 'static,
+    {
+        self.trash_index_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_undelete_index_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    super::metastore::UndeleteIndexRequest,
+                    super::metastore::IndexMetadataResponse,
+                    crate::control_plane::ControlPlaneError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                super::metastore::UndeleteIndexRequest,
+                Response = super::metastore::IndexMetadataResponse,
+                Error = crate::control_plane::ControlPlaneError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            super::metastore::UndeleteIndexRequest,
+        >>::Future: Send + 'static,
+    {
+        self.undelete_index_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
     pub fn build<T>(self, instance: T) -> ControlPlaneServiceClient
     where
         T: ControlPlaneService,
@@ -1481,6 +1704,22 @@ impl ControlPlaneServiceTowerLayerStack {
                 quickwit_common::tower::BoxService::new(inner_client.clone()),
                 |svc, layer| layer.layer(svc),
             );
+        let trash_index_svc = self
+            .trash_index_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let undelete_index_svc = self
+            .undelete_index_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
         let tower_svc_stack = ControlPlaneServiceTowerServiceStack {
             inner: inner_client,
             create_index_svc,
@@ -1493,6 +1732,8 @@ impl ControlPlaneServiceTowerLayerStack {
             get_or_create_open_shards_svc,
             advise_reset_shards_svc,
             prune_shards_svc,
+            trash_index_svc,
+            undelete_index_svc,
         };
         ControlPlaneServiceClient::new(tower_svc_stack)
     }
@@ -1658,6 +1899,24 @@ where
                 super::metastore::EmptyResponse,
                 crate::control_plane::ControlPlaneError,
             >,
+        >
+        + tower::Service<
+            super::metastore::TrashIndexRequest,
+            Response = super::metastore::EmptyResponse,
+            Error = crate::control_plane::ControlPlaneError,
+            Future = BoxFuture<
+                super::metastore::EmptyResponse,
+                crate::control_plane::ControlPlaneError,
+            >,
+        >
+        + tower::Service<
+            super::metastore::UndeleteIndexRequest,
+            Response = super::metastore::IndexMetadataResponse,
+            Error = crate::control_plane::ControlPlaneError,
+            Future = BoxFuture<
+                super::metastore::IndexMetadataResponse,
+                crate::control_plane::ControlPlaneError,
+            >,
         >,
 {
     async fn create_index(
@@ -1724,6 +1983,18 @@ where
     ) -> crate::control_plane::ControlPlaneResult<super::metastore::EmptyResponse> {
         self.clone().call(request).await
     }
+    async fn trash_index(
+        &self,
+        request: super::metastore::TrashIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::EmptyResponse> {
+        self.clone().call(request).await
+    }
+    async fn undelete_index(
+        &self,
+        request: super::metastore::UndeleteIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::IndexMetadataResponse> {
+        self.clone().call(request).await
+    }
 }
 #[derive(Debug, Clone)]
 pub struct ControlPlaneServiceGrpcClientAdapter<T> {
@@ -1903,6 +2174,34 @@ where
                 super::metastore::PruneShardsRequest::rpc_name(),
             ))
     }
+    async fn trash_index(
+        &self,
+        request: super::metastore::TrashIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::EmptyResponse> {
+        self.inner
+            .clone()
+            .trash_index(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                super::metastore::TrashIndexRequest::rpc_name(),
+            ))
+    }
+    async fn undelete_index(
+        &self,
+        request: super::metastore::UndeleteIndexRequest,
+    ) -> crate::control_plane::ControlPlaneResult<super::metastore::IndexMetadataResponse> {
+        self.inner
+            .clone()
+            .undelete_index(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                super::metastore::UndeleteIndexRequest::rpc_name(),
+            ))
+    }
 }
 #[derive(Debug)]
 pub struct ControlPlaneServiceGrpcServerAdapter {
@@ -2034,6 +2333,28 @@ for ControlPlaneServiceGrpcServerAdapter {
             .map(tonic::Response::new)
             .map_err(crate::error::grpc_error_to_grpc_status)
     }
+    async fn trash_index(
+        &self,
+        request: tonic::Request<super::metastore::TrashIndexRequest>,
+    ) -> Result<tonic::Response<super::metastore::EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .trash_index(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn undelete_index(
+        &self,
+        request: tonic::Request<super::metastore::UndeleteIndexRequest>,
+    ) -> Result<tonic::Response<super::metastore::IndexMetadataResponse>, tonic::Status> {
+        self.inner
+            .0
+            .undelete_index(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
 }
 /// Generated client implementations.
 pub mod control_plane_service_grpc_client {
@@ -2439,6 +2760,68 @@ pub mod control_plane_service_grpc_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        /// Moves an index to the trash.
+        pub async fn trash_index(
+            &mut self,
+            request: impl tonic::IntoRequest<super::super::metastore::TrashIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::metastore::EmptyResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.control_plane.ControlPlaneService/TrashIndex",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.control_plane.ControlPlaneService",
+                        "TrashIndex",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Restores an index from the trash.
+        pub async fn undelete_index(
+            &mut self,
+            request: impl tonic::IntoRequest<super::super::metastore::UndeleteIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::metastore::IndexMetadataResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.control_plane.ControlPlaneService/UndeleteIndex",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.control_plane.ControlPlaneService",
+                        "UndeleteIndex",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -2529,6 +2912,22 @@ pub mod control_plane_service_grpc_server {
             tonic::Response<super::super::metastore::EmptyResponse>,
             tonic::Status,
         >;
+        /// Moves an index to the trash.
+        async fn trash_index(
+            &self,
+            request: tonic::Request<super::super::metastore::TrashIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::metastore::EmptyResponse>,
+            tonic::Status,
+        >;
+        /// Restores an index from the trash.
+        async fn undelete_index(
+            &self,
+            request: tonic::Request<super::super::metastore::UndeleteIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::super::metastore::IndexMetadataResponse>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct ControlPlaneServiceGrpcServer<T: ControlPlaneServiceGrpc> {
@@ -3094,6 +3493,104 @@ pub mod control_plane_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
+                "/quickwit.control_plane.ControlPlaneService/TrashIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct TrashIndexSvc<T: ControlPlaneServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: ControlPlaneServiceGrpc,
+                    > tonic::server::UnaryService<
+                        super::super::metastore::TrashIndexRequest,
+                    > for TrashIndexSvc<T> {
+                        type Response = super::super::metastore::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::metastore::TrashIndexRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).trash_index(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = TrashIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.control_plane.ControlPlaneService/UndeleteIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct UndeleteIndexSvc<T: ControlPlaneServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: ControlPlaneServiceGrpc,
+                    > tonic::server::UnaryService<
+                        super::super::metastore::UndeleteIndexRequest,
+                    > for UndeleteIndexSvc<T> {
+                        type Response = super::super::metastore::IndexMetadataResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::metastore::UndeleteIndexRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).undelete_index(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UndeleteIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
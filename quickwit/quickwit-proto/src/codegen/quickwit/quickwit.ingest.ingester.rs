@@ -15,11 +15,21 @@ pub struct RetainShardsForSource {
 pub struct RetainShardsRequest {
     #[prost(message, repeated, tag = "1")]
     pub retain_shards_for_sources: ::prost::alloc::vec::Vec<RetainShardsForSource>,
+    /// Only the shards that have not been written to for at least this duration are removed.
+    #[prost(uint64, tag = "2")]
+    pub min_idle_secs: u64,
+    /// When true, the shards that would be removed are only reported.
+    #[prost(bool, tag = "3")]
+    pub dry_run: bool,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct RetainShardsResponse {}
+pub struct RetainShardsResponse {
+    /// Queue IDs of the shards removed or, in dry-run mode, of the shards that would have been removed.
+    #[prost(string, repeated, tag = "1")]
+    pub removed_queue_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -154,6 +164,10 @@ pub struct OpenReplicationStreamResponse {
     /// Position of the response in the replication stream. It should match the position of the request.
     #[prost(uint64, tag = "1")]
     pub replication_seqno: u64,
+    /// Whether the follower can decode compressed documents. Leaders only compress the documents they
+    /// replicate to followers that set this flag.
+    #[prost(bool, tag = "2")]
+    pub accepts_compressed_doc_batches: bool,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -203,6 +217,9 @@ pub struct ReplicateSubrequest {
     pub from_position_exclusive: ::core::option::Option<crate::types::Position>,
     #[prost(message, optional, tag = "6")]
     pub doc_batch: ::core::option::Option<super::DocBatchV2>,
+    /// Codec used to encode the `doc_buffer` of `doc_batch`. See `quickwit_proto::compression::PayloadCodec`.
+    #[prost(int32, tag = "7")]
+    pub doc_buffer_codec: i32,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -34,6 +34,15 @@ pub struct UpdateIndexRequest {
     pub indexing_settings_json: ::prost::alloc::string::String,
     #[prost(string, tag = "5")]
     pub doc_mapping_json: ::prost::alloc::string::String,
+    /// The rollout of the index is left unchanged if not set.
+    #[prost(string, optional, tag = "6")]
+    pub rollout_json: ::core::option::Option<::prost::alloc::string::String>,
+    /// The ingestion freeze of the index is left unchanged if not set and lifted if set to `null`.
+    #[prost(string, optional, tag = "7")]
+    pub ingestion_freeze_json: ::core::option::Option<::prost::alloc::string::String>,
+    /// The labels of the index are left unchanged if not set.
+    #[prost(string, optional, tag = "8")]
+    pub labels_json: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -44,6 +53,12 @@ pub struct ListIndexesMetadataRequest {
     /// with a '-'), and no negative pattern (a pattern starting with a '-').
     #[prost(string, repeated, tag = "2")]
     pub index_id_patterns: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Whether indexes in the trash should be returned as well.
+    #[prost(bool, tag = "3")]
+    pub include_trashed: bool,
+    /// List of label selectors, `key` or `key:value`, an index must all match to get returned.
+    #[prost(string, repeated, tag = "4")]
+    pub label_selectors: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -66,6 +81,20 @@ pub struct DeleteIndexRequest {
     #[prost(message, optional, tag = "1")]
     pub index_uid: ::core::option::Option<crate::types::IndexUid>,
 }
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TrashIndexRequest {
+    #[prost(message, optional, tag = "1")]
+    pub index_uid: ::core::option::Option<crate::types::IndexUid>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UndeleteIndexRequest {
+    #[prost(string, tag = "1")]
+    pub index_id: ::prost::alloc::string::String,
+}
 /// Request the metadata of an index.
 /// Either `index_uid` or `index_id` must be specified.
 ///
@@ -538,6 +567,67 @@ pub struct DeleteIndexTemplatesRequest {
     pub template_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IndexUsage {
+    #[prost(uint64, tag = "1")]
+    pub num_docs_ingested: u64,
+    #[prost(uint64, tag = "2")]
+    pub num_bytes_ingested: u64,
+    #[prost(uint64, tag = "3")]
+    pub num_queries: u64,
+    #[prost(uint64, tag = "4")]
+    pub num_bytes_scanned: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AccumulateIndexUsageRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub subrequests: ::prost::alloc::vec::Vec<AccumulateIndexUsageSubrequest>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AccumulateIndexUsageSubrequest {
+    #[prost(message, optional, tag = "1")]
+    pub index_uid: ::core::option::Option<crate::types::IndexUid>,
+    #[prost(message, optional, tag = "2")]
+    pub usage_delta: ::core::option::Option<IndexUsage>,
+    /// Start of the UTC day the usage was recorded in, in seconds since epoch.
+    #[prost(int64, tag = "3")]
+    pub period_start_timestamp: i64,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetIndexUsageRequest {
+    #[prost(message, optional, tag = "1")]
+    pub index_uid: ::core::option::Option<crate::types::IndexUid>,
+    /// If set, only the usage recorded on or after the UTC day of this timestamp is returned.
+    #[prost(int64, optional, tag = "2")]
+    pub start_timestamp: ::core::option::Option<i64>,
+    /// If set, only the usage recorded before the UTC day of this timestamp is returned.
+    #[prost(int64, optional, tag = "3")]
+    pub end_timestamp: ::core::option::Option<i64>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetIndexUsageResponse {
+    #[prost(message, optional, tag = "1")]
+    pub usage: ::core::option::Option<IndexUsage>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AddTagOverlayRequest {
+    #[prost(message, optional, tag = "1")]
+    pub index_uid: ::core::option::Option<crate::types::IndexUid>,
+    #[prost(string, repeated, tag = "2")]
+    pub tag_overlays_json: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -799,6 +889,31 @@ impl RpcName for DeleteIndexTemplatesRequest {
         "delete_index_templates"
     }
 }
+impl RpcName for AccumulateIndexUsageRequest {
+    fn rpc_name() -> &'static str {
+        "accumulate_index_usage"
+    }
+}
+impl RpcName for GetIndexUsageRequest {
+    fn rpc_name() -> &'static str {
+        "get_index_usage"
+    }
+}
+impl RpcName for AddTagOverlayRequest {
+    fn rpc_name() -> &'static str {
+        "add_tag_overlay"
+    }
+}
+impl RpcName for TrashIndexRequest {
+    fn rpc_name() -> &'static str {
+        "trash_index"
+    }
+}
+impl RpcName for UndeleteIndexRequest {
+    fn rpc_name() -> &'static str {
+        "undelete_index"
+    }
+}
 pub type MetastoreServiceStream<T> = quickwit_common::ServiceStream<
     crate::metastore::MetastoreResult<T>,
 >;
@@ -976,6 +1091,32 @@ pub trait MetastoreService: std::fmt::Debug + Send + Sync + 'static {
         &self,
         request: DeleteIndexTemplatesRequest,
     ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Adds deltas to the usage counters of one or several indexes.
+    async fn accumulate_index_usage(
+        &self,
+        request: AccumulateIndexUsageRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Fetches the usage counters of an index.
+    async fn get_index_usage(
+        &self,
+        request: GetIndexUsageRequest,
+    ) -> crate::metastore::MetastoreResult<GetIndexUsageResponse>;
+    /// Adds tag overlays to an index. Either all the tag overlays are added or none is.
+    async fn add_tag_overlay(
+        &self,
+        request: AddTagOverlayRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Moves an index to the trash. Trashed indexes keep their splits but are hidden from the index
+    /// metadata APIs until they are either restored or purged.
+    async fn trash_index(
+        &self,
+        request: TrashIndexRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Restores an index from the trash.
+    async fn undelete_index(
+        &self,
+        request: UndeleteIndexRequest,
+    ) -> crate::metastore::MetastoreResult<IndexMetadataResponse>;
     async fn check_connectivity(&self) -> anyhow::Result<()>;
     fn endpoints(&self) -> Vec<quickwit_common::uri::Uri>;
 }
@@ -1256,6 +1397,36 @@ impl MetastoreService for MetastoreServiceClient {
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner.0.delete_index_templates(request).await
     }
+    async fn accumulate_index_usage(
+        &self,
+        request: AccumulateIndexUsageRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.0.accumulate_index_usage(request).await
+    }
+    async fn get_index_usage(
+        &self,
+        request: GetIndexUsageRequest,
+    ) -> crate::metastore::MetastoreResult<GetIndexUsageResponse> {
+        self.inner.0.get_index_usage(request).await
+    }
+    async fn add_tag_overlay(
+        &self,
+        request: AddTagOverlayRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.0.add_tag_overlay(request).await
+    }
+    async fn trash_index(
+        &self,
+        request: TrashIndexRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.0.trash_index(request).await
+    }
+    async fn undelete_index(
+        &self,
+        request: UndeleteIndexRequest,
+    ) -> crate::metastore::MetastoreResult<IndexMetadataResponse> {
+        self.inner.0.undelete_index(request).await
+    }
     async fn check_connectivity(&self) -> anyhow::Result<()> {
         self.inner.0.check_connectivity().await
     }
@@ -1462,6 +1633,36 @@ pub mod mock_metastore_service {
         ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
             self.inner.lock().await.delete_index_templates(request).await
         }
+        async fn accumulate_index_usage(
+            &self,
+            request: super::AccumulateIndexUsageRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.accumulate_index_usage(request).await
+        }
+        async fn get_index_usage(
+            &self,
+            request: super::GetIndexUsageRequest,
+        ) -> crate::metastore::MetastoreResult<super::GetIndexUsageResponse> {
+            self.inner.lock().await.get_index_usage(request).await
+        }
+        async fn add_tag_overlay(
+            &self,
+            request: super::AddTagOverlayRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.add_tag_overlay(request).await
+        }
+        async fn trash_index(
+            &self,
+            request: super::TrashIndexRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.trash_index(request).await
+        }
+        async fn undelete_index(
+            &self,
+            request: super::UndeleteIndexRequest,
+        ) -> crate::metastore::MetastoreResult<super::IndexMetadataResponse> {
+            self.inner.lock().await.undelete_index(request).await
+        }
         async fn check_connectivity(&self) -> anyhow::Result<()> {
             self.inner.lock().await.check_connectivity().await
         }
@@ -1969,6 +2170,86 @@ impl tower::Service<DeleteIndexTemplatesRequest> for InnerMetastoreServiceClient
         Box::pin(fut)
     }
 }
+impl tower::Service<AccumulateIndexUsageRequest> for InnerMetastoreServiceClient {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: AccumulateIndexUsageRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.accumulate_index_usage(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<GetIndexUsageRequest> for InnerMetastoreServiceClient {
+    type Response = GetIndexUsageResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: GetIndexUsageRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.get_index_usage(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<AddTagOverlayRequest> for InnerMetastoreServiceClient {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: AddTagOverlayRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.add_tag_overlay(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<TrashIndexRequest> for InnerMetastoreServiceClient {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: TrashIndexRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.trash_index(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<UndeleteIndexRequest> for InnerMetastoreServiceClient {
+    type Response = IndexMetadataResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: UndeleteIndexRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.undelete_index(request).await };
+        Box::pin(fut)
+    }
+}
 /// A tower service stack is a set of tower services.
 #[derive(Debug)]
 struct MetastoreServiceTowerServiceStack {
@@ -2129,6 +2410,31 @@ struct MetastoreServiceTowerServiceStack {
         EmptyResponse,
         crate::metastore::MetastoreError,
     >,
+    accumulate_index_usage_svc: quickwit_common::tower::BoxService<
+        AccumulateIndexUsageRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    get_index_usage_svc: quickwit_common::tower::BoxService<
+        GetIndexUsageRequest,
+        GetIndexUsageResponse,
+        crate::metastore::MetastoreError,
+    >,
+    add_tag_overlay_svc: quickwit_common::tower::BoxService<
+        AddTagOverlayRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    trash_index_svc: quickwit_common::tower::BoxService<
+        TrashIndexRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    undelete_index_svc: quickwit_common::tower::BoxService<
+        UndeleteIndexRequest,
+        IndexMetadataResponse,
+        crate::metastore::MetastoreError,
+    >,
 }
 #[async_trait::async_trait]
 impl MetastoreService for MetastoreServiceTowerServiceStack {
@@ -2318,6 +2624,36 @@ impl MetastoreService for MetastoreServiceTowerServiceStack {
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.delete_index_templates_svc.clone().ready().await?.call(request).await
     }
+    async fn accumulate_index_usage(
+        &self,
+        request: AccumulateIndexUsageRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.accumulate_index_usage_svc.clone().ready().await?.call(request).await
+    }
+    async fn get_index_usage(
+        &self,
+        request: GetIndexUsageRequest,
+    ) -> crate::metastore::MetastoreResult<GetIndexUsageResponse> {
+        self.get_index_usage_svc.clone().ready().await?.call(request).await
+    }
+    async fn add_tag_overlay(
+        &self,
+        request: AddTagOverlayRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.add_tag_overlay_svc.clone().ready().await?.call(request).await
+    }
+    async fn trash_index(
+        &self,
+        request: TrashIndexRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.trash_index_svc.clone().ready().await?.call(request).await
+    }
+    async fn undelete_index(
+        &self,
+        request: UndeleteIndexRequest,
+    ) -> crate::metastore::MetastoreResult<IndexMetadataResponse> {
+        self.undelete_index_svc.clone().ready().await?.call(request).await
+    }
     async fn check_connectivity(&self) -> anyhow::Result<()> {
         self.inner.0.check_connectivity().await
     }
@@ -2635,6 +2971,56 @@ type DeleteIndexTemplatesLayer = quickwit_common::tower::BoxLayer<
     EmptyResponse,
     crate::metastore::MetastoreError,
 >;
+type AccumulateIndexUsageLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        AccumulateIndexUsageRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    AccumulateIndexUsageRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
+type GetIndexUsageLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        GetIndexUsageRequest,
+        GetIndexUsageResponse,
+        crate::metastore::MetastoreError,
+    >,
+    GetIndexUsageRequest,
+    GetIndexUsageResponse,
+    crate::metastore::MetastoreError,
+>;
+type AddTagOverlayLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        AddTagOverlayRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    AddTagOverlayRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
+type TrashIndexLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        TrashIndexRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    TrashIndexRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
+type UndeleteIndexLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        UndeleteIndexRequest,
+        IndexMetadataResponse,
+        crate::metastore::MetastoreError,
+    >,
+    UndeleteIndexRequest,
+    IndexMetadataResponse,
+    crate::metastore::MetastoreError,
+>;
 #[derive(Debug, Default)]
 pub struct MetastoreServiceTowerLayerStack {
     create_index_layers: Vec<CreateIndexLayer>,
@@ -2668,6 +3054,11 @@ pub struct MetastoreServiceTowerLayerStack {
     find_index_template_matches_layers: Vec<FindIndexTemplateMatchesLayer>,
     list_index_templates_layers: Vec<ListIndexTemplatesLayer>,
     delete_index_templates_layers: Vec<DeleteIndexTemplatesLayer>,
+    accumulate_index_usage_layers: Vec<AccumulateIndexUsageLayer>,
+    get_index_usage_layers: Vec<GetIndexUsageLayer>,
+    add_tag_overlay_layers: Vec<AddTagOverlayLayer>,
+    trash_index_layers: Vec<TrashIndexLayer>,
+    undelete_index_layers: Vec<UndeleteIndexLayer>,
 }
 impl MetastoreServiceTowerLayerStack {
     pub fn stack_layer<L>(mut self, layer: L) -> Self
@@ -3463,29 +3854,164 @@ impl MetastoreServiceTowerLayerStack {
         >>::Service as tower::Service<
             DeleteIndexTemplatesRequest,
         >>::Future: Send + 'static,
-    {
-        self.create_index_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.update_index_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.index_metadata_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.indexes_metadata_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.list_indexes_metadata_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.delete_index_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.list_splits_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.stage_splits_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.publish_splits_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.mark_splits_for_deletion_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.delete_splits_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    AccumulateIndexUsageRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                AccumulateIndexUsageRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                AccumulateIndexUsageRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                AccumulateIndexUsageRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            AccumulateIndexUsageRequest,
+        >>::Future: Send + 'static,
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    GetIndexUsageRequest,
+                    GetIndexUsageResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                GetIndexUsageRequest,
+                GetIndexUsageResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                GetIndexUsageRequest,
+                Response = GetIndexUsageResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                GetIndexUsageRequest,
+                GetIndexUsageResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            GetIndexUsageRequest,
+        >>::Future: Send + 'static,
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    AddTagOverlayRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                AddTagOverlayRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                AddTagOverlayRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                AddTagOverlayRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            AddTagOverlayRequest,
+        >>::Future: Send + 'static,
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    TrashIndexRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                TrashIndexRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                TrashIndexRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                TrashIndexRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            TrashIndexRequest,
+        >>::Future: Send + 'static,
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    UndeleteIndexRequest,
+                    IndexMetadataResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                UndeleteIndexRequest,
+                IndexMetadataResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                UndeleteIndexRequest,
+                Response = IndexMetadataResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                UndeleteIndexRequest,
+                IndexMetadataResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            UndeleteIndexRequest,
+        >>::Future: Send + 'static,
+    {
+        self.create_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.update_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.index_metadata_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.indexes_metadata_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_indexes_metadata_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_splits_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.stage_splits_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.publish_splits_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.mark_splits_for_deletion_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_splits_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self.add_source_layers
             .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self.update_source_layers
@@ -3526,6 +4052,16 @@ impl MetastoreServiceTowerLayerStack {
             .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self.delete_index_templates_layers
             .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.accumulate_index_usage_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.get_index_usage_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.add_tag_overlay_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.trash_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.undelete_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self
     }
     pub fn stack_create_index_layer<L>(mut self, layer: L) -> Self
@@ -4144,6 +4680,116 @@ impl MetastoreServiceTowerLayerStack {
             .push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
+    pub fn stack_accumulate_index_usage_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    AccumulateIndexUsageRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                AccumulateIndexUsageRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            AccumulateIndexUsageRequest,
+        >>::Future: Send + 'static,
+    {
+        self.accumulate_index_usage_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_get_index_usage_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    GetIndexUsageRequest,
+                    GetIndexUsageResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                GetIndexUsageRequest,
+                Response = GetIndexUsageResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            GetIndexUsageRequest,
+        >>::Future: Send + 'static,
+    {
+        self.get_index_usage_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_add_tag_overlay_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    AddTagOverlayRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                AddTagOverlayRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            AddTagOverlayRequest,
+        >>::Future: Send + 'static,
+    {
+        self.add_tag_overlay_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_trash_index_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    TrashIndexRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                TrashIndexRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            TrashIndexRequest,
+        >>::Future: Send + 'static,
+    {
+        self.trash_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_undelete_index_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    UndeleteIndexRequest,
+                    IndexMetadataResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                UndeleteIndexRequest,
+                Response = IndexMetadataResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            UndeleteIndexRequest,
+        >>::Future: Send + 'static,
+    {
+        self.undelete_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
     pub fn build<T>(self, instance: T) -> MetastoreServiceClient
     where
         T: MetastoreService,
@@ -4448,6 +5094,46 @@ impl MetastoreServiceTowerLayerStack {
                 quickwit_common::tower::BoxService::new(inner_client.clone()),
                 |svc, layer| layer.layer(svc),
             );
+        let accumulate_index_usage_svc = self
+            .accumulate_index_usage_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let get_index_usage_svc = self
+            .get_index_usage_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let add_tag_overlay_svc = self
+            .add_tag_overlay_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let trash_index_svc = self
+            .trash_index_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let undelete_index_svc = self
+            .undelete_index_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
         let tower_svc_stack = MetastoreServiceTowerServiceStack {
             inner: inner_client,
             create_index_svc,
@@ -4481,6 +5167,11 @@ impl MetastoreServiceTowerLayerStack {
             find_index_template_matches_svc,
             list_index_templates_svc,
             delete_index_templates_svc,
+            accumulate_index_usage_svc,
+            get_index_usage_svc,
+            add_tag_overlay_svc,
+            trash_index_svc,
+            undelete_index_svc,
         };
         MetastoreServiceClient::new(tower_svc_stack)
     }
@@ -4763,6 +5454,39 @@ where
             Response = EmptyResponse,
             Error = crate::metastore::MetastoreError,
             Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            AccumulateIndexUsageRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            GetIndexUsageRequest,
+            Response = GetIndexUsageResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<
+                GetIndexUsageResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >
+        + tower::Service<
+            AddTagOverlayRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            TrashIndexRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            UndeleteIndexRequest,
+            Response = IndexMetadataResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<IndexMetadataResponse, crate::metastore::MetastoreError>,
         >,
 {
     async fn create_index(
@@ -4951,6 +5675,36 @@ where
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.clone().call(request).await
     }
+    async fn accumulate_index_usage(
+        &self,
+        request: AccumulateIndexUsageRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.clone().call(request).await
+    }
+    async fn get_index_usage(
+        &self,
+        request: GetIndexUsageRequest,
+    ) -> crate::metastore::MetastoreResult<GetIndexUsageResponse> {
+        self.clone().call(request).await
+    }
+    async fn add_tag_overlay(
+        &self,
+        request: AddTagOverlayRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.clone().call(request).await
+    }
+    async fn trash_index(
+        &self,
+        request: TrashIndexRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.clone().call(request).await
+    }
+    async fn undelete_index(
+        &self,
+        request: UndeleteIndexRequest,
+    ) -> crate::metastore::MetastoreResult<IndexMetadataResponse> {
+        self.clone().call(request).await
+    }
     async fn check_connectivity(&self) -> anyhow::Result<()> {
         if self.inner.is_disconnected() {
             anyhow::bail!("actor `{}` is disconnected", self.inner.actor_instance_id())
@@ -5440,6 +6194,76 @@ where
                 DeleteIndexTemplatesRequest::rpc_name(),
             ))
     }
+    async fn accumulate_index_usage(
+        &self,
+        request: AccumulateIndexUsageRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner
+            .clone()
+            .accumulate_index_usage(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                AccumulateIndexUsageRequest::rpc_name(),
+            ))
+    }
+    async fn get_index_usage(
+        &self,
+        request: GetIndexUsageRequest,
+    ) -> crate::metastore::MetastoreResult<GetIndexUsageResponse> {
+        self.inner
+            .clone()
+            .get_index_usage(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                GetIndexUsageRequest::rpc_name(),
+            ))
+    }
+    async fn add_tag_overlay(
+        &self,
+        request: AddTagOverlayRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner
+            .clone()
+            .add_tag_overlay(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                AddTagOverlayRequest::rpc_name(),
+            ))
+    }
+    async fn trash_index(
+        &self,
+        request: TrashIndexRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner
+            .clone()
+            .trash_index(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                TrashIndexRequest::rpc_name(),
+            ))
+    }
+    async fn undelete_index(
+        &self,
+        request: UndeleteIndexRequest,
+    ) -> crate::metastore::MetastoreResult<IndexMetadataResponse> {
+        self.inner
+            .clone()
+            .undelete_index(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                UndeleteIndexRequest::rpc_name(),
+            ))
+    }
     async fn check_connectivity(&self) -> anyhow::Result<()> {
         if self.connection_addrs_rx.borrow().len() == 0 {
             anyhow::bail!("no server currently available")
@@ -5819,6 +6643,61 @@ for MetastoreServiceGrpcServerAdapter {
             .map(tonic::Response::new)
             .map_err(crate::error::grpc_error_to_grpc_status)
     }
+    async fn accumulate_index_usage(
+        &self,
+        request: tonic::Request<AccumulateIndexUsageRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .accumulate_index_usage(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn get_index_usage(
+        &self,
+        request: tonic::Request<GetIndexUsageRequest>,
+    ) -> Result<tonic::Response<GetIndexUsageResponse>, tonic::Status> {
+        self.inner
+            .0
+            .get_index_usage(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn add_tag_overlay(
+        &self,
+        request: tonic::Request<AddTagOverlayRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .add_tag_overlay(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn trash_index(
+        &self,
+        request: tonic::Request<TrashIndexRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .trash_index(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn undelete_index(
+        &self,
+        request: tonic::Request<UndeleteIndexRequest>,
+    ) -> Result<tonic::Response<IndexMetadataResponse>, tonic::Status> {
+        self.inner
+            .0
+            .undelete_index(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
 }
 /// Generated client implementations.
 pub mod metastore_service_grpc_client {
@@ -6685,11 +7564,157 @@ pub mod metastore_service_grpc_client {
         }
         pub async fn list_shards(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListShardsRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::ListShardsResponse>,
-            tonic::Status,
-        > {
+            request: impl tonic::IntoRequest<super::ListShardsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListShardsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/ListShards",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.metastore.MetastoreService", "ListShards"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Creates an index template.
+        pub async fn create_index_template(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateIndexTemplateRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/CreateIndexTemplate",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "CreateIndexTemplate",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Fetches an index template.
+        pub async fn get_index_template(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetIndexTemplateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetIndexTemplateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/GetIndexTemplate",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "GetIndexTemplate",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Finds matching index templates.
+        pub async fn find_index_template_matches(
+            &mut self,
+            request: impl tonic::IntoRequest<super::FindIndexTemplateMatchesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FindIndexTemplateMatchesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/FindIndexTemplateMatches",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "FindIndexTemplateMatches",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Returns all the index templates.
+        pub async fn list_index_templates(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListIndexTemplatesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListIndexTemplatesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/ListIndexTemplates",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "ListIndexTemplates",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Deletes index templates.
+        pub async fn delete_index_templates(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteIndexTemplatesRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -6701,19 +7726,22 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/ListShards",
+                "/quickwit.metastore.MetastoreService/DeleteIndexTemplates",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("quickwit.metastore.MetastoreService", "ListShards"),
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "DeleteIndexTemplates",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Creates an index template.
-        pub async fn create_index_template(
+        /// Adds deltas to the usage counters of one or several indexes.
+        pub async fn accumulate_index_usage(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateIndexTemplateRequest>,
+            request: impl tonic::IntoRequest<super::AccumulateIndexUsageRequest>,
         ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
@@ -6726,24 +7754,24 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/CreateIndexTemplate",
+                "/quickwit.metastore.MetastoreService/AccumulateIndexUsage",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "CreateIndexTemplate",
+                        "AccumulateIndexUsage",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Fetches an index template.
-        pub async fn get_index_template(
+        /// Fetches the usage counters of an index.
+        pub async fn get_index_usage(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetIndexTemplateRequest>,
+            request: impl tonic::IntoRequest<super::GetIndexUsageRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetIndexTemplateResponse>,
+            tonic::Response<super::GetIndexUsageResponse>,
             tonic::Status,
         > {
             self.inner
@@ -6757,26 +7785,23 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/GetIndexTemplate",
+                "/quickwit.metastore.MetastoreService/GetIndexUsage",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "GetIndexTemplate",
+                        "GetIndexUsage",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Finds matching index templates.
-        pub async fn find_index_template_matches(
+        /// Adds tag overlays to an index. Either all the tag overlays are added or none is.
+        pub async fn add_tag_overlay(
             &mut self,
-            request: impl tonic::IntoRequest<super::FindIndexTemplateMatchesRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::FindIndexTemplateMatchesResponse>,
-            tonic::Status,
-        > {
+            request: impl tonic::IntoRequest<super::AddTagOverlayRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -6788,26 +7813,24 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/FindIndexTemplateMatches",
+                "/quickwit.metastore.MetastoreService/AddTagOverlay",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "FindIndexTemplateMatches",
+                        "AddTagOverlay",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Returns all the index templates.
-        pub async fn list_index_templates(
+        /// Moves an index to the trash. Trashed indexes keep their splits but are hidden from the index
+        /// metadata APIs until they are either restored or purged.
+        pub async fn trash_index(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListIndexTemplatesRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::ListIndexTemplatesResponse>,
-            tonic::Status,
-        > {
+            request: impl tonic::IntoRequest<super::TrashIndexRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -6819,23 +7842,23 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/ListIndexTemplates",
+                "/quickwit.metastore.MetastoreService/TrashIndex",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "ListIndexTemplates",
+                        "TrashIndex",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Deletes index templates.
-        pub async fn delete_index_templates(
+        /// Restores an index from the trash.
+        pub async fn undelete_index(
             &mut self,
-            request: impl tonic::IntoRequest<super::DeleteIndexTemplatesRequest>,
-        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::UndeleteIndexRequest>,
+        ) -> std::result::Result<tonic::Response<super::IndexMetadataResponse>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -6847,14 +7870,14 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/DeleteIndexTemplates",
+                "/quickwit.metastore.MetastoreService/UndeleteIndex",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "DeleteIndexTemplates",
+                        "UndeleteIndex",
                     ),
                 );
             self.inner.unary(req, path, codec).await
@@ -7093,6 +8116,35 @@ pub mod metastore_service_grpc_server {
             &self,
             request: tonic::Request<super::DeleteIndexTemplatesRequest>,
         ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Adds deltas to the usage counters of one or several indexes.
+        async fn accumulate_index_usage(
+            &self,
+            request: tonic::Request<super::AccumulateIndexUsageRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Fetches the usage counters of an index.
+        async fn get_index_usage(
+            &self,
+            request: tonic::Request<super::GetIndexUsageRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetIndexUsageResponse>,
+            tonic::Status,
+        >;
+        /// Adds tag overlays to an index. Either all the tag overlays are added or none is.
+        async fn add_tag_overlay(
+            &self,
+            request: tonic::Request<super::AddTagOverlayRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Moves an index to the trash. Trashed indexes keep their splits but are hidden from the index
+        /// metadata APIs until they are either restored or purged.
+        async fn trash_index(
+            &self,
+            request: tonic::Request<super::TrashIndexRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Restores an index from the trash.
+        async fn undelete_index(
+            &self,
+            request: tonic::Request<super::UndeleteIndexRequest>,
+        ) -> std::result::Result<tonic::Response<super::IndexMetadataResponse>, tonic::Status>;
     }
     /// Metastore meant to manage Quickwit's indexes, their splits and delete tasks.
     ///
@@ -8648,6 +9700,236 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
+                "/quickwit.metastore.MetastoreService/AccumulateIndexUsage" => {
+                    #[allow(non_camel_case_types)]
+                    struct AccumulateIndexUsageSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::AccumulateIndexUsageRequest>
+                    for AccumulateIndexUsageSvc<T> {
+                        type Response = super::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AccumulateIndexUsageRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).accumulate_index_usage(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = AccumulateIndexUsageSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/GetIndexUsage" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetIndexUsageSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::GetIndexUsageRequest>
+                    for GetIndexUsageSvc<T> {
+                        type Response = super::GetIndexUsageResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetIndexUsageRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).get_index_usage(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetIndexUsageSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/AddTagOverlay" => {
+                    #[allow(non_camel_case_types)]
+                    struct AddTagOverlaySvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::AddTagOverlayRequest>
+                    for AddTagOverlaySvc<T> {
+                        type Response = super::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AddTagOverlayRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).add_tag_overlay(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = AddTagOverlaySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/TrashIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct TrashIndexSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::TrashIndexRequest>
+                    for TrashIndexSvc<T> {
+                        type Response = super::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::TrashIndexRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).trash_index(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = TrashIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/UndeleteIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct UndeleteIndexSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::UndeleteIndexRequest>
+                    for UndeleteIndexSvc<T> {
+                        type Response = super::IndexMetadataResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UndeleteIndexRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).undelete_index(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UndeleteIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
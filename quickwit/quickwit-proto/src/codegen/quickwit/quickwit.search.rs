@@ -48,6 +48,13 @@ pub struct ReportSplit {
     /// The storage uri. This URI does NOT include the split id.
     #[prost(string, tag = "1")]
     pub storage_uri: ::prost::alloc::string::String,
+    /// Name of the storage credentials defined in the node config used to access the index
+    /// storage. Unset when the index uses the default storage configs.
+    #[prost(string, optional, tag = "3")]
+    pub storage_credentials: ::core::option::Option<::prost::alloc::string::String>,
+    /// ID of the index of the split, used to enforce the split cache index quotas.
+    #[prost(string, optional, tag = "4")]
+    pub index_id: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -63,6 +70,74 @@ pub struct ReportSplitsResponse {}
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PinSplitsRequest {
+    /// Splits to pin or unpin.
+    #[prost(message, repeated, tag = "1")]
+    pub splits: ::prost::alloc::vec::Vec<ReportSplit>,
+    /// Unpins the splits instead of pinning them.
+    #[prost(bool, tag = "2")]
+    pub unpin: bool,
+    /// Unpins all the splits pinned with one of these storage URIs, for instance all the splits
+    /// of an index.
+    #[prost(string, repeated, tag = "3")]
+    pub unpin_storage_uris: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PinSplitsResponse {}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelLeafSearchesRequest {
+    /// ID of the search request whose leaf searches are cancelled.
+    #[prost(string, tag = "1")]
+    pub search_request_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelLeafSearchesResponse {
+    /// Number of leaf searches cancelled on the searcher.
+    #[prost(uint32, tag = "1")]
+    pub num_cancelled_leaf_searches: u32,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelSearchRequest {
+    /// ID of the search request, set by the client or generated by the node running it.
+    #[prost(string, tag = "1")]
+    pub request_id: ::prost::alloc::string::String,
+    /// Security context of the caller. Callers can only cancel their own search requests.
+    #[prost(message, optional, tag = "2")]
+    pub security_context: ::core::option::Option<SecurityContext>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelSearchResponse {
+    /// Cancelled search request, missing if the search request is not running on the searcher.
+    #[prost(message, optional, tag = "1")]
+    pub running_search: ::core::option::Option<RunningSearchInfo>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RunningSearchInfo {
+    /// ID of the search request.
+    #[prost(string, tag = "1")]
+    pub request_id: ::prost::alloc::string::String,
+    /// Index ID patterns targeted by the search request.
+    #[prost(string, repeated, tag = "2")]
+    pub index_id_patterns: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Time elapsed since the search request started (in seconds).
+    #[prost(double, tag = "3")]
+    pub elapsed_secs: f64,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListFieldsRequest {
     /// Index ID patterns
     #[prost(string, repeated, tag = "1")]
@@ -78,6 +153,10 @@ pub struct ListFieldsRequest {
     pub start_timestamp: ::core::option::Option<i64>,
     #[prost(int64, optional, tag = "4")]
     pub end_timestamp: ::core::option::Option<i64>,
+    /// Identity of the caller, used to enforce the security policies of the
+    /// targeted indexes. Unset for trusted callers.
+    #[prost(message, optional, tag = "5")]
+    pub security_context: ::core::option::Option<SecurityContext>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -97,6 +176,10 @@ pub struct LeafListFieldsRequest {
     /// Wildcard expressions are supported.
     #[prost(string, repeated, tag = "4")]
     pub fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Name of the storage credentials defined in the node config used to access the index
+    /// storage. Unset when the index uses the default storage configs.
+    #[prost(string, optional, tag = "5")]
+    pub storage_credentials: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -191,6 +274,40 @@ pub struct SearchRequest {
     pub search_after: ::core::option::Option<PartialHit>,
     #[prost(enumeration = "CountHits", tag = "17")]
     pub count_hits: i32,
+    /// Identity of the caller, used to enforce the security policies of the
+    /// targeted indexes. Unset for trusted callers.
+    #[prost(message, optional, tag = "18")]
+    pub security_context: ::core::option::Option<SecurityContext>,
+    /// If set, the search response includes per-split execution statistics.
+    #[prost(bool, tag = "19")]
+    pub profile: bool,
+    /// If set, the search response holds the intermediate aggregation results instead of the final
+    /// ones, so that they can be merged with the results of other clusters.
+    #[prost(bool, tag = "20")]
+    pub return_intermediate_aggregation: bool,
+    /// Fast fields to return in the hits. If set, the hits only hold the values of these fields,
+    /// read from the columnar storage, and the stored documents are not fetched.
+    #[prost(string, repeated, tag = "21")]
+    pub docvalue_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// ID of the search request, used to cancel its leaf searches.
+    #[prost(string, optional, tag = "22")]
+    pub search_request_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[derive(Eq, Hash)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SecurityContext {
+    /// Role associated with the API key of the caller.
+    #[prost(string, tag = "1")]
+    pub role: ::prost::alloc::string::String,
+    /// Claims associated with the API key of the caller, referenced in the
+    /// security policy filters as `{claims.<name>}`.
+    #[prost(btree_map = "string, string", tag = "2")]
+    pub claims: ::prost::alloc::collections::BTreeMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[derive(Eq, Hash)]
@@ -241,6 +358,70 @@ pub struct SearchResponse {
     /// Total number of successful splits searched.
     #[prost(uint64, tag = "8")]
     pub num_successful_splits: u64,
+    /// Execution statistics, only set if `profile` was set in the request.
+    #[prost(message, optional, tag = "9")]
+    pub profile: ::core::option::Option<SearchProfile>,
+    /// Warnings about how the search request was executed, for instance when its time range was
+    /// clamped to the retention period of the searched indexes.
+    #[prost(string, repeated, tag = "10")]
+    pub warnings: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Postcard-serialized intermediate aggregation results, only set if
+    /// `return_intermediate_aggregation` was set in the request.
+    #[prost(bytes = "vec", optional, tag = "11")]
+    pub intermediate_aggregation_result: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+/// Execution statistics of a search request, grouped by searcher node.
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchProfile {
+    #[prost(message, repeated, tag = "1")]
+    pub nodes: ::prost::alloc::vec::Vec<NodeSearchProfile>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeSearchProfile {
+    /// gRPC address of the searcher node.
+    #[prost(string, tag = "1")]
+    pub searcher_addr: ::prost::alloc::string::String,
+    /// Time spent by the node fetching the documents of the hits, in microseconds.
+    #[prost(uint64, tag = "2")]
+    pub fetch_docs_microsecs: u64,
+    #[prost(message, repeated, tag = "3")]
+    pub splits: ::prost::alloc::vec::Vec<SplitSearchProfile>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SplitSearchProfile {
+    #[prost(string, tag = "1")]
+    pub split_id: ::prost::alloc::string::String,
+    /// gRPC address of the searcher node that searched the split, set by the root searcher.
+    #[prost(string, tag = "2")]
+    pub searcher_addr: ::prost::alloc::string::String,
+    /// Whether the leaf response was served from the partial request cache.
+    #[prost(bool, tag = "3")]
+    pub partial_request_cache_hit: bool,
+    /// Whether the split footer was served from the split footer cache.
+    #[prost(bool, tag = "4")]
+    pub split_footer_cache_hit: bool,
+    /// Time spent opening the split, in microseconds.
+    #[prost(uint64, tag = "5")]
+    pub open_microsecs: u64,
+    /// Time spent downloading the data required by the query, in microseconds.
+    #[prost(uint64, tag = "6")]
+    pub warmup_microsecs: u64,
+    /// Time spent executing the query, in microseconds.
+    #[prost(uint64, tag = "7")]
+    pub search_microsecs: u64,
+    /// Number of bytes downloaded during the warmup, by index component
+    /// (`term`, `idx`, `pos`, `fast`, `fieldnorm`, ...).
+    #[prost(btree_map = "string, uint64", tag = "8")]
+    pub warmup_num_bytes_by_component: ::prost::alloc::collections::BTreeMap<
+        ::prost::alloc::string::String,
+        u64,
+    >,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -285,6 +466,10 @@ pub struct LeafSearchRequest {
     /// split files.
     #[prost(string, repeated, tag = "9")]
     pub index_uris: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Whether the caller can decode a compressed `LeafSearchResponse.intermediate_aggregation_result`.
+    /// Leaves only compress the intermediate aggregation result of callers that set this flag.
+    #[prost(bool, tag = "10")]
+    pub accepts_compressed_intermediate_aggregation_result: bool,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -316,6 +501,13 @@ pub struct LeafRequestRef {
     /// This ids are resolved from the index_uri defined in the search_request.
     #[prost(message, repeated, tag = "3")]
     pub split_offsets: ::prost::alloc::vec::Vec<SplitIdAndFooterOffsets>,
+    /// UID of the index, used to account for the bytes read from its splits.
+    #[prost(string, tag = "4")]
+    pub index_uid: ::prost::alloc::string::String,
+    /// Name of the storage credentials defined in the node config used to access the index
+    /// storage. Unset when the index uses the default storage configs.
+    #[prost(string, optional, tag = "5")]
+    pub storage_credentials: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -367,6 +559,9 @@ pub struct LeafHit {
     /// A snippet of the matching content
     #[prost(string, optional, tag = "3")]
     pub leaf_snippet_json: ::core::option::Option<::prost::alloc::string::String>,
+    /// Names of the named clauses of the query matching the hit
+    #[prost(string, repeated, tag = "4")]
+    pub matched_queries: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -384,6 +579,9 @@ pub struct Hit {
     /// The index id of the hit
     #[prost(string, tag = "4")]
     pub index_id: ::prost::alloc::string::String,
+    /// Names of the named clauses of the query matching the hit
+    #[prost(string, repeated, tag = "5")]
+    pub matched_queries: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 /// A partial hit, is a hit for which we have not fetch the content yet.
 /// Instead, it holds a document_uri which is enough information to
@@ -474,6 +672,12 @@ pub struct LeafSearchResponse {
     >,
     #[prost(message, optional, tag = "8")]
     pub resource_stats: ::core::option::Option<ResourceStats>,
+    /// Execution statistics of the searched splits, only set if `profile` was set in the request.
+    #[prost(message, repeated, tag = "9")]
+    pub split_profiles: ::prost::alloc::vec::Vec<SplitSearchProfile>,
+    /// Codec used to encode `intermediate_aggregation_result`. See `quickwit_proto::compression::PayloadCodec`.
+    #[prost(int32, tag = "10")]
+    pub intermediate_aggregation_result_codec: i32,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -505,6 +709,17 @@ pub struct FetchDocsRequest {
     /// `DocMapper` as json serialized trait.
     #[prost(string, tag = "6")]
     pub doc_mapper: ::prost::alloc::string::String,
+    /// Fast fields to read from the columnar storage instead of fetching the stored documents.
+    #[prost(string, repeated, tag = "8")]
+    pub docvalue_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Resolved query AST containing named clauses. When set, the names of the clauses matching
+    /// each hit are returned with the hit.
+    #[prost(string, optional, tag = "9")]
+    pub named_queries_query_ast: ::core::option::Option<::prost::alloc::string::String>,
+    /// Name of the storage credentials defined in the node config used to access the index
+    /// storage. Unset when the index uses the default storage configs.
+    #[prost(string, optional, tag = "10")]
+    pub storage_credentials: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -537,6 +752,10 @@ pub struct ListTermsRequest {
     pub start_key: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
     #[prost(bytes = "vec", optional, tag = "8")]
     pub end_key: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Identity of the caller, used to enforce the security policies of the
+    /// targeted indexes. Unset for trusted callers.
+    #[prost(message, optional, tag = "9")]
+    pub security_context: ::core::option::Option<SecurityContext>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -571,6 +790,13 @@ pub struct LeafListTermsRequest {
     /// split files.
     #[prost(string, tag = "3")]
     pub index_uri: ::prost::alloc::string::String,
+    /// UID of the index, used to account for the bytes read from its splits.
+    #[prost(string, tag = "4")]
+    pub index_uid: ::prost::alloc::string::String,
+    /// Name of the storage credentials defined in the node config used to access the index
+    /// storage. Unset when the index uses the default storage configs.
+    #[prost(string, optional, tag = "5")]
+    pub storage_credentials: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -616,6 +842,10 @@ pub struct SearchStreamRequest {
     /// Fields to extract snippet on.
     #[prost(string, repeated, tag = "10")]
     pub snippet_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Identity of the caller, used to enforce the security policies of the
+    /// targeted index. Unset for trusted callers.
+    #[prost(message, optional, tag = "12")]
+    pub security_context: ::core::option::Option<SecurityContext>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -636,6 +866,13 @@ pub struct LeafSearchStreamRequest {
     /// split files.
     #[prost(string, tag = "6")]
     pub index_uri: ::prost::alloc::string::String,
+    /// UID of the index, used to account for the bytes read from its splits.
+    #[prost(string, tag = "7")]
+    pub index_uid: ::prost::alloc::string::String,
+    /// Name of the storage credentials defined in the node config used to access the index
+    /// storage. Unset when the index uses the default storage configs.
+    #[prost(string, optional, tag = "8")]
+    pub storage_credentials: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -1190,6 +1427,91 @@ pub mod search_service_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        /// Pins or unpins splits in the split cache of the searcher.
+        /// Pinned splits are downloaded first and are never evicted from the cache.
+        pub async fn pin_splits(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PinSplitsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PinSplitsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.search.SearchService/PinSplits",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.search.SearchService", "PinSplits"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Cancels the leaf searches of a search request running on the searcher.
+        pub async fn cancel_leaf_searches(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelLeafSearchesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CancelLeafSearchesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.search.SearchService/CancelLeafSearches",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.search.SearchService", "CancelLeafSearches"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Cancels a search request running on the searcher, along with its leaf searches.
+        pub async fn cancel_search(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelSearchRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CancelSearchResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.search.SearchService/CancelSearch",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.search.SearchService", "CancelSearch"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn list_fields(
             &mut self,
             request: impl tonic::IntoRequest<super::ListFieldsRequest>,
@@ -1375,6 +1697,31 @@ pub mod search_service_server {
             tonic::Response<super::ReportSplitsResponse>,
             tonic::Status,
         >;
+        /// Pins or unpins splits in the split cache of the searcher.
+        /// Pinned splits are downloaded first and are never evicted from the cache.
+        async fn pin_splits(
+            &self,
+            request: tonic::Request<super::PinSplitsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PinSplitsResponse>,
+            tonic::Status,
+        >;
+        /// Cancels the leaf searches of a search request running on the searcher.
+        async fn cancel_leaf_searches(
+            &self,
+            request: tonic::Request<super::CancelLeafSearchesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CancelLeafSearchesResponse>,
+            tonic::Status,
+        >;
+        /// Cancels a search request running on the searcher, along with its leaf searches.
+        async fn cancel_search(
+            &self,
+            request: tonic::Request<super::CancelSearchRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CancelSearchResponse>,
+            tonic::Status,
+        >;
         async fn list_fields(
             &self,
             request: tonic::Request<super::ListFieldsRequest>,
@@ -1925,6 +2272,144 @@ pub mod search_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/quickwit.search.SearchService/PinSplits" => {
+                    #[allow(non_camel_case_types)]
+                    struct PinSplitsSvc<T: SearchService>(pub Arc<T>);
+                    impl<
+                        T: SearchService,
+                    > tonic::server::UnaryService<super::PinSplitsRequest>
+                    for PinSplitsSvc<T> {
+                        type Response = super::PinSplitsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PinSplitsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).pin_splits(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = PinSplitsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.search.SearchService/CancelLeafSearches" => {
+                    #[allow(non_camel_case_types)]
+                    struct CancelLeafSearchesSvc<T: SearchService>(pub Arc<T>);
+                    impl<
+                        T: SearchService,
+                    > tonic::server::UnaryService<super::CancelLeafSearchesRequest>
+                    for CancelLeafSearchesSvc<T> {
+                        type Response = super::CancelLeafSearchesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CancelLeafSearchesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).cancel_leaf_searches(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CancelLeafSearchesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.search.SearchService/CancelSearch" => {
+                    #[allow(non_camel_case_types)]
+                    struct CancelSearchSvc<T: SearchService>(pub Arc<T>);
+                    impl<
+                        T: SearchService,
+                    > tonic::server::UnaryService<super::CancelSearchRequest>
+                    for CancelSearchSvc<T> {
+                        type Response = super::CancelSearchResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CancelSearchRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).cancel_search(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CancelSearchSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/quickwit.search.SearchService/ListFields" => {
                     #[allow(non_camel_case_types)]
                     struct ListFieldsSvc<T: SearchService>(pub Arc<T>);
@@ -0,0 +1,120 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Optional compression of large payloads exchanged between nodes, such as the documents
+//! replicated between ingesters or the intermediate aggregation results returned by leaf
+//! searches.
+//!
+//! Compression is decided by the sender, which records the codec used along with the payload.
+//! Receivers always decode the codec they are given, so nodes with different compression
+//! settings can talk to each other.
+
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
+/// Codec used to encode a payload. The discriminant is the value stored in the `*_codec` fields
+/// of the protobuf messages.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadCodec {
+    #[default]
+    None = 0,
+    Zstd = 1,
+}
+
+impl PayloadCodec {
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Self::None),
+            1 => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Compression applied by a sender to its outgoing payloads.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PayloadCompression {
+    pub codec: PayloadCodec,
+    /// Payloads smaller than this threshold are sent uncompressed.
+    pub min_payload_num_bytes: usize,
+}
+
+impl PayloadCompression {
+    /// Compresses the payload if it is large enough, and returns it along with the codec to
+    /// record in the message. Falls back to the uncompressed payload if compression fails.
+    pub fn compress(&self, payload: Vec<u8>) -> (Vec<u8>, PayloadCodec) {
+        if payload.len() < self.min_payload_num_bytes {
+            return (payload, PayloadCodec::None);
+        }
+        match self.codec {
+            PayloadCodec::None => (payload, PayloadCodec::None),
+            PayloadCodec::Zstd => match zstd::bulk::compress(&payload, ZSTD_COMPRESSION_LEVEL) {
+                Ok(compressed_payload) => (compressed_payload, PayloadCodec::Zstd),
+                Err(_) => (payload, PayloadCodec::None),
+            },
+        }
+    }
+}
+
+/// Decompresses a payload encoded with the codec recorded in a message.
+pub fn decompress_payload(codec: i32, payload: Vec<u8>) -> io::Result<Vec<u8>> {
+    match PayloadCodec::from_i32(codec) {
+        Some(PayloadCodec::None) => Ok(payload),
+        Some(PayloadCodec::Zstd) => zstd::decode_all(payload.as_slice()),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unknown payload codec `{codec}`"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_compression() {
+        let payload = b"hello world ".repeat(100);
+
+        let no_compression = PayloadCompression::default();
+        let (encoded_payload, codec) = no_compression.compress(payload.clone());
+        assert_eq!(codec, PayloadCodec::None);
+        assert_eq!(encoded_payload, payload);
+
+        let zstd_compression = PayloadCompression {
+            codec: PayloadCodec::Zstd,
+            min_payload_num_bytes: 2_000,
+        };
+        let (encoded_payload, codec) = zstd_compression.compress(payload.clone());
+        assert_eq!(codec, PayloadCodec::None);
+        assert_eq!(encoded_payload, payload);
+
+        let zstd_compression = PayloadCompression {
+            codec: PayloadCodec::Zstd,
+            min_payload_num_bytes: 1_000,
+        };
+        let (encoded_payload, codec) = zstd_compression.compress(payload.clone());
+        assert_eq!(codec, PayloadCodec::Zstd);
+        assert!(encoded_payload.len() < payload.len());
+
+        let decoded_payload = decompress_payload(codec as i32, encoded_payload).unwrap();
+        assert_eq!(decoded_payload, payload);
+
+        decompress_payload(42, payload).unwrap_err();
+    }
+}
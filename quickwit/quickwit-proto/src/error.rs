@@ -34,6 +34,10 @@ const QW_ERROR_HEADER_NAME: &str = "qw-error-bin";
 pub enum ServiceErrorCode {
     AlreadyExists,
     BadRequest,
+    // The request was cancelled by the caller.
+    Cancelled,
+    // The request conflicts with the current state of the target resource.
+    Conflict,
     // Use `Unauthenticated` if the caller cannot be identified.
     Forbidden,
     Internal,
@@ -49,6 +53,8 @@ impl ServiceErrorCode {
         match self {
             Self::AlreadyExists => tonic::Code::AlreadyExists,
             Self::BadRequest => tonic::Code::InvalidArgument,
+            Self::Cancelled => tonic::Code::Cancelled,
+            Self::Conflict => tonic::Code::FailedPrecondition,
             Self::Forbidden => tonic::Code::PermissionDenied,
             Self::Internal => tonic::Code::Internal,
             Self::NotFound => tonic::Code::NotFound,
@@ -59,10 +65,40 @@ impl ServiceErrorCode {
         }
     }
 
+    /// Returns the stable, machine-readable code of the error exposed by the REST API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AlreadyExists => "already_exists",
+            Self::BadRequest => "bad_request",
+            Self::Cancelled => "cancelled",
+            Self::Conflict => "conflict",
+            Self::Forbidden => "forbidden",
+            Self::Internal => "internal",
+            Self::NotFound => "not_found",
+            Self::Timeout => "timeout",
+            Self::TooManyRequests => "too_many_requests",
+            Self::Unauthenticated => "unauthenticated",
+            Self::Unavailable => "unavailable",
+        }
+    }
+
+    /// Returns whether a request failing with this error may succeed if retried as is.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Timeout | Self::TooManyRequests | Self::Unavailable
+        )
+    }
+
     pub fn http_status_code(&self) -> http::StatusCode {
         match self {
             Self::AlreadyExists => http::StatusCode::BAD_REQUEST,
             Self::BadRequest => http::StatusCode::BAD_REQUEST,
+            // Non-standard "Client Closed Request" status code.
+            Self::Cancelled => {
+                http::StatusCode::from_u16(499).expect("499 should be a valid status code")
+            }
+            Self::Conflict => http::StatusCode::CONFLICT,
             Self::Forbidden => http::StatusCode::FORBIDDEN,
             Self::Internal => http::StatusCode::INTERNAL_SERVER_ERROR,
             Self::NotFound => http::StatusCode::NOT_FOUND,
@@ -113,8 +113,10 @@ generate_getters! {
     TruncateShardsSubrequest,
 
     // Metastore API
+    AccumulateIndexUsageSubrequest,
     AcquireShardsRequest,
     AddSourceRequest,
+    AddTagOverlayRequest,
     CreateIndexResponse,
     DeleteIndexRequest,
     DeleteQuery,
@@ -122,6 +124,7 @@ generate_getters! {
     DeleteShardsResponse,
     DeleteSourceRequest,
     DeleteSplitsRequest,
+    GetIndexUsageRequest,
     LastDeleteOpstampRequest,
     ListDeleteTasksRequest,
     ListShardsSubrequest,
@@ -134,6 +137,7 @@ generate_getters! {
     ResetSourceCheckpointRequest,
     StageSplitsRequest,
     ToggleSourceRequest,
+    TrashIndexRequest,
     UpdateIndexRequest,
     UpdateSourceRequest,
     UpdateSplitsDeleteOpstampRequest
@@ -12,8 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io;
+
+use bytes::Bytes;
 use bytesize::ByteSize;
 
+use crate::compression::{decompress_payload, PayloadCodec, PayloadCompression};
 use crate::types::{queue_id, Position, QueueId};
 
 include!("../codegen/quickwit/quickwit.ingest.ingester.rs");
@@ -177,6 +181,34 @@ impl ReplicateSubrequest {
     pub fn queue_id(&self) -> QueueId {
         queue_id(self.index_uid(), &self.source_id, self.shard_id())
     }
+
+    /// Compresses the documents of the subrequest before sending it to the follower.
+    pub fn compress_doc_batch(&mut self, compression: &PayloadCompression) {
+        if self.doc_buffer_codec != PayloadCodec::None as i32 {
+            return;
+        }
+        if let Some(doc_batch) = &mut self.doc_batch {
+            let doc_buffer = std::mem::take(&mut doc_batch.doc_buffer);
+            let (encoded_doc_buffer, codec) = compression.compress(doc_buffer.to_vec());
+            doc_batch.doc_buffer = Bytes::from(encoded_doc_buffer);
+            self.doc_buffer_codec = codec as i32;
+        }
+    }
+
+    /// Decompresses the documents of a subrequest received from the leader.
+    pub fn decompress_doc_batch(&mut self) -> io::Result<()> {
+        if self.doc_buffer_codec == PayloadCodec::None as i32 {
+            return Ok(());
+        }
+        if let Some(doc_batch) = &mut self.doc_batch {
+            let encoded_doc_buffer = std::mem::take(&mut doc_batch.doc_buffer);
+            let doc_buffer =
+                decompress_payload(self.doc_buffer_codec, encoded_doc_buffer.to_vec())?;
+            doc_batch.doc_buffer = Bytes::from(doc_buffer);
+        }
+        self.doc_buffer_codec = PayloadCodec::None as i32;
+        Ok(())
+    }
 }
 
 impl TruncateShardsSubrequest {
@@ -26,6 +26,7 @@ use tracing::Span;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 pub mod cluster;
+pub mod compression;
 pub mod control_plane;
 pub use {bytes, tonic};
 pub mod developer;
@@ -387,6 +387,34 @@ impl ListDeleteTasksRequest {
     }
 }
 
+/// Duration of the periods the index usage is recorded by.
+pub const INDEX_USAGE_PERIOD_SECS: i64 = 24 * 3_600;
+
+/// Returns the start of the index usage period, i.e. the UTC day, containing `timestamp`.
+pub fn index_usage_period_start(timestamp: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(INDEX_USAGE_PERIOD_SECS)
+}
+
+impl IndexUsage {
+    /// Adds the counters of `delta` to the counters of this index usage.
+    pub fn accumulate(&mut self, delta: &IndexUsage) {
+        self.num_docs_ingested = self
+            .num_docs_ingested
+            .saturating_add(delta.num_docs_ingested);
+        self.num_bytes_ingested = self
+            .num_bytes_ingested
+            .saturating_add(delta.num_bytes_ingested);
+        self.num_queries = self.num_queries.saturating_add(delta.num_queries);
+        self.num_bytes_scanned = self
+            .num_bytes_scanned
+            .saturating_add(delta.num_bytes_scanned);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 pub mod serde_utils {
     use serde::de::DeserializeOwned;
     use serde::{Deserialize, Serialize};
@@ -471,6 +499,17 @@ impl ListIndexesMetadataRequest {
     pub fn all() -> ListIndexesMetadataRequest {
         ListIndexesMetadataRequest {
             index_id_patterns: vec!["*".to_string()],
+            include_trashed: false,
+            label_selectors: Vec::new(),
+        }
+    }
+
+    /// Lists all the indexes, including the ones in the trash.
+    pub fn all_including_trashed() -> ListIndexesMetadataRequest {
+        ListIndexesMetadataRequest {
+            index_id_patterns: vec!["*".to_string()],
+            include_trashed: true,
+            label_selectors: Vec::new(),
         }
     }
 }
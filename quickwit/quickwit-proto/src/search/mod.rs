@@ -19,6 +19,8 @@ use std::io::{self, Read};
 use prost::Message;
 pub use sort_by_value::SortValue;
 
+use crate::compression::{decompress_payload, PayloadCodec, PayloadCompression};
+
 include!("../codegen/quickwit/quickwit.search.rs");
 
 pub const SEARCH_FILE_DESCRIPTOR_SET: &[u8] =
@@ -46,6 +48,36 @@ impl SplitIdAndFooterOffsets {
     }
 }
 
+impl LeafSearchResponse {
+    /// Compresses the intermediate aggregation result before sending the response to another
+    /// node.
+    pub fn compress_intermediate_aggregation_result(&mut self, compression: &PayloadCompression) {
+        if self.intermediate_aggregation_result_codec != PayloadCodec::None as i32 {
+            return;
+        }
+        if let Some(payload) = self.intermediate_aggregation_result.take() {
+            let (encoded_payload, codec) = compression.compress(payload);
+            self.intermediate_aggregation_result = Some(encoded_payload);
+            self.intermediate_aggregation_result_codec = codec as i32;
+        }
+    }
+
+    /// Decompresses the intermediate aggregation result of a response received from another
+    /// node.
+    pub fn decompress_intermediate_aggregation_result(&mut self) -> io::Result<()> {
+        if self.intermediate_aggregation_result_codec == PayloadCodec::None as i32 {
+            return Ok(());
+        }
+        if let Some(encoded_payload) = self.intermediate_aggregation_result.take() {
+            let payload =
+                decompress_payload(self.intermediate_aggregation_result_codec, encoded_payload)?;
+            self.intermediate_aggregation_result = Some(payload);
+        }
+        self.intermediate_aggregation_result_codec = PayloadCodec::None as i32;
+        Ok(())
+    }
+}
+
 impl fmt::Display for SplitSearchError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "({}, split_id: {})", self.error, self.split_id)
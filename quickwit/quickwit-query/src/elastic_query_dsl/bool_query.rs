@@ -43,6 +43,9 @@ pub struct BoolQuery {
     pub boost: Option<NotNaNf32>,
     #[serde(default)]
     pub minimum_should_match: Option<MinimumShouldMatch>,
+    #[serde(rename = "_name")]
+    #[serde(default)]
+    pub name: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -126,6 +129,7 @@ impl BoolQuery {
             filter: Vec::new(),
             boost: None,
             minimum_should_match: None,
+            name: None,
         }
     }
 }
@@ -159,7 +163,7 @@ impl ConvertibleToQueryAst for BoolQuery {
             filter,
             minimum_should_match: minimum_should_match_opt,
         };
-        Ok(bool_query_ast.into())
+        Ok(QueryAst::from(bool_query_ast).named(self.name))
     }
 }
 
@@ -199,7 +203,8 @@ mod tests {
                 should: Vec::new(),
                 filter: Vec::new(),
                 boost: None,
-                minimum_should_match: None
+                minimum_should_match: None,
+                name: None,
             }
         );
     }
@@ -220,6 +225,7 @@ mod tests {
                 filter: vec![term_query_from_field_value("product_id", "2").into(),],
                 boost: None,
                 minimum_should_match: None,
+                name: None,
             }
         );
     }
@@ -243,6 +249,7 @@ mod tests {
                 filter: Vec::new(),
                 boost: None,
                 minimum_should_match: None,
+                name: None,
             }
         );
     }
@@ -288,6 +295,26 @@ mod tests {
         assert_eq!(bool_query_ast.minimum_should_match, Some(2));
     }
 
+    #[test]
+    fn test_dsl_query_with_named_clauses() {
+        let bool_query_json = r#"{
+                "should": [
+                    { "term": {"product_id": {"value": "1", "_name": "first" }} },
+                    { "term": {"product_id": {"value": "2" }} }
+                ],
+                "_name": "products"
+            }"#;
+        let bool_query: BoolQuery = serde_json::from_str(bool_query_json).unwrap();
+        assert_eq!(bool_query.name.as_deref(), Some("products"));
+        let query_ast = bool_query.convert_to_query_ast().unwrap();
+        let named_queries: Vec<&str> = query_ast
+            .named_queries()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(named_queries, ["products", "first"]);
+    }
+
     #[test]
     fn test_parse_percentage() {
         assert_eq!(parse_percentage("10%"), Some(10));
@@ -20,13 +20,16 @@ use crate::query_ast::{self, QueryAst};
 #[derive(Deserialize, Clone, Eq, PartialEq, Debug)]
 pub struct ExistsQuery {
     field: String,
+    #[serde(rename = "_name")]
+    #[serde(default)]
+    name: Option<String>,
 }
 
 impl ConvertibleToQueryAst for ExistsQuery {
     fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
-        Ok(QueryAst::FieldPresence(query_ast::FieldPresenceQuery {
-            field: self.field,
-        }))
+        let field_presence_query_ast =
+            QueryAst::FieldPresence(query_ast::FieldPresenceQuery { field: self.field });
+        Ok(field_presence_query_ast.named(self.name))
     }
 }
 
@@ -44,6 +47,7 @@ mod tests {
             &bool_query,
             &ExistsQuery {
                 field: "privileged".to_string(),
+                name: None,
             }
         );
     }
@@ -39,12 +39,13 @@ impl ConvertibleToQueryAst for MatchBoolPrefixQuery {
             },
             zero_terms_query: self.params.zero_terms_query,
         };
-        Ok(QueryAst::FullText(FullTextQuery {
+        let full_text_query_ast = QueryAst::FullText(FullTextQuery {
             field: self.field,
             text: self.params.query,
             params: full_text_params,
             lenient: self.params.lenient,
-        }))
+        });
+        Ok(full_text_query_ast.named(self.params.name))
     }
 }
 
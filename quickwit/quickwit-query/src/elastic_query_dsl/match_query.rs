@@ -40,6 +40,9 @@ pub(crate) struct MatchQueryParams {
     pub(crate) zero_terms_query: MatchAllOrNone,
     #[serde(default)]
     pub(crate) lenient: LeniencyBool,
+    #[serde(rename = "_name")]
+    #[serde(default)]
+    pub(crate) name: Option<String>,
 }
 
 impl ConvertibleToQueryAst for MatchQuery {
@@ -49,12 +52,13 @@ impl ConvertibleToQueryAst for MatchQuery {
             mode: self.params.operator.into(),
             zero_terms_query: self.params.zero_terms_query,
         };
-        Ok(QueryAst::FullText(FullTextQuery {
+        let full_text_query_ast = QueryAst::FullText(FullTextQuery {
             field: self.field,
             text: self.params.query,
             params: full_text_params,
             lenient: self.params.lenient,
-        }))
+        });
+        Ok(full_text_query_ast.named(self.params.name))
     }
 }
 
@@ -83,6 +87,7 @@ impl From<String> for MatchQueryParams {
             zero_terms_query: Default::default(),
             operator: Default::default(),
             lenient: false,
+            name: None,
         }
     }
 }
@@ -132,6 +137,7 @@ mod tests {
                 operator: BooleanOperand::And,
                 zero_terms_query: crate::MatchAllOrNone::MatchAll,
                 lenient: false,
+                name: None,
             },
         };
         let ast = match_query.convert_to_query_ast().unwrap();
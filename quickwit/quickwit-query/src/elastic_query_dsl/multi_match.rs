@@ -192,6 +192,7 @@ mod tests {
                         operator: crate::BooleanOperand::Or,
                         zero_terms_query: Default::default(),
                         lenient: false,
+                        name: None,
                     },
                 }
                 .into(),
@@ -202,6 +203,7 @@ mod tests {
                         operator: crate::BooleanOperand::Or,
                         zero_terms_query: Default::default(),
                         lenient: false,
+                        name: None,
                     },
                 }
                 .into(),
@@ -222,6 +224,7 @@ mod tests {
                         operator: crate::BooleanOperand::Or,
                         zero_terms_query: Default::default(),
                         lenient: false,
+                        name: None,
                     },
                 }
                 .into(),
@@ -232,6 +235,7 @@ mod tests {
                         operator: crate::BooleanOperand::Or,
                         zero_terms_query: Default::default(),
                         lenient: false,
+                        name: None,
                     },
                 }
                 .into(),
@@ -252,6 +256,7 @@ mod tests {
                         operator: crate::BooleanOperand::Or,
                         zero_terms_query: Default::default(),
                         lenient: false,
+                        name: None,
                     },
                 }
                 .into(),
@@ -262,6 +267,7 @@ mod tests {
                         operator: crate::BooleanOperand::Or,
                         zero_terms_query: Default::default(),
                         lenient: false,
+                        name: None,
                     },
                 }
                 .into(),
@@ -344,6 +350,7 @@ mod tests {
                         operator: crate::BooleanOperand::Or,
                         zero_terms_query: Default::default(),
                         lenient: false,
+                        name: None,
                     },
                 }
                 .into(),
@@ -354,6 +361,7 @@ mod tests {
                         operator: crate::BooleanOperand::Or,
                         zero_terms_query: Default::default(),
                         lenient: false,
+                        name: None,
                     },
                 }
                 .into(),
@@ -17,7 +17,7 @@ use serde::Deserialize;
 use super::LeniencyBool;
 use crate::elastic_query_dsl::ConvertibleToQueryAst;
 use crate::not_nan_f32::NotNaNf32;
-use crate::query_ast::UserInputQuery;
+use crate::query_ast::{QueryAst, UserInputQuery};
 use crate::BooleanOperand;
 
 #[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -38,10 +38,13 @@ pub(crate) struct QueryStringQuery {
     boost: Option<NotNaNf32>,
     #[serde(default)]
     lenient: LeniencyBool,
+    #[serde(rename = "_name")]
+    #[serde(default)]
+    name: Option<String>,
 }
 
 impl ConvertibleToQueryAst for QueryStringQuery {
-    fn convert_to_query_ast(self) -> anyhow::Result<crate::query_ast::QueryAst> {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
         if self.default_field.is_some() && self.fields.is_some() {
             anyhow::bail!("fields and default_field cannot be both set in `query_string` queries");
         }
@@ -52,10 +55,10 @@ impl ConvertibleToQueryAst for QueryStringQuery {
         let user_text_query = UserInputQuery {
             user_text: self.query,
             default_fields,
-            default_operator: self.default_operator,
-            lenient: self.lenient,
+            default_operator: Some(self.default_operator),
+            lenient: Some(self.lenient),
         };
-        Ok(user_text_query.into())
+        Ok(QueryAst::from(user_text_query).named(self.name))
     }
 }
 
@@ -73,6 +76,7 @@ mod tests {
             default_operator: crate::BooleanOperand::Or,
             default_field: None,
             boost: None,
+            name: None,
             lenient: false,
         };
         let QueryAst::UserInput(user_input_query) =
@@ -80,7 +84,7 @@ mod tests {
         else {
             panic!();
         };
-        assert_eq!(user_input_query.default_operator, BooleanOperand::Or);
+        assert_eq!(user_input_query.default_operator, Some(BooleanOperand::Or));
         assert_eq!(
             user_input_query.default_fields.unwrap(),
             vec!["hello".to_string()]
@@ -95,6 +99,7 @@ mod tests {
             default_operator: crate::BooleanOperand::Or,
             default_field: Some("hello".to_string()),
             boost: None,
+            name: None,
             lenient: false,
         };
         let QueryAst::UserInput(user_input_query) =
@@ -102,7 +107,7 @@ mod tests {
         else {
             panic!();
         };
-        assert_eq!(user_input_query.default_operator, BooleanOperand::Or);
+        assert_eq!(user_input_query.default_operator, Some(BooleanOperand::Or));
         assert_eq!(
             user_input_query.default_fields.unwrap(),
             vec!["hello".to_string()]
@@ -117,6 +122,7 @@ mod tests {
             default_operator: crate::BooleanOperand::Or,
             default_field: Some("hello".to_string()),
             boost: None,
+            name: None,
             lenient: false,
         };
         let err_msg = query_string_query
@@ -134,6 +140,7 @@ mod tests {
             default_field: None,
             default_operator: crate::BooleanOperand::And,
             boost: None,
+            name: None,
             lenient: false,
         };
         let QueryAst::UserInput(user_input_query) =
@@ -141,7 +148,7 @@ mod tests {
         else {
             panic!();
         };
-        assert_eq!(user_input_query.default_operator, BooleanOperand::And);
+        assert_eq!(user_input_query.default_operator, Some(BooleanOperand::And));
     }
 
     #[test]
@@ -152,6 +159,7 @@ mod tests {
             default_field: None,
             default_operator: crate::BooleanOperand::Or,
             boost: None,
+            name: None,
             lenient: false,
         };
         let QueryAst::UserInput(user_input_query) =
@@ -159,7 +167,7 @@ mod tests {
         else {
             panic!();
         };
-        assert_eq!(user_input_query.default_operator, BooleanOperand::Or);
+        assert_eq!(user_input_query.default_operator, Some(BooleanOperand::Or));
         assert!(user_input_query.default_fields.unwrap().is_empty());
     }
 
@@ -171,6 +179,7 @@ mod tests {
             default_field: None,
             default_operator: crate::BooleanOperand::Or,
             boost: None,
+            name: None,
             lenient: false,
         };
         let QueryAst::UserInput(user_input_query) =
@@ -197,7 +206,7 @@ mod tests {
             default_operator,
             lenient: _,
         }) if user_text == "hello world"
-            && default_operator == BooleanOperand::Or
+            && default_operator == Some(BooleanOperand::Or)
             && default_fields == Some(vec!["text".to_string()])));
     }
 }
@@ -14,7 +14,7 @@
 
 use std::ops::Bound;
 
-use quickwit_datetime::StrptimeParser;
+use quickwit_datetime::{is_date_math_expression, StrptimeParser};
 use serde::Deserialize;
 use time::format_description::well_known::Rfc3339;
 
@@ -39,6 +39,9 @@ pub struct RangeQueryParams {
     boost: Option<NotNaNf32>,
     #[serde(default)]
     format: Option<JsonLiteral>,
+    #[serde(rename = "_name")]
+    #[serde(default)]
+    name: Option<String>,
 }
 
 pub type RangeQuery = OneFieldMap<RangeQueryParams>;
@@ -53,6 +56,7 @@ impl ConvertibleToQueryAst for RangeQuery {
             lte,
             boost,
             format,
+            name,
         } = self.value;
         let (gt, gte, lt, lte) = if let Some(JsonLiteral::String(java_date_format)) = format {
             let parser = StrptimeParser::from_java_datetime_format(&java_date_format)
@@ -87,12 +91,17 @@ impl ConvertibleToQueryAst for RangeQuery {
             },
         };
         let ast: QueryAst = range_query_ast.into();
-        Ok(ast.boost(boost))
+        Ok(ast.boost(boost).named(name))
     }
 }
 
 fn parse_and_convert(literal: JsonLiteral, parser: &StrptimeParser) -> anyhow::Result<JsonLiteral> {
     if let JsonLiteral::String(date_time_str) = literal {
+        // Date math expressions are evaluated against the schema, once we know the field is a
+        // datetime field.
+        if is_date_math_expression(&date_time_str) {
+            return Ok(JsonLiteral::String(date_time_str));
+        }
         let parsed_date_time = parser
             .parse_date_time(&date_time_str)
             .map_err(|reason| anyhow::anyhow!("Failed to parse date time: {}", reason))?;
@@ -121,6 +130,7 @@ mod tests {
             lte: None,
             boost: None,
             format: JsonLiteral::String("yyyy-MM-dd['T'HH:mm:ss]".to_string()).into(),
+            name: None,
         };
         let range_query: ElasticRangeQuery = ElasticRangeQuery {
             field: "date".to_string(),
@@ -147,6 +157,7 @@ mod tests {
             lte: Some(JsonLiteral::String("2024-09-28T10:22:55.797Z".to_string())),
             boost: None,
             format: JsonLiteral::String("strict_date_optional_time".to_string()).into(),
+            name: None,
         };
         let range_query: ElasticRangeQuery = ElasticRangeQuery {
             field: "timestamp".to_string(),
@@ -163,4 +174,30 @@ mod tests {
             if field == "timestamp" && upper_bound == JsonLiteral::String("2024-09-28T10:22:55.797Z".to_string())
         ));
     }
+
+    #[test]
+    fn test_date_range_query_with_date_math() {
+        let range_query_params = ElasticRangeQueryParams {
+            gt: None,
+            gte: Some(JsonLiteral::String("now-15m".to_string())),
+            lt: None,
+            lte: Some(JsonLiteral::String("now".to_string())),
+            boost: None,
+            format: JsonLiteral::String("strict_date_optional_time".to_string()).into(),
+            name: None,
+        };
+        let range_query: ElasticRangeQuery = ElasticRangeQuery {
+            field: "timestamp".to_string(),
+            value: range_query_params,
+        };
+        let range_query_ast = range_query.convert_to_query_ast().unwrap();
+        assert_eq!(
+            range_query_ast,
+            QueryAst::Range(RangeQuery {
+                field: "timestamp".to_string(),
+                lower_bound: Bound::Included(JsonLiteral::String("now-15m".to_string())),
+                upper_bound: Bound::Included(JsonLiteral::String("now".to_string())),
+            })
+        );
+    }
 }
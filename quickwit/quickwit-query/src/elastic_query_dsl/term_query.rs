@@ -41,6 +41,7 @@ impl From<String> for TermQueryParams {
         TermQueryParams {
             value: query,
             boost: None,
+            name: None,
         }
     }
 }
@@ -70,6 +71,9 @@ pub struct TermQueryParams {
     pub value: String,
     #[serde(default)]
     pub boost: Option<NotNaNf32>,
+    #[serde(rename = "_name")]
+    #[serde(default)]
+    pub name: Option<String>,
 }
 
 pub fn term_query_from_field_value(field: impl ToString, value: impl ToString) -> TermQuery {
@@ -78,6 +82,7 @@ pub fn term_query_from_field_value(field: impl ToString, value: impl ToString) -
         value: TermQueryParams {
             value: value.to_string(),
             boost: None,
+            name: None,
         },
     }
 }
@@ -90,13 +95,13 @@ impl From<TermQuery> for ElasticQueryDslInner {
 
 impl ConvertibleToQueryAst for TermQuery {
     fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
-        let TermQueryParams { value, boost } = self.value;
+        let TermQueryParams { value, boost, name } = self.value;
         let term_ast: QueryAst = query_ast::TermQuery {
             field: self.field,
             value,
         }
         .into();
-        Ok(term_ast.boost(boost))
+        Ok(term_ast.boost(boost).named(name))
     }
 }
 
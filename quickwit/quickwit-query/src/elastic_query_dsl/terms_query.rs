@@ -21,18 +21,27 @@ use crate::elastic_query_dsl::{ConvertibleToQueryAst, ElasticQueryDslInner};
 use crate::not_nan_f32::NotNaNf32;
 use crate::query_ast::QueryAst;
 
+/// Maximum number of values accepted in a `terms` query, mirroring Elasticsearch's
+/// `index.max_terms_count` default. Each value is expanded into a term clause, so larger queries
+/// are too expensive to plan and execute.
+const MAX_TERMS_COUNT: usize = 65_536;
+
 #[derive(PartialEq, Eq, Debug, Deserialize, Clone)]
 #[serde(try_from = "TermsQueryForSerialization")]
 pub struct TermsQuery {
     pub boost: Option<NotNaNf32>,
     pub field: String,
     pub values: Vec<String>,
+    pub name: Option<String>,
 }
 
 #[derive(Deserialize)]
 struct TermsQueryForSerialization {
     #[serde(default)]
     boost: Option<NotNaNf32>,
+    #[serde(rename = "_name")]
+    #[serde(default)]
+    name: Option<String>,
     #[serde(flatten)]
     capture_other: serde_json::Value,
 }
@@ -62,12 +71,21 @@ impl TryFrom<TermsQueryForSerialization> for TermsQuery {
             boost: value.boost,
             field: one_field.field,
             values: one_field_values,
+            name: value.name,
         })
     }
 }
 
 impl ConvertibleToQueryAst for TermsQuery {
     fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        if self.values.len() > MAX_TERMS_COUNT {
+            anyhow::bail!(
+                "`terms` query on field `{}` has {} values, which exceeds the maximum of \
+                 {MAX_TERMS_COUNT}",
+                self.field,
+                self.values.len()
+            );
+        }
         let term_queries: Vec<ElasticQueryDslInner> = self
             .values
             .into_iter()
@@ -76,6 +94,7 @@ impl ConvertibleToQueryAst for TermsQuery {
             .collect();
         let mut union = BoolQuery::union(term_queries);
         union.boost = self.boost;
+        union.name = self.name;
         union.convert_to_query_ast()
     }
 }
@@ -121,4 +140,18 @@ mod tests {
         let boost: f32 = terms_query.boost.unwrap().into();
         assert!((boost - 2.0f32).abs() < 0.0001f32);
     }
+
+    #[test]
+    fn test_terms_query_too_many_values() {
+        let terms_query = TermsQuery {
+            boost: None,
+            field: "user.id".to_string(),
+            values: vec!["hello".to_string(); MAX_TERMS_COUNT + 1],
+            name: None,
+        };
+        let error = terms_query.convert_to_query_ast().unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("`terms` query on field `user.id` has 65537 values"));
+    }
 }
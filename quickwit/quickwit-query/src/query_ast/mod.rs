@@ -36,12 +36,12 @@ pub use bool_query::BoolQuery;
 pub use field_presence::FieldPresenceQuery;
 pub use full_text_query::{FullTextMode, FullTextParams, FullTextQuery};
 pub use phrase_prefix_query::PhrasePrefixQuery;
-pub use range_query::RangeQuery;
+pub use range_query::{is_date_field, DateMathResolver, RangeQuery};
 pub use regex_query::{AutomatonQuery, JsonPathPrefix, RegexQuery};
 use tantivy_query_ast::TantivyQueryAst;
 pub use term_query::TermQuery;
 pub use term_set_query::TermSetQuery;
-pub use user_input_query::UserInputQuery;
+pub use user_input_query::{UserInputQuery, UserQueryDefaults};
 pub use visitor::{QueryAstTransformer, QueryAstVisitor};
 pub use wildcard_query::WildcardQuery;
 
@@ -67,12 +67,29 @@ pub enum QueryAst {
         underlying: Box<QueryAst>,
         boost: NotNaNf32,
     },
+    /// Labels a query clause so that the hits matching it can report its name. Naming a clause
+    /// has no effect on the matched documents or their scores.
+    Named {
+        underlying: Box<QueryAst>,
+        name: String,
+    },
 }
 
 impl QueryAst {
     pub fn parse_user_query(
         self: QueryAst,
         default_search_fields: &[String],
+    ) -> anyhow::Result<QueryAst> {
+        self.parse_user_query_with_defaults(UserQueryDefaults::with_search_fields(
+            default_search_fields,
+        ))
+    }
+
+    /// Parses the user input queries of the AST, applying the given defaults to the ones that do
+    /// not specify their search fields, default operator, or leniency.
+    pub fn parse_user_query_with_defaults(
+        self: QueryAst,
+        defaults: UserQueryDefaults,
     ) -> anyhow::Result<QueryAst> {
         match self {
             QueryAst::Bool(BoolQuery {
@@ -82,10 +99,10 @@ impl QueryAst {
                 filter,
                 minimum_should_match,
             }) => {
-                let must = parse_user_query_in_asts(must, default_search_fields)?;
-                let must_not = parse_user_query_in_asts(must_not, default_search_fields)?;
-                let should = parse_user_query_in_asts(should, default_search_fields)?;
-                let filter = parse_user_query_in_asts(filter, default_search_fields)?;
+                let must = parse_user_query_in_asts(must, defaults)?;
+                let must_not = parse_user_query_in_asts(must_not, defaults)?;
+                let should = parse_user_query_in_asts(should, defaults)?;
+                let filter = parse_user_query_in_asts(filter, defaults)?;
                 Ok(BoolQuery {
                     must,
                     must_not,
@@ -106,18 +123,44 @@ impl QueryAst {
             | ast @ QueryAst::Wildcard(_)
             | ast @ QueryAst::Regex(_) => Ok(ast),
             QueryAst::UserInput(user_text_query) => {
-                user_text_query.parse_user_query(default_search_fields)
+                user_text_query.parse_user_query_with_defaults(defaults)
             }
             QueryAst::Boost { underlying, boost } => {
-                let underlying = underlying.parse_user_query(default_search_fields)?;
+                let underlying = underlying.parse_user_query_with_defaults(defaults)?;
                 Ok(QueryAst::Boost {
                     underlying: Box::new(underlying),
                     boost,
                 })
             }
+            QueryAst::Named { underlying, name } => {
+                let underlying = underlying.parse_user_query_with_defaults(defaults)?;
+                Ok(QueryAst::Named {
+                    underlying: Box::new(underlying),
+                    name,
+                })
+            }
         }
     }
 
+    pub fn named(self, name_opt: Option<String>) -> Self {
+        let Some(name) = name_opt else {
+            return self;
+        };
+        QueryAst::Named {
+            underlying: Box::new(self),
+            name,
+        }
+    }
+
+    /// Returns the named clauses of the query, in the order they appear in the query.
+    pub fn named_queries(&self) -> Vec<(&str, &QueryAst)> {
+        let mut named_queries_collector = NamedQueriesCollector::default();
+        named_queries_collector
+            .visit(self)
+            .expect("can't fail unwrapping Infallible");
+        named_queries_collector.named_queries
+    }
+
     pub fn boost(self, scale_boost_opt: Option<NotNaNf32>) -> Self {
         let Some(scale_boost) = scale_boost_opt else {
             return self;
@@ -216,6 +259,12 @@ impl BuildTantivyAst for QueryAst {
                 let boost_query = TantivyBoostQuery::new(underlying.into(), (*boost).into());
                 Ok(boost_query.into())
             }
+            QueryAst::Named { underlying, .. } => underlying.build_tantivy_ast_call(
+                schema,
+                tokenizer_manager,
+                search_fields,
+                with_validation,
+            ),
             QueryAst::TermSet(term_set) => term_set.build_tantivy_ast_call(
                 schema,
                 tokenizer_manager,
@@ -272,12 +321,26 @@ impl QueryAst {
     }
 }
 
+#[derive(Default)]
+struct NamedQueriesCollector<'a> {
+    named_queries: Vec<(&'a str, &'a QueryAst)>,
+}
+
+impl<'a> QueryAstVisitor<'a> for NamedQueriesCollector<'a> {
+    type Err = std::convert::Infallible;
+
+    fn visit_named(&mut self, underlying: &'a QueryAst, name: &'a str) -> Result<(), Self::Err> {
+        self.named_queries.push((name, underlying));
+        self.visit(underlying)
+    }
+}
+
 fn parse_user_query_in_asts(
     asts: Vec<QueryAst>,
-    default_search_fields: &[String],
+    defaults: UserQueryDefaults,
 ) -> anyhow::Result<Vec<QueryAst>> {
     asts.into_iter()
-        .map(|ast| ast.parse_user_query(default_search_fields))
+        .map(|ast| ast.parse_user_query_with_defaults(defaults))
         .collect::<anyhow::Result<_>>()
 }
 
@@ -322,8 +385,8 @@ pub fn query_ast_from_user_text(user_text: &str, default_fields: Option<Vec<Stri
     UserInputQuery {
         user_text: user_text.to_string(),
         default_fields,
-        default_operator: BooleanOperand::And,
-        lenient: false,
+        default_operator: None,
+        lenient: None,
     }
     .into()
 }
@@ -333,6 +396,7 @@ mod tests {
     use crate::query_ast::tantivy_query_ast::TantivyQueryAst;
     use crate::query_ast::{
         query_ast_from_user_text, BoolQuery, BuildTantivyAst, QueryAst, UserInputQuery,
+        UserQueryDefaults,
     };
     use crate::{create_default_quickwit_tokenizer_manager, BooleanOperand, InvalidQuery};
 
@@ -342,7 +406,7 @@ mod tests {
             user_text: "*".to_string(),
             default_fields: Default::default(),
             default_operator: Default::default(),
-            lenient: false,
+            lenient: Some(false),
         }
         .into();
         let schema = tantivy::schema::Schema::builder().build();
@@ -366,7 +430,7 @@ mod tests {
             user_text: "*".to_string(),
             default_fields: Default::default(),
             default_operator: Default::default(),
-            lenient: false,
+            lenient: Some(false),
         }
         .into();
         let query_ast_with_parsed_user_query: QueryAst = query_ast.parse_user_query(&[]).unwrap();
@@ -388,7 +452,7 @@ mod tests {
             user_text: "*".to_string(),
             default_fields: Default::default(),
             default_operator: Default::default(),
-            lenient: false,
+            lenient: Some(false),
         }
         .into();
         let bool_query_ast: QueryAst = BoolQuery {
@@ -417,13 +481,41 @@ mod tests {
         assert_eq!(&tantivy_bool_query.filter[0], &TantivyQueryAst::match_all(),);
     }
 
+    #[test]
+    fn test_named_query_parsed_query_ast() {
+        let query_ast: QueryAst = UserInputQuery {
+            user_text: "*".to_string(),
+            default_fields: Default::default(),
+            default_operator: Default::default(),
+            lenient: Some(false),
+        }
+        .into();
+        let named_query_ast = query_ast.named(Some("everything".to_string()));
+        let query_ast_with_parsed_user_query: QueryAst =
+            named_query_ast.parse_user_query(&[]).unwrap();
+        let named_queries = query_ast_with_parsed_user_query.named_queries();
+        assert_eq!(named_queries.len(), 1);
+        assert_eq!(named_queries[0].0, "everything");
+        assert_eq!(named_queries[0].1, &QueryAst::MatchAll);
+        let schema = tantivy::schema::Schema::builder().build();
+        let tantivy_query_ast = query_ast_with_parsed_user_query
+            .build_tantivy_ast_call(
+                &schema,
+                &create_default_quickwit_tokenizer_manager(),
+                &[],
+                true,
+            )
+            .unwrap();
+        assert_eq!(&tantivy_query_ast, &TantivyQueryAst::match_all());
+    }
+
     #[test]
     fn test_query_parse_default_occur_must() {
         let query_ast: QueryAst = UserInputQuery {
             user_text: "field:hello field:toto".to_string(),
             default_fields: None,
-            default_operator: crate::BooleanOperand::And,
-            lenient: false,
+            default_operator: Some(crate::BooleanOperand::And),
+            lenient: Some(false),
         }
         .parse_user_query(&[])
         .unwrap();
@@ -438,8 +530,8 @@ mod tests {
         let query_ast: QueryAst = UserInputQuery {
             user_text: "field:hello field:toto".to_string(),
             default_fields: None,
-            default_operator: crate::BooleanOperand::Or,
-            lenient: false,
+            default_operator: Some(crate::BooleanOperand::Or),
+            lenient: Some(false),
         }
         .parse_user_query(&[])
         .unwrap();
@@ -455,6 +547,54 @@ mod tests {
         let QueryAst::UserInput(input_query) = ast else {
             panic!()
         };
-        assert_eq!(input_query.default_operator, BooleanOperand::And);
+        assert_eq!(input_query.default_operator, None);
+        let QueryAst::Bool(bool_query) = QueryAst::UserInput(input_query)
+            .parse_user_query(&["body".to_string()])
+            .unwrap()
+        else {
+            panic!()
+        };
+        assert_eq!(bool_query.must.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_user_query_with_defaults() {
+        let default_search_fields = vec!["body".to_string()];
+        let defaults = UserQueryDefaults {
+            default_search_fields: &default_search_fields,
+            default_operator: BooleanOperand::Or,
+            lenient: true,
+        };
+        let QueryAst::Bool(bool_query) = query_ast_from_user_text("hello you", None)
+            .parse_user_query_with_defaults(defaults)
+            .unwrap()
+        else {
+            panic!()
+        };
+        assert_eq!(bool_query.should.len(), 2);
+        let QueryAst::FullText(full_text_query) = bool_query.should[0].clone() else {
+            panic!()
+        };
+        assert_eq!(full_text_query.field, "body");
+        assert!(full_text_query.lenient);
+
+        // The operator and the leniency of the query take precedence over the defaults.
+        let query_ast: QueryAst = UserInputQuery {
+            user_text: "hello you".to_string(),
+            default_fields: None,
+            default_operator: Some(BooleanOperand::And),
+            lenient: Some(false),
+        }
+        .into();
+        let QueryAst::Bool(bool_query) =
+            query_ast.parse_user_query_with_defaults(defaults).unwrap()
+        else {
+            panic!()
+        };
+        assert_eq!(bool_query.must.len(), 2);
+        let QueryAst::FullText(full_text_query) = bool_query.must[0].clone() else {
+            panic!()
+        };
+        assert!(!full_text_query.lenient);
     }
 }
@@ -14,18 +14,21 @@
 
 use std::ops::Bound;
 
+use quickwit_datetime::{evaluate_date_math, is_date_math_expression, DateMathRounding};
 use serde::{Deserialize, Serialize};
 use tantivy::fastfield::FastValue;
 use tantivy::query::FastFieldRangeQuery;
 use tantivy::schema::Schema as TantivySchema;
 use tantivy::tokenizer::TextAnalyzer;
 use tantivy::{DateTime, Term};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 
 use super::tantivy_query_ast::TantivyBoolQuery;
 use super::QueryAst;
-use crate::json_literal::InterpretUserInput;
+use crate::json_literal::{get_default_date_time_format, InterpretUserInput};
 use crate::query_ast::tantivy_query_ast::TantivyQueryAst;
-use crate::query_ast::BuildTantivyAst;
+use crate::query_ast::{BuildTantivyAst, QueryAstTransformer};
 use crate::tokenizers::TokenizerManager;
 use crate::{InvalidQuery, JsonLiteral};
 
@@ -36,6 +39,86 @@ pub struct RangeQuery {
     pub upper_bound: Bound<JsonLiteral>,
 }
 
+impl RangeQuery {
+    /// Replaces the date math bounds (`now-15m`, `now/d`, `2024-01-01||+1M`, ...) with the RFC
+    /// 3339 dates they evaluate to at `now`. The rounding of each bound follows Elasticsearch so
+    /// that, for instance, `[now/d TO now/d]` matches the whole current day.
+    ///
+    /// This must only be applied to range queries on datetime fields: `now-1d` is a perfectly
+    /// valid bound for a keyword field.
+    pub(crate) fn resolve_date_math(mut self, now: OffsetDateTime) -> anyhow::Result<Self> {
+        self.lower_bound = resolve_date_math_bound(self.lower_bound, true, now)?;
+        self.upper_bound = resolve_date_math_bound(self.upper_bound, false, now)?;
+        Ok(self)
+    }
+}
+
+/// Resolves the date math bounds of the range queries targeting a datetime field, as told by
+/// `is_date_field`.
+///
+/// Resolving once, at the root, pins `now` for all the splits and keeps the leaf search cache from
+/// serving results computed for an earlier `now`.
+pub struct DateMathResolver<F> {
+    pub is_date_field: F,
+    pub now: OffsetDateTime,
+}
+
+impl<F> QueryAstTransformer for DateMathResolver<F>
+where F: Fn(&str) -> bool
+{
+    type Err = InvalidQuery;
+
+    fn transform_range(&mut self, range_query: RangeQuery) -> Result<Option<QueryAst>, Self::Err> {
+        if !(self.is_date_field)(&range_query.field) {
+            return Ok(Some(range_query.into()));
+        }
+        let range_query = range_query.resolve_date_math(self.now)?;
+        Ok(Some(range_query.into()))
+    }
+}
+
+/// Returns true if `field_path` targets a datetime field of the schema.
+pub fn is_date_field(field_path: &str, schema: &TantivySchema) -> bool {
+    super::utils::find_field_or_hit_dynamic(field_path, schema).is_some_and(
+        |(_field, field_entry, _json_path)| {
+            matches!(
+                field_entry.field_type(),
+                tantivy::schema::FieldType::Date(_)
+            )
+        },
+    )
+}
+
+fn resolve_date_math_bound(
+    bound: Bound<JsonLiteral>,
+    is_lower_bound: bool,
+    now: OffsetDateTime,
+) -> anyhow::Result<Bound<JsonLiteral>> {
+    let (expression, is_inclusive) = match bound {
+        Bound::Included(JsonLiteral::String(text)) if is_date_math_expression(&text) => {
+            (text, true)
+        }
+        Bound::Excluded(JsonLiteral::String(text)) if is_date_math_expression(&text) => {
+            (text, false)
+        }
+        _ => return Ok(bound),
+    };
+    let rounding = if is_lower_bound == is_inclusive {
+        DateMathRounding::Down
+    } else {
+        DateMathRounding::Up
+    };
+    let date_time = evaluate_date_math(&expression, now, rounding, get_default_date_time_format())
+        .map_err(|error| anyhow::anyhow!("failed to evaluate date math: {error}"))?;
+    let date_time_literal = JsonLiteral::String(date_time.into_utc().format(&Rfc3339)?);
+
+    if is_inclusive {
+        Ok(Bound::Included(date_time_literal))
+    } else {
+        Ok(Bound::Excluded(date_time_literal))
+    }
+}
+
 /// Converts a given bound JsonLiteral bound into a bound of type T.
 fn convert_bound<'a, T>(bound: &'a Bound<JsonLiteral>) -> Option<Bound<T>>
 where T: InterpretUserInput<'a> {
@@ -180,8 +263,14 @@ impl BuildTantivyAst for RangeQuery {
                 });
             }
             tantivy::schema::FieldType::Date(date_options) => {
-                let (lower_bound, upper_bound) =
-                    convert_bounds(&self.lower_bound, &self.upper_bound, field_entry.name())?;
+                // The root usually resolved the date math bounds already, but not every query
+                // goes through it.
+                let range_query = self.clone().resolve_date_math(OffsetDateTime::now_utc())?;
+                let (lower_bound, upper_bound) = convert_bounds(
+                    &range_query.lower_bound,
+                    &range_query.upper_bound,
+                    field_entry.name(),
+                )?;
                 let truncate_datetime =
                     |date: &DateTime| date.truncate(date_options.get_precision());
                 let lower_bound = map_bound(&lower_bound, truncate_datetime);
@@ -286,9 +375,10 @@ mod tests {
     use std::ops::Bound;
 
     use tantivy::schema::{DateOptions, DateTimePrecision, Schema, FAST, STORED, TEXT};
+    use time::macros::datetime;
 
-    use super::RangeQuery;
-    use crate::query_ast::BuildTantivyAst;
+    use super::{is_date_field, DateMathResolver, RangeQuery};
+    use crate::query_ast::{BoolQuery, BuildTantivyAst, QueryAst, QueryAstTransformer};
     use crate::{
         create_default_quickwit_tokenizer_manager, InvalidQuery, JsonLiteral, MatchAllOrNone,
     };
@@ -442,4 +532,150 @@ mod tests {
             .unwrap_err();
         assert!(matches!(err, InvalidQuery::SchemaError { .. }));
     }
+
+    #[test]
+    fn test_range_query_resolve_date_math() {
+        let now = datetime!(2024-03-31 13:37:42.123 UTC);
+        let range_query = RangeQuery {
+            field: "timestamp".to_string(),
+            lower_bound: Bound::Included(JsonLiteral::String("now-1d/d".to_string())),
+            upper_bound: Bound::Included(JsonLiteral::String("now/d".to_string())),
+        }
+        .resolve_date_math(now)
+        .unwrap();
+        assert_eq!(
+            range_query.lower_bound,
+            Bound::Included(JsonLiteral::String("2024-03-30T00:00:00Z".to_string()))
+        );
+        assert_eq!(
+            range_query.upper_bound,
+            Bound::Included(JsonLiteral::String("2024-03-31T23:59:59.999Z".to_string()))
+        );
+
+        let range_query = RangeQuery {
+            field: "timestamp".to_string(),
+            lower_bound: Bound::Excluded(JsonLiteral::String(
+                "2024-01-01T00:00:00Z||+1M/M".to_string(),
+            )),
+            upper_bound: Bound::Excluded(JsonLiteral::String("now-15m".to_string())),
+        }
+        .resolve_date_math(now)
+        .unwrap();
+        assert_eq!(
+            range_query.lower_bound,
+            Bound::Excluded(JsonLiteral::String("2024-02-29T23:59:59.999Z".to_string()))
+        );
+        assert_eq!(
+            range_query.upper_bound,
+            Bound::Excluded(JsonLiteral::String("2024-03-31T13:22:42.123Z".to_string()))
+        );
+
+        let range_query = RangeQuery {
+            field: "my_str_field".to_string(),
+            lower_bound: Bound::Included(JsonLiteral::String("nowhere".to_string())),
+            upper_bound: Bound::Unbounded,
+        };
+        assert_eq!(
+            range_query.clone().resolve_date_math(now).unwrap(),
+            range_query
+        );
+
+        RangeQuery {
+            field: "timestamp".to_string(),
+            lower_bound: Bound::Included(JsonLiteral::String("now-1q".to_string())),
+            upper_bound: Bound::Unbounded,
+        }
+        .resolve_date_math(now)
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_range_query_keyword_field_keeps_date_math_bounds() {
+        let schema = make_schema(false);
+        let range_query = RangeQuery {
+            field: "my_str_field".to_string(),
+            lower_bound: Bound::Included(JsonLiteral::String("now-1d".to_string())),
+            upper_bound: Bound::Included(JsonLiteral::String("now".to_string())),
+        };
+        let tantivy_ast = range_query
+            .build_tantivy_ast_call(
+                &schema,
+                &create_default_quickwit_tokenizer_manager(),
+                &[],
+                true,
+            )
+            .unwrap()
+            .simplify();
+        let leaf_str = format!("{:?}", tantivy_ast.as_leaf().unwrap());
+        assert!(leaf_str.contains("\"now-1d\""), "{leaf_str}");
+        assert!(leaf_str.contains("\"now\""), "{leaf_str}");
+    }
+
+    #[test]
+    fn test_range_query_date_field_resolves_date_math_bounds() {
+        let schema = make_schema(false);
+        let range_query = RangeQuery {
+            field: "my_date_field".to_string(),
+            lower_bound: Bound::Included(JsonLiteral::String("now-1d".to_string())),
+            upper_bound: Bound::Excluded(JsonLiteral::String("now".to_string())),
+        };
+        range_query
+            .build_tantivy_ast_call(
+                &schema,
+                &create_default_quickwit_tokenizer_manager(),
+                &[],
+                true,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_date_math_resolver() {
+        let schema = make_schema(false);
+        let mut date_math_resolver = DateMathResolver {
+            is_date_field: |field_path: &str| is_date_field(field_path, &schema),
+            now: datetime!(2024-03-31 13:37:42.123 UTC),
+        };
+        let date_range_query = RangeQuery {
+            field: "my_date_field".to_string(),
+            lower_bound: Bound::Included(JsonLiteral::String("now-1d/d".to_string())),
+            upper_bound: Bound::Unbounded,
+        };
+        let str_range_query = RangeQuery {
+            field: "my_str_field".to_string(),
+            lower_bound: Bound::Included(JsonLiteral::String("now-1d/d".to_string())),
+            upper_bound: Bound::Unbounded,
+        };
+        let missing_field_range_query = RangeQuery {
+            field: "missing_field".to_string(),
+            lower_bound: Bound::Included(JsonLiteral::String("now-1d/d".to_string())),
+            upper_bound: Bound::Unbounded,
+        };
+        let query_ast: QueryAst = BoolQuery {
+            must: vec![
+                date_range_query.into(),
+                str_range_query.clone().into(),
+                missing_field_range_query.clone().into(),
+            ],
+            ..Default::default()
+        }
+        .into();
+        let resolved_query_ast = date_math_resolver.transform(query_ast).unwrap().unwrap();
+
+        let expected_date_range_query = RangeQuery {
+            field: "my_date_field".to_string(),
+            lower_bound: Bound::Included(JsonLiteral::String("2024-03-30T00:00:00Z".to_string())),
+            upper_bound: Bound::Unbounded,
+        };
+        let expected_query_ast: QueryAst = BoolQuery {
+            must: vec![
+                expected_date_range_query.into(),
+                str_range_query.into(),
+                missing_field_range_query.into(),
+            ],
+            ..Default::default()
+        }
+        .into();
+        assert_eq!(resolved_query_ast, expected_query_ast);
+    }
 }
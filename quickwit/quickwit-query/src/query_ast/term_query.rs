@@ -15,15 +15,17 @@
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
-use tantivy::schema::Schema as TantivySchema;
+use tantivy::schema::{FieldType, Schema as TantivySchema};
 
 use super::{BuildTantivyAst, QueryAst};
 use crate::query_ast::{FullTextParams, TantivyQueryAst};
 use crate::tokenizers::TokenizerManager;
-use crate::{BooleanOperand, InvalidQuery};
+use crate::{find_field_or_hit_dynamic, BooleanOperand, InvalidQuery};
 
 /// The TermQuery acts exactly like a FullTextQuery with
-/// a raw tokenizer.
+/// the normalizer of the field as tokenizer: the value is not split
+/// into several terms, but it is normalized (lowercased, trimmed, ...)
+/// the same way as the field values were at indexing.
 #[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
 pub struct TermQuery {
     pub field: String,
@@ -54,8 +56,10 @@ impl BuildTantivyAst for TermQuery {
         _search_fields: &[String],
         _with_validation: bool,
     ) -> Result<TantivyQueryAst, InvalidQuery> {
+        let normalizer_name = field_normalizer_name(&self.field, schema, tokenizer_manager)
+            .unwrap_or_else(|| "raw".to_string());
         let full_text_params = FullTextParams {
-            tokenizer: Some("raw".to_string()),
+            tokenizer: Some(normalizer_name),
             // The parameter below won't matter, since we will have only one term
             mode: BooleanOperand::Or.into(),
             zero_terms_query: Default::default(),
@@ -71,6 +75,23 @@ impl BuildTantivyAst for TermQuery {
     }
 }
 
+/// Returns the name of the normalizer of the tokenizer a text or JSON field is indexed with.
+fn field_normalizer_name(
+    full_path: &str,
+    schema: &TantivySchema,
+    tokenizer_manager: &TokenizerManager,
+) -> Option<String> {
+    let (_field, field_entry, _json_path) = find_field_or_hit_dynamic(full_path, schema)?;
+    let tokenizer_name = match field_entry.field_type() {
+        FieldType::Str(text_options) => text_options.get_indexing_options()?.tokenizer(),
+        FieldType::JsonObject(json_options) => {
+            json_options.get_text_indexing_options()?.tokenizer()
+        }
+        _ => return None,
+    };
+    tokenizer_manager.get_normalizer_name(tokenizer_name)
+}
+
 // Private struct used for serialization.
 // It represents the value of a term query. in the json form : `{field: <TermQueryValue>}`.
 #[derive(Serialize, Deserialize)]
@@ -121,10 +142,55 @@ impl From<TermQuery> for HashMap<String, TermQueryValue> {
 
 #[cfg(test)]
 mod tests {
-    use tantivy::schema::{Schema, INDEXED};
+    use tantivy::schema::{Schema, TextFieldIndexing, TextOptions, INDEXED};
+    use tantivy::tokenizer::{LowerCaser, RawTokenizer, TextAnalyzer};
 
-    use crate::create_default_quickwit_tokenizer_manager;
     use crate::query_ast::{BuildTantivyAst, TermQuery};
+    use crate::{create_default_quickwit_tokenizer_manager, TrimFilter};
+
+    fn build_term_query_on_text_field(tokenizer_name: &str, value: &str) -> String {
+        let term_query = TermQuery {
+            field: "text".to_string(),
+            value: value.to_string(),
+        };
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field(
+            "text",
+            TextOptions::default()
+                .set_indexing_options(TextFieldIndexing::default().set_tokenizer(tokenizer_name)),
+        );
+        let schema = schema_builder.build();
+
+        let tokenizer_manager = create_default_quickwit_tokenizer_manager();
+        let keyword_normalizer = TextAnalyzer::builder(RawTokenizer::default())
+            .filter(TrimFilter)
+            .filter(LowerCaser)
+            .build();
+        tokenizer_manager.register_normalizer("keyword_normalizer", keyword_normalizer, true);
+
+        let tantivy_query_ast = term_query
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap();
+        let leaf = tantivy_query_ast.as_leaf().unwrap();
+        format!("{leaf:?}")
+    }
+
+    #[test]
+    fn test_term_query_uses_field_normalizer() {
+        assert_eq!(
+            build_term_query_on_text_field("raw", " Hello World "),
+            r#"TermQuery(Term(field=0, type=Str, " Hello World "))"#
+        );
+        // The value is lowercased like the field values, but it is not split into several terms.
+        assert_eq!(
+            build_term_query_on_text_field("default", " Hello World "),
+            r#"TermQuery(Term(field=0, type=Str, " hello world "))"#
+        );
+        assert_eq!(
+            build_term_query_on_text_field("keyword_normalizer", " Hello World "),
+            r#"TermQuery(Term(field=0, type=Str, "hello world"))"#
+        );
+    }
 
     #[test]
     fn test_term_query_with_ipaddr_ipv4() {
@@ -43,11 +43,34 @@ pub struct UserInputQuery {
     // will be used.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default_fields: Option<Vec<String>>,
+    /// If None, the default operator of the index is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_operator: Option<BooleanOperand>,
+    /// Support missing fields. If None, the leniency of the index is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lenient: Option<bool>,
+}
+
+/// Defaults applied when parsing the user input queries that do not specify them. They are
+/// usually defined in the search settings of the index.
+#[derive(Clone, Copy, Debug)]
+pub struct UserQueryDefaults<'a> {
+    pub default_search_fields: &'a [String],
     pub default_operator: BooleanOperand,
-    /// Support missing fields
     pub lenient: bool,
 }
 
+impl<'a> UserQueryDefaults<'a> {
+    /// Defaults with the given search fields, the `AND` operator, and strict parsing.
+    pub fn with_search_fields(default_search_fields: &'a [String]) -> Self {
+        UserQueryDefaults {
+            default_search_fields,
+            default_operator: BooleanOperand::And,
+            lenient: false,
+        }
+    }
+}
+
 impl UserInputQuery {
     /// Parse the user query to generate a structured QueryAST, without any UserInputQuery node.
     ///
@@ -59,14 +82,25 @@ impl UserInputQuery {
     /// The default_search_fields argument on the other hand, is the default search fields defined
     /// in the `DocMapper`.
     pub fn parse_user_query(&self, default_search_fields: &[String]) -> anyhow::Result<QueryAst> {
+        self.parse_user_query_with_defaults(UserQueryDefaults::with_search_fields(
+            default_search_fields,
+        ))
+    }
+
+    /// Same as [`UserInputQuery::parse_user_query`], but the default operator and the leniency
+    /// of the query also fall back to the given defaults when unspecified.
+    pub fn parse_user_query_with_defaults(
+        &self,
+        defaults: UserQueryDefaults,
+    ) -> anyhow::Result<QueryAst> {
         let search_fields = self
             .default_fields
             .as_ref()
             .map(|search_fields| &search_fields[..])
-            .unwrap_or(default_search_fields);
+            .unwrap_or(defaults.default_search_fields);
         let user_input_ast = tantivy::query_grammar::parse_query(&self.user_text)
             .map_err(|_| anyhow::anyhow!("failed to parse query: `{}`", &self.user_text))?;
-        let default_occur = match self.default_operator {
+        let default_occur = match self.default_operator.unwrap_or(defaults.default_operator) {
             BooleanOperand::And => Occur::Must,
             BooleanOperand::Or => Occur::Should,
         };
@@ -74,7 +108,7 @@ impl UserInputQuery {
             user_input_ast,
             default_occur,
             search_fields,
-            self.lenient,
+            self.lenient.unwrap_or(defaults.lenient),
         )
     }
 }
@@ -315,8 +349,8 @@ mod tests {
         let user_input_query = UserInputQuery {
             user_text: "hello".to_string(),
             default_fields: None,
-            default_operator: BooleanOperand::And,
-            lenient: false,
+            default_operator: Some(BooleanOperand::And),
+            lenient: Some(false),
         };
         let schema = tantivy::schema::Schema::builder().build();
         {
@@ -349,8 +383,8 @@ mod tests {
             let invalid_err = UserInputQuery {
                 user_text: "hello".to_string(),
                 default_fields: None,
-                default_operator: BooleanOperand::And,
-                lenient: false,
+                default_operator: Some(BooleanOperand::And),
+                lenient: Some(false),
             }
             .parse_user_query(&[])
             .unwrap_err();
@@ -363,8 +397,8 @@ mod tests {
             let invalid_err = UserInputQuery {
                 user_text: "hello".to_string(),
                 default_fields: Some(Vec::new()),
-                default_operator: BooleanOperand::And,
-                lenient: false,
+                default_operator: Some(BooleanOperand::And),
+                lenient: Some(false),
             }
             .parse_user_query(&[])
             .unwrap_err();
@@ -380,8 +414,8 @@ mod tests {
         let ast = UserInputQuery {
             user_text: "hello".to_string(),
             default_fields: None,
-            default_operator: BooleanOperand::And,
-            lenient: false,
+            default_operator: Some(BooleanOperand::And),
+            lenient: Some(false),
         }
         .parse_user_query(&["defaultfield".to_string()])
         .unwrap();
@@ -401,8 +435,8 @@ mod tests {
         let ast = UserInputQuery {
             user_text: "field:\"hello\"*".to_string(),
             default_fields: None,
-            default_operator: BooleanOperand::And,
-            lenient: false,
+            default_operator: Some(BooleanOperand::And),
+            lenient: Some(false),
         }
         .parse_user_query(&[])
         .unwrap();
@@ -423,8 +457,8 @@ mod tests {
         let ast = UserInputQuery {
             user_text: "hello".to_string(),
             default_fields: Some(vec!["defaultfield".to_string()]),
-            default_operator: BooleanOperand::And,
-            lenient: false,
+            default_operator: Some(BooleanOperand::And),
+            lenient: Some(false),
         }
         .parse_user_query(&["defaultfieldweshouldignore".to_string()])
         .unwrap();
@@ -444,8 +478,8 @@ mod tests {
         let ast = UserInputQuery {
             user_text: "hello".to_string(),
             default_fields: Some(vec!["fielda".to_string(), "fieldb".to_string()]),
-            default_operator: BooleanOperand::And,
-            lenient: false,
+            default_operator: Some(BooleanOperand::And),
+            lenient: Some(false),
         }
         .parse_user_query(&["defaultfieldweshouldignore".to_string()])
         .unwrap();
@@ -460,8 +494,8 @@ mod tests {
         let ast = UserInputQuery {
             user_text: "myfield:hello".to_string(),
             default_fields: Some(vec!["fieldtoignore".to_string()]),
-            default_operator: BooleanOperand::And,
-            lenient: false,
+            default_operator: Some(BooleanOperand::And),
+            lenient: Some(false),
         }
         .parse_user_query(&["fieldtoignore".to_string()])
         .unwrap();
@@ -482,8 +516,8 @@ mod tests {
             let ast = UserInputQuery {
                 user_text: query.to_string(),
                 default_fields: None,
-                default_operator: BooleanOperand::Or,
-                lenient: false,
+                default_operator: Some(BooleanOperand::Or),
+                lenient: Some(false),
             }
             .parse_user_query(&[])
             .unwrap();
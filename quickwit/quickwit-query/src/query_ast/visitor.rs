@@ -37,6 +37,7 @@ pub trait QueryAstVisitor<'a> {
             QueryAst::MatchAll => self.visit_match_all(),
             QueryAst::MatchNone => self.visit_match_none(),
             QueryAst::Boost { underlying, boost } => self.visit_boost(underlying, *boost),
+            QueryAst::Named { underlying, name } => self.visit_named(underlying, name),
             QueryAst::UserInput(user_text_query) => self.visit_user_text(user_text_query),
             QueryAst::FieldPresence(exists) => self.visit_exists(exists),
             QueryAst::Wildcard(wildcard) => self.visit_wildcard(wildcard),
@@ -92,6 +93,10 @@ pub trait QueryAstVisitor<'a> {
         self.visit(underlying)
     }
 
+    fn visit_named(&mut self, underlying: &'a QueryAst, _name: &'a str) -> Result<(), Self::Err> {
+        self.visit(underlying)
+    }
+
     fn visit_range(&mut self, _range_query: &'a RangeQuery) -> Result<(), Self::Err> {
         Ok(())
     }
@@ -130,6 +135,7 @@ pub trait QueryAstTransformer {
             QueryAst::MatchAll => self.transform_match_all(),
             QueryAst::MatchNone => self.transform_match_none(),
             QueryAst::Boost { underlying, boost } => self.transform_boost(*underlying, boost),
+            QueryAst::Named { underlying, name } => self.transform_named(*underlying, name),
             QueryAst::UserInput(user_text_query) => self.transform_user_text(user_text_query),
             QueryAst::FieldPresence(exists) => self.transform_exists(exists),
             QueryAst::Wildcard(wildcard) => self.transform_wildcard(wildcard),
@@ -208,6 +214,19 @@ pub trait QueryAstTransformer {
         })
     }
 
+    fn transform_named(
+        &mut self,
+        underlying: QueryAst,
+        name: String,
+    ) -> Result<Option<QueryAst>, Self::Err> {
+        self.transform(underlying).map(|maybe_ast| {
+            maybe_ast.map(|underlying| QueryAst::Named {
+                underlying: Box::new(underlying),
+                name,
+            })
+        })
+    }
+
     fn transform_range(&mut self, range_query: RangeQuery) -> Result<Option<QueryAst>, Self::Err> {
         Ok(Some(QueryAst::Range(range_query)))
     }
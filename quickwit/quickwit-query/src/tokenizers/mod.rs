@@ -17,6 +17,7 @@ mod code_tokenizer;
 #[cfg(feature = "multilang")]
 mod multilang;
 mod tokenizer_manager;
+mod trim_filter;
 
 use once_cell::sync::Lazy;
 use tantivy::tokenizer::{
@@ -29,6 +30,7 @@ pub use self::code_tokenizer::CodeTokenizer;
 #[cfg(feature = "multilang")]
 pub use self::multilang::MultiLangTokenizer;
 pub use self::tokenizer_manager::TokenizerManager;
+pub use self::trim_filter::TrimFilter;
 
 pub const DEFAULT_REMOVE_TOKEN_LENGTH: usize = 255;
 
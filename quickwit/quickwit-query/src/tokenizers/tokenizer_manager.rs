@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 
 use tantivy::tokenizer::{
@@ -30,6 +30,7 @@ const RAW_LOWERCASE_TOKENIZER_NAME: &str = "raw_lowercase";
 pub struct TokenizerManager {
     inner: TantivyTokenizerManager,
     is_lowercaser: Arc<RwLock<HashMap<String, bool>>>,
+    normalizers: Arc<RwLock<HashSet<String>>>,
 }
 
 impl TokenizerManager {
@@ -38,6 +39,7 @@ impl TokenizerManager {
         let this = Self {
             inner: TantivyTokenizerManager::new(),
             is_lowercaser: Arc::new(RwLock::new(HashMap::new())),
+            normalizers: Arc::new(RwLock::new(HashSet::new())),
         };
 
         // in practice these will almost always be overridden in
@@ -68,6 +70,24 @@ impl TokenizerManager {
             .write()
             .unwrap()
             .insert(tokenizer_name.to_string(), does_lowercasing);
+        self.normalizers.write().unwrap().remove(tokenizer_name);
+    }
+
+    /// Registers a new tokenizer that does not split the text. Such a tokenizer is its own
+    /// normalizer.
+    pub fn register_normalizer<T>(
+        &self,
+        tokenizer_name: &str,
+        tokenizer: T,
+        does_lowercasing: bool,
+    ) where
+        TextAnalyzer: From<T>,
+    {
+        self.register(tokenizer_name, tokenizer, does_lowercasing);
+        self.normalizers
+            .write()
+            .unwrap()
+            .insert(tokenizer_name.to_string());
     }
 
     /// Accessing a tokenizer given its name.
@@ -75,20 +95,31 @@ impl TokenizerManager {
         self.inner.get(tokenizer_name)
     }
 
-    /// Query whether a given tokenizer does lowercasing
-    pub fn get_normalizer(&self, tokenizer_name: &str) -> Option<TextAnalyzer> {
+    /// Returns the name of the tokenizer normalizing the text the same way as the given tokenizer,
+    /// without splitting it.
+    pub fn get_normalizer_name(&self, tokenizer_name: &str) -> Option<String> {
+        if self.normalizers.read().unwrap().contains(tokenizer_name) {
+            return Some(tokenizer_name.to_string());
+        }
         let use_lowercaser = self
             .is_lowercaser
             .read()
             .unwrap()
             .get(tokenizer_name)
             .copied()?;
-        let analyzer = if use_lowercaser {
+        let normalizer_name = if use_lowercaser {
             RAW_LOWERCASE_TOKENIZER_NAME
         } else {
             RAW_TOKENIZER_NAME
         };
-        self.get_tokenizer(analyzer)
+        Some(normalizer_name.to_string())
+    }
+
+    /// Returns the tokenizer normalizing the text the same way as the given tokenizer, without
+    /// splitting it.
+    pub fn get_normalizer(&self, tokenizer_name: &str) -> Option<TextAnalyzer> {
+        let normalizer_name = self.get_normalizer_name(tokenizer_name)?;
+        self.get_tokenizer(&normalizer_name)
     }
 
     /// Get the inner TokenizerManager
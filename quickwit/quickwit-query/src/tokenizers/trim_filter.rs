@@ -0,0 +1,104 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream, Tokenizer};
+
+/// A token filter removing the leading and trailing whitespaces of the tokens.
+///
+/// It is meant to normalize keywords indexed with the `raw` tokenizer. Tokens made only of
+/// whitespaces are removed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TrimFilter;
+
+impl TokenFilter for TrimFilter {
+    type Tokenizer<T: Tokenizer> = TrimFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> TrimFilterWrapper<T> {
+        TrimFilterWrapper(tokenizer)
+    }
+}
+
+#[derive(Clone)]
+pub struct TrimFilterWrapper<T>(T);
+
+impl<T: Tokenizer> Tokenizer for TrimFilterWrapper<T> {
+    type TokenStream<'a> = TrimFilterStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        TrimFilterStream(self.0.token_stream(text))
+    }
+}
+
+pub struct TrimFilterStream<T>(T);
+
+impl<T: TokenStream> TokenStream for TrimFilterStream<T> {
+    fn advance(&mut self) -> bool {
+        while self.0.advance() {
+            let token = self.0.token_mut();
+            let num_leading_bytes = token.text.len() - token.text.trim_start().len();
+            let num_trimmed_bytes = token.text.trim().len();
+            if num_trimmed_bytes == 0 {
+                continue;
+            }
+            if num_trimmed_bytes != token.text.len() {
+                token.text.truncate(num_leading_bytes + num_trimmed_bytes);
+                token.text.drain(..num_leading_bytes);
+                token.offset_from += num_leading_bytes;
+                token.offset_to = token.offset_from + num_trimmed_bytes;
+            }
+            return true;
+        }
+        false
+    }
+
+    fn token(&self) -> &Token {
+        self.0.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.0.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::tokenizer::{RawTokenizer, TextAnalyzer, Token};
+
+    use super::TrimFilter;
+
+    fn trimmed_tokens(text: &str) -> Vec<Token> {
+        let mut text_analyzer = TextAnalyzer::builder(RawTokenizer::default())
+            .filter(TrimFilter)
+            .build();
+        let mut token_stream = text_analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        token_stream.process(&mut |token| tokens.push(token.clone()));
+        tokens
+    }
+
+    #[test]
+    fn test_trim_filter() {
+        let tokens = trimmed_tokens("  Hello World\t");
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].text, "Hello World");
+        assert_eq!(tokens[0].offset_from, 2);
+        assert_eq!(tokens[0].offset_to, 13);
+
+        let tokens = trimmed_tokens("hello");
+        assert_eq!(tokens[0].text, "hello");
+        assert_eq!(tokens[0].offset_to, 5);
+
+        assert!(trimmed_tokens(" \n ").is_empty());
+    }
+}
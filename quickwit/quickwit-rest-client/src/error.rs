@@ -55,6 +55,10 @@ impl Error {
 pub struct ApiError {
     pub message: Option<String>,
     pub code: StatusCode,
+    /// Machine-readable error code returned by the server, e.g. `not_found`.
+    pub error_code: Option<String>,
+    /// Whether the server reported that the request may succeed if retried.
+    pub retryable: bool,
 }
 
 // Implement `Display` for `ApiError`.
@@ -71,4 +75,8 @@ impl std::fmt::Display for ApiError {
 #[derive(Deserialize)]
 pub(crate) struct ErrorResponsePayload {
     pub message: String,
+    #[serde(default)]
+    pub error_code: Option<String>,
+    #[serde(default)]
+    pub retryable: bool,
 }
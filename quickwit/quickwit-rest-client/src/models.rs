@@ -44,22 +44,35 @@ impl ApiResponse {
         Ok(())
     }
 
-    async fn extract_error_message(self) -> Option<String> {
+    async fn extract_error_payload(self) -> Option<ErrorResponsePayload> {
         let error_body_bytes = self.inner.bytes().await.ok()?;
         let error_body_text = std::str::from_utf8(&error_body_bytes).ok()?;
         if let Ok(error_payload) = serde_json::from_str::<ErrorResponsePayload>(error_body_text) {
-            Some(error_payload.message)
+            Some(error_payload)
         } else {
-            Some(error_body_text.to_string())
+            Some(ErrorResponsePayload {
+                message: error_body_text.to_string(),
+                error_code: None,
+                retryable: false,
+            })
         }
     }
 
     async fn api_error(self) -> Error {
         let code = self.inner.status();
-        let error_message = self.extract_error_message().await;
+        let Some(error_payload) = self.extract_error_payload().await else {
+            return Error::from(ApiError {
+                message: None,
+                code,
+                error_code: None,
+                retryable: false,
+            });
+        };
         Error::from(ApiError {
-            message: error_message,
+            message: Some(error_payload.message),
             code,
+            error_code: error_payload.error_code,
+            retryable: error_payload.retryable,
         })
     }
 
@@ -845,6 +845,8 @@ mod test {
             num_ingested_docs: Some(2),
             num_rejected_docs: Some(0),
             parse_failures: Some(Vec::new()),
+            index_failures: None,
+            ack_level: None,
         };
         Mock::given(method("POST"))
             .and(path("/api/v1/my-index/ingest"))
@@ -880,6 +882,8 @@ mod test {
             num_ingested_docs: Some(2),
             num_rejected_docs: Some(0),
             parse_failures: Some(Vec::new()),
+            index_failures: None,
+            ack_level: None,
         };
         Mock::given(method("POST"))
             .and(path("/api/v1/my-index/ingest"))
@@ -915,6 +919,8 @@ mod test {
             num_ingested_docs: Some(2),
             num_rejected_docs: Some(0),
             parse_failures: Some(Vec::new()),
+            index_failures: None,
+            ack_level: None,
         };
         Mock::given(method("POST"))
             .and(path("/api/v1/my-index/ingest"))
@@ -21,7 +21,9 @@ use bytesize::ByteSize;
 use futures::{StreamExt, TryStreamExt};
 use http::Uri;
 use quickwit_proto::search::{
-    GetKvRequest, LeafSearchStreamResponse, PutKvRequest, ReportSplitsRequest,
+    CancelLeafSearchesRequest, CancelLeafSearchesResponse, CancelSearchRequest,
+    CancelSearchResponse, GetKvRequest, LeafSearchStreamResponse, PinSplitsRequest, PutKvRequest,
+    ReportSplitsRequest,
 };
 use quickwit_proto::tonic::codegen::InterceptedService;
 use quickwit_proto::tonic::transport::{Channel, Endpoint};
@@ -32,7 +34,7 @@ use tower::timeout::Timeout;
 use tracing::{info_span, warn, Instrument};
 
 use crate::error::parse_grpc_error;
-use crate::SearchService;
+use crate::{SearchError, SearchService};
 
 /// Impl is an enumeration that meant to manage Quickwit's search service client types.
 #[derive(Clone)]
@@ -120,14 +122,27 @@ impl SearchServiceClient {
         &mut self,
         request: quickwit_proto::search::LeafSearchRequest,
     ) -> crate::Result<quickwit_proto::search::LeafSearchResponse> {
-        match &mut self.client_impl {
+        let mut leaf_search_response = match &mut self.client_impl {
             SearchServiceClientImpl::Grpc(grpc_client) => grpc_client
                 .leaf_search(request)
                 .await
                 .map(|tonic_response| tonic_response.into_inner())
                 .map_err(|tonic_error| parse_grpc_error(&tonic_error)),
             SearchServiceClientImpl::Local(service) => service.leaf_search(request).await,
+        }?;
+        // Leaves may compress their intermediate aggregation results before sending them.
+        leaf_search_response
+            .decompress_intermediate_aggregation_result()
+            .map_err(|error| {
+                SearchError::Internal(format!(
+                    "failed to decompress intermediate aggregation result: {error}"
+                ))
+            })?;
+        // The leaves do not know the address they are reached at, so we tag their profiles here.
+        for split_profile in &mut leaf_search_response.split_profiles {
+            split_profile.searcher_addr = self.grpc_addr.to_string();
         }
+        Ok(leaf_search_response)
     }
 
     /// Perform leaf search.
@@ -298,6 +313,56 @@ impl SearchServiceClient {
             }
         }
     }
+
+    /// Pins or unpins splits in the split cache of the targeted searcher.
+    pub async fn pin_splits(&mut self, pin_splits_request: PinSplitsRequest) -> crate::Result<()> {
+        match &mut self.client_impl {
+            SearchServiceClientImpl::Local(service) => {
+                service.pin_splits(pin_splits_request).await;
+            }
+            SearchServiceClientImpl::Grpc(grpc_client) => {
+                grpc_client
+                    .pin_splits(pin_splits_request)
+                    .await
+                    .map_err(|tonic_error| parse_grpc_error(&tonic_error))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Cancels the leaf searches of a search request running on the targeted searcher.
+    pub async fn cancel_leaf_searches(
+        &mut self,
+        cancel_leaf_searches_request: CancelLeafSearchesRequest,
+    ) -> crate::Result<CancelLeafSearchesResponse> {
+        match &mut self.client_impl {
+            SearchServiceClientImpl::Local(service) => Ok(service
+                .cancel_leaf_searches(cancel_leaf_searches_request)
+                .await),
+            SearchServiceClientImpl::Grpc(grpc_client) => grpc_client
+                .cancel_leaf_searches(cancel_leaf_searches_request)
+                .await
+                .map(|tonic_response| tonic_response.into_inner())
+                .map_err(|tonic_error| parse_grpc_error(&tonic_error)),
+        }
+    }
+
+    /// Cancels a search request running on the targeted searcher.
+    pub async fn cancel_search(
+        &mut self,
+        cancel_search_request: CancelSearchRequest,
+    ) -> crate::Result<CancelSearchResponse> {
+        match &mut self.client_impl {
+            SearchServiceClientImpl::Local(service) => {
+                Ok(service.cancel_search(cancel_search_request).await)
+            }
+            SearchServiceClientImpl::Grpc(grpc_client) => grpc_client
+                .cancel_search(cancel_search_request)
+                .await
+                .map(|tonic_response| tonic_response.into_inner())
+                .map_err(|tonic_error| parse_grpc_error(&tonic_error)),
+        }
+    }
 }
 
 /// Creates a [`SearchServiceClient`] from a socket address.
@@ -12,15 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::time::Duration;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use base64::Engine;
 use futures::future::ready;
 use futures::{Future, StreamExt};
+use quickwit_config::LeafSearchHedgingPolicy;
+use quickwit_proto::compression::PayloadCodec;
 use quickwit_proto::search::{
     FetchDocsRequest, FetchDocsResponse, GetKvRequest, LeafListFieldsRequest, LeafListTermsRequest,
     LeafListTermsResponse, LeafSearchRequest, LeafSearchResponse, LeafSearchStreamRequest,
-    LeafSearchStreamResponse, ListFieldsResponse, PutKvRequest,
+    LeafSearchStreamResponse, ListFieldsResponse, PutKvRequest, SplitIdAndFooterOffsets,
+    SplitSearchError,
 };
 use tantivy::aggregation::intermediate_agg_result::IntermediateAggregationResults;
 use tokio::sync::mpsc::error::SendError;
@@ -28,9 +34,13 @@ use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{debug, error, info, warn};
 
+use crate::metrics::SEARCH_METRICS;
+use crate::retry::hedging::LeafSearchHedger;
 use crate::retry::search::LeafSearchRetryPolicy;
 use crate::retry::search_stream::{LeafSearchStreamRetryPolicy, SuccessfulSplitIds};
 use crate::retry::{retry_client, DefaultRetryPolicy, RetryPolicy};
+use crate::root::compute_split_cost;
+use crate::search_job_placer::Job;
 use crate::{merge_resource_stats_it, SearchError, SearchJobPlacer, SearchServiceClient};
 
 /// Maximum number of put requests emitted to perform a replicated given PUT KV.
@@ -48,12 +58,26 @@ const TARGET_NUM_REPLICATION: usize = 2;
 #[derive(Clone)]
 pub struct ClusterClient {
     pub(crate) search_job_placer: SearchJobPlacer,
+    leaf_search_hedger_opt: Option<Arc<LeafSearchHedger>>,
 }
 
 impl ClusterClient {
     /// Instantiates [`ClusterClient`].
     pub fn new(search_job_placer: SearchJobPlacer) -> Self {
-        Self { search_job_placer }
+        Self {
+            search_job_placer,
+            leaf_search_hedger_opt: None,
+        }
+    }
+
+    /// Enables the speculative re-dispatch of slow leaf search requests to another node client.
+    pub fn with_leaf_search_hedging_policy(
+        mut self,
+        leaf_search_hedging_policy: LeafSearchHedgingPolicy,
+    ) -> Self {
+        let leaf_search_hedger = LeafSearchHedger::new(leaf_search_hedging_policy);
+        self.leaf_search_hedger_opt = Some(Arc::new(leaf_search_hedger));
+        self
     }
 
     /// Fetches docs with retry on another node client.
@@ -87,7 +111,20 @@ impl ClusterClient {
         request: LeafSearchRequest,
         mut client: SearchServiceClient,
     ) -> crate::Result<LeafSearchResponse> {
-        let mut response_res = client.leaf_search(request.clone()).await;
+        let mut response_res = if let Some(leaf_search_hedger) = &self.leaf_search_hedger_opt {
+            self.hedged_leaf_search(leaf_search_hedger, request.clone(), client.clone())
+                .await
+        } else {
+            client.leaf_search(request.clone()).await
+        };
+        if matches!(response_res, Err(SearchError::TooManyRequests)) {
+            if let Some(replanned_response_res) = self
+                .replan_saturated_leaf_search(&request, client.grpc_addr())
+                .await
+            {
+                response_res = replanned_response_res;
+            }
+        }
         let retry_policy = LeafSearchRetryPolicy {};
         // We retry only once.
         let Some(retry_request) = retry_policy.retry_request(request, &response_res) else {
@@ -122,6 +159,190 @@ impl ClusterClient {
         response_res
     }
 
+    /// Re-plans the splits of a leaf search request rejected by a saturated searcher onto the
+    /// other searchers, following the same split affinity as the initial placement.
+    ///
+    /// Returns `None` if there is no other searcher to re-plan the splits onto. Sub-requests that
+    /// fail are reported as failed splits so that they can be retried like any other failing
+    /// split.
+    async fn replan_saturated_leaf_search(
+        &self,
+        request: &LeafSearchRequest,
+        saturated_addr: SocketAddr,
+    ) -> Option<crate::Result<LeafSearchResponse>> {
+        let replanned_splits: Vec<ReplannedSplit> = request
+            .leaf_requests
+            .iter()
+            .flat_map(|leaf_req| leaf_req.split_offsets.iter())
+            .map(ReplannedSplit)
+            .collect();
+        let excluded_addrs = HashSet::from_iter([saturated_addr]);
+        let assignments: Vec<(SearchServiceClient, Vec<String>)> = self
+            .search_job_placer
+            .assign_jobs(replanned_splits, &excluded_addrs)
+            .await
+            .ok()?
+            .map(|(client, splits)| {
+                let split_ids = splits
+                    .into_iter()
+                    .map(|split| split.split_id().to_string())
+                    .collect();
+                (client, split_ids)
+            })
+            .collect();
+        // The job placer ignores the exclusion if the saturated searcher is the only one left.
+        if assignments
+            .iter()
+            .any(|(client, _)| client.grpc_addr() == saturated_addr)
+        {
+            return None;
+        }
+        let num_replanned_splits: usize = assignments
+            .iter()
+            .map(|(_, split_ids)| split_ids.len())
+            .sum();
+        info!(
+            "searcher `{saturated_addr}` is saturated, re-planning {num_replanned_splits} splits \
+             onto {} other searcher(s)",
+            assignments.len()
+        );
+        SEARCH_METRICS
+            .leaf_search_replanned_splits_total
+            .inc_by(num_replanned_splits as u64);
+
+        let sub_request_futures = assignments.into_iter().map(|(mut client, split_ids)| {
+            let sub_request = leaf_search_request_for_splits(request, &split_ids);
+            async move {
+                let response_res = client.leaf_search(sub_request).await;
+                (split_ids, response_res)
+            }
+        });
+        let mut merged_response_opt: Option<LeafSearchResponse> = None;
+        let mut failed_splits: Vec<SplitSearchError> = Vec::new();
+        let mut first_error_opt: Option<SearchError> = None;
+
+        for (split_ids, response_res) in futures::future::join_all(sub_request_futures).await {
+            let mut response = match response_res {
+                Ok(response) => response,
+                Err(search_error) => {
+                    let num_attempted_splits = split_ids.len() as u64;
+                    failed_splits.extend(split_ids.into_iter().map(|split_id| SplitSearchError {
+                        error: search_error.to_string(),
+                        split_id,
+                        retryable_error: true,
+                    }));
+                    first_error_opt.get_or_insert(search_error);
+                    LeafSearchResponse {
+                        num_attempted_splits,
+                        ..Default::default()
+                    }
+                }
+            };
+            failed_splits.append(&mut response.failed_splits);
+            let merged_response = match merged_response_opt.take() {
+                Some(merged_response) => {
+                    match merge_original_with_retry_leaf_search_response(merged_response, response)
+                    {
+                        Ok(merged_response) => merged_response,
+                        Err(merge_error) => return Some(Err(merge_error)),
+                    }
+                }
+                None => response,
+            };
+            merged_response_opt = Some(merged_response);
+        }
+        let mut merged_response = merged_response_opt?;
+
+        if merged_response.num_successful_splits == 0 {
+            if let Some(first_error) = first_error_opt {
+                return Some(Err(first_error));
+            }
+        }
+        merged_response.failed_splits = failed_splits;
+        Some(Ok(merged_response))
+    }
+
+    /// Leaf search that is speculatively re-dispatched to another node client if it takes longer
+    /// than the hedging delay. The first successful response wins.
+    async fn hedged_leaf_search(
+        &self,
+        leaf_search_hedger: &LeafSearchHedger,
+        request: LeafSearchRequest,
+        mut client: SearchServiceClient,
+    ) -> crate::Result<LeafSearchResponse> {
+        let start = Instant::now();
+        let hedging_delay_opt = leaf_search_hedger.on_request();
+        let primary_addr = client.grpc_addr();
+        let primary_fut = client.leaf_search(request.clone());
+        tokio::pin!(primary_fut);
+
+        let response_res = 'race: {
+            let Some(hedging_delay) = hedging_delay_opt else {
+                break 'race primary_fut.await;
+            };
+            let timeout_res = tokio::time::timeout(hedging_delay, &mut primary_fut).await;
+
+            if let Ok(response_res) = timeout_res {
+                break 'race response_res;
+            }
+            let Some(mut hedge_client) = self
+                .hedge_client(leaf_search_hedger, &request, primary_addr)
+                .await
+            else {
+                break 'race primary_fut.await;
+            };
+            debug!(
+                "leaf search request to `{primary_addr}` exceeded the hedging delay of \
+                 {hedging_delay:?}, re-dispatching it to `{}`",
+                hedge_client.grpc_addr()
+            );
+            SEARCH_METRICS.leaf_search_hedged_requests_total.inc();
+            let hedge_fut = hedge_client.leaf_search(request);
+            tokio::pin!(hedge_fut);
+
+            tokio::select! {
+                response_res = &mut primary_fut => {
+                    if response_res.is_ok() {
+                        break 'race response_res;
+                    }
+                    hedge_fut.await
+                }
+                response_res = &mut hedge_fut => {
+                    if response_res.is_ok() {
+                        break 'race response_res;
+                    }
+                    primary_fut.await
+                }
+            }
+        };
+        leaf_search_hedger.record_latency(start.elapsed());
+        response_res
+    }
+
+    /// Returns a client on another node to re-dispatch a slow leaf search request to, if the
+    /// hedging budget allows it.
+    async fn hedge_client(
+        &self,
+        leaf_search_hedger: &LeafSearchHedger,
+        request: &LeafSearchRequest,
+        primary_addr: SocketAddr,
+    ) -> Option<SearchServiceClient> {
+        // As for retries, we pick a client only considering the affinity of the first split.
+        let first_split = request
+            .leaf_requests
+            .iter()
+            .flat_map(|leaf_req| leaf_req.split_offsets.iter())
+            .next()?;
+        let hedge_client =
+            retry_client(&self.search_job_placer, primary_addr, &first_split.split_id)
+                .await
+                .ok()?;
+        if hedge_client.grpc_addr() == primary_addr || !leaf_search_hedger.try_acquire_hedge() {
+            return None;
+        }
+        Some(hedge_client)
+    }
+
     /// Leaf search with retry on another node client.
     pub async fn leaf_list_fields(
         &self,
@@ -302,6 +523,9 @@ fn merge_original_with_retry_leaf_search_response(
     original_response
         .partial_hits
         .extend(retry_response.partial_hits);
+    original_response
+        .split_profiles
+        .extend(retry_response.split_profiles);
     let intermediate_aggregation_result: Option<Vec<u8>> = match (
         original_response.intermediate_aggregation_result,
         retry_response.intermediate_aggregation_result,
@@ -329,10 +553,44 @@ fn merge_original_with_retry_leaf_search_response(
         num_successful_splits: original_response.num_successful_splits
             + retry_response.num_successful_splits,
         resource_stats,
+        split_profiles: original_response.split_profiles,
+        intermediate_aggregation_result_codec: PayloadCodec::None as i32,
     })
 }
 
 // Merge initial leaf search results with results obtained from a retry.
+/// A split of a leaf search request being re-planned onto another searcher.
+struct ReplannedSplit<'a>(&'a SplitIdAndFooterOffsets);
+
+impl Job for ReplannedSplit<'_> {
+    fn split_id(&self) -> &str {
+        &self.0.split_id
+    }
+
+    // Same cost as for the initial placement of the split.
+    fn cost(&self) -> usize {
+        compute_split_cost(self.0.num_docs)
+    }
+}
+
+/// Builds a copy of the leaf search request restricted to the given splits.
+fn leaf_search_request_for_splits(
+    request: &LeafSearchRequest,
+    split_ids: &[String],
+) -> LeafSearchRequest {
+    let split_ids: HashSet<&str> = split_ids.iter().map(String::as_str).collect();
+    let mut sub_request = request.clone();
+    for leaf_request in sub_request.leaf_requests.iter_mut() {
+        leaf_request
+            .split_offsets
+            .retain(|split_offsets| split_ids.contains(split_offsets.split_id.as_str()));
+    }
+    sub_request
+        .leaf_requests
+        .retain(|leaf_request| !leaf_request.split_offsets.is_empty());
+    sub_request
+}
+
 fn merge_original_with_retry_leaf_search_results(
     left_search_response_result: crate::Result<LeafSearchResponse>,
     right_search_response_result: crate::Result<LeafSearchResponse>,
@@ -377,16 +635,180 @@ async fn forward_leaf_search_stream(
 mod tests {
     use std::collections::HashSet;
     use std::net::SocketAddr;
+    use std::pin::Pin;
 
+    use async_trait::async_trait;
+    use bytes::Bytes;
     use quickwit_proto::search::{
-        LeafRequestRef, PartialHit, SearchRequest, SearchStreamRequest, SortValue,
-        SplitIdAndFooterOffsets, SplitSearchError,
+        CancelLeafSearchesRequest, CancelLeafSearchesResponse, CancelSearchRequest,
+        CancelSearchResponse, LeafRequestRef, ListFieldsRequest, ListTermsRequest,
+        ListTermsResponse, PartialHit, PinSplitsRequest, PinSplitsResponse, ReportSplitsRequest,
+        ReportSplitsResponse, ScrollRequest, SearchPlanResponse, SearchRequest, SearchResponse,
+        SearchStreamRequest, SortValue, SplitIdAndFooterOffsets, SplitSearchError,
     };
     use quickwit_query::query_ast::qast_json_helper;
 
     use super::*;
+    use crate::retry::hedging::MIN_NUM_LATENCY_SAMPLES;
     use crate::root::SearchJob;
-    use crate::{searcher_pool_for_test, MockSearchService};
+    use crate::{
+        searcher_pool_for_test, ExplainRequest, ExplainResponse, MockSearchService,
+        PinIndexSplitsRequest, PinIndexSplitsResponse, SearchService,
+    };
+
+    /// Search service answering leaf search requests after a delay. Mocked search services
+    /// answer synchronously, so they cannot be used to exercise hedging.
+    struct SlowLeafSearchService {
+        delay: Duration,
+        num_hits: u64,
+    }
+
+    #[async_trait]
+    impl SearchService for SlowLeafSearchService {
+        async fn root_search(&self, _request: SearchRequest) -> crate::Result<SearchResponse> {
+            unimplemented!()
+        }
+
+        async fn leaf_search(
+            &self,
+            _request: LeafSearchRequest,
+        ) -> crate::Result<LeafSearchResponse> {
+            tokio::time::sleep(self.delay).await;
+            Ok(LeafSearchResponse {
+                num_hits: self.num_hits,
+                ..Default::default()
+            })
+        }
+
+        async fn fetch_docs(&self, _request: FetchDocsRequest) -> crate::Result<FetchDocsResponse> {
+            unimplemented!()
+        }
+
+        async fn root_search_stream(
+            &self,
+            _request: SearchStreamRequest,
+        ) -> crate::Result<Pin<Box<dyn futures::Stream<Item = crate::Result<Bytes>> + Send>>>
+        {
+            unimplemented!()
+        }
+
+        async fn leaf_search_stream(
+            &self,
+            _request: LeafSearchStreamRequest,
+        ) -> crate::Result<UnboundedReceiverStream<crate::Result<LeafSearchStreamResponse>>>
+        {
+            unimplemented!()
+        }
+
+        async fn root_list_terms(
+            &self,
+            _request: ListTermsRequest,
+        ) -> crate::Result<ListTermsResponse> {
+            unimplemented!()
+        }
+
+        async fn leaf_list_terms(
+            &self,
+            _request: LeafListTermsRequest,
+        ) -> crate::Result<LeafListTermsResponse> {
+            unimplemented!()
+        }
+
+        async fn scroll(&self, _scroll_request: ScrollRequest) -> crate::Result<SearchResponse> {
+            unimplemented!()
+        }
+
+        async fn put_kv(&self, _put_kv: PutKvRequest) {
+            unimplemented!()
+        }
+
+        async fn get_kv(&self, _get_kv: GetKvRequest) -> Option<Vec<u8>> {
+            unimplemented!()
+        }
+
+        async fn report_splits(&self, _report_splits: ReportSplitsRequest) -> ReportSplitsResponse {
+            unimplemented!()
+        }
+
+        async fn pin_splits(&self, _pin_splits: PinSplitsRequest) -> PinSplitsResponse {
+            unimplemented!()
+        }
+
+        async fn root_pin_splits(
+            &self,
+            _request: PinIndexSplitsRequest,
+        ) -> crate::Result<PinIndexSplitsResponse> {
+            unimplemented!()
+        }
+
+        async fn cancel_leaf_searches(
+            &self,
+            _request: CancelLeafSearchesRequest,
+        ) -> CancelLeafSearchesResponse {
+            unimplemented!()
+        }
+
+        async fn root_cancel_leaf_searches(&self, _search_request_id: String) {
+            unimplemented!()
+        }
+
+        async fn cancel_search(&self, _request: CancelSearchRequest) -> CancelSearchResponse {
+            unimplemented!()
+        }
+
+        async fn root_cancel_search(&self, _request: CancelSearchRequest) -> CancelSearchResponse {
+            unimplemented!()
+        }
+
+        async fn root_list_fields(
+            &self,
+            _list_fields: ListFieldsRequest,
+        ) -> crate::Result<ListFieldsResponse> {
+            unimplemented!()
+        }
+
+        async fn leaf_list_fields(
+            &self,
+            _list_fields: LeafListFieldsRequest,
+        ) -> crate::Result<ListFieldsResponse> {
+            unimplemented!()
+        }
+
+        async fn search_plan(&self, _request: SearchRequest) -> crate::Result<SearchPlanResponse> {
+            unimplemented!()
+        }
+
+        async fn explain(&self, _request: ExplainRequest) -> crate::Result<ExplainResponse> {
+            unimplemented!()
+        }
+    }
+
+    /// Returns a cluster client hedging leaf search requests after 10ms, along with a client for
+    /// the slow searcher `127.0.0.1:1001`. The other searcher answers the hedged requests.
+    fn hedging_cluster_client(
+        slow_leaf_search_service: SlowLeafSearchService,
+        mock_search_service: MockSearchService,
+    ) -> (ClusterClient, SearchServiceClient) {
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1002", mock_search_service)]);
+        let slow_client_addr: SocketAddr = "127.0.0.1:1001".parse().unwrap();
+        let slow_client =
+            SearchServiceClient::from_service(Arc::new(slow_leaf_search_service), slow_client_addr);
+        searcher_pool.insert(slow_client_addr, slow_client.clone());
+
+        let leaf_search_hedging_policy = LeafSearchHedgingPolicy {
+            latency_percentile: 90.0,
+            min_delay_millis: 10,
+            max_hedged_requests_ratio: 0.5,
+        };
+        let cluster_client = ClusterClient::new(SearchJobPlacer::new(searcher_pool))
+            .with_leaf_search_hedging_policy(leaf_search_hedging_policy);
+        let leaf_search_hedger = cluster_client.leaf_search_hedger_opt.as_ref().unwrap();
+
+        for _ in 0..MIN_NUM_LATENCY_SAMPLES {
+            leaf_search_hedger.record_latency(Duration::from_millis(1));
+        }
+        (cluster_client, slow_client)
+    }
 
     fn mock_partial_hit(split_id: &str, sort_value: u64, doc_id: u32) -> PartialHit {
         PartialHit {
@@ -425,6 +847,7 @@ mod tests {
             search_request: Some(search_request),
             doc_mappers: vec!["doc_mapper".to_string()],
             index_uris: vec!["uri".to_string()],
+            accepts_compressed_intermediate_aggregation_result: false,
             leaf_requests: vec![LeafRequestRef {
                 index_uri_ord: 0,
                 doc_mapper_ord: 0,
@@ -446,6 +869,8 @@ mod tests {
                         num_docs: 0,
                     },
                 ],
+                index_uid: "test-idx:00000000000000000000000000".to_string(),
+                storage_credentials: None,
             }],
         }
     }
@@ -460,6 +885,7 @@ mod tests {
             fast_field: "fast".to_string(),
             output_format: 0,
             partition_by_field: None,
+            security_context: None,
         };
         LeafSearchStreamRequest {
             request: Some(search_request),
@@ -483,6 +909,8 @@ mod tests {
                     num_docs: 0,
                 },
             ],
+            index_uid: "test-idx:00000000000000000000000000".to_string(),
+            storage_credentials: None,
         }
     }
 
@@ -633,6 +1061,152 @@ mod tests {
         assert_eq!(result.unwrap().num_hits, 2);
     }
 
+    #[tokio::test]
+    async fn test_cluster_client_leaf_search_replans_splits_of_saturated_searcher() {
+        let request = mock_leaf_search_request();
+        let mut mock_search_service_1 = MockSearchService::new();
+        mock_search_service_1
+            .expect_leaf_search()
+            .return_once(|_: LeafSearchRequest| Err(SearchError::TooManyRequests));
+
+        let mut mock_search_service_2 = MockSearchService::new();
+        mock_search_service_2
+            .expect_leaf_search()
+            .returning(|request: LeafSearchRequest| {
+                let num_splits = request.leaf_requests[0].split_offsets.len() as u64;
+                Ok(LeafSearchResponse {
+                    num_hits: num_splits,
+                    num_attempted_splits: num_splits,
+                    num_successful_splits: num_splits,
+                    ..Default::default()
+                })
+            });
+        let mut mock_search_service_3 = MockSearchService::new();
+        mock_search_service_3
+            .expect_leaf_search()
+            .returning(|request: LeafSearchRequest| {
+                let num_splits = request.leaf_requests[0].split_offsets.len() as u64;
+                Ok(LeafSearchResponse {
+                    num_hits: num_splits,
+                    num_attempted_splits: num_splits,
+                    num_successful_splits: num_splits,
+                    ..Default::default()
+                })
+            });
+        let searcher_pool = searcher_pool_for_test([
+            ("127.0.0.1:1001", mock_search_service_1),
+            ("127.0.0.1:1002", mock_search_service_2),
+            ("127.0.0.1:1003", mock_search_service_3),
+        ]);
+        let first_client_addr: SocketAddr = "127.0.0.1:1001".parse().unwrap();
+        let first_client = searcher_pool.get(&first_client_addr).unwrap();
+        let search_job_placer = SearchJobPlacer::new(searcher_pool);
+        let cluster_client = ClusterClient::new(search_job_placer);
+
+        let replanned_splits_before = SEARCH_METRICS.leaf_search_replanned_splits_total.get();
+        let leaf_search_response = cluster_client
+            .leaf_search(request, first_client)
+            .await
+            .unwrap();
+        assert_eq!(leaf_search_response.num_hits, 2);
+        assert_eq!(leaf_search_response.num_attempted_splits, 2);
+        assert_eq!(leaf_search_response.num_successful_splits, 2);
+        assert!(leaf_search_response.failed_splits.is_empty());
+        assert!(
+            SEARCH_METRICS.leaf_search_replanned_splits_total.get() >= replanned_splits_before + 2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cluster_client_leaf_search_saturated_single_searcher() {
+        let request = mock_leaf_search_request();
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_leaf_search()
+            .times(2)
+            .returning(|_: LeafSearchRequest| Err(SearchError::TooManyRequests));
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", mock_search_service)]);
+        let first_client_addr: SocketAddr = "127.0.0.1:1001".parse().unwrap();
+        let first_client = searcher_pool.get(&first_client_addr).unwrap();
+        let search_job_placer = SearchJobPlacer::new(searcher_pool);
+        let cluster_client = ClusterClient::new(search_job_placer);
+        let search_error = cluster_client
+            .leaf_search(request, first_client)
+            .await
+            .unwrap_err();
+        assert!(matches!(search_error, SearchError::TooManyRequests));
+    }
+
+    #[tokio::test]
+    async fn test_cluster_client_hedged_leaf_search_slow_primary() {
+        let request = mock_leaf_search_request();
+        let slow_leaf_search_service = SlowLeafSearchService {
+            delay: Duration::from_secs(60),
+            num_hits: 1,
+        };
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_leaf_search()
+            .return_once(|_: LeafSearchRequest| {
+                Ok(LeafSearchResponse {
+                    num_hits: 2,
+                    ..Default::default()
+                })
+            });
+        let (cluster_client, slow_client) =
+            hedging_cluster_client(slow_leaf_search_service, mock_search_service);
+
+        let hedged_requests_before = SEARCH_METRICS.leaf_search_hedged_requests_total.get();
+        let leaf_search_response = tokio::time::timeout(
+            Duration::from_secs(10),
+            cluster_client.leaf_search(request, slow_client),
+        )
+        .await
+        .expect("the hedged request should answer before the slow searcher")
+        .unwrap();
+        assert_eq!(leaf_search_response.num_hits, 2);
+        assert!(
+            SEARCH_METRICS.leaf_search_hedged_requests_total.get() >= hedged_requests_before + 1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cluster_client_hedged_leaf_search_exhausted_budget() {
+        let request = mock_leaf_search_request();
+        let slow_leaf_search_service = SlowLeafSearchService {
+            delay: Duration::from_millis(50),
+            num_hits: 1,
+        };
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service.expect_leaf_search().never();
+        let (cluster_client, slow_client) =
+            hedging_cluster_client(slow_leaf_search_service, mock_search_service);
+
+        let leaf_search_hedger = cluster_client.leaf_search_hedger_opt.as_ref().unwrap();
+        while leaf_search_hedger.try_acquire_hedge() {}
+
+        let leaf_search_response = cluster_client
+            .leaf_search(request, slow_client)
+            .await
+            .unwrap();
+        assert_eq!(leaf_search_response.num_hits, 1);
+    }
+
+    #[test]
+    fn test_leaf_search_request_for_splits() {
+        let request = mock_leaf_search_request();
+        let sub_request = leaf_search_request_for_splits(&request, &["split_2".to_string()]);
+        assert_eq!(sub_request.leaf_requests.len(), 1);
+        assert_eq!(sub_request.leaf_requests[0].split_offsets.len(), 1);
+        assert_eq!(
+            sub_request.leaf_requests[0].split_offsets[0].split_id,
+            "split_2"
+        );
+
+        let sub_request = leaf_search_request_for_splits(&request, &["split_3".to_string()]);
+        assert!(sub_request.leaf_requests.is_empty());
+    }
+
     #[test]
     fn test_merge_leaf_search_retry_on_partial_success() -> anyhow::Result<()> {
         let split_error = SplitSearchError {
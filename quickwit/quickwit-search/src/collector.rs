@@ -19,9 +19,10 @@ use std::collections::HashSet;
 use itertools::Itertools;
 use quickwit_common::binary_heap::{SortKeyMapper, TopK};
 use quickwit_doc_mapper::{FastFieldWarmupInfo, WarmupInfo};
+use quickwit_proto::compression::PayloadCodec;
 use quickwit_proto::search::{
     LeafSearchResponse, PartialHit, ResourceStats, SearchRequest, SortByValue, SortOrder,
-    SortValue, SplitSearchError,
+    SortValue, SplitSearchError, SplitSearchProfile,
 };
 use quickwit_proto::types::SplitId;
 use serde::Deserialize;
@@ -31,7 +32,8 @@ use tantivy::aggregation::{AggregationLimitsGuard, AggregationSegmentCollector};
 use tantivy::collector::{Collector, SegmentCollector};
 use tantivy::columnar::{ColumnType, MonotonicallyMappableToU64};
 use tantivy::fastfield::Column;
-use tantivy::{DateTime, DocId, Score, SegmentOrdinal, SegmentReader, TantivyError};
+use tantivy::query::{EnableScoring, Query};
+use tantivy::{DateTime, DocId, Score, Searcher, SegmentOrdinal, SegmentReader, TantivyError};
 
 use crate::find_trace_ids_collector::{FindTraceIdsCollector, FindTraceIdsSegmentCollector, Span};
 use crate::top_k_collector::{specialized_top_k_segment_collector, QuickwitSegmentTopKCollector};
@@ -591,6 +593,8 @@ impl SegmentCollector for QuickwitSegmentCollector {
             num_attempted_splits: 1,
             num_successful_splits: 1,
             resource_stats: None,
+            split_profiles: Vec::new(),
+            intermediate_aggregation_result_codec: PayloadCodec::None as i32,
         })
     }
 }
@@ -760,6 +764,40 @@ impl QuickwitCollector {
             ..WarmupInfo::default()
         }
     }
+
+    /// Returns true if hits are sorted by a fast field, making it possible to skip the segments
+    /// that cannot hold a document ranking better than the current top hits.
+    pub fn can_prune_segments(&self) -> bool {
+        self.max_hits + self.start_offset > 0
+            && self.aggregation.is_none()
+            && matches!(self.sort_by.first, SortByComponent::FastField { .. })
+    }
+
+    /// Returns the best sort value a document of the segment can have according to the primary
+    /// sort field, or `None` if the segment has no value for this field.
+    fn segment_best_sort_value(
+        &self,
+        segment_reader: &SegmentReader,
+    ) -> tantivy::Result<Option<SortValue>> {
+        let sort_field_extractor = self
+            .sort_by
+            .first
+            .to_sorting_field_extractor_component(segment_reader)?;
+        let SortingFieldExtractorComponent::FastField { sort_column, .. } = &sort_field_extractor
+        else {
+            return Ok(None);
+        };
+        if sort_column.values.num_vals() == 0 {
+            return Ok(None);
+        }
+        let best_value = match self.sort_by.first.sort_order() {
+            SortOrder::Asc => sort_column.min_value(),
+            SortOrder::Desc => sort_column.max_value(),
+        };
+        Ok(Some(
+            sort_field_extractor.convert_u64_ff_val_to_sort_value(best_value),
+        ))
+    }
 }
 
 impl Collector for QuickwitCollector {
@@ -867,7 +905,7 @@ fn map_error(err: postcard::Error) -> TantivyError {
 }
 
 /// Merges a set of Leaf Results.
-fn merge_intermediate_aggregation_result<'a>(
+pub(crate) fn merge_intermediate_aggregation_result<'a>(
     aggregations_opt: &Option<QuickwitAggregations>,
     intermediate_aggregation_results: impl Iterator<Item = &'a [u8]>,
 ) -> tantivy::Result<Option<Vec<u8>>> {
@@ -952,6 +990,10 @@ fn merge_leaf_responses(
         .flat_map(|leaf_response| leaf_response.failed_splits.iter())
         .cloned()
         .collect_vec();
+    let split_profiles = leaf_responses
+        .iter_mut()
+        .flat_map(|leaf_response| std::mem::take(&mut leaf_response.split_profiles))
+        .collect_vec();
     let all_partial_hits: Vec<PartialHit> = leaf_responses
         .into_iter()
         .flat_map(|leaf_response| leaf_response.partial_hits)
@@ -970,6 +1012,8 @@ fn merge_leaf_responses(
         num_attempted_splits,
         num_successful_splits,
         resource_stats: merged_resource_stats,
+        split_profiles,
+        intermediate_aggregation_result_codec: PayloadCodec::None as i32,
     })
 }
 
@@ -1051,6 +1095,96 @@ pub(crate) fn make_collector_for_split(
     })
 }
 
+/// Searches a split segment by segment, skipping the segments that cannot hold a document ranking
+/// better than the top hits.
+///
+/// Segments are visited starting with the one holding the best value of the primary sort field.
+/// Once `max_hits` hits have been collected, or if `worst_hit_sort_value_opt` (the sort value of
+/// the worst of the top hits collected on other splits) is set, the remaining segments whose best
+/// value ranks strictly below that bound are not searched at all.
+///
+/// Skipped segments do not contribute to `num_hits`, so this should only be used when the hit
+/// count is allowed to be underestimated, and with a collector for which
+/// [`QuickwitCollector::can_prune_segments`] returns true.
+///
+/// Returns the search response along with the number of segments that were skipped.
+pub(crate) fn search_with_segment_pruning(
+    searcher: &Searcher,
+    query: &dyn Query,
+    collector: &QuickwitCollector,
+    worst_hit_sort_value_opt: Option<SortValue>,
+) -> tantivy::Result<(LeafSearchResponse, usize)> {
+    let leaf_max_hits = collector.max_hits + collector.start_offset;
+    let (order1, order2) = collector.sort_by.sort_orders();
+
+    let mut segments: Vec<(SegmentOrdinal, Option<SortValue>)> =
+        Vec::with_capacity(searcher.segment_readers().len());
+    for (segment_ord, segment_reader) in searcher.segment_readers().iter().enumerate() {
+        let best_sort_value_opt = collector.segment_best_sort_value(segment_reader)?;
+        segments.push((segment_ord as SegmentOrdinal, best_sort_value_opt));
+    }
+    // Most promising segments first.
+    segments.sort_by(|(_, left), (_, right)| order1.compare_opt(right, left));
+
+    let enable_scoring = if collector.requires_scoring() {
+        EnableScoring::enabled_from_searcher(searcher)
+    } else {
+        EnableScoring::disabled_from_searcher(searcher)
+    };
+    let weight = query.weight(enable_scoring)?;
+
+    let num_segments = segments.len();
+    let mut bound_opt: Option<SortValue> = worst_hit_sort_value_opt;
+    let mut top_hits: Vec<PartialHit> = Vec::new();
+    let mut segment_fruits: Vec<tantivy::Result<LeafSearchResponse>> =
+        Vec::with_capacity(num_segments);
+
+    for (segment_ord, best_sort_value_opt) in segments {
+        if bound_opt.is_some() && order1.compare_opt(&best_sort_value_opt, &bound_opt).is_lt() {
+            // Segments are sorted, none of the remaining segments can do better.
+            break;
+        }
+        let segment_reader = searcher.segment_reader(segment_ord);
+        let segment_fruit =
+            collector.collect_segment(weight.as_ref(), segment_ord, segment_reader)??;
+
+        top_hits = top_k_partial_hits(
+            top_hits
+                .into_iter()
+                .chain(segment_fruit.partial_hits.iter().cloned()),
+            order1,
+            order2,
+            leaf_max_hits,
+        );
+        if top_hits.len() >= leaf_max_hits {
+            if let Some(worst_sort_value) = top_hits.last().and_then(PartialHit::sort_value) {
+                if order1
+                    .compare_opt(&Some(worst_sort_value), &bound_opt)
+                    .is_gt()
+                {
+                    bound_opt = Some(worst_sort_value);
+                }
+            }
+        }
+        segment_fruits.push(Ok(segment_fruit));
+    }
+    let num_pruned_segments = num_segments - segment_fruits.len();
+    crate::SEARCH_METRICS
+        .leaf_search_pruned_segments_total
+        .inc_by(num_pruned_segments as u64);
+
+    let leaf_search_response = if segment_fruits.is_empty() {
+        LeafSearchResponse {
+            num_attempted_splits: 1,
+            num_successful_splits: 1,
+            ..Default::default()
+        }
+    } else {
+        collector.merge_fruits(segment_fruits)?
+    };
+    Ok((leaf_search_response, num_pruned_segments))
+}
+
 /// Builds a QuickwitCollector that's only useful for merging fruits.
 pub(crate) fn make_merge_collector(
     search_request: &SearchRequest,
@@ -1194,6 +1328,7 @@ pub(crate) struct IncrementalCollector {
     num_successful_splits: u64,
     start_offset: usize,
     resource_stats: Option<ResourceStats>,
+    split_profiles: Vec<SplitSearchProfile>,
 }
 
 impl IncrementalCollector {
@@ -1215,6 +1350,7 @@ impl IncrementalCollector {
             num_attempted_splits: 0,
             num_successful_splits: 0,
             resource_stats: None,
+            split_profiles: Vec::new(),
         }
     }
 
@@ -1228,9 +1364,12 @@ impl IncrementalCollector {
             intermediate_aggregation_result,
             num_successful_splits,
             resource_stats,
+            split_profiles,
+            intermediate_aggregation_result_codec: _,
         } = leaf_response;
 
         merge_resource_stats(&resource_stats, &mut self.resource_stats);
+        self.split_profiles.extend(split_profiles);
 
         self.num_hits += num_hits;
         self.top_k_hits.add_entries(partial_hits.into_iter());
@@ -1282,6 +1421,8 @@ impl IncrementalCollector {
             num_successful_splits: self.num_successful_splits,
             intermediate_aggregation_result,
             resource_stats: self.resource_stats,
+            split_profiles: self.split_profiles,
+            intermediate_aggregation_result_codec: PayloadCodec::None as i32,
         })
     }
 }
@@ -1290,6 +1431,7 @@ impl IncrementalCollector {
 mod tests {
     use std::cmp::Ordering;
 
+    use quickwit_proto::compression::PayloadCodec;
     use quickwit_proto::search::{
         LeafSearchResponse, PartialHit, ResourceStats, SearchRequest, SortByValue, SortField,
         SortOrder, SortValue, SplitSearchError,
@@ -1607,6 +1749,103 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_search_with_segment_pruning() {
+        use tantivy::indexer::NoMergePolicy;
+        use tantivy::schema::{NumericOptions, Schema};
+        use tantivy::Index;
+
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_u64_field("sort1", NumericOptions::default().set_fast());
+        let schema = schema_builder.build();
+        let field = schema.get_field("sort1").unwrap();
+
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_with_num_threads(1, 15_000_000).unwrap();
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        for segment_values in [0..10, 100..110, 50..60] {
+            for value in segment_values {
+                let mut doc = TantivyDocument::new();
+                doc.add_u64(field, value);
+                index_writer.add_document(doc).unwrap();
+            }
+            index_writer.commit().unwrap();
+        }
+        let reader = index.reader().unwrap();
+        let searcher = reader.searcher();
+        assert_eq!(searcher.segment_readers().len(), 3);
+
+        for (sort_str, expected_sort_values) in [
+            ("sort1", [109, 108, 107, 106, 105]),
+            ("-sort1", [0, 1, 2, 3, 4]),
+        ] {
+            let collector = super::make_collector_for_split(
+                "fake_split_id".to_string(),
+                &make_request(5, sort_str),
+                Default::default(),
+            )
+            .unwrap();
+            assert!(collector.can_prune_segments());
+
+            let expected_res = searcher
+                .search(&tantivy::query::AllQuery, &collector)
+                .unwrap();
+            assert_eq!(expected_res.num_hits, 30);
+
+            let (res, num_pruned_segments) = super::search_with_segment_pruning(
+                &searcher,
+                &tantivy::query::AllQuery,
+                &collector,
+                None,
+            )
+            .unwrap();
+            assert_eq!(num_pruned_segments, 2);
+            assert_eq!(res.num_hits, 10);
+            assert_eq!(res.partial_hits, expected_res.partial_hits);
+            let sort_values: Vec<SortValue> = res
+                .partial_hits
+                .iter()
+                .map(|hit| hit.sort_value().unwrap())
+                .collect();
+            assert_eq!(sort_values, expected_sort_values.map(SortValue::U64));
+        }
+
+        // The top hits of other splits are better than anything in this split.
+        let collector = super::make_collector_for_split(
+            "fake_split_id".to_string(),
+            &make_request(5, "sort1"),
+            Default::default(),
+        )
+        .unwrap();
+        let (res, num_pruned_segments) = super::search_with_segment_pruning(
+            &searcher,
+            &tantivy::query::AllQuery,
+            &collector,
+            Some(SortValue::U64(200)),
+        )
+        .unwrap();
+        assert_eq!(num_pruned_segments, 3);
+        assert_eq!(res.num_hits, 0);
+        assert!(res.partial_hits.is_empty());
+        assert_eq!(res.num_successful_splits, 1);
+
+        // Counting only or sorting by score requires visiting every segment.
+        let collector = super::make_collector_for_split(
+            "fake_split_id".to_string(),
+            &make_request(0, "sort1"),
+            Default::default(),
+        )
+        .unwrap();
+        assert!(!collector.can_prune_segments());
+        let collector = super::make_collector_for_split(
+            "fake_split_id".to_string(),
+            &make_request(5, "_score"),
+            Default::default(),
+        )
+        .unwrap();
+        assert!(!collector.can_prune_segments());
+    }
+
     #[test]
     fn test_search_after() {
         let index = make_index();
@@ -1789,6 +2028,8 @@ mod tests {
                 num_successful_splits: 3,
                 intermediate_aggregation_result: None,
                 resource_stats: None,
+                split_profiles: Vec::new(),
+                intermediate_aggregation_result_codec: PayloadCodec::None as i32,
             }],
         );
 
@@ -1808,6 +2049,8 @@ mod tests {
                 num_successful_splits: 3,
                 intermediate_aggregation_result: None,
                 resource_stats: None,
+                split_profiles: Vec::new(),
+                intermediate_aggregation_result_codec: PayloadCodec::None as i32,
             }
         );
 
@@ -1847,6 +2090,8 @@ mod tests {
                     num_successful_splits: 3,
                     intermediate_aggregation_result: None,
                     resource_stats: None,
+                    split_profiles: Vec::new(),
+                    intermediate_aggregation_result_codec: PayloadCodec::None as i32,
                 },
                 LeafSearchResponse {
                     num_hits: 10,
@@ -1866,6 +2111,8 @@ mod tests {
                     num_successful_splits: 1,
                     intermediate_aggregation_result: None,
                     resource_stats: None,
+                    split_profiles: Vec::new(),
+                    intermediate_aggregation_result_codec: PayloadCodec::None as i32,
                 },
             ],
         );
@@ -1899,6 +2146,8 @@ mod tests {
                 num_successful_splits: 4,
                 intermediate_aggregation_result: None,
                 resource_stats: None,
+                split_profiles: Vec::new(),
+                intermediate_aggregation_result_codec: PayloadCodec::None as i32,
             }
         );
 
@@ -1942,6 +2191,8 @@ mod tests {
                         cpu_microsecs: 100,
                         ..Default::default()
                     }),
+                    split_profiles: Vec::new(),
+                    intermediate_aggregation_result_codec: PayloadCodec::None as i32,
                 },
                 LeafSearchResponse {
                     num_hits: 10,
@@ -1964,6 +2215,8 @@ mod tests {
                         cpu_microsecs: 50,
                         ..Default::default()
                     }),
+                    split_profiles: Vec::new(),
+                    intermediate_aggregation_result_codec: PayloadCodec::None as i32,
                 },
             ],
         );
@@ -2000,6 +2253,8 @@ mod tests {
                     cpu_microsecs: 150,
                     ..Default::default()
                 }),
+                split_profiles: Vec::new(),
+                intermediate_aggregation_result_codec: PayloadCodec::None as i32,
             }
         );
         // TODO would be nice to test aggregation too.
@@ -30,6 +30,10 @@ use tokio::task::JoinError;
 #[derive(Error, Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum SearchError {
+    #[error("search request cancelled: {0}")]
+    Cancelled(String),
+    #[error("forbidden: {0}")]
+    Forbidden(String),
     #[error("could not find indexes matching the IDs `{index_ids:?}`")]
     IndexesNotFound { index_ids: Vec<String> },
     #[error("internal error: `{0}`")]
@@ -70,6 +74,8 @@ impl SearchError {
 impl ServiceError for SearchError {
     fn error_code(&self) -> ServiceErrorCode {
         match self {
+            Self::Cancelled(_) => ServiceErrorCode::Cancelled,
+            Self::Forbidden(_) => ServiceErrorCode::Forbidden,
             Self::IndexesNotFound { .. } => ServiceErrorCode::NotFound,
             Self::Internal(error_msg) => {
                 rate_limited_error!(limit_per_min = 6, "search internal error: {error_msg}");
@@ -119,8 +125,13 @@ impl From<SearchError> for tonic::Status {
 /// Parse tonic error and returns `SearchError`.
 pub fn parse_grpc_error(grpc_error: &tonic::Status) -> SearchError {
     // TODO: the serialization to JSON part is missing.
-    serde_json::from_str(grpc_error.message())
-        .unwrap_or_else(|_| SearchError::Internal(grpc_error.message().to_string()))
+    serde_json::from_str(grpc_error.message()).unwrap_or_else(|_| {
+        // Searchers shedding load at the transport level do not return a serialized error.
+        if grpc_error.code() == tonic::Code::ResourceExhausted {
+            return SearchError::TooManyRequests;
+        }
+        SearchError::Internal(grpc_error.message().to_string())
+    })
 }
 
 impl From<TantivyError> for SearchError {
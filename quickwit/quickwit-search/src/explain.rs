@@ -0,0 +1,395 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use quickwit_config::build_doc_mapper;
+use quickwit_doc_mapper::DocMapper;
+use quickwit_metastore::IndexMetadataResponseExt;
+use quickwit_proto::metastore::{IndexMetadataRequest, MetastoreService, MetastoreServiceClient};
+use quickwit_proto::search::{SearchRequest, SecurityContext};
+use quickwit_proto::types::IndexId;
+use quickwit_query::query_ast::QueryAst;
+use quickwit_storage::{ByteRangeCache, StorageResolver};
+use serde::{Deserialize, Serialize};
+use tantivy::schema::{FieldType, TantivyDocument, Value};
+use tantivy::tokenizer::TokenStream;
+use tantivy::{DocAddress, ReloadPolicy, Searcher, TantivyError};
+
+use crate::leaf::{open_index_with_caches, warmup};
+use crate::security::{apply_security_policies, RetrievableFields};
+use crate::service::SearcherContext;
+use crate::tag_overlay::apply_tag_overlays;
+use crate::{
+    extract_split_and_footer_offsets, list_relevant_splits, GlobalDocAddress, SearchError,
+};
+
+/// Request to explain whether and why a document matches a query.
+#[derive(Clone, Debug)]
+pub struct ExplainRequest {
+    /// Index of the document.
+    pub index_id: IndexId,
+    /// Serialized query AST. It may contain a user query that is resolved with the default search
+    /// fields of the index.
+    pub query_ast: String,
+    /// Address of the document.
+    pub doc_address: GlobalDocAddress,
+    /// Security context of the requester, if any.
+    pub security_context: Option<SecurityContext>,
+}
+
+/// Explanation of whether and why a document matches a query.
+#[derive(Debug, Serialize, Deserialize, PartialEq, utoipa::ToSchema)]
+pub struct ExplainResponse {
+    /// Address of the document.
+    pub doc_address: String,
+    /// Whether the document matches the query.
+    pub matches: bool,
+    /// Score of the document, if it matches the query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+    /// Clause-by-clause explanation of the query.
+    pub explanation: ClauseExplanation,
+}
+
+/// Explanation of whether a clause of a query matches a document.
+#[derive(Debug, Serialize, Deserialize, PartialEq, utoipa::ToSchema)]
+pub struct ClauseExplanation {
+    /// Occurrence of the clause in its parent boolean query: `must`, `must_not`, `should`, or
+    /// `filter`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub occur: Option<String>,
+    /// The clause, as a query AST.
+    #[schema(value_type = Object)]
+    pub query: QueryAst,
+    /// Whether the clause matches the document.
+    pub matches: bool,
+    /// Score of the clause, if it matches the document.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+    /// Tokens produced by the analysis of the query text of the clause.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub query_tokens: Vec<String>,
+    /// Tokens produced by the analysis of the stored values of the field targeted by the clause.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub document_tokens: Vec<String>,
+    /// Error raised while building the clause, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Explanations of the sub-clauses of the clause.
+    #[schema(value_type = Vec<Object>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub clauses: Vec<ClauseExplanation>,
+}
+
+/// Explains whether and why a document matches a query.
+///
+/// The split holding the document is opened by the node serving the request, so this is meant for
+/// debugging purposes only.
+pub async fn explain(
+    explain_request: ExplainRequest,
+    mut metastore: MetastoreServiceClient,
+    storage_resolver: &StorageResolver,
+    searcher_context: &SearcherContext,
+) -> crate::Result<ExplainResponse> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(explain_request.index_id);
+    let index_metadata = metastore
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?;
+    let index_id = &index_metadata.index_config.index_id;
+    let doc_mapper = build_doc_mapper(
+        &index_metadata.index_config.doc_mapping,
+        &index_metadata.index_config.search_settings,
+    )
+    .map_err(|err| SearchError::Internal(format!("failed to build doc mapper. cause: {err}")))?;
+
+    // The filters of the security policies are explained like any other clause of the query.
+    let mut search_request = SearchRequest {
+        index_id_patterns: vec![index_id.clone()],
+        query_ast: explain_request.query_ast,
+        security_context: explain_request.security_context.clone(),
+        ..Default::default()
+    };
+    let retrievable_fields =
+        apply_security_policies(std::slice::from_ref(&index_metadata), &mut search_request)?;
+
+    // Documents excluded by the security filter must not be explained, so the filter is also
+    // evaluated on its own.
+    let mut security_filter_request = SearchRequest {
+        index_id_patterns: vec![index_id.clone()],
+        query_ast: serde_json::to_string(&QueryAst::MatchAll)?,
+        security_context: explain_request.security_context,
+        ..Default::default()
+    };
+    apply_security_policies(
+        std::slice::from_ref(&index_metadata),
+        &mut security_filter_request,
+    )?;
+    let resolve_query_ast = |query_ast_json: &str| -> crate::Result<QueryAst> {
+        let query_ast: QueryAst = serde_json::from_str(query_ast_json)
+            .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
+        let query_ast = query_ast
+            .parse_user_query_with_defaults(doc_mapper.user_query_defaults())
+            .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
+        Ok(apply_tag_overlays(query_ast, &index_metadata.tag_overlays))
+    };
+    let query_ast = resolve_query_ast(&search_request.query_ast)?;
+    let security_filter_ast = resolve_query_ast(&security_filter_request.query_ast)?;
+
+    let doc_address = &explain_request.doc_address;
+    let split_metadata = list_relevant_splits(
+        vec![index_metadata.index_uid.clone()],
+        None,
+        None,
+        None,
+        &mut metastore,
+    )
+    .await?
+    .into_iter()
+    .find(|split_metadata| split_metadata.split_id == doc_address.split)
+    .ok_or_else(|| {
+        SearchError::InvalidArgument(format!(
+            "split `{}` not found in index `{index_id}`",
+            doc_address.split
+        ))
+    })?;
+    let split_and_footer_offsets = extract_split_and_footer_offsets(&split_metadata);
+    let index_storage = storage_resolver
+        .resolve_with_credentials(
+            index_metadata.index_uri(),
+            index_metadata
+                .index_config
+                .storage_credentials_opt
+                .as_deref(),
+        )
+        .await?;
+    let ephemeral_unbounded_cache =
+        ByteRangeCache::with_infinite_capacity(&quickwit_storage::STORAGE_METRICS.shortlived_cache);
+    let (index, _) = open_index_with_caches(
+        searcher_context,
+        index_storage,
+        Some(index_metadata.index_id()),
+        &split_and_footer_offsets,
+        Some(doc_mapper.tokenizer_manager()),
+        Some(ephemeral_unbounded_cache),
+    )
+    .await?;
+    let index_reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()?;
+    let searcher = index_reader.searcher();
+
+    let DocAddress {
+        segment_ord,
+        doc_id,
+    } = doc_address.doc_addr;
+    let doc_exists = searcher
+        .segment_readers()
+        .get(segment_ord as usize)
+        .map(|segment_reader| doc_id < segment_reader.max_doc())
+        .unwrap_or(false);
+    let document_not_found_error = || {
+        SearchError::InvalidArgument(format!(
+            "document `{doc_address}` not found in index `{index_id}`"
+        ))
+    };
+    if !doc_exists {
+        return Err(document_not_found_error());
+    }
+    // Scoring requires the field norms, which are not always part of the warmup info.
+    let (_, mut warmup_info) = doc_mapper.query(searcher.schema().clone(), &query_ast, true)?;
+    warmup_info.field_norms = true;
+    warmup(&searcher, &warmup_info).await?;
+
+    // The query embeds the security filter, so its terms are already warmed up.
+    let (security_filter, _) =
+        doc_mapper.query(searcher.schema().clone(), &security_filter_ast, true)?;
+    match security_filter.explain(&searcher, doc_address.doc_addr) {
+        Ok(_) => {}
+        // Tantivy reports that a document does not match with an invalid argument error.
+        Err(TantivyError::InvalidArgument(_)) => return Err(document_not_found_error()),
+        Err(error) => return Err(error.into()),
+    }
+
+    let document: TantivyDocument = searcher.doc_async(doc_address.doc_addr).await?;
+    let clause_explainer = ClauseExplainer {
+        doc_mapper: &doc_mapper,
+        searcher: &searcher,
+        doc_address: doc_address.doc_addr,
+        document: &document,
+        index_id,
+        retrievable_fields: &retrievable_fields,
+    };
+    let explanation = clause_explainer.explain(query_ast, None);
+
+    Ok(ExplainResponse {
+        doc_address: doc_address.to_string(),
+        matches: explanation.matches,
+        score: explanation.score,
+        explanation,
+    })
+}
+
+struct ClauseExplainer<'a> {
+    doc_mapper: &'a DocMapper,
+    searcher: &'a Searcher,
+    doc_address: DocAddress,
+    document: &'a TantivyDocument,
+    index_id: &'a str,
+    retrievable_fields: &'a RetrievableFields,
+}
+
+impl ClauseExplainer<'_> {
+    fn explain(&self, query_ast: QueryAst, occur_opt: Option<&str>) -> ClauseExplanation {
+        let mut clauses = Vec::new();
+
+        match &query_ast {
+            QueryAst::Bool(bool_query) => {
+                for (occur, sub_query_asts) in [
+                    ("must", &bool_query.must),
+                    ("must_not", &bool_query.must_not),
+                    ("should", &bool_query.should),
+                    ("filter", &bool_query.filter),
+                ] {
+                    for sub_query_ast in sub_query_asts {
+                        clauses.push(self.explain(sub_query_ast.clone(), Some(occur)));
+                    }
+                }
+            }
+            QueryAst::Boost { underlying, .. } | QueryAst::Named { underlying, .. } => {
+                clauses.push(self.explain((**underlying).clone(), None));
+            }
+            _ => {}
+        }
+        let (matches, score, error) =
+            match self
+                .doc_mapper
+                .query(self.searcher.schema().clone(), &query_ast, true)
+            {
+                Ok((query, _)) => match query.explain(self.searcher, self.doc_address) {
+                    Ok(explanation) => (true, Some(explanation.value()), None),
+                    // Tantivy reports that a document does not match with an invalid argument
+                    // error.
+                    Err(TantivyError::InvalidArgument(_)) => (false, None, None),
+                    Err(error) => (false, None, Some(error.to_string())),
+                },
+                Err(error) => (false, None, Some(error.to_string())),
+            };
+        let (query_tokens, document_tokens) = self.analyze(&query_ast);
+
+        ClauseExplanation {
+            occur: occur_opt.map(str::to_string),
+            query: query_ast,
+            matches,
+            score,
+            query_tokens,
+            document_tokens,
+            error,
+            clauses,
+        }
+    }
+
+    /// Returns the tokens of the query text of a full-text or term clause and the tokens of the
+    /// stored values of the field it targets. The stored values of the fields the caller cannot
+    /// retrieve are not analyzed.
+    fn analyze(&self, query_ast: &QueryAst) -> (Vec<String>, Vec<String>) {
+        let (field_path, query_tokens, tokenizer_name_opt) = match query_ast {
+            QueryAst::FullText(full_text_query) => {
+                let Some(tokenizer_name) = full_text_query
+                    .params
+                    .tokenizer
+                    .clone()
+                    .or_else(|| self.field_tokenizer_name(&full_text_query.field))
+                else {
+                    return (Vec::new(), Vec::new());
+                };
+                let query_tokens = self.tokenize(&tokenizer_name, &full_text_query.text);
+                (&full_text_query.field, query_tokens, Some(tokenizer_name))
+            }
+            QueryAst::PhrasePrefix(phrase_prefix_query) => {
+                let Some(tokenizer_name) = phrase_prefix_query
+                    .params
+                    .tokenizer
+                    .clone()
+                    .or_else(|| self.field_tokenizer_name(&phrase_prefix_query.field))
+                else {
+                    return (Vec::new(), Vec::new());
+                };
+                let query_tokens = self.tokenize(&tokenizer_name, &phrase_prefix_query.phrase);
+                (
+                    &phrase_prefix_query.field,
+                    query_tokens,
+                    Some(tokenizer_name),
+                )
+            }
+            QueryAst::Term(term_query) => (
+                &term_query.field,
+                vec![term_query.value.clone()],
+                self.field_tokenizer_name(&term_query.field),
+            ),
+            _ => return (Vec::new(), Vec::new()),
+        };
+        let Some(tokenizer_name) = tokenizer_name_opt else {
+            return (query_tokens, Vec::new());
+        };
+        if !self
+            .retrievable_fields
+            .is_retrievable(self.index_id, field_path)
+        {
+            return (query_tokens, Vec::new());
+        }
+        let schema = self.searcher.schema();
+
+        // The stored values of the fields nested in a JSON field are not analyzed.
+        let document_tokens = match schema.find_field(field_path) {
+            Some((field, "")) => self
+                .document
+                .get_all(field)
+                .filter_map(|value| value.as_str())
+                .flat_map(|text| self.tokenize(&tokenizer_name, text))
+                .collect(),
+            _ => Vec::new(),
+        };
+        (query_tokens, document_tokens)
+    }
+
+    fn field_tokenizer_name(&self, field_path: &str) -> Option<String> {
+        let schema = self.searcher.schema();
+        let (field, _) = schema.find_field(field_path)?;
+
+        let text_field_indexing = match schema.get_field_entry(field).field_type() {
+            FieldType::Str(text_options) => text_options.get_indexing_options()?,
+            FieldType::JsonObject(json_options) => json_options.get_text_indexing_options()?,
+            _ => return None,
+        };
+        Some(text_field_indexing.tokenizer().to_string())
+    }
+
+    fn tokenize(&self, tokenizer_name: &str, text: &str) -> Vec<String> {
+        let Some(mut text_analyzer) = self
+            .doc_mapper
+            .tokenizer_manager()
+            .get_tokenizer(tokenizer_name)
+        else {
+            return Vec::new();
+        };
+        let mut token_stream = text_analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        token_stream.process(&mut |token| tokens.push(token.text.clone()));
+        tokens
+    }
+}
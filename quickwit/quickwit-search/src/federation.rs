@@ -0,0 +1,765 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use bytesize::ByteSize;
+use futures::future::join_all;
+use http::Uri;
+use quickwit_common::binary_heap::{SortKeyMapper, TopK};
+use quickwit_config::{RemoteClusterConfig, RemoteClusterTlsConfig};
+use quickwit_proto::metastore::MetastoreServiceClient;
+use quickwit_proto::search::search_service_client::SearchServiceClient as SearchServiceGrpcClient;
+use quickwit_proto::search::{Hit, PartialHit, SearchProfile, SearchRequest, SearchResponse};
+use quickwit_proto::tonic::codegen::InterceptedService;
+use quickwit_proto::tonic::metadata::{Ascii, MetadataValue};
+use quickwit_proto::tonic::service::Interceptor;
+use quickwit_proto::tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use quickwit_proto::tonic::{Request, Status};
+use quickwit_proto::SpanContextInterceptor;
+use tower::timeout::Timeout;
+use tracing::warn;
+
+use crate::collector::{
+    merge_intermediate_aggregation_result, sort_by_from_request, HitSortingMapper,
+    PartialHitSortingKey,
+};
+use crate::error::parse_grpc_error;
+use crate::rate_aggregation::RateAggregations;
+use crate::root::finalize_aggregation_if_any;
+use crate::{root_search, ClusterClient, QuickwitAggregations, SearchError, SearcherContext};
+
+/// Separates the ID of a remote cluster from an index ID pattern in the index ID patterns of a
+/// search request: `<cluster_id>:<index_id_pattern>`.
+const REMOTE_CLUSTER_SEPARATOR: char = ':';
+
+/// Injects the span context and the API key of a remote cluster into the requests sent to it.
+#[derive(Clone)]
+struct RemoteClusterInterceptor {
+    authorization_opt: Option<MetadataValue<Ascii>>,
+}
+
+impl Interceptor for RemoteClusterInterceptor {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let mut request = SpanContextInterceptor.call(request)?;
+
+        if let Some(authorization) = &self.authorization_opt {
+            request
+                .metadata_mut()
+                .insert("authorization", authorization.clone());
+        }
+        Ok(request)
+    }
+}
+
+#[derive(Clone)]
+enum RemoteSearchClient {
+    Grpc(SearchServiceGrpcClient<InterceptedService<Timeout<Channel>, RemoteClusterInterceptor>>),
+    #[cfg(test)]
+    Local(Arc<dyn crate::SearchService>),
+}
+
+impl RemoteSearchClient {
+    async fn root_search(
+        &mut self,
+        search_request: SearchRequest,
+    ) -> crate::Result<SearchResponse> {
+        match self {
+            Self::Grpc(grpc_client) => grpc_client
+                .root_search(search_request)
+                .await
+                .map(|tonic_response| tonic_response.into_inner())
+                .map_err(|tonic_error| parse_grpc_error(&tonic_error)),
+            #[cfg(test)]
+            Self::Local(search_service) => search_service.root_search(search_request).await,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct RemoteCluster {
+    config: RemoteClusterConfig,
+    search_client: RemoteSearchClient,
+}
+
+impl RemoteCluster {
+    /// Returns whether all the index IDs matched by `index_id_pattern` are matched by one of the
+    /// index ID patterns the remote cluster allows to search.
+    fn allows(&self, index_id_pattern: &str) -> bool {
+        self.config
+            .index_id_patterns
+            .iter()
+            .any(|allowed_index_id_pattern| {
+                glob_matches(allowed_index_id_pattern, index_id_pattern)
+            })
+    }
+}
+
+/// The remote Quickwit clusters to which root search requests can be federated.
+#[derive(Clone, Default)]
+pub struct RemoteClusters {
+    remote_clusters: Arc<HashMap<String, RemoteCluster>>,
+}
+
+impl RemoteClusters {
+    /// Creates the search clients of the remote clusters.
+    ///
+    /// The connections are established lazily and the host of the gRPC endpoint is resolved each
+    /// time a connection is established, so a remote cluster that is unavailable when the node
+    /// starts is searched once it becomes available. Must be called from a Tokio runtime.
+    pub fn connect(
+        remote_cluster_configs: &[RemoteClusterConfig],
+        request_timeout: Duration,
+        max_message_size: ByteSize,
+    ) -> anyhow::Result<Self> {
+        let mut remote_clusters = Vec::with_capacity(remote_cluster_configs.len());
+
+        for remote_cluster_config in remote_cluster_configs {
+            let search_client =
+                connect_remote_cluster(remote_cluster_config, request_timeout, max_message_size)
+                    .with_context(|| {
+                        format!(
+                            "failed to create search client of remote cluster `{}`",
+                            remote_cluster_config.cluster_id
+                        )
+                    })?;
+            remote_clusters.push((remote_cluster_config.clone(), search_client));
+        }
+        Ok(Self::from_search_clients(remote_clusters))
+    }
+
+    fn from_search_clients(
+        remote_clusters: impl IntoIterator<Item = (RemoteClusterConfig, RemoteSearchClient)>,
+    ) -> Self {
+        let remote_clusters = remote_clusters
+            .into_iter()
+            .map(|(config, search_client)| {
+                let remote_cluster = RemoteCluster {
+                    config,
+                    search_client,
+                };
+                (remote_cluster.config.cluster_id.clone(), remote_cluster)
+            })
+            .collect();
+        Self {
+            remote_clusters: Arc::new(remote_clusters),
+        }
+    }
+
+    /// Splits the index ID patterns of a search request into the patterns targeting the local
+    /// cluster and the patterns targeting each remote cluster.
+    #[allow(clippy::type_complexity)]
+    fn route_index_id_patterns(
+        &self,
+        index_id_patterns: &[String],
+    ) -> crate::Result<(Vec<String>, BTreeMap<&str, (&RemoteCluster, Vec<String>)>)> {
+        let mut local_index_id_patterns = Vec::new();
+        let mut remote_index_id_patterns = BTreeMap::new();
+
+        for index_id_pattern in index_id_patterns {
+            let Some((cluster_id, remote_index_id_pattern)) =
+                index_id_pattern.split_once(REMOTE_CLUSTER_SEPARATOR)
+            else {
+                local_index_id_patterns.push(index_id_pattern.clone());
+                continue;
+            };
+            let Some(remote_cluster) = self.remote_clusters.get(cluster_id) else {
+                return Err(SearchError::InvalidArgument(format!(
+                    "unknown remote cluster `{cluster_id}`"
+                )));
+            };
+            if !remote_cluster.allows(remote_index_id_pattern) {
+                return Err(SearchError::InvalidArgument(format!(
+                    "index ID pattern `{remote_index_id_pattern}` is not allowed on remote \
+                     cluster `{cluster_id}`"
+                )));
+            }
+            remote_index_id_patterns
+                .entry(remote_cluster.config.cluster_id.as_str())
+                .or_insert_with(|| (remote_cluster, Vec::new()))
+                .1
+                .push(remote_index_id_pattern.to_string());
+        }
+        // Negative patterns only exclude indexes, so there is nothing to search locally if they
+        // are the only local patterns.
+        if local_index_id_patterns
+            .iter()
+            .all(|index_id_pattern| index_id_pattern.starts_with('-'))
+        {
+            local_index_id_patterns.clear();
+        }
+        Ok((local_index_id_patterns, remote_index_id_patterns))
+    }
+}
+
+fn connect_remote_cluster(
+    remote_cluster_config: &RemoteClusterConfig,
+    request_timeout: Duration,
+    max_message_size: ByteSize,
+) -> anyhow::Result<RemoteSearchClient> {
+    let grpc_endpoint = remote_cluster_config.grpc_endpoint()?;
+    let scheme = if remote_cluster_config.tls.is_some() {
+        "https"
+    } else {
+        "http"
+    };
+    let uri = Uri::builder()
+        .scheme(scheme)
+        .authority(grpc_endpoint.to_string().as_str())
+        .path_and_query("/")
+        .build()?;
+    let mut endpoint = Endpoint::from(uri);
+
+    if let Some(tls_config) = &remote_cluster_config.tls {
+        endpoint = endpoint.tls_config(make_client_tls_config(tls_config)?)?;
+    }
+    let channel = Timeout::new(endpoint.connect_lazy(), request_timeout);
+    let authorization_opt = remote_cluster_config
+        .api_key
+        .as_ref()
+        .map(|api_key| MetadataValue::try_from(format!("Bearer {api_key}")))
+        .transpose()?;
+    let interceptor = RemoteClusterInterceptor { authorization_opt };
+    let grpc_client = SearchServiceGrpcClient::with_interceptor(channel, interceptor)
+        .max_decoding_message_size(max_message_size.0 as usize)
+        .max_encoding_message_size(max_message_size.0 as usize);
+    Ok(RemoteSearchClient::Grpc(grpc_client))
+}
+
+fn make_client_tls_config(tls_config: &RemoteClusterTlsConfig) -> anyhow::Result<ClientTlsConfig> {
+    let ca_pem = std::fs::read_to_string(&tls_config.ca_path)
+        .with_context(|| format!("failed to read CA certificate `{}`", tls_config.ca_path))?;
+    let mut client_tls_config =
+        ClientTlsConfig::new().ca_certificate(Certificate::from_pem(ca_pem));
+
+    if let (Some(cert_path), Some(key_path)) = (&tls_config.cert_path, &tls_config.key_path) {
+        let cert_pem = std::fs::read_to_string(cert_path)
+            .with_context(|| format!("failed to read certificate `{cert_path}`"))?;
+        let key_pem = std::fs::read_to_string(key_path)
+            .with_context(|| format!("failed to read key `{key_path}`"))?;
+        client_tls_config = client_tls_config.identity(Identity::from_pem(cert_pem, key_pem));
+    }
+    if let Some(expected_name) = &tls_config.expected_name {
+        client_tls_config = client_tls_config.domain_name(expected_name);
+    }
+    Ok(client_tls_config)
+}
+
+/// Returns whether the search request targets the indexes of a remote cluster.
+pub(crate) fn is_federated_search_request(search_request: &SearchRequest) -> bool {
+    search_request
+        .index_id_patterns
+        .iter()
+        .any(|index_id_pattern| index_id_pattern.contains(REMOTE_CLUSTER_SEPARATOR))
+}
+
+/// Performs a search across the local cluster and the remote clusters targeted by the index ID
+/// patterns of the request.
+///
+/// Each cluster executes a root search, returning the first `start_offset + max_hits` hits and
+/// its intermediate aggregation results. The hits are then merged according to the sort order of
+/// the request and the aggregations are merged and finalized. The hits of the remote clusters are
+/// attributed to their origin cluster by prefixing their index ID with the cluster ID.
+pub(crate) async fn federated_root_search(
+    searcher_context: &SearcherContext,
+    mut search_request: SearchRequest,
+    metastore: MetastoreServiceClient,
+    cluster_client: &ClusterClient,
+    remote_clusters: &RemoteClusters,
+) -> crate::Result<SearchResponse> {
+    let start_instant = Instant::now();
+
+    if search_request.scroll_ttl_secs.is_some() || search_request.search_after.is_some() {
+        return Err(SearchError::InvalidArgument(
+            "scroll and search_after are not supported for searches targeting remote clusters"
+                .to_string(),
+        ));
+    }
+    let (local_index_id_patterns, remote_index_id_patterns) =
+        remote_clusters.route_index_id_patterns(&search_request.index_id_patterns)?;
+    let rate_aggregations = RateAggregations::extract_from_request(&mut search_request)?;
+
+    let mut cluster_search_request = search_request.clone();
+    cluster_search_request.start_offset = 0;
+    cluster_search_request.max_hits = search_request.start_offset + search_request.max_hits;
+    cluster_search_request.return_intermediate_aggregation = true;
+
+    let local_search_future = async {
+        if local_index_id_patterns.is_empty() {
+            return Ok(None);
+        }
+        let local_search_request = SearchRequest {
+            index_id_patterns: local_index_id_patterns,
+            ..cluster_search_request.clone()
+        };
+        root_search(
+            searcher_context,
+            local_search_request,
+            metastore,
+            cluster_client,
+        )
+        .await
+        .map(Some)
+    };
+    let remote_search_futures =
+        remote_index_id_patterns
+            .into_values()
+            .map(|(remote_cluster, index_id_patterns)| {
+                let mut search_client = remote_cluster.search_client.clone();
+                let remote_search_request = SearchRequest {
+                    index_id_patterns,
+                    ..cluster_search_request.clone()
+                };
+                async move {
+                    let search_result = search_client.root_search(remote_search_request).await;
+                    (remote_cluster, search_result)
+                }
+            });
+    let (local_search_result, remote_search_results) =
+        tokio::join!(local_search_future, join_all(remote_search_futures));
+
+    let mut cluster_search_responses: Vec<(Option<&str>, SearchResponse)> = Vec::new();
+    let mut errors = Vec::new();
+
+    if let Some(local_search_response) = local_search_result? {
+        cluster_search_responses.push((None, local_search_response));
+    }
+    for (remote_cluster, search_result) in remote_search_results {
+        let cluster_id = remote_cluster.config.cluster_id.as_str();
+
+        match search_result {
+            Ok(search_response) => {
+                cluster_search_responses.push((Some(cluster_id), search_response));
+            }
+            Err(search_error) if remote_cluster.config.skip_unavailable => {
+                warn!(cluster_id, error=%search_error, "failed to search remote cluster");
+                errors.push(format!(
+                    "failed to search remote cluster `{cluster_id}`: {search_error}"
+                ));
+            }
+            Err(search_error) => {
+                warn!(cluster_id, error=%search_error, "failed to search remote cluster");
+                return Err(search_error);
+            }
+        }
+    }
+    let mut search_response = merge_cluster_search_responses(
+        searcher_context,
+        &search_request,
+        cluster_search_responses,
+    )?;
+    rate_aggregations.apply(&mut search_response)?;
+    search_response.errors.extend(errors);
+    search_response.elapsed_time_micros = start_instant.elapsed().as_micros() as u64;
+    Ok(search_response)
+}
+
+fn merge_cluster_search_responses(
+    searcher_context: &SearcherContext,
+    search_request: &SearchRequest,
+    cluster_search_responses: Vec<(Option<&str>, SearchResponse)>,
+) -> crate::Result<SearchResponse> {
+    let (sort_order1, sort_order2) = sort_by_from_request(search_request).sort_orders();
+    let hit_sorting_mapper = HitSortingMapper {
+        order1: sort_order1,
+        order2: sort_order2,
+    };
+    let num_hits_to_keep = (search_request.start_offset + search_request.max_hits) as usize;
+    let mut top_k_hits = TopK::new(num_hits_to_keep, hit_sorting_mapper);
+
+    let mut merged_search_response = SearchResponse::default();
+    let mut intermediate_aggregation_results = Vec::new();
+
+    for (cluster_id_opt, search_response) in cluster_search_responses {
+        merged_search_response.num_hits += search_response.num_hits;
+        merged_search_response.num_successful_splits += search_response.num_successful_splits;
+        merged_search_response
+            .failed_splits
+            .extend(search_response.failed_splits);
+        merged_search_response.errors.extend(search_response.errors);
+        merged_search_response
+            .warnings
+            .extend(search_response.warnings);
+
+        if let Some(profile) = search_response.profile {
+            merged_search_response
+                .profile
+                .get_or_insert_with(SearchProfile::default)
+                .nodes
+                .extend(profile.nodes);
+        }
+        if let Some(intermediate_aggregation_result) =
+            search_response.intermediate_aggregation_result
+        {
+            intermediate_aggregation_results.push(intermediate_aggregation_result);
+        }
+        for mut hit in search_response.hits {
+            if let Some(cluster_id) = cluster_id_opt {
+                hit.index_id = format!("{cluster_id}{REMOTE_CLUSTER_SEPARATOR}{}", hit.index_id);
+            }
+            top_k_hits.add_entry(hit);
+        }
+    }
+    merged_search_response.hits = top_k_hits
+        .finalize()
+        .into_iter()
+        .skip(search_request.start_offset as usize)
+        .collect();
+
+    let aggregations_opt: Option<QuickwitAggregations> = search_request
+        .aggregation_request
+        .as_ref()
+        .map(|aggregation_request| serde_json::from_str(aggregation_request))
+        .transpose()?;
+    let merged_intermediate_aggregation_result_opt = merge_intermediate_aggregation_result(
+        &aggregations_opt,
+        intermediate_aggregation_results.iter().map(Vec::as_slice),
+    )?;
+    merged_search_response.aggregation = finalize_aggregation_if_any(
+        search_request,
+        merged_intermediate_aggregation_result_opt,
+        searcher_context,
+    )?;
+    Ok(merged_search_response)
+}
+
+impl SortKeyMapper<Hit> for HitSortingMapper {
+    type Key = PartialHitSortingKey;
+
+    fn get_sort_key(&self, hit: &Hit) -> PartialHitSortingKey {
+        let partial_hit = hit
+            .partial_hit
+            .as_ref()
+            .expect("partial hit must be present");
+        SortKeyMapper::<PartialHit>::get_sort_key(self, partial_hit)
+    }
+}
+
+/// Matches `text` against the glob `pattern`, where `*` matches any sequence of characters.
+///
+/// The `*` characters of `text` are matched as regular characters, so a pattern matching another
+/// pattern also matches all the index IDs matched by the latter.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.as_bytes();
+    let text = text.as_bytes();
+
+    let mut pattern_pos = 0;
+    let mut text_pos = 0;
+    // Position of the last `*` in the pattern and of the text it is matched against.
+    let mut backtrack_opt: Option<(usize, usize)> = None;
+
+    while text_pos < text.len() {
+        if pattern.get(pattern_pos) == Some(&b'*') {
+            backtrack_opt = Some((pattern_pos, text_pos));
+            pattern_pos += 1;
+        } else if pattern.get(pattern_pos) == Some(&text[text_pos]) {
+            pattern_pos += 1;
+            text_pos += 1;
+        } else if let Some((star_pos, star_text_pos)) = backtrack_opt {
+            // Let the last `*` consume one more character.
+            backtrack_opt = Some((star_pos, star_text_pos + 1));
+            pattern_pos = star_pos + 1;
+            text_pos = star_text_pos + 1;
+        } else {
+            return false;
+        }
+    }
+    pattern[pattern_pos..].iter().all(|&byte| byte == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_proto::metastore::MockMetastoreService;
+    use quickwit_proto::search::{SortField, SortOrder, SortValue};
+
+    use super::*;
+    use crate::{MockSearchService, SearchJobPlacer, SearcherPool};
+
+    fn remote_cluster_config(cluster_id: &str, skip_unavailable: bool) -> RemoteClusterConfig {
+        RemoteClusterConfig {
+            cluster_id: cluster_id.to_string(),
+            grpc_endpoint: format!("quickwit-{cluster_id}:7281"),
+            index_id_patterns: vec!["logs-*".to_string()],
+            skip_unavailable,
+            tls: None,
+            api_key: None,
+        }
+    }
+
+    fn remote_clusters_for_test(
+        remote_clusters: Vec<(RemoteClusterConfig, MockSearchService)>,
+    ) -> RemoteClusters {
+        RemoteClusters::from_search_clients(remote_clusters.into_iter().map(
+            |(config, mock_search_service)| {
+                let search_client = RemoteSearchClient::Local(Arc::new(mock_search_service));
+                (config, search_client)
+            },
+        ))
+    }
+
+    fn mock_hit(index_id: &str, sort_value: u64) -> Hit {
+        Hit {
+            json: format!(r#"{{"sort_value": {sort_value}}}"#),
+            partial_hit: Some(PartialHit {
+                sort_value: Some(SortValue::U64(sort_value).into()),
+                sort_value2: None,
+                split_id: "split".to_string(),
+                segment_ord: 0,
+                doc_id: sort_value as u32,
+            }),
+            snippet: None,
+            index_id: index_id.to_string(),
+            matched_queries: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remote_clusters_connect_lazily() {
+        let mut eu_west_config = remote_cluster_config("eu-west", true);
+        // The host cannot be resolved, which must not prevent the node from starting.
+        eu_west_config.grpc_endpoint = "quickwit-eu-west.invalid:7281".to_string();
+        eu_west_config.api_key = Some("eu-west-api-key".to_string());
+
+        let remote_clusters =
+            RemoteClusters::connect(&[eu_west_config], Duration::from_secs(1), ByteSize::mib(20))
+                .unwrap();
+        let mut search_client = remote_clusters.remote_clusters["eu-west"]
+            .search_client
+            .clone();
+        search_client
+            .root_search(SearchRequest::default())
+            .await
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_remote_cluster_interceptor() {
+        let mut interceptor = RemoteClusterInterceptor {
+            authorization_opt: Some(MetadataValue::from_static("Bearer eu-west-api-key")),
+        };
+        let request = interceptor.call(Request::new(())).unwrap();
+        assert_eq!(
+            request.metadata().get("authorization").unwrap(),
+            "Bearer eu-west-api-key"
+        );
+
+        let mut interceptor = RemoteClusterInterceptor {
+            authorization_opt: None,
+        };
+        let request = interceptor.call(Request::new(())).unwrap();
+        assert!(request.metadata().get("authorization").is_none());
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("*", "logs"));
+        assert!(glob_matches("*", "logs-*"));
+        assert!(glob_matches("logs", "logs"));
+        assert!(!glob_matches("logs", "logs-*"));
+        assert!(glob_matches("logs-*", "logs-"));
+        assert!(glob_matches("logs-*", "logs-eu-*"));
+        assert!(!glob_matches("logs-*", "logs*"));
+        assert!(!glob_matches("logs-*", "*"));
+        assert!(glob_matches("logs-*-prod", "logs-eu-prod"));
+        assert!(glob_matches("logs-*-prod", "logs-*x-prod"));
+        assert!(!glob_matches("logs-*-prod", "logs-*"));
+        assert!(glob_matches("*-prod*", "logs-prod-prod-eu"));
+        assert!(!glob_matches("*-prod", "logs-prod-eu"));
+    }
+
+    #[test]
+    fn test_route_index_id_patterns() {
+        let remote_clusters = remote_clusters_for_test(vec![
+            (
+                remote_cluster_config("eu-west", false),
+                MockSearchService::new(),
+            ),
+            (
+                remote_cluster_config("us-east", false),
+                MockSearchService::new(),
+            ),
+        ]);
+        let index_id_patterns = [
+            "logs".to_string(),
+            "eu-west:logs-eu".to_string(),
+            "-logs-debug".to_string(),
+            "eu-west:logs-eu-*".to_string(),
+        ];
+        let (local_index_id_patterns, remote_index_id_patterns) = remote_clusters
+            .route_index_id_patterns(&index_id_patterns)
+            .unwrap();
+        assert_eq!(local_index_id_patterns, ["logs", "-logs-debug"]);
+        assert_eq!(remote_index_id_patterns.len(), 1);
+        assert_eq!(
+            remote_index_id_patterns["eu-west"].1,
+            ["logs-eu", "logs-eu-*"]
+        );
+
+        let index_id_patterns = ["-logs-debug".to_string(), "us-east:logs-us".to_string()];
+        let (local_index_id_patterns, remote_index_id_patterns) = remote_clusters
+            .route_index_id_patterns(&index_id_patterns)
+            .unwrap();
+        assert!(local_index_id_patterns.is_empty());
+        assert_eq!(remote_index_id_patterns["us-east"].1, ["logs-us"]);
+
+        let error = remote_clusters
+            .route_index_id_patterns(&["ap-south:logs".to_string()])
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid argument: unknown remote cluster `ap-south`"
+        );
+        let error = remote_clusters
+            .route_index_id_patterns(&["us-east:*".to_string()])
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid argument: index ID pattern `*` is not allowed on remote cluster `us-east`"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_federated_root_search() {
+        let mut mock_search_service_eu = MockSearchService::new();
+        mock_search_service_eu
+            .expect_root_search()
+            .returning(|search_request| {
+                assert_eq!(search_request.index_id_patterns, ["logs-eu"]);
+                assert_eq!(search_request.start_offset, 0);
+                assert_eq!(search_request.max_hits, 3);
+                assert!(search_request.return_intermediate_aggregation);
+
+                Ok(SearchResponse {
+                    num_hits: 10,
+                    hits: vec![mock_hit("logs-eu", 5), mock_hit("logs-eu", 3)],
+                    num_successful_splits: 2,
+                    ..Default::default()
+                })
+            });
+        let mut mock_search_service_us = MockSearchService::new();
+        mock_search_service_us
+            .expect_root_search()
+            .returning(|search_request| {
+                assert_eq!(search_request.index_id_patterns, ["logs-us"]);
+
+                Ok(SearchResponse {
+                    num_hits: 20,
+                    hits: vec![mock_hit("logs-us", 4), mock_hit("logs-us", 1)],
+                    num_successful_splits: 3,
+                    errors: vec!["some split failed".to_string()],
+                    ..Default::default()
+                })
+            });
+        let mut mock_search_service_ap = MockSearchService::new();
+        mock_search_service_ap
+            .expect_root_search()
+            .returning(|_search_request| {
+                Err(SearchError::Unavailable("cluster is down".to_string()))
+            });
+        let remote_clusters = remote_clusters_for_test(vec![
+            (
+                remote_cluster_config("eu-west", false),
+                mock_search_service_eu,
+            ),
+            (
+                remote_cluster_config("us-east", false),
+                mock_search_service_us,
+            ),
+            (
+                remote_cluster_config("ap-south", true),
+                mock_search_service_ap,
+            ),
+        ]);
+        let search_request = SearchRequest {
+            index_id_patterns: vec![
+                "eu-west:logs-eu".to_string(),
+                "us-east:logs-us".to_string(),
+                "ap-south:logs-ap".to_string(),
+            ],
+            max_hits: 2,
+            start_offset: 1,
+            sort_fields: vec![SortField {
+                field_name: "timestamp".to_string(),
+                sort_order: SortOrder::Desc as i32,
+                sort_datetime_format: None,
+            }],
+            ..Default::default()
+        };
+        let metastore = MetastoreServiceClient::from_mock(MockMetastoreService::new());
+        let cluster_client = ClusterClient::new(SearchJobPlacer::new(SearcherPool::default()));
+
+        let search_response = federated_root_search(
+            &SearcherContext::for_test(),
+            search_request.clone(),
+            metastore.clone(),
+            &cluster_client,
+            &remote_clusters,
+        )
+        .await
+        .unwrap();
+        assert_eq!(search_response.num_hits, 30);
+        assert_eq!(search_response.num_successful_splits, 5);
+        assert_eq!(search_response.hits.len(), 2);
+        assert_eq!(search_response.hits[0].index_id, "us-east:logs-us");
+        assert_eq!(search_response.hits[0].json, r#"{"sort_value": 4}"#);
+        assert_eq!(search_response.hits[1].index_id, "eu-west:logs-eu");
+        assert_eq!(search_response.hits[1].json, r#"{"sort_value": 3}"#);
+        assert_eq!(search_response.errors.len(), 2);
+        assert_eq!(search_response.errors[0], "some split failed");
+        assert!(search_response.errors[1].starts_with("failed to search remote cluster `ap-south`"));
+
+        let remote_clusters =
+            remote_clusters_for_test(vec![(remote_cluster_config("ap-south", false), {
+                let mut mock_search_service_ap = MockSearchService::new();
+                mock_search_service_ap
+                    .expect_root_search()
+                    .returning(|_search_request| {
+                        Err(SearchError::Unavailable("cluster is down".to_string()))
+                    });
+                mock_search_service_ap
+            })]);
+        let search_request = SearchRequest {
+            index_id_patterns: vec!["ap-south:logs-ap".to_string()],
+            ..search_request
+        };
+        let error = federated_root_search(
+            &SearcherContext::for_test(),
+            search_request.clone(),
+            metastore.clone(),
+            &cluster_client,
+            &remote_clusters,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(error, SearchError::Unavailable(_)));
+
+        let search_request = SearchRequest {
+            scroll_ttl_secs: Some(60),
+            ..search_request
+        };
+        let error = federated_root_search(
+            &SearcherContext::for_test(),
+            search_request,
+            metastore,
+            &cluster_client,
+            &remote_clusters,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(error, SearchError::InvalidArgument(_)));
+    }
+}
@@ -12,25 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, HashMap};
+use std::io;
 use std::sync::Arc;
 
 use anyhow::{Context, Ok};
 use futures::{StreamExt, TryStreamExt};
 use itertools::Itertools;
-use quickwit_doc_mapper::DocMapper;
+use quickwit_doc_mapper::{
+    tantivy_value_to_json, DocMapper, FastFieldWarmupInfo, JsonObject, WarmupInfo,
+};
 use quickwit_proto::search::{
     FetchDocsResponse, PartialHit, SnippetRequest, SplitIdAndFooterOffsets,
 };
-use quickwit_storage::Storage;
-use tantivy::query::Query;
+use quickwit_query::query_ast::QueryAst;
+use quickwit_storage::{ByteRangeCache, Storage};
+use serde_json::Value as JsonValue;
+use tantivy::columnar::DynamicColumn;
+use tantivy::query::{EnableScoring, Query};
 use tantivy::schema::document::CompactDocValue;
-use tantivy::schema::{Document as DocumentTrait, Field, TantivyDocument, Value};
+use tantivy::schema::{Document as DocumentTrait, Field, OwnedValue, TantivyDocument, Value};
 use tantivy::snippet::SnippetGenerator;
-use tantivy::{ReloadPolicy, Score, Searcher, Term};
+use tantivy::{DocAddress, DocId, DocSet, ReloadPolicy, Score, Searcher, Term};
 use tracing::{error, Instrument};
 
-use crate::leaf::open_index_with_caches;
+use crate::leaf::{open_index_with_caches, warmup};
 use crate::service::SearcherContext;
 use crate::{convert_document_to_json_string, GlobalDocAddress};
 
@@ -38,6 +45,7 @@ const SNIPPET_MAX_NUM_CHARS: usize = 150;
 
 /// Given a list of global doc address, fetches all the documents and
 /// returns them as a hashmap.
+#[allow(clippy::too_many_arguments)]
 async fn fetch_docs_to_map(
     searcher_context: Arc<SearcherContext>,
     mut global_doc_addrs: Vec<GlobalDocAddress>,
@@ -45,6 +53,8 @@ async fn fetch_docs_to_map(
     splits: &[SplitIdAndFooterOffsets],
     doc_mapper: Arc<DocMapper>,
     snippet_request_opt: Option<&SnippetRequest>,
+    docvalue_fields: &[String],
+    named_queries_query_ast_opt: Option<&str>,
 ) -> anyhow::Result<HashMap<GlobalDocAddress, Document>> {
     let mut split_fetch_docs_futures = Vec::new();
 
@@ -72,6 +82,8 @@ async fn fetch_docs_to_map(
             split_and_offset,
             doc_mapper.clone(),
             snippet_request_opt,
+            docvalue_fields,
+            named_queries_query_ast_opt,
         ));
     }
 
@@ -105,6 +117,13 @@ async fn fetch_docs_to_map(
 /// This function takes a list of partial hits (possibly from different splits)
 /// and the storage associated to an index, fetches the document from
 /// the split document stores, and returns the full hits.
+///
+/// If `docvalue_fields` is not empty, the hits only hold the values of these fast fields, read
+/// from the columnar storage of the splits, and the document stores are not accessed.
+///
+/// If `named_queries_query_ast_opt` is set, the hits also hold the names of the named clauses of
+/// the query they match.
+#[allow(clippy::too_many_arguments)]
 pub async fn fetch_docs(
     searcher_context: Arc<SearcherContext>,
     partial_hits: Vec<PartialHit>,
@@ -112,6 +131,8 @@ pub async fn fetch_docs(
     splits: &[SplitIdAndFooterOffsets],
     doc_mapper: Arc<DocMapper>,
     snippet_request_opt: Option<&SnippetRequest>,
+    docvalue_fields: &[String],
+    named_queries_query_ast_opt: Option<&str>,
 ) -> anyhow::Result<FetchDocsResponse> {
     let global_doc_addrs: Vec<GlobalDocAddress> = partial_hits
         .iter()
@@ -125,6 +146,8 @@ pub async fn fetch_docs(
         splits,
         doc_mapper,
         snippet_request_opt,
+        docvalue_fields,
+        named_queries_query_ast_opt,
     )
     .await?;
 
@@ -138,6 +161,7 @@ pub async fn fetch_docs(
                     leaf_json: document.content_json,
                     partial_hit: Some(partial_hit.clone()),
                     leaf_snippet_json: document.snippet_json,
+                    matched_queries: document.matched_queries,
                 })
             } else {
                 None
@@ -150,14 +174,16 @@ pub async fn fetch_docs(
 // number of concurrent fetch allowed for a single split.
 const NUM_CONCURRENT_REQUESTS: usize = 30;
 
-/// A struct for holding a fetched document's content and snippet.
+/// A struct for holding a fetched document's content, snippet, and matched queries.
 #[derive(Debug)]
 struct Document {
     content_json: String,
     snippet_json: Option<String>,
+    matched_queries: Vec<String>,
 }
 
 /// Fetching docs from a specific split.
+#[allow(clippy::too_many_arguments)]
 async fn fetch_docs_in_split(
     searcher_context: Arc<SearcherContext>,
     mut global_doc_addrs: Vec<GlobalDocAddress>,
@@ -165,16 +191,30 @@ async fn fetch_docs_in_split(
     split: &SplitIdAndFooterOffsets,
     doc_mapper: Arc<DocMapper>,
     snippet_request_opt: Option<&SnippetRequest>,
+    docvalue_fields: &[String],
+    named_queries_query_ast_opt: Option<&str>,
 ) -> anyhow::Result<Vec<(GlobalDocAddress, Document)>> {
     global_doc_addrs.sort_by_key(|doc| doc.doc_addr);
     // Opens the index without the ephemeral unbounded cache, this cache is indeed not useful
-    // when fetching docs as we will fetch them only once.
+    // when fetching docs as we will fetch them only once. Fast field columns and the data read by
+    // the named queries, however, are read synchronously, so they must be warmed up in the
+    // ephemeral cache first.
+    let ephemeral_unbounded_cache_opt =
+        if docvalue_fields.is_empty() && named_queries_query_ast_opt.is_none() {
+            None
+        } else {
+            Some(ByteRangeCache::with_infinite_capacity(
+                &quickwit_storage::STORAGE_METRICS.shortlived_cache,
+            ))
+        };
+    // The splits were touched along with their index by the leaf search.
     let (mut index, _) = open_index_with_caches(
         &searcher_context,
         index_storage,
+        None,
         split,
         Some(doc_mapper.tokenizer_manager()),
-        None,
+        ephemeral_unbounded_cache_opt,
     )
     .await
     .context("open-index-for-split")?;
@@ -191,6 +231,25 @@ async fn fetch_docs_in_split(
         .reload_policy(ReloadPolicy::Manual)
         .try_into()?;
     let searcher = Arc::new(index_reader.searcher());
+
+    let mut matched_queries_per_doc =
+        if let Some(named_queries_query_ast) = named_queries_query_ast_opt {
+            match_named_queries(
+                &searcher,
+                &doc_mapper,
+                named_queries_query_ast,
+                &global_doc_addrs,
+            )
+            .await?
+        } else {
+            HashMap::new()
+        };
+    if !docvalue_fields.is_empty() {
+        let mut docs =
+            fetch_docvalues_in_split(&searcher, global_doc_addrs, docvalue_fields).await?;
+        set_matched_queries(&mut docs, &mut matched_queries_per_doc);
+        return Ok(docs);
+    }
     let fields_snippet_generator_opt = if let Some(snippet_request) = snippet_request_opt {
         Some(create_fields_snippet_generator(&searcher, doc_mapper.clone(), snippet_request).await?)
     } else {
@@ -215,6 +274,7 @@ async fn fetch_docs_in_split(
                     Document {
                         content_json,
                         snippet_json: None,
+                        matched_queries: Vec::new(),
                     },
                 ));
             }
@@ -226,6 +286,7 @@ async fn fetch_docs_in_split(
                     Document {
                         content_json,
                         snippet_json: None,
+                        matched_queries: Vec::new(),
                     },
                 ));
             }
@@ -245,16 +306,220 @@ async fn fetch_docs_in_split(
                 Document {
                     content_json,
                     snippet_json: Some(snippet_json),
+                    matched_queries: Vec::new(),
                 },
             ))
         }
         .in_current_span()
     });
 
-    futures::stream::iter(doc_futures)
+    let mut docs = futures::stream::iter(doc_futures)
         .buffer_unordered(NUM_CONCURRENT_REQUESTS)
         .try_collect::<Vec<_>>()
+        .await?;
+    set_matched_queries(&mut docs, &mut matched_queries_per_doc);
+    Ok(docs)
+}
+
+/// Returns the names of the named clauses of the query matching each document. The documents must
+/// be sorted by address.
+async fn match_named_queries(
+    searcher: &Searcher,
+    doc_mapper: &DocMapper,
+    named_queries_query_ast: &str,
+    global_doc_addrs: &[GlobalDocAddress],
+) -> anyhow::Result<HashMap<DocAddress, Vec<String>>> {
+    let query_ast: QueryAst =
+        serde_json::from_str(named_queries_query_ast).context("failed to deserialize QueryAst")?;
+    let schema = searcher.schema();
+    let mut named_queries: Vec<(&str, Box<dyn Query>)> = Vec::new();
+    let mut warmup_info = WarmupInfo::default();
+
+    for (name, named_query_ast) in query_ast.named_queries() {
+        let (query, named_query_warmup_info) =
+            doc_mapper.query(schema.clone(), named_query_ast, false)?;
+        warmup_info.merge(named_query_warmup_info);
+        named_queries.push((name, query));
+    }
+    warmup(searcher, &warmup_info)
         .await
+        .context("failed to warm up named queries")?;
+
+    let mut matched_queries_per_doc: HashMap<DocAddress, Vec<String>> = HashMap::new();
+
+    for (name, query) in named_queries {
+        let weight = query.weight(EnableScoring::disabled_from_searcher(searcher))?;
+
+        for (segment_ord, doc_addrs) in &global_doc_addrs
+            .iter()
+            .map(|global_doc_addr| global_doc_addr.doc_addr)
+            .chunk_by(|doc_addr| doc_addr.segment_ord)
+        {
+            let segment_reader = searcher.segment_reader(segment_ord);
+            let mut scorer = weight.scorer(segment_reader, 1.0)?;
+
+            for doc_addr in doc_addrs {
+                let doc_id = if scorer.doc() < doc_addr.doc_id {
+                    scorer.seek(doc_addr.doc_id)
+                } else {
+                    scorer.doc()
+                };
+                if doc_id != doc_addr.doc_id {
+                    continue;
+                }
+                let matched_queries = matched_queries_per_doc.entry(doc_addr).or_default();
+                // Several clauses can share the same name (e.g. a named `multi_match` query).
+                if !matched_queries
+                    .iter()
+                    .any(|matched_query| matched_query == name)
+                {
+                    matched_queries.push(name.to_string());
+                }
+            }
+        }
+    }
+    Ok(matched_queries_per_doc)
+}
+
+fn set_matched_queries(
+    docs: &mut [(GlobalDocAddress, Document)],
+    matched_queries_per_doc: &mut HashMap<DocAddress, Vec<String>>,
+) {
+    for (global_doc_addr, document) in docs {
+        if let Some(matched_queries) = matched_queries_per_doc.remove(&global_doc_addr.doc_addr) {
+            document.matched_queries = matched_queries;
+        }
+    }
+}
+
+/// Fetches the values of the docvalue fields of the documents from the columnar storage of a split.
+///
+/// Values are returned as arrays, keyed by field name. Fields without any value for a document are
+/// omitted.
+async fn fetch_docvalues_in_split(
+    searcher: &Searcher,
+    global_doc_addrs: Vec<GlobalDocAddress>,
+    docvalue_fields: &[String],
+) -> anyhow::Result<Vec<(GlobalDocAddress, Document)>> {
+    let fast_fields = docvalue_fields
+        .iter()
+        .map(|field_name| FastFieldWarmupInfo {
+            name: field_name.clone(),
+            with_subfields: false,
+        })
+        .collect();
+    let warmup_info = WarmupInfo {
+        fast_fields,
+        ..Default::default()
+    };
+    warmup(searcher, &warmup_info)
+        .await
+        .context("failed to warm up docvalue fields")?;
+
+    let mut columns_per_segment: HashMap<u32, Vec<(&str, Vec<DynamicColumn>)>> = HashMap::new();
+    let mut docs = Vec::with_capacity(global_doc_addrs.len());
+
+    for global_doc_addr in global_doc_addrs {
+        let DocAddress {
+            segment_ord,
+            doc_id,
+        } = global_doc_addr.doc_addr;
+
+        let columns = match columns_per_segment.entry(segment_ord) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let fast_field_readers = searcher.segment_reader(segment_ord).fast_fields();
+                let mut columns = Vec::with_capacity(docvalue_fields.len());
+
+                for field_name in docvalue_fields {
+                    let field_columns = fast_field_readers
+                        .dynamic_column_handles(field_name)?
+                        .into_iter()
+                        .map(|column_handle| column_handle.open())
+                        .collect::<io::Result<Vec<DynamicColumn>>>()?;
+                    columns.push((field_name.as_str(), field_columns));
+                }
+                entry.insert(columns)
+            }
+        };
+        let mut json_obj = JsonObject::with_capacity(columns.len());
+
+        for (field_name, field_columns) in columns.iter() {
+            let mut values = Vec::new();
+
+            for column in field_columns {
+                append_column_values(column, doc_id, &mut values)?;
+            }
+            if !values.is_empty() {
+                json_obj.insert(field_name.to_string(), JsonValue::Array(values));
+            }
+        }
+        let document = Document {
+            content_json: serde_json::to_string(&json_obj)?,
+            snippet_json: None,
+            matched_queries: Vec::new(),
+        };
+        docs.push((global_doc_addr, document));
+    }
+    Ok(docs)
+}
+
+fn append_column_values(
+    column: &DynamicColumn,
+    doc_id: DocId,
+    values: &mut Vec<JsonValue>,
+) -> io::Result<()> {
+    match column {
+        DynamicColumn::Bool(column) => values.extend(
+            column
+                .values_for_doc(doc_id)
+                .map(|value| tantivy_value_to_json(OwnedValue::Bool(value))),
+        ),
+        DynamicColumn::I64(column) => values.extend(
+            column
+                .values_for_doc(doc_id)
+                .map(|value| tantivy_value_to_json(OwnedValue::I64(value))),
+        ),
+        DynamicColumn::U64(column) => values.extend(
+            column
+                .values_for_doc(doc_id)
+                .map(|value| tantivy_value_to_json(OwnedValue::U64(value))),
+        ),
+        DynamicColumn::F64(column) => values.extend(
+            column
+                .values_for_doc(doc_id)
+                .map(|value| tantivy_value_to_json(OwnedValue::F64(value))),
+        ),
+        DynamicColumn::IpAddr(column) => values.extend(
+            column
+                .values_for_doc(doc_id)
+                .map(|value| tantivy_value_to_json(OwnedValue::IpAddr(value))),
+        ),
+        DynamicColumn::DateTime(column) => values.extend(
+            column
+                .values_for_doc(doc_id)
+                .map(|value| tantivy_value_to_json(OwnedValue::Date(value))),
+        ),
+        DynamicColumn::Bytes(column) => {
+            let mut buffer = Vec::new();
+
+            for term_ord in column.term_ords(doc_id) {
+                buffer.clear();
+                column.ord_to_bytes(term_ord, &mut buffer)?;
+                values.push(tantivy_value_to_json(OwnedValue::Bytes(buffer.clone())));
+            }
+        }
+        DynamicColumn::Str(column) => {
+            let mut buffer = String::new();
+
+            for term_ord in column.term_ords(doc_id) {
+                buffer.clear();
+                column.ord_to_str(term_ord, &mut buffer)?;
+                values.push(tantivy_value_to_json(OwnedValue::Str(buffer.clone())));
+            }
+        }
+    }
+    Ok(())
 }
 
 // A struct to hold the snippet generators associated to
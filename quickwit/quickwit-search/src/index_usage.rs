@@ -0,0 +1,240 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::mem;
+use std::sync::{Arc, Mutex, Once, OnceLock, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use quickwit_proto::metastore::{
+    index_usage_period_start, AccumulateIndexUsageRequest, AccumulateIndexUsageSubrequest,
+    IndexUsage, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::types::IndexUid;
+use tracing::warn;
+
+/// Interval at which the accumulated usage is flushed to the metastore.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Usage accumulated since the last flush, keyed by index and usage period.
+type PendingUsage = HashMap<(IndexUid, i64), IndexUsage>;
+
+/// Accumulates the usage (queries served, bytes read) of the indexes searched by this node
+/// and adds it to the index usage counters persisted in the metastore.
+///
+/// The usage is flushed by a background task every [`FLUSH_INTERVAL`], so that bursts of
+/// queries translate into a single metastore request and quiet periods still get flushed.
+#[derive(Default)]
+pub struct IndexUsageAccumulator {
+    metastore_opt: OnceLock<MetastoreServiceClient>,
+    pending_usage: Arc<Mutex<PendingUsage>>,
+    flush_loop_started: Once,
+}
+
+impl IndexUsageAccumulator {
+    /// Sets the metastore the usage is flushed to. Usage recorded before the metastore is set is
+    /// discarded.
+    pub fn set_metastore(&self, metastore: MetastoreServiceClient) {
+        let _ = self.metastore_opt.set(metastore);
+    }
+
+    /// Records usage deltas in the current usage period. The first call spawns the task flushing
+    /// the accumulated usage to the metastore.
+    pub fn record(&self, usage_deltas: impl IntoIterator<Item = (IndexUid, IndexUsage)>) {
+        let Some(metastore) = self.metastore_opt.get() else {
+            return;
+        };
+        let now_timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+        let period_start_timestamp = index_usage_period_start(now_timestamp);
+        accumulate(
+            &mut self
+                .pending_usage
+                .lock()
+                .expect("lock should not be poisoned"),
+            usage_deltas
+                .into_iter()
+                .map(|(index_uid, usage_delta)| ((index_uid, period_start_timestamp), usage_delta)),
+        );
+        self.flush_loop_started.call_once(|| {
+            let pending_usage_weak = Arc::downgrade(&self.pending_usage);
+            tokio::spawn(flush_loop(pending_usage_weak, metastore.clone()));
+        });
+    }
+}
+
+fn accumulate(
+    pending_usage: &mut PendingUsage,
+    usage_deltas: impl IntoIterator<Item = ((IndexUid, i64), IndexUsage)>,
+) {
+    for (key, usage_delta) in usage_deltas {
+        pending_usage
+            .entry(key)
+            .or_default()
+            .accumulate(&usage_delta);
+    }
+}
+
+fn build_accumulate_request(pending_usage: &PendingUsage) -> AccumulateIndexUsageRequest {
+    let subrequests = pending_usage
+        .iter()
+        .map(
+            |((index_uid, period_start_timestamp), usage_delta)| AccumulateIndexUsageSubrequest {
+                index_uid: Some(index_uid.clone()),
+                usage_delta: Some(usage_delta.clone()),
+                period_start_timestamp: *period_start_timestamp,
+            },
+        )
+        .collect();
+    AccumulateIndexUsageRequest { subrequests }
+}
+
+/// Flushes the pending usage periodically until the accumulator is dropped. Usage that fails to
+/// be flushed is put back and retried on the next tick.
+async fn flush_loop(
+    pending_usage_weak: Weak<Mutex<PendingUsage>>,
+    metastore: MetastoreServiceClient,
+) {
+    let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        let Some(pending_usage_arc) = pending_usage_weak.upgrade() else {
+            return;
+        };
+        let pending_usage = mem::take(
+            &mut *pending_usage_arc
+                .lock()
+                .expect("lock should not be poisoned"),
+        );
+        drop(pending_usage_arc);
+
+        if pending_usage.is_empty() {
+            continue;
+        }
+        let accumulate_request = build_accumulate_request(&pending_usage);
+
+        if let Err(error) = metastore.accumulate_index_usage(accumulate_request).await {
+            warn!(%error, "failed to flush index usage to metastore");
+
+            let Some(pending_usage_arc) = pending_usage_weak.upgrade() else {
+                return;
+            };
+            accumulate(
+                &mut pending_usage_arc
+                    .lock()
+                    .expect("lock should not be poisoned"),
+                pending_usage,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use quickwit_proto::metastore::{EmptyResponse, MetastoreError, MockMetastoreService};
+
+    use super::*;
+
+    #[test]
+    fn test_index_usage_accumulate() {
+        let index_uid_0 = IndexUid::for_test("test-index-0", 0);
+        let index_uid_1 = IndexUid::for_test("test-index-1", 0);
+
+        let usage_delta = IndexUsage {
+            num_queries: 1,
+            num_bytes_scanned: 100,
+            ..Default::default()
+        };
+        let mut pending_usage = PendingUsage::new();
+        accumulate(
+            &mut pending_usage,
+            [
+                ((index_uid_0.clone(), 0), usage_delta.clone()),
+                ((index_uid_1.clone(), 0), usage_delta.clone()),
+                ((index_uid_0.clone(), 0), usage_delta.clone()),
+                ((index_uid_0.clone(), 86_400), usage_delta.clone()),
+            ],
+        );
+        let mut subrequests = build_accumulate_request(&pending_usage).subrequests;
+        subrequests.sort_by(|left, right| {
+            (left.index_uid(), left.period_start_timestamp)
+                .cmp(&(right.index_uid(), right.period_start_timestamp))
+        });
+        assert_eq!(subrequests.len(), 3);
+
+        assert_eq!(subrequests[0].index_uid(), &index_uid_0);
+        assert_eq!(subrequests[0].period_start_timestamp, 0);
+        let usage = subrequests[0].usage_delta.as_ref().unwrap();
+        assert_eq!(usage.num_queries, 2);
+        assert_eq!(usage.num_bytes_scanned, 200);
+
+        assert_eq!(subrequests[1].index_uid(), &index_uid_0);
+        assert_eq!(subrequests[1].period_start_timestamp, 86_400);
+        let usage = subrequests[1].usage_delta.as_ref().unwrap();
+        assert_eq!(usage.num_queries, 1);
+
+        assert_eq!(subrequests[2].index_uid(), &index_uid_1);
+        let usage = subrequests[2].usage_delta.as_ref().unwrap();
+        assert_eq!(usage.num_queries, 1);
+        assert_eq!(usage.num_bytes_scanned, 100);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_index_usage_accumulator_flushes_periodically() {
+        let num_flushes = Arc::new(AtomicUsize::new(0));
+        let num_flushes_clone = num_flushes.clone();
+
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_accumulate_index_usage()
+            .returning(move |request| {
+                assert_eq!(request.subrequests.len(), 1);
+                let usage = request.subrequests[0].usage_delta.as_ref().unwrap();
+
+                // The first flush fails, so its usage is flushed again along with the next one.
+                if num_flushes_clone.fetch_add(1, Ordering::Relaxed) == 0 {
+                    assert_eq!(usage.num_queries, 1);
+                    return Err(MetastoreError::Unavailable("test".to_string()));
+                }
+                assert_eq!(usage.num_queries, 2);
+                Ok(EmptyResponse {})
+            });
+        let index_usage_accumulator = IndexUsageAccumulator::default();
+        index_usage_accumulator.set_metastore(MetastoreServiceClient::from_mock(mock_metastore));
+
+        let index_uid = IndexUid::for_test("test-index", 0);
+        let usage_delta = IndexUsage {
+            num_queries: 1,
+            ..Default::default()
+        };
+        index_usage_accumulator.record([(index_uid.clone(), usage_delta.clone())]);
+        tokio::time::sleep(FLUSH_INTERVAL + Duration::from_millis(1)).await;
+        assert_eq!(num_flushes.load(Ordering::Relaxed), 1);
+
+        index_usage_accumulator.record([(index_uid, usage_delta)]);
+        tokio::time::sleep(FLUSH_INTERVAL).await;
+        assert_eq!(num_flushes.load(Ordering::Relaxed), 2);
+
+        // Nothing is flushed when no usage was recorded.
+        tokio::time::sleep(FLUSH_INTERVAL * 2).await;
+        assert_eq!(num_flushes.load(Ordering::Relaxed), 2);
+    }
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::Bound;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -25,10 +25,13 @@ use futures::future::try_join_all;
 use quickwit_common::pretty::PrettySample;
 use quickwit_directories::{CachingDirectory, HotDirectory, StorageDirectory};
 use quickwit_doc_mapper::{Automaton, DocMapper, FastFieldWarmupInfo, TermRange, WarmupInfo};
+use quickwit_proto::compression::PayloadCodec;
+use quickwit_proto::metastore::IndexUsage;
 use quickwit_proto::search::{
     CountHits, LeafSearchRequest, LeafSearchResponse, PartialHit, ResourceStats, SearchRequest,
-    SortOrder, SortValue, SplitIdAndFooterOffsets, SplitSearchError,
+    SortOrder, SortValue, SplitIdAndFooterOffsets, SplitSearchError, SplitSearchProfile,
 };
+use quickwit_proto::types::{IndexId, IndexUid};
 use quickwit_query::query_ast::{BoolQuery, QueryAst, QueryAstTransformer, RangeQuery, TermQuery};
 use quickwit_query::tokenizers::TokenizerManager;
 use quickwit_storage::{
@@ -42,9 +45,13 @@ use tantivy::fastfield::FastFieldReaders;
 use tantivy::schema::Field;
 use tantivy::{DateTime, Index, ReloadPolicy, Searcher, TantivyError, Term};
 use tokio::task::JoinError;
+use tokio_util::task::AbortOnDropHandle;
 use tracing::*;
 
-use crate::collector::{make_collector_for_split, make_merge_collector, IncrementalCollector};
+use crate::collector::{
+    make_collector_for_split, make_merge_collector, search_with_segment_pruning,
+    IncrementalCollector,
+};
 use crate::metrics::SEARCH_METRICS;
 use crate::root::is_metadata_count_request_with_ast;
 use crate::search_permit_provider::{compute_initial_memory_allocation, SearchPermit};
@@ -56,6 +63,7 @@ async fn get_split_footer_from_cache_or_fetch(
     index_storage: Arc<dyn Storage>,
     split_and_footer_offsets: &SplitIdAndFooterOffsets,
     footer_cache: &MemorySizedCache<String>,
+    split_cache_opt: Option<&Arc<SplitCache>>,
 ) -> anyhow::Result<OwnedBytes> {
     {
         let possible_val = footer_cache.get(&split_and_footer_offsets.split_id);
@@ -63,13 +71,26 @@ async fn get_split_footer_from_cache_or_fetch(
             return Ok(footer_data);
         }
     }
+    let footer_range = split_and_footer_offsets.split_footer_start as usize
+        ..split_and_footer_offsets.split_footer_end as usize;
+
+    // The footer tier of the split cache holds the footers of many more splits than the
+    // in-memory footer cache.
+    if let Some(split_cache) = split_cache_opt {
+        if let Some(footer_data) = split_cache
+            .get_split_footer(&split_and_footer_offsets.split_id, footer_range.clone())
+            .await
+        {
+            footer_cache.put(
+                split_and_footer_offsets.split_id.to_owned(),
+                footer_data.clone(),
+            );
+            return Ok(footer_data);
+        }
+    }
     let split_file = PathBuf::from(format!("{}.split", split_and_footer_offsets.split_id));
     let footer_data_opt = index_storage
-        .get_slice(
-            &split_file,
-            split_and_footer_offsets.split_footer_start as usize
-                ..split_and_footer_offsets.split_footer_end as usize,
-        )
+        .get_slice(&split_file, footer_range)
         .await
         .with_context(|| {
             format!(
@@ -83,16 +104,22 @@ async fn get_split_footer_from_cache_or_fetch(
         split_and_footer_offsets.split_id.to_owned(),
         footer_data_opt.clone(),
     );
-
+    if let Some(split_cache) = split_cache_opt {
+        split_cache
+            .spawn_put_split_footer(&split_and_footer_offsets.split_id, footer_data_opt.clone());
+    }
     Ok(footer_data_opt)
 }
 
 /// Returns hotcache_bytes and the split directory (`BundleStorage`) with cache layer:
 /// - A split footer cache given by `SearcherContext.split_footer_cache`.
+///
+/// The index ID, if known, is used to enforce the split cache index quotas.
 #[instrument(skip_all, fields(split_footer_start=split_and_footer_offsets.split_footer_start, split_footer_end=split_and_footer_offsets.split_footer_end))]
 pub(crate) async fn open_split_bundle(
     searcher_context: &SearcherContext,
     index_storage: Arc<dyn Storage>,
+    index_id_opt: Option<&str>,
     split_and_footer_offsets: &SplitIdAndFooterOffsets,
 ) -> anyhow::Result<(FileSlice, BundleStorage)> {
     let split_file = PathBuf::from(format!("{}.split", split_and_footer_offsets.split_id));
@@ -100,6 +127,7 @@ pub(crate) async fn open_split_bundle(
         index_storage.clone(),
         split_and_footer_offsets,
         &searcher_context.split_footer_cache,
+        searcher_context.split_cache_opt.as_ref(),
     )
     .await?;
 
@@ -107,7 +135,11 @@ pub(crate) async fn open_split_bundle(
     // This is before the bundle storage: at this point, this storage is reading `.split` files.
     let index_storage_with_split_cache =
         if let Some(split_cache) = searcher_context.split_cache_opt.as_ref() {
-            SplitCache::wrap_storage(split_cache.clone(), index_storage.clone())
+            SplitCache::wrap_storage(
+                split_cache.clone(),
+                index_storage.clone(),
+                index_id_opt.map(str::to_string),
+            )
         } else {
             index_storage.clone()
         };
@@ -148,6 +180,7 @@ fn configure_storage_retries(
 pub(crate) async fn open_index_with_caches(
     searcher_context: &SearcherContext,
     index_storage: Arc<dyn Storage>,
+    index_id_opt: Option<&str>,
     split_and_footer_offsets: &SplitIdAndFooterOffsets,
     tokenizer_manager: Option<&TokenizerManager>,
     ephemeral_unbounded_cache: Option<ByteRangeCache>,
@@ -158,6 +191,7 @@ pub(crate) async fn open_index_with_caches(
     let (hotcache_bytes, bundle_storage) = open_split_bundle(
         searcher_context,
         index_storage_with_retry_on_timeout,
+        index_id_opt,
         split_and_footer_offsets,
     )
     .await?;
@@ -418,6 +452,8 @@ fn get_leaf_resp_from_count(count: u64) -> LeafSearchResponse {
         num_successful_splits: 1,
         intermediate_aggregation_result: None,
         resource_stats: None,
+        split_profiles: Vec::new(),
+        intermediate_aggregation_result_codec: PayloadCodec::None as i32,
     }
 }
 
@@ -432,15 +468,32 @@ fn compute_index_size(hot_directory: &HotDirectory) -> ByteSize {
     ByteSize(size_bytes)
 }
 
+/// Sums the bytes read during the warmup of a split by component, i.e. by file extension (`term`,
+/// `idx`, `pos`, `fast`, ...).
+fn warmup_num_bytes_by_component(byte_range_cache: &ByteRangeCache) -> BTreeMap<String, u64> {
+    let mut num_bytes_by_component: BTreeMap<String, u64> = BTreeMap::new();
+
+    for (path, num_bytes) in byte_range_cache.get_num_bytes_by_path() {
+        let component = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_string())
+            .unwrap_or_else(|| "other".to_string());
+        *num_bytes_by_component.entry(component).or_default() += num_bytes;
+    }
+    num_bytes_by_component
+}
+
 /// Apply a leaf search on a single split.
 #[allow(clippy::too_many_arguments)]
 async fn leaf_search_single_split(
     searcher_context: &SearcherContext,
     mut search_request: SearchRequest,
     storage: Arc<dyn Storage>,
+    index_id_opt: Option<&str>,
     split: SplitIdAndFooterOffsets,
     doc_mapper: Arc<DocMapper>,
     split_filter: Arc<RwLock<CanSplitDoBetter>>,
+    incremental_merge_collector: Arc<Mutex<IncrementalCollector>>,
     aggregations_limits: AggregationLimitsGuard,
     search_permit: &mut SearchPermit,
 ) -> crate::Result<LeafSearchResponse> {
@@ -449,10 +502,19 @@ async fn leaf_search_single_split(
         &split,
         doc_mapper.timestamp_field_name(),
     );
-    if let Some(cached_answer) = searcher_context
+    if let Some(mut cached_answer) = searcher_context
         .leaf_search_cache
         .get(split.clone(), search_request.clone())
     {
+        if search_request.profile {
+            cached_answer.split_profiles = vec![SplitSearchProfile {
+                split_id: split.split_id.clone(),
+                partial_request_cache_hit: true,
+                ..Default::default()
+            }];
+        }
+        // Serving a cached answer does not read anything from the split.
+        cached_answer.resource_stats = None;
         return Ok(cached_answer);
     }
 
@@ -468,16 +530,25 @@ async fn leaf_search_single_split(
     }
 
     let split_id = split.split_id.to_string();
+    // Looking up the footer cache counts as an extra cache hit, so we only do it when profiling.
+    let split_footer_cache_hit = search_request.profile
+        && searcher_context
+            .split_footer_cache
+            .get(&split.split_id)
+            .is_some();
     let byte_range_cache =
         ByteRangeCache::with_infinite_capacity(&quickwit_storage::STORAGE_METRICS.shortlived_cache);
+    let open_start = Instant::now();
     let (index, hot_directory) = open_index_with_caches(
         searcher_context,
         storage,
+        index_id_opt,
         &split,
         Some(doc_mapper.tokenizer_manager()),
         Some(byte_range_cache.clone()),
     )
     .await?;
+    let open_duration = open_start.elapsed();
 
     let index_size = compute_index_size(&hot_directory);
     if index_size < search_permit.memory_allocation() {
@@ -522,7 +593,7 @@ async fn leaf_search_single_split(
 
     let span = info_span!("tantivy_search");
 
-    let (search_request, leaf_search_response) = {
+    let (search_request, mut leaf_search_response, is_cacheable) = {
         let split = split.clone();
 
         crate::search_thread_pool()
@@ -534,12 +605,31 @@ async fn leaf_search_single_split(
                 // request based on the results of the preceding searches
                 check_optimize_search_request(&mut search_request, &split, &split_filter);
                 collector.update_search_param(&search_request);
+                let mut num_pruned_segments = 0;
                 let mut leaf_search_response: LeafSearchResponse =
                     if is_metadata_count_request_with_ast(&query_ast, &search_request) {
                         get_leaf_resp_from_count(searcher.num_docs())
                     } else if collector.is_count_only() {
                         let count = query.count(&searcher)? as u64;
                         get_leaf_resp_from_count(count)
+                    } else if search_request.count_hits() == CountHits::Underestimate
+                        && collector.can_prune_segments()
+                    {
+                        // The worst of the top hits collected on the other splits lets us skip
+                        // the segments that cannot make it to the top hits.
+                        let worst_hit_sort_value_opt = incremental_merge_collector
+                            .lock()
+                            .unwrap()
+                            .peek_worst_hit()
+                            .and_then(|worst_hit| worst_hit.sort_value());
+                        let (leaf_search_response, num_pruned) = search_with_segment_pruning(
+                            &searcher,
+                            query.as_ref(),
+                            &collector,
+                            worst_hit_sort_value_opt,
+                        )?;
+                        num_pruned_segments = num_pruned;
+                        leaf_search_response
                     } else {
                         searcher.search(&query, &collector)?
                     };
@@ -551,7 +641,10 @@ async fn leaf_search_single_split(
                     cpu_thread_pool_wait_microsecs: cpu_thread_pool_wait_microsecs.as_micros()
                         as u64,
                 });
-                Result::<_, TantivyError>::Ok((search_request, leaf_search_response))
+                // The cache does not tell apart exact and underestimated hit counts, and the
+                // pruned segments depend on the other splits: we don't cache partial results.
+                let is_cacheable = num_pruned_segments == 0;
+                Result::<_, TantivyError>::Ok((search_request, leaf_search_response, is_cacheable))
             })
             .await
             .map_err(|_| {
@@ -559,9 +652,29 @@ async fn leaf_search_single_split(
             })??
     };
 
-    searcher_context
-        .leaf_search_cache
-        .put(split, search_request, leaf_search_response.clone());
+    let profile = search_request.profile;
+    if is_cacheable {
+        searcher_context
+            .leaf_search_cache
+            .put(split, search_request, leaf_search_response.clone());
+    }
+
+    if profile {
+        let resource_stats = leaf_search_response
+            .resource_stats
+            .clone()
+            .unwrap_or_default();
+        leaf_search_response.split_profiles = vec![SplitSearchProfile {
+            split_id,
+            searcher_addr: String::new(),
+            partial_request_cache_hit: false,
+            split_footer_cache_hit,
+            open_microsecs: open_duration.as_micros() as u64,
+            warmup_microsecs: resource_stats.warmup_microsecs,
+            search_microsecs: resource_stats.cpu_microsecs,
+            warmup_num_bytes_by_component: warmup_num_bytes_by_component(&byte_range_cache),
+        }];
+    }
     Ok(leaf_search_response)
 }
 
@@ -1228,18 +1341,22 @@ pub async fn multi_leaf_search(
             })?
             .clone();
 
-        let leaf_request_future = tokio::spawn(
+        // The task is aborted if the leaf search request is dropped, for instance when the root
+        // search is cancelled.
+        let leaf_request_future = AbortOnDropHandle::new(tokio::spawn(
             resolve_storage_and_leaf_search(
                 searcher_context.clone(),
                 search_request.clone(),
                 index_uri,
+                leaf_search_request_ref.storage_credentials,
                 storage_resolver.clone(),
+                IndexUid::from_str(&leaf_search_request_ref.index_uid).ok(),
                 leaf_search_request_ref.split_offsets,
                 doc_mapper,
                 aggregation_limits.clone(),
             )
             .in_current_span(),
-        );
+        ));
         leaf_request_tasks.push(leaf_request_future);
     }
 
@@ -1272,27 +1389,65 @@ pub async fn multi_leaf_search(
         .context("failed to merge split search responses")?
 }
 
-/// Resolves storage and calls leaf_search
+/// Resolves storage, calls leaf_search and records the bytes read from the splits of the index.
 #[allow(clippy::too_many_arguments)]
 async fn resolve_storage_and_leaf_search(
     searcher_context: Arc<SearcherContext>,
     search_request: Arc<SearchRequest>,
     index_uri: quickwit_common::uri::Uri,
+    storage_credentials_opt: Option<String>,
     storage_resolver: StorageResolver,
+    index_uid_opt: Option<IndexUid>,
     splits: Vec<SplitIdAndFooterOffsets>,
     doc_mapper: Arc<DocMapper>,
     aggregations_limits: AggregationLimitsGuard,
 ) -> crate::Result<LeafSearchResponse> {
-    let storage = storage_resolver.resolve(&index_uri).await?;
-    leaf_search(
+    let storage = storage_resolver
+        .resolve_with_credentials(&index_uri, storage_credentials_opt.as_deref())
+        .await?;
+    let index_id_opt = index_uid_opt
+        .as_ref()
+        .map(|index_uid| index_uid.index_id.clone());
+    let leaf_search_response = leaf_search(
         searcher_context.clone(),
         search_request.clone(),
         storage.clone(),
+        index_id_opt,
         splits,
         doc_mapper,
         aggregations_limits,
     )
-    .await
+    .await?;
+
+    let num_bytes_read = leaf_search_response
+        .resource_stats
+        .as_ref()
+        .map(|resource_stats| resource_stats.short_lived_cache_num_bytes)
+        .unwrap_or_default();
+    record_num_bytes_read(&searcher_context, index_uid_opt, num_bytes_read);
+    Ok(leaf_search_response)
+}
+
+/// Records the bytes read by a leaf request in the usage of the index. Requests sent by nodes
+/// that do not fill the index UID are not accounted for.
+pub(crate) fn record_num_bytes_read(
+    searcher_context: &SearcherContext,
+    index_uid_opt: Option<IndexUid>,
+    num_bytes_read: u64,
+) {
+    let Some(index_uid) = index_uid_opt else {
+        return;
+    };
+    if num_bytes_read == 0 {
+        return;
+    }
+    let usage_delta = IndexUsage {
+        num_bytes_scanned: num_bytes_read,
+        ..Default::default()
+    };
+    searcher_context
+        .index_usage_accumulator
+        .record([(index_uid, usage_delta)]);
 }
 
 /// Optimizes the search_request based on CanSplitDoBetter
@@ -1329,6 +1484,7 @@ pub async fn leaf_search(
     searcher_context: Arc<SearcherContext>,
     request: Arc<SearchRequest>,
     index_storage: Arc<dyn Storage>,
+    index_id_opt: Option<IndexId>,
     splits: Vec<SplitIdAndFooterOffsets>,
     doc_mapper: Arc<DocMapper>,
     aggregations_limits: AggregationLimitsGuard,
@@ -1352,7 +1508,9 @@ pub async fn leaf_search(
 
     let split_filter = Arc::new(RwLock::new(split_filter));
 
-    let mut leaf_search_single_split_join_handles: Vec<(String, tokio::task::JoinHandle<()>)> =
+    // The single split search tasks are aborted, releasing their search permits, if the leaf
+    // search is dropped.
+    let mut leaf_search_single_split_join_handles: Vec<(String, AbortOnDropHandle<()>)> =
         Vec::with_capacity(split_with_req.len());
 
     let merge_collector = make_merge_collector(&request, &aggregations_limits)?;
@@ -1388,11 +1546,12 @@ pub async fn leaf_search(
 
         leaf_search_single_split_join_handles.push((
             split.split_id.clone(),
-            tokio::spawn(
+            AbortOnDropHandle::new(tokio::spawn(
                 leaf_search_single_split_wrapper(
                     request,
                     searcher_context.clone(),
                     index_storage.clone(),
+                    index_id_opt.clone(),
                     doc_mapper.clone(),
                     split,
                     split_filter.clone(),
@@ -1401,7 +1560,7 @@ pub async fn leaf_search(
                     aggregations_limits.clone(),
                 )
                 .in_current_span(),
-            ),
+            )),
         ));
     }
 
@@ -1465,6 +1624,7 @@ async fn leaf_search_single_split_wrapper(
     request: SearchRequest,
     searcher_context: Arc<SearcherContext>,
     index_storage: Arc<dyn Storage>,
+    index_id_opt: Option<IndexId>,
     doc_mapper: Arc<DocMapper>,
     split: SplitIdAndFooterOffsets,
     split_filter: Arc<RwLock<CanSplitDoBetter>>,
@@ -1480,9 +1640,11 @@ async fn leaf_search_single_split_wrapper(
         &searcher_context,
         request,
         index_storage,
+        index_id_opt.as_deref(),
         split.clone(),
         doc_mapper,
         split_filter.clone(),
+        incremental_merge_collector.clone(),
         aggregations_limits,
         &mut search_permit,
     )
@@ -50,6 +50,11 @@ impl LeafSearchCache {
             ),
         }
     }
+
+    pub fn set_capacity(&self, capacity: usize) {
+        self.content.set_capacity_in_bytes(capacity);
+    }
+
     pub fn get(
         &self,
         split_info: SplitIdAndFooterOffsets,
@@ -100,6 +105,8 @@ impl CacheKey {
         // it doesn't matter whether or not we count all hits at the scale of a
         // single split: either we did process it and got everything, or we didn't.
         search_request.count_hits = CountHits::CountAll.into();
+        // profiling does not change the result of the search.
+        search_request.profile = false;
 
         CacheKey {
             split_id: split_info.split_id,
@@ -186,6 +193,7 @@ impl std::ops::RangeBounds<i64> for Range {
 
 #[cfg(test)]
 mod tests {
+    use quickwit_proto::compression::PayloadCodec;
     use quickwit_proto::search::{
         LeafSearchResponse, PartialHit, ResourceStats, SearchRequest, SortValue,
         SplitIdAndFooterOffsets,
@@ -249,6 +257,8 @@ mod tests {
                 split_id: "split_1".to_string(),
             }],
             resource_stats: None,
+            split_profiles: Vec::new(),
+            intermediate_aggregation_result_codec: PayloadCodec::None as i32,
         };
 
         assert!(cache.get(split_1.clone(), query_1.clone()).is_none());
@@ -340,6 +350,8 @@ mod tests {
                 split_id: "split_1".to_string(),
             }],
             resource_stats: Some(ResourceStats::default()),
+            split_profiles: Vec::new(),
+            intermediate_aggregation_result_codec: PayloadCodec::None as i32,
         };
 
         // for split_1, 1 and 1bis cover different timestamp ranges
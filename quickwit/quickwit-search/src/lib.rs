@@ -21,21 +21,32 @@ mod client;
 mod cluster_client;
 mod collector;
 mod error;
+mod explain;
+mod federation;
 mod fetch_docs;
 mod filters;
 mod find_trace_ids_collector;
+mod index_usage;
 mod leaf;
 mod leaf_cache;
 mod list_fields;
 mod list_fields_cache;
 mod list_terms;
+mod memory_budget;
+mod rate_aggregation;
 mod retry;
 mod root;
+mod running_leaf_searches;
+mod running_searches;
 mod scroll_context;
 mod search_job_placer;
 mod search_response_rest;
 mod search_stream;
+mod security;
 mod service;
+mod split_pinning;
+mod split_prefetch;
+mod tag_overlay;
 pub(crate) mod top_k_collector;
 
 mod metrics;
@@ -80,17 +91,22 @@ pub use crate::client::{
 };
 pub use crate::cluster_client::ClusterClient;
 pub use crate::error::{parse_grpc_error, SearchError};
+pub use crate::explain::{ClauseExplanation, ExplainRequest, ExplainResponse};
+pub use crate::federation::RemoteClusters;
 use crate::fetch_docs::fetch_docs;
 pub use crate::root::{
-    check_all_index_metadata_found, jobs_to_leaf_request, root_search, search_plan,
-    IndexMetasForLeafSearch, SearchJob,
+    check_all_index_metadata_found, exclude_rollout_candidates, jobs_to_leaf_request, root_search,
+    search_plan, IndexMetasForLeafSearch, SearchJob,
 };
+pub use crate::running_searches::{CancelSearchError, RunningSearchGuard, RunningSearches};
 pub use crate::search_job_placer::{Job, SearchJobPlacer};
 pub use crate::search_response_rest::{
     AggregationResults, SearchPlanResponseRest, SearchResponseRest,
 };
 pub use crate::search_stream::root_search_stream;
+pub use crate::security::authorize_tag_updates;
 pub use crate::service::{MockSearchService, SearchService, SearchServiceImpl};
+pub use crate::split_pinning::{PinIndexSplitsRequest, PinIndexSplitsResponse};
 
 /// A pool of searcher clients identified by their gRPC socket address.
 pub type SearcherPool = Pool<SocketAddr, SearchServiceClient>;
@@ -227,16 +243,19 @@ pub async fn resolve_index_patterns(
     } else {
         ListIndexesMetadataRequest {
             index_id_patterns: index_id_patterns.to_owned(),
+            include_trashed: false,
+            label_selectors: Vec::new(),
         }
     };
 
     // Get the index ids from the request
-    let indexes_metadata = metastore
+    let mut indexes_metadata = metastore
         .list_indexes_metadata(list_indexes_metadata_request)
         .await?
         .deserialize_indexes_metadata()
         .await?;
     check_all_index_metadata_found(&indexes_metadata, index_id_patterns)?;
+    exclude_rollout_candidates(&mut indexes_metadata, index_id_patterns);
     Ok(indexes_metadata)
 }
 
@@ -262,14 +281,26 @@ pub async fn start_searcher_service(
     storage_resolver: StorageResolver,
     search_job_placer: SearchJobPlacer,
     searcher_context: Arc<SearcherContext>,
+    remote_clusters: RemoteClusters,
 ) -> anyhow::Result<Arc<dyn SearchService>> {
-    let cluster_client = ClusterClient::new(search_job_placer);
-    let search_service = Arc::new(SearchServiceImpl::new(
-        metastore,
-        storage_resolver,
-        cluster_client,
-        searcher_context,
-    ));
+    let mut cluster_client = ClusterClient::new(search_job_placer);
+
+    if let Some(leaf_search_hedging_policy) =
+        &searcher_context.searcher_config.leaf_search_hedging_policy
+    {
+        cluster_client =
+            cluster_client.with_leaf_search_hedging_policy(leaf_search_hedging_policy.clone());
+    }
+    memory_budget::spawn_cache_rebalancing_task(&searcher_context);
+    let search_service = Arc::new(
+        SearchServiceImpl::new(
+            metastore,
+            storage_resolver,
+            cluster_client,
+            searcher_context,
+        )
+        .with_remote_clusters(remote_clusters),
+    );
     Ok(search_service)
 }
 
@@ -34,6 +34,7 @@ use quickwit_storage::Storage;
 
 use crate::leaf::open_split_bundle;
 use crate::search_job_placer::group_jobs_by_index_id;
+use crate::security::apply_security_policies_to_list_fields;
 use crate::service::SearcherContext;
 use crate::{list_relevant_splits, resolve_index_patterns, ClusterClient, SearchError, SearchJob};
 
@@ -50,8 +51,13 @@ pub async fn get_fields_from_split<'a>(
     {
         return Ok(Box::new(list_fields.fields.into_iter()));
     }
-    let (_, split_bundle) =
-        open_split_bundle(searcher_context, index_storage, split_and_footer_offsets).await?;
+    let (_, split_bundle) = open_split_bundle(
+        searcher_context,
+        index_storage,
+        Some(index_id.as_str()),
+        split_and_footer_offsets,
+    )
+    .await?;
 
     let serialized_split_fields = split_bundle
         .get_all(Path::new(SPLIT_FIELDS_FILE_NAME))
@@ -274,6 +280,8 @@ pub struct IndexMetasForLeafSearch {
     pub index_id: IndexId,
     /// Index URI.
     pub index_uri: Uri,
+    /// Name of the storage credentials used to access the index storage.
+    pub storage_credentials_opt: Option<String>,
 }
 
 /// Performs a distributed list fields request.
@@ -291,11 +299,17 @@ pub async fn root_list_fields(
     if indexes_metadata.is_empty() {
         return Ok(ListFieldsResponse { fields: Vec::new() });
     }
+    let retrievable_fields =
+        apply_security_policies_to_list_fields(&indexes_metadata, &list_fields_req)?;
     let index_uid_to_index_meta: HashMap<IndexUid, IndexMetasForLeafSearch> = indexes_metadata
         .iter()
         .map(|index_metadata| {
             let index_metadata_for_leaf_search = IndexMetasForLeafSearch {
                 index_uri: index_metadata.index_uri().clone(),
+                storage_credentials_opt: index_metadata
+                    .index_config
+                    .storage_credentials_opt
+                    .clone(),
                 index_id: index_metadata.index_config.index_id.to_string(),
             };
 
@@ -340,7 +354,9 @@ pub async fn root_list_fields(
             .map(|resp| resp.fields.into_iter())
             .collect_vec(),
     )?;
-    Ok(ListFieldsResponse { fields })
+    let mut list_fields_response = ListFieldsResponse { fields };
+    retrievable_fields.restrict_list_fields_response(&mut list_fields_response);
+    Ok(list_fields_response)
 }
 
 /// Builds a list of [`LeafListFieldsRequest`], one per index, from a list of [`SearchJob`].
@@ -365,6 +381,7 @@ pub fn jobs_to_leaf_requests(
             index_uri: index_meta.index_uri.to_string(),
             fields: search_request_for_leaf.fields.clone(),
             split_offsets: job_group.into_iter().map(|job| job.offsets).collect(),
+            storage_credentials: index_meta.storage_credentials_opt.clone(),
         };
         leaf_search_requests.push(leaf_search_request);
         Ok(())
@@ -34,6 +34,11 @@ impl ListFieldsCache {
             ),
         }
     }
+
+    pub fn set_capacity(&self, capacity: usize) {
+        self.content.set_capacity_in_bytes(capacity);
+    }
+
     pub fn get(&self, split_info: SplitIdAndFooterOffsets) -> Option<ListFields> {
         let key = CacheKey::from_split_meta(split_info);
         let encoded_result = self.content.get(&key)?;
@@ -33,9 +33,11 @@ use tantivy::schema::{Field, FieldType};
 use tantivy::{ReloadPolicy, Term};
 use tracing::{debug, error, info, instrument};
 
-use crate::leaf::open_index_with_caches;
+use crate::leaf::{open_index_with_caches, record_num_bytes_read};
+use crate::root::query_usage_deltas;
 use crate::search_job_placer::group_jobs_by_index_id;
 use crate::search_permit_provider::compute_initial_memory_allocation;
+use crate::security::apply_security_policies_to_list_terms;
 use crate::{resolve_index_patterns, ClusterClient, SearchError, SearchJob, SearcherContext};
 
 /// Performs a distributed list terms.
@@ -43,8 +45,9 @@ use crate::{resolve_index_patterns, ClusterClient, SearchError, SearchJob, Searc
 /// 2. Merges the search results.
 /// 3. Builds the response and returns.
 /// this is much simpler than `root_search` as it doesn't need to get actual docs.
-#[instrument(skip(list_terms_request, cluster_client, metastore))]
+#[instrument(skip(searcher_context, list_terms_request, cluster_client, metastore))]
 pub async fn root_list_terms(
+    searcher_context: &SearcherContext,
     list_terms_request: &ListTermsRequest,
     mut metastore: MetastoreServiceClient,
     cluster_client: &ClusterClient,
@@ -61,6 +64,7 @@ pub async fn root_list_terms(
             errors: Vec::new(),
         });
     }
+    apply_security_policies_to_list_terms(&indexes_metadata, list_terms_request)?;
 
     for index_metadata in indexes_metadata.iter() {
         let index_config = &index_metadata.index_config;
@@ -87,7 +91,8 @@ pub async fn root_list_terms(
         .map(|index_metadata| index_metadata.index_uid.clone())
         .collect();
 
-    let Some(mut query) = quickwit_metastore::ListSplitsQuery::try_from_index_uids(index_uids)
+    let Some(mut query) =
+        quickwit_metastore::ListSplitsQuery::try_from_index_uids(index_uids.clone())
     else {
         return Ok(ListTermsResponse::default());
     };
@@ -100,12 +105,16 @@ pub async fn root_list_terms(
     if let Some(end_ts) = list_terms_request.end_timestamp {
         query = query.with_time_range_end_lt(end_ts);
     }
-    let index_uid_to_index_uri: HashMap<IndexUid, String> = indexes_metadata
+    // Maps index UIDs to the URI of the index storage and the credentials used to access it.
+    let index_uid_to_index_storage: HashMap<IndexUid, (String, Option<String>)> = indexes_metadata
         .iter()
         .map(|index_metadata| {
             (
                 index_metadata.index_uid.clone(),
-                index_metadata.index_uri().to_string(),
+                (
+                    index_metadata.index_uri().to_string(),
+                    index_metadata.index_config.storage_credentials_opt.clone(),
+                ),
             )
         })
         .collect();
@@ -126,7 +135,7 @@ pub async fn root_list_terms(
     // For each node, forward to a node with an affinity for that index id.
     for (client, client_jobs) in assigned_leaf_search_jobs {
         let leaf_requests =
-            jobs_to_leaf_requests(list_terms_request, &index_uid_to_index_uri, client_jobs)?;
+            jobs_to_leaf_requests(list_terms_request, &index_uid_to_index_storage, client_jobs)?;
         for leaf_request in leaf_requests {
             leaf_request_tasks.push(cluster_client.leaf_list_terms(leaf_request, client.clone()));
         }
@@ -148,6 +157,9 @@ pub async fn root_list_terms(
             .join(", ");
         return Err(SearchError::Internal(errors));
     }
+    searcher_context
+        .index_usage_accumulator
+        .record(query_usage_deltas(index_uids));
 
     // Merging is a cpu-bound task, but probably fast enough to not require
     // spawning it on a blocking thread.
@@ -180,23 +192,27 @@ pub async fn root_list_terms(
 /// Builds a list of [`LeafListTermsRequest`], one per index, from a list of [`SearchJob`].
 pub fn jobs_to_leaf_requests(
     request: &ListTermsRequest,
-    index_uid_to_uri: &HashMap<IndexUid, String>,
+    index_uid_to_index_storage: &HashMap<IndexUid, (String, Option<String>)>,
     jobs: Vec<SearchJob>,
 ) -> crate::Result<Vec<LeafListTermsRequest>> {
     let search_request_for_leaf = request.clone();
     let mut leaf_search_requests = Vec::new();
     group_jobs_by_index_id(jobs, |job_group| {
         let index_uid = &job_group[0].index_uid;
-        let index_uri = index_uid_to_uri.get(index_uid).ok_or_else(|| {
-            SearchError::Internal(format!(
-                "received list fields job for an unknown index {index_uid}. it should never happen"
-            ))
-        })?;
+        let (index_uri, storage_credentials_opt) =
+            index_uid_to_index_storage.get(index_uid).ok_or_else(|| {
+                SearchError::Internal(format!(
+                    "received list fields job for an unknown index {index_uid}. it should never \
+                     happen"
+                ))
+            })?;
 
         let leaf_search_request = LeafListTermsRequest {
             list_terms_request: Some(search_request_for_leaf.clone()),
             index_uri: index_uri.to_string(),
+            index_uid: index_uid.to_string(),
             split_offsets: job_group.into_iter().map(|job| job.offsets).collect(),
+            storage_credentials: storage_credentials_opt.clone(),
         };
         leaf_search_requests.push(leaf_search_request);
         Ok(())
@@ -209,13 +225,24 @@ pub fn jobs_to_leaf_requests(
 async fn leaf_list_terms_single_split(
     searcher_context: &SearcherContext,
     search_request: &ListTermsRequest,
+    index_uid_opt: Option<IndexUid>,
     storage: Arc<dyn Storage>,
     split: SplitIdAndFooterOffsets,
 ) -> crate::Result<LeafListTermsResponse> {
     let cache =
         ByteRangeCache::with_infinite_capacity(&quickwit_storage::STORAGE_METRICS.shortlived_cache);
-    let (index, _) =
-        open_index_with_caches(searcher_context, storage, &split, None, Some(cache)).await?;
+    let index_id_opt = index_uid_opt
+        .as_ref()
+        .map(|index_uid| index_uid.index_id.as_str());
+    let (index, _) = open_index_with_caches(
+        searcher_context,
+        storage,
+        index_id_opt,
+        &split,
+        None,
+        Some(cache.clone()),
+    )
+    .await?;
     let split_schema = index.schema();
     let reader = index
         .reader_builder()
@@ -285,6 +312,7 @@ async fn leaf_list_terms_single_split(
         }
         segment_results.push(segment_result);
     }
+    record_num_bytes_read(searcher_context, index_uid_opt, cache.get_num_bytes());
 
     let merged_iter = segment_results.into_iter().kmerge().dedup();
     let merged_results: Vec<Vec<u8>> = if let Some(limit) = search_request.max_hits {
@@ -320,6 +348,7 @@ fn term_to_data(field: Field, field_type: &FieldType, field_value: &[u8]) -> Vec
 pub async fn leaf_list_terms(
     searcher_context: Arc<SearcherContext>,
     request: &ListTermsRequest,
+    index_uid_opt: Option<IndexUid>,
     index_storage: Arc<dyn Storage>,
     splits: &[SplitIdAndFooterOffsets],
 ) -> Result<LeafListTermsResponse, SearchError> {
@@ -342,6 +371,7 @@ pub async fn leaf_list_terms(
         .map(|(split, search_permit_recv)| {
             let index_storage_clone = index_storage.clone();
             let searcher_context_clone = searcher_context.clone();
+            let index_uid_opt_clone = index_uid_opt.clone();
             async move {
                 let leaf_split_search_permit = search_permit_recv.await;
                 // TODO dedicated counter and timer?
@@ -352,6 +382,7 @@ pub async fn leaf_list_terms(
                 let leaf_search_single_split_res = leaf_list_terms_single_split(
                     &searcher_context_clone,
                     request,
+                    index_uid_opt_clone,
                     index_storage_clone,
                     split.clone(),
                 )
@@ -0,0 +1,258 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use bytesize::ByteSize;
+use quickwit_config::SearcherConfig;
+use quickwit_storage::STORAGE_METRICS;
+use tracing::debug;
+
+use crate::metrics::SEARCH_METRICS;
+use crate::SearcherContext;
+
+/// Period at which the searcher memory budget is repartitioned between the caches.
+const REBALANCING_PERIOD: Duration = Duration::from_secs(30);
+
+/// Fraction of the cache budget that each cache is always granted, so that a cache that is
+/// momentarily unused can still warm up again.
+const MIN_CACHE_SHARE: f64 = 0.1;
+
+/// Fraction of the gap between the current and the target capacities closed at each rebalancing.
+/// It damps the oscillations caused by short bursts of misses.
+const SMOOTHING_FACTOR: f64 = 0.5;
+
+const NUM_BUDGETED_CACHES: usize = 3;
+
+/// Capacities in bytes of the fast field cache, the split footer cache, and the partial request
+/// cache, in this order.
+pub(crate) type CacheCapacities = [u64; NUM_BUDGETED_CACHES];
+
+/// Component names of the budgeted caches, matching the names of their cache metrics.
+const CACHE_COMPONENT_NAMES: [&str; NUM_BUDGETED_CACHES] =
+    ["fastfields", "splitfooter", "partial_request"];
+
+/// Returns the capacities of the caches configured in the searcher config. When a memory budget
+/// is set, the configured capacities are only used as initial proportions: they are scaled to
+/// fill the budget left after carving out the aggregation memory limit.
+pub(crate) fn initial_cache_capacities(searcher_config: &SearcherConfig) -> CacheCapacities {
+    let configured_capacities = [
+        searcher_config.fast_field_cache_capacity.as_u64(),
+        searcher_config.split_footer_cache_capacity.as_u64(),
+        searcher_config.partial_request_cache_capacity.as_u64(),
+    ];
+    let Some(cache_budget) = cache_budget(searcher_config) else {
+        return configured_capacities;
+    };
+    let total_configured_capacity: u64 = configured_capacities.iter().sum();
+
+    if total_configured_capacity == 0 {
+        return [cache_budget / NUM_BUDGETED_CACHES as u64; NUM_BUDGETED_CACHES];
+    }
+    configured_capacities.map(|configured_capacity| {
+        (cache_budget as u128 * configured_capacity as u128 / total_configured_capacity as u128)
+            as u64
+    })
+}
+
+/// Returns the part of the searcher memory budget that is shared by the caches, or `None` if no
+/// memory budget is configured.
+fn cache_budget(searcher_config: &SearcherConfig) -> Option<u64> {
+    let memory_budget: ByteSize = searcher_config.memory_budget?;
+    let cache_budget = memory_budget
+        .as_u64()
+        .saturating_sub(searcher_config.aggregation_memory_limit.as_u64());
+    Some(cache_budget)
+}
+
+/// Computes the next capacities of the caches. Each cache is granted [`MIN_CACHE_SHARE`] of the
+/// budget, and the remainder is split in proportion to the number of misses of each cache over
+/// the last period. The capacities are left untouched if no cache missed.
+fn rebalance_cache_capacities(
+    cache_budget: u64,
+    capacities: CacheCapacities,
+    num_misses: [u64; NUM_BUDGETED_CACHES],
+) -> CacheCapacities {
+    let total_num_misses: u64 = num_misses.iter().sum();
+
+    if total_num_misses == 0 {
+        return capacities;
+    }
+    let min_capacity = (cache_budget as f64 * MIN_CACHE_SHARE) as u64;
+    let shared_budget = cache_budget.saturating_sub(min_capacity * NUM_BUDGETED_CACHES as u64);
+    let mut new_capacities = capacities;
+
+    for (cache_ord, new_capacity) in new_capacities.iter_mut().enumerate() {
+        let miss_share = num_misses[cache_ord] as f64 / total_num_misses as f64;
+        let target_capacity = min_capacity as f64 + shared_budget as f64 * miss_share;
+        let current_capacity = capacities[cache_ord] as f64;
+        *new_capacity =
+            (current_capacity + (target_capacity - current_capacity) * SMOOTHING_FACTOR) as u64;
+    }
+    new_capacities
+}
+
+fn apply_cache_capacities(searcher_context: &SearcherContext, capacities: CacheCapacities) {
+    let [fast_field_cache_capacity, split_footer_cache_capacity, partial_request_cache_capacity] =
+        capacities;
+    searcher_context
+        .fast_fields_cache
+        .set_fast_field_cache_capacity(fast_field_cache_capacity as usize);
+    searcher_context
+        .split_footer_cache
+        .set_capacity_in_bytes(split_footer_cache_capacity as usize);
+    // The leaf search cache and the list fields cache both report to the partial request cache
+    // metrics, so they share its capacity evenly.
+    let half_partial_request_cache_capacity = partial_request_cache_capacity as usize / 2;
+    searcher_context
+        .leaf_search_cache
+        .set_capacity(half_partial_request_cache_capacity);
+    searcher_context
+        .list_fields_cache
+        .set_capacity(half_partial_request_cache_capacity);
+    record_cache_capacities(capacities);
+}
+
+pub(crate) fn record_cache_capacities(capacities: CacheCapacities) {
+    for (component_name, capacity) in CACHE_COMPONENT_NAMES.iter().zip(capacities) {
+        SEARCH_METRICS
+            .searcher_cache_capacity_bytes
+            .with_label_values([component_name])
+            .set(capacity as i64);
+    }
+}
+
+/// Reads the cumulative hits and misses of the budgeted caches.
+fn read_cache_counters() -> [(u64, u64); NUM_BUDGETED_CACHES] {
+    [
+        &STORAGE_METRICS.fast_field_cache,
+        &STORAGE_METRICS.split_footer_cache,
+        &STORAGE_METRICS.partial_request_cache,
+    ]
+    .map(|cache_metrics| {
+        (
+            cache_metrics.hits_num_items.get(),
+            cache_metrics.misses_num_items.get(),
+        )
+    })
+}
+
+/// Spawns a task that periodically repartitions the searcher memory budget between the caches,
+/// based on their recent misses. This is a no-op if no memory budget is configured. The task
+/// stops once the searcher context is dropped.
+pub(crate) fn spawn_cache_rebalancing_task(searcher_context: &Arc<SearcherContext>) {
+    let Some(cache_budget) = cache_budget(&searcher_context.searcher_config) else {
+        return;
+    };
+    let mut capacities = initial_cache_capacities(&searcher_context.searcher_config);
+    let weak_searcher_context: Weak<SearcherContext> = Arc::downgrade(searcher_context);
+
+    tokio::spawn(async move {
+        let mut previous_counters = read_cache_counters();
+        let mut interval = tokio::time::interval(REBALANCING_PERIOD);
+        // The first tick completes immediately.
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            let Some(searcher_context) = weak_searcher_context.upgrade() else {
+                return;
+            };
+            let counters = read_cache_counters();
+            let mut num_misses = [0; NUM_BUDGETED_CACHES];
+
+            for cache_ord in 0..NUM_BUDGETED_CACHES {
+                let (hits, misses) = counters[cache_ord];
+                let (previous_hits, previous_misses) = previous_counters[cache_ord];
+                let num_hits = hits.saturating_sub(previous_hits);
+                num_misses[cache_ord] = misses.saturating_sub(previous_misses);
+                let num_lookups = num_hits + num_misses[cache_ord];
+
+                if num_lookups > 0 {
+                    SEARCH_METRICS
+                        .searcher_cache_hit_ratio
+                        .with_label_values([CACHE_COMPONENT_NAMES[cache_ord]])
+                        .set(num_hits as f64 / num_lookups as f64);
+                }
+            }
+            previous_counters = counters;
+
+            let new_capacities = rebalance_cache_capacities(cache_budget, capacities, num_misses);
+
+            if new_capacities != capacities {
+                debug!(
+                    fast_field_cache_capacity = new_capacities[0],
+                    split_footer_cache_capacity = new_capacities[1],
+                    partial_request_cache_capacity = new_capacities[2],
+                    "rebalancing searcher memory budget"
+                );
+                capacities = new_capacities;
+                apply_cache_capacities(&searcher_context, capacities);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initial_cache_capacities() {
+        let mut searcher_config = SearcherConfig {
+            fast_field_cache_capacity: ByteSize::mb(600),
+            split_footer_cache_capacity: ByteSize::mb(300),
+            partial_request_cache_capacity: ByteSize::mb(100),
+            aggregation_memory_limit: ByteSize::mb(500),
+            ..Default::default()
+        };
+        assert_eq!(
+            initial_cache_capacities(&searcher_config),
+            [600_000_000, 300_000_000, 100_000_000]
+        );
+
+        searcher_config.memory_budget = Some(ByteSize::mb(2_500));
+        assert_eq!(
+            initial_cache_capacities(&searcher_config),
+            [1_200_000_000, 600_000_000, 200_000_000]
+        );
+    }
+
+    #[test]
+    fn test_rebalance_cache_capacities() {
+        let capacities = [500, 300, 200];
+
+        // No misses, no rebalancing.
+        assert_eq!(
+            rebalance_cache_capacities(1_000, capacities, [0, 0, 0]),
+            capacities
+        );
+        // Only the fast field cache misses: it moves halfway toward 80% of the budget.
+        assert_eq!(
+            rebalance_cache_capacities(1_000, capacities, [10, 0, 0]),
+            [650, 200, 150]
+        );
+        // Even misses converge toward an even split.
+        let mut capacities = capacities;
+        for _ in 0..20 {
+            capacities = rebalance_cache_capacities(1_000, capacities, [5, 5, 5]);
+        }
+        for capacity in capacities {
+            assert!((332..=334).contains(&capacity));
+        }
+        assert!(capacities.iter().sum::<u64>() <= 1_000);
+    }
+}
@@ -17,8 +17,9 @@
 use bytesize::ByteSize;
 use once_cell::sync::Lazy;
 use quickwit_common::metrics::{
-    exponential_buckets, linear_buckets, new_counter, new_counter_vec, new_gauge, new_gauge_vec,
-    new_histogram, new_histogram_vec, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    exponential_buckets, linear_buckets, new_counter, new_counter_vec, new_float_gauge_vec,
+    new_gauge, new_gauge_vec, new_histogram, new_histogram_vec, FloatGaugeVec, Histogram,
+    HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 
 pub struct SearchMetrics {
@@ -28,6 +29,11 @@ pub struct SearchMetrics {
     pub leaf_search_requests_total: IntCounterVec<1>,
     pub leaf_search_request_duration_seconds: HistogramVec<1>,
     pub leaf_search_targeted_splits: HistogramVec<1>,
+    pub leaf_search_hedged_requests_total: IntCounter,
+    pub leaf_search_replanned_splits_total: IntCounter,
+    pub leaf_search_rejected_requests_total: IntCounter,
+    pub leaf_search_pruned_segments_total: IntCounter,
+    pub prefetched_splits_total: IntCounter,
     pub leaf_searches_splits_total: IntCounter,
     pub leaf_search_split_duration_secs: Histogram,
     pub job_assigned_total: IntCounterVec<1>,
@@ -35,6 +41,8 @@ pub struct SearchMetrics {
     pub leaf_search_single_split_tasks_ongoing: IntGauge,
     pub leaf_search_single_split_warmup_num_bytes: Histogram,
     pub searcher_local_kv_store_size_bytes: IntGauge,
+    pub searcher_cache_capacity_bytes: IntGaugeVec<1>,
+    pub searcher_cache_hit_ratio: FloatGaugeVec<1>,
 }
 
 impl Default for SearchMetrics {
@@ -117,6 +125,41 @@ impl Default for SearchMetrics {
                 ["status"],
                 targeted_splits_buckets,
             ),
+            leaf_search_hedged_requests_total: new_counter(
+                "leaf_search_hedged_requests_total",
+                "Number of slow leaf search requests speculatively re-dispatched to another \
+                 searcher.",
+                "search",
+                &[],
+            ),
+            leaf_search_replanned_splits_total: new_counter(
+                "leaf_search_replanned_splits_total",
+                "Number of splits re-planned onto another searcher after a saturated searcher \
+                 rejected their leaf search request.",
+                "search",
+                &[],
+            ),
+            leaf_search_rejected_requests_total: new_counter(
+                "leaf_search_rejected_requests_total",
+                "Number of leaf search requests rejected because too many split searches were \
+                 waiting to run.",
+                "search",
+                &[],
+            ),
+            leaf_search_pruned_segments_total: new_counter(
+                "leaf_search_pruned_segments_total",
+                "Number of segments skipped because they could not hold any document better than \
+                 the top hits collected so far.",
+                "search",
+                &[],
+            ),
+            prefetched_splits_total: new_counter(
+                "prefetched_splits_total",
+                "Number of splits reported to the searcher split caches because they are \
+                 time-adjacent to the splits of a search request.",
+                "search",
+                &[],
+            ),
             leaf_searches_splits_total: new_counter(
                 "leaf_searches_splits_total",
                 "Number of leaf searches (count of splits) started.",
@@ -154,6 +197,21 @@ impl Default for SearchMetrics {
                 "search",
                 &[],
             ),
+            searcher_cache_capacity_bytes: new_gauge_vec(
+                "searcher_cache_capacity_bytes",
+                "Capacity assigned to each searcher cache by the searcher memory budget.",
+                "search",
+                &[],
+                ["component_name"],
+            ),
+            searcher_cache_hit_ratio: new_float_gauge_vec(
+                "searcher_cache_hit_ratio",
+                "Ratio of lookups hitting each searcher cache over the last rebalancing period of \
+                 the searcher memory budget.",
+                "search",
+                &[],
+                ["component_name"],
+            ),
         }
     }
 }
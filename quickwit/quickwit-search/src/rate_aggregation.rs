@@ -0,0 +1,465 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use quickwit_proto::search::{SearchRequest, SearchResponse};
+use serde::Deserialize;
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+
+use crate::SearchError;
+
+/// Time unit in which the value of a `rate` aggregation is expressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RateUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+}
+
+impl RateUnit {
+    fn as_millis(self) -> u64 {
+        match self {
+            RateUnit::Second => 1_000,
+            RateUnit::Minute => 60 * 1_000,
+            RateUnit::Hour => 60 * 60 * 1_000,
+            RateUnit::Day => 24 * 60 * 60 * 1_000,
+            RateUnit::Week => 7 * 24 * 60 * 60 * 1_000,
+        }
+    }
+}
+
+/// Metric aggregated over the field of a `rate` aggregation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RateMode {
+    #[default]
+    Sum,
+    ValueCount,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RateAggregationParams {
+    #[serde(default)]
+    field: Option<String>,
+    #[serde(default)]
+    unit: Option<RateUnit>,
+    #[serde(default)]
+    mode: RateMode,
+}
+
+/// A `rate` aggregation, nested in a date histogram aggregation.
+#[derive(Debug, Clone, PartialEq)]
+struct RateAggregation {
+    /// Names of the bucket aggregations leading to the date histogram, starting from the root.
+    bucket_agg_path: Vec<String>,
+    name: String,
+    /// Whether the rate is computed from a metric over a field rather than from the doc count of
+    /// the buckets.
+    has_field: bool,
+    /// Factor converting the value of a bucket into a rate expressed in the requested unit.
+    scale: f64,
+}
+
+impl RateAggregation {
+    fn compute(&self, aggregation_results: &mut JsonValue, bucket_agg_path: &[String]) {
+        let Some((bucket_agg_name, sub_bucket_agg_path)) = bucket_agg_path.split_first() else {
+            // `aggregation_results` is a date histogram bucket.
+            self.compute_in_bucket(aggregation_results);
+            return;
+        };
+        let buckets: Vec<&mut JsonValue> = match aggregation_results
+            .get_mut(bucket_agg_name)
+            .and_then(|bucket_agg_result| bucket_agg_result.get_mut("buckets"))
+        {
+            Some(JsonValue::Array(buckets)) => buckets.iter_mut().collect(),
+            // Keyed bucket aggregations.
+            Some(JsonValue::Object(buckets)) => buckets.values_mut().collect(),
+            _ => return,
+        };
+        for bucket in buckets {
+            self.compute(bucket, sub_bucket_agg_path);
+        }
+    }
+
+    fn compute_in_bucket(&self, bucket: &mut JsonValue) {
+        let value_opt = if self.has_field {
+            bucket
+                .get(&self.name)
+                .and_then(|metric_result| metric_result.get("value"))
+                .and_then(JsonValue::as_f64)
+        } else {
+            bucket.get("doc_count").and_then(JsonValue::as_f64)
+        };
+        let Some(bucket) = bucket.as_object_mut() else {
+            return;
+        };
+        let rate_opt = value_opt.map(|value| value * self.scale);
+        bucket.insert(self.name.clone(), json!({ "value": rate_opt }));
+    }
+}
+
+/// The `rate` aggregations of a search request.
+///
+/// Tantivy does not support `rate` aggregations: they are replaced in the request by the metric
+/// aggregation they are computed from, and the rates are derived from the final aggregation
+/// results by normalizing the value of each date histogram bucket by the bucket interval.
+#[derive(Debug, Default)]
+pub(crate) struct RateAggregations {
+    rate_aggregations: Vec<RateAggregation>,
+}
+
+impl RateAggregations {
+    /// Extracts the `rate` aggregations of the search request, rewriting its aggregation request
+    /// so that it can be handled by tantivy.
+    pub(crate) fn extract_from_request(search_request: &mut SearchRequest) -> crate::Result<Self> {
+        let Some(aggregation_request) = &search_request.aggregation_request else {
+            return Ok(Self::default());
+        };
+        if !aggregation_request.contains("\"rate\"") {
+            return Ok(Self::default());
+        }
+        let Ok(mut aggregations) = serde_json::from_str::<JsonValue>(aggregation_request) else {
+            // Invalid requests are reported by the regular validation of the request.
+            return Ok(Self::default());
+        };
+        let Some(aggregations_map) = aggregations.as_object_mut() else {
+            return Ok(Self::default());
+        };
+        let mut rate_aggregations = Vec::new();
+        extract_rate_aggregations(
+            aggregations_map,
+            &mut Vec::new(),
+            None,
+            &mut rate_aggregations,
+        )?;
+        if !rate_aggregations.is_empty() {
+            search_request.aggregation_request = Some(serde_json::to_string(&aggregations)?);
+        }
+        Ok(Self { rate_aggregations })
+    }
+
+    /// Computes the rates out of the final aggregation results of the search response.
+    pub(crate) fn apply(&self, search_response: &mut SearchResponse) -> crate::Result<()> {
+        if self.rate_aggregations.is_empty() {
+            return Ok(());
+        }
+        let Some(aggregation_results_json) = &search_response.aggregation else {
+            return Ok(());
+        };
+        let mut aggregation_results: JsonValue = serde_json::from_str(aggregation_results_json)?;
+
+        for rate_aggregation in &self.rate_aggregations {
+            rate_aggregation.compute(&mut aggregation_results, &rate_aggregation.bucket_agg_path);
+        }
+        search_response.aggregation = Some(serde_json::to_string(&aggregation_results)?);
+        Ok(())
+    }
+}
+
+fn extract_rate_aggregations(
+    aggregations: &mut JsonMap<String, JsonValue>,
+    bucket_agg_path: &mut Vec<String>,
+    interval_millis_opt: Option<u64>,
+    rate_aggregations: &mut Vec<RateAggregation>,
+) -> crate::Result<()> {
+    let mut doc_count_rate_names = Vec::new();
+
+    for (name, aggregation) in aggregations.iter_mut() {
+        let Some(aggregation) = aggregation.as_object_mut() else {
+            continue;
+        };
+        if let Some(rate_params_json) = aggregation.remove("rate") {
+            let Some(interval_millis) = interval_millis_opt else {
+                return Err(SearchError::InvalidAggregationRequest(format!(
+                    "rate aggregation `{name}` must be a sub-aggregation of a date_histogram \
+                     aggregation with a `fixed_interval`"
+                )));
+            };
+            if !aggregation.is_empty() {
+                return Err(SearchError::InvalidAggregationRequest(format!(
+                    "rate aggregation `{name}` does not support sub-aggregations"
+                )));
+            }
+            let rate_params: RateAggregationParams = serde_json::from_value(rate_params_json)
+                .map_err(|error| {
+                    SearchError::InvalidAggregationRequest(format!(
+                        "invalid rate aggregation `{name}`: {error}"
+                    ))
+                })?;
+            let unit_millis = rate_params
+                .unit
+                .map(RateUnit::as_millis)
+                .unwrap_or(interval_millis);
+
+            if let Some(field) = &rate_params.field {
+                let metric = match rate_params.mode {
+                    RateMode::Sum => "sum",
+                    RateMode::ValueCount => "value_count",
+                };
+                aggregation.insert(metric.to_string(), json!({ "field": field }));
+            } else {
+                doc_count_rate_names.push(name.clone());
+            }
+            rate_aggregations.push(RateAggregation {
+                bucket_agg_path: bucket_agg_path.clone(),
+                name: name.clone(),
+                has_field: rate_params.field.is_some(),
+                scale: unit_millis as f64 / interval_millis as f64,
+            });
+            continue;
+        }
+        let sub_interval_millis_opt = aggregation
+            .get("date_histogram")
+            .and_then(fixed_interval_millis);
+
+        for sub_aggregations_key in ["aggs", "aggregations"] {
+            let Some(sub_aggregations) = aggregation
+                .get_mut(sub_aggregations_key)
+                .and_then(JsonValue::as_object_mut)
+            else {
+                continue;
+            };
+            bucket_agg_path.push(name.clone());
+            extract_rate_aggregations(
+                sub_aggregations,
+                bucket_agg_path,
+                sub_interval_millis_opt,
+                rate_aggregations,
+            )?;
+            bucket_agg_path.pop();
+        }
+    }
+    // Rates of doc counts are computed from the doc count of the buckets, they do not need any
+    // aggregation.
+    for name in doc_count_rate_names {
+        aggregations.remove(&name);
+    }
+    Ok(())
+}
+
+/// Returns the `fixed_interval` of a date histogram aggregation in milliseconds.
+fn fixed_interval_millis(date_histogram: &JsonValue) -> Option<u64> {
+    let fixed_interval = date_histogram.get("fixed_interval")?.as_str()?;
+    let unit_pos = fixed_interval.find(|c: char| !c.is_ascii_digit())?;
+    let (num_units_str, unit) = fixed_interval.split_at(unit_pos);
+    let num_units: u64 = num_units_str.parse().ok()?;
+    let unit_millis = match unit {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" => 60 * 1_000,
+        "h" => 60 * 60 * 1_000,
+        "d" => 24 * 60 * 60 * 1_000,
+        _ => return None,
+    };
+    num_units
+        .checked_mul(unit_millis)
+        .filter(|interval_millis| *interval_millis > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search_request_with_aggs(aggs: JsonValue) -> SearchRequest {
+        SearchRequest {
+            aggregation_request: Some(aggs.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fixed_interval_millis() {
+        let fixed_interval_millis = |fixed_interval: &str| {
+            fixed_interval_millis(&json!({ "fixed_interval": fixed_interval }))
+        };
+        assert_eq!(fixed_interval_millis("250ms"), Some(250));
+        assert_eq!(fixed_interval_millis("30s"), Some(30_000));
+        assert_eq!(fixed_interval_millis("5m"), Some(300_000));
+        assert_eq!(fixed_interval_millis("1h"), Some(3_600_000));
+        assert_eq!(fixed_interval_millis("1d"), Some(86_400_000));
+        assert_eq!(fixed_interval_millis("0s"), None);
+        assert_eq!(fixed_interval_millis("1M"), None);
+        assert_eq!(fixed_interval_millis("s"), None);
+        assert_eq!(fixed_interval_millis("10"), None);
+    }
+
+    #[test]
+    fn test_extract_rate_aggregations() {
+        let mut search_request = search_request_with_aggs(json!({
+            "per_host": {
+                "terms": { "field": "host" },
+                "aggs": {
+                    "histo": {
+                        "date_histogram": { "field": "timestamp", "fixed_interval": "1m" },
+                        "aggs": {
+                            "bytes_per_sec": { "rate": { "field": "bytes", "unit": "second" } },
+                            "requests_per_hour": { "rate": { "unit": "hour" } },
+                            "responses": { "rate": { "field": "status", "mode": "value_count" } }
+                        }
+                    }
+                }
+            }
+        }));
+        let rate_aggregations =
+            RateAggregations::extract_from_request(&mut search_request).unwrap();
+        let aggregation_request: JsonValue =
+            serde_json::from_str(search_request.aggregation_request.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            aggregation_request,
+            json!({
+                "per_host": {
+                    "terms": { "field": "host" },
+                    "aggs": {
+                        "histo": {
+                            "date_histogram": { "field": "timestamp", "fixed_interval": "1m" },
+                            "aggs": {
+                                "bytes_per_sec": { "sum": { "field": "bytes" } },
+                                "responses": { "value_count": { "field": "status" } }
+                            }
+                        }
+                    }
+                }
+            })
+        );
+        let mut rate_aggregations_vec = rate_aggregations.rate_aggregations.clone();
+        rate_aggregations_vec.sort_by(|left, right| left.name.cmp(&right.name));
+        let bucket_agg_path = vec!["per_host".to_string(), "histo".to_string()];
+        assert_eq!(
+            rate_aggregations_vec,
+            [
+                RateAggregation {
+                    bucket_agg_path: bucket_agg_path.clone(),
+                    name: "bytes_per_sec".to_string(),
+                    has_field: true,
+                    scale: 1.0 / 60.0,
+                },
+                RateAggregation {
+                    bucket_agg_path: bucket_agg_path.clone(),
+                    name: "requests_per_hour".to_string(),
+                    has_field: false,
+                    scale: 60.0,
+                },
+                RateAggregation {
+                    bucket_agg_path,
+                    name: "responses".to_string(),
+                    has_field: true,
+                    scale: 1.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_rate_aggregations_no_rate() {
+        let aggs = json!({
+            "rate": {
+                "date_histogram": { "field": "timestamp", "fixed_interval": "1m" }
+            }
+        });
+        let mut search_request = search_request_with_aggs(aggs.clone());
+        let rate_aggregations =
+            RateAggregations::extract_from_request(&mut search_request).unwrap();
+        assert!(rate_aggregations.rate_aggregations.is_empty());
+        assert_eq!(search_request.aggregation_request, Some(aggs.to_string()));
+    }
+
+    #[test]
+    fn test_extract_rate_aggregations_invalid() {
+        let mut search_request = search_request_with_aggs(json!({
+            "bytes_per_sec": { "rate": { "field": "bytes", "unit": "second" } }
+        }));
+        let error = RateAggregations::extract_from_request(&mut search_request).unwrap_err();
+        assert!(error.to_string().contains(
+            "rate aggregation `bytes_per_sec` must be a sub-aggregation of a date_histogram"
+        ));
+
+        let mut search_request = search_request_with_aggs(json!({
+            "histo": {
+                "date_histogram": { "field": "timestamp", "fixed_interval": "1m" },
+                "aggs": {
+                    "bytes_per_sec": { "rate": { "field": "bytes", "unit": "month" } }
+                }
+            }
+        }));
+        let error = RateAggregations::extract_from_request(&mut search_request).unwrap_err();
+        assert!(matches!(error, SearchError::InvalidAggregationRequest(_)));
+        assert!(error
+            .to_string()
+            .contains("invalid rate aggregation `bytes_per_sec`"));
+    }
+
+    #[test]
+    fn test_apply_rate_aggregations() {
+        let mut search_request = search_request_with_aggs(json!({
+            "histo": {
+                "date_histogram": { "field": "timestamp", "fixed_interval": "10s" },
+                "aggs": {
+                    "bytes_per_sec": { "rate": { "field": "bytes", "unit": "second" } },
+                    "requests_per_min": { "rate": { "unit": "minute" } }
+                }
+            }
+        }));
+        let rate_aggregations =
+            RateAggregations::extract_from_request(&mut search_request).unwrap();
+        let mut search_response = SearchResponse {
+            aggregation: Some(
+                json!({
+                    "histo": {
+                        "buckets": [
+                            {
+                                "key": 0.0,
+                                "doc_count": 5,
+                                "bytes_per_sec": { "value": 100.0 }
+                            },
+                            {
+                                "key": 10000.0,
+                                "doc_count": 0,
+                                "bytes_per_sec": { "value": null }
+                            }
+                        ]
+                    }
+                })
+                .to_string(),
+            ),
+            ..Default::default()
+        };
+        rate_aggregations.apply(&mut search_response).unwrap();
+        let aggregation_results: JsonValue =
+            serde_json::from_str(search_response.aggregation.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            aggregation_results,
+            json!({
+                "histo": {
+                    "buckets": [
+                        {
+                            "key": 0.0,
+                            "doc_count": 5,
+                            "bytes_per_sec": { "value": 10.0 },
+                            "requests_per_min": { "value": 30.0 }
+                        },
+                        {
+                            "key": 10000.0,
+                            "doc_count": 0,
+                            "bytes_per_sec": { "value": null },
+                            "requests_per_min": { "value": 0.0 }
+                        }
+                    ]
+                }
+            })
+        );
+    }
+}
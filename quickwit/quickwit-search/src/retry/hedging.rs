@@ -0,0 +1,174 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use quickwit_config::LeafSearchHedgingPolicy;
+
+/// Number of recent leaf search latencies used to compute the hedging delay.
+const LATENCY_WINDOW_SIZE: usize = 1_000;
+
+/// Leaf search requests are not hedged until that many latencies have been observed.
+pub(crate) const MIN_NUM_LATENCY_SAMPLES: usize = 20;
+
+/// The hedging delay is recomputed every time that many latencies have been observed.
+const DELAY_REFRESH_PERIOD: usize = 50;
+
+/// Maximum number of hedged requests that can be emitted in a burst.
+const MAX_HEDGING_BUDGET: f64 = 10.0;
+
+/// Decides when a slow leaf search request should be speculatively re-dispatched to another
+/// searcher.
+///
+/// The hedging delay is a percentile of the latencies of the recent leaf search requests, and the
+/// hedged requests are bounded by a budget replenished by a fraction of a token for each leaf
+/// search request.
+pub(crate) struct LeafSearchHedger {
+    policy: LeafSearchHedgingPolicy,
+    state: Mutex<HedgerState>,
+}
+
+struct HedgerState {
+    latencies: VecDeque<Duration>,
+    num_latencies_since_refresh: usize,
+    hedging_delay_opt: Option<Duration>,
+    budget: f64,
+}
+
+impl LeafSearchHedger {
+    pub fn new(policy: LeafSearchHedgingPolicy) -> Self {
+        let state = HedgerState {
+            latencies: VecDeque::with_capacity(LATENCY_WINDOW_SIZE),
+            num_latencies_since_refresh: 0,
+            hedging_delay_opt: None,
+            budget: MAX_HEDGING_BUDGET,
+        };
+        Self {
+            policy,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Registers a new leaf search request and returns the delay after which it should be hedged,
+    /// or `None` if not enough latencies have been observed yet.
+    pub fn on_request(&self) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        state.budget =
+            (state.budget + self.policy.max_hedged_requests_ratio).min(MAX_HEDGING_BUDGET);
+        state.hedging_delay_opt
+    }
+
+    /// Consumes a token from the budget of hedged requests. Returns `false` if the budget is
+    /// exhausted.
+    pub fn try_acquire_hedge(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        if state.budget < 1.0 {
+            return false;
+        }
+        state.budget -= 1.0;
+        true
+    }
+
+    pub fn record_latency(&self, latency: Duration) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.latencies.len() == LATENCY_WINDOW_SIZE {
+            state.latencies.pop_front();
+        }
+        state.latencies.push_back(latency);
+        state.num_latencies_since_refresh += 1;
+
+        if state.latencies.len() < MIN_NUM_LATENCY_SAMPLES {
+            return;
+        }
+        if state.hedging_delay_opt.is_some()
+            && state.num_latencies_since_refresh < DELAY_REFRESH_PERIOD
+        {
+            return;
+        }
+        let mut sorted_latencies: Vec<Duration> = state.latencies.iter().copied().collect();
+        sorted_latencies.sort_unstable();
+
+        let rank = (self.policy.latency_percentile / 100.0 * sorted_latencies.len() as f64).ceil()
+            as usize;
+        let percentile_latency = sorted_latencies[rank.clamp(1, sorted_latencies.len()) - 1];
+
+        state.hedging_delay_opt = Some(percentile_latency.max(self.policy.min_delay()));
+        state.num_latencies_since_refresh = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hedging_policy() -> LeafSearchHedgingPolicy {
+        LeafSearchHedgingPolicy {
+            latency_percentile: 90.0,
+            min_delay_millis: 5,
+            max_hedged_requests_ratio: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_leaf_search_hedger_delay() {
+        let hedger = LeafSearchHedger::new(hedging_policy());
+        assert!(hedger.on_request().is_none());
+
+        for latency_millis in 1..MIN_NUM_LATENCY_SAMPLES as u64 {
+            hedger.record_latency(Duration::from_millis(latency_millis * 10));
+        }
+        assert!(hedger.on_request().is_none());
+
+        hedger.record_latency(Duration::from_millis(200));
+        assert_eq!(hedger.on_request(), Some(Duration::from_millis(180)));
+
+        // The delay is not recomputed until enough latencies have been observed.
+        for _ in 0..DELAY_REFRESH_PERIOD - 1 {
+            hedger.record_latency(Duration::from_millis(1));
+        }
+        assert_eq!(hedger.on_request(), Some(Duration::from_millis(180)));
+
+        hedger.record_latency(Duration::from_millis(1));
+        assert_eq!(hedger.on_request(), Some(Duration::from_millis(130)));
+
+        // The delay never goes below `min_delay_millis`.
+        let hedger = LeafSearchHedger::new(hedging_policy());
+
+        for _ in 0..MIN_NUM_LATENCY_SAMPLES {
+            hedger.record_latency(Duration::from_millis(1));
+        }
+        assert_eq!(hedger.on_request(), Some(Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn test_leaf_search_hedger_budget() {
+        let hedger = LeafSearchHedger::new(hedging_policy());
+
+        for _ in 0..MAX_HEDGING_BUDGET as usize {
+            assert!(hedger.try_acquire_hedge());
+        }
+        assert!(!hedger.try_acquire_hedge());
+
+        hedger.on_request();
+        assert!(!hedger.try_acquire_hedge());
+
+        hedger.on_request();
+        assert!(hedger.try_acquire_hedge());
+        assert!(!hedger.try_acquire_hedge());
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub(crate) mod hedging;
 pub mod search;
 pub mod search_stream;
 
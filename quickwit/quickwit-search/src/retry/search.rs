@@ -53,7 +53,8 @@ impl RetryPolicy<LeafSearchRequest, LeafSearchResponse, SearchError> for LeafSea
                     .retain(|request| !request.split_offsets.is_empty());
                 Some(request)
             }
-            Err(SearchError::Timeout(_)) => None, // Don't retry on timeout
+            // Don't retry on timeout or cancellation
+            Err(SearchError::Timeout(_) | SearchError::Cancelled(_)) => None,
             Err(_) => Some(request),
         }
     }
@@ -82,6 +83,7 @@ mod tests {
             search_request: Some(search_request),
             doc_mappers: vec!["doc_mapper".to_string()],
             index_uris: vec!["uri".to_string()],
+            accepts_compressed_intermediate_aggregation_result: false,
             leaf_requests: vec![LeafRequestRef {
                 index_uri_ord: 0,
                 doc_mapper_ord: 0,
@@ -103,6 +105,8 @@ mod tests {
                         num_docs: 0,
                     },
                 ],
+                index_uid: "test-idx:00000000000000000000000000".to_string(),
+                storage_credentials: None,
             }],
         }
     }
@@ -12,10 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::SocketAddr;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::OnceLock;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Context;
 use futures::future::try_join_all;
@@ -25,25 +26,31 @@ use quickwit_common::shared_consts;
 use quickwit_common::uri::Uri;
 use quickwit_config::build_doc_mapper;
 use quickwit_doc_mapper::tag_pruning::extract_tags_from_query;
-use quickwit_doc_mapper::DYNAMIC_FIELD_NAME;
-use quickwit_metastore::{IndexMetadata, ListIndexesMetadataResponseExt, SplitMetadata};
+use quickwit_doc_mapper::{DYNAMIC_FIELD_NAME, LOGICAL_TIMESTAMP_FIELD_NAME};
+use quickwit_metastore::{
+    IndexMetadata, ListIndexesMetadataResponseExt, SplitMetadata, TagOverlay,
+};
+use quickwit_proto::compression::PayloadCodec;
 use quickwit_proto::metastore::{
-    ListIndexesMetadataRequest, MetastoreService, MetastoreServiceClient,
+    IndexUsage, ListIndexesMetadataRequest, MetastoreService, MetastoreServiceClient,
 };
 use quickwit_proto::search::{
     FetchDocsRequest, FetchDocsResponse, Hit, LeafHit, LeafRequestRef, LeafSearchRequest,
-    LeafSearchResponse, PartialHit, SearchPlanResponse, SearchRequest, SearchResponse,
-    SnippetRequest, SortDatetimeFormat, SortField, SortValue, SplitIdAndFooterOffsets,
+    LeafSearchResponse, NodeSearchProfile, PartialHit, SearchPlanResponse, SearchProfile,
+    SearchRequest, SearchResponse, SnippetRequest, SortDatetimeFormat, SortField, SortValue,
+    SplitIdAndFooterOffsets, SplitSearchProfile,
 };
 use quickwit_proto::types::{IndexUid, SplitId};
 use quickwit_query::query_ast::{
-    BoolQuery, QueryAst, QueryAstVisitor, RangeQuery, TermQuery, TermSetQuery,
+    is_date_field, BoolQuery, DateMathResolver, FieldPresenceQuery, FullTextQuery, QueryAst,
+    QueryAstTransformer, QueryAstVisitor, RangeQuery, TermQuery, TermSetQuery,
 };
 use serde::{Deserialize, Serialize};
 use tantivy::aggregation::agg_result::AggregationResults;
 use tantivy::aggregation::intermediate_agg_result::IntermediateAggregationResults;
 use tantivy::collector::Collector;
 use tantivy::schema::{Field, FieldEntry, FieldType, Schema};
+use tantivy::time::OffsetDateTime;
 use tantivy::TantivyError;
 use tracing::{debug, info_span, instrument};
 
@@ -51,10 +58,17 @@ use crate::cluster_client::ClusterClient;
 use crate::collector::{make_merge_collector, QuickwitAggregations};
 use crate::find_trace_ids_collector::Span;
 use crate::metrics::SEARCH_METRICS;
+use crate::rate_aggregation::RateAggregations;
 use crate::scroll_context::{ScrollContext, ScrollKeyAndStartOffset};
 use crate::search_job_placer::{group_by, group_jobs_by_index_id, Job};
 use crate::search_response_rest::StorageRequestCount;
+use crate::security::apply_security_policies;
 use crate::service::SearcherContext;
+use crate::split_prefetch::spawn_adjacent_splits_prefetch;
+use crate::tag_overlay::{
+    add_tag_overlay_named_queries, apply_tag_overlays, apply_tag_overlays_to_hit,
+    validate_tag_overlay_fast_fields,
+};
 use crate::{
     extract_split_and_footer_offsets, list_relevant_splits, SearchError, SearchJobPlacer,
     SearchPlanResponseRest, SearchServiceClient,
@@ -112,7 +126,7 @@ impl<'a> From<&'a SplitMetadata> for SearchJob {
     fn from(split_metadata: &'a SplitMetadata) -> Self {
         SearchJob {
             index_uid: split_metadata.index_uid.clone(),
-            cost: compute_split_cost(split_metadata),
+            cost: compute_split_cost(split_metadata.num_docs as u64),
             offsets: extract_split_and_footer_offsets(split_metadata),
         }
     }
@@ -155,8 +169,14 @@ impl From<FetchDocsJob> for SplitIdAndFooterOffsets {
 pub struct IndexMetasForLeafSearch {
     /// Index URI.
     pub index_uri: Uri,
+    /// Name of the storage credentials used to access the index storage.
+    #[serde(default)]
+    pub storage_credentials_opt: Option<String>,
     /// Doc mapper json string.
     pub doc_mapper_str: String,
+    /// Tag overlays of the index, applied to the source of the fetched documents.
+    #[serde(default)]
+    pub tag_overlays: Vec<TagOverlay>,
 }
 
 pub(crate) type IndexesMetasForLeafSearch = HashMap<IndexUid, IndexMetasForLeafSearch>;
@@ -170,7 +190,8 @@ struct RequestMetadata {
 }
 
 /// Validates request against each index's doc mapper and ensures that:
-/// - timestamp fields (if any) are equal across indexes.
+/// - timestamp fields (if any) are equal across indexes, unless the query targets the logical
+///   `@timestamp` field, which each index resolves to its own timestamp field.
 /// - resolved query ASTs are the same across indexes.
 /// - if a sort field is of type datetime, it must be a datetime field on all indexes. This
 ///   constraint come from the need to support datetime formatting on sort values.
@@ -193,7 +214,10 @@ fn validate_request_and_build_metadata(
         HashMap::new();
     let mut query_ast_resolved_opt: Option<QueryAst> = None;
     let mut timestamp_field_opt: Option<String> = None;
+    let mut timestamp_fields_differ = false;
+    let mut logical_timestamp_field_supported = true;
     let mut sort_fields_is_datetime: HashMap<String, bool> = HashMap::new();
+    let mut schemas: Vec<Schema> = Vec::with_capacity(indexes_metadata.len());
 
     for index_metadata in indexes_metadata {
         let doc_mapper = build_doc_mapper(
@@ -205,16 +229,18 @@ fn validate_request_and_build_metadata(
         })?;
         let query_ast_resolved_for_index = query_ast
             .clone()
-            .parse_user_query(doc_mapper.default_search_fields())
+            .parse_user_query_with_defaults(doc_mapper.user_query_defaults())
             // We convert the error to return a 400 to the user (and not a 500).
             .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
+        let query_ast_resolved_for_index =
+            apply_tag_overlays(query_ast_resolved_for_index, &index_metadata.tag_overlays);
 
         // Validate uniqueness of resolved query AST.
         if let Some(query_ast_resolved) = &query_ast_resolved_opt {
             if query_ast_resolved != &query_ast_resolved_for_index {
                 return Err(SearchError::InvalidQuery(
                     "resolved query ASTs must be the same across indexes. resolving queries with \
-                     different default fields are different between indexes is not supported"
+                     different default fields or tag overlays between indexes is not supported"
                         .to_string(),
                 ));
             }
@@ -222,14 +248,11 @@ fn validate_request_and_build_metadata(
             query_ast_resolved_opt = Some(query_ast_resolved_for_index.clone());
         }
 
-        // Validate uniqueness of timestamp field if any.
+        // Record whether the timestamp fields differ across indexes.
         if let Some(timestamp_field_for_index) = doc_mapper.timestamp_field_name() {
-            match timestamp_field_opt {
+            match &timestamp_field_opt {
                 Some(timestamp_field) if timestamp_field != timestamp_field_for_index => {
-                    return Err(SearchError::InvalidQuery(
-                        "the timestamp field (if present) must be the same for all indexes"
-                            .to_string(),
-                    ));
+                    timestamp_fields_differ = true;
                 }
                 None => {
                     timestamp_field_opt = Some(timestamp_field_for_index.to_string());
@@ -237,10 +260,22 @@ fn validate_request_and_build_metadata(
                 _ => {}
             }
         }
+        if doc_mapper.logical_timestamp_field_name().is_none() {
+            logical_timestamp_field_supported = false;
+        }
 
         // Validate request against the current index schema.
         let schema = doc_mapper.schema();
-        validate_request(&schema, &doc_mapper.timestamp_field_name(), search_request)?;
+        validate_request(
+            &schema,
+            &doc_mapper.timestamp_field_name(),
+            index_metadata
+                .index_config
+                .search_settings
+                .max_result_window,
+            search_request,
+        )?;
+        validate_tag_overlay_fast_fields(search_request, &index_metadata.tag_overlays)?;
 
         validate_sort_field_types(
             &schema,
@@ -250,12 +285,15 @@ fn validate_request_and_build_metadata(
 
         // Validates the query by effectively building it against the current schema.
         doc_mapper.query(doc_mapper.schema(), &query_ast_resolved_for_index, true)?;
+        schemas.push(schema);
 
         let index_metadata_for_leaf_search = IndexMetasForLeafSearch {
             index_uri: index_metadata.index_uri().clone(),
+            storage_credentials_opt: index_metadata.index_config.storage_credentials_opt.clone(),
             doc_mapper_str: serde_json::to_string(&doc_mapper).map_err(|err| {
                 SearchError::Internal(format!("failed to serialize doc mapper. cause: {err}"))
             })?,
+            tag_overlays: index_metadata.tag_overlays.clone(),
         };
         indexes_meta_for_leaf_search.insert(
             index_metadata.index_uid.clone(),
@@ -268,6 +306,30 @@ fn validate_request_and_build_metadata(
             "resolved query AST must be present. this should never happen".to_string(),
         )
     })?;
+    // Date math bounds are resolved once here so that all the splits share the same `now`.
+    let mut date_math_resolver = DateMathResolver {
+        is_date_field: |field_path: &str| {
+            (logical_timestamp_field_supported && field_path == LOGICAL_TIMESTAMP_FIELD_NAME)
+                || schemas
+                    .iter()
+                    .any(|schema| is_date_field(field_path, schema))
+        },
+        now: OffsetDateTime::now_utc(),
+    };
+    let query_ast_resolved = date_math_resolver
+        .transform(query_ast_resolved)
+        .map_err(|err| SearchError::InvalidQuery(err.to_string()))?
+        .unwrap_or(QueryAst::MatchAll);
+
+    // The time range of a query targeting the logical `@timestamp` field applies to the
+    // timestamp field of each index, so it can be used to prune the splits of all of them.
+    if logical_timestamp_field_supported && targets_logical_timestamp_field(&query_ast_resolved) {
+        timestamp_field_opt = Some(LOGICAL_TIMESTAMP_FIELD_NAME.to_string());
+    } else if timestamp_fields_differ {
+        return Err(SearchError::InvalidQuery(
+            "the timestamp field (if present) must be the same for all indexes".to_string(),
+        ));
+    }
 
     Ok(RequestMetadata {
         timestamp_field_opt,
@@ -366,12 +428,21 @@ fn simplify_search_request_for_scroll_api(req: &SearchRequest) -> crate::Result<
         // request is simplified after initial query, and we cache the hit count, so we don't need
         // to recompute it afterward.
         count_hits: quickwit_proto::search::CountHits::Underestimate as i32,
+        security_context: req.security_context.clone(),
+        // Profiling is only supported for the initial request.
+        profile: false,
+        return_intermediate_aggregation: false,
+        docvalue_fields: req.docvalue_fields.clone(),
+        // Scroll requests cannot be cancelled.
+        search_request_id: None,
     })
 }
 
 /// Validates sort fields and search after values.
 /// - validate sort fields length.
 /// - search after values must be set for all sort fields.
+/// - with search after, sort fields must be unique and `_doc` must be the last one.
+/// - the search after value of `_score` must be a float.
 fn validate_sort_by_fields_and_search_after(
     sort_fields: &[SortField],
     search_after: &Option<PartialHit>,
@@ -388,6 +459,22 @@ fn validate_sort_by_fields_and_search_after(
     let Some(search_after_partial_hit) = search_after.as_ref() else {
         return Ok(());
     };
+    if sort_fields.len() == 2 && sort_fields[0].field_name == sort_fields[1].field_name {
+        return Err(SearchError::InvalidArgument(format!(
+            "search_after requires distinct sort fields, but `{}` is used twice",
+            sort_fields[0].field_name
+        )));
+    }
+    if let Some(doc_sort_field_pos) = sort_fields
+        .iter()
+        .position(|sort_field| SORT_DOC_FIELD_NAMES.contains(&sort_field.field_name.as_str()))
+    {
+        if doc_sort_field_pos != sort_fields.len() - 1 {
+            return Err(SearchError::InvalidArgument(
+                "search_after requires the sort field `_doc` to be the last sort field".to_string(),
+            ));
+        }
+    }
 
     let sort_fields_without_doc_count = sort_fields
         .iter()
@@ -403,8 +490,8 @@ fn validate_sort_by_fields_and_search_after(
     }
 
     let mut search_after_sort_value_count = 0;
-    // TODO: we could validate if the search after sort value types of consistent with the sort
-    // field types.
+    // Numeric sort values are converted to the type of the sort field by the leaves, so only the
+    // score requires a specific type.
     if let Some(sort_by_value) = search_after_partial_hit.sort_value.as_ref() {
         sort_by_value.sort_value.context("sort value must be set")?;
         search_after_sort_value_count += 1;
@@ -415,6 +502,23 @@ fn validate_sort_by_fields_and_search_after(
             .context("sort value must be set")?;
         search_after_sort_value_count += 1;
     }
+    let search_after_sort_values = [
+        search_after_partial_hit.sort_value.as_ref(),
+        search_after_partial_hit.sort_value2.as_ref(),
+    ];
+    for (sort_field, sort_by_value_opt) in sort_fields.iter().zip(search_after_sort_values) {
+        if sort_field.field_name != "_score" {
+            continue;
+        }
+        if !matches!(
+            sort_by_value_opt.and_then(|sort_by_value| sort_by_value.sort_value),
+            None | Some(SortValue::F64(_))
+        ) {
+            return Err(SearchError::InvalidArgument(
+                "search_after value for the sort field `_score` must be a float".to_string(),
+            ));
+        }
+    }
     if search_after_sort_value_count != sort_fields_without_doc_count {
         return Err(SearchError::InvalidArgument(format!(
             "`search_after` must have the same number of sort values as sort by fields {:?}",
@@ -494,6 +598,7 @@ fn check_is_fast_field(
 fn validate_request(
     schema: &Schema,
     timestamp_field_name: &Option<&str>,
+    max_result_window: u64,
     search_request: &SearchRequest,
 ) -> crate::Result<()> {
     if timestamp_field_name.is_none()
@@ -508,6 +613,18 @@ fn validate_request(
 
     validate_requested_snippet_fields(schema, &search_request.snippet_fields)?;
 
+    if !search_request.docvalue_fields.is_empty() {
+        if !search_request.snippet_fields.is_empty() {
+            return Err(SearchError::InvalidArgument(
+                "docvalue fields cannot be combined with snippet fields".to_string(),
+            ));
+        }
+        let dynamic_field = schema.get_field(DYNAMIC_FIELD_NAME).ok();
+        for docvalue_field_name in &search_request.docvalue_fields {
+            check_is_fast_field(schema, docvalue_field_name, dynamic_field)?;
+        }
+    }
+
     if let Some(agg) = search_request.aggregation_request.as_ref() {
         let aggs: QuickwitAggregations = serde_json::from_str(agg).map_err(|_err| {
             let err = serde_json::from_str::<tantivy::aggregation::agg_req::Aggregations>(agg)
@@ -523,16 +640,17 @@ fn validate_request(
         }
     };
 
-    if search_request.start_offset > 10_000 {
+    if search_request.start_offset > max_result_window {
         return Err(SearchError::InvalidArgument(format!(
-            "max value for start_offset is 10_000, but got {}",
+            "max value for start_offset is {max_result_window}, but got {}. use `search_after` to \
+             paginate deeper",
             search_request.start_offset
         )));
     }
 
-    if search_request.max_hits > 10_000 {
+    if search_request.max_hits > max_result_window {
         return Err(SearchError::InvalidArgument(format!(
-            "max value for max_hits is 10_000, but got {}",
+            "max value for max_hits is {max_result_window}, but got {}",
             search_request.max_hits
         )));
     }
@@ -680,6 +798,8 @@ pub fn get_count_from_metadata(split_metadatas: &[SplitMetadata]) -> Vec<LeafSea
             num_successful_splits: 1,
             intermediate_aggregation_result: None,
             resource_stats: None,
+            split_profiles: Vec::new(),
+            intermediate_aggregation_result_codec: PayloadCodec::None as i32,
         })
         .collect()
 }
@@ -804,6 +924,19 @@ pub(crate) fn get_snippet_request(search_request: &SearchRequest) -> Option<Snip
     })
 }
 
+/// Returns the resolved query AST of the request if it contains named clauses, in which case the
+/// names of the clauses matching each hit are reported.
+pub(crate) fn get_named_queries_query_ast(search_request: &SearchRequest) -> Option<String> {
+    let query_ast: QueryAst = serde_json::from_str(&search_request.query_ast).ok()?;
+
+    if query_ast.named_queries().is_empty() {
+        return None;
+    }
+    Some(search_request.query_ast.clone())
+}
+
+/// Fetches the documents of the partial hits. Also returns how long it took to fetch the documents
+/// from each searcher.
 #[instrument(skip_all, fields(partial_hits_num=partial_hits.len()))]
 pub(crate) async fn fetch_docs_phase(
     indexes_metas_for_leaf_search: &IndexesMetasForLeafSearch,
@@ -811,8 +944,9 @@ pub(crate) async fn fetch_docs_phase(
     split_metadatas: &[SplitMetadata],
     search_request: &SearchRequest,
     cluster_client: &ClusterClient,
-) -> crate::Result<Vec<Hit>> {
+) -> crate::Result<(Vec<Hit>, HashMap<SocketAddr, Duration>)> {
     let snippet_request: Option<SnippetRequest> = get_snippet_request(search_request);
+    let named_queries_query_ast_opt: Option<String> = get_named_queries_query_ast(search_request);
     let hit_order: HashMap<(String, u32, u32), usize> = partial_hits
         .iter()
         .enumerate()
@@ -837,14 +971,36 @@ pub(crate) async fn fetch_docs_phase(
     for (client, client_jobs) in assigned_fetch_docs_jobs {
         let fetch_jobs_requests = jobs_to_fetch_docs_requests(
             snippet_request.clone(),
+            &search_request.docvalue_fields,
+            named_queries_query_ast_opt.clone(),
             indexes_metas_for_leaf_search,
             client_jobs,
         )?;
         for fetch_docs_request in fetch_jobs_requests {
-            fetch_docs_tasks.push(cluster_client.fetch_docs(fetch_docs_request, client.clone()));
+            let client = client.clone();
+            fetch_docs_tasks.push(async move {
+                let grpc_addr = client.grpc_addr();
+                let start = Instant::now();
+                let fetch_docs_response = cluster_client
+                    .fetch_docs(fetch_docs_request, client)
+                    .await?;
+                crate::Result::Ok((grpc_addr, start.elapsed(), fetch_docs_response))
+            });
         }
     }
-    let fetch_docs_responses: Vec<FetchDocsResponse> = try_join_all(fetch_docs_tasks).await?;
+    let fetch_docs_results: Vec<(SocketAddr, Duration, FetchDocsResponse)> =
+        try_join_all(fetch_docs_tasks).await?;
+
+    // The requests sent to the same searcher run concurrently.
+    let mut fetch_docs_durations: HashMap<SocketAddr, Duration> = HashMap::new();
+    let mut fetch_docs_responses: Vec<FetchDocsResponse> =
+        Vec::with_capacity(fetch_docs_results.len());
+
+    for (grpc_addr, fetch_docs_duration, fetch_docs_response) in fetch_docs_results {
+        let max_fetch_docs_duration = fetch_docs_durations.entry(grpc_addr).or_default();
+        *max_fetch_docs_duration = (*max_fetch_docs_duration).max(fetch_docs_duration);
+        fetch_docs_responses.push(fetch_docs_response);
+    }
 
     // Merge the fetched docs.
     let leaf_hits = fetch_docs_responses
@@ -880,12 +1036,58 @@ pub(crate) async fn fetch_docs_phase(
         .try_collect()?;
 
     hits_with_position.sort_by_key(|(position, _)| *position);
+
+    let tag_overlays_per_index_id: HashMap<&str, &[TagOverlay]> = indexes_metas_for_leaf_search
+        .iter()
+        .map(|(index_uid, index_meta)| {
+            (
+                index_uid.index_id.as_str(),
+                index_meta.tag_overlays.as_slice(),
+            )
+        })
+        .collect();
     let hits: Vec<Hit> = hits_with_position
         .into_iter()
-        .map(|(_position, hit)| hit)
+        .map(|(_position, mut hit)| {
+            if let Some(tag_overlays) = tag_overlays_per_index_id.get(hit.index_id.as_str()) {
+                apply_tag_overlays_to_hit(&mut hit, tag_overlays);
+            }
+            hit
+        })
         .collect();
 
-    Ok(hits)
+    Ok((hits, fetch_docs_durations))
+}
+
+/// Groups the profiles of the searched splits and the fetch docs durations by searcher.
+fn build_search_profile(
+    split_profiles: Vec<SplitSearchProfile>,
+    fetch_docs_durations: HashMap<SocketAddr, Duration>,
+) -> SearchProfile {
+    let mut node_profiles: BTreeMap<String, NodeSearchProfile> = BTreeMap::new();
+
+    for split_profile in split_profiles {
+        node_profiles
+            .entry(split_profile.searcher_addr.clone())
+            .or_insert_with_key(|searcher_addr| NodeSearchProfile {
+                searcher_addr: searcher_addr.clone(),
+                ..Default::default()
+            })
+            .splits
+            .push(split_profile);
+    }
+    for (grpc_addr, fetch_docs_duration) in fetch_docs_durations {
+        node_profiles
+            .entry(grpc_addr.to_string())
+            .or_insert_with_key(|searcher_addr| NodeSearchProfile {
+                searcher_addr: searcher_addr.clone(),
+                ..Default::default()
+            })
+            .fetch_docs_microsecs = fetch_docs_duration.as_micros() as u64;
+    }
+    SearchProfile {
+        nodes: node_profiles.into_values().collect(),
+    }
 }
 
 fn build_hit_with_position(
@@ -935,6 +1137,7 @@ fn build_hit_with_position(
             partial_hit: leaf_hit.partial_hit,
             snippet: leaf_hit.leaf_snippet_json,
             index_id,
+            matched_queries: leaf_hit.matched_queries,
         },
     ))
 }
@@ -979,7 +1182,7 @@ async fn root_search_aux(
     )
     .await?;
 
-    let hits = fetch_docs_phase(
+    let (hits, fetch_docs_durations) = fetch_docs_phase(
         indexes_metas_for_leaf_search,
         &first_phase_result.partial_hits,
         &split_metadatas[..],
@@ -987,15 +1190,30 @@ async fn root_search_aux(
         cluster_client,
     )
     .await?;
+    let profile_opt = if search_request.profile {
+        Some(build_search_profile(
+            first_phase_result.split_profiles,
+            fetch_docs_durations,
+        ))
+    } else {
+        None
+    };
+
+    let mut aggregation_result_json_opt = None;
+    let mut intermediate_aggregation_result_opt = None;
 
-    let mut aggregation_result_json_opt = finalize_aggregation_if_any(
-        &search_request,
-        first_phase_result.intermediate_aggregation_result,
-        searcher_context,
-    )?;
     // In case there is no index, we don't want the response to contain any aggregation structure
-    if indexes_metas_for_leaf_search.is_empty() {
-        aggregation_result_json_opt = None;
+    if !indexes_metas_for_leaf_search.is_empty() {
+        if search_request.return_intermediate_aggregation {
+            intermediate_aggregation_result_opt =
+                first_phase_result.intermediate_aggregation_result;
+        } else {
+            aggregation_result_json_opt = finalize_aggregation_if_any(
+                &search_request,
+                first_phase_result.intermediate_aggregation_result,
+                searcher_context,
+            )?;
+        }
     }
 
     Ok(SearchResponse {
@@ -1009,6 +1227,9 @@ async fn root_search_aux(
             .map(ToString::to_string),
         failed_splits: first_phase_result.failed_splits,
         num_successful_splits: first_phase_result.num_successful_splits,
+        profile: profile_opt,
+        warnings: Vec::new(),
+        intermediate_aggregation_result: intermediate_aggregation_result_opt,
     })
 }
 
@@ -1048,7 +1269,7 @@ fn finalize_aggregation(
     Ok(Some(merge_aggregation_result))
 }
 
-fn finalize_aggregation_if_any(
+pub(crate) fn finalize_aggregation_if_any(
     search_request: &SearchRequest,
     intermediate_aggregation_result_bytes_opt: Option<Vec<u8>>,
     searcher_context: &SearcherContext,
@@ -1105,6 +1326,27 @@ pub fn check_all_index_metadata_found(
     Ok(())
 }
 
+/// Removes the candidate indexes of the ongoing rollouts matched by a wildcard pattern. A candidate
+/// index receives a copy of the documents of its primary index, so searching both would return
+/// duplicate hits. Candidate indexes can still be searched by targeting their index ID
+/// explicitly.
+pub fn exclude_rollout_candidates(
+    indexes_metadata: &mut Vec<IndexMetadata>,
+    index_id_patterns: &[String],
+) {
+    indexes_metadata.retain(|index_metadata| {
+        let is_rollout_candidate = index_metadata
+            .index_config
+            .rollout_opt
+            .as_ref()
+            .is_some_and(|rollout| rollout.is_evaluating());
+        !is_rollout_candidate
+            || index_id_patterns
+                .iter()
+                .any(|index_id_pattern| index_id_pattern == index_metadata.index_id())
+    });
+}
+
 async fn refine_and_list_matches(
     metastore: &mut MetastoreServiceClient,
     search_request: &mut SearchRequest,
@@ -1112,6 +1354,7 @@ async fn refine_and_list_matches(
     query_ast_resolved: QueryAst,
     sort_fields_is_datetime: HashMap<String, bool>,
     timestamp_field_opt: Option<String>,
+    warnings: &mut Vec<String>,
 ) -> crate::Result<Vec<SplitMetadata>> {
     let index_uids = indexes_metadata
         .iter()
@@ -1130,6 +1373,15 @@ async fn refine_and_list_matches(
             &mut search_request.start_timestamp,
             &mut search_request.end_timestamp,
         );
+        let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+
+        if let Some(warning) = clamp_start_timestamp_to_retention_period(
+            &indexes_metadata,
+            &mut search_request.start_timestamp,
+            now_timestamp,
+        ) {
+            warnings.push(warning);
+        }
     }
     let tag_filter_ast = extract_tags_from_query(query_ast_resolved);
 
@@ -1146,6 +1398,64 @@ async fn refine_and_list_matches(
     Ok(split_metadatas)
 }
 
+/// Clamps the start timestamp of a search request to the retention period of the searched
+/// indexes, provided they all opted in with the `clamp_to_retention_period` search setting. When
+/// the indexes have different retention periods, the longest one is used.
+///
+/// Returns a warning if the time range of the request was narrowed.
+fn clamp_start_timestamp_to_retention_period(
+    indexes_metadata: &[IndexMetadata],
+    start_timestamp: &mut Option<i64>,
+    now_timestamp: i64,
+) -> Option<String> {
+    let mut retention_start_timestamp_opt: Option<i64> = None;
+
+    for index_metadata in indexes_metadata {
+        let index_config = &index_metadata.index_config;
+
+        if !index_config.search_settings.clamp_to_retention_period {
+            return None;
+        }
+        let retention_period = index_config
+            .retention_policy_opt
+            .as_ref()?
+            .retention_period()
+            .ok()?;
+        let index_retention_start_timestamp = now_timestamp - retention_period.as_secs() as i64;
+        let retention_start_timestamp = retention_start_timestamp_opt
+            .unwrap_or(i64::MAX)
+            .min(index_retention_start_timestamp);
+        retention_start_timestamp_opt = Some(retention_start_timestamp);
+    }
+    let retention_start_timestamp = retention_start_timestamp_opt?;
+
+    if let Some(start_timestamp) = *start_timestamp {
+        if start_timestamp >= retention_start_timestamp {
+            return None;
+        }
+    }
+    *start_timestamp = Some(retention_start_timestamp);
+    let warning = format!(
+        "the time range of the search was clamped to the retention period of the searched \
+         indexes: documents older than {retention_start_timestamp} (unix timestamp in seconds) \
+         were ignored"
+    );
+    Some(warning)
+}
+
+/// Returns the usage of a query on each searched index. The bytes read are recorded by the leaves.
+pub(crate) fn query_usage_deltas(
+    index_uids: impl IntoIterator<Item = IndexUid>,
+) -> impl Iterator<Item = (IndexUid, IndexUsage)> {
+    index_uids.into_iter().map(|index_uid| {
+        let usage_delta = IndexUsage {
+            num_queries: 1,
+            ..Default::default()
+        };
+        (index_uid, usage_delta)
+    })
+}
+
 /// Performs a distributed search.
 /// 1. Sends leaf request over gRPC to multiple leaf nodes.
 /// 2. Merges the search results.
@@ -1159,16 +1469,20 @@ pub async fn root_search(
     cluster_client: &ClusterClient,
 ) -> crate::Result<SearchResponse> {
     let start_instant = tokio::time::Instant::now();
+    let rate_aggregations = RateAggregations::extract_from_request(&mut search_request)?;
     let list_indexes_metadatas_request = ListIndexesMetadataRequest {
         index_id_patterns: search_request.index_id_patterns.clone(),
+        include_trashed: false,
+        label_selectors: Vec::new(),
     };
-    let indexes_metadata: Vec<IndexMetadata> = metastore
+    let mut indexes_metadata: Vec<IndexMetadata> = metastore
         .list_indexes_metadata(list_indexes_metadatas_request)
         .await?
         .deserialize_indexes_metadata()
         .await?;
 
     check_all_index_metadata_found(&indexes_metadata[..], &search_request.index_id_patterns[..])?;
+    exclude_rollout_candidates(&mut indexes_metadata, &search_request.index_id_patterns);
 
     if indexes_metadata.is_empty() {
         // We go through root_search_aux instead of directly
@@ -1186,7 +1500,13 @@ pub async fn root_search(
         return Ok(search_response);
     }
 
+    let retrievable_fields = apply_security_policies(&indexes_metadata, &mut search_request)?;
     let request_metadata = validate_request_and_build_metadata(&indexes_metadata, &search_request)?;
+    let index_uids: Vec<IndexUid> = indexes_metadata
+        .iter()
+        .map(|index_metadata| index_metadata.index_uid.clone())
+        .collect();
+    let mut warnings = Vec::new();
     let split_metadatas = refine_and_list_matches(
         &mut metastore,
         &mut search_request,
@@ -1194,6 +1514,7 @@ pub async fn root_search(
         request_metadata.query_ast_resolved,
         request_metadata.sort_fields_is_datetime,
         request_metadata.timestamp_field_opt,
+        &mut warnings,
     )
     .await?;
 
@@ -1203,6 +1524,21 @@ pub async fn root_search(
     current_span.record("num_docs", num_docs);
     current_span.record("num_splits", num_splits);
 
+    let prefetch_adjacent_splits = searcher_context
+        .searcher_config
+        .split_cache
+        .as_ref()
+        .is_some_and(|split_cache_limits| split_cache_limits.prefetch_adjacent_splits);
+    if prefetch_adjacent_splits {
+        spawn_adjacent_splits_prefetch(
+            &search_request,
+            &split_metadatas,
+            &request_metadata.indexes_meta_for_leaf_search,
+            metastore.clone(),
+            cluster_client.search_job_placer.clone(),
+        );
+    }
+
     let mut search_response_result = root_search_aux(
         searcher_context,
         &request_metadata.indexes_meta_for_leaf_search,
@@ -1210,12 +1546,21 @@ pub async fn root_search(
         split_metadatas,
         cluster_client,
     )
-    .await;
+    .await
+    .and_then(|mut search_response| {
+        rate_aggregations.apply(&mut search_response)?;
+        Ok(search_response)
+    });
 
     let elapsed = start_instant.elapsed();
 
     if let Ok(search_response) = &mut search_response_result {
+        retrievable_fields.restrict_search_response(search_response);
         search_response.elapsed_time_micros = elapsed.as_micros() as u64;
+        search_response.warnings = warnings;
+        searcher_context
+            .index_usage_accumulator
+            .record(query_usage_deltas(index_uids));
     }
 
     let label_values = if search_response_result.is_ok() {
@@ -1246,14 +1591,18 @@ pub async fn search_plan(
 ) -> crate::Result<SearchPlanResponse> {
     let list_indexes_metadatas_request = ListIndexesMetadataRequest {
         index_id_patterns: search_request.index_id_patterns.clone(),
+        include_trashed: false,
+        label_selectors: Vec::new(),
     };
-    let indexes_metadata: Vec<IndexMetadata> = metastore
+    let mut indexes_metadata: Vec<IndexMetadata> = metastore
         .list_indexes_metadata(list_indexes_metadatas_request)
         .await?
         .deserialize_indexes_metadata()
         .await?;
 
     check_all_index_metadata_found(&indexes_metadata[..], &search_request.index_id_patterns[..])?;
+    exclude_rollout_candidates(&mut indexes_metadata, &search_request.index_id_patterns);
+    RateAggregations::extract_from_request(&mut search_request)?;
     if indexes_metadata.is_empty() {
         return Ok(SearchPlanResponse {
             result: serde_json::to_string(&SearchPlanResponseRest {
@@ -1270,6 +1619,7 @@ pub async fn search_plan(
     )
     .map_err(|err| SearchError::Internal(format!("failed to build doc mapper. cause: {err}")))?;
 
+    apply_security_policies(&indexes_metadata, &mut search_request)?;
     let request_metadata = validate_request_and_build_metadata(&indexes_metadata, &search_request)?;
     let split_metadatas = refine_and_list_matches(
         &mut metastore,
@@ -1278,6 +1628,7 @@ pub async fn search_plan(
         request_metadata.query_ast_resolved.clone(),
         request_metadata.sort_fields_is_datetime,
         request_metadata.timestamp_field_opt,
+        &mut Vec::new(),
     )
     .await?;
 
@@ -1601,6 +1952,61 @@ impl<'b> QueryAstVisitor<'b> for ExtractTimestampRange<'_> {
     }
 }
 
+/// Returns whether the query AST targets the logical `@timestamp` field.
+fn targets_logical_timestamp_field(query_ast: &QueryAst) -> bool {
+    let mut logical_timestamp_field_finder = LogicalTimestampFieldFinder { found: false };
+    logical_timestamp_field_finder
+        .visit(query_ast)
+        .expect("can't fail unwrapping Infallible");
+    logical_timestamp_field_finder.found
+}
+
+struct LogicalTimestampFieldFinder {
+    found: bool,
+}
+
+impl LogicalTimestampFieldFinder {
+    fn check_field(&mut self, field: &str) {
+        if field == LOGICAL_TIMESTAMP_FIELD_NAME {
+            self.found = true;
+        }
+    }
+}
+
+impl<'a> QueryAstVisitor<'a> for LogicalTimestampFieldFinder {
+    type Err = std::convert::Infallible;
+
+    fn visit_term(&mut self, term_query: &'a TermQuery) -> Result<(), Self::Err> {
+        self.check_field(&term_query.field);
+        Ok(())
+    }
+
+    fn visit_term_set(&mut self, term_set_query: &'a TermSetQuery) -> Result<(), Self::Err> {
+        if term_set_query
+            .terms_per_field
+            .contains_key(LOGICAL_TIMESTAMP_FIELD_NAME)
+        {
+            self.found = true;
+        }
+        Ok(())
+    }
+
+    fn visit_full_text(&mut self, full_text_query: &'a FullTextQuery) -> Result<(), Self::Err> {
+        self.check_field(&full_text_query.field);
+        Ok(())
+    }
+
+    fn visit_range(&mut self, range_query: &'a RangeQuery) -> Result<(), Self::Err> {
+        self.check_field(&range_query.field);
+        Ok(())
+    }
+
+    fn visit_exists(&mut self, exists_query: &'a FieldPresenceQuery) -> Result<(), Self::Err> {
+        self.check_field(&exists_query.field);
+        Ok(())
+    }
+}
+
 async fn assign_client_fetch_docs_jobs(
     partial_hits: &[PartialHit],
     split_metadatas: &[SplitMetadata],
@@ -1654,11 +2060,11 @@ async fn assign_client_fetch_docs_jobs(
     Ok(assigned_jobs)
 }
 
-// Measure the cost associated to searching in a given split metadata.
-fn compute_split_cost(split_metadata: &SplitMetadata) -> usize {
+// Measure the cost associated to searching in a split with the given number of documents.
+pub(crate) fn compute_split_cost(num_docs: u64) -> usize {
     // TODO this formula could be tuned a lot more. The general idea is that there is a fixed
     // cost to searching a split, plus a somewhat-linear cost depending on the size of the split
-    5 + split_metadata.num_docs / 100_000
+    5 + num_docs as usize / 100_000
 }
 
 /// Builds a LeafSearchRequest to one node, from a list of [`SearchJob`].
@@ -1677,6 +2083,8 @@ pub fn jobs_to_leaf_request(
         leaf_requests: Vec::new(),
         doc_mappers: Vec::new(),
         index_uris: Vec::new(),
+        // The search client decompresses the leaf responses.
+        accepts_compressed_intermediate_aggregation_result: true,
     };
 
     let mut added_doc_mappers: HashMap<&str, u32> = HashMap::new();
@@ -1709,9 +2117,11 @@ pub fn jobs_to_leaf_request(
             .push(search_index_meta.index_uri.to_string());
 
         let leaf_search_request_ref = LeafRequestRef {
+            index_uid: index_uid.to_string(),
             split_offsets: job_group.into_iter().map(|job| job.offsets).collect(),
             doc_mapper_ord,
             index_uri_ord,
+            storage_credentials: search_index_meta.storage_credentials_opt.clone(),
         };
         leaf_search_request
             .leaf_requests
@@ -1724,6 +2134,8 @@ pub fn jobs_to_leaf_request(
 /// Builds a list of [`FetchDocsRequest`], one per index, from a list of [`FetchDocsJob`].
 pub fn jobs_to_fetch_docs_requests(
     snippet_request_opt: Option<SnippetRequest>,
+    docvalue_fields: &[String],
+    named_queries_query_ast_opt: Option<String>,
     indexes_metas_for_leaf_search: &IndexesMetasForLeafSearch,
     jobs: Vec<FetchDocsJob>,
 ) -> crate::Result<Vec<FetchDocsRequest>> {
@@ -1750,12 +2162,19 @@ pub fn jobs_to_fetch_docs_requests(
                 .into_iter()
                 .map(|fetch_doc_job| fetch_doc_job.into())
                 .collect();
+            let named_queries_query_ast = add_tag_overlay_named_queries(
+                named_queries_query_ast_opt.as_deref(),
+                &index_meta.tag_overlays,
+            )?;
             let fetch_docs_req = FetchDocsRequest {
                 partial_hits,
                 split_offsets,
                 index_uri: index_meta.index_uri.to_string(),
                 snippet_request: snippet_request_opt.clone(),
                 doc_mapper: index_meta.doc_mapper_str.clone(),
+                docvalue_fields: docvalue_fields.to_vec(),
+                named_queries_query_ast,
+                storage_credentials: index_meta.storage_credentials_opt.clone(),
             };
             fetch_docs_requests.push(fetch_docs_req);
 
@@ -1767,13 +2186,16 @@ pub fn jobs_to_fetch_docs_requests(
 
 #[cfg(test)]
 mod tests {
-    use std::ops::Range;
+    use std::ops::{Bound, Range};
     use std::str::FromStr;
     use std::sync::{Arc, RwLock};
 
     use quickwit_common::shared_consts::SCROLL_BATCH_LEN;
     use quickwit_common::ServiceStream;
-    use quickwit_config::{DocMapping, IndexConfig, IndexingSettings, SearchSettings};
+    use quickwit_config::{
+        DocMapping, IndexConfig, IndexRollout, IndexingSettings, RetentionPolicy, RolloutState,
+        SearchSettings,
+    };
     use quickwit_indexing::MockSplitBuilder;
     use quickwit_metastore::{IndexMetadata, ListSplitsRequestExt, ListSplitsResponseExt};
     use quickwit_proto::metastore::{
@@ -1783,11 +2205,60 @@ mod tests {
         ScrollRequest, SortByValue, SortOrder, SortValue, SplitSearchError,
     };
     use quickwit_query::query_ast::{qast_helper, qast_json_helper, query_ast_from_user_text};
+    use quickwit_query::JsonLiteral;
     use tantivy::schema::{FAST, STORED, TEXT};
 
     use super::*;
     use crate::{searcher_pool_for_test, MockSearchService};
 
+    #[test]
+    fn test_exclude_rollout_candidates() {
+        let candidate_index_metadata = |candidate_index_id: &str, state: RolloutState| {
+            let mut index_metadata = IndexMetadata::for_test(
+                candidate_index_id,
+                &format!("ram:///{candidate_index_id}"),
+            );
+            index_metadata.index_config.rollout_opt = Some(IndexRollout {
+                primary_index_id: "hdfs".to_string(),
+                ingest_percentage: 50.0,
+                search_percentage: 0.0,
+                evaluation_period: IndexRollout::default_evaluation_period(),
+                min_num_docs: 0,
+                max_storage_ratio: None,
+                max_scanned_bytes_ratio: None,
+                state,
+            });
+            index_metadata
+        };
+        let indexes_metadata = vec![
+            IndexMetadata::for_test("hdfs", "ram:///hdfs"),
+            candidate_index_metadata("hdfs-candidate", RolloutState::Evaluating),
+            candidate_index_metadata("hdfs-promoted", RolloutState::Promoted),
+        ];
+        let index_ids = |indexes_metadata: &[IndexMetadata]| {
+            indexes_metadata
+                .iter()
+                .map(|index_metadata| index_metadata.index_id().to_string())
+                .collect::<Vec<_>>()
+        };
+        let mut wildcard_indexes_metadata = indexes_metadata.clone();
+        exclude_rollout_candidates(&mut wildcard_indexes_metadata, &["hdfs*".to_string()]);
+        assert_eq!(
+            index_ids(&wildcard_indexes_metadata),
+            ["hdfs", "hdfs-promoted"]
+        );
+
+        let mut explicit_indexes_metadata = indexes_metadata;
+        exclude_rollout_candidates(
+            &mut explicit_indexes_metadata,
+            &["hdfs".to_string(), "hdfs-candidate".to_string()],
+        );
+        assert_eq!(
+            index_ids(&explicit_indexes_metadata),
+            ["hdfs", "hdfs-candidate", "hdfs-promoted"]
+        );
+    }
+
     #[track_caller]
     fn check_snippet_fields_validation(snippet_fields: &[String]) -> anyhow::Result<()> {
         let mut schema_builder = Schema::builder();
@@ -1866,6 +2337,11 @@ mod tests {
         let indexing_settings = IndexingSettings::default();
         let search_settings = SearchSettings {
             default_search_fields: vec!["body".to_string()],
+            default_operator: None,
+            lenient: false,
+            security_policies: Vec::new(),
+            clamp_to_retention_period: false,
+            max_result_window: 10_000,
         };
         IndexMetadata::new(IndexConfig {
             index_id: index_id.to_string(),
@@ -1874,6 +2350,10 @@ mod tests {
             indexing_settings,
             search_settings,
             retention_policy_opt: Default::default(),
+            rollout_opt: None,
+            ingestion_freeze_opt: None,
+            storage_credentials_opt: None,
+            labels: Default::default(),
         })
     }
 
@@ -1978,6 +2458,99 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_request_and_build_metadatas_with_logical_timestamp_field() {
+        let query_ast: QueryAst = RangeQuery {
+            field: "@timestamp".to_string(),
+            lower_bound: Bound::Included(JsonLiteral::String("2024-01-01T00:00:00Z".to_string())),
+            upper_bound: Bound::Unbounded,
+        }
+        .into();
+        let search_request = quickwit_proto::search::SearchRequest {
+            index_id_patterns: vec!["test-index".to_string()],
+            query_ast: serde_json::to_string(&query_ast).unwrap(),
+            max_hits: 10,
+            ..Default::default()
+        };
+        let index_metadata_1 = IndexMetadata::for_test("test-index-1", "ram:///test-index-1");
+        let mut index_metadata_2 = IndexMetadata::for_test("test-index-2", "ram:///test-index-2");
+        let doc_mapping_json_2 = r#"{
+            "mode": "lenient",
+            "field_mappings": [
+                {
+                    "name": "timestamp-2",
+                    "type": "datetime",
+                    "fast": true
+                },
+                {
+                    "name": "body",
+                    "type": "text"
+                }
+            ],
+            "timestamp_field": "timestamp-2",
+            "store_source": true
+        }"#;
+        let doc_mapping_2: DocMapping = serde_json::from_str(doc_mapping_json_2).unwrap();
+        index_metadata_2.index_config.doc_mapping = doc_mapping_2;
+        index_metadata_2
+            .index_config
+            .search_settings
+            .default_search_fields = Vec::new();
+        let request_metadata = validate_request_and_build_metadata(
+            &[index_metadata_1.clone(), index_metadata_2],
+            &search_request,
+        )
+        .unwrap();
+        assert_eq!(
+            request_metadata.timestamp_field_opt.as_deref(),
+            Some(LOGICAL_TIMESTAMP_FIELD_NAME)
+        );
+        let mut start_timestamp = None;
+        let mut end_timestamp = None;
+        refine_start_end_timestamp_from_ast(
+            &request_metadata.query_ast_resolved,
+            LOGICAL_TIMESTAMP_FIELD_NAME,
+            &mut start_timestamp,
+            &mut end_timestamp,
+        );
+        assert_eq!(start_timestamp, Some(1_704_067_200));
+        assert!(end_timestamp.is_none());
+
+        // The logical field cannot be resolved if an index defines an `@timestamp` field.
+        let mut index_metadata_3 = IndexMetadata::for_test("test-index-3", "ram:///test-index-3");
+        let doc_mapping_json_3 = r#"{
+            "mode": "lenient",
+            "field_mappings": [
+                {
+                    "name": "timestamp-3",
+                    "type": "datetime",
+                    "fast": true
+                },
+                {
+                    "name": "@timestamp",
+                    "type": "datetime",
+                    "fast": true
+                }
+            ],
+            "timestamp_field": "timestamp-3"
+        }"#;
+        let doc_mapping_3: DocMapping = serde_json::from_str(doc_mapping_json_3).unwrap();
+        index_metadata_3.index_config.doc_mapping = doc_mapping_3;
+        index_metadata_3
+            .index_config
+            .search_settings
+            .default_search_fields = Vec::new();
+        let timestamp_field_different = validate_request_and_build_metadata(
+            &[index_metadata_1, index_metadata_3],
+            &search_request,
+        )
+        .unwrap_err();
+        assert_eq!(
+            timestamp_field_different.to_string(),
+            "the timestamp field (if present) must be the same for all indexes"
+        );
+    }
+
     #[test]
     fn test_validate_request_and_build_metadatas_fail_with_different_resolved_qast() {
         let qast = query_ast_from_user_text("test", None);
@@ -2002,7 +2575,7 @@ mod tests {
         assert_eq!(
             timestamp_field_different.to_string(),
             "resolved query ASTs must be the same across indexes. resolving queries with \
-             different default fields are different between indexes is not supported"
+             different default fields or tag overlays between indexes is not supported"
         );
     }
 
@@ -2038,6 +2611,11 @@ mod tests {
         let indexing_settings = IndexingSettings::default();
         let search_settings = SearchSettings {
             default_search_fields: vec!["body".to_string()],
+            default_operator: None,
+            lenient: false,
+            security_policies: Vec::new(),
+            clamp_to_retention_period: false,
+            max_result_window: 10_000,
         };
         IndexMetadata::new(IndexConfig {
             index_id: index_id.to_string(),
@@ -2046,6 +2624,10 @@ mod tests {
             indexing_settings,
             search_settings,
             retention_policy_opt: Default::default(),
+            rollout_opt: None,
+            ingestion_freeze_opt: None,
+            storage_credentials_opt: None,
+            labels: Default::default(),
         })
     }
 
@@ -2081,6 +2663,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_clamp_start_timestamp_to_retention_period() {
+        fn index_metadata_with_retention(
+            index_id: &str,
+            retention_period_opt: Option<&str>,
+            clamp_to_retention_period: bool,
+        ) -> IndexMetadata {
+            let mut index_metadata =
+                IndexMetadata::for_test(index_id, &format!("ram:///indexes/{index_id}"));
+            index_metadata.index_config.retention_policy_opt =
+                retention_period_opt.map(|retention_period| RetentionPolicy {
+                    retention_period: retention_period.to_string(),
+                    evaluation_schedule: "hourly".to_string(),
+                });
+            index_metadata
+                .index_config
+                .search_settings
+                .clamp_to_retention_period = clamp_to_retention_period;
+            index_metadata
+        }
+        let now_timestamp = 1_000_000;
+        let one_hour_index = index_metadata_with_retention("one-hour", Some("1 hour"), true);
+        let two_hours_index = index_metadata_with_retention("two-hours", Some("2 hours"), true);
+
+        let mut start_timestamp = None;
+        let warning = clamp_start_timestamp_to_retention_period(
+            &[one_hour_index.clone()],
+            &mut start_timestamp,
+            now_timestamp,
+        )
+        .unwrap();
+        assert_eq!(start_timestamp, Some(now_timestamp - 3_600));
+        assert!(warning.contains("clamped to the retention period"));
+
+        // The longest retention period wins.
+        let mut start_timestamp = Some(0);
+        clamp_start_timestamp_to_retention_period(
+            &[one_hour_index.clone(), two_hours_index],
+            &mut start_timestamp,
+            now_timestamp,
+        )
+        .unwrap();
+        assert_eq!(start_timestamp, Some(now_timestamp - 7_200));
+
+        // Time ranges within the retention period are left untouched.
+        let mut start_timestamp = Some(now_timestamp - 60);
+        assert!(clamp_start_timestamp_to_retention_period(
+            &[one_hour_index.clone()],
+            &mut start_timestamp,
+            now_timestamp,
+        )
+        .is_none());
+        assert_eq!(start_timestamp, Some(now_timestamp - 60));
+
+        // All the indexes must opt in and have a retention policy.
+        let opted_out_index = index_metadata_with_retention("opted-out", Some("1 hour"), false);
+        let no_retention_index = index_metadata_with_retention("no-retention", None, true);
+
+        for index_metadata in [opted_out_index, no_retention_index] {
+            let mut start_timestamp = None;
+            assert!(clamp_start_timestamp_to_retention_period(
+                &[one_hour_index.clone(), index_metadata],
+                &mut start_timestamp,
+                now_timestamp,
+            )
+            .is_none());
+            assert!(start_timestamp.is_none());
+        }
+    }
+
     #[test]
     fn test_convert_sort_datetime_value() {
         let mut sort_value = SortValue::U64(1617000000000000000);
@@ -2450,6 +3102,98 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_sort_by_fields_and_search_after_strict() {
+        let sort_field = |field_name: &str| SortField {
+            field_name: field_name.to_string(),
+            sort_order: 0,
+            sort_datetime_format: None,
+        };
+        let partial_hit = |sort_value: SortValue| PartialHit {
+            sort_value: Some(SortByValue {
+                sort_value: Some(sort_value),
+            }),
+            sort_value2: Some(SortByValue {
+                sort_value: Some(SortValue::U64(2)),
+            }),
+            split_id: "split1".to_string(),
+            segment_ord: 1,
+            doc_id: 1,
+        };
+        let error = validate_sort_by_fields_and_search_after(
+            &[sort_field("id"), sort_field("id")],
+            &Some(partial_hit(SortValue::U64(1))),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid argument: search_after requires distinct sort fields, but `id` is used twice"
+        );
+        // Sorting twice on the same field is fine without search_after.
+        validate_sort_by_fields_and_search_after(&[sort_field("id"), sort_field("id")], &None)
+            .unwrap();
+
+        let error = validate_sort_by_fields_and_search_after(
+            &[sort_field("_doc"), sort_field("id")],
+            &Some(partial_hit(SortValue::U64(1))),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid argument: search_after requires the sort field `_doc` to be the last sort \
+             field"
+        );
+
+        let error = validate_sort_by_fields_and_search_after(
+            &[sort_field("_score"), sort_field("id")],
+            &Some(partial_hit(SortValue::U64(1))),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid argument: search_after value for the sort field `_score` must be a float"
+        );
+        validate_sort_by_fields_and_search_after(
+            &[sort_field("_score"), sort_field("id")],
+            &Some(partial_hit(SortValue::F64(1.5))),
+        )
+        .unwrap();
+        // Numeric values are converted to the type of the sort field.
+        validate_sort_by_fields_and_search_after(
+            &[sort_field("timestamp"), sort_field("id")],
+            &Some(partial_hit(SortValue::F64(1.5))),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_validate_request_max_result_window() {
+        let schema = Schema::builder().build();
+        let search_request = SearchRequest {
+            start_offset: 20_000,
+            max_hits: 20,
+            ..Default::default()
+        };
+        validate_request(&schema, &None, 50_000, &search_request).unwrap();
+
+        let error = validate_request(&schema, &None, 10_000, &search_request).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid argument: max value for start_offset is 10000, but got 20000. use \
+             `search_after` to paginate deeper"
+        );
+
+        let search_request = SearchRequest {
+            max_hits: 50_001,
+            ..Default::default()
+        };
+        let error = validate_request(&schema, &None, 50_000, &search_request).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid argument: max value for max_hits is 50000, but got 50001"
+        );
+    }
+
     #[test]
     fn test_validate_sort_by_field_type_invalid() {
         // sort non-datetime field with a datetime format is invalid.
@@ -2535,6 +3279,7 @@ mod tests {
                 .expect("Json serialization should not fail"),
                 partial_hit: Some(req),
                 leaf_snippet_json: None,
+                matched_queries: Vec::new(),
             })
             .collect()
     }
@@ -3985,7 +4730,8 @@ mod tests {
         assert!(search_response.is_err());
         assert_eq!(
             search_response.unwrap_err().to_string(),
-            "Invalid argument: max value for start_offset is 10_000, but got 20000",
+            "Invalid argument: max value for start_offset is 10000, but got 20000. use \
+             `search_after` to paginate deeper",
         );
 
         let search_request = quickwit_proto::search::SearchRequest {
@@ -4005,7 +4751,7 @@ mod tests {
         assert!(search_response.is_err());
         assert_eq!(
             search_response.unwrap_err().to_string(),
-            "Invalid argument: max value for max_hits is 10_000, but got 20000",
+            "Invalid argument: max value for max_hits is 10000, but got 20000",
         );
 
         Ok(())
@@ -5032,4 +5778,87 @@ mod tests {
         assert_eq!(search_response.failed_splits.len(), 1);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_root_search_with_profile() -> anyhow::Result<()> {
+        let search_request = quickwit_proto::search::SearchRequest {
+            index_id_patterns: vec!["test-index-1".to_string()],
+            query_ast: qast_json_helper("test", &["body"]),
+            max_hits: 10,
+            profile: true,
+            ..Default::default()
+        };
+        let mut mock_metastore = MockMetastoreService::new();
+        let index_metadata_1 = IndexMetadata::for_test("test-index-1", "ram:///test-index-1");
+        let index_uid_1 = index_metadata_1.index_uid.clone();
+        mock_metastore.expect_list_indexes_metadata().return_once(
+            move |_list_indexes_metadata_request: ListIndexesMetadataRequest| {
+                Ok(ListIndexesMetadataResponse::for_test(vec![
+                    index_metadata_1,
+                ]))
+            },
+        );
+        mock_metastore
+            .expect_list_splits()
+            .return_once(move |_list_splits_request| {
+                let splits = vec![MockSplitBuilder::new("index-1-split-1")
+                    .with_index_uid(&index_uid_1)
+                    .build()];
+                let splits_response = ListSplitsResponse::try_from_splits(splits).unwrap();
+                Ok(ServiceStream::from(vec![Ok(splits_response)]))
+            });
+        let mut mock_search_service_1 = MockSearchService::new();
+        mock_search_service_1
+            .expect_leaf_search()
+            .times(1)
+            .returning(
+                |_leaf_search_req: quickwit_proto::search::LeafSearchRequest| {
+                    let split_profile = SplitSearchProfile {
+                        split_id: "index-1-split-1".to_string(),
+                        warmup_microsecs: 10,
+                        search_microsecs: 20,
+                        ..Default::default()
+                    };
+                    Ok(quickwit_proto::search::LeafSearchResponse {
+                        num_hits: 1,
+                        partial_hits: vec![mock_partial_hit("index-1-split-1", 0u64, 1u32)],
+                        num_attempted_splits: 1,
+                        num_successful_splits: 1,
+                        split_profiles: vec![split_profile],
+                        ..Default::default()
+                    })
+                },
+            );
+        mock_search_service_1
+            .expect_fetch_docs()
+            .times(1)
+            .returning(|fetch_docs_req| {
+                Ok(quickwit_proto::search::FetchDocsResponse {
+                    hits: get_doc_for_fetch_req(fetch_docs_req),
+                })
+            });
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", mock_search_service_1)]);
+        let search_job_placer = SearchJobPlacer::new(searcher_pool);
+        let cluster_client = ClusterClient::new(search_job_placer.clone());
+        let search_response = root_search(
+            &SearcherContext::for_test(),
+            search_request,
+            MetastoreServiceClient::from_mock(mock_metastore),
+            &cluster_client,
+        )
+        .await
+        .unwrap();
+        assert_eq!(search_response.num_hits, 1);
+
+        let search_profile = search_response.profile.unwrap();
+        assert_eq!(search_profile.nodes.len(), 1);
+
+        let node_profile = &search_profile.nodes[0];
+        assert_eq!(node_profile.searcher_addr, "127.0.0.1:1001");
+        assert_eq!(node_profile.splits.len(), 1);
+        assert_eq!(node_profile.splits[0].split_id, "index-1-split-1");
+        assert_eq!(node_profile.splits[0].searcher_addr, "127.0.0.1:1001");
+        assert_eq!(node_profile.splits[0].search_microsecs, 20);
+        Ok(())
+    }
 }
@@ -0,0 +1,133 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures::future::{AbortHandle, Abortable};
+
+use crate::SearchError;
+
+/// Keeps track of the leaf searches running on this searcher by search request ID, so that they
+/// can be cancelled along with their root search.
+#[derive(Default)]
+pub(crate) struct RunningLeafSearches {
+    inner: Arc<Mutex<HashMap<String, HashMap<u64, AbortHandle>>>>,
+    next_leaf_search_id: AtomicU64,
+}
+
+impl RunningLeafSearches {
+    /// Runs a leaf search until it completes or its search request is cancelled. Leaf searches
+    /// without a search request ID cannot be cancelled.
+    pub async fn run<T>(
+        &self,
+        search_request_id_opt: Option<&str>,
+        leaf_search_future: impl Future<Output = crate::Result<T>>,
+    ) -> crate::Result<T> {
+        let Some(search_request_id) = search_request_id_opt else {
+            return leaf_search_future.await;
+        };
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let leaf_search_id = self.next_leaf_search_id.fetch_add(1, Ordering::Relaxed);
+        self.inner
+            .lock()
+            .unwrap()
+            .entry(search_request_id.to_string())
+            .or_default()
+            .insert(leaf_search_id, abort_handle);
+
+        let _guard = RunningLeafSearchGuard {
+            inner: self.inner.clone(),
+            search_request_id: search_request_id.to_string(),
+            leaf_search_id,
+        };
+        Abortable::new(leaf_search_future, abort_registration)
+            .await
+            .unwrap_or_else(|_aborted| {
+                Err(SearchError::Cancelled(format!(
+                    "search request `{search_request_id}` was cancelled"
+                )))
+            })
+    }
+
+    /// Cancels the leaf searches of a search request and returns how many were cancelled.
+    pub fn cancel(&self, search_request_id: &str) -> usize {
+        let inner = self.inner.lock().unwrap();
+        let Some(abort_handles) = inner.get(search_request_id) else {
+            return 0;
+        };
+        for abort_handle in abort_handles.values() {
+            abort_handle.abort();
+        }
+        abort_handles.len()
+    }
+}
+
+/// Unregisters a leaf search when dropped.
+struct RunningLeafSearchGuard {
+    inner: Arc<Mutex<HashMap<String, HashMap<u64, AbortHandle>>>>,
+    search_request_id: String,
+    leaf_search_id: u64,
+}
+
+impl Drop for RunningLeafSearchGuard {
+    fn drop(&mut self) {
+        let mut inner = self.inner.lock().unwrap();
+
+        if let Some(abort_handles) = inner.get_mut(&self.search_request_id) {
+            abort_handles.remove(&self.leaf_search_id);
+
+            if abort_handles.is_empty() {
+                inner.remove(&self.search_request_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_running_leaf_searches() {
+        let running_leaf_searches = Arc::new(RunningLeafSearches::default());
+        assert_eq!(running_leaf_searches.cancel("test-search"), 0);
+
+        let running_leaf_searches_clone = running_leaf_searches.clone();
+        let leaf_search_handle = tokio::spawn(async move {
+            running_leaf_searches_clone
+                .run(
+                    Some("test-search"),
+                    futures::future::pending::<crate::Result<()>>(),
+                )
+                .await
+        });
+        while running_leaf_searches.inner.lock().unwrap().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(running_leaf_searches.cancel("other-search"), 0);
+        assert_eq!(running_leaf_searches.cancel("test-search"), 1);
+
+        let error = leaf_search_handle.await.unwrap().unwrap_err();
+        assert!(matches!(error, SearchError::Cancelled(_)));
+        assert!(running_leaf_searches.inner.lock().unwrap().is_empty());
+
+        let leaf_search_result = running_leaf_searches
+            .run(None, async { Ok::<_, SearchError>(42) })
+            .await;
+        assert_eq!(leaf_search_result.unwrap(), 42);
+    }
+}
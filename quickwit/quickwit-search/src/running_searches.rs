@@ -0,0 +1,486 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use futures::future::{AbortHandle, AbortRegistration, Abortable};
+use quickwit_proto::search::{RunningSearchInfo, SecurityContext};
+use quickwit_proto::{ServiceError, ServiceErrorCode};
+use ulid::Ulid;
+
+use crate::{SearchError, SearchService};
+
+const MAX_SEARCH_REQUEST_ID_LEN: usize = 128;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CancelSearchError {
+    #[error("could not find running search request `{0}`")]
+    NotFound(String),
+}
+
+impl ServiceError for CancelSearchError {
+    fn error_code(&self) -> ServiceErrorCode {
+        match self {
+            Self::NotFound(_) => ServiceErrorCode::NotFound,
+        }
+    }
+}
+
+struct RunningSearch {
+    request_id: String,
+    /// Unique ID of the search request, propagated to the leaf searches so they can be cancelled
+    /// on every node. Unlike the request ID, it cannot be chosen by the client.
+    search_request_uid: String,
+    index_id_patterns: Vec<String>,
+    /// Security context of the caller that started the search request, if authentication is
+    /// enabled.
+    owner_opt: Option<SecurityContext>,
+    start: Instant,
+    abort_handle: AbortHandle,
+}
+
+impl RunningSearch {
+    fn info(&self) -> RunningSearchInfo {
+        RunningSearchInfo {
+            request_id: self.request_id.clone(),
+            index_id_patterns: self.index_id_patterns.clone(),
+            elapsed_secs: self.start.elapsed().as_secs_f64(),
+        }
+    }
+
+    /// Returns whether the caller can see and cancel this search request. Callers can only
+    /// access their own search requests, unless authentication is disabled.
+    fn is_visible_to(&self, security_context_opt: Option<&SecurityContext>) -> bool {
+        match (&self.owner_opt, security_context_opt) {
+            (Some(owner), Some(security_context)) => owner == security_context,
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+}
+
+/// Keeps track of the search requests running on this node so they can be listed and cancelled.
+///
+/// The request IDs are scoped to the caller that started the search requests: callers can reuse
+/// the request IDs of other callers, and cannot tell whether they are in use.
+#[derive(Default)]
+pub struct RunningSearches {
+    /// Running search requests keyed by request ID.
+    searches: Mutex<HashMap<String, Vec<RunningSearch>>>,
+}
+
+impl RunningSearches {
+    /// Registers a new search request. The returned registration must be used to make the search
+    /// future abortable, and the guard unregisters the search request when dropped. The search
+    /// service is used to cancel the leaf searches of the search requests that do not run to
+    /// completion.
+    pub fn register(
+        self: &Arc<Self>,
+        request_id_opt: Option<String>,
+        index_id_patterns: Vec<String>,
+        security_context_opt: Option<SecurityContext>,
+        search_service: Arc<dyn SearchService>,
+    ) -> Result<(RunningSearchGuard, AbortRegistration), SearchError> {
+        let request_id = match request_id_opt {
+            Some(request_id) => {
+                if request_id.is_empty() || request_id.len() > MAX_SEARCH_REQUEST_ID_LEN {
+                    return Err(SearchError::InvalidArgument(format!(
+                        "search request ID must be between 1 and {MAX_SEARCH_REQUEST_ID_LEN} \
+                         characters long"
+                    )));
+                }
+                request_id
+            }
+            None => Ulid::new().to_string(),
+        };
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let search_request_uid = Ulid::new().to_string();
+        let running_search = RunningSearch {
+            request_id: request_id.clone(),
+            search_request_uid: search_request_uid.clone(),
+            index_id_patterns,
+            owner_opt: security_context_opt,
+            start: Instant::now(),
+            abort_handle,
+        };
+        let mut searches = self.searches.lock().unwrap();
+        let same_request_id_searches = searches.entry(request_id.clone()).or_default();
+
+        if same_request_id_searches
+            .iter()
+            .any(|same_request_id_search| {
+                same_request_id_search.owner_opt == running_search.owner_opt
+            })
+        {
+            return Err(SearchError::InvalidArgument(format!(
+                "search request `{request_id}` is already running"
+            )));
+        }
+        same_request_id_searches.push(running_search);
+
+        let guard = RunningSearchGuard {
+            running_searches: self.clone(),
+            request_id,
+            search_request_uid,
+            search_service,
+            is_finished: AtomicBool::new(false),
+        };
+        Ok((guard, abort_registration))
+    }
+
+    /// Aborts a running search request and returns its info along with the unique ID of the
+    /// search request, which must be used to cancel its leaf searches. The search requests started
+    /// by other callers are reported as not found.
+    pub fn cancel(
+        &self,
+        request_id: &str,
+        security_context_opt: Option<&SecurityContext>,
+    ) -> Result<(RunningSearchInfo, String), CancelSearchError> {
+        let searches = self.searches.lock().unwrap();
+        let same_request_id_searches = searches
+            .get(request_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        // The search request of the caller takes precedence over an unauthenticated search request
+        // with the same ID.
+        let running_search = same_request_id_searches
+            .iter()
+            .find(|running_search| running_search.owner_opt.as_ref() == security_context_opt)
+            .or_else(|| {
+                same_request_id_searches
+                    .iter()
+                    .find(|running_search| running_search.is_visible_to(security_context_opt))
+            })
+            .ok_or_else(|| CancelSearchError::NotFound(request_id.to_string()))?;
+        running_search.abort_handle.abort();
+        Ok((
+            running_search.info(),
+            running_search.search_request_uid.clone(),
+        ))
+    }
+
+    /// Lists the search requests of the caller running on this node, oldest first.
+    pub fn list(&self, security_context_opt: Option<&SecurityContext>) -> Vec<RunningSearchInfo> {
+        let searches = self.searches.lock().unwrap();
+        let mut running_search_infos: Vec<(Instant, RunningSearchInfo)> = searches
+            .values()
+            .flatten()
+            .filter(|running_search| running_search.is_visible_to(security_context_opt))
+            .map(|running_search| (running_search.start, running_search.info()))
+            .collect();
+        running_search_infos.sort_by_key(|(start, _)| *start);
+        running_search_infos
+            .into_iter()
+            .map(|(_, running_search_info)| running_search_info)
+            .collect()
+    }
+}
+
+/// Unregisters a search request when dropped, whether it completed, failed, was cancelled, or the
+/// client went away.
+pub struct RunningSearchGuard {
+    running_searches: Arc<RunningSearches>,
+    request_id: String,
+    search_request_uid: String,
+    search_service: Arc<dyn SearchService>,
+    /// Whether the search future completed or was cancelled, as opposed to dropped.
+    is_finished: AtomicBool,
+}
+
+impl RunningSearchGuard {
+    pub fn request_id(&self) -> &str {
+        &self.request_id
+    }
+
+    pub fn search_request_uid(&self) -> &str {
+        &self.search_request_uid
+    }
+
+    /// Runs a search future until it completes or the search request is cancelled.
+    pub async fn run_until_cancelled<T, E: From<SearchError>>(
+        &self,
+        abort_registration: AbortRegistration,
+        search_future: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let search_result = Abortable::new(search_future, abort_registration)
+            .await
+            .unwrap_or_else(|_aborted| {
+                Err(E::from(SearchError::Cancelled(format!(
+                    "search request `{}` was cancelled",
+                    self.request_id
+                ))))
+            });
+        self.is_finished.store(true, Ordering::Relaxed);
+        search_result
+    }
+}
+
+impl Drop for RunningSearchGuard {
+    fn drop(&mut self) {
+        let mut searches = self.running_searches.searches.lock().unwrap();
+
+        if let Some(same_request_id_searches) = searches.get_mut(&self.request_id) {
+            same_request_id_searches.retain(|running_search| {
+                running_search.search_request_uid != self.search_request_uid
+            });
+            if same_request_id_searches.is_empty() {
+                searches.remove(&self.request_id);
+            }
+        }
+        drop(searches);
+
+        if self.is_finished.load(Ordering::Relaxed) {
+            return;
+        }
+        // The search future was dropped before completing, typically because the client went
+        // away. Its leaf searches would otherwise keep running and holding search permits on the
+        // other nodes.
+        if let Ok(runtime_handle) = tokio::runtime::Handle::try_current() {
+            let search_service = self.search_service.clone();
+            let search_request_uid = self.search_request_uid.clone();
+            runtime_handle.spawn(async move {
+                search_service
+                    .root_cancel_leaf_searches(search_request_uid)
+                    .await;
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::MockSearchService;
+
+    fn search_service() -> Arc<dyn SearchService> {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_cancel_leaf_searches()
+            .returning(|_| ());
+        Arc::new(mock_search_service)
+    }
+
+    #[tokio::test]
+    async fn test_running_searches() {
+        let running_searches = Arc::new(RunningSearches::default());
+
+        let (guard, abort_registration) = running_searches
+            .register(
+                Some("test-running-searches".to_string()),
+                vec!["my-index".to_string()],
+                None,
+                search_service(),
+            )
+            .unwrap();
+        assert_eq!(guard.request_id(), "test-running-searches");
+
+        let running_search_infos = running_searches.list(None);
+        let running_search_info = running_search_infos
+            .iter()
+            .find(|info| info.request_id == "test-running-searches")
+            .unwrap();
+        assert_eq!(running_search_info.index_id_patterns, ["my-index"]);
+
+        let error = running_searches
+            .register(
+                Some("test-running-searches".to_string()),
+                Vec::new(),
+                None,
+                search_service(),
+            )
+            .map(|_| ())
+            .unwrap_err();
+        assert!(matches!(error, SearchError::InvalidArgument(_)));
+
+        let (_, search_request_uid) = running_searches
+            .cancel("test-running-searches", None)
+            .unwrap();
+        assert_eq!(search_request_uid, guard.search_request_uid());
+
+        let error = guard
+            .run_until_cancelled(
+                abort_registration,
+                futures::future::pending::<Result<(), SearchError>>(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SearchError::Cancelled(_)));
+
+        drop(guard);
+        let error = running_searches
+            .cancel("test-running-searches", None)
+            .unwrap_err();
+        assert!(matches!(error, CancelSearchError::NotFound(_)));
+    }
+
+    #[test]
+    fn test_running_searches_generates_request_ids() {
+        let running_searches = Arc::new(RunningSearches::default());
+        let (guard, _abort_registration) = running_searches
+            .register(None, Vec::new(), None, search_service())
+            .unwrap();
+        assert!(!guard.request_id().is_empty());
+
+        running_searches
+            .register(Some(String::new()), Vec::new(), None, search_service())
+            .map(|_| ())
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_running_searches_are_scoped_to_their_owner() {
+        let running_searches = Arc::new(RunningSearches::default());
+        let owner = SecurityContext {
+            role: "owner".to_string(),
+            ..Default::default()
+        };
+        let other = SecurityContext {
+            role: "other".to_string(),
+            ..Default::default()
+        };
+        let (_guard, _abort_registration) = running_searches
+            .register(
+                Some("test-scoped-running-searches".to_string()),
+                Vec::new(),
+                Some(owner.clone()),
+                search_service(),
+            )
+            .unwrap();
+
+        let is_listed = |security_context_opt: Option<&SecurityContext>| {
+            running_searches
+                .list(security_context_opt)
+                .iter()
+                .any(|info| info.request_id == "test-scoped-running-searches")
+        };
+        assert!(is_listed(Some(&owner)));
+        assert!(!is_listed(Some(&other)));
+        assert!(!is_listed(None));
+
+        let error = running_searches
+            .cancel("test-scoped-running-searches", Some(&other))
+            .unwrap_err();
+        assert!(matches!(error, CancelSearchError::NotFound(_)));
+
+        running_searches
+            .cancel("test-scoped-running-searches", Some(&owner))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_running_search_request_ids_are_scoped_to_their_owner() {
+        let running_searches = Arc::new(RunningSearches::default());
+        let owner = SecurityContext {
+            role: "owner".to_string(),
+            ..Default::default()
+        };
+        let other = SecurityContext {
+            role: "other".to_string(),
+            ..Default::default()
+        };
+        let (owner_guard, _abort_registration) = running_searches
+            .register(
+                Some("my-search".to_string()),
+                Vec::new(),
+                Some(owner.clone()),
+                search_service(),
+            )
+            .unwrap();
+        // Another caller can use the same request ID without learning that it is in use.
+        let (other_guard, _abort_registration) = running_searches
+            .register(
+                Some("my-search".to_string()),
+                Vec::new(),
+                Some(other.clone()),
+                search_service(),
+            )
+            .unwrap();
+        let error = running_searches
+            .register(
+                Some("my-search".to_string()),
+                Vec::new(),
+                Some(owner.clone()),
+                search_service(),
+            )
+            .map(|_| ())
+            .unwrap_err();
+        assert!(matches!(error, SearchError::InvalidArgument(_)));
+
+        let (_, search_request_uid) = running_searches.cancel("my-search", Some(&other)).unwrap();
+        assert_eq!(search_request_uid, other_guard.search_request_uid());
+
+        drop(other_guard);
+        running_searches
+            .cancel("my-search", Some(&other))
+            .unwrap_err();
+        let (_, search_request_uid) = running_searches.cancel("my-search", Some(&owner)).unwrap();
+        assert_eq!(search_request_uid, owner_guard.search_request_uid());
+
+        drop(owner_guard);
+        assert!(running_searches.searches.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_running_search_guard_cancels_leaf_searches_when_dropped() {
+        let (cancelled_tx, mut cancelled_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_cancel_leaf_searches()
+            .returning(move |search_request_uid| {
+                cancelled_tx.send(search_request_uid).unwrap();
+            });
+        let search_service: Arc<dyn SearchService> = Arc::new(mock_search_service);
+        let running_searches = Arc::new(RunningSearches::default());
+
+        let (completed_guard, abort_registration) = running_searches
+            .register(
+                Some("test-completed-search".to_string()),
+                Vec::new(),
+                None,
+                search_service.clone(),
+            )
+            .unwrap();
+        completed_guard
+            .run_until_cancelled(abort_registration, async { Ok::<_, SearchError>(()) })
+            .await
+            .unwrap();
+        drop(completed_guard);
+
+        let (dropped_guard, _abort_registration) = running_searches
+            .register(
+                Some("test-dropped-search".to_string()),
+                Vec::new(),
+                None,
+                search_service,
+            )
+            .unwrap();
+        let dropped_search_request_uid = dropped_guard.search_request_uid().to_string();
+        drop(dropped_guard);
+
+        let cancelled_search_request_uid =
+            tokio::time::timeout(Duration::from_secs(1), cancelled_rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(cancelled_search_request_uid, dropped_search_request_uid);
+        assert!(cancelled_rx.try_recv().is_err());
+    }
+}
@@ -24,6 +24,7 @@ use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 use quickwit_common::metrics::GaugeGuard;
 use quickwit_common::shared_consts::SCROLL_BATCH_LEN;
+use quickwit_config::SearchSettings;
 use quickwit_metastore::SplitMetadata;
 use quickwit_proto::search::{LeafSearchResponse, PartialHit, SearchRequest, SplitSearchError};
 use quickwit_proto::types::IndexUid;
@@ -244,7 +245,7 @@ impl FromStr for ScrollKeyAndStartOffset {
         let scroll_ulid = u128::from_le_bytes(scroll_ulid_bytes.try_into().unwrap()).into();
         let from = u64::from_le_bytes(from_bytes.try_into().unwrap());
         let max_hits = u32::from_le_bytes(max_hits_bytes.try_into().unwrap());
-        if max_hits > 10_000 {
+        if max_hits as u64 > SearchSettings::MAX_RESULT_WINDOW_LIMIT {
             return Err("scroll id is malformed");
         }
         let search_after =
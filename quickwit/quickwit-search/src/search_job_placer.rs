@@ -23,7 +23,10 @@ use async_trait::async_trait;
 use quickwit_common::pubsub::EventSubscriber;
 use quickwit_common::rendezvous_hasher::{node_affinity, sort_by_rendez_vous_hash};
 use quickwit_common::SocketAddrLegacyHash;
-use quickwit_proto::search::{ReportSplit, ReportSplitsRequest};
+use quickwit_proto::search::{
+    CancelLeafSearchesRequest, CancelSearchRequest, PinSplitsRequest, ReportSplit,
+    ReportSplitsRequest, RunningSearchInfo,
+};
 use tracing::{info, warn};
 
 use crate::{SearchJob, SearchServiceClient, SearcherPool, SEARCH_METRICS};
@@ -63,33 +66,7 @@ pub struct SearchJobPlacer {
 #[async_trait]
 impl EventSubscriber<ReportSplitsRequest> for SearchJobPlacer {
     async fn handle_event(&mut self, evt: ReportSplitsRequest) {
-        let mut nodes: HashMap<SocketAddr, SearchServiceClient> =
-            self.searcher_pool.pairs().into_iter().collect();
-        if nodes.is_empty() {
-            return;
-        }
-        let mut splits_per_node: HashMap<SocketAddr, Vec<ReportSplit>> =
-            HashMap::with_capacity(nodes.len().min(evt.report_splits.len()));
-        for report_split in evt.report_splits {
-            let node_addr = nodes
-                .keys()
-                .max_by_key(|node_addr| {
-                    node_affinity(SocketAddrLegacyHash(node_addr), &report_split.split_id)
-                })
-                // This actually never happens thanks to the if-condition at the
-                // top of this function.
-                .expect("`nodes` should not be empty");
-            splits_per_node
-                .entry(*node_addr)
-                .or_default()
-                .push(report_split);
-        }
-        for (node_addr, report_splits) in splits_per_node {
-            if let Some(search_client) = nodes.get_mut(&node_addr) {
-                let report_splits_req = ReportSplitsRequest { report_splits };
-                let _ = search_client.report_splits(report_splits_req).await;
-            }
-        }
+        self.report_splits(evt.report_splits).await;
     }
 }
 
@@ -104,6 +81,183 @@ impl SearchJobPlacer {
     pub fn new(searcher_pool: SearcherPool) -> Self {
         Self { searcher_pool }
     }
+
+    /// Reports splits to the split cache of the search nodes that will most likely be assigned
+    /// the jobs targeting them.
+    pub(crate) async fn report_splits(&mut self, report_splits: Vec<ReportSplit>) {
+        let mut nodes: HashMap<SocketAddr, SearchServiceClient> =
+            self.searcher_pool.pairs().into_iter().collect();
+        for (node_addr, report_splits) in splits_per_node(&nodes, report_splits) {
+            if let Some(search_client) = nodes.get_mut(&node_addr) {
+                let report_splits_req = ReportSplitsRequest { report_splits };
+                let _ = search_client.report_splits(report_splits_req).await;
+            }
+        }
+    }
+
+    /// Pins splits in the split cache of the search nodes that will most likely be assigned the
+    /// jobs targeting them.
+    ///
+    /// The nodes are pinned concurrently. If any of them fails, the splits pinned on the other
+    /// nodes are unpinned so that no partial pin is left behind.
+    pub(crate) async fn pin_splits(&mut self, splits: Vec<ReportSplit>) -> crate::Result<()> {
+        let nodes: HashMap<SocketAddr, SearchServiceClient> =
+            self.searcher_pool.pairs().into_iter().collect();
+        let pin_futures =
+            splits_per_node(&nodes, splits)
+                .into_iter()
+                .filter_map(|(node_addr, splits)| {
+                    let mut search_client = nodes.get(&node_addr)?.clone();
+                    let pin_splits_req = PinSplitsRequest {
+                        splits,
+                        unpin: false,
+                        unpin_storage_uris: Vec::new(),
+                    };
+                    Some(async move {
+                        let pin_result = search_client.pin_splits(pin_splits_req.clone()).await;
+                        (node_addr, search_client, pin_splits_req, pin_result)
+                    })
+                });
+        let pin_outcomes = futures::future::join_all(pin_futures).await;
+
+        let Some(pin_error) = pin_outcomes
+            .iter()
+            .find_map(|(_, _, _, pin_result)| pin_result.as_ref().err().cloned())
+        else {
+            return Ok(());
+        };
+        let rollback_futures = pin_outcomes.into_iter().filter_map(
+            |(node_addr, mut search_client, mut pin_splits_req, pin_result)| {
+                pin_result.ok()?;
+                pin_splits_req.unpin = true;
+
+                Some(async move {
+                    if let Err(error) = search_client.pin_splits(pin_splits_req).await {
+                        warn!(%error, node_addr=%node_addr, "failed to roll back split pins");
+                    }
+                })
+            },
+        );
+        futures::future::join_all(rollback_futures).await;
+        Err(pin_error)
+    }
+
+    /// Unpins splits from the split cache of all the search nodes, as the node a split was pinned
+    /// on may have changed since.
+    ///
+    /// The nodes are unpinned concurrently and all of them are attempted even if some fail.
+    /// Unpinning is idempotent, so a failed request can simply be retried.
+    pub(crate) async fn unpin_splits(
+        &mut self,
+        splits: Vec<ReportSplit>,
+        unpin_storage_uris: Vec<String>,
+    ) -> crate::Result<()> {
+        let unpin_futures =
+            self.searcher_pool
+                .pairs()
+                .into_iter()
+                .map(|(node_addr, mut search_client)| {
+                    let unpin_splits_req = PinSplitsRequest {
+                        splits: splits.clone(),
+                        unpin: true,
+                        unpin_storage_uris: unpin_storage_uris.clone(),
+                    };
+                    async move {
+                        search_client
+                            .pin_splits(unpin_splits_req)
+                            .await
+                            .inspect_err(|error| {
+                                warn!(%error, node_addr=%node_addr, "failed to unpin splits");
+                            })
+                    }
+                });
+        futures::future::join_all(unpin_futures)
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    /// Cancels the leaf searches of a search request on all the search nodes. Cancellation is
+    /// best effort: the nodes that cannot be reached are skipped.
+    pub(crate) async fn cancel_leaf_searches(&self, search_request_id: String) {
+        let cancel_futures =
+            self.searcher_pool
+                .pairs()
+                .into_iter()
+                .map(|(node_addr, mut search_client)| {
+                    let cancel_leaf_searches_req = CancelLeafSearchesRequest {
+                        search_request_id: search_request_id.clone(),
+                    };
+                    async move {
+                        if let Err(error) = search_client
+                            .cancel_leaf_searches(cancel_leaf_searches_req)
+                            .await
+                        {
+                            warn!(
+                                %error,
+                                node_addr=%node_addr,
+                                "failed to cancel leaf searches"
+                            );
+                        }
+                    }
+                });
+        futures::future::join_all(cancel_futures).await;
+    }
+
+    /// Cancels a search request on all the search nodes, and returns the search request if one of
+    /// them was running it. Cancellation is best effort: the nodes that cannot be reached are
+    /// skipped.
+    pub(crate) async fn cancel_search(
+        &self,
+        cancel_search_request: CancelSearchRequest,
+    ) -> Option<RunningSearchInfo> {
+        let cancel_futures =
+            self.searcher_pool
+                .pairs()
+                .into_iter()
+                .map(|(node_addr, mut search_client)| {
+                    let cancel_search_req = cancel_search_request.clone();
+                    async move {
+                        match search_client.cancel_search(cancel_search_req).await {
+                            Ok(cancel_search_response) => cancel_search_response.running_search,
+                            Err(error) => {
+                                warn!(
+                                    %error,
+                                    node_addr=%node_addr,
+                                    "failed to cancel search request"
+                                );
+                                None
+                            }
+                        }
+                    }
+                });
+        futures::future::join_all(cancel_futures)
+            .await
+            .into_iter()
+            .flatten()
+            .next()
+    }
+}
+
+/// Groups splits by the search node that will most likely be assigned the jobs targeting them.
+fn splits_per_node(
+    nodes: &HashMap<SocketAddr, SearchServiceClient>,
+    splits: Vec<ReportSplit>,
+) -> HashMap<SocketAddr, Vec<ReportSplit>> {
+    let mut splits_per_node: HashMap<SocketAddr, Vec<ReportSplit>> =
+        HashMap::with_capacity(nodes.len().min(splits.len()));
+    if nodes.is_empty() {
+        return splits_per_node;
+    }
+    for split in splits {
+        let node_addr = nodes
+            .keys()
+            .max_by_key(|node_addr| node_affinity(SocketAddrLegacyHash(node_addr), &split.split_id))
+            // This actually never happens thanks to the if-condition above.
+            .expect("`nodes` should not be empty");
+        splits_per_node.entry(*node_addr).or_default().push(split);
+    }
+    splits_per_node
 }
 
 struct SocketAddrAndClient {
@@ -15,6 +15,8 @@
 use std::collections::VecDeque;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use bytesize::ByteSize;
@@ -34,6 +36,7 @@ use tokio::sync::{mpsc, oneshot};
 #[derive(Clone)]
 pub struct SearchPermitProvider {
     message_sender: mpsc::UnboundedSender<SearchPermitMessage>,
+    num_pending_permits: Arc<AtomicUsize>,
     #[cfg(test)]
     actor_stopped: watch::Receiver<bool>,
 }
@@ -83,6 +86,7 @@ impl SearchPermitProvider {
         let (message_sender, message_receiver) = mpsc::unbounded_channel();
         #[cfg(test)]
         let (state_sender, state_receiver) = watch::channel(false);
+        let num_pending_permits = Arc::new(AtomicUsize::new(0));
         let actor = SearchPermitActor {
             msg_receiver: message_receiver,
             msg_sender: message_sender.downgrade(),
@@ -90,12 +94,14 @@ impl SearchPermitProvider {
             total_memory_budget: memory_budget.as_u64(),
             permits_requests: VecDeque::new(),
             total_memory_allocated: 0u64,
+            num_pending_permits: num_pending_permits.clone(),
             #[cfg(test)]
             stopped: state_sender,
         };
         tokio::spawn(actor.run());
         Self {
             message_sender,
+            num_pending_permits,
             #[cfg(test)]
             actor_stopped: state_receiver,
         }
@@ -124,6 +130,11 @@ impl SearchPermitProvider {
             .await
             .expect("Receiver lives longer than sender")
     }
+
+    /// Returns the number of permits requested and not assigned yet.
+    pub fn num_pending_permits(&self) -> usize {
+        self.num_pending_permits.load(Ordering::Relaxed)
+    }
 }
 
 struct SearchPermitActor {
@@ -136,6 +147,7 @@ struct SearchPermitActor {
     total_memory_budget: u64,
     total_memory_allocated: u64,
     permits_requests: VecDeque<(oneshot::Sender<SearchPermit>, u64)>,
+    num_pending_permits: Arc<AtomicUsize>,
     #[cfg(test)]
     stopped: watch::Sender<bool>,
 }
@@ -229,6 +241,8 @@ impl SearchPermitActor {
                 // created SearchPermit which releases the resources
                 .ok();
         }
+        self.num_pending_permits
+            .store(self.permits_requests.len(), Ordering::Relaxed);
         crate::SEARCH_METRICS
             .leaf_search_single_split_tasks_pending
             .set(self.permits_requests.len() as i64);
@@ -414,6 +428,7 @@ mod tests {
             .await;
         let mut remaining_permit_futs = permit_futs.split_off(10).into_iter();
         assert_eq!(remaining_permit_futs.len(), 4);
+        assert_eq!(permit_provider.num_pending_permits(), 4);
         // we should be able to obtain 10 permits right away (100MB / 10MB)
         let mut permits: Vec<SearchPermit> = futures::stream::iter(permit_futs.into_iter())
             .buffered(1)
@@ -16,7 +16,7 @@ use std::convert::TryFrom;
 use std::io;
 
 use quickwit_common::truncate_str;
-use quickwit_proto::search::SearchResponse;
+use quickwit_proto::search::{SearchProfile, SearchResponse};
 use quickwit_query::query_ast::QueryAst;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
@@ -59,6 +59,13 @@ pub struct SearchResponseRest {
     #[schema(value_type = Object)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub aggregations: Option<AggregationResults>,
+    /// Execution statistics, only returned if the `profile` parameter is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<SearchProfile>,
+    /// Warnings about how the search was executed.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 impl TryFrom<SearchResponse> for SearchResponseRest {
@@ -109,6 +116,8 @@ impl TryFrom<SearchResponse> for SearchResponseRest {
             elapsed_time_micros: search_response.elapsed_time_micros,
             errors: search_response.errors,
             aggregations: aggregations_opt,
+            profile: search_response.profile,
+            warnings: search_response.warnings,
         })
     }
 }
@@ -24,6 +24,7 @@ use quickwit_proto::search::{
     LeafSearchStreamResponse, OutputFormat, SearchRequest, SearchStreamRequest,
     SplitIdAndFooterOffsets,
 };
+use quickwit_proto::types::IndexUid;
 use quickwit_storage::{ByteRangeCache, Storage};
 use tantivy::columnar::{DynamicColumn, HasAssociatedColumnType};
 use tantivy::fastfield::Column;
@@ -36,7 +37,9 @@ use tracing::*;
 use super::collector::{PartionnedFastFieldCollector, PartitionValues};
 use super::FastFieldCollector;
 use crate::filters::{create_timestamp_filter_builder, TimestampFilterBuilder};
-use crate::leaf::{open_index_with_caches, rewrite_start_end_time_bounds, warmup};
+use crate::leaf::{
+    open_index_with_caches, record_num_bytes_read, rewrite_start_end_time_bounds, warmup,
+};
 use crate::service::SearcherContext;
 use crate::{Result, SearchError};
 
@@ -51,6 +54,7 @@ use crate::{Result, SearchError};
 pub async fn leaf_search_stream(
     searcher_context: Arc<SearcherContext>,
     request: SearchStreamRequest,
+    index_uid_opt: Option<IndexUid>,
     storage: Arc<dyn Storage>,
     splits: Vec<SplitIdAndFooterOffsets>,
     doc_mapper: Arc<DocMapper>,
@@ -60,9 +64,15 @@ pub async fn leaf_search_stream(
     let span = info_span!("leaf_search_stream",);
     tokio::spawn(
         async move {
-            let mut stream =
-                leaf_search_results_stream(searcher_context, request, storage, splits, doc_mapper)
-                    .await;
+            let mut stream = leaf_search_results_stream(
+                searcher_context,
+                request,
+                index_uid_opt,
+                storage,
+                splits,
+                doc_mapper,
+            )
+            .await;
             while let Some(item) = stream.next().await {
                 if let Err(error) = result_sender.send(item) {
                     error!(
@@ -81,6 +91,7 @@ pub async fn leaf_search_stream(
 async fn leaf_search_results_stream(
     searcher_context: Arc<SearcherContext>,
     request: SearchStreamRequest,
+    index_uid_opt: Option<IndexUid>,
     storage: Arc<dyn Storage>,
     splits: Vec<SplitIdAndFooterOffsets>,
     doc_mapper: Arc<DocMapper>,
@@ -95,6 +106,7 @@ async fn leaf_search_results_stream(
                 split,
                 doc_mapper.clone(),
                 request.clone(),
+                index_uid_opt.clone(),
                 storage.clone(),
             )
             .shared()
@@ -109,6 +121,7 @@ async fn leaf_search_stream_single_split(
     split: SplitIdAndFooterOffsets,
     doc_mapper: Arc<DocMapper>,
     mut stream_request: SearchStreamRequest,
+    index_uid_opt: Option<IndexUid>,
     storage: Arc<dyn Storage>,
 ) -> crate::Result<LeafSearchStreamResponse> {
     // TODO: Should we track the memory here using the SearchPermitProvider?
@@ -128,9 +141,12 @@ async fn leaf_search_stream_single_split(
     let (index, _) = open_index_with_caches(
         &searcher_context,
         storage,
+        index_uid_opt
+            .as_ref()
+            .map(|index_uid| index_uid.index_id.as_str()),
         &split,
         Some(doc_mapper.tokenizer_manager()),
-        Some(cache),
+        Some(cache.clone()),
     )
     .await?;
     let split_schema = index.schema();
@@ -194,6 +210,7 @@ async fn leaf_search_stream_single_split(
     warmup_info.simplify();
 
     warmup(&searcher, &warmup_info).await?;
+    record_num_bytes_read(&searcher_context, index_uid_opt, cache.get_num_bytes());
 
     let span = info_span!(
         "collect_fast_field",
@@ -505,6 +522,7 @@ mod tests {
             fast_field: "ts".to_string(),
             output_format: 0,
             partition_by_field: None,
+            security_context: None,
         };
         let splits = test_sandbox
             .metastore()
@@ -521,6 +539,7 @@ mod tests {
         let mut single_node_stream = leaf_search_stream(
             searcher_context,
             request,
+            Some(test_sandbox.index_uid()),
             test_sandbox.storage(),
             splits_offsets,
             test_sandbox.doc_mapper(),
@@ -584,6 +603,7 @@ mod tests {
             fast_field: "ts".to_string(),
             output_format: 0,
             partition_by_field: None,
+            security_context: None,
         };
         let splits = test_sandbox
             .metastore()
@@ -599,6 +619,7 @@ mod tests {
         let mut single_node_stream = leaf_search_stream(
             searcher_context,
             request,
+            Some(test_sandbox.index_uid()),
             test_sandbox.storage(),
             splits_offsets,
             test_sandbox.doc_mapper(),
@@ -641,6 +662,7 @@ mod tests {
             fast_field: "app".to_string(),
             output_format: 0,
             partition_by_field: None,
+            security_context: None,
         };
         let splits = test_sandbox
             .metastore()
@@ -656,6 +678,7 @@ mod tests {
         let mut single_node_stream = leaf_search_stream(
             searcher_context,
             request,
+            Some(test_sandbox.index_uid()),
             test_sandbox.storage(),
             splits_offsets,
             test_sandbox.doc_mapper(),
@@ -731,6 +754,7 @@ mod tests {
             fast_field: "fast_field".to_string(),
             output_format: 1,
             partition_by_field: Some(String::from("partition_by_fast_field")),
+            security_context: None,
         };
         let splits = test_sandbox
             .metastore()
@@ -746,6 +770,7 @@ mod tests {
         let mut single_node_stream = leaf_search_stream(
             searcher_context,
             request,
+            Some(test_sandbox.index_uid()),
             test_sandbox.storage(),
             splits_offsets,
             test_sandbox.doc_mapper(),
@@ -22,17 +22,20 @@ use quickwit_doc_mapper::tag_pruning::extract_tags_from_query;
 use quickwit_metastore::IndexMetadataResponseExt;
 use quickwit_proto::metastore::{IndexMetadataRequest, MetastoreService, MetastoreServiceClient};
 use quickwit_proto::search::{LeafSearchStreamRequest, SearchRequest, SearchStreamRequest};
+use quickwit_proto::types::IndexUid;
 use quickwit_query::query_ast::QueryAst;
 use tokio_stream::StreamMap;
 use tracing::*;
 
 use crate::cluster_client::ClusterClient;
-use crate::root::{refine_start_end_timestamp_from_ast, SearchJob};
-use crate::{list_relevant_splits, SearchError};
+use crate::root::{query_usage_deltas, refine_start_end_timestamp_from_ast, SearchJob};
+use crate::security::apply_security_policies_to_stream;
+use crate::{list_relevant_splits, SearchError, SearcherContext};
 
 /// Perform a distributed search stream.
-#[instrument(skip(metastore, cluster_client))]
+#[instrument(skip(searcher_context, metastore, cluster_client))]
 pub async fn root_search_stream(
+    searcher_context: &SearcherContext,
     mut search_stream_request: SearchStreamRequest,
     mut metastore: MetastoreServiceClient,
     cluster_client: ClusterClient,
@@ -45,6 +48,7 @@ pub async fn root_search_stream(
         .index_metadata(index_metadata_request)
         .await?
         .deserialize_index_metadata()?;
+    apply_security_policies_to_stream(&index_metadata, &mut search_stream_request)?;
     let index_uid = index_metadata.index_uid.clone();
     let index_config = index_metadata.into_index_config();
 
@@ -55,7 +59,8 @@ pub async fn root_search_stream(
 
     let query_ast: QueryAst = serde_json::from_str(&search_stream_request.query_ast)
         .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
-    let query_ast_resolved = query_ast.parse_user_query(doc_mapper.default_search_fields())?;
+    let query_ast_resolved =
+        query_ast.parse_user_query_with_defaults(doc_mapper.user_query_defaults())?;
     let tags_filter_ast = extract_tags_from_query(query_ast_resolved.clone());
 
     if let Some(timestamp_field) = doc_mapper.timestamp_field_name() {
@@ -73,7 +78,7 @@ pub async fn root_search_stream(
 
     let search_request = SearchRequest::try_from(search_stream_request.clone())?;
     let split_metadatas = list_relevant_splits(
-        vec![index_uid],
+        vec![index_uid.clone()],
         search_request.start_timestamp,
         search_request.end_timestamp,
         tags_filter_ast,
@@ -98,6 +103,8 @@ pub async fn root_search_stream(
             &search_stream_request,
             &doc_mapper_str,
             index_uri.as_ref(),
+            index_config.storage_credentials_opt.as_deref(),
+            &index_uid,
             client_jobs,
         );
         let leaf_stream = cluster_client
@@ -105,6 +112,10 @@ pub async fn root_search_stream(
             .await;
         stream_map.insert(leaf_ord, leaf_stream);
     }
+    searcher_context
+        .index_usage_accumulator
+        .record(query_usage_deltas([index_uid]));
+
     Ok(stream_map
         .map(|(_leaf_ord, result)| result)
         .map_ok(|leaf_response| Bytes::from(leaf_response.data)))
@@ -114,6 +125,8 @@ fn jobs_to_leaf_request(
     request: &SearchStreamRequest,
     doc_mapper_str: &str,
     index_uri: &str, // TODO make Uri
+    storage_credentials_opt: Option<&str>,
+    index_uid: &IndexUid,
     jobs: Vec<SearchJob>,
 ) -> LeafSearchStreamRequest {
     LeafSearchStreamRequest {
@@ -121,6 +134,8 @@ fn jobs_to_leaf_request(
         split_offsets: jobs.into_iter().map(Into::into).collect(),
         doc_mapper: doc_mapper_str.to_string(),
         index_uri: index_uri.to_string(),
+        index_uid: index_uid.to_string(),
+        storage_credentials: storage_credentials_opt.map(ToString::to_string),
     }
 }
 
@@ -184,6 +199,7 @@ mod tests {
         let search_job_placer = SearchJobPlacer::new(searcher_pool);
         let cluster_client = ClusterClient::new(search_job_placer.clone());
         let result: Vec<Bytes> = root_search_stream(
+            &SearcherContext::for_test(),
             request,
             MetastoreServiceClient::from_mock(mock_metastore),
             cluster_client,
@@ -242,6 +258,7 @@ mod tests {
         let search_job_placer = SearchJobPlacer::new(searcher_pool);
         let cluster_client = ClusterClient::new(search_job_placer.clone());
         let stream = root_search_stream(
+            &SearcherContext::for_test(),
             request,
             MetastoreServiceClient::from_mock(mock_metastore),
             cluster_client,
@@ -311,6 +328,7 @@ mod tests {
         let search_job_placer = SearchJobPlacer::new(searcher_pool);
         let cluster_client = ClusterClient::new(search_job_placer.clone());
         let stream = root_search_stream(
+            &SearcherContext::for_test(),
             request,
             MetastoreServiceClient::from_mock(mock_metastore),
             cluster_client,
@@ -342,6 +360,7 @@ mod tests {
         let search_job_placer = SearchJobPlacer::new(searcher_pool);
         let metastore = MetastoreServiceClient::from_mock(mock_metastore);
         assert!(root_search_stream(
+            &SearcherContext::for_test(),
             quickwit_proto::search::SearchStreamRequest {
                 index_id: "test-index".to_string(),
                 query_ast: qast_json_helper(r#"invalid_field:"test""#, &[]),
@@ -357,6 +376,7 @@ mod tests {
         .is_err());
 
         assert!(root_search_stream(
+            &SearcherContext::for_test(),
             quickwit_proto::search::SearchStreamRequest {
                 index_id: "test-index".to_string(),
                 query_ast: qast_json_helper("test", &["invalid_field"]),
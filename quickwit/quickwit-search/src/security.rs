@@ -0,0 +1,1181 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+
+use itertools::Itertools;
+use quickwit_config::SecurityPolicy;
+use quickwit_metastore::IndexMetadata;
+use quickwit_proto::search::{
+    ListFieldsRequest, ListFieldsResponse, ListTermsRequest, SearchRequest, SearchResponse,
+    SearchStreamRequest, SecurityContext,
+};
+use quickwit_proto::types::IndexId;
+use quickwit_query::query_ast::{
+    query_ast_from_user_text, BoolQuery, FieldPresenceQuery, FullTextQuery, PhrasePrefixQuery,
+    QueryAst, QueryAstTransformer, QueryAstVisitor, RangeQuery, RegexQuery, TermQuery,
+    TermSetQuery, UserInputQuery, WildcardQuery,
+};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use crate::SearchError;
+
+const CLAIMS_PLACEHOLDER_PREFIX: &str = "{claims.";
+
+/// Token standing for the value of a claim in a security filter while it is parsed.
+const CLAIM_TOKEN_PREFIX: &str = "__qw_claim_";
+
+/// Sort fields that do not expose the values of a document field.
+const INTERNAL_SORT_FIELDS: [&str; 3] = ["_score", "_doc", "_shard_doc"];
+
+/// Fields that can be retrieved by the caller, for the indexes whose security policy restricts
+/// them.
+#[derive(Debug, Default)]
+pub(crate) struct RetrievableFields {
+    retrievable_fields_per_index: HashMap<IndexId, Vec<String>>,
+}
+
+impl RetrievableFields {
+    pub fn is_unrestricted(&self) -> bool {
+        self.retrievable_fields_per_index.is_empty()
+    }
+
+    /// Returns true if the caller can retrieve the values of `field_name` in index `index_id`.
+    pub fn is_retrievable(&self, index_id: &str, field_name: &str) -> bool {
+        self.retrievable_fields_per_index
+            .get(index_id)
+            .map_or(true, |retrievable_fields| {
+                is_retrievable_field(retrievable_fields, field_name)
+            })
+    }
+
+    /// Returns true if the caller can retrieve the values of `field_name` in all the indexes.
+    fn is_retrievable_in_all_indexes(&self, field_name: &str) -> bool {
+        self.retrievable_fields_per_index
+            .values()
+            .all(|retrievable_fields| is_retrievable_field(retrievable_fields, field_name))
+    }
+
+    fn ensure_retrievable(&self, field_name: &str, usage: &str) -> crate::Result<()> {
+        if self.is_retrievable_in_all_indexes(field_name) {
+            return Ok(());
+        }
+        Err(SearchError::Forbidden(format!(
+            "the security policy does not allow {usage} field `{field_name}`"
+        )))
+    }
+
+    /// Removes the fields that cannot be retrieved from the hits of the search response.
+    pub fn restrict_search_response(&self, search_response: &mut SearchResponse) {
+        if self.is_unrestricted() {
+            return;
+        }
+        for hit in &mut search_response.hits {
+            let Some(retrievable_fields) = self.retrievable_fields_per_index.get(&hit.index_id)
+            else {
+                continue;
+            };
+            hit.json = restrict_json_str(&hit.json, retrievable_fields);
+
+            if let Some(snippet_json) = &hit.snippet {
+                hit.snippet = Some(restrict_json_str(snippet_json, retrievable_fields));
+            }
+        }
+    }
+
+    /// Removes the fields that cannot be retrieved from the list fields response.
+    pub fn restrict_list_fields_response(&self, list_fields_response: &mut ListFieldsResponse) {
+        if self.is_unrestricted() {
+            return;
+        }
+        for field_entry in &mut list_fields_response.fields {
+            let field_name = &field_entry.field_name;
+            field_entry
+                .index_ids
+                .retain(|index_id| self.is_retrievable(index_id, field_name));
+            field_entry
+                .non_searchable_index_ids
+                .retain(|index_id| self.is_retrievable(index_id, field_name));
+            field_entry
+                .non_aggregatable_index_ids
+                .retain(|index_id| self.is_retrievable(index_id, field_name));
+        }
+        list_fields_response
+            .fields
+            .retain(|field_entry| !field_entry.index_ids.is_empty());
+    }
+}
+
+/// Enforces the security policies of the targeted indexes on a search request.
+///
+/// The mandatory filter of the caller's role is injected into the query, and the fields the
+/// caller is allowed to retrieve are returned so they can be enforced on the response. Querying,
+/// sorting and aggregating on fields the caller cannot retrieve is rejected since matching
+/// documents, sort values and aggregation results would expose them. Requests without a security
+/// context and indexes without security policies are left untouched.
+pub(crate) fn apply_security_policies(
+    indexes_metadata: &[IndexMetadata],
+    search_request: &mut SearchRequest,
+) -> crate::Result<RetrievableFields> {
+    let Some(security_context) = &search_request.security_context else {
+        return Ok(RetrievableFields::default());
+    };
+    let security_policies = find_security_policies(indexes_metadata, security_context)?;
+    let retrievable_fields = retrievable_fields(&security_policies);
+
+    // The query is checked before the filter is injected: the filter may target fields the caller
+    // cannot retrieve.
+    let query_ast = parse_query_ast(&search_request.query_ast)?;
+    ensure_query_fields_retrievable(&retrievable_fields, indexes_metadata, &query_ast)?;
+
+    if let Some(filter_ast) = common_filter(&security_policies, &security_context.claims)? {
+        search_request.query_ast = add_filter(query_ast, filter_ast)?;
+    }
+    if retrievable_fields.is_unrestricted() {
+        return Ok(retrievable_fields);
+    }
+    if search_request.scroll_ttl_secs.is_some() {
+        return Err(SearchError::Forbidden(
+            "the scroll API is not available when the security policy restricts the retrievable \
+             fields"
+                .to_string(),
+        ));
+    }
+    for sort_field in &search_request.sort_fields {
+        if INTERNAL_SORT_FIELDS.contains(&sort_field.field_name.as_str()) {
+            continue;
+        }
+        retrievable_fields.ensure_retrievable(&sort_field.field_name, "sorting on")?;
+    }
+    if let Some(aggregation_request) = &search_request.aggregation_request {
+        let aggregation_json: JsonValue = serde_json::from_str(aggregation_request)
+            .map_err(|error| SearchError::InvalidAggregationRequest(error.to_string()))?;
+        let mut field_names = Vec::new();
+        collect_aggregation_fields(&aggregation_json, &mut field_names);
+
+        for field_name in field_names {
+            retrievable_fields.ensure_retrievable(field_name, "aggregating on")?;
+        }
+    }
+    Ok(retrievable_fields)
+}
+
+/// Enforces the security policy of the targeted index on a search stream request.
+///
+/// The mandatory filter of the caller's role is injected into the query, and the request is
+/// rejected if it queries or extracts fields the caller cannot retrieve.
+pub(crate) fn apply_security_policies_to_stream(
+    index_metadata: &IndexMetadata,
+    search_stream_request: &mut SearchStreamRequest,
+) -> crate::Result<()> {
+    let Some(security_context) = &search_stream_request.security_context else {
+        return Ok(());
+    };
+    let security_policies =
+        find_security_policies(std::slice::from_ref(index_metadata), security_context)?;
+    let retrievable_fields = retrievable_fields(&security_policies);
+
+    let query_ast = parse_query_ast(&search_stream_request.query_ast)?;
+    ensure_query_fields_retrievable(
+        &retrievable_fields,
+        std::slice::from_ref(index_metadata),
+        &query_ast,
+    )?;
+
+    if let Some(filter_ast) = common_filter(&security_policies, &security_context.claims)? {
+        search_stream_request.query_ast = add_filter(query_ast, filter_ast)?;
+    }
+    retrievable_fields.ensure_retrievable(&search_stream_request.fast_field, "streaming")?;
+
+    if let Some(partition_by_field) = &search_stream_request.partition_by_field {
+        retrievable_fields.ensure_retrievable(partition_by_field, "partitioning by")?;
+    }
+    for snippet_field in &search_stream_request.snippet_fields {
+        retrievable_fields.ensure_retrievable(snippet_field, "extracting snippets from")?;
+    }
+    Ok(())
+}
+
+/// Checks that the caller's role can access the targeted indexes of a list fields request and
+/// returns the fields the caller is allowed to list.
+pub(crate) fn apply_security_policies_to_list_fields(
+    indexes_metadata: &[IndexMetadata],
+    list_fields_request: &ListFieldsRequest,
+) -> crate::Result<RetrievableFields> {
+    let Some(security_context) = &list_fields_request.security_context else {
+        return Ok(RetrievableFields::default());
+    };
+    let security_policies = find_security_policies(indexes_metadata, security_context)?;
+    Ok(retrievable_fields(&security_policies))
+}
+
+/// Checks that the caller's role can list the terms of a field of the targeted indexes.
+///
+/// Term dictionaries cannot be restricted to the documents matching a filter, so the request is
+/// rejected if the security policy of the caller's role filters the documents of one of the
+/// indexes or does not allow retrieving the field.
+pub(crate) fn apply_security_policies_to_list_terms(
+    indexes_metadata: &[IndexMetadata],
+    list_terms_request: &ListTermsRequest,
+) -> crate::Result<()> {
+    let Some(security_context) = &list_terms_request.security_context else {
+        return Ok(());
+    };
+    let security_policies = find_security_policies(indexes_metadata, security_context)?;
+
+    for (index_id, security_policy_opt) in &security_policies {
+        if security_policy_opt.is_some_and(|security_policy| security_policy.filter.is_some()) {
+            return Err(SearchError::Forbidden(format!(
+                "the security policy of index `{index_id}` does not allow listing terms"
+            )));
+        }
+    }
+    retrievable_fields(&security_policies)
+        .ensure_retrievable(&list_terms_request.field, "listing the terms of")
+}
+
+/// Authorizes the caller to update the tags of the documents of an index matching `query_ast`, and
+/// returns the query restricted to the documents the caller can search.
+///
+/// The update is rejected if the security policy of the caller's role does not allow updates or
+/// if the caller cannot retrieve one of the queried or updated fields. Requests without a security
+/// context and indexes without security policies are left untouched.
+pub fn authorize_tag_updates(
+    index_metadata: &IndexMetadata,
+    security_context_opt: Option<&SecurityContext>,
+    query_ast: QueryAst,
+    field_names: &[&str],
+) -> crate::Result<QueryAst> {
+    let Some(security_context) = security_context_opt else {
+        return Ok(query_ast);
+    };
+    let security_policies =
+        find_security_policies(std::slice::from_ref(index_metadata), security_context)?;
+    let Some((index_id, Some(security_policy))) = security_policies.first() else {
+        return Ok(query_ast);
+    };
+    if !security_policy.allow_updates {
+        return Err(SearchError::Forbidden(format!(
+            "role `{}` is not allowed to update index `{index_id}`",
+            security_context.role
+        )));
+    }
+    let retrievable_fields = retrievable_fields(&security_policies);
+
+    ensure_query_fields_retrievable(
+        &retrievable_fields,
+        std::slice::from_ref(index_metadata),
+        &query_ast,
+    )?;
+
+    for field_name in field_names {
+        retrievable_fields.ensure_retrievable(field_name, "updating")?;
+    }
+    let Some(filter_template) = &security_policy.filter else {
+        return Ok(query_ast);
+    };
+    let filter_ast = render_filter(filter_template, &security_context.claims)?;
+    let filtered_query_ast = BoolQuery {
+        must: vec![query_ast],
+        filter: vec![filter_ast],
+        ..Default::default()
+    }
+    .into();
+    Ok(filtered_query_ast)
+}
+
+/// Security policy of the caller's role for each targeted index, or `None` if the index does not
+/// have security policies.
+type IndexSecurityPolicies<'a> = Vec<(&'a str, Option<&'a SecurityPolicy>)>;
+
+fn find_security_policies<'a>(
+    indexes_metadata: &'a [IndexMetadata],
+    security_context: &SecurityContext,
+) -> crate::Result<IndexSecurityPolicies<'a>> {
+    let mut index_security_policies = Vec::with_capacity(indexes_metadata.len());
+
+    for index_metadata in indexes_metadata {
+        let index_id = index_metadata.index_config.index_id.as_str();
+        let security_policies = &index_metadata
+            .index_config
+            .search_settings
+            .security_policies;
+
+        if security_policies.is_empty() {
+            index_security_policies.push((index_id, None));
+            continue;
+        }
+        let security_policy = find_security_policy(security_policies, security_context, index_id)?;
+        index_security_policies.push((index_id, Some(security_policy)));
+    }
+    Ok(index_security_policies)
+}
+
+fn find_security_policy<'a>(
+    security_policies: &'a [SecurityPolicy],
+    security_context: &SecurityContext,
+    index_id: &str,
+) -> crate::Result<&'a SecurityPolicy> {
+    security_policies
+        .iter()
+        .find(|security_policy| security_policy.role == security_context.role)
+        .ok_or_else(|| {
+            SearchError::Forbidden(format!(
+                "role `{}` is not allowed to search index `{index_id}`",
+                security_context.role
+            ))
+        })
+}
+
+fn retrievable_fields(index_security_policies: &IndexSecurityPolicies<'_>) -> RetrievableFields {
+    let retrievable_fields_per_index = index_security_policies
+        .iter()
+        .filter_map(|(index_id, security_policy_opt)| {
+            let fields = security_policy_opt.as_ref()?.retrievable_fields.as_ref()?;
+            Some((index_id.to_string(), fields.clone()))
+        })
+        .collect();
+    RetrievableFields {
+        retrievable_fields_per_index,
+    }
+}
+
+/// Returns the filter enforced on all the targeted indexes. Indexes with different filters cannot
+/// be searched together.
+fn common_filter(
+    index_security_policies: &IndexSecurityPolicies<'_>,
+    claims: &BTreeMap<String, String>,
+) -> crate::Result<Option<QueryAst>> {
+    let mut filter_opt: Option<Option<QueryAst>> = None;
+
+    for (_index_id, security_policy_opt) in index_security_policies {
+        let filter = security_policy_opt
+            .and_then(|security_policy| security_policy.filter.as_ref())
+            .map(|filter_template| render_filter(filter_template, claims))
+            .transpose()?;
+
+        if *filter_opt.get_or_insert_with(|| filter.clone()) != filter {
+            return Err(conflicting_filters_error());
+        }
+    }
+    Ok(filter_opt.flatten())
+}
+
+fn parse_query_ast(query_ast_json: &str) -> crate::Result<QueryAst> {
+    serde_json::from_str(query_ast_json)
+        .map_err(|_| SearchError::Internal("query ast must be present".to_string()))
+}
+
+fn add_filter(query_ast: QueryAst, filter_ast: QueryAst) -> crate::Result<String> {
+    let filtered_query_ast: QueryAst = BoolQuery {
+        must: vec![query_ast],
+        filter: vec![filter_ast],
+        ..Default::default()
+    }
+    .into();
+    let filtered_query_ast_json = serde_json::to_string(&filtered_query_ast)?;
+    Ok(filtered_query_ast_json)
+}
+
+fn conflicting_filters_error() -> SearchError {
+    SearchError::Forbidden(
+        "the targeted indexes enforce different security filters, search them separately"
+            .to_string(),
+    )
+}
+
+/// Builds the filter of a security policy.
+///
+/// The `{claims.<name>}` placeholders must be the value of a field clause, e.g.
+/// `tenant_id:{claims.tenant}`. They are turned into term queries on the caller's claims after the
+/// filter is parsed, so claims cannot alter the structure of the filter.
+fn render_filter(
+    filter_template: &str,
+    claims: &BTreeMap<String, String>,
+) -> crate::Result<QueryAst> {
+    let mut filter_text = String::with_capacity(filter_template.len());
+    let mut claim_values: HashMap<String, String> = HashMap::new();
+    let mut remaining = filter_template;
+
+    while let Some(start) = remaining.find(CLAIMS_PLACEHOLDER_PREFIX) {
+        filter_text.push_str(&remaining[..start]);
+        let placeholder = &remaining[start + CLAIMS_PLACEHOLDER_PREFIX.len()..];
+
+        let Some(end) = placeholder.find('}') else {
+            return Err(SearchError::Internal(format!(
+                "security filter `{filter_template}` contains an unclosed claim placeholder"
+            )));
+        };
+        let claim_name = &placeholder[..end];
+        let Some(claim_value) = claims.get(claim_name) else {
+            return Err(SearchError::Forbidden(format!(
+                "API key is missing the claim `{claim_name}` required by the security policy"
+            )));
+        };
+        let claim_token = format!("{CLAIM_TOKEN_PREFIX}{}", claim_values.len());
+        filter_text.push_str(&claim_token);
+        claim_values.insert(claim_token, claim_value.clone());
+        remaining = &placeholder[end + 1..];
+    }
+    filter_text.push_str(remaining);
+
+    let invalid_filter_error = |reason: String| {
+        SearchError::Internal(format!(
+            "security filter `{filter_template}` is invalid: {reason}"
+        ))
+    };
+    let filter_ast = query_ast_from_user_text(&filter_text, None)
+        .parse_user_query(&[])
+        .map_err(|error| invalid_filter_error(error.to_string()))?;
+    let filter_ast = ClaimTermsBuilder {
+        claim_values: &claim_values,
+    }
+    .transform(filter_ast)?
+    .unwrap_or(QueryAst::MatchAll);
+
+    let filter_ast_json = serde_json::to_string(&filter_ast)?;
+
+    if filter_ast_json.contains(CLAIM_TOKEN_PREFIX) {
+        return Err(invalid_filter_error(
+            "claim placeholders must be the value of a field clause".to_string(),
+        ));
+    }
+    Ok(filter_ast)
+}
+
+/// Replaces the clauses whose value is a claim token with term queries on the claim value.
+struct ClaimTermsBuilder<'a> {
+    claim_values: &'a HashMap<String, String>,
+}
+
+impl QueryAstTransformer for ClaimTermsBuilder<'_> {
+    type Err = SearchError;
+
+    fn transform_full_text(
+        &mut self,
+        full_text_query: FullTextQuery,
+    ) -> Result<Option<QueryAst>, Self::Err> {
+        let Some(claim_value) = self.claim_values.get(&full_text_query.text) else {
+            return Ok(Some(full_text_query.into()));
+        };
+        let term_query = TermQuery {
+            field: full_text_query.field,
+            value: claim_value.clone(),
+        };
+        Ok(Some(term_query.into()))
+    }
+}
+
+fn is_retrievable_field(retrievable_fields: &[String], field_name: &str) -> bool {
+    retrievable_fields.iter().any(|retrievable_field| {
+        field_name
+            .strip_prefix(retrievable_field.as_str())
+            .is_some_and(|suffix| suffix.is_empty() || suffix.starts_with('.'))
+    })
+}
+
+/// Checks that the caller can retrieve all the fields targeted by a query, since the documents
+/// matching a query on a field expose its values. Query strings are parsed with the default search
+/// fields of the targeted indexes.
+fn ensure_query_fields_retrievable(
+    retrievable_fields: &RetrievableFields,
+    indexes_metadata: &[IndexMetadata],
+    query_ast: &QueryAst,
+) -> crate::Result<()> {
+    if retrievable_fields.is_unrestricted() {
+        return Ok(());
+    }
+    let default_search_fields: Vec<String> = indexes_metadata
+        .iter()
+        .flat_map(|index_metadata| {
+            index_metadata
+                .index_config
+                .search_settings
+                .default_search_fields
+                .iter()
+                .cloned()
+        })
+        .sorted()
+        .dedup()
+        .collect();
+    QueryFieldsChecker {
+        retrievable_fields,
+        default_search_fields: &default_search_fields,
+    }
+    .visit(query_ast)
+}
+
+struct QueryFieldsChecker<'b> {
+    retrievable_fields: &'b RetrievableFields,
+    default_search_fields: &'b [String],
+}
+
+impl QueryFieldsChecker<'_> {
+    fn ensure_retrievable(&self, field_name: &str) -> crate::Result<()> {
+        self.retrievable_fields
+            .ensure_retrievable(field_name, "querying")
+    }
+}
+
+impl<'a> QueryAstVisitor<'a> for QueryFieldsChecker<'_> {
+    type Err = SearchError;
+
+    fn visit_term(&mut self, term_query: &'a TermQuery) -> crate::Result<()> {
+        self.ensure_retrievable(&term_query.field)
+    }
+
+    fn visit_term_set(&mut self, term_set_query: &'a TermSetQuery) -> crate::Result<()> {
+        for field_name in term_set_query.terms_per_field.keys() {
+            self.ensure_retrievable(field_name)?;
+        }
+        Ok(())
+    }
+
+    fn visit_full_text(&mut self, full_text_query: &'a FullTextQuery) -> crate::Result<()> {
+        self.ensure_retrievable(&full_text_query.field)
+    }
+
+    fn visit_phrase_prefix(
+        &mut self,
+        phrase_prefix_query: &'a PhrasePrefixQuery,
+    ) -> crate::Result<()> {
+        self.ensure_retrievable(&phrase_prefix_query.field)
+    }
+
+    fn visit_range(&mut self, range_query: &'a RangeQuery) -> crate::Result<()> {
+        self.ensure_retrievable(&range_query.field)
+    }
+
+    fn visit_user_text(&mut self, user_text_query: &'a UserInputQuery) -> crate::Result<()> {
+        let query_ast = user_text_query
+            .parse_user_query(self.default_search_fields)
+            .map_err(|error| SearchError::InvalidQuery(error.to_string()))?;
+        QueryFieldsChecker {
+            retrievable_fields: self.retrievable_fields,
+            default_search_fields: self.default_search_fields,
+        }
+        .visit(&query_ast)
+    }
+
+    fn visit_exists(&mut self, exists_query: &'a FieldPresenceQuery) -> crate::Result<()> {
+        self.ensure_retrievable(&exists_query.field)
+    }
+
+    fn visit_wildcard(&mut self, wildcard_query: &'a WildcardQuery) -> crate::Result<()> {
+        self.ensure_retrievable(&wildcard_query.field)
+    }
+
+    fn visit_regex(&mut self, regex_query: &'a RegexQuery) -> crate::Result<()> {
+        self.ensure_retrievable(&regex_query.field)
+    }
+}
+
+/// Collects the fields referenced by an aggregation request, including the fields returned and
+/// sorted on by `top_hits` aggregations.
+fn collect_aggregation_fields<'a>(json_value: &'a JsonValue, field_names: &mut Vec<&'a str>) {
+    match json_value {
+        JsonValue::Object(json_obj) => {
+            for (key, value) in json_obj {
+                match (key.as_str(), value) {
+                    ("field", JsonValue::String(field_name)) => field_names.push(field_name),
+                    ("docvalue_fields", JsonValue::Array(values)) => {
+                        field_names.extend(values.iter().filter_map(JsonValue::as_str));
+                    }
+                    ("sort", JsonValue::Array(values)) => {
+                        for value in values {
+                            match value {
+                                JsonValue::String(field_name) => field_names.push(field_name),
+                                JsonValue::Object(sort_obj) => {
+                                    field_names.extend(sort_obj.keys().map(String::as_str));
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    ("sort", JsonValue::Object(sort_obj)) => {
+                        field_names.extend(sort_obj.keys().map(String::as_str));
+                    }
+                    _ => collect_aggregation_fields(value, field_names),
+                }
+            }
+        }
+        JsonValue::Array(values) => {
+            for value in values {
+                collect_aggregation_fields(value, field_names);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn restrict_json_str(json_str: &str, retrievable_fields: &[String]) -> String {
+    let Ok(JsonValue::Object(json_obj)) = serde_json::from_str::<JsonValue>(json_str) else {
+        return "{}".to_string();
+    };
+    let restricted_json_obj = restrict_json_obj(json_obj, retrievable_fields);
+    serde_json::to_string(&restricted_json_obj).expect("JSON object should be serializable")
+}
+
+/// Keeps only the values of `json_obj` located at the retrievable field paths. Dots in field
+/// paths denote nested objects.
+fn restrict_json_obj(
+    mut json_obj: JsonMap<String, JsonValue>,
+    retrievable_fields: &[String],
+) -> JsonMap<String, JsonValue> {
+    let mut restricted_json_obj = JsonMap::new();
+
+    for retrievable_field in retrievable_fields {
+        // Flattened keys, e.g. in snippets, match the field path as a whole.
+        if let Some(json_value) = json_obj.remove(retrievable_field) {
+            restricted_json_obj.insert(retrievable_field.clone(), json_value);
+            continue;
+        }
+        let Some((head, tail)) = retrievable_field.split_once('.') else {
+            continue;
+        };
+        let Some(JsonValue::Object(child_json_obj)) = json_obj.get(head) else {
+            continue;
+        };
+        let restricted_child_json_obj =
+            restrict_json_obj(child_json_obj.clone(), &[tail.to_string()]);
+
+        if restricted_child_json_obj.is_empty() {
+            continue;
+        }
+        let entry = restricted_json_obj
+            .entry(head)
+            .or_insert_with(|| JsonValue::Object(JsonMap::new()));
+        if let JsonValue::Object(entry_json_obj) = entry {
+            entry_json_obj.extend(restricted_child_json_obj);
+        }
+    }
+    restricted_json_obj
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_config::SearchSettings;
+    use quickwit_proto::search::{Hit, ListFieldsEntryResponse, SortField};
+    use serde_json::json;
+
+    use super::*;
+
+    fn index_metadata_with_policies(
+        index_id: &str,
+        security_policies: Vec<SecurityPolicy>,
+    ) -> IndexMetadata {
+        let mut index_metadata =
+            IndexMetadata::for_test(index_id, &format!("ram:///indexes/{index_id}"));
+        index_metadata.index_config.search_settings = SearchSettings {
+            security_policies,
+            ..Default::default()
+        };
+        index_metadata
+    }
+
+    fn support_policy() -> SecurityPolicy {
+        SecurityPolicy {
+            role: "support".to_string(),
+            retrievable_fields: Some(vec!["timestamp".to_string(), "user.name".to_string()]),
+            filter: Some("tenant_id:{claims.tenant}".to_string()),
+            allow_updates: true,
+        }
+    }
+
+    fn search_request(security_context: Option<SecurityContext>) -> SearchRequest {
+        SearchRequest {
+            index_id_patterns: vec!["test-index".to_string()],
+            query_ast: serde_json::to_string(&QueryAst::MatchAll).unwrap(),
+            security_context,
+            ..Default::default()
+        }
+    }
+
+    fn security_context(role: &str, claims: &[(&str, &str)]) -> SecurityContext {
+        SecurityContext {
+            role: role.to_string(),
+            claims: claims
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_apply_security_policies_without_security_context() {
+        let indexes_metadata = [index_metadata_with_policies(
+            "test-index",
+            vec![support_policy()],
+        )];
+        let mut search_request = search_request(None);
+        let retrievable_fields =
+            apply_security_policies(&indexes_metadata, &mut search_request).unwrap();
+        assert!(retrievable_fields.is_unrestricted());
+        assert_eq!(
+            search_request.query_ast,
+            serde_json::to_string(&QueryAst::MatchAll).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_apply_security_policies_injects_filter() {
+        let indexes_metadata = [index_metadata_with_policies(
+            "test-index",
+            vec![support_policy()],
+        )];
+        let mut search_request =
+            search_request(Some(security_context("support", &[("tenant", "acme")])));
+        let retrievable_fields =
+            apply_security_policies(&indexes_metadata, &mut search_request).unwrap();
+        assert!(!retrievable_fields.is_unrestricted());
+
+        let query_ast: QueryAst = serde_json::from_str(&search_request.query_ast).unwrap();
+        let expected_query_ast: QueryAst = BoolQuery {
+            must: vec![QueryAst::MatchAll],
+            filter: vec![TermQuery {
+                field: "tenant_id".to_string(),
+                value: "acme".to_string(),
+            }
+            .into()],
+            ..Default::default()
+        }
+        .into();
+        assert_eq!(query_ast, expected_query_ast);
+    }
+
+    #[test]
+    fn test_apply_security_policies_forbids_restricted_sort_fields() {
+        let indexes_metadata = [index_metadata_with_policies(
+            "test-index",
+            vec![support_policy()],
+        )];
+        let mut search_request =
+            search_request(Some(security_context("support", &[("tenant", "acme")])));
+        search_request.sort_fields = vec![SortField {
+            field_name: "user.name".to_string(),
+            ..Default::default()
+        }];
+        apply_security_policies(&indexes_metadata, &mut search_request).unwrap();
+
+        search_request.sort_fields = vec![SortField {
+            field_name: "_score".to_string(),
+            ..Default::default()
+        }];
+        apply_security_policies(&indexes_metadata, &mut search_request).unwrap();
+
+        search_request.sort_fields = vec![SortField {
+            field_name: "secret".to_string(),
+            ..Default::default()
+        }];
+        let error = apply_security_policies(&indexes_metadata, &mut search_request).unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(message) if message.contains("secret")));
+    }
+
+    #[test]
+    fn test_apply_security_policies_forbids_restricted_aggregation_fields() {
+        let indexes_metadata = [index_metadata_with_policies(
+            "test-index",
+            vec![support_policy()],
+        )];
+        let mut search_request =
+            search_request(Some(security_context("support", &[("tenant", "acme")])));
+        search_request.aggregation_request = Some(
+            json!({
+                "users": {
+                    "terms": {"field": "user.name"},
+                    "aggs": {"latest": {"max": {"field": "timestamp"}}}
+                }
+            })
+            .to_string(),
+        );
+        apply_security_policies(&indexes_metadata, &mut search_request).unwrap();
+
+        search_request.aggregation_request = Some(
+            json!({
+                "users": {
+                    "terms": {"field": "user.name"},
+                    "aggs": {"secrets": {"terms": {"field": "secret"}}}
+                }
+            })
+            .to_string(),
+        );
+        let error = apply_security_policies(&indexes_metadata, &mut search_request).unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(message) if message.contains("secret")));
+
+        search_request.aggregation_request = Some(
+            json!({
+                "top": {
+                    "top_hits": {
+                        "size": 1,
+                        "sort": [{"timestamp": "desc"}],
+                        "docvalue_fields": ["user.email"]
+                    }
+                }
+            })
+            .to_string(),
+        );
+        let error = apply_security_policies(&indexes_metadata, &mut search_request).unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(message) if message.contains("user.email")));
+    }
+
+    #[test]
+    fn test_apply_security_policies_to_stream() {
+        let index_metadata = index_metadata_with_policies("test-index", vec![support_policy()]);
+        let mut search_stream_request = SearchStreamRequest {
+            index_id: "test-index".to_string(),
+            query_ast: serde_json::to_string(&QueryAst::MatchAll).unwrap(),
+            fast_field: "timestamp".to_string(),
+            security_context: Some(security_context("support", &[("tenant", "acme")])),
+            ..Default::default()
+        };
+        apply_security_policies_to_stream(&index_metadata, &mut search_stream_request).unwrap();
+
+        let query_ast: QueryAst = serde_json::from_str(&search_stream_request.query_ast).unwrap();
+        let QueryAst::Bool(bool_query) = query_ast else {
+            panic!("expected a bool query");
+        };
+        assert_eq!(bool_query.filter.len(), 1);
+
+        search_stream_request.fast_field = "secret".to_string();
+        let error = apply_security_policies_to_stream(&index_metadata, &mut search_stream_request)
+            .unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(_)));
+
+        search_stream_request.query_ast =
+            serde_json::to_string(&query_ast_from_user_text("ssn:123*", None)).unwrap();
+        search_stream_request.fast_field = "timestamp".to_string();
+        let error = apply_security_policies_to_stream(&index_metadata, &mut search_stream_request)
+            .unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(_)));
+    }
+
+    #[test]
+    fn test_apply_security_policies_forbids_restricted_query_fields() {
+        let mut index_metadata = index_metadata_with_policies("test-index", vec![support_policy()]);
+        index_metadata
+            .index_config
+            .search_settings
+            .default_search_fields = vec!["body".to_string()];
+        let indexes_metadata = [index_metadata];
+        let security_context = security_context("support", &[("tenant", "acme")]);
+
+        for user_text in [
+            "ssn:123",
+            "ssn:123*",
+            "ssn:[100 TO 200]",
+            "ssn:\"123 456\"",
+            "ssn:/12.*/",
+            "user.name:bob OR ssn:123",
+            "NOT ssn:*",
+            "123",
+        ] {
+            let mut search_request = SearchRequest {
+                query_ast: serde_json::to_string(&query_ast_from_user_text(user_text, None))
+                    .unwrap(),
+                ..search_request(Some(security_context.clone()))
+            };
+            let error =
+                apply_security_policies(&indexes_metadata, &mut search_request).unwrap_err();
+            assert!(
+                matches!(error, SearchError::Forbidden(_)),
+                "query `{user_text}` should be forbidden"
+            );
+        }
+        let query_ast: QueryAst = TermQuery {
+            field: "ssn".to_string(),
+            value: "123".to_string(),
+        }
+        .into();
+        let mut search_request = SearchRequest {
+            query_ast: serde_json::to_string(&query_ast).unwrap(),
+            ..search_request(Some(security_context.clone()))
+        };
+        let error = apply_security_policies(&indexes_metadata, &mut search_request).unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(_)));
+
+        let mut search_request = SearchRequest {
+            query_ast: serde_json::to_string(&query_ast_from_user_text(
+                "user.name:bob AND timestamp:[2024-01-01T00:00:00Z TO *]",
+                None,
+            ))
+            .unwrap(),
+            ..search_request(Some(security_context))
+        };
+        apply_security_policies(&indexes_metadata, &mut search_request).unwrap();
+    }
+
+    #[test]
+    fn test_restrict_list_fields_response() {
+        let indexes_metadata = [
+            index_metadata_with_policies("test-index-1", vec![support_policy()]),
+            index_metadata_with_policies("test-index-2", Vec::new()),
+        ];
+        let list_fields_request = ListFieldsRequest {
+            index_id_patterns: vec!["test-index-*".to_string()],
+            security_context: Some(security_context("support", &[("tenant", "acme")])),
+            ..Default::default()
+        };
+        let retrievable_fields =
+            apply_security_policies_to_list_fields(&indexes_metadata, &list_fields_request)
+                .unwrap();
+
+        let field_entry = |field_name: &str| ListFieldsEntryResponse {
+            field_name: field_name.to_string(),
+            index_ids: vec!["test-index-1".to_string(), "test-index-2".to_string()],
+            ..Default::default()
+        };
+        let mut list_fields_response = ListFieldsResponse {
+            fields: vec![field_entry("secret"), field_entry("user.name")],
+        };
+        retrievable_fields.restrict_list_fields_response(&mut list_fields_response);
+
+        assert_eq!(list_fields_response.fields.len(), 2);
+        assert_eq!(list_fields_response.fields[0].field_name, "secret");
+        assert_eq!(list_fields_response.fields[0].index_ids, ["test-index-2"]);
+        assert_eq!(list_fields_response.fields[1].field_name, "user.name");
+        assert_eq!(
+            list_fields_response.fields[1].index_ids,
+            ["test-index-1", "test-index-2"]
+        );
+    }
+
+    #[test]
+    fn test_apply_security_policies_to_list_terms() {
+        let unfiltered_policy = SecurityPolicy {
+            filter: None,
+            ..support_policy()
+        };
+        let indexes_metadata = [index_metadata_with_policies(
+            "test-index",
+            vec![unfiltered_policy],
+        )];
+        let mut list_terms_request = ListTermsRequest {
+            index_id_patterns: vec!["test-index".to_string()],
+            field: "user.name".to_string(),
+            security_context: Some(security_context("support", &[])),
+            ..Default::default()
+        };
+        apply_security_policies_to_list_terms(&indexes_metadata, &list_terms_request).unwrap();
+
+        list_terms_request.field = "secret".to_string();
+        let error = apply_security_policies_to_list_terms(&indexes_metadata, &list_terms_request)
+            .unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(_)));
+
+        let indexes_metadata = [index_metadata_with_policies(
+            "test-index",
+            vec![support_policy()],
+        )];
+        list_terms_request.field = "user.name".to_string();
+        let error = apply_security_policies_to_list_terms(&indexes_metadata, &list_terms_request)
+            .unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(_)));
+    }
+
+    #[test]
+    fn test_apply_security_policies_unknown_role() {
+        let indexes_metadata = [index_metadata_with_policies(
+            "test-index",
+            vec![support_policy()],
+        )];
+        let mut search_request = search_request(Some(security_context("guest", &[])));
+        let error = apply_security_policies(&indexes_metadata, &mut search_request).unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(_)));
+    }
+
+    #[test]
+    fn test_authorize_tag_updates() {
+        let index_metadata = index_metadata_with_policies("test-index", vec![support_policy()]);
+        let query_ast =
+            authorize_tag_updates(&index_metadata, None, QueryAst::MatchAll, &["body"]).unwrap();
+        assert_eq!(query_ast, QueryAst::MatchAll);
+
+        let security_context = security_context("support", &[("tenant", "acme")]);
+        let query_ast = authorize_tag_updates(
+            &index_metadata,
+            Some(&security_context),
+            QueryAst::MatchAll,
+            &["user.name"],
+        )
+        .unwrap();
+        let expected_query_ast: QueryAst = BoolQuery {
+            must: vec![QueryAst::MatchAll],
+            filter: vec![TermQuery {
+                field: "tenant_id".to_string(),
+                value: "acme".to_string(),
+            }
+            .into()],
+            ..Default::default()
+        }
+        .into();
+        assert_eq!(query_ast, expected_query_ast);
+
+        let error = authorize_tag_updates(
+            &index_metadata,
+            Some(&security_context),
+            QueryAst::MatchAll,
+            &["body"],
+        )
+        .unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(_)));
+
+        let error = authorize_tag_updates(
+            &index_metadata,
+            Some(&security_context),
+            query_ast_from_user_text("ssn:[100 TO 200]", None),
+            &["user.name"],
+        )
+        .unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(_)));
+
+        let read_only_policy = SecurityPolicy {
+            allow_updates: false,
+            ..support_policy()
+        };
+        let index_metadata = index_metadata_with_policies("test-index", vec![read_only_policy]);
+        let error = authorize_tag_updates(
+            &index_metadata,
+            Some(&security_context),
+            QueryAst::MatchAll,
+            &["user.name"],
+        )
+        .unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(_)));
+    }
+
+    #[test]
+    fn test_apply_security_policies_missing_claim() {
+        let indexes_metadata = [index_metadata_with_policies(
+            "test-index",
+            vec![support_policy()],
+        )];
+        let mut search_request = search_request(Some(security_context("support", &[])));
+        let error = apply_security_policies(&indexes_metadata, &mut search_request).unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(message) if message.contains("tenant")));
+    }
+
+    #[test]
+    fn test_apply_security_policies_conflicting_filters() {
+        let indexes_metadata = [
+            index_metadata_with_policies("test-index-1", vec![support_policy()]),
+            index_metadata_with_policies("test-index-2", Vec::new()),
+        ];
+        let mut search_request =
+            search_request(Some(security_context("support", &[("tenant", "acme")])));
+        let error = apply_security_policies(&indexes_metadata, &mut search_request).unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(_)));
+    }
+
+    #[test]
+    fn test_apply_security_policies_forbids_scroll() {
+        let indexes_metadata = [index_metadata_with_policies(
+            "test-index",
+            vec![support_policy()],
+        )];
+        let mut search_request =
+            search_request(Some(security_context("support", &[("tenant", "acme")])));
+        search_request.scroll_ttl_secs = Some(60);
+        let error = apply_security_policies(&indexes_metadata, &mut search_request).unwrap_err();
+        assert!(matches!(error, SearchError::Forbidden(_)));
+    }
+
+    #[test]
+    fn test_render_filter() {
+        let claims = BTreeMap::from_iter([
+            ("tenant".to_string(), "acme".to_string()),
+            ("region".to_string(), "eu".to_string()),
+        ]);
+        let filter_ast = render_filter(
+            "tenant_id:{claims.tenant} AND region:{claims.region}",
+            &claims,
+        )
+        .unwrap();
+        let expected_filter_ast: QueryAst = BoolQuery {
+            must: vec![
+                TermQuery {
+                    field: "tenant_id".to_string(),
+                    value: "acme".to_string(),
+                }
+                .into(),
+                TermQuery {
+                    field: "region".to_string(),
+                    value: "eu".to_string(),
+                }
+                .into(),
+            ],
+            ..Default::default()
+        }
+        .into();
+        assert_eq!(filter_ast, expected_filter_ast);
+
+        assert_eq!(
+            render_filter("public:true", &claims).unwrap(),
+            query_ast_from_user_text("public:true", None)
+                .parse_user_query(&[])
+                .unwrap()
+        );
+        render_filter("tenant_id:{claims.tenant", &claims).unwrap_err();
+        render_filter("tenant_id:{claims.team}", &claims).unwrap_err();
+        render_filter("tenant_id:prefix-{claims.tenant}", &claims).unwrap_err();
+    }
+
+    #[test]
+    fn test_render_filter_does_not_parse_claims() {
+        let claims =
+            BTreeMap::from_iter([("tenant".to_string(), "acme OR tenant_id:*".to_string())]);
+        let filter_ast = render_filter("tenant_id:{claims.tenant}", &claims).unwrap();
+        let expected_filter_ast: QueryAst = TermQuery {
+            field: "tenant_id".to_string(),
+            value: "acme OR tenant_id:*".to_string(),
+        }
+        .into();
+        assert_eq!(filter_ast, expected_filter_ast);
+    }
+
+    #[test]
+    fn test_restrict_search_response() {
+        let indexes_metadata = [index_metadata_with_policies(
+            "test-index",
+            vec![support_policy()],
+        )];
+        let mut search_request =
+            search_request(Some(security_context("support", &[("tenant", "acme")])));
+        let retrievable_fields =
+            apply_security_policies(&indexes_metadata, &mut search_request).unwrap();
+
+        let mut search_response = SearchResponse {
+            hits: vec![Hit {
+                json: json!({
+                    "timestamp": 1,
+                    "secret": "password",
+                    "user": {"name": "john", "email": "john@example.com"}
+                })
+                .to_string(),
+                snippet: Some(
+                    json!({"user.name": ["<b>john</b>"], "secret": ["pass"]}).to_string(),
+                ),
+                index_id: "test-index".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        retrievable_fields.restrict_search_response(&mut search_response);
+
+        let hit = &search_response.hits[0];
+        let hit_json: JsonValue = serde_json::from_str(&hit.json).unwrap();
+        assert_eq!(hit_json, json!({"timestamp": 1, "user": {"name": "john"}}));
+        let snippet_json: JsonValue = serde_json::from_str(hit.snippet.as_ref().unwrap()).unwrap();
+        assert_eq!(snippet_json, json!({"user.name": ["<b>john</b>"]}));
+    }
+}
@@ -19,35 +19,43 @@ use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use bytes::Bytes;
+use quickwit_common::runtimes::instrument_task;
 use quickwit_common::uri::Uri;
 use quickwit_config::SearcherConfig;
 use quickwit_doc_mapper::DocMapper;
 use quickwit_proto::metastore::MetastoreServiceClient;
 use quickwit_proto::search::{
-    FetchDocsRequest, FetchDocsResponse, GetKvRequest, Hit, LeafListFieldsRequest,
-    LeafListTermsRequest, LeafListTermsResponse, LeafSearchRequest, LeafSearchResponse,
-    LeafSearchStreamRequest, LeafSearchStreamResponse, ListFieldsRequest, ListFieldsResponse,
-    ListTermsRequest, ListTermsResponse, PutKvRequest, ReportSplitsRequest, ReportSplitsResponse,
-    ScrollRequest, SearchPlanResponse, SearchRequest, SearchResponse, SearchStreamRequest,
-    SnippetRequest,
-};
-use quickwit_storage::{
-    MemorySizedCache, QuickwitCache, SplitCache, StorageCache, StorageResolver,
+    CancelLeafSearchesRequest, CancelLeafSearchesResponse, CancelSearchRequest,
+    CancelSearchResponse, FetchDocsRequest, FetchDocsResponse, GetKvRequest, Hit,
+    LeafListFieldsRequest, LeafListTermsRequest, LeafListTermsResponse, LeafSearchRequest,
+    LeafSearchResponse, LeafSearchStreamRequest, LeafSearchStreamResponse, ListFieldsRequest,
+    ListFieldsResponse, ListTermsRequest, ListTermsResponse, PinSplitsRequest, PinSplitsResponse,
+    PutKvRequest, ReportSplitsRequest, ReportSplitsResponse, ScrollRequest, SearchPlanResponse,
+    SearchRequest, SearchResponse, SearchStreamRequest, SnippetRequest,
 };
+use quickwit_proto::types::IndexUid;
+use quickwit_storage::{MemorySizedCache, QuickwitCache, SplitCache, StorageResolver};
 use tantivy::aggregation::AggregationLimitsGuard;
 use tokio::sync::Semaphore;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
+use crate::explain::{explain, ExplainRequest, ExplainResponse};
+use crate::federation::{federated_root_search, is_federated_search_request, RemoteClusters};
+use crate::index_usage::IndexUsageAccumulator;
 use crate::leaf::multi_leaf_search;
 use crate::leaf_cache::LeafSearchCache;
 use crate::list_fields::{leaf_list_fields, root_list_fields};
 use crate::list_fields_cache::ListFieldsCache;
 use crate::list_terms::{leaf_list_terms, root_list_terms};
+use crate::memory_budget::{initial_cache_capacities, record_cache_capacities};
 use crate::metrics::SEARCH_METRICS;
 use crate::root::fetch_docs_phase;
+use crate::running_leaf_searches::RunningLeafSearches;
+use crate::running_searches::RunningSearches;
 use crate::scroll_context::{MiniKV, ScrollContext, ScrollKeyAndStartOffset};
 use crate::search_permit_provider::SearchPermitProvider;
 use crate::search_stream::{leaf_search_stream, root_search_stream};
+use crate::split_pinning::{root_pin_splits, PinIndexSplitsRequest, PinIndexSplitsResponse};
 use crate::{fetch_docs, root_search, search_plan, ClusterClient, SearchError};
 
 #[derive(Clone)]
@@ -58,6 +66,8 @@ pub struct SearchServiceImpl {
     cluster_client: ClusterClient,
     searcher_context: Arc<SearcherContext>,
     local_kv_store: MiniKV,
+    remote_clusters: RemoteClusters,
+    running_leaf_searches: RunningLeafSearches,
 }
 
 /// Trait representing a search service.
@@ -136,6 +146,32 @@ pub trait SearchService: 'static + Send + Sync {
     /// would then be considered as a candidate for the searcher split cache.
     async fn report_splits(&self, report_splits: ReportSplitsRequest) -> ReportSplitsResponse;
 
+    /// Pins or unpins splits in the split cache of the searcher. Pinned splits are downloaded
+    /// first and are never evicted from the cache.
+    async fn pin_splits(&self, pin_splits: PinSplitsRequest) -> PinSplitsResponse;
+
+    /// Pins or unpins the splits of an index in the split caches of the searchers.
+    async fn root_pin_splits(
+        &self,
+        request: PinIndexSplitsRequest,
+    ) -> crate::Result<PinIndexSplitsResponse>;
+
+    /// Cancels the leaf searches of a search request running on this searcher.
+    async fn cancel_leaf_searches(
+        &self,
+        request: CancelLeafSearchesRequest,
+    ) -> CancelLeafSearchesResponse;
+
+    /// Cancels the leaf searches of a search request on all the searchers of the cluster.
+    async fn root_cancel_leaf_searches(&self, search_request_id: String);
+
+    /// Cancels a search request running on this searcher, along with its leaf searches on all
+    /// the searchers of the cluster.
+    async fn cancel_search(&self, request: CancelSearchRequest) -> CancelSearchResponse;
+
+    /// Cancels a search request running on any searcher of the cluster.
+    async fn root_cancel_search(&self, request: CancelSearchRequest) -> CancelSearchResponse;
+
     /// Return the list of fields for a given or multiple indices.
     async fn root_list_fields(
         &self,
@@ -150,6 +186,9 @@ pub trait SearchService: 'static + Send + Sync {
 
     /// Describe how a search would be processed.
     async fn search_plan(&self, request: SearchRequest) -> crate::Result<SearchPlanResponse>;
+
+    /// Explains whether and why a document matches a query.
+    async fn explain(&self, request: ExplainRequest) -> crate::Result<ExplainResponse>;
 }
 
 impl SearchServiceImpl {
@@ -160,14 +199,26 @@ impl SearchServiceImpl {
         cluster_client: ClusterClient,
         searcher_context: Arc<SearcherContext>,
     ) -> Self {
+        searcher_context
+            .index_usage_accumulator
+            .set_metastore(metastore.clone());
         SearchServiceImpl {
             metastore,
             storage_resolver,
             cluster_client,
             searcher_context,
             local_kv_store: MiniKV::default(),
+            remote_clusters: RemoteClusters::default(),
+            running_leaf_searches: RunningLeafSearches::default(),
         }
     }
+
+    /// Sets the remote clusters to which the root search requests targeting their indexes are
+    /// federated.
+    pub fn with_remote_clusters(mut self, remote_clusters: RemoteClusters) -> Self {
+        self.remote_clusters = remote_clusters;
+        self
+    }
 }
 
 pub fn deserialize_doc_mapper(doc_mapper_str: &str) -> crate::Result<Arc<DocMapper>> {
@@ -180,11 +231,24 @@ pub fn deserialize_doc_mapper(doc_mapper_str: &str) -> crate::Result<Arc<DocMapp
 #[async_trait]
 impl SearchService for SearchServiceImpl {
     async fn root_search(&self, search_request: SearchRequest) -> crate::Result<SearchResponse> {
-        let search_result = root_search(
-            &self.searcher_context,
-            search_request,
-            self.metastore.clone(),
-            &self.cluster_client,
+        if is_federated_search_request(&search_request) {
+            let search_future = federated_root_search(
+                &self.searcher_context,
+                search_request,
+                self.metastore.clone(),
+                &self.cluster_client,
+                &self.remote_clusters,
+            );
+            return instrument_task(search_future, "search").await;
+        }
+        let search_result = instrument_task(
+            root_search(
+                &self.searcher_context,
+                search_request,
+                self.metastore.clone(),
+                &self.cluster_client,
+            ),
+            "search",
         )
         .await?;
         Ok(search_result)
@@ -198,13 +262,40 @@ impl SearchService for SearchServiceImpl {
         if leaf_search_request.search_request.is_none() {
             return Err(SearchError::Internal("no search request".to_string()));
         }
+        // Saturated searchers shed load so that the root searcher re-plans the splits onto the
+        // other searchers.
+        if let Some(max_num_pending_split_searches) = self
+            .searcher_context
+            .searcher_config
+            .max_num_pending_split_searches
+        {
+            let num_pending_split_searches = self
+                .searcher_context
+                .search_permit_provider
+                .num_pending_permits();
+
+            if num_pending_split_searches >= max_num_pending_split_searches.get() {
+                SEARCH_METRICS.leaf_search_rejected_requests_total.inc();
+                return Err(SearchError::TooManyRequests);
+            }
+        }
+        let search_request_id_opt = leaf_search_request
+            .search_request
+            .as_ref()
+            .and_then(|search_request| search_request.search_request_id.clone());
         let start = Instant::now();
-        let leaf_search_response_result = multi_leaf_search(
-            self.searcher_context.clone(),
-            leaf_search_request,
-            &self.storage_resolver,
-        )
-        .await;
+        let leaf_search_future = instrument_task(
+            multi_leaf_search(
+                self.searcher_context.clone(),
+                leaf_search_request,
+                &self.storage_resolver,
+            ),
+            "search",
+        );
+        let leaf_search_response_result = self
+            .running_leaf_searches
+            .run(search_request_id_opt.as_deref(), leaf_search_future)
+            .await;
 
         let elapsed = start.elapsed().as_secs_f64();
         let label_values = if leaf_search_response_result.is_ok() {
@@ -229,7 +320,13 @@ impl SearchService for SearchServiceImpl {
         fetch_docs_request: FetchDocsRequest,
     ) -> crate::Result<FetchDocsResponse> {
         let index_uri = Uri::from_str(&fetch_docs_request.index_uri)?;
-        let storage = self.storage_resolver.resolve(&index_uri).await?;
+        let storage = self
+            .storage_resolver
+            .resolve_with_credentials(
+                &index_uri,
+                fetch_docs_request.storage_credentials.as_deref(),
+            )
+            .await?;
         let snippet_request_opt: Option<&SnippetRequest> =
             fetch_docs_request.snippet_request.as_ref();
         let doc_mapper = deserialize_doc_mapper(&fetch_docs_request.doc_mapper)?;
@@ -240,6 +337,8 @@ impl SearchService for SearchServiceImpl {
             &fetch_docs_request.split_offsets,
             doc_mapper,
             snippet_request_opt,
+            &fetch_docs_request.docvalue_fields,
+            fetch_docs_request.named_queries_query_ast.as_deref(),
         )
         .await?;
 
@@ -251,6 +350,7 @@ impl SearchService for SearchServiceImpl {
         stream_request: SearchStreamRequest,
     ) -> crate::Result<Pin<Box<dyn futures::Stream<Item = crate::Result<Bytes>> + Send>>> {
         let data = root_search_stream(
+            &self.searcher_context,
             stream_request,
             self.metastore.clone(),
             self.cluster_client.clone(),
@@ -267,11 +367,18 @@ impl SearchService for SearchServiceImpl {
             .request
             .ok_or_else(|| SearchError::Internal("no search request".to_string()))?;
         let index_uri = Uri::from_str(&leaf_stream_request.index_uri)?;
-        let storage = self.storage_resolver.resolve(&index_uri).await?;
+        let storage = self
+            .storage_resolver
+            .resolve_with_credentials(
+                &index_uri,
+                leaf_stream_request.storage_credentials.as_deref(),
+            )
+            .await?;
         let doc_mapper = deserialize_doc_mapper(&leaf_stream_request.doc_mapper)?;
         let leaf_receiver = leaf_search_stream(
             self.searcher_context.clone(),
             stream_request,
+            IndexUid::from_str(&leaf_stream_request.index_uid).ok(),
             storage,
             leaf_stream_request.split_offsets,
             doc_mapper,
@@ -285,6 +392,7 @@ impl SearchService for SearchServiceImpl {
         list_terms_request: ListTermsRequest,
     ) -> crate::Result<ListTermsResponse> {
         let search_result = root_list_terms(
+            &self.searcher_context,
             &list_terms_request,
             self.metastore.clone(),
             &self.cluster_client,
@@ -302,12 +410,19 @@ impl SearchService for SearchServiceImpl {
             .list_terms_request
             .ok_or_else(|| SearchError::Internal("no search request".to_string()))?;
         let index_uri = Uri::from_str(&leaf_search_request.index_uri)?;
-        let storage = self.storage_resolver.resolve(&index_uri).await?;
+        let storage = self
+            .storage_resolver
+            .resolve_with_credentials(
+                &index_uri,
+                leaf_search_request.storage_credentials.as_deref(),
+            )
+            .await?;
         let split_ids = leaf_search_request.split_offsets;
 
         let leaf_search_response = leaf_list_terms(
             self.searcher_context.clone(),
             &search_request,
+            IndexUid::from_str(&leaf_search_request.index_uid).ok(),
             storage.clone(),
             &split_ids[..],
         )
@@ -339,6 +454,77 @@ impl SearchService for SearchServiceImpl {
         ReportSplitsResponse {}
     }
 
+    async fn pin_splits(&self, pin_splits: PinSplitsRequest) -> PinSplitsResponse {
+        if let Some(split_cache) = self.searcher_context.split_cache_opt.as_ref() {
+            split_cache.pin_splits(pin_splits);
+        }
+        PinSplitsResponse {}
+    }
+
+    async fn root_pin_splits(
+        &self,
+        request: PinIndexSplitsRequest,
+    ) -> crate::Result<PinIndexSplitsResponse> {
+        root_pin_splits(
+            request,
+            self.metastore.clone(),
+            self.cluster_client.search_job_placer.clone(),
+        )
+        .await
+    }
+
+    async fn cancel_leaf_searches(
+        &self,
+        request: CancelLeafSearchesRequest,
+    ) -> CancelLeafSearchesResponse {
+        let num_cancelled_leaf_searches = self
+            .running_leaf_searches
+            .cancel(&request.search_request_id);
+        CancelLeafSearchesResponse {
+            num_cancelled_leaf_searches: num_cancelled_leaf_searches as u32,
+        }
+    }
+
+    async fn root_cancel_leaf_searches(&self, search_request_id: String) {
+        self.cluster_client
+            .search_job_placer
+            .cancel_leaf_searches(search_request_id)
+            .await;
+    }
+
+    async fn cancel_search(&self, request: CancelSearchRequest) -> CancelSearchResponse {
+        let Ok((running_search, search_request_uid)) = self
+            .searcher_context
+            .running_searches
+            .cancel(&request.request_id, request.security_context.as_ref())
+        else {
+            return CancelSearchResponse::default();
+        };
+        // Aborting the root search does not stop the leaf searches already running on the
+        // other nodes.
+        self.root_cancel_leaf_searches(search_request_uid).await;
+        CancelSearchResponse {
+            running_search: Some(running_search),
+        }
+    }
+
+    async fn root_cancel_search(&self, request: CancelSearchRequest) -> CancelSearchResponse {
+        // The search request usually runs on the node that receives the cancellation.
+        let cancel_search_response = self.cancel_search(request.clone()).await;
+
+        if cancel_search_response.running_search.is_some() {
+            return cancel_search_response;
+        }
+        let running_search_opt = self
+            .cluster_client
+            .search_job_placer
+            .cancel_search(request)
+            .await;
+        CancelSearchResponse {
+            running_search: running_search_opt,
+        }
+    }
+
     async fn root_list_fields(
         &self,
         list_fields_req: ListFieldsRequest,
@@ -356,7 +542,10 @@ impl SearchService for SearchServiceImpl {
         list_fields_req: LeafListFieldsRequest,
     ) -> crate::Result<ListFieldsResponse> {
         let index_uri = Uri::from_str(&list_fields_req.index_uri)?;
-        let storage = self.storage_resolver.resolve(&index_uri).await?;
+        let storage = self
+            .storage_resolver
+            .resolve_with_credentials(&index_uri, list_fields_req.storage_credentials.as_deref())
+            .await?;
         let index_id = list_fields_req.index_id;
         let split_ids = list_fields_req.split_offsets;
         leaf_list_fields(
@@ -376,6 +565,17 @@ impl SearchService for SearchServiceImpl {
         let search_plan = search_plan(search_request, self.metastore.clone()).await?;
         Ok(search_plan)
     }
+
+    async fn explain(&self, explain_request: ExplainRequest) -> crate::Result<ExplainResponse> {
+        let explain_response = explain(
+            explain_request,
+            self.metastore.clone(),
+            &self.storage_resolver,
+            &self.searcher_context,
+        )
+        .await?;
+        Ok(explain_response)
+    }
 }
 
 pub(crate) async fn scroll(
@@ -416,7 +616,7 @@ pub(crate) async fn scroll(
     }
 
     // Fetch the actual documents.
-    let hits: Vec<Hit> = fetch_docs_phase(
+    let (hits, _fetch_docs_durations): (Vec<Hit>, _) = fetch_docs_phase(
         &scroll_context.indexes_metas_for_leaf_search,
         &partial_hits[..],
         &scroll_context.split_metadatas[..],
@@ -450,6 +650,9 @@ pub(crate) async fn scroll(
         aggregation: None,
         failed_splits: scroll_context.failed_splits,
         num_successful_splits: scroll_context.num_successful_splits,
+        profile: None,
+        warnings: Vec::new(),
+        intermediate_aggregation_result: None,
     })
 }
 /// [`SearcherContext`] provides a common set of variables
@@ -459,7 +662,7 @@ pub struct SearcherContext {
     /// Searcher config.
     pub searcher_config: SearcherConfig,
     /// Fast fields cache.
-    pub fast_fields_cache: Arc<dyn StorageCache>,
+    pub fast_fields_cache: Arc<QuickwitCache>,
     /// Counting semaphore to limit concurrent leaf search split requests.
     pub search_permit_provider: SearchPermitProvider,
     /// Split footer cache.
@@ -474,6 +677,10 @@ pub struct SearcherContext {
     pub list_fields_cache: ListFieldsCache,
     /// The aggregation limits are passed to limit the memory usage.
     pub aggregation_limit: AggregationLimitsGuard,
+    /// Accumulates the usage of the indexes searched by this node.
+    pub(crate) index_usage_accumulator: IndexUsageAccumulator,
+    /// Search requests running on this node, which can be listed and cancelled.
+    pub running_searches: Arc<RunningSearches>,
 }
 
 impl std::fmt::Debug for SearcherContext {
@@ -494,9 +701,11 @@ impl SearcherContext {
 
     /// Creates a new searcher context, given a searcher config, and an optional `SplitCache`.
     pub fn new(searcher_config: SearcherConfig, split_cache_opt: Option<Arc<SplitCache>>) -> Self {
-        let capacity_in_bytes = searcher_config.split_footer_cache_capacity.as_u64() as usize;
+        let cache_capacities = initial_cache_capacities(&searcher_config);
+        let [fast_field_cache_capacity, split_footer_cache_capacity, partial_request_capacity] =
+            cache_capacities.map(|capacity| capacity as usize);
         let global_split_footer_cache = MemorySizedCache::with_capacity_in_bytes(
-            capacity_in_bytes,
+            split_footer_cache_capacity,
             &quickwit_storage::STORAGE_METRICS.split_footer_cache,
         );
         let leaf_search_split_semaphore = SearchPermitProvider::new(
@@ -505,12 +714,17 @@ impl SearcherContext {
         );
         let split_stream_semaphore =
             Semaphore::new(searcher_config.max_num_concurrent_split_streams);
-        let fast_field_cache_capacity = searcher_config.fast_field_cache_capacity.as_u64() as usize;
         let storage_long_term_cache = Arc::new(QuickwitCache::new(fast_field_cache_capacity));
-        let leaf_search_cache =
-            LeafSearchCache::new(searcher_config.partial_request_cache_capacity.as_u64() as usize);
-        let list_fields_cache =
-            ListFieldsCache::new(searcher_config.partial_request_cache_capacity.as_u64() as usize);
+        // With a memory budget, the leaf search cache and the list fields cache share the partial
+        // request cache capacity.
+        let partial_request_cache_capacity = if searcher_config.memory_budget.is_some() {
+            record_cache_capacities(cache_capacities);
+            partial_request_capacity / 2
+        } else {
+            partial_request_capacity
+        };
+        let leaf_search_cache = LeafSearchCache::new(partial_request_cache_capacity);
+        let list_fields_cache = ListFieldsCache::new(partial_request_cache_capacity);
         let aggregation_limit = AggregationLimitsGuard::new(
             Some(searcher_config.aggregation_memory_limit.as_u64()),
             Some(searcher_config.aggregation_bucket_limit),
@@ -526,6 +740,8 @@ impl SearcherContext {
             list_fields_cache,
             split_cache_opt,
             aggregation_limit,
+            index_usage_accumulator: IndexUsageAccumulator::default(),
+            running_searches: Arc::default(),
         }
     }
 
@@ -0,0 +1,143 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use quickwit_metastore::{IndexMetadataResponseExt, SplitMetadata};
+use quickwit_proto::metastore::{IndexMetadataRequest, MetastoreService, MetastoreServiceClient};
+use quickwit_proto::search::ReportSplit;
+use quickwit_proto::types::{IndexId, SplitId};
+use serde::{Deserialize, Serialize};
+
+use crate::{list_relevant_splits, SearchError, SearchJobPlacer};
+
+/// Request to pin or unpin the splits of an index in the split caches of the searchers.
+#[derive(Clone, Debug)]
+pub struct PinIndexSplitsRequest {
+    pub index_id: IndexId,
+    /// Splits to pin or unpin. All the splits of the index if empty.
+    pub split_ids: Vec<SplitId>,
+    /// Unpins the splits instead of pinning them.
+    pub unpin: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
+pub struct PinIndexSplitsResponse {
+    /// Number of splits pinned or unpinned. When all the splits of an index are unpinned, it is
+    /// the number of splits currently published.
+    pub num_splits: usize,
+}
+
+/// Pins the published splits of an index on the searchers whose split cache they are reported to,
+/// or unpins them from all the searchers.
+///
+/// Pins are taken on the splits published at the time of the request: the splits published
+/// afterwards, for instance by merges, are not pinned.
+pub(crate) async fn root_pin_splits(
+    request: PinIndexSplitsRequest,
+    mut metastore: MetastoreServiceClient,
+    mut search_job_placer: SearchJobPlacer,
+) -> crate::Result<PinIndexSplitsResponse> {
+    let index_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(request.index_id))
+        .await?
+        .deserialize_index_metadata()?;
+    let index_uri = index_metadata.index_uri().to_string();
+    let storage_credentials_opt = index_metadata
+        .index_config
+        .storage_credentials_opt
+        .as_deref();
+
+    if request.unpin {
+        if request.split_ids.is_empty() {
+            // Unpinning by storage URI also unpins the splits that are no longer published.
+            let num_splits = list_relevant_splits(
+                vec![index_metadata.index_uid.clone()],
+                None,
+                None,
+                None,
+                &mut metastore,
+            )
+            .await?
+            .len();
+            search_job_placer
+                .unpin_splits(Vec::new(), vec![index_uri])
+                .await?;
+            return Ok(PinIndexSplitsResponse { num_splits });
+        }
+        let num_splits = request.split_ids.len();
+        let splits = report_splits(
+            request.split_ids,
+            index_metadata.index_id(),
+            &index_uri,
+            storage_credentials_opt,
+        );
+        search_job_placer.unpin_splits(splits, Vec::new()).await?;
+        return Ok(PinIndexSplitsResponse { num_splits });
+    }
+    let split_metadatas: Vec<SplitMetadata> = list_relevant_splits(
+        vec![index_metadata.index_uid.clone()],
+        None,
+        None,
+        None,
+        &mut metastore,
+    )
+    .await?;
+    let published_split_ids: Vec<SplitId> = split_metadatas
+        .into_iter()
+        .map(|split_metadata| split_metadata.split_id)
+        .collect();
+    let split_ids: Vec<SplitId> = if request.split_ids.is_empty() {
+        published_split_ids
+    } else {
+        let published_split_ids: HashSet<SplitId> = published_split_ids.into_iter().collect();
+        if let Some(unknown_split_id) = request
+            .split_ids
+            .iter()
+            .find(|split_id| !published_split_ids.contains(*split_id))
+        {
+            return Err(SearchError::InvalidArgument(format!(
+                "split `{unknown_split_id}` is not a published split of index `{}`",
+                index_metadata.index_id()
+            )));
+        }
+        request.split_ids
+    };
+    let num_splits = split_ids.len();
+    let splits = report_splits(
+        split_ids,
+        index_metadata.index_id(),
+        &index_uri,
+        storage_credentials_opt,
+    );
+    search_job_placer.pin_splits(splits).await?;
+    Ok(PinIndexSplitsResponse { num_splits })
+}
+
+fn report_splits(
+    split_ids: Vec<SplitId>,
+    index_id: &str,
+    index_uri: &str,
+    storage_credentials_opt: Option<&str>,
+) -> Vec<ReportSplit> {
+    split_ids
+        .into_iter()
+        .map(|split_id| ReportSplit {
+            split_id,
+            storage_uri: index_uri.to_string(),
+            storage_credentials: storage_credentials_opt.map(ToString::to_string),
+            index_id: Some(index_id.to_string()),
+        })
+        .collect()
+}
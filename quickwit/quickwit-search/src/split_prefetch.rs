@@ -0,0 +1,204 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use quickwit_common::uri::Uri;
+use quickwit_metastore::SplitMetadata;
+use quickwit_proto::metastore::MetastoreServiceClient;
+use quickwit_proto::search::{ReportSplit, SearchRequest};
+use quickwit_proto::types::{IndexUid, SplitId};
+use tracing::debug;
+
+use crate::metrics::SEARCH_METRICS;
+use crate::root::IndexesMetasForLeafSearch;
+use crate::{list_relevant_splits, SearchJobPlacer};
+
+/// Maximum number of time-adjacent splits reported to the split caches for a single search
+/// request.
+const MAX_NUM_PREFETCHED_SPLITS: usize = 100;
+
+/// Returns the time range that spans the `[start_timestamp, end_timestamp)` time range of a
+/// search request, extended by its own width on each side. Zooming out or panning a dashboard
+/// typically queries this time range next.
+fn adjacent_time_range(start_timestamp: i64, end_timestamp: i64) -> Option<Range<i64>> {
+    let width = end_timestamp
+        .checked_sub(start_timestamp)
+        .filter(|width| *width > 0)?;
+    Some(start_timestamp.saturating_sub(width)..end_timestamp.saturating_add(width))
+}
+
+/// Distance (in seconds) between the time range of a split and the `[start_timestamp,
+/// end_timestamp)` time range.
+fn distance_to_time_range(split_metadata: &SplitMetadata, time_range: &Range<i64>) -> i64 {
+    let Some(split_time_range) = &split_metadata.time_range else {
+        return 0;
+    };
+    if *split_time_range.end() < time_range.start {
+        time_range.start - split_time_range.end()
+    } else if *split_time_range.start() >= time_range.end {
+        split_time_range.start() - time_range.end + 1
+    } else {
+        0
+    }
+}
+
+/// Selects the splits to prefetch among the splits overlapping the adjacent time range: the
+/// splits already targeted by the search request are skipped and the splits closest to the
+/// searched time range come first.
+fn select_splits_to_prefetch(
+    adjacent_split_metadatas: Vec<SplitMetadata>,
+    searched_split_ids: &HashSet<SplitId>,
+    searched_time_range: &Range<i64>,
+) -> Vec<SplitMetadata> {
+    let mut splits_to_prefetch: Vec<SplitMetadata> = adjacent_split_metadatas
+        .into_iter()
+        .filter(|split_metadata| {
+            split_metadata.time_range.is_some()
+                && !searched_split_ids.contains(&split_metadata.split_id)
+        })
+        .collect();
+    splits_to_prefetch
+        .sort_by_key(|split_metadata| distance_to_time_range(split_metadata, searched_time_range));
+    splits_to_prefetch.truncate(MAX_NUM_PREFETCHED_SPLITS);
+    splits_to_prefetch
+}
+
+/// Reports the splits temporally adjacent to the splits targeted by a time-range search request
+/// to the split cache of the searchers, in the background.
+///
+/// Reported splits are only downloaded after the splits that were recently accessed, so
+/// prefetching never evicts the splits the searchers are actively reading.
+pub(crate) fn spawn_adjacent_splits_prefetch(
+    search_request: &SearchRequest,
+    searched_split_metadatas: &[SplitMetadata],
+    indexes_metas_for_leaf_search: &IndexesMetasForLeafSearch,
+    mut metastore: MetastoreServiceClient,
+    mut search_job_placer: SearchJobPlacer,
+) {
+    let (Some(start_timestamp), Some(end_timestamp)) =
+        (search_request.start_timestamp, search_request.end_timestamp)
+    else {
+        return;
+    };
+    let Some(adjacent_time_range) = adjacent_time_range(start_timestamp, end_timestamp) else {
+        return;
+    };
+    let index_storages: HashMap<IndexUid, (Uri, Option<String>)> = indexes_metas_for_leaf_search
+        .iter()
+        .map(|(index_uid, index_metas)| {
+            let index_storage = (
+                index_metas.index_uri.clone(),
+                index_metas.storage_credentials_opt.clone(),
+            );
+            (index_uid.clone(), index_storage)
+        })
+        .collect();
+    let searched_split_ids: HashSet<SplitId> = searched_split_metadatas
+        .iter()
+        .map(|split_metadata| split_metadata.split_id.clone())
+        .collect();
+    let searched_time_range = start_timestamp..end_timestamp;
+
+    tokio::spawn(async move {
+        let index_uids: Vec<IndexUid> = index_storages.keys().cloned().collect();
+        let adjacent_split_metadatas = match list_relevant_splits(
+            index_uids,
+            Some(adjacent_time_range.start),
+            Some(adjacent_time_range.end),
+            None,
+            &mut metastore,
+        )
+        .await
+        {
+            Ok(adjacent_split_metadatas) => adjacent_split_metadatas,
+            Err(error) => {
+                debug!(error=%error, "failed to list time-adjacent splits to prefetch");
+                return;
+            }
+        };
+        let splits_to_prefetch = select_splits_to_prefetch(
+            adjacent_split_metadatas,
+            &searched_split_ids,
+            &searched_time_range,
+        );
+        let report_splits: Vec<ReportSplit> = splits_to_prefetch
+            .into_iter()
+            .filter_map(|split_metadata| {
+                let (index_uri, storage_credentials_opt) =
+                    index_storages.get(&split_metadata.index_uid)?;
+                Some(ReportSplit {
+                    split_id: split_metadata.split_id,
+                    storage_uri: index_uri.to_string(),
+                    storage_credentials: storage_credentials_opt.clone(),
+                    index_id: Some(split_metadata.index_uid.index_id),
+                })
+            })
+            .collect();
+        if report_splits.is_empty() {
+            return;
+        }
+        SEARCH_METRICS
+            .prefetched_splits_total
+            .inc_by(report_splits.len() as u64);
+        search_job_placer.report_splits(report_splits).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split_metadata_for_test(
+        split_id: &str,
+        time_range_opt: Option<(i64, i64)>,
+    ) -> SplitMetadata {
+        SplitMetadata {
+            split_id: split_id.to_string(),
+            time_range: time_range_opt.map(|(start, end)| start..=end),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_adjacent_time_range() {
+        assert_eq!(adjacent_time_range(100, 150), Some(50..200));
+        assert_eq!(adjacent_time_range(100, 100), None);
+        assert_eq!(adjacent_time_range(150, 100), None);
+        assert_eq!(
+            adjacent_time_range(i64::MIN + 1, 0),
+            Some(i64::MIN..i64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_select_splits_to_prefetch() {
+        let searched_split_ids: HashSet<SplitId> = HashSet::from(["searched".to_string()]);
+        let adjacent_split_metadatas = vec![
+            split_metadata_for_test("far-before", Some((50, 60))),
+            split_metadata_for_test("searched", Some((100, 120))),
+            split_metadata_for_test("no-time-range", None),
+            split_metadata_for_test("just-after", Some((150, 160))),
+            split_metadata_for_test("before", Some((80, 95))),
+        ];
+        let splits_to_prefetch =
+            select_splits_to_prefetch(adjacent_split_metadatas, &searched_split_ids, &(100..150));
+        let split_ids: Vec<&str> = splits_to_prefetch
+            .iter()
+            .map(|split_metadata| split_metadata.split_id.as_str())
+            .collect();
+        assert_eq!(split_ids, ["just-after", "before", "far-before"]);
+    }
+}
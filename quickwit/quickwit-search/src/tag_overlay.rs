@@ -0,0 +1,594 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::convert::Infallible;
+use std::ops::Bound;
+
+use quickwit_metastore::TagOverlay;
+use quickwit_proto::search::{Hit, SearchRequest};
+use quickwit_query::query_ast::{
+    BoolQuery, FieldPresenceQuery, FullTextQuery, QueryAst, QueryAstTransformer, RangeQuery,
+    TermQuery, TermSetQuery,
+};
+use quickwit_query::JsonLiteral;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use crate::collector::QuickwitAggregations;
+use crate::SearchError;
+
+/// Prefix of the names of the clauses matching the documents of each tag overlay. The clauses are
+/// evaluated against the fetched documents to find the tag overlays to apply to their source.
+const TAG_OVERLAY_QUERY_NAME_PREFIX: &str = "_tag_overlay_";
+
+/// Rewrites the queries targeting the tag field of a tag overlay so that the documents matching
+/// the overlay query behave as if their tag field was set to the value of the overlay.
+///
+/// Overlays are applied in their order of creation: the last overlay matching a document wins.
+pub(crate) fn apply_tag_overlays(query_ast: QueryAst, tag_overlays: &[TagOverlay]) -> QueryAst {
+    if tag_overlays.is_empty() {
+        return query_ast;
+    }
+    let mut tag_overlays_per_field: HashMap<&str, Vec<&TagOverlay>> = HashMap::new();
+
+    for tag_overlay in tag_overlays {
+        tag_overlays_per_field
+            .entry(tag_overlay.field_name.as_str())
+            .or_default()
+            .push(tag_overlay);
+    }
+    let mut rewriter = TagOverlayRewriter {
+        tag_overlays_per_field,
+    };
+    rewriter
+        .transform(query_ast)
+        .expect("can't fail unwrapping Infallible")
+        .unwrap_or(QueryAst::MatchAll)
+}
+
+/// Returns an error if the search request sorts on, aggregates on, or returns the doc values of a
+/// tag field with tag overlays: tag overlays rewrite the queries and the returned documents, not
+/// the fast fields stored in the splits.
+pub(crate) fn validate_tag_overlay_fast_fields(
+    search_request: &SearchRequest,
+    tag_overlays: &[TagOverlay],
+) -> crate::Result<()> {
+    if tag_overlays.is_empty() {
+        return Ok(());
+    }
+    let mut fast_field_names: HashSet<String> = search_request
+        .sort_fields
+        .iter()
+        .map(|sort_field| sort_field.field_name.clone())
+        .chain(search_request.docvalue_fields.iter().cloned())
+        .collect();
+
+    if let Some(aggregation_request) = &search_request.aggregation_request {
+        let aggregations: QuickwitAggregations = serde_json::from_str(aggregation_request)
+            .map_err(|error| SearchError::InvalidAggregationRequest(error.to_string()))?;
+        fast_field_names.extend(aggregations.fast_field_names());
+    }
+    for tag_overlay in tag_overlays {
+        if fast_field_names.contains(&tag_overlay.field_name) {
+            return Err(SearchError::InvalidArgument(format!(
+                "field `{}` has tag overlays: sorting on it, aggregating on it, or returning its \
+                 doc values is not supported",
+                tag_overlay.field_name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Adds a named clause matching the documents of each tag overlay to the query whose named
+/// clauses are evaluated against the fetched documents, so that the tag overlays can be applied
+/// to their source with [`apply_tag_overlays_to_hit`].
+pub(crate) fn add_tag_overlay_named_queries(
+    named_queries_query_ast_opt: Option<&str>,
+    tag_overlays: &[TagOverlay],
+) -> crate::Result<Option<String>> {
+    if tag_overlays.is_empty() {
+        return Ok(named_queries_query_ast_opt.map(str::to_string));
+    }
+    let mut should: Vec<QueryAst> = Vec::with_capacity(tag_overlays.len() + 1);
+
+    if let Some(named_queries_query_ast) = named_queries_query_ast_opt {
+        let query_ast: QueryAst = serde_json::from_str(named_queries_query_ast)
+            .map_err(|error| SearchError::InvalidQuery(error.to_string()))?;
+        should.push(query_ast);
+    }
+    for (overlay_ord, tag_overlay) in tag_overlays.iter().enumerate() {
+        let name = format!("{TAG_OVERLAY_QUERY_NAME_PREFIX}{overlay_ord}");
+        should.push(tag_overlay.query_ast.clone().named(Some(name)));
+    }
+    let query_ast: QueryAst = BoolQuery {
+        should,
+        ..Default::default()
+    }
+    .into();
+    let query_ast_json = serde_json::to_string(&query_ast).map_err(|error| {
+        SearchError::Internal(format!("failed to serialize query AST: {error}"))
+    })?;
+    Ok(Some(query_ast_json))
+}
+
+/// Applies to the source of a fetched document the tag overlays whose named clause, added by
+/// [`add_tag_overlay_named_queries`], matched it, and removes these clauses from its matched
+/// queries.
+pub(crate) fn apply_tag_overlays_to_hit(hit: &mut Hit, tag_overlays: &[TagOverlay]) {
+    if tag_overlays.is_empty() {
+        return;
+    }
+    let mut matched_overlay_ords: Vec<usize> = Vec::new();
+
+    hit.matched_queries.retain(|matched_query| {
+        let Some(overlay_ord_str) = matched_query.strip_prefix(TAG_OVERLAY_QUERY_NAME_PREFIX)
+        else {
+            return true;
+        };
+        if let Ok(overlay_ord) = overlay_ord_str.parse::<usize>() {
+            matched_overlay_ords.push(overlay_ord);
+        }
+        false
+    });
+    if matched_overlay_ords.is_empty() {
+        return;
+    }
+    let Ok(mut doc) = serde_json::from_str::<JsonMap<String, JsonValue>>(&hit.json) else {
+        return;
+    };
+    matched_overlay_ords.sort_unstable();
+
+    for overlay_ord in matched_overlay_ords {
+        let Some(tag_overlay) = tag_overlays.get(overlay_ord) else {
+            continue;
+        };
+        // Tag fields are declared at the root of the doc mapping.
+        if let Some(value) = &tag_overlay.value_opt {
+            doc.insert(tag_overlay.field_name.clone(), value.clone());
+        } else {
+            doc.remove(&tag_overlay.field_name);
+        }
+    }
+    hit.json = JsonValue::Object(doc).to_string();
+}
+
+struct TagOverlayRewriter<'a> {
+    tag_overlays_per_field: HashMap<&'a str, Vec<&'a TagOverlay>>,
+}
+
+impl TagOverlayRewriter<'_> {
+    /// Rewrites `query_ast`, a query targeting the tag field `field`, into:
+    /// - the documents matching `query_ast` and none of the overlay queries;
+    /// - or the documents matching an overlay query whose value matches `value_matches`, and none
+    ///   of the overlay queries created after it.
+    fn rewrite(
+        &self,
+        field: &str,
+        query_ast: QueryAst,
+        value_matches: impl Fn(Option<&JsonValue>) -> bool,
+    ) -> QueryAst {
+        let Some(tag_overlays) = self.tag_overlays_per_field.get(field) else {
+            return query_ast;
+        };
+        let overlay_queries: Vec<QueryAst> = tag_overlays
+            .iter()
+            .map(|tag_overlay| tag_overlay.query_ast.clone())
+            .collect();
+        let untouched_documents_query = BoolQuery {
+            filter: vec![query_ast],
+            must_not: overlay_queries.clone(),
+            ..Default::default()
+        };
+        let mut should = vec![untouched_documents_query.into()];
+
+        for (overlay_ord, tag_overlay) in tag_overlays.iter().enumerate() {
+            if !value_matches(tag_overlay.value_opt.as_ref()) {
+                continue;
+            }
+            let tagged_documents_query = BoolQuery {
+                filter: vec![tag_overlay.query_ast.clone()],
+                must_not: overlay_queries[overlay_ord + 1..].to_vec(),
+                ..Default::default()
+            };
+            should.push(tagged_documents_query.into());
+        }
+        BoolQuery {
+            should,
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+fn value_as_string(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(value_str) => value_str.clone(),
+        _ => value.to_string(),
+    }
+}
+
+/// Compares a tag value with a bound of a range query: as numbers if both are numbers, as strings
+/// otherwise.
+fn cmp_value_with_bound(value: &JsonValue, bound_value: &JsonLiteral) -> Option<Ordering> {
+    let bound_str = match bound_value {
+        JsonLiteral::Number(number) => number.to_string(),
+        JsonLiteral::String(bound_str) => bound_str.clone(),
+        JsonLiteral::Bool(_) => return None,
+    };
+    let value_f64_opt = value
+        .as_f64()
+        .or_else(|| value.as_str()?.parse::<f64>().ok());
+
+    if let (Some(value_f64), Ok(bound_f64)) = (value_f64_opt, bound_str.parse::<f64>()) {
+        return value_f64.partial_cmp(&bound_f64);
+    }
+    Some(value_as_string(value).cmp(&bound_str))
+}
+
+fn value_in_range(
+    value: &JsonValue,
+    lower_bound: &Bound<JsonLiteral>,
+    upper_bound: &Bound<JsonLiteral>,
+) -> bool {
+    let above_lower_bound = match lower_bound {
+        Bound::Included(bound_value) => {
+            cmp_value_with_bound(value, bound_value).is_some_and(Ordering::is_ge)
+        }
+        Bound::Excluded(bound_value) => {
+            cmp_value_with_bound(value, bound_value).is_some_and(Ordering::is_gt)
+        }
+        Bound::Unbounded => true,
+    };
+    let below_upper_bound = match upper_bound {
+        Bound::Included(bound_value) => {
+            cmp_value_with_bound(value, bound_value).is_some_and(Ordering::is_le)
+        }
+        Bound::Excluded(bound_value) => {
+            cmp_value_with_bound(value, bound_value).is_some_and(Ordering::is_lt)
+        }
+        Bound::Unbounded => true,
+    };
+    above_lower_bound && below_upper_bound
+}
+
+impl QueryAstTransformer for TagOverlayRewriter<'_> {
+    type Err = Infallible;
+
+    fn transform_term(&mut self, term_query: TermQuery) -> Result<Option<QueryAst>, Self::Err> {
+        let field = term_query.field.clone();
+        let term = term_query.value.clone();
+        let query_ast = self.rewrite(&field, term_query.into(), |value_opt| {
+            value_opt.is_some_and(|value| value_as_string(value) == term)
+        });
+        Ok(Some(query_ast))
+    }
+
+    fn transform_term_set(
+        &mut self,
+        term_set_query: TermSetQuery,
+    ) -> Result<Option<QueryAst>, Self::Err> {
+        let (overlaid_terms_per_field, other_terms_per_field): (HashMap<_, _>, HashMap<_, _>) =
+            term_set_query
+                .terms_per_field
+                .into_iter()
+                .partition(|(field, _)| self.tag_overlays_per_field.contains_key(field.as_str()));
+
+        let mut should: Vec<QueryAst> = Vec::new();
+
+        if !other_terms_per_field.is_empty() {
+            let term_set_query = TermSetQuery {
+                terms_per_field: other_terms_per_field,
+            };
+            should.push(term_set_query.into());
+        }
+        for (field, terms) in overlaid_terms_per_field {
+            let term_set_query = TermSetQuery {
+                terms_per_field: HashMap::from([(field.clone(), terms.clone())]),
+            };
+            let query_ast = self.rewrite(&field, term_set_query.into(), |value_opt| {
+                value_opt.is_some_and(|value| terms.contains(&value_as_string(value)))
+            });
+            should.push(query_ast);
+        }
+        if should.len() == 1 {
+            return Ok(should.pop());
+        }
+        let query_ast = BoolQuery {
+            should,
+            ..Default::default()
+        }
+        .into();
+        Ok(Some(query_ast))
+    }
+
+    fn transform_full_text(
+        &mut self,
+        full_text_query: FullTextQuery,
+    ) -> Result<Option<QueryAst>, Self::Err> {
+        let field = full_text_query.field.clone();
+        let text = full_text_query.text.clone();
+        let query_ast = self.rewrite(&field, full_text_query.into(), |value_opt| {
+            value_opt.is_some_and(|value| value_as_string(value) == text)
+        });
+        Ok(Some(query_ast))
+    }
+
+    fn transform_range(&mut self, range_query: RangeQuery) -> Result<Option<QueryAst>, Self::Err> {
+        let field = range_query.field.clone();
+        let lower_bound = range_query.lower_bound.clone();
+        let upper_bound = range_query.upper_bound.clone();
+        let query_ast = self.rewrite(&field, range_query.into(), |value_opt| {
+            value_opt.is_some_and(|value| value_in_range(value, &lower_bound, &upper_bound))
+        });
+        Ok(Some(query_ast))
+    }
+
+    fn transform_exists(
+        &mut self,
+        field_presence_query: FieldPresenceQuery,
+    ) -> Result<Option<QueryAst>, Self::Err> {
+        let field = field_presence_query.field.clone();
+        let query_ast = self.rewrite(&field, field_presence_query.into(), |value_opt| {
+            value_opt.is_some()
+        });
+        Ok(Some(query_ast))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term_query(field: &str, value: &str) -> QueryAst {
+        TermQuery {
+            field: field.to_string(),
+            value: value.to_string(),
+        }
+        .into()
+    }
+
+    fn tag_overlay(query_ast: QueryAst, value_opt: Option<JsonValue>) -> TagOverlay {
+        TagOverlay {
+            query_ast,
+            field_name: "acknowledged".to_string(),
+            value_opt,
+            create_timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_apply_tag_overlays_no_overlays() {
+        let query_ast = term_query("acknowledged", "true");
+        assert_eq!(apply_tag_overlays(query_ast.clone(), &[]), query_ast);
+    }
+
+    #[test]
+    fn test_apply_tag_overlays_ignores_other_fields() {
+        let tag_overlays = [tag_overlay(
+            term_query("host", "web-1"),
+            Some(JsonValue::Bool(true)),
+        )];
+        let query_ast = term_query("severity", "high");
+        assert_eq!(
+            apply_tag_overlays(query_ast.clone(), &tag_overlays),
+            query_ast
+        );
+    }
+
+    #[test]
+    fn test_apply_tag_overlays_term() {
+        let tag_overlays = [
+            tag_overlay(term_query("host", "web-1"), Some(JsonValue::Bool(true))),
+            tag_overlay(term_query("host", "web-2"), None),
+        ];
+        let query_ast = BoolQuery {
+            must: vec![
+                term_query("acknowledged", "true"),
+                term_query("severity", "high"),
+            ],
+            ..Default::default()
+        }
+        .into();
+        let expected_query_ast: QueryAst = BoolQuery {
+            must: vec![
+                BoolQuery {
+                    should: vec![
+                        BoolQuery {
+                            filter: vec![term_query("acknowledged", "true")],
+                            must_not: vec![
+                                term_query("host", "web-1"),
+                                term_query("host", "web-2"),
+                            ],
+                            ..Default::default()
+                        }
+                        .into(),
+                        BoolQuery {
+                            filter: vec![term_query("host", "web-1")],
+                            must_not: vec![term_query("host", "web-2")],
+                            ..Default::default()
+                        }
+                        .into(),
+                    ],
+                    ..Default::default()
+                }
+                .into(),
+                term_query("severity", "high"),
+            ],
+            ..Default::default()
+        }
+        .into();
+        assert_eq!(
+            apply_tag_overlays(query_ast, &tag_overlays),
+            expected_query_ast
+        );
+    }
+
+    #[test]
+    fn test_apply_tag_overlays_term_set() {
+        let tag_overlays = [tag_overlay(
+            term_query("host", "web-1"),
+            Some(JsonValue::String("yes".to_string())),
+        )];
+        let query_ast = TermSetQuery {
+            terms_per_field: HashMap::from([(
+                "acknowledged".to_string(),
+                BTreeSet::from(["yes".to_string(), "maybe".to_string()]),
+            )]),
+        }
+        .into();
+        let QueryAst::Bool(bool_query) = apply_tag_overlays(query_ast, &tag_overlays) else {
+            panic!("expected a bool query");
+        };
+        assert_eq!(bool_query.should.len(), 2);
+    }
+
+    #[test]
+    fn test_apply_tag_overlays_field_presence() {
+        let tag_overlays = [
+            tag_overlay(term_query("host", "web-1"), None),
+            tag_overlay(term_query("host", "web-2"), Some(JsonValue::Bool(false))),
+        ];
+        let query_ast = FieldPresenceQuery {
+            field: "acknowledged".to_string(),
+        }
+        .into();
+        let QueryAst::Bool(bool_query) = apply_tag_overlays(query_ast, &tag_overlays) else {
+            panic!("expected a bool query");
+        };
+        // Only the overlay setting a value matches the field presence query.
+        assert_eq!(bool_query.should.len(), 2);
+        let QueryAst::Bool(tagged_documents_query) = &bool_query.should[1] else {
+            panic!("expected a bool query");
+        };
+        assert_eq!(tagged_documents_query.filter, [term_query("host", "web-2")]);
+        assert!(tagged_documents_query.must_not.is_empty());
+    }
+
+    #[test]
+    fn test_apply_tag_overlays_range() {
+        let tag_overlays = [
+            tag_overlay(term_query("host", "web-1"), Some(JsonValue::from(5))),
+            tag_overlay(term_query("host", "web-2"), Some(JsonValue::from(50))),
+        ];
+        let query_ast = RangeQuery {
+            field: "acknowledged".to_string(),
+            lower_bound: Bound::Included(JsonLiteral::Number(1.into())),
+            upper_bound: Bound::Excluded(JsonLiteral::String("10".to_string())),
+        }
+        .into();
+        let QueryAst::Bool(bool_query) = apply_tag_overlays(query_ast, &tag_overlays) else {
+            panic!("expected a bool query");
+        };
+        // Only the overlay setting a value within the range matches the range query.
+        assert_eq!(bool_query.should.len(), 2);
+        let QueryAst::Bool(tagged_documents_query) = &bool_query.should[1] else {
+            panic!("expected a bool query");
+        };
+        assert_eq!(tagged_documents_query.filter, [term_query("host", "web-1")]);
+        assert_eq!(
+            tagged_documents_query.must_not,
+            [term_query("host", "web-2")]
+        );
+    }
+
+    #[test]
+    fn test_validate_tag_overlay_fast_fields() {
+        let tag_overlays = [tag_overlay(
+            term_query("host", "web-1"),
+            Some(JsonValue::Bool(true)),
+        )];
+        let search_request = SearchRequest {
+            aggregation_request: Some(r#"{"hosts": {"terms": {"field": "host"}}}"#.to_string()),
+            ..Default::default()
+        };
+        validate_tag_overlay_fast_fields(&search_request, &tag_overlays).unwrap();
+
+        let search_request = SearchRequest {
+            aggregation_request: Some(
+                r#"{"acks": {"terms": {"field": "acknowledged"}}}"#.to_string(),
+            ),
+            ..Default::default()
+        };
+        let error = validate_tag_overlay_fast_fields(&search_request, &tag_overlays).unwrap_err();
+        assert!(matches!(error, SearchError::InvalidArgument(_)));
+
+        let search_request = SearchRequest {
+            sort_fields: vec![quickwit_proto::search::SortField {
+                field_name: "acknowledged".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        validate_tag_overlay_fast_fields(&search_request, &tag_overlays).unwrap_err();
+        validate_tag_overlay_fast_fields(&search_request, &[]).unwrap();
+    }
+
+    #[test]
+    fn test_apply_tag_overlays_to_hit() {
+        let tag_overlays = [
+            tag_overlay(term_query("host", "web-1"), Some(JsonValue::Bool(true))),
+            TagOverlay {
+                field_name: "owner".to_string(),
+                ..tag_overlay(term_query("host", "web-1"), Some(JsonValue::from("alice")))
+            },
+            tag_overlay(term_query("host", "web-2"), None),
+        ];
+        let named_queries_query_ast_json = add_tag_overlay_named_queries(
+            Some(r#"{"type": "named", "name": "q", "underlying": {"type": "match_all"}}"#),
+            &tag_overlays,
+        )
+        .unwrap()
+        .unwrap();
+        let named_queries_query_ast: QueryAst =
+            serde_json::from_str(&named_queries_query_ast_json).unwrap();
+        let names: Vec<&str> = named_queries_query_ast
+            .named_queries()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(
+            names,
+            ["q", "_tag_overlay_0", "_tag_overlay_1", "_tag_overlay_2"]
+        );
+
+        let mut hit = Hit {
+            json: r#"{"host": "web-1", "acknowledged": false}"#.to_string(),
+            matched_queries: vec![
+                "q".to_string(),
+                "_tag_overlay_1".to_string(),
+                "_tag_overlay_0".to_string(),
+            ],
+            ..Default::default()
+        };
+        apply_tag_overlays_to_hit(&mut hit, &tag_overlays);
+        assert_eq!(hit.matched_queries, ["q"]);
+        let doc: JsonValue = serde_json::from_str(&hit.json).unwrap();
+        assert_eq!(
+            doc,
+            serde_json::json!({"host": "web-1", "acknowledged": true, "owner": "alice"})
+        );
+
+        let mut hit = Hit {
+            json: r#"{"host": "web-2", "acknowledged": false}"#.to_string(),
+            matched_queries: vec!["_tag_overlay_2".to_string()],
+            ..Default::default()
+        };
+        apply_tag_overlays_to_hit(&mut hit, &tag_overlays);
+        assert!(hit.matched_queries.is_empty());
+        let doc: JsonValue = serde_json::from_str(&hit.json).unwrap();
+        assert_eq!(doc, serde_json::json!({"host": "web-2"}));
+    }
+}
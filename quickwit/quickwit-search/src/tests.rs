@@ -15,14 +15,17 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use assert_json_diff::{assert_json_eq, assert_json_include};
-use quickwit_config::SearcherConfig;
+use bytesize::ByteSize;
+use quickwit_config::{SearchSettings, SearcherConfig, SecurityPolicy};
 use quickwit_doc_mapper::tag_pruning::extract_tags_from_query;
 use quickwit_doc_mapper::DocMapper;
 use quickwit_indexing::TestSandbox;
+use quickwit_metastore::{IndexMetadataResponseExt, UpdateIndexRequestExt};
 use quickwit_opentelemetry::otlp::TraceId;
+use quickwit_proto::metastore::{IndexMetadataRequest, MockMetastoreService, UpdateIndexRequest};
 use quickwit_proto::search::{
-    LeafListTermsResponse, ListTermsRequest, SearchRequest, SortByValue, SortField, SortOrder,
-    SortValue,
+    LeafListTermsResponse, LeafSearchRequest, ListTermsRequest, SearchRequest, SecurityContext,
+    SortByValue, SortField, SortOrder, SortValue,
 };
 use quickwit_query::query_ast::{
     qast_helper, qast_json_helper, query_ast_from_user_text, QueryAst,
@@ -209,6 +212,188 @@ async fn slop_search_and_check(
     Ok(())
 }
 
+#[tokio::test]
+async fn test_explain() -> anyhow::Result<()> {
+    let index_id = "explain";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: title
+                type: text
+              - name: body
+                type: text
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    let docs = vec![
+        json!({"title": "snoopy", "body": "Snoopy is an anthropomorphic beagle"}),
+        json!({"title": "beagle", "body": "The beagle is a breed of small scent hound"}),
+    ];
+    test_sandbox.add_documents(docs).await?;
+    let mut metastore = test_sandbox.metastore();
+    let splits = list_all_splits(vec![test_sandbox.index_uid()], &mut metastore).await?;
+    assert_eq!(splits.len(), 1);
+
+    let searcher_context = SearcherContext::for_test();
+    let explain_request = |doc_id: u32| ExplainRequest {
+        index_id: index_id.to_string(),
+        query_ast: qast_json_helper("body:beagle AND title:snoopy", &[]),
+        doc_address: GlobalDocAddress {
+            split: splits[0].split_id.clone(),
+            doc_addr: DocAddress::new(0, doc_id),
+        },
+        security_context: None,
+    };
+    let explain_response = explain::explain(
+        explain_request(0),
+        metastore.clone(),
+        &test_sandbox.storage_resolver(),
+        &searcher_context,
+    )
+    .await?;
+    assert!(explain_response.matches);
+    assert!(explain_response.score.unwrap() > 0.0);
+    assert_eq!(explain_response.explanation.clauses.len(), 2);
+    assert!(explain_response
+        .explanation
+        .clauses
+        .iter()
+        .all(|clause| clause.matches && clause.occur.as_deref() == Some("must")));
+
+    let explain_response = explain::explain(
+        explain_request(1),
+        metastore.clone(),
+        &test_sandbox.storage_resolver(),
+        &searcher_context,
+    )
+    .await?;
+    assert!(!explain_response.matches);
+    assert!(explain_response.score.is_none());
+
+    let body_clause = &explain_response.explanation.clauses[0];
+    assert!(body_clause.matches);
+    assert_eq!(body_clause.query_tokens, ["beagle"]);
+
+    let title_clause = &explain_response.explanation.clauses[1];
+    assert!(!title_clause.matches);
+    assert_eq!(title_clause.query_tokens, ["snoopy"]);
+    assert_eq!(title_clause.document_tokens, ["beagle"]);
+
+    let error = explain::explain(
+        explain_request(2),
+        metastore,
+        &test_sandbox.storage_resolver(),
+        &searcher_context,
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(error, SearchError::InvalidArgument(_)));
+
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_explain_with_security_policy() -> anyhow::Result<()> {
+    let index_id = "explain-security-policy";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: title
+                type: text
+              - name: body
+                type: text
+              - name: tenant
+                type: text
+                tokenizer: raw
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    let docs = vec![
+        json!({"title": "snoopy", "body": "Snoopy is a beagle", "tenant": "acme"}),
+        json!({"title": "snoopy", "body": "Odie is a beagle too", "tenant": "globex"}),
+    ];
+    test_sandbox.add_documents(docs).await?;
+    let mut metastore = test_sandbox.metastore();
+
+    let index_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(index_id.to_string()))
+        .await?
+        .deserialize_index_metadata()?;
+    let index_config = index_metadata.index_config;
+    let search_settings = SearchSettings {
+        security_policies: vec![SecurityPolicy {
+            role: "support".to_string(),
+            retrievable_fields: Some(vec!["body".to_string()]),
+            filter: Some("tenant:{claims.tenant}".to_string()),
+            allow_updates: false,
+        }],
+        ..index_config.search_settings
+    };
+    let update_index_request = UpdateIndexRequest::try_from_updates(
+        index_metadata.index_uid,
+        &search_settings,
+        &index_config.retention_policy_opt,
+        &index_config.indexing_settings,
+        &index_config.doc_mapping,
+    )?;
+    metastore.update_index(update_index_request).await?;
+
+    let splits = list_all_splits(vec![test_sandbox.index_uid()], &mut metastore).await?;
+    assert_eq!(splits.len(), 1);
+
+    let searcher_context = SearcherContext::for_test();
+    let explain_request = |user_text: &str, doc_id: u32| ExplainRequest {
+        index_id: index_id.to_string(),
+        query_ast: qast_json_helper(user_text, &[]),
+        doc_address: GlobalDocAddress {
+            split: splits[0].split_id.clone(),
+            doc_addr: DocAddress::new(0, doc_id),
+        },
+        security_context: Some(SecurityContext {
+            role: "support".to_string(),
+            claims: BTreeMap::from_iter([("tenant".to_string(), "acme".to_string())]),
+        }),
+    };
+    let explain_response = explain::explain(
+        explain_request("body:beagle AND body:snoopy", 0),
+        metastore.clone(),
+        &test_sandbox.storage_resolver(),
+        &searcher_context,
+    )
+    .await?;
+    assert!(explain_response.matches);
+
+    let user_query_clause = &explain_response.explanation.clauses[0];
+    let body_clause = &user_query_clause.clauses[0];
+    assert_eq!(body_clause.document_tokens, ["snoopy", "is", "a", "beagle"]);
+
+    let snoopy_clause = &user_query_clause.clauses[1];
+    assert!(snoopy_clause.matches);
+    assert_eq!(snoopy_clause.query_tokens, ["snoopy"]);
+
+    // The fields the role cannot retrieve cannot be queried.
+    let error = explain::explain(
+        explain_request("body:beagle AND title:snoopy", 0),
+        metastore.clone(),
+        &test_sandbox.storage_resolver(),
+        &searcher_context,
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(error, SearchError::Forbidden(_)));
+
+    // The document of the other tenant is hidden by the security filter.
+    let error = explain::explain(
+        explain_request("body:beagle AND body:snoopy", 1),
+        metastore,
+        &test_sandbox.storage_resolver(),
+        &searcher_context,
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(error, SearchError::InvalidArgument(_)));
+
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_slop_queries() {
     let index_id = "slop-query";
@@ -1053,6 +1238,7 @@ async fn test_search_util(test_sandbox: &TestSandbox, query: &str) -> Vec<u32> {
         searcher_context,
         request,
         test_sandbox.storage(),
+        Some(test_sandbox.index_uid().index_id),
         splits_offsets,
         test_sandbox.doc_mapper(),
         agg_limits,
@@ -1400,6 +1586,62 @@ async fn test_single_node_aggregation() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_single_node_rate_aggregation() -> anyhow::Result<()> {
+    let index_id = "single-node-rate-agg";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: ts
+                type: datetime
+                input_formats:
+                  - unix_timestamp
+                fast: true
+              - name: bytes
+                type: u64
+                fast: true
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &[]).await?;
+    let docs = vec![
+        json!({"ts": 1_700_000_000, "bytes": 10}),
+        json!({"ts": 1_700_000_001, "bytes": 20}),
+        json!({"ts": 1_700_000_002, "bytes": 30}),
+        json!({"ts": 1_700_000_012, "bytes": 40}),
+    ];
+    test_sandbox.add_documents(docs).await?;
+    let agg_req = json!({
+        "histo": {
+            "date_histogram": { "field": "ts", "fixed_interval": "10s" },
+            "aggs": {
+                "bytes_per_sec": { "rate": { "field": "bytes", "unit": "second" } },
+                "docs_per_min": { "rate": { "unit": "minute" } }
+            }
+        }
+    });
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("*", &[]),
+        aggregation_request: Some(agg_req.to_string()),
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    let agg_res_json: JsonValue = serde_json::from_str(&single_node_result.aggregation.unwrap())?;
+    let buckets = agg_res_json["histo"]["buckets"].as_array().unwrap();
+    assert_eq!(buckets.len(), 2);
+    assert_eq!(buckets[0]["doc_count"], 3);
+    assert_eq!(buckets[0]["bytes_per_sec"]["value"], 6.0);
+    assert_eq!(buckets[0]["docs_per_min"]["value"], 18.0);
+    assert_eq!(buckets[1]["doc_count"], 1);
+    assert_eq!(buckets[1]["bytes_per_sec"]["value"], 4.0);
+    assert_eq!(buckets[1]["docs_per_min"]["value"], 6.0);
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_single_node_aggregation_missing_fast_field() {
     let index_id = "single-node-agg-2";
@@ -1462,6 +1704,173 @@ async fn test_single_node_aggregation_missing_fast_field() {
     test_sandbox.assert_quit().await;
 }
 
+#[tokio::test]
+async fn test_single_node_docvalue_fields() -> anyhow::Result<()> {
+    let index_id = "single-node-docvalue-fields";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: body
+                type: text
+              - name: status
+                type: text
+                tokenizer: raw
+                fast: true
+              - name: latency
+                type: f64
+                fast: true
+              - name: host
+                type: ip
+                fast: true
+              - name: tags
+                type: array<u64>
+                fast: true
+            mode: dynamic
+            dynamic_mapping:
+              fast: true
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    let docs = vec![
+        json!({
+            "body": "first",
+            "status": "ok",
+            "latency": 1.5,
+            "host": "10.10.11.125",
+            "tags": [1, 2],
+            "attributes": {"region": "us"}
+        }),
+        json!({"body": "second", "status": "error", "latency": 2.5}),
+    ];
+    test_sandbox.add_documents(docs).await?;
+
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("first", &["body"]),
+        max_hits: 10,
+        docvalue_fields: vec![
+            "status".to_string(),
+            "latency".to_string(),
+            "host".to_string(),
+            "tags".to_string(),
+            "attributes.region".to_string(),
+        ],
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(single_node_result.num_hits, 1);
+
+    let hit_json: JsonValue = serde_json::from_str(&single_node_result.hits[0].json)?;
+    assert_eq!(
+        hit_json,
+        json!({
+            "status": ["ok"],
+            "latency": [1.5],
+            "host": ["10.10.11.125"],
+            "tags": [1, 2],
+            "attributes.region": ["us"],
+        })
+    );
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("second", &["body"]),
+        max_hits: 10,
+        docvalue_fields: vec!["status".to_string(), "host".to_string()],
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    let hit_json: JsonValue = serde_json::from_str(&single_node_result.hits[0].json)?;
+    assert_eq!(hit_json, json!({"status": ["error"]}));
+
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("*", &[]),
+        max_hits: 10,
+        docvalue_fields: vec!["body".to_string()],
+        ..Default::default()
+    };
+    let single_node_error = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await
+    .unwrap_err();
+    let SearchError::InvalidArgument(error_msg) = single_node_error else {
+        panic!("expected an invalid argument error, got `{single_node_error}`");
+    };
+    assert!(error_msg.contains("Field \"body\" is not configured as a fast field"));
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_single_node_matched_queries() -> anyhow::Result<()> {
+    let index_id = "single-node-matched-queries";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: title
+                type: text
+              - name: body
+                type: text
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    let docs = vec![
+        json!({"title": "snoopy", "body": "Snoopy is an anthropomorphic beagle."}),
+        json!({"title": "beagle", "body": "The beagle is a breed of small scent hound."}),
+        json!({"title": "foxhound", "body": "The foxhound is a larger hound."}),
+    ];
+    test_sandbox.add_documents(docs).await?;
+
+    let query_ast: QueryAst = quickwit_query::query_ast::BoolQuery {
+        should: vec![
+            qast_helper("title:snoopy", &[]).named(Some("snoopy-title".to_string())),
+            qast_helper("body:beagle", &[]).named(Some("beagle-body".to_string())),
+            qast_helper("title:beagle", &[]).named(Some("beagle-title".to_string())),
+        ],
+        ..Default::default()
+    }
+    .into();
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: serde_json::to_string(&query_ast).unwrap(),
+        max_hits: 10,
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(single_node_result.num_hits, 2);
+
+    let mut matched_queries_per_title: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for hit in single_node_result.hits {
+        let hit_json: JsonValue = serde_json::from_str(&hit.json)?;
+        let title = hit_json["title"].as_str().unwrap().to_string();
+        matched_queries_per_title.insert(title, hit.matched_queries);
+    }
+    assert_eq!(
+        matched_queries_per_title["snoopy"],
+        ["snoopy-title", "beagle-body"]
+    );
+    assert_eq!(
+        matched_queries_per_title["beagle"],
+        ["beagle-body", "beagle-title"]
+    );
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_single_node_with_ip_field() -> anyhow::Result<()> {
     let index_id = "single-node-with-ip-field";
@@ -1692,10 +2101,12 @@ async fn test_single_node_list_terms() -> anyhow::Result<()> {
             start_timestamp: None,
             end_timestamp: None,
             max_hits: Some(100),
+            security_context: None,
         };
         let search_response = leaf_list_terms(
             searcher_context.clone(),
             &request,
+            Some(test_sandbox.index_uid()),
             test_sandbox.storage(),
             &splits_offsets,
         )
@@ -1713,10 +2124,12 @@ async fn test_single_node_list_terms() -> anyhow::Result<()> {
             start_timestamp: None,
             end_timestamp: None,
             max_hits: Some(1),
+            security_context: None,
         };
         let search_response = leaf_list_terms(
             searcher_context.clone(),
             &request,
+            Some(test_sandbox.index_uid()),
             test_sandbox.storage(),
             &splits_offsets,
         )
@@ -1734,10 +2147,12 @@ async fn test_single_node_list_terms() -> anyhow::Result<()> {
             start_timestamp: None,
             end_timestamp: None,
             max_hits: Some(100),
+            security_context: None,
         };
         let search_response = leaf_list_terms(
             searcher_context.clone(),
             &request,
+            Some(test_sandbox.index_uid()),
             test_sandbox.storage(),
             &splits_offsets,
         )
@@ -1755,10 +2170,12 @@ async fn test_single_node_list_terms() -> anyhow::Result<()> {
             start_timestamp: None,
             end_timestamp: None,
             max_hits: Some(100),
+            security_context: None,
         };
         let search_response = leaf_list_terms(
             searcher_context.clone(),
             &request,
+            Some(test_sandbox.index_uid()),
             test_sandbox.storage(),
             &splits_offsets,
         )
@@ -1897,3 +2314,33 @@ fn test_global_doc_address_ser_deser() {
     let doc_address_deser: GlobalDocAddress = doc_address_string.parse().unwrap();
     assert_eq!(doc_address_deser, doc_address);
 }
+
+#[tokio::test]
+async fn test_leaf_search_rejected_when_searcher_is_saturated() {
+    let searcher_config = SearcherConfig {
+        max_num_concurrent_split_searches: 1,
+        max_num_pending_split_searches: std::num::NonZeroUsize::new(2),
+        ..Default::default()
+    };
+    let searcher_context = Arc::new(SearcherContext::new(searcher_config, None));
+    let search_service = SearchServiceImpl::new(
+        MetastoreServiceClient::from_mock(MockMetastoreService::new()),
+        StorageResolver::unconfigured(),
+        ClusterClient::new(SearchJobPlacer::new(SearcherPool::default())),
+        searcher_context.clone(),
+    );
+    let leaf_search_request = LeafSearchRequest {
+        search_request: Some(SearchRequest::default()),
+        ..Default::default()
+    };
+    // One split search runs and two wait for a permit.
+    let _permit_futures = searcher_context
+        .search_permit_provider
+        .get_permits([ByteSize::mb(10); 3])
+        .await;
+    let search_error = search_service
+        .leaf_search(leaf_search_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(search_error, SearchError::TooManyRequests));
+}
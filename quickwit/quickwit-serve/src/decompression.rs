@@ -12,14 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io;
 use std::io::Read;
 use std::sync::OnceLock;
 
-use bytes::Bytes;
+use async_compression::tokio::bufread::{
+    GzipDecoder, ZlibDecoder as AsyncZlibDecoder, ZstdDecoder,
+};
+use bytes::{Buf, Bytes};
 use flate2::read::{MultiGzDecoder, ZlibDecoder};
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt, TryStreamExt};
 use quickwit_common::metrics::{GaugeGuard, MEMORY_METRICS};
 use quickwit_common::thread_pool::run_cpu_intensive;
 use thiserror::Error;
+use tokio_util::io::{ReaderStream, StreamReader};
 use warp::reject::Reject;
 use warp::Filter;
 
@@ -36,7 +43,8 @@ fn get_ingest_load_shield() -> &'static LoadShield {
 ///
 /// The first approach lowers the latency, while the second approach is more CPU efficient.
 /// Ingesting data is usually CPU bound and there is considerable latency until the data is
-/// searchable, so the second approach is more suitable for this use case.
+/// searchable, so the second approach is more suitable for this use case. The first approach is
+/// only used for the bulk requests too large to be buffered, see [`get_body_stream`].
 async fn decompress_body(encoding: Option<String>, body: Bytes) -> Result<Bytes, warp::Rejection> {
     match encoding.as_deref() {
         Some("identity") => Ok(body),
@@ -106,6 +114,60 @@ pub(crate) fn get_body_bytes() -> impl Filter<Extract = (Body,), Error = warp::R
         })
 }
 
+/// Custom filter streaming the body of the requests chunk by chunk. Compressed bodies are
+/// decompressed on the fly, so that the whole body is never buffered.
+pub(crate) fn get_body_stream(
+) -> impl Filter<Extract = (BodyStream,), Error = warp::Rejection> + Clone {
+    warp::header::optional("content-encoding")
+        .and(warp::body::stream())
+        .and_then(|encoding: Option<String>, stream| async move {
+            let permit = get_ingest_load_shield().acquire_permit().await?;
+            let chunks = stream
+                .map_ok(|mut buf| buf.copy_to_bytes(buf.remaining()))
+                .map_err(io::Error::other);
+            let chunks = decompress_body_stream(encoding, chunks)?;
+            Result::<_, warp::Rejection>::Ok(BodyStream {
+                chunks,
+                _permit: permit,
+            })
+        })
+}
+
+fn decompress_body_stream(
+    encoding: Option<String>,
+    chunks: impl Stream<Item = io::Result<Bytes>> + Send + 'static,
+) -> Result<BoxStream<'static, io::Result<Bytes>>, warp::Rejection> {
+    if matches!(encoding.as_deref(), None | Some("identity")) {
+        return Ok(chunks.boxed());
+    }
+    let reader = StreamReader::new(chunks);
+
+    let decompressed_chunks = match encoding.as_deref() {
+        Some("gzip" | "x-gzip") => {
+            let mut decoder = GzipDecoder::new(reader);
+            decoder.multiple_members(true);
+            ReaderStream::new(decoder).boxed()
+        }
+        Some("zstd") => {
+            let mut decoder = ZstdDecoder::new(reader);
+            decoder.multiple_members(true);
+            ReaderStream::new(decoder).boxed()
+        }
+        Some("deflate" | "x-deflate") => ReaderStream::new(AsyncZlibDecoder::new(reader)).boxed(),
+        Some(encoding) => {
+            return Err(warp::reject::custom(UnsupportedEncoding(
+                encoding.to_string(),
+            )))
+        }
+    };
+    Ok(decompressed_chunks)
+}
+
+pub(crate) struct BodyStream {
+    pub chunks: BoxStream<'static, io::Result<Bytes>>,
+    _permit: LoadShieldPermit,
+}
+
 pub(crate) struct Body {
     pub content: Bytes,
     _gauge_guard: GaugeGuard<'static>,
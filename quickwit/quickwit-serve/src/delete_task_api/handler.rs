@@ -21,8 +21,11 @@ use quickwit_proto::metastore::{
 };
 use quickwit_proto::search::SearchRequest;
 use quickwit_proto::types::{IndexId, IndexUid};
-use quickwit_query::query_ast::{query_ast_from_user_text, QueryAst};
+use quickwit_query::query_ast::{
+    is_date_field, query_ast_from_user_text, DateMathResolver, QueryAst, QueryAstTransformer,
+};
 use serde::Deserialize;
+use time::OffsetDateTime;
 use warp::{Filter, Rejection};
 
 use crate::format::extract_format_from_qs;
@@ -150,9 +153,24 @@ pub async fn post_delete_request(
         .await?
         .deserialize_index_metadata()?;
     let index_uid: IndexUid = metadata.index_uid.clone();
+    let index_config = metadata.into_index_config();
+    // TODO should it be something else than a JanitorError?
+    let doc_mapper = build_doc_mapper(&index_config.doc_mapping, &index_config.search_settings)
+        .map_err(|error| JanitorError::Internal(error.to_string()))?;
     let query_ast = query_ast_from_user_text(&delete_request.query, delete_request.search_fields)
-        .parse_user_query(&metadata.index_config.search_settings.default_search_fields)
+        .parse_user_query_with_defaults(index_config.search_settings.user_query_defaults())
         .map_err(|err| JanitorError::InvalidDeleteQuery(err.to_string()))?;
+    // The delete query runs later on: its date math bounds are resolved against the time of the
+    // request.
+    let schema = doc_mapper.schema();
+    let mut date_math_resolver = DateMathResolver {
+        is_date_field: |field_path: &str| is_date_field(field_path, &schema),
+        now: OffsetDateTime::now_utc(),
+    };
+    let query_ast = date_math_resolver
+        .transform(query_ast)
+        .map_err(|err| JanitorError::InvalidDeleteQuery(err.to_string()))?
+        .unwrap_or(QueryAst::MatchAll);
     let query_ast_json = serde_json::to_string(&query_ast).map_err(|_err| {
         JanitorError::Internal("failed to serialized delete query ast".to_string())
     })?;
@@ -162,10 +180,6 @@ pub async fn post_delete_request(
         end_timestamp: delete_request.end_timestamp,
         query_ast: query_ast_json,
     };
-    let index_config = metadata.into_index_config();
-    // TODO should it be something else than a JanitorError?
-    let doc_mapper = build_doc_mapper(&index_config.doc_mapping, &index_config.search_settings)
-        .map_err(|error| JanitorError::Internal(error.to_string()))?;
     let delete_search_request = SearchRequest::try_from(delete_query.clone())
         .map_err(|error| JanitorError::InvalidDeleteQuery(error.to_string()))?;
 
@@ -24,83 +24,177 @@ use quickwit_proto::ingest::router::IngestRouterServiceClient;
 use quickwit_proto::types::IndexId;
 use warp::{Filter, Rejection};
 
-use super::bulk_v2::{elastic_bulk_ingest_v2, ElasticBulkResponse};
-use crate::elasticsearch_api::filter::{elastic_bulk_filter, elastic_index_bulk_filter};
+use super::bulk_v2::{
+    elastic_bulk_ingest_v2, elastic_bulk_ingest_v2_streaming, ElasticBulkResponse,
+};
+use crate::elasticsearch_api::filter::{
+    elastic_bulk_filter, elastic_index_bulk_filter, elastic_index_streaming_bulk_filter,
+    elastic_streaming_bulk_filter,
+};
 use crate::elasticsearch_api::make_elastic_api_response;
 use crate::elasticsearch_api::model::{BulkAction, ElasticBulkOptions, ElasticsearchError};
 use crate::format::extract_format_from_qs;
-use crate::ingest_api::lines;
+use crate::ingest_api::{lines, WriteAliasResolver};
 use crate::rest::recover_fn;
 use crate::{with_arg, Body};
 
 /// POST `_elastic/_bulk`
+///
+/// When `streaming_content_length_limit` is set and ingest V2 is enabled, the request bodies are
+/// streamed, decompressed on the fly if needed, and ingested in batches of at most
+/// `content_length_limit` bytes.
 pub fn es_compat_bulk_handler(
     ingest_service: IngestServiceClient,
     ingest_router: IngestRouterServiceClient,
+    write_alias_resolver: WriteAliasResolver,
     content_length_limit: ByteSize,
+    streaming_content_length_limit_opt: Option<ByteSize>,
     enable_ingest_v1: bool,
     enable_ingest_v2: bool,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    elastic_bulk_filter(content_length_limit)
-        .and(with_arg(ingest_service))
-        .and(with_arg(ingest_router))
-        .then(move |body, bulk_options, ingest_service, ingest_router| {
-            elastic_ingest_bulk(
-                None,
-                body,
-                bulk_options,
-                ingest_service,
-                ingest_router,
-                enable_ingest_v1,
-                enable_ingest_v2,
-            )
-        })
+    let streaming_content_length_limit_opt =
+        streaming_content_length_limit_opt.filter(|_| enable_ingest_v2);
+    let ingest_router_clone = ingest_router.clone();
+    let write_alias_resolver_clone = write_alias_resolver.clone();
+
+    with_streaming_enabled(streaming_content_length_limit_opt)
+        .and(elastic_streaming_bulk_filter())
+        .then(
+            move |streaming_content_length_limit, content_length_opt, body_stream, bulk_options| {
+                elastic_bulk_ingest_v2_streaming(
+                    None,
+                    content_length_opt,
+                    body_stream,
+                    bulk_options,
+                    ingest_router_clone.clone(),
+                    write_alias_resolver_clone.clone(),
+                    content_length_limit,
+                    streaming_content_length_limit,
+                )
+            },
+        )
+        .or(elastic_bulk_filter(content_length_limit)
+            .and(with_arg(ingest_service))
+            .and(with_arg(ingest_router))
+            .and(with_arg(write_alias_resolver))
+            .then(
+                move |body, bulk_options, ingest_service, ingest_router, write_alias_resolver| {
+                    elastic_ingest_bulk(
+                        None,
+                        body,
+                        bulk_options,
+                        ingest_service,
+                        ingest_router,
+                        write_alias_resolver,
+                        enable_ingest_v1,
+                        enable_ingest_v2,
+                    )
+                },
+            ))
+        .unify()
         .and(extract_format_from_qs())
         .map(make_elastic_api_response)
         .recover(recover_fn)
 }
 
 /// POST `_elastic/<index>/_bulk`
+///
+/// See [`es_compat_bulk_handler`] for the streaming behavior.
 pub fn es_compat_index_bulk_handler(
     ingest_service: IngestServiceClient,
     ingest_router: IngestRouterServiceClient,
+    write_alias_resolver: WriteAliasResolver,
     content_length_limit: ByteSize,
+    streaming_content_length_limit_opt: Option<ByteSize>,
     enable_ingest_v1: bool,
     enable_ingest_v2: bool,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    elastic_index_bulk_filter(content_length_limit)
-        .and(with_arg(ingest_service))
-        .and(with_arg(ingest_router))
+    let streaming_content_length_limit_opt =
+        streaming_content_length_limit_opt.filter(|_| enable_ingest_v2);
+    let ingest_router_clone = ingest_router.clone();
+    let write_alias_resolver_clone = write_alias_resolver.clone();
+
+    with_streaming_enabled(streaming_content_length_limit_opt)
+        .and(elastic_index_streaming_bulk_filter())
         .then(
-            move |index_id, body, bulk_options, ingest_service, ingest_router| {
-                elastic_ingest_bulk(
+            move |streaming_content_length_limit,
+                  index_id,
+                  content_length_opt,
+                  body_stream,
+                  bulk_options| {
+                elastic_bulk_ingest_v2_streaming(
                     Some(index_id),
-                    body,
+                    content_length_opt,
+                    body_stream,
                     bulk_options,
-                    ingest_service,
-                    ingest_router,
-                    enable_ingest_v1,
-                    enable_ingest_v2,
+                    ingest_router_clone.clone(),
+                    write_alias_resolver_clone.clone(),
+                    content_length_limit,
+                    streaming_content_length_limit,
                 )
             },
         )
+        .or(elastic_index_bulk_filter(content_length_limit)
+            .and(with_arg(ingest_service))
+            .and(with_arg(ingest_router))
+            .and(with_arg(write_alias_resolver))
+            .then(
+                move |index_id,
+                      body,
+                      bulk_options,
+                      ingest_service,
+                      ingest_router,
+                      write_alias_resolver| {
+                    elastic_ingest_bulk(
+                        Some(index_id),
+                        body,
+                        bulk_options,
+                        ingest_service,
+                        ingest_router,
+                        write_alias_resolver,
+                        enable_ingest_v1,
+                        enable_ingest_v2,
+                    )
+                },
+            ))
+        .unify()
         .and(extract_format_from_qs())
         .map(make_elastic_api_response)
         .recover(recover_fn)
         .boxed()
 }
 
+/// Extracts the streaming content length limit, or rejects the request if streaming is disabled.
+/// This filter must come before the body is consumed so that the request can fall back to the
+/// buffered bulk filters.
+fn with_streaming_enabled(
+    streaming_content_length_limit_opt: Option<ByteSize>,
+) -> impl Filter<Extract = (ByteSize,), Error = Rejection> + Clone {
+    warp::any().and_then(move || async move {
+        streaming_content_length_limit_opt.ok_or_else(warp::reject::not_found)
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn elastic_ingest_bulk(
     default_index_id: Option<IndexId>,
     body: Body,
     bulk_options: ElasticBulkOptions,
     ingest_service: IngestServiceClient,
     ingest_router: IngestRouterServiceClient,
+    write_alias_resolver: WriteAliasResolver,
     enable_ingest_v1: bool,
     enable_ingest_v2: bool,
 ) -> Result<ElasticBulkResponse, ElasticsearchError> {
     if enable_ingest_v2 && !bulk_options.use_legacy_ingest {
-        return elastic_bulk_ingest_v2(default_index_id, body, bulk_options, ingest_router).await;
+        return elastic_bulk_ingest_v2(
+            default_index_id,
+            body,
+            bulk_options,
+            ingest_router,
+            write_alias_resolver,
+        )
+        .await;
     }
     if !enable_ingest_v1 {
         return Err(ElasticsearchError::new(
@@ -170,22 +264,31 @@ async fn elastic_ingest_bulk(
 
 #[cfg(test)]
 mod tests {
+    use std::io::Write;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
     use std::time::Duration;
 
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
     use hyper::StatusCode;
     use quickwit_config::{IngestApiConfig, NodeConfig};
     use quickwit_index_management::IndexService;
     use quickwit_ingest::{FetchRequest, IngestServiceClient, SuggestTruncateRequest};
     use quickwit_metastore::metastore_for_test;
-    use quickwit_proto::ingest::router::IngestRouterServiceClient;
+    use quickwit_proto::ingest::router::{
+        IngestResponseV2, IngestRouterServiceClient, IngestSuccess, MockIngestRouterService,
+    };
+    use quickwit_proto::ingest::IngestV2Error;
     use quickwit_proto::metastore::MetastoreServiceClient;
+    use quickwit_proto::types::{IndexUid, Position, ShardId};
     use quickwit_search::MockSearchService;
     use quickwit_storage::StorageResolver;
 
-    use crate::elasticsearch_api::bulk_v2::ElasticBulkResponse;
+    use super::*;
+    use crate::elasticsearch_api::bulk_v2::{ElasticBulkAction, ElasticBulkResponse};
     use crate::elasticsearch_api::elastic_api_handlers;
-    use crate::elasticsearch_api::model::ElasticsearchError;
+    use crate::elasticsearch_api::model::{ElasticException, ElasticsearchError};
     use crate::elasticsearch_api::tests::mock_cluster;
     use crate::ingest_api::setup_ingest_v1_service;
 
@@ -202,10 +305,13 @@ mod tests {
             mock_cluster().await,
             config,
             search_service,
+            Arc::default(),
             ingest_service,
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -238,10 +344,13 @@ mod tests {
             mock_cluster().await,
             config,
             search_service,
+            Arc::default(),
             ingest_service,
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -277,10 +386,13 @@ mod tests {
             mock_cluster().await,
             config,
             search_service,
+            Arc::default(),
             ingest_service,
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -314,10 +426,13 @@ mod tests {
             mock_cluster().await,
             config,
             search_service,
+            Arc::default(),
             ingest_service,
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -354,10 +469,13 @@ mod tests {
             mock_cluster().await,
             config,
             search_service,
+            Arc::default(),
             ingest_service,
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -445,10 +563,13 @@ mod tests {
             mock_cluster().await,
             config,
             search_service,
+            Arc::default(),
             ingest_service,
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -533,10 +654,13 @@ mod tests {
             mock_cluster().await,
             config,
             search_service,
+            Arc::default(),
             ingest_service,
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -557,4 +681,172 @@ mod tests {
             "Malformed action/metadata line [#0]. Details: `expected value at line 1 column 57`"
         );
     }
+
+    fn mock_streaming_ingest_router(num_failing_batches_after: usize) -> IngestRouterServiceClient {
+        let num_batches = Arc::new(AtomicUsize::new(0));
+        let mut mock_ingest_router = MockIngestRouterService::new();
+        mock_ingest_router
+            .expect_ingest()
+            .returning(move |ingest_request| {
+                if num_batches.fetch_add(1, Ordering::Relaxed) >= num_failing_batches_after {
+                    return Err(IngestV2Error::Unavailable("test".to_string()));
+                }
+                let successes = ingest_request
+                    .subrequests
+                    .into_iter()
+                    .map(|subrequest| IngestSuccess {
+                        subrequest_id: subrequest.subrequest_id,
+                        index_uid: Some(IndexUid::for_test(&subrequest.index_id, 0)),
+                        source_id: subrequest.source_id,
+                        shard_id: Some(ShardId::from(1)),
+                        replication_position_inclusive: Some(Position::offset(0u64)),
+                        num_ingested_docs: subrequest.doc_batch.unwrap().num_docs() as u32,
+                        parse_failures: Vec::new(),
+                    })
+                    .collect();
+                Ok(IngestResponseV2 {
+                    successes,
+                    failures: Vec::new(),
+                })
+            });
+        IngestRouterServiceClient::from_mock(mock_ingest_router)
+    }
+
+    const STREAMED_PAYLOAD: &str = "{\"create\":{\"_index\":\"my-index\",\"_id\":\"1\"}}\n{\"\
+                                    message\":\"push\"}\n{\"create\":{\"_index\":\"my-index\",\"\
+                                    _id\":\"2\"}}\n{\"message\":\"push\"}\n";
+
+    #[tokio::test]
+    async fn test_streaming_bulk_api_reports_failed_batches_as_item_failures() {
+        // Each batch holds a single action/source line pair.
+        let handler = es_compat_bulk_handler(
+            IngestServiceClient::mocked(),
+            mock_streaming_ingest_router(1),
+            WriteAliasResolver::default(),
+            ByteSize::b(80),
+            Some(ByteSize::mb(1)),
+            false,
+            true,
+        );
+        let response = warp::test::request()
+            .path("/_elastic/_bulk")
+            .method("POST")
+            .body(STREAMED_PAYLOAD)
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let bulk_response: ElasticBulkResponse = serde_json::from_slice(response.body()).unwrap();
+        assert!(bulk_response.errors);
+        assert_eq!(bulk_response.actions.len(), 2);
+
+        let ElasticBulkAction::Index(item) = &bulk_response.actions[0] else {
+            panic!("expected index action");
+        };
+        assert_eq!(item.index_id, "my-index");
+        assert_eq!(item.es_doc_id.as_deref(), Some("1"));
+        assert_eq!(item.status, StatusCode::CREATED);
+        assert!(item.error.is_none());
+
+        let ElasticBulkAction::Index(item) = &bulk_response.actions[1] else {
+            panic!("expected index action");
+        };
+        assert_eq!(item.index_id, "my-index");
+        assert_eq!(item.es_doc_id.as_deref(), Some("2"));
+        assert_eq!(item.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            item.error.as_ref().unwrap().exception,
+            ElasticException::Internal
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_bulk_api_fails_if_first_batch_fails() {
+        let handler = es_compat_bulk_handler(
+            IngestServiceClient::mocked(),
+            mock_streaming_ingest_router(0),
+            WriteAliasResolver::default(),
+            ByteSize::b(80),
+            Some(ByteSize::mb(1)),
+            false,
+            true,
+        );
+        let response = warp::test::request()
+            .path("/_elastic/_bulk")
+            .method("POST")
+            .body(STREAMED_PAYLOAD)
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 503);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_bulk_api_decompresses_body() {
+        let handler = es_compat_bulk_handler(
+            IngestServiceClient::mocked(),
+            mock_streaming_ingest_router(usize::MAX),
+            WriteAliasResolver::default(),
+            ByteSize::b(80),
+            Some(ByteSize::mb(1)),
+            false,
+            true,
+        );
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(STREAMED_PAYLOAD.as_bytes()).unwrap();
+        let compressed_payload = encoder.finish().unwrap();
+
+        let response = warp::test::request()
+            .path("/_elastic/_bulk")
+            .method("POST")
+            .header("content-encoding", "gzip")
+            .body(compressed_payload)
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let bulk_response: ElasticBulkResponse = serde_json::from_slice(response.body()).unwrap();
+        assert!(!bulk_response.errors);
+        assert_eq!(bulk_response.actions.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_api_resolves_write_aliases() {
+        let write_alias_resolver = WriteAliasResolver::for_test(&[("logs", "logs-000002")]);
+        let payload = "{\"create\":{\"_index\":\"logs\"}}\n{\"message\":\"push\"}\n{\"create\":\
+                       {}}\n{\"message\":\"push\"}\n{\"create\":{\"_index\":\"other\"}}\n{\"\
+                       message\":\"push\"}\n";
+
+        for streaming_content_length_limit_opt in [None, Some(ByteSize::mb(1))] {
+            let handler = es_compat_index_bulk_handler(
+                IngestServiceClient::mocked(),
+                mock_streaming_ingest_router(usize::MAX),
+                write_alias_resolver.clone(),
+                ByteSize::mb(1),
+                streaming_content_length_limit_opt,
+                false,
+                true,
+            );
+            let response = warp::test::request()
+                .path("/_elastic/logs/_bulk")
+                .method("POST")
+                .body(payload)
+                .reply(&handler)
+                .await;
+            assert_eq!(response.status(), 200);
+
+            let bulk_response: ElasticBulkResponse =
+                serde_json::from_slice(response.body()).unwrap();
+            assert!(!bulk_response.errors);
+
+            let index_ids: Vec<&str> = bulk_response
+                .actions
+                .iter()
+                .map(|action| match action {
+                    ElasticBulkAction::Create(item) => item.index_id.as_str(),
+                    ElasticBulkAction::Index(item) => item.index_id.as_str(),
+                })
+                .collect();
+            assert_eq!(index_ids, ["logs-000002", "logs-000002", "other"]);
+        }
+    }
 }
@@ -15,7 +15,11 @@
 use std::collections::HashMap;
 use std::time::Instant;
 
+use bytes::{Bytes, BytesMut};
+use bytesize::ByteSize;
+use futures::StreamExt;
 use hyper::StatusCode;
+use quickwit_common::metrics::{GaugeGuard, MEMORY_METRICS};
 use quickwit_common::rate_limited_error;
 use quickwit_config::{validate_identifier, INGEST_V2_SOURCE_ID};
 use quickwit_ingest::IngestRequestV2Builder;
@@ -27,8 +31,9 @@ use quickwit_proto::types::{DocUid, IndexId};
 use serde::{Deserialize, Serialize};
 
 use super::model::ElasticException;
+use crate::decompression::BodyStream;
 use crate::elasticsearch_api::model::{BulkAction, ElasticBulkOptions, ElasticsearchError};
-use crate::ingest_api::lines;
+use crate::ingest_api::{is_empty_or_blank_line, lines, WriteAliasResolver};
 use crate::Body;
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -85,10 +90,253 @@ pub(crate) async fn elastic_bulk_ingest_v2(
     body: Body,
     bulk_options: ElasticBulkOptions,
     ingest_router: IngestRouterServiceClient,
+    write_alias_resolver: WriteAliasResolver,
 ) -> Result<ElasticBulkResponse, ElasticsearchError> {
     let now = Instant::now();
+    elastic_bulk_ingest_batch_v2(
+        &default_index_id,
+        &body.content,
+        bulk_options,
+        &ingest_router,
+        &write_alias_resolver,
+        now,
+    )
+    .await
+}
+
+/// Ingests a bulk request whose body is streamed rather than buffered. The body is cut into
+/// batches of at most `batch_num_bytes_limit` bytes along action/source line pair boundaries, and
+/// each batch is ingested as a separate ingest request. The total size of the body is capped by
+/// `content_length_limit`.
+///
+/// Once a batch has been ingested, the request can no longer fail as a whole: the actions of the
+/// batches that fail afterwards are reported as failed items, like in the response of a buffered
+/// bulk request. If the body turns out to be too large or unreadable at that point, the actions
+/// read so far are reported as failed and the rest of the body is not read.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn elastic_bulk_ingest_v2_streaming(
+    default_index_id: Option<IndexId>,
+    content_length_opt: Option<u64>,
+    mut body_stream: BodyStream,
+    bulk_options: ElasticBulkOptions,
+    ingest_router: IngestRouterServiceClient,
+    write_alias_resolver: WriteAliasResolver,
+    batch_num_bytes_limit: ByteSize,
+    content_length_limit: ByteSize,
+) -> Result<ElasticBulkResponse, ElasticsearchError> {
+    if content_length_opt
+        .is_some_and(|content_length| content_length > content_length_limit.as_u64())
+    {
+        return Err(make_payload_too_large_error(content_length_limit));
+    }
+    let now = Instant::now();
+    let batch_num_bytes_limit = batch_num_bytes_limit.as_u64() as usize;
+    let mut gauge_guard = GaugeGuard::from_gauge(&MEMORY_METRICS.in_flight.rest_server);
+    let mut buffer = BytesMut::new();
+    let mut num_bytes_read: u64 = 0;
+    let mut is_end_of_stream = false;
+    let mut bulk_response = ElasticBulkResponse::default();
+    let mut has_ingested_batch = false;
+
+    loop {
+        let mut read_error_opt: Option<ElasticsearchError> = None;
+
+        while !is_end_of_stream && buffer.len() < batch_num_bytes_limit {
+            let Some(chunk_res) = body_stream.chunks.next().await else {
+                is_end_of_stream = true;
+                break;
+            };
+            let chunk = match chunk_res {
+                Ok(chunk) => chunk,
+                Err(error) => {
+                    read_error_opt = Some(ElasticsearchError::new(
+                        StatusCode::BAD_REQUEST,
+                        format!("failed to read request body: {error}"),
+                        None,
+                    ));
+                    break;
+                }
+            };
+            num_bytes_read += chunk.len() as u64;
+
+            if num_bytes_read > content_length_limit.as_u64() {
+                read_error_opt = Some(make_payload_too_large_error(content_length_limit));
+                break;
+            }
+            gauge_guard.add(chunk.len() as i64);
+            buffer.extend_from_slice(&chunk);
+        }
+        if let Some(read_error) = read_error_opt {
+            if !has_ingested_batch {
+                return Err(read_error);
+            }
+            let complete_len = complete_line_pairs_len(&buffer, buffer.len()).unwrap_or_default();
+            let batch = buffer.split_to(complete_len).freeze();
+            let failed_actions = make_failed_batch_actions(&batch, &default_index_id, &read_error);
+            bulk_response.errors = true;
+            bulk_response.actions.extend(failed_actions);
+            break;
+        }
+        let batch_len_opt = if is_end_of_stream && buffer.len() <= batch_num_bytes_limit {
+            Some(buffer.len())
+        } else {
+            complete_line_pairs_len(&buffer, batch_num_bytes_limit)
+        };
+        let Some(batch_len) = batch_len_opt else {
+            let line_pair_too_large_error = ElasticsearchError::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "action/source line pair exceeds the maximum batch size of {}",
+                    ByteSize::b(batch_num_bytes_limit as u64)
+                ),
+                None,
+            );
+            if !has_ingested_batch {
+                return Err(line_pair_too_large_error);
+            }
+            // Only the action line of the oversized pair is complete.
+            let action_line_len = buffer
+                .iter()
+                .position(|byte| *byte == b'\n')
+                .map(|newline_pos| newline_pos + 1)
+                .unwrap_or(buffer.len());
+            let batch = buffer.split_to(action_line_len).freeze();
+            let failed_actions =
+                make_failed_batch_actions(&batch, &default_index_id, &line_pair_too_large_error);
+            bulk_response.errors = true;
+            bulk_response.actions.extend(failed_actions);
+            break;
+        };
+        let batch = buffer.split_to(batch_len).freeze();
+        let batch_response_res = elastic_bulk_ingest_batch_v2(
+            &default_index_id,
+            &batch,
+            bulk_options,
+            &ingest_router,
+            &write_alias_resolver,
+            now,
+        )
+        .await;
+        gauge_guard.sub(batch.len() as i64);
+
+        match batch_response_res {
+            Ok(batch_response) => {
+                has_ingested_batch = true;
+                bulk_response.errors |= batch_response.errors;
+                bulk_response.actions.extend(batch_response.actions);
+            }
+            Err(batch_error) if has_ingested_batch => {
+                let failed_actions =
+                    make_failed_batch_actions(&batch, &default_index_id, &batch_error);
+                bulk_response.errors = true;
+                bulk_response.actions.extend(failed_actions);
+            }
+            Err(batch_error) => {
+                return Err(batch_error);
+            }
+        }
+        if is_end_of_stream && buffer.is_empty() {
+            break;
+        }
+    }
+    bulk_response.took_millis = now.elapsed().as_millis() as u64;
+    Ok(bulk_response)
+}
+
+/// Reports each action of a batch that could not be ingested as a failed item carrying the error
+/// of the batch. The actions that cannot be parsed are attributed to the default index.
+fn make_failed_batch_actions(
+    batch: &Bytes,
+    default_index_id: &Option<IndexId>,
+    batch_error: &ElasticsearchError,
+) -> Vec<ElasticBulkAction> {
+    let exception = batch_error
+        .error
+        .ty
+        .as_ref()
+        .and_then(|ty| {
+            serde_json::from_value::<ElasticException>(serde_json::Value::String(ty.clone())).ok()
+        })
+        .unwrap_or(ElasticException::Internal);
+    let reason = batch_error.error.reason.clone().unwrap_or_default();
+    let mut failed_actions = Vec::new();
+    let mut lines = lines(batch);
+
+    while let Some(action_line) = lines.next() {
+        let _source_line_opt = lines.next();
+        let (index_id_opt, es_doc_id) = match serde_json::from_slice::<BulkAction>(action_line) {
+            Ok(action) => {
+                let meta = action.into_meta();
+                (meta.index_id, meta.es_doc_id)
+            }
+            Err(_) => (None, None),
+        };
+        let index_id = index_id_opt
+            .or_else(|| default_index_id.clone())
+            .unwrap_or_default();
+        let error = ElasticBulkError {
+            index_id: Some(index_id.clone()),
+            exception,
+            reason: reason.clone(),
+        };
+        let item = ElasticBulkItem {
+            index_id,
+            es_doc_id,
+            status: batch_error.status,
+            error: Some(error),
+        };
+        failed_actions.push(ElasticBulkAction::Index(item));
+    }
+    failed_actions
+}
+
+/// Returns the length of the longest prefix of `buffer` that fits in `max_num_bytes` and only
+/// contains complete action/source line pairs, ignoring blank lines.
+fn complete_line_pairs_len(buffer: &[u8], max_num_bytes: usize) -> Option<usize> {
+    let mut complete_line_pairs_len_opt = None;
+    let mut num_lines = 0;
+    let mut line_start = 0;
+
+    for (newline_pos, _) in buffer
+        .iter()
+        .enumerate()
+        .filter(|(_, byte)| **byte == b'\n')
+    {
+        let line_end = newline_pos + 1;
+
+        if line_end > max_num_bytes {
+            break;
+        }
+        if !is_empty_or_blank_line(&buffer[line_start..newline_pos]) {
+            num_lines += 1;
+
+            if num_lines % 2 == 0 {
+                complete_line_pairs_len_opt = Some(line_end);
+            }
+        }
+        line_start = line_end;
+    }
+    complete_line_pairs_len_opt
+}
+
+fn make_payload_too_large_error(content_length_limit: ByteSize) -> ElasticsearchError {
+    ElasticsearchError::new(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        format!("request body exceeds the content length limit of {content_length_limit}"),
+        None,
+    )
+}
+
+async fn elastic_bulk_ingest_batch_v2(
+    default_index_id: &Option<IndexId>,
+    content: &Bytes,
+    bulk_options: ElasticBulkOptions,
+    ingest_router: &IngestRouterServiceClient,
+    write_alias_resolver: &WriteAliasResolver,
+    now: Instant,
+) -> Result<ElasticBulkResponse, ElasticsearchError> {
     let mut ingest_request_builder = IngestRequestV2Builder::default();
-    let mut lines = lines(&body.content).enumerate();
+    let mut lines = lines(content).enumerate();
     let mut per_subrequest_doc_handles: HashMap<u32, Vec<DocHandle>> = HashMap::new();
     let mut action_count = 0;
     let mut invalid_index_id_items = Vec::new();
@@ -131,7 +379,8 @@ pub(crate) async fn elastic_bulk_ingest_v2(
             action_count += 1;
             continue;
         }
-
+        // Documents sent to the write alias of a rollover policy land in its latest generation.
+        let index_id = write_alias_resolver.resolve(index_id);
         let (subrequest_id, doc_uid) = ingest_request_builder.add_doc(index_id, doc);
 
         let doc_handle = DocHandle {
@@ -266,6 +515,11 @@ fn make_elastic_bulk_response_v2(
                 format!("no such index [{}]", failure.index_id),
                 StatusCode::NOT_FOUND,
             ),
+            IngestFailureReason::IndexFrozen => (
+                ElasticException::IngestionFrozen,
+                format!("ingestion frozen [{}]", failure.index_id),
+                StatusCode::CONFLICT,
+            ),
             IngestFailureReason::SourceNotFound => (
                 ElasticException::SourceNotFound,
                 format!("no such source [{}]", failure.index_id),
@@ -433,7 +687,13 @@ mod tests {
         elastic_bulk_filter(content_length_limit)
             .and(with_arg(ingest_router))
             .then(|body, bulk_options, ingest_router| {
-                elastic_bulk_ingest_v2(None, body, bulk_options, ingest_router)
+                elastic_bulk_ingest_v2(
+                    None,
+                    body,
+                    bulk_options,
+                    ingest_router,
+                    WriteAliasResolver::default(),
+                )
             })
             .and(extract_format_from_qs())
             .map(make_elastic_api_response)
@@ -945,4 +1205,17 @@ mod tests {
         assert_eq!(items[2].es_doc_id.as_ref().unwrap(), "1");
         assert_eq!(items[2].status, StatusCode::CREATED);
     }
+
+    #[test]
+    fn test_complete_line_pairs_len() {
+        assert_eq!(complete_line_pairs_len(b"", 100), None);
+        assert_eq!(complete_line_pairs_len(b"{}\n", 100), None);
+        assert_eq!(complete_line_pairs_len(b"{}\n{}", 100), None);
+        assert_eq!(complete_line_pairs_len(b"{}\n{}\n", 100), Some(6));
+        assert_eq!(complete_line_pairs_len(b"{}\n{}\n{}\n{}", 100), Some(6));
+        assert_eq!(complete_line_pairs_len(b"{}\n{}\n{}\n{}\n", 100), Some(12));
+        assert_eq!(complete_line_pairs_len(b"{}\n{}\n{}\n{}\n", 11), Some(6));
+        assert_eq!(complete_line_pairs_len(b"{}\n{}\n{}\n{}\n", 5), None);
+        assert_eq!(complete_line_pairs_len(b"{}\n\n \n{}\n\n", 100), Some(9));
+    }
 }
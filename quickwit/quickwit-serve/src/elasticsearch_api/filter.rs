@@ -20,17 +20,15 @@ use warp::{Filter, Rejection};
 
 use super::model::{
     CatIndexQueryParams, DeleteQueryParams, FieldCapabilityQueryParams, FieldCapabilityRequestBody,
-    MultiSearchQueryParams, SearchQueryParamsCount,
+    MultiSearchQueryParams, SearchQueryParamsCount, UpdateByQueryBody,
 };
-use crate::decompression::get_body_bytes;
+use crate::decompression::{get_body_bytes, get_body_stream, BodyStream};
 use crate::elasticsearch_api::model::{
     ElasticBulkOptions, ScrollQueryParams, SearchBody, SearchQueryParams,
 };
 use crate::search_api::{extract_index_id_patterns, extract_index_id_patterns_default};
 use crate::Body;
 
-const BODY_LENGTH_LIMIT: ByteSize = ByteSize::mib(1);
-
 // TODO: Make all elastic endpoint models `utoipa` compatible
 // and register them here.
 #[derive(utoipa::OpenApi)]
@@ -103,10 +101,56 @@ pub(crate) fn elastic_index_bulk_filter(
         ))
 }
 
+/// Rejects the bulk requests that explicitly target the legacy ingest API, which does not support
+/// streaming.
+fn streamable_bulk_options(
+) -> impl Filter<Extract = (ElasticBulkOptions,), Error = Rejection> + Clone {
+    serde_qs::warp::query::<ElasticBulkOptions>(serde_qs::Config::default()).and_then(
+        |bulk_options: ElasticBulkOptions| async move {
+            if bulk_options.use_legacy_ingest {
+                return Err(warp::reject::not_found());
+            }
+            Ok(bulk_options)
+        },
+    )
+}
+
+/// Same as [`elastic_bulk_filter`], but streams the body of the requests instead of buffering it.
+pub(crate) fn elastic_streaming_bulk_filter(
+) -> impl Filter<Extract = (Option<u64>, BodyStream, ElasticBulkOptions), Error = Rejection> + Clone
+{
+    warp::path!("_elastic" / "_bulk")
+        .and(warp::post().or(warp::put()).unify())
+        .and(streamable_bulk_options())
+        .and(warp::header::optional::<u64>("content-length"))
+        .and(get_body_stream())
+        .map(|bulk_options, content_length_opt, body_stream| {
+            (content_length_opt, body_stream, bulk_options)
+        })
+        .untuple_one()
+}
+
+/// Same as [`elastic_index_bulk_filter`], but streams the body of the requests instead of
+/// buffering it.
+pub(crate) fn elastic_index_streaming_bulk_filter(
+) -> impl Filter<Extract = (String, Option<u64>, BodyStream, ElasticBulkOptions), Error = Rejection>
+       + Clone {
+    warp::path!("_elastic" / String / "_bulk")
+        .and(warp::post().or(warp::put()).unify())
+        .and(streamable_bulk_options())
+        .and(warp::header::optional::<u64>("content-length"))
+        .and(get_body_stream())
+        .map(|index_id, bulk_options, content_length_opt, body_stream| {
+            (index_id, content_length_opt, body_stream, bulk_options)
+        })
+        .untuple_one()
+}
+
 /// Like the warp json filter, but accepts an empty body and interprets it as `T::default`.
 fn json_or_empty<T: DeserializeOwned + Send + Default>(
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (T,), Error = Rejection> + Copy {
-    warp::body::content_length_limit(BODY_LENGTH_LIMIT.as_u64())
+    warp::body::content_length_limit(content_length_limit.as_u64())
         .and(warp::body::bytes().and_then(|buf: Bytes| async move {
             if buf.is_empty() {
                 return Ok(T::default());
@@ -127,7 +171,9 @@ fn json_or_empty<T: DeserializeOwned + Send + Default>(
 }
 
 #[utoipa::path(get, tag = "Metadata", path = "/{index}/_field_caps")]
-pub(crate) fn elastic_index_field_capabilities_filter() -> impl Filter<
+pub(crate) fn elastic_index_field_capabilities_filter(
+    content_length_limit: ByteSize,
+) -> impl Filter<
     Extract = (
         Vec<String>,
         FieldCapabilityQueryParams,
@@ -139,11 +185,13 @@ pub(crate) fn elastic_index_field_capabilities_filter() -> impl Filter<
         .and_then(extract_index_id_patterns)
         .and(warp::get().or(warp::post()).unify())
         .and(serde_qs::warp::query(serde_qs::Config::default()))
-        .and(json_or_empty())
+        .and(json_or_empty(content_length_limit))
 }
 
 #[utoipa::path(get, tag = "Metadata", path = "/_field_caps")]
-pub(crate) fn elastic_field_capabilities_filter() -> impl Filter<
+pub(crate) fn elastic_field_capabilities_filter(
+    content_length_limit: ByteSize,
+) -> impl Filter<
     Extract = (
         Vec<String>,
         FieldCapabilityQueryParams,
@@ -155,7 +203,7 @@ pub(crate) fn elastic_field_capabilities_filter() -> impl Filter<
         .and_then(extract_index_id_patterns_default)
         .and(warp::get().or(warp::post()).unify())
         .and(serde_qs::warp::query(serde_qs::Config::default()))
-        .and(json_or_empty())
+        .and(json_or_empty(content_length_limit))
 }
 
 #[utoipa::path(get, tag = "Metadata", path = "/_resolve/index/{index}")]
@@ -168,13 +216,14 @@ pub(crate) fn elastic_resolve_index_filter(
 
 #[utoipa::path(get, tag = "Count", path = "/{index}/_count")]
 pub(crate) fn elastic_index_count_filter(
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (Vec<String>, SearchQueryParamsCount, SearchBody), Error = Rejection> + Clone
 {
     warp::path!("_elastic" / String / "_count")
         .and_then(extract_index_id_patterns)
         .and(warp::get().or(warp::post()).unify())
         .and(serde_qs::warp::query(serde_qs::Config::default()))
-        .and(json_or_empty())
+        .and(json_or_empty(content_length_limit))
 }
 
 #[utoipa::path(delete, tag = "Indexes", path = "/{index}")]
@@ -225,20 +274,34 @@ pub(crate) fn elastic_cat_indices_filter(
 
 #[utoipa::path(get, tag = "Search", path = "/{index}/_search")]
 pub(crate) fn elastic_index_search_filter(
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (Vec<String>, SearchQueryParams, SearchBody), Error = Rejection> + Clone
 {
     warp::path!("_elastic" / String / "_search")
         .and_then(extract_index_id_patterns)
         .and(warp::get().or(warp::post()).unify())
         .and(serde_qs::warp::query(serde_qs::Config::default()))
-        .and(json_or_empty())
+        .and(json_or_empty(content_length_limit))
+}
+
+#[utoipa::path(post, tag = "Indexes", path = "/{index}/_update_by_query")]
+pub(crate) fn elastic_update_by_query_filter(
+    content_length_limit: ByteSize,
+) -> impl Filter<Extract = (Vec<String>, UpdateByQueryBody), Error = Rejection> + Clone {
+    warp::path!("_elastic" / String / "_update_by_query")
+        .and_then(extract_index_id_patterns)
+        .and(warp::post())
+        .and(json_or_empty(content_length_limit))
 }
 
 #[utoipa::path(post, tag = "Search", path = "/_msearch")]
 pub(crate) fn elastic_multi_search_filter(
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (Bytes, MultiSearchQueryParams), Error = Rejection> + Clone {
     warp::path!("_elastic" / "_msearch")
-        .and(warp::body::content_length_limit(BODY_LENGTH_LIMIT.as_u64()))
+        .and(warp::body::content_length_limit(
+            content_length_limit.as_u64(),
+        ))
         .and(warp::body::bytes())
         .and(warp::post())
         .and(serde_qs::warp::query(serde_qs::Config::default()))
@@ -256,12 +319,15 @@ fn merge_scroll_body_params(
 
 #[utoipa::path(post, tag = "Search", path = "/_search/scroll")]
 pub(crate) fn elastic_scroll_filter(
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (ScrollQueryParams,), Error = Rejection> + Clone {
     warp::path!("_elastic" / "_search" / "scroll")
-        .and(warp::body::content_length_limit(BODY_LENGTH_LIMIT.as_u64()))
+        .and(warp::body::content_length_limit(
+            content_length_limit.as_u64(),
+        ))
         .and(warp::get().or(warp::post()).unify())
         .and(serde_qs::warp::query(serde_qs::Config::default()))
-        .and(json_or_empty())
+        .and(json_or_empty(content_length_limit))
         .map(
             |scroll_query_params: ScrollQueryParams, scroll_body: ScrollQueryParams| {
                 merge_scroll_body_params(scroll_query_params, scroll_body)
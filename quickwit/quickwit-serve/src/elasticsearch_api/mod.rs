@@ -16,6 +16,7 @@ mod bulk;
 mod bulk_v2;
 mod filter;
 mod model;
+mod query_limits;
 mod rest_handler;
 
 use std::sync::Arc;
@@ -29,19 +30,24 @@ use quickwit_index_management::IndexService;
 use quickwit_ingest::IngestServiceClient;
 use quickwit_proto::ingest::router::IngestRouterServiceClient;
 use quickwit_proto::metastore::MetastoreServiceClient;
-use quickwit_search::SearchService;
+use quickwit_search::{RunningSearches, SearchService};
 use rest_handler::es_compat_cluster_health_handler;
+pub(crate) use rest_handler::filter_source;
 pub use rest_handler::{
     es_compat_cat_indices_handler, es_compat_cluster_info_handler, es_compat_delete_index_handler,
     es_compat_index_cat_indices_handler, es_compat_index_count_handler,
     es_compat_index_field_capabilities_handler, es_compat_index_multi_search_handler,
     es_compat_index_search_handler, es_compat_index_stats_handler, es_compat_resolve_index_handler,
     es_compat_scroll_handler, es_compat_search_handler, es_compat_stats_handler,
+    es_compat_update_by_query_handler,
 };
 use serde::{Deserialize, Serialize};
+use warp::path::Peek;
 use warp::{Filter, Rejection};
 
+use crate::authentication::{require_admin, Authenticator};
 use crate::elasticsearch_api::model::ElasticsearchError;
+use crate::ingest_api::WriteAliasResolver;
 use crate::rest::recover_fn;
 use crate::rest_api_response::RestApiResponse;
 use crate::{BodyFormat, BuildInfo};
@@ -55,51 +61,105 @@ pub fn elastic_api_handlers(
     cluster: Cluster,
     node_config: Arc<NodeConfig>,
     search_service: Arc<dyn SearchService>,
+    running_searches: Arc<RunningSearches>,
     ingest_service: IngestServiceClient,
     ingest_router: IngestRouterServiceClient,
+    write_alias_resolver: WriteAliasResolver,
     metastore: MetastoreServiceClient,
     index_service: IndexService,
+    authenticator: Arc<Authenticator>,
     enable_ingest_v1: bool,
     enable_ingest_v2: bool,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     let ingest_content_length_limit = node_config.ingest_api_config.content_length_limit;
+    let ingest_streaming_content_length_limit =
+        node_config.ingest_api_config.streaming_content_length_limit;
+    let search_content_length_limit = node_config.rest_config.content_length_limits.search;
     es_compat_cluster_info_handler(node_config, BuildInfo::get())
         .or(es_compat_search_handler(search_service.clone()))
-        .or(es_compat_bulk_handler(
-            ingest_service.clone(),
-            ingest_router.clone(),
-            ingest_content_length_limit,
-            enable_ingest_v1,
-            enable_ingest_v2,
+        .or(es_compat_index_search_handler(
+            search_service.clone(),
+            running_searches,
+            authenticator.clone(),
+            search_content_length_limit,
         ))
-        .boxed()
-        .or(es_compat_index_bulk_handler(
-            ingest_service,
-            ingest_router,
-            ingest_content_length_limit,
-            enable_ingest_v1,
-            enable_ingest_v2,
+        .or(es_compat_index_count_handler(
+            search_service.clone(),
+            authenticator.clone(),
+            search_content_length_limit,
+        ))
+        .or(es_compat_scroll_handler(
+            search_service.clone(),
+            search_content_length_limit,
+        ))
+        .or(es_compat_index_multi_search_handler(
+            search_service.clone(),
+            authenticator.clone(),
+            search_content_length_limit,
         ))
-        .or(es_compat_index_search_handler(search_service.clone()))
-        .or(es_compat_index_count_handler(search_service.clone()))
-        .or(es_compat_scroll_handler(search_service.clone()))
-        .or(es_compat_index_multi_search_handler(search_service.clone()))
         .or(es_compat_index_field_capabilities_handler(
             search_service.clone(),
+            authenticator.clone(),
+            search_content_length_limit,
+        ))
+        .boxed()
+        .or(require_elastic_admin(authenticator.clone()).and(
+            es_compat_bulk_handler(
+                ingest_service.clone(),
+                ingest_router.clone(),
+                write_alias_resolver.clone(),
+                ingest_content_length_limit,
+                ingest_streaming_content_length_limit,
+                enable_ingest_v1,
+                enable_ingest_v2,
+            )
+            .or(es_compat_index_bulk_handler(
+                ingest_service,
+                ingest_router,
+                write_alias_resolver,
+                ingest_content_length_limit,
+                ingest_streaming_content_length_limit,
+                enable_ingest_v1,
+                enable_ingest_v2,
+            ))
+            .or(es_compat_index_stats_handler(metastore.clone()))
+            .or(es_compat_delete_index_handler(index_service))
+            .or(es_compat_stats_handler(metastore.clone()))
+            .or(es_compat_index_cat_indices_handler(metastore.clone()))
+            .or(es_compat_cat_indices_handler(metastore.clone()))
+            .or(es_compat_resolve_index_handler(metastore.clone())),
         ))
         .boxed()
-        .or(es_compat_index_stats_handler(metastore.clone()))
-        .or(es_compat_delete_index_handler(index_service))
-        .or(es_compat_stats_handler(metastore.clone()))
         .or(es_compat_cluster_health_handler(cluster))
-        .or(es_compat_index_cat_indices_handler(metastore.clone()))
-        .or(es_compat_cat_indices_handler(metastore.clone()))
-        .or(es_compat_resolve_index_handler(metastore.clone()))
+        .or(es_compat_update_by_query_handler(
+            search_service,
+            metastore,
+            authenticator,
+            search_content_length_limit,
+        ))
         .recover(recover_fn)
         .boxed()
     // Register newly created handlers here.
 }
 
+/// Rejects the callers whose role is not an admin role on the Elasticsearch-compatible endpoints
+/// that ingest documents or manage and describe the indexes. The role is only checked for the
+/// requests under `_elastic`.
+fn require_elastic_admin(
+    authenticator: Arc<Authenticator>,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::path::peek()
+        .and_then(|peek: Peek| async move {
+            if peek.segments().next() == Some("_elastic") {
+                Ok(())
+            } else {
+                Err(warp::reject::not_found())
+            }
+        })
+        .untuple_one()
+        .and(require_admin(authenticator))
+}
+
 /// Helper type needed by the Elasticsearch endpoints.
 /// Control how the total number of hits should be tracked.
 ///
@@ -145,14 +205,20 @@ mod tests {
     use std::sync::Arc;
 
     use assert_json_diff::assert_json_include;
+    use bytesize::ByteSize;
     use mockall::predicate;
     use quickwit_cluster::{create_cluster_for_test, ChannelTransport, Cluster};
     use quickwit_config::NodeConfig;
     use quickwit_index_management::IndexService;
     use quickwit_ingest::{IngestApiService, IngestServiceClient};
-    use quickwit_metastore::metastore_for_test;
+    use quickwit_metastore::{
+        metastore_for_test, AddTagOverlayRequestExt, IndexMetadata, ListIndexesMetadataResponseExt,
+    };
     use quickwit_proto::ingest::router::IngestRouterServiceClient;
-    use quickwit_proto::metastore::MetastoreServiceClient;
+    use quickwit_proto::metastore::{
+        EmptyResponse, ListIndexesMetadataResponse, MetastoreServiceClient, MockMetastoreService,
+    };
+    use quickwit_proto::search::SearchResponse;
     use quickwit_search::MockSearchService;
     use quickwit_storage::StorageResolver;
     use serde_json::Value as JsonValue;
@@ -160,8 +226,12 @@ mod tests {
 
     use super::elastic_api_handlers;
     use super::model::ElasticsearchError;
-    use crate::elasticsearch_api::rest_handler::es_compat_cluster_info_handler;
+    use crate::elasticsearch_api::rest_handler::{
+        es_compat_cluster_info_handler, es_compat_update_by_query_handler,
+    };
+    use crate::ingest_api::WriteAliasResolver;
     use crate::rest::recover_fn;
+    use crate::search_api::SEARCH_REQUEST_ID_HEADER;
     use crate::BuildInfo;
 
     fn ingest_service_client() -> IngestServiceClient {
@@ -201,10 +271,13 @@ mod tests {
             mock_cluster().await,
             config,
             Arc::new(mock_search_service),
+            Arc::default(),
             ingest_service_client(),
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -237,6 +310,45 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_search_api_request_id() {
+        let config = Arc::new(NodeConfig::for_test());
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .withf(|search_request| search_request.search_request_id.is_some())
+            .returning(|_| Ok(Default::default()));
+        let ingest_router = IngestRouterServiceClient::mocked();
+        let index_service =
+            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let es_search_api_handler = super::elastic_api_handlers(
+            mock_cluster().await,
+            config,
+            Arc::new(mock_search_service),
+            Arc::default(),
+            ingest_service_client(),
+            ingest_router,
+            WriteAliasResolver::default(),
+            MetastoreServiceClient::mocked(),
+            index_service,
+            Arc::default(),
+            true,
+            false,
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/index-1/_search")
+            .method("POST")
+            .header(SEARCH_REQUEST_ID_HEADER, "test-es-search-api-request-id")
+            .body(r#"{"query":{"query_string":{"query":"test"}}}"#)
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers().get(SEARCH_REQUEST_ID_HEADER).unwrap(),
+            "test-es-search-api-request-id"
+        );
+    }
+
     #[tokio::test]
     async fn test_msearch_api_return_one_500_and_one_200_responses() {
         let config = Arc::new(NodeConfig::for_test());
@@ -263,10 +375,13 @@ mod tests {
             mock_cluster().await,
             config,
             Arc::new(mock_search_service),
+            Arc::default(),
             ingest_service_client(),
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -313,10 +428,13 @@ mod tests {
             mock_cluster().await,
             config,
             Arc::new(mock_search_service),
+            Arc::default(),
             ingest_service_client(),
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -351,10 +469,13 @@ mod tests {
             mock_cluster().await,
             config,
             Arc::new(mock_search_service),
+            Arc::default(),
             ingest_service_client(),
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -389,10 +510,13 @@ mod tests {
             mock_cluster().await,
             config,
             Arc::new(mock_search_service),
+            Arc::default(),
             ingest_service_client(),
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -426,10 +550,13 @@ mod tests {
             mock_cluster().await,
             config,
             Arc::new(mock_search_service),
+            Arc::default(),
             ingest_service_client(),
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -475,10 +602,13 @@ mod tests {
             mock_cluster().await,
             config,
             Arc::new(mock_search_service),
+            Arc::default(),
             ingest_service_client(),
             ingest_router,
+            WriteAliasResolver::default(),
             MetastoreServiceClient::mocked(),
             index_service,
+            Arc::default(),
             true,
             false,
         );
@@ -533,4 +663,80 @@ mod tests {
             .await;
         assert_eq!(resp.status(), 200);
     }
+
+    #[tokio::test]
+    async fn test_es_compat_update_by_query_handler() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_list_indexes_metadata()
+            .returning(|_| {
+                let index_metadata =
+                    IndexMetadata::for_test("test-index", "ram:///indexes/test-index");
+                Ok(ListIndexesMetadataResponse::for_test(vec![index_metadata]))
+            });
+        mock_metastore
+            .expect_add_tag_overlay()
+            .return_once(|add_tag_overlay_request| {
+                let tag_overlays = add_tag_overlay_request.deserialize_tag_overlays().unwrap();
+                assert_eq!(tag_overlays.len(), 1);
+                assert_eq!(tag_overlays[0].field_name, "owner");
+                assert_eq!(tag_overlays[0].value_opt, Some(JsonValue::from("alice")));
+                Ok(EmptyResponse {})
+            });
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .return_once(|search_request| {
+                assert_eq!(search_request.max_hits, 0);
+                Ok(SearchResponse {
+                    num_hits: 3,
+                    ..Default::default()
+                })
+            });
+        let handler = es_compat_update_by_query_handler(
+            Arc::new(mock_search_service),
+            MetastoreServiceClient::from_mock(mock_metastore),
+            Arc::default(),
+            ByteSize::mib(1),
+        )
+        .recover(recover_fn);
+
+        let resp = warp::test::request()
+            .path("/_elastic/test-index/_update_by_query")
+            .method("POST")
+            .json(&serde_json::json!({
+                "query": {"term": {"body": "error"}},
+                "script": {
+                    "source": "ctx._source.owner = params.owner",
+                    "params": {"owner": "alice"}
+                }
+            }))
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let expected_response_json = serde_json::json!({
+            "total": 3,
+            "updated": 3,
+            "deleted": 0,
+            "failures": [],
+        });
+        assert_json_include!(actual: resp_json, expected: expected_response_json);
+
+        let resp = warp::test::request()
+            .path("/_elastic/test-index/_update_by_query")
+            .method("POST")
+            .json(&serde_json::json!({
+                "script": {"source": "ctx._source.timestamp = 'yesterday'"}
+            }))
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+        let es_error: ElasticsearchError = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            es_error.error.reason.unwrap(),
+            "field `timestamp` cannot be updated: only text, bool, and numeric fields are \
+             supported"
+        );
+    }
 }
@@ -149,6 +149,9 @@ pub enum ElasticException {
     #[serde(rename = "index_not_found_exception")]
     IndexNotFound,
     // This is an exception proper to Quickwit.
+    #[serde(rename = "ingestion_frozen_exception")]
+    IngestionFrozen,
+    // This is an exception proper to Quickwit.
     #[serde(rename = "rate_limited_exception")]
     RateLimited,
     // This is an exception proper to Quickwit.
@@ -167,6 +170,7 @@ impl ElasticException {
             Self::RateLimited => "rate_limited_exception",
             Self::IllegalArgument => "illegal_argument_exception",
             Self::IndexNotFound => "index_not_found_exception",
+            Self::IngestionFrozen => "ingestion_frozen_exception",
             Self::SourceNotFound => "source_not_found_exception",
             Self::Timeout => "timeout_exception",
         }
@@ -23,6 +23,7 @@ mod search_body;
 mod search_query_params;
 mod search_response;
 mod stats;
+mod update_by_query;
 
 pub use bulk_body::BulkAction;
 pub use bulk_query_params::ElasticBulkOptions;
@@ -40,11 +41,14 @@ pub use multi_search::{
 };
 use quickwit_proto::search::{SortDatetimeFormat, SortOrder};
 pub use scroll::ScrollQueryParams;
-pub use search_body::SearchBody;
+pub use search_body::{SearchBody, SourceParam};
 pub use search_query_params::{DeleteQueryParams, SearchQueryParams, SearchQueryParamsCount};
 pub use search_response::ElasticsearchResponse;
 use serde::{Deserialize, Serialize};
 pub use stats::{ElasticsearchStatsResponse, StatsResponseEntry};
+pub use update_by_query::{
+    ElasticsearchUpdateByQueryResponse, TagUpdate, UpdateByQueryBody, UpdateByQueryScript,
+};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SortField {
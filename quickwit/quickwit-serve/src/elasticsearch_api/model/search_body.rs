@@ -81,11 +81,11 @@ pub struct SearchBody {
     pub stored_fields: Option<BTreeSet<String>>,
     #[serde(default)]
     pub search_after: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub _source: Option<SourceParam>,
 
     // Ignored values, only here for compatibility with OpenSearch Dashboards.
     #[serde(default)]
-    pub _source: serde::de::IgnoredAny,
-    #[serde(default)]
     pub docvalue_fields: serde::de::IgnoredAny,
     #[serde(default)]
     pub script_fields: serde::de::IgnoredAny,
@@ -95,6 +95,30 @@ pub struct SearchBody {
     pub version: serde::de::IgnoredAny,
 }
 
+/// The `_source` parameter of the search body, which filters the fields of the returned documents.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum SourceParam {
+    /// `false` removes all the fields of the documents.
+    Enabled(bool),
+    /// Single field or pattern to include.
+    Include(String),
+    /// Fields or patterns to include.
+    Includes(Vec<String>),
+    Filter(SourceFilterParam),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourceFilterParam {
+    #[serde(default)]
+    #[serde(alias = "include")]
+    pub includes: Vec<String>,
+    #[serde(default)]
+    #[serde(alias = "exclude")]
+    pub excludes: Vec<String>,
+}
+
 struct FieldSortVecVisitor;
 
 #[derive(Deserialize)]
@@ -212,6 +236,37 @@ mod tests {
         assert_eq!(sort_fields[4].date_format, None);
     }
 
+    #[test]
+    fn test_source_param() {
+        let search_body: SearchBody = serde_json::from_str(r#"{"_source": false}"#).unwrap();
+        assert_eq!(search_body._source, Some(SourceParam::Enabled(false)));
+
+        let search_body: SearchBody = serde_json::from_str(r#"{"_source": "app.*"}"#).unwrap();
+        assert_eq!(
+            search_body._source,
+            Some(SourceParam::Include("app.*".to_string()))
+        );
+        let search_body: SearchBody =
+            serde_json::from_str(r#"{"_source": ["app.id", "user.*"]}"#).unwrap();
+        assert_eq!(
+            search_body._source,
+            Some(SourceParam::Includes(vec![
+                "app.id".to_string(),
+                "user.*".to_string()
+            ]))
+        );
+        let search_body: SearchBody =
+            serde_json::from_str(r#"{"_source": {"include": ["app.*"], "excludes": ["app.raw"]}}"#)
+                .unwrap();
+        assert_eq!(
+            search_body._source,
+            Some(SourceParam::Filter(SourceFilterParam {
+                includes: vec!["app.*".to_string()],
+                excludes: vec!["app.raw".to_string()],
+            }))
+        );
+    }
+
     #[test]
     fn test_sort_field_obj() {
         let json = r#"
@@ -0,0 +1,282 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use quickwit_query::ElasticQueryDsl;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+/// Body of an `_update_by_query` request.
+///
+/// Quickwit does not rewrite the splits: the script can only set or unset tag fields, and the
+/// updates are recorded as tag overlays in the index metadata.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateByQueryBody {
+    #[serde(default)]
+    pub query: Option<ElasticQueryDsl>,
+    #[serde(default)]
+    pub script: Option<UpdateByQueryScript>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateByQueryScript {
+    pub source: String,
+    #[serde(default)]
+    pub lang: Option<String>,
+    #[serde(default)]
+    pub params: JsonMap<String, JsonValue>,
+}
+
+/// Assignment of a tag field extracted from an `_update_by_query` script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagUpdate {
+    pub field_name: String,
+    /// `None` unsets the field.
+    pub value_opt: Option<JsonValue>,
+}
+
+impl UpdateByQueryScript {
+    /// Parses the script into tag updates.
+    ///
+    /// Only a small subset of Painless is supported: a sequence of statements of the form
+    /// `ctx._source.<field> = <literal or params.<name>>` or `ctx._source.remove('<field>')`.
+    pub fn tag_updates(&self) -> Result<Vec<TagUpdate>, String> {
+        if let Some(lang) = &self.lang {
+            if lang != "painless" {
+                return Err(format!("unsupported script language `{lang}`"));
+            }
+        }
+        let mut tag_updates = Vec::new();
+
+        for statement in self.source.split(';') {
+            let statement = statement.trim();
+
+            if statement.is_empty() {
+                continue;
+            }
+            let tag_update = self.parse_statement(statement)?;
+            tag_updates.push(tag_update);
+        }
+        if tag_updates.is_empty() {
+            return Err("script must update at least one field".to_string());
+        }
+        Ok(tag_updates)
+    }
+
+    fn parse_statement(&self, statement: &str) -> Result<TagUpdate, String> {
+        let unsupported_statement_error = || format!("unsupported script statement `{statement}`");
+
+        let Some(statement_suffix) = statement.strip_prefix("ctx._source") else {
+            return Err(unsupported_statement_error());
+        };
+        if let Some(remove_args) = statement_suffix
+            .strip_prefix(".remove(")
+            .and_then(|remove_args| remove_args.strip_suffix(')'))
+        {
+            let Some(field_name) = parse_string_literal(remove_args.trim()) else {
+                return Err(unsupported_statement_error());
+            };
+            let tag_update = TagUpdate {
+                field_name,
+                value_opt: None,
+            };
+            return Ok(tag_update);
+        }
+        let Some((target, value)) = statement_suffix.split_once('=') else {
+            return Err(unsupported_statement_error());
+        };
+        let target = target.trim();
+        let field_name = if let Some(field_name) = target.strip_prefix('.') {
+            if !field_name
+                .chars()
+                .all(|ch| ch.is_alphanumeric() || matches!(ch, '_' | '-' | '.' | '@'))
+            {
+                return Err(unsupported_statement_error());
+            }
+            field_name.to_string()
+        } else if let Some(field_name) = target
+            .strip_prefix('[')
+            .and_then(|field_name| field_name.strip_suffix(']'))
+            .and_then(|field_name| parse_string_literal(field_name.trim()))
+        {
+            field_name
+        } else {
+            return Err(unsupported_statement_error());
+        };
+        if field_name.is_empty() {
+            return Err(unsupported_statement_error());
+        }
+        let value = value.trim();
+
+        let value_opt = if let Some(param_name) = value.strip_prefix("params.") {
+            let Some(param_value) = self.params.get(param_name) else {
+                return Err(format!("script parameter `{param_name}` is not defined"));
+            };
+            Some(param_value.clone())
+        } else if let Some(value_str) = parse_string_literal(value) {
+            Some(JsonValue::String(value_str))
+        } else {
+            serde_json::from_str(value).map_err(|_| unsupported_statement_error())?
+        };
+        let value_opt = value_opt.filter(|value| !value.is_null());
+
+        if let Some(value) = &value_opt {
+            if value.is_array() || value.is_object() {
+                return Err(format!(
+                    "field `{field_name}` can only be assigned a string, number, or boolean"
+                ));
+            }
+        }
+        let tag_update = TagUpdate {
+            field_name,
+            value_opt,
+        };
+        Ok(tag_update)
+    }
+}
+
+fn parse_string_literal(literal: &str) -> Option<String> {
+    let single_quoted_opt = literal
+        .strip_prefix('\'')
+        .and_then(|literal| literal.strip_suffix('\''));
+    if let Some(single_quoted) = single_quoted_opt {
+        return Some(single_quoted.to_string());
+    }
+    if literal.starts_with('"') {
+        return serde_json::from_str(literal).ok();
+    }
+    None
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ElasticsearchUpdateByQueryResponse {
+    pub took: u64,
+    pub timed_out: bool,
+    pub total: u64,
+    pub updated: u64,
+    pub deleted: u64,
+    pub batches: u64,
+    pub version_conflicts: u64,
+    pub noops: u64,
+    pub failures: Vec<JsonValue>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn script(source: &str, params: JsonValue) -> UpdateByQueryScript {
+        let JsonValue::Object(params) = params else {
+            panic!("params must be an object");
+        };
+        UpdateByQueryScript {
+            source: source.to_string(),
+            lang: None,
+            params,
+        }
+    }
+
+    #[test]
+    fn test_update_by_query_script_tag_updates() {
+        let tag_updates = script(
+            "ctx._source.acknowledged = true; ctx._source['owner'] = 'alice'; \
+             ctx._source.priority = params.priority; ctx._source.remove(\"assignee\")",
+            json!({"priority": 3}),
+        )
+        .tag_updates()
+        .unwrap();
+        assert_eq!(
+            tag_updates,
+            [
+                TagUpdate {
+                    field_name: "acknowledged".to_string(),
+                    value_opt: Some(json!(true)),
+                },
+                TagUpdate {
+                    field_name: "owner".to_string(),
+                    value_opt: Some(json!("alice")),
+                },
+                TagUpdate {
+                    field_name: "priority".to_string(),
+                    value_opt: Some(json!(3)),
+                },
+                TagUpdate {
+                    field_name: "assignee".to_string(),
+                    value_opt: None,
+                },
+            ]
+        );
+        let tag_updates = script("ctx._source.acknowledged = null", json!({}))
+            .tag_updates()
+            .unwrap();
+        assert_eq!(tag_updates[0].value_opt, None);
+    }
+
+    #[test]
+    fn test_update_by_query_script_errors() {
+        let error = script("", json!({})).tag_updates().unwrap_err();
+        assert_eq!(error, "script must update at least one field");
+
+        let error = script("ctx._source.count += 1", json!({}))
+            .tag_updates()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "unsupported script statement `ctx._source.count += 1`"
+        );
+
+        let error = script("ctx._source.owner = params.owner", json!({}))
+            .tag_updates()
+            .unwrap_err();
+        assert_eq!(error, "script parameter `owner` is not defined");
+
+        let error = script("ctx._source.tags = ['a']", json!({}))
+            .tag_updates()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "unsupported script statement `ctx._source.tags = ['a']`"
+        );
+
+        let error = script("ctx._source.tags = params.tags", json!({"tags": ["a"]}))
+            .tag_updates()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "field `tags` can only be assigned a string, number, or boolean"
+        );
+
+        let mut expression_script = script("1 + 1", json!({}));
+        expression_script.lang = Some("expression".to_string());
+        let error = expression_script.tag_updates().unwrap_err();
+        assert_eq!(error, "unsupported script language `expression`");
+    }
+
+    #[test]
+    fn test_update_by_query_body_deser() {
+        let body: UpdateByQueryBody = serde_json::from_value(json!({
+            "query": {"term": {"host": "web-1"}},
+            "script": {"source": "ctx._source.acknowledged = true", "lang": "painless"}
+        }))
+        .unwrap();
+        assert!(body.query.is_some());
+        assert_eq!(
+            body.script.unwrap().source,
+            "ctx._source.acknowledged = true"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bounds on the Elasticsearch DSL features that are cheap to express but expensive to execute.
+//! Requests exceeding them are rejected with a 400 error instead of exhausting the searchers.
+
+use quickwit_query::query_ast::{BoolQuery, QueryAst, QueryAstVisitor};
+use quickwit_search::SearchError;
+use serde_json::{Map, Value};
+
+/// Maximum nesting depth of `bool` queries, mirroring Elasticsearch's
+/// `indices.query.bool.max_nested_depth` default.
+const MAX_BOOL_QUERY_DEPTH: usize = 30;
+
+/// Maximum `size` and `shard_size` of an aggregation, aligned with tantivy's default bucket limit.
+const MAX_AGGREGATION_SIZE: u64 = 65_000;
+
+/// Parameters of an aggregation that control how many buckets or hits it returns.
+const AGGREGATION_SIZE_PARAMS: [&str; 2] = ["size", "shard_size"];
+
+pub(crate) fn validate_query_limits(query_ast: &QueryAst) -> Result<(), SearchError> {
+    BoolQueryDepthValidator::default().visit(query_ast)
+}
+
+pub(crate) fn validate_aggregation_limits(aggs: &Map<String, Value>) -> Result<(), SearchError> {
+    for (aggregation_name, aggregation) in aggs {
+        let Some(aggregation) = aggregation.as_object() else {
+            continue;
+        };
+        for (aggregation_type, aggregation_params) in aggregation {
+            let Some(aggregation_params) = aggregation_params.as_object() else {
+                continue;
+            };
+            if aggregation_type == "aggs" || aggregation_type == "aggregations" {
+                validate_aggregation_limits(aggregation_params)?;
+                continue;
+            }
+            for size_param in AGGREGATION_SIZE_PARAMS {
+                let Some(size) = aggregation_params.get(size_param).and_then(Value::as_u64) else {
+                    continue;
+                };
+                if size > MAX_AGGREGATION_SIZE {
+                    return Err(SearchError::InvalidAggregationRequest(format!(
+                        "`{size_param}` of aggregation `{aggregation_name}` is {size}, which \
+                         exceeds the maximum of {MAX_AGGREGATION_SIZE}"
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct BoolQueryDepthValidator {
+    depth: usize,
+}
+
+impl<'a> QueryAstVisitor<'a> for BoolQueryDepthValidator {
+    type Err = SearchError;
+
+    fn visit_bool(&mut self, bool_query: &'a BoolQuery) -> Result<(), SearchError> {
+        self.depth += 1;
+
+        if self.depth > MAX_BOOL_QUERY_DEPTH {
+            return Err(SearchError::InvalidQuery(format!(
+                "`bool` queries are nested more than {MAX_BOOL_QUERY_DEPTH} levels deep"
+            )));
+        }
+        for ast in bool_query
+            .must
+            .iter()
+            .chain(bool_query.should.iter())
+            .chain(bool_query.must_not.iter())
+            .chain(bool_query.filter.iter())
+        {
+            self.visit(ast)?;
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn nested_bool_query(depth: usize) -> QueryAst {
+        let mut query_ast = QueryAst::MatchAll;
+
+        for _ in 0..depth {
+            query_ast = QueryAst::Bool(BoolQuery {
+                must: vec![query_ast],
+                ..Default::default()
+            });
+        }
+        query_ast
+    }
+
+    #[test]
+    fn test_validate_query_limits() {
+        validate_query_limits(&QueryAst::MatchAll).unwrap();
+        validate_query_limits(&nested_bool_query(MAX_BOOL_QUERY_DEPTH)).unwrap();
+
+        let error =
+            validate_query_limits(&nested_bool_query(MAX_BOOL_QUERY_DEPTH + 1)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`bool` queries are nested more than 30 levels deep"
+        );
+    }
+
+    #[test]
+    fn test_validate_aggregation_limits() {
+        let aggs = json!({
+            "hosts": {
+                "terms": { "field": "host", "size": 100 },
+                "aggs": {
+                    "services": { "terms": { "field": "service", "shard_size": 1000 } }
+                }
+            }
+        });
+        validate_aggregation_limits(aggs.as_object().unwrap()).unwrap();
+
+        let aggs = json!({
+            "hosts": {
+                "terms": { "field": "host", "size": 100 },
+                "aggs": {
+                    "services": { "terms": { "field": "service", "size": 1_000_000 } }
+                }
+            }
+        });
+        let error = validate_aggregation_limits(aggs.as_object().unwrap()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid aggregation request: `size` of aggregation `services` is 1000000, which \
+             exceeds the maximum of 65000"
+        );
+    }
+}
@@ -13,36 +13,43 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::ops::Bound;
 use std::str::from_utf8;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use bytes::Bytes;
+use bytesize::ByteSize;
 use elasticsearch_dsl::search::Hit as ElasticHit;
 use elasticsearch_dsl::{HitsMetadata, ShardStatistics, Source, TotalHits, TotalHitsRelation};
 use futures_util::StreamExt;
+use hyper::header::HeaderValue;
 use hyper::StatusCode;
 use itertools::Itertools;
 use quickwit_cluster::Cluster;
 use quickwit_common::truncate_str;
-use quickwit_config::{validate_index_id_pattern, NodeConfig};
+use quickwit_config::{validate_federated_index_id_pattern, NodeConfig};
+use quickwit_doc_mapper::{DocMapping, FieldMappingType};
 use quickwit_index_management::IndexService;
 use quickwit_metastore::*;
-use quickwit_proto::metastore::MetastoreServiceClient;
+use quickwit_proto::metastore::{AddTagOverlayRequest, MetastoreService, MetastoreServiceClient};
 use quickwit_proto::search::{
-    CountHits, ListFieldsResponse, PartialHit, ScrollRequest, SearchResponse, SortByValue,
-    SortDatetimeFormat,
+    CountHits, ListFieldsResponse, PartialHit, ScrollRequest, SearchResponse, SecurityContext,
+    SortByValue, SortDatetimeFormat,
 };
 use quickwit_proto::types::IndexUid;
-use quickwit_query::query_ast::{BoolQuery, QueryAst, UserInputQuery};
-use quickwit_query::BooleanOperand;
+use quickwit_query::query_ast::{BoolQuery, QueryAst, RangeQuery, UserInputQuery};
+use quickwit_query::{BooleanOperand, JsonLiteral};
 use quickwit_search::{
-    list_all_splits, resolve_index_patterns, AggregationResults, SearchError, SearchService,
+    authorize_tag_updates, list_all_splits, resolve_index_patterns, AggregationResults,
+    RunningSearches, SearchError, SearchService,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 use warp::reply::with_status;
-use warp::{Filter, Rejection};
+use warp::{Filter, Rejection, Reply};
 
 use super::filter::{
     elastic_cat_indices_filter, elastic_cluster_health_filter, elastic_cluster_info_filter,
@@ -50,21 +57,26 @@ use super::filter::{
     elastic_index_cat_indices_filter, elastic_index_count_filter,
     elastic_index_field_capabilities_filter, elastic_index_search_filter,
     elastic_index_stats_filter, elastic_multi_search_filter, elastic_resolve_index_filter,
-    elastic_scroll_filter, elastic_stats_filter, elasticsearch_filter,
+    elastic_scroll_filter, elastic_stats_filter, elastic_update_by_query_filter,
+    elasticsearch_filter,
 };
 use super::model::{
     build_list_field_request_for_es_api, convert_to_es_field_capabilities_response,
-    CatIndexQueryParams, DeleteQueryParams, ElasticsearchCatIndexResponse, ElasticsearchError,
-    ElasticsearchResolveIndexEntryResponse, ElasticsearchResolveIndexResponse,
-    ElasticsearchResponse, ElasticsearchStatsResponse, FieldCapabilityQueryParams,
-    FieldCapabilityRequestBody, FieldCapabilityResponse, MultiSearchHeader, MultiSearchQueryParams,
-    MultiSearchResponse, MultiSearchSingleResponse, ScrollQueryParams, SearchBody,
-    SearchQueryParams, SearchQueryParamsCount, StatsResponseEntry,
+    CatIndexQueryParams, DeleteQueryParams, ElasticException, ElasticsearchCatIndexResponse,
+    ElasticsearchError, ElasticsearchResolveIndexEntryResponse, ElasticsearchResolveIndexResponse,
+    ElasticsearchResponse, ElasticsearchStatsResponse, ElasticsearchUpdateByQueryResponse,
+    FieldCapabilityQueryParams, FieldCapabilityRequestBody, FieldCapabilityResponse,
+    MultiSearchHeader, MultiSearchQueryParams, MultiSearchResponse, MultiSearchSingleResponse,
+    ScrollQueryParams, SearchBody, SearchQueryParams, SearchQueryParamsCount, SourceParam,
+    StatsResponseEntry, TagUpdate, UpdateByQueryBody,
 };
+use super::query_limits::{validate_aggregation_limits, validate_query_limits};
 use super::{make_elastic_api_response, TrackTotalHits};
+use crate::authentication::{extract_security_context, Authenticator};
 use crate::format::BodyFormat;
 use crate::rest::recover_fn;
 use crate::rest_api_response::{RestApiError, RestApiResponse};
+use crate::search_api::SEARCH_REQUEST_ID_HEADER;
 use crate::{with_arg, BuildInfo};
 
 /// Elastic compatible cluster info handler.
@@ -99,12 +111,11 @@ pub fn es_compat_search_handler(
     elasticsearch_filter()
         .then(|_params: SearchQueryParams| async move {
             // TODO
-            let api_error = RestApiError {
-                status_code: StatusCode::NOT_IMPLEMENTED,
-                message: "_elastic/_search is not supported yet. Please try the index search \
-                          endpoint (_elastic/{index}/search)"
-                    .to_string(),
-            };
+            let api_error = RestApiError::new(
+                StatusCode::NOT_IMPLEMENTED,
+                "_elastic/_search is not supported yet. Please try the index search endpoint \
+                 (_elastic/{index}/search)",
+            );
             RestApiResponse::new::<(), _>(
                 &Err(api_error),
                 StatusCode::NOT_IMPLEMENTED,
@@ -117,10 +128,13 @@ pub fn es_compat_search_handler(
 /// GET or POST _elastic/{index}/_field_caps
 pub fn es_compat_index_field_capabilities_handler(
     search_service: Arc<dyn SearchService>,
+    authenticator: Arc<Authenticator>,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    elastic_index_field_capabilities_filter()
-        .or(elastic_field_capabilities_filter())
+    elastic_index_field_capabilities_filter(content_length_limit)
+        .or(elastic_field_capabilities_filter(content_length_limit))
         .unify()
+        .and(extract_security_context(authenticator))
         .and(with_arg(search_service))
         .then(es_compat_index_field_capabilities)
         .map(|result| make_elastic_api_response(result, BodyFormat::default()))
@@ -138,6 +152,23 @@ pub fn es_compat_delete_index_handler(
         .boxed()
 }
 
+/// POST _elastic/{index}/_update_by_query
+pub fn es_compat_update_by_query_handler(
+    search_service: Arc<dyn SearchService>,
+    metastore_service: MetastoreServiceClient,
+    authenticator: Arc<Authenticator>,
+    content_length_limit: ByteSize,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_update_by_query_filter(content_length_limit)
+        .and(extract_security_context(authenticator))
+        .and(with_arg(search_service))
+        .and(with_arg(metastore_service))
+        .then(es_compat_update_by_query)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+        .boxed()
+}
+
 /// GET _elastic/_stats
 pub fn es_compat_stats_handler(
     metastore_service: MetastoreServiceClient,
@@ -251,11 +282,16 @@ pub fn es_compat_resolve_index_handler(
 /// GET or POST _elastic/{index}/_search
 pub fn es_compat_index_search_handler(
     search_service: Arc<dyn SearchService>,
+    running_searches: Arc<RunningSearches>,
+    authenticator: Arc<Authenticator>,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    elastic_index_search_filter()
+    elastic_index_search_filter(content_length_limit)
+        .and(warp::header::optional::<String>(SEARCH_REQUEST_ID_HEADER))
+        .and(extract_security_context(authenticator))
         .and(with_arg(search_service))
-        .then(es_compat_index_search)
-        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .and(with_arg(running_searches))
+        .then(es_compat_cancellable_index_search)
         .recover(recover_fn)
         .boxed()
 }
@@ -263,8 +299,11 @@ pub fn es_compat_index_search_handler(
 /// GET or POST _elastic/{index}/_count
 pub fn es_compat_index_count_handler(
     search_service: Arc<dyn SearchService>,
+    authenticator: Arc<Authenticator>,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    elastic_index_count_filter()
+    elastic_index_count_filter(content_length_limit)
+        .and(extract_security_context(authenticator))
         .and(with_arg(search_service))
         .then(es_compat_index_count)
         .map(|result| make_elastic_api_response(result, BodyFormat::default()))
@@ -275,8 +314,11 @@ pub fn es_compat_index_count_handler(
 /// POST _elastic/_msearch
 pub fn es_compat_index_multi_search_handler(
     search_service: Arc<dyn SearchService>,
+    authenticator: Arc<Authenticator>,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    elastic_multi_search_filter()
+    elastic_multi_search_filter(content_length_limit)
+        .and(extract_security_context(authenticator))
         .and(with_arg(search_service))
         .then(es_compat_index_multi_search)
         .map(|result: Result<MultiSearchResponse, ElasticsearchError>| {
@@ -293,8 +335,9 @@ pub fn es_compat_index_multi_search_handler(
 /// GET or POST _elastic/_search/scroll
 pub fn es_compat_scroll_handler(
     search_service: Arc<dyn SearchService>,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    elastic_scroll_filter()
+    elastic_scroll_filter(content_length_limit)
         .and(with_arg(search_service))
         .then(es_scroll)
         .map(|result| make_elastic_api_response(result, BodyFormat::default()))
@@ -314,8 +357,8 @@ fn build_request_for_es_api(
         let user_text_query = UserInputQuery {
             user_text: q.to_string(),
             default_fields: None,
-            default_operator,
-            lenient: false,
+            default_operator: Some(default_operator),
+            lenient: Some(false),
         };
         user_text_query.into()
     } else if let Some(query_dsl) = search_body.query {
@@ -333,8 +376,8 @@ fn build_request_for_es_api(
                 let user_text_query = UserInputQuery {
                     user_text: query.to_string(),
                     default_fields: None,
-                    default_operator,
-                    lenient: false,
+                    default_operator: Some(default_operator),
+                    lenient: Some(false),
                 };
                 QueryAst::UserInput(user_text_query)
             })
@@ -348,6 +391,8 @@ fn build_request_for_es_api(
             minimum_should_match: None,
         });
     }
+    validate_query_limits(&query_ast)?;
+    validate_aggregation_limits(&search_body.aggs)?;
 
     let aggregation_request: Option<String> = if search_body.aggs.is_empty() {
         None
@@ -409,6 +454,10 @@ fn build_request_for_es_api(
             scroll_ttl_secs,
             search_after,
             count_hits,
+            security_context: None,
+            profile: false,
+            return_intermediate_aggregation: false,
+            search_request_id: None,
         },
         has_doc_id_field,
     ))
@@ -485,11 +534,13 @@ async fn es_compat_index_count(
     index_id_patterns: Vec<String>,
     search_params: SearchQueryParamsCount,
     search_body: SearchBody,
+    security_context_opt: Option<SecurityContext>,
     search_service: Arc<dyn SearchService>,
 ) -> Result<ElasticsearchCountResponse, ElasticsearchError> {
     let search_params: SearchQueryParams = search_params.into();
-    let (search_request, _append_shard_doc) =
+    let (mut search_request, _append_shard_doc) =
         build_request_for_es_api(index_id_patterns, search_params, search_body)?;
+    search_request.security_context = security_context_opt;
     let search_response: SearchResponse = search_service.root_search(search_request).await?;
     let search_response_rest: ElasticsearchCountResponse = ElasticsearchCountResponse {
         count: search_response.num_hits,
@@ -497,10 +548,55 @@ async fn es_compat_index_count(
     Ok(search_response_rest)
 }
 
+/// Runs an index search that can be listed and cancelled through the running searches API.
+async fn es_compat_cancellable_index_search(
+    index_id_patterns: Vec<String>,
+    search_params: SearchQueryParams,
+    search_body: SearchBody,
+    request_id_opt: Option<String>,
+    security_context_opt: Option<SecurityContext>,
+    search_service: Arc<dyn SearchService>,
+    running_searches: Arc<RunningSearches>,
+) -> warp::reply::Response {
+    let (running_search_guard, abort_registration) = match running_searches.register(
+        request_id_opt,
+        index_id_patterns.clone(),
+        security_context_opt.clone(),
+        search_service.clone(),
+    ) {
+        Ok(registration) => registration,
+        Err(search_error) => {
+            let result: Result<(), ElasticsearchError> = Err(search_error.into());
+            return make_elastic_api_response(result, BodyFormat::default()).into_response();
+        }
+    };
+    let search_future = es_compat_index_search(
+        index_id_patterns,
+        search_params,
+        search_body,
+        Some(running_search_guard.search_request_uid().to_string()),
+        security_context_opt,
+        search_service,
+    );
+    let result = running_search_guard
+        .run_until_cancelled(abort_registration, search_future)
+        .await;
+    let mut response = make_elastic_api_response(result, BodyFormat::default()).into_response();
+
+    if let Ok(header_value) = HeaderValue::from_str(running_search_guard.request_id()) {
+        response
+            .headers_mut()
+            .insert(SEARCH_REQUEST_ID_HEADER, header_value);
+    }
+    response
+}
+
 async fn es_compat_index_search(
     index_id_patterns: Vec<String>,
     search_params: SearchQueryParams,
     search_body: SearchBody,
+    search_request_uid_opt: Option<String>,
+    security_context_opt: Option<SecurityContext>,
     search_service: Arc<dyn SearchService>,
 ) -> Result<ElasticsearchResponse, ElasticsearchError> {
     if search_params.scroll.is_some() && !search_params.allow_partial_search_results() {
@@ -509,12 +605,14 @@ async fn es_compat_index_search(
                 .to_string(),
         )));
     }
-    let _source_excludes = search_params._source_excludes.clone();
-    let _source_includes = search_params._source_includes.clone();
+    let (_source_excludes, _source_includes) =
+        source_filter_from_params(&search_params, &search_body)?;
     let start_instant = Instant::now();
     let allow_partial_search_results = search_params.allow_partial_search_results();
-    let (search_request, append_shard_doc) =
+    let (mut search_request, append_shard_doc) =
         build_request_for_es_api(index_id_patterns, search_params, search_body)?;
+    search_request.search_request_id = search_request_uid_opt;
+    search_request.security_context = security_context_opt;
     let search_response: SearchResponse = search_service.root_search(search_request).await?;
     let elapsed = start_instant.elapsed();
     let mut search_response_rest: ElasticsearchResponse = convert_to_es_search_response(
@@ -553,6 +651,158 @@ async fn es_compat_delete_index(
     Ok(ElasticsearchDeleteResponse { acknowledged: true })
 }
 
+/// Checks that a tag update targets a top-level text, bool, or numeric field declared in the doc
+/// mapping, and that its value matches the type of the field.
+fn validate_tag_update(doc_mapping: &DocMapping, tag_update: &TagUpdate) -> Result<(), String> {
+    let field_name = &tag_update.field_name;
+    let Some(field_mapping) = doc_mapping
+        .field_mappings
+        .iter()
+        .find(|field_mapping| field_mapping.name == *field_name)
+    else {
+        return Err(format!(
+            "field `{field_name}` is not declared at the root of the doc mapping"
+        ));
+    };
+    let Some(value) = &tag_update.value_opt else {
+        return Ok(());
+    };
+    let value_matches_field_type = match &field_mapping.mapping_type {
+        FieldMappingType::Text(..) => value.is_string(),
+        FieldMappingType::Bool(..) => value.is_boolean(),
+        FieldMappingType::I64(..) => value.is_i64(),
+        FieldMappingType::U64(..) => value.is_u64(),
+        FieldMappingType::F64(..) => value.is_number(),
+        _ => {
+            return Err(format!(
+                "field `{field_name}` cannot be updated: only text, bool, and numeric fields are \
+                 supported"
+            ));
+        }
+    };
+    if !value_matches_field_type {
+        return Err(format!(
+            "value `{value}` does not match the type of field `{field_name}`"
+        ));
+    }
+    Ok(())
+}
+
+async fn es_compat_update_by_query(
+    index_id_patterns: Vec<String>,
+    update_by_query_body: UpdateByQueryBody,
+    security_context_opt: Option<SecurityContext>,
+    search_service: Arc<dyn SearchService>,
+    mut metastore: MetastoreServiceClient,
+) -> Result<ElasticsearchUpdateByQueryResponse, ElasticsearchError> {
+    let start_instant = Instant::now();
+    let illegal_argument_error = |reason: String| {
+        ElasticsearchError::new(
+            StatusCode::BAD_REQUEST,
+            reason,
+            Some(ElasticException::IllegalArgument),
+        )
+    };
+    let Some(script) = update_by_query_body.script else {
+        return Err(ElasticsearchError::new(
+            StatusCode::BAD_REQUEST,
+            "Quickwit only supports update by query requests with a script".to_string(),
+            Some(ElasticException::ActionRequestValidation),
+        ));
+    };
+    let tag_updates = script.tag_updates().map_err(illegal_argument_error)?;
+    let query_ast: QueryAst = if let Some(query_dsl) = update_by_query_body.query {
+        query_dsl
+            .try_into()
+            .map_err(|err: anyhow::Error| SearchError::InvalidQuery(err.to_string()))?
+    } else {
+        QueryAst::MatchAll
+    };
+    let indexes_metadata = resolve_index_patterns(&index_id_patterns, &mut metastore).await?;
+
+    // We validate the request against all the indexes before recording any tag overlay.
+    let mut tag_overlays_per_index: Vec<(IndexUid, Vec<TagOverlay>)> =
+        Vec::with_capacity(indexes_metadata.len());
+    let now = OffsetDateTime::now_utc();
+    let create_timestamp = now.unix_timestamp();
+    let field_names: Vec<&str> = tag_updates
+        .iter()
+        .map(|tag_update| tag_update.field_name.as_str())
+        .collect();
+
+    for index_metadata in &indexes_metadata {
+        let index_config = &index_metadata.index_config;
+
+        for tag_update in &tag_updates {
+            validate_tag_update(&index_config.doc_mapping, tag_update)
+                .map_err(illegal_argument_error)?;
+        }
+        let mut query_ast_resolved = authorize_tag_updates(
+            index_metadata,
+            security_context_opt.as_ref(),
+            query_ast.clone(),
+            &field_names,
+        )?
+        .parse_user_query_with_defaults(index_config.search_settings.user_query_defaults())
+        .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
+
+        // The tag overlays must not apply to the documents ingested after the update.
+        if let Some(timestamp_field) = &index_config.doc_mapping.timestamp_field {
+            let now_rfc3339 = now
+                .format(&Rfc3339)
+                .expect("RFC 3339 formatting of the current time should not fail");
+            let before_update_query_ast = RangeQuery {
+                field: timestamp_field.clone(),
+                lower_bound: Bound::Unbounded,
+                upper_bound: Bound::Excluded(JsonLiteral::String(now_rfc3339)),
+            }
+            .into();
+            query_ast_resolved = BoolQuery {
+                must: vec![query_ast_resolved],
+                filter: vec![before_update_query_ast],
+                ..Default::default()
+            }
+            .into();
+        }
+        let tag_overlays = tag_updates
+            .iter()
+            .map(|tag_update| TagOverlay {
+                query_ast: query_ast_resolved.clone(),
+                field_name: tag_update.field_name.clone(),
+                value_opt: tag_update.value_opt.clone(),
+                create_timestamp,
+            })
+            .collect();
+        tag_overlays_per_index.push((index_metadata.index_uid.clone(), tag_overlays));
+    }
+    let count_request = quickwit_proto::search::SearchRequest {
+        index_id_patterns,
+        query_ast: serde_json::to_string(&query_ast).expect("Failed to serialize QueryAst"),
+        max_hits: 0,
+        security_context: security_context_opt,
+        ..Default::default()
+    };
+    let num_hits = search_service.root_search(count_request).await?.num_hits;
+
+    for (index_uid, tag_overlays) in tag_overlays_per_index {
+        let add_tag_overlay_request =
+            AddTagOverlayRequest::try_from_tag_overlays(index_uid, &tag_overlays)
+                .map_err(SearchError::from)?;
+        metastore
+            .add_tag_overlay(add_tag_overlay_request)
+            .await
+            .map_err(SearchError::from)?;
+    }
+    let update_by_query_response = ElasticsearchUpdateByQueryResponse {
+        took: start_instant.elapsed().as_millis() as u64,
+        total: num_hits,
+        updated: num_hits,
+        batches: 1,
+        ..Default::default()
+    };
+    Ok(update_by_query_response)
+}
+
 async fn es_compat_stats(
     metastore: MetastoreServiceClient,
 ) -> Result<ElasticsearchStatsResponse, ElasticsearchError> {
@@ -660,10 +910,12 @@ async fn es_compat_index_field_capabilities(
     index_id_patterns: Vec<String>,
     search_params: FieldCapabilityQueryParams,
     search_body: FieldCapabilityRequestBody,
+    security_context_opt: Option<SecurityContext>,
     search_service: Arc<dyn SearchService>,
 ) -> Result<FieldCapabilityResponse, ElasticsearchError> {
-    let search_request =
+    let mut search_request =
         build_list_field_request_for_es_api(index_id_patterns, search_params, search_body)?;
+    search_request.security_context = security_context_opt;
     let search_response: ListFieldsResponse =
         search_service.root_list_fields(search_request).await?;
     let search_response_rest: FieldCapabilityResponse =
@@ -671,7 +923,9 @@ async fn es_compat_index_field_capabilities(
     Ok(search_response_rest)
 }
 
-fn filter_source(
+/// Removes the fields excluded or not included from a document. The paths are dot-separated and
+/// may contain `*` wildcards.
+pub(crate) fn filter_source(
     value: &mut serde_json::Value,
     _source_excludes: &Option<Vec<String>>,
     _source_includes: &Option<Vec<String>>,
@@ -712,7 +966,22 @@ fn filter_source(
                 }
                 // Check if the path is sub path of any allowed path
                 for allowed_path in include_paths {
-                    if allowed_path.starts_with(path.as_str()) {
+                    let Some(pattern) = source_wildcard_pattern(allowed_path) else {
+                        if allowed_path.starts_with(path.as_str()) {
+                            retain_includes(sub_value, &path, include_paths);
+                            return true;
+                        }
+                        continue;
+                    };
+                    if pattern.matches(&path) {
+                        return true;
+                    }
+                    // The descendants of the node may match the pattern.
+                    let literal_prefix = allowed_path.split('*').next().unwrap_or_default();
+                    if sub_value.is_object()
+                        && (literal_prefix.starts_with(path.as_str())
+                            || path.starts_with(literal_prefix))
+                    {
                         retain_includes(sub_value, &path, include_paths);
                         return true;
                     }
@@ -730,9 +999,91 @@ fn filter_source(
     // Remove fields that are excluded
     if let Some(excludes) = _source_excludes {
         for exclude in excludes {
-            remove_path(value, exclude);
+            if let Some(pattern) = source_wildcard_pattern(exclude) {
+                remove_matching_paths(value, "", &pattern);
+            } else {
+                remove_path(value, exclude);
+            }
+        }
+    }
+}
+
+/// Returns the glob pattern of a `_source` include or exclude pattern if it contains a wildcard.
+/// The wildcard matches any sequence of characters, including dots.
+fn source_wildcard_pattern(source_pattern: &str) -> Option<glob::Pattern> {
+    if !source_pattern.contains('*') {
+        return None;
+    }
+    glob::Pattern::new(source_pattern).ok()
+}
+
+fn remove_matching_paths(
+    value: &mut serde_json::Value,
+    current_path: &str,
+    pattern: &glob::Pattern,
+) {
+    if let Some(map) = value.as_object_mut() {
+        map.retain(|key, sub_value| {
+            let path = if current_path.is_empty() {
+                key.to_string()
+            } else {
+                format!("{current_path}.{key}")
+            };
+            if pattern.matches(&path) {
+                return false;
+            }
+            remove_matching_paths(sub_value, &path, pattern);
+            true
+        });
+    }
+}
+
+/// Resolves the fields to exclude and include from the hits, in that order. The `_source` query
+/// parameters take precedence over the `_source` parameter of the request body.
+fn source_filter_from_params(
+    search_params: &SearchQueryParams,
+    search_body: &SearchBody,
+) -> Result<(Option<Vec<String>>, Option<Vec<String>>), ElasticsearchError> {
+    let (mut source_excludes, mut source_includes) = match &search_body._source {
+        None | Some(SourceParam::Enabled(true)) => (None, None),
+        Some(SourceParam::Enabled(false)) => (None, Some(Vec::new())),
+        Some(SourceParam::Include(include)) => (None, Some(vec![include.clone()])),
+        Some(SourceParam::Includes(includes)) => (None, Some(includes.clone())),
+        Some(SourceParam::Filter(filter)) => (
+            Some(filter.excludes.clone()).filter(|excludes| !excludes.is_empty()),
+            Some(filter.includes.clone()).filter(|includes| !includes.is_empty()),
+        ),
+    };
+    if let Some(source) = &search_params._source {
+        match source.as_slice() {
+            [enabled] if enabled == "true" => {
+                source_excludes = None;
+                source_includes = None;
+            }
+            [enabled] if enabled == "false" => {
+                source_includes = Some(Vec::new());
+            }
+            _ => {
+                source_includes = Some(source.clone());
+            }
+        }
+    }
+    if let Some(excludes) = &search_params._source_excludes {
+        source_excludes = Some(excludes.clone());
+    }
+    if let Some(includes) = &search_params._source_includes {
+        source_includes = Some(includes.clone());
+    }
+    for source_pattern in source_excludes.iter().chain(&source_includes).flatten() {
+        if source_pattern.contains('*') {
+            glob::Pattern::new(source_pattern).map_err(|err| {
+                ElasticsearchError::from(SearchError::InvalidArgument(format!(
+                    "invalid `_source` pattern `{source_pattern}`: {err}"
+                )))
+            })?;
         }
     }
+    Ok((source_excludes, source_includes))
 }
 
 /// "app.id.name" -> [("app", Some("id.name")), ("app.id", Some("name")), ("app.id.name", None)]
@@ -796,7 +1147,7 @@ fn convert_hit(
         source,
         highlight: Default::default(),
         inner_hits: Default::default(),
-        matched_queries: Vec::default(),
+        matched_queries: hit.matched_queries,
         sort,
     }
 }
@@ -804,6 +1155,7 @@ fn convert_hit(
 async fn es_compat_index_multi_search(
     payload: Bytes,
     multi_search_params: MultiSearchQueryParams,
+    security_context_opt: Option<SecurityContext>,
     search_service: Arc<dyn SearchService>,
 ) -> Result<MultiSearchResponse, ElasticsearchError> {
     let mut search_requests = Vec::new();
@@ -825,7 +1177,7 @@ async fn es_compat_index_multi_search(
             )));
         }
         for index in &request_header.index {
-            validate_index_id_pattern(index, true).map_err(|err| {
+            validate_federated_index_id_pattern(index, true).map_err(|err| {
                 SearchError::InvalidArgument(format!(
                     "request header contains an invalid index: {}",
                     err
@@ -857,19 +1209,24 @@ async fn es_compat_index_multi_search(
         if let Some(extra_filters) = &multi_search_params.extra_filters {
             search_query_params.extra_filters = Some(extra_filters.to_vec());
         }
-        let es_request =
+        let (_source_excludes, _source_includes) =
+            source_filter_from_params(&search_query_params, &search_body)?;
+        let (mut search_request, append_shard_doc) =
             build_request_for_es_api(index_ids_patterns, search_query_params, search_body)?;
-        search_requests.push(es_request);
+        search_request.security_context = security_context_opt.clone();
+        search_requests.push((
+            search_request,
+            append_shard_doc,
+            _source_excludes,
+            _source_includes,
+        ));
     }
 
     // TODO: forced to do weird referencing to work around https://github.com/rust-lang/rust/issues/100905
     // otherwise append_shard_doc is captured by ref, and we get lifetime issues
-    let futures = search_requests
-        .into_iter()
-        .map(|(search_request, append_shard_doc)| {
+    let futures = search_requests.into_iter().map(
+        |(search_request, append_shard_doc, _source_excludes, _source_includes)| {
             let search_service = &search_service;
-            let _source_excludes = multi_search_params._source_excludes.clone();
-            let _source_includes = multi_search_params._source_includes.clone();
             async move {
                 let start_instant = Instant::now();
                 let search_response: SearchResponse =
@@ -886,7 +1243,8 @@ async fn es_compat_index_multi_search(
                 search_response_rest.took = elapsed.as_millis() as u32;
                 Ok::<_, ElasticsearchError>(search_response_rest)
             }
-        });
+        },
+    );
     let max_concurrent_searches =
         multi_search_params.max_concurrent_searches.unwrap_or(10) as usize;
     let search_responses = futures::stream::iter(futures)
@@ -1238,6 +1596,66 @@ mod tests {
         assert_eq!(fields, expected);
     }
 
+    #[test]
+    fn test_filter_source_with_wildcards() {
+        let mut fields = json!({
+            "app": { "id": 123, "name": "Blub", "raw_payload": "..." },
+            "user": { "id": 456, "name": "Fred" },
+            "raw_body": "..."
+        });
+        let includes = Some(vec!["app.*".to_string(), "*.name".to_string()]);
+        let excludes = Some(vec!["*raw*".to_string()]);
+        filter_source(&mut fields, &excludes, &includes);
+
+        let expected = json!({
+            "app": { "id": 123, "name": "Blub" },
+            "user": { "name": "Fred" }
+        });
+        assert_eq!(fields, expected);
+    }
+
+    #[test]
+    fn test_source_filter_from_params() {
+        let search_body: SearchBody = serde_json::from_value(json!({
+            "_source": { "includes": ["app.*"], "excludes": ["app.raw_payload"] }
+        }))
+        .unwrap();
+        let (excludes, includes) =
+            source_filter_from_params(&SearchQueryParams::default(), &search_body).unwrap();
+        assert_eq!(excludes, Some(vec!["app.raw_payload".to_string()]));
+        assert_eq!(includes, Some(vec!["app.*".to_string()]));
+
+        // The query parameters take precedence over the request body.
+        let search_params = SearchQueryParams {
+            _source_includes: Some(vec!["user.name".to_string()]),
+            ..Default::default()
+        };
+        let (excludes, includes) = source_filter_from_params(&search_params, &search_body).unwrap();
+        assert_eq!(excludes, Some(vec!["app.raw_payload".to_string()]));
+        assert_eq!(includes, Some(vec!["user.name".to_string()]));
+
+        let search_body: SearchBody = serde_json::from_value(json!({ "_source": false })).unwrap();
+        let (excludes, includes) =
+            source_filter_from_params(&SearchQueryParams::default(), &search_body).unwrap();
+        assert_eq!(excludes, None);
+        assert_eq!(includes, Some(Vec::new()));
+
+        let search_params = SearchQueryParams {
+            _source: Some(vec!["true".to_string()]),
+            ..Default::default()
+        };
+        let (excludes, includes) = source_filter_from_params(&search_params, &search_body).unwrap();
+        assert_eq!(excludes, None);
+        assert_eq!(includes, None);
+
+        let search_params = SearchQueryParams {
+            _source_excludes: Some(vec!["app[.*".to_string()]),
+            ..Default::default()
+        };
+        let error = source_filter_from_params(&search_params, &SearchBody::default()).unwrap_err();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+
     // We test that the behavior of allow partial search results.
     #[test]
     fn test_convert_to_es_search_response_allow_partial() {
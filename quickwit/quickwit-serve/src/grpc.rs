@@ -184,7 +184,8 @@ pub(crate) async fn start_grpc_server(
         file_descriptor_sets.push(quickwit_proto::search::SEARCH_FILE_DESCRIPTOR_SET);
 
         let search_service = services.search_service.clone();
-        let grpc_search_service = GrpcSearchAdapter::from(search_service);
+        let grpc_search_service = GrpcSearchAdapter::from(search_service)
+            .with_payload_compression(grpc_config.compression.payload_compression());
         Some(
             SearchServiceServer::new(grpc_search_service)
                 .max_decoding_message_size(grpc_config.max_message_size.0 as usize)
@@ -15,9 +15,11 @@
 use std::sync::Arc;
 
 use bytes::Bytes;
+use bytesize::ByteSize;
 use quickwit_common::uri::Uri;
 use quickwit_config::{
-    load_index_config_update, validate_index_id_pattern, ConfigFormat, NodeConfig,
+    load_index_config_update, validate_index_id_pattern, ConfigFormat, IndexLabelSelector,
+    IngestionFreeze, NodeConfig,
 };
 use quickwit_index_management::{IndexService, IndexServiceError};
 use quickwit_metastore::{
@@ -26,11 +28,13 @@ use quickwit_metastore::{
     UpdateIndexRequestExt,
 };
 use quickwit_proto::metastore::{
-    IndexMetadataRequest, ListIndexesMetadataRequest, ListSplitsRequest, MetastoreError,
-    MetastoreResult, MetastoreService, MetastoreServiceClient, UpdateIndexRequest,
+    serde_utils, GetIndexUsageRequest, IndexMetadataRequest, ListIndexesMetadataRequest,
+    ListSplitsRequest, MetastoreError, MetastoreResult, MetastoreService, MetastoreServiceClient,
+    UpdateIndexRequest,
 };
 use quickwit_proto::types::IndexId;
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 use tracing::info;
 use warp::{Filter, Rejection};
 
@@ -74,6 +78,12 @@ pub struct ListIndexesQueryParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub index_id_patterns: Option<Vec<String>>,
+    /// Comma-separated list of label selectors, `key` or `key:value`. Only the indexes matching
+    /// all the selectors are returned.
+    #[serde(deserialize_with = "from_simple_list")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub labels: Option<Vec<String>>,
 }
 
 pub fn list_indexes_metadata_handler(
@@ -187,6 +197,103 @@ pub fn describe_index_handler(
         .boxed()
 }
 
+/// Usage counters of an index, accumulated since its creation or over the requested time range.
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+pub struct IndexUsageStats {
+    #[schema(value_type = String)]
+    pub index_id: IndexId,
+    pub num_docs_ingested: u64,
+    pub num_bytes_ingested: u64,
+    pub num_bytes_stored: u64,
+    pub num_queries: u64,
+    pub num_bytes_scanned: u64,
+}
+
+/// Time range of the usage to return. Usage is recorded per UTC day, so the bounds are rounded
+/// down to the start of their day.
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams, utoipa::ToSchema)]
+#[into_params(parameter_in = Query)]
+pub struct IndexUsageQueryParams {
+    /// If set, only return the usage recorded on or after the UTC day of this timestamp (in
+    /// seconds).
+    #[serde(default)]
+    pub start_timestamp: Option<i64>,
+    /// If set, only return the usage recorded before the UTC day of this timestamp (in seconds).
+    #[serde(default)]
+    pub end_timestamp: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    tag = "Indexes",
+    path = "/indexes/{index_id}/usage",
+    responses(
+        (status = 200, description = "Successfully fetched the usage of the index.", body = IndexUsageStats)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to get the usage of."),
+        IndexUsageQueryParams,
+    )
+)]
+
+/// Gets the usage counters of an index.
+pub async fn get_index_usage(
+    index_id: IndexId,
+    query_params: IndexUsageQueryParams,
+    metastore: MetastoreServiceClient,
+) -> MetastoreResult<IndexUsageStats> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.to_string());
+    let index_uid = metastore
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?
+        .index_uid;
+    let get_index_usage_request = GetIndexUsageRequest {
+        index_uid: Some(index_uid.clone()),
+        start_timestamp: query_params.start_timestamp,
+        end_timestamp: query_params.end_timestamp,
+    };
+    let usage = metastore
+        .get_index_usage(get_index_usage_request)
+        .await?
+        .usage
+        .unwrap_or_default();
+
+    let query = ListSplitsQuery::for_index(index_uid).with_split_state(SplitState::Published);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+    let num_bytes_stored: u64 = metastore
+        .list_splits(list_splits_request)
+        .await?
+        .collect_splits()
+        .await?
+        .iter()
+        .map(|split| split.split_metadata.footer_offsets.end)
+        .sum();
+
+    let index_usage_stats = IndexUsageStats {
+        index_id,
+        num_docs_ingested: usage.num_docs_ingested,
+        num_bytes_ingested: usage.num_bytes_ingested,
+        num_bytes_stored,
+        num_queries: usage.num_queries,
+        num_bytes_scanned: usage.num_bytes_scanned,
+    };
+    Ok(index_usage_stats)
+}
+
+pub fn get_index_usage_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("indexes" / String / "usage")
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(with_arg(metastore))
+        .then(get_index_usage)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+        .boxed()
+}
+
 #[utoipa::path(
     get,
     tag = "Indexes",
@@ -205,7 +312,16 @@ pub async fn list_indexes_metadata(
     list_indexes_params: ListIndexesQueryParams,
     metastore: MetastoreServiceClient,
 ) -> MetastoreResult<Vec<IndexMetadata>> {
-    let list_indexes_metata_request =
+    let label_selectors = list_indexes_params.labels.unwrap_or_default();
+
+    for label_selector in &label_selectors {
+        label_selector
+            .parse::<IndexLabelSelector>()
+            .map_err(|error| MetastoreError::InvalidArgument {
+                message: error.to_string(),
+            })?;
+    }
+    let mut list_indexes_metata_request =
         if let Some(index_id_patterns) = list_indexes_params.index_id_patterns {
             for index_id_pattern in &index_id_patterns {
                 validate_index_id_pattern(index_id_pattern, true).map_err(|error| {
@@ -214,10 +330,16 @@ pub async fn list_indexes_metadata(
                     }
                 })?;
             }
-            ListIndexesMetadataRequest { index_id_patterns }
+            ListIndexesMetadataRequest {
+                index_id_patterns,
+                include_trashed: false,
+                label_selectors: Vec::new(),
+            }
         } else {
             ListIndexesMetadataRequest::all()
         };
+    list_indexes_metata_request.label_selectors = label_selectors;
+
     metastore
         .list_indexes_metadata(list_indexes_metata_request)
         .await?
@@ -236,11 +358,17 @@ pub fn create_index_handler(
     index_service: IndexService,
     node_config: Arc<NodeConfig>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    let content_length_limit = node_config
+        .rest_config
+        .content_length_limits
+        .index_management;
     warp::path!("indexes")
         .and(warp::post())
         .and(serde_qs::warp::query(serde_qs::Config::default()))
         .and(extract_config_format())
-        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::content_length_limit(
+            content_length_limit.as_u64(),
+        ))
         .and(warp::filters::body::bytes())
         .and(with_arg(index_service))
         .and(with_arg(node_config))
@@ -286,11 +414,14 @@ pub async fn create_index(
 
 pub fn update_index_handler(
     metastore: MetastoreServiceClient,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     warp::path!("indexes" / String)
         .and(warp::put())
         .and(extract_config_format())
-        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::content_length_limit(
+            content_length_limit.as_u64(),
+        ))
         .and(warp::filters::body::bytes())
         .and(with_arg(metastore))
         .then(update_index)
@@ -340,13 +471,134 @@ pub async fn update_index(
         load_index_config_update(config_format, &index_config_bytes, &current_index_config)
             .map_err(IndexServiceError::InvalidConfig)?;
 
-    let update_request = UpdateIndexRequest::try_from_updates(
+    let mut update_request = UpdateIndexRequest::try_from_updates(
         index_uid,
         &new_index_config.search_settings,
         &new_index_config.retention_policy_opt,
         &new_index_config.indexing_settings,
         &new_index_config.doc_mapping,
     )?;
+    update_request.labels_json = Some(serde_utils::to_json_str(&new_index_config.labels)?);
+
+    let update_resp = metastore.update_index(update_request).await?;
+    Ok(update_resp.deserialize_index_metadata()?)
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FreezeIngestionRequest {
+    /// The reason why the ingestion is frozen, e.g. a link to the incident.
+    #[serde(default)]
+    pub reason: String,
+}
+
+pub fn freeze_ingestion_handler(
+    metastore: MetastoreServiceClient,
+    content_length_limit: ByteSize,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("indexes" / String / "freeze-ingestion")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            content_length_limit.as_u64(),
+        ))
+        .and(warp::filters::body::bytes())
+        .and(with_arg(metastore))
+        .then(freeze_ingestion)
+        .map(log_failure("failed to freeze index ingestion"))
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+        .boxed()
+}
+
+#[utoipa::path(
+    post,
+    tag = "Indexes",
+    path = "/indexes/{index_id}/freeze-ingestion",
+    request_body = FreezeIngestionRequest,
+    responses(
+        (status = 200, description = "Successfully froze the index ingestion.", body = VersionedIndexMetadata)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to freeze."),
+    )
+)]
+/// Freezes the ingestion into an index.
+///
+/// While the ingestion is frozen, the ingest API rejects the documents targeting the index and the
+/// indexing pipelines of its sources are paused. Searches are not affected.
+pub async fn freeze_ingestion(
+    index_id: IndexId,
+    freeze_ingestion_request_bytes: Bytes,
+    metastore: MetastoreServiceClient,
+) -> Result<IndexMetadata, IndexServiceError> {
+    let freeze_ingestion_request: FreezeIngestionRequest =
+        if freeze_ingestion_request_bytes.is_empty() {
+            FreezeIngestionRequest::default()
+        } else {
+            serde_json::from_slice(&freeze_ingestion_request_bytes)
+                .map_err(|error| IndexServiceError::InvalidConfig(error.into()))?
+        };
+    info!(index_id = %index_id, reason = %freeze_ingestion_request.reason, "freeze-ingestion");
+    let ingestion_freeze = IngestionFreeze {
+        reason: freeze_ingestion_request.reason,
+        frozen_at: OffsetDateTime::now_utc().unix_timestamp(),
+    };
+    update_ingestion_freeze(index_id, Some(ingestion_freeze), metastore).await
+}
+
+pub fn unfreeze_ingestion_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("indexes" / String / "unfreeze-ingestion")
+        .and(warp::post())
+        .and(with_arg(metastore))
+        .then(unfreeze_ingestion)
+        .map(log_failure("failed to unfreeze index ingestion"))
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+        .boxed()
+}
+
+#[utoipa::path(
+    post,
+    tag = "Indexes",
+    path = "/indexes/{index_id}/unfreeze-ingestion",
+    responses(
+        (status = 200, description = "Successfully unfroze the index ingestion.", body = VersionedIndexMetadata)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to unfreeze."),
+    )
+)]
+/// Lifts the ingestion freeze of an index.
+pub async fn unfreeze_ingestion(
+    index_id: IndexId,
+    metastore: MetastoreServiceClient,
+) -> Result<IndexMetadata, IndexServiceError> {
+    info!(index_id = %index_id, "unfreeze-ingestion");
+    update_ingestion_freeze(index_id, None, metastore).await
+}
+
+async fn update_ingestion_freeze(
+    index_id: IndexId,
+    ingestion_freeze_opt: Option<IngestionFreeze>,
+    metastore: MetastoreServiceClient,
+) -> Result<IndexMetadata, IndexServiceError> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id);
+    let index_metadata = metastore
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?;
+    let index_config = &index_metadata.index_config;
+
+    let mut update_request = UpdateIndexRequest::try_from_updates(
+        index_metadata.index_uid.clone(),
+        &index_config.search_settings,
+        &index_config.retention_policy_opt,
+        &index_config.indexing_settings,
+        &index_config.doc_mapping,
+    )?;
+    update_request.ingestion_freeze_json = Some(serde_utils::to_json_str(&ingestion_freeze_opt)?);
     let update_resp = metastore.update_index(update_request).await?;
     Ok(update_resp.deserialize_index_metadata()?)
 }
@@ -428,3 +680,36 @@ pub async fn delete_index(
         .delete_index(&index_id, delete_index_query_param.dry_run)
         .await
 }
+
+pub fn undelete_index_handler(
+    index_service: IndexService,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("indexes" / String / "undelete")
+        .and(warp::post())
+        .and(with_arg(index_service))
+        .then(undelete_index)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+        .boxed()
+}
+
+#[utoipa::path(
+    post,
+    tag = "Indexes",
+    path = "/indexes/{index_id}/undelete",
+    responses(
+        // We return `VersionedIndexMetadata` as it's the serialized model view.
+        (status = 200, description = "Successfully restored index.", body = VersionedIndexMetadata)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to restore."),
+    )
+)]
+/// Restores an index from the trash.
+pub async fn undelete_index(
+    index_id: IndexId,
+    index_service: IndexService,
+) -> Result<IndexMetadata, IndexServiceError> {
+    info!(index_id = %index_id, "undelete-index");
+    index_service.undelete_index(&index_id).await
+}
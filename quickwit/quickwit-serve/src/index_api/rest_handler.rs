@@ -14,6 +14,7 @@
 
 use std::sync::Arc;
 
+use bytesize::ByteSize;
 use quickwit_config::NodeConfig;
 use quickwit_doc_mapper::{analyze_text, TokenizerConfig};
 use quickwit_index_management::{IndexService, IndexServiceError};
@@ -25,16 +26,21 @@ use warp::{Filter, Rejection};
 
 use super::get_index_metadata_handler;
 use super::index_resource::{
-    __path_clear_index, __path_create_index, __path_delete_index, __path_list_indexes_metadata,
-    __path_update_index, clear_index_handler, create_index_handler, delete_index_handler,
-    describe_index_handler, list_indexes_metadata_handler, update_index_handler, IndexStats,
-    __path_describe_index,
+    __path_clear_index, __path_create_index, __path_delete_index, __path_describe_index,
+    __path_freeze_ingestion, __path_get_index_usage, __path_list_indexes_metadata,
+    __path_undelete_index, __path_unfreeze_ingestion, __path_update_index, clear_index_handler,
+    create_index_handler, delete_index_handler, describe_index_handler, freeze_ingestion_handler,
+    get_index_usage_handler, list_indexes_metadata_handler, undelete_index_handler,
+    unfreeze_ingestion_handler, update_index_handler, FreezeIngestionRequest, IndexStats,
+    IndexUsageStats,
 };
 use super::source_resource::{
     __path_create_source, __path_delete_source, __path_reset_source_checkpoint,
-    __path_toggle_source, __path_update_source, create_source_handler, delete_source_handler,
-    get_source_handler, get_source_shards_handler, reset_source_checkpoint_handler,
-    toggle_source_handler, update_source_handler, ToggleSource,
+    __path_test_source_transform, __path_toggle_source, __path_update_source,
+    create_source_handler, delete_source_handler, get_source_handler, get_source_shards_handler,
+    reset_source_checkpoint_handler, test_source_transform_handler, toggle_source_handler,
+    update_source_handler, TestSourceTransformRequest, TestSourceTransformResponse,
+    TestSourceTransformResult, ToggleSource,
 };
 use super::split_resource::{
     __path_list_splits, __path_mark_splits_for_deletion, list_splits_handler,
@@ -52,17 +58,31 @@ use crate::simple_list::from_simple_list;
         update_index,
         clear_index,
         delete_index,
+        undelete_index,
+        freeze_ingestion,
+        unfreeze_ingestion,
         list_indexes_metadata,
         list_splits,
         describe_index,
+        get_index_usage,
         mark_splits_for_deletion,
         create_source,
         update_source,
         reset_source_checkpoint,
         toggle_source,
+        test_source_transform,
         delete_source,
     ),
-    components(schemas(ToggleSource, SplitsForDeletion, IndexStats))
+    components(schemas(
+        ToggleSource,
+        TestSourceTransformRequest,
+        TestSourceTransformResponse,
+        TestSourceTransformResult,
+        SplitsForDeletion,
+        IndexStats,
+        IndexUsageStats,
+        FreezeIngestionRequest
+    ))
 )]
 pub struct IndexApi;
 
@@ -78,32 +98,63 @@ pub fn log_failure<T, E: std::fmt::Display>(
 }
 
 pub fn json_body<T: DeserializeOwned + Send>(
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (T,), Error = warp::Rejection> + Clone {
-    warp::body::content_length_limit(1024 * 1024).and(warp::body::json())
+    warp::body::content_length_limit(content_length_limit.as_u64()).and(warp::body::json())
 }
 
 pub fn index_management_handlers(
     index_service: IndexService,
     node_config: Arc<NodeConfig>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    let content_length_limit = node_config
+        .rest_config
+        .content_length_limits
+        .index_management;
     // Indexes handlers.
     get_index_metadata_handler(index_service.metastore())
         .or(list_indexes_metadata_handler(index_service.metastore()))
         .or(create_index_handler(index_service.clone(), node_config))
-        .or(update_index_handler(index_service.metastore()))
+        .or(update_index_handler(
+            index_service.metastore(),
+            content_length_limit,
+        ))
         .or(clear_index_handler(index_service.clone()))
         .or(delete_index_handler(index_service.clone()))
+        .or(undelete_index_handler(index_service.clone()))
+        .or(freeze_ingestion_handler(
+            index_service.metastore(),
+            content_length_limit,
+        ))
+        .or(unfreeze_ingestion_handler(index_service.metastore()))
         .boxed()
         // Splits handlers
         .or(list_splits_handler(index_service.metastore()))
         .or(describe_index_handler(index_service.metastore()))
-        .or(mark_splits_for_deletion_handler(index_service.metastore()))
+        .or(get_index_usage_handler(index_service.metastore()))
+        .or(mark_splits_for_deletion_handler(
+            index_service.metastore(),
+            content_length_limit,
+        ))
         .boxed()
         // Sources handlers.
         .or(reset_source_checkpoint_handler(index_service.metastore()))
-        .or(toggle_source_handler(index_service.metastore()))
-        .or(create_source_handler(index_service.clone()))
-        .or(update_source_handler(index_service.clone()))
+        .or(toggle_source_handler(
+            index_service.metastore(),
+            content_length_limit,
+        ))
+        .or(test_source_transform_handler(
+            index_service.metastore(),
+            content_length_limit,
+        ))
+        .or(create_source_handler(
+            index_service.clone(),
+            content_length_limit,
+        ))
+        .or(update_source_handler(
+            index_service.clone(),
+            content_length_limit,
+        ))
         .or(get_source_handler(index_service.metastore()))
         .or(delete_source_handler(index_service.metastore()))
         .or(get_source_shards_handler(index_service.metastore()))
@@ -205,13 +256,15 @@ async fn parse_query_request(request: ParseQueryRequest) -> Result<QueryAst, Ind
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::ops::{Bound, RangeInclusive};
 
     use assert_json_diff::assert_json_include;
     use quickwit_common::uri::Uri;
     use quickwit_common::ServiceStream;
     use quickwit_config::{
-        NodeConfig, SourceParams, VecSourceParams, CLI_SOURCE_ID, INGEST_API_SOURCE_ID,
+        NodeConfig, SourceConfig, SourceParams, VecSourceParams, CLI_SOURCE_ID,
+        INGEST_API_SOURCE_ID,
     };
     use quickwit_indexing::{mock_split, MockSplitBuilder};
     use quickwit_metastore::{
@@ -219,11 +272,11 @@ mod tests {
         ListIndexesMetadataResponseExt, ListSplitsRequestExt, ListSplitsResponseExt, SplitState,
     };
     use quickwit_proto::metastore::{
-        DeleteSourceRequest, EmptyResponse, EntityKind, IndexMetadataRequest,
-        IndexMetadataResponse, ListIndexesMetadataRequest, ListIndexesMetadataResponse,
-        ListSplitsRequest, ListSplitsResponse, MarkSplitsForDeletionRequest, MetastoreError,
-        MetastoreService, MetastoreServiceClient, MockMetastoreService,
-        ResetSourceCheckpointRequest, SourceType, ToggleSourceRequest,
+        DeleteSourceRequest, EmptyResponse, EntityKind, GetIndexUsageResponse,
+        IndexMetadataRequest, IndexMetadataResponse, IndexUsage, ListIndexesMetadataRequest,
+        ListIndexesMetadataResponse, ListSplitsRequest, ListSplitsResponse,
+        MarkSplitsForDeletionRequest, MetastoreError, MetastoreService, MetastoreServiceClient,
+        MockMetastoreService, ResetSourceCheckpointRequest, SourceType, ToggleSourceRequest,
     };
     use quickwit_proto::types::IndexUid;
     use quickwit_storage::StorageResolver;
@@ -423,6 +476,72 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_index_usage() {
+        let mut mock_metastore = MockMetastoreService::new();
+        let index_metadata =
+            IndexMetadata::for_test("quickwit-demo-index", "ram:///indexes/quickwit-demo-index");
+        let index_uid = index_metadata.index_uid.clone();
+        mock_metastore
+            .expect_index_metadata()
+            .return_once(move |_| {
+                Ok(IndexMetadataResponse::try_from_index_metadata(&index_metadata).unwrap())
+            });
+        let index_uid_clone = index_uid.clone();
+        mock_metastore
+            .expect_get_index_usage()
+            .withf(move |get_index_usage_request| {
+                get_index_usage_request.index_uid() == &index_uid_clone
+                    && get_index_usage_request.start_timestamp == Some(86_400)
+                    && get_index_usage_request.end_timestamp.is_none()
+            })
+            .return_once(|_| {
+                let usage = IndexUsage {
+                    num_docs_ingested: 100,
+                    num_bytes_ingested: 10_000,
+                    num_queries: 3,
+                    num_bytes_scanned: 4_800,
+                };
+                Ok(GetIndexUsageResponse { usage: Some(usage) })
+            });
+        mock_metastore
+            .expect_list_splits()
+            .withf(move |list_split_request| -> bool {
+                let list_split_query = list_split_request.deserialize_list_splits_query().unwrap();
+                list_split_query.index_uids.unwrap().contains(&index_uid)
+                    && list_split_query.split_states == [SplitState::Published]
+            })
+            .return_once(|_| {
+                let splits = vec![mock_split("split_1"), mock_split("split_2")];
+                let splits = ListSplitsResponse::try_from_splits(splits).unwrap();
+                Ok(ServiceStream::from(vec![Ok(splits)]))
+            });
+
+        let index_service = IndexService::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            StorageResolver::unconfigured(),
+        );
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(NodeConfig::for_test()))
+                .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/indexes/quickwit-demo-index/usage?start_timestamp=86400")
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let actual_response_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let expected_response_json = serde_json::json!({
+            "index_id": "quickwit-demo-index",
+            "num_docs_ingested": 100,
+            "num_bytes_ingested": 10_000,
+            "num_bytes_stored": 1600,
+            "num_queries": 3,
+            "num_bytes_scanned": 4_800,
+        });
+        assert_eq!(actual_response_json, expected_response_json);
+    }
+
     #[tokio::test]
     async fn test_get_all_splits() {
         let mut mock_metastore = MockMetastoreService::new();
@@ -564,6 +683,50 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_list_indexes_with_labels() -> anyhow::Result<()> {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_list_indexes_metadata()
+            .withf(|request| request.label_selectors == ["env:prod", "pii"])
+            .times(1)
+            .returning(|_| {
+                let mut prod_index_metadata =
+                    IndexMetadata::for_test("prod-index", "ram:///indexes/prod-index");
+                prod_index_metadata.index_config.labels = BTreeMap::from([
+                    ("env".to_string(), "prod".to_string()),
+                    ("pii".to_string(), "true".to_string()),
+                ]);
+                Ok(ListIndexesMetadataResponse::for_test(vec![
+                    prod_index_metadata,
+                ]))
+            });
+        let index_service = IndexService::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            StorageResolver::unconfigured(),
+        );
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(NodeConfig::for_test()))
+                .recover(recover_fn);
+
+        // The label selectors are pushed down to the metastore.
+        let resp = warp::test::request()
+            .path("/indexes?labels=env:prod,pii")
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let indexes_metadata: Vec<IndexMetadata> = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(indexes_metadata.len(), 1);
+        assert_eq!(indexes_metadata[0].index_id(), "prod-index");
+
+        let resp = warp::test::request()
+            .path("/indexes?labels=:prod")
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_clear_index() -> anyhow::Result<()> {
         let mut mock_metastore = MockMetastoreService::new();
@@ -675,6 +838,43 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_undelete_index() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_undelete_index()
+            .return_once(|undelete_index_request| {
+                assert_eq!(undelete_index_request.index_id, "quickwit-demo-index");
+                Ok(
+                    IndexMetadataResponse::try_from_index_metadata(&IndexMetadata::for_test(
+                        "quickwit-demo-index",
+                        "file:///path/to/index/quickwit-demo-index",
+                    ))
+                    .unwrap(),
+                )
+            });
+        let index_service = IndexService::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            StorageResolver::unconfigured(),
+        );
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(NodeConfig::for_test()))
+                .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/indexes/quickwit-demo-index/undelete")
+            .method("POST")
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let expected_response_json = serde_json::json!({
+            "index_config": {
+                "index_id": "quickwit-demo-index",
+            }
+        });
+        assert_json_include!(actual: resp_json, expected: expected_response_json);
+    }
+
     #[tokio::test]
     async fn test_delete_on_non_existing_index() {
         let metastore = metastore_for_test();
@@ -1036,6 +1236,77 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_freeze_and_unfreeze_ingestion() {
+        let metastore = metastore_for_test();
+        let index_service = IndexService::new(metastore.clone(), StorageResolver::unconfigured());
+        let mut node_config = NodeConfig::for_test();
+        node_config.default_index_root_uri = Uri::for_test("file:///default-index-root-uri");
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(node_config));
+        {
+            let resp = warp::test::request()
+                .path("/indexes")
+                .method("POST")
+                .json(&true)
+                .body(r#"{"version": "0.7", "index_id": "hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}]}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+        }
+        {
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs/freeze-ingestion")
+                .method("POST")
+                .body(r#"{"reason": "incident-1234"}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+            let resp_json: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+            let expected_response_json = serde_json::json!({
+                "index_config": {
+                    "ingestion_freeze": {
+                        "reason": "incident-1234"
+                    }
+                }
+            });
+            assert_json_include!(actual: resp_json, expected: expected_response_json);
+        }
+        let index_metadata = metastore
+            .index_metadata(IndexMetadataRequest::for_index_id("hdfs-logs".to_string()))
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap();
+        let ingestion_freeze = index_metadata.index_config.ingestion_freeze_opt.unwrap();
+        assert_eq!(ingestion_freeze.reason, "incident-1234");
+        assert!(ingestion_freeze.frozen_at > 0);
+        {
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs/freeze-ingestion")
+                .method("POST")
+                .body(r#"{"unknown_field": "incident-1234"}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 400);
+        }
+        {
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs/unfreeze-ingestion")
+                .method("POST")
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+        }
+        let index_metadata = metastore
+            .index_metadata(IndexMetadataRequest::for_index_id("hdfs-logs".to_string()))
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap();
+        assert!(index_metadata.index_config.ingestion_freeze_opt.is_none());
+    }
+
     #[tokio::test]
     async fn test_create_source_with_bad_config() {
         let metastore = metastore_for_test();
@@ -1379,6 +1650,55 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_source_transform_test() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_index_metadata().returning(|_| {
+            let mut index_metadata = IndexMetadata::for_test(
+                "quickwit-demo-index",
+                "file:///path/to/index/quickwit-demo-index",
+            );
+            let source_config = SourceConfig::for_test("void-source", SourceParams::void());
+            index_metadata.add_source(source_config).unwrap();
+            Ok(IndexMetadataResponse::try_from_index_metadata(&index_metadata).unwrap())
+        });
+        let index_service = IndexService::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            StorageResolver::unconfigured(),
+        );
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(NodeConfig::for_test()))
+                .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/indexes/quickwit-demo-index/sources/void-source/transform/_test")
+            .method("POST")
+            .body(r#"{"docs": [{"body": "happy"}]}"#)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert!(resp_json["message"]
+            .as_str()
+            .unwrap()
+            .contains("source `void-source` of index `quickwit-demo-index` has no transform"));
+
+        let resp = warp::test::request()
+            .path("/indexes/quickwit-demo-index/sources/void-source/transform/_test")
+            .method("POST")
+            .body(r#"{"documents": [{"body": "happy"}]}"#) // unknown field, should return 400.
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+
+        let resp = warp::test::request()
+            .path("/indexes/quickwit-demo-index/sources/unknown-source/transform/_test")
+            .method("POST")
+            .body(r#"{"docs": [{"body": "happy"}]}"#)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 404);
+    }
+
     #[tokio::test]
     async fn test_analyze_request() {
         let mut mock_metastore = MockMetastoreService::new();
@@ -13,11 +13,14 @@
 // limitations under the License.
 
 use bytes::Bytes;
+use bytesize::ByteSize;
 use quickwit_config::{
     load_source_config_from_user_config, load_source_config_update, ConfigFormat, FileSourceParams,
-    SourceConfig, SourceParams, CLI_SOURCE_ID, INGEST_API_SOURCE_ID,
+    SourceConfig, SourceInputFormat, SourceParams, CLI_SOURCE_ID, INGEST_API_SOURCE_ID,
 };
+use quickwit_doc_mapper::JsonObject;
 use quickwit_index_management::{IndexService, IndexServiceError};
+use quickwit_indexing::actors::dry_run_transform;
 use quickwit_metastore::IndexMetadataResponseExt;
 use quickwit_proto::ingest::Shard;
 use quickwit_proto::metastore::{
@@ -26,7 +29,8 @@ use quickwit_proto::metastore::{
     ResetSourceCheckpointRequest, ToggleSourceRequest,
 };
 use quickwit_proto::types::{IndexId, IndexUid, SourceId};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use tracing::info;
 use warp::{Filter, Rejection};
 
@@ -37,11 +41,14 @@ use crate::with_arg;
 
 pub fn create_source_handler(
     index_service: IndexService,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     warp::path!("indexes" / String / "sources")
         .and(warp::post())
         .and(extract_config_format())
-        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::content_length_limit(
+            content_length_limit.as_u64(),
+        ))
         .and(warp::filters::body::bytes())
         .and(with_arg(index_service))
         .then(create_source)
@@ -97,11 +104,14 @@ pub async fn create_source(
 
 pub fn update_source_handler(
     index_service: IndexService,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     warp::path!("indexes" / String / "sources" / String)
         .and(warp::put())
         .and(extract_config_format())
-        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::content_length_limit(
+            content_length_limit.as_u64(),
+        ))
         .and(warp::filters::body::bytes())
         .and(with_arg(index_service))
         .then(update_source)
@@ -239,10 +249,11 @@ pub async fn reset_source_checkpoint(
 
 pub fn toggle_source_handler(
     metastore: MetastoreServiceClient,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     warp::path!("indexes" / String / "sources" / String / "toggle")
         .and(warp::put())
-        .and(json_body())
+        .and(json_body(content_length_limit))
         .and(with_arg(metastore))
         .then(toggle_source)
         .and(extract_format_from_qs())
@@ -298,6 +309,117 @@ pub async fn toggle_source(
     Ok(())
 }
 
+pub fn test_source_transform_handler(
+    metastore: MetastoreServiceClient,
+    content_length_limit: ByteSize,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("indexes" / String / "sources" / String / "transform" / "_test")
+        .and(warp::post())
+        .and(json_body(content_length_limit))
+        .and(with_arg(metastore))
+        .then(test_source_transform)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+        .boxed()
+}
+
+#[derive(Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TestSourceTransformRequest {
+    /// Sample documents to transform. Documents of sources with the `plain_text` input format are
+    /// passed as strings.
+    #[schema(value_type = Vec<Object>)]
+    docs: Vec<JsonValue>,
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct TestSourceTransformResponse {
+    pub num_transformed_docs: usize,
+    pub num_transform_errors: usize,
+    /// Outcome of the transform for each sample document, in the order of the request.
+    pub results: Vec<TestSourceTransformResult>,
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct TestSourceTransformResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Object)]
+    pub doc: Option<JsonObject>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    tag = "Sources",
+    path = "/indexes/{index_id}/sources/{source_id}/transform/_test",
+    request_body = TestSourceTransformRequest,
+    responses(
+        (
+            status = 200,
+            description = "Successfully ran the source transform.",
+            body = TestSourceTransformResponse
+        )
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID of the source."),
+        ("source_id" = String, Path, description = "The source ID whose transform is tested."),
+    )
+)]
+/// Runs the VRL transform of a source against sample documents without indexing them.
+pub async fn test_source_transform(
+    index_id: IndexId,
+    source_id: SourceId,
+    test_request: TestSourceTransformRequest,
+    metastore: MetastoreServiceClient,
+) -> Result<TestSourceTransformResponse, IndexServiceError> {
+    info!(index_id = %index_id, source_id = %source_id, "test-source-transform");
+    let source_config = get_source(index_id.clone(), source_id.clone(), metastore).await?;
+
+    let Some(transform_config) = source_config.transform_config else {
+        return Err(IndexServiceError::InvalidConfig(anyhow::anyhow!(
+            "source `{source_id}` of index `{index_id}` has no transform"
+        )));
+    };
+    let input_format = source_config.input_format;
+    let raw_docs: Vec<Bytes> = test_request
+        .docs
+        .into_iter()
+        .map(|doc| match (input_format, doc) {
+            (SourceInputFormat::PlainText, JsonValue::String(text)) => Bytes::from(text),
+            (_, doc) => Bytes::from(doc.to_string()),
+        })
+        .collect();
+    let results = dry_run_transform(input_format, transform_config, raw_docs)
+        .map_err(IndexServiceError::InvalidConfig)?;
+
+    let mut test_response = TestSourceTransformResponse {
+        num_transformed_docs: 0,
+        num_transform_errors: 0,
+        results: Vec::with_capacity(results.len()),
+    };
+    for result in results {
+        let test_result = match result {
+            Ok(doc) => {
+                test_response.num_transformed_docs += 1;
+                TestSourceTransformResult {
+                    doc: Some(doc),
+                    error: None,
+                }
+            }
+            Err(error) => {
+                test_response.num_transform_errors += 1;
+                TestSourceTransformResult {
+                    doc: None,
+                    error: Some(error.to_string()),
+                }
+            }
+        };
+        test_response.results.push(test_result);
+    }
+    Ok(test_response)
+}
+
 pub fn delete_source_handler(
     metastore: MetastoreServiceClient,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use bytesize::ByteSize;
 use quickwit_metastore::{
     IndexMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt,
     MetastoreServiceStreamSplitsExt, Split, SplitState,
@@ -195,10 +196,11 @@ pub async fn mark_splits_for_deletion(
 
 pub fn mark_splits_for_deletion_handler(
     metastore: MetastoreServiceClient,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     warp::path!("indexes" / String / "splits" / "mark-for-deletion")
         .and(warp::put())
-        .and(json_body())
+        .and(json_body(content_length_limit))
         .and(with_arg(metastore))
         .then(mark_splits_for_deletion)
         .and(extract_format_from_qs())
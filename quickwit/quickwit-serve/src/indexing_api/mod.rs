@@ -14,4 +14,4 @@
 
 mod rest_handler;
 
-pub use rest_handler::{indexing_get_handler, IndexingApi};
+pub use rest_handler::{indexing_get_handler, indexing_sources_get_handler, IndexingApi};
@@ -16,6 +16,7 @@ use std::convert::Infallible;
 
 use quickwit_actors::{AskError, Mailbox, Observe};
 use quickwit_indexing::actors::{IndexingService, IndexingServiceCounters};
+use quickwit_indexing::models::{ObserveSourceLags, SourceLagReport};
 use warp::{Filter, Rejection};
 
 use crate::format::extract_format_from_qs;
@@ -24,7 +25,7 @@ use crate::rest::recover_fn;
 use crate::rest_api_response::into_rest_api_response;
 
 #[derive(utoipa::OpenApi)]
-#[openapi(paths(indexing_endpoint))]
+#[openapi(paths(indexing_endpoint, indexing_sources_endpoint))]
 pub struct IndexingApi;
 
 #[utoipa::path(
@@ -48,6 +49,29 @@ fn indexing_get_filter() -> impl Filter<Extract = (), Error = Rejection> + Clone
     warp::path!("indexing").and(warp::get())
 }
 
+#[utoipa::path(
+    get,
+    tag = "Indexing",
+    path = "/indexing/sources",
+    responses(
+        (status = 200, description = "Successfully observed the lag and throughput of the sources.", body = [SourceLagReport])
+    ),
+)]
+/// Observe Source Lags
+///
+/// Returns the current position, end position (when the source is able to obtain it), lag and
+/// throughput of the source of each indexing pipeline running on the node.
+async fn indexing_sources_endpoint(
+    indexing_service_mailbox: Mailbox<IndexingService>,
+) -> Result<Vec<SourceLagReport>, AskError<Infallible>> {
+    let source_lag_reports = indexing_service_mailbox.ask(ObserveSourceLags).await?;
+    Ok(source_lag_reports)
+}
+
+fn indexing_sources_get_filter() -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::path!("indexing" / "sources").and(warp::get())
+}
+
 pub fn indexing_get_handler(
     indexing_service_mailbox_opt: Option<Mailbox<IndexingService>>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
@@ -59,3 +83,15 @@ pub fn indexing_get_handler(
         .recover(recover_fn)
         .boxed()
 }
+
+pub fn indexing_sources_get_handler(
+    indexing_service_mailbox_opt: Option<Mailbox<IndexingService>>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    indexing_sources_get_filter()
+        .and(require(indexing_service_mailbox_opt))
+        .then(indexing_sources_endpoint)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+        .recover(recover_fn)
+        .boxed()
+}
@@ -0,0 +1,263 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, Context};
+use quickwit_config::validate_identifier;
+use quickwit_datetime::{
+    parse_date_time_str, parse_timestamp, DateTimeInputFormat, StrptimeParser,
+};
+use quickwit_proto::ingest::ParseFailureReason;
+use quickwit_proto::types::IndexId;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use time::OffsetDateTime;
+
+enum TemplatePart {
+    Literal(String),
+    Field(String),
+    DateTime(StrptimeParser),
+}
+
+/// Template of the ID of the index to which a document is routed, for instance
+/// `logs-{service}-{yyyy.MM}`.
+///
+/// Placeholders are replaced by the value of the document field they name, or by the timestamp of
+/// the document formatted according to a Java date time format when they only contain date time
+/// pattern letters (`y`, `M`, `d`, `H`, `m`, `s`) and separators (`.`, `-`, `_`).
+pub(crate) struct IndexIdTemplate {
+    parts: Vec<TemplatePart>,
+    has_date_time_parts: bool,
+}
+
+impl IndexIdTemplate {
+    pub fn parse(template: &str) -> anyhow::Result<Self> {
+        let mut parts = Vec::new();
+        let mut remaining = template;
+
+        while let Some(start) = remaining.find('{') {
+            if start > 0 {
+                parts.push(TemplatePart::Literal(remaining[..start].to_string()));
+            }
+            let placeholder_and_remaining = &remaining[start + 1..];
+            let end = placeholder_and_remaining.find('}').with_context(|| {
+                format!("unclosed placeholder in index ID template `{template}`")
+            })?;
+            let placeholder = placeholder_and_remaining[..end].trim();
+
+            if placeholder.is_empty() {
+                bail!("empty placeholder in index ID template `{template}`");
+            }
+            let part = if is_date_time_format(placeholder) {
+                let date_time_parser = StrptimeParser::from_java_datetime_format(placeholder)
+                    .map_err(|error| {
+                        anyhow::anyhow!(
+                            "invalid date time format in index ID template `{template}`: {error}"
+                        )
+                    })?;
+                TemplatePart::DateTime(date_time_parser)
+            } else {
+                TemplatePart::Field(placeholder.to_string())
+            };
+            parts.push(part);
+            remaining = &placeholder_and_remaining[end + 1..];
+        }
+        if !remaining.is_empty() {
+            parts.push(TemplatePart::Literal(remaining.to_string()));
+        }
+        let has_date_time_parts = parts
+            .iter()
+            .any(|part| matches!(part, TemplatePart::DateTime(_)));
+        Ok(Self {
+            parts,
+            has_date_time_parts,
+        })
+    }
+
+    /// Returns the ID of the index to which the document must be routed, or the reason why it
+    /// cannot be routed.
+    ///
+    /// The timestamp of the document is read from `timestamp_field_opt` if the field is present,
+    /// otherwise the current time is used.
+    pub fn route(
+        &self,
+        doc: &[u8],
+        timestamp_field_opt: Option<&str>,
+    ) -> Result<IndexId, (ParseFailureReason, String)> {
+        let json_obj: JsonMap<String, JsonValue> =
+            serde_json::from_slice(doc).map_err(|error| {
+                (
+                    ParseFailureReason::InvalidJson,
+                    format!("failed to parse JSON document: {error}"),
+                )
+            })?;
+        let timestamp = if self.has_date_time_parts {
+            timestamp_field_opt
+                .and_then(|timestamp_field| find_field(&json_obj, timestamp_field))
+                .map(parse_doc_timestamp)
+                .transpose()
+                .map_err(|error| (ParseFailureReason::InvalidSchema, error))?
+                .unwrap_or_else(OffsetDateTime::now_utc)
+        } else {
+            OffsetDateTime::UNIX_EPOCH
+        };
+        self.render(&json_obj, timestamp)
+            .map_err(|error| (ParseFailureReason::InvalidSchema, error))
+    }
+
+    fn render(
+        &self,
+        json_obj: &JsonMap<String, JsonValue>,
+        timestamp: OffsetDateTime,
+    ) -> Result<IndexId, String> {
+        let mut index_id = String::new();
+
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(literal) => index_id.push_str(literal),
+                TemplatePart::Field(field) => match find_field(json_obj, field) {
+                    Some(JsonValue::String(value)) => index_id.push_str(value),
+                    Some(JsonValue::Number(value)) => index_id.push_str(&value.to_string()),
+                    Some(JsonValue::Bool(value)) => index_id.push_str(&value.to_string()),
+                    _ => {
+                        return Err(format!(
+                            "routing field `{field}` is missing or is not a string, a number, or \
+                             a boolean"
+                        ));
+                    }
+                },
+                TemplatePart::DateTime(date_time_parser) => {
+                    let formatted_timestamp = date_time_parser
+                        .format_date_time(&timestamp)
+                        .map_err(|error| format!("failed to format timestamp: {error}"))?;
+                    index_id.push_str(&formatted_timestamp);
+                }
+            }
+        }
+        validate_identifier("routed index", &index_id).map_err(|error| error.to_string())?;
+        Ok(index_id)
+    }
+}
+
+fn is_date_time_format(placeholder: &str) -> bool {
+    placeholder.chars().any(|ch| ch.is_ascii_alphabetic())
+        && placeholder
+            .chars()
+            .all(|ch| matches!(ch, 'y' | 'M' | 'd' | 'H' | 'm' | 's' | '.' | '-' | '_'))
+}
+
+/// Looks up a field by its path, for instance `service.name`, either as a flattened key or as a
+/// path in nested objects.
+fn find_field<'a>(json_obj: &'a JsonMap<String, JsonValue>, path: &str) -> Option<&'a JsonValue> {
+    if let Some(value) = json_obj.get(path) {
+        return Some(value);
+    }
+    let (head, tail) = path.split_once('.')?;
+    match json_obj.get(head)? {
+        JsonValue::Object(child_obj) => find_field(child_obj, tail),
+        _ => None,
+    }
+}
+
+fn parse_doc_timestamp(value: &JsonValue) -> Result<OffsetDateTime, String> {
+    let date_time = match value {
+        JsonValue::String(value) => parse_date_time_str(
+            value,
+            &[DateTimeInputFormat::Rfc3339, DateTimeInputFormat::Timestamp],
+        )?,
+        JsonValue::Number(value) => {
+            let timestamp = value
+                .as_i64()
+                .ok_or_else(|| format!("timestamp `{value}` is not an integer"))?;
+            parse_timestamp(timestamp)?
+        }
+        _ => return Err("timestamp must be an RFC 3339 string or a Unix timestamp".to_string()),
+    };
+    Ok(date_time.into_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn test_index_id_template_parse() {
+        IndexIdTemplate::parse("logs").unwrap();
+        IndexIdTemplate::parse("logs-{service}-{yyyy.MM}").unwrap();
+
+        let error = IndexIdTemplate::parse("logs-{service").unwrap_err();
+        assert!(error.to_string().contains("unclosed placeholder"));
+
+        let error = IndexIdTemplate::parse("logs-{}").unwrap_err();
+        assert!(error.to_string().contains("empty placeholder"));
+    }
+
+    #[test]
+    fn test_index_id_template_render() {
+        let index_id_template = IndexIdTemplate::parse("logs-{service.name}-{yyyy.MM}").unwrap();
+        let timestamp = datetime!(2024-03-07 12:00 UTC);
+
+        let json_obj = serde_json::json!({"service": {"name": "api"}});
+        let index_id = index_id_template
+            .render(json_obj.as_object().unwrap(), timestamp)
+            .unwrap();
+        assert_eq!(index_id, "logs-api-2024.03");
+
+        let json_obj = serde_json::json!({"service.name": "web"});
+        let index_id = index_id_template
+            .render(json_obj.as_object().unwrap(), timestamp)
+            .unwrap();
+        assert_eq!(index_id, "logs-web-2024.03");
+
+        let json_obj = serde_json::json!({"service": "api"});
+        let error = index_id_template
+            .render(json_obj.as_object().unwrap(), timestamp)
+            .unwrap_err();
+        assert!(error.contains("routing field `service.name` is missing"));
+
+        let json_obj = serde_json::json!({"service": {"name": "my api"}});
+        let error = index_id_template
+            .render(json_obj.as_object().unwrap(), timestamp)
+            .unwrap_err();
+        assert!(error.contains("routed index ID `logs-my api-2024.03` is invalid"));
+    }
+
+    #[test]
+    fn test_index_id_template_route() {
+        let index_id_template = IndexIdTemplate::parse("logs-{service}-{yyyy.MM.dd}").unwrap();
+
+        let index_id = index_id_template
+            .route(
+                br#"{"service": "api", "ts": "2024-03-07T12:00:00Z"}"#,
+                Some("ts"),
+            )
+            .unwrap();
+        assert_eq!(index_id, "logs-api-2024.03.07");
+
+        let index_id = index_id_template
+            .route(br#"{"service": "api", "ts": 1709812800}"#, Some("ts"))
+            .unwrap();
+        assert_eq!(index_id, "logs-api-2024.03.07");
+
+        let (reason, _) = index_id_template
+            .route(br#"{"service": "api", "ts": true}"#, Some("ts"))
+            .unwrap_err();
+        assert_eq!(reason, ParseFailureReason::InvalidSchema);
+
+        let (reason, _) = index_id_template
+            .route(b"not json", Some("ts"))
+            .unwrap_err();
+        assert_eq!(reason, ParseFailureReason::InvalidJson);
+    }
+}
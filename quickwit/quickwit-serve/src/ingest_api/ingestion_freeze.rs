@@ -0,0 +1,158 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use quickwit_ingest::IngestServiceError;
+use quickwit_metastore::IndexMetadataResponseExt;
+use quickwit_proto::metastore::{
+    IndexMetadataRequest, MetastoreError, MetastoreResult, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::types::IndexId;
+use tracing::warn;
+
+/// Duration during which the freeze status of an index is served from the cache.
+const CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// Tells whether the ingestion into an index is frozen.
+///
+/// Ingest v2 requests into a frozen index are rejected by the control plane, which refuses to open
+/// shards for it. Ingest v1 requests are written to the local queues of the node without going
+/// through the control plane, so the ingest v1 API checks the index config itself. The freeze
+/// status of each index is cached for a few seconds so that the metastore is not queried on every
+/// request.
+#[derive(Clone, Default)]
+pub(crate) struct IngestionFreezeChecker {
+    metastore_opt: Option<MetastoreServiceClient>,
+    cache: Arc<Mutex<HashMap<IndexId, (Instant, bool)>>>,
+}
+
+impl IngestionFreezeChecker {
+    pub fn new(metastore: MetastoreServiceClient) -> Self {
+        Self {
+            metastore_opt: Some(metastore),
+            cache: Default::default(),
+        }
+    }
+
+    /// Returns an error if the ingestion into the index is frozen. The indexes whose metadata
+    /// cannot be fetched are considered not frozen: ingest v1 reports the missing indexes itself.
+    pub async fn check(&self, index_id: &str) -> Result<(), IngestServiceError> {
+        let Some(metastore) = &self.metastore_opt else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        let cached_is_frozen_opt = self
+            .cache
+            .lock()
+            .expect("lock should not be poisoned")
+            .get(index_id)
+            .filter(|(fetched_at, _)| now.duration_since(*fetched_at) < CACHE_TTL)
+            .map(|(_, is_frozen)| *is_frozen);
+
+        let is_frozen = if let Some(is_frozen) = cached_is_frozen_opt {
+            is_frozen
+        } else {
+            let is_frozen = match fetch_is_frozen(metastore, index_id).await {
+                Ok(is_frozen) => is_frozen,
+                Err(MetastoreError::NotFound(_)) => return Ok(()),
+                Err(error) => {
+                    warn!(index_id=%index_id, %error, "failed to check whether ingestion is frozen");
+                    return Ok(());
+                }
+            };
+            self.cache
+                .lock()
+                .expect("lock should not be poisoned")
+                .insert(index_id.to_string(), (now, is_frozen));
+            is_frozen
+        };
+        if is_frozen {
+            return Err(IngestServiceError::IngestionFrozen {
+                index_id: index_id.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+async fn fetch_is_frozen(
+    metastore: &MetastoreServiceClient,
+    index_id: &str,
+) -> MetastoreResult<bool> {
+    let index_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(index_id.to_string()))
+        .await?
+        .deserialize_index_metadata()?;
+    Ok(index_metadata.index_config.ingestion_freeze_opt.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_config::IngestionFreeze;
+    use quickwit_metastore::IndexMetadata;
+    use quickwit_proto::metastore::{EntityKind, IndexMetadataResponse, MockMetastoreService};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ingestion_freeze_checker() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_index_metadata()
+            .times(2)
+            .returning(|request| {
+                let index_id = request.index_id.unwrap();
+
+                if index_id == "missing-index" {
+                    return Err(MetastoreError::NotFound(EntityKind::Index { index_id }));
+                }
+                let mut index_metadata =
+                    IndexMetadata::for_test(&index_id, &format!("ram:///indexes/{index_id}"));
+                if index_id == "frozen-index" {
+                    index_metadata.index_config.ingestion_freeze_opt = Some(IngestionFreeze {
+                        reason: "incident".to_string(),
+                        frozen_at: 1_700_000_000,
+                    });
+                }
+                Ok(IndexMetadataResponse::try_from_index_metadata(&index_metadata).unwrap())
+            });
+        let ingestion_freeze_checker =
+            IngestionFreezeChecker::new(MetastoreServiceClient::from_mock(mock_metastore));
+
+        let error = ingestion_freeze_checker
+            .check("frozen-index")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, IngestServiceError::IngestionFrozen { .. }));
+
+        // The freeze status is served from the cache.
+        ingestion_freeze_checker
+            .check("frozen-index")
+            .await
+            .unwrap_err();
+
+        ingestion_freeze_checker
+            .check("missing-index")
+            .await
+            .unwrap();
+
+        IngestionFreezeChecker::default()
+            .check("frozen-index")
+            .await
+            .unwrap();
+    }
+}
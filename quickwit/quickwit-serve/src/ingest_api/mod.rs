@@ -12,11 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod index_routing;
+mod ingestion_freeze;
 mod response;
 mod rest_handler;
+mod write_alias;
 
-pub use response::{RestIngestResponse, RestParseFailure};
+pub(crate) use ingestion_freeze::IngestionFreezeChecker;
+pub use response::{IngestAckLevel, RestIndexFailure, RestIngestResponse, RestParseFailure};
 #[cfg(test)]
 pub(crate) use rest_handler::tests::setup_ingest_v1_service;
-pub(crate) use rest_handler::{ingest_api_handlers, lines};
+pub(crate) use rest_handler::{
+    ingest_api_handlers, is_empty_or_blank_line, lines, IngestApiContext,
+};
 pub use rest_handler::{IngestApi, IngestApiSchemas};
+pub(crate) use write_alias::WriteAliasResolver;
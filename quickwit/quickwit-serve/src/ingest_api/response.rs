@@ -16,7 +16,7 @@ use std::collections::BTreeMap;
 
 use bytes::Bytes;
 use quickwit_ingest::{IngestResponse, IngestServiceError};
-use quickwit_proto::ingest::router::IngestResponseV2;
+use quickwit_proto::ingest::router::{IngestFailureReason, IngestResponseV2};
 use quickwit_proto::ingest::{DocBatchV2, ParseFailureReason};
 use quickwit_proto::types::DocUid;
 use serde::{Deserialize, Serialize};
@@ -28,6 +28,46 @@ pub struct RestParseFailure {
     pub reason: ParseFailureReason,
 }
 
+/// Failure of the documents routed to an index by the multi-index ingest endpoint. The documents
+/// routed to the other indexes are ingested regardless.
+#[derive(Serialize, Deserialize, Debug, PartialEq, utoipa::ToSchema)]
+pub struct RestIndexFailure {
+    pub index_id: String,
+    /// Number of documents routed to the index, all rejected.
+    pub num_docs: u64,
+    pub reason: IngestFailureReason,
+    pub message: String,
+}
+
+/// Acknowledgment level of an ingest request, from the fastest to the most durable.
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum IngestAckLevel {
+    /// The request is acknowledged as soon as it is received, before the documents are persisted
+    /// (fire-and-forget).
+    None,
+    /// The documents are persisted in the write-ahead log of the leader of the shard.
+    Leader,
+    /// The documents are persisted in the write-ahead logs of the leader and of the follower of
+    /// the shard.
+    Replicated,
+    /// The documents are indexed and searchable.
+    Searchable,
+}
+
+impl IngestAckLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Leader => "leader",
+            Self::Replicated => "replicated",
+            Self::Searchable => "searchable",
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Default, utoipa::ToSchema)]
 pub struct RestIngestResponse {
     /// Number of rows in the request payload
@@ -42,6 +82,12 @@ pub struct RestIngestResponse {
     /// `detailed_response` is set to `true`)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parse_failures: Option<Vec<RestParseFailure>>,
+    /// Indexes whose documents were all rejected (multi-index ingest only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index_failures: Option<Vec<RestIndexFailure>>,
+    /// Acknowledgment level achieved by the request (ingest v2 only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ack_level: Option<IngestAckLevel>,
 }
 
 impl RestIngestResponse {
@@ -77,6 +123,8 @@ impl RestIngestResponse {
             num_ingested_docs: Some(success_resp.num_ingested_docs as u64),
             num_rejected_docs: Some(success_resp.parse_failures.len() as u64),
             parse_failures: None,
+            index_failures: None,
+            ack_level: None,
         };
         if let Some(doc_batch) = doc_batch_clone_opt {
             let docs: BTreeMap<DocUid, Bytes> = doc_batch.docs().collect();
@@ -112,6 +160,10 @@ impl RestIngestResponse {
             parse_failures: apply_op(self.parse_failures, other.parse_failures, |a, b| {
                 a.into_iter().chain(b).collect()
             }),
+            index_failures: apply_op(self.index_failures, other.index_failures, |a, b| {
+                a.into_iter().chain(b).collect()
+            }),
+            ack_level: apply_op(self.ack_level, other.ack_level, std::cmp::min),
         }
     }
 }
@@ -216,6 +268,8 @@ mod tests {
                 document: "doc1".to_string(),
                 reason: ParseFailureReason::InvalidJson,
             }]),
+            index_failures: None,
+            ack_level: None,
         };
         let response2 = RestIngestResponse {
             num_docs_for_processing: 15,
@@ -226,6 +280,8 @@ mod tests {
                 document: "doc2".to_string(),
                 reason: ParseFailureReason::InvalidJson,
             }]),
+            index_failures: None,
+            ack_level: None,
         };
         let merged_response = response1.merge(response2);
         assert_eq!(merged_response.num_docs_for_processing, 25);
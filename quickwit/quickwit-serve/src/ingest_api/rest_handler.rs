@@ -12,28 +12,37 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use bytes::{Buf, Bytes};
+use quickwit_common::rate_limited_warn;
 use quickwit_config::{validate_identifier, IngestApiConfig, INGEST_V2_SOURCE_ID};
 use quickwit_ingest::{
-    CommitType, DocBatchBuilder, DocBatchV2Builder, FetchResponse, IngestRequest, IngestService,
-    IngestServiceClient, IngestServiceError, TailRequest,
+    CommitType, DocBatchBuilder, DocBatchV2Builder, FetchResponse, IngestRequest,
+    IngestRequestV2Builder, IngestService, IngestServiceClient, IngestServiceError, TailRequest,
 };
 use quickwit_proto::ingest::router::{
     IngestRequestV2, IngestRouterService, IngestRouterServiceClient, IngestSubrequest,
 };
-use quickwit_proto::ingest::CommitTypeV2;
-use quickwit_proto::types::{DocUidGenerator, IndexId};
+use quickwit_proto::ingest::{CommitTypeV2, RateLimitingCause};
+use quickwit_proto::types::{DocUid, DocUidGenerator, IndexId, SubrequestId};
 use serde::Deserialize;
+use tokio::sync::Semaphore;
 use warp::{Filter, Rejection};
 
-use super::RestIngestResponse;
+use super::index_routing::IndexIdTemplate;
+use super::ingestion_freeze::IngestionFreezeChecker;
+use super::write_alias::WriteAliasResolver;
+use super::{IngestAckLevel, RestIndexFailure, RestIngestResponse, RestParseFailure};
 use crate::decompression::get_body_bytes;
 use crate::format::extract_format_from_qs;
 use crate::rest_api_response::into_rest_api_response;
-use crate::{with_arg, Body, BodyFormat};
+use crate::rollout::{mirror_ingest, RolloutRouter};
+use crate::{with_arg, Body, BodyFormat, SERVE_METRICS};
 
 #[derive(utoipa::OpenApi)]
-#[openapi(paths(ingest, tail_endpoint,))]
+#[openapi(paths(ingest, routed_ingest, tail_endpoint,))]
 pub struct IngestApi;
 
 #[derive(utoipa::OpenApi)]
@@ -42,6 +51,8 @@ pub struct IngestApi;
     quickwit_ingest::FetchResponse,
     quickwit_ingest::IngestResponse,
     quickwit_ingest::CommitType,
+    IngestAckLevel,
+    RestIndexFailure,
 )))]
 pub struct IngestApiSchemas;
 
@@ -53,6 +64,22 @@ struct IngestOptions {
     use_legacy_ingest: bool,
     #[serde(default)]
     detailed_response: bool,
+    /// Acknowledgment level requested by the client. Defaults to the level implied by the commit
+    /// type.
+    #[serde(default)]
+    ack: Option<IngestAckLevel>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+struct RoutedIngestOptions {
+    /// Template of the ID of the index to which each document is routed.
+    index: String,
+    #[serde(default)]
+    timestamp_field: Option<String>,
+    #[serde(alias = "commit", default = "IngestOptions::default_commit_type")]
+    commit_type: CommitTypeV2,
+    #[serde(default)]
+    detailed_response: bool,
 }
 
 impl IngestOptions {
@@ -69,23 +96,98 @@ impl IngestOptions {
             CommitTypeV2::WaitFor => CommitType::WaitFor,
         }
     }
+
+    /// Resolves the acknowledgment level achieved by a request with these options, given the
+    /// replication factor of the cluster. This may set the commit type to `wait_for` when the
+    /// client requests documents to be searchable.
+    fn resolve_ack_level(
+        &mut self,
+        replication_factor: usize,
+    ) -> Result<IngestAckLevel, IngestServiceError> {
+        let persisted_ack_level = if replication_factor > 1 {
+            IngestAckLevel::Replicated
+        } else {
+            IngestAckLevel::Leader
+        };
+        let commits = matches!(
+            self.commit_type,
+            CommitTypeV2::WaitFor | CommitTypeV2::Force
+        );
+        let Some(requested_ack_level) = self.ack else {
+            if commits {
+                return Ok(IngestAckLevel::Searchable);
+            }
+            return Ok(persisted_ack_level);
+        };
+        if requested_ack_level == IngestAckLevel::Searchable {
+            if !commits {
+                self.commit_type = CommitTypeV2::WaitFor;
+            }
+            return Ok(IngestAckLevel::Searchable);
+        }
+        if commits {
+            let commit_type = if self.commit_type == CommitTypeV2::Force {
+                "force"
+            } else {
+                "wait_for"
+            };
+            let message = format!(
+                "ack level `{}` cannot be combined with commit type `{commit_type}`, use \
+                 `ack=searchable` instead",
+                requested_ack_level.as_str(),
+            );
+            return Err(IngestServiceError::BadRequest(message));
+        }
+        match requested_ack_level {
+            IngestAckLevel::None => Ok(IngestAckLevel::None),
+            IngestAckLevel::Replicated if replication_factor < 2 => {
+                Err(IngestServiceError::BadRequest(
+                    "ack level `replicated` requires a replication factor of 2".to_string(),
+                ))
+            }
+            // Documents are persisted on the follower before the leader acknowledges them when
+            // replication is enabled, so the achieved level may be higher than the requested one.
+            _ => Ok(persisted_ack_level),
+        }
+    }
+}
+
+/// Services and settings used by the ingest API handlers.
+#[derive(Clone)]
+pub(crate) struct IngestApiContext {
+    pub ingest_router: IngestRouterServiceClient,
+    pub ingest_service: IngestServiceClient,
+    pub write_alias_resolver: WriteAliasResolver,
+    pub rollout_router: RolloutRouter,
+    pub ingestion_freeze_checker: IngestionFreezeChecker,
+    pub config: IngestApiConfig,
+    pub enable_ingest_v1: bool,
+    pub enable_ingest_v2: bool,
 }
 
 pub(crate) fn ingest_api_handlers(
-    ingest_router: IngestRouterServiceClient,
-    ingest_service: IngestServiceClient,
-    config: IngestApiConfig,
-    enable_ingest_v1: bool,
-    enable_ingest_v2: bool,
+    context: IngestApiContext,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    let replication_factor = context
+        .config
+        .replication_factor()
+        .map(|replication_factor| replication_factor.get())
+        .unwrap_or(1);
+    let unacknowledged_memory_budget = Arc::new(Semaphore::new(
+        context.config.max_unacknowledged_memory_usage.as_u64() as usize,
+    ));
     ingest_handler(
-        ingest_router,
-        ingest_service.clone(),
-        config,
-        enable_ingest_v1,
-        enable_ingest_v2,
+        context.clone(),
+        replication_factor,
+        unacknowledged_memory_budget,
     )
-    .or(tail_handler(ingest_service))
+    .or(routed_ingest_handler(
+        context.ingest_router,
+        context.write_alias_resolver,
+        context.config,
+        context.enable_ingest_v2,
+    ))
+    .or(tail_handler(context.ingest_service))
     .boxed()
 }
 
@@ -104,28 +206,22 @@ fn ingest_filter(
 }
 
 fn ingest_handler(
-    ingest_router: IngestRouterServiceClient,
-    ingest_service: IngestServiceClient,
-    config: IngestApiConfig,
-    enable_ingest_v1: bool,
-    enable_ingest_v2: bool,
+    context: IngestApiContext,
+    replication_factor: usize,
+    unacknowledged_memory_budget: Arc<Semaphore>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    ingest_filter(config)
-        .and(with_arg(ingest_router))
-        .and(with_arg(ingest_service))
-        .then(
-            move |index_id, body, ingest_options, ingest_router, ingest_service| {
-                ingest(
-                    index_id,
-                    body,
-                    ingest_options,
-                    ingest_router,
-                    ingest_service,
-                    enable_ingest_v1,
-                    enable_ingest_v2,
-                )
-            },
-        )
+    ingest_filter(context.config.clone())
+        .and(with_arg(context))
+        .then(move |index_id, body, ingest_options, context| {
+            ingest(
+                index_id,
+                body,
+                ingest_options,
+                context,
+                replication_factor,
+                unacknowledged_memory_budget.clone(),
+            )
+        })
         .map(|result| into_rest_api_response(result, BodyFormat::default()))
         .boxed()
 }
@@ -141,26 +237,117 @@ fn ingest_handler(
     params(
         ("index_id" = String, Path, description = "The index ID to add docs to."),
         ("commit" = Option<CommitType>, Query, description = "Force or wait for commit at the end of the indexing operation."),
+        ("ack" = Option<IngestAckLevel>, Query, description = "Acknowledgment level to wait for before responding (ingest v2 only)."),
     )
 )]
 /// Ingest documents
 async fn ingest(
     index_id: IndexId,
     body: Body,
-    ingest_options: IngestOptions,
-    ingest_router: IngestRouterServiceClient,
-    ingest_service: IngestServiceClient,
-    enable_ingest_v1: bool,
-    enable_ingest_v2: bool,
+    mut ingest_options: IngestOptions,
+    context: IngestApiContext,
+    replication_factor: usize,
+    unacknowledged_memory_budget: Arc<Semaphore>,
 ) -> Result<RestIngestResponse, IngestServiceError> {
-    if enable_ingest_v2 && !ingest_options.use_legacy_ingest {
-        return ingest_v2(index_id, body, ingest_options, ingest_router).await;
+    if context.enable_ingest_v2 && !ingest_options.use_legacy_ingest {
+        let ack_level = ingest_options.resolve_ack_level(replication_factor)?;
+        let index_id = context.write_alias_resolver.resolve(index_id);
+        let rollout_candidate_opt = context.rollout_router.sample_ingest(&index_id);
+
+        if ack_level == IngestAckLevel::None {
+            // Errors occurring in the background cannot be returned to the client, so we validate
+            // what we can upfront.
+            if validate_identifier("", &index_id).is_err() {
+                return Err(IngestServiceError::BadRequest(
+                    "invalid index ID".to_string(),
+                ));
+            }
+            // The body is held in memory until the request is ingested, so the requests running in
+            // the background are bounded by a memory budget.
+            let num_permits = u32::try_from(body.content.len()).unwrap_or(u32::MAX);
+            let Ok(memory_permit) =
+                unacknowledged_memory_budget.try_acquire_many_owned(num_permits)
+            else {
+                record_unacknowledged_ingest("rejected");
+                return Err(IngestServiceError::RateLimited(
+                    RateLimitingCause::LoadShedding,
+                ));
+            };
+            let num_docs_for_processing = lines(&body.content).count() as u64;
+            tokio::spawn(async move {
+                let ingest_result = ingest_v2(
+                    index_id.clone(),
+                    body,
+                    ingest_options,
+                    context.ingest_router,
+                    rollout_candidate_opt,
+                )
+                .await;
+                drop(memory_permit);
+                report_unacknowledged_ingest(&index_id, ingest_result);
+            });
+            let response = RestIngestResponse {
+                num_docs_for_processing,
+                ack_level: Some(IngestAckLevel::None),
+                ..Default::default()
+            };
+            return Ok(response);
+        }
+        let mut response = ingest_v2(
+            index_id,
+            body,
+            ingest_options,
+            context.ingest_router,
+            rollout_candidate_opt,
+        )
+        .await?;
+        response.ack_level = Some(ack_level);
+        return Ok(response);
     }
-    if !enable_ingest_v1 {
+    if !context.enable_ingest_v1 {
         let message = "ingest v1 is disabled: environment variable `QW_DISABLE_INGEST_V1` is set";
         return Err(IngestServiceError::Internal(message.to_string()));
     }
-    ingest_v1(index_id, body, ingest_options, ingest_service).await
+    context.ingestion_freeze_checker.check(&index_id).await?;
+    ingest_v1(index_id, body, ingest_options, context.ingest_service).await
+}
+
+/// Logs and counts the outcome of an ingest request that was acknowledged before being ingested,
+/// since its errors cannot be returned to the client.
+fn report_unacknowledged_ingest(
+    index_id: &str,
+    ingest_result: Result<RestIngestResponse, IngestServiceError>,
+) {
+    match ingest_result {
+        Ok(response) if response.num_rejected_docs.unwrap_or_default() == 0 => {
+            record_unacknowledged_ingest("ingested");
+        }
+        Ok(response) => {
+            record_unacknowledged_ingest("failed");
+            rate_limited_warn!(
+                limit_per_min = 10,
+                index_id = index_id,
+                num_rejected_docs = response.num_rejected_docs.unwrap_or_default(),
+                "unacknowledged ingest request rejected documents"
+            );
+        }
+        Err(error) => {
+            record_unacknowledged_ingest("failed");
+            rate_limited_warn!(
+                limit_per_min = 10,
+                index_id = index_id,
+                error = %error,
+                "unacknowledged ingest request failed"
+            );
+        }
+    }
+}
+
+fn record_unacknowledged_ingest(outcome: &str) {
+    SERVE_METRICS
+        .unacknowledged_ingest_requests_total
+        .with_label_values([outcome])
+        .inc();
 }
 
 /// Ingest documents
@@ -175,6 +362,11 @@ async fn ingest_v1(
             "detailed_response is not supported in ingest v1".to_string(),
         ));
     }
+    if ingest_options.ack.is_some() {
+        return Err(IngestServiceError::BadRequest(
+            "ack is not supported in ingest v1".to_string(),
+        ));
+    }
     // The size of the body should be an upper bound of the size of the batch. The removal of the
     // end of line character for each doc compensates the addition of the `DocCommand` header.
     let mut doc_batch_builder = DocBatchBuilder::with_capacity(index_id, body.content.remaining());
@@ -194,6 +386,7 @@ async fn ingest_v2(
     body: Body,
     ingest_options: IngestOptions,
     ingest_router: IngestRouterServiceClient,
+    rollout_candidate_opt: Option<IndexId>,
 ) -> Result<RestIngestResponse, IngestServiceError> {
     let mut doc_batch_builder = DocBatchV2Builder::default();
     let mut doc_uid_generator = DocUidGenerator::default();
@@ -222,7 +415,9 @@ async fn ingest_v2(
             "invalid index ID".to_string(),
         ));
     }
-
+    if let Some(candidate_index_id) = rollout_candidate_opt {
+        mirror_ingest(candidate_index_id, doc_batch.clone(), ingest_router.clone());
+    }
     let subrequest = IngestSubrequest {
         subrequest_id: 0,
         index_id,
@@ -241,6 +436,191 @@ async fn ingest_v2(
     )
 }
 
+fn routed_ingest_filter(
+    config: IngestApiConfig,
+) -> impl Filter<Extract = (Body, RoutedIngestOptions), Error = Rejection> + Clone {
+    warp::path!("_ingest")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            config.content_length_limit.as_u64(),
+        ))
+        .and(get_body_bytes())
+        .and(serde_qs::warp::query::<RoutedIngestOptions>(
+            serde_qs::Config::default(),
+        ))
+}
+
+fn routed_ingest_handler(
+    ingest_router: IngestRouterServiceClient,
+    write_alias_resolver: WriteAliasResolver,
+    config: IngestApiConfig,
+    enable_ingest_v2: bool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    routed_ingest_filter(config)
+        .and(with_arg(ingest_router))
+        .and(with_arg(write_alias_resolver))
+        .then(
+            move |body, routed_ingest_options, ingest_router, write_alias_resolver| {
+                routed_ingest(
+                    body,
+                    routed_ingest_options,
+                    ingest_router,
+                    write_alias_resolver,
+                    enable_ingest_v2,
+                )
+            },
+        )
+        .map(|result| into_rest_api_response(result, BodyFormat::default()))
+        .boxed()
+}
+
+#[utoipa::path(
+    post,
+    tag = "Ingest",
+    path = "/_ingest",
+    request_body(content = String, description = "Documents to ingest in NDJSON format and limited to 10MB", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Successfully ingested documents.", body = RestIngestResponse)
+    ),
+    params(
+        ("index" = String, Query, description = "Template of the index ID to route each document to, for instance `logs-{service}-{yyyy.MM}`."),
+        ("timestamp_field" = Option<String>, Query, description = "Field holding the timestamp used to format the date placeholders of the template."),
+        ("commit" = Option<CommitType>, Query, description = "Force or wait for commit at the end of the indexing operation."),
+    )
+)]
+/// Ingest documents into multiple indexes
+///
+/// Routes each document to the index whose ID results from the rendering of the `index` template
+/// with the fields of the document. Indexes that do not exist are created from the matching index
+/// templates. When the documents of some indexes cannot be ingested, they are reported in
+/// `index_failures` and counted as rejected, while the documents of the other indexes are
+/// ingested.
+async fn routed_ingest(
+    body: Body,
+    routed_ingest_options: RoutedIngestOptions,
+    ingest_router: IngestRouterServiceClient,
+    write_alias_resolver: WriteAliasResolver,
+    enable_ingest_v2: bool,
+) -> Result<RestIngestResponse, IngestServiceError> {
+    if !enable_ingest_v2 {
+        return Err(IngestServiceError::BadRequest(
+            "routing documents to multiple indexes requires ingest v2".to_string(),
+        ));
+    }
+    let index_id_template = IndexIdTemplate::parse(&routed_ingest_options.index)
+        .map_err(|error| IngestServiceError::BadRequest(error.to_string()))?;
+    let detailed_response = routed_ingest_options.detailed_response;
+
+    let mut ingest_request_builder = IngestRequestV2Builder::default();
+    let mut num_docs_for_processing = 0;
+    let mut parse_failures = Vec::new();
+    let mut num_rejected_docs = 0;
+    let mut docs: HashMap<DocUid, &[u8]> = HashMap::new();
+    let mut num_docs_per_subrequest: HashMap<SubrequestId, u64> = HashMap::new();
+
+    for doc in lines(&body.content) {
+        num_docs_for_processing += 1;
+
+        match index_id_template.route(doc, routed_ingest_options.timestamp_field.as_deref()) {
+            Ok(index_id) => {
+                let index_id = write_alias_resolver.resolve(index_id);
+                let (subrequest_id, doc_uid) = ingest_request_builder.add_doc(index_id, doc);
+                *num_docs_per_subrequest.entry(subrequest_id).or_default() += 1;
+
+                if detailed_response {
+                    docs.insert(doc_uid, doc);
+                }
+            }
+            Err((reason, message)) => {
+                num_rejected_docs += 1;
+
+                if detailed_response {
+                    parse_failures.push(RestParseFailure {
+                        message,
+                        document: String::from_utf8_lossy(doc).into_owned(),
+                        reason,
+                    });
+                }
+            }
+        }
+    }
+    let mut response = RestIngestResponse {
+        num_docs_for_processing,
+        num_ingested_docs: Some(0),
+        num_rejected_docs: Some(num_rejected_docs),
+        parse_failures: None,
+        index_failures: None,
+        ack_level: None,
+    };
+    let ingest_request_opt =
+        ingest_request_builder.build(INGEST_V2_SOURCE_ID, routed_ingest_options.commit_type);
+
+    if let Some(ingest_request) = ingest_request_opt {
+        let ingest_response = ingest_router.ingest(ingest_request).await?;
+
+        // When no document could be ingested, the request fails as a whole so that clients can
+        // retry it, for instance when they are rate limited.
+        if ingest_response.successes.is_empty() {
+            if let Some(failure) = ingest_response.failures.into_iter().next() {
+                return Err(failure.into());
+            }
+        }
+        // The documents of the other subrequests are already persisted, so the failed
+        // subrequests are reported along with the successful ones.
+        let mut index_failures = Vec::with_capacity(ingest_response.failures.len());
+
+        for failure in ingest_response.failures {
+            let num_docs = num_docs_per_subrequest
+                .get(&failure.subrequest_id)
+                .copied()
+                .unwrap_or_default();
+            num_rejected_docs += num_docs;
+
+            let index_id = failure.index_id.clone();
+            let reason = failure.reason();
+            let message = IngestServiceError::from(failure).to_string();
+            index_failures.push(RestIndexFailure {
+                index_id,
+                num_docs,
+                reason,
+                message,
+            });
+        }
+        if !index_failures.is_empty() {
+            response.index_failures = Some(index_failures);
+        }
+        let mut num_ingested_docs = 0;
+
+        for success in ingest_response.successes {
+            num_ingested_docs += success.num_ingested_docs as u64;
+            num_rejected_docs += success.parse_failures.len() as u64;
+
+            if !detailed_response {
+                continue;
+            }
+            for parse_failure in success.parse_failures {
+                let doc = docs.get(&parse_failure.doc_uid()).ok_or_else(|| {
+                    IngestServiceError::Internal(format!(
+                        "failed doc_uid {} not found in the original request",
+                        parse_failure.doc_uid()
+                    ))
+                })?;
+                parse_failures.push(RestParseFailure {
+                    reason: parse_failure.reason(),
+                    message: parse_failure.message,
+                    document: String::from_utf8_lossy(doc).into_owned(),
+                });
+            }
+        }
+        response.num_ingested_docs = Some(num_ingested_docs);
+        response.num_rejected_docs = Some(num_rejected_docs);
+    }
+    if detailed_response {
+        response.parse_failures = Some(parse_failures);
+    }
+    Ok(response)
+}
+
 pub fn tail_handler(
     ingest_service: IngestServiceClient,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
@@ -282,7 +662,7 @@ pub(crate) fn lines(body: &Bytes) -> impl Iterator<Item = &[u8]> {
 }
 
 #[inline]
-fn is_empty_or_blank_line(line: &[u8]) -> bool {
+pub(crate) fn is_empty_or_blank_line(line: &[u8]) -> bool {
     line.is_empty() || line.iter().all(|ch| ch.is_ascii_whitespace())
 }
 
@@ -292,16 +672,31 @@ pub(crate) mod tests {
     use std::time::Duration;
 
     use bytes::Bytes;
+    use bytesize::ByteSize;
     use quickwit_actors::{Mailbox, Universe};
-    use quickwit_config::IngestApiConfig;
+    use quickwit_config::{IngestApiConfig, IngestionFreeze};
     use quickwit_ingest::{
         init_ingest_api, CreateQueueIfNotExistsRequest, FetchRequest, FetchResponse,
         IngestApiService, IngestServiceClient, SuggestTruncateRequest, QUEUES_DIR_NAME,
     };
-    use quickwit_proto::ingest::router::IngestRouterServiceClient;
+    use quickwit_metastore::{IndexMetadata, IndexMetadataResponseExt};
+    use quickwit_proto::ingest::router::{
+        IngestFailure, IngestFailureReason, IngestRequestV2, IngestResponseV2,
+        IngestRouterServiceClient, IngestSuccess, MockIngestRouterService,
+    };
+    use quickwit_proto::ingest::CommitTypeV2;
+    use quickwit_proto::metastore::{
+        IndexMetadataResponse, MetastoreServiceClient, MockMetastoreService,
+    };
+    use quickwit_proto::types::{IndexUid, ShardId};
 
-    use super::{ingest_api_handlers, RestIngestResponse};
+    use super::{
+        ingest_api_handlers, report_unacknowledged_ingest, IngestAckLevel, IngestApiContext,
+        IngestServiceError, IngestionFreezeChecker, RestIngestResponse, RolloutRouter,
+        WriteAliasResolver,
+    };
     use crate::ingest_api::lines;
+    use crate::SERVE_METRICS;
 
     #[test]
     fn test_process_lines() {
@@ -354,13 +749,16 @@ pub(crate) mod tests {
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_v1_service(&["my-index"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let ingest_api_handlers = ingest_api_handlers(
+        let ingest_api_handlers = ingest_api_handlers(IngestApiContext {
             ingest_router,
             ingest_service,
-            IngestApiConfig::default(),
-            true,
-            false,
-        );
+            write_alias_resolver: WriteAliasResolver::default(),
+            rollout_router: RolloutRouter::default(),
+            ingestion_freeze_checker: IngestionFreezeChecker::default(),
+            config: IngestApiConfig::default(),
+            enable_ingest_v1: true,
+            enable_ingest_v2: false,
+        });
         let resp = warp::test::request()
             .path("/my-index/ingest")
             .method("POST")
@@ -390,18 +788,57 @@ pub(crate) mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_ingest_api_v1_returns_409_when_ingestion_is_frozen() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_v1_service(&["my-index"], &IngestApiConfig::default()).await;
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_index_metadata().returning(|_| {
+            let mut index_metadata = IndexMetadata::for_test("my-index", "ram:///indexes/my-index");
+            index_metadata.index_config.ingestion_freeze_opt = Some(IngestionFreeze {
+                reason: "incident".to_string(),
+                frozen_at: 1_700_000_000,
+            });
+            Ok(IndexMetadataResponse::try_from_index_metadata(&index_metadata).unwrap())
+        });
+        let ingest_api_handlers = ingest_api_handlers(IngestApiContext {
+            ingest_router: IngestRouterServiceClient::mocked(),
+            ingest_service,
+            write_alias_resolver: WriteAliasResolver::default(),
+            rollout_router: RolloutRouter::default(),
+            ingestion_freeze_checker: IngestionFreezeChecker::new(
+                MetastoreServiceClient::from_mock(mock_metastore),
+            ),
+            config: IngestApiConfig::default(),
+            enable_ingest_v1: true,
+            enable_ingest_v2: false,
+        });
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .body(r#"{"id": 1, "message": "push"}"#)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 409);
+
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_ingest_api_returns_200_when_ingest_ndjson_and_fetch() {
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_v1_service(&["my-index"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let ingest_api_handlers = ingest_api_handlers(
+        let ingest_api_handlers = ingest_api_handlers(IngestApiContext {
             ingest_router,
             ingest_service,
-            IngestApiConfig::default(),
-            true,
-            false,
-        );
+            write_alias_resolver: WriteAliasResolver::default(),
+            rollout_router: RolloutRouter::default(),
+            ingestion_freeze_checker: IngestionFreezeChecker::default(),
+            config: IngestApiConfig::default(),
+            enable_ingest_v1: true,
+            enable_ingest_v2: false,
+        });
         let payload = r#"
             {"id": 1, "message": "push"}
             {"id": 2, "message": "push"}
@@ -426,13 +863,16 @@ pub(crate) mod tests {
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_v1_service(&["my-index"], &config).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let ingest_api_handlers = ingest_api_handlers(
+        let ingest_api_handlers = ingest_api_handlers(IngestApiContext {
             ingest_router,
             ingest_service,
-            IngestApiConfig::default(),
-            true,
-            false,
-        );
+            write_alias_resolver: WriteAliasResolver::default(),
+            rollout_router: RolloutRouter::default(),
+            ingestion_freeze_checker: IngestionFreezeChecker::default(),
+            config: IngestApiConfig::default(),
+            enable_ingest_v1: true,
+            enable_ingest_v2: false,
+        });
         let resp = warp::test::request()
             .path("/my-index/ingest")
             .method("POST")
@@ -451,8 +891,16 @@ pub(crate) mod tests {
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_v1_service(&["my-index"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let ingest_api_handlers =
-            ingest_api_handlers(ingest_router, ingest_service, config.clone(), true, false);
+        let ingest_api_handlers = ingest_api_handlers(IngestApiContext {
+            ingest_router,
+            ingest_service,
+            write_alias_resolver: WriteAliasResolver::default(),
+            rollout_router: RolloutRouter::default(),
+            ingestion_freeze_checker: IngestionFreezeChecker::default(),
+            config: config.clone(),
+            enable_ingest_v1: true,
+            enable_ingest_v2: false,
+        });
         let resp = warp::test::request()
             .path("/my-index/ingest")
             .method("POST")
@@ -469,13 +917,16 @@ pub(crate) mod tests {
         let (universe, _temp_dir, ingest_service_client, ingest_service_mailbox) =
             setup_ingest_v1_service(&["my-index"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let ingest_api_handlers = ingest_api_handlers(
+        let ingest_api_handlers = ingest_api_handlers(IngestApiContext {
             ingest_router,
-            ingest_service_client,
-            IngestApiConfig::default(),
-            true,
-            false,
-        );
+            ingest_service: ingest_service_client,
+            write_alias_resolver: WriteAliasResolver::default(),
+            rollout_router: RolloutRouter::default(),
+            ingestion_freeze_checker: IngestionFreezeChecker::default(),
+            config: IngestApiConfig::default(),
+            enable_ingest_v1: true,
+            enable_ingest_v2: false,
+        });
         let handle = tokio::spawn(async move {
             let resp = warp::test::request()
                 .path("/my-index/ingest?commit=wait_for")
@@ -520,13 +971,16 @@ pub(crate) mod tests {
         let (universe, _temp_dir, ingest_service_client, ingest_service_mailbox) =
             setup_ingest_v1_service(&["my-index"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let ingest_api_handlers = ingest_api_handlers(
+        let ingest_api_handlers = ingest_api_handlers(IngestApiContext {
             ingest_router,
-            ingest_service_client,
-            IngestApiConfig::default(),
-            true,
-            false,
-        );
+            ingest_service: ingest_service_client,
+            write_alias_resolver: WriteAliasResolver::default(),
+            rollout_router: RolloutRouter::default(),
+            ingestion_freeze_checker: IngestionFreezeChecker::default(),
+            config: IngestApiConfig::default(),
+            enable_ingest_v1: true,
+            enable_ingest_v2: false,
+        });
         let handle = tokio::spawn(async move {
             let resp = warp::test::request()
                 .path("/my-index/ingest?commit=force")
@@ -571,13 +1025,16 @@ pub(crate) mod tests {
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_v1_service(&["my-index"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let ingest_api_handlers = ingest_api_handlers(
+        let ingest_api_handlers = ingest_api_handlers(IngestApiContext {
             ingest_router,
             ingest_service,
-            IngestApiConfig::default(),
-            true,
-            false,
-        );
+            write_alias_resolver: WriteAliasResolver::default(),
+            rollout_router: RolloutRouter::default(),
+            ingestion_freeze_checker: IngestionFreezeChecker::default(),
+            config: IngestApiConfig::default(),
+            enable_ingest_v1: true,
+            enable_ingest_v2: false,
+        });
         let resp = warp::test::request()
             .path("/my-index/ingest?detailed_response=true")
             .method("POST")
@@ -588,4 +1045,312 @@ pub(crate) mod tests {
         assert_eq!(resp.status(), 400);
         universe.assert_quit().await;
     }
+
+    #[tokio::test]
+    async fn test_routed_ingest_api() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_v1_service(&[], &IngestApiConfig::default()).await;
+        let mut mock_ingest_router = MockIngestRouterService::new();
+        mock_ingest_router
+            .expect_ingest()
+            .once()
+            .returning(|ingest_request| {
+                let mut subrequests = ingest_request.subrequests;
+                subrequests.sort_by(|left, right| left.index_id.cmp(&right.index_id));
+
+                assert_eq!(subrequests.len(), 2);
+                assert_eq!(subrequests[0].index_id, "logs-api-2024.03");
+                assert_eq!(subrequests[0].doc_batch.as_ref().unwrap().num_docs(), 2);
+                assert_eq!(subrequests[1].index_id, "logs-web-2024.03");
+                assert_eq!(subrequests[1].doc_batch.as_ref().unwrap().num_docs(), 1);
+
+                let successes = subrequests
+                    .into_iter()
+                    .map(|subrequest| IngestSuccess {
+                        subrequest_id: subrequest.subrequest_id,
+                        index_uid: Some(IndexUid::for_test(&subrequest.index_id, 0)),
+                        source_id: subrequest.source_id,
+                        shard_id: Some(ShardId::from(1)),
+                        replication_position_inclusive: None,
+                        num_ingested_docs: subrequest.doc_batch.unwrap().num_docs() as u32,
+                        parse_failures: Vec::new(),
+                    })
+                    .collect();
+                Ok(IngestResponseV2 {
+                    successes,
+                    failures: Vec::new(),
+                })
+            });
+        let ingest_router = IngestRouterServiceClient::from_mock(mock_ingest_router);
+        let ingest_api_handlers = ingest_api_handlers(IngestApiContext {
+            ingest_router,
+            ingest_service,
+            write_alias_resolver: WriteAliasResolver::default(),
+            rollout_router: RolloutRouter::default(),
+            ingestion_freeze_checker: IngestionFreezeChecker::default(),
+            config: IngestApiConfig::default(),
+            enable_ingest_v1: false,
+            enable_ingest_v2: true,
+        });
+        let payload = r#"
+            {"service": "api", "ts": "2024-03-07T12:00:00Z"}
+            {"service": "web", "ts": "2024-03-08T12:00:00Z"}
+            {"service": "api", "ts": "2024-03-09T12:00:00Z"}
+            {"ts": "2024-03-09T12:00:00Z"}
+        "#;
+        // `logs-{service}-{yyyy.MM}`
+        let index_id_template = "logs-%7Bservice%7D-%7Byyyy.MM%7D";
+        let resp = warp::test::request()
+            .path(&format!(
+                "/_ingest?index={index_id_template}&timestamp_field=ts&detailed_response=true"
+            ))
+            .method("POST")
+            .body(payload)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let ingest_response: RestIngestResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(ingest_response.num_docs_for_processing, 4);
+        assert_eq!(ingest_response.num_ingested_docs, Some(3));
+        assert_eq!(ingest_response.num_rejected_docs, Some(1));
+
+        let parse_failures = ingest_response.parse_failures.unwrap();
+        assert_eq!(parse_failures.len(), 1);
+        assert!(parse_failures[0]
+            .message
+            .contains("routing field `service` is missing"));
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_routed_ingest_api_partial_failure() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_v1_service(&[], &IngestApiConfig::default()).await;
+        let mut mock_ingest_router = MockIngestRouterService::new();
+        mock_ingest_router
+            .expect_ingest()
+            .once()
+            .returning(|ingest_request| {
+                let mut successes = Vec::new();
+                let mut failures = Vec::new();
+
+                for subrequest in ingest_request.subrequests {
+                    if subrequest.index_id == "logs-web" {
+                        failures.push(IngestFailure {
+                            subrequest_id: subrequest.subrequest_id,
+                            index_id: subrequest.index_id,
+                            source_id: subrequest.source_id,
+                            reason: IngestFailureReason::IndexFrozen as i32,
+                        });
+                        continue;
+                    }
+                    successes.push(IngestSuccess {
+                        subrequest_id: subrequest.subrequest_id,
+                        index_uid: Some(IndexUid::for_test(&subrequest.index_id, 0)),
+                        source_id: subrequest.source_id,
+                        shard_id: Some(ShardId::from(1)),
+                        replication_position_inclusive: None,
+                        num_ingested_docs: subrequest.doc_batch.unwrap().num_docs() as u32,
+                        parse_failures: Vec::new(),
+                    });
+                }
+                Ok(IngestResponseV2 {
+                    successes,
+                    failures,
+                })
+            });
+        let ingest_router = IngestRouterServiceClient::from_mock(mock_ingest_router);
+        let ingest_api_handlers = ingest_api_handlers(IngestApiContext {
+            ingest_router,
+            ingest_service,
+            write_alias_resolver: WriteAliasResolver::default(),
+            rollout_router: RolloutRouter::default(),
+            ingestion_freeze_checker: IngestionFreezeChecker::default(),
+            config: IngestApiConfig::default(),
+            enable_ingest_v1: false,
+            enable_ingest_v2: true,
+        });
+        let payload = r#"
+            {"service": "api"}
+            {"service": "web"}
+            {"service": "web"}
+        "#;
+        // `logs-{service}`
+        let resp = warp::test::request()
+            .path("/_ingest?index=logs-%7Bservice%7D")
+            .method("POST")
+            .body(payload)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let ingest_response: RestIngestResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(ingest_response.num_docs_for_processing, 3);
+        assert_eq!(ingest_response.num_ingested_docs, Some(1));
+        assert_eq!(ingest_response.num_rejected_docs, Some(2));
+
+        let index_failures = ingest_response.index_failures.unwrap();
+        assert_eq!(index_failures.len(), 1);
+        assert_eq!(index_failures[0].index_id, "logs-web");
+        assert_eq!(index_failures[0].num_docs, 2);
+        assert_eq!(index_failures[0].reason, IngestFailureReason::IndexFrozen);
+        universe.assert_quit().await;
+    }
+
+    fn ingest_success_response(ingest_request: IngestRequestV2) -> IngestResponseV2 {
+        let successes = ingest_request
+            .subrequests
+            .into_iter()
+            .map(|subrequest| IngestSuccess {
+                subrequest_id: subrequest.subrequest_id,
+                index_uid: Some(IndexUid::for_test(&subrequest.index_id, 0)),
+                source_id: subrequest.source_id,
+                shard_id: Some(ShardId::from(1)),
+                replication_position_inclusive: None,
+                num_ingested_docs: subrequest.doc_batch.unwrap().num_docs() as u32,
+                parse_failures: Vec::new(),
+            })
+            .collect();
+        IngestResponseV2 {
+            successes,
+            failures: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_ack_levels() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_v1_service(&[], &IngestApiConfig::default()).await;
+        let (ingest_request_tx, mut ingest_request_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut mock_ingest_router = MockIngestRouterService::new();
+        mock_ingest_router
+            .expect_ingest()
+            .times(3)
+            .returning(move |ingest_request| {
+                ingest_request_tx
+                    .send(ingest_request.commit_type())
+                    .unwrap();
+                Ok(ingest_success_response(ingest_request))
+            });
+        let ingest_router = IngestRouterServiceClient::from_mock(mock_ingest_router);
+        let ingest_api_handlers = ingest_api_handlers(IngestApiContext {
+            ingest_router,
+            ingest_service,
+            write_alias_resolver: WriteAliasResolver::default(),
+            rollout_router: RolloutRouter::default(),
+            ingestion_freeze_checker: IngestionFreezeChecker::default(),
+            config: IngestApiConfig::default(),
+            enable_ingest_v1: false,
+            enable_ingest_v2: true,
+        });
+        let payload = r#"{"id": 1, "message": "push"}"#;
+
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .body(payload)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let ingest_response: RestIngestResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(ingest_response.num_ingested_docs, Some(1));
+        assert_eq!(ingest_response.ack_level, Some(IngestAckLevel::Leader));
+        assert_eq!(ingest_request_rx.recv().await.unwrap(), CommitTypeV2::Auto);
+
+        let resp = warp::test::request()
+            .path("/my-index/ingest?ack=searchable")
+            .method("POST")
+            .body(payload)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let ingest_response: RestIngestResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(ingest_response.ack_level, Some(IngestAckLevel::Searchable));
+        assert_eq!(
+            ingest_request_rx.recv().await.unwrap(),
+            CommitTypeV2::WaitFor
+        );
+
+        let resp = warp::test::request()
+            .path("/my-index/ingest?ack=none")
+            .method("POST")
+            .body(payload)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let ingest_response: RestIngestResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(ingest_response.num_docs_for_processing, 1);
+        assert_eq!(ingest_response.num_ingested_docs, None);
+        assert_eq!(ingest_response.ack_level, Some(IngestAckLevel::None));
+        // The request is still ingested in the background.
+        assert_eq!(ingest_request_rx.recv().await.unwrap(), CommitTypeV2::Auto);
+
+        let resp = warp::test::request()
+            .path("/my-index/ingest?ack=replicated")
+            .method("POST")
+            .body(payload)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+        assert!(str::from_utf8(resp.body())
+            .unwrap()
+            .contains("requires a replication factor of 2"));
+
+        let resp = warp::test::request()
+            .path("/my-index/ingest?ack=leader&commit=wait_for")
+            .method("POST")
+            .body(payload)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_ack_none_rejects_requests_exceeding_memory_budget() {
+        let ingest_service = IngestServiceClient::mocked();
+        let ingest_router = IngestRouterServiceClient::mocked();
+        let ingest_config = IngestApiConfig {
+            max_unacknowledged_memory_usage: ByteSize::b(16),
+            ..Default::default()
+        };
+        let ingest_api_handlers = ingest_api_handlers(IngestApiContext {
+            ingest_router,
+            ingest_service,
+            write_alias_resolver: WriteAliasResolver::default(),
+            rollout_router: RolloutRouter::default(),
+            ingestion_freeze_checker: IngestionFreezeChecker::default(),
+            config: ingest_config,
+            enable_ingest_v1: false,
+            enable_ingest_v2: true,
+        });
+        let resp = warp::test::request()
+            .path("/my-index/ingest?ack=none")
+            .method("POST")
+            .body(r#"{"id": 1, "message": "push"}"#)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 429);
+    }
+
+    #[test]
+    fn test_report_unacknowledged_ingest() {
+        let num_failed_requests = || {
+            SERVE_METRICS
+                .unacknowledged_ingest_requests_total
+                .with_label_values(["failed"])
+                .get()
+        };
+        let num_failed_requests_before = num_failed_requests();
+        report_unacknowledged_ingest(
+            "my-index",
+            Err(IngestServiceError::Internal("no shards".to_string())),
+        );
+        let rejected_docs_response = RestIngestResponse {
+            num_rejected_docs: Some(1),
+            ..Default::default()
+        };
+        report_unacknowledged_ingest("my-index", Ok(rejected_docs_response));
+        // Other tests running concurrently may record failures as well.
+        assert!(num_failed_requests() >= num_failed_requests_before + 2);
+    }
 }
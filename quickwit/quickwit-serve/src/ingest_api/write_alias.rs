@@ -0,0 +1,203 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, Weak};
+use std::time::Duration;
+
+use quickwit_config::{IndexTemplate, RolloverPolicy};
+use quickwit_metastore::ListIndexesMetadataResponseExt;
+use quickwit_proto::metastore::{
+    serde_utils, ListIndexTemplatesRequest, ListIndexesMetadataRequest, MetastoreService,
+    MetastoreServiceClient,
+};
+use quickwit_proto::types::IndexId;
+use tracing::error;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+type WriteAliases = HashMap<String, IndexId>;
+
+/// Resolves the write aliases of the index templates with a rollover policy to the latest
+/// generation of the alias, so that documents ingested into `logs` land in `logs-000042`.
+///
+/// The mapping is refreshed periodically from the metastore; index IDs that are not write aliases
+/// are left untouched.
+#[derive(Clone, Default)]
+pub(crate) struct WriteAliasResolver {
+    write_aliases: Arc<RwLock<WriteAliases>>,
+}
+
+impl WriteAliasResolver {
+    /// Creates a resolver and spawns a task that refreshes its mapping until the resolver is
+    /// dropped.
+    pub fn spawn(metastore: MetastoreServiceClient) -> Self {
+        let write_alias_resolver = Self::default();
+        let weak_write_aliases = Arc::downgrade(&write_alias_resolver.write_aliases);
+        tokio::spawn(refresh_loop(metastore, weak_write_aliases));
+        write_alias_resolver
+    }
+
+    #[cfg(test)]
+    pub fn for_test(write_aliases: &[(&str, &str)]) -> Self {
+        let write_aliases = write_aliases
+            .iter()
+            .map(|(write_alias, index_id)| (write_alias.to_string(), index_id.to_string()))
+            .collect();
+        Self {
+            write_aliases: Arc::new(RwLock::new(write_aliases)),
+        }
+    }
+
+    pub fn resolve(&self, index_id: IndexId) -> IndexId {
+        let write_aliases = self
+            .write_aliases
+            .read()
+            .expect("lock should not be poisoned");
+
+        if let Some(latest_index_id) = write_aliases.get(&index_id) {
+            return latest_index_id.clone();
+        }
+        index_id
+    }
+}
+
+async fn refresh_loop(
+    metastore: MetastoreServiceClient,
+    weak_write_aliases: Weak<RwLock<WriteAliases>>,
+) {
+    let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let Some(write_aliases) = weak_write_aliases.upgrade() else {
+            return;
+        };
+        match fetch_write_aliases(&metastore).await {
+            Ok(new_write_aliases) => {
+                *write_aliases.write().expect("lock should not be poisoned") = new_write_aliases;
+            }
+            Err(error) => {
+                error!(%error, "failed to refresh write aliases");
+            }
+        }
+    }
+}
+
+async fn fetch_write_aliases(metastore: &MetastoreServiceClient) -> anyhow::Result<WriteAliases> {
+    let list_index_templates_response = metastore
+        .list_index_templates(ListIndexTemplatesRequest {})
+        .await?;
+    let mut rollover_policies: Vec<RolloverPolicy> = Vec::new();
+
+    for index_template_json in &list_index_templates_response.index_templates_json {
+        let index_template: IndexTemplate = serde_utils::from_json_str(index_template_json)?;
+
+        if let Some(rollover_policy) = index_template.rollover_policy_opt {
+            rollover_policies.push(rollover_policy);
+        }
+    }
+    if rollover_policies.is_empty() {
+        return Ok(WriteAliases::new());
+    }
+    let list_indexes_metadata_request = ListIndexesMetadataRequest {
+        index_id_patterns: rollover_policies
+            .iter()
+            .map(|rollover_policy| format!("{}-*", rollover_policy.write_alias))
+            .collect(),
+        include_trashed: false,
+        label_selectors: Vec::new(),
+    };
+    let indexes_metadata = metastore
+        .list_indexes_metadata(list_indexes_metadata_request)
+        .await?
+        .deserialize_indexes_metadata()
+        .await?;
+    Ok(build_write_aliases(
+        &rollover_policies,
+        indexes_metadata
+            .iter()
+            .map(|index_metadata| index_metadata.index_id()),
+    ))
+}
+
+fn build_write_aliases<'a>(
+    rollover_policies: &[RolloverPolicy],
+    index_ids: impl Iterator<Item = &'a str> + Clone,
+) -> WriteAliases {
+    let mut write_aliases = WriteAliases::new();
+
+    for rollover_policy in rollover_policies {
+        let latest_index_id_opt = index_ids
+            .clone()
+            .filter_map(|index_id| {
+                let generation = rollover_policy.parse_generation(index_id)?;
+                Some((generation, index_id))
+            })
+            .max_by_key(|(generation, _)| *generation)
+            .map(|(_, index_id)| index_id.to_string());
+
+        if let Some(latest_index_id) = latest_index_id_opt {
+            write_aliases.insert(rollover_policy.write_alias.clone(), latest_index_id);
+        }
+    }
+    write_aliases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rollover_policy(write_alias: &str) -> RolloverPolicy {
+        RolloverPolicy {
+            write_alias: write_alias.to_string(),
+            max_age: Some("1d".to_string()),
+            max_size: None,
+            max_num_docs: None,
+        }
+    }
+
+    #[test]
+    fn test_build_write_aliases() {
+        let rollover_policies = [rollover_policy("logs"), rollover_policy("traces")];
+        let index_ids = [
+            "logs-000001",
+            "logs-000010",
+            "logs-app-000042",
+            "other-000003",
+        ];
+        let write_aliases = build_write_aliases(&rollover_policies, index_ids.iter().copied());
+
+        assert_eq!(write_aliases.len(), 1);
+        assert_eq!(write_aliases["logs"], "logs-000010");
+    }
+
+    #[test]
+    fn test_write_alias_resolver_resolve() {
+        let write_alias_resolver = WriteAliasResolver::default();
+        assert_eq!(write_alias_resolver.resolve("logs".to_string()), "logs");
+
+        write_alias_resolver
+            .write_aliases
+            .write()
+            .unwrap()
+            .insert("logs".to_string(), "logs-000002".to_string());
+        assert_eq!(
+            write_alias_resolver.resolve("logs".to_string()),
+            "logs-000002"
+        );
+        assert_eq!(write_alias_resolver.resolve("other".to_string()), "other");
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
 
 use hyper::StatusCode;
@@ -22,6 +23,7 @@ use quickwit_proto::jaeger::storage::v1::{
     FindTracesRequest, GetOperationsRequest, GetServicesRequest, GetTraceRequest,
     SpansResponseChunk, TraceQueryParameters,
 };
+use quickwit_proto::search::SecurityContext;
 use quickwit_proto::tonic;
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
@@ -30,6 +32,7 @@ use warp::{Filter, Rejection};
 
 use super::model::build_jaeger_traces;
 use super::parse_duration::{parse_duration_with_units, to_well_known_timestamp};
+use crate::authentication::{extract_security_context, Authenticator};
 use crate::jaeger_api::model::{
     JaegerError, JaegerResponseBody, JaegerSpan, JaegerTrace, TracesSearchQueryParams,
     DEFAULT_NUMBER_OF_TRACES,
@@ -55,13 +58,18 @@ pub(crate) struct JaegerApi;
 /// Request are executed on the `otel-traces-v0_*` indexes.
 pub(crate) fn jaeger_api_handlers(
     jaeger_service_opt: Option<JaegerService>,
+    authenticator: Arc<Authenticator>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    jaeger_services_handler(jaeger_service_opt.clone())
+    jaeger_services_handler(jaeger_service_opt.clone(), authenticator.clone())
         .or(jaeger_service_operations_handler(
             jaeger_service_opt.clone(),
+            authenticator.clone(),
         ))
-        .or(jaeger_traces_search_handler(jaeger_service_opt.clone()))
-        .or(jaeger_traces_handler(jaeger_service_opt.clone()))
+        .or(jaeger_traces_search_handler(
+            jaeger_service_opt.clone(),
+            authenticator.clone(),
+        ))
+        .or(jaeger_traces_handler(jaeger_service_opt, authenticator))
         .recover(recover_fn)
         .boxed()
 }
@@ -85,9 +93,11 @@ fn jaeger_api_path_filter() -> impl Filter<Extract = (Vec<String>,), Error = Rej
 )]
 pub fn jaeger_services_handler(
     jaeger_service_opt: Option<JaegerService>,
+    authenticator: Arc<Authenticator>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     jaeger_api_path_filter()
         .and(warp::path!("services"))
+        .and(extract_security_context(authenticator))
         .and(require(jaeger_service_opt))
         .then(jaeger_services)
         .map(|result| make_jaeger_api_response(result, BodyFormat::default()))
@@ -107,9 +117,11 @@ pub fn jaeger_services_handler(
 )]
 pub fn jaeger_service_operations_handler(
     jaeger_service_opt: Option<JaegerService>,
+    authenticator: Arc<Authenticator>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     jaeger_api_path_filter()
         .and(warp::path!("services" / String / "operations"))
+        .and(extract_security_context(authenticator))
         .and(require(jaeger_service_opt))
         .then(jaeger_service_operations)
         .map(|result| make_jaeger_api_response(result, BodyFormat::default()))
@@ -136,10 +148,12 @@ pub fn jaeger_service_operations_handler(
 )]
 pub fn jaeger_traces_search_handler(
     jaeger_service_opt: Option<JaegerService>,
+    authenticator: Arc<Authenticator>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     jaeger_api_path_filter()
         .and(warp::path!("traces"))
         .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(extract_security_context(authenticator))
         .and(require(jaeger_service_opt))
         .then(jaeger_traces_search)
         .map(|result| make_jaeger_api_response(result, BodyFormat::default()))
@@ -159,10 +173,12 @@ pub fn jaeger_traces_search_handler(
 )]
 pub fn jaeger_traces_handler(
     jaeger_service_opt: Option<JaegerService>,
+    authenticator: Arc<Authenticator>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     jaeger_api_path_filter()
         .and(warp::path!("traces" / String))
         .and(warp::get())
+        .and(extract_security_context(authenticator))
         .and(require(jaeger_service_opt))
         .then(jaeger_get_trace_by_id)
         .map(|result| make_jaeger_api_response(result, BodyFormat::default()))
@@ -170,10 +186,15 @@ pub fn jaeger_traces_handler(
 
 async fn jaeger_services(
     index_id_patterns: Vec<String>,
+    security_context_opt: Option<SecurityContext>,
     jaeger_service: JaegerService,
 ) -> Result<JaegerResponseBody<Vec<String>>, JaegerError> {
     let get_services_response = jaeger_service
-        .get_services_for_indexes(GetServicesRequest {}, index_id_patterns)
+        .get_services_for_indexes(
+            GetServicesRequest {},
+            index_id_patterns,
+            security_context_opt,
+        )
         .await
         .map_err(|error| JaegerError {
             status: StatusCode::INTERNAL_SERVER_ERROR,
@@ -187,6 +208,7 @@ async fn jaeger_services(
 async fn jaeger_service_operations(
     index_id_patterns: Vec<String>,
     service_name: String,
+    security_context_opt: Option<SecurityContext>,
     jaeger_service: JaegerService,
 ) -> Result<JaegerResponseBody<Vec<String>>, JaegerError> {
     let get_operations_request = GetOperationsRequest {
@@ -194,7 +216,11 @@ async fn jaeger_service_operations(
         span_kind: "".to_string(),
     };
     let get_operations_response = jaeger_service
-        .get_operations_for_indexes(get_operations_request, index_id_patterns)
+        .get_operations_for_indexes(
+            get_operations_request,
+            index_id_patterns,
+            security_context_opt,
+        )
         .await
         .map_err(|error| JaegerError {
             status: StatusCode::INTERNAL_SERVER_ERROR,
@@ -212,6 +238,7 @@ async fn jaeger_service_operations(
 async fn jaeger_traces_search(
     index_id_patterns: Vec<String>,
     search_params: TracesSearchQueryParams,
+    security_context_opt: Option<SecurityContext>,
     jaeger_service: JaegerService,
 ) -> Result<JaegerResponseBody<Vec<JaegerTrace>>, JaegerError> {
     let duration_min = search_params
@@ -262,6 +289,7 @@ async fn jaeger_traces_search(
             Instant::now(),
             index_id_patterns,
             true,
+            security_context_opt,
         )
         .await
         .map_err(|error| {
@@ -293,6 +321,7 @@ async fn collect_and_build_jaeger_spans(
 async fn jaeger_get_trace_by_id(
     index_id_patterns: Vec<String>,
     trace_id_string: String,
+    security_context_opt: Option<SecurityContext>,
     jaeger_service: JaegerService,
 ) -> Result<JaegerResponseBody<Vec<JaegerTrace>>, JaegerError> {
     let trace_id = hex::decode(trace_id_string.clone()).map_err(|error| {
@@ -310,6 +339,7 @@ async fn jaeger_get_trace_by_id(
                 "get_trace",
                 Instant::now(),
                 index_id_patterns,
+                security_context_opt,
             )
             .await
             .map_err(|error| {
@@ -352,7 +382,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_when_jaeger_not_found() {
-        let jaeger_api_handler = jaeger_api_handlers(None).recover(crate::rest::recover_fn_final);
+        let jaeger_api_handler =
+            jaeger_api_handlers(None, Arc::default()).recover(crate::rest::recover_fn_final);
         let resp = warp::test::request()
             .path("/otel-traces-v0_9/jaeger/api/services")
             .reply(&jaeger_api_handler)
@@ -384,7 +415,8 @@ mod tests {
         let mock_search_service = Arc::new(mock_search_service);
         let jaeger = JaegerService::new(JaegerConfig::default(), mock_search_service);
 
-        let jaeger_api_handler = jaeger_api_handlers(Some(jaeger)).recover(recover_fn);
+        let jaeger_api_handler =
+            jaeger_api_handlers(Some(jaeger), Arc::default()).recover(recover_fn);
         let resp = warp::test::request()
             .path("/otel-traces-v0_9/jaeger/api/services")
             .reply(&jaeger_api_handler)
@@ -420,7 +452,8 @@ mod tests {
             });
         let mock_search_service = Arc::new(mock_search_service);
         let jaeger = JaegerService::new(JaegerConfig::default(), mock_search_service);
-        let jaeger_api_handler = jaeger_api_handlers(Some(jaeger)).recover(recover_fn);
+        let jaeger_api_handler =
+            jaeger_api_handlers(Some(jaeger), Arc::default()).recover(recover_fn);
         let resp = warp::test::request()
             .path("/otel-traces-v0_9/jaeger/api/services/service1/operations")
             .reply(&jaeger_api_handler)
@@ -477,11 +510,15 @@ mod tests {
                     scroll_id: None,
                     failed_splits: Vec::new(),
                     num_successful_splits: 1,
+                    profile: None,
+                    warnings: Vec::new(),
+                    intermediate_aggregation_result: None,
                 })
             });
         let mock_search_service = Arc::new(mock_search_service);
         let jaeger = JaegerService::new(JaegerConfig::default(), mock_search_service);
-        let jaeger_api_handler = jaeger_api_handlers(Some(jaeger)).recover(recover_fn);
+        let jaeger_api_handler =
+            jaeger_api_handlers(Some(jaeger), Arc::default()).recover(recover_fn);
         let resp = warp::test::request()
             .path(
                 "/otel-traces-v0_9/jaeger/api/traces?service=quickwit&\
@@ -510,12 +547,16 @@ mod tests {
                     scroll_id: None,
                     failed_splits: Vec::new(),
                     num_successful_splits: 1,
+                    profile: None,
+                    warnings: Vec::new(),
+                    intermediate_aggregation_result: None,
                 })
             });
         let mock_search_service = Arc::new(mock_search_service);
         let jaeger = JaegerService::new(JaegerConfig::default(), mock_search_service);
 
-        let jaeger_api_handler = jaeger_api_handlers(Some(jaeger)).recover(recover_fn);
+        let jaeger_api_handler =
+            jaeger_api_handlers(Some(jaeger), Arc::default()).recover(recover_fn);
         let resp = warp::test::request()
             .path("/otel-traces-v0_9/jaeger/api/traces/1506026ddd216249555653218dc88a6c")
             .reply(&jaeger_api_handler)
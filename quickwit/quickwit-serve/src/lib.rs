@@ -14,6 +14,7 @@
 
 #![recursion_limit = "256"]
 
+mod authentication;
 mod build_info;
 mod cluster_api;
 mod decompression;
@@ -33,20 +34,27 @@ mod metrics_api;
 mod node_info_handler;
 mod openapi;
 mod otlp_api;
+#[cfg(feature = "plugins")]
+mod plugin;
 mod rate_modulator;
+mod request_sampling;
+mod request_tracing;
 mod rest;
 mod rest_api_response;
+mod rollout;
 mod search_api;
+mod shadowing;
 pub(crate) mod simple_list;
 pub mod tcp_listener;
 mod template_api;
 mod ui_handler;
+mod usage_sampling;
 
 use std::collections::{HashMap, HashSet};
 use std::convert::Infallible;
 use std::fs;
 use std::net::SocketAddr;
-use std::num::NonZeroUsize;
+use std::num::{NonZeroU32, NonZeroUsize};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -106,8 +114,8 @@ use quickwit_proto::metastore::{
 use quickwit_proto::search::ReportSplitsRequest;
 use quickwit_proto::types::NodeId;
 use quickwit_search::{
-    create_search_client_from_channel, start_searcher_service, SearchJobPlacer, SearchService,
-    SearchServiceClient, SearcherContext, SearcherPool,
+    create_search_client_from_channel, start_searcher_service, RemoteClusters, RunningSearches,
+    SearchJobPlacer, SearchService, SearchServiceClient, SearcherContext, SearcherPool,
 };
 use quickwit_storage::{SplitCache, StorageResolver};
 use tcp_listener::TcpListenerResolver;
@@ -121,8 +129,10 @@ use warp::{Filter, Rejection};
 
 pub use crate::build_info::{BuildInfo, RuntimeInfo};
 pub use crate::index_api::{ListSplitsQueryParams, ListSplitsResponse};
-pub use crate::ingest_api::{RestIngestResponse, RestParseFailure};
+pub use crate::ingest_api::{IngestAckLevel, RestIngestResponse, RestParseFailure};
 pub use crate::metrics::SERVE_METRICS;
+#[cfg(feature = "plugins")]
+pub use crate::plugin::{register_plugin, PluginContext, PluginRoutes, RestPlugin, UiPanel};
 use crate::rate_modulator::RateModulator;
 #[cfg(test)]
 use crate::rest::recover_fn;
@@ -199,6 +209,9 @@ struct QuickwitServices {
     /// It is only used to serve the rest API calls and will only execute
     /// the root requests.
     pub search_service: Arc<dyn SearchService>,
+    /// Searches started through the REST API on this node, shared with the search service so
+    /// they can be cancelled.
+    pub running_searches: Arc<RunningSearches>,
 
     pub env_filter_reload_fn: EnvFilterReloadFn,
 
@@ -327,6 +340,9 @@ async fn start_control_plane_if_needed(
             metastore_client.clone(),
             node_config.default_index_root_uri.clone(),
             &node_config.ingest_api_config,
+            node_config.split_cache_prewarming_config_opt.as_ref().map(
+                |split_cache_prewarming_config| split_cache_prewarming_config.num_splits_per_index,
+            ),
         )
         .await?;
 
@@ -549,7 +565,8 @@ pub async fn serve_quickwit(
     let mut index_manager = IndexManager::new(
         metastore_through_control_plane.clone(),
         storage_resolver.clone(),
-    );
+    )
+    .with_trash_enabled(node_config.trash_config_opt.is_some());
 
     if node_config.is_service_enabled(QuickwitService::Indexer)
         && node_config.indexer_config.enable_otlp_endpoint
@@ -576,13 +593,28 @@ pub async fn serve_quickwit(
             }
         }
     }
+    if let Some(usage_sampling_config) = &node_config.usage_sampling_config_opt {
+        let usage_index_config = usage_sampling::usage_index_config(
+            usage_sampling_config,
+            &node_config.default_index_root_uri,
+        )
+        .context("failed to load usage index config")?;
+
+        match index_manager.create_index(usage_index_config, false).await {
+            Ok(_)
+            | Err(IndexServiceError::Metastore(MetastoreError::AlreadyExists(
+                EntityKind::Index { .. },
+            ))) => {}
+            Err(error) => bail!("failed to create usage index: {error}"),
+        };
+    }
 
     let split_cache_opt: Option<Arc<SplitCache>> =
-        if let Some(split_cache_limits) = node_config.searcher_config.split_cache {
+        if let Some(split_cache_limits) = &node_config.searcher_config.split_cache {
             let split_cache = SplitCache::with_root_path(
                 node_config.data_dir_path.join("searcher-split-cache"),
                 storage_resolver.clone(),
-                split_cache_limits,
+                split_cache_limits.clone(),
             )
             .context("failed to load searcher split cache")?;
             Some(split_cache)
@@ -594,6 +626,7 @@ pub async fn serve_quickwit(
         node_config.searcher_config.clone(),
         split_cache_opt,
     ));
+    let running_searches = searcher_context.running_searches.clone();
 
     let (search_job_placer, search_service) = setup_searcher(
         &node_config,
@@ -635,6 +668,7 @@ pub async fn serve_quickwit(
             metastore_through_control_plane.clone(),
             search_job_placer,
             storage_resolver.clone(),
+            ingester_pool.clone(),
             event_broker.clone(),
             !get_bool_from_env(DISABLE_DELETE_TASK_SERVICE_ENV_KEY, false),
         )
@@ -698,6 +732,7 @@ pub async fn serve_quickwit(
         otlp_logs_service_opt,
         otlp_traces_service_opt,
         search_service,
+        running_searches,
         env_filter_reload_fn,
     });
     // Setup and start gRPC server.
@@ -902,6 +937,7 @@ async fn setup_ingest_v2(
             node_config.ingest_api_config.max_queue_memory_usage,
             rate_limiter_settings,
             replication_factor,
+            node_config.grpc_config.compression.payload_compression(),
             idle_shard_timeout,
         )
         .await?;
@@ -982,16 +1018,22 @@ async fn setup_searcher(
 ) -> anyhow::Result<(SearchJobPlacer, Arc<dyn SearchService>)> {
     let searcher_pool = SearcherPool::default();
     let search_job_placer = SearchJobPlacer::new(searcher_pool.clone());
+    let max_message_size = node_config.grpc_config.max_message_size;
+    let request_timeout = node_config.searcher_config.request_timeout();
+    let remote_clusters = RemoteClusters::connect(
+        &node_config.searcher_config.remote_clusters,
+        request_timeout,
+        max_message_size,
+    )?;
     let search_service = start_searcher_service(
         metastore,
         storage_resolver,
         search_job_placer.clone(),
         searcher_context,
+        remote_clusters,
     )
     .await?;
     let search_service_clone = search_service.clone();
-    let max_message_size = node_config.grpc_config.max_message_size;
-    let request_timeout = node_config.searcher_config.request_timeout();
     let searcher_change_stream = cluster_change_stream.filter_map(move |cluster_change| {
         let search_service_clone = search_service_clone.clone();
         Box::pin(async move {
@@ -1049,6 +1091,7 @@ async fn setup_control_plane(
     metastore: MetastoreServiceClient,
     default_index_root_uri: Uri,
     ingest_api_config: &IngestApiConfig,
+    split_cache_prewarm_num_splits_per_index: Option<NonZeroU32>,
 ) -> anyhow::Result<Mailbox<ControlPlane>> {
     let cluster_id = cluster.cluster_id().to_string();
     let replication_factor = ingest_api_config
@@ -1062,6 +1105,7 @@ async fn setup_control_plane(
         replication_factor,
         shard_throughput_limit: ingest_api_config.shard_throughput_limit,
         shard_scale_up_factor: ingest_api_config.shard_scale_up_factor,
+        split_cache_prewarm_num_splits_per_index,
     };
     let (control_plane_mailbox, _control_plane_handle, mut readiness_rx) = ControlPlane::spawn(
         universe,
@@ -1071,6 +1115,7 @@ async fn setup_control_plane(
         indexer_pool,
         ingester_pool,
         metastore,
+        event_broker.clone(),
     );
     let subscriber = ControlPlaneEventSubscriber::new(control_plane_mailbox.downgrade());
     event_broker
@@ -24,6 +24,11 @@ pub struct ServeMetrics {
     pub ongoing_requests: IntGaugeVec<1>,
     pub pending_requests: IntGaugeVec<1>,
     pub circuit_break_total: IntCounter,
+    pub shadow_requests_total: IntCounterVec<2>,
+    pub shadow_divergences_total: IntCounterVec<1>,
+    pub rollout_mirrored_requests_total: IntCounterVec<2>,
+    pub usage_samples_total: IntCounterVec<1>,
+    pub unacknowledged_ingest_requests_total: IntCounterVec<1>,
 }
 
 impl Default for ServeMetrics {
@@ -65,6 +70,42 @@ impl Default for ServeMetrics {
                 ["endpoint_group"],
             ),
             circuit_break_total,
+            shadow_requests_total: new_counter_vec(
+                "shadow_requests_total",
+                "Number of requests shadowed to the secondary cluster, by outcome.",
+                "shadowing",
+                &[],
+                ["kind", "outcome"],
+            ),
+            shadow_divergences_total: new_counter_vec(
+                "shadow_divergences_total",
+                "Number of shadow requests whose response diverged from the primary response.",
+                "shadowing",
+                &[],
+                ["kind"],
+            ),
+            rollout_mirrored_requests_total: new_counter_vec(
+                "rollout_mirrored_requests_total",
+                "Number of requests mirrored to the candidate index of a rollout, by outcome.",
+                "rollout",
+                &[],
+                ["kind", "outcome"],
+            ),
+            usage_samples_total: new_counter_vec(
+                "usage_samples_total",
+                "Number of search requests sampled into the usage index, by outcome.",
+                "usage_sampling",
+                &[],
+                ["outcome"],
+            ),
+            unacknowledged_ingest_requests_total: new_counter_vec(
+                "unacknowledged_ingest_requests_total",
+                "Number of ingest requests with `ack=none`, by outcome (`ingested`, `failed`, or \
+                 `rejected` when the memory budget is exhausted).",
+                "ingest",
+                &[],
+                ["outcome"],
+            ),
         }
     }
 }
@@ -0,0 +1,319 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Extension point for downstream distributions of Quickwit.
+//!
+//! A plugin contributes a REST route tree, served under `/api/plugins/{name}/`, and optionally
+//! static assets and UI panels, served under `/ui/plugins/{name}/`. Plugins are compiled into the
+//! binary and must be registered with [`register_plugin`] before the REST server starts.
+
+use std::borrow::Cow;
+use std::sync::{Arc, RwLock};
+
+use anyhow::bail;
+use hyper::header::HeaderValue;
+use once_cell::sync::Lazy;
+use quickwit_config::NodeConfig;
+use quickwit_proto::metastore::MetastoreServiceClient;
+use quickwit_search::SearchService;
+use serde::Serialize;
+use warp::filters::BoxedFilter;
+use warp::path::Tail;
+use warp::reply::Response;
+use warp::{Filter, Rejection, Reply};
+
+use crate::authentication::{require_authentication, Authenticator};
+use crate::QuickwitServices;
+
+static PLUGIN_REGISTRY: Lazy<RwLock<Vec<Arc<dyn RestPlugin>>>> = Lazy::new(Default::default);
+
+/// Route tree contributed by a plugin.
+pub type PluginRoutes = BoxedFilter<(Box<dyn Reply>,)>;
+
+/// Services made available to the routes of the plugins.
+#[derive(Clone)]
+pub struct PluginContext {
+    pub node_config: Arc<NodeConfig>,
+    pub metastore: MetastoreServiceClient,
+    pub search_service: Arc<dyn SearchService>,
+}
+
+/// Panel added to the UI by a plugin.
+#[derive(Clone, Debug, Serialize, utoipa::ToSchema)]
+pub struct UiPanel {
+    /// Identifier of the panel, unique within the plugin.
+    pub id: String,
+    /// Title of the panel displayed in the UI navigation.
+    pub title: String,
+    /// Path of the entry point of the panel, relative to the static assets of the plugin.
+    pub entry_point: String,
+}
+
+pub trait RestPlugin: Send + Sync + 'static {
+    /// Name of the plugin, which namespaces its routes and assets. It must consist of lowercase
+    /// ASCII letters, digits, `-`, and `_`.
+    fn name(&self) -> &'static str;
+
+    /// Returns the routes of the plugin, relative to `/api/plugins/{name}/`.
+    fn routes(&self, context: PluginContext) -> PluginRoutes;
+
+    /// Returns the static asset located at `path`, relative to `/ui/plugins/{name}/`.
+    fn asset(&self, _path: &str) -> Option<Cow<'static, [u8]>> {
+        None
+    }
+
+    /// Returns the panels the plugin adds to the UI.
+    fn ui_panels(&self) -> Vec<UiPanel> {
+        Vec::new()
+    }
+}
+
+/// Registers a plugin. Returns an error if the name of the plugin is invalid or already taken.
+pub fn register_plugin(plugin: impl RestPlugin) -> anyhow::Result<()> {
+    let name = plugin.name();
+
+    if name.is_empty()
+        || !name
+            .bytes()
+            .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || b"-_".contains(&byte))
+    {
+        bail!("plugin name `{name}` is invalid");
+    }
+    let mut plugins = PLUGIN_REGISTRY
+        .write()
+        .expect("lock should not be poisoned");
+
+    if plugins.iter().any(|registered| registered.name() == name) {
+        bail!("plugin `{name}` is already registered");
+    }
+    plugins.push(Arc::new(plugin));
+    Ok(())
+}
+
+fn registered_plugins() -> Vec<Arc<dyn RestPlugin>> {
+    PLUGIN_REGISTRY
+        .read()
+        .expect("lock should not be poisoned")
+        .clone()
+}
+
+#[derive(Serialize)]
+struct PluginDescriptor {
+    name: &'static str,
+    ui_panels: Vec<UiPanel>,
+}
+
+/// Builds the routes of the registered plugins.
+pub(crate) fn plugin_routes(
+    quickwit_services: &QuickwitServices,
+    authenticator: Arc<Authenticator>,
+    ui_authenticator: Arc<Authenticator>,
+) -> PluginRoutes {
+    let context = PluginContext {
+        node_config: quickwit_services.node_config.clone(),
+        metastore: quickwit_services.metastore_client.clone(),
+        search_service: quickwit_services.search_service.clone(),
+    };
+    build_plugin_routes(
+        registered_plugins(),
+        context,
+        authenticator,
+        ui_authenticator,
+    )
+}
+
+/// Builds the routes of the plugins:
+/// - `GET /api/plugins` lists the plugins and their UI panels;
+/// - `/api/plugins/{name}/*` are the routes of each plugin;
+/// - `GET /ui/plugins/{name}/*` are the static assets of each plugin.
+fn build_plugin_routes(
+    plugins: Vec<Arc<dyn RestPlugin>>,
+    context: PluginContext,
+    authenticator: Arc<Authenticator>,
+    ui_authenticator: Arc<Authenticator>,
+) -> PluginRoutes {
+    let plugin_descriptors: Vec<PluginDescriptor> = plugins
+        .iter()
+        .map(|plugin| PluginDescriptor {
+            name: plugin.name(),
+            ui_panels: plugin.ui_panels(),
+        })
+        .collect();
+    let plugin_descriptors = Arc::new(plugin_descriptors);
+
+    let list_plugins_route = warp::path!("api" / "plugins")
+        .and(warp::get())
+        .and(require_authentication(authenticator.clone()))
+        .map(move || Box::new(warp::reply::json(&*plugin_descriptors)) as Box<dyn Reply>)
+        .boxed();
+
+    plugins
+        .into_iter()
+        .fold(list_plugins_route, |routes, plugin| {
+            let name = plugin.name();
+            let api_route = warp::path("api")
+                .and(warp::path("plugins"))
+                .and(warp::path(name))
+                .and(require_authentication(authenticator.clone()))
+                .and(plugin.routes(context.clone()));
+            let assets_route = warp::path("ui")
+                .and(warp::path("plugins"))
+                .and(warp::path(name))
+                .and(warp::get())
+                .and(require_authentication(ui_authenticator.clone()))
+                .and(warp::path::tail())
+                .and_then(move |path: Tail| {
+                    let plugin = plugin.clone();
+                    async move { serve_asset(&*plugin, path.as_str()) }
+                });
+            routes
+                .or(api_route)
+                .unify()
+                .or(assets_route)
+                .unify()
+                .boxed()
+        })
+}
+
+fn serve_asset(plugin: &dyn RestPlugin, path: &str) -> Result<Box<dyn Reply>, Rejection> {
+    let asset = plugin.asset(path).ok_or_else(warp::reject::not_found)?;
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+
+    let mut response = Response::new(asset.into_owned().into());
+    response.headers_mut().insert(
+        "content-type",
+        HeaderValue::from_str(mime.as_ref()).unwrap(),
+    );
+    Ok(Box::new(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_search::MockSearchService;
+
+    use super::*;
+
+    struct HelloPlugin;
+
+    impl RestPlugin for HelloPlugin {
+        fn name(&self) -> &'static str {
+            "hello"
+        }
+
+        fn routes(&self, context: PluginContext) -> PluginRoutes {
+            warp::path!("greeting")
+                .and(warp::get())
+                .map(move || {
+                    let greeting = format!("hello from {}", context.node_config.node_id);
+                    Box::new(greeting) as Box<dyn Reply>
+                })
+                .boxed()
+        }
+
+        fn asset(&self, path: &str) -> Option<Cow<'static, [u8]>> {
+            (path == "panel.html").then_some(Cow::Borrowed(b"<p>hello</p>"))
+        }
+
+        fn ui_panels(&self) -> Vec<UiPanel> {
+            vec![UiPanel {
+                id: "greetings".to_string(),
+                title: "Greetings".to_string(),
+                entry_point: "panel.html".to_string(),
+            }]
+        }
+    }
+
+    struct NamedPlugin(&'static str);
+
+    impl RestPlugin for NamedPlugin {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn routes(&self, _context: PluginContext) -> PluginRoutes {
+            warp::any()
+                .and_then(|| async { Err::<Box<dyn Reply>, _>(warp::reject::not_found()) })
+                .boxed()
+        }
+    }
+
+    #[test]
+    fn test_register_plugin() {
+        register_plugin(NamedPlugin("test-register-plugin")).unwrap();
+
+        let error = register_plugin(NamedPlugin("test-register-plugin")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "plugin `test-register-plugin` is already registered"
+        );
+        let error = register_plugin(NamedPlugin("Invalid/Name")).unwrap_err();
+        assert_eq!(error.to_string(), "plugin name `Invalid/Name` is invalid");
+
+        register_plugin(NamedPlugin("")).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_plugin_routes() {
+        let context = PluginContext {
+            node_config: Arc::new(NodeConfig::for_test()),
+            metastore: MetastoreServiceClient::mocked(),
+            search_service: Arc::new(MockSearchService::new()),
+        };
+        let node_id = context.node_config.node_id.clone();
+        let routes = build_plugin_routes(
+            vec![Arc::new(HelloPlugin)],
+            context,
+            Arc::default(),
+            Arc::default(),
+        );
+        let response = warp::test::request()
+            .path("/api/plugins")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), 200);
+        let plugins: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(
+            plugins,
+            serde_json::json!([{
+                "name": "hello",
+                "ui_panels": [{
+                    "id": "greetings",
+                    "title": "Greetings",
+                    "entry_point": "panel.html",
+                }],
+            }])
+        );
+
+        let response = warp::test::request()
+            .path("/api/plugins/hello/greeting")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.body(), format!("hello from {node_id}").as_str());
+
+        let response = warp::test::request()
+            .path("/ui/plugins/hello/panel.html")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["content-type"], "text/html");
+        assert_eq!(response.body(), "<p>hello</p>");
+
+        let response = warp::test::request()
+            .path("/ui/plugins/hello/missing.js")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), 404);
+    }
+}
@@ -0,0 +1,337 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+
+use bytes::{Bytes, BytesMut};
+use futures::Stream;
+use hyper::body::HttpBody;
+use hyper::http::header::CONTENT_LENGTH;
+use hyper::http::{HeaderMap, Method};
+use hyper::Body;
+
+/// Number of bytes of the search responses retained to extract their number of hits, which is
+/// serialized before the hits in both the Quickwit and Elasticsearch search responses.
+pub(crate) const MAX_INSPECTED_RESPONSE_NUM_BYTES: usize = 4 * 1024; // 4 KiB
+
+/// REST endpoints whose requests can be shadowed or sampled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SampledEndpoint {
+    Ingest,
+    ElasticBulk,
+    Search,
+    ElasticSearch,
+}
+
+impl SampledEndpoint {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ingest => "ingest",
+            Self::ElasticBulk => "elastic_bulk",
+            Self::Search => "search",
+            Self::ElasticSearch => "elastic_search",
+        }
+    }
+}
+
+/// Returns the endpoint and the index ID patterns of the REST requests eligible for shadowing or
+/// sampling, `None` for the other requests.
+pub(crate) fn classify_request(
+    method: &Method,
+    path: &str,
+) -> Option<(SampledEndpoint, Vec<String>)> {
+    let path = path.strip_prefix("/api/v1/")?;
+    let path_segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+
+    let (endpoint, index_id_patterns_str) = match (method, path_segments.as_slice()) {
+        (&Method::POST, [index_id, "ingest"]) => (SampledEndpoint::Ingest, *index_id),
+        (&Method::POST | &Method::PUT, ["_elastic", "_bulk"]) => (SampledEndpoint::ElasticBulk, ""),
+        (&Method::POST | &Method::PUT, ["_elastic", index_id, "_bulk"]) => {
+            (SampledEndpoint::ElasticBulk, *index_id)
+        }
+        (&Method::GET | &Method::POST, [index_id_patterns, "search"]) => {
+            (SampledEndpoint::Search, *index_id_patterns)
+        }
+        (&Method::GET | &Method::POST, ["_elastic", "_search"]) => {
+            (SampledEndpoint::ElasticSearch, "")
+        }
+        (&Method::GET | &Method::POST, ["_elastic", index_id_patterns, "_search"]) => {
+            (SampledEndpoint::ElasticSearch, *index_id_patterns)
+        }
+        _ => return None,
+    };
+    let index_id_patterns = index_id_patterns_str
+        .split(',')
+        .filter(|index_id_pattern| !index_id_pattern.is_empty())
+        .map(|index_id_pattern| index_id_pattern.to_string())
+        .collect();
+    Some((endpoint, index_id_patterns))
+}
+
+/// Selects a percentage of the requests, evenly spread over the sequence of requests.
+pub(crate) struct RequestSampler {
+    ratio: f64,
+    num_requests: AtomicU64,
+}
+
+impl RequestSampler {
+    pub fn new(percentage: f64) -> Self {
+        Self {
+            ratio: percentage / 100.0,
+            num_requests: AtomicU64::new(0),
+        }
+    }
+
+    pub fn sample(&self) -> bool {
+        if self.ratio <= 0.0 {
+            return false;
+        }
+        let request_ord = self.num_requests.fetch_add(1, Ordering::Relaxed) as f64;
+        ((request_ord + 1.0) * self.ratio).floor() > (request_ord * self.ratio).floor()
+    }
+}
+
+pub(crate) fn content_length_opt(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(CONTENT_LENGTH)
+        .and_then(|header_value| header_value.to_str().ok())
+        .and_then(|content_length_str| content_length_str.parse().ok())
+}
+
+/// Returns a body yielding the error that occurred while buffering the original body, so that the
+/// inner service fails the same way it would have with the original body.
+pub(crate) fn body_with_error(error: hyper::Error) -> Body {
+    Body::wrap_stream(futures::stream::once(async move { Err::<Bytes, _>(error) }))
+}
+
+/// Extracts the number of hits from the beginning of a Quickwit (`num_hits`) or Elasticsearch
+/// (`hits.total.value`) search response, without parsing the rest of the response.
+pub(crate) fn extract_num_hits(response_prefix: &[u8]) -> Option<u64> {
+    if let Some(num_hits) = find_json_value_after(response_prefix, &[b"\"num_hits\"", b":"]) {
+        return Some(num_hits);
+    }
+    find_json_value_after(
+        response_prefix,
+        &[b"\"total\"", b":", b"{", b"\"value\"", b":"],
+    )
+}
+
+/// Returns the first unsigned integer following the given sequence of JSON tokens, which may be
+/// separated by whitespace.
+fn find_json_value_after(json_prefix: &[u8], tokens: &[&[u8]]) -> Option<u64> {
+    let first_token = tokens.first()?;
+
+    let candidate_starts = json_prefix
+        .windows(first_token.len())
+        .enumerate()
+        .filter(|(_, window)| window == first_token)
+        .map(|(candidate_start, _)| candidate_start);
+
+    'candidates: for candidate_start in candidate_starts {
+        let mut remaining = &json_prefix[candidate_start..];
+
+        for token in tokens {
+            remaining = remaining.trim_ascii_start();
+
+            let Some(after_token) = remaining.strip_prefix(*token) else {
+                continue 'candidates;
+            };
+            remaining = after_token;
+        }
+        remaining = remaining.trim_ascii_start();
+        let num_digits = remaining
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count();
+
+        // The number may be cut by the end of the prefix.
+        if num_digits == 0 || num_digits == remaining.len() {
+            continue;
+        }
+        return std::str::from_utf8(&remaining[..num_digits])
+            .ok()?
+            .parse()
+            .ok();
+    }
+    None
+}
+
+/// Body of a search response, which forwards the chunks of the response as they come while
+/// retaining its first [`MAX_INSPECTED_RESPONSE_NUM_BYTES`] bytes to extract the number of hits.
+/// The number of hits is handed over to `on_complete` once the body is fully sent, or dropped.
+pub(crate) struct InspectedResponseBody<F: FnOnce(Option<u64>)> {
+    body: Body,
+    response_prefix: BytesMut,
+    on_complete_opt: Option<F>,
+}
+
+impl<F: FnOnce(Option<u64>)> InspectedResponseBody<F> {
+    pub fn new(body: Body, on_complete: F) -> Self {
+        Self {
+            body,
+            response_prefix: BytesMut::new(),
+            on_complete_opt: Some(on_complete),
+        }
+    }
+
+    fn complete(&mut self) {
+        if let Some(on_complete) = self.on_complete_opt.take() {
+            on_complete(extract_num_hits(&self.response_prefix));
+        }
+    }
+}
+
+impl<F: FnOnce(Option<u64>) + Unpin> Stream for InspectedResponseBody<F> {
+    type Item = Result<Bytes, hyper::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.body).poll_data(cx);
+
+        match &poll {
+            Poll::Ready(Some(Ok(chunk))) => {
+                let num_bytes_to_retain = MAX_INSPECTED_RESPONSE_NUM_BYTES
+                    .saturating_sub(this.response_prefix.len())
+                    .min(chunk.len());
+                this.response_prefix
+                    .extend_from_slice(&chunk[..num_bytes_to_retain]);
+            }
+            Poll::Ready(None) => this.complete(),
+            Poll::Ready(Some(Err(_))) | Poll::Pending => {}
+        }
+        poll
+    }
+}
+
+impl<F: FnOnce(Option<u64>)> Drop for InspectedResponseBody<F> {
+    fn drop(&mut self) {
+        self.complete();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_request() {
+        assert_eq!(
+            classify_request(&Method::POST, "/api/v1/logs/ingest"),
+            Some((SampledEndpoint::Ingest, vec!["logs".to_string()]))
+        );
+        assert_eq!(
+            classify_request(&Method::POST, "/api/v1/_elastic/_bulk"),
+            Some((SampledEndpoint::ElasticBulk, Vec::new()))
+        );
+        assert_eq!(
+            classify_request(&Method::PUT, "/api/v1/_elastic/logs/_bulk"),
+            Some((SampledEndpoint::ElasticBulk, vec!["logs".to_string()]))
+        );
+        assert_eq!(
+            classify_request(&Method::GET, "/api/v1/logs/search"),
+            Some((SampledEndpoint::Search, vec!["logs".to_string()]))
+        );
+        assert_eq!(
+            classify_request(&Method::POST, "/api/v1/logs-*,traces/search/"),
+            Some((
+                SampledEndpoint::Search,
+                vec!["logs-*".to_string(), "traces".to_string()]
+            ))
+        );
+        assert_eq!(
+            classify_request(&Method::POST, "/api/v1/_elastic/_search"),
+            Some((SampledEndpoint::ElasticSearch, Vec::new()))
+        );
+        assert_eq!(
+            classify_request(&Method::GET, "/api/v1/_elastic/logs/_search"),
+            Some((SampledEndpoint::ElasticSearch, vec!["logs".to_string()]))
+        );
+
+        assert_eq!(classify_request(&Method::GET, "/api/v1/logs/ingest"), None);
+        assert_eq!(
+            classify_request(&Method::DELETE, "/api/v1/indexes/logs"),
+            None
+        );
+        assert_eq!(
+            classify_request(&Method::GET, "/api/v1/logs/search/stream"),
+            None
+        );
+        assert_eq!(
+            classify_request(&Method::POST, "/api/v1/_elastic/_msearch"),
+            None
+        );
+        assert_eq!(classify_request(&Method::GET, "/health/livez"), None);
+    }
+
+    #[test]
+    fn test_request_sampler() {
+        let num_sampled = |percentage: f64| {
+            let sampler = RequestSampler::new(percentage);
+            (0..1_000).filter(|_| sampler.sample()).count()
+        };
+        assert_eq!(num_sampled(0.0), 0);
+        assert_eq!(num_sampled(0.5), 5);
+        assert_eq!(num_sampled(25.0), 250);
+        assert_eq!(num_sampled(100.0), 1_000);
+    }
+
+    #[test]
+    fn test_extract_num_hits() {
+        assert_eq!(extract_num_hits(br#"{"num_hits":3,"hits":[]}"#), Some(3));
+        assert_eq!(
+            extract_num_hits(b"{\n  \"num_hits\" : 12,\n  \"hits\": []\n}"),
+            Some(12)
+        );
+        assert_eq!(
+            extract_num_hits(
+                br#"{"took":1,"_shards":{"total":4,"failed":0},"hits":{"total":{"value":42,"relation":"eq"},"hits":[]}}"#
+            ),
+            Some(42)
+        );
+        assert_eq!(extract_num_hits(br#"{"hits": []}"#), None);
+        assert_eq!(extract_num_hits(b"not json"), None);
+        // The number of hits is cut by the end of the prefix.
+        assert_eq!(extract_num_hits(br#"{"num_hits":12"#), None);
+        assert_eq!(extract_num_hits(br#"{"took":1,"hits":"#), None);
+    }
+
+    #[tokio::test]
+    async fn test_inspected_response_body() {
+        let (num_hits_tx, num_hits_rx) = std::sync::mpsc::channel();
+        let response_body = format!(
+            r#"{{"num_hits": 3, "hits": ["{}"]}}"#,
+            "a".repeat(2 * MAX_INSPECTED_RESPONSE_NUM_BYTES)
+        );
+        let inspected_body =
+            InspectedResponseBody::new(Body::from(response_body.clone()), move |num_hits_opt| {
+                num_hits_tx.send(num_hits_opt).unwrap()
+            });
+        let body_bytes = hyper::body::to_bytes(Body::wrap_stream(inspected_body))
+            .await
+            .unwrap();
+        assert_eq!(body_bytes, response_body.as_bytes());
+        assert_eq!(num_hits_rx.try_recv().unwrap(), Some(3));
+        assert!(num_hits_rx.try_recv().is_err());
+
+        // The number of hits is also handed over when the body is dropped before the end.
+        let (num_hits_tx, num_hits_rx) = std::sync::mpsc::channel();
+        let inspected_body = InspectedResponseBody::new(Body::from("{}"), move |num_hits_opt| {
+            num_hits_tx.send(num_hits_opt).unwrap()
+        });
+        drop(inspected_body);
+        assert_eq!(num_hits_rx.try_recv().unwrap(), None);
+    }
+}
@@ -0,0 +1,350 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::task::{Context as TaskContext, Poll};
+
+use futures::future::BoxFuture;
+use hyper::http::{HeaderMap, HeaderValue, Request, Response};
+use hyper::Body;
+use opentelemetry::trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState};
+use opentelemetry::Context;
+use tower::{Layer, Service};
+use tracing::{field, info_span, Instrument, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Response header echoing the ID of the trace the REST request belongs to.
+pub(crate) const TRACE_ID_HEADER: &str = "x-quickwit-trace-id";
+
+const TRACEPARENT_HEADER: &str = "traceparent";
+const B3_HEADER: &str = "b3";
+const B3_TRACE_ID_HEADER: &str = "x-b3-traceid";
+const B3_SPAN_ID_HEADER: &str = "x-b3-spanid";
+const B3_SAMPLED_HEADER: &str = "x-b3-sampled";
+const B3_FLAGS_HEADER: &str = "x-b3-flags";
+
+tokio::task_local! {
+    static REQUEST_TRACE_ID: TraceId;
+}
+
+/// Returns the ID of the trace of the REST request being served, if any.
+pub(crate) fn current_trace_id() -> Option<TraceId> {
+    REQUEST_TRACE_ID.try_with(|trace_id| *trace_id).ok()
+}
+
+fn is_hex(value: &str, num_digits: usize) -> bool {
+    value.len() == num_digits && value.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+fn new_remote_span_context(trace_id: &str, span_id: &str, sampled: bool) -> Option<SpanContext> {
+    // B3 accepts 64-bit trace IDs, which are left-padded with zeros.
+    if !(is_hex(trace_id, 32) || is_hex(trace_id, 16)) || !is_hex(span_id, 16) {
+        return None;
+    }
+    let trace_id = TraceId::from_hex(trace_id).ok()?;
+    let span_id = SpanId::from_hex(span_id).ok()?;
+    let trace_flags = if sampled {
+        TraceFlags::SAMPLED
+    } else {
+        TraceFlags::default()
+    };
+    let span_context =
+        SpanContext::new(trace_id, span_id, trace_flags, true, TraceState::default());
+    span_context.is_valid().then_some(span_context)
+}
+
+/// Parses a W3C `traceparent` header: `{version}-{trace-id}-{parent-id}-{trace-flags}`.
+fn parse_traceparent(traceparent: &str) -> Option<SpanContext> {
+    let mut parts = traceparent.trim().split('-');
+    let version = parts.next()?;
+    let trace_id = parts.next()?;
+    let span_id = parts.next()?;
+    let trace_flags = parts.next()?;
+
+    // Future versions may append fields to the header, version `00` cannot.
+    if !is_hex(version, 2) || version == "ff" || (version == "00" && parts.next().is_some()) {
+        return None;
+    }
+    if !is_hex(trace_id, 32) || !is_hex(trace_flags, 2) {
+        return None;
+    }
+    let trace_flags = u8::from_str_radix(trace_flags, 16).ok()?;
+    let sampled = trace_flags & TraceFlags::SAMPLED.to_u8() != 0;
+    new_remote_span_context(trace_id, span_id, sampled)
+}
+
+/// Parses a B3 single header: `{trace-id}-{span-id}-{sampling-state}-{parent-span-id}`, where the
+/// last two fields are optional.
+fn parse_b3_single_header(b3: &str) -> Option<SpanContext> {
+    let mut parts = b3.trim().split('-');
+    let trace_id = parts.next()?;
+    let span_id = parts.next()?;
+    let sampled = matches!(parts.next(), Some("1" | "d"));
+    new_remote_span_context(trace_id, span_id, sampled)
+}
+
+/// Parses the B3 multi headers: `X-B3-TraceId`, `X-B3-SpanId`, `X-B3-Sampled`, and `X-B3-Flags`.
+fn parse_b3_multi_headers(headers: &HeaderMap) -> Option<SpanContext> {
+    let header_str = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|header_value| header_value.to_str().ok())
+            .map(str::trim)
+    };
+    let trace_id = header_str(B3_TRACE_ID_HEADER)?;
+    let span_id = header_str(B3_SPAN_ID_HEADER)?;
+    let sampled = matches!(header_str(B3_SAMPLED_HEADER), Some("1" | "true"))
+        || header_str(B3_FLAGS_HEADER) == Some("1");
+    new_remote_span_context(trace_id, span_id, sampled)
+}
+
+/// Extracts the context of the client span from the W3C Trace Context or B3 headers. The W3C
+/// header takes precedence if both are present.
+fn extract_remote_span_context(headers: &HeaderMap) -> Option<SpanContext> {
+    let header_str = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|header_value| header_value.to_str().ok())
+    };
+    header_str(TRACEPARENT_HEADER)
+        .and_then(parse_traceparent)
+        .or_else(|| header_str(B3_HEADER).and_then(parse_b3_single_header))
+        .or_else(|| parse_b3_multi_headers(headers))
+}
+
+/// Returns the ID of the trace of the request: the trace ID of its OpenTelemetry span when the
+/// OTLP exporter is enabled, the trace ID sent by the client otherwise, or a new random ID.
+fn request_trace_id(span: &Span, remote_span_context_opt: Option<&SpanContext>) -> TraceId {
+    let context = span.context();
+    let span_context = context.span().span_context().clone();
+
+    if span_context.is_valid() {
+        return span_context.trace_id();
+    }
+    if let Some(remote_span_context) = remote_span_context_opt {
+        return remote_span_context.trace_id();
+    }
+    TraceId::from_bytes(rand::random::<[u8; 16]>())
+}
+
+/// Layer running each REST request in a span attached to the trace of the client, if any. The
+/// trace ID is recorded in the logs of the request, propagated to the internal gRPC calls, and
+/// echoed in the `x-quickwit-trace-id` response header and in the error bodies.
+#[derive(Clone, Default)]
+pub(crate) struct RequestTracingLayer;
+
+impl<S> Layer<S> for RequestTracingLayer {
+    type Service = RequestTracingService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestTracingService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct RequestTracingService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for RequestTracingService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<Body>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let span = info_span!(
+            "rest_request",
+            method = %request.method(),
+            path = request.uri().path(),
+            trace_id = field::Empty,
+        );
+        let remote_span_context_opt = extract_remote_span_context(request.headers());
+
+        if let Some(remote_span_context) = &remote_span_context_opt {
+            span.set_parent(Context::new().with_remote_span_context(remote_span_context.clone()));
+        }
+        let trace_id = request_trace_id(&span, remote_span_context_opt.as_ref());
+        span.record("trace_id", field::display(trace_id));
+
+        let response_fut = self.inner.call(request).instrument(span);
+
+        Box::pin(REQUEST_TRACE_ID.scope(trace_id, async move {
+            let mut response = response_fut.await?;
+
+            if let Ok(header_value) = HeaderValue::from_str(&trace_id.to_string()) {
+                response.headers_mut().insert(TRACE_ID_HEADER, header_value);
+            }
+            Ok(response)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::StatusCode;
+    use quickwit_proto::error::ServiceErrorCode;
+    use quickwit_proto::ServiceError;
+    use warp::Filter;
+
+    use super::*;
+    use crate::rest_api_response::into_rest_api_response;
+    use crate::BodyFormat;
+
+    #[test]
+    fn test_parse_traceparent() {
+        let span_context =
+            parse_traceparent("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01").unwrap();
+        assert_eq!(
+            span_context.trace_id().to_string(),
+            "0af7651916cd43dd8448eb211c80319c"
+        );
+        assert_eq!(span_context.span_id().to_string(), "b7ad6b7169203331");
+        assert!(span_context.is_sampled());
+        assert!(span_context.is_remote());
+
+        let span_context =
+            parse_traceparent("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-00").unwrap();
+        assert!(!span_context.is_sampled());
+
+        // Future versions may carry extra fields.
+        assert!(
+            parse_traceparent("01-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-extra")
+                .is_some()
+        );
+        assert!(
+            parse_traceparent("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-extra")
+                .is_none()
+        );
+        assert!(
+            parse_traceparent("ff-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01").is_none()
+        );
+        assert!(
+            parse_traceparent("00-00000000000000000000000000000000-b7ad6b7169203331-01").is_none()
+        );
+        assert!(parse_traceparent("00-0af7651916cd43dd-b7ad6b7169203331-01").is_none());
+        assert!(parse_traceparent("not-a-traceparent").is_none());
+    }
+
+    #[test]
+    fn test_parse_b3_headers() {
+        let span_context =
+            parse_b3_single_header("80f198ee56343ba864fe8b2a57d3eff7-e457b5a2e4d86bd1-1").unwrap();
+        assert_eq!(
+            span_context.trace_id().to_string(),
+            "80f198ee56343ba864fe8b2a57d3eff7"
+        );
+        assert_eq!(span_context.span_id().to_string(), "e457b5a2e4d86bd1");
+        assert!(span_context.is_sampled());
+
+        let span_context = parse_b3_single_header("64fe8b2a57d3eff7-e457b5a2e4d86bd1").unwrap();
+        assert_eq!(
+            span_context.trace_id().to_string(),
+            "000000000000000064fe8b2a57d3eff7"
+        );
+        assert!(!span_context.is_sampled());
+
+        assert!(parse_b3_single_header("0").is_none());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "X-B3-TraceId",
+            HeaderValue::from_static("80f198ee56343ba864fe8b2a57d3eff7"),
+        );
+        assert!(parse_b3_multi_headers(&headers).is_none());
+
+        headers.insert("X-B3-SpanId", HeaderValue::from_static("e457b5a2e4d86bd1"));
+        headers.insert("X-B3-Sampled", HeaderValue::from_static("1"));
+        let span_context = parse_b3_multi_headers(&headers).unwrap();
+        assert_eq!(
+            span_context.trace_id().to_string(),
+            "80f198ee56343ba864fe8b2a57d3eff7"
+        );
+        assert!(span_context.is_sampled());
+
+        // The W3C header takes precedence.
+        headers.insert(
+            "traceparent",
+            HeaderValue::from_static("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"),
+        );
+        let span_context = extract_remote_span_context(&headers).unwrap();
+        assert_eq!(
+            span_context.trace_id().to_string(),
+            "0af7651916cd43dd8448eb211c80319c"
+        );
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("index `foo` not found")]
+    struct NotFoundError;
+
+    impl ServiceError for NotFoundError {
+        fn error_code(&self) -> ServiceErrorCode {
+            ServiceErrorCode::NotFound
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_tracing_service() {
+        let routes = warp::path!("api" / "v1" / "foo")
+            .map(|| into_rest_api_response::<(), _>(Err(NotFoundError), BodyFormat::default()));
+        let mut service = RequestTracingLayer.layer(warp::service(routes));
+
+        let request = Request::get("/api/v1/foo")
+            .header(
+                "traceparent",
+                "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            )
+            .body(Body::empty())
+            .unwrap();
+        let response = service.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers().get(TRACE_ID_HEADER).unwrap(),
+            "0af7651916cd43dd8448eb211c80319c"
+        );
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let error_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            error_json,
+            serde_json::json!({
+                "error_code": "not_found",
+                "message": "index `foo` not found",
+                "retryable": false,
+                "trace_id": "0af7651916cd43dd8448eb211c80319c",
+            })
+        );
+
+        // Requests without trace context get a new trace ID.
+        let request = Request::get("/api/v1/foo").body(Body::empty()).unwrap();
+        let response = service.call(request).await.unwrap();
+        let trace_id = response
+            .headers()
+            .get(TRACE_ID_HEADER)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(trace_id.len(), 32);
+        assert_ne!(trace_id, "0af7651916cd43dd8448eb211c80319c");
+
+        assert!(current_trace_id().is_none());
+    }
+}
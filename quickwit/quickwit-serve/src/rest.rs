@@ -16,13 +16,14 @@ use std::fmt::Formatter;
 use std::pin::Pin;
 use std::sync::Arc;
 
-use hyper::http::HeaderValue;
+use bytesize::ByteSize;
+use hyper::http::{HeaderName, HeaderValue};
 use hyper::server::accept::Accept;
 use hyper::server::conn::AddrIncoming;
 use hyper::{http, Method, StatusCode};
 use quickwit_common::tower::BoxFutureInfaillible;
-use quickwit_config::{disable_ingest_v1, enable_ingest_v2};
-use quickwit_search::SearchService;
+use quickwit_config::{disable_ingest_v1, enable_ingest_v2, RestConfig};
+use quickwit_search::{RunningSearches, SearchService};
 use tokio::net::TcpListener;
 use tower::make::Shared;
 use tower::ServiceBuilder;
@@ -33,6 +34,9 @@ use tracing::{error, info};
 use warp::filters::log::Info;
 use warp::{redirect, Filter, Rejection, Reply};
 
+use crate::authentication::{
+    oidc_login_handlers, require_admin, require_authentication, Authenticator,
+};
 use crate::cluster_api::cluster_handler;
 use crate::decompression::{CorruptedData, UnsupportedEncoding};
 use crate::delete_task_api::delete_task_api_handlers;
@@ -40,19 +44,30 @@ use crate::developer_api::developer_api_routes;
 use crate::elasticsearch_api::elastic_api_handlers;
 use crate::health_check_api::health_check_handlers;
 use crate::index_api::index_management_handlers;
-use crate::indexing_api::indexing_get_handler;
-use crate::ingest_api::ingest_api_handlers;
+use crate::indexing_api::{indexing_get_handler, indexing_sources_get_handler};
+use crate::ingest_api::{
+    ingest_api_handlers, IngestApiContext, IngestionFreezeChecker, WriteAliasResolver,
+};
 use crate::jaeger_api::jaeger_api_handlers;
 use crate::metrics_api::metrics_handler;
 use crate::node_info_handler::node_info_handler;
 use crate::otlp_api::otlp_ingest_api_handlers;
-use crate::rest_api_response::{RestApiError, RestApiResponse};
+#[cfg(feature = "plugins")]
+use crate::plugin::plugin_routes;
+use crate::request_tracing::RequestTracingLayer;
+use crate::rest_api_response::{
+    parameter_from_deserialization_error, RestApiError, RestApiResponse,
+};
+use crate::rollout::RolloutRouter;
 use crate::search_api::{
-    search_get_handler, search_plan_get_handler, search_plan_post_handler, search_post_handler,
-    search_stream_handler,
+    cancel_search_handler, explain_handler, field_stats_handler, list_running_searches_handler,
+    pin_splits_handler, search_get_handler, search_plan_get_handler, search_plan_post_handler,
+    search_post_handler, search_stream_handler, unpin_splits_handler,
 };
+use crate::shadowing::{Shadower, ShadowingLayer};
 use crate::template_api::index_template_api_handlers;
 use crate::ui_handler::ui_handler;
+use crate::usage_sampling::{UsageSampler, UsageSamplingLayer};
 use crate::{BodyFormat, BuildInfo, QuickwitServices, RuntimeInfo};
 
 #[derive(Debug)]
@@ -65,6 +80,16 @@ pub(crate) struct InvalidArgument(pub String);
 
 impl warp::reject::Reject for InvalidArgument {}
 
+#[derive(Debug)]
+pub(crate) struct Unauthenticated(pub String);
+
+impl warp::reject::Reject for Unauthenticated {}
+
+#[derive(Debug)]
+pub(crate) struct Forbidden(pub String);
+
+impl warp::reject::Reject for Forbidden {}
+
 #[derive(Debug)]
 pub struct TooManyRequests;
 
@@ -168,8 +193,25 @@ pub(crate) async fn start_rest_server(
         quickwit_services.env_filter_reload_fn.clone(),
     )
     .boxed();
+    let authenticator = Arc::new(Authenticator::from_rest_config(
+        &quickwit_services.node_config.rest_config,
+    ));
     // `/api/v1/*` routes.
-    let api_v1_root_route = api_v1_routes(quickwit_services.clone());
+    let api_v1_root_route = api_v1_routes(quickwit_services.clone(), authenticator.clone());
+
+    // `/ui/*` routes, protected only when an OIDC provider is configured since browsers cannot
+    // send API keys.
+    let ui_authenticator = if authenticator.is_oidc_enabled() {
+        authenticator.clone()
+    } else {
+        Arc::default()
+    };
+
+    // `/oidc/login` and `/oidc/callback` routes, through which the UI users log in.
+    let oidc_login_routes = oidc_login_handlers(authenticator.clone());
+
+    // `/api/plugins/*` and `/ui/plugins/*` routes.
+    let plugin_routes = plugin_routes(&quickwit_services, authenticator, ui_authenticator.clone());
 
     let redirect_root_to_ui_route = warp::path::end()
         .and(warp::get())
@@ -189,7 +231,9 @@ pub(crate) async fn start_rest_server(
     let rest_routes = api_v1_root_route
         .or(api_doc)
         .or(redirect_root_to_ui_route)
-        .or(ui_handler())
+        .or(plugin_routes)
+        .or(ui_handler(ui_authenticator))
+        .or(oidc_login_routes)
         .or(health_check_routes)
         .or(metrics_routes)
         .or(developer_routes)
@@ -200,9 +244,25 @@ pub(crate) async fn start_rest_server(
 
     let warp_service = warp::service(rest_routes);
     let compression_predicate = CompressionPredicate::from_env().and(NotForContentType::IMAGES);
-    let cors = build_cors(&quickwit_services.node_config.rest_config.cors_allow_origins);
+    let cors = build_cors(&quickwit_services.node_config.rest_config);
+    let shadower_opt = quickwit_services
+        .node_config
+        .shadowing_config_opt
+        .as_ref()
+        .map(Shadower::new)
+        .transpose()?
+        .map(Arc::new);
+    let usage_sampler_opt = quickwit_services
+        .node_config
+        .usage_sampling_config_opt
+        .as_ref()
+        .map(|usage_sampling_config| {
+            let ingest_router = quickwit_services.ingest_router_service.clone();
+            Arc::new(UsageSampler::spawn(usage_sampling_config, ingest_router))
+        });
 
     let service = ServiceBuilder::new()
+        .layer(RequestTracingLayer)
         .layer(
             CompressionLayer::new()
                 .zstd(true)
@@ -211,6 +271,8 @@ pub(crate) async fn start_rest_server(
                 .compress_when(compression_predicate),
         )
         .layer(cors)
+        .layer(ShadowingLayer::new(shadower_opt))
+        .layer(UsageSamplingLayer::new(usage_sampler_opt))
         .service(warp_service);
 
     let rest_listen_addr = tcp_listener.local_addr()?;
@@ -247,79 +309,179 @@ pub(crate) async fn start_rest_server(
     Ok(())
 }
 
+/// Without the `plugins` feature, no plugin can be registered.
+#[cfg(not(feature = "plugins"))]
+fn plugin_routes(
+    _quickwit_services: &QuickwitServices,
+    _authenticator: Arc<Authenticator>,
+    _ui_authenticator: Arc<Authenticator>,
+) -> warp::filters::BoxedFilter<(Box<dyn Reply>,)> {
+    warp::any()
+        .and_then(|| async { Err::<Box<dyn Reply>, _>(warp::reject::not_found()) })
+        .boxed()
+}
+
 fn search_routes(
     search_service: Arc<dyn SearchService>,
+    running_searches: Arc<RunningSearches>,
+    authenticator: Arc<Authenticator>,
+    rollout_router: RolloutRouter,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    search_get_handler(search_service.clone())
-        .or(search_post_handler(search_service.clone()))
-        .or(search_plan_get_handler(search_service.clone()))
-        .or(search_plan_post_handler(search_service.clone()))
-        .or(search_stream_handler(search_service))
-        .recover(recover_fn)
-        .boxed()
+    search_get_handler(
+        search_service.clone(),
+        running_searches.clone(),
+        authenticator.clone(),
+        rollout_router.clone(),
+    )
+    .or(search_post_handler(
+        search_service.clone(),
+        running_searches.clone(),
+        authenticator.clone(),
+        rollout_router,
+        content_length_limit,
+    ))
+    .or(search_plan_get_handler(
+        search_service.clone(),
+        authenticator.clone(),
+    ))
+    .or(search_plan_post_handler(
+        search_service.clone(),
+        authenticator.clone(),
+        content_length_limit,
+    ))
+    .or(explain_handler(
+        search_service.clone(),
+        authenticator.clone(),
+    ))
+    .or(field_stats_handler(
+        search_service.clone(),
+        authenticator.clone(),
+    ))
+    .or(pin_splits_handler(
+        search_service.clone(),
+        authenticator.clone(),
+    ))
+    .or(unpin_splits_handler(
+        search_service.clone(),
+        authenticator.clone(),
+    ))
+    .or(search_stream_handler(
+        search_service.clone(),
+        authenticator.clone(),
+    ))
+    .or(list_running_searches_handler(
+        running_searches,
+        authenticator.clone(),
+    ))
+    .or(cancel_search_handler(search_service, authenticator))
+    .recover(recover_fn)
+    .boxed()
 }
 
 fn api_v1_routes(
     quickwit_services: Arc<QuickwitServices>,
+    authenticator: Arc<Authenticator>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    let write_alias_resolver = if enable_ingest_v2() {
+        WriteAliasResolver::spawn(quickwit_services.metastore_client.clone())
+    } else {
+        WriteAliasResolver::default()
+    };
+    let rollout_router = RolloutRouter::spawn(quickwit_services.metastore_client.clone());
     let api_v1_root_url = warp::path!("api" / "v1" / ..);
-    api_v1_root_url.and(
-        elastic_api_handlers(
-            quickwit_services.cluster.clone(),
-            quickwit_services.node_config.clone(),
-            quickwit_services.search_service.clone(),
-            quickwit_services.ingest_service.clone(),
-            quickwit_services.ingest_router_service.clone(),
-            quickwit_services.metastore_client.clone(),
-            quickwit_services.index_manager.clone(),
-            !disable_ingest_v1(),
-            enable_ingest_v2(),
+    api_v1_root_url
+        .and(require_authentication(authenticator.clone()))
+        .and(
+            elastic_api_handlers(
+                quickwit_services.cluster.clone(),
+                quickwit_services.node_config.clone(),
+                quickwit_services.search_service.clone(),
+                quickwit_services.running_searches.clone(),
+                quickwit_services.ingest_service.clone(),
+                quickwit_services.ingest_router_service.clone(),
+                write_alias_resolver.clone(),
+                quickwit_services.metastore_client.clone(),
+                quickwit_services.index_manager.clone(),
+                authenticator.clone(),
+                !disable_ingest_v1(),
+                enable_ingest_v2(),
+            )
+            .or(cluster_handler(quickwit_services.cluster.clone()))
+            .boxed()
+            .or(node_info_handler(
+                BuildInfo::get(),
+                RuntimeInfo::get(),
+                quickwit_services.node_config.clone(),
+            ))
+            .boxed()
+            .or(indexing_get_handler(
+                quickwit_services.indexing_service_opt.clone(),
+            ))
+            .boxed()
+            .or(indexing_sources_get_handler(
+                quickwit_services.indexing_service_opt.clone(),
+            ))
+            .boxed()
+            .or(search_routes(
+                quickwit_services.search_service.clone(),
+                quickwit_services.running_searches.clone(),
+                authenticator.clone(),
+                rollout_router.clone(),
+                quickwit_services
+                    .node_config
+                    .rest_config
+                    .content_length_limits
+                    .search,
+            ))
+            .boxed()
+            .or(
+                require_admin(authenticator.clone()).and(ingest_api_handlers(IngestApiContext {
+                    ingest_router: quickwit_services.ingest_router_service.clone(),
+                    ingest_service: quickwit_services.ingest_service.clone(),
+                    write_alias_resolver,
+                    rollout_router,
+                    ingestion_freeze_checker: IngestionFreezeChecker::new(
+                        quickwit_services.metastore_client.clone(),
+                    ),
+                    config: quickwit_services.node_config.ingest_api_config.clone(),
+                    enable_ingest_v1: !disable_ingest_v1(),
+                    enable_ingest_v2: enable_ingest_v2(),
+                })),
+            )
+            .boxed()
+            .or(
+                require_admin(authenticator.clone()).and(otlp_ingest_api_handlers(
+                    quickwit_services.otlp_logs_service_opt.clone(),
+                    quickwit_services.otlp_traces_service_opt.clone(),
+                )),
+            )
+            .boxed()
+            .or(
+                require_admin(authenticator.clone()).and(index_management_handlers(
+                    quickwit_services.index_manager.clone(),
+                    quickwit_services.node_config.clone(),
+                )),
+            )
+            .boxed()
+            .or(
+                require_admin(authenticator.clone()).and(delete_task_api_handlers(
+                    quickwit_services.metastore_client.clone(),
+                )),
+            )
+            .boxed()
+            .or(jaeger_api_handlers(
+                quickwit_services.jaeger_service_opt.clone(),
+                authenticator.clone(),
+            ))
+            .boxed()
+            .or(
+                require_admin(authenticator).and(index_template_api_handlers(
+                    quickwit_services.metastore_client.clone(),
+                )),
+            )
+            .boxed(),
         )
-        .or(cluster_handler(quickwit_services.cluster.clone()))
-        .boxed()
-        .or(node_info_handler(
-            BuildInfo::get(),
-            RuntimeInfo::get(),
-            quickwit_services.node_config.clone(),
-        ))
-        .boxed()
-        .or(indexing_get_handler(
-            quickwit_services.indexing_service_opt.clone(),
-        ))
-        .boxed()
-        .or(search_routes(quickwit_services.search_service.clone()))
-        .boxed()
-        .or(ingest_api_handlers(
-            quickwit_services.ingest_router_service.clone(),
-            quickwit_services.ingest_service.clone(),
-            quickwit_services.node_config.ingest_api_config.clone(),
-            !disable_ingest_v1(),
-            enable_ingest_v2(),
-        ))
-        .boxed()
-        .or(otlp_ingest_api_handlers(
-            quickwit_services.otlp_logs_service_opt.clone(),
-            quickwit_services.otlp_traces_service_opt.clone(),
-        ))
-        .boxed()
-        .or(index_management_handlers(
-            quickwit_services.index_manager.clone(),
-            quickwit_services.node_config.clone(),
-        ))
-        .boxed()
-        .or(delete_task_api_handlers(
-            quickwit_services.metastore_client.clone(),
-        ))
-        .boxed()
-        .or(jaeger_api_handlers(
-            quickwit_services.jaeger_service_opt.clone(),
-        ))
-        .boxed()
-        .or(index_template_api_handlers(
-            quickwit_services.metastore_client.clone(),
-        ))
-        .boxed(),
-    )
 }
 
 /// This function returns a formatted error based on the given rejection reason.
@@ -347,16 +509,10 @@ pub async fn recover_fn(rejection: Rejection) -> Result<impl Reply, Rejection> {
 pub async fn recover_fn_final(rejection: Rejection) -> Result<impl Reply, Rejection> {
     let error = get_status_with_error(rejection).unwrap_or_else(|rejection: Rejection| {
         if rejection.is_not_found() {
-            RestApiError {
-                status_code: StatusCode::NOT_FOUND,
-                message: "Route not found".to_string(),
-            }
+            RestApiError::new(StatusCode::NOT_FOUND, "Route not found")
         } else {
             error!("REST server error: {:?}", rejection);
-            RestApiError {
-                status_code: StatusCode::INTERNAL_SERVER_ERROR,
-                message: "internal server error".to_string(),
-            }
+            RestApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
         }
     });
     let status_code = error.status_code;
@@ -369,96 +525,119 @@ pub async fn recover_fn_final(rejection: Rejection) -> Result<impl Reply, Reject
 
 fn get_status_with_error(rejection: Rejection) -> Result<RestApiError, Rejection> {
     if let Some(error) = rejection.find::<crate::format::UnsupportedMediaType>() {
-        Ok(RestApiError {
-            status_code: StatusCode::UNSUPPORTED_MEDIA_TYPE,
-            message: error.to_string(),
-        })
+        Ok(RestApiError::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            error.to_string(),
+        ))
     } else if let Some(error) = rejection.find::<serde_qs::Error>() {
-        Ok(RestApiError {
-            status_code: StatusCode::BAD_REQUEST,
-            message: error.to_string(),
-        })
+        let message = error.to_string();
+        let parameter_opt = parameter_from_deserialization_error(&message);
+        Ok(RestApiError::new(StatusCode::BAD_REQUEST, message).with_parameter(parameter_opt))
     } else if let Some(error) = rejection.find::<InvalidJsonRequest>() {
         // Happens when the request body could not be deserialized correctly.
-        Ok(RestApiError {
-            status_code: StatusCode::BAD_REQUEST,
-            message: error.0.to_string(),
-        })
+        let message = error.0.to_string();
+        let parameter_opt = parameter_from_deserialization_error(&message);
+        Ok(RestApiError::new(StatusCode::BAD_REQUEST, message).with_parameter(parameter_opt))
     } else if let Some(error) = rejection.find::<warp::filters::body::BodyDeserializeError>() {
         // Happens when the request body could not be deserialized correctly.
-        Ok(RestApiError {
-            status_code: StatusCode::BAD_REQUEST,
-            message: error.to_string(),
-        })
+        let message = error.to_string();
+        let parameter_opt = parameter_from_deserialization_error(&message);
+        Ok(RestApiError::new(StatusCode::BAD_REQUEST, message).with_parameter(parameter_opt))
     } else if let Some(error) = rejection.find::<warp::reject::UnsupportedMediaType>() {
-        Ok(RestApiError {
-            status_code: StatusCode::UNSUPPORTED_MEDIA_TYPE,
-            message: error.to_string(),
-        })
+        Ok(RestApiError::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            error.to_string(),
+        ))
     } else if let Some(error) = rejection.find::<UnsupportedEncoding>() {
-        Ok(RestApiError {
-            status_code: StatusCode::UNSUPPORTED_MEDIA_TYPE,
-            message: error.to_string(),
-        })
+        Ok(RestApiError::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            error.to_string(),
+        ))
     } else if let Some(error) = rejection.find::<CorruptedData>() {
-        Ok(RestApiError {
-            status_code: StatusCode::BAD_REQUEST,
-            message: error.to_string(),
-        })
+        Ok(RestApiError::new(
+            StatusCode::BAD_REQUEST,
+            error.to_string(),
+        ))
     } else if let Some(error) = rejection.find::<warp::reject::InvalidQuery>() {
-        Ok(RestApiError {
-            status_code: StatusCode::BAD_REQUEST,
-            message: error.to_string(),
-        })
+        Ok(RestApiError::new(
+            StatusCode::BAD_REQUEST,
+            error.to_string(),
+        ))
     } else if let Some(error) = rejection.find::<warp::reject::LengthRequired>() {
-        Ok(RestApiError {
-            status_code: StatusCode::LENGTH_REQUIRED,
-            message: error.to_string(),
-        })
+        Ok(RestApiError::new(
+            StatusCode::LENGTH_REQUIRED,
+            error.to_string(),
+        ))
     } else if let Some(error) = rejection.find::<warp::reject::MissingHeader>() {
-        Ok(RestApiError {
-            status_code: StatusCode::BAD_REQUEST,
-            message: error.to_string(),
-        })
+        Ok(
+            RestApiError::new(StatusCode::BAD_REQUEST, error.to_string())
+                .with_parameter(Some(error.name().to_string())),
+        )
     } else if let Some(error) = rejection.find::<warp::reject::InvalidHeader>() {
-        Ok(RestApiError {
-            status_code: StatusCode::BAD_REQUEST,
-            message: error.to_string(),
-        })
+        Ok(
+            RestApiError::new(StatusCode::BAD_REQUEST, error.to_string())
+                .with_parameter(Some(error.name().to_string())),
+        )
     } else if let Some(error) = rejection.find::<warp::reject::PayloadTooLarge>() {
-        Ok(RestApiError {
-            status_code: StatusCode::PAYLOAD_TOO_LARGE,
-            message: error.to_string(),
-        })
+        Ok(RestApiError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            error.to_string(),
+        ))
+    } else if let Some(error) = rejection.find::<Unauthenticated>() {
+        Ok(RestApiError::new(
+            StatusCode::UNAUTHORIZED,
+            error.0.to_string(),
+        ))
+    } else if let Some(error) = rejection.find::<Forbidden>() {
+        Ok(RestApiError::new(
+            StatusCode::FORBIDDEN,
+            error.0.to_string(),
+        ))
     } else if let Some(err) = rejection.find::<TooManyRequests>() {
-        Ok(RestApiError {
-            status_code: StatusCode::TOO_MANY_REQUESTS,
-            message: err.to_string(),
-        })
+        Ok(RestApiError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            err.to_string(),
+        ))
     } else if let Some(error) = rejection.find::<InvalidArgument>() {
         // Happens when the url path or request body contains invalid argument(s).
-        Ok(RestApiError {
-            status_code: StatusCode::BAD_REQUEST,
-            message: error.0.to_string(),
-        })
+        Ok(RestApiError::new(
+            StatusCode::BAD_REQUEST,
+            error.0.to_string(),
+        ))
     } else if let Some(error) = rejection.find::<warp::reject::MethodNotAllowed>() {
-        Ok(RestApiError {
-            status_code: StatusCode::METHOD_NOT_ALLOWED,
-            message: error.to_string(),
-        })
+        Ok(RestApiError::new(
+            StatusCode::METHOD_NOT_ALLOWED,
+            error.to_string(),
+        ))
     } else {
         Err(rejection)
     }
 }
 
-fn build_cors(cors_origins: &[String]) -> CorsLayer {
-    let mut cors = CorsLayer::new().allow_methods([
-        Method::GET,
-        Method::POST,
-        Method::PUT,
-        Method::DELETE,
-        Method::OPTIONS,
-    ]);
+fn build_cors(rest_config: &RestConfig) -> CorsLayer {
+    let cors_origins = &rest_config.cors_allow_origins;
+    let mut cors = if rest_config.cors_allow_methods.is_empty() {
+        CorsLayer::new().allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+    } else if rest_config
+        .cors_allow_methods
+        .iter()
+        .any(|method| method == "*")
+    {
+        CorsLayer::new().allow_methods(tower_http::cors::Any)
+    } else {
+        let methods = rest_config
+            .cors_allow_methods
+            .iter()
+            .map(|method| method.parse::<Method>().unwrap())
+            .collect::<Vec<_>>();
+        CorsLayer::new().allow_methods(methods)
+    };
     if !cors_origins.is_empty() {
         let allow_any = cors_origins.iter().any(|origin| origin.as_str() == "*");
 
@@ -474,7 +653,23 @@ fn build_cors(cors_origins: &[String]) -> CorsLayer {
             cors = cors.allow_origin(origins);
         };
     }
-
+    if rest_config
+        .cors_allow_headers
+        .iter()
+        .any(|header| header == "*")
+    {
+        cors = cors.allow_headers(tower_http::cors::Any);
+    } else if !rest_config.cors_allow_headers.is_empty() {
+        let headers = rest_config
+            .cors_allow_headers
+            .iter()
+            .map(|header| header.parse::<HeaderName>().unwrap())
+            .collect::<Vec<_>>();
+        cors = cors.allow_headers(headers);
+    }
+    if rest_config.cors_allow_credentials {
+        cors = cors.allow_credentials(true);
+    }
     cors
 }
 
@@ -731,7 +926,7 @@ mod tests {
     async fn test_cors() {
         // No cors enabled
         {
-            let cors = build_cors(&[]);
+            let cors = build_cors(&rest_config_with_cors_origins(&[]));
 
             let mut layer = ServiceBuilder::new().layer(cors).service(HelloWorld);
 
@@ -762,7 +957,7 @@ mod tests {
 
         // Wildcard cors enabled
         {
-            let cors = build_cors(&["*".to_string()]);
+            let cors = build_cors(&rest_config_with_cors_origins(&["*"]));
 
             let mut layer = ServiceBuilder::new().layer(cors).service(HelloWorld);
 
@@ -799,7 +994,7 @@ mod tests {
 
         // Specific origin cors enabled
         {
-            let cors = build_cors(&["https://quickwit.io".to_string()]);
+            let cors = build_cors(&rest_config_with_cors_origins(&["https://quickwit.io"]));
 
             let mut layer = ServiceBuilder::new().layer(cors).service(HelloWorld);
 
@@ -850,10 +1045,10 @@ mod tests {
 
         // Specific multiple-origin cors enabled
         {
-            let cors = build_cors(&[
-                "https://quickwit.io".to_string(),
-                "http://localhost:3000".to_string(),
-            ]);
+            let cors = build_cors(&rest_config_with_cors_origins(&[
+                "https://quickwit.io",
+                "http://localhost:3000",
+            ]));
 
             let mut layer = ServiceBuilder::new().layer(cors).service(HelloWorld);
 
@@ -906,6 +1101,48 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_cors_methods_headers_and_credentials() {
+        let mut rest_config = rest_config_with_cors_origins(&["https://quickwit.io"]);
+        rest_config.cors_allow_methods = vec!["GET".to_string(), "POST".to_string()];
+        rest_config.cors_allow_headers = vec!["content-type".to_string()];
+        rest_config.cors_allow_credentials = true;
+        let cors = build_cors(&rest_config);
+
+        let mut layer = ServiceBuilder::new().layer(cors).service(HelloWorld);
+
+        let resp = layer
+            .call(cors_request("https://quickwit.io"))
+            .await
+            .unwrap();
+        let headers = resp.headers();
+        assert_eq!(
+            headers.get("Access-Control-Allow-Origin"),
+            Some(&"https://quickwit.io".parse::<HeaderValue>().unwrap())
+        );
+        assert_eq!(
+            headers.get("Access-Control-Allow-Methods"),
+            Some(&"GET,POST".parse::<HeaderValue>().unwrap())
+        );
+        assert_eq!(
+            headers.get("Access-Control-Allow-Headers"),
+            Some(&"content-type".parse::<HeaderValue>().unwrap())
+        );
+        assert_eq!(
+            headers.get("Access-Control-Allow-Credentials"),
+            Some(&"true".parse::<HeaderValue>().unwrap())
+        );
+    }
+
+    fn rest_config_with_cors_origins(cors_origins: &[&str]) -> RestConfig {
+        let mut rest_config = NodeConfig::for_test().rest_config;
+        rest_config.cors_allow_origins = cors_origins
+            .iter()
+            .map(|origin| origin.to_string())
+            .collect();
+        rest_config
+    }
+
     fn cors_request(origin: &'static str) -> Request<()> {
         let mut request = Request::new(());
         (*request.method_mut()) = Method::OPTIONS;
@@ -977,11 +1214,12 @@ mod tests {
             metastore_server_opt: None,
             node_config: Arc::new(node_config.clone()),
             search_service: Arc::new(MockSearchService::new()),
+            running_searches: Arc::default(),
             jaeger_service_opt: None,
             env_filter_reload_fn: crate::do_nothing_env_filter_reload_fn(),
         };
 
-        let handler = api_v1_routes(Arc::new(quickwit_services))
+        let handler = api_v1_routes(Arc::new(quickwit_services), Arc::default())
             .recover(recover_fn_final)
             .with(warp::reply::with::headers(
                 node_config.rest_config.extra_headers.clone(),
@@ -15,21 +15,104 @@
 use hyper::header::CONTENT_TYPE;
 use hyper::http::HeaderValue;
 use hyper::{Body, Response, StatusCode};
-use quickwit_proto::ServiceError;
-use serde::{self, Serialize};
+use quickwit_proto::{ServiceError, ServiceErrorCode};
+use serde::ser::SerializeStruct;
+use serde::{self, Serialize, Serializer};
 use warp::Reply;
 
 use crate::format::BodyFormat;
+use crate::request_tracing::current_trace_id;
 
 const JSON_SERIALIZATION_ERROR: &str = "JSON serialization failed.";
 
-#[derive(Serialize)]
+/// Error returned by the REST API. Along with a human-readable message, it exposes a stable
+/// error code, the offending request parameter if known, and whether the request may be retried,
+/// so that clients can handle failures without parsing messages.
 pub(crate) struct RestApiError {
-    // For now, we want to keep [`RestApiError`] as simple as possible
-    // and return just a message.
-    #[serde(skip_serializing)]
     pub status_code: StatusCode,
+    pub error_code: &'static str,
     pub message: String,
+    pub parameter_opt: Option<String>,
+    pub retryable: bool,
+}
+
+impl RestApiError {
+    /// Creates an error for a failure that did not originate from a service, deriving its code
+    /// from the HTTP status code.
+    pub fn new(status_code: StatusCode, message: impl Into<String>) -> Self {
+        let (error_code, retryable) = match status_code {
+            StatusCode::BAD_REQUEST => ("bad_request", false),
+            StatusCode::UNAUTHORIZED => ("unauthenticated", false),
+            StatusCode::FORBIDDEN => ("forbidden", false),
+            StatusCode::NOT_FOUND => ("not_found", false),
+            StatusCode::METHOD_NOT_ALLOWED => ("method_not_allowed", false),
+            StatusCode::REQUEST_TIMEOUT => ("timeout", true),
+            StatusCode::LENGTH_REQUIRED => ("length_required", false),
+            StatusCode::PAYLOAD_TOO_LARGE => ("payload_too_large", false),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE => ("unsupported_media_type", false),
+            StatusCode::TOO_MANY_REQUESTS => ("too_many_requests", true),
+            StatusCode::NOT_IMPLEMENTED => ("not_implemented", false),
+            StatusCode::SERVICE_UNAVAILABLE => ("unavailable", true),
+            _ => ("internal", false),
+        };
+        RestApiError {
+            status_code,
+            error_code,
+            message: message.into(),
+            parameter_opt: None,
+            retryable,
+        }
+    }
+
+    pub fn from_service_error<E: ServiceError>(error: &E) -> Self {
+        let error_code: ServiceErrorCode = error.error_code();
+        RestApiError {
+            status_code: error_code.http_status_code(),
+            error_code: error_code.as_str(),
+            message: error.to_string(),
+            parameter_opt: None,
+            retryable: error_code.is_retryable(),
+        }
+    }
+
+    pub fn with_parameter(mut self, parameter_opt: Option<String>) -> Self {
+        self.parameter_opt = parameter_opt;
+        self
+    }
+}
+
+impl Serialize for RestApiError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        let trace_id_opt = current_trace_id();
+        let num_fields =
+            3 + self.parameter_opt.is_some() as usize + trace_id_opt.is_some() as usize;
+        let mut state = serializer.serialize_struct("RestApiError", num_fields)?;
+        state.serialize_field("error_code", self.error_code)?;
+        state.serialize_field("message", &self.message)?;
+
+        if let Some(parameter) = &self.parameter_opt {
+            state.serialize_field("parameter", parameter)?;
+        }
+        state.serialize_field("retryable", &self.retryable)?;
+
+        if let Some(trace_id) = trace_id_opt {
+            state.serialize_field("trace_id", &trace_id.to_string())?;
+        }
+        state.end()
+    }
+}
+
+/// Extracts the name of the offending field from a deserialization error message, as formatted
+/// by serde for unknown, missing, and duplicate fields.
+pub(crate) fn parameter_from_deserialization_error(error_message: &str) -> Option<String> {
+    ["unknown field `", "missing field `", "duplicate field `"]
+        .iter()
+        .find_map(|prefix| {
+            let start = error_message.find(prefix)? + prefix.len();
+            let len = error_message[start..].find('`')?;
+            Some(error_message[start..start + len].to_string())
+        })
 }
 
 /// Makes a JSON API response from a result.
@@ -39,10 +122,7 @@ pub(crate) fn into_rest_api_response<T: serde::Serialize, E: ServiceError>(
     result: Result<T, E>,
     body_format: BodyFormat,
 ) -> RestApiResponse {
-    let rest_api_result = result.map_err(|error| RestApiError {
-        status_code: error.error_code().http_status_code(),
-        message: error.to_string(),
-    });
+    let rest_api_result = result.map_err(|error| RestApiError::from_service_error(&error));
     let status_code = match &rest_api_result {
         Ok(_) => StatusCode::OK,
         Err(error) => error.status_code,
@@ -84,12 +164,60 @@ impl Reply for RestApiResponse {
                     limit_per_min = 10,
                     "REST body json serialization error."
                 );
-                warp::reply::json(&RestApiError {
-                    status_code: StatusCode::INTERNAL_SERVER_ERROR,
-                    message: JSON_SERIALIZATION_ERROR.to_string(),
-                })
+                warp::reply::json(&RestApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    JSON_SERIALIZATION_ERROR,
+                ))
                 .into_response()
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rest_api_error_serialization() {
+        let error = RestApiError::new(StatusCode::TOO_MANY_REQUESTS, "rate limited");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "error_code": "too_many_requests",
+                "message": "rate limited",
+                "retryable": true,
+            })
+        );
+        let error = RestApiError::new(StatusCode::BAD_REQUEST, "unknown field `foo`")
+            .with_parameter(Some("foo".to_string()));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "error_code": "bad_request",
+                "message": "unknown field `foo`",
+                "parameter": "foo",
+                "retryable": false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parameter_from_deserialization_error() {
+        assert_eq!(
+            parameter_from_deserialization_error(
+                "unknown field `max_hit`, expected one of `query`, `max_hits`"
+            )
+            .as_deref(),
+            Some("max_hit")
+        );
+        assert_eq!(
+            parameter_from_deserialization_error("missing field `query`").as_deref(),
+            Some("query")
+        );
+        assert_eq!(
+            parameter_from_deserialization_error("invalid type: string, expected u64"),
+            None
+        );
+    }
+}
@@ -0,0 +1,310 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, Weak};
+use std::time::Duration;
+
+use quickwit_config::{IndexRollout, INGEST_V2_SOURCE_ID};
+use quickwit_metastore::{IndexMetadata, ListIndexesMetadataResponseExt};
+use quickwit_proto::ingest::router::{
+    IngestRequestV2, IngestRouterService, IngestRouterServiceClient, IngestSubrequest,
+};
+use quickwit_proto::ingest::{CommitTypeV2, DocBatchV2};
+use quickwit_proto::metastore::{
+    ListIndexesMetadataRequest, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::search::SearchRequest;
+use quickwit_proto::types::IndexId;
+use quickwit_search::SearchService;
+use tracing::{debug, error};
+
+use crate::request_sampling::RequestSampler;
+use crate::SERVE_METRICS;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+struct ActiveRollout {
+    candidate_index_id: IndexId,
+    ingest_sampler: RequestSampler,
+    search_sampler: RequestSampler,
+}
+
+impl ActiveRollout {
+    fn new(candidate_index_id: IndexId, rollout: &IndexRollout) -> Self {
+        Self {
+            candidate_index_id,
+            ingest_sampler: RequestSampler::new(rollout.ingest_percentage),
+            search_sampler: RequestSampler::new(rollout.search_percentage),
+        }
+    }
+}
+
+/// Active rollouts keyed by primary index ID.
+type ActiveRollouts = HashMap<IndexId, Arc<ActiveRollout>>;
+
+/// Mirrors a percentage of the ingest and search requests targeting the primary index of an
+/// ongoing rollout to its candidate index, so that the control plane can compare both indexes
+/// before promoting the configuration of the candidate. The requests are mirrored, not split: the
+/// primary index keeps serving all of them. Conversely, the root searchers exclude the candidate
+/// indexes from wildcard index ID patterns to avoid returning duplicate hits.
+///
+/// The ongoing rollouts are refreshed periodically from the metastore.
+#[derive(Clone, Default)]
+pub struct RolloutRouter {
+    active_rollouts: Arc<RwLock<ActiveRollouts>>,
+}
+
+impl RolloutRouter {
+    /// Creates a router and spawns a task that refreshes its rollouts until the router is
+    /// dropped.
+    pub(crate) fn spawn(metastore: MetastoreServiceClient) -> Self {
+        let rollout_router = Self::default();
+        let weak_active_rollouts = Arc::downgrade(&rollout_router.active_rollouts);
+        tokio::spawn(refresh_loop(metastore, weak_active_rollouts));
+        rollout_router
+    }
+
+    /// Returns the candidate index to which an ingest request targeting `index_id` should be
+    /// mirrored, if any.
+    pub(crate) fn sample_ingest(&self, index_id: &str) -> Option<IndexId> {
+        self.sample(index_id, |active_rollout| &active_rollout.ingest_sampler)
+    }
+
+    /// Returns the candidate index to which a search request targeting `index_id_patterns` should
+    /// be mirrored, if any. Only the searches targeting exactly the primary index are mirrored.
+    pub(crate) fn sample_search(&self, index_id_patterns: &[String]) -> Option<IndexId> {
+        let [index_id] = index_id_patterns else {
+            return None;
+        };
+        self.sample(index_id, |active_rollout| &active_rollout.search_sampler)
+    }
+
+    fn sample(
+        &self,
+        index_id: &str,
+        sampler_fn: impl Fn(&ActiveRollout) -> &RequestSampler,
+    ) -> Option<IndexId> {
+        let active_rollouts = self
+            .active_rollouts
+            .read()
+            .expect("lock should not be poisoned");
+        let active_rollout = active_rollouts.get(index_id)?;
+
+        if sampler_fn(active_rollout).sample() {
+            Some(active_rollout.candidate_index_id.clone())
+        } else {
+            None
+        }
+    }
+}
+
+async fn refresh_loop(
+    metastore: MetastoreServiceClient,
+    weak_active_rollouts: Weak<RwLock<ActiveRollouts>>,
+) {
+    // Rollouts are long-lived: there is no need to list the indexes right at startup.
+    let start = tokio::time::Instant::now() + REFRESH_INTERVAL;
+    let mut interval = tokio::time::interval_at(start, REFRESH_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let Some(active_rollouts) = weak_active_rollouts.upgrade() else {
+            return;
+        };
+        match fetch_indexes_metadata(&metastore).await {
+            Ok(indexes_metadata) => {
+                let mut active_rollouts_guard = active_rollouts
+                    .write()
+                    .expect("lock should not be poisoned");
+                *active_rollouts_guard =
+                    build_active_rollouts(&indexes_metadata, &active_rollouts_guard);
+            }
+            Err(error) => {
+                error!(%error, "failed to refresh index rollouts");
+            }
+        }
+    }
+}
+
+async fn fetch_indexes_metadata(
+    metastore: &MetastoreServiceClient,
+) -> anyhow::Result<Vec<IndexMetadata>> {
+    let indexes_metadata = metastore
+        .list_indexes_metadata(ListIndexesMetadataRequest::all())
+        .await?
+        .deserialize_indexes_metadata()
+        .await?;
+    Ok(indexes_metadata)
+}
+
+/// Builds the active rollouts from the indexes metadata, keeping the samplers of the rollouts
+/// that were already active.
+fn build_active_rollouts(
+    indexes_metadata: &[IndexMetadata],
+    previous_active_rollouts: &ActiveRollouts,
+) -> ActiveRollouts {
+    let mut active_rollouts = ActiveRollouts::new();
+
+    for index_metadata in indexes_metadata {
+        let Some(rollout) = &index_metadata.index_config.rollout_opt else {
+            continue;
+        };
+        if !rollout.is_evaluating() {
+            continue;
+        }
+        let candidate_index_id = index_metadata.index_id();
+        let active_rollout = previous_active_rollouts
+            .get(&rollout.primary_index_id)
+            .filter(|active_rollout| active_rollout.candidate_index_id == candidate_index_id)
+            .cloned()
+            .unwrap_or_else(|| {
+                Arc::new(ActiveRollout::new(candidate_index_id.to_string(), rollout))
+            });
+        active_rollouts.insert(rollout.primary_index_id.clone(), active_rollout);
+    }
+    active_rollouts
+}
+
+/// Sends a copy of the documents ingested into the primary index to the candidate index in the
+/// background.
+pub(crate) fn mirror_ingest(
+    candidate_index_id: IndexId,
+    doc_batch: DocBatchV2,
+    ingest_router: IngestRouterServiceClient,
+) {
+    let subrequest = IngestSubrequest {
+        subrequest_id: 0,
+        index_id: candidate_index_id,
+        source_id: INGEST_V2_SOURCE_ID.to_string(),
+        doc_batch: Some(doc_batch),
+    };
+    let ingest_request = IngestRequestV2 {
+        commit_type: CommitTypeV2::Auto as i32,
+        subrequests: vec![subrequest],
+    };
+    tokio::spawn(async move {
+        let outcome = match ingest_router.ingest(ingest_request).await {
+            Ok(ingest_response) if ingest_response.failures.is_empty() => "success",
+            Ok(_) => "failure",
+            Err(error) => {
+                debug!(%error, "failed to mirror ingest request to rollout candidate");
+                "failure"
+            }
+        };
+        SERVE_METRICS
+            .rollout_mirrored_requests_total
+            .with_label_values(["ingest", outcome])
+            .inc();
+    });
+}
+
+/// Replays a search request targeting the primary index against the candidate index in the
+/// background. The response is discarded: the searches only feed the usage of the candidate
+/// index.
+pub(crate) fn mirror_search(
+    candidate_index_id: IndexId,
+    mut search_request: SearchRequest,
+    search_service: Arc<dyn SearchService>,
+) {
+    search_request.index_id_patterns = vec![candidate_index_id];
+
+    tokio::spawn(async move {
+        let outcome = match search_service.root_search(search_request).await {
+            Ok(_) => "success",
+            Err(error) => {
+                debug!(%error, "failed to mirror search request to rollout candidate");
+                "failure"
+            }
+        };
+        SERVE_METRICS
+            .rollout_mirrored_requests_total
+            .with_label_values(["search", outcome])
+            .inc();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_config::RolloutState;
+
+    use super::*;
+
+    fn candidate_index_metadata(
+        candidate_index_id: &str,
+        primary_index_id: &str,
+        state: RolloutState,
+    ) -> IndexMetadata {
+        let mut index_metadata =
+            IndexMetadata::for_test(candidate_index_id, &format!("ram:///{candidate_index_id}"));
+        index_metadata.index_config.rollout_opt = Some(IndexRollout {
+            primary_index_id: primary_index_id.to_string(),
+            ingest_percentage: 50.0,
+            search_percentage: 0.0,
+            evaluation_period: IndexRollout::default_evaluation_period(),
+            min_num_docs: 0,
+            max_storage_ratio: None,
+            max_scanned_bytes_ratio: None,
+            state,
+        });
+        index_metadata
+    }
+
+    #[test]
+    fn test_build_active_rollouts() {
+        let indexes_metadata = [
+            IndexMetadata::for_test("logs", "ram:///logs"),
+            candidate_index_metadata("logs-candidate", "logs", RolloutState::Evaluating),
+            candidate_index_metadata("traces-candidate", "traces", RolloutState::Promoted),
+        ];
+        let active_rollouts = build_active_rollouts(&indexes_metadata, &ActiveRollouts::new());
+        assert_eq!(active_rollouts.len(), 1);
+        assert_eq!(active_rollouts["logs"].candidate_index_id, "logs-candidate");
+
+        let previous_active_rollout = active_rollouts["logs"].clone();
+        let active_rollouts = build_active_rollouts(&indexes_metadata, &active_rollouts);
+        assert!(Arc::ptr_eq(
+            &active_rollouts["logs"],
+            &previous_active_rollout
+        ));
+    }
+
+    #[test]
+    fn test_rollout_router_sample() {
+        let rollout_router = RolloutRouter::default();
+        assert_eq!(rollout_router.sample_ingest("logs"), None);
+
+        let indexes_metadata = [candidate_index_metadata(
+            "logs-candidate",
+            "logs",
+            RolloutState::Evaluating,
+        )];
+        *rollout_router.active_rollouts.write().unwrap() =
+            build_active_rollouts(&indexes_metadata, &ActiveRollouts::new());
+
+        let num_sampled_ingests = (0..10)
+            .filter_map(|_| rollout_router.sample_ingest("logs"))
+            .inspect(|candidate_index_id| assert_eq!(candidate_index_id, "logs-candidate"))
+            .count();
+        assert_eq!(num_sampled_ingests, 5);
+        assert_eq!(rollout_router.sample_ingest("other"), None);
+
+        assert_eq!(rollout_router.sample_search(&["logs".to_string()]), None);
+        assert_eq!(
+            rollout_router.sample_search(&["logs".to_string(), "other".to_string()]),
+            None
+        );
+    }
+}
@@ -16,23 +16,62 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use futures::TryStreamExt;
+use quickwit_proto::compression::{PayloadCodec, PayloadCompression};
 use quickwit_proto::error::convert_to_grpc_result;
 use quickwit_proto::search::{
-    search_service_server as grpc, GetKvRequest, GetKvResponse, LeafListFieldsRequest,
-    LeafSearchStreamRequest, LeafSearchStreamResponse, ListFieldsRequest, ListFieldsResponse,
-    ReportSplitsRequest, ReportSplitsResponse,
+    search_service_server as grpc, CancelLeafSearchesRequest, CancelLeafSearchesResponse,
+    CancelSearchRequest, CancelSearchResponse, GetKvRequest, GetKvResponse, LeafListFieldsRequest,
+    LeafSearchResponse, LeafSearchStreamRequest, LeafSearchStreamResponse, ListFieldsRequest,
+    ListFieldsResponse, PinSplitsRequest, PinSplitsResponse, ReportSplitsRequest,
+    ReportSplitsResponse,
 };
 use quickwit_proto::{set_parent_span_from_request_metadata, tonic, GrpcServiceError};
 use quickwit_search::SearchService;
 use tracing::instrument;
 
 #[derive(Clone)]
-pub struct GrpcSearchAdapter(Arc<dyn SearchService>);
+pub struct GrpcSearchAdapter {
+    search_service: Arc<dyn SearchService>,
+    payload_compression: PayloadCompression,
+}
+
+impl GrpcSearchAdapter {
+    /// Sets the compression applied to the intermediate aggregation results of leaf search
+    /// responses.
+    pub fn with_payload_compression(mut self, payload_compression: PayloadCompression) -> Self {
+        self.payload_compression = payload_compression;
+        self
+    }
+}
 
 impl From<Arc<dyn SearchService>> for GrpcSearchAdapter {
     fn from(search_service_arc: Arc<dyn SearchService>) -> Self {
-        GrpcSearchAdapter(search_service_arc)
+        GrpcSearchAdapter {
+            search_service: search_service_arc,
+            payload_compression: PayloadCompression::default(),
+        }
+    }
+}
+
+/// Compresses the intermediate aggregation result of a leaf search response on the blocking
+/// thread pool so that compressing large results does not stall the runtime.
+async fn compress_intermediate_aggregation_result(
+    mut leaf_search_response: LeafSearchResponse,
+    payload_compression: PayloadCompression,
+) -> LeafSearchResponse {
+    if payload_compression.codec == PayloadCodec::None
+        || leaf_search_response
+            .intermediate_aggregation_result
+            .is_none()
+    {
+        return leaf_search_response;
     }
+    tokio::task::spawn_blocking(move || {
+        leaf_search_response.compress_intermediate_aggregation_result(&payload_compression);
+        leaf_search_response
+    })
+    .await
+    .expect("compression task should not panic")
 }
 
 #[async_trait]
@@ -44,7 +83,7 @@ impl grpc::SearchService for GrpcSearchAdapter {
     ) -> Result<tonic::Response<quickwit_proto::search::SearchResponse>, tonic::Status> {
         set_parent_span_from_request_metadata(request.metadata());
         let search_request = request.into_inner();
-        let search_result = self.0.root_search(search_request).await;
+        let search_result = self.search_service.root_search(search_request).await;
         convert_to_grpc_result(search_result)
     }
 
@@ -55,7 +94,21 @@ impl grpc::SearchService for GrpcSearchAdapter {
     ) -> Result<tonic::Response<quickwit_proto::search::LeafSearchResponse>, tonic::Status> {
         set_parent_span_from_request_metadata(request.metadata());
         let leaf_search_request = request.into_inner();
-        let leaf_search_result = self.0.leaf_search(leaf_search_request).await;
+        // Callers running a version that cannot decode compressed results do not set the flag.
+        let payload_compression =
+            if leaf_search_request.accepts_compressed_intermediate_aggregation_result {
+                self.payload_compression
+            } else {
+                PayloadCompression::default()
+            };
+        let leaf_search_result = match self.search_service.leaf_search(leaf_search_request).await {
+            Ok(leaf_search_response) => Ok(compress_intermediate_aggregation_result(
+                leaf_search_response,
+                payload_compression,
+            )
+            .await),
+            Err(search_error) => Err(search_error),
+        };
         convert_to_grpc_result(leaf_search_result)
     }
 
@@ -66,7 +119,7 @@ impl grpc::SearchService for GrpcSearchAdapter {
     ) -> Result<tonic::Response<quickwit_proto::search::FetchDocsResponse>, tonic::Status> {
         set_parent_span_from_request_metadata(request.metadata());
         let fetch_docs_request = request.into_inner();
-        let fetch_docs_result = self.0.fetch_docs(fetch_docs_request).await;
+        let fetch_docs_result = self.search_service.fetch_docs(fetch_docs_request).await;
         convert_to_grpc_result(fetch_docs_result)
     }
 
@@ -85,7 +138,7 @@ impl grpc::SearchService for GrpcSearchAdapter {
         set_parent_span_from_request_metadata(request.metadata());
         let leaf_search_request = request.into_inner();
         let leaf_search_result = self
-            .0
+            .search_service
             .leaf_search_stream(leaf_search_request)
             .await
             .map_err(|error| error.into_grpc_status())?
@@ -100,7 +153,7 @@ impl grpc::SearchService for GrpcSearchAdapter {
     ) -> Result<tonic::Response<quickwit_proto::search::ListTermsResponse>, tonic::Status> {
         set_parent_span_from_request_metadata(request.metadata());
         let search_request = request.into_inner();
-        let search_result = self.0.root_list_terms(search_request).await;
+        let search_result = self.search_service.root_list_terms(search_request).await;
         convert_to_grpc_result(search_result)
     }
 
@@ -111,7 +164,10 @@ impl grpc::SearchService for GrpcSearchAdapter {
     ) -> Result<tonic::Response<quickwit_proto::search::LeafListTermsResponse>, tonic::Status> {
         set_parent_span_from_request_metadata(request.metadata());
         let leaf_search_request = request.into_inner();
-        let leaf_search_result = self.0.leaf_list_terms(leaf_search_request).await;
+        let leaf_search_result = self
+            .search_service
+            .leaf_list_terms(leaf_search_request)
+            .await;
         convert_to_grpc_result(leaf_search_result)
     }
 
@@ -120,7 +176,7 @@ impl grpc::SearchService for GrpcSearchAdapter {
         request: tonic::Request<quickwit_proto::search::ScrollRequest>,
     ) -> Result<tonic::Response<quickwit_proto::search::SearchResponse>, tonic::Status> {
         let scroll_request = request.into_inner();
-        let scroll_result = self.0.scroll(scroll_request).await;
+        let scroll_result = self.search_service.scroll(scroll_request).await;
         convert_to_grpc_result(scroll_result)
     }
 
@@ -131,7 +187,7 @@ impl grpc::SearchService for GrpcSearchAdapter {
     ) -> Result<tonic::Response<quickwit_proto::search::PutKvResponse>, tonic::Status> {
         set_parent_span_from_request_metadata(request.metadata());
         let put_request = request.into_inner();
-        self.0.put_kv(put_request).await;
+        self.search_service.put_kv(put_request).await;
         Ok(tonic::Response::new(
             quickwit_proto::search::PutKvResponse {},
         ))
@@ -144,7 +200,10 @@ impl grpc::SearchService for GrpcSearchAdapter {
     ) -> Result<tonic::Response<GetKvResponse>, tonic::Status> {
         set_parent_span_from_request_metadata(request.metadata());
         let get_search_after_context_request = request.into_inner();
-        let payload = self.0.get_kv(get_search_after_context_request).await;
+        let payload = self
+            .search_service
+            .get_kv(get_search_after_context_request)
+            .await;
         let get_response = GetKvResponse { payload };
         Ok(tonic::Response::new(get_response))
     }
@@ -156,17 +215,61 @@ impl grpc::SearchService for GrpcSearchAdapter {
     ) -> Result<tonic::Response<ReportSplitsResponse>, tonic::Status> {
         set_parent_span_from_request_metadata(request.metadata());
         let get_search_after_context_request = request.into_inner();
-        self.0.report_splits(get_search_after_context_request).await;
+        self.search_service
+            .report_splits(get_search_after_context_request)
+            .await;
         Ok(tonic::Response::new(ReportSplitsResponse {}))
     }
 
+    #[instrument(skip(self, request))]
+    async fn pin_splits(
+        &self,
+        request: tonic::Request<PinSplitsRequest>,
+    ) -> Result<tonic::Response<PinSplitsResponse>, tonic::Status> {
+        set_parent_span_from_request_metadata(request.metadata());
+        let pin_splits_request = request.into_inner();
+        let pin_splits_response = self.search_service.pin_splits(pin_splits_request).await;
+        Ok(tonic::Response::new(pin_splits_response))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn cancel_leaf_searches(
+        &self,
+        request: tonic::Request<CancelLeafSearchesRequest>,
+    ) -> Result<tonic::Response<CancelLeafSearchesResponse>, tonic::Status> {
+        set_parent_span_from_request_metadata(request.metadata());
+        let cancel_leaf_searches_request = request.into_inner();
+        let cancel_leaf_searches_response = self
+            .search_service
+            .cancel_leaf_searches(cancel_leaf_searches_request)
+            .await;
+        Ok(tonic::Response::new(cancel_leaf_searches_response))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn cancel_search(
+        &self,
+        request: tonic::Request<CancelSearchRequest>,
+    ) -> Result<tonic::Response<CancelSearchResponse>, tonic::Status> {
+        set_parent_span_from_request_metadata(request.metadata());
+        let cancel_search_request = request.into_inner();
+        let cancel_search_response = self
+            .search_service
+            .cancel_search(cancel_search_request)
+            .await;
+        Ok(tonic::Response::new(cancel_search_response))
+    }
+
     #[instrument(skip(self, request))]
     async fn list_fields(
         &self,
         request: tonic::Request<ListFieldsRequest>,
     ) -> Result<tonic::Response<ListFieldsResponse>, tonic::Status> {
         set_parent_span_from_request_metadata(request.metadata());
-        let resp = self.0.root_list_fields(request.into_inner()).await;
+        let resp = self
+            .search_service
+            .root_list_fields(request.into_inner())
+            .await;
         convert_to_grpc_result(resp)
     }
     #[instrument(skip(self, request))]
@@ -175,7 +278,10 @@ impl grpc::SearchService for GrpcSearchAdapter {
         request: tonic::Request<LeafListFieldsRequest>,
     ) -> Result<tonic::Response<ListFieldsResponse>, tonic::Status> {
         set_parent_span_from_request_metadata(request.metadata());
-        let resp = self.0.leaf_list_fields(request.into_inner()).await;
+        let resp = self
+            .search_service
+            .leaf_list_fields(request.into_inner())
+            .await;
         convert_to_grpc_result(resp)
     }
 
@@ -186,7 +292,7 @@ impl grpc::SearchService for GrpcSearchAdapter {
     ) -> Result<tonic::Response<quickwit_proto::search::SearchPlanResponse>, tonic::Status> {
         set_parent_span_from_request_metadata(request.metadata());
         let search_request = request.into_inner();
-        let search_result = self.0.search_plan(search_request).await;
+        let search_result = self.search_service.search_plan(search_request).await;
         convert_to_grpc_result(search_result)
     }
 }
@@ -16,11 +16,14 @@ mod grpc_adapter;
 mod rest_handler;
 
 pub use self::grpc_adapter::GrpcSearchAdapter;
-pub(crate) use self::rest_handler::{extract_index_id_patterns, extract_index_id_patterns_default};
 pub use self::rest_handler::{
-    search_get_handler, search_plan_get_handler, search_plan_post_handler, search_post_handler,
-    search_request_from_api_request, search_stream_handler, SearchApi, SearchRequestQueryString,
-    SortBy,
+    cancel_search_handler, explain_handler, field_stats_handler, list_running_searches_handler,
+    pin_splits_handler, search_get_handler, search_plan_get_handler, search_plan_post_handler,
+    search_post_handler, search_request_from_api_request, search_stream_handler,
+    unpin_splits_handler, SearchApi, SearchRequestQueryString, SortBy,
+};
+pub(crate) use self::rest_handler::{
+    extract_index_id_patterns, extract_index_id_patterns_default, SEARCH_REQUEST_ID_HEADER,
 };
 
 #[cfg(test)]
@@ -31,6 +34,7 @@ mod tests {
     use bytesize::ByteSize;
     use futures::TryStreamExt;
     use quickwit_common::ServiceStream;
+    use quickwit_config::SearcherConfig;
     use quickwit_indexing::MockSplitBuilder;
     use quickwit_metastore::{IndexMetadata, IndexMetadataResponseExt, ListSplitsResponseExt};
     use quickwit_proto::metastore::{
@@ -42,7 +46,7 @@ mod tests {
     use quickwit_query::query_ast::qast_json_helper;
     use quickwit_search::{
         create_search_client_from_grpc_addr, root_search_stream, ClusterClient, MockSearchService,
-        SearchError, SearchJobPlacer, SearchService, SearcherPool,
+        SearchError, SearchJobPlacer, SearchService, SearcherContext, SearcherPool,
     };
     use tokio_stream::wrappers::UnboundedReceiverStream;
     use tonic::transport::Server;
@@ -76,6 +80,7 @@ mod tests {
             fast_field: "timestamp".to_string(),
             output_format: OutputFormat::Csv as i32,
             partition_by_field: None,
+            security_context: None,
         };
         let mut mock_metastore = MockMetastoreService::new();
         let index_metadata = IndexMetadata::for_test("test-index", "ram:///indexes/test-index");
@@ -132,6 +137,7 @@ mod tests {
         let search_job_placer = SearchJobPlacer::new(searcher_pool);
         let cluster_client = ClusterClient::new(search_job_placer.clone());
         let stream = root_search_stream(
+            &SearcherContext::new(SearcherConfig::default(), None),
             request,
             MetastoreServiceClient::from_mock(mock_metastore),
             cluster_client,
@@ -15,16 +15,24 @@
 use std::convert::TryFrom;
 use std::sync::Arc;
 
+use bytesize::ByteSize;
 use futures::stream::StreamExt;
 use hyper::header::HeaderValue;
 use hyper::HeaderMap;
 use percent_encoding::percent_decode_str;
-use quickwit_config::validate_index_id_pattern;
-use quickwit_proto::search::{CountHits, OutputFormat, SortField, SortOrder};
+use quickwit_config::validate_federated_index_id_pattern;
+use quickwit_proto::search::{
+    CancelSearchRequest, CountHits, NodeSearchProfile, OutputFormat, RunningSearchInfo,
+    SearchProfile, SecurityContext, SortField, SortOrder, SplitSearchProfile,
+};
 use quickwit_proto::types::IndexId;
 use quickwit_proto::ServiceError;
 use quickwit_query::query_ast::query_ast_from_user_text;
-use quickwit_search::{SearchError, SearchPlanResponseRest, SearchResponseRest, SearchService};
+use quickwit_search::{
+    CancelSearchError, ClauseExplanation, ExplainRequest, ExplainResponse, GlobalDocAddress,
+    PinIndexSplitsRequest, PinIndexSplitsResponse, RunningSearches, SearchError,
+    SearchPlanResponseRest, SearchResponseRest, SearchService,
+};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value as JsonValue;
 use tracing::info;
@@ -32,10 +40,20 @@ use warp::hyper::header::CONTENT_TYPE;
 use warp::hyper::StatusCode;
 use warp::{reply, Filter, Rejection, Reply};
 
+use crate::authentication::{extract_security_context, require_authentication, Authenticator};
+use crate::elasticsearch_api::filter_source;
 use crate::rest_api_response::into_rest_api_response;
+use crate::rollout::{mirror_search, RolloutRouter};
 use crate::simple_list::{from_simple_list, to_simple_list};
 use crate::{with_arg, BodyFormat};
 
+/// Header carrying the warnings of a search response.
+const SEARCH_WARNING_HEADER: &str = "x-quickwit-warning";
+
+/// Header used by the clients to set the ID of their search requests. It is also set on the
+/// search responses. When the header is missing, an ID is generated.
+pub(crate) const SEARCH_REQUEST_ID_HEADER: &str = "x-quickwit-search-request-id";
+
 #[derive(utoipa::OpenApi)]
 #[openapi(
     paths(
@@ -44,16 +62,32 @@ use crate::{with_arg, BodyFormat};
         search_stream_handler,
         search_plan_get_handler,
         search_plan_post_handler,
+        explain_handler,
+        field_stats_handler,
+        list_running_searches_handler,
+        cancel_search_handler,
+        pin_splits_handler,
+        unpin_splits_handler,
     ),
     components(schemas(
         BodyFormat,
+        ClauseExplanation,
+        ExplainResponse,
+        FieldStats,
+        FieldStatsResponse,
+        FieldValueCount,
+        NodeSearchProfile,
         OutputFormat,
+        PinIndexSplitsResponse,
+        RunningSearchInfo,
+        SearchProfile,
         SearchRequestQueryString,
         SearchResponseRest,
         SearchPlanResponseRest,
         SortBy,
         SortField,
         SortOrder,
+        SplitSearchProfile,
     ),)
 )]
 pub struct SearchApi;
@@ -79,7 +113,7 @@ pub(crate) async fn extract_index_id_patterns(
     let mut index_id_patterns = Vec::new();
 
     for index_id_pattern in percent_decoded_comma_separated_index_id_patterns.split(',') {
-        validate_index_id_pattern(index_id_pattern, true)
+        validate_federated_index_id_pattern(index_id_pattern, true)
             .map_err(|error| crate::rest::InvalidArgument(error.to_string()))?;
         index_id_patterns.push(index_id_pattern.to_string());
     }
@@ -201,6 +235,26 @@ pub struct SearchRequestQueryString {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(serialize_with = "to_simple_list")]
     pub snippet_fields: Option<Vec<String>>,
+    /// Fast fields to return in the hits. If set, the hits only hold the values of these fields,
+    /// read from the columnar storage, instead of the whole stored documents.
+    #[serde(default)]
+    #[serde(deserialize_with = "from_simple_list")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "to_simple_list")]
+    pub docvalue_fields: Option<Vec<String>>,
+    /// Fields to return in the hits, as dot-separated paths that may contain `*` wildcards. The
+    /// other fields are removed from the hits before the response is serialized.
+    #[serde(default)]
+    #[serde(deserialize_with = "from_simple_list")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "to_simple_list")]
+    pub fields: Option<Vec<String>>,
+    /// Fields to remove from the hits, as dot-separated paths that may contain `*` wildcards.
+    #[serde(default)]
+    #[serde(deserialize_with = "from_simple_list")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "to_simple_list")]
+    pub exclude_fields: Option<Vec<String>>,
     /// If set, restrict search to documents with a `timestamp >= start_timestamp`.
     /// This timestamp is expressed in seconds.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -238,6 +292,11 @@ pub struct SearchRequestQueryString {
     #[schema(value_type = bool)]
     #[serde(default)]
     pub allow_failed_splits: bool,
+    /// If set, the response contains per-split execution statistics.
+    #[param(value_type = bool)]
+    #[schema(value_type = bool)]
+    #[serde(default)]
+    pub profile: bool,
 }
 
 mod count_hits_from_bool {
@@ -281,6 +340,7 @@ pub fn search_request_from_api_request(
         index_id_patterns,
         query_ast: query_ast_json,
         snippet_fields: search_request.snippet_fields.unwrap_or_default(),
+        docvalue_fields: search_request.docvalue_fields.unwrap_or_default(),
         start_timestamp: search_request.start_timestamp,
         end_timestamp: search_request.end_timestamp,
         max_hits: search_request.max_hits,
@@ -292,6 +352,10 @@ pub fn search_request_from_api_request(
         scroll_ttl_secs: None,
         search_after: None,
         count_hits: search_request.count_all.into(),
+        security_context: None,
+        profile: search_request.profile,
+        return_intermediate_aggregation: false,
+        search_request_id: None,
     };
     Ok(search_request)
 }
@@ -299,10 +363,27 @@ pub fn search_request_from_api_request(
 async fn search_endpoint(
     index_id_patterns: Vec<String>,
     search_request: SearchRequestQueryString,
-    search_service: &dyn SearchService,
+    search_request_uid: String,
+    security_context_opt: Option<SecurityContext>,
+    search_service: Arc<dyn SearchService>,
+    rollout_router: RolloutRouter,
 ) -> Result<SearchResponseRest, SearchError> {
     let allow_failed_splits = search_request.allow_failed_splits;
-    let search_request = search_request_from_api_request(index_id_patterns, search_request)?;
+    let include_fields_opt = search_request.fields.clone();
+    let exclude_fields_opt = search_request.exclude_fields.clone();
+    let mut search_request = search_request_from_api_request(index_id_patterns, search_request)?;
+    search_request.search_request_id = Some(search_request_uid);
+    search_request.security_context = security_context_opt;
+
+    if let Some(candidate_index_id) =
+        rollout_router.sample_search(&search_request.index_id_patterns)
+    {
+        mirror_search(
+            candidate_index_id,
+            search_request.clone(),
+            search_service.clone(),
+        );
+    }
     let search_response =
         search_service
             .root_search(search_request)
@@ -317,7 +398,13 @@ async fn search_endpoint(
                 }
                 Ok(search_response)
             })?;
-    let search_response_rest = SearchResponseRest::try_from(search_response)?;
+    let mut search_response_rest = SearchResponseRest::try_from(search_response)?;
+
+    if include_fields_opt.is_some() || exclude_fields_opt.is_some() {
+        for hit in &mut search_response_rest.hits {
+            filter_source(hit, &exclude_fields_opt, &include_fields_opt);
+        }
+    }
     Ok(search_response_rest)
 }
 
@@ -330,11 +417,14 @@ fn search_get_filter(
 }
 
 fn search_post_filter(
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (Vec<String>, SearchRequestQueryString), Error = Rejection> + Clone {
     warp::path!(String / "search")
         .and_then(extract_index_id_patterns)
         .and(warp::post())
-        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::content_length_limit(
+            content_length_limit.as_u64(),
+        ))
         .and(warp::body::json())
 }
 
@@ -347,33 +437,130 @@ fn search_plan_get_filter(
 }
 
 fn search_plan_post_filter(
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (Vec<String>, SearchRequestQueryString), Error = Rejection> + Clone {
     warp::path!(String / "search-plan")
         .and_then(extract_index_id_patterns)
         .and(warp::post())
-        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::content_length_limit(
+            content_length_limit.as_u64(),
+        ))
         .and(warp::body::json())
 }
 
+fn explain_filter(
+) -> impl Filter<Extract = (IndexId, ExplainRequestQueryString), Error = Rejection> + Clone {
+    warp::path!(String / "explain")
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
+fn field_stats_filter(
+) -> impl Filter<Extract = (IndexId, FieldStatsRequestQueryString), Error = Rejection> + Clone {
+    warp::path!(String / "field-stats")
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
 async fn search(
     index_id_patterns: Vec<String>,
     search_request: SearchRequestQueryString,
+    request_id_opt: Option<String>,
+    security_context_opt: Option<SecurityContext>,
     search_service: Arc<dyn SearchService>,
+    running_searches: Arc<RunningSearches>,
+    rollout_router: RolloutRouter,
 ) -> impl warp::Reply {
     info!(request =? search_request, "search");
     let body_format = search_request.format;
-    let result = search_endpoint(index_id_patterns, search_request, &*search_service).await;
-    into_rest_api_response(result, body_format)
+    let (running_search_guard, abort_registration) = match running_searches.register(
+        request_id_opt,
+        index_id_patterns.clone(),
+        security_context_opt.clone(),
+        search_service.clone(),
+    ) {
+        Ok(registration) => registration,
+        Err(search_error) => {
+            return into_rest_api_response::<(), _>(Err(search_error), body_format).into_response();
+        }
+    };
+    let search_future = search_endpoint(
+        index_id_patterns,
+        search_request,
+        running_search_guard.search_request_uid().to_string(),
+        security_context_opt,
+        search_service,
+        rollout_router,
+    );
+    let result = running_search_guard
+        .run_until_cancelled(abort_registration, search_future)
+        .await;
+    let warnings = result
+        .as_ref()
+        .map(|search_response| search_response.warnings.clone())
+        .unwrap_or_default();
+    let mut response = into_rest_api_response(result, body_format).into_response();
+
+    for warning in warnings {
+        if let Ok(header_value) = HeaderValue::from_str(&warning) {
+            response
+                .headers_mut()
+                .append(SEARCH_WARNING_HEADER, header_value);
+        }
+    }
+    if let Ok(header_value) = HeaderValue::from_str(running_search_guard.request_id()) {
+        response
+            .headers_mut()
+            .insert(SEARCH_REQUEST_ID_HEADER, header_value);
+    }
+    response
+}
+
+fn list_running_searches_filter() -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::path!("search" / "running").and(warp::get())
+}
+
+fn cancel_search_filter() -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::path!("search" / String).and(warp::delete())
+}
+
+async fn list_running_searches(
+    security_context_opt: Option<SecurityContext>,
+    running_searches: Arc<RunningSearches>,
+) -> impl warp::Reply {
+    let result: Result<Vec<RunningSearchInfo>, SearchError> =
+        Ok(running_searches.list(security_context_opt.as_ref()));
+    into_rest_api_response(result, BodyFormat::default())
+}
+
+async fn cancel_search(
+    request_id: String,
+    security_context_opt: Option<SecurityContext>,
+    search_service: Arc<dyn SearchService>,
+) -> impl warp::Reply {
+    info!(request_id=%request_id, "cancel-search");
+    let cancel_search_request = CancelSearchRequest {
+        request_id: request_id.clone(),
+        security_context: security_context_opt,
+    };
+    let result: Result<RunningSearchInfo, CancelSearchError> = search_service
+        .root_cancel_search(cancel_search_request)
+        .await
+        .running_search
+        .ok_or(CancelSearchError::NotFound(request_id));
+    into_rest_api_response(result, BodyFormat::default())
 }
 
 async fn search_plan(
     index_id_patterns: Vec<String>,
     search_request: SearchRequestQueryString,
+    security_context_opt: Option<SecurityContext>,
     search_service: Arc<dyn SearchService>,
 ) -> impl warp::Reply {
     let body_format = search_request.format;
     let result: Result<SearchPlanResponseRest, SearchError> = async {
-        let plan_request = search_request_from_api_request(index_id_patterns, search_request)?;
+        let mut plan_request = search_request_from_api_request(index_id_patterns, search_request)?;
+        plan_request.security_context = security_context_opt;
         let plan_response = search_service.search_plan(plan_request).await?;
         let response = serde_json::from_str(&plan_response.result)?;
         Ok(response)
@@ -382,6 +569,34 @@ async fn search_plan(
     into_rest_api_response(result, body_format)
 }
 
+async fn explain(
+    index_id: IndexId,
+    explain_request: ExplainRequestQueryString,
+    security_context_opt: Option<SecurityContext>,
+    search_service: Arc<dyn SearchService>,
+) -> impl warp::Reply {
+    info!(index_id=%index_id, request=?explain_request, "explain");
+    let result: Result<ExplainResponse, SearchError> = async {
+        let doc_address: GlobalDocAddress = explain_request.doc_address.parse().map_err(|_| {
+            SearchError::InvalidArgument(format!(
+                "invalid document address `{}`",
+                explain_request.doc_address
+            ))
+        })?;
+        let query_ast =
+            query_ast_from_user_text(&explain_request.query, explain_request.search_fields);
+        let request = ExplainRequest {
+            index_id,
+            query_ast: serde_json::to_string(&query_ast)?,
+            doc_address,
+            security_context: security_context_opt,
+        };
+        search_service.explain(request).await
+    }
+    .await;
+    into_rest_api_response(result, BodyFormat::default())
+}
+
 #[utoipa::path(
     get,
     tag = "Search",
@@ -399,9 +614,16 @@ async fn search_plan(
 /// Parses the search request from the request query string.
 pub fn search_get_handler(
     search_service: Arc<dyn SearchService>,
+    running_searches: Arc<RunningSearches>,
+    authenticator: Arc<Authenticator>,
+    rollout_router: RolloutRouter,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     search_get_filter()
+        .and(warp::header::optional::<String>(SEARCH_REQUEST_ID_HEADER))
+        .and(extract_security_context(authenticator))
         .and(with_arg(search_service))
+        .and(with_arg(running_searches))
+        .and(with_arg(rollout_router))
         .then(search)
 }
 
@@ -424,9 +646,17 @@ pub fn search_get_handler(
 /// Parses the search request from the request body.
 pub fn search_post_handler(
     search_service: Arc<dyn SearchService>,
+    running_searches: Arc<RunningSearches>,
+    authenticator: Arc<Authenticator>,
+    rollout_router: RolloutRouter,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    search_post_filter()
+    search_post_filter(content_length_limit)
+        .and(warp::header::optional::<String>(SEARCH_REQUEST_ID_HEADER))
+        .and(extract_security_context(authenticator))
         .and(with_arg(search_service))
+        .and(with_arg(running_searches))
+        .and(with_arg(rollout_router))
         .then(search)
 }
 
@@ -445,8 +675,10 @@ pub fn search_post_handler(
 /// Stream Search Index
 pub fn search_stream_handler(
     search_service: Arc<dyn SearchService>,
+    authenticator: Arc<Authenticator>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     search_stream_filter()
+        .and(extract_security_context(authenticator))
         .and(with_arg(search_service))
         .then(search_stream)
 }
@@ -468,8 +700,10 @@ pub fn search_stream_handler(
 /// Parses the search request from the request query string.
 pub fn search_plan_get_handler(
     search_service: Arc<dyn SearchService>,
+    authenticator: Arc<Authenticator>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     search_plan_get_filter()
+        .and(extract_security_context(authenticator))
         .and(with_arg(search_service))
         .then(search_plan)
 }
@@ -491,12 +725,320 @@ pub fn search_plan_get_handler(
 /// Parses the search request from the request body.
 pub fn search_plan_post_handler(
     search_service: Arc<dyn SearchService>,
+    authenticator: Arc<Authenticator>,
+    content_length_limit: ByteSize,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    search_plan_post_filter()
+    search_plan_post_filter(content_length_limit)
+        .and(extract_security_context(authenticator))
         .and(with_arg(search_service))
         .then(search_plan)
 }
 
+#[utoipa::path(
+    get,
+    tag = "Search",
+    path = "/{index_id}/explain",
+    responses(
+        (status = 200, description = "Successfully explained the query.", body = ExplainResponse)
+    ),
+    params(
+        ExplainRequestQueryString,
+        ("index_id" = String, Path, description = "The index ID of the document."),
+    )
+)]
+/// Explain Query
+///
+/// Explains whether and why a document matches a query.
+pub fn explain_handler(
+    search_service: Arc<dyn SearchService>,
+    authenticator: Arc<Authenticator>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    explain_filter()
+        .and(extract_security_context(authenticator))
+        .and(with_arg(search_service))
+        .then(explain)
+}
+
+#[utoipa::path(
+    get,
+    tag = "Search",
+    path = "/{index_id}/field-stats",
+    responses(
+        (status = 200, description = "Successfully computed the field statistics.", body = FieldStatsResponse)
+    ),
+    params(
+        FieldStatsRequestQueryString,
+        ("index_id" = String, Path, description = "The index ID to compute field statistics on."),
+    )
+)]
+/// Field Statistics
+///
+/// Returns the most frequent values and the approximate number of distinct values of some fast
+/// fields over the documents matching a query, for instance to populate facet panels.
+pub fn field_stats_handler(
+    search_service: Arc<dyn SearchService>,
+    authenticator: Arc<Authenticator>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    field_stats_filter()
+        .and(extract_security_context(authenticator))
+        .and(with_arg(search_service))
+        .then(field_stats)
+}
+
+#[utoipa::path(
+    get,
+    tag = "Search",
+    path = "/search/running",
+    responses(
+        (status = 200, description = "Running search requests.", body = [RunningSearchInfo])
+    ),
+)]
+/// List Running Searches
+///
+/// Lists the search requests of the caller running on the node that receives the request.
+pub fn list_running_searches_handler(
+    running_searches: Arc<RunningSearches>,
+    authenticator: Arc<Authenticator>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    list_running_searches_filter()
+        .and(extract_security_context(authenticator))
+        .and(with_arg(running_searches))
+        .then(list_running_searches)
+}
+
+#[utoipa::path(
+    delete,
+    tag = "Search",
+    path = "/search/{request_id}",
+    responses(
+        (status = 200, description = "Cancelled search request.", body = RunningSearchInfo)
+    ),
+    params(
+        ("request_id" = String, Path, description = "The ID of the search request to cancel."),
+    )
+)]
+/// Cancel Search
+///
+/// Cancels a search request of the caller, whichever search node it is running on. The leaf
+/// searches are aborted on all the search nodes and their search permits released.
+pub fn cancel_search_handler(
+    search_service: Arc<dyn SearchService>,
+    authenticator: Arc<Authenticator>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    cancel_search_filter()
+        .and(extract_security_context(authenticator))
+        .and(with_arg(search_service))
+        .then(cancel_search)
+}
+
+/// This struct represents the explain query passed to the REST API.
+#[derive(Deserialize, Debug, Eq, PartialEq, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+struct ExplainRequestQueryString {
+    /// Query text. The query language is that of tantivy.
+    pub query: String,
+    // Fields to search on.
+    #[param(rename = "search_field")]
+    #[serde(default)]
+    #[serde(rename(deserialize = "search_field"))]
+    #[serde(deserialize_with = "from_simple_list")]
+    pub search_fields: Option<Vec<String>>,
+    /// Address of the document, as returned by the `_shard_doc` sort of the Elasticsearch API:
+    /// `{split_id}:{segment_ord}:{doc_id}`.
+    pub doc_address: String,
+}
+
+/// Maximum number of distinct values counted per field. Beyond that, the distinct count is a
+/// lower bound.
+const FIELD_STATS_MAX_DISTINCT_VALUES: usize = 1_000;
+
+fn default_num_top_values() -> usize {
+    10
+}
+
+/// This struct represents the field statistics query passed to the REST API.
+#[derive(Deserialize, Debug, Eq, PartialEq, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+struct FieldStatsRequestQueryString {
+    /// Comma-separated list of the fast fields to compute statistics for.
+    #[param(value_type = String)]
+    #[serde(default)]
+    #[serde(deserialize_with = "from_simple_list")]
+    pub fields: Option<Vec<String>>,
+    /// Query text restricting the documents to compute statistics on. Defaults to all documents.
+    #[serde(default = "default_field_stats_query")]
+    pub query: String,
+    // Fields to search on.
+    #[param(rename = "search_field")]
+    #[serde(default)]
+    #[serde(rename(deserialize = "search_field"))]
+    #[serde(deserialize_with = "from_simple_list")]
+    pub search_fields: Option<Vec<String>>,
+    /// If set, restricts statistics to documents with a `timestamp >= start_timestamp`.
+    #[serde(default)]
+    pub start_timestamp: Option<i64>,
+    /// If set, restricts statistics to documents with a `timestamp < end_timestamp`.
+    #[serde(default)]
+    pub end_timestamp: Option<i64>,
+    /// Maximum number of top values returned per field.
+    #[serde(default = "default_num_top_values")]
+    pub num_top_values: usize,
+}
+
+fn default_field_stats_query() -> String {
+    "*".to_string()
+}
+
+/// Number of documents holding a given value.
+#[derive(Serialize, Deserialize, Debug, PartialEq, utoipa::ToSchema)]
+pub struct FieldValueCount {
+    #[schema(value_type = Object)]
+    pub value: JsonValue,
+    pub count: u64,
+}
+
+/// Statistics of a single field.
+#[derive(Serialize, Deserialize, Debug, PartialEq, utoipa::ToSchema)]
+pub struct FieldStats {
+    pub field: String,
+    /// Number of matching documents holding a value for the field.
+    pub num_docs_with_value: u64,
+    /// Number of distinct values of the field.
+    pub distinct_count: u64,
+    /// Whether the field has more distinct values than `distinct_count`.
+    pub distinct_count_is_lower_bound: bool,
+    /// Most frequent values of the field, by decreasing number of documents.
+    pub top_values: Vec<FieldValueCount>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, utoipa::ToSchema)]
+pub struct FieldStatsResponse {
+    /// Number of documents matching the query.
+    pub num_docs: u64,
+    pub fields: Vec<FieldStats>,
+}
+
+async fn field_stats(
+    index_id: IndexId,
+    field_stats_request: FieldStatsRequestQueryString,
+    security_context_opt: Option<SecurityContext>,
+    search_service: Arc<dyn SearchService>,
+) -> impl warp::Reply {
+    info!(index_id=%index_id, request=?field_stats_request, "field-stats");
+    let result = field_stats_endpoint(
+        index_id,
+        field_stats_request,
+        security_context_opt,
+        search_service,
+    )
+    .await;
+    into_rest_api_response(result, BodyFormat::default())
+}
+
+async fn field_stats_endpoint(
+    index_id: IndexId,
+    field_stats_request: FieldStatsRequestQueryString,
+    security_context_opt: Option<SecurityContext>,
+    search_service: Arc<dyn SearchService>,
+) -> Result<FieldStatsResponse, SearchError> {
+    let fields = field_stats_request.fields.unwrap_or_default();
+
+    if fields.is_empty() {
+        return Err(SearchError::InvalidArgument(
+            "field statistics require at least one field".to_string(),
+        ));
+    }
+    // Each field is aggregated with a terms aggregation named after its position in the list.
+    // The terms aggregation only keeps the top terms of each split, so counts are approximate.
+    let aggregation_request: serde_json::Map<String, JsonValue> = fields
+        .iter()
+        .enumerate()
+        .map(|(field_ord, field)| {
+            let terms_aggregation = serde_json::json!({
+                "terms": {
+                    "field": field,
+                    "size": FIELD_STATS_MAX_DISTINCT_VALUES,
+                }
+            });
+            (field_ord.to_string(), terms_aggregation)
+        })
+        .collect();
+    let query_ast = query_ast_from_user_text(
+        &field_stats_request.query,
+        field_stats_request.search_fields,
+    );
+    let search_request = quickwit_proto::search::SearchRequest {
+        index_id_patterns: vec![index_id],
+        query_ast: serde_json::to_string(&query_ast)?,
+        start_timestamp: field_stats_request.start_timestamp,
+        end_timestamp: field_stats_request.end_timestamp,
+        max_hits: 0,
+        aggregation_request: Some(serde_json::to_string(&aggregation_request)?),
+        security_context: security_context_opt,
+        ..Default::default()
+    };
+    let search_response = search_service.root_search(search_request).await?;
+    let search_response_rest = SearchResponseRest::try_from(search_response)?;
+    let aggregations_json = match &search_response_rest.aggregations {
+        Some(aggregations) => serde_json::to_value(aggregations)?,
+        None => JsonValue::Null,
+    };
+    let response = field_stats_from_aggregations(
+        fields,
+        search_response_rest.num_hits,
+        &aggregations_json,
+        field_stats_request.num_top_values,
+    );
+    Ok(response)
+}
+
+/// Builds the field statistics from the JSON representation of the terms aggregations computed
+/// for each field.
+fn field_stats_from_aggregations(
+    fields: Vec<String>,
+    num_docs: u64,
+    aggregations_json: &JsonValue,
+    num_top_values: usize,
+) -> FieldStatsResponse {
+    let fields = fields
+        .into_iter()
+        .enumerate()
+        .map(|(field_ord, field)| {
+            let terms_aggregation = &aggregations_json[field_ord.to_string()];
+            let buckets: &[JsonValue] = terms_aggregation["buckets"]
+                .as_array()
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let sum_other_doc_count = terms_aggregation["sum_other_doc_count"]
+                .as_u64()
+                .unwrap_or_default();
+            let num_docs_with_value = buckets
+                .iter()
+                .filter_map(|bucket| bucket["doc_count"].as_u64())
+                .sum::<u64>()
+                + sum_other_doc_count;
+            let top_values = buckets
+                .iter()
+                .take(num_top_values)
+                .map(|bucket| FieldValueCount {
+                    value: bucket["key"].clone(),
+                    count: bucket["doc_count"].as_u64().unwrap_or_default(),
+                })
+                .collect();
+            FieldStats {
+                field,
+                num_docs_with_value,
+                distinct_count: buckets.len() as u64,
+                distinct_count_is_lower_bound: sum_other_doc_count > 0,
+                top_values,
+            }
+        })
+        .collect();
+    FieldStatsResponse { num_docs, fields }
+}
+
 /// This struct represents the search stream query passed to
 /// the REST API.
 #[derive(Deserialize, Debug, Eq, PartialEq, utoipa::IntoParams)]
@@ -533,6 +1075,7 @@ struct SearchStreamRequestQueryString {
 async fn search_stream_endpoint(
     index_id: IndexId,
     search_request: SearchStreamRequestQueryString,
+    security_context_opt: Option<SecurityContext>,
     search_service: &dyn SearchService,
 ) -> Result<hyper::Body, SearchError> {
     let query_ast = query_ast_from_user_text(&search_request.query, search_request.search_fields);
@@ -546,6 +1089,7 @@ async fn search_stream_endpoint(
         fast_field: search_request.fast_field,
         output_format: search_request.output_format as i32,
         partition_by_field: search_request.partition_by_field,
+        security_context: security_context_opt,
     };
     let mut data = search_service.root_search_stream(request).await?;
     let (mut sender, body) = hyper::Body::channel();
@@ -600,6 +1144,7 @@ fn make_streaming_reply(result: Result<hyper::Body, SearchError>) -> impl Reply
 async fn search_stream(
     index_id: IndexId,
     request: SearchStreamRequestQueryString,
+    security_context_opt: Option<SecurityContext>,
     search_service: Arc<dyn SearchService>,
 ) -> impl warp::Reply {
     info!(index_id=%index_id,request=?request, "search_stream");
@@ -607,8 +1152,9 @@ async fn search_stream(
         OutputFormat::ClickHouseRowBinary => "application/octet-stream",
         OutputFormat::Csv => "text/csv",
     };
-    let reply =
-        make_streaming_reply(search_stream_endpoint(index_id, request, &*search_service).await);
+    let reply = make_streaming_reply(
+        search_stream_endpoint(index_id, request, security_context_opt, &*search_service).await,
+    );
     reply::with_header(reply, CONTENT_TYPE, content_type)
 }
 
@@ -619,11 +1165,109 @@ fn search_stream_filter(
         .and(serde_qs::warp::query(serde_qs::Config::default()))
 }
 
+/// This struct represents the split pinning query passed to the REST API.
+#[derive(Deserialize, Debug, Default, Eq, PartialEq, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+struct PinnedSplitsQueryString {
+    /// Comma-separated list of the IDs of the splits to pin or unpin. Defaults to all the splits
+    /// of the index.
+    #[param(value_type = String)]
+    #[serde(default)]
+    #[serde(deserialize_with = "from_simple_list")]
+    pub split_ids: Option<Vec<String>>,
+}
+
+fn pin_splits_filter(
+) -> impl Filter<Extract = (IndexId, PinnedSplitsQueryString), Error = Rejection> + Clone {
+    warp::path!(String / "pinned-splits")
+        .and(warp::put())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
+fn unpin_splits_filter(
+) -> impl Filter<Extract = (IndexId, PinnedSplitsQueryString), Error = Rejection> + Clone {
+    warp::path!(String / "pinned-splits")
+        .and(warp::delete())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
+async fn pin_splits(
+    index_id: IndexId,
+    query_string: PinnedSplitsQueryString,
+    unpin: bool,
+    search_service: Arc<dyn SearchService>,
+) -> impl warp::Reply {
+    info!(index_id=%index_id, request=?query_string, unpin, "pin-splits");
+    let request = PinIndexSplitsRequest {
+        index_id,
+        split_ids: query_string.split_ids.unwrap_or_default(),
+        unpin,
+    };
+    let result = search_service.root_pin_splits(request).await;
+    into_rest_api_response(result, BodyFormat::default())
+}
+
+#[utoipa::path(
+    put,
+    tag = "Search",
+    path = "/{index_id}/pinned-splits",
+    responses(
+        (status = 200, description = "Successfully pinned the splits.", body = PinIndexSplitsResponse)
+    ),
+    params(
+        PinnedSplitsQueryString,
+        ("index_id" = String, Path, description = "The index ID of the splits to pin."),
+    )
+)]
+/// Pin Splits
+///
+/// Pins published splits of an index in the split caches of the searchers, so that they are
+/// never evicted and are always searched from local disk.
+pub fn pin_splits_handler(
+    search_service: Arc<dyn SearchService>,
+    authenticator: Arc<Authenticator>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    pin_splits_filter()
+        .and(require_authentication(authenticator))
+        .and(with_arg(false))
+        .and(with_arg(search_service))
+        .then(pin_splits)
+}
+
+#[utoipa::path(
+    delete,
+    tag = "Search",
+    path = "/{index_id}/pinned-splits",
+    responses(
+        (status = 200, description = "Successfully unpinned the splits.", body = PinIndexSplitsResponse)
+    ),
+    params(
+        PinnedSplitsQueryString,
+        ("index_id" = String, Path, description = "The index ID of the splits to unpin."),
+    )
+)]
+/// Unpin Splits
+///
+/// Unpins splits of an index from the split caches of the searchers, making them evictable again.
+pub fn unpin_splits_handler(
+    search_service: Arc<dyn SearchService>,
+    authenticator: Arc<Authenticator>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    unpin_splits_filter()
+        .and(require_authentication(authenticator))
+        .and(with_arg(true))
+        .and(with_arg(search_service))
+        .then(pin_splits)
+}
+
 #[cfg(test)]
 mod tests {
     use assert_json_diff::{assert_json_eq, assert_json_include};
     use bytes::Bytes;
     use mockall::predicate;
+    use quickwit_config::ApiKeyConfig;
+    use quickwit_proto::search::CancelSearchResponse;
     use quickwit_search::{MockSearchService, SearchError};
     use serde_json::{json, Value as JsonValue};
 
@@ -632,14 +1276,73 @@ mod tests {
 
     fn search_handler(
         mock_search_service: MockSearchService,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+        search_handler_with_authenticator(mock_search_service, Authenticator::default())
+    }
+
+    fn search_handler_with_running_searches(
+        mock_search_service: MockSearchService,
+        running_searches: Arc<RunningSearches>,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+        search_get_handler(
+            Arc::new(mock_search_service),
+            running_searches,
+            Arc::default(),
+            RolloutRouter::default(),
+        )
+        .recover(recover_fn)
+    }
+
+    fn search_handler_with_authenticator(
+        mock_search_service: MockSearchService,
+        authenticator: Authenticator,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
         let mock_search_service_in_arc = Arc::new(mock_search_service);
-        search_get_handler(mock_search_service_in_arc.clone())
-            .or(search_post_handler(mock_search_service_in_arc.clone()))
-            .or(search_stream_handler(mock_search_service_in_arc.clone()))
-            .or(search_plan_get_handler(mock_search_service_in_arc.clone()))
-            .or(search_plan_post_handler(mock_search_service_in_arc.clone()))
-            .recover(recover_fn)
+        let authenticator = Arc::new(authenticator);
+        let running_searches: Arc<RunningSearches> = Arc::default();
+        search_get_handler(
+            mock_search_service_in_arc.clone(),
+            running_searches.clone(),
+            authenticator.clone(),
+            RolloutRouter::default(),
+        )
+        .or(search_post_handler(
+            mock_search_service_in_arc.clone(),
+            running_searches,
+            authenticator.clone(),
+            RolloutRouter::default(),
+            ByteSize::mib(1),
+        ))
+        .or(search_stream_handler(
+            mock_search_service_in_arc.clone(),
+            authenticator.clone(),
+        ))
+        .or(search_plan_get_handler(
+            mock_search_service_in_arc.clone(),
+            authenticator.clone(),
+        ))
+        .or(search_plan_post_handler(
+            mock_search_service_in_arc.clone(),
+            authenticator.clone(),
+            ByteSize::mib(1),
+        ))
+        .or(explain_handler(
+            mock_search_service_in_arc.clone(),
+            authenticator.clone(),
+        ))
+        .or(field_stats_handler(
+            mock_search_service_in_arc.clone(),
+            authenticator.clone(),
+        ))
+        .or(pin_splits_handler(
+            mock_search_service_in_arc.clone(),
+            authenticator.clone(),
+        ))
+        .or(unpin_splits_handler(
+            mock_search_service_in_arc,
+            authenticator,
+        ))
+        .recover(recover_fn)
     }
 
     #[tokio::test]
@@ -659,10 +1362,19 @@ mod tests {
                 .unwrap(),
             vec!["my-index-1".to_string(), "my-index-*".to_string()]
         );
+        assert_eq!(
+            extract_index_id_patterns("my-index-1,eu-west:my-index-%2A".to_string())
+                .await
+                .unwrap(),
+            vec!["my-index-1".to_string(), "eu-west:my-index-*".to_string()]
+        );
         extract_index_id_patterns("".to_string()).await.unwrap_err();
         extract_index_id_patterns(" ".to_string())
             .await
             .unwrap_err();
+        extract_index_id_patterns("eu-west:-my-index".to_string())
+            .await
+            .unwrap_err();
     }
 
     #[test]
@@ -674,6 +1386,8 @@ mod tests {
             elapsed_time_micros: 0u64,
             errors: Vec::new(),
             aggregations: None,
+            profile: None,
+            warnings: Vec::new(),
         };
         let search_response_json: JsonValue = serde_json::to_value(search_response)?;
         let expected_search_response_json: JsonValue = json!({
@@ -1040,6 +1754,88 @@ mod tests {
             .as_str()
             .unwrap()
             .contains("unknown field `end_unix_timestamp`"));
+        assert_eq!(resp_json["error_code"], "bad_request");
+        assert_eq!(resp_json["parameter"], "end_unix_timestamp");
+        assert_eq!(resp_json["retryable"], false);
+    }
+
+    #[tokio::test]
+    async fn test_rest_search_api_request_id() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .withf(|search_request| search_request.search_request_id.is_some())
+            .returning(|_| Ok(Default::default()));
+        let running_searches: Arc<RunningSearches> = Arc::default();
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*")
+            .header(SEARCH_REQUEST_ID_HEADER, "test-rest-search-api-request-id")
+            .reply(&search_handler_with_running_searches(
+                mock_search_service,
+                running_searches.clone(),
+            ))
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers().get(SEARCH_REQUEST_ID_HEADER).unwrap(),
+            "test-rest-search-api-request-id"
+        );
+        // The search request is unregistered once it completes.
+        assert!(running_searches.list(None).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rest_cancel_search_not_found() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_cancel_search()
+            .returning(|_| CancelSearchResponse::default());
+        let resp = warp::test::request()
+            .method("DELETE")
+            .path("/search/unknown-search-request")
+            .reply(
+                &cancel_search_handler(Arc::new(mock_search_service), Arc::default())
+                    .recover(recover_fn),
+            )
+            .await;
+        assert_eq!(resp.status(), 404);
+
+        let resp = warp::test::request()
+            .path("/search/running")
+            .reply(
+                &list_running_searches_handler(Arc::default(), Arc::default()).recover(recover_fn),
+            )
+            .await;
+        assert_eq!(resp.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_rest_cancel_search() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_cancel_search()
+            .withf(|cancel_search_request| {
+                cancel_search_request.request_id == "test-rest-cancel-search"
+            })
+            .times(1)
+            .returning(|cancel_search_request| CancelSearchResponse {
+                running_search: Some(RunningSearchInfo {
+                    request_id: cancel_search_request.request_id,
+                    index_id_patterns: vec!["my-index".to_string()],
+                    elapsed_secs: 1.0,
+                }),
+            });
+        let resp = warp::test::request()
+            .method("DELETE")
+            .path("/search/test-rest-cancel-search")
+            .reply(
+                &cancel_search_handler(Arc::new(mock_search_service), Arc::default())
+                    .recover(recover_fn),
+            )
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["index_id_patterns"], json!(["my-index"]));
     }
 
     #[tokio::test]
@@ -1085,6 +1881,35 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_rest_search_api_route_with_warnings() -> anyhow::Result<()> {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service.expect_root_search().returning(|_| {
+            Ok(quickwit_proto::search::SearchResponse {
+                num_hits: 10,
+                warnings: vec!["the time range of the search was clamped".to_string()],
+                ..Default::default()
+            })
+        });
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers().get("x-quickwit-warning").unwrap(),
+            "the time range of the search was clamped"
+        );
+        let resp_json: JsonValue = serde_json::from_slice(resp.body())?;
+        let expected_response_json = serde_json::json!({
+            "num_hits": 10,
+            "warnings": ["the time range of the search was clamped"],
+        });
+        assert_json_include!(actual: resp_json, expected: expected_response_json);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_rest_search_api_start_offset_and_num_hits_parameter() -> anyhow::Result<()> {
         let mut mock_search_service = MockSearchService::new();
@@ -1108,6 +1933,50 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_rest_search_api_with_api_keys() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(
+                |search_request: &quickwit_proto::search::SearchRequest| {
+                    let security_context = search_request.security_context.as_ref().unwrap();
+                    security_context.role == "support"
+                        && security_context.claims.get("tenant").unwrap() == "acme"
+                },
+            ))
+            .returning(|_| Ok(Default::default()));
+        let api_keys = vec![ApiKeyConfig {
+            key: "secret-key".to_string(),
+            role: "support".to_string(),
+            claims: [("tenant".to_string(), "acme".to_string())].into(),
+        }];
+        let rest_search_api_handler = search_handler_with_authenticator(
+            mock_search_service,
+            Authenticator::for_api_keys(api_keys),
+        );
+
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 401);
+
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*")
+            .header("authorization", "Bearer wrong-key")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 401);
+
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*")
+            .header("authorization", "Bearer secret-key")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+    }
+
     #[tokio::test]
     async fn test_rest_search_api_with_index_does_not_exist() -> anyhow::Result<()> {
         let mut mock_search_service = MockSearchService::new();
@@ -1267,6 +2136,202 @@ mod tests {
         assert_eq!(parse_error.to_string(), "expected a non-empty string field");
     }
 
+    #[tokio::test]
+    async fn test_rest_explain_api() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_explain()
+            .with(predicate::function(|explain_request: &ExplainRequest| {
+                explain_request.index_id == "my-index"
+                    && explain_request.doc_address.to_string() == "split-1:00000000:00000002"
+            }))
+            .return_once(|_| {
+                Ok(ExplainResponse {
+                    doc_address: "split-1:00000000:00000002".to_string(),
+                    matches: true,
+                    score: Some(1.5),
+                    explanation: ClauseExplanation {
+                        occur: None,
+                        query: quickwit_query::query_ast::QueryAst::MatchAll,
+                        matches: true,
+                        score: Some(1.5),
+                        query_tokens: Vec::new(),
+                        document_tokens: Vec::new(),
+                        error: None,
+                        clauses: Vec::new(),
+                    },
+                })
+            });
+        let rest_explain_api_handler = search_handler(mock_search_service);
+        let response = warp::test::request()
+            .path("/my-index/explain?query=*&doc_address=split-1:00000000:00000002")
+            .reply(&rest_explain_api_handler)
+            .await;
+        assert_eq!(response.status(), 200);
+        let explain_response_json: JsonValue = serde_json::from_slice(response.body()).unwrap();
+        let expected_explain_response_json = json!({
+            "doc_address": "split-1:00000000:00000002",
+            "matches": true,
+            "score": 1.5,
+            "explanation": {
+                "query": {"type": "match_all"},
+                "matches": true,
+                "score": 1.5,
+            }
+        });
+        assert_json_eq!(explain_response_json, expected_explain_response_json);
+
+        let response = warp::test::request()
+            .path("/my-index/explain?query=*&doc_address=split-1")
+            .reply(&search_handler(MockSearchService::new()))
+            .await;
+        assert_eq!(response.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_rest_field_stats_api() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(
+                |search_request: &quickwit_proto::search::SearchRequest| {
+                    let aggregation_request: JsonValue =
+                        serde_json::from_str(search_request.aggregation_request.as_ref().unwrap())
+                            .unwrap();
+                    search_request.index_id_patterns == ["my-index"]
+                        && search_request.max_hits == 0
+                        && search_request.start_timestamp == Some(10)
+                        && search_request.end_timestamp == Some(20)
+                        && aggregation_request["0"]["terms"]["field"] == "severity"
+                        && aggregation_request["1"]["terms"]["field"] == "service"
+                },
+            ))
+            .return_once(|_| {
+                Ok(quickwit_proto::search::SearchResponse {
+                    num_hits: 3,
+                    ..Default::default()
+                })
+            });
+        let rest_field_stats_api_handler = search_handler(mock_search_service);
+        let response = warp::test::request()
+            .path(
+                "/my-index/field-stats?fields=severity,service&start_timestamp=10&end_timestamp=20",
+            )
+            .reply(&rest_field_stats_api_handler)
+            .await;
+        assert_eq!(response.status(), 200);
+        let field_stats_response: FieldStatsResponse =
+            serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(field_stats_response.num_docs, 3);
+        assert_eq!(field_stats_response.fields.len(), 2);
+        assert_eq!(field_stats_response.fields[0].field, "severity");
+        assert_eq!(field_stats_response.fields[0].distinct_count, 0);
+
+        let response = warp::test::request()
+            .path("/my-index/field-stats?query=*")
+            .reply(&search_handler(MockSearchService::new()))
+            .await;
+        assert_eq!(response.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_rest_pin_splits_api() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_pin_splits()
+            .with(predicate::function(|request: &PinIndexSplitsRequest| {
+                request.index_id == "my-index"
+                    && request.split_ids == ["split-1", "split-2"]
+                    && !request.unpin
+            }))
+            .return_once(|_| Ok(PinIndexSplitsResponse { num_splits: 2 }));
+        mock_search_service
+            .expect_root_pin_splits()
+            .with(predicate::function(|request: &PinIndexSplitsRequest| {
+                request.index_id == "my-index" && request.split_ids.is_empty() && request.unpin
+            }))
+            .return_once(|_| Ok(PinIndexSplitsResponse { num_splits: 3 }));
+        let rest_pin_splits_api_handler = search_handler(mock_search_service);
+
+        let response = warp::test::request()
+            .method("PUT")
+            .path("/my-index/pinned-splits?split_ids=split-1,split-2")
+            .reply(&rest_pin_splits_api_handler)
+            .await;
+        assert_eq!(response.status(), 200);
+        let pin_splits_response: PinIndexSplitsResponse =
+            serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(pin_splits_response.num_splits, 2);
+
+        let response = warp::test::request()
+            .method("DELETE")
+            .path("/my-index/pinned-splits")
+            .reply(&rest_pin_splits_api_handler)
+            .await;
+        assert_eq!(response.status(), 200);
+        let unpin_splits_response: PinIndexSplitsResponse =
+            serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(unpin_splits_response.num_splits, 3);
+    }
+
+    #[test]
+    fn test_field_stats_from_aggregations() {
+        let aggregations_json = json!({
+            "0": {
+                "buckets": [
+                    {"key": "ERROR", "doc_count": 5},
+                    {"key": "WARN", "doc_count": 3},
+                    {"key": "INFO", "doc_count": 1},
+                ],
+                "sum_other_doc_count": 0,
+            },
+            "1": {
+                "buckets": [
+                    {"key": 200, "doc_count": 7},
+                ],
+                "sum_other_doc_count": 2,
+            },
+        });
+        let field_stats_response = field_stats_from_aggregations(
+            vec!["severity".to_string(), "status".to_string()],
+            10,
+            &aggregations_json,
+            2,
+        );
+        let expected_field_stats_response = FieldStatsResponse {
+            num_docs: 10,
+            fields: vec![
+                FieldStats {
+                    field: "severity".to_string(),
+                    num_docs_with_value: 9,
+                    distinct_count: 3,
+                    distinct_count_is_lower_bound: false,
+                    top_values: vec![
+                        FieldValueCount {
+                            value: json!("ERROR"),
+                            count: 5,
+                        },
+                        FieldValueCount {
+                            value: json!("WARN"),
+                            count: 3,
+                        },
+                    ],
+                },
+                FieldStats {
+                    field: "status".to_string(),
+                    num_docs_with_value: 9,
+                    distinct_count: 1,
+                    distinct_count_is_lower_bound: true,
+                    top_values: vec![FieldValueCount {
+                        value: json!(200),
+                        count: 7,
+                    }],
+                },
+            ],
+        };
+        assert_eq!(field_stats_response, expected_field_stats_response);
+    }
+
     #[tokio::test]
     async fn test_rest_search_api_route_serialize_results_with_snippet() -> anyhow::Result<()> {
         let mut mock_search_service = MockSearchService::new();
@@ -1277,6 +2342,7 @@ mod tests {
                     partial_hit: None,
                     snippet: Some(r#"{"title": [], "body": ["foo <em>bar</em> baz"]}"#.to_string()),
                     index_id: "quickwit-demo-index".to_string(),
+                    matched_queries: Vec::new(),
                 }],
                 num_hits: 1,
                 elapsed_time_micros: 16,
@@ -1306,6 +2372,50 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_rest_search_api_fields_projection() -> anyhow::Result<()> {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service.expect_root_search().returning(|_| {
+            Ok(quickwit_proto::search::SearchResponse {
+                hits: vec![quickwit_proto::search::Hit {
+                    json: json!({
+                        "title": "foo",
+                        "body": "foo bar baz",
+                        "attributes": {"host": "h1", "pid": 1}
+                    })
+                    .to_string(),
+                    partial_hit: None,
+                    snippet: None,
+                    index_id: "quickwit-demo-index".to_string(),
+                    matched_queries: Vec::new(),
+                }],
+                num_hits: 1,
+                elapsed_time_micros: 16,
+                errors: Vec::new(),
+                ..Default::default()
+            })
+        });
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let resp = warp::test::request()
+            .path(
+                "/quickwit-demo-index/search?query=bar&fields=title,attributes&\
+                 exclude_fields=attributes.pid",
+            )
+            .reply(&rest_search_api_handler)
+            .await;
+
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body())?;
+        let expected_response_json = serde_json::json!({
+            "num_hits": 1,
+            "hits": [{"title": "foo", "attributes": {"host": "h1"}}],
+            "elapsed_time_micros": 16,
+            "errors": [],
+        });
+        assert_json_eq!(resp_json, expected_response_json);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_rest_search_api_multi_indexes() {
         {
@@ -0,0 +1,473 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::{Bytes, BytesMut};
+use futures::future::BoxFuture;
+use http::header::{
+    ACCEPT_ENCODING, AUTHORIZATION, CONNECTION, CONTENT_LENGTH, COOKIE, HOST, PROXY_AUTHORIZATION,
+    TRANSFER_ENCODING,
+};
+use http::{HeaderMap, HeaderValue, Method, Request, Response, StatusCode};
+use hyper::Body;
+use quickwit_config::ShadowingConfig;
+use tokio::sync::Semaphore;
+use tower::{Layer, Service};
+use tracing::debug;
+
+use crate::request_sampling::{
+    body_with_error, classify_request, content_length_opt, extract_num_hits, InspectedResponseBody,
+    RequestSampler, SampledEndpoint, MAX_INSPECTED_RESPONSE_NUM_BYTES,
+};
+use crate::SERVE_METRICS;
+
+/// Header set on the shadow requests. Requests carrying it are never shadowed, which prevents
+/// loops between clusters shadowing each other.
+const SHADOW_REQUEST_HEADER: &str = "x-quickwit-shadow";
+
+/// Requests with a larger body, or with a streamed body, are not shadowed to avoid buffering them.
+const MAX_SHADOWED_BODY_NUM_BYTES: u64 = 10 * 1024 * 1024; // 10 MiB
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ShadowedRequestKind {
+    Ingest,
+    Search,
+}
+
+impl ShadowedRequestKind {
+    fn from_endpoint(endpoint: SampledEndpoint) -> Self {
+        match endpoint {
+            SampledEndpoint::Ingest | SampledEndpoint::ElasticBulk => Self::Ingest,
+            SampledEndpoint::Search | SampledEndpoint::ElasticSearch => Self::Search,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ingest => "ingest",
+            Self::Search => "search",
+        }
+    }
+}
+
+struct ShadowRequest {
+    method: Method,
+    path_and_query: String,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+struct PrimaryResponse {
+    status: StatusCode,
+    num_hits_opt: Option<u64>,
+}
+
+/// Replays a percentage of the ingest and search requests against a secondary cluster and
+/// compares its responses with the primary responses.
+pub(crate) struct Shadower {
+    endpoint: String,
+    authorization_opt: Option<HeaderValue>,
+    http_client: reqwest::Client,
+    ingest_sampler: RequestSampler,
+    search_sampler: RequestSampler,
+    in_flight_permits: Arc<Semaphore>,
+}
+
+impl Shadower {
+    pub fn new(shadowing_config: &ShadowingConfig) -> anyhow::Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .timeout(shadowing_config.timeout())
+            .build()?;
+        let authorization_opt = shadowing_config
+            .api_key
+            .as_ref()
+            .map(|api_key| {
+                let mut authorization = HeaderValue::try_from(format!("Bearer {api_key}"))?;
+                authorization.set_sensitive(true);
+                anyhow::Ok(authorization)
+            })
+            .transpose()?;
+        Ok(Self {
+            endpoint: shadowing_config.endpoint().to_string(),
+            authorization_opt,
+            http_client,
+            ingest_sampler: RequestSampler::new(shadowing_config.ingest_percentage),
+            search_sampler: RequestSampler::new(shadowing_config.search_percentage),
+            in_flight_permits: Arc::new(Semaphore::new(
+                shadowing_config.max_num_in_flight_requests.get(),
+            )),
+        })
+    }
+
+    /// Decides whether the request should be shadowed.
+    fn sample(&self, request: &Request<Body>) -> Option<ShadowedRequestKind> {
+        if request.headers().contains_key(SHADOW_REQUEST_HEADER) {
+            return None;
+        }
+        let (endpoint, _index_id_patterns) =
+            classify_request(request.method(), request.uri().path())?;
+        let kind = ShadowedRequestKind::from_endpoint(endpoint);
+        let sampler = match kind {
+            ShadowedRequestKind::Ingest => &self.ingest_sampler,
+            ShadowedRequestKind::Search => &self.search_sampler,
+        };
+        if !sampler.sample() {
+            return None;
+        }
+        let is_body_bufferable = match content_length_opt(request.headers()) {
+            Some(content_length) => content_length <= MAX_SHADOWED_BODY_NUM_BYTES,
+            None => request.method() == Method::GET,
+        };
+        if !is_body_bufferable {
+            record_shadow_request(kind, "skipped");
+            return None;
+        }
+        Some(kind)
+    }
+
+    fn spawn_shadow_request(
+        self: Arc<Self>,
+        kind: ShadowedRequestKind,
+        shadow_request: ShadowRequest,
+        primary_response: PrimaryResponse,
+    ) {
+        let Ok(permit) = self.in_flight_permits.clone().try_acquire_owned() else {
+            record_shadow_request(kind, "dropped");
+            return;
+        };
+        tokio::spawn(async move {
+            let outcome = self
+                .send_shadow_request(kind, shadow_request, primary_response)
+                .await;
+            record_shadow_request(kind, outcome);
+            drop(permit);
+        });
+    }
+
+    async fn send_shadow_request(
+        &self,
+        kind: ShadowedRequestKind,
+        shadow_request: ShadowRequest,
+        primary_response: PrimaryResponse,
+    ) -> &'static str {
+        let url = format!("{}{}", self.endpoint, shadow_request.path_and_query);
+        let mut headers = shadow_request.headers;
+
+        // The credentials of the caller are meant for the primary cluster: they must not leak to
+        // the secondary cluster, which gets the configured API key instead.
+        for header_name in [
+            ACCEPT_ENCODING,
+            AUTHORIZATION,
+            CONNECTION,
+            CONTENT_LENGTH,
+            COOKIE,
+            HOST,
+            PROXY_AUTHORIZATION,
+            TRANSFER_ENCODING,
+        ] {
+            headers.remove(header_name);
+        }
+        if let Some(authorization) = &self.authorization_opt {
+            headers.insert(AUTHORIZATION, authorization.clone());
+        }
+        headers.insert(SHADOW_REQUEST_HEADER, HeaderValue::from_static("true"));
+
+        let shadow_response_result = self
+            .http_client
+            .request(shadow_request.method, &url)
+            .headers(headers)
+            .body(shadow_request.body)
+            .send()
+            .await;
+        let shadow_response = match shadow_response_result {
+            Ok(shadow_response) => shadow_response,
+            Err(error) => {
+                debug!(url=%url, error=%error, "failed to send shadow request");
+                return "error";
+            }
+        };
+        let shadow_status = shadow_response.status();
+
+        let has_diverged = if shadow_status.is_success() != primary_response.status.is_success() {
+            true
+        } else if let Some(primary_num_hits) = primary_response.num_hits_opt {
+            let shadow_response_prefix = read_response_prefix(shadow_response).await;
+            extract_num_hits(&shadow_response_prefix) != Some(primary_num_hits)
+        } else {
+            false
+        };
+        if has_diverged {
+            debug!(
+                url=%url,
+                primary_status=%primary_response.status,
+                shadow_status=%shadow_status,
+                "shadow response diverged from primary response"
+            );
+            SERVE_METRICS
+                .shadow_divergences_total
+                .with_label_values([kind.as_str()])
+                .inc();
+        }
+        "success"
+    }
+}
+
+fn record_shadow_request(kind: ShadowedRequestKind, outcome: &str) {
+    SERVE_METRICS
+        .shadow_requests_total
+        .with_label_values([kind.as_str(), outcome])
+        .inc();
+}
+
+/// Reads the first [`MAX_INSPECTED_RESPONSE_NUM_BYTES`] bytes of a shadow response.
+async fn read_response_prefix(mut response: reqwest::Response) -> BytesMut {
+    let mut response_prefix = BytesMut::new();
+
+    while response_prefix.len() < MAX_INSPECTED_RESPONSE_NUM_BYTES {
+        let Ok(Some(chunk)) = response.chunk().await else {
+            break;
+        };
+        let num_bytes_to_retain =
+            (MAX_INSPECTED_RESPONSE_NUM_BYTES - response_prefix.len()).min(chunk.len());
+        response_prefix.extend_from_slice(&chunk[..num_bytes_to_retain]);
+    }
+    response_prefix
+}
+
+/// Layer duplicating a percentage of the ingest and search REST requests to a secondary cluster.
+/// The shadow requests are sent after the primary responses and never delay them.
+#[derive(Clone)]
+pub(crate) struct ShadowingLayer {
+    shadower_opt: Option<Arc<Shadower>>,
+}
+
+impl ShadowingLayer {
+    pub fn new(shadower_opt: Option<Arc<Shadower>>) -> Self {
+        Self { shadower_opt }
+    }
+}
+
+impl<S> Layer<S> for ShadowingLayer {
+    type Service = ShadowingService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ShadowingService {
+            inner,
+            shadower_opt: self.shadower_opt.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct ShadowingService<S> {
+    inner: S,
+    shadower_opt: Option<Arc<Shadower>>,
+}
+
+impl<S> Service<Request<Body>> for ShadowingService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<Body>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let Some((shadower, kind)) = self.shadower_opt.as_ref().and_then(|shadower| {
+            shadower
+                .sample(&request)
+                .map(|kind| (shadower.clone(), kind))
+        }) else {
+            return Box::pin(self.inner.call(request));
+        };
+        // The inner service polled ready is moved into the future and replaced by a clone.
+        let inner_clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner_clone);
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+
+            let body_bytes = match hyper::body::to_bytes(body).await {
+                Ok(body_bytes) => body_bytes,
+                Err(error) => {
+                    let request = Request::from_parts(parts, body_with_error(error));
+                    return inner.call(request).await;
+                }
+            };
+            let shadow_request = ShadowRequest {
+                method: parts.method.clone(),
+                path_and_query: parts
+                    .uri
+                    .path_and_query()
+                    .map(|path_and_query| path_and_query.to_string())
+                    .unwrap_or_default(),
+                headers: parts.headers.clone(),
+                body: body_bytes.clone(),
+            };
+            let request = Request::from_parts(parts, Body::from(body_bytes));
+            let response = inner.call(request).await?;
+            let status = response.status();
+
+            if kind != ShadowedRequestKind::Search || !status.is_success() {
+                let primary_response = PrimaryResponse {
+                    status,
+                    num_hits_opt: None,
+                };
+                shadower.spawn_shadow_request(kind, shadow_request, primary_response);
+                return Ok(response);
+            }
+            let (parts, body) = response.into_parts();
+            let shadowed_body = InspectedResponseBody::new(body, move |num_hits_opt| {
+                let primary_response = PrimaryResponse {
+                    status,
+                    num_hits_opt,
+                };
+                shadower.spawn_shadow_request(kind, shadow_request, primary_response);
+            });
+            Ok(Response::from_parts(
+                parts,
+                Body::wrap_stream(shadowed_body),
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::num::{NonZeroU64, NonZeroUsize};
+    use std::time::Duration;
+
+    use tokio::sync::mpsc;
+    use warp::Filter;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shadowing_service() {
+        let (shadow_request_tx, mut shadow_request_rx) = mpsc::unbounded_channel();
+        let shadow_routes = warp::any()
+            .and(warp::path::full())
+            .and(warp::header::optional::<String>(SHADOW_REQUEST_HEADER))
+            .and(warp::header::headers_cloned())
+            .and(warp::body::bytes())
+            .map(
+                move |path: warp::path::FullPath,
+                      shadow_header: Option<String>,
+                      headers: HeaderMap,
+                      body: Bytes| {
+                    shadow_request_tx
+                        .send((path.as_str().to_string(), shadow_header, headers, body))
+                        .unwrap();
+                    warp::reply::json(&serde_json::json!({"num_hits": 4}))
+                },
+            );
+        let (shadow_addr, shadow_server) =
+            warp::serve(shadow_routes).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(shadow_server);
+
+        let shadowing_config = ShadowingConfig {
+            endpoint: format!("http://{shadow_addr}"),
+            ingest_percentage: 100.0,
+            search_percentage: 100.0,
+            timeout_secs: NonZeroU64::new(5).unwrap(),
+            max_num_in_flight_requests: NonZeroUsize::new(10).unwrap(),
+            api_key: Some("shadow-api-key".to_string()),
+        };
+        let shadower = Arc::new(Shadower::new(&shadowing_config).unwrap());
+        // The number of hits is extracted from the beginning of the primary response, which is
+        // not buffered.
+        let primary_response_body = format!(
+            r#"{{"num_hits": 3, "hits": ["{}"]}}"#,
+            "a".repeat(2 * MAX_INSPECTED_RESPONSE_NUM_BYTES)
+        );
+        let primary_service_response_body = primary_response_body.clone();
+        let primary_service = tower::service_fn(move |_request: Request<Body>| {
+            let primary_response_body = primary_service_response_body.clone();
+            async move { Ok::<_, Infallible>(Response::new(Body::from(primary_response_body))) }
+        });
+        let mut shadowing_service = ShadowingLayer::new(Some(shadower)).layer(primary_service);
+
+        let divergences_before = SERVE_METRICS
+            .shadow_divergences_total
+            .with_label_values(["search"])
+            .get();
+
+        let request = Request::post("/api/v1/logs/search?max_hits=5")
+            .header(CONTENT_LENGTH, "2")
+            .header(AUTHORIZATION, "Bearer secret")
+            .header(COOKIE, "quickwit_session=secret")
+            .header("x-test", "kept")
+            .body(Body::from("{}"))
+            .unwrap();
+        let response = shadowing_service.call(request).await.unwrap();
+        let response_body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(response_body, primary_response_body.as_bytes());
+
+        let (path, shadow_header, headers, body) =
+            tokio::time::timeout(Duration::from_secs(5), shadow_request_rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(path, "/api/v1/logs/search");
+        assert_eq!(shadow_header.as_deref(), Some("true"));
+        assert_eq!(headers.get(AUTHORIZATION).unwrap(), "Bearer shadow-api-key");
+        assert!(!headers.contains_key(COOKIE));
+        assert_eq!(headers.get("x-test").unwrap(), "kept");
+        assert_eq!(body.as_ref(), b"{}");
+
+        // The divergence is recorded after the shadow response is received.
+        for _ in 0..50 {
+            let divergences = SERVE_METRICS
+                .shadow_divergences_total
+                .with_label_values(["search"])
+                .get();
+            if divergences > divergences_before {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(
+            SERVE_METRICS
+                .shadow_divergences_total
+                .with_label_values(["search"])
+                .get(),
+            divergences_before + 1
+        );
+
+        // Shadow requests and non-eligible requests are not shadowed.
+        let request = Request::post("/api/v1/logs/search")
+            .header(CONTENT_LENGTH, "2")
+            .header(SHADOW_REQUEST_HEADER, "true")
+            .body(Body::from("{}"))
+            .unwrap();
+        shadowing_service.call(request).await.unwrap();
+
+        let request = Request::delete("/api/v1/indexes/logs")
+            .body(Body::empty())
+            .unwrap();
+        shadowing_service.call(request).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(shadow_request_rx.try_recv().is_err());
+    }
+}
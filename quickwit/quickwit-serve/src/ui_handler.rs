@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use hyper::header::HeaderValue;
 use once_cell::sync::Lazy;
 use quickwit_telemetry::payload::TelemetryEvent;
@@ -21,6 +23,7 @@ use warp::path::Tail;
 use warp::reply::Response;
 use warp::{Filter, Rejection};
 
+use crate::authentication::{recover_login_required, require_ui_authentication, Authenticator};
 use crate::rest::recover_fn;
 
 /// Regular expression to identify which path should serve an asset file.
@@ -33,10 +36,14 @@ const UI_INDEX_FILE_NAME: &str = "index.html";
 #[folder = "../quickwit-ui/build/"]
 struct Asset;
 
-pub fn ui_handler() -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+pub fn ui_handler(
+    authenticator: Arc<Authenticator>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     warp::path("ui")
+        .and(require_ui_authentication(authenticator))
         .and(warp::path::tail())
         .and_then(serve_file)
+        .recover(recover_login_required)
         .recover(recover_fn)
         .boxed()
 }
@@ -0,0 +1,529 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use hyper::http::{Request, Response};
+use hyper::Body;
+use quickwit_common::spawn_named_task;
+use quickwit_common::uri::Uri;
+use quickwit_config::{
+    load_index_config_from_user_config, ConfigFormat, IndexConfig, SecurityPolicy,
+    UsageSamplingConfig, INGEST_V2_SOURCE_ID,
+};
+use quickwit_ingest::JsonDocBatchV2Builder;
+use quickwit_proto::ingest::router::{
+    IngestRequestV2, IngestRouterService, IngestRouterServiceClient, IngestSubrequest,
+};
+use quickwit_proto::ingest::CommitTypeV2;
+use quickwit_proto::types::{DocUidGenerator, IndexId};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use time::OffsetDateTime;
+use tokio::sync::mpsc;
+use tower::{Layer, Service};
+use tracing::{debug, warn};
+
+use crate::request_sampling::{
+    body_with_error, classify_request, content_length_opt, InspectedResponseBody, RequestSampler,
+    SampledEndpoint,
+};
+use crate::SERVE_METRICS;
+
+/// Requests with a larger body, or with a streamed body, are sampled without their query to
+/// avoid buffering them.
+const MAX_SAMPLED_BODY_NUM_BYTES: u64 = 1024 * 1024; // 1 MiB
+
+/// Maximum number of samples waiting to be ingested. Samples are dropped when the queue is full.
+const SAMPLE_QUEUE_CAPACITY: usize = 1_000;
+
+/// Maximum number of samples ingested in a single request.
+const MAX_SAMPLE_BATCH_SIZE: usize = 100;
+
+const USAGE_INDEX_CONFIG: &str = r#"
+version: 0.8
+
+index_id: ${INDEX_ID}
+
+doc_mapping:
+  mode: strict
+  field_mappings:
+    - name: timestamp
+      type: datetime
+      input_formats: [unix_timestamp]
+      output_format: rfc3339
+      fast: true
+    - name: endpoint
+      type: text
+      tokenizer: raw
+      fast: true
+    - name: index_id_patterns
+      type: array<text>
+      tokenizer: raw
+      fast: true
+    - name: query
+      type: text
+      tokenizer: default
+      record: position
+      redact: [email, credit_card, token]
+    - name: status_code
+      type: u64
+      fast: true
+    - name: latency_ms
+      type: u64
+      fast: true
+    - name: num_hits
+      type: u64
+      fast: true
+  timestamp_field: timestamp
+
+indexing_settings:
+  commit_timeout_secs: 30
+
+search_settings:
+  default_search_fields: [query]
+
+retention:
+  period: 30 days
+  schedule: daily
+"#;
+
+/// Returns the config of the usage index. The secrets and personal data of the sampled queries are
+/// redacted, and only the reader roles are allowed to search the index.
+pub(crate) fn usage_index_config(
+    usage_sampling_config: &UsageSamplingConfig,
+    default_index_root_uri: &Uri,
+) -> anyhow::Result<IndexConfig> {
+    let index_config_str =
+        USAGE_INDEX_CONFIG.replace("${INDEX_ID}", &usage_sampling_config.index_id);
+    let mut index_config = load_index_config_from_user_config(
+        ConfigFormat::Yaml,
+        index_config_str.as_bytes(),
+        default_index_root_uri,
+    )?;
+    index_config.search_settings.security_policies = usage_sampling_config
+        .reader_roles
+        .iter()
+        .map(|role| SecurityPolicy {
+            role: role.clone(),
+            retrievable_fields: None,
+            filter: None,
+            allow_updates: false,
+        })
+        .collect();
+    Ok(index_config)
+}
+
+#[derive(Deserialize)]
+struct QueryParams {
+    query: Option<String>,
+    q: Option<String>,
+}
+
+/// Extracts the query of a search request from its query string (`query` for the Quickwit API,
+/// `q` for the Elasticsearch API) or from its JSON body. Queries expressed in the Elasticsearch
+/// query DSL are recorded as JSON.
+fn extract_query(query_string_opt: Option<&str>, body_bytes: &[u8]) -> Option<String> {
+    if let Some(query_params) = query_string_opt
+        .and_then(|query_string| serde_qs::from_str::<QueryParams>(query_string).ok())
+    {
+        if let Some(query) = query_params.query.or(query_params.q) {
+            return Some(query);
+        }
+    }
+    let body_json: JsonValue = serde_json::from_slice(body_bytes).ok()?;
+
+    match body_json.get("query")? {
+        JsonValue::String(query) => Some(query.clone()),
+        query_json => Some(query_json.to_string()),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct UsageSample {
+    timestamp: i64,
+    endpoint: &'static str,
+    index_id_patterns: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<String>,
+    status_code: u16,
+    latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_hits: Option<u64>,
+}
+
+/// Samples a percentage of the search requests and ingests them into the usage index in the
+/// background.
+pub(crate) struct UsageSampler {
+    usage_index_id: IndexId,
+    search_sampler: RequestSampler,
+    elastic_search_sampler: RequestSampler,
+    sample_tx: mpsc::Sender<UsageSample>,
+}
+
+impl UsageSampler {
+    pub fn spawn(
+        usage_sampling_config: &UsageSamplingConfig,
+        ingest_router: IngestRouterServiceClient,
+    ) -> Self {
+        let (sample_tx, sample_rx) = mpsc::channel(SAMPLE_QUEUE_CAPACITY);
+        spawn_named_task(
+            ingest_samples_loop(
+                usage_sampling_config.index_id.clone(),
+                sample_rx,
+                ingest_router,
+            ),
+            "usage_sampling",
+        );
+        Self {
+            usage_index_id: usage_sampling_config.index_id.clone(),
+            search_sampler: RequestSampler::new(usage_sampling_config.search_percentage),
+            elastic_search_sampler: RequestSampler::new(
+                usage_sampling_config.elastic_search_percentage,
+            ),
+            sample_tx,
+        }
+    }
+
+    /// Decides whether the request should be sampled. Requests targeting the usage index are
+    /// never sampled.
+    fn sample(&self, request: &Request<Body>) -> Option<(SampledEndpoint, Vec<String>)> {
+        let (endpoint, index_id_patterns) =
+            classify_request(request.method(), request.uri().path())?;
+
+        if index_id_patterns.contains(&self.usage_index_id) {
+            return None;
+        }
+        let sampler = match endpoint {
+            SampledEndpoint::Search => &self.search_sampler,
+            SampledEndpoint::ElasticSearch => &self.elastic_search_sampler,
+            SampledEndpoint::Ingest | SampledEndpoint::ElasticBulk => return None,
+        };
+        if !sampler.sample() {
+            return None;
+        }
+        Some((endpoint, index_id_patterns))
+    }
+
+    fn record(&self, sample: UsageSample) {
+        if self.sample_tx.try_send(sample).is_err() {
+            record_usage_samples("dropped", 1);
+        }
+    }
+}
+
+fn record_usage_samples(outcome: &str, num_samples: usize) {
+    SERVE_METRICS
+        .usage_samples_total
+        .with_label_values([outcome])
+        .inc_by(num_samples as u64);
+}
+
+async fn ingest_samples_loop(
+    usage_index_id: IndexId,
+    mut sample_rx: mpsc::Receiver<UsageSample>,
+    ingest_router: IngestRouterServiceClient,
+) {
+    let mut samples = Vec::with_capacity(MAX_SAMPLE_BATCH_SIZE);
+
+    while sample_rx
+        .recv_many(&mut samples, MAX_SAMPLE_BATCH_SIZE)
+        .await
+        > 0
+    {
+        let num_samples = samples.len();
+        let mut doc_batch_builder = JsonDocBatchV2Builder::default();
+        let mut doc_uid_generator = DocUidGenerator::default();
+
+        for sample in samples.drain(..) {
+            let doc_uid = doc_uid_generator.next_doc_uid();
+            if let Err(error) = doc_batch_builder.add_doc(doc_uid, sample) {
+                warn!(%error, "failed to JSON serialize usage sample");
+            }
+        }
+        let subrequest = IngestSubrequest {
+            subrequest_id: 0,
+            index_id: usage_index_id.clone(),
+            source_id: INGEST_V2_SOURCE_ID.to_string(),
+            doc_batch: Some(doc_batch_builder.build()),
+        };
+        let ingest_request = IngestRequestV2 {
+            commit_type: CommitTypeV2::Auto as i32,
+            subrequests: vec![subrequest],
+        };
+        let outcome = match ingest_router.ingest(ingest_request).await {
+            Ok(ingest_response) if ingest_response.failures.is_empty() => "ingested",
+            Ok(ingest_response) => {
+                debug!(failures=?ingest_response.failures, "failed to ingest usage samples");
+                "failed"
+            }
+            Err(error) => {
+                debug!(%error, "failed to ingest usage samples");
+                "failed"
+            }
+        };
+        record_usage_samples(outcome, num_samples);
+    }
+}
+
+/// Layer sampling a percentage of the search REST requests into the usage index. The samples are
+/// ingested in the background and never delay the responses.
+#[derive(Clone)]
+pub(crate) struct UsageSamplingLayer {
+    usage_sampler_opt: Option<Arc<UsageSampler>>,
+}
+
+impl UsageSamplingLayer {
+    pub fn new(usage_sampler_opt: Option<Arc<UsageSampler>>) -> Self {
+        Self { usage_sampler_opt }
+    }
+}
+
+impl<S> Layer<S> for UsageSamplingLayer {
+    type Service = UsageSamplingService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        UsageSamplingService {
+            inner,
+            usage_sampler_opt: self.usage_sampler_opt.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct UsageSamplingService<S> {
+    inner: S,
+    usage_sampler_opt: Option<Arc<UsageSampler>>,
+}
+
+impl<S> Service<Request<Body>> for UsageSamplingService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<Body>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let Some((usage_sampler, endpoint, index_id_patterns)) =
+            self.usage_sampler_opt.as_ref().and_then(|usage_sampler| {
+                usage_sampler
+                    .sample(&request)
+                    .map(|(endpoint, index_id_patterns)| {
+                        (usage_sampler.clone(), endpoint, index_id_patterns)
+                    })
+            })
+        else {
+            return Box::pin(self.inner.call(request));
+        };
+        // The inner service polled ready is moved into the future and replaced by a clone.
+        let inner_clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner_clone);
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let is_body_bufferable = content_length_opt(request.headers())
+                .map(|content_length| content_length <= MAX_SAMPLED_BODY_NUM_BYTES)
+                .unwrap_or(false);
+
+            let (request, body_bytes) = if is_body_bufferable {
+                let (parts, body) = request.into_parts();
+
+                match hyper::body::to_bytes(body).await {
+                    Ok(body_bytes) => (
+                        Request::from_parts(parts, Body::from(body_bytes.clone())),
+                        body_bytes,
+                    ),
+                    Err(error) => {
+                        let request = Request::from_parts(parts, body_with_error(error));
+                        return inner.call(request).await;
+                    }
+                }
+            } else {
+                (request, Bytes::new())
+            };
+            let query_opt = extract_query(request.uri().query(), &body_bytes);
+
+            let response = inner.call(request).await?;
+            let status = response.status();
+
+            let usage_sample = UsageSample {
+                timestamp: OffsetDateTime::now_utc().unix_timestamp(),
+                endpoint: endpoint.as_str(),
+                index_id_patterns,
+                query: query_opt,
+                status_code: status.as_u16(),
+                latency_ms: start.elapsed().as_millis() as u64,
+                num_hits: None,
+            };
+            if !status.is_success() {
+                usage_sampler.record(usage_sample);
+                return Ok(response);
+            }
+            let (parts, body) = response.into_parts();
+            let sampled_body = InspectedResponseBody::new(body, move |num_hits_opt| {
+                usage_sampler.record(UsageSample {
+                    num_hits: num_hits_opt,
+                    ..usage_sample
+                });
+            });
+            Ok(Response::from_parts(parts, Body::wrap_stream(sampled_body)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::time::Duration;
+
+    use hyper::http::header::CONTENT_LENGTH;
+    use quickwit_proto::ingest::router::{
+        IngestResponseV2, IngestSuccess, MockIngestRouterService,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_extract_query() {
+        assert_eq!(
+            extract_query(Some("query=severity_text%3AERROR&max_hits=10"), b""),
+            Some("severity_text:ERROR".to_string())
+        );
+        assert_eq!(
+            extract_query(Some("q=body:error"), b""),
+            Some("body:error".to_string())
+        );
+        assert_eq!(
+            extract_query(None, br#"{"query": "body:error", "max_hits": 10}"#),
+            Some("body:error".to_string())
+        );
+        assert_eq!(
+            extract_query(None, br#"{"query": {"match_all": {}}}"#),
+            Some(r#"{"match_all":{}}"#.to_string())
+        );
+        assert_eq!(extract_query(Some("max_hits=10"), b"{}"), None);
+        assert_eq!(extract_query(None, b"not json"), None);
+    }
+
+    #[test]
+    fn test_usage_index_config() {
+        let usage_sampling_config = UsageSamplingConfig {
+            index_id: "usage".to_string(),
+            search_percentage: 100.0,
+            elastic_search_percentage: 0.0,
+            reader_roles: vec!["admin".to_string()],
+        };
+        let default_index_root_uri = Uri::for_test("s3://quickwit/indexes");
+        let index_config =
+            usage_index_config(&usage_sampling_config, &default_index_root_uri).unwrap();
+        assert_eq!(index_config.index_id, "usage");
+        assert_eq!(
+            index_config.index_uri,
+            "s3://quickwit/indexes/usage".parse::<Uri>().unwrap()
+        );
+        assert!(index_config.retention_policy_opt.is_some());
+
+        let security_policies = &index_config.search_settings.security_policies;
+        assert_eq!(security_policies.len(), 1);
+        assert_eq!(security_policies[0].role, "admin");
+        assert!(security_policies[0].retrievable_fields.is_none());
+
+        let doc_mapping_json = serde_json::to_value(&index_config.doc_mapping).unwrap();
+        let query_field_mapping_json = doc_mapping_json["field_mappings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|field_mapping_json| field_mapping_json["name"] == "query")
+            .unwrap();
+        assert_eq!(
+            query_field_mapping_json["redact"],
+            serde_json::json!(["email", "credit_card", "token"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_usage_sampling_service() {
+        let (doc_tx, mut doc_rx) = mpsc::unbounded_channel();
+        let mut mock_ingest_router = MockIngestRouterService::new();
+        mock_ingest_router
+            .expect_ingest()
+            .returning(move |request| {
+                assert_eq!(request.subrequests.len(), 1);
+                let subrequest = &request.subrequests[0];
+                assert_eq!(subrequest.index_id, "usage");
+
+                for (_doc_uid, doc) in subrequest.doc_batch.as_ref().unwrap().docs() {
+                    let doc_json: JsonValue = serde_json::from_slice(&doc).unwrap();
+                    doc_tx.send(doc_json).unwrap();
+                }
+                Ok(IngestResponseV2 {
+                    successes: vec![IngestSuccess::default()],
+                    failures: Vec::new(),
+                })
+            });
+        let usage_sampling_config = UsageSamplingConfig {
+            index_id: "usage".to_string(),
+            search_percentage: 100.0,
+            elastic_search_percentage: 0.0,
+            reader_roles: vec!["admin".to_string()],
+        };
+        let usage_sampler = UsageSampler::spawn(
+            &usage_sampling_config,
+            IngestRouterServiceClient::from_mock(mock_ingest_router),
+        );
+        let inner_service = tower::service_fn(|_request: Request<Body>| async {
+            Ok::<_, Infallible>(Response::new(Body::from(r#"{"num_hits": 3}"#)))
+        });
+        let mut usage_sampling_service =
+            UsageSamplingLayer::new(Some(Arc::new(usage_sampler))).layer(inner_service);
+
+        for request in [
+            Request::get("/api/v1/_elastic/logs/_search?q=error")
+                .body(Body::empty())
+                .unwrap(),
+            Request::get("/api/v1/usage/search?query=*")
+                .body(Body::empty())
+                .unwrap(),
+            Request::post("/api/v1/logs/search")
+                .header(CONTENT_LENGTH, "17")
+                .body(Body::from(r#"{"query":"error"}"#))
+                .unwrap(),
+        ] {
+            let response = usage_sampling_service.call(request).await.unwrap();
+            let response_body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            assert_eq!(response_body.as_ref(), br#"{"num_hits": 3}"#);
+        }
+        let doc_json = tokio::time::timeout(Duration::from_secs(5), doc_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(doc_json["endpoint"], "search");
+        assert_eq!(doc_json["index_id_patterns"], serde_json::json!(["logs"]));
+        assert_eq!(doc_json["query"], "error");
+        assert_eq!(doc_json["status_code"], 200);
+        assert_eq!(doc_json["num_hits"], 3);
+        assert!(doc_rx.try_recv().is_err());
+    }
+}
@@ -369,6 +369,19 @@ impl ByteRangeCache {
         self.inner_arc.num_stored_bytes.load(Ordering::Relaxed)
     }
 
+    /// Amount of bytes stored in the cache for each path.
+    pub fn get_num_bytes_by_path(&self) -> BTreeMap<PathBuf, u64> {
+        let need_mut_byte_range_cache_locked =
+            self.inner_arc.need_mut_byte_range_cache.lock().unwrap();
+        let mut num_bytes_by_path: BTreeMap<PathBuf, u64> = BTreeMap::new();
+
+        for (key, value) in &need_mut_byte_range_cache_locked.cache {
+            *num_bytes_by_path.entry(key.tag.to_path_buf()).or_default() +=
+                (value.range_end - key.range_start) as u64;
+        }
+        num_bytes_by_path
+    }
+
     /// If available, returns the cached view of the slice.
     pub fn get_slice(&self, path: &Path, byte_range: Range<usize>) -> Option<OwnedBytes> {
         self.inner_arc
@@ -558,4 +571,19 @@ mod tests {
             assert_eq!(mutable_cache.cache_counters.in_cache_num_bytes.get(), 20);
         }
     }
+
+    #[test]
+    fn test_byte_range_cache_get_num_bytes_by_path() {
+        let cache = ByteRangeCache::with_infinite_capacity(&CACHE_METRICS_FOR_TESTS);
+        assert!(cache.get_num_bytes_by_path().is_empty());
+
+        cache.put_slice("a.idx".into(), 0..5, OwnedBytes::new(vec![0; 5]));
+        cache.put_slice("a.idx".into(), 10..13, OwnedBytes::new(vec![0; 3]));
+        cache.put_slice("b.term".into(), 0..7, OwnedBytes::new(vec![0; 7]));
+
+        let num_bytes_by_path = cache.get_num_bytes_by_path();
+        assert_eq!(num_bytes_by_path.len(), 2);
+        assert_eq!(num_bytes_by_path[Path::new("a.idx")], 8);
+        assert_eq!(num_bytes_by_path[Path::new("b.term")], 7);
+    }
 }
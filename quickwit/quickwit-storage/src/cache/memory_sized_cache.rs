@@ -126,6 +126,19 @@ impl<K: Hash + Eq> NeedMutMemorySizedCache<K> {
         }
     }
 
+    /// Updates the capacity, evicting the least recently used items regardless of their last
+    /// access time until the cache fits.
+    fn set_capacity(&mut self, capacity: Capacity) {
+        self.capacity = capacity;
+
+        while self.capacity.exceeds_capacity(self.num_bytes as usize) {
+            let Some((_, bytes)) = self.lru_cache.pop_lru() else {
+                break;
+            };
+            self.drop_item(bytes.len() as u64);
+        }
+    }
+
     /// Attempt to put the given amount of data in the cache.
     /// This may fail silently if the owned_bytes slice is larger than the cache
     /// capacity.
@@ -205,6 +218,15 @@ impl<K: Hash + Eq> MemorySizedCache<K> {
         }
     }
 
+    /// Updates the capacity of the cache. If the cache exceeds the new capacity, the least
+    /// recently used items are evicted.
+    pub fn set_capacity_in_bytes(&self, capacity_in_bytes: usize) {
+        self.inner
+            .lock()
+            .unwrap()
+            .set_capacity(Capacity::InBytes(capacity_in_bytes));
+    }
+
     /// If available, returns the cached view of the slice.
     pub fn get<Q>(&self, cache_key: &Q) -> Option<OwnedBytes>
     where
@@ -302,6 +324,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cache_set_capacity() {
+        let cache =
+            MemorySizedCache::<String>::with_capacity_in_bytes(10, &CACHE_METRICS_FOR_TESTS);
+        cache.put("3".to_string(), OwnedBytes::new(&b"abc"[..]));
+        cache.put("2".to_string(), OwnedBytes::new(&b"de"[..]));
+        cache.put("4".to_string(), OwnedBytes::new(&b"fghi"[..]));
+
+        // Shrinking the cache evicts the least recently used items, even if they are recent.
+        cache.set_capacity_in_bytes(6);
+        assert!(cache.get(&"3".to_string()).is_none());
+        assert_eq!(cache.get(&"2".to_string()).unwrap(), &b"de"[..]);
+        assert_eq!(cache.get(&"4".to_string()).unwrap(), &b"fghi"[..]);
+
+        cache.set_capacity_in_bytes(12);
+        cache.put("6".to_string(), OwnedBytes::new(&b"jklmno"[..]));
+        assert_eq!(cache.get(&"6".to_string()).unwrap(), &b"jklmno"[..]);
+        assert_eq!(cache.get(&"2".to_string()).unwrap(), &b"de"[..]);
+    }
+
     #[test]
     fn test_cache() {
         let cache = MemorySizedCache::with_capacity_in_bytes(10_000, &CACHE_METRICS_FOR_TESTS);
@@ -28,11 +28,15 @@ const FULL_SLICE: Range<usize> = 0..usize::MAX;
 /// It is used currently by to cache only fast fields data.
 pub struct QuickwitCache {
     router: Vec<(&'static str, Arc<dyn StorageCache>)>,
+    fast_field_cache_opt: Option<Arc<SimpleCache>>,
 }
 
 impl From<Vec<(&'static str, Arc<dyn StorageCache>)>> for QuickwitCache {
     fn from(router: Vec<(&'static str, Arc<dyn StorageCache>)>) -> Self {
-        QuickwitCache { router }
+        QuickwitCache {
+            router,
+            fast_field_cache_opt: None,
+        }
     }
 }
 
@@ -43,16 +47,25 @@ impl QuickwitCache {
         let mut quickwit_cache = QuickwitCache::empty();
         let fast_field_cache_counters: &'static CacheMetrics =
             &crate::STORAGE_METRICS.fast_field_cache;
-        quickwit_cache.add_route(
-            ".fast",
-            Arc::new(SimpleCache::with_capacity_in_bytes(
-                fast_field_cache_capacity,
-                fast_field_cache_counters,
-            )),
-        );
+        let fast_field_cache = Arc::new(SimpleCache::with_capacity_in_bytes(
+            fast_field_cache_capacity,
+            fast_field_cache_counters,
+        ));
+        quickwit_cache.add_route(".fast", fast_field_cache.clone());
+        quickwit_cache.fast_field_cache_opt = Some(fast_field_cache);
         quickwit_cache
     }
 
+    /// Updates the capacity of the fast field cache, evicting the least recently used entries if
+    /// necessary. This is a no-op if the cache was not created with [`QuickwitCache::new`].
+    pub fn set_fast_field_cache_capacity(&self, fast_field_cache_capacity: usize) {
+        if let Some(fast_field_cache) = &self.fast_field_cache_opt {
+            fast_field_cache
+                .slice_cache
+                .set_capacity_in_bytes(fast_field_cache_capacity);
+        }
+    }
+
     /// Empties cache.
     pub fn empty() -> QuickwitCache {
         QuickwitCache::from(Vec::new())
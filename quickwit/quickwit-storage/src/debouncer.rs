@@ -145,6 +145,17 @@ impl<T: Storage> Storage for DebouncedStorage<T> {
         self.underlying.put(path, payload).await
     }
 
+    async fn put_with_storage_class(
+        &self,
+        path: &Path,
+        payload: Box<dyn crate::PutPayload>,
+        storage_class: &str,
+    ) -> crate::StorageResult<()> {
+        self.underlying
+            .put_with_storage_class(path, payload, storage_class)
+            .await
+    }
+
     async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
         self.underlying.copy_to(path, output).await
     }
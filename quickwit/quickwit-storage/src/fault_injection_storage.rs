@@ -0,0 +1,258 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use quickwit_common::uri::Uri;
+use quickwit_config::StorageFaultInjectionConfig;
+use rand::Rng;
+use tantivy::directory::OwnedBytes;
+use tokio::io::AsyncRead;
+
+use crate::storage::SendableAsync;
+use crate::{BulkDeleteError, PutPayload, Storage, StorageErrorKind, StorageResult};
+
+const LATENCY_MILLIS_ENV_KEY: &str = "QW_STORAGE_FAULT_INJECTION_LATENCY_MILLIS";
+const LATENCY_JITTER_MILLIS_ENV_KEY: &str = "QW_STORAGE_FAULT_INJECTION_LATENCY_JITTER_MILLIS";
+const ERROR_RATE_PERCENT_ENV_KEY: &str = "QW_STORAGE_FAULT_INJECTION_ERROR_RATE_PERCENT";
+
+/// Resolves the fault injection config of the `file` and `ram` storages. The storage config takes
+/// precedence over the `QW_STORAGE_FAULT_INJECTION_*` environment variables.
+pub(crate) fn resolve_fault_injection_config(
+    fault_injection_config_opt: Option<&StorageFaultInjectionConfig>,
+) -> Option<StorageFaultInjectionConfig> {
+    if let Some(fault_injection_config) = fault_injection_config_opt {
+        return Some(fault_injection_config.clone());
+    }
+    let latency_millis_opt = quickwit_common::get_from_env_opt(LATENCY_MILLIS_ENV_KEY);
+    let latency_jitter_millis_opt =
+        quickwit_common::get_from_env_opt(LATENCY_JITTER_MILLIS_ENV_KEY);
+    let error_rate_percent_opt = quickwit_common::get_from_env_opt(ERROR_RATE_PERCENT_ENV_KEY);
+
+    if latency_millis_opt.is_none()
+        && latency_jitter_millis_opt.is_none()
+        && error_rate_percent_opt.is_none()
+    {
+        return None;
+    }
+    let fault_injection_config = StorageFaultInjectionConfig {
+        latency_millis: latency_millis_opt.unwrap_or_default(),
+        latency_jitter_millis: latency_jitter_millis_opt.unwrap_or_default(),
+        error_rate_percent: error_rate_percent_opt.unwrap_or_default().min(100),
+    };
+    Some(fault_injection_config)
+}
+
+/// Storage proxy that delays requests and makes some of them fail according to a
+/// [`StorageFaultInjectionConfig`].
+///
+/// This is used to emulate an object storage on top of the `file` and `ram` storages in tests and
+/// development setups.
+#[derive(Clone, Debug)]
+pub struct FaultInjectionStorage {
+    underlying: Arc<dyn Storage>,
+    fault_injection_config: StorageFaultInjectionConfig,
+}
+
+impl FaultInjectionStorage {
+    /// Creates a new `FaultInjectionStorage`.
+    pub fn new(
+        storage: Arc<dyn Storage>,
+        fault_injection_config: StorageFaultInjectionConfig,
+    ) -> Self {
+        FaultInjectionStorage {
+            underlying: storage,
+            fault_injection_config,
+        }
+    }
+
+    async fn inject_fault(&self, operation: &str) -> StorageResult<()> {
+        let (latency_jitter_millis, should_fail) = {
+            let mut rng = rand::thread_rng();
+            let latency_jitter_millis =
+                rng.gen_range(0..=self.fault_injection_config.latency_jitter_millis);
+            let should_fail =
+                rng.gen_range(0..100) < self.fault_injection_config.error_rate_percent;
+            (latency_jitter_millis, should_fail)
+        };
+        let latency_millis = self.fault_injection_config.latency_millis + latency_jitter_millis;
+
+        if latency_millis > 0 {
+            tokio::time::sleep(Duration::from_millis(latency_millis)).await;
+        }
+        if should_fail {
+            let error = anyhow::anyhow!("injected fault on `{operation}`");
+            return Err(StorageErrorKind::Service.with_error(error));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Storage for FaultInjectionStorage {
+    async fn check_connectivity(&self) -> anyhow::Result<()> {
+        self.underlying.check_connectivity().await
+    }
+
+    async fn put(&self, path: &Path, payload: Box<dyn PutPayload>) -> StorageResult<()> {
+        self.inject_fault("put").await?;
+        self.underlying.put(path, payload).await
+    }
+
+    async fn put_with_storage_class(
+        &self,
+        path: &Path,
+        payload: Box<dyn PutPayload>,
+        storage_class: &str,
+    ) -> StorageResult<()> {
+        self.inject_fault("put").await?;
+        self.underlying
+            .put_with_storage_class(path, payload, storage_class)
+            .await
+    }
+
+    fn copy_to<'life0, 'life1, 'life2, 'async_trait>(
+        &'life0 self,
+        path: &'life1 Path,
+        output: &'life2 mut dyn SendableAsync,
+    ) -> ::core::pin::Pin<
+        Box<
+            dyn ::core::future::Future<Output = StorageResult<()>>
+                + ::core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            self.inject_fault("copy_to").await?;
+            self.underlying.copy_to(path, output).await
+        })
+    }
+
+    async fn copy_to_file(&self, path: &Path, output_path: &Path) -> StorageResult<u64> {
+        self.inject_fault("copy_to_file").await?;
+        self.underlying.copy_to_file(path, output_path).await
+    }
+
+    async fn get_slice(&self, path: &Path, range: Range<usize>) -> StorageResult<OwnedBytes> {
+        self.inject_fault("get_slice").await?;
+        self.underlying.get_slice(path, range).await
+    }
+
+    async fn get_slice_stream(
+        &self,
+        path: &Path,
+        range: Range<usize>,
+    ) -> StorageResult<Box<dyn AsyncRead + Send + Unpin>> {
+        self.inject_fault("get_slice_stream").await?;
+        self.underlying.get_slice_stream(path, range).await
+    }
+
+    async fn get_all(&self, path: &Path) -> StorageResult<OwnedBytes> {
+        self.inject_fault("get_all").await?;
+        self.underlying.get_all(path).await
+    }
+
+    async fn delete(&self, path: &Path) -> StorageResult<()> {
+        self.inject_fault("delete").await?;
+        self.underlying.delete(path).await
+    }
+
+    async fn bulk_delete<'a>(&self, paths: &[&'a Path]) -> Result<(), BulkDeleteError> {
+        if let Err(error) = self.inject_fault("bulk_delete").await {
+            return Err(BulkDeleteError {
+                error: Some(error),
+                unattempted: paths.iter().map(|path| path.to_path_buf()).collect(),
+                ..Default::default()
+            });
+        }
+        self.underlying.bulk_delete(paths).await
+    }
+
+    async fn exists(&self, path: &Path) -> StorageResult<bool> {
+        self.inject_fault("exists").await?;
+        self.underlying.exists(path).await
+    }
+
+    async fn file_num_bytes(&self, path: &Path) -> StorageResult<u64> {
+        self.inject_fault("file_num_bytes").await?;
+        self.underlying.file_num_bytes(path).await
+    }
+
+    fn uri(&self) -> &Uri {
+        self.underlying.uri()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RamStorage;
+
+    #[tokio::test]
+    async fn test_fault_injection_storage_errors() {
+        let ram_storage = Arc::new(RamStorage::default());
+        ram_storage
+            .put(Path::new("foo"), Box::new(b"bar".to_vec()))
+            .await
+            .unwrap();
+
+        let failing_storage = FaultInjectionStorage::new(
+            ram_storage.clone(),
+            StorageFaultInjectionConfig {
+                error_rate_percent: 100,
+                ..Default::default()
+            },
+        );
+        let error = failing_storage.get_all(Path::new("foo")).await.unwrap_err();
+        assert_eq!(error.kind(), StorageErrorKind::Service);
+
+        let bulk_delete_error = failing_storage
+            .bulk_delete(&[Path::new("foo")])
+            .await
+            .unwrap_err();
+        assert_eq!(bulk_delete_error.unattempted.len(), 1);
+
+        let healthy_storage =
+            FaultInjectionStorage::new(ram_storage, StorageFaultInjectionConfig::default());
+        let payload = healthy_storage.get_all(Path::new("foo")).await.unwrap();
+        assert_eq!(payload.as_slice(), b"bar");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fault_injection_storage_latency() {
+        let storage = FaultInjectionStorage::new(
+            Arc::new(RamStorage::default()),
+            StorageFaultInjectionConfig {
+                latency_millis: 100,
+                latency_jitter_millis: 50,
+                ..Default::default()
+            },
+        );
+        let start = tokio::time::Instant::now();
+        assert!(!storage.exists(Path::new("foo")).await.unwrap());
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(100));
+        assert!(elapsed <= Duration::from_millis(150));
+    }
+}
@@ -19,6 +19,7 @@ use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
+use memmap2::{Advice, Mmap};
 use tantivy::directory::OwnedBytes;
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use ulid::Ulid;
@@ -29,6 +30,7 @@ pub struct FileDescriptorCache {
     fd_cache: Mutex<lru::LruCache<Ulid, SplitFile>>,
     fd_semaphore: Arc<Semaphore>,
     fd_cache_metrics: CacheMetrics,
+    use_mmap: bool,
 }
 
 #[derive(Clone)]
@@ -37,10 +39,31 @@ pub struct SplitFile(Arc<SplitFileInner>);
 struct SplitFileInner {
     num_bytes: u64,
     // Order matters here. We want file to be dropped (closed) before the semaphore.
-    file: File,
+    reader: SplitFileReader,
     _fd_semaphore_guard: OwnedSemaphorePermit,
 }
 
+enum SplitFileReader {
+    File(File),
+    // The file descriptor is closed once the file is mapped, but the mapping still counts
+    // against the file descriptor semaphore.
+    Mmap(Arc<Mmap>),
+}
+
+fn open_split_file_reader(split_path: &Path, use_mmap: bool) -> io::Result<SplitFileReader> {
+    let file = File::open(split_path)?;
+    if !use_mmap {
+        return Ok(SplitFileReader::File(file));
+    }
+    // SAFETY: split files are never modified once downloaded into the cache. They may only be
+    // deleted, which does not invalidate the existing mappings.
+    let mmap = unsafe { Mmap::map(&file)? };
+    // Searches read small ranges scattered across the split file, so read-ahead would mostly
+    // load pages that are never read.
+    mmap.advise(Advice::Random)?;
+    Ok(SplitFileReader::Mmap(Arc::new(mmap)))
+}
+
 fn get_split_file_path(root_path: &Path, split_id: Ulid) -> PathBuf {
     let split_filename = quickwit_common::split_file(split_id);
     root_path.join(split_filename)
@@ -79,19 +102,39 @@ impl FileDescriptorCache {
             fd_cache,
             fd_semaphore,
             fd_cache_metrics,
+            use_mmap: false,
         }
     }
 
     pub fn with_fd_cache_capacity(fd_cache_capacity: NonZeroU32) -> FileDescriptorCache {
-        let max_fd_limit = (fd_cache_capacity.get() * 2)
-            .clamp(fd_cache_capacity.get() + 100, fd_cache_capacity.get() + 200);
+        Self::with_limits(fd_cache_capacity, None)
+    }
+
+    /// Creates a new file descriptor cache. If `max_fd_limit_opt` is not set, the limit of open
+    /// file descriptors is derived from the cache capacity.
+    pub fn with_limits(
+        fd_cache_capacity: NonZeroU32,
+        max_fd_limit_opt: Option<NonZeroU32>,
+    ) -> FileDescriptorCache {
+        let max_fd_limit = max_fd_limit_opt.unwrap_or_else(|| {
+            let max_fd_limit = (fd_cache_capacity.get() * 2)
+                .clamp(fd_cache_capacity.get() + 100, fd_cache_capacity.get() + 200);
+            NonZeroU32::new(max_fd_limit).unwrap()
+        });
         Self::new(
-            NonZeroU32::new(max_fd_limit).unwrap(),
+            max_fd_limit,
             fd_cache_capacity,
             crate::STORAGE_METRICS.fd_cache_metrics.clone(),
         )
     }
 
+    /// Makes the split files opened from now on be read through memory mappings rather than
+    /// copied into heap buffers.
+    pub fn with_mmap(mut self, use_mmap: bool) -> FileDescriptorCache {
+        self.use_mmap = use_mmap;
+        self
+    }
+
     fn get_split_file(&self, split_id: Ulid) -> Option<SplitFile> {
         self.fd_cache.lock().unwrap().get(&split_id).cloned()
     }
@@ -135,17 +178,19 @@ impl FileDescriptorCache {
         let fd_semaphore_guard = Semaphore::acquire_owned(self.fd_semaphore.clone())
             .await
             .expect("fd_semaphore acquire failed. please report");
-        let file: File = tokio::task::spawn_blocking(move || std::fs::File::open(split_path))
-            .await
-            .map_err(|join_error| {
-                io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Failed to open file: {:?}", join_error),
-                )
-            })??;
+        let use_mmap = self.use_mmap;
+        let reader: SplitFileReader =
+            tokio::task::spawn_blocking(move || open_split_file_reader(&split_path, use_mmap))
+                .await
+                .map_err(|join_error| {
+                    io::Error::new(
+                        io::ErrorKind::Other,
+                        format!("Failed to open file: {:?}", join_error),
+                    )
+                })??;
         let split_file = SplitFile(Arc::new(SplitFileInner {
             num_bytes,
-            file,
+            reader,
             _fd_semaphore_guard: fd_semaphore_guard,
         }));
         self.put_split_file(split_id, split_file.clone());
@@ -156,14 +201,31 @@ impl FileDescriptorCache {
 impl SplitFile {
     pub async fn get_range(&self, range: Range<usize>) -> io::Result<OwnedBytes> {
         use std::os::unix::fs::FileExt;
-        let file = self.clone();
+        if let SplitFileReader::Mmap(mmap) = &self.0.reader {
+            if range.end > mmap.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "range {range:?} is out of the bounds of the split file ({} bytes)",
+                        mmap.len()
+                    ),
+                ));
+            }
+            // This is only a hint: the pages are loaded on access regardless.
+            let _ = mmap.advise_range(Advice::WillNeed, range.start, range.len());
+            return Ok(OwnedBytes::new(mmap.clone()).slice(range));
+        }
+        let split_file = self.clone();
         let buf = tokio::task::spawn_blocking(move || {
+            let SplitFileReader::File(file) = &split_file.0.reader else {
+                unreachable!("memory mapped split files are read without blocking");
+            };
             let mut buf = Vec::with_capacity(range.len());
             #[allow(clippy::uninit_vec)]
             unsafe {
                 buf.set_len(range.len());
             }
-            file.0.file.read_exact_at(&mut buf, range.start as u64)?;
+            file.read_exact_at(&mut buf, range.start as u64)?;
             io::Result::Ok(buf)
         })
         .await
@@ -179,6 +241,7 @@ impl SplitFile {
 #[cfg(test)]
 mod tests {
     use std::num::NonZeroU32;
+    use std::time::Duration;
 
     use tokio::fs;
     use ulid::Ulid;
@@ -257,6 +320,45 @@ mod tests {
         assert_eq!(cache_metrics.misses_num_items.get(), 100);
     }
 
+    #[tokio::test]
+    async fn test_fd_cache_max_fd_limit() {
+        let fd_cache = FileDescriptorCache::with_limits(
+            NonZeroU32::new(1).unwrap(),
+            Some(NonZeroU32::new(2).unwrap()),
+        );
+        let tempdir = tempfile::tempdir().unwrap();
+        let split_ids: Vec<Ulid> = std::iter::repeat_with(Ulid::new).take(3).collect();
+        for &split_id in &split_ids {
+            let split_filepath = super::get_split_file_path(tempdir.path(), split_id);
+            fs::write(split_filepath, split_id.to_string().as_bytes())
+                .await
+                .unwrap();
+        }
+        let split_file_0 = fd_cache
+            .get_or_open_split_file(tempdir.path(), split_ids[0], 26)
+            .await
+            .unwrap();
+        let split_file_1 = fd_cache
+            .get_or_open_split_file(tempdir.path(), split_ids[1], 26)
+            .await
+            .unwrap();
+        // Both files are still open, so opening a third one waits for a file to be closed.
+        tokio::time::timeout(
+            Duration::from_millis(50),
+            fd_cache.get_or_open_split_file(tempdir.path(), split_ids[2], 26),
+        )
+        .await
+        .unwrap_err();
+
+        // The first split file was evicted from the cache when the second one was opened.
+        drop(split_file_0);
+        drop(split_file_1);
+        fd_cache
+            .get_or_open_split_file(tempdir.path(), split_ids[2], 26)
+            .await
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn test_split_file() {
         let fd_cache = FileDescriptorCache::with_fd_cache_capacity(NonZeroU32::new(20).unwrap());
@@ -279,4 +381,33 @@ mod tests {
             assert_eq!(bytes.as_slice(), &content.as_bytes()[1..3]);
         }
     }
+
+    #[tokio::test]
+    async fn test_split_file_mmap() {
+        let fd_cache = FileDescriptorCache::with_fd_cache_capacity(NonZeroU32::new(20).unwrap())
+            .with_mmap(true);
+        let tempdir = tempfile::tempdir().unwrap();
+        let split_id: Ulid = Ulid::new();
+        let split_filepath = super::get_split_file_path(tempdir.path(), split_id);
+        let content = split_id.to_string();
+        fs::write(&split_filepath, content.as_bytes())
+            .await
+            .unwrap();
+        let split_file = fd_cache
+            .get_or_open_split_file(tempdir.path(), split_id, 26)
+            .await
+            .unwrap();
+        // The mapping remains readable after the file is evicted from the cache.
+        fs::remove_file(&split_filepath).await.unwrap();
+        {
+            let bytes = split_file.get_all().await.unwrap();
+            assert_eq!(bytes.as_slice(), content.as_bytes());
+        }
+        {
+            let bytes = split_file.get_range(1..3).await.unwrap();
+            assert_eq!(bytes.as_slice(), &content.as_bytes()[1..3]);
+        }
+        let error = split_file.get_range(20..30).await.unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
 }
@@ -40,6 +40,7 @@ pub use self::storage::Storage;
 
 mod bundle_storage;
 mod error;
+mod fault_injection_storage;
 
 mod local_file_storage;
 mod object_storage;
@@ -65,6 +66,7 @@ pub use self::cache::MockStorageCache;
 pub use self::cache::{
     wrap_storage_with_cache, ByteRangeCache, MemorySizedCache, QuickwitCache, StorageCache,
 };
+pub use self::fault_injection_storage::FaultInjectionStorage;
 pub use self::local_file_storage::{LocalFileStorage, LocalFileStorageFactory};
 #[cfg(feature = "azure")]
 pub use self::object_storage::{AzureBlobStorage, AzureBlobStorageFactory};
@@ -76,7 +78,7 @@ pub use self::opendal_storage::new_emulated_google_cloud_storage;
 #[cfg(feature = "gcs")]
 pub use self::opendal_storage::GoogleCloudStorageFactory;
 pub use self::ram_storage::{RamStorage, RamStorageBuilder};
-pub use self::split::{SplitPayload, SplitPayloadBuilder};
+pub use self::split::{FilePayload, SplitPayload, SplitPayloadBuilder};
 #[cfg(any(test, feature = "testsuite"))]
 pub use self::storage::MockStorage;
 #[cfg(any(test, feature = "testsuite"))]
@@ -145,7 +147,7 @@ mod tests {
     #[tokio::test]
     async fn test_load_file() {
         let storage_resolver = StorageResolver::builder()
-            .register(LocalFileStorageFactory)
+            .register(LocalFileStorageFactory::default())
             .build()
             .unwrap();
         let expected_bytes = tokio::fs::read_to_string("Cargo.toml").await.unwrap();
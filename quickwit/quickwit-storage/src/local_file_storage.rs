@@ -24,15 +24,16 @@ use futures::future::{BoxFuture, FutureExt};
 use futures::StreamExt;
 use quickwit_common::ignore_error_kind;
 use quickwit_common::uri::Uri;
-use quickwit_config::StorageBackend;
+use quickwit_config::{FileStorageConfig, StorageBackend, StorageFaultInjectionConfig};
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tracing::warn;
 
+use crate::fault_injection_storage::resolve_fault_injection_config;
 use crate::metrics::object_storage_get_slice_in_flight_guards;
 use crate::storage::SendableAsync;
 use crate::{
-    BulkDeleteError, DebouncedStorage, DeleteFailure, OwnedBytes, Storage, StorageError,
-    StorageErrorKind, StorageFactory, StorageResolverError, StorageResult,
+    BulkDeleteError, DebouncedStorage, DeleteFailure, FaultInjectionStorage, OwnedBytes, Storage,
+    StorageError, StorageErrorKind, StorageFactory, StorageResolverError, StorageResult,
 };
 
 /// File system compatible storage implementation.
@@ -348,7 +349,20 @@ impl Storage for LocalFileStorage {
 
 /// A File storage resolver
 #[derive(Clone, Debug, Default)]
-pub struct LocalFileStorageFactory;
+pub struct LocalFileStorageFactory {
+    fault_injection_config_opt: Option<StorageFaultInjectionConfig>,
+}
+
+impl LocalFileStorageFactory {
+    /// Creates a new file storage factory via a [`FileStorageConfig`].
+    pub fn new(file_storage_config: FileStorageConfig) -> Self {
+        let fault_injection_config_opt =
+            resolve_fault_injection_config(file_storage_config.fault_injection.as_ref());
+        Self {
+            fault_injection_config_opt,
+        }
+    }
+}
 
 #[async_trait]
 impl StorageFactory for LocalFileStorageFactory {
@@ -358,7 +372,13 @@ impl StorageFactory for LocalFileStorageFactory {
 
     async fn resolve(&self, uri: &Uri) -> Result<Arc<dyn Storage>, StorageResolverError> {
         let storage = LocalFileStorage::from_uri(uri)?;
-        Ok(Arc::new(DebouncedStorage::new(storage)))
+        let storage = Arc::new(DebouncedStorage::new(storage));
+
+        if let Some(fault_injection_config) = &self.fault_injection_config_opt {
+            let storage = FaultInjectionStorage::new(storage, fault_injection_config.clone());
+            return Ok(Arc::new(storage));
+        }
+        Ok(storage)
     }
 }
 
@@ -403,7 +423,7 @@ mod tests {
         let temp_dir = tempfile::tempdir()?;
         let index_uri =
             Uri::from_str(&format!("file://{}/foo/bar", temp_dir.path().display())).unwrap();
-        let local_file_storage_factory = LocalFileStorageFactory;
+        let local_file_storage_factory = LocalFileStorageFactory::default();
         let local_file_storage = local_file_storage_factory.resolve(&index_uri).await?;
         assert_eq!(local_file_storage.uri(), &index_uri);
 
@@ -28,6 +28,8 @@ pub struct StorageMetrics {
     pub fast_field_cache: CacheMetrics,
     pub split_footer_cache: CacheMetrics,
     pub searcher_split_cache: CacheMetrics,
+    pub searcher_split_footer_cache: CacheMetrics,
+    pub split_cache: SplitCacheMetrics,
     pub get_slice_timeout_successes: [IntCounter; 3],
     pub get_slice_timeout_all_timeouts: IntCounter,
     pub object_storage_get_total: IntCounter,
@@ -36,6 +38,7 @@ pub struct StorageMetrics {
     pub object_storage_get_slice_in_flight_num_bytes: IntGauge,
     pub object_storage_put_total: IntCounter,
     pub object_storage_put_parts: IntCounter,
+    pub object_storage_unverified_uploads: IntCounter,
     pub object_storage_download_num_bytes: IntCounter,
     pub object_storage_upload_num_bytes: IntCounter,
 
@@ -93,8 +96,10 @@ impl Default for StorageMetrics {
             fd_cache_metrics: CacheMetrics::for_component("fd"),
             partial_request_cache: CacheMetrics::for_component("partial_request"),
             searcher_split_cache: CacheMetrics::for_component("searcher_split"),
+            searcher_split_footer_cache: CacheMetrics::for_component("searcher_split_footer"),
             shortlived_cache: CacheMetrics::for_component("shortlived"),
             split_footer_cache: CacheMetrics::for_component("splitfooter"),
+            split_cache: SplitCacheMetrics::default(),
             get_slice_timeout_successes,
             get_slice_timeout_all_timeouts,
             object_storage_get_total: new_counter(
@@ -137,6 +142,12 @@ impl Default for StorageMetrics {
                 "",
                 &[],
             ),
+            object_storage_unverified_uploads: new_counter(
+                "object_storage_unverified_uploads",
+                "Number of uploads whose checksum was not returned by the object storage.",
+                "",
+                &[],
+            ),
             object_storage_download_num_bytes: new_counter(
                 "object_storage_download_num_bytes",
                 "Amount of data downloaded from an object storage.",
@@ -157,6 +168,68 @@ impl Default for StorageMetrics {
     }
 }
 
+/// Counters associated to the searcher split cache, on top of its generic [`CacheMetrics`].
+pub struct SplitCacheMetrics {
+    pub hits_total: IntCounter,
+    pub misses_total: IntCounter,
+    pub downloads_started_total: IntCounter,
+    pub downloads_completed_total: IntCounter,
+    pub downloads_failed_total: IntCounter,
+    pub downloaded_num_bytes: IntCounter,
+    pub evictions_total: IntCounterVec<1>,
+    pub disk_pressure: IntGauge,
+}
+
+impl Default for SplitCacheMetrics {
+    fn default() -> Self {
+        const SPLIT_CACHE_METRICS_NAMESPACE: &str = "searcher_split_cache";
+
+        let downloads_total = new_counter_vec(
+            "downloads_total",
+            "Number of split downloads, by outcome.",
+            SPLIT_CACHE_METRICS_NAMESPACE,
+            &[],
+            ["outcome"],
+        );
+        SplitCacheMetrics {
+            hits_total: new_counter(
+                "hits_total",
+                "Number of split accesses served by the split cache.",
+                SPLIT_CACHE_METRICS_NAMESPACE,
+                &[],
+            ),
+            misses_total: new_counter(
+                "misses_total",
+                "Number of split accesses not served by the split cache.",
+                SPLIT_CACHE_METRICS_NAMESPACE,
+                &[],
+            ),
+            downloads_started_total: downloads_total.with_label_values(["started"]),
+            downloads_completed_total: downloads_total.with_label_values(["completed"]),
+            downloads_failed_total: downloads_total.with_label_values(["failed"]),
+            downloaded_num_bytes: new_counter(
+                "downloaded_bytes_total",
+                "Number of bytes downloaded into the split cache.",
+                SPLIT_CACHE_METRICS_NAMESPACE,
+                &[],
+            ),
+            evictions_total: new_counter_vec(
+                "evictions_total",
+                "Number of splits evicted from the split cache, by reason.",
+                SPLIT_CACHE_METRICS_NAMESPACE,
+                &[],
+                ["reason"],
+            ),
+            disk_pressure: new_gauge(
+                "disk_pressure",
+                "Whether the split cache is shrunk because its disk is running out of space.",
+                SPLIT_CACHE_METRICS_NAMESPACE,
+                &[],
+            ),
+        }
+    }
+}
+
 /// Counters associated to a cache.
 #[derive(Clone)]
 pub struct CacheMetrics {
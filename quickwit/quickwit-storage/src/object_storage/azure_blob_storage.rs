@@ -219,7 +219,8 @@ impl AzureBlobStorage {
         .map_err(StorageError::from)
     }
 
-    /// Performs a single part upload.
+    /// Performs a single part upload. The blob is sent with its MD5 digest so that Azure rejects
+    /// it if it is corrupted in transit.
     async fn put_single_part<'a>(
         &'a self,
         name: &'a str,
@@ -244,7 +245,8 @@ impl AzureBlobStorage {
         Ok(())
     }
 
-    /// Performs a multipart upload.
+    /// Performs a multipart upload. Each block is sent with its MD5 digest so that Azure rejects
+    /// it if it is corrupted in transit.
     async fn put_multi_part<'a>(
         &'a self,
         name: &'a str,
@@ -28,7 +28,10 @@ use aws_sdk_s3::operation::delete_objects::DeleteObjectsOutput;
 use aws_sdk_s3::operation::get_object::{GetObjectError, GetObjectOutput};
 use aws_sdk_s3::primitives::ByteStream;
 use aws_sdk_s3::types::builders::ObjectIdentifierBuilder;
-use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart, Delete, ObjectIdentifier};
+use aws_sdk_s3::types::{
+    ChecksumAlgorithm, CompletedMultipartUpload, CompletedPart, Delete, ObjectIdentifier,
+    StorageClass,
+};
 use aws_sdk_s3::Client as S3Client;
 use base64::prelude::{Engine, BASE64_STANDARD};
 use futures::{stream, StreamExt};
@@ -87,6 +90,7 @@ pub struct S3CompatibleObjectStorage {
     retry_params: RetryParams,
     disable_multi_object_delete: bool,
     disable_multipart_upload: bool,
+    disable_flexible_checksums: bool,
 }
 
 impl fmt::Debug for S3CompatibleObjectStorage {
@@ -108,7 +112,11 @@ fn get_credentials_provider(
     ) {
         (Some(access_key_id), Some(secret_access_key)) => {
             info!("using S3 credentials defined in storage config");
-            let credentials = Credentials::from_keys(access_key_id, secret_access_key, None);
+            let credentials = Credentials::from_keys(
+                access_key_id,
+                secret_access_key,
+                s3_storage_config.session_token.clone(),
+            );
             let credentials_provider = SharedCredentialsProvider::new(credentials);
             Some(credentials_provider)
         }
@@ -173,6 +181,7 @@ impl S3CompatibleObjectStorage {
         let retry_params = RetryParams::aggressive();
         let disable_multi_object_delete = s3_storage_config.disable_multi_object_delete;
         let disable_multipart_upload = s3_storage_config.disable_multipart_upload;
+        let disable_flexible_checksums = s3_storage_config.disable_flexible_checksums;
         Ok(Self {
             s3_client,
             uri: uri.clone(),
@@ -182,6 +191,7 @@ impl S3CompatibleObjectStorage {
             retry_params,
             disable_multi_object_delete,
             disable_multipart_upload,
+            disable_flexible_checksums,
         })
     }
 
@@ -199,6 +209,7 @@ impl S3CompatibleObjectStorage {
             retry_params: self.retry_params,
             disable_multi_object_delete: self.disable_multi_object_delete,
             disable_multipart_upload: self.disable_multipart_upload,
+            disable_flexible_checksums: self.disable_flexible_checksums,
         }
     }
 
@@ -236,7 +247,7 @@ struct MultipartUploadId(pub String);
 struct Part {
     pub part_number: usize,
     pub range: Range<u64>,
-    pub md5: md5::Digest,
+    pub checksums: Checksums,
 }
 
 impl Part {
@@ -245,20 +256,59 @@ impl Part {
     }
 }
 
-const MD5_CHUNK_SIZE: usize = 1_000_000;
+/// Checksums of an object or a part, letting the object storage reject a payload corrupted in
+/// transit.
+#[derive(Clone, Copy, Debug)]
+struct Checksums {
+    md5: md5::Digest,
+    crc32c: u32,
+}
+
+const CHECKSUMS_CHUNK_SIZE: usize = 1_000_000;
 
-async fn compute_md5<T: AsyncRead + std::marker::Unpin>(mut read: T) -> io::Result<md5::Digest> {
-    let mut checksum = md5::Context::new();
-    let mut buf = vec![0; MD5_CHUNK_SIZE];
+/// Computes the MD5 digest and the CRC32C checksum of a payload in a single pass.
+async fn compute_checksums<T: AsyncRead + std::marker::Unpin>(
+    mut read: T,
+) -> io::Result<Checksums> {
+    let mut md5 = md5::Context::new();
+    let mut crc32c = 0u32;
+    let mut buf = vec![0; CHECKSUMS_CHUNK_SIZE];
     loop {
         let read_len = read.read(&mut buf).await?;
-        checksum.consume(&buf[..read_len]);
         if read_len == 0 {
-            return Ok(checksum.compute());
+            return Ok(Checksums {
+                md5: md5.compute(),
+                crc32c,
+            });
         }
+        md5.consume(&buf[..read_len]);
+        crc32c = crc32c::crc32c_append(crc32c, &buf[..read_len]);
     }
 }
 
+fn encode_md5(md5: md5::Digest) -> String {
+    BASE64_STANDARD.encode(md5.0)
+}
+
+/// Encodes a CRC32C checksum as expected by the `x-amz-checksum-crc32c` header.
+fn encode_crc32c(crc32c: u32) -> String {
+    BASE64_STANDARD.encode(crc32c.to_be_bytes())
+}
+
+/// Returns the checksum of a multipart upload, computed by the object storage as the CRC32C
+/// checksum of the concatenated checksums of the parts, followed by the number of parts.
+fn multipart_crc32c(parts: &[Part]) -> String {
+    let part_checksums: Vec<u8> = parts
+        .iter()
+        .flat_map(|part| part.checksums.crc32c.to_be_bytes())
+        .collect();
+    format!(
+        "{}-{}",
+        encode_crc32c(crc32c::crc32c(&part_checksums)),
+        parts.len()
+    )
+}
+
 impl S3CompatibleObjectStorage {
     fn key(&self, relative_path: &Path) -> String {
         // FIXME: This may not work on Windows.
@@ -266,6 +316,16 @@ impl S3CompatibleObjectStorage {
         key_path.to_string_lossy().to_string()
     }
 
+    /// Returns the encoded CRC32C checksum to send along a payload, unless flexible checksums are
+    /// not supported by the object storage.
+    fn crc32c_header(&self, checksums: Checksums) -> Option<String> {
+        if self.disable_flexible_checksums {
+            None
+        } else {
+            Some(encode_crc32c(checksums.crc32c))
+        }
+    }
+
     fn relative_path(&self, key: &str) -> PathBuf {
         // FIXME: This may not work on Windows.
         Path::new(key)
@@ -280,6 +340,8 @@ impl S3CompatibleObjectStorage {
         key: &'a str,
         payload: Box<dyn crate::PutPayload>,
         len: u64,
+        checksums: Checksums,
+        storage_class_opt: Option<&'a str>,
     ) -> Result<(), Retry<StorageError>> {
         let body = payload
             .byte_stream()
@@ -297,6 +359,9 @@ impl S3CompatibleObjectStorage {
             .key(key)
             .body(body)
             .content_length(len as i64)
+            .content_md5(encode_md5(checksums.md5))
+            .set_checksum_crc32_c(self.crc32c_header(checksums))
+            .set_storage_class(storage_class_opt.map(StorageClass::from))
             .send()
             .await
             .map_err(|sdk_error| {
@@ -314,23 +379,41 @@ impl S3CompatibleObjectStorage {
         key: &'a str,
         payload: Box<dyn crate::PutPayload>,
         len: u64,
+        storage_class_opt: Option<&'a str>,
     ) -> StorageResult<()> {
         let bucket = &self.bucket;
+        let read = payload.byte_stream().await?.into_async_read();
+        let checksums = compute_checksums(read).await?;
         aws_retry(&self.retry_params, || async {
-            self.put_single_part_single_try(bucket, key, payload.clone(), len)
-                .await
+            self.put_single_part_single_try(
+                bucket,
+                key,
+                payload.clone(),
+                len,
+                checksums,
+                storage_class_opt,
+            )
+            .await
         })
         .await
         .map_err(|error| error.into_inner())?;
         Ok(())
     }
 
-    async fn create_multipart_upload(&self, key: &str) -> StorageResult<MultipartUploadId> {
+    async fn create_multipart_upload(
+        &self,
+        key: &str,
+        storage_class_opt: Option<&str>,
+    ) -> StorageResult<MultipartUploadId> {
         let upload_id = aws_retry(&self.retry_params, || async {
             self.s3_client
                 .create_multipart_upload()
                 .bucket(self.bucket.clone())
                 .key(key)
+                .set_checksum_algorithm(
+                    (!self.disable_flexible_checksums).then_some(ChecksumAlgorithm::Crc32C),
+                )
+                .set_storage_class(storage_class_opt.map(StorageClass::from))
                 .send()
                 .await
         })
@@ -361,12 +444,12 @@ impl S3CompatibleObjectStorage {
                 .range_byte_stream(multipart_range.clone())
                 .await?
                 .into_async_read();
-            let md5 = compute_md5(read).await?;
+            let checksums = compute_checksums(read).await?;
 
             let part = Part {
                 part_number: multipart_id + 1, // parts are 1-indexed
                 range: multipart_range,
-                md5,
+                checksums,
             };
             parts.push(part);
         }
@@ -419,7 +502,7 @@ impl S3CompatibleObjectStorage {
             .await
             .map_err(StorageError::from)
             .map_err(Retry::Permanent)?;
-        let md5 = BASE64_STANDARD.encode(part.md5.0);
+        let crc32c_opt = self.crc32c_header(part.checksums);
 
         crate::STORAGE_METRICS.object_storage_put_parts.inc();
         crate::STORAGE_METRICS
@@ -433,7 +516,8 @@ impl S3CompatibleObjectStorage {
             .key(key)
             .body(byte_stream)
             .content_length(part.len() as i64)
-            .content_md5(md5)
+            .content_md5(encode_md5(part.checksums.md5))
+            .set_checksum_crc32_c(crc32c_opt.clone())
             .part_number(part.part_number as i32)
             .upload_id(upload_id.0)
             .send()
@@ -448,22 +532,47 @@ impl S3CompatibleObjectStorage {
 
         let completed_part = CompletedPart::builder()
             .set_e_tag(upload_part_output.e_tag)
+            .set_checksum_crc32_c(crc32c_opt)
             .part_number(part.part_number as i32)
             .build();
         Ok(completed_part)
     }
 
+    async fn put_impl(
+        &self,
+        path: &Path,
+        payload: Box<dyn crate::PutPayload>,
+        storage_class_opt: Option<&str>,
+    ) -> StorageResult<()> {
+        crate::STORAGE_METRICS.object_storage_put_total.inc();
+        let _permit = REQUEST_SEMAPHORE.acquire().await;
+        let key = self.key(path);
+        let total_len = payload.len();
+        let part_num_bytes = self.multipart_policy.part_num_bytes(total_len);
+        if self.disable_multipart_upload || part_num_bytes >= total_len {
+            self.put_single_part(&key, payload, total_len, storage_class_opt)
+                .await?;
+        } else {
+            self.put_multipart(&key, payload, part_num_bytes, total_len, storage_class_opt)
+                .await?;
+        }
+        Ok(())
+    }
+
     async fn put_multipart<'a>(
         &'a self,
         key: &'a str,
         payload: Box<dyn crate::PutPayload>,
         part_len: u64,
         total_len: u64,
+        storage_class_opt: Option<&'a str>,
     ) -> StorageResult<()> {
-        let upload_id = self.create_multipart_upload(key).await?;
+        let upload_id = self.create_multipart_upload(key, storage_class_opt).await?;
         let parts = self
             .create_multipart_requests(payload.clone(), total_len, part_len)
             .await?;
+        let expected_crc32c_opt =
+            (!self.disable_flexible_checksums).then(|| multipart_crc32c(&parts));
         let max_concurrent_upload = self.multipart_policy.max_concurrent_uploads();
         let completed_parts_res: StorageResult<Vec<CompletedPart>> =
             stream::iter(parts.into_iter().map(|part| {
@@ -481,8 +590,13 @@ impl S3CompatibleObjectStorage {
             .collect();
         match completed_parts_res {
             Ok(completed_parts) => {
-                self.complete_multipart_upload(key, completed_parts, &upload_id.0)
-                    .await
+                self.complete_multipart_upload(
+                    key,
+                    completed_parts,
+                    &upload_id.0,
+                    expected_crc32c_opt.as_deref(),
+                )
+                .await
             }
             Err(upload_error) => {
                 let abort_multipart_upload_res: StorageResult<()> =
@@ -499,16 +613,20 @@ impl S3CompatibleObjectStorage {
         }
     }
 
+    /// Completes a multipart upload and checks that the checksum of the object assembled by the
+    /// object storage matches the checksums of the parts that were sent. Uploads whose checksum is
+    /// not reported back by the object storage are logged and counted.
     async fn complete_multipart_upload(
         &self,
         key: &str,
         completed_parts: Vec<CompletedPart>,
         upload_id: &str,
+        expected_crc32c_opt: Option<&str>,
     ) -> StorageResult<()> {
         let completed_upload = CompletedMultipartUpload::builder()
             .set_parts(Some(completed_parts))
             .build();
-        aws_retry(&self.retry_params, || async {
+        let complete_multipart_upload_output = aws_retry(&self.retry_params, || async {
             self.s3_client
                 .complete_multipart_upload()
                 .bucket(self.bucket.clone())
@@ -519,6 +637,27 @@ impl S3CompatibleObjectStorage {
                 .await
         })
         .await?;
+
+        let Some(expected_crc32c) = expected_crc32c_opt else {
+            return Ok(());
+        };
+        let Some(crc32c) = complete_multipart_upload_output.checksum_crc32_c() else {
+            warn!(
+                key = %key,
+                "Object storage did not return the checksum of the multipart upload. Consider \
+                 setting `disable_flexible_checksums`."
+            );
+            crate::STORAGE_METRICS
+                .object_storage_unverified_uploads
+                .inc();
+            return Ok(());
+        };
+        if crc32c != expected_crc32c {
+            return Err(StorageErrorKind::Io.with_error(anyhow!(
+                "checksum mismatch for multipart upload of `{key}`: expected `{expected_crc32c}`, \
+                 got `{crc32c}`"
+            )));
+        }
         Ok(())
     }
 
@@ -744,18 +883,16 @@ impl Storage for S3CompatibleObjectStorage {
         path: &Path,
         payload: Box<dyn crate::PutPayload>,
     ) -> crate::StorageResult<()> {
-        crate::STORAGE_METRICS.object_storage_put_total.inc();
-        let _permit = REQUEST_SEMAPHORE.acquire().await;
-        let key = self.key(path);
-        let total_len = payload.len();
-        let part_num_bytes = self.multipart_policy.part_num_bytes(total_len);
-        if self.disable_multipart_upload || part_num_bytes >= total_len {
-            self.put_single_part(&key, payload, total_len).await?;
-        } else {
-            self.put_multipart(&key, payload, part_num_bytes, total_len)
-                .await?;
-        }
-        Ok(())
+        self.put_impl(path, payload, None).await
+    }
+
+    async fn put_with_storage_class(
+        &self,
+        path: &Path,
+        payload: Box<dyn crate::PutPayload>,
+        storage_class: &str,
+    ) -> crate::StorageResult<()> {
+        self.put_impl(path, payload, Some(storage_class)).await
     }
 
     async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
@@ -897,14 +1034,45 @@ mod tests {
     use crate::{MultiPartPolicy, S3CompatibleObjectStorage};
 
     #[tokio::test]
-    async fn test_md5_calc() -> std::io::Result<()> {
+    async fn test_checksums_calc() -> std::io::Result<()> {
         let data = (0..1_500_000).map(|el| el as u8).collect::<Vec<_>>();
-        let md5 = compute_md5(data.as_slice()).await?;
-        assert_eq!(md5, md5::compute(data));
+        let checksums = compute_checksums(data.as_slice()).await?;
+        assert_eq!(checksums.md5, md5::compute(&data));
+        assert_eq!(checksums.crc32c, crc32c::crc32c(&data));
 
         Ok(())
     }
 
+    #[test]
+    fn test_multipart_crc32c() {
+        let parts = [
+            Part {
+                part_number: 1,
+                range: 0..3,
+                checksums: Checksums {
+                    md5: md5::compute(b"foo"),
+                    crc32c: crc32c::crc32c(b"foo"),
+                },
+            },
+            Part {
+                part_number: 2,
+                range: 3..6,
+                checksums: Checksums {
+                    md5: md5::compute(b"bar"),
+                    crc32c: crc32c::crc32c(b"bar"),
+                },
+            },
+        ];
+        let mut part_checksums = Vec::new();
+        part_checksums.extend_from_slice(&crc32c::crc32c(b"foo").to_be_bytes());
+        part_checksums.extend_from_slice(&crc32c::crc32c(b"bar").to_be_bytes());
+        let expected_crc32c = format!(
+            "{}-2",
+            BASE64_STANDARD.encode(crc32c::crc32c(&part_checksums).to_be_bytes())
+        );
+        assert_eq!(multipart_crc32c(&parts), expected_crc32c);
+    }
+
     #[test]
     fn test_split_range_into_chunks_inexact() {
         assert_eq!(
@@ -967,6 +1135,7 @@ mod tests {
             retry_params: RetryParams::for_test(),
             disable_multi_object_delete: false,
             disable_multipart_upload: false,
+            disable_flexible_checksums: false,
         };
         assert_eq!(
             s3_storage.relative_path("indexes/foo"),
@@ -1022,6 +1191,7 @@ mod tests {
             retry_params: RetryParams::for_test(),
             disable_multi_object_delete: true,
             disable_multipart_upload: false,
+            disable_flexible_checksums: false,
         };
         let _ = s3_storage
             .bulk_delete(&[Path::new("foo"), Path::new("bar")])
@@ -1063,6 +1233,7 @@ mod tests {
             retry_params: RetryParams::for_test(),
             disable_multi_object_delete: false,
             disable_multipart_upload: false,
+            disable_flexible_checksums: false,
         };
         let _ = s3_storage
             .bulk_delete(&[Path::new("foo"), Path::new("bar")])
@@ -1145,6 +1316,7 @@ mod tests {
             retry_params: RetryParams::for_test(),
             disable_multi_object_delete: false,
             disable_multipart_upload: false,
+            disable_flexible_checksums: false,
         };
         let bulk_delete_error = s3_storage
             .bulk_delete(&[
@@ -1238,10 +1410,254 @@ mod tests {
             retry_params: RetryParams::for_test(),
             disable_multi_object_delete: false,
             disable_multipart_upload: false,
+            disable_flexible_checksums: false,
+        };
+        s3_storage
+            .put(Path::new("my-path"), Box::new(vec![1, 2, 3]))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_s3_compatible_storage_put_with_storage_class() {
+        let client = StaticReplayClient::new(vec![ReplayEvent::new(
+            http::Request::builder()
+                .body(SdkBody::from_body_0_4(Body::empty()))
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .body(SdkBody::from_body_0_4(Body::empty()))
+                .unwrap(),
+        )]);
+        let credentials = Credentials::new("mock_key", "mock_secret", None, None, "mock_provider");
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(aws_behavior_version())
+            .region(Some(Region::new("Foo")))
+            .http_client(client.clone())
+            .credentials_provider(credentials)
+            .build();
+        let s3_client = S3Client::from_conf(config);
+        let uri = Uri::for_test("s3://bucket/indexes");
+        let bucket = "bucket".to_string();
+        let prefix = PathBuf::new();
+
+        let s3_storage = S3CompatibleObjectStorage {
+            s3_client,
+            uri,
+            bucket,
+            prefix,
+            multipart_policy: MultiPartPolicy::default(),
+            retry_params: RetryParams::for_test(),
+            disable_multi_object_delete: false,
+            disable_multipart_upload: false,
+            disable_flexible_checksums: false,
+        };
+        s3_storage
+            .put_with_storage_class(Path::new("my-path"), Box::new(vec![1, 2, 3]), "STANDARD_IA")
+            .await
+            .unwrap();
+
+        let requests = client.actual_requests().collect::<Vec<_>>();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].headers().get("x-amz-storage-class"),
+            Some("STANDARD_IA")
+        );
+        let expected_md5 = encode_md5(md5::compute([1u8, 2, 3]));
+        assert_eq!(
+            requests[0].headers().get("content-md5"),
+            Some(expected_md5.as_str())
+        );
+        let expected_crc32c = encode_crc32c(crc32c::crc32c(&[1, 2, 3]));
+        assert_eq!(
+            requests[0].headers().get("x-amz-checksum-crc32c"),
+            Some(expected_crc32c.as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_s3_compatible_storage_put_without_flexible_checksums() {
+        let client = StaticReplayClient::new(vec![ReplayEvent::new(
+            http::Request::builder()
+                .body(SdkBody::from_body_0_4(Body::empty()))
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .body(SdkBody::from_body_0_4(Body::empty()))
+                .unwrap(),
+        )]);
+        let credentials = Credentials::new("mock_key", "mock_secret", None, None, "mock_provider");
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(aws_behavior_version())
+            .region(Some(Region::new("Foo")))
+            .http_client(client.clone())
+            .credentials_provider(credentials)
+            .build();
+        let s3_storage = S3CompatibleObjectStorage {
+            s3_client: S3Client::from_conf(config),
+            uri: Uri::for_test("s3://bucket/indexes"),
+            bucket: "bucket".to_string(),
+            prefix: PathBuf::new(),
+            multipart_policy: MultiPartPolicy::default(),
+            retry_params: RetryParams::for_test(),
+            disable_multi_object_delete: false,
+            disable_multipart_upload: false,
+            disable_flexible_checksums: true,
         };
         s3_storage
             .put(Path::new("my-path"), Box::new(vec![1, 2, 3]))
             .await
             .unwrap();
+
+        let requests = client.actual_requests().collect::<Vec<_>>();
+        assert_eq!(requests.len(), 1);
+        let expected_md5 = encode_md5(md5::compute([1u8, 2, 3]));
+        assert_eq!(
+            requests[0].headers().get("content-md5"),
+            Some(expected_md5.as_str())
+        );
+        assert!(requests[0].headers().get("x-amz-checksum-crc32c").is_none());
+    }
+
+    fn multipart_replay_events(crc32c_opt: Option<&str>) -> Vec<ReplayEvent> {
+        let checksum_element = crc32c_opt
+            .map(|crc32c| format!("<ChecksumCRC32C>{crc32c}</ChecksumCRC32C>"))
+            .unwrap_or_default();
+        let create_multipart_upload_event = ReplayEvent::new(
+            http::Request::builder()
+                .body(SdkBody::from_body_0_4(Body::empty()))
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .body(SdkBody::from_body_0_4(Body::from(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+                    <InitiateMultipartUploadResult>
+                        <Bucket>bucket</Bucket>
+                        <Key>my-path</Key>
+                        <UploadId>my-upload-id</UploadId>
+                    </InitiateMultipartUploadResult>"#,
+                )))
+                .unwrap(),
+        );
+        let upload_part_event = || {
+            ReplayEvent::new(
+                http::Request::builder()
+                    .body(SdkBody::from_body_0_4(Body::empty()))
+                    .unwrap(),
+                http::Response::builder()
+                    .status(200)
+                    .header("ETag", "my-etag")
+                    .body(SdkBody::from_body_0_4(Body::empty()))
+                    .unwrap(),
+            )
+        };
+        let complete_multipart_upload_event = ReplayEvent::new(
+            http::Request::builder()
+                .body(SdkBody::from_body_0_4(Body::empty()))
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .body(SdkBody::from_body_0_4(Body::from(format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+                    <CompleteMultipartUploadResult>
+                        <Bucket>bucket</Bucket>
+                        <Key>my-path</Key>
+                        <ETag>my-etag</ETag>
+                        {checksum_element}
+                    </CompleteMultipartUploadResult>"#
+                ))))
+                .unwrap(),
+        );
+        vec![
+            create_multipart_upload_event,
+            upload_part_event(),
+            upload_part_event(),
+            complete_multipart_upload_event,
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_s3_compatible_storage_put_multipart_checks_crc32c() {
+        let parts = [
+            Part {
+                part_number: 1,
+                range: 0..3,
+                checksums: Checksums {
+                    md5: md5::compute(b"foo"),
+                    crc32c: crc32c::crc32c(b"foo"),
+                },
+            },
+            Part {
+                part_number: 2,
+                range: 3..6,
+                checksums: Checksums {
+                    md5: md5::compute(b"bar"),
+                    crc32c: crc32c::crc32c(b"bar"),
+                },
+            },
+        ];
+        let expected_crc32c = multipart_crc32c(&parts);
+
+        let unverified_uploads_before = crate::STORAGE_METRICS
+            .object_storage_unverified_uploads
+            .get();
+
+        for (crc32c_opt, is_valid) in [
+            (Some(expected_crc32c.as_str()), true),
+            (Some("AAAAAA==-2"), false),
+            (None, true),
+        ] {
+            let client = StaticReplayClient::new(multipart_replay_events(crc32c_opt));
+            let credentials =
+                Credentials::new("mock_key", "mock_secret", None, None, "mock_provider");
+            let config = aws_sdk_s3::Config::builder()
+                .behavior_version(aws_behavior_version())
+                .region(Some(Region::new("Foo")))
+                .http_client(client.clone())
+                .credentials_provider(credentials)
+                .build();
+            let s3_storage = S3CompatibleObjectStorage {
+                s3_client: S3Client::from_conf(config),
+                uri: Uri::for_test("s3://bucket/indexes"),
+                bucket: "bucket".to_string(),
+                prefix: PathBuf::new(),
+                multipart_policy: MultiPartPolicy {
+                    target_part_num_bytes: 3,
+                    max_num_parts: 10_000,
+                    multipart_threshold_num_bytes: 5,
+                    max_object_num_bytes: 5_000_000_000_000,
+                    max_concurrent_uploads: 1,
+                },
+                retry_params: RetryParams::for_test(),
+                disable_multi_object_delete: false,
+                disable_multipart_upload: false,
+                disable_flexible_checksums: false,
+            };
+            let put_res = s3_storage
+                .put(Path::new("my-path"), Box::new(b"foobar".to_vec()))
+                .await;
+            assert_eq!(put_res.is_ok(), is_valid);
+
+            let requests = client.actual_requests().collect::<Vec<_>>();
+            assert_eq!(requests.len(), 4);
+            assert_eq!(
+                requests[0].headers().get("x-amz-checksum-algorithm"),
+                Some("CRC32C")
+            );
+            let expected_part_md5 = encode_md5(md5::compute(b"foo"));
+            assert_eq!(
+                requests[1].headers().get("content-md5"),
+                Some(expected_part_md5.as_str())
+            );
+            let expected_part_crc32c = encode_crc32c(crc32c::crc32c(b"foo"));
+            assert_eq!(
+                requests[1].headers().get("x-amz-checksum-crc32c"),
+                Some(expected_part_crc32c.as_str())
+            );
+        }
+        let unverified_uploads_after = crate::STORAGE_METRICS
+            .object_storage_unverified_uploads
+            .get();
+        assert!(unverified_uploads_after > unverified_uploads_before);
     }
 }
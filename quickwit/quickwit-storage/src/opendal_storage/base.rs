@@ -16,11 +16,14 @@ use std::fmt;
 use std::ops::Range;
 use std::path::Path;
 
+use anyhow::anyhow;
 use async_trait::async_trait;
+use base64::prelude::{Engine, BASE64_STANDARD};
 use bytesize::ByteSize;
 use opendal::Operator;
 use quickwit_common::uri::Uri;
-use tokio::io::{AsyncRead, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tracing::warn;
 
 use crate::metrics::object_storage_get_slice_in_flight_guards;
 use crate::storage::SendableAsync;
@@ -59,6 +62,25 @@ impl OpendalStorage {
         let op = Operator::new(cfg)?.finish();
         Ok(Self { uri, op })
     }
+
+    /// Checks the MD5 digest of an uploaded object. Objects whose digest is not reported by the
+    /// object storage, such as composite objects, are logged and counted.
+    async fn check_md5(&self, path: &str, expected_md5: &str) -> StorageResult<()> {
+        let metadata = self.op.stat(path).await?;
+        let Some(md5) = metadata.content_md5() else {
+            warn!(path = %path, "Object storage did not return the MD5 digest of the object.");
+            crate::STORAGE_METRICS
+                .object_storage_unverified_uploads
+                .inc();
+            return Ok(());
+        };
+        if md5 != expected_md5 {
+            return Err(StorageErrorKind::Io.with_error(anyhow!(
+                "checksum mismatch for upload of `{path}`: expected `{expected_md5}`, got `{md5}`"
+            )));
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -72,6 +94,9 @@ impl Storage for OpendalStorage {
     ///
     /// We can implement something like `multipart_policy` determine whether to use copy.
     /// If the payload is small enough, we can call `op.write()` at once.
+    ///
+    /// The MD5 digest of the payload is computed while it is written and checked against the one
+    /// reported by the object storage, so that a payload corrupted in transit is detected.
     async fn put(&self, path: &Path, payload: Box<dyn PutPayload>) -> StorageResult<()> {
         let path = path.as_os_str().to_string_lossy();
         let mut payload_reader = payload.byte_stream().await?.into_async_read();
@@ -81,10 +106,20 @@ impl Storage for OpendalStorage {
             .writer_with(&path)
             .buffer(ByteSize::mb(8).as_u64() as usize)
             .await?;
-        tokio::io::copy(&mut payload_reader, &mut storage_writer).await?;
+        let mut md5 = md5::Context::new();
+        let mut buf = vec![0; ByteSize::mb(1).as_u64() as usize];
+        loop {
+            let read_len = payload_reader.read(&mut buf).await?;
+            if read_len == 0 {
+                break;
+            }
+            md5.consume(&buf[..read_len]);
+            storage_writer.write_all(&buf[..read_len]).await?;
+        }
         storage_writer.close().await?;
 
-        Ok(())
+        let expected_md5 = BASE64_STANDARD.encode(md5.compute().0);
+        self.check_md5(&path, &expected_md5).await
     }
 
     async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
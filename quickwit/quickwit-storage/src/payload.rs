@@ -18,6 +18,9 @@ use std::ops::Range;
 use async_trait::async_trait;
 use aws_sdk_s3::primitives::ByteStream;
 use tantivy::directory::OwnedBytes;
+use tokio::io::AsyncReadExt;
+
+const CRC32C_CHUNK_SIZE: usize = 1_000_000;
 
 #[async_trait]
 /// PutPayload is used to upload data and support multipart.
@@ -46,6 +49,22 @@ pub trait PutPayload: PutPayloadClone + Send + Sync {
 
         Ok(OwnedBytes::new(data))
     }
+
+    /// Computes the CRC32C checksum of the whole payload without loading it into memory.
+    async fn crc32c(&self) -> io::Result<u32> {
+        let mut reader = self.byte_stream().await?.into_async_read();
+        let mut buffer = vec![0u8; CRC32C_CHUNK_SIZE];
+        let mut checksum = 0u32;
+
+        loop {
+            let num_bytes_read = reader.read(&mut buffer).await?;
+
+            if num_bytes_read == 0 {
+                return Ok(checksum);
+            }
+            checksum = crc32c::crc32c_append(checksum, &buffer[..num_bytes_read]);
+        }
+    }
 }
 
 pub trait PutPayloadClone {
@@ -55,6 +55,17 @@ impl Storage for PrefixStorage {
         self.storage.put(&self.prefix.join(path), payload).await
     }
 
+    async fn put_with_storage_class(
+        &self,
+        path: &Path,
+        payload: Box<dyn crate::PutPayload>,
+        storage_class: &str,
+    ) -> crate::StorageResult<()> {
+        self.storage
+            .put_with_storage_class(&self.prefix.join(path), payload, storage_class)
+            .await
+    }
+
     async fn copy_to(
         &self,
         path: &Path,
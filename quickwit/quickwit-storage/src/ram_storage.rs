@@ -21,15 +21,16 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use quickwit_common::uri::{Protocol, Uri};
-use quickwit_config::StorageBackend;
+use quickwit_config::{RamStorageConfig, StorageBackend, StorageFaultInjectionConfig};
 use tokio::io::{AsyncRead, AsyncWriteExt};
 use tokio::sync::RwLock;
 
+use crate::fault_injection_storage::resolve_fault_injection_config;
 use crate::prefix_storage::add_prefix_to_storage;
 use crate::storage::SendableAsync;
 use crate::{
-    BulkDeleteError, OwnedBytes, Storage, StorageErrorKind, StorageFactory, StorageResolverError,
-    StorageResult,
+    BulkDeleteError, FaultInjectionStorage, OwnedBytes, Storage, StorageErrorKind, StorageFactory,
+    StorageResolverError, StorageResult,
 };
 
 /// In Ram implementation of quickwit's storage.
@@ -183,12 +184,26 @@ impl RamStorageBuilder {
 /// Storage resolver for [`RamStorage`].
 pub struct RamStorageFactory {
     ram_storage: Arc<dyn Storage>,
+    fault_injection_config_opt: Option<StorageFaultInjectionConfig>,
 }
 
 impl Default for RamStorageFactory {
     fn default() -> Self {
         RamStorageFactory {
             ram_storage: Arc::new(RamStorage::default()),
+            fault_injection_config_opt: None,
+        }
+    }
+}
+
+impl RamStorageFactory {
+    /// Creates a new RAM storage factory via a [`RamStorageConfig`].
+    pub fn new(ram_storage_config: RamStorageConfig) -> Self {
+        let fault_injection_config_opt =
+            resolve_fault_injection_config(ram_storage_config.fault_injection.as_ref());
+        RamStorageFactory {
+            fault_injection_config_opt,
+            ..Default::default()
         }
     }
 }
@@ -200,17 +215,20 @@ impl StorageFactory for RamStorageFactory {
     }
 
     async fn resolve(&self, uri: &Uri) -> Result<Arc<dyn Storage>, StorageResolverError> {
-        match uri.filepath() {
-            Some(prefix) if uri.protocol() == Protocol::Ram => Ok(add_prefix_to_storage(
-                self.ram_storage.clone(),
-                prefix.to_path_buf(),
-                uri.clone(),
-            )),
+        let storage = match uri.filepath() {
+            Some(prefix) if uri.protocol() == Protocol::Ram => {
+                add_prefix_to_storage(self.ram_storage.clone(), prefix.to_path_buf(), uri.clone())
+            }
             _ => {
                 let message = format!("URI `{uri}` is not a valid RAM URI");
-                Err(StorageResolverError::InvalidUri(message))
+                return Err(StorageResolverError::InvalidUri(message));
             }
+        };
+        if let Some(fault_injection_config) = &self.fault_injection_config_opt {
+            let storage = FaultInjectionStorage::new(storage, fault_injection_config.clone());
+            return Ok(Arc::new(storage));
         }
+        Ok(storage)
     }
 }
 
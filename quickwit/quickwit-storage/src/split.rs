@@ -100,12 +100,51 @@ impl PutPayload for SplitPayload {
     }
 }
 
+/// A payload streaming the content of a local file, or of a slice of it.
 #[derive(Clone)]
-struct FilePayload {
+pub struct FilePayload {
+    offset: u64,
     len: u64,
+    is_whole_file: bool,
     path: PathBuf,
 }
 
+impl FilePayload {
+    /// Creates a payload for the file located at `path`.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let len = std::fs::metadata(path)?.len();
+        let file_payload = Self {
+            offset: 0,
+            len,
+            is_whole_file: true,
+            path: path.to_path_buf(),
+        };
+        Ok(file_payload)
+    }
+
+    /// Creates a payload for the bytes of the file located at `path` within `byte_range`.
+    pub fn open_slice(path: &Path, byte_range: Range<u64>) -> io::Result<Self> {
+        let file_len = std::fs::metadata(path)?.len();
+
+        if byte_range.end > file_len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "byte range {byte_range:?} exceeds the length of file `{}`",
+                    path.display()
+                ),
+            ));
+        }
+        let file_payload = Self {
+            offset: byte_range.start,
+            len: byte_range.end - byte_range.start,
+            is_whole_file: byte_range.start == 0 && byte_range.end == file_len,
+            path: path.to_path_buf(),
+        };
+        Ok(file_payload)
+    }
+}
+
 #[async_trait]
 impl PutPayload for FilePayload {
     fn len(&self) -> u64 {
@@ -116,11 +155,12 @@ impl PutPayload for FilePayload {
         assert!(!range.is_empty());
         assert!(range.end <= self.len);
         let mut file = tokio::fs::File::open(&self.path).await?;
-        if range.start > 0 {
-            file.seek(SeekFrom::Start(range.start)).await?;
+        let start = self.offset + range.start;
+        if start > 0 {
+            file.seek(SeekFrom::Start(start)).await?;
         }
 
-        let body = if range.end == self.len {
+        let body = if self.is_whole_file && range.end == self.len {
             Body::wrap_stream(ReaderStream::new(file))
         } else {
             Body::wrap_stream(ReaderStream::new(file.take(range.end - range.start)))
@@ -167,7 +207,6 @@ impl SplitPayloadBuilder {
 
     /// Adds the file to the bundle file.
     pub fn add_file(&mut self, path: &Path) -> io::Result<()> {
-        let file = std::fs::metadata(path)?;
         let file_name = path
             .file_name()
             .and_then(std::ffi::OsStr::to_str)
@@ -179,10 +218,7 @@ impl SplitPayloadBuilder {
                 )
             })?;
 
-        let file_payload = FilePayload {
-            path: path.to_owned(),
-            len: file.len(),
-        };
+        let file_payload = FilePayload::open(path)?;
 
         self.add_payload(file_name, Box::new(file_payload));
 
@@ -468,6 +504,32 @@ mod tests {
 
         // last 8 bytes are the length of the hotcache bytes
         assert_eq!(all_data[all_data.len() - 4..], 3_u32.to_le_bytes());
+
+        let checksum = split_streamer.crc32c().await?;
+        assert_eq!(checksum, crc32c::crc32c(&all_data));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_file_payload_slice() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let test_filepath = temp_dir.path().join("f");
+        File::create(&test_filepath)?.write_all(b"hello world")?;
+
+        let file_payload = FilePayload::open_slice(&test_filepath, 6..11)?;
+        assert_eq!(file_payload.len(), 5);
+        assert_eq!(&file_payload.read_all().await?[..], b"world");
+
+        let mut data = Vec::new();
+        file_payload
+            .range_byte_stream(1..3)
+            .await?
+            .into_async_read()
+            .read_to_end(&mut data)
+            .await?;
+        assert_eq!(data, b"or");
+
+        FilePayload::open_slice(&test_filepath, 6..12).unwrap_err();
         Ok(())
     }
 }
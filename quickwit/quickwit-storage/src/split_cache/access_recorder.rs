@@ -0,0 +1,148 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::Instant;
+
+use ulid::Ulid;
+
+use crate::split_cache::split_table::{compute_timestamp, LastAccessDate};
+
+/// Number of shards of the access recorder. Splits are dispatched to the shards based on the
+/// random bits of their ULID.
+const NUM_SHARDS: usize = 16;
+
+/// Accesses to a split not applied to the split table yet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct PendingAccess {
+    pub last_accessed: LastAccessDate,
+    pub num_accesses: u32,
+}
+
+#[derive(Default)]
+struct Shard {
+    // Size of the splits on disk, mirroring the split table.
+    on_disk_splits: RwLock<HashMap<Ulid, u64>>,
+    pending_accesses: Mutex<HashMap<Ulid, PendingAccess>>,
+}
+
+/// Serves the accesses to the splits on disk without taking the split table lock.
+///
+/// The split table keeps the recorder in sync with the splits it has on disk. Accesses to these
+/// splits are buffered, and applied to the split table whenever it runs its bookkeeping
+/// (download opportunities, eviction, manifest persistence).
+pub(crate) struct SplitAccessRecorder {
+    origin_time: Instant,
+    shards: Box<[Shard]>,
+}
+
+impl SplitAccessRecorder {
+    pub fn with_origin_time(origin_time: Instant) -> SplitAccessRecorder {
+        SplitAccessRecorder {
+            origin_time,
+            shards: std::iter::repeat_with(Shard::default)
+                .take(NUM_SHARDS)
+                .collect(),
+        }
+    }
+
+    fn shard(&self, split_ulid: Ulid) -> &Shard {
+        &self.shards[(split_ulid.0 % NUM_SHARDS as u128) as usize]
+    }
+
+    pub fn insert_on_disk(&self, split_ulid: Ulid, num_bytes: u64) {
+        self.shard(split_ulid)
+            .on_disk_splits
+            .write()
+            .unwrap()
+            .insert(split_ulid, num_bytes);
+    }
+
+    pub fn remove_on_disk(&self, split_ulid: Ulid) {
+        self.shard(split_ulid)
+            .on_disk_splits
+            .write()
+            .unwrap()
+            .remove(&split_ulid);
+    }
+
+    /// If the split is on disk, records the access and returns `Some(num_bytes)`.
+    ///
+    /// Returns `None` otherwise, in which case the access needs to go through the split table.
+    pub fn touch_on_disk(&self, split_ulid: Ulid) -> Option<u64> {
+        let shard = self.shard(split_ulid);
+        let num_bytes = *shard.on_disk_splits.read().unwrap().get(&split_ulid)?;
+        let last_accessed = compute_timestamp(self.origin_time);
+        shard
+            .pending_accesses
+            .lock()
+            .unwrap()
+            .entry(split_ulid)
+            .and_modify(|pending_access| {
+                pending_access.last_accessed = pending_access.last_accessed.max(last_accessed);
+                pending_access.num_accesses = pending_access.num_accesses.saturating_add(1);
+            })
+            .or_insert(PendingAccess {
+                last_accessed,
+                num_accesses: 1,
+            });
+        crate::metrics::STORAGE_METRICS.split_cache.hits_total.inc();
+        Some(num_bytes)
+    }
+
+    /// Returns the accesses recorded since the last call.
+    pub fn drain_pending_accesses(&self) -> Vec<(Ulid, PendingAccess)> {
+        let mut pending_accesses = Vec::new();
+        for shard in self.shards.iter() {
+            pending_accesses.extend(shard.pending_accesses.lock().unwrap().drain());
+        }
+        pending_accesses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_access_recorder() {
+        let access_recorder = SplitAccessRecorder::with_origin_time(Instant::now());
+        let split_ulid_0 = Ulid::new();
+        let split_ulid_1 = Ulid::new();
+
+        assert!(access_recorder.touch_on_disk(split_ulid_0).is_none());
+        assert!(access_recorder.drain_pending_accesses().is_empty());
+
+        access_recorder.insert_on_disk(split_ulid_0, 1_000);
+        access_recorder.insert_on_disk(split_ulid_1, 2_000);
+
+        assert_eq!(access_recorder.touch_on_disk(split_ulid_0), Some(1_000));
+        assert_eq!(access_recorder.touch_on_disk(split_ulid_0), Some(1_000));
+        assert_eq!(access_recorder.touch_on_disk(split_ulid_1), Some(2_000));
+
+        let mut pending_accesses = access_recorder.drain_pending_accesses();
+        pending_accesses.sort_by_key(|(split_ulid, _)| *split_ulid == split_ulid_1);
+        assert_eq!(pending_accesses.len(), 2);
+        assert_eq!(pending_accesses[0].0, split_ulid_0);
+        assert_eq!(pending_accesses[0].1.num_accesses, 2);
+        assert_eq!(pending_accesses[1].0, split_ulid_1);
+        assert_eq!(pending_accesses[1].1.num_accesses, 1);
+        assert!(access_recorder.drain_pending_accesses().is_empty());
+
+        access_recorder.remove_on_disk(split_ulid_0);
+        assert!(access_recorder.touch_on_disk(split_ulid_0).is_none());
+        assert!(access_recorder.drain_pending_accesses().is_empty());
+    }
+}
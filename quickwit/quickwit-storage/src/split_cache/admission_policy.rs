@@ -0,0 +1,166 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use quickwit_config::SplitCacheAdmissionPolicy;
+use ulid::Ulid;
+
+/// Decides whether a split candidate for download is worth evicting a cached split.
+pub(crate) trait AdmissionPolicy: Send + 'static {
+    /// Records an access to a split, whether it is cached or not.
+    fn record_access(&mut self, split_ulid: Ulid);
+
+    /// Returns true if the candidate split should be downloaded, even though it means evicting
+    /// the victim split.
+    fn admit(&self, candidate_split_ulid: Ulid, victim_split_ulid: Ulid) -> bool;
+}
+
+pub(crate) fn admission_policy(
+    admission_policy: SplitCacheAdmissionPolicy,
+    max_num_splits: usize,
+) -> Box<dyn AdmissionPolicy> {
+    match admission_policy {
+        SplitCacheAdmissionPolicy::Recency => Box::new(RecencyAdmissionPolicy),
+        SplitCacheAdmissionPolicy::TinyLfu => {
+            Box::new(TinyLfuAdmissionPolicy::with_max_num_splits(max_num_splits))
+        }
+    }
+}
+
+/// Always admits the candidate. The candidates being selected by recency, the split cache then
+/// behaves as an LRU cache.
+pub(crate) struct RecencyAdmissionPolicy;
+
+impl AdmissionPolicy for RecencyAdmissionPolicy {
+    fn record_access(&mut self, _split_ulid: Ulid) {}
+
+    fn admit(&self, _candidate_split_ulid: Ulid, _victim_split_ulid: Ulid) -> bool {
+        true
+    }
+}
+
+const NUM_SKETCH_ROWS: usize = 4;
+
+const MAX_COUNT: u8 = 15;
+
+// Odd multipliers used to derive one hash per row of the sketch.
+const ROW_SEEDS: [u64; NUM_SKETCH_ROWS] = [
+    0x9E37_79B9_7F4A_7C15,
+    0xC2B2_AE3D_27D4_EB4F,
+    0x1656_67B1_9E37_79F9,
+    0xD6E8_FEB8_6659_FD93,
+];
+
+/// Admits a candidate only if its estimated access frequency is higher than the one of the
+/// victim, as described in the TinyLFU paper.
+///
+/// Frequencies are estimated with a count-min sketch. The counters are halved periodically, so
+/// that the frequencies reflect the recent accesses.
+pub(crate) struct TinyLfuAdmissionPolicy {
+    counters: Vec<[u8; NUM_SKETCH_ROWS]>,
+    num_accesses: usize,
+    reset_period: usize,
+}
+
+impl TinyLfuAdmissionPolicy {
+    pub fn with_max_num_splits(max_num_splits: usize) -> Self {
+        let width = (max_num_splits.max(16) * 4).next_power_of_two();
+        TinyLfuAdmissionPolicy {
+            counters: vec![[0u8; NUM_SKETCH_ROWS]; width],
+            num_accesses: 0,
+            reset_period: width * 10,
+        }
+    }
+
+    fn slots(&self, split_ulid: Ulid) -> impl Iterator<Item = (usize, usize)> {
+        let key = (split_ulid.0 as u64) ^ ((split_ulid.0 >> 64) as u64);
+        let mask = self.counters.len() - 1;
+        ROW_SEEDS
+            .iter()
+            .enumerate()
+            .map(move |(row, seed)| ((key.wrapping_mul(*seed) >> 32) as usize & mask, row))
+    }
+
+    pub fn frequency(&self, split_ulid: Ulid) -> u8 {
+        self.slots(split_ulid)
+            .map(|(slot, row)| self.counters[slot][row])
+            .min()
+            .unwrap_or(0)
+    }
+
+    fn halve_counters(&mut self) {
+        for row_counters in &mut self.counters {
+            for counter in row_counters.iter_mut() {
+                *counter /= 2;
+            }
+        }
+        self.num_accesses /= 2;
+    }
+}
+
+impl AdmissionPolicy for TinyLfuAdmissionPolicy {
+    fn record_access(&mut self, split_ulid: Ulid) {
+        let slots: Vec<(usize, usize)> = self.slots(split_ulid).collect();
+        for (slot, row) in slots {
+            let counter = &mut self.counters[slot][row];
+            *counter = (*counter + 1).min(MAX_COUNT);
+        }
+        self.num_accesses += 1;
+        if self.num_accesses >= self.reset_period {
+            self.halve_counters();
+        }
+    }
+
+    fn admit(&self, candidate_split_ulid: Ulid, victim_split_ulid: Ulid) -> bool {
+        self.frequency(candidate_split_ulid) > self.frequency(victim_split_ulid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiny_lfu_admission_policy() {
+        let mut admission_policy = TinyLfuAdmissionPolicy::with_max_num_splits(100);
+        let hot_split_ulid = Ulid::new();
+        let cold_split_ulid = Ulid::new();
+        for _ in 0..3 {
+            admission_policy.record_access(hot_split_ulid);
+        }
+        admission_policy.record_access(cold_split_ulid);
+        assert_eq!(admission_policy.frequency(hot_split_ulid), 3);
+        assert!(admission_policy.frequency(cold_split_ulid) >= 1);
+        assert!(admission_policy.admit(hot_split_ulid, cold_split_ulid));
+        assert!(!admission_policy.admit(cold_split_ulid, hot_split_ulid));
+
+        for _ in 0..100 {
+            admission_policy.record_access(hot_split_ulid);
+        }
+        assert_eq!(admission_policy.frequency(hot_split_ulid), MAX_COUNT);
+    }
+
+    #[test]
+    fn test_tiny_lfu_admission_policy_halves_counters() {
+        let mut admission_policy = TinyLfuAdmissionPolicy::with_max_num_splits(16);
+        assert_eq!(admission_policy.reset_period, 640);
+        let split_ulid = Ulid::new();
+        for _ in 0..8 {
+            admission_policy.record_access(split_ulid);
+        }
+        assert_eq!(admission_policy.frequency(split_ulid), 8);
+        admission_policy.halve_counters();
+        assert_eq!(admission_policy.frequency(split_ulid), 4);
+        assert_eq!(admission_policy.num_accesses, 4);
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io;
 use std::num::NonZeroU32;
 use std::path::Path;
 use std::sync::Arc;
@@ -23,9 +24,19 @@ use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use crate::split_cache::split_table::{CandidateSplit, DownloadOpportunity};
 use crate::{SplitCache, StorageResolver};
 
+/// Returns true if the download failed because the disk of the split cache is full.
+fn is_disk_full_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<io::Error>()
+            .is_some_and(|io_error| io_error.kind() == io::ErrorKind::StorageFull)
+    })
+}
+
 async fn download_split(
     root_path: &Path,
     candidate_split: &CandidateSplit,
+    storage_credentials_opt: Option<&str>,
     storage_resolver: StorageResolver,
 ) -> anyhow::Result<u64> {
     let CandidateSplit {
@@ -35,7 +46,9 @@ async fn download_split(
     } = candidate_split;
     let split_filename = split_file(*split_ulid);
     let target_filepath = root_path.join(&split_filename);
-    let storage = storage_resolver.resolve(storage_uri).await?;
+    let storage = storage_resolver
+        .resolve_with_credentials(storage_uri, storage_credentials_opt)
+        .await?;
     let num_bytes = storage
         .copy_to_file(Path::new(&split_filename), &target_filepath)
         .await?;
@@ -59,8 +72,34 @@ async fn perform_eviction_and_download(
         split_cache_clone.evict(&splits_to_delete[..]);
     })
     .await;
-    let num_bytes =
-        download_split(&split_cache.root_path, &split_to_download, storage_resolver).await?;
+    let split_cache_metrics = &crate::metrics::STORAGE_METRICS.split_cache;
+    split_cache_metrics.downloads_started_total.inc();
+
+    let storage_credentials_opt = split_cache.storage_credentials(&split_to_download.storage_uri);
+    let num_bytes = match download_split(
+        &split_cache.root_path,
+        &split_to_download,
+        storage_credentials_opt.as_deref(),
+        storage_resolver,
+    )
+    .await
+    {
+        Ok(num_bytes) => num_bytes,
+        Err(error) => {
+            split_cache_metrics.downloads_failed_total.inc();
+
+            if is_disk_full_error(&error) {
+                let _ = tokio::task::spawn_blocking(move || {
+                    split_cache.shrink_on_disk_full();
+                })
+                .await;
+            }
+            return Err(error);
+        }
+    };
+    split_cache_metrics.downloads_completed_total.inc();
+    split_cache_metrics.downloaded_num_bytes.inc_by(num_bytes);
+
     let mut shared_split_table_lock = split_cache.split_table.lock().unwrap();
     shared_split_table_lock.register_as_downloaded(split_ulid, num_bytes);
     Ok(())
@@ -95,3 +134,19 @@ pub(crate) fn spawn_download_task(
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_disk_full_error() {
+        let disk_full_error = anyhow::Error::from(io::Error::from(io::ErrorKind::StorageFull))
+            .context("failed to download split");
+        assert!(is_disk_full_error(&disk_full_error));
+
+        let not_found_error = anyhow::Error::from(io::Error::from(io::ErrorKind::NotFound))
+            .context("failed to download split");
+        assert!(!is_disk_full_error(&not_found_error));
+    }
+}
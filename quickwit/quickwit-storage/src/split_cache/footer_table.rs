@@ -0,0 +1,139 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use lru::LruCache;
+use ulid::Ulid;
+
+/// In memory structure listing the split footers cached on disk, in least recently used order.
+pub(crate) struct FooterTable {
+    max_num_bytes: u64,
+    num_bytes: u64,
+    footers: LruCache<Ulid, u64>,
+}
+
+impl FooterTable {
+    pub fn with_max_num_bytes(max_num_bytes: u64) -> FooterTable {
+        FooterTable {
+            max_num_bytes,
+            num_bytes: 0,
+            footers: LruCache::unbounded(),
+        }
+    }
+
+    pub fn num_bytes(&self) -> u64 {
+        self.num_bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.footers.len()
+    }
+
+    /// Returns the size of the footer of the split if it is cached, and marks it as the most
+    /// recently used.
+    pub fn touch(&mut self, split_ulid: Ulid) -> Option<u64> {
+        self.footers.get(&split_ulid).copied()
+    }
+
+    /// Returns whether the footer of a split is worth writing on disk, i.e. it is not cached yet
+    /// and fits in the tier.
+    pub fn accepts(&self, split_ulid: Ulid, num_bytes: u64) -> bool {
+        num_bytes <= self.max_num_bytes && !self.footers.contains(&split_ulid)
+    }
+
+    /// Registers the footer of a split and returns the footers that must be evicted to make
+    /// room for it.
+    ///
+    /// Returns `None` if the footer is already cached or larger than the tier itself, in which
+    /// case it should not be written on disk.
+    pub fn insert(&mut self, split_ulid: Ulid, num_bytes: u64) -> Option<Vec<Ulid>> {
+        if !self.accepts(split_ulid, num_bytes) {
+            return None;
+        }
+        let mut evicted_footers = Vec::new();
+
+        while self.num_bytes + num_bytes > self.max_num_bytes {
+            let Some((evicted_split_ulid, evicted_num_bytes)) = self.footers.pop_lru() else {
+                break;
+            };
+            self.num_bytes -= evicted_num_bytes;
+            evicted_footers.push(evicted_split_ulid);
+
+            let footer_metrics = &crate::STORAGE_METRICS.searcher_split_footer_cache;
+            footer_metrics.evict_num_items.inc();
+            footer_metrics.evict_num_bytes.inc_by(evicted_num_bytes);
+        }
+        self.footers.put(split_ulid, num_bytes);
+        self.num_bytes += num_bytes;
+        self.update_metrics();
+        Some(evicted_footers)
+    }
+
+    /// Forgets the footer of a split, for instance if its file could not be read.
+    pub fn remove(&mut self, split_ulid: Ulid) {
+        if let Some(num_bytes) = self.footers.pop(&split_ulid) {
+            self.num_bytes -= num_bytes;
+            self.update_metrics();
+        }
+    }
+
+    fn update_metrics(&self) {
+        let footer_metrics = &crate::STORAGE_METRICS.searcher_split_footer_cache;
+        footer_metrics.in_cache_count.set(self.footers.len() as i64);
+        footer_metrics.in_cache_num_bytes.set(self.num_bytes as i64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_footer_table_insert_evicts_least_recently_used() {
+        let mut footer_table = FooterTable::with_max_num_bytes(1_000);
+        let split_ulid_0 = Ulid::new();
+        let split_ulid_1 = Ulid::new();
+        let split_ulid_2 = Ulid::new();
+
+        assert_eq!(footer_table.insert(split_ulid_0, 400), Some(Vec::new()));
+        assert_eq!(footer_table.insert(split_ulid_1, 400), Some(Vec::new()));
+        assert_eq!(footer_table.num_bytes(), 800);
+
+        assert_eq!(footer_table.touch(split_ulid_0), Some(400));
+
+        let evicted_footers = footer_table.insert(split_ulid_2, 400).unwrap();
+        assert_eq!(evicted_footers, vec![split_ulid_1]);
+        assert_eq!(footer_table.len(), 2);
+        assert_eq!(footer_table.num_bytes(), 800);
+        assert!(footer_table.touch(split_ulid_1).is_none());
+    }
+
+    #[test]
+    fn test_footer_table_insert_rejects_footers() {
+        let mut footer_table = FooterTable::with_max_num_bytes(1_000);
+        let split_ulid = Ulid::new();
+
+        assert!(!footer_table.accepts(split_ulid, 1_001));
+        assert!(footer_table.insert(split_ulid, 1_001).is_none());
+        assert_eq!(footer_table.len(), 0);
+
+        assert_eq!(footer_table.insert(split_ulid, 100), Some(Vec::new()));
+        assert!(!footer_table.accepts(split_ulid, 100));
+        assert!(footer_table.insert(split_ulid, 100).is_none());
+        assert_eq!(footer_table.num_bytes(), 100);
+
+        footer_table.remove(split_ulid);
+        assert_eq!(footer_table.len(), 0);
+        assert_eq!(footer_table.num_bytes(), 0);
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright 2021-Present Datadog, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Name of the file of the split cache directory persisting the access metadata of the cached
+/// splits, so that the cache warmth survives restarts.
+pub(crate) const MANIFEST_FILE_NAME: &str = "split_cache_manifest.json";
+
+/// Thanks to its `.temp` extension, a leftover temporary manifest is removed when the split cache
+/// starts.
+const MANIFEST_TEMP_FILE_NAME: &str = "split_cache_manifest.json.temp";
+
+#[derive(Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SplitCacheManifest {
+    pub splits: Vec<SplitAccessRecord>,
+    /// Splits pinned in the split cache, whether they are downloaded yet or not.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pinned_splits: Vec<PinnedSplitRecord>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PinnedSplitRecord {
+    pub split_id: String,
+    pub storage_uri: String,
+    /// ID of the index of the split, used to enforce the index quotas.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SplitAccessRecord {
+    pub split_id: String,
+    pub num_bytes: u64,
+    /// Unix timestamp of the last access, in milliseconds.
+    pub last_accessed_millis: u64,
+    /// ID of the index of the split, used to enforce the index quotas.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_id: Option<String>,
+}
+
+impl SplitAccessRecord {
+    pub fn last_accessed(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.last_accessed_millis)
+    }
+}
+
+pub(crate) fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+impl SplitCacheManifest {
+    /// Loads the manifest stored in the split cache directory.
+    ///
+    /// A missing or corrupted manifest is not an error: the cached splits are then considered as
+    /// not accessed for a long time.
+    pub fn load(root_path: &Path) -> SplitCacheManifest {
+        let manifest_path = root_path.join(MANIFEST_FILE_NAME);
+        let manifest_json = match std::fs::read(&manifest_path) {
+            Ok(manifest_json) => manifest_json,
+            Err(io_error) => {
+                if io_error.kind() != io::ErrorKind::NotFound {
+                    warn!(path=%manifest_path.display(), error=%io_error, "failed to read split cache manifest");
+                }
+                return SplitCacheManifest::default();
+            }
+        };
+        match serde_json::from_slice(&manifest_json) {
+            Ok(manifest) => manifest,
+            Err(error) => {
+                warn!(path=%manifest_path.display(), error=%error, "failed to parse split cache manifest");
+                SplitCacheManifest::default()
+            }
+        }
+    }
+
+    /// Atomically writes the manifest to the split cache directory.
+    pub fn save(&self, root_path: &Path) -> io::Result<()> {
+        let manifest_json = serde_json::to_vec(self)?;
+        let temp_path = root_path.join(MANIFEST_TEMP_FILE_NAME);
+        std::fs::write(&temp_path, manifest_json)?;
+        std::fs::rename(&temp_path, root_path.join(MANIFEST_FILE_NAME))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_cache_manifest_save_and_load() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            SplitCacheManifest::load(temp_dir.path()),
+            SplitCacheManifest::default()
+        );
+        let manifest = SplitCacheManifest {
+            splits: vec![SplitAccessRecord {
+                split_id: "01GF521CZC1SQEMVZMW7XFBQSM".to_string(),
+                num_bytes: 1_000,
+                last_accessed_millis: 1_700_000_000_000,
+                index_id: Some("test-index".to_string()),
+            }],
+            pinned_splits: vec![PinnedSplitRecord {
+                split_id: "01GF521CZC1SQEMVZMW7XFBQSM".to_string(),
+                storage_uri: "s3://test/test-index".to_string(),
+                index_id: Some("test-index".to_string()),
+            }],
+        };
+        manifest.save(temp_dir.path()).unwrap();
+        assert_eq!(SplitCacheManifest::load(temp_dir.path()), manifest);
+
+        std::fs::write(temp_dir.path().join(MANIFEST_FILE_NAME), b"{").unwrap();
+        assert_eq!(
+            SplitCacheManifest::load(temp_dir.path()),
+            SplitCacheManifest::default()
+        );
+    }
+}
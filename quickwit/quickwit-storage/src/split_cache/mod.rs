@@ -12,31 +12,64 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod access_recorder;
+mod admission_policy;
 mod download_task;
+mod footer_table;
+mod manifest;
 mod split_table;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ffi::OsStr;
 use std::io;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use async_trait::async_trait;
+use bytesize::ByteSize;
+use quickwit_common::fs::get_available_disk_space;
 use quickwit_common::split_file;
 use quickwit_common::uri::Uri;
 use quickwit_config::SplitCacheLimits;
-use quickwit_proto::search::ReportSplit;
+use quickwit_proto::search::{PinSplitsRequest, ReportSplit};
 use tantivy::directory::OwnedBytes;
 use tracing::{error, info, instrument, warn};
 use ulid::Ulid;
 
 use crate::file_descriptor_cache::{FileDescriptorCache, SplitFile};
+use crate::split_cache::access_recorder::SplitAccessRecorder;
 use crate::split_cache::download_task::spawn_download_task;
+use crate::split_cache::footer_table::FooterTable;
+use crate::split_cache::manifest::{SplitCacheManifest, MANIFEST_FILE_NAME};
 use crate::split_cache::split_table::SplitTable;
 use crate::{wrap_storage_with_cache, Storage, StorageCache};
 
+/// Interval at which the splits not accessed for more than `max_age` are evicted.
+const EXPIRED_SPLITS_EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Interval at which the access metadata of the cached splits is persisted.
+const MANIFEST_PERSISTENCE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Interval at which the space available on the disk of the split cache is checked.
+const DISK_PRESSURE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Space the split cache leaves available on its disk. Below this threshold, the cache shrinks
+/// until enough space is available again.
+const MIN_AVAILABLE_DISK_SPACE: ByteSize = ByteSize::gb(1);
+
+/// Fraction of the cache evicted when a download fails because the disk is full.
+const DISK_FULL_SHRINK_RATIO: f64 = 0.1;
+
+/// Name of the directory, within the root directory, containing the cached split footers.
+const FOOTERS_DIR_NAME: &str = "footers";
+
+/// Maximum number of footers being written into the footer tier at the same time. The footers
+/// fetched while this many writes are in flight are not cached.
+const MAX_CONCURRENT_FOOTER_WRITES: usize = 8;
+
 /// On disk Cache of splits for searchers.
 ///
 /// The search acts receives reports of splits.
@@ -47,7 +80,19 @@ pub struct SplitCache {
     // In memory structure, listing the splits we know about regardless
     // of whether they are in cache, being downloaded, or just available for download.
     split_table: Mutex<SplitTable>,
+    // Serves the accesses to the splits on disk without locking the split table, so that
+    // concurrent searches do not contend on it.
+    access_recorder: Arc<SplitAccessRecorder>,
+    // Second tier, only caching the footer (and hotcache) of the splits. Enabled if
+    // `max_footer_num_bytes` is set.
+    footer_table_opt: Option<Mutex<FooterTable>>,
+    // Splits whose footer is being written into the footer tier.
+    footer_writes_in_flight: Arc<Mutex<HashSet<Ulid>>>,
     fd_cache: FileDescriptorCache,
+    // Names of the storage credentials used to download the splits of a storage, as reported
+    // along with the splits. Storages absent from the map are accessed with the default storage
+    // configs.
+    storage_credentials: Mutex<HashMap<Uri, String>>,
 }
 
 impl SplitCache {
@@ -64,7 +109,7 @@ impl SplitCache {
             let dir_entry = dir_entry_res?;
             let path = dir_entry.path();
             let meta = std::fs::metadata(&path)?;
-            if meta.is_dir() {
+            if meta.is_dir() || path.file_name() == Some(OsStr::new(MANIFEST_FILE_NAME)) {
                 continue;
             }
             let ext = path.extension().and_then(OsStr::to_str).unwrap_or("");
@@ -91,10 +136,14 @@ impl SplitCache {
                 }
             }
         }
-        let mut split_table = SplitTable::with_limits_and_existing_splits(limits, existing_splits);
+        let mut split_table =
+            SplitTable::with_limits_and_existing_splits(limits.clone(), existing_splits);
+        // Restores the last access dates, so that the hottest splits are not the first evicted
+        // after a restart.
+        split_table.restore_last_accesses(&SplitCacheManifest::load(&root_path));
 
         // In case of a setting change, it could be useful to evict some splits on startup.
-        let splits_to_remove_res = split_table.make_room_for_split_if_necessary(u64::MAX);
+        let splits_to_remove_res = split_table.make_room_for_split_if_necessary(u64::MAX, None);
         if let Ok(splits_to_remove) = splits_to_remove_res {
             info!(
                 num_splits = splits_to_remove.len(),
@@ -102,11 +151,28 @@ impl SplitCache {
             );
             delete_evicted_splits(&root_path, &splits_to_remove[..]);
         }
-        let fd_cache = FileDescriptorCache::with_fd_cache_capacity(limits.max_file_descriptors);
+        let footer_table_opt = limits
+            .max_footer_num_bytes
+            .map(|max_footer_num_bytes| {
+                load_footer_table(
+                    &root_path.join(FOOTERS_DIR_NAME),
+                    max_footer_num_bytes.as_u64(),
+                )
+            })
+            .transpose()?
+            .map(Mutex::new);
+        let fd_cache =
+            FileDescriptorCache::with_limits(limits.max_file_descriptors, limits.max_open_fds)
+                .with_mmap(limits.use_mmap);
+        let access_recorder = split_table.access_recorder();
         let split_cache = Arc::new(SplitCache {
             root_path,
             split_table: Mutex::new(split_table),
+            access_recorder,
+            footer_table_opt,
+            footer_writes_in_flight: Default::default(),
             fd_cache,
+            storage_credentials: Mutex::default(),
         });
 
         spawn_download_task(
@@ -114,6 +180,11 @@ impl SplitCache {
             storage_resolver,
             limits.num_concurrent_downloads,
         );
+        if limits.max_age.is_some() {
+            spawn_expired_splits_eviction_task(split_cache.clone());
+        }
+        spawn_manifest_persistence_task(split_cache.clone());
+        spawn_disk_pressure_task(split_cache.clone());
 
         Ok(split_cache)
     }
@@ -125,18 +196,24 @@ impl SplitCache {
         delete_evicted_splits(&self.root_path, splits_to_evict);
     }
 
-    /// Wraps a storage with our split cache.
-    pub fn wrap_storage(self_arc: Arc<Self>, storage: Arc<dyn Storage>) -> Arc<dyn Storage> {
+    /// Wraps a storage with our split cache. The ID of the index stored in the storage, if known,
+    /// is used to enforce the index quotas.
+    pub fn wrap_storage(
+        self_arc: Arc<Self>,
+        storage: Arc<dyn Storage>,
+        index_id_opt: Option<String>,
+    ) -> Arc<dyn Storage> {
         let cache = Arc::new(SplitCacheBackingStorage {
             split_cache: self_arc,
             storage_root_uri: storage.uri().clone(),
+            index_id_opt,
         });
         wrap_storage_with_cache(cache, storage)
     }
 
     /// Report the split cache about the existence of new splits.
     pub fn report_splits(&self, report_splits: Vec<ReportSplit>) {
-        let mut split_table = self.split_table.lock().unwrap();
+        let mut splits: Vec<(Ulid, Uri, Option<String>)> = Vec::with_capacity(report_splits.len());
         for report_split in report_splits {
             let Ok(split_ulid) = Ulid::from_str(&report_split.split_id) else {
                 error!(split_id=%report_split.split_id, "received invalid split ulid: ignoring");
@@ -146,20 +223,253 @@ impl SplitCache {
                 error!(storage_uri=%report_split.storage_uri, "received invalid storage uri: ignoring");
                 continue;
             };
-            split_table.report(split_ulid, storage_uri);
+            self.record_storage_credentials(&storage_uri, report_split.storage_credentials);
+            splits.push((split_ulid, storage_uri, report_split.index_id));
         }
+        self.split_table.lock().unwrap().report_many(splits);
+    }
+
+    fn record_storage_credentials(
+        &self,
+        storage_uri: &Uri,
+        storage_credentials_opt: Option<String>,
+    ) {
+        let mut storage_credentials = self.storage_credentials.lock().unwrap();
+
+        if let Some(storage_credentials_name) = storage_credentials_opt {
+            storage_credentials.insert(storage_uri.clone(), storage_credentials_name);
+        } else {
+            storage_credentials.remove(storage_uri);
+        }
+    }
+
+    /// Returns the name of the storage credentials reported for a storage.
+    fn storage_credentials(&self, storage_uri: &Uri) -> Option<String> {
+        self.storage_credentials
+            .lock()
+            .unwrap()
+            .get(storage_uri)
+            .cloned()
+    }
+
+    /// Pins or unpins splits. Pinned splits are downloaded first and are never evicted.
+    pub fn pin_splits(&self, pin_splits_request: PinSplitsRequest) {
+        let mut splits: Vec<(Ulid, Uri, Option<String>)> =
+            Vec::with_capacity(pin_splits_request.splits.len());
+        for split in pin_splits_request.splits {
+            let Ok(split_ulid) = Ulid::from_str(&split.split_id) else {
+                error!(split_id=%split.split_id, "received invalid split ulid: ignoring");
+                continue;
+            };
+            let Ok(storage_uri) = Uri::from_str(&split.storage_uri) else {
+                error!(storage_uri=%split.storage_uri, "received invalid storage uri: ignoring");
+                continue;
+            };
+            self.record_storage_credentials(&storage_uri, split.storage_credentials);
+            splits.push((split_ulid, storage_uri, split.index_id));
+        }
+        let mut split_table = self.split_table.lock().unwrap();
+        for unpin_storage_uri in &pin_splits_request.unpin_storage_uris {
+            let Ok(storage_uri) = Uri::from_str(unpin_storage_uri) else {
+                error!(storage_uri=%unpin_storage_uri, "received invalid storage uri: ignoring");
+                continue;
+            };
+            split_table.unpin_storage_uri(&storage_uri);
+        }
+        for (split_ulid, storage_uri, index_id_opt) in splits {
+            if pin_splits_request.unpin {
+                split_table.unpin(split_ulid);
+            } else {
+                split_table.pin(split_ulid, storage_uri, index_id_opt);
+            }
+        }
+    }
+
+    /// Returns the footer of a split, hotcache included, if it is cached in the footer tier.
+    pub async fn get_split_footer(
+        &self,
+        split_id: &str,
+        footer_range: Range<usize>,
+    ) -> Option<OwnedBytes> {
+        let footer_table = self.footer_table_opt.as_ref()?;
+        let split_ulid = Ulid::from_str(split_id).ok()?;
+        let footer_metrics = &crate::STORAGE_METRICS.searcher_split_footer_cache;
+
+        let Some(num_bytes) = footer_table.lock().unwrap().touch(split_ulid) else {
+            footer_metrics.misses_num_items.inc();
+            return None;
+        };
+        if num_bytes != footer_range.len() as u64 {
+            footer_metrics.misses_num_items.inc();
+            return None;
+        }
+        let footer_path = self.footer_path(split_ulid);
+
+        match tokio::fs::read(&footer_path).await {
+            Ok(footer_data) if footer_data.len() == footer_range.len() => {
+                footer_metrics.hits_num_items.inc();
+                footer_metrics.hits_num_bytes.inc_by(num_bytes);
+                Some(OwnedBytes::new(footer_data))
+            }
+            _ => {
+                warn!(path=%footer_path.display(), "failed to read split footer from cache");
+                footer_table.lock().unwrap().remove(split_ulid);
+                footer_metrics.misses_num_items.inc();
+                None
+            }
+        }
+    }
+
+    /// Stores the footer of a split in the footer tier in a background task. See
+    /// [`SplitCache::put_split_footer`].
+    pub fn spawn_put_split_footer(self: &Arc<Self>, split_id: &str, footer_data: OwnedBytes) {
+        let Some(footer_write_guard) = self.start_footer_write(split_id, footer_data.len()) else {
+            return;
+        };
+        let split_cache = self.clone();
+        tokio::spawn(async move {
+            split_cache
+                .write_split_footer(footer_write_guard, footer_data)
+                .await;
+        });
+    }
+
+    /// Stores the footer of a split, hotcache included, in the footer tier, evicting the least
+    /// recently used footers if necessary. This is a no-op if the footer tier is disabled, if the
+    /// footer of the split is already being written, or if too many footers are being written.
+    pub async fn put_split_footer(&self, split_id: &str, footer_data: OwnedBytes) {
+        let Some(footer_write_guard) = self.start_footer_write(split_id, footer_data.len()) else {
+            return;
+        };
+        self.write_split_footer(footer_write_guard, footer_data)
+            .await;
+    }
+
+    fn start_footer_write(&self, split_id: &str, num_bytes: usize) -> Option<FooterWriteGuard> {
+        let footer_table = self.footer_table_opt.as_ref()?;
+        let split_ulid = Ulid::from_str(split_id).ok()?;
+
+        if !footer_table
+            .lock()
+            .unwrap()
+            .accepts(split_ulid, num_bytes as u64)
+        {
+            return None;
+        }
+        let mut footer_writes_in_flight = self.footer_writes_in_flight.lock().unwrap();
+
+        if footer_writes_in_flight.len() >= MAX_CONCURRENT_FOOTER_WRITES
+            || !footer_writes_in_flight.insert(split_ulid)
+        {
+            return None;
+        }
+        Some(FooterWriteGuard {
+            split_ulid,
+            footer_writes_in_flight: self.footer_writes_in_flight.clone(),
+        })
+    }
+
+    async fn write_split_footer(
+        &self,
+        footer_write_guard: FooterWriteGuard,
+        footer_data: OwnedBytes,
+    ) {
+        let Some(footer_table) = self.footer_table_opt.as_ref() else {
+            return;
+        };
+        let split_ulid = footer_write_guard.split_ulid;
+        let num_bytes = footer_data.len() as u64;
+        let footer_path = self.footer_path(split_ulid);
+
+        // The footer is registered in the table only once written, so that the footers listed in
+        // the table can always be read. We write into a temporary file first so that concurrent
+        // reads never observe a partially written footer.
+        let write_res = tokio::task::spawn_blocking(move || {
+            let temp_footer_path = footer_path.with_extension("temp");
+            std::fs::write(&temp_footer_path, footer_data.as_slice())?;
+            std::fs::rename(&temp_footer_path, &footer_path)
+        })
+        .await;
+
+        if !matches!(write_res, Ok(Ok(()))) {
+            error!(split_id=%split_ulid, "failed to write split footer into cache");
+            return;
+        }
+        let evicted_footers_opt = footer_table.lock().unwrap().insert(split_ulid, num_bytes);
+        drop(footer_write_guard);
+
+        let Some(evicted_footers) = evicted_footers_opt else {
+            return;
+        };
+        if evicted_footers.is_empty() {
+            return;
+        }
+        let footers_path = self.root_path.join(FOOTERS_DIR_NAME);
+        let _ = tokio::task::spawn_blocking(move || {
+            delete_evicted_footers(&footers_path, &evicted_footers);
+        })
+        .await;
+    }
+
+    fn footer_path(&self, split_ulid: Ulid) -> PathBuf {
+        self.root_path
+            .join(FOOTERS_DIR_NAME)
+            .join(format!("{split_ulid}.footer"))
+    }
+
+    /// Caps the size of the cache so that `MIN_AVAILABLE_DISK_SPACE` remains available on its
+    /// disk, and evicts the splits exceeding the cap. The cap is lifted once the disk has
+    /// enough space for the whole cache.
+    fn update_disk_pressure(&self, available_disk_space: ByteSize) {
+        let splits_to_evict = {
+            let mut split_table = self.split_table.lock().unwrap();
+            let max_num_bytes = (split_table.num_bytes() + available_disk_space.as_u64())
+                .saturating_sub(MIN_AVAILABLE_DISK_SPACE.as_u64());
+            split_table.set_disk_pressure_max_num_bytes(Some(max_num_bytes))
+        };
+        if !splits_to_evict.is_empty() {
+            warn!(
+                num_splits = splits_to_evict.len(),
+                available_disk_space = %available_disk_space,
+                "evicting splits from the searcher cache: disk is running out of space"
+            );
+            self.evict(&splits_to_evict);
+        }
+    }
+
+    /// Shrinks the cache by `DISK_FULL_SHRINK_RATIO` after a download failed because the disk is
+    /// full.
+    pub(crate) fn shrink_on_disk_full(&self) {
+        let splits_to_evict = {
+            let mut split_table = self.split_table.lock().unwrap();
+            let num_bytes = split_table.num_bytes();
+            let max_num_bytes = num_bytes - (num_bytes as f64 * DISK_FULL_SHRINK_RATIO) as u64;
+            split_table.set_disk_pressure_max_num_bytes(Some(max_num_bytes))
+        };
+        warn!(
+            num_splits = splits_to_evict.len(),
+            "evicting splits from the searcher cache: disk is full"
+        );
+        self.evict(&splits_to_evict);
     }
 
     // Returns a split guard object. As long as it is not dropped, the
     // split won't be evinced from the cache.
-    async fn get_split_file(&self, split_id: Ulid, storage_uri: &Uri) -> Option<SplitFile> {
+    async fn get_split_file(
+        &self,
+        split_id: Ulid,
+        storage_uri: &Uri,
+        index_id_opt: Option<&str>,
+    ) -> Option<SplitFile> {
         // We touch before even checking the fd cache in order to update the file's last access time
-        // for the file cache.
-        let num_bytes_opt: Option<u64> = self
-            .split_table
-            .lock()
-            .unwrap()
-            .touch(split_id, storage_uri);
+        // for the file cache. The split table is only locked if the split is not on disk.
+        let num_bytes_opt: Option<u64> =
+            self.access_recorder.touch_on_disk(split_id).or_else(|| {
+                self.split_table
+                    .lock()
+                    .unwrap()
+                    .touch(split_id, storage_uri, index_id_opt)
+            });
 
         let num_bytes = num_bytes_opt?;
         self.fd_cache
@@ -169,6 +479,71 @@ impl SplitCache {
     }
 }
 
+/// Periodically evicts the splits that have not been accessed for more than `max_age`,
+/// regardless of the cache size.
+fn spawn_expired_splits_eviction_task(split_cache: Arc<SplitCache>) {
+    tokio::task::spawn(async move {
+        let mut interval = tokio::time::interval(EXPIRED_SPLITS_EVICTION_INTERVAL);
+        loop {
+            interval.tick().await;
+            let expired_splits = split_cache
+                .split_table
+                .lock()
+                .unwrap()
+                .evict_expired_splits();
+            if expired_splits.is_empty() {
+                continue;
+            }
+            info!(
+                num_splits = expired_splits.len(),
+                "evicting expired splits from the searcher cache"
+            );
+            let split_cache_clone = split_cache.clone();
+            let _ = tokio::task::spawn_blocking(move || {
+                split_cache_clone.evict(&expired_splits[..]);
+            })
+            .await;
+        }
+    });
+}
+
+/// Periodically persists the access metadata of the cached splits, so that it can be restored
+/// after a restart.
+fn spawn_manifest_persistence_task(split_cache: Arc<SplitCache>) {
+    tokio::task::spawn(async move {
+        let mut interval = tokio::time::interval(MANIFEST_PERSISTENCE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let manifest = split_cache.split_table.lock().unwrap().manifest();
+            let root_path = split_cache.root_path.clone();
+            let save_res = tokio::task::spawn_blocking(move || manifest.save(&root_path)).await;
+            if let Ok(Err(io_error)) = save_res {
+                warn!(error=%io_error, "failed to persist split cache manifest");
+            }
+        }
+    });
+}
+
+/// Periodically checks the space available on the disk of the split cache, shrinking the cache
+/// if the disk is running out of space.
+fn spawn_disk_pressure_task(split_cache: Arc<SplitCache>) {
+    tokio::task::spawn(async move {
+        let mut interval = tokio::time::interval(DISK_PRESSURE_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let split_cache_clone = split_cache.clone();
+            let _ = tokio::task::spawn_blocking(move || {
+                if let Some(available_disk_space) =
+                    get_available_disk_space(&split_cache_clone.root_path)
+                {
+                    split_cache_clone.update_disk_pressure(available_disk_space);
+                }
+            })
+            .await;
+        }
+    });
+}
+
 /// Removes the evicted split files from the file system.
 /// This function just logs errors, and swallows them.
 ///
@@ -186,6 +561,73 @@ fn delete_evicted_splits(root_path: &Path, splits_to_delete: &[Ulid]) {
     }
 }
 
+/// Lists the split footers cached on disk and removes the temporary files, as well as the
+/// footers exceeding the size of the footer tier.
+fn load_footer_table(footers_path: &Path, max_footer_num_bytes: u64) -> io::Result<FooterTable> {
+    std::fs::create_dir_all(footers_path)?;
+    let mut existing_footers: BTreeMap<Ulid, u64> = Default::default();
+    for dir_entry_res in std::fs::read_dir(footers_path)? {
+        let dir_entry = dir_entry_res?;
+        let path = dir_entry.path();
+        let meta = std::fs::metadata(&path)?;
+        if meta.is_dir() {
+            continue;
+        }
+        let split_ulid_opt = path
+            .file_name()
+            .and_then(OsStr::to_str)
+            .and_then(|file_name| file_name.strip_suffix(".footer"))
+            .and_then(|split_id| Ulid::from_str(split_id).ok());
+        if let Some(split_ulid) = split_ulid_opt {
+            existing_footers.insert(split_ulid, meta.len());
+        } else if let Err(io_err) = std::fs::remove_file(&path) {
+            if io_err.kind() != io::ErrorKind::NotFound {
+                error!(path=?path, "failed to remove file from footer cache directory");
+            }
+        }
+    }
+    let mut footer_table = FooterTable::with_max_num_bytes(max_footer_num_bytes);
+    let mut rejected_footers = Vec::new();
+
+    // Footers are inserted in the split creation order, so that the oldest splits are the
+    // first evicted.
+    for (split_ulid, num_bytes) in existing_footers {
+        match footer_table.insert(split_ulid, num_bytes) {
+            Some(evicted_footers) => rejected_footers.extend(evicted_footers),
+            None => rejected_footers.push(split_ulid),
+        }
+    }
+    delete_evicted_footers(footers_path, &rejected_footers);
+    Ok(footer_table)
+}
+
+/// Marks the footer of a split as being written into the footer tier until dropped.
+struct FooterWriteGuard {
+    split_ulid: Ulid,
+    footer_writes_in_flight: Arc<Mutex<HashSet<Ulid>>>,
+}
+
+impl Drop for FooterWriteGuard {
+    fn drop(&mut self) {
+        self.footer_writes_in_flight
+            .lock()
+            .unwrap()
+            .remove(&self.split_ulid);
+    }
+}
+
+/// Removes the evicted split footer files from the file system, logging errors.
+fn delete_evicted_footers(footers_path: &Path, footers_to_delete: &[Ulid]) {
+    for split_ulid in footers_to_delete {
+        let footer_path = footers_path.join(format!("{split_ulid}.footer"));
+        if let Err(io_err) = std::fs::remove_file(&footer_path) {
+            if io_err.kind() != io::ErrorKind::NotFound {
+                error!(path=%footer_path.display(), "failed to remove split footer from cache directory");
+            }
+        }
+    }
+}
+
 fn split_id_from_path(split_path: &Path) -> Option<Ulid> {
     let split_filename = split_path.file_name()?.to_str()?;
     let split_id_str = split_filename.strip_suffix(".split")?;
@@ -195,6 +637,7 @@ fn split_id_from_path(split_path: &Path) -> Option<Ulid> {
 struct SplitCacheBackingStorage {
     split_cache: Arc<SplitCache>,
     storage_root_uri: Uri,
+    index_id_opt: Option<String>,
 }
 
 impl SplitCacheBackingStorage {
@@ -202,7 +645,11 @@ impl SplitCacheBackingStorage {
         let split_id = split_id_from_path(path)?;
         let split_file: SplitFile = self
             .split_cache
-            .get_split_file(split_id, &self.storage_root_uri)
+            .get_split_file(
+                split_id,
+                &self.storage_root_uri,
+                self.index_id_opt.as_deref(),
+            )
             .await?;
         split_file.get_range(byte_range).await.ok()
     }
@@ -211,7 +658,11 @@ impl SplitCacheBackingStorage {
         let split_id = split_id_from_path(path)?;
         let split_file = self
             .split_cache
-            .get_split_file(split_id, &self.storage_root_uri)
+            .get_split_file(
+                split_id,
+                &self.storage_root_uri,
+                self.index_id_opt.as_deref(),
+            )
             .await?;
         split_file.get_all().await.ok()
     }
@@ -244,3 +695,161 @@ impl StorageCache for SplitCacheBackingStorage {
     async fn put(&self, _path: PathBuf, _byte_range: Range<usize>, _bytes: OwnedBytes) {}
     async fn put_all(&self, _path: PathBuf, _bytes: OwnedBytes) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use bytesize::ByteSize;
+    use quickwit_config::SplitCacheAdmissionPolicy;
+
+    use super::*;
+    use crate::StorageResolver;
+
+    fn split_cache_limits_for_test(max_footer_num_bytes: ByteSize) -> SplitCacheLimits {
+        SplitCacheLimits {
+            max_num_bytes: ByteSize::mb(10),
+            max_num_splits: NonZeroU32::new(10).unwrap(),
+            num_concurrent_downloads: NonZeroU32::new(1).unwrap(),
+            max_file_descriptors: NonZeroU32::new(100).unwrap(),
+            max_open_fds: None,
+            min_residency_secs: None,
+            max_age: None,
+            admission_policy: SplitCacheAdmissionPolicy::Recency,
+            prefetch_adjacent_splits: false,
+            use_mmap: false,
+            max_footer_num_bytes: Some(max_footer_num_bytes),
+            index_quotas: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_split_cache_footer_tier() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root_path = temp_dir.path().to_path_buf();
+        let limits = split_cache_limits_for_test(ByteSize::b(10));
+
+        let split_cache = SplitCache::with_root_path(
+            root_path.clone(),
+            StorageResolver::unconfigured(),
+            limits.clone(),
+        )
+        .unwrap();
+        let split_id_0 = Ulid::new().to_string();
+        let split_id_1 = Ulid::new().to_string();
+
+        assert!(split_cache
+            .get_split_footer(&split_id_0, 0..6)
+            .await
+            .is_none());
+
+        split_cache
+            .put_split_footer(&split_id_0, OwnedBytes::new(b"footer".to_vec()))
+            .await;
+        let footer_data = split_cache
+            .get_split_footer(&split_id_0, 0..6)
+            .await
+            .unwrap();
+        assert_eq!(footer_data.as_slice(), b"footer");
+
+        // The footer range does not match.
+        assert!(split_cache
+            .get_split_footer(&split_id_0, 0..4)
+            .await
+            .is_none());
+
+        // The footer tier is full: the footer of the first split is evicted.
+        split_cache
+            .put_split_footer(&split_id_1, OwnedBytes::new(b"hotcache".to_vec()))
+            .await;
+        assert!(split_cache
+            .get_split_footer(&split_id_0, 0..6)
+            .await
+            .is_none());
+        assert!(!root_path
+            .join(FOOTERS_DIR_NAME)
+            .join(format!("{split_id_0}.footer"))
+            .try_exists()
+            .unwrap());
+
+        // The cached footers are restored after a restart.
+        drop(split_cache);
+        let split_cache =
+            SplitCache::with_root_path(root_path, StorageResolver::unconfigured(), limits).unwrap();
+        let footer_data = split_cache
+            .get_split_footer(&split_id_1, 0..8)
+            .await
+            .unwrap();
+        assert_eq!(footer_data.as_slice(), b"hotcache");
+    }
+
+    #[tokio::test]
+    async fn test_split_cache_footer_writes_in_flight() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let split_cache = SplitCache::with_root_path(
+            temp_dir.path().to_path_buf(),
+            StorageResolver::unconfigured(),
+            split_cache_limits_for_test(ByteSize::b(10)),
+        )
+        .unwrap();
+        let split_id = Ulid::new().to_string();
+
+        let footer_write_guard = split_cache.start_footer_write(&split_id, 6).unwrap();
+        // The footer of the split is already being written.
+        assert!(split_cache.start_footer_write(&split_id, 6).is_none());
+        drop(footer_write_guard);
+
+        let footer_write_guards: Vec<FooterWriteGuard> = (0..MAX_CONCURRENT_FOOTER_WRITES)
+            .map(|_| {
+                split_cache
+                    .start_footer_write(&Ulid::new().to_string(), 1)
+                    .unwrap()
+            })
+            .collect();
+        // Too many footers are being written.
+        assert!(split_cache.start_footer_write(&split_id, 6).is_none());
+        drop(footer_write_guards);
+
+        split_cache
+            .put_split_footer(&split_id, OwnedBytes::new(b"footer".to_vec()))
+            .await;
+        assert!(split_cache
+            .get_split_footer(&split_id, 0..6)
+            .await
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_split_cache_report_splits_storage_credentials() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let split_cache = SplitCache::with_root_path(
+            temp_dir.path().to_path_buf(),
+            StorageResolver::unconfigured(),
+            split_cache_limits_for_test(ByteSize::b(10)),
+        )
+        .unwrap();
+        let storage_uri = Uri::for_test("s3://account-a-bucket/test-index");
+
+        split_cache.report_splits(vec![ReportSplit {
+            split_id: Ulid::new().to_string(),
+            storage_uri: storage_uri.to_string(),
+            storage_credentials: Some("account-a".to_string()),
+            index_id: None,
+        }]);
+        assert_eq!(
+            split_cache.storage_credentials(&storage_uri).as_deref(),
+            Some("account-a")
+        );
+        assert!(split_cache
+            .storage_credentials(&Uri::for_test("s3://account-a-bucket"))
+            .is_none());
+
+        split_cache.report_splits(vec![ReportSplit {
+            split_id: Ulid::new().to_string(),
+            storage_uri: storage_uri.to_string(),
+            storage_credentials: None,
+            index_id: None,
+        }]);
+        assert!(split_cache.storage_credentials(&storage_uri).is_none());
+    }
+}
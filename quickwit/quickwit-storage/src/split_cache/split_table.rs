@@ -13,22 +13,80 @@
 // limitations under the License.
 
 use std::cmp::Ordering;
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::str::FromStr;
 use std::sync::{Arc, Weak};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 use quickwit_common::uri::Uri;
 use quickwit_config::SplitCacheLimits;
 use ulid::Ulid;
 
-type LastAccessDate = u64;
+use crate::split_cache::access_recorder::SplitAccessRecorder;
+use crate::split_cache::admission_policy::{admission_policy, AdmissionPolicy};
+use crate::split_cache::manifest::{
+    unix_millis, PinnedSplitRecord, SplitAccessRecord, SplitCacheManifest,
+};
+
+pub(crate) type LastAccessDate = u64;
 
 /// Maximum number of splits to track.
 const MAX_NUM_CANDIDATES: usize = 1_000;
 
+/// Pinned splits can take at most `1 / MAX_PINNED_SHARE_DIVISOR` of the number of bytes and
+/// splits of the cache. Beyond that, pinned candidates are not downloaded, so that pins can neither
+/// starve the other splits nor fill the cache with splits that cannot be evicted.
+const MAX_PINNED_SHARE_DIVISOR: u64 = 2;
+
 /// Splits that are freshly reported get a last access time of `now - NEWLY_REPORT_SPLIT_LAST_TIME`.
 const NEWLY_REPORTED_SPLIT_LAST_TIME: Duration = Duration::from_secs(60 * 10); // 10mn
 
+/// Reason for which a split is evicted from the cache.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EvictionReason {
+    /// The cache exceeds its maximum number of bytes.
+    SizeLimit,
+    /// The cache exceeds its maximum number of splits.
+    NumSplitsLimit,
+    /// The split has not been accessed for more than `max_age`.
+    Ttl,
+    /// The disk of the cache is running out of space.
+    DiskPressure,
+    /// The index of the split exceeds its quota.
+    IndexQuota,
+}
+
+impl EvictionReason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::SizeLimit => "size_limit",
+            Self::NumSplitsLimit => "num_splits_limit",
+            Self::Ttl => "ttl",
+            Self::DiskPressure => "disk_pressure",
+            Self::IndexQuota => "index_quota",
+        }
+    }
+}
+
+/// Records the eviction of a split from the cache. Splits that are not on disk are not
+/// accounted for.
+fn record_eviction(split_info: &SplitInfo, eviction_reason: EvictionReason) {
+    let Status::OnDisk { num_bytes, .. } = split_info.status else {
+        return;
+    };
+    let storage_metrics = &crate::metrics::STORAGE_METRICS;
+    storage_metrics.searcher_split_cache.evict_num_items.inc();
+    storage_metrics
+        .searcher_split_cache
+        .evict_num_bytes
+        .inc_by(num_bytes);
+    storage_metrics
+        .split_cache
+        .evictions_total
+        .with_label_values([eviction_reason.as_str()])
+        .inc();
+}
+
 #[derive(Clone, Copy)]
 pub(crate) struct SplitKey {
     pub last_accessed: LastAccessDate,
@@ -58,8 +116,14 @@ impl Eq for SplitKey {}
 #[derive(Clone, Debug)]
 enum Status {
     Candidate(CandidateSplit),
-    Downloading { alive_token: Weak<()> },
-    OnDisk { num_bytes: u64 },
+    Downloading {
+        alive_token: Weak<()>,
+    },
+    OnDisk {
+        num_bytes: u64,
+        // `None` for the splits found on disk at startup.
+        downloaded_at_opt: Option<Instant>,
+    },
 }
 
 impl PartialEq for Status {
@@ -70,9 +134,10 @@ impl PartialEq for Status {
             }
             (Status::Downloading { .. }, Status::Downloading { .. }) => true,
             (
-                Status::OnDisk { num_bytes },
+                Status::OnDisk { num_bytes, .. },
                 Status::OnDisk {
                     num_bytes: other_num_bytes,
+                    ..
                 },
             ) => num_bytes == other_num_bytes,
             _ => false,
@@ -83,6 +148,24 @@ impl PartialEq for Status {
 pub struct SplitInfo {
     pub(crate) split_key: SplitKey,
     status: Status,
+    // `None` if the index of the split is unknown, for instance for the splits found on disk at
+    // startup and absent from the manifest, or reported by nodes that do not send the index ID.
+    index_id_opt: Option<String>,
+}
+
+/// Storage URI and index of a pinned split, needed to download it again after a restart.
+struct PinnedSplit {
+    storage_uri: Uri,
+    index_id_opt: Option<String>,
+}
+
+/// Number of splits and bytes taken in the cache by the splits of an index.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct IndexUsage {
+    // Splits on disk or being downloaded.
+    num_splits: usize,
+    // Splits on disk only.
+    num_bytes: u64,
 }
 
 /// The split table keeps track of splits we know about (regardless of whether they have already
@@ -105,6 +188,16 @@ pub struct SplitTable {
     origin_time: Instant,
     limits: SplitCacheLimits,
     on_disk_bytes: u64,
+    admission_policy: Box<dyn AdmissionPolicy>,
+    // Pinned splits are downloaded first and are never evicted. They are kept with their storage
+    // URI so that they can be unpinned by storage URI.
+    pinned_splits: HashMap<Ulid, PinnedSplit>,
+    // Read path of the split table, kept in sync with the splits on disk.
+    access_recorder: Arc<SplitAccessRecorder>,
+    // Number of bytes the cache is shrunk to when its disk is running out of space.
+    disk_pressure_max_num_bytes_opt: Option<u64>,
+    // Usage of the cache per index, checked against the index quotas.
+    index_usages: HashMap<String, IndexUsage>,
 }
 
 impl SplitTable {
@@ -113,6 +206,10 @@ impl SplitTable {
         existing_filepaths: BTreeMap<Ulid, u64>,
     ) -> SplitTable {
         let origin_time = Instant::now() - NEWLY_REPORTED_SPLIT_LAST_TIME;
+        let admission_policy = admission_policy(
+            limits.admission_policy,
+            limits.max_num_splits.get() as usize,
+        );
         let mut split_table = SplitTable {
             on_disk_splits: BTreeSet::default(),
             candidate_splits: BTreeSet::default(),
@@ -121,6 +218,11 @@ impl SplitTable {
             origin_time,
             limits,
             on_disk_bytes: 0u64,
+            admission_policy,
+            pinned_splits: HashMap::default(),
+            access_recorder: Arc::new(SplitAccessRecorder::with_origin_time(origin_time)),
+            disk_pressure_max_num_bytes_opt: None,
+            index_usages: HashMap::default(),
         };
         split_table.acknowledge_on_disk_splits(existing_filepaths);
         split_table
@@ -133,14 +235,107 @@ impl SplitTable {
                     last_accessed: 0,
                     split_ulid,
                 },
-                status: Status::OnDisk { num_bytes },
+                status: Status::OnDisk {
+                    num_bytes,
+                    downloaded_at_opt: None,
+                },
+                index_id_opt: None,
             };
             self.insert(split_info);
         }
     }
 }
 
-fn compute_timestamp(start: Instant) -> LastAccessDate {
+impl SplitTable {
+    /// Restores the last access dates of the splits found on disk from the manifest persisted
+    /// before a restart.
+    ///
+    /// Records whose size does not match the file on disk are ignored.
+    pub(crate) fn restore_last_accesses(&mut self, manifest: &SplitCacheManifest) {
+        let now_timestamp = compute_timestamp(self.origin_time);
+        let now = SystemTime::now();
+        for split_access_record in &manifest.splits {
+            let Ok(split_ulid) = Ulid::from_str(&split_access_record.split_id) else {
+                continue;
+            };
+            let Some(split_info) = self.split_to_status.get(&split_ulid) else {
+                continue;
+            };
+            let Status::OnDisk { num_bytes, .. } = split_info.status else {
+                continue;
+            };
+            if num_bytes != split_access_record.num_bytes {
+                continue;
+            }
+            let age = now
+                .duration_since(split_access_record.last_accessed())
+                .unwrap_or_default();
+            let last_accessed = now_timestamp.saturating_sub(age.as_micros() as u64);
+            let index_id_opt = split_access_record.index_id.clone();
+            // The split is reinserted so that it is accounted for in the usage of its index.
+            self.mutate_split(split_ulid, move |split_info_opt| {
+                let mut split_info = split_info_opt.expect("split should be on disk");
+                split_info.split_key.last_accessed = last_accessed;
+                split_info.index_id_opt = index_id_opt;
+                split_info
+            });
+        }
+        // Pinned splits that were not downloaded yet are registered again as candidates.
+        for pinned_split_record in &manifest.pinned_splits {
+            let Ok(split_ulid) = Ulid::from_str(&pinned_split_record.split_id) else {
+                continue;
+            };
+            let Ok(storage_uri) = Uri::from_str(&pinned_split_record.storage_uri) else {
+                continue;
+            };
+            self.pin(
+                split_ulid,
+                storage_uri,
+                pinned_split_record.index_id.clone(),
+            );
+        }
+    }
+
+    /// Returns the manifest recording the last access dates of the splits on disk.
+    pub(crate) fn manifest(&mut self) -> SplitCacheManifest {
+        self.apply_pending_accesses();
+        let now_timestamp = compute_timestamp(self.origin_time);
+        let now = SystemTime::now();
+        let splits = self
+            .on_disk_splits
+            .iter()
+            .filter_map(|split_key| {
+                let split_info = self.split_to_status.get(&split_key.split_ulid)?;
+                let Status::OnDisk { num_bytes, .. } = split_info.status else {
+                    return None;
+                };
+                let age =
+                    Duration::from_micros(now_timestamp.saturating_sub(split_key.last_accessed));
+                Some(SplitAccessRecord {
+                    split_id: split_key.split_ulid.to_string(),
+                    num_bytes,
+                    last_accessed_millis: unix_millis(now - age),
+                    index_id: split_info.index_id_opt.clone(),
+                })
+            })
+            .collect();
+        let pinned_splits = self
+            .pinned_splits
+            .iter()
+            .map(|(split_ulid, pinned_split)| PinnedSplitRecord {
+                split_id: split_ulid.to_string(),
+                storage_uri: pinned_split.storage_uri.to_string(),
+                index_id: pinned_split.index_id_opt.clone(),
+            })
+            .collect();
+        SplitCacheManifest {
+            splits,
+            pinned_splits,
+        }
+    }
+}
+
+pub(crate) fn compute_timestamp(start: Instant) -> LastAccessDate {
     start.elapsed().as_micros() as u64
 }
 
@@ -150,7 +345,7 @@ impl SplitTable {
         let split_queue: &mut BTreeSet<SplitKey> = match split_info.status {
             Status::Candidate { .. } => &mut self.candidate_splits,
             Status::Downloading { .. } => &mut self.downloading_splits,
-            Status::OnDisk { num_bytes } => {
+            Status::OnDisk { num_bytes, .. } => {
                 self.on_disk_bytes -= num_bytes;
                 crate::metrics::STORAGE_METRICS
                     .searcher_split_cache
@@ -160,19 +355,13 @@ impl SplitTable {
                     .searcher_split_cache
                     .in_cache_num_bytes
                     .sub(num_bytes as i64);
-                crate::metrics::STORAGE_METRICS
-                    .searcher_split_cache
-                    .evict_num_items
-                    .inc();
-                crate::metrics::STORAGE_METRICS
-                    .searcher_split_cache
-                    .evict_num_bytes
-                    .inc_by(num_bytes);
+                self.access_recorder.remove_on_disk(split_ulid);
                 &mut self.on_disk_splits
             }
         };
         let is_in_queue = split_queue.remove(&split_info.split_key);
         assert!(is_in_queue);
+        self.update_index_usage(&split_info, false);
         if let Status::Downloading { alive_token } = &split_info.status {
             if alive_token.strong_count() == 0 {
                 return None;
@@ -215,7 +404,7 @@ impl SplitTable {
                 self.candidate_splits.insert(split_info.split_key)
             }
             Status::Downloading { .. } => self.downloading_splits.insert(split_info.split_key),
-            Status::OnDisk { num_bytes } => {
+            Status::OnDisk { num_bytes, .. } => {
                 self.on_disk_bytes += num_bytes;
                 crate::metrics::STORAGE_METRICS
                     .searcher_split_cache
@@ -225,9 +414,12 @@ impl SplitTable {
                     .searcher_split_cache
                     .in_cache_num_bytes
                     .add(num_bytes as i64);
+                self.access_recorder
+                    .insert_on_disk(split_info.split_key.split_ulid, num_bytes);
                 self.on_disk_splits.insert(split_info.split_key)
             }
         };
+        self.update_index_usage(&split_info, true);
         // this is fine to do in an inconsistent state, the last entry will just be ignored while
         // gcing
         self.gc_downloading_splits_if_necessary();
@@ -239,17 +431,56 @@ impl SplitTable {
         assert!(split_ulid_was_absent);
     }
 
+    /// Accounts for a split inserted into or removed from the table in the usage of its index.
+    /// Candidate splits do not count.
+    fn update_index_usage(&mut self, split_info: &SplitInfo, is_insertion: bool) {
+        let Some(index_id) = &split_info.index_id_opt else {
+            return;
+        };
+        let num_bytes = match split_info.status {
+            Status::Candidate(_) => return,
+            Status::Downloading { .. } => 0,
+            Status::OnDisk { num_bytes, .. } => num_bytes,
+        };
+        if is_insertion {
+            let index_usage = self.index_usages.entry(index_id.clone()).or_default();
+            index_usage.num_splits += 1;
+            index_usage.num_bytes += num_bytes;
+            return;
+        }
+        let Some(index_usage) = self.index_usages.get_mut(index_id) else {
+            return;
+        };
+        index_usage.num_splits -= 1;
+        index_usage.num_bytes -= num_bytes;
+
+        if index_usage.num_splits == 0 {
+            self.index_usages.remove(index_id);
+        }
+    }
+
     /// Touch the file, updating its last access time, possibly extending its life in the
     /// cache (if in cache).
     ///
     /// If the file is already on the disk cache, return `Some(num_bytes)`.
     /// If the file is not in cache, return `None`, and register the file in the candidate for
     /// download list.
-    pub fn touch(&mut self, split_ulid: Ulid, storage_uri: &Uri) -> Option<u64> {
+    ///
+    /// The index ID, if known, is used to enforce the index quotas.
+    pub fn touch(
+        &mut self,
+        split_ulid: Ulid,
+        storage_uri: &Uri,
+        index_id_opt: Option<&str>,
+    ) -> Option<u64> {
+        self.admission_policy.record_access(split_ulid);
         let timestamp = compute_timestamp(self.origin_time);
         let status = self.mutate_split(split_ulid, |old_split_info| {
             if let Some(mut split_info) = old_split_info {
                 split_info.split_key.last_accessed = timestamp;
+                if split_info.index_id_opt.is_none() {
+                    split_info.index_id_opt = index_id_opt.map(str::to_string);
+                }
                 split_info
             } else {
                 SplitInfo {
@@ -262,16 +493,50 @@ impl SplitTable {
                         split_ulid,
                         living_token: Arc::new(()),
                     }),
+                    index_id_opt: index_id_opt.map(str::to_string),
                 }
             }
         });
-        if let Status::OnDisk { num_bytes } = status {
+        let split_cache_metrics = &crate::metrics::STORAGE_METRICS.split_cache;
+
+        if let Status::OnDisk { num_bytes, .. } = status {
+            split_cache_metrics.hits_total.inc();
             Some(num_bytes)
         } else {
+            split_cache_metrics.misses_total.inc();
             None
         }
     }
 
+    /// Returns the recorder serving the accesses to the splits on disk without locking the
+    /// split table.
+    pub(crate) fn access_recorder(&self) -> Arc<SplitAccessRecorder> {
+        self.access_recorder.clone()
+    }
+
+    /// Applies the accesses buffered by the access recorder, as if the splits had been touched.
+    pub(crate) fn apply_pending_accesses(&mut self) {
+        for (split_ulid, pending_access) in self.access_recorder.drain_pending_accesses() {
+            for _ in 0..pending_access.num_accesses {
+                self.admission_policy.record_access(split_ulid);
+            }
+            let Some(split_info) = self.split_to_status.get_mut(&split_ulid) else {
+                continue;
+            };
+            if split_info.split_key.last_accessed >= pending_access.last_accessed {
+                continue;
+            }
+            let split_queue: &mut BTreeSet<SplitKey> = match split_info.status {
+                Status::Candidate { .. } => &mut self.candidate_splits,
+                Status::Downloading { .. } => &mut self.downloading_splits,
+                Status::OnDisk { .. } => &mut self.on_disk_splits,
+            };
+            split_queue.remove(&split_info.split_key);
+            split_info.split_key.last_accessed = pending_access.last_accessed;
+            split_queue.insert(split_info.split_key);
+        }
+    }
+
     /// Mutates a split ulid.
     ///
     /// By design this function maintains the invariant.
@@ -301,12 +566,35 @@ impl SplitTable {
                         split_ulid,
                     },
                     status,
+                    index_id_opt: None,
                 }
             }
         });
     }
 
-    pub(crate) fn report(&mut self, split_ulid: Ulid, storage_uri: Uri) {
+    /// Returns true if the split is known to the table, and is not a download that was
+    /// cancelled.
+    fn is_tracked(&self, split_ulid: Ulid) -> bool {
+        match self.split_to_status.get(&split_ulid) {
+            Some(SplitInfo {
+                status: Status::Downloading { alive_token },
+                ..
+            }) => alive_token.strong_count() > 0,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    pub(crate) fn report(
+        &mut self,
+        split_ulid: Ulid,
+        storage_uri: Uri,
+        index_id_opt: Option<String>,
+    ) {
+        if self.is_tracked(split_ulid) {
+            self.set_index_id_if_unknown(split_ulid, index_id_opt);
+            return;
+        }
         let origin_time = self.origin_time;
         self.mutate_split(split_ulid, move |split_info_opt| {
             if let Some(split_info) = split_info_opt {
@@ -318,6 +606,7 @@ impl SplitTable {
                         .saturating_sub(NEWLY_REPORTED_SPLIT_LAST_TIME.as_micros() as u64),
                     split_ulid,
                 },
+                index_id_opt,
                 status: Status::Candidate(CandidateSplit {
                     storage_uri,
                     split_ulid,
@@ -327,6 +616,36 @@ impl SplitTable {
         });
     }
 
+    /// Sets the index of a tracked split whose index is not known yet, for instance a split
+    /// found on disk at startup.
+    fn set_index_id_if_unknown(&mut self, split_ulid: Ulid, index_id_opt: Option<String>) {
+        if index_id_opt.is_none() {
+            return;
+        }
+        let Some(split_info) = self.split_to_status.get(&split_ulid) else {
+            return;
+        };
+        if split_info.index_id_opt.is_some() {
+            return;
+        }
+        // The split is reinserted so that it is accounted for in the usage of its index.
+        self.mutate_split(split_ulid, move |split_info_opt| {
+            let mut split_info = split_info_opt.expect("split should be tracked");
+            split_info.index_id_opt = index_id_opt;
+            split_info
+        });
+    }
+
+    /// Reports a batch of splits. Duplicate splits within the batch are only reported once.
+    pub(crate) fn report_many(&mut self, splits: Vec<(Ulid, Uri, Option<String>)>) {
+        let mut reported_split_ulids: HashSet<Ulid> = HashSet::with_capacity(splits.len());
+        for (split_ulid, storage_uri, index_id_opt) in splits {
+            if reported_split_ulids.insert(split_ulid) {
+                self.report(split_ulid, storage_uri, index_id_opt);
+            }
+        }
+    }
+
     /// Make sure we have at most `MAX_CANDIDATES` candidate splits.
     fn truncate_candidate_list(&mut self) {
         // we remove one more to make place for one candidate about to be inserted
@@ -337,7 +656,13 @@ impl SplitTable {
     }
 
     pub(crate) fn register_as_downloaded(&mut self, split_ulid: Ulid, num_bytes: u64) {
-        self.change_split_status(split_ulid, Status::OnDisk { num_bytes });
+        self.change_split_status(
+            split_ulid,
+            Status::OnDisk {
+                num_bytes,
+                downloaded_at_opt: Some(Instant::now()),
+            },
+        );
     }
 
     /// Change the state of the given split from candidate to downloading state,
@@ -355,72 +680,317 @@ impl SplitTable {
         self.insert(SplitInfo {
             split_key: split_info.split_key,
             status: Status::Downloading { alive_token },
+            index_id_opt: split_info.index_id_opt,
         });
         Some(candidate_split)
     }
 
+    /// Pins a split, registering it as a candidate for download if it is not known yet.
+    pub(crate) fn pin(&mut self, split_ulid: Ulid, storage_uri: Uri, index_id_opt: Option<String>) {
+        let pinned_split = PinnedSplit {
+            storage_uri: storage_uri.clone(),
+            index_id_opt: index_id_opt.clone(),
+        };
+        self.pinned_splits.insert(split_ulid, pinned_split);
+        self.report(split_ulid, storage_uri, index_id_opt);
+    }
+
+    pub(crate) fn unpin(&mut self, split_ulid: Ulid) {
+        self.pinned_splits.remove(&split_ulid);
+    }
+
+    /// Unpins all the splits pinned with the given storage URI.
+    pub(crate) fn unpin_storage_uri(&mut self, storage_uri: &Uri) {
+        self.pinned_splits
+            .retain(|_, pinned_split| &pinned_split.storage_uri != storage_uri);
+    }
+
+    fn is_pinned(&self, split_ulid: Ulid) -> bool {
+        self.pinned_splits.contains_key(&split_ulid)
+    }
+
+    /// Returns true if the pinned splits on disk or being downloaded leave room for one more
+    /// pinned split.
+    fn has_room_for_pinned_split(&self) -> bool {
+        let mut num_pinned_splits = 0u64;
+        let mut num_pinned_bytes = 0u64;
+
+        for split_ulid in self.pinned_splits.keys() {
+            let Some(split_info) = self.split_to_status.get(split_ulid) else {
+                continue;
+            };
+            match split_info.status {
+                Status::Candidate(_) => {}
+                Status::Downloading { .. } => num_pinned_splits += 1,
+                Status::OnDisk { num_bytes, .. } => {
+                    num_pinned_splits += 1;
+                    num_pinned_bytes += num_bytes;
+                }
+            }
+        }
+        let max_num_pinned_splits =
+            (self.limits.max_num_splits.get() as u64 / MAX_PINNED_SHARE_DIVISOR).max(1);
+        let max_num_pinned_bytes = self.limits.max_num_bytes.as_u64() / MAX_PINNED_SHARE_DIVISOR;
+        num_pinned_splits < max_num_pinned_splits && num_pinned_bytes < max_num_pinned_bytes
+    }
+
+    /// Returns the most recently accessed pinned candidate, or if there are none, the most
+    /// recently accessed candidate.
+    ///
+    /// When the pinned splits already take their share of the cache, the pinned candidates are
+    /// skipped altogether: they are downloaded once other pinned splits are unpinned.
     fn best_candidate(&self) -> Option<SplitKey> {
-        self.candidate_splits.last().copied()
+        if !self.has_room_for_pinned_split() {
+            return self
+                .candidate_splits
+                .iter()
+                .rev()
+                .find(|split_key| !self.is_pinned(split_key.split_ulid))
+                .copied();
+        }
+        let best_pinned_candidate_opt = self
+            .pinned_splits
+            .keys()
+            .filter_map(|split_ulid| {
+                let split_info = self.split_to_status.get(split_ulid)?;
+                matches!(split_info.status, Status::Candidate(_)).then_some(split_info.split_key)
+            })
+            .max();
+        best_pinned_candidate_opt.or_else(|| self.candidate_splits.last().copied())
     }
 
-    fn is_out_of_limits(&self) -> bool {
+    /// Returns the limit exceeded by the cache, if any.
+    fn exceeded_limit(&self) -> Option<EvictionReason> {
         if self.on_disk_splits.is_empty() {
-            return false;
+            return None;
         }
         if self.on_disk_splits.len() + self.downloading_splits.len()
             >= self.limits.max_num_splits.get() as usize
         {
-            return true;
+            return Some(EvictionReason::NumSplitsLimit);
         }
         if self.on_disk_bytes > self.limits.max_num_bytes.as_u64() {
-            return true;
+            return Some(EvictionReason::SizeLimit);
+        }
+        if let Some(disk_pressure_max_num_bytes) = self.disk_pressure_max_num_bytes_opt {
+            if self.on_disk_bytes > disk_pressure_max_num_bytes {
+                return Some(EvictionReason::DiskPressure);
+            }
+        }
+        None
+    }
+
+    fn is_out_of_limits(&self) -> bool {
+        self.exceeded_limit().is_some()
+    }
+
+    /// Returns true if the index exceeds its quota, if any. Like for the global limits, the
+    /// number of splits must leave room for one more split.
+    fn exceeds_index_quota(&self, index_id: &str) -> bool {
+        let Some(index_quota) = self.limits.index_quota(index_id) else {
+            return false;
+        };
+        let Some(index_usage) = self.index_usages.get(index_id) else {
+            return false;
+        };
+        if let Some(max_num_splits) = index_quota.max_num_splits {
+            if index_usage.num_splits >= max_num_splits.get() as usize {
+                return true;
+            }
+        }
+        if let Some(max_num_bytes) = index_quota.max_num_bytes {
+            if index_usage.num_bytes > max_num_bytes.as_u64() {
+                return true;
+            }
         }
         false
     }
 
+    /// Returns true if the split is pinned or was downloaded less than `min_residency` ago, in
+    /// which case it cannot be evicted yet.
+    fn is_protected_from_eviction(&self, split_ulid: Ulid, now: Instant) -> bool {
+        if self.is_pinned(split_ulid) {
+            return true;
+        }
+        let Some(split_info) = self.split_to_status.get(&split_ulid) else {
+            return false;
+        };
+        let Status::OnDisk {
+            downloaded_at_opt: Some(downloaded_at),
+            ..
+        } = split_info.status
+        else {
+            return false;
+        };
+        now.saturating_duration_since(downloaded_at) < self.limits.min_residency()
+    }
+
+    /// Returns the least recently accessed split that can be evicted, restricted to the splits
+    /// of the given index if any.
+    fn first_evictable_split(&self, now: Instant, index_id_opt: Option<&str>) -> Option<SplitKey> {
+        self.on_disk_splits
+            .iter()
+            .filter(|split_key| {
+                let Some(index_id) = index_id_opt else {
+                    return true;
+                };
+                self.split_to_status
+                    .get(&split_key.split_ulid)
+                    .and_then(|split_info| split_info.index_id_opt.as_deref())
+                    == Some(index_id)
+            })
+            .find(|split_key| !self.is_protected_from_eviction(split_key.split_ulid, now))
+            .copied()
+    }
+
     /// Evicts splits to reach the target limits.
     ///
     /// Returns false if the first candidate for eviction is
     /// fresher that the candidate split. (Note this is suboptimal.
     ///
     /// Returns `None` if this would mean evicting splits that
-    /// have been accessed more recently than the candidate split.
+    /// have been accessed more recently than the candidate split, or
+    /// splits still within their minimum residency window.
+    ///
+    /// If the index of the candidate split is given, the splits of this index are evicted first
+    /// to satisfy its quota.
     pub(crate) fn make_room_for_split_if_necessary(
         &mut self,
         last_access_date: LastAccessDate,
+        index_id_opt: Option<&str>,
     ) -> Result<Vec<Ulid>, NoRoomAvailable> {
-        let mut split_infos = Vec::new();
-        while self.is_out_of_limits() {
-            if let Some(first_split) = self.on_disk_splits.first() {
+        let now = Instant::now();
+        let mut evicted_splits: Vec<(SplitInfo, EvictionReason)> = Vec::new();
+        loop {
+            let (eviction_reason, eviction_index_id_opt) = match index_id_opt {
+                Some(index_id) if self.exceeds_index_quota(index_id) => {
+                    (EvictionReason::IndexQuota, Some(index_id))
+                }
+                _ => {
+                    let Some(eviction_reason) = self.exceeded_limit() else {
+                        break;
+                    };
+                    (eviction_reason, None)
+                }
+            };
+            if let Some(first_split) = self.first_evictable_split(now, eviction_index_id_opt) {
                 if first_split.last_accessed > last_access_date {
                     // This is not worth doing the eviction.
                     break;
                 }
-                split_infos.extend(self.remove(first_split.split_ulid));
+                if let Some(split_info) = self.remove(first_split.split_ulid) {
+                    evicted_splits.push((split_info, eviction_reason));
+                }
             } else {
                 break;
             }
         }
-        if self.is_out_of_limits() {
+        let exceeds_index_quota =
+            index_id_opt.is_some_and(|index_id| self.exceeds_index_quota(index_id));
+
+        if exceeds_index_quota || self.is_out_of_limits() {
             // We are still out of limits.
             // Let's not go through with the eviction, and reinsert the splits.
-            for split_info in split_infos {
+            for (split_info, _) in evicted_splits {
                 self.insert(split_info);
             }
             Err(NoRoomAvailable)
         } else {
-            Ok(split_infos
+            Ok(evicted_splits
                 .into_iter()
-                .map(|split_info| split_info.split_key.split_ulid)
+                .map(|(split_info, eviction_reason)| {
+                    record_eviction(&split_info, eviction_reason);
+                    split_info.split_key.split_ulid
+                })
                 .collect())
         }
     }
 
+    /// Removes the splits that have not been accessed for more than `max_age` from the split
+    /// table, and returns them. The files need to be deleted by the caller.
+    ///
+    /// Splits still within their minimum residency window are kept.
+    pub(crate) fn evict_expired_splits(&mut self) -> Vec<Ulid> {
+        self.apply_pending_accesses();
+        let Some(max_age) = self.limits.max_age else {
+            return Vec::new();
+        };
+        let Some(expiration_date) =
+            compute_timestamp(self.origin_time).checked_sub(max_age.as_micros() as u64)
+        else {
+            return Vec::new();
+        };
+        let now = Instant::now();
+        let expired_splits: Vec<Ulid> = self
+            .on_disk_splits
+            .iter()
+            .take_while(|split_key| split_key.last_accessed < expiration_date)
+            .filter(|split_key| !self.is_protected_from_eviction(split_key.split_ulid, now))
+            .map(|split_key| split_key.split_ulid)
+            .collect();
+        for &split_ulid in &expired_splits {
+            if let Some(split_info) = self.remove(split_ulid) {
+                record_eviction(&split_info, EvictionReason::Ttl);
+            }
+        }
+        expired_splits
+    }
+
+    /// Returns false if making room for the candidate split requires evicting a split the
+    /// admission policy deems more valuable.
+    fn is_admitted(&self, candidate_split_ulid: Ulid) -> bool {
+        if !self.is_out_of_limits() {
+            return true;
+        }
+        let Some(victim_split_key) = self.first_evictable_split(Instant::now(), None) else {
+            return true;
+        };
+        self.admission_policy
+            .admit(candidate_split_ulid, victim_split_key.split_ulid)
+    }
+
     pub(crate) fn find_download_opportunity(&mut self) -> Option<DownloadOpportunity> {
-        let best_candidate_split_key = self.best_candidate()?;
-        let splits_to_delete: Vec<Ulid> = self
-            .make_room_for_split_if_necessary(best_candidate_split_key.last_accessed)
-            .ok()?;
+        self.apply_pending_accesses();
+        let (best_candidate_split_key, splits_to_delete) = loop {
+            let best_candidate_split_key = self.best_candidate()?;
+
+            if self.is_pinned(best_candidate_split_key.split_ulid) {
+                // Pinned splits are worth evicting any split that is not pinned, and are not
+                // subject to the index quotas.
+                let splits_to_delete = self
+                    .make_room_for_split_if_necessary(LastAccessDate::MAX, None)
+                    .ok()?;
+                break (best_candidate_split_key, splits_to_delete);
+            }
+            if !self.is_admitted(best_candidate_split_key.split_ulid) {
+                // Rejected candidates are dropped. They will be registered again on their next
+                // access, with their access frequency preserved by the admission policy.
+                self.remove(best_candidate_split_key.split_ulid);
+                continue;
+            }
+            let index_id_opt: Option<String> = self
+                .split_to_status
+                .get(&best_candidate_split_key.split_ulid)
+                .and_then(|split_info| split_info.index_id_opt.clone());
+
+            match self.make_room_for_split_if_necessary(
+                best_candidate_split_key.last_accessed,
+                index_id_opt.as_deref(),
+            ) {
+                Ok(splits_to_delete) => break (best_candidate_split_key, splits_to_delete),
+                Err(NoRoomAvailable) => {
+                    let exceeds_index_quota = index_id_opt
+                        .as_deref()
+                        .is_some_and(|index_id| self.exceeds_index_quota(index_id));
+                    if !exceeds_index_quota {
+                        return None;
+                    }
+                    // The candidate would evict fresher splits of its own index. It is dropped
+                    // so that it does not hold back the candidates of the other indexes.
+                    self.remove(best_candidate_split_key.split_ulid);
+                }
+            }
+        };
         let split_to_download: CandidateSplit =
             self.start_download(best_candidate_split_key.split_ulid)?;
         Some(DownloadOpportunity {
@@ -429,7 +999,39 @@ impl SplitTable {
         })
     }
 
-    #[cfg(test)]
+    /// Shrinks the cache below `max_num_bytes` while its disk is running out of space, evicting
+    /// the least recently accessed splits right away. The limit is lifted if `None` or not below
+    /// `max_num_bytes`.
+    ///
+    /// Returns the evicted splits. The files need to be deleted by the caller.
+    pub(crate) fn set_disk_pressure_max_num_bytes(
+        &mut self,
+        max_num_bytes_opt: Option<u64>,
+    ) -> Vec<Ulid> {
+        self.disk_pressure_max_num_bytes_opt = max_num_bytes_opt
+            .filter(|max_num_bytes| *max_num_bytes < self.limits.max_num_bytes.as_u64());
+        crate::metrics::STORAGE_METRICS
+            .split_cache
+            .disk_pressure
+            .set(self.disk_pressure_max_num_bytes_opt.is_some() as i64);
+        let Some(disk_pressure_max_num_bytes) = self.disk_pressure_max_num_bytes_opt else {
+            return Vec::new();
+        };
+        let now = Instant::now();
+        let mut evicted_splits = Vec::new();
+
+        while self.on_disk_bytes > disk_pressure_max_num_bytes {
+            let Some(split_key) = self.first_evictable_split(now, None) else {
+                break;
+            };
+            if let Some(split_info) = self.remove(split_key.split_ulid) {
+                record_eviction(&split_info, EvictionReason::DiskPressure);
+                evicted_splits.push(split_key.split_ulid);
+            }
+        }
+        evicted_splits
+    }
+
     pub fn num_bytes(&self) -> u64 {
         self.on_disk_bytes
     }
@@ -454,16 +1056,19 @@ pub(crate) struct DownloadOpportunity {
 
 #[cfg(test)]
 mod tests {
-    use std::num::NonZeroU32;
+    use std::collections::BTreeMap;
+    use std::num::{NonZeroU32, NonZeroU64};
     use std::sync::Arc;
+    use std::time::{Duration, Instant};
 
     use bytesize::ByteSize;
     use quickwit_common::uri::Uri;
-    use quickwit_config::SplitCacheLimits;
+    use quickwit_config::{SplitCacheAdmissionPolicy, SplitCacheIndexQuota, SplitCacheLimits};
     use ulid::Ulid;
 
     use crate::split_cache::split_table::{
-        CandidateSplit, DownloadOpportunity, SplitInfo, SplitKey, SplitTable, Status,
+        CandidateSplit, DownloadOpportunity, EvictionReason, SplitInfo, SplitKey, SplitTable,
+        Status,
     };
 
     const TEST_STORAGE_URI: &str = "s3://test";
@@ -475,43 +1080,46 @@ mod tests {
         split_ulids
     }
 
+    fn test_limits() -> SplitCacheLimits {
+        SplitCacheLimits {
+            max_num_bytes: ByteSize::kb(1),
+            max_num_splits: NonZeroU32::new(1).unwrap(),
+            num_concurrent_downloads: NonZeroU32::new(1).unwrap(),
+            max_file_descriptors: NonZeroU32::new(100).unwrap(),
+            max_open_fds: None,
+            min_residency_secs: None,
+            max_age: None,
+            admission_policy: SplitCacheAdmissionPolicy::Recency,
+            prefetch_adjacent_splits: false,
+            use_mmap: false,
+            max_footer_num_bytes: None,
+            index_quotas: Vec::new(),
+        }
+    }
+
     #[test]
     fn test_split_table() {
-        let mut split_table = SplitTable::with_limits_and_existing_splits(
-            SplitCacheLimits {
-                max_num_bytes: ByteSize::kb(1),
-                max_num_splits: NonZeroU32::new(1).unwrap(),
-                num_concurrent_downloads: NonZeroU32::new(1).unwrap(),
-                max_file_descriptors: NonZeroU32::new(100).unwrap(),
-            },
-            Default::default(),
-        );
+        let mut split_table =
+            SplitTable::with_limits_and_existing_splits(test_limits(), Default::default());
         let ulids = sorted_split_ulids(2);
         let ulid1 = ulids[0];
         let ulid2 = ulids[1];
-        split_table.report(ulid1, Uri::for_test(TEST_STORAGE_URI));
-        split_table.report(ulid2, Uri::for_test(TEST_STORAGE_URI));
+        split_table.report(ulid1, Uri::for_test(TEST_STORAGE_URI), None);
+        split_table.report(ulid2, Uri::for_test(TEST_STORAGE_URI), None);
         let candidate = split_table.best_candidate().unwrap();
         assert_eq!(candidate.split_ulid, ulid2);
     }
 
     #[test]
     fn test_split_table_prefer_last_touched() {
-        let mut split_table = SplitTable::with_limits_and_existing_splits(
-            SplitCacheLimits {
-                max_num_bytes: ByteSize::kb(1),
-                max_num_splits: NonZeroU32::new(1).unwrap(),
-                num_concurrent_downloads: NonZeroU32::new(1).unwrap(),
-                max_file_descriptors: NonZeroU32::new(100).unwrap(),
-            },
-            Default::default(),
-        );
+        let mut split_table =
+            SplitTable::with_limits_and_existing_splits(test_limits(), Default::default());
         let ulids = sorted_split_ulids(2);
         let ulid1 = ulids[0];
         let ulid2 = ulids[1];
-        split_table.report(ulid1, Uri::for_test(TEST_STORAGE_URI));
-        split_table.report(ulid2, Uri::for_test(TEST_STORAGE_URI));
-        let num_bytes_opt = split_table.touch(ulid1, &Uri::for_test("s3://test1/"));
+        split_table.report(ulid1, Uri::for_test(TEST_STORAGE_URI), None);
+        split_table.report(ulid2, Uri::for_test(TEST_STORAGE_URI), None);
+        let num_bytes_opt = split_table.touch(ulid1, &Uri::for_test("s3://test1/"), None);
         assert!(num_bytes_opt.is_none());
         let candidate = split_table.best_candidate().unwrap();
         assert_eq!(candidate.split_ulid, ulid1);
@@ -519,17 +1127,10 @@ mod tests {
 
     #[test]
     fn test_split_table_prefer_start_download_prevent_new_report() {
-        let mut split_table = SplitTable::with_limits_and_existing_splits(
-            SplitCacheLimits {
-                max_num_bytes: ByteSize::kb(1),
-                max_num_splits: NonZeroU32::new(1).unwrap(),
-                num_concurrent_downloads: NonZeroU32::new(1).unwrap(),
-                max_file_descriptors: NonZeroU32::new(100).unwrap(),
-            },
-            Default::default(),
-        );
+        let mut split_table =
+            SplitTable::with_limits_and_existing_splits(test_limits(), Default::default());
         let ulid1 = Ulid::new();
-        split_table.report(ulid1, Uri::for_test(TEST_STORAGE_URI));
+        split_table.report(ulid1, Uri::for_test(TEST_STORAGE_URI), None);
         assert_eq!(split_table.num_bytes(), 0);
         let download = split_table.start_download(ulid1);
         assert!(download.is_some());
@@ -537,11 +1138,11 @@ mod tests {
         split_table.register_as_downloaded(ulid1, 10_000_000);
         assert_eq!(split_table.num_bytes(), 10_000_000);
         assert_eq!(
-            split_table.touch(ulid1, &Uri::for_test(TEST_STORAGE_URI)),
+            split_table.touch(ulid1, &Uri::for_test(TEST_STORAGE_URI), None),
             Some(10_000_000)
         );
         let ulid2 = Ulid::new();
-        split_table.report(ulid2, Uri::for_test("s3://test`/"));
+        split_table.report(ulid2, Uri::for_test("s3://test`/"), None);
         let download = split_table.start_download(ulid2);
         assert!(download.is_some());
         assert!(split_table.start_download(ulid2).is_none());
@@ -556,8 +1157,7 @@ mod tests {
             SplitCacheLimits {
                 max_num_bytes: ByteSize::mb(1),
                 max_num_splits: NonZeroU32::new(30).unwrap(),
-                num_concurrent_downloads: NonZeroU32::new(1).unwrap(),
-                max_file_descriptors: NonZeroU32::new(100).unwrap(),
+                ..test_limits()
             },
             Default::default(),
         );
@@ -572,11 +1172,15 @@ mod tests {
             (split_ulids[5], 300_000),
         ];
         for (split_ulid, num_bytes) in splits {
-            split_table.report(split_ulid, Uri::for_test(TEST_STORAGE_URI));
+            split_table.report(split_ulid, Uri::for_test(TEST_STORAGE_URI), None);
             split_table.register_as_downloaded(split_ulid, num_bytes);
         }
+        assert_eq!(
+            split_table.exceeded_limit(),
+            Some(EvictionReason::SizeLimit)
+        );
         let new_ulid = Ulid::new();
-        split_table.report(new_ulid, Uri::for_test(TEST_STORAGE_URI));
+        split_table.report(new_ulid, Uri::for_test(TEST_STORAGE_URI), None);
         let DownloadOpportunity {
             splits_to_delete,
             split_to_download,
@@ -594,8 +1198,7 @@ mod tests {
             SplitCacheLimits {
                 max_num_bytes: ByteSize::mb(10),
                 max_num_splits: NonZeroU32::new(5).unwrap(),
-                num_concurrent_downloads: NonZeroU32::new(1).unwrap(),
-                max_file_descriptors: NonZeroU32::new(100).unwrap(),
+                ..test_limits()
             },
             Default::default(),
         );
@@ -610,11 +1213,15 @@ mod tests {
             (split_ulids[5], 300_000),
         ];
         for (split_ulid, num_bytes) in splits {
-            split_table.report(split_ulid, Uri::for_test(TEST_STORAGE_URI));
+            split_table.report(split_ulid, Uri::for_test(TEST_STORAGE_URI), None);
             split_table.register_as_downloaded(split_ulid, num_bytes);
         }
+        assert_eq!(
+            split_table.exceeded_limit(),
+            Some(EvictionReason::NumSplitsLimit)
+        );
         let new_ulid = Ulid::new();
-        split_table.report(new_ulid, Uri::for_test(TEST_STORAGE_URI));
+        split_table.report(new_ulid, Uri::for_test(TEST_STORAGE_URI), None);
         let DownloadOpportunity {
             splits_to_delete,
             split_to_download,
@@ -623,22 +1230,486 @@ mod tests {
         assert_eq!(split_to_download.split_ulid, new_ulid);
     }
 
+    #[test]
+    fn test_eviction_protects_recently_downloaded_splits() {
+        let mut split_table = SplitTable::with_limits_and_existing_splits(
+            SplitCacheLimits {
+                max_num_bytes: ByteSize::mb(10),
+                max_num_splits: NonZeroU32::new(3).unwrap(),
+                min_residency_secs: NonZeroU64::new(600),
+                ..test_limits()
+            },
+            [(Ulid::new(), 10_000)].into_iter().collect(),
+        );
+        let split_ulids = sorted_split_ulids(2);
+        for split_ulid in &split_ulids {
+            split_table.report(*split_ulid, Uri::for_test(TEST_STORAGE_URI), None);
+            split_table.register_as_downloaded(*split_ulid, 10_000);
+        }
+        // The split found on disk at startup is not protected.
+        let new_ulid = Ulid::new();
+        split_table.touch(new_ulid, &Uri::for_test(TEST_STORAGE_URI), None);
+        let DownloadOpportunity {
+            splits_to_delete,
+            split_to_download,
+        } = split_table.find_download_opportunity().unwrap();
+        assert_eq!(splits_to_delete.len(), 1);
+        assert!(!split_ulids.contains(&splits_to_delete[0]));
+        assert_eq!(split_to_download.split_ulid, new_ulid);
+        split_table.register_as_downloaded(new_ulid, 10_000);
+
+        // All the splits on disk have just been downloaded.
+        let other_ulid = Ulid::new();
+        split_table.touch(other_ulid, &Uri::for_test(TEST_STORAGE_URI), None);
+        assert!(split_table.find_download_opportunity().is_none());
+    }
+
+    #[test]
+    fn test_evict_expired_splits() {
+        let existing_split_ulid = Ulid::new();
+        let mut split_table = SplitTable::with_limits_and_existing_splits(
+            SplitCacheLimits {
+                max_num_bytes: ByteSize::mb(10),
+                max_num_splits: NonZeroU32::new(5).unwrap(),
+                max_age: Some(Duration::from_secs(60)),
+                ..test_limits()
+            },
+            [(existing_split_ulid, 10_000)].into_iter().collect(),
+        );
+        let split_ulid = Ulid::new();
+        split_table.touch(split_ulid, &Uri::for_test(TEST_STORAGE_URI), None);
+        split_table.start_download(split_ulid).unwrap();
+        split_table.register_as_downloaded(split_ulid, 20_000);
+        assert_eq!(split_table.num_bytes(), 30_000);
+
+        // The existing split was last accessed before the table was created.
+        assert_eq!(split_table.evict_expired_splits(), &[existing_split_ulid]);
+        assert_eq!(split_table.num_bytes(), 20_000);
+        assert!(split_table.evict_expired_splits().is_empty());
+        assert_eq!(
+            split_table.touch(split_ulid, &Uri::for_test(TEST_STORAGE_URI), None),
+            Some(20_000)
+        );
+    }
+
+    #[test]
+    fn test_split_table_apply_pending_accesses() {
+        let split_ulids = sorted_split_ulids(2);
+        let mut split_table = SplitTable::with_limits_and_existing_splits(
+            SplitCacheLimits {
+                max_num_bytes: ByteSize::mb(10),
+                max_num_splits: NonZeroU32::new(2).unwrap(),
+                ..test_limits()
+            },
+            split_ulids
+                .iter()
+                .map(|split_ulid| (*split_ulid, 10_000))
+                .collect(),
+        );
+        let access_recorder = split_table.access_recorder();
+        assert_eq!(access_recorder.touch_on_disk(split_ulids[0]), Some(10_000));
+        assert!(access_recorder.touch_on_disk(Ulid::new()).is_none());
+
+        // The access is not visible to the split table until it is applied.
+        let now = Instant::now();
+        assert_eq!(
+            split_table
+                .first_evictable_split(now, None)
+                .unwrap()
+                .split_ulid,
+            split_ulids[0]
+        );
+        split_table.apply_pending_accesses();
+        assert_eq!(
+            split_table
+                .first_evictable_split(now, None)
+                .unwrap()
+                .split_ulid,
+            split_ulids[1]
+        );
+
+        // Evicted splits are no longer served by the access recorder.
+        assert_eq!(
+            split_table
+                .make_room_for_split_if_necessary(u64::MAX, None)
+                .unwrap(),
+            &[split_ulids[1]]
+        );
+        assert!(access_recorder.touch_on_disk(split_ulids[1]).is_none());
+        assert_eq!(access_recorder.touch_on_disk(split_ulids[0]), Some(10_000));
+    }
+
+    #[test]
+    fn test_split_table_disk_pressure() {
+        let split_ulids = sorted_split_ulids(3);
+        let mut split_table = SplitTable::with_limits_and_existing_splits(
+            SplitCacheLimits {
+                max_num_bytes: ByteSize::mb(10),
+                max_num_splits: NonZeroU32::new(5).unwrap(),
+                ..test_limits()
+            },
+            split_ulids
+                .iter()
+                .map(|split_ulid| (*split_ulid, 10_000))
+                .collect(),
+        );
+        split_table.touch(split_ulids[0], &Uri::for_test(TEST_STORAGE_URI), None);
+
+        // A limit above `max_num_bytes` does not put the cache under pressure.
+        assert!(split_table
+            .set_disk_pressure_max_num_bytes(Some(20_000_000))
+            .is_empty());
+        assert!(split_table.disk_pressure_max_num_bytes_opt.is_none());
+
+        let evicted_splits = split_table.set_disk_pressure_max_num_bytes(Some(15_000));
+        assert_eq!(evicted_splits, &[split_ulids[1], split_ulids[2]]);
+        assert_eq!(split_table.num_bytes(), 10_000);
+
+        // No split is downloaded while the cache is at its disk pressure limit.
+        let other_split_ulid = Ulid::new();
+        split_table.touch(other_split_ulid, &Uri::for_test(TEST_STORAGE_URI), None);
+        split_table.start_download(other_split_ulid).unwrap();
+        split_table.register_as_downloaded(other_split_ulid, 10_000);
+        assert_eq!(
+            split_table.exceeded_limit(),
+            Some(EvictionReason::DiskPressure)
+        );
+
+        assert!(split_table.set_disk_pressure_max_num_bytes(None).is_empty());
+        assert!(split_table.exceeded_limit().is_none());
+    }
+
+    #[test]
+    fn test_split_table_index_quotas() {
+        let mut split_table = SplitTable::with_limits_and_existing_splits(
+            SplitCacheLimits {
+                max_num_bytes: ByteSize::mb(10),
+                max_num_splits: NonZeroU32::new(10).unwrap(),
+                index_quotas: vec![SplitCacheIndexQuota {
+                    index_id_pattern: "logs-*".to_string(),
+                    max_num_bytes: None,
+                    max_num_splits: NonZeroU32::new(2),
+                }],
+                ..test_limits()
+            },
+            BTreeMap::default(),
+        );
+        // The index IDs are reported along with the splits: the storage URIs are not used.
+        let storage_uri = Uri::for_test("s3://bucket/custom-uri");
+
+        // The split of the index without quota is the least recently accessed one.
+        let split_ulids = sorted_split_ulids(6);
+        for (split_ulid, index_id) in split_ulids[..3]
+            .iter()
+            .zip(["metrics", "logs-app", "logs-app"])
+        {
+            split_table.touch(*split_ulid, &storage_uri, Some(index_id));
+            split_table.start_download(*split_ulid).unwrap();
+            split_table.register_as_downloaded(*split_ulid, 10_000);
+        }
+        // The index quota is reached: the least recently accessed split of the index is evicted.
+        split_table.touch(split_ulids[3], &storage_uri, Some("logs-app"));
+        let DownloadOpportunity {
+            splits_to_delete,
+            split_to_download,
+        } = split_table.find_download_opportunity().unwrap();
+        assert_eq!(splits_to_delete, &[split_ulids[1]]);
+        assert_eq!(split_to_download.split_ulid, split_ulids[3]);
+        split_table.register_as_downloaded(split_ulids[3], 10_000);
+        assert!(split_table.is_tracked(split_ulids[0]));
+
+        // Freshly reported splits are older than the splits of the index on disk: the candidate
+        // is dropped instead of holding back the candidates of the other indexes.
+        split_table.report(
+            split_ulids[4],
+            storage_uri.clone(),
+            Some("metrics".to_string()),
+        );
+        split_table.report(split_ulids[5], storage_uri, Some("logs-app".to_string()));
+        let DownloadOpportunity {
+            splits_to_delete,
+            split_to_download,
+        } = split_table.find_download_opportunity().unwrap();
+        assert!(splits_to_delete.is_empty());
+        assert_eq!(split_to_download.split_ulid, split_ulids[4]);
+        assert!(!split_table.is_tracked(split_ulids[5]));
+    }
+
+    #[test]
+    fn test_split_table_restore_last_accesses() {
+        let limits = SplitCacheLimits {
+            max_num_bytes: ByteSize::mb(10),
+            max_num_splits: NonZeroU32::new(2).unwrap(),
+            ..test_limits()
+        };
+        let split_ulids = sorted_split_ulids(2);
+        let existing_splits: BTreeMap<Ulid, u64> = split_ulids
+            .iter()
+            .map(|split_ulid| (*split_ulid, 10_000))
+            .collect();
+        let mut split_table =
+            SplitTable::with_limits_and_existing_splits(limits.clone(), existing_splits.clone());
+        // The split with the lowest ulid is the hottest one.
+        split_table.touch(split_ulids[0], &Uri::for_test(TEST_STORAGE_URI), None);
+        let manifest = split_table.manifest();
+        assert_eq!(manifest.splits.len(), 2);
+        assert_eq!(manifest.splits[1].split_id, split_ulids[0].to_string());
+
+        let mut restarted_split_table =
+            SplitTable::with_limits_and_existing_splits(limits, existing_splits);
+        restarted_split_table.restore_last_accesses(&manifest);
+        let splits_to_delete = restarted_split_table
+            .make_room_for_split_if_necessary(u64::MAX, None)
+            .unwrap();
+        assert_eq!(splits_to_delete, &[split_ulids[1]]);
+    }
+
+    #[test]
+    fn test_split_table_learns_index_of_splits_on_disk() {
+        let split_ulid = Ulid::new();
+        let mut split_table = SplitTable::with_limits_and_existing_splits(
+            SplitCacheLimits {
+                max_num_bytes: ByteSize::mb(10),
+                max_num_splits: NonZeroU32::new(5).unwrap(),
+                ..test_limits()
+            },
+            BTreeMap::from([(split_ulid, 10_000)]),
+        );
+        assert!(split_table.index_usages.is_empty());
+
+        split_table.report(
+            split_ulid,
+            Uri::for_test(TEST_STORAGE_URI),
+            Some("test-index".to_string()),
+        );
+        assert_eq!(
+            split_table.index_usages["test-index"],
+            IndexUsage {
+                num_splits: 1,
+                num_bytes: 10_000,
+            }
+        );
+        // The index of a split is never changed once known.
+        split_table.touch(
+            split_ulid,
+            &Uri::for_test(TEST_STORAGE_URI),
+            Some("other-index"),
+        );
+        assert!(!split_table.index_usages.contains_key("other-index"));
+    }
+
+    #[test]
+    fn test_split_table_report_many() {
+        let mut split_table = SplitTable::with_limits_and_existing_splits(
+            SplitCacheLimits {
+                max_num_bytes: ByteSize::mb(10),
+                max_num_splits: NonZeroU32::new(5).unwrap(),
+                ..test_limits()
+            },
+            Default::default(),
+        );
+        let split_ulids = sorted_split_ulids(3);
+        split_table.report(split_ulids[0], Uri::for_test(TEST_STORAGE_URI), None);
+        split_table.start_download(split_ulids[0]).unwrap();
+        split_table.register_as_downloaded(split_ulids[0], 10_000);
+
+        split_table.report_many(vec![
+            (split_ulids[0], Uri::for_test(TEST_STORAGE_URI), None),
+            (split_ulids[1], Uri::for_test(TEST_STORAGE_URI), None),
+            (split_ulids[2], Uri::for_test(TEST_STORAGE_URI), None),
+            (split_ulids[1], Uri::for_test(TEST_STORAGE_URI), None),
+        ]);
+        assert_eq!(split_table.candidate_splits.len(), 2);
+        assert_eq!(split_table.on_disk_splits.len(), 1);
+        assert_eq!(split_table.num_bytes(), 10_000);
+    }
+
+    #[test]
+    fn test_tiny_lfu_admission_policy_protects_hot_splits() {
+        let mut split_table = SplitTable::with_limits_and_existing_splits(
+            SplitCacheLimits {
+                max_num_bytes: ByteSize::mb(10),
+                admission_policy: SplitCacheAdmissionPolicy::TinyLfu,
+                ..test_limits()
+            },
+            Default::default(),
+        );
+        let hot_split_ulid = Ulid::new();
+        for _ in 0..3 {
+            split_table.touch(hot_split_ulid, &Uri::for_test(TEST_STORAGE_URI), None);
+        }
+        let DownloadOpportunity {
+            split_to_download, ..
+        } = split_table.find_download_opportunity().unwrap();
+        assert_eq!(split_to_download.split_ulid, hot_split_ulid);
+        split_table.register_as_downloaded(hot_split_ulid, 10_000);
+
+        // A split accessed once by a scan does not evict the hot split.
+        let scanned_split_ulid = Ulid::new();
+        split_table.touch(scanned_split_ulid, &Uri::for_test(TEST_STORAGE_URI), None);
+        assert!(split_table.find_download_opportunity().is_none());
+        assert!(split_table.candidate_splits.is_empty());
+
+        // A split accessed more frequently than the hot split gets admitted.
+        let hotter_split_ulid = Ulid::new();
+        for _ in 0..4 {
+            split_table.touch(hotter_split_ulid, &Uri::for_test(TEST_STORAGE_URI), None);
+        }
+        let DownloadOpportunity {
+            splits_to_delete,
+            split_to_download,
+        } = split_table.find_download_opportunity().unwrap();
+        assert_eq!(splits_to_delete, &[hot_split_ulid]);
+        assert_eq!(split_to_download.split_ulid, hotter_split_ulid);
+    }
+
+    #[test]
+    fn test_split_table_pinned_splits() {
+        let mut split_table = SplitTable::with_limits_and_existing_splits(
+            SplitCacheLimits {
+                max_num_bytes: ByteSize::mb(10),
+                max_num_splits: NonZeroU32::new(2).unwrap(),
+                ..test_limits()
+            },
+            Default::default(),
+        );
+        let split_ulids = sorted_split_ulids(4);
+        let index_uri = Uri::for_test("s3://test/index");
+
+        // Pinned splits are downloaded first.
+        split_table.touch(split_ulids[0], &Uri::for_test(TEST_STORAGE_URI), None);
+        split_table.pin(split_ulids[1], index_uri.clone(), None);
+        let DownloadOpportunity {
+            split_to_download, ..
+        } = split_table.find_download_opportunity().unwrap();
+        assert_eq!(split_to_download.split_ulid, split_ulids[1]);
+        split_table.register_as_downloaded(split_ulids[1], 10_000);
+
+        let DownloadOpportunity {
+            split_to_download, ..
+        } = split_table.find_download_opportunity().unwrap();
+        assert_eq!(split_to_download.split_ulid, split_ulids[0]);
+        split_table.register_as_downloaded(split_ulids[0], 10_000);
+
+        // Pinned splits are never evicted, even though they are the least recently accessed.
+        split_table.touch(split_ulids[2], &Uri::for_test(TEST_STORAGE_URI), None);
+        let DownloadOpportunity {
+            splits_to_delete, ..
+        } = split_table.find_download_opportunity().unwrap();
+        assert_eq!(splits_to_delete, &[split_ulids[0]]);
+        split_table.register_as_downloaded(split_ulids[2], 10_000);
+
+        split_table.unpin_storage_uri(&index_uri);
+        split_table.touch(split_ulids[3], &Uri::for_test(TEST_STORAGE_URI), None);
+        let DownloadOpportunity {
+            splits_to_delete, ..
+        } = split_table.find_download_opportunity().unwrap();
+        assert_eq!(splits_to_delete, &[split_ulids[1]]);
+    }
+
+    #[test]
+    fn test_split_table_pinned_splits_share() {
+        let mut split_table = SplitTable::with_limits_and_existing_splits(
+            SplitCacheLimits {
+                max_num_bytes: ByteSize::mb(10),
+                max_num_splits: NonZeroU32::new(5).unwrap(),
+                ..test_limits()
+            },
+            Default::default(),
+        );
+        let split_ulids = sorted_split_ulids(4);
+        let index_uri = Uri::for_test("s3://test/index");
+
+        for &split_ulid in &split_ulids[..3] {
+            split_table.pin(split_ulid, index_uri.clone(), None);
+        }
+        split_table.touch(split_ulids[3], &Uri::for_test(TEST_STORAGE_URI), None);
+
+        // Pinned splits can take up to half of the cache: the third pinned split is skipped.
+        for expected_split_ulid in [split_ulids[2], split_ulids[1], split_ulids[3]] {
+            let DownloadOpportunity {
+                split_to_download, ..
+            } = split_table.find_download_opportunity().unwrap();
+            assert_eq!(split_to_download.split_ulid, expected_split_ulid);
+            split_table.register_as_downloaded(expected_split_ulid, 10_000);
+        }
+        assert!(split_table.find_download_opportunity().is_none());
+
+        split_table.unpin(split_ulids[2]);
+        let DownloadOpportunity {
+            split_to_download, ..
+        } = split_table.find_download_opportunity().unwrap();
+        assert_eq!(split_to_download.split_ulid, split_ulids[0]);
+    }
+
+    #[test]
+    fn test_split_table_pinned_splits_survive_restart() {
+        let limits = SplitCacheLimits {
+            max_num_bytes: ByteSize::mb(10),
+            max_num_splits: NonZeroU32::new(10).unwrap(),
+            ..test_limits()
+        };
+        let mut split_table =
+            SplitTable::with_limits_and_existing_splits(limits.clone(), Default::default());
+        let split_ulids = sorted_split_ulids(2);
+        let index_uri = Uri::for_test("s3://test/index");
+
+        split_table.pin(
+            split_ulids[0],
+            index_uri.clone(),
+            Some("test-index".to_string()),
+        );
+        split_table.pin(
+            split_ulids[1],
+            index_uri.clone(),
+            Some("test-index".to_string()),
+        );
+        split_table.start_download(split_ulids[0]).unwrap();
+        split_table.register_as_downloaded(split_ulids[0], 10_000);
+        let manifest = split_table.manifest();
+        assert_eq!(manifest.pinned_splits.len(), 2);
+        assert!(manifest
+            .pinned_splits
+            .iter()
+            .all(|pinned_split| pinned_split.index_id.as_deref() == Some("test-index")));
+
+        let mut restarted_split_table = SplitTable::with_limits_and_existing_splits(
+            limits,
+            BTreeMap::from([(split_ulids[0], 10_000)]),
+        );
+        restarted_split_table.restore_last_accesses(&manifest);
+        assert!(restarted_split_table.is_pinned(split_ulids[0]));
+        assert!(restarted_split_table.is_pinned(split_ulids[1]));
+        assert_eq!(
+            restarted_split_table.index_usages["test-index"],
+            IndexUsage {
+                num_splits: 1,
+                num_bytes: 10_000,
+            }
+        );
+
+        // The pinned split that was not downloaded yet is a candidate again.
+        let DownloadOpportunity {
+            split_to_download, ..
+        } = restarted_split_table.find_download_opportunity().unwrap();
+        assert_eq!(split_to_download.split_ulid, split_ulids[1]);
+    }
+
     #[test]
     fn test_failed_download_can_be_re_reported() {
         let mut split_table = SplitTable::with_limits_and_existing_splits(
             SplitCacheLimits {
                 max_num_bytes: ByteSize::mb(10),
                 max_num_splits: NonZeroU32::new(5).unwrap(),
-                num_concurrent_downloads: NonZeroU32::new(1).unwrap(),
-                max_file_descriptors: NonZeroU32::new(100).unwrap(),
+                ..test_limits()
             },
             Default::default(),
         );
         let split_ulid = Ulid::new();
-        split_table.report(split_ulid, Uri::for_test(TEST_STORAGE_URI));
+        split_table.report(split_ulid, Uri::for_test(TEST_STORAGE_URI), None);
         let candidate = split_table.start_download(split_ulid).unwrap();
         // This report should be cancelled as we have a download currently running.
-        split_table.report(split_ulid, Uri::for_test(TEST_STORAGE_URI));
+        split_table.report(split_ulid, Uri::for_test(TEST_STORAGE_URI), None);
 
         assert!(split_table.start_download(split_ulid).is_none());
         std::mem::drop(candidate);
@@ -647,7 +1718,7 @@ mod tests {
         assert!(split_table.start_download(split_ulid).is_none());
 
         // This report should be considered as our candidate (and its alive token has been dropped)
-        split_table.report(split_ulid, Uri::for_test(TEST_STORAGE_URI));
+        split_table.report(split_ulid, Uri::for_test(TEST_STORAGE_URI), None);
 
         let candidate2 = split_table.start_download(split_ulid).unwrap();
         assert_eq!(candidate2.split_ulid, split_ulid);
@@ -659,14 +1730,13 @@ mod tests {
             SplitCacheLimits {
                 max_num_bytes: ByteSize::mb(10),
                 max_num_splits: NonZeroU32::new(5).unwrap(),
-                num_concurrent_downloads: NonZeroU32::new(1).unwrap(),
-                max_file_descriptors: NonZeroU32::new(100).unwrap(),
+                ..test_limits()
             },
             Default::default(),
         );
         for i in 1..2_000 {
             let split_ulid = Ulid::new();
-            split_table.report(split_ulid, Uri::for_test(TEST_STORAGE_URI));
+            split_table.report(split_ulid, Uri::for_test(TEST_STORAGE_URI), None);
             assert_eq!(
                 split_table.candidate_splits.len(),
                 i.min(super::MAX_NUM_CANDIDATES)
@@ -681,8 +1751,7 @@ mod tests {
             SplitCacheLimits {
                 max_num_bytes: ByteSize::mb(10),
                 max_num_splits: NonZeroU32::new(2).unwrap(),
-                num_concurrent_downloads: NonZeroU32::new(1).unwrap(),
-                max_file_descriptors: NonZeroU32::new(100).unwrap(),
+                ..test_limits()
             },
             Default::default(),
         );
@@ -699,6 +1768,7 @@ mod tests {
                     split_ulid,
                 },
                 status: Status::Candidate(candidate_split),
+                index_id_opt: None,
             };
             split_table.insert(split_info);
         }
@@ -50,6 +50,17 @@ pub trait Storage: fmt::Debug + Send + Sync + 'static {
     /// Saves a file into the storage.
     async fn put(&self, path: &Path, payload: Box<dyn PutPayload>) -> StorageResult<()>;
 
+    /// Saves a file into the storage, in the given storage class (e.g. `STANDARD_IA` on Amazon
+    /// S3). Storages without storage classes ignore it.
+    async fn put_with_storage_class(
+        &self,
+        path: &Path,
+        payload: Box<dyn PutPayload>,
+        _storage_class: &str,
+    ) -> StorageResult<()> {
+        self.put(path, payload).await
+    }
+
     /// Copies the file associated to `Path` into an `AsyncWrite`.
     /// This function is required to call `.flush()` before it successfully returns.
     ///
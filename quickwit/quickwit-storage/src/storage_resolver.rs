@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::sync::Arc;
 
@@ -28,12 +28,19 @@ use crate::AzureBlobStorageFactory;
 use crate::GoogleCloudStorageFactory;
 use crate::{S3CompatibleObjectStorageFactory, Storage, StorageFactory, StorageResolverError};
 
+type PerBackendFactories = Arc<HashMap<StorageBackend, Box<dyn StorageFactory>>>;
+
 /// Returns the [`Storage`] instance associated with the protocol of a URI. The actual creation of
 /// storage objects is delegated to pre-registered [`StorageFactory`]. The resolver is only
 /// responsible for dispatching to the appropriate factory.
+///
+/// The URIs of the indexes referencing named storage credentials are resolved with
+/// [`StorageResolver::resolve_with_credentials`], which dispatches to the factories configured
+/// with these credentials instead.
 #[derive(Clone)]
 pub struct StorageResolver {
-    per_backend_factories: Arc<HashMap<StorageBackend, Box<dyn StorageFactory>>>,
+    per_backend_factories: PerBackendFactories,
+    per_credentials_factories: Arc<HashMap<String, PerBackendFactories>>,
 }
 
 impl fmt::Debug for StorageResolver {
@@ -48,8 +55,24 @@ impl StorageResolver {
         StorageResolverBuilder::default()
     }
 
+    /// Returns whether storage credentials with the given name are configured.
+    pub fn has_storage_credentials(&self, storage_credentials: &str) -> bool {
+        self.per_credentials_factories
+            .contains_key(storage_credentials)
+    }
+
     /// Resolves the given URI.
     pub async fn resolve(&self, uri: &Uri) -> Result<Arc<dyn Storage>, StorageResolverError> {
+        self.resolve_with_credentials(uri, None).await
+    }
+
+    /// Resolves the given URI with the named storage credentials defined in the node config, or
+    /// with the default storage configs when `storage_credentials_opt` is `None`.
+    pub async fn resolve_with_credentials(
+        &self,
+        uri: &Uri,
+        storage_credentials_opt: Option<&str>,
+    ) -> Result<Arc<dyn Storage>, StorageResolverError> {
         let backend = match uri.protocol() {
             Protocol::Azure => StorageBackend::Azure,
             Protocol::File => StorageBackend::File,
@@ -64,7 +87,20 @@ impl StorageResolver {
                 return Err(StorageResolverError::UnsupportedBackend(message));
             }
         };
-        let storage_factory = self.per_backend_factories.get(&backend).ok_or({
+        let per_backend_factories = if let Some(storage_credentials) = storage_credentials_opt {
+            self.per_credentials_factories
+                .get(storage_credentials)
+                .ok_or_else(|| {
+                    let message = format!(
+                        "storage credentials `{storage_credentials}` used to access `{uri}` are \
+                         not defined in the node config"
+                    );
+                    StorageResolverError::InvalidConfig(message)
+                })?
+        } else {
+            &self.per_backend_factories
+        };
+        let storage_factory = per_backend_factories.get(&backend).ok_or({
             let message = format!("no storage factory is registered for {}", uri.protocol());
             StorageResolverError::UnsupportedBackend(message)
         })?;
@@ -87,8 +123,12 @@ impl StorageResolver {
     /// Creates and returns a [`StorageResolver`].
     pub fn configured(storage_configs: &StorageConfigs) -> Self {
         let mut builder = StorageResolver::builder()
-            .register(LocalFileStorageFactory)
-            .register(RamStorageFactory::default())
+            .register(LocalFileStorageFactory::new(
+                storage_configs.find_file().cloned().unwrap_or_default(),
+            ))
+            .register(RamStorageFactory::new(
+                storage_configs.find_ram().cloned().unwrap_or_default(),
+            ))
             .register(S3CompatibleObjectStorageFactory::new(
                 storage_configs.find_s3().cloned().unwrap_or_default(),
             ));
@@ -127,13 +167,32 @@ impl StorageResolver {
             .expect("storage factory and config backends should match")
     }
 
+    /// Creates and returns a [`StorageResolver`] also holding the named storage credentials
+    /// defined in the `storage_credentials` section of the node config.
+    pub fn configured_with_credentials(
+        storage_configs: &StorageConfigs,
+        storage_credentials: &BTreeMap<String, StorageConfigs>,
+    ) -> Self {
+        let mut storage_resolver = StorageResolver::configured(storage_configs);
+        let per_credentials_factories = storage_credentials
+            .iter()
+            .map(|(name, storage_configs)| {
+                let per_backend_factories =
+                    StorageResolver::configured(storage_configs).per_backend_factories;
+                (name.clone(), per_backend_factories)
+            })
+            .collect();
+        storage_resolver.per_credentials_factories = Arc::new(per_credentials_factories);
+        storage_resolver
+    }
+
     /// Returns a [`StorageResolver`] for testing purposes. Unlike
     /// [`StorageResolver::unconfigured`], this resolver does not return a singleton.
     #[cfg(any(test, feature = "testsuite"))]
     pub fn for_test() -> Self {
         StorageResolver::builder()
             .register(RamStorageFactory::default())
-            .register(LocalFileStorageFactory)
+            .register(LocalFileStorageFactory::default())
             .build()
             .expect("storage factory and config backends should match")
     }
@@ -142,6 +201,7 @@ impl StorageResolver {
 #[derive(Default)]
 pub struct StorageResolverBuilder {
     per_backend_factories: HashMap<StorageBackend, Box<dyn StorageFactory>>,
+    per_credentials_factories: HashMap<String, PerBackendFactories>,
 }
 
 impl StorageResolverBuilder {
@@ -152,10 +212,23 @@ impl StorageResolverBuilder {
         self
     }
 
+    /// Registers the factories of a [`StorageResolver`] as the ones to use for the indexes
+    /// referencing the storage credentials `name`.
+    pub fn register_storage_credentials(
+        mut self,
+        name: impl Into<String>,
+        storage_resolver: StorageResolver,
+    ) -> Self {
+        self.per_credentials_factories
+            .insert(name.into(), storage_resolver.per_backend_factories);
+        self
+    }
+
     /// Builds the [`StorageResolver`].
     pub fn build(self) -> anyhow::Result<StorageResolver> {
         let storage_resolver = StorageResolver {
             per_backend_factories: Arc::new(self.per_backend_factories),
+            per_credentials_factories: Arc::new(self.per_credentials_factories),
         };
         Ok(storage_resolver)
     }
@@ -231,6 +304,59 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_storage_resolver_storage_credentials() {
+        let ram_storage_factory = |content: &'static [u8]| {
+            let mut ram_storage_factory = MockStorageFactory::new();
+            ram_storage_factory
+                .expect_backend()
+                .returning(|| StorageBackend::Ram);
+            ram_storage_factory.expect_resolve().returning(move |_uri| {
+                Ok(Arc::new(
+                    RamStorage::builder().put("hello", content).build(),
+                ))
+            });
+            ram_storage_factory
+        };
+        let account_a_storage_resolver = StorageResolver::builder()
+            .register(ram_storage_factory(b"account-a"))
+            .build()
+            .unwrap();
+        let storage_resolver = StorageResolver::builder()
+            .register(ram_storage_factory(b"default"))
+            .register_storage_credentials("account-a", account_a_storage_resolver)
+            .build()
+            .unwrap();
+        assert!(storage_resolver.has_storage_credentials("account-a"));
+        assert!(!storage_resolver.has_storage_credentials("account-b"));
+
+        let index_uri = Uri::for_test("ram:///indexes/index-a");
+
+        for (storage_credentials_opt, expected_content) in [
+            (None, b"default".as_slice()),
+            (Some("account-a"), b"account-a"),
+        ] {
+            let storage = storage_resolver
+                .resolve_with_credentials(&index_uri, storage_credentials_opt)
+                .await
+                .unwrap();
+            let data = storage.get_all(Path::new("hello")).await.unwrap();
+            assert_eq!(&data[..], expected_content);
+        }
+        let storage = storage_resolver.resolve(&index_uri).await.unwrap();
+        let data = storage.get_all(Path::new("hello")).await.unwrap();
+        assert_eq!(&data[..], b"default");
+
+        let resolver_error = storage_resolver
+            .resolve_with_credentials(&index_uri, Some("account-b"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            resolver_error,
+            StorageResolverError::InvalidConfig(_)
+        ));
+    }
+
     #[tokio::test]
     async fn test_storage_resolver_unsupported_protocol() {
         let storage_resolver = StorageResolver::unconfigured();
@@ -61,6 +61,17 @@ impl Storage for TimeoutAndRetryStorage {
         self.underlying.put(path, payload).await
     }
 
+    async fn put_with_storage_class(
+        &self,
+        path: &Path,
+        payload: Box<dyn PutPayload>,
+        storage_class: &str,
+    ) -> StorageResult<()> {
+        self.underlying
+            .put_with_storage_class(path, payload, storage_class)
+            .await
+    }
+
     fn copy_to<'life0, 'life1, 'life2, 'async_trait>(
         &'life0 self,
         path: &'life1 Path,